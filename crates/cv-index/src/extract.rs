@@ -0,0 +1,556 @@
+//! Walks a syntax tree and turns the symbols it recognises into chunks.
+//!
+//! Each language maps a handful of node kinds to a [`SymbolKind`]. A chunk
+//! spans the symbol's outermost node (so `export`, Python decorators and a
+//! Go `type` keyword are included), and its doc comment is read from the
+//! comments directly above that node, or from a Python docstring.
+
+use tree_sitter::{Node, Tree};
+
+use crate::{CodeChunk, Language, SymbolKind};
+
+pub(crate) fn chunks(file: &str, language: Language, source: &str, tree: &Tree) -> Vec<CodeChunk> {
+    let mut chunks = Vec::new();
+    let mut cursor = tree.walk();
+    // Pre-order, so chunks come out in source order and nested symbols
+    // (methods, inner functions) follow the symbol containing them
+    loop {
+        let node = cursor.node();
+        if let Some(chunk) = chunk(file, language, source, node) {
+            chunks.push(chunk);
+        }
+        if cursor.goto_first_child() {
+            continue;
+        }
+        loop {
+            if cursor.goto_next_sibling() {
+                break;
+            }
+            if !cursor.goto_parent() {
+                return chunks;
+            }
+        }
+    }
+}
+
+/// What a node defines: its kind, the node holding its name and the node
+/// the chunk spans
+struct Symbol<'t> {
+    kind: SymbolKind,
+    name: Node<'t>,
+    outer: Node<'t>,
+}
+
+fn chunk(file: &str, language: Language, source: &str, node: Node<'_>) -> Option<CodeChunk> {
+    let symbol = match language {
+        Language::Rust => rust_symbol(node),
+        Language::TypeScript | Language::Tsx => typescript_symbol(node),
+        Language::Python => python_symbol(node),
+        Language::Go => go_symbol(node),
+    }?;
+    let docstring = match language {
+        Language::Rust => rust_doc(symbol.outer, source),
+        Language::TypeScript | Language::Tsx => jsdoc(symbol.outer, source),
+        Language::Python => python_docstring(node, source),
+        Language::Go => go_doc(symbol.outer, source),
+    };
+
+    let outer = symbol.outer;
+    let start_line = outer.start_position().row + 1;
+    let end_line = outer.end_position().row + 1;
+    Some(CodeChunk {
+        id: format!("{file}:{start_line}:{end_line}"),
+        file: file.to_string(),
+        language: language.name(),
+        start_line,
+        end_line,
+        start_byte: outer.start_byte(),
+        end_byte: outer.end_byte(),
+        text: text(outer, source).to_string(),
+        symbol_name: text(symbol.name, source).to_string(),
+        symbol_kind: symbol.kind,
+        docstring,
+    })
+}
+
+fn text<'s>(node: Node<'_>, source: &'s str) -> &'s str {
+    &source[node.start_byte()..node.end_byte()]
+}
+
+fn parent_kind(node: Node<'_>) -> Option<&'static str> {
+    node.parent().map(|p| p.kind())
+}
+
+fn named<'t>(kind: SymbolKind, node: Node<'t>, outer: Node<'t>) -> Option<Symbol<'t>> {
+    let name = node.child_by_field_name("name")?;
+    Some(Symbol { kind, name, outer })
+}
+
+// ========== Rust ==========
+
+fn rust_symbol(node: Node<'_>) -> Option<Symbol<'_>> {
+    let kind = match node.kind() {
+        "function_item" | "function_signature_item" => {
+            let in_impl = node
+                .parent()
+                .filter(|list| list.kind() == "declaration_list")
+                .and_then(|list| list.parent())
+                .is_some_and(|owner| matches!(owner.kind(), "impl_item" | "trait_item"));
+            if in_impl {
+                SymbolKind::Method
+            } else {
+                SymbolKind::Function
+            }
+        }
+        "struct_item" | "union_item" => SymbolKind::Struct,
+        "enum_item" => SymbolKind::Enum,
+        "trait_item" => SymbolKind::Interface,
+        "type_item" => SymbolKind::Type,
+        _ => return None,
+    };
+    named(kind, node, node)
+}
+
+/// `///` lines or a `/** */` block above the item, past its attributes
+fn rust_doc(node: Node<'_>, source: &str) -> Option<String> {
+    let comments = comments_above(node, source, &["line_comment", "block_comment"], &["attribute_item"], |c| {
+        (c.starts_with("///") && !c.starts_with("////")) || (c.starts_with("/**") && !c.starts_with("/**/"))
+    });
+    doc_text(&comments, "///")
+}
+
+// ========== TypeScript ==========
+
+fn typescript_symbol(node: Node<'_>) -> Option<Symbol<'_>> {
+    let kind = match node.kind() {
+        "function_declaration" | "generator_function_declaration" => SymbolKind::Function,
+        "class_declaration" | "abstract_class_declaration" => SymbolKind::Class,
+        "method_definition" | "abstract_method_signature" => SymbolKind::Method,
+        "interface_declaration" => SymbolKind::Interface,
+        "type_alias_declaration" => SymbolKind::Type,
+        "enum_declaration" => SymbolKind::Enum,
+        // const handler = () => { ... }
+        "variable_declarator" => {
+            let value = node.child_by_field_name("value")?;
+            if !matches!(
+                value.kind(),
+                "arrow_function" | "function_expression" | "function" | "generator_function"
+            ) {
+                return None;
+            }
+            let declaration = node.parent()?;
+            return named(SymbolKind::Function, node, exported(declaration));
+        }
+        _ => return None,
+    };
+    named(kind, node, exported(node))
+}
+
+/// The `/** */` block directly above the declaration
+fn jsdoc(node: Node<'_>, source: &str) -> Option<String> {
+    let comments = comments_above(node, source, &["comment"], &["decorator"], |c| {
+        c.starts_with("/**") && !c.starts_with("/**/")
+    });
+    doc_text(&comments[comments.len().saturating_sub(1)..], "//")
+}
+
+/// The `export` statement wrapping a declaration, if any
+fn exported(declaration: Node<'_>) -> Node<'_> {
+    match declaration.parent() {
+        Some(parent) if parent.kind() == "export_statement" => parent,
+        _ => declaration,
+    }
+}
+
+// ========== Python ==========
+
+fn python_symbol(node: Node<'_>) -> Option<Symbol<'_>> {
+    let outer = match node.parent() {
+        Some(parent) if parent.kind() == "decorated_definition" => parent,
+        _ => node,
+    };
+    let kind = match node.kind() {
+        "function_definition" => {
+            let in_class = outer
+                .parent()
+                .filter(|block| block.kind() == "block")
+                .is_some_and(|block| parent_kind(block) == Some("class_definition"));
+            if in_class {
+                SymbolKind::Method
+            } else {
+                SymbolKind::Function
+            }
+        }
+        "class_definition" => SymbolKind::Class,
+        _ => return None,
+    };
+    named(kind, node, outer)
+}
+
+/// A string literal as the first statement of the body
+fn python_docstring(node: Node<'_>, source: &str) -> Option<String> {
+    let body = node.child_by_field_name("body")?;
+    let mut cursor = body.walk();
+    let first = body.named_children(&mut cursor).find(|n| n.kind() != "comment")?;
+    if first.kind() != "expression_statement" {
+        return None;
+    }
+    let mut cursor = first.walk();
+    let literal = first.named_children(&mut cursor).next()?;
+    if literal.kind() != "string" {
+        return None;
+    }
+    let doc = clean_doc(unquote(text(literal, source)));
+    (!doc.is_empty()).then_some(doc)
+}
+
+/// Contents of a Python string literal, without prefix and quotes
+fn unquote(literal: &str) -> &str {
+    let body = literal.trim_start_matches(|c: char| c.is_ascii_alphabetic());
+    for quote in ["\"\"\"", "'''", "\"", "'"] {
+        if let Some(inner) = body.strip_prefix(quote).and_then(|b| b.strip_suffix(quote)) {
+            return inner;
+        }
+    }
+    body
+}
+
+/// Dedent every line after the first by their common indentation and drop
+/// blank lines around the text, as `inspect.cleandoc` does
+fn clean_doc(doc: &str) -> String {
+    let mut lines: Vec<&str> = doc.lines().collect();
+    let indent = lines
+        .iter()
+        .skip(1)
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start_matches([' ', '\t']).len())
+        .min()
+        .unwrap_or(0);
+    for (i, line) in lines.iter_mut().enumerate() {
+        *line = if i == 0 {
+            line.trim()
+        } else if line.trim().is_empty() {
+            ""
+        } else {
+            line.get(indent..).unwrap_or(line).trim_end()
+        };
+    }
+    lines.join("\n").trim_matches('\n').to_string()
+}
+
+// ========== Go ==========
+
+fn go_symbol(node: Node<'_>) -> Option<Symbol<'_>> {
+    let kind = match node.kind() {
+        "function_declaration" => SymbolKind::Function,
+        "method_declaration" => SymbolKind::Method,
+        "type_spec" => match node.child_by_field_name("type")?.kind() {
+            "struct_type" => SymbolKind::Struct,
+            "interface_type" => SymbolKind::Interface,
+            _ => SymbolKind::Type,
+        },
+        "type_alias" => SymbolKind::Type,
+        _ => return None,
+    };
+    // `type Foo struct {...}` spans the declaration; in a `type (...)`
+    // group each spec is its own chunk
+    let outer = match node.parent() {
+        Some(declaration) if declaration.kind() == "type_declaration" => {
+            let mut cursor = declaration.walk();
+            let specs = declaration
+                .named_children(&mut cursor)
+                .filter(|n| matches!(n.kind(), "type_spec" | "type_alias"))
+                .count();
+            if specs == 1 {
+                declaration
+            } else {
+                node
+            }
+        }
+        _ => node,
+    };
+    named(kind, node, outer)
+}
+
+/// The comment lines directly above the declaration
+fn go_doc(node: Node<'_>, source: &str) -> Option<String> {
+    let comments = comments_above(node, source, &["comment"], &[], |_| true);
+    doc_text(&comments, "//")
+}
+
+// ========== Comments ==========
+
+/// Comments ending on the line above `node` (or above the previous one),
+/// nearest last; `skip` kinds (attributes, decorators) may sit between
+fn comments_above<'s>(
+    node: Node<'_>,
+    source: &'s str,
+    comment_kinds: &[&str],
+    skip: &[&str],
+    is_doc: impl Fn(&str) -> bool,
+) -> Vec<&'s str> {
+    let mut found = Vec::new();
+    let mut next_row = node.start_position().row;
+    let mut sibling = node.prev_sibling();
+    while let Some(current) = sibling {
+        if skip.contains(&current.kind()) {
+            next_row = current.start_position().row;
+            sibling = current.prev_sibling();
+            continue;
+        }
+        if !comment_kinds.contains(&current.kind()) {
+            break;
+        }
+        // Line comments may end at column 0 of the next line
+        let end = current.end_position();
+        let last_row = if end.column == 0 && end.row > current.start_position().row {
+            end.row - 1
+        } else {
+            end.row
+        };
+        let comment = text(current, source).trim_end();
+        if next_row.saturating_sub(last_row) > 1 || !is_doc(comment) {
+            break;
+        }
+        found.push(comment);
+        next_row = current.start_position().row;
+        sibling = current.prev_sibling();
+    }
+    found.reverse();
+    found
+}
+
+/// Comment text without markers: `line_marker` line comments are joined,
+/// a `/* */` block loses its delimiters and leading `*`s
+fn doc_text(comments: &[&str], line_marker: &str) -> Option<String> {
+    let mut lines = Vec::new();
+    for comment in comments {
+        if let Some(block) = comment.strip_prefix("/*") {
+            let block = block.strip_prefix('*').unwrap_or(block);
+            let block = block.strip_suffix("*/").unwrap_or(block);
+            for line in block.lines() {
+                let line = line.trim();
+                let line = line.strip_prefix('*').unwrap_or(line);
+                lines.push(line.strip_prefix(' ').unwrap_or(line).trim_end());
+            }
+        } else {
+            let line = comment.strip_prefix(line_marker).unwrap_or(comment);
+            lines.push(line.strip_prefix(' ').unwrap_or(line).trim_end());
+        }
+    }
+    let doc = lines.join("\n").trim_matches('\n').to_string();
+    (!doc.is_empty()).then_some(doc)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{index_source, CodeChunk, Language, SymbolKind};
+
+    fn index(language: Language, source: &str) -> Vec<CodeChunk> {
+        let chunks = index_source("src/sample", language, source).unwrap();
+        for chunk in &chunks {
+            assert_eq!(&source[chunk.start_byte..chunk.end_byte], chunk.text);
+        }
+        chunks
+    }
+
+    fn symbols(chunks: &[CodeChunk]) -> Vec<(&str, SymbolKind)> {
+        chunks.iter().map(|c| (c.symbol_name.as_str(), c.symbol_kind)).collect()
+    }
+
+    #[test]
+    fn test_rust() {
+        let source = r#"// Copyright notice
+
+/// A point in the plane.
+///
+/// Coordinates are in pixels.
+#[derive(Debug)]
+pub struct Point {
+    x: i32,
+}
+
+pub enum Shape { Square }
+
+/** Anything with an area. */
+pub trait Area {
+    fn area(&self) -> f64;
+}
+
+impl Point {
+    /// Origin.
+    pub fn origin() -> Self {
+        Point { x: 0 }
+    }
+}
+
+type Meters = f64;
+
+fn helper() {}
+"#;
+        let chunks = index(Language::Rust, source);
+        assert_eq!(
+            symbols(&chunks),
+            vec![
+                ("Point", SymbolKind::Struct),
+                ("Shape", SymbolKind::Enum),
+                ("Area", SymbolKind::Interface),
+                ("area", SymbolKind::Method),
+                ("origin", SymbolKind::Method),
+                ("Meters", SymbolKind::Type),
+                ("helper", SymbolKind::Function),
+            ]
+        );
+        assert_eq!(chunks[0].docstring.as_deref(), Some("A point in the plane.\n\nCoordinates are in pixels."));
+        assert_eq!(chunks[0].start_line, 7);
+        assert_eq!(chunks[0].id, "src/sample:7:9");
+        assert_eq!(chunks[1].docstring, None);
+        assert_eq!(chunks[2].docstring.as_deref(), Some("Anything with an area."));
+        assert_eq!(chunks[4].docstring.as_deref(), Some("Origin."));
+        assert_eq!(chunks[6].docstring, None);
+        assert_eq!(chunks[0].language, "rust");
+    }
+
+    #[test]
+    fn test_typescript() {
+        let source = "/**
+ * Sum two numbers.
+ * @param a first
+ */
+export function add(a: number, b: number): number {
+  return a + b;
+}
+
+export interface Options { verbose: boolean }
+type Id = string;
+enum Color { Red }
+
+// not a doc comment
+export const handler = async () => {};
+const limit = 10;
+
+export class Service {
+  /** Start serving. */
+  start(): void {}
+}
+";
+        let chunks = index(Language::TypeScript, source);
+        assert_eq!(
+            symbols(&chunks),
+            vec![
+                ("add", SymbolKind::Function),
+                ("Options", SymbolKind::Interface),
+                ("Id", SymbolKind::Type),
+                ("Color", SymbolKind::Enum),
+                ("handler", SymbolKind::Function),
+                ("Service", SymbolKind::Class),
+                ("start", SymbolKind::Method),
+            ]
+        );
+        assert_eq!(chunks[0].docstring.as_deref(), Some("Sum two numbers.\n@param a first"));
+        assert!(chunks[0].text.starts_with("export function add"));
+        assert_eq!((chunks[0].start_line, chunks[0].end_line), (5, 7));
+        assert_eq!(chunks[4].docstring, None);
+        assert!(chunks[4].text.starts_with("export const handler"));
+        assert_eq!(chunks[6].docstring.as_deref(), Some("Start serving."));
+
+        let tsx = index(Language::Tsx, "export const App = () => <div>hi</div>;\n");
+        assert_eq!(symbols(&tsx), vec![("App", SymbolKind::Function)]);
+        assert_eq!(tsx[0].language, "typescript");
+    }
+
+    #[test]
+    fn test_python() {
+        let source = r#"import os
+
+def load(path):
+    """Read a file.
+
+    Returns its contents.
+    """
+    return open(path).read()
+
+class Cache:
+    '''In-memory cache.'''
+
+    @staticmethod
+    def create():
+        return Cache()
+
+    def get(self, key):
+        # no docstring
+        return None
+"#;
+        let chunks = index(Language::Python, source);
+        assert_eq!(
+            symbols(&chunks),
+            vec![
+                ("load", SymbolKind::Function),
+                ("Cache", SymbolKind::Class),
+                ("create", SymbolKind::Method),
+                ("get", SymbolKind::Method),
+            ]
+        );
+        assert_eq!(chunks[0].docstring.as_deref(), Some("Read a file.\n\nReturns its contents."));
+        assert_eq!(chunks[1].docstring.as_deref(), Some("In-memory cache."));
+        assert!(chunks[2].text.starts_with("@staticmethod"));
+        assert_eq!(chunks[3].docstring, None);
+    }
+
+    #[test]
+    fn test_go() {
+        let source = "package main
+
+// Server handles requests.
+// It is safe for concurrent use.
+type Server struct {
+	addr string
+}
+
+type (
+	// Handler serves one route.
+	Handler interface{ Serve() }
+	ID = string
+)
+
+// Start listens on the address.
+func (s *Server) Start() error { return nil }
+
+func main() {}
+";
+        let chunks = index(Language::Go, source);
+        assert_eq!(
+            symbols(&chunks),
+            vec![
+                ("Server", SymbolKind::Struct),
+                ("Handler", SymbolKind::Interface),
+                ("ID", SymbolKind::Type),
+                ("Start", SymbolKind::Method),
+                ("main", SymbolKind::Function),
+            ]
+        );
+        assert_eq!(chunks[0].docstring.as_deref(), Some("Server handles requests.\nIt is safe for concurrent use."));
+        assert!(chunks[0].text.starts_with("type Server struct"));
+        assert_eq!(chunks[1].docstring.as_deref(), Some("Handler serves one route."));
+        assert!(chunks[1].text.starts_with("Handler interface"));
+        assert_eq!(chunks[3].docstring.as_deref(), Some("Start listens on the address."));
+        assert_eq!(chunks[4].docstring, None);
+    }
+
+    #[test]
+    fn test_recovers_from_syntax_errors() {
+        let chunks = index(Language::Rust, "fn fine() {}\n\nfn broken( {\n");
+        assert!(chunks.iter().any(|c| c.symbol_name == "fine"));
+    }
+
+    #[test]
+    fn test_json_shape() {
+        let chunks = index(Language::Go, "package p\n\n// F does it.\nfunc F() {}\n");
+        let json = serde_json::to_value(&chunks[0]).unwrap();
+        assert_eq!(json["symbolKind"], "function");
+        assert_eq!(json["startByte"], 25);
+        assert_eq!(json["endByte"], 36);
+        assert_eq!(json["docstring"], "F does it.");
+        assert_eq!(json["file"], "src/sample");
+    }
+}
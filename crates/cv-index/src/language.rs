@@ -0,0 +1,62 @@
+//! Supported languages and their tree-sitter grammars.
+
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Language {
+    Rust,
+    TypeScript,
+    /// TypeScript with JSX, which needs its own grammar
+    Tsx,
+    Python,
+    Go,
+}
+
+impl Language {
+    /// Language of a file, from its extension
+    pub fn from_path(path: &Path) -> Option<Language> {
+        match path.extension()?.to_str()? {
+            "rs" => Some(Language::Rust),
+            "ts" | "mts" | "cts" => Some(Language::TypeScript),
+            "tsx" => Some(Language::Tsx),
+            "py" | "pyi" => Some(Language::Python),
+            "go" => Some(Language::Go),
+            _ => None,
+        }
+    }
+
+    /// Name in `CodeChunk.language`, as the TypeScript parsers report it
+    pub fn name(self) -> &'static str {
+        match self {
+            Language::Rust => "rust",
+            Language::TypeScript | Language::Tsx => "typescript",
+            Language::Python => "python",
+            Language::Go => "go",
+        }
+    }
+
+    pub(crate) fn grammar(self) -> tree_sitter::Language {
+        match self {
+            Language::Rust => tree_sitter_rust::LANGUAGE.into(),
+            Language::TypeScript => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+            Language::Tsx => tree_sitter_typescript::LANGUAGE_TSX.into(),
+            Language::Python => tree_sitter_python::LANGUAGE.into(),
+            Language::Go => tree_sitter_go::LANGUAGE.into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_path() {
+        assert_eq!(Language::from_path(Path::new("src/lib.rs")), Some(Language::Rust));
+        assert_eq!(Language::from_path(Path::new("app/page.tsx")), Some(Language::Tsx));
+        assert_eq!(Language::from_path(Path::new("tool.pyi")), Some(Language::Python));
+        assert_eq!(Language::from_path(Path::new("README.md")), None);
+        assert_eq!(Language::from_path(Path::new("Makefile")), None);
+        assert_eq!(Language::Tsx.name(), "typescript");
+    }
+}
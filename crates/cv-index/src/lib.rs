@@ -0,0 +1,113 @@
+//! cv-index — tree-sitter code indexer for CV-Git.
+//!
+//! Parses Rust, TypeScript (and TSX), Python and Go sources and extracts
+//! functions, methods and types, each with its doc comment, as
+//! [`CodeChunk`] records carrying the file path, byte and line ranges and
+//! symbol kind. Serialized as JSON the records have the shape of
+//! `CodeChunk` in `@cv-git/shared`, so `cv sync` can store them as they are.
+
+mod extract;
+mod language;
+
+use std::fmt;
+use std::path::Path;
+
+use serde::Serialize;
+
+pub use language::Language;
+
+/// Kind of symbol a chunk holds; the names match `SymbolKind` in `@cv-git/shared`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SymbolKind {
+    Function,
+    Method,
+    Class,
+    /// Interfaces, and Rust traits
+    Interface,
+    /// Type aliases and other named types
+    Type,
+    Enum,
+    Struct,
+}
+
+/// One symbol's source, with where it sits in the file
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodeChunk {
+    /// `file:startLine:endLine`
+    pub id: String,
+    pub file: String,
+    pub language: &'static str,
+    /// 1-based, inclusive
+    pub start_line: usize,
+    pub end_line: usize,
+    /// UTF-8 byte offset of the chunk's first character
+    pub start_byte: usize,
+    /// UTF-8 byte offset just past the chunk's last character
+    pub end_byte: usize,
+    pub text: String,
+    pub symbol_name: String,
+    pub symbol_kind: SymbolKind,
+    /// Doc comment (or Python docstring) without its comment markers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub docstring: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum IndexError {
+    /// The file's extension is not one of the supported languages
+    UnsupportedLanguage(String),
+    Io(std::io::Error),
+    /// The grammar was built for a tree-sitter version this crate can't load
+    Grammar(tree_sitter::LanguageError),
+    /// tree-sitter gave up without producing a tree
+    Parse(String),
+}
+
+impl fmt::Display for IndexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IndexError::UnsupportedLanguage(file) => write!(f, "{file}: unsupported language"),
+            IndexError::Io(e) => write!(f, "{e}"),
+            IndexError::Grammar(e) => write!(f, "cannot load grammar: {e}"),
+            IndexError::Parse(file) => write!(f, "{file}: parse failed"),
+        }
+    }
+}
+
+impl std::error::Error for IndexError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            IndexError::Io(e) => Some(e),
+            IndexError::Grammar(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for IndexError {
+    fn from(e: std::io::Error) -> Self {
+        IndexError::Io(e)
+    }
+}
+
+/// Chunks of `source`, in source order; `file` is recorded on each chunk.
+/// Syntax errors don't fail the file: tree-sitter recovers, and the
+/// symbols it still recognises are returned.
+pub fn index_source(file: &str, language: Language, source: &str) -> Result<Vec<CodeChunk>, IndexError> {
+    let mut parser = tree_sitter::Parser::new();
+    parser.set_language(&language.grammar()).map_err(IndexError::Grammar)?;
+    let tree = parser
+        .parse(source, None)
+        .ok_or_else(|| IndexError::Parse(file.to_string()))?;
+    Ok(extract::chunks(file, language, source, &tree))
+}
+
+/// Read and index a file, choosing the language from its extension
+pub fn index_file(path: &Path) -> Result<Vec<CodeChunk>, IndexError> {
+    let file = path.to_string_lossy().replace('\\', "/");
+    let language = Language::from_path(path).ok_or_else(|| IndexError::UnsupportedLanguage(file.clone()))?;
+    let source = std::fs::read_to_string(path)?;
+    index_source(&file, language, &source)
+}
@@ -0,0 +1,101 @@
+//! `cv-index [--pretty] <paths...>`
+//!
+//! Indexes the given files and directories and prints one JSON `CodeChunk`
+//! per line. Directories are walked recursively, skipping hidden entries,
+//! `target` and `node_modules`; files in unsupported languages are ignored
+//! unless named directly.
+
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use cv_index::{index_file, Language};
+
+const SKIPPED_DIRS: &[&str] = &["target", "node_modules"];
+
+fn main() -> ExitCode {
+    let mut pretty = false;
+    let mut paths = Vec::new();
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--pretty" => pretty = true,
+            "-h" | "--help" => {
+                println!("usage: cv-index [--pretty] <paths...>");
+                return ExitCode::SUCCESS;
+            }
+            _ => paths.push(PathBuf::from(arg)),
+        }
+    }
+    if paths.is_empty() {
+        eprintln!("usage: cv-index [--pretty] <paths...>");
+        return ExitCode::from(2);
+    }
+
+    let mut files = Vec::new();
+    let mut failed = false;
+    for path in &paths {
+        if path.is_dir() {
+            if let Err(e) = collect(path, &mut files) {
+                eprintln!("cv-index: {}: {e}", path.display());
+                failed = true;
+            }
+        } else {
+            files.push(path.clone());
+        }
+    }
+
+    let mut out = BufWriter::new(io::stdout().lock());
+    for file in &files {
+        let chunks = match index_file(file) {
+            Ok(chunks) => chunks,
+            Err(e) => {
+                eprintln!("cv-index: {e}");
+                failed = true;
+                continue;
+            }
+        };
+        for chunk in &chunks {
+            let written = if pretty {
+                serde_json::to_writer_pretty(&mut out, chunk)
+            } else {
+                serde_json::to_writer(&mut out, chunk)
+            };
+            if written.map_err(io::Error::from).and_then(|()| writeln!(out)).is_err() {
+                // stdout closed (e.g. piped into `head`)
+                return ExitCode::FAILURE;
+            }
+        }
+    }
+    if out.flush().is_err() {
+        return ExitCode::FAILURE;
+    }
+
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+/// Supported source files under `dir`, in a stable order
+fn collect(dir: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    let mut entries = std::fs::read_dir(dir)?.collect::<io::Result<Vec<_>>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+    for entry in entries {
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name.starts_with('.') {
+            continue;
+        }
+        let path = entry.path();
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            if !SKIPPED_DIRS.contains(&name.as_ref()) {
+                collect(&path, files)?;
+            }
+        } else if file_type.is_file() && Language::from_path(&path).is_some() {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
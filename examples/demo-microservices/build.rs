@@ -0,0 +1,5 @@
+fn main() {
+    // Needs `protoc` on PATH (protobuf-compiler) when the grpc feature is on
+    #[cfg(feature = "grpc")]
+    tonic_build::compile_protos("proto/compute.proto").expect("failed to compile proto/compute.proto");
+}
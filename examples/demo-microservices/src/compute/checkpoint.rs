@@ -0,0 +1,269 @@
+// Checkpointing - resumable gradient descent for long optimization runs
+
+use std::io::{self, BufRead, Write};
+
+use crate::OptimizationEngine;
+
+const CHECKPOINT_MAGIC: &str = "cvopt-v1";
+
+/// Snapshot of an in-progress optimization
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OptimizerState {
+    /// Current position
+    pub x: f64,
+    /// Iterations completed so far
+    pub iteration: usize,
+    /// First moment (momentum velocity) of the gradient
+    pub velocity: f64,
+    /// Whether the run met the tolerance check
+    pub converged: bool,
+}
+
+impl OptimizerState {
+    /// Fresh state starting at `x`
+    pub fn new(x: f64) -> Self {
+        OptimizerState {
+            x,
+            iteration: 0,
+            velocity: 0.0,
+            converged: false,
+        }
+    }
+
+    /// Serialize as a single line; floats are stored as raw bits so resumes are exact
+    pub fn write_to<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writeln!(
+            writer,
+            "{} x={:016x} iteration={} velocity={:016x} converged={}",
+            CHECKPOINT_MAGIC,
+            self.x.to_bits(),
+            self.iteration,
+            self.velocity.to_bits(),
+            self.converged as u8
+        )?;
+        writer.flush()
+    }
+
+    /// Parse one checkpoint line
+    pub fn parse(line: &str) -> Option<Self> {
+        let mut fields = line.split_whitespace();
+        if fields.next()? != CHECKPOINT_MAGIC {
+            return None;
+        }
+
+        let mut x = None;
+        let mut iteration = None;
+        let mut velocity = None;
+        let mut converged = None;
+        for field in fields {
+            let (key, value) = field.split_once('=')?;
+            match key {
+                "x" => x = u64::from_str_radix(value, 16).ok().map(f64::from_bits),
+                "iteration" => iteration = value.parse().ok(),
+                "velocity" => velocity = u64::from_str_radix(value, 16).ok().map(f64::from_bits),
+                "converged" => converged = Some(value == "1"),
+                _ => {}
+            }
+        }
+
+        Some(OptimizerState {
+            x: x?,
+            iteration: iteration?,
+            velocity: velocity?,
+            converged: converged?,
+        })
+    }
+
+    /// Read the most recent complete checkpoint from a stream of checkpoint lines.
+    /// A truncated trailing line (e.g. from a killed pod) is ignored.
+    pub fn read_latest<R: BufRead>(reader: R) -> io::Result<Self> {
+        let mut latest = None;
+        for line in reader.lines() {
+            if let Some(state) = Self::parse(&line?) {
+                latest = Some(state);
+            }
+        }
+        latest.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "no valid checkpoint found"))
+    }
+}
+
+/// How often to checkpoint and how to step
+#[derive(Debug, Clone, Copy)]
+pub struct CheckpointPolicy {
+    /// Write a checkpoint every N iterations (0 disables periodic checkpoints)
+    pub every: usize,
+    /// Momentum coefficient; 0.0 reproduces `gradient_descent` exactly
+    pub momentum: f64,
+}
+
+impl Default for CheckpointPolicy {
+    fn default() -> Self {
+        CheckpointPolicy {
+            every: 1000,
+            momentum: 0.0,
+        }
+    }
+}
+
+impl OptimizationEngine {
+    /// Gradient descent from `x`, writing checkpoints to `writer` as it goes.
+    /// A final checkpoint is always written when the run stops.
+    pub fn run_with_checkpoint<F, W>(
+        &self,
+        x: f64,
+        f: F,
+        writer: &mut W,
+        policy: CheckpointPolicy,
+    ) -> io::Result<OptimizerState>
+    where
+        F: Fn(f64) -> f64,
+        W: Write,
+    {
+        self.optimize_from(OptimizerState::new(x), f, writer, policy)
+    }
+
+    /// Continue a run from the latest checkpoint in `checkpoint`
+    pub fn resume_from<F, R, W>(
+        &self,
+        checkpoint: R,
+        f: F,
+        writer: &mut W,
+        policy: CheckpointPolicy,
+    ) -> io::Result<OptimizerState>
+    where
+        F: Fn(f64) -> f64,
+        R: BufRead,
+        W: Write,
+    {
+        let state = OptimizerState::read_latest(checkpoint)?;
+        if state.converged || state.iteration >= self.max_iterations {
+            return Ok(state);
+        }
+        self.optimize_from(state, f, writer, policy)
+    }
+
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            skip_all,
+            fields(start_iteration = state.iteration, momentum = policy.momentum)
+        )
+    )]
+    fn optimize_from<F, W>(
+        &self,
+        mut state: OptimizerState,
+        f: F,
+        writer: &mut W,
+        policy: CheckpointPolicy,
+    ) -> io::Result<OptimizerState>
+    where
+        F: Fn(f64) -> f64,
+        W: Write,
+    {
+        // Same step parameters as gradient_descent
+        let learning_rate = 0.01;
+        let h = 1e-5;
+
+        while state.iteration < self.max_iterations {
+            let grad = (f(state.x + h) - f(state.x - h)) / (2.0 * h);
+            state.velocity = policy.momentum * state.velocity + grad;
+            let x_new = state.x - learning_rate * state.velocity;
+            state.iteration += 1;
+
+            if (x_new - state.x).abs() < self.tolerance {
+                state.converged = true;
+                break;
+            }
+            state.x = x_new;
+
+            if policy.every > 0 && state.iteration.is_multiple_of(policy.every) {
+                state.write_to(writer)?;
+                #[cfg(feature = "tracing")]
+                tracing::trace!(iteration = state.iteration, x = state.x, "checkpoint written");
+            }
+        }
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            iterations = state.iteration,
+            x = state.x,
+            converged = state.converged,
+            "optimization stopped"
+        );
+
+        state.write_to(writer)?;
+        Ok(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn parabola(x: f64) -> f64 {
+        (x - 3.0).powi(2)
+    }
+
+    #[test]
+    fn test_matches_gradient_descent() {
+        let engine = OptimizationEngine::new(10_000, 1e-9);
+        let mut sink = Vec::new();
+        let state = engine
+            .run_with_checkpoint(0.0, parabola, &mut sink, CheckpointPolicy::default())
+            .unwrap();
+        assert_eq!(state.x, engine.gradient_descent(0.0, parabola));
+        assert!(state.converged);
+    }
+
+    #[test]
+    fn test_resume_continues_interrupted_run() {
+        let policy = CheckpointPolicy {
+            every: 50,
+            momentum: 0.5,
+        };
+
+        // Interrupted run: budget runs out partway through
+        let short = OptimizationEngine::new(120, 1e-12);
+        let mut checkpoints = Vec::new();
+        let partial = short
+            .run_with_checkpoint(10.0, parabola, &mut checkpoints, policy)
+            .unwrap();
+        assert_eq!(partial.iteration, 120);
+        assert!(!partial.converged);
+
+        // Simulate a kill mid-write leaving a truncated trailing line
+        checkpoints.extend_from_slice(b"cvopt-v1 x=4008");
+
+        let long = OptimizationEngine::new(10_000, 1e-12);
+        let mut more = Vec::new();
+        let resumed = long
+            .resume_from(Cursor::new(checkpoints), parabola, &mut more, policy)
+            .unwrap();
+
+        let mut uninterrupted_sink = Vec::new();
+        let uninterrupted = long
+            .run_with_checkpoint(10.0, parabola, &mut uninterrupted_sink, policy)
+            .unwrap();
+        assert_eq!(resumed, uninterrupted);
+    }
+
+    #[test]
+    fn test_round_trip_is_exact() {
+        let state = OptimizerState {
+            x: std::f64::consts::PI,
+            iteration: 42,
+            velocity: -1e-300,
+            converged: true,
+        };
+        let mut buf = Vec::new();
+        state.write_to(&mut buf).unwrap();
+        assert_eq!(OptimizerState::read_latest(Cursor::new(buf)).unwrap(), state);
+    }
+
+    #[test]
+    fn test_empty_checkpoint_is_an_error() {
+        assert!(OptimizerState::read_latest(Cursor::new(Vec::new())).is_err());
+    }
+}
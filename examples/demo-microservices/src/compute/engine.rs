@@ -1,17 +1,92 @@
 // Compute Engine - Rust
 // High-performance computation service for data-intensive operations
 
-use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::sync::Arc;
+#[cfg(feature = "gpu")]
+use std::sync::OnceLock;
+
+mod checkpoint;
+#[cfg(feature = "gpu")]
+mod gpu;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+#[cfg(feature = "http")]
+pub mod http;
+pub mod histogram;
+mod job_queue;
+pub mod linalg;
+pub mod metrics;
+mod numeric;
+mod registry;
+mod rolling;
+#[cfg(feature = "persistent-cache")]
+mod persistent_cache;
+#[cfg(any(feature = "grpc", feature = "http"))]
+mod shutdown;
+
+pub use checkpoint::{CheckpointPolicy, OptimizerState};
+pub use histogram::{BinStrategy, Distribution, DistributionFit, Histogram};
+pub use job_queue::{Job, JobId, JobOutput, JobQueue, JobStatus, Priority, QueueError};
+pub use linalg::{Matrix, PrincipalComponents};
+pub use metrics::{ComputeMetrics, Metrics, Operation};
+pub use numeric::{neumaier_sum, Precision};
+pub use registry::{Computation, ComputationError, ComputationRegistry};
+pub use rolling::RollingWindow;
+#[cfg(feature = "persistent-cache")]
+pub use persistent_cache::PersistentCache;
+#[cfg(any(feature = "grpc", feature = "http"))]
+pub use shutdown::shutdown_signal;
 
 /// Configuration for the compute engine
 pub struct ComputeConfig {
     max_workers: usize,
+    /// Per-request deadline; not enforced by the engine yet
+    #[allow(dead_code)]
     timeout_seconds: u64,
+    bind_addr: SocketAddr,
+}
+
+impl ComputeConfig {
+    /// Default listen address for the HTTP/gRPC service modes
+    pub const DEFAULT_BIND_ADDR: SocketAddr =
+        SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), 8080);
+
+    /// Address the service listens on
+    pub fn bind_addr(&self) -> SocketAddr {
+        self.bind_addr
+    }
+}
+
+/// Where matrix multiplication and the Fourier transform run
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ComputeBackend {
+    /// GPU for large inputs when the `gpu` feature is built and an adapter is found
+    #[default]
+    Auto,
+    /// Always the CPU
+    Cpu,
+    /// The GPU whenever an adapter is found, regardless of input size.
+    /// Falls back to the CPU without an adapter or the `gpu` feature.
+    Gpu,
+}
+
+impl std::str::FromStr for ComputeBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "auto" => Ok(ComputeBackend::Auto),
+            "cpu" => Ok(ComputeBackend::Cpu),
+            "gpu" => Ok(ComputeBackend::Gpu),
+            other => Err(format!("unknown compute backend '{}' (expected auto, cpu or gpu)", other)),
+        }
+    }
 }
 
 /// Result of a computation
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ComputeResult {
     pub success: bool,
     pub value: f64,
@@ -21,7 +96,14 @@ pub struct ComputeResult {
 /// Main compute engine for performing calculations
 pub struct ComputeEngine {
     config: ComputeConfig,
-    cache: HashMap<String, f64>,
+    metrics: Option<Arc<dyn Metrics>>,
+    precision: Precision,
+    backend: ComputeBackend,
+    registry: ComputationRegistry,
+    #[cfg(feature = "gpu")]
+    gpu: OnceLock<Option<gpu::GpuContext>>,
+    #[cfg(feature = "persistent-cache")]
+    persistent_cache: Option<PersistentCache>,
 }
 
 impl ComputeEngine {
@@ -29,19 +111,167 @@ impl ComputeEngine {
     pub fn new(config: ComputeConfig) -> Self {
         ComputeEngine {
             config,
-            cache: HashMap::new(),
+            metrics: None,
+            precision: Precision::Fast,
+            backend: ComputeBackend::Auto,
+            registry: ComputationRegistry::with_builtins(),
+            #[cfg(feature = "gpu")]
+            gpu: OnceLock::new(),
+            #[cfg(feature = "persistent-cache")]
+            persistent_cache: None,
         }
     }
 
+    /// Create an engine whose results are cached on disk under `path`,
+    /// so they survive service restarts
+    #[cfg(feature = "persistent-cache")]
+    pub fn with_persistent_cache<P: AsRef<std::path::Path>>(
+        config: ComputeConfig,
+        path: P,
+    ) -> std::io::Result<Self> {
+        let mut engine = ComputeEngine::new(config);
+        engine.persistent_cache = Some(PersistentCache::open(path, PersistentCache::DEFAULT_MAX_BYTES)?);
+        Ok(engine)
+    }
+
+    /// Attach a metrics sink that records every instrumented operation
+    pub fn with_metrics(mut self, metrics: Arc<dyn Metrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Select the floating-point mode used by reductions
+    pub fn with_precision(mut self, precision: Precision) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    /// Force a compute backend; the default picks the GPU for large inputs
+    pub fn with_backend(mut self, backend: ComputeBackend) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Replace the named computations available to [`ComputeEngine::run_named`]
+    pub fn with_registry(mut self, registry: ComputationRegistry) -> Self {
+        self.registry = registry;
+        self
+    }
+
+    pub fn registry(&self) -> &ComputationRegistry {
+        &self.registry
+    }
+
+    /// Run the latest version of a registered computation by name
+    pub fn run_named(&self, name: &str, input: &[f64]) -> Result<Vec<f64>, ComputationError> {
+        self.registry.run(name, None, input)
+    }
+
+    /// Run a specific version of a registered computation
+    pub fn run_named_version(&self, name: &str, version: u32, input: &[f64]) -> Result<Vec<f64>, ComputationError> {
+        self.registry.run(name, Some(version), input)
+    }
+
+    /// The GPU context to use for a job of `work` multiply-adds, if any.
+    /// Deterministic precision stays on the f64 CPU path.
+    #[cfg(feature = "gpu")]
+    fn gpu_for(&self, work: usize) -> Option<&gpu::GpuContext> {
+        /// Below this the transfer overhead outweighs the GPU speedup
+        const AUTO_GPU_MIN_WORK: usize = 1 << 24;
+
+        let wanted = match self.backend {
+            ComputeBackend::Cpu => false,
+            ComputeBackend::Gpu => true,
+            ComputeBackend::Auto => work >= AUTO_GPU_MIN_WORK && self.precision == Precision::Fast,
+        };
+        if !wanted {
+            return None;
+        }
+        // Adapter discovery is slow, so it happens once, on first use
+        self.gpu.get_or_init(gpu::GpuContext::new).as_ref()
+    }
+
+    /// Sum `f(x)` over `data` according to the configured precision
+    fn sum_by(&self, data: &[f64], f: impl Fn(f64) -> f64 + Sync) -> f64 {
+        match self.precision {
+            Precision::Fast => data.iter().map(|&x| f(x)).sum(),
+            Precision::Deterministic => {
+                numeric::deterministic_sum(data, self.config.max_workers.max(1), f)
+            }
+        }
+    }
+
+    fn record(&self, op: Operation, start: std::time::Instant, success: bool) {
+        if let Some(metrics) = &self.metrics {
+            metrics.record(op, start.elapsed(), success);
+        }
+    }
+
+    /// Serve `op` from the persistent cache when enabled, computing and storing on a miss
+    #[cfg_attr(not(feature = "persistent-cache"), allow(unused_variables))]
+    fn cached<T>(
+        &self,
+        op: &str,
+        data: &[f64],
+        encode: fn(&T) -> Vec<f64>,
+        decode: fn(Vec<f64>) -> Option<T>,
+        compute: impl FnOnce() -> T,
+    ) -> T {
+        #[cfg(feature = "persistent-cache")]
+        if let Some(cache) = &self.persistent_cache {
+            // Modes and backends (f32 GPU vs f64 CPU) can differ in the last
+            // bits, so they never share entries
+            let mut op = op.to_string();
+            if self.precision == Precision::Deterministic {
+                op.push_str(":deterministic");
+            }
+            match self.backend {
+                ComputeBackend::Auto => {}
+                ComputeBackend::Cpu => op.push_str(":cpu"),
+                ComputeBackend::Gpu => op.push_str(":gpu"),
+            }
+            let op = op.as_str();
+            if let Some(value) = cache.get(op, data).and_then(decode) {
+                #[cfg(feature = "tracing")]
+                tracing::debug!(operation = op, "persistent cache hit");
+                return value;
+            }
+            let value = compute();
+            // A failed write only costs a future recomputation
+            let _ = cache.put(op, data, &encode(&value));
+            return value;
+        }
+
+        compute()
+    }
+
     /// Perform a heavy computation
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(input_len = data.len()))
+    )]
     pub fn compute_heavy_task(&self, data: &[f64]) -> ComputeResult {
         let start = std::time::Instant::now();
 
-        let result = if data.len() == 0 {
+        let result = if data.is_empty() {
             0.0
         } else {
-            self.perform_complex_calculation(data)
+            self.cached(
+                "compute_heavy_task",
+                data,
+                |v| vec![*v],
+                |v| v.first().copied(),
+                || self.perform_complex_calculation(data),
+            )
         };
+        self.record(Operation::HeavyTask, start, true);
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            value = result,
+            duration_us = start.elapsed().as_micros() as u64,
+            "heavy task complete"
+        );
 
         ComputeResult {
             success: true,
@@ -51,7 +281,32 @@ impl ComputeEngine {
     }
 
     /// Perform matrix multiplication
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            skip_all,
+            fields(rows_a = a.len(), rows_b = b.len())
+        )
+    )]
     pub fn multiply_matrices(&self, a: &[Vec<f64>], b: &[Vec<f64>]) -> Option<Vec<Vec<f64>>> {
+        let start = std::time::Instant::now();
+        let result = self.multiply_matrices_inner(a, b);
+        self.record(Operation::MultiplyMatrices, start, result.is_some());
+
+        #[cfg(feature = "tracing")]
+        match &result {
+            Some(_) => tracing::debug!(
+                duration_us = start.elapsed().as_micros() as u64,
+                "matrix multiplication complete"
+            ),
+            None => tracing::warn!("matrix dimensions incompatible"),
+        }
+
+        result
+    }
+
+    fn multiply_matrices_inner(&self, a: &[Vec<f64>], b: &[Vec<f64>]) -> Option<Vec<Vec<f64>>> {
         if a.is_empty() || b.is_empty() {
             return None;
         }
@@ -64,6 +319,11 @@ impl ComputeEngine {
             return None;
         }
 
+        #[cfg(feature = "gpu")]
+        if let Some(result) = self.gpu_for(rows_a * cols_a * cols_b).and_then(|gpu| gpu.multiply(a, b)) {
+            return Some(result);
+        }
+
         let mut result = vec![vec![0.0; cols_b]; rows_a];
 
         for i in 0..rows_a {
@@ -78,24 +338,129 @@ impl ComputeEngine {
     }
 
     /// Calculate statistical measures
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(input_len = data.len()))
+    )]
     pub fn calculate_statistics(&self, data: &[f64]) -> Statistics {
         if data.is_empty() {
             return Statistics::default();
         }
 
-        let sum: f64 = data.iter().sum();
+        self.cached(
+            "calculate_statistics",
+            data,
+            |s| vec![s.mean, s.median, s.std_dev, s.min, s.max],
+            |v| match v[..] {
+                [mean, median, std_dev, min, max] => Some(Statistics { mean, median, std_dev, min, max }),
+                _ => None,
+            },
+            || self.compute_statistics(data),
+        )
+    }
+
+    /// Calculate statistics for many series at once, spreading them across
+    /// `max_workers` threads. Results are returned in input order. Series are
+    /// computed directly and do not go through the persistent cache.
+    pub fn calculate_statistics_batch(&self, series: &[&[f64]]) -> Vec<Statistics> {
+        self.calculate_statistics_batch_with(series, &mut StatisticsScratch::default())
+    }
+
+    /// Like [`calculate_statistics_batch`](Self::calculate_statistics_batch), reusing
+    /// the caller's scratch buffers so repeated calls don't reallocate
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(series = series.len()))
+    )]
+    pub fn calculate_statistics_batch_with(
+        &self,
+        series: &[&[f64]],
+        scratch: &mut StatisticsScratch,
+    ) -> Vec<Statistics> {
+        let workers = self.config.max_workers.clamp(1, series.len().max(1));
+        if scratch.buffers.len() < workers {
+            scratch.buffers.resize_with(workers, Vec::new);
+        }
+
+        if workers == 1 {
+            let buffer = &mut scratch.buffers[0];
+            return series.iter().map(|data| self.statistics_into(data, buffer)).collect();
+        }
+
+        let per_worker = series.len().div_ceil(workers);
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = series
+                .chunks(per_worker)
+                .zip(scratch.buffers.iter_mut())
+                .map(|(group, buffer)| {
+                    scope.spawn(move || {
+                        group
+                            .iter()
+                            .map(|data| self.statistics_into(data, buffer))
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|h| h.join().expect("statistics worker panicked"))
+                .collect()
+        })
+    }
+
+    /// Statistics for each `size`-sample window of `data`, advancing by `stride`.
+    /// Computed incrementally; for unbounded streams use [`RollingWindow`] directly.
+    pub fn rolling_statistics(&self, data: &[f64], size: usize, stride: usize) -> Vec<Statistics> {
+        RollingWindow::new(size, stride)
+            .map(|mut window| window.extend(data))
+            .unwrap_or_default()
+    }
+
+    /// Principal component analysis of `data` (samples as rows), keeping `k`
+    /// components with their explained-variance ratios. `None` if `k` is zero or
+    /// exceeds the feature count, or there are fewer than two samples.
+    pub fn principal_components(&self, data: &Matrix, k: usize) -> Option<PrincipalComponents> {
+        linalg::principal_components(data, k)
+    }
+
+    /// Pearson correlation between every pair of columns of `data`
+    pub fn correlation_matrix(&self, data: &Matrix) -> Option<Matrix> {
+        linalg::correlation_matrix(data)
+    }
+
+    /// Point statistics plus a histogram and best-fitting distribution.
+    /// Returns `None` when `data` has no finite values to bin.
+    pub fn summarize_distribution(&self, data: &[f64], strategy: BinStrategy) -> Option<DistributionSummary> {
+        let histogram = Histogram::from_slice(data, strategy)?;
+        Some(DistributionSummary {
+            statistics: self.calculate_statistics(data),
+            cumulative: histogram.cumulative(),
+            histogram,
+            fit: histogram::best_fit(data),
+        })
+    }
+
+    fn compute_statistics(&self, data: &[f64]) -> Statistics {
+        self.statistics_into(data, &mut Vec::with_capacity(data.len()))
+    }
+
+    /// Compute statistics using `sorted` as the buffer for the median sort
+    fn statistics_into(&self, data: &[f64], sorted: &mut Vec<f64>) -> Statistics {
+        if data.is_empty() {
+            return Statistics::default();
+        }
+
+        let sum = self.sum_by(data, |x| x);
         let mean = sum / data.len() as f64;
 
-        let variance = data
-            .iter()
-            .map(|x| (x - mean).powi(2))
-            .sum::<f64>() / data.len() as f64;
+        let variance = self.sum_by(data, |x| (x - mean).powi(2)) / data.len() as f64;
 
         let std_dev = variance.sqrt();
 
-        let mut sorted = data.to_vec();
+        sorted.clear();
+        sorted.extend_from_slice(data);
         sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
-        let median = if sorted.len() % 2 == 0 {
+        let median = if sorted.len().is_multiple_of(2) {
             (sorted[sorted.len() / 2 - 1] + sorted[sorted.len() / 2]) / 2.0
         } else {
             sorted[sorted.len() / 2]
@@ -112,8 +477,46 @@ impl ComputeEngine {
 
     /// Perform Fourier transform (simplified)
     /// This is a high-complexity function for demo
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(input_len = data.len()))
+    )]
     pub fn fourier_transform(&self, data: &[f64]) -> Vec<Complex> {
+        let start = std::time::Instant::now();
+        let result = self.cached(
+            "fourier_transform",
+            data,
+            |spectrum: &Vec<Complex>| spectrum.iter().flat_map(|c| [c.real, c.imag]).collect(),
+            |v| {
+                (v.len() % 2 == 0).then(|| {
+                    v.chunks_exact(2)
+                        .map(|c| Complex { real: c[0], imag: c[1] })
+                        .collect()
+                })
+            },
+            || self.dft(data),
+        );
+
+        self.record(Operation::FourierTransform, start, true);
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            duration_us = start.elapsed().as_micros() as u64,
+            "fourier transform complete"
+        );
+
+        result
+    }
+
+    // Private helper methods
+
+    fn dft(&self, data: &[f64]) -> Vec<Complex> {
         let n = data.len();
+
+        #[cfg(feature = "gpu")]
+        if let Some(spectrum) = self.gpu_for(n * n).and_then(|gpu| gpu.dft(data)) {
+            return spectrum.into_iter().map(|(real, imag)| Complex { real, imag }).collect();
+        }
         let mut result = Vec::with_capacity(n);
 
         for k in 0..n {
@@ -132,13 +535,11 @@ impl ComputeEngine {
         result
     }
 
-    // Private helper methods
-
     fn perform_complex_calculation(&self, data: &[f64]) -> f64 {
         // Simulate complex calculation with multiple steps
-        let sum: f64 = data.iter().sum();
+        let sum = self.sum_by(data, |x| x);
         let product: f64 = data.iter().product();
-        let squares: f64 = data.iter().map(|x| x * x).sum();
+        let squares = self.sum_by(data, |x| x * x);
 
         (sum + product + squares) / data.len() as f64
     }
@@ -146,6 +547,7 @@ impl ComputeEngine {
 
 /// Statistical measures
 #[derive(Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Statistics {
     pub mean: f64,
     pub median: f64,
@@ -154,8 +556,32 @@ pub struct Statistics {
     pub max: f64,
 }
 
+/// Distribution summary returned by [`ComputeEngine::summarize_distribution`]
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DistributionSummary {
+    pub statistics: Statistics,
+    pub histogram: Histogram,
+    pub cumulative: Vec<f64>,
+    pub fit: Option<DistributionFit>,
+}
+
+/// Reusable buffers for [`ComputeEngine::calculate_statistics_batch_with`],
+/// one per worker thread
+#[derive(Debug, Default)]
+pub struct StatisticsScratch {
+    buffers: Vec<Vec<f64>>,
+}
+
+impl StatisticsScratch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
 /// Complex number representation
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Complex {
     pub real: f64,
     pub imag: f64,
@@ -199,6 +625,14 @@ impl OptimizationEngine {
 
     /// Find minimum using gradient descent
     /// High complexity function that could be a hotspot
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            skip_all,
+            fields(x0 = x, max_iterations = self.max_iterations, tolerance = self.tolerance)
+        )
+    )]
     pub fn gradient_descent<F>(&self, mut x: f64, f: F) -> f64
     where
         F: Fn(f64) -> f64,
@@ -206,7 +640,7 @@ impl OptimizationEngine {
         let learning_rate = 0.01;
         let h = 1e-5;
 
-        for _ in 0..self.max_iterations {
+        for _iteration in 0..self.max_iterations {
             // Compute gradient numerically
             let grad = (f(x + h) - f(x - h)) / (2.0 * h);
 
@@ -215,19 +649,74 @@ impl OptimizationEngine {
 
             // Check convergence
             if (x_new - x).abs() < self.tolerance {
-                break;
+                #[cfg(feature = "tracing")]
+                tracing::debug!(iterations = _iteration + 1, x, "converged");
+                return x;
             }
 
             x = x_new;
         }
 
+        #[cfg(feature = "tracing")]
+        tracing::warn!(x, "did not converge within max_iterations");
+
         x
     }
 }
 
-// This function might be dead code if never called
-pub fn legacy_computation(input: f64) -> f64 {
-    input * 2.0 + 1.0
+fn main() {
+    // JSON lines on stdout; the OTEL collector's filelog receiver parses these
+    #[cfg(feature = "tracing")]
+    tracing_subscriber::fmt()
+        .json()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .init();
+
+    let bind_addr = std::env::var("COMPUTE_BIND_ADDR")
+        .ok()
+        .map(|addr| addr.parse().expect("COMPUTE_BIND_ADDR must be host:port"));
+
+    let config = ComputeConfig {
+        max_workers: 4,
+        timeout_seconds: 30,
+        bind_addr: bind_addr.unwrap_or(ComputeConfig::DEFAULT_BIND_ADDR),
+    };
+
+    // COMPUTE_BACKEND=cpu|gpu overrides automatic backend selection
+    let backend = std::env::var("COMPUTE_BACKEND")
+        .map(|b| b.parse().expect("invalid COMPUTE_BACKEND"))
+        .unwrap_or_default();
+
+    let engine = ComputeEngine::new(config).with_backend(backend);
+
+    // COMPUTE_BIND_ADDR=0.0.0.0:8080 runs the HTTP API instead of the demo
+    #[cfg(feature = "http")]
+    if bind_addr.is_some() {
+        let runtime = tokio::runtime::Runtime::new().expect("failed to start tokio runtime");
+        runtime
+            .block_on(http::serve(Arc::new(engine), shutdown_signal()))
+            .expect("HTTP server failed");
+        return;
+    }
+
+    // COMPUTE_GRPC_ADDR=0.0.0.0:50051 runs the service instead of the demo
+    #[cfg(feature = "grpc")]
+    if let Ok(addr) = std::env::var("COMPUTE_GRPC_ADDR") {
+        let addr = addr.parse().expect("COMPUTE_GRPC_ADDR must be host:port");
+        let runtime = tokio::runtime::Runtime::new().expect("failed to start tokio runtime");
+        runtime
+            .block_on(grpc::serve(addr, Arc::new(engine), shutdown_signal()))
+            .expect("gRPC server failed");
+        return;
+    }
+
+    let data = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+
+    let result = engine.compute_heavy_task(&data);
+    println!("Computation result: {:?}", result);
+
+    let stats = engine.calculate_statistics(&data);
+    println!("Statistics: {:?}", stats);
 }
 
 #[cfg(test)]
@@ -239,6 +728,7 @@ mod tests {
         let config = ComputeConfig {
             max_workers: 4,
             timeout_seconds: 30,
+            bind_addr: ComputeConfig::DEFAULT_BIND_ADDR,
         };
         let engine = ComputeEngine::new(config);
         let data = vec![1.0, 2.0, 3.0, 4.0, 5.0];
@@ -251,26 +741,266 @@ mod tests {
         let config = ComputeConfig {
             max_workers: 4,
             timeout_seconds: 30,
+            bind_addr: ComputeConfig::DEFAULT_BIND_ADDR,
         };
         let engine = ComputeEngine::new(config);
         let data = vec![1.0, 2.0, 3.0, 4.0, 5.0];
         let stats = engine.calculate_statistics(&data);
         assert_eq!(stats.mean, 3.0);
     }
-}
 
-fn main() {
-    let config = ComputeConfig {
-        max_workers: 4,
-        timeout_seconds: 30,
-    };
+    #[test]
+    fn test_statistics_batch_matches_single_calls() {
+        let config = ComputeConfig {
+            max_workers: 3,
+            timeout_seconds: 30,
+            bind_addr: ComputeConfig::DEFAULT_BIND_ADDR,
+        };
+        let engine = ComputeEngine::new(config);
+        let owned: Vec<Vec<f64>> = (0..10)
+            .map(|n| (0..n * 7).map(|i| ((i * 31 + n) % 17) as f64).collect())
+            .collect();
+        let series: Vec<&[f64]> = owned.iter().map(|s| s.as_slice()).collect();
+
+        let mut scratch = StatisticsScratch::new();
+        let batch = engine.calculate_statistics_batch_with(&series, &mut scratch);
+        assert_eq!(batch.len(), series.len());
+        for (data, stats) in series.iter().zip(&batch) {
+            let single = engine.calculate_statistics(data);
+            assert_eq!(stats.mean.to_bits(), single.mean.to_bits());
+            assert_eq!(stats.median, single.median);
+            assert_eq!(stats.std_dev.to_bits(), single.std_dev.to_bits());
+        }
 
-    let engine = ComputeEngine::new(config);
-    let data = vec![1.0, 2.0, 3.0, 4.0, 5.0];
+        // Scratch buffers keep their capacity for the next call
+        assert!(scratch.buffers.iter().any(|b| b.capacity() > 0));
+        assert!(engine.calculate_statistics_batch(&[]).is_empty());
+    }
 
-    let result = engine.compute_heavy_task(&data);
-    println!("Computation result: {:?}", result);
+    #[test]
+    fn test_distribution_summary() {
+        let config = ComputeConfig {
+            max_workers: 2,
+            timeout_seconds: 30,
+            bind_addr: ComputeConfig::DEFAULT_BIND_ADDR,
+        };
+        let engine = ComputeEngine::new(config);
+        let data: Vec<f64> = (0..100).map(|i| i as f64).collect();
+
+        let summary = engine
+            .summarize_distribution(&data, BinStrategy::FixedWidth { bins: 4 })
+            .unwrap();
+        assert_eq!(summary.statistics.mean, 49.5);
+        assert_eq!(summary.histogram.counts(), &[25, 25, 25, 25]);
+        assert_eq!(summary.cumulative, vec![0.25, 0.5, 0.75, 1.0]);
+        assert!(summary.fit.is_some());
+        assert!(engine.summarize_distribution(&[], BinStrategy::FreedmanDiaconis).is_none());
+    }
 
-    let stats = engine.calculate_statistics(&data);
-    println!("Statistics: {:?}", stats);
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_distribution_summary_serde_round_trip() {
+        let engine = ComputeEngine::new(ComputeConfig {
+            max_workers: 2,
+            timeout_seconds: 30,
+            bind_addr: ComputeConfig::DEFAULT_BIND_ADDR,
+        });
+        let data: Vec<f64> = (0..50).map(|i| (i % 7) as f64).collect();
+        let summary = engine.summarize_distribution(&data, BinStrategy::FreedmanDiaconis).unwrap();
+
+        let json = serde_json::to_value(&summary).unwrap();
+        assert_eq!(json["statistics"]["mean"], summary.statistics.mean);
+        let parsed: DistributionSummary = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.statistics.median, summary.statistics.median);
+        assert_eq!(parsed.histogram, summary.histogram);
+        assert_eq!(parsed.cumulative, summary.cumulative);
+        assert_eq!(parsed.fit, summary.fit);
+
+        let result: ComputeResult = serde_json::from_str(r#"{"success":true,"value":2.5,"duration_ms":3}"#).unwrap();
+        assert!(result.success && result.value == 2.5 && result.duration_ms == 3);
+    }
+
+    #[test]
+    fn test_run_named_dispatches_to_registry() {
+        let config = ComputeConfig {
+            max_workers: 1,
+            timeout_seconds: 30,
+            bind_addr: ComputeConfig::DEFAULT_BIND_ADDR,
+        };
+        let engine = ComputeEngine::new(config);
+        assert_eq!(engine.run_named("legacy", &[0.5, 3.0]).unwrap(), vec![2.0, 7.0]);
+        assert_eq!(engine.registry().get("legacy", None).unwrap().calls(), 1);
+        assert!(engine.run_named_version("legacy", 2, &[1.0]).is_err());
+    }
+
+    #[test]
+    fn test_forced_backend_falls_back_to_cpu() {
+        let config = ComputeConfig {
+            max_workers: 1,
+            timeout_seconds: 30,
+            bind_addr: ComputeConfig::DEFAULT_BIND_ADDR,
+        };
+        // Without an adapter (or the gpu feature) a forced GPU backend still computes on the CPU
+        let engine = ComputeEngine::new(config).with_backend("gpu".parse().unwrap());
+        let product = engine
+            .multiply_matrices(&[vec![1.0, 2.0], vec![3.0, 4.0]], &[vec![5.0], vec![6.0]])
+            .unwrap();
+        assert!((product[0][0] - 17.0).abs() < 1e-4 && (product[1][0] - 39.0).abs() < 1e-4);
+        assert!("tpu".parse::<ComputeBackend>().is_err());
+    }
+
+    #[test]
+    fn test_deterministic_statistics_ignore_worker_count() {
+        let data: Vec<f64> = (0..50_000).map(|i| 1e8 + (i as f64) * 0.1).collect();
+        let stats = |max_workers| {
+            let config = ComputeConfig {
+                max_workers,
+                timeout_seconds: 30,
+                bind_addr: ComputeConfig::DEFAULT_BIND_ADDR,
+            };
+            ComputeEngine::new(config)
+                .with_precision(Precision::Deterministic)
+                .calculate_statistics(&data)
+        };
+
+        let single = stats(1);
+        for workers in [2, 4, 7] {
+            let other = stats(workers);
+            assert_eq!(other.mean.to_bits(), single.mean.to_bits());
+            assert_eq!(other.std_dev.to_bits(), single.std_dev.to_bits());
+        }
+    }
+
+    /// Spans opened and events emitted while `f` runs, one line each: the span
+    /// name or event level followed by its fields as ` name=value`
+    #[cfg(feature = "tracing")]
+    fn traced<R>(f: impl FnOnce() -> R) -> (R, Vec<String>) {
+        use std::fmt::Write;
+        use std::sync::Mutex;
+        use tracing_subscriber::layer::{Context, SubscriberExt};
+        use tracing_subscriber::Layer;
+
+        struct Fields(String);
+
+        impl tracing::field::Visit for Fields {
+            fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+                let _ = write!(self.0, " {}={:?}", field.name(), value);
+            }
+        }
+
+        struct Capture(Arc<Mutex<Vec<String>>>);
+
+        impl<S: tracing::Subscriber> Layer<S> for Capture {
+            fn on_new_span(
+                &self,
+                attrs: &tracing::span::Attributes<'_>,
+                _: &tracing::span::Id,
+                _: Context<'_, S>,
+            ) {
+                let mut fields = Fields(attrs.metadata().name().to_string());
+                attrs.record(&mut fields);
+                self.0.lock().unwrap().push(fields.0);
+            }
+
+            fn on_event(&self, event: &tracing::Event<'_>, _: Context<'_, S>) {
+                let mut fields = Fields(event.metadata().level().to_string());
+                event.record(&mut fields);
+                self.0.lock().unwrap().push(fields.0);
+            }
+        }
+
+        let lines = Arc::new(Mutex::new(Vec::new()));
+        let subscriber = tracing_subscriber::registry().with(Capture(Arc::clone(&lines)));
+        let result = tracing::subscriber::with_default(subscriber, f);
+        let lines = lines.lock().unwrap().clone();
+        (result, lines)
+    }
+
+    #[cfg(feature = "tracing")]
+    fn has_line(lines: &[String], prefix: &str, parts: &[&str]) -> bool {
+        lines
+            .iter()
+            .any(|line| line.starts_with(prefix) && parts.iter().all(|part| line.contains(part)))
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_compute_operations_are_traced() {
+        let engine = ComputeEngine::new(ComputeConfig {
+            max_workers: 1,
+            timeout_seconds: 30,
+            bind_addr: ComputeConfig::DEFAULT_BIND_ADDR,
+        });
+
+        let (_, lines) = traced(|| {
+            engine.compute_heavy_task(&[1.0, 2.0, 3.0]);
+            engine.multiply_matrices(&[vec![1.0, 2.0]], &[vec![1.0, 2.0]]);
+            engine.fourier_transform(&[1.0, 0.0, -1.0, 0.0]);
+            engine.calculate_statistics(&[4.0, 5.0]);
+        });
+
+        assert!(has_line(&lines, "compute_heavy_task", &["input_len=3"]));
+        assert!(has_line(&lines, "DEBUG", &["message=heavy task complete", "duration_us="]));
+        assert!(has_line(&lines, "multiply_matrices", &["rows_a=1", "rows_b=1"]));
+        assert!(has_line(&lines, "WARN", &["message=matrix dimensions incompatible"]));
+        assert!(has_line(&lines, "fourier_transform", &["input_len=4"]));
+        assert!(has_line(&lines, "DEBUG", &["message=fourier transform complete"]));
+        assert!(has_line(&lines, "calculate_statistics", &["input_len=2"]));
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_optimization_convergence_is_traced() {
+        let quadratic = |x: f64| (x - 2.0).powi(2);
+
+        let optimizer = OptimizationEngine::new(10_000, 1e-6);
+        let (_, lines) = traced(|| optimizer.gradient_descent(5.0, quadratic));
+        assert!(has_line(&lines, "gradient_descent", &["x0=5.0", "max_iterations=10000"]));
+        assert!(has_line(&lines, "DEBUG", &["message=converged", "iterations="]));
+
+        let (_, lines) = traced(|| OptimizationEngine::new(1, 1e-12).gradient_descent(5.0, quadratic));
+        assert!(has_line(&lines, "WARN", &["message=did not converge within max_iterations"]));
+
+        let policy = CheckpointPolicy { every: 1, momentum: 0.0 };
+        let (state, lines) = traced(|| {
+            OptimizationEngine::new(3, 1e-12).run_with_checkpoint(5.0, quadratic, &mut Vec::<u8>::new(), policy)
+        });
+        assert_eq!(state.unwrap().iteration, 3);
+        assert!(has_line(&lines, "optimize_from", &["start_iteration=0"]));
+        assert!(has_line(&lines, "TRACE", &["message=checkpoint written", "iteration=1"]));
+        assert!(has_line(&lines, "DEBUG", &["message=optimization stopped", "converged=false"]));
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_deprecated_computation_is_traced() {
+        let registry = ComputationRegistry::with_builtins();
+        let (output, lines) = traced(|| registry.run("legacy", None, &[1.0]));
+        assert_eq!(output.unwrap(), vec![3.0]);
+        assert!(has_line(&lines, "WARN", &["computation=\"legacy\"", "version=1", "calls=1"]));
+    }
+
+    #[cfg(all(feature = "tracing", feature = "persistent-cache"))]
+    #[test]
+    fn test_persistent_cache_hits_are_traced() {
+        let dir = std::env::temp_dir().join(format!("cv-compute-trace-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let engine = ComputeEngine::with_persistent_cache(
+            ComputeConfig {
+                max_workers: 1,
+                timeout_seconds: 30,
+                bind_addr: ComputeConfig::DEFAULT_BIND_ADDR,
+            },
+            &dir,
+        )
+        .unwrap();
+
+        let (_, first) = traced(|| engine.calculate_statistics(&[1.0, 2.0]));
+        let (_, second) = traced(|| engine.calculate_statistics(&[1.0, 2.0]));
+        assert!(!has_line(&first, "DEBUG", &["message=persistent cache hit"]));
+        let hit = ["message=persistent cache hit", "operation=\"calculate_statistics\""];
+        assert!(has_line(&second, "DEBUG", &hit));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
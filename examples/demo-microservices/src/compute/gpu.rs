@@ -0,0 +1,352 @@
+// GPU backend - wgpu compute shaders for matrix multiplication and the DFT (feature = "gpu")
+//
+// Kernels run in f32, so results differ from the f64 CPU path in the low bits.
+// Inputs too large for the adapter's storage-buffer or dispatch limits return
+// `None` and the engine falls back to the CPU.
+
+use std::sync::mpsc;
+
+use wgpu::util::DeviceExt;
+
+const MATMUL_SHADER: &str = r#"
+struct Dims { m: u32, k: u32, n: u32, _pad: u32 }
+
+@group(0) @binding(0) var<storage, read> a: array<f32>;
+@group(0) @binding(1) var<storage, read> b: array<f32>;
+@group(0) @binding(2) var<storage, read_write> out: array<f32>;
+@group(0) @binding(3) var<uniform> dims: Dims;
+
+const TILE: u32 = 16u;
+var<workgroup> tile_a: array<array<f32, 16>, 16>;
+var<workgroup> tile_b: array<array<f32, 16>, 16>;
+
+@compute @workgroup_size(16, 16)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>, @builtin(local_invocation_id) lid: vec3<u32>) {
+    let row = gid.y;
+    let col = gid.x;
+    var acc = 0.0;
+
+    let tiles = (dims.k + TILE - 1u) / TILE;
+    for (var t = 0u; t < tiles; t++) {
+        let ka = t * TILE + lid.x;
+        let kb = t * TILE + lid.y;
+        tile_a[lid.y][lid.x] = 0.0;
+        tile_b[lid.y][lid.x] = 0.0;
+        if (row < dims.m && ka < dims.k) {
+            tile_a[lid.y][lid.x] = a[row * dims.k + ka];
+        }
+        if (kb < dims.k && col < dims.n) {
+            tile_b[lid.y][lid.x] = b[kb * dims.n + col];
+        }
+        workgroupBarrier();
+
+        for (var i = 0u; i < TILE; i++) {
+            acc += tile_a[lid.y][i] * tile_b[i][lid.x];
+        }
+        workgroupBarrier();
+    }
+
+    if (row < dims.m && col < dims.n) {
+        out[row * dims.n + col] = acc;
+    }
+}
+"#;
+
+const DFT_SHADER: &str = r#"
+struct Params { n: u32, _pad0: u32, _pad1: u32, _pad2: u32 }
+
+@group(0) @binding(0) var<storage, read> input: array<f32>;
+@group(0) @binding(1) var<storage, read_write> out: array<vec2<f32>>;
+@group(0) @binding(2) var<uniform> params: Params;
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    let k = gid.x;
+    let n = params.n;
+    if (k >= n) {
+        return;
+    }
+
+    var re = 0.0;
+    var im = 0.0;
+    // (k * t) mod n, kept incrementally so it never overflows and the angle stays small
+    var idx = 0u;
+    for (var t = 0u; t < n; t++) {
+        let angle = -6.283185307179586 * f32(idx) / f32(n);
+        re += input[t] * cos(angle);
+        im += input[t] * sin(angle);
+        idx = (idx + k) % n;
+    }
+    out[k] = vec2<f32>(re, im);
+}
+"#;
+
+/// A GPU device with the compute pipelines compiled
+pub struct GpuContext {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    matmul: wgpu::ComputePipeline,
+    dft: wgpu::ComputePipeline,
+    max_binding_bytes: u64,
+    max_workgroups: u32,
+}
+
+impl GpuContext {
+    /// Acquire a GPU adapter; `None` when the machine has no usable adapter
+    pub fn new() -> Option<GpuContext> {
+        pollster::block_on(Self::init())
+    }
+
+    async fn init() -> Option<GpuContext> {
+        let instance = wgpu::Instance::default();
+        let adapter = instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                power_preference: wgpu::PowerPreference::HighPerformance,
+                compatible_surface: None,
+                force_fallback_adapter: false,
+            })
+            .await?;
+
+        let limits = adapter.limits();
+        let (device, queue) = adapter
+            .request_device(
+                &wgpu::DeviceDescriptor {
+                    label: Some("compute-engine"),
+                    required_features: wgpu::Features::empty(),
+                    required_limits: limits.clone(),
+                },
+                None,
+            )
+            .await
+            .ok()?;
+
+        let pipeline = |label: &str, source: &str| {
+            let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some(label),
+                source: wgpu::ShaderSource::Wgsl(source.into()),
+            });
+            device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: Some(label),
+                layout: None,
+                module: &module,
+                entry_point: "main",
+                compilation_options: Default::default(),
+            })
+        };
+
+        Some(GpuContext {
+            matmul: pipeline("matmul", MATMUL_SHADER),
+            dft: pipeline("dft", DFT_SHADER),
+            max_binding_bytes: u64::from(limits.max_storage_buffer_binding_size).min(limits.max_buffer_size),
+            max_workgroups: limits.max_compute_workgroups_per_dimension,
+            device,
+            queue,
+        })
+    }
+
+    /// Multiply `a` (m×k) by `b` (k×n); dimensions must already be validated
+    pub fn multiply(&self, a: &[Vec<f64>], b: &[Vec<f64>]) -> Option<Vec<Vec<f64>>> {
+        let (m, k) = (a.len(), b.len());
+        if m == 0 || k == 0 {
+            return None;
+        }
+        let n = b[0].len();
+        if n == 0 {
+            return None;
+        }
+        let largest = (m * k).max(k * n).max(m * n) as u64 * 4;
+        if largest > self.max_binding_bytes {
+            return None;
+        }
+        let groups = (
+            dispatch_size(n, 16, self.max_workgroups)?,
+            dispatch_size(m, 16, self.max_workgroups)?,
+            1,
+        );
+
+        let a_bytes = to_bytes(a.iter().flat_map(|row| row.iter()));
+        let b_bytes = to_bytes(b.iter().flat_map(|row| row.iter()));
+        let dims = to_u32_bytes(&[m as u32, k as u32, n as u32, 0]);
+
+        let out = self.run(
+            &self.matmul,
+            &[&a_bytes, &b_bytes],
+            &dims,
+            (m * n * 4) as u64,
+            groups,
+        )?;
+
+        Some(out.chunks_exact(n).map(|row| row.to_vec()).collect())
+    }
+
+    /// Discrete Fourier transform as (real, imag) pairs
+    pub fn dft(&self, data: &[f64]) -> Option<Vec<(f64, f64)>> {
+        let n = data.len();
+        if n == 0 || (n * 8) as u64 > self.max_binding_bytes {
+            return None;
+        }
+        let groups = dispatch_size(n, 64, self.max_workgroups)?;
+
+        let input = to_bytes(data.iter());
+        let params = to_u32_bytes(&[n as u32, 0, 0, 0]);
+        let out = self.run(&self.dft, &[&input], &params, (n * 8) as u64, (groups, 1, 1))?;
+
+        Some(out.chunks_exact(2).map(|c| (c[0], c[1])).collect())
+    }
+
+    /// Bind `inputs` (read-only storage), an output buffer and a uniform in
+    /// shader binding order, dispatch, and read the output back as f64
+    fn run(
+        &self,
+        pipeline: &wgpu::ComputePipeline,
+        inputs: &[&[u8]],
+        uniform: &[u8],
+        output_bytes: u64,
+        workgroups: (u32, u32, u32),
+    ) -> Option<Vec<f64>> {
+        let storage: Vec<wgpu::Buffer> = inputs
+            .iter()
+            .map(|bytes| {
+                self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                    label: None,
+                    contents: bytes,
+                    usage: wgpu::BufferUsages::STORAGE,
+                })
+            })
+            .collect();
+        let output = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("output"),
+            size: output_bytes,
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        let readback = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("readback"),
+            size: output_bytes,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let uniform = self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("params"),
+            contents: uniform,
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+
+        let entries: Vec<wgpu::BindGroupEntry> = storage
+            .iter()
+            .chain([&output, &uniform])
+            .enumerate()
+            .map(|(binding, buffer)| wgpu::BindGroupEntry {
+                binding: binding as u32,
+                resource: buffer.as_entire_binding(),
+            })
+            .collect();
+
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &pipeline.get_bind_group_layout(0),
+            entries: &entries,
+        });
+
+        let mut encoder = self
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        {
+            let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: None,
+                timestamp_writes: None,
+            });
+            pass.set_pipeline(pipeline);
+            pass.set_bind_group(0, &bind_group, &[]);
+            pass.dispatch_workgroups(workgroups.0, workgroups.1, workgroups.2);
+        }
+        encoder.copy_buffer_to_buffer(&output, 0, &readback, 0, output_bytes);
+        self.queue.submit(Some(encoder.finish()));
+
+        let slice = readback.slice(..);
+        let (tx, rx) = mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = tx.send(result);
+        });
+        self.device.poll(wgpu::Maintain::Wait);
+        rx.recv().ok()?.ok()?;
+
+        let values = slice
+            .get_mapped_range()
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64)
+            .collect();
+        readback.unmap();
+        Some(values)
+    }
+}
+
+/// Workgroups covering `items` along one dimension, or `None` past the adapter's
+/// per-dimension limit (wgpu would fail validation and panic)
+fn dispatch_size(items: usize, per_group: usize, max_workgroups: u32) -> Option<u32> {
+    u32::try_from(items.div_ceil(per_group))
+        .ok()
+        .filter(|&groups| groups <= max_workgroups)
+}
+
+fn to_bytes<'a>(values: impl Iterator<Item = &'a f64>) -> Vec<u8> {
+    values.flat_map(|&v| (v as f32).to_le_bytes()).collect()
+}
+
+fn to_u32_bytes(values: &[u32]) -> Vec<u8> {
+    values.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Skipped (not failed) on machines without an adapter, e.g. headless CI
+    fn context() -> Option<GpuContext> {
+        let ctx = GpuContext::new();
+        if ctx.is_none() {
+            eprintln!("no GPU adapter; skipping");
+        }
+        ctx
+    }
+
+    #[test]
+    fn test_dispatch_size_respects_limit() {
+        assert_eq!(dispatch_size(100, 64, 65535), Some(2));
+        assert_eq!(dispatch_size(65535 * 64, 64, 65535), Some(65535));
+        // A DFT of ~4.2M samples fits the buffers but not one dispatch dimension
+        assert_eq!(dispatch_size(65535 * 64 + 1, 64, 65535), None);
+    }
+
+    #[test]
+    fn test_gpu_matmul_matches_cpu() {
+        let Some(gpu) = context() else { return };
+        let a: Vec<Vec<f64>> = (0..37).map(|i| (0..29).map(|j| ((i * j) % 7) as f64).collect()).collect();
+        let b: Vec<Vec<f64>> = (0..29).map(|i| (0..41).map(|j| ((i + j) % 5) as f64).collect()).collect();
+
+        let result = gpu.multiply(&a, &b).unwrap();
+        for i in 0..37 {
+            for j in 0..41 {
+                let expected: f64 = (0..29).map(|k| a[i][k] * b[k][j]).sum();
+                assert!((result[i][j] - expected).abs() < 1e-3);
+            }
+        }
+    }
+
+    #[test]
+    fn test_gpu_dft_matches_cpu() {
+        let Some(gpu) = context() else { return };
+        let data: Vec<f64> = (0..100).map(|i| (i as f64 * 0.3).sin()).collect();
+        let spectrum = gpu.dft(&data).unwrap();
+
+        for (k, &(re, im)) in spectrum.iter().enumerate() {
+            let (mut er, mut ei) = (0.0, 0.0);
+            for (t, &x) in data.iter().enumerate() {
+                let angle = -2.0 * std::f64::consts::PI * (k * t) as f64 / 100.0;
+                er += x * angle.cos();
+                ei += x * angle.sin();
+            }
+            assert!((re - er).abs() < 1e-2 && (im - ei).abs() < 1e-2);
+        }
+    }
+}
@@ -0,0 +1,167 @@
+// gRPC server - exposes ComputeEngine over tonic (feature = "grpc")
+//
+// Generated types come from proto/compute.proto, compiled by build.rs; building
+// with the feature needs `protoc` on PATH.
+
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use tonic::{transport::Server, Request, Response, Status};
+
+use crate::{ComputeEngine, Statistics};
+
+pub mod proto {
+    tonic::include_proto!("compute.v1");
+}
+
+use proto::compute_service_server::{ComputeService, ComputeServiceServer};
+use proto::{
+    ComputeResponse, DataRequest, Matrix, MatrixRequest, MatrixResponse, Row, StatisticsResponse,
+};
+
+/// gRPC handler backed by a shared engine
+pub struct ComputeGrpcService {
+    engine: Arc<ComputeEngine>,
+}
+
+impl ComputeGrpcService {
+    pub fn new(engine: Arc<ComputeEngine>) -> Self {
+        ComputeGrpcService { engine }
+    }
+
+    /// CPU-bound work runs on the blocking pool so it can't stall the reactor
+    async fn run_blocking<T, F>(&self, f: F) -> Result<T, Status>
+    where
+        F: FnOnce(&ComputeEngine) -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let engine = Arc::clone(&self.engine);
+        tokio::task::spawn_blocking(move || f(&engine))
+            .await
+            .map_err(|e| Status::internal(format!("computation panicked: {}", e)))
+    }
+}
+
+#[tonic::async_trait]
+impl ComputeService for ComputeGrpcService {
+    async fn compute_heavy_task(
+        &self,
+        request: Request<DataRequest>,
+    ) -> Result<Response<ComputeResponse>, Status> {
+        let values = request.into_inner().values;
+        let result = self.run_blocking(move |e| e.compute_heavy_task(&values)).await?;
+
+        Ok(Response::new(ComputeResponse {
+            success: result.success,
+            value: result.value,
+            duration_ms: result.duration_ms as u64,
+        }))
+    }
+
+    async fn multiply_matrices(
+        &self,
+        request: Request<MatrixRequest>,
+    ) -> Result<Response<MatrixResponse>, Status> {
+        let req = request.into_inner();
+        let a = from_proto_matrix(req.a);
+        let b = from_proto_matrix(req.b);
+
+        let product = self
+            .run_blocking(move |e| e.multiply_matrices(&a, &b))
+            .await?
+            .ok_or_else(|| Status::invalid_argument("matrix dimensions are incompatible"))?;
+
+        Ok(Response::new(MatrixResponse {
+            result: Some(to_proto_matrix(product)),
+        }))
+    }
+
+    async fn calculate_statistics(
+        &self,
+        request: Request<DataRequest>,
+    ) -> Result<Response<StatisticsResponse>, Status> {
+        let values = request.into_inner().values;
+        let stats = self.run_blocking(move |e| e.calculate_statistics(&values)).await?;
+        Ok(Response::new(to_proto_statistics(stats)))
+    }
+}
+
+fn from_proto_matrix(matrix: Option<Matrix>) -> Vec<Vec<f64>> {
+    matrix
+        .map(|m| m.rows.into_iter().map(|r| r.values).collect())
+        .unwrap_or_default()
+}
+
+fn to_proto_matrix(rows: Vec<Vec<f64>>) -> Matrix {
+    Matrix {
+        rows: rows.into_iter().map(|values| Row { values }).collect(),
+    }
+}
+
+fn to_proto_statistics(stats: Statistics) -> StatisticsResponse {
+    StatisticsResponse {
+        mean: stats.mean,
+        median: stats.median,
+        std_dev: stats.std_dev,
+        min: stats.min,
+        max: stats.max,
+    }
+}
+
+/// Serve the compute gRPC API on `addr` until `shutdown` resolves.
+/// In-flight requests are allowed to finish before this returns.
+pub async fn serve<S>(
+    addr: SocketAddr,
+    engine: Arc<ComputeEngine>,
+    shutdown: S,
+) -> Result<(), tonic::transport::Error>
+where
+    S: Future<Output = ()>,
+{
+    Server::builder()
+        .add_service(ComputeServiceServer::new(ComputeGrpcService::new(engine)))
+        .serve_with_shutdown(addr, shutdown)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ComputeConfig;
+
+    fn service() -> ComputeGrpcService {
+        ComputeGrpcService::new(Arc::new(ComputeEngine::new(ComputeConfig {
+            max_workers: 2,
+            timeout_seconds: 30,
+            bind_addr: ComputeConfig::DEFAULT_BIND_ADDR,
+        })))
+    }
+
+    #[tokio::test]
+    async fn test_statistics_rpc() {
+        let response = service()
+            .calculate_statistics(Request::new(DataRequest {
+                values: vec![1.0, 2.0, 3.0, 4.0, 5.0],
+            }))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(response.mean, 3.0);
+        assert_eq!(response.max, 5.0);
+    }
+
+    #[tokio::test]
+    async fn test_multiply_rejects_bad_shapes() {
+        let a = to_proto_matrix(vec![vec![1.0, 2.0]]);
+        let b = to_proto_matrix(vec![vec![1.0, 2.0]]);
+        let status = service()
+            .multiply_matrices(Request::new(MatrixRequest {
+                a: Some(a),
+                b: Some(b),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+    }
+}
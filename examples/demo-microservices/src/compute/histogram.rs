@@ -0,0 +1,338 @@
+// Histogram - binned distributions and simple distribution fitting
+
+/// Upper bound on bins produced by data-driven strategies
+pub const MAX_BINS: usize = 1000;
+
+/// How bin edges are chosen
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BinStrategy {
+    /// `bins` equal-width bins spanning min..=max
+    FixedWidth { bins: usize },
+    /// Width 2 * IQR / n^(1/3); robust to outliers
+    FreedmanDiaconis,
+    /// `bins` bins equally spaced in log10; requires strictly positive data
+    LogScale { bins: usize },
+}
+
+/// Counts of values falling into contiguous bins.
+/// Bin `i` covers `[edges[i], edges[i + 1])`; the last bin also includes its upper edge.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "RawHistogram")
+)]
+pub struct Histogram {
+    edges: Vec<f64>,
+    counts: Vec<u64>,
+    total: u64,
+}
+
+/// Deserialized fields, checked against the invariants `from_slice` upholds
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct RawHistogram {
+    edges: Vec<f64>,
+    counts: Vec<u64>,
+    total: u64,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawHistogram> for Histogram {
+    type Error = &'static str;
+
+    fn try_from(raw: RawHistogram) -> Result<Histogram, &'static str> {
+        if raw.counts.is_empty() || raw.edges.len() != raw.counts.len() + 1 {
+            return Err("a histogram needs one more edge than it has bins, and at least one bin");
+        }
+        if raw.counts.iter().sum::<u64>() != raw.total {
+            return Err("histogram counts do not add up to its total");
+        }
+        Ok(Histogram {
+            edges: raw.edges,
+            counts: raw.counts,
+            total: raw.total,
+        })
+    }
+}
+
+impl Histogram {
+    /// Bin the finite values of `data`. Returns `None` if there are none, or if
+    /// a log-scale histogram is requested for data containing non-positive values.
+    pub fn from_slice(data: &[f64], strategy: BinStrategy) -> Option<Histogram> {
+        let mut values: Vec<f64> = data.iter().copied().filter(|x| x.is_finite()).collect();
+        if values.is_empty() {
+            return None;
+        }
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        let min = values[0];
+        let max = values[values.len() - 1];
+
+        let edges = match strategy {
+            BinStrategy::FixedWidth { bins } => linear_edges(min, max, bins.clamp(1, MAX_BINS)),
+            BinStrategy::FreedmanDiaconis => {
+                let iqr = quantile(&values, 0.75) - quantile(&values, 0.25);
+                let width = 2.0 * iqr / (values.len() as f64).cbrt();
+                let bins = if width > 0.0 {
+                    ((max - min) / width).ceil() as usize
+                } else {
+                    1
+                };
+                linear_edges(min, max, bins.clamp(1, MAX_BINS))
+            }
+            BinStrategy::LogScale { bins } => {
+                if min <= 0.0 {
+                    return None;
+                }
+                let mut edges: Vec<f64> = linear_edges(min.log10(), max.log10(), bins.clamp(1, MAX_BINS))
+                    .into_iter()
+                    .map(|e| 10f64.powf(e))
+                    .collect();
+                // Exact endpoints, so min and max land inside despite powf rounding
+                let last = edges.len() - 1;
+                edges[0] = min;
+                edges[last] = max;
+                edges
+            }
+        };
+
+        let mut counts = vec![0u64; edges.len() - 1];
+        let last = counts.len() - 1;
+        for &x in &values {
+            let bin = edges.partition_point(|&e| e <= x).saturating_sub(1).min(last);
+            counts[bin] += 1;
+        }
+
+        Some(Histogram {
+            edges,
+            total: values.len() as u64,
+            counts,
+        })
+    }
+
+    /// Bin boundaries; one more than the number of bins
+    pub fn edges(&self) -> &[f64] {
+        &self.edges
+    }
+
+    /// Number of values in each bin
+    pub fn counts(&self) -> &[u64] {
+        &self.counts
+    }
+
+    /// Number of values binned (non-finite inputs are excluded)
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// Empirical cumulative distribution evaluated at each bin's upper edge
+    pub fn cumulative(&self) -> Vec<f64> {
+        let mut running = 0u64;
+        self.counts
+            .iter()
+            .map(|&c| {
+                running += c;
+                running as f64 / self.total as f64
+            })
+            .collect()
+    }
+}
+
+fn linear_edges(lo: f64, hi: f64, bins: usize) -> Vec<f64> {
+    if hi <= lo {
+        return vec![lo, hi];
+    }
+    let width = (hi - lo) / bins as f64;
+    let mut edges: Vec<f64> = (0..bins).map(|i| lo + width * i as f64).collect();
+    // Pin the last edge so rounding never leaves the maximum outside
+    edges.push(hi);
+    edges
+}
+
+/// Linearly interpolated quantile of sorted data
+fn quantile(sorted: &[f64], q: f64) -> f64 {
+    let pos = q * (sorted.len() - 1) as f64;
+    let lower = pos.floor() as usize;
+    let upper = pos.ceil() as usize;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (pos - lower as f64)
+}
+
+/// A fitted parametric distribution
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Distribution {
+    Normal { mean: f64, std_dev: f64 },
+    Exponential { rate: f64 },
+}
+
+impl Distribution {
+    /// Cumulative distribution function
+    pub fn cdf(&self, x: f64) -> f64 {
+        match *self {
+            Distribution::Normal { mean, std_dev } => {
+                if std_dev == 0.0 {
+                    return if x < mean { 0.0 } else { 1.0 };
+                }
+                0.5 * (1.0 + erf((x - mean) / (std_dev * std::f64::consts::SQRT_2)))
+            }
+            Distribution::Exponential { rate } => {
+                if x < 0.0 {
+                    0.0
+                } else {
+                    1.0 - (-rate * x).exp()
+                }
+            }
+        }
+    }
+}
+
+/// A distribution with its goodness of fit
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DistributionFit {
+    pub distribution: Distribution,
+    /// Kolmogorov–Smirnov statistic: largest gap between empirical and fitted CDFs
+    pub ks_statistic: f64,
+}
+
+/// Fit a normal distribution by the method of moments
+pub fn fit_normal(data: &[f64]) -> Option<DistributionFit> {
+    let (values, mean) = finite_sorted_with_mean(data)?;
+    let variance = values.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / values.len() as f64;
+    let distribution = Distribution::Normal {
+        mean,
+        std_dev: variance.sqrt(),
+    };
+    Some(DistributionFit {
+        distribution,
+        ks_statistic: ks_statistic(&values, &distribution),
+    })
+}
+
+/// Fit an exponential distribution by the method of moments (rate = 1 / mean).
+/// Returns `None` for data with negative values or a non-positive mean.
+pub fn fit_exponential(data: &[f64]) -> Option<DistributionFit> {
+    let (values, mean) = finite_sorted_with_mean(data)?;
+    if values[0] < 0.0 || mean <= 0.0 {
+        return None;
+    }
+    let distribution = Distribution::Exponential { rate: 1.0 / mean };
+    Some(DistributionFit {
+        distribution,
+        ks_statistic: ks_statistic(&values, &distribution),
+    })
+}
+
+/// The candidate (normal or exponential) that fits `data` best by KS statistic
+pub fn best_fit(data: &[f64]) -> Option<DistributionFit> {
+    match (fit_normal(data), fit_exponential(data)) {
+        (Some(n), Some(e)) => Some(if e.ks_statistic < n.ks_statistic { e } else { n }),
+        (n, e) => n.or(e),
+    }
+}
+
+fn finite_sorted_with_mean(data: &[f64]) -> Option<(Vec<f64>, f64)> {
+    let mut values: Vec<f64> = data.iter().copied().filter(|x| x.is_finite()).collect();
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    Some((values, mean))
+}
+
+fn ks_statistic(sorted: &[f64], distribution: &Distribution) -> f64 {
+    let n = sorted.len() as f64;
+    sorted
+        .iter()
+        .enumerate()
+        .map(|(i, &x)| {
+            let cdf = distribution.cdf(x);
+            (cdf - i as f64 / n).abs().max(((i + 1) as f64 / n - cdf).abs())
+        })
+        .fold(0.0, f64::max)
+}
+
+/// Error function (Abramowitz & Stegun 7.1.26, |error| < 1.5e-7)
+fn erf(x: f64) -> f64 {
+    let sign = if x < 0.0 { -1.0 } else { 1.0 };
+    let x = x.abs();
+    let t = 1.0 / (1.0 + 0.3275911 * x);
+    let poly = t
+        * (0.254829592 + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+    sign * (1.0 - poly * (-x * x).exp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_width_bins_and_cumulative() {
+        let data = [0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 10.0, f64::NAN];
+        let hist = Histogram::from_slice(&data, BinStrategy::FixedWidth { bins: 5 }).unwrap();
+
+        assert_eq!(hist.edges(), &[0.0, 2.0, 4.0, 6.0, 8.0, 10.0]);
+        assert_eq!(hist.counts(), &[2, 2, 2, 2, 2]);
+        assert_eq!(hist.total(), 10);
+        assert_eq!(hist.cumulative().last().copied(), Some(1.0));
+        assert_eq!(hist.cumulative()[1], 0.4);
+    }
+
+    #[test]
+    fn test_freedman_diaconis_and_log_scale() {
+        let data: Vec<f64> = (1..=1000).map(|i| i as f64).collect();
+        let fd = Histogram::from_slice(&data, BinStrategy::FreedmanDiaconis).unwrap();
+        // IQR ~ 499.5, width ~ 99.9 => 10 bins
+        assert_eq!(fd.counts().len(), 10);
+        assert_eq!(fd.counts().iter().sum::<u64>(), 1000);
+
+        let log = Histogram::from_slice(&[1.5, 15.0, 150.0, 999.0], BinStrategy::LogScale { bins: 3 }).unwrap();
+        assert_eq!(log.counts(), &[1, 1, 2]);
+        assert!(Histogram::from_slice(&[0.0, 1.0], BinStrategy::LogScale { bins: 3 }).is_none());
+
+        let constant = Histogram::from_slice(&[2.0; 4], BinStrategy::FreedmanDiaconis).unwrap();
+        assert_eq!(constant.counts(), &[4]);
+    }
+
+    #[test]
+    fn test_distribution_fit_prefers_the_generating_family() {
+        // Deterministic exponential quantiles
+        let n = 500;
+        let exponential: Vec<f64> = (0..n)
+            .map(|i| -((1.0 - (i as f64 + 0.5) / n as f64).ln()) / 2.0)
+            .collect();
+        let fit = best_fit(&exponential).unwrap();
+        match fit.distribution {
+            Distribution::Exponential { rate } => assert!((rate - 2.0).abs() < 0.05),
+            other => panic!("expected exponential, got {:?}", other),
+        }
+
+        // Symmetric data with negative values can only be normal
+        let symmetric: Vec<f64> = (-50..=50).map(|i| i as f64 / 10.0).collect();
+        assert!(fit_exponential(&symmetric).is_none());
+        assert!(matches!(best_fit(&symmetric).unwrap().distribution, Distribution::Normal { .. }));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_histogram_serde_round_trip_checks_invariants() {
+        let hist = Histogram::from_slice(&[1.0, 2.0, 2.5, 9.0], BinStrategy::FixedWidth { bins: 4 }).unwrap();
+        let json = serde_json::to_string(&hist).unwrap();
+        assert_eq!(serde_json::from_str::<Histogram>(&json).unwrap(), hist);
+
+        let strategy = BinStrategy::LogScale { bins: 3 };
+        let json = serde_json::to_string(&strategy).unwrap();
+        assert_eq!(serde_json::from_str::<BinStrategy>(&json).unwrap(), strategy);
+
+        for bad in [
+            r#"{"edges":[0.0,1.0],"counts":[1,1],"total":2}"#,
+            r#"{"edges":[0.0],"counts":[],"total":0}"#,
+            r#"{"edges":[0.0,1.0,2.0],"counts":[1,1],"total":3}"#,
+        ] {
+            assert!(serde_json::from_str::<Histogram>(bad).is_err(), "{}", bad);
+        }
+    }
+}
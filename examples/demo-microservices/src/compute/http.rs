@@ -0,0 +1,295 @@
+// HTTP API - axum REST layer over ComputeEngine (feature = "http", implies "serde")
+
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+
+use crate::{ComputationError, ComputeEngine, ComputeResult, Statistics};
+
+/// How long `/readyz` reports 503 before the server stops accepting
+/// connections, so load balancers take the instance out of rotation first
+pub const DEFAULT_DRAIN_PERIOD: Duration = Duration::from_secs(5);
+
+/// Request body for `POST /compute` and `POST /statistics`
+#[derive(Debug, Deserialize)]
+pub struct DataRequest {
+    pub values: Vec<f64>,
+}
+
+/// Query string for `POST /computations/:name`; omit `version` for the latest
+#[derive(Debug, Deserialize)]
+pub struct VersionQuery {
+    pub version: Option<u32>,
+}
+
+/// Response body for `POST /computations/:name`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ComputationResponse {
+    pub name: String,
+    pub version: u32,
+    pub values: Vec<f64>,
+    /// Set when the computation is deprecated, so clients can migrate
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deprecation: Option<String>,
+}
+
+struct AppState {
+    engine: Arc<ComputeEngine>,
+    ready: AtomicBool,
+}
+
+/// Build the router; exposed separately from `serve` for in-process tests
+pub fn router(engine: Arc<ComputeEngine>) -> Router {
+    router_with_state(Arc::new(AppState {
+        engine,
+        ready: AtomicBool::new(true),
+    }))
+}
+
+fn router_with_state(state: Arc<AppState>) -> Router {
+    Router::new()
+        .route("/compute", post(compute))
+        .route("/statistics", post(statistics))
+        .route("/computations/:name", post(run_computation))
+        .route("/healthz", get(healthz))
+        .route("/readyz", get(readyz))
+        .with_state(state)
+}
+
+async fn compute(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<DataRequest>,
+) -> Result<Json<ComputeResult>, StatusCode> {
+    let engine = Arc::clone(&state.engine);
+    tokio::task::spawn_blocking(move || engine.compute_heavy_task(&request.values))
+        .await
+        .map(Json)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+async fn statistics(
+    State(state): State<Arc<AppState>>,
+    Json(request): Json<DataRequest>,
+) -> Result<Json<Statistics>, StatusCode> {
+    let engine = Arc::clone(&state.engine);
+    tokio::task::spawn_blocking(move || engine.calculate_statistics(&request.values))
+        .await
+        .map(Json)
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)
+}
+
+async fn run_computation(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+    Query(query): Query<VersionQuery>,
+    Json(request): Json<DataRequest>,
+) -> Result<Json<ComputationResponse>, (StatusCode, String)> {
+    let engine = Arc::clone(&state.engine);
+    tokio::task::spawn_blocking(move || {
+        let entry = engine.registry().get(&name, query.version)?;
+        let (version, deprecation) = (entry.version(), entry.deprecation().map(str::to_string));
+        let values = engine.run_named_version(&name, version, &request.values)?;
+        Ok(ComputationResponse {
+            name,
+            version,
+            values,
+            deprecation,
+        })
+    })
+    .await
+    .map_err(|_| (StatusCode::INTERNAL_SERVER_ERROR, "computation panicked".to_string()))?
+    .map(Json)
+    .map_err(|e: ComputationError| {
+        let status = match e {
+            ComputationError::InvalidInput(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            _ => StatusCode::NOT_FOUND,
+        };
+        (status, e.to_string())
+    })
+}
+
+/// Liveness: the process is up and serving requests
+async fn healthz() -> &'static str {
+    "ok"
+}
+
+/// Readiness: 503 while draining after shutdown starts
+async fn readyz(State(state): State<Arc<AppState>>) -> StatusCode {
+    if state.ready.load(Ordering::SeqCst) {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    }
+}
+
+/// Serve the HTTP API on the engine's configured bind address until `shutdown` resolves
+pub async fn serve<S>(engine: Arc<ComputeEngine>, shutdown: S) -> std::io::Result<()>
+where
+    S: Future<Output = ()> + Send + 'static,
+{
+    let listener = tokio::net::TcpListener::bind(engine.config.bind_addr()).await?;
+    serve_on(listener, engine, shutdown, DEFAULT_DRAIN_PERIOD).await
+}
+
+/// Serve on `listener`; once `shutdown` resolves, fail readiness and keep
+/// serving for `drain` before the graceful shutdown starts
+async fn serve_on<S>(
+    listener: tokio::net::TcpListener,
+    engine: Arc<ComputeEngine>,
+    shutdown: S,
+    drain: Duration,
+) -> std::io::Result<()>
+where
+    S: Future<Output = ()> + Send + 'static,
+{
+    let state = Arc::new(AppState {
+        engine,
+        ready: AtomicBool::new(true),
+    });
+
+    let draining = Arc::clone(&state);
+    axum::serve(listener, router_with_state(state))
+        .with_graceful_shutdown(async move {
+            shutdown.await;
+            draining.ready.store(false, Ordering::SeqCst);
+            tokio::time::sleep(drain).await;
+        })
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ComputeConfig;
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    fn engine() -> Arc<ComputeEngine> {
+        Arc::new(ComputeEngine::new(ComputeConfig {
+            max_workers: 2,
+            timeout_seconds: 30,
+            bind_addr: ComputeConfig::DEFAULT_BIND_ADDR,
+        }))
+    }
+
+    fn app() -> Router {
+        router(engine())
+    }
+
+    /// Status code of a GET over a real connection, as a load balancer sees it
+    async fn status_of(addr: std::net::SocketAddr, path: &str) -> u16 {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let request = format!("GET {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n", path);
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response.split_whitespace().nth(1).unwrap().parse().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_health_endpoints() {
+        for path in ["/healthz", "/readyz"] {
+            let response = app()
+                .oneshot(Request::get(path).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_readyz_fails_while_draining() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+        let server = tokio::spawn(serve_on(
+            listener,
+            engine(),
+            async {
+                let _ = stopped.await;
+            },
+            Duration::from_millis(500),
+        ));
+
+        assert_eq!(status_of(addr, "/readyz").await, 200);
+        stop.send(()).unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        // Still accepting connections, but out of rotation
+        assert_eq!(status_of(addr, "/readyz").await, 503);
+        assert_eq!(status_of(addr, "/healthz").await, 200);
+        server.await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_statistics_endpoint() {
+        let response = app()
+            .oneshot(
+                Request::post("/statistics")
+                    .header("content-type", "application/json")
+                    .body(Body::from(r#"{"values":[1,2,3,4,5]}"#))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let stats: Statistics = serde_json::from_slice(&body).unwrap();
+        assert_eq!(stats.mean, 3.0);
+    }
+
+    #[tokio::test]
+    async fn test_named_computation_endpoint() {
+        let response = app()
+            .oneshot(
+                Request::post("/computations/legacy?version=1")
+                    .header("content-type", "application/json")
+                    .body(Body::from(r#"{"values":[1,2]}"#))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let result: ComputationResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(result.values, vec![3.0, 5.0]);
+        assert!(result.deprecation.is_some());
+
+        let missing = app()
+            .oneshot(
+                Request::post("/computations/nope")
+                    .header("content-type", "application/json")
+                    .body(Body::from(r#"{"values":[]}"#))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_malformed_json() {
+        let response = app()
+            .oneshot(
+                Request::post("/compute")
+                    .header("content-type", "application/json")
+                    .body(Body::from("{\"values\": 3}"))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert!(response.status().is_client_error());
+    }
+}
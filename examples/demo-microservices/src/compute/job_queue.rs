@@ -0,0 +1,552 @@
+// Job Queue - priority scheduling with backpressure for the compute engine
+
+use std::any::Any;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering as AtomicOrdering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+use crate::{Complex, ComputeEngine, ComputeResult, Statistics};
+
+/// Identifier assigned to a submitted job
+pub type JobId = u64;
+
+/// Finished results kept for pollers by default; older ones are dropped
+pub const DEFAULT_RESULT_LIMIT: usize = 1024;
+
+/// Scheduling priority; higher priorities are dequeued first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    Low,
+    Normal,
+    High,
+    Critical,
+}
+
+/// Work that can be submitted to the queue
+#[derive(Debug, Clone)]
+pub enum Job {
+    HeavyTask(Vec<f64>),
+    MultiplyMatrices(Vec<Vec<f64>>, Vec<Vec<f64>>),
+    Statistics(Vec<f64>),
+    FourierTransform(Vec<f64>),
+}
+
+/// Output of a completed job
+#[derive(Debug)]
+pub enum JobOutput {
+    HeavyTask(ComputeResult),
+    Matrix(Vec<Vec<f64>>),
+    Statistics(Statistics),
+    Spectrum(Vec<Complex>),
+}
+
+/// Lifecycle of a job as seen by pollers
+#[derive(Debug)]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Completed(JobOutput),
+    Failed(String),
+    Cancelled,
+}
+
+impl JobStatus {
+    /// Whether the job has reached a final state
+    pub fn is_terminal(&self) -> bool {
+        !matches!(self, JobStatus::Queued | JobStatus::Running)
+    }
+}
+
+/// Errors returned when submitting or managing jobs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueueError {
+    /// The queue is at capacity; retry later or use `submit_timeout`
+    Full,
+    /// The queue has been shut down and accepts no new work
+    ShutDown,
+    /// No job with the given ID is known
+    UnknownJob(JobId),
+    /// The job already finished and can no longer be cancelled
+    AlreadyFinished(JobId),
+}
+
+impl fmt::Display for QueueError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueueError::Full => write!(f, "job queue is full"),
+            QueueError::ShutDown => write!(f, "job queue is shut down"),
+            QueueError::UnknownJob(id) => write!(f, "unknown job {}", id),
+            QueueError::AlreadyFinished(id) => write!(f, "job {} already finished", id),
+        }
+    }
+}
+
+impl std::error::Error for QueueError {}
+
+/// Heap entry ordered by priority, then by submission order (FIFO within a level)
+struct QueuedJob {
+    id: JobId,
+    priority: Priority,
+    seq: u64,
+    job: Job,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl PartialEq for QueuedJob {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority && self.seq == other.seq
+    }
+}
+
+impl Eq for QueuedJob {}
+
+impl PartialOrd for QueuedJob {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for QueuedJob {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+struct QueueState {
+    pending: BinaryHeap<QueuedJob>,
+    statuses: HashMap<JobId, JobStatus>,
+    cancel_flags: HashMap<JobId, Arc<AtomicBool>>,
+    /// Jobs in the order they finished, to drop results nobody collected
+    finished: VecDeque<JobId>,
+    result_limit: usize,
+    queued: usize,
+    shutting_down: bool,
+}
+
+impl QueueState {
+    fn new(result_limit: usize) -> Self {
+        QueueState {
+            pending: BinaryHeap::new(),
+            statuses: HashMap::new(),
+            cancel_flags: HashMap::new(),
+            finished: VecDeque::new(),
+            result_limit: result_limit.max(1),
+            queued: 0,
+            shutting_down: false,
+        }
+    }
+
+    /// Record a terminal status, dropping the oldest uncollected results past the limit
+    fn finish(&mut self, id: JobId, status: JobStatus) {
+        self.statuses.insert(id, status);
+        self.finished.push_back(id);
+        while self.finished.len() > self.result_limit {
+            let Some(oldest) = self.finished.pop_front() else {
+                break;
+            };
+            // Already collected by poll/wait, or finished again after a cancel
+            if self.statuses.get(&oldest).is_some_and(JobStatus::is_terminal) {
+                self.statuses.remove(&oldest);
+                self.cancel_flags.remove(&oldest);
+            }
+        }
+    }
+}
+
+struct Shared {
+    state: Mutex<QueueState>,
+    work_available: Condvar,
+    space_available: Condvar,
+    status_changed: Condvar,
+}
+
+/// Priority job queue that runs work on a fixed pool of `max_workers` threads
+pub struct JobQueue {
+    shared: Arc<Shared>,
+    workers: Vec<JoinHandle<()>>,
+    capacity: usize,
+    next_id: AtomicU64,
+}
+
+impl JobQueue {
+    /// Create a queue over the engine, holding at most `capacity` pending jobs
+    pub fn new(engine: ComputeEngine, capacity: usize) -> Self {
+        Self::with_result_limit(engine, capacity, DEFAULT_RESULT_LIMIT)
+    }
+
+    /// Create a queue that keeps results for only the last `result_limit`
+    /// finished jobs; older results nobody polled become `UnknownJob`
+    pub fn with_result_limit(engine: ComputeEngine, capacity: usize, result_limit: usize) -> Self {
+        let worker_count = engine.config.max_workers.max(1);
+        let engine = Arc::new(engine);
+        let shared = Arc::new(Shared {
+            state: Mutex::new(QueueState::new(result_limit)),
+            work_available: Condvar::new(),
+            space_available: Condvar::new(),
+            status_changed: Condvar::new(),
+        });
+
+        let workers = (0..worker_count)
+            .map(|_| {
+                let shared = Arc::clone(&shared);
+                let engine = Arc::clone(&engine);
+                thread::spawn(move || worker_loop(&shared, &engine))
+            })
+            .collect();
+
+        JobQueue {
+            shared,
+            workers,
+            capacity: capacity.max(1),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    /// Submit a job, failing fast with `QueueError::Full` when at capacity
+    pub fn submit(&self, job: Job, priority: Priority) -> Result<JobId, QueueError> {
+        let mut state = self.shared.state.lock().unwrap();
+        if state.shutting_down {
+            return Err(QueueError::ShutDown);
+        }
+        if state.queued >= self.capacity {
+            return Err(QueueError::Full);
+        }
+        Ok(self.enqueue(&mut state, job, priority))
+    }
+
+    /// Submit a job, waiting up to `timeout` for space to free up
+    pub fn submit_timeout(
+        &self,
+        job: Job,
+        priority: Priority,
+        timeout: Duration,
+    ) -> Result<JobId, QueueError> {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.shared.state.lock().unwrap();
+
+        loop {
+            if state.shutting_down {
+                return Err(QueueError::ShutDown);
+            }
+            if state.queued < self.capacity {
+                return Ok(self.enqueue(&mut state, job, priority));
+            }
+
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(QueueError::Full);
+            }
+            state = self
+                .shared
+                .space_available
+                .wait_timeout(state, deadline - now)
+                .unwrap()
+                .0;
+        }
+    }
+
+    /// Look up the current status of a job; terminal results are returned once
+    pub fn poll(&self, id: JobId) -> Result<JobStatus, QueueError> {
+        let mut state = self.shared.state.lock().unwrap();
+        match state.statuses.get(&id) {
+            None => Err(QueueError::UnknownJob(id)),
+            Some(JobStatus::Queued) => Ok(JobStatus::Queued),
+            Some(JobStatus::Running) => Ok(JobStatus::Running),
+            Some(_) => {
+                state.cancel_flags.remove(&id);
+                Ok(state.statuses.remove(&id).unwrap())
+            }
+        }
+    }
+
+    /// Block until the job reaches a terminal state and return it
+    pub fn wait(&self, id: JobId) -> Result<JobStatus, QueueError> {
+        let mut state = self.shared.state.lock().unwrap();
+        loop {
+            match state.statuses.get(&id) {
+                None => return Err(QueueError::UnknownJob(id)),
+                Some(status) if status.is_terminal() => {
+                    state.cancel_flags.remove(&id);
+                    return Ok(state.statuses.remove(&id).unwrap());
+                }
+                Some(_) => state = self.shared.status_changed.wait(state).unwrap(),
+            }
+        }
+    }
+
+    /// Cancel a job. Queued jobs never run; running jobs have their result discarded.
+    pub fn cancel(&self, id: JobId) -> Result<(), QueueError> {
+        let mut state = self.shared.state.lock().unwrap();
+        match state.statuses.get(&id) {
+            None => Err(QueueError::UnknownJob(id)),
+            Some(status) if status.is_terminal() => Err(QueueError::AlreadyFinished(id)),
+            Some(status) => {
+                let was_queued = matches!(status, JobStatus::Queued);
+                if let Some(flag) = state.cancel_flags.get(&id) {
+                    flag.store(true, AtomicOrdering::SeqCst);
+                }
+                state.finish(id, JobStatus::Cancelled);
+                if was_queued {
+                    // The heap entry is skipped lazily by workers; free its slot now
+                    state.queued -= 1;
+                    self.shared.space_available.notify_one();
+                }
+                self.shared.status_changed.notify_all();
+                Ok(())
+            }
+        }
+    }
+
+    /// Number of jobs waiting to start
+    pub fn pending(&self) -> usize {
+        self.shared.state.lock().unwrap().queued
+    }
+
+    /// Maximum number of pending jobs before backpressure applies
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Stop accepting work, let queued jobs drain, and join the workers
+    pub fn shutdown(mut self) {
+        self.stop();
+    }
+
+    fn stop(&mut self) {
+        {
+            let mut state = self.shared.state.lock().unwrap();
+            state.shutting_down = true;
+        }
+        self.shared.work_available.notify_all();
+        self.shared.space_available.notify_all();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+
+    fn enqueue(&self, state: &mut QueueState, job: Job, priority: Priority) -> JobId {
+        let id = self.next_id.fetch_add(1, AtomicOrdering::SeqCst);
+        let cancelled = Arc::new(AtomicBool::new(false));
+        state.pending.push(QueuedJob {
+            id,
+            priority,
+            seq: id,
+            job,
+            cancelled: Arc::clone(&cancelled),
+        });
+        state.statuses.insert(id, JobStatus::Queued);
+        state.cancel_flags.insert(id, cancelled);
+        state.queued += 1;
+        self.shared.work_available.notify_one();
+        id
+    }
+}
+
+impl Drop for JobQueue {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+fn worker_loop(shared: &Shared, engine: &ComputeEngine) {
+    loop {
+        let next = {
+            let mut state = shared.state.lock().unwrap();
+            loop {
+                match state.pending.pop() {
+                    // Cancelled entries already released their slot
+                    Some(entry) if entry.cancelled.load(AtomicOrdering::SeqCst) => continue,
+                    Some(entry) => {
+                        state.queued -= 1;
+                        state.statuses.insert(entry.id, JobStatus::Running);
+                        shared.space_available.notify_one();
+                        break Some(entry);
+                    }
+                    None if state.shutting_down => break None,
+                    None => state = shared.work_available.wait(state).unwrap(),
+                }
+            }
+        };
+
+        let Some(entry) = next else {
+            return;
+        };
+
+        let QueuedJob { id, job, cancelled, .. } = entry;
+        // A panicking job fails on its own; the worker lives on for the next one
+        let status = panic::catch_unwind(AssertUnwindSafe(|| run_job(engine, job)))
+            .unwrap_or_else(|payload| JobStatus::Failed(panic_message(payload.as_ref())));
+
+        let mut state = shared.state.lock().unwrap();
+        if !cancelled.load(AtomicOrdering::SeqCst) {
+            state.finish(id, status);
+        }
+        shared.status_changed.notify_all();
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> String {
+    let detail = payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned());
+    match detail {
+        Some(detail) => format!("job panicked: {}", detail),
+        None => "job panicked".to_string(),
+    }
+}
+
+fn run_job(engine: &ComputeEngine, job: Job) -> JobStatus {
+    match job {
+        Job::HeavyTask(data) => JobStatus::Completed(JobOutput::HeavyTask(
+            engine.compute_heavy_task(&data),
+        )),
+        Job::MultiplyMatrices(a, b) => match engine.multiply_matrices(&a, &b) {
+            Some(product) => JobStatus::Completed(JobOutput::Matrix(product)),
+            None => JobStatus::Failed("incompatible matrix dimensions".to_string()),
+        },
+        Job::Statistics(data) => JobStatus::Completed(JobOutput::Statistics(
+            engine.calculate_statistics(&data),
+        )),
+        Job::FourierTransform(data) => JobStatus::Completed(JobOutput::Spectrum(
+            engine.fourier_transform(&data),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ComputeConfig;
+
+    fn engine(max_workers: usize) -> ComputeEngine {
+        ComputeEngine::new(ComputeConfig {
+            max_workers,
+            timeout_seconds: 30,
+            bind_addr: ComputeConfig::DEFAULT_BIND_ADDR,
+        })
+    }
+
+    fn queue(max_workers: usize, capacity: usize) -> JobQueue {
+        JobQueue::new(engine(max_workers), capacity)
+    }
+
+    #[test]
+    fn test_job_completes() {
+        let queue = queue(2, 8);
+        let id = queue
+            .submit(Job::Statistics(vec![1.0, 2.0, 3.0]), Priority::Normal)
+            .unwrap();
+        match queue.wait(id).unwrap() {
+            JobStatus::Completed(JobOutput::Statistics(stats)) => assert_eq!(stats.mean, 2.0),
+            other => panic!("unexpected status: {:?}", other),
+        }
+        assert_eq!(queue.poll(id).unwrap_err(), QueueError::UnknownJob(id));
+    }
+
+    #[test]
+    fn test_priority_ordering() {
+        let shared = Arc::new(Shared {
+            state: Mutex::new(QueueState::new(DEFAULT_RESULT_LIMIT)),
+            work_available: Condvar::new(),
+            space_available: Condvar::new(),
+            status_changed: Condvar::new(),
+        });
+        let mut state = shared.state.lock().unwrap();
+        for (seq, priority) in [Priority::Low, Priority::Critical, Priority::Normal, Priority::Critical]
+            .into_iter()
+            .enumerate()
+        {
+            state.pending.push(QueuedJob {
+                id: seq as JobId,
+                priority,
+                seq: seq as u64,
+                job: Job::HeavyTask(vec![]),
+                cancelled: Arc::new(AtomicBool::new(false)),
+            });
+        }
+        let order: Vec<JobId> = std::iter::from_fn(|| state.pending.pop().map(|j| j.id)).collect();
+        assert_eq!(order, vec![1, 3, 2, 0]);
+    }
+
+    #[test]
+    fn test_backpressure_and_cancel() {
+        // The single worker is busy with a large transform, so the queue fills up
+        let queue = queue(1, 2);
+        let big = Job::FourierTransform(vec![1.0; 4096]);
+        let _running = queue.submit(big, Priority::Normal).unwrap();
+
+        let mut accepted = Vec::new();
+        let mut rejected = None;
+        for _ in 0..=queue.capacity() {
+            match queue.submit(Job::HeavyTask(vec![1.0]), Priority::Low) {
+                Ok(id) => accepted.push(id),
+                Err(err) => {
+                    rejected = Some(err);
+                    break;
+                }
+            }
+        }
+        assert_eq!(rejected, Some(QueueError::Full));
+        assert!(queue.pending() <= queue.capacity());
+
+        let victim = *accepted.last().unwrap();
+        queue.cancel(victim).unwrap();
+        assert!(matches!(queue.wait(victim).unwrap(), JobStatus::Cancelled));
+        assert!(queue
+            .submit_timeout(Job::HeavyTask(vec![1.0]), Priority::High, Duration::from_secs(5))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_invalid_matrix_fails() {
+        let queue = queue(1, 4);
+        let id = queue
+            .submit(
+                Job::MultiplyMatrices(vec![vec![1.0, 2.0]], vec![vec![1.0]]),
+                Priority::High,
+            )
+            .unwrap();
+        assert!(matches!(queue.wait(id).unwrap(), JobStatus::Failed(_)));
+    }
+
+    #[test]
+    fn test_panicking_job_fails_without_losing_the_worker() {
+        // NaN makes the median sort panic; the single worker must survive it
+        let queue = queue(1, 4);
+        let id = queue
+            .submit(Job::Statistics(vec![1.0, f64::NAN, 3.0]), Priority::Normal)
+            .unwrap();
+        match queue.wait(id).unwrap() {
+            JobStatus::Failed(message) => assert!(message.starts_with("job panicked")),
+            other => panic!("unexpected status: {:?}", other),
+        }
+
+        let next = queue
+            .submit(Job::Statistics(vec![1.0, 2.0, 3.0]), Priority::Normal)
+            .unwrap();
+        assert!(matches!(queue.wait(next).unwrap(), JobStatus::Completed(_)));
+    }
+
+    #[test]
+    fn test_uncollected_results_are_capped() {
+        let queue = JobQueue::with_result_limit(engine(1), 8, 2);
+        let ids: Vec<JobId> = (0..3)
+            .map(|_| queue.submit(Job::HeavyTask(vec![1.0]), Priority::Normal).unwrap())
+            .collect();
+        // One worker runs jobs in order, so the earlier ones have finished by now
+        let last = queue.submit(Job::HeavyTask(vec![1.0]), Priority::Low).unwrap();
+        assert!(matches!(queue.wait(last).unwrap(), JobStatus::Completed(_)));
+
+        assert_eq!(queue.poll(ids[0]).unwrap_err(), QueueError::UnknownJob(ids[0]));
+        assert_eq!(queue.poll(ids[1]).unwrap_err(), QueueError::UnknownJob(ids[1]));
+        assert!(matches!(queue.poll(ids[2]).unwrap(), JobStatus::Completed(_)));
+    }
+}
@@ -0,0 +1,399 @@
+// Linear algebra - dense row-major matrices and symmetric eigendecomposition
+
+use std::ops::{Index, IndexMut};
+
+/// Sweeps of the cyclic Jacobi method before giving up on convergence
+const MAX_JACOBI_SWEEPS: usize = 100;
+
+/// Dense row-major matrix of f64
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "RawMatrix")
+)]
+pub struct Matrix {
+    rows: usize,
+    cols: usize,
+    data: Vec<f64>,
+}
+
+/// Deserialized fields, checked by [`Matrix::new`] before use
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct RawMatrix {
+    rows: usize,
+    cols: usize,
+    data: Vec<f64>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawMatrix> for Matrix {
+    type Error = String;
+
+    fn try_from(raw: RawMatrix) -> Result<Matrix, String> {
+        let len = raw.data.len();
+        Matrix::new(raw.rows, raw.cols, raw.data)
+            .ok_or_else(|| format!("{} values do not fill a {}x{} matrix", len, raw.rows, raw.cols))
+    }
+}
+
+impl Matrix {
+    /// Wrap row-major `data`; `None` unless it holds exactly `rows * cols` values
+    pub fn new(rows: usize, cols: usize, data: Vec<f64>) -> Option<Matrix> {
+        (data.len() == rows * cols).then_some(Matrix { rows, cols, data })
+    }
+
+    pub fn zeros(rows: usize, cols: usize) -> Matrix {
+        Matrix {
+            rows,
+            cols,
+            data: vec![0.0; rows * cols],
+        }
+    }
+
+    pub fn identity(n: usize) -> Matrix {
+        let mut m = Matrix::zeros(n, n);
+        for i in 0..n {
+            m[(i, i)] = 1.0;
+        }
+        m
+    }
+
+    /// Build from rows; `None` if the rows have different lengths
+    pub fn from_rows(rows: &[Vec<f64>]) -> Option<Matrix> {
+        let cols = rows.first().map_or(0, Vec::len);
+        if rows.iter().any(|r| r.len() != cols) {
+            return None;
+        }
+        Matrix::new(rows.len(), cols, rows.concat())
+    }
+
+    pub fn to_rows(&self) -> Vec<Vec<f64>> {
+        self.data.chunks(self.cols.max(1)).take(self.rows).map(<[f64]>::to_vec).collect()
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    pub fn row(&self, r: usize) -> &[f64] {
+        &self.data[r * self.cols..(r + 1) * self.cols]
+    }
+
+    pub fn transpose(&self) -> Matrix {
+        let mut t = Matrix::zeros(self.cols, self.rows);
+        for r in 0..self.rows {
+            for c in 0..self.cols {
+                t[(c, r)] = self[(r, c)];
+            }
+        }
+        t
+    }
+
+    /// Matrix product; `None` if the inner dimensions differ
+    pub fn multiply(&self, other: &Matrix) -> Option<Matrix> {
+        if self.cols != other.rows {
+            return None;
+        }
+        let mut out = Matrix::zeros(self.rows, other.cols);
+        for i in 0..self.rows {
+            for k in 0..self.cols {
+                let a = self[(i, k)];
+                for j in 0..other.cols {
+                    out[(i, j)] += a * other[(k, j)];
+                }
+            }
+        }
+        Some(out)
+    }
+
+    /// Mean of each column
+    pub fn column_means(&self) -> Vec<f64> {
+        let mut means = vec![0.0; self.cols];
+        for r in 0..self.rows {
+            for (m, x) in means.iter_mut().zip(self.row(r)) {
+                *m += x;
+            }
+        }
+        let n = self.rows.max(1) as f64;
+        means.iter_mut().for_each(|m| *m /= n);
+        means
+    }
+
+    /// Sample covariance of the columns (divides by n - 1); `None` with fewer than two rows
+    pub fn covariance(&self) -> Option<Matrix> {
+        if self.rows < 2 {
+            return None;
+        }
+        let means = self.column_means();
+        let mut cov = Matrix::zeros(self.cols, self.cols);
+        for r in 0..self.rows {
+            let row = self.row(r);
+            for i in 0..self.cols {
+                let di = row[i] - means[i];
+                for j in i..self.cols {
+                    cov[(i, j)] += di * (row[j] - means[j]);
+                }
+            }
+        }
+        let denom = (self.rows - 1) as f64;
+        for i in 0..self.cols {
+            for j in i..self.cols {
+                let v = cov[(i, j)] / denom;
+                cov[(i, j)] = v;
+                cov[(j, i)] = v;
+            }
+        }
+        Some(cov)
+    }
+
+    /// Eigenvalues (descending) and unit eigenvectors (as columns) of a
+    /// symmetric matrix, by the cyclic Jacobi method. `None` if the matrix is
+    /// not square or fails to converge.
+    pub fn symmetric_eigen(&self) -> Option<(Vec<f64>, Matrix)> {
+        if self.rows != self.cols {
+            return None;
+        }
+        let n = self.rows;
+        let mut a = self.clone();
+        let mut v = Matrix::identity(n);
+        let scale: f64 = a.data.iter().map(|x| x * x).sum::<f64>().sqrt().max(f64::MIN_POSITIVE);
+
+        let mut converged = false;
+        for _ in 0..MAX_JACOBI_SWEEPS {
+            let off: f64 = (0..n)
+                .flat_map(|i| (0..n).filter(move |&j| j != i).map(move |j| (i, j)))
+                .map(|(i, j)| a[(i, j)] * a[(i, j)])
+                .sum();
+            if off.sqrt() <= 1e-12 * scale {
+                converged = true;
+                break;
+            }
+
+            for p in 0..n {
+                for q in p + 1..n {
+                    if a[(p, q)].abs() <= f64::MIN_POSITIVE {
+                        continue;
+                    }
+                    let theta = (a[(q, q)] - a[(p, p)]) / (2.0 * a[(p, q)]);
+                    let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                    let c = 1.0 / (t * t + 1.0).sqrt();
+                    let s = t * c;
+
+                    for k in 0..n {
+                        let (akp, akq) = (a[(k, p)], a[(k, q)]);
+                        a[(k, p)] = c * akp - s * akq;
+                        a[(k, q)] = s * akp + c * akq;
+                    }
+                    for k in 0..n {
+                        let (apk, aqk) = (a[(p, k)], a[(q, k)]);
+                        a[(p, k)] = c * apk - s * aqk;
+                        a[(q, k)] = s * apk + c * aqk;
+                    }
+                    for k in 0..n {
+                        let (vkp, vkq) = (v[(k, p)], v[(k, q)]);
+                        v[(k, p)] = c * vkp - s * vkq;
+                        v[(k, q)] = s * vkp + c * vkq;
+                    }
+                }
+            }
+        }
+        if !converged {
+            return None;
+        }
+
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by(|&i, &j| a[(j, j)].total_cmp(&a[(i, i)]));
+
+        let values = order.iter().map(|&i| a[(i, i)]).collect();
+        let mut vectors = Matrix::zeros(n, n);
+        for (col, &i) in order.iter().enumerate() {
+            for r in 0..n {
+                vectors[(r, col)] = v[(r, i)];
+            }
+        }
+        Some((values, vectors))
+    }
+}
+
+impl Index<(usize, usize)> for Matrix {
+    type Output = f64;
+
+    fn index(&self, (r, c): (usize, usize)) -> &f64 {
+        &self.data[r * self.cols + c]
+    }
+}
+
+impl IndexMut<(usize, usize)> for Matrix {
+    fn index_mut(&mut self, (r, c): (usize, usize)) -> &mut f64 {
+        &mut self.data[r * self.cols + c]
+    }
+}
+
+/// Result of a principal component analysis
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PrincipalComponents {
+    /// k × features; each row is a unit-length component
+    pub components: Matrix,
+    /// Variance along each component
+    pub explained_variance: Vec<f64>,
+    /// Fraction of total variance along each component
+    pub explained_variance_ratio: Vec<f64>,
+    /// Feature means subtracted before projecting
+    pub mean: Vec<f64>,
+}
+
+impl PrincipalComponents {
+    /// Project samples (rows) onto the components; `None` on a feature-count mismatch
+    pub fn transform(&self, data: &Matrix) -> Option<Matrix> {
+        if data.cols() != self.mean.len() {
+            return None;
+        }
+        let mut centered = data.clone();
+        for r in 0..centered.rows() {
+            for c in 0..centered.cols() {
+                centered[(r, c)] -= self.mean[c];
+            }
+        }
+        centered.multiply(&self.components.transpose())
+    }
+}
+
+/// PCA of `data` (samples as rows) keeping the top `k` components.
+/// Each component's sign is fixed so its largest-magnitude loading is positive.
+pub fn principal_components(data: &Matrix, k: usize) -> Option<PrincipalComponents> {
+    if k == 0 || k > data.cols() {
+        return None;
+    }
+    let (values, vectors) = data.covariance()?.symmetric_eigen()?;
+    // Tiny negative eigenvalues are rounding noise on a PSD matrix
+    let values: Vec<f64> = values.into_iter().map(|v| v.max(0.0)).collect();
+    let total: f64 = values.iter().sum();
+
+    let features = data.cols();
+    let mut components = Matrix::zeros(k, features);
+    for i in 0..k {
+        let largest = (0..features)
+            .max_by(|&a, &b| vectors[(a, i)].abs().total_cmp(&vectors[(b, i)].abs()))
+            .unwrap_or(0);
+        let sign = if vectors[(largest, i)] < 0.0 { -1.0 } else { 1.0 };
+        for f in 0..features {
+            components[(i, f)] = sign * vectors[(f, i)];
+        }
+    }
+
+    Some(PrincipalComponents {
+        components,
+        explained_variance: values[..k].to_vec(),
+        explained_variance_ratio: values[..k]
+            .iter()
+            .map(|v| if total > 0.0 { v / total } else { 0.0 })
+            .collect(),
+        mean: data.column_means(),
+    })
+}
+
+/// Pearson correlations between the columns of `data`. Pairs involving a
+/// constant column are NaN (the diagonal is always 1).
+pub fn correlation_matrix(data: &Matrix) -> Option<Matrix> {
+    let cov = data.covariance()?;
+    let n = cov.rows();
+    let mut corr = Matrix::identity(n);
+    for i in 0..n {
+        for j in 0..n {
+            if i != j {
+                let denom = (cov[(i, i)] * cov[(j, j)]).sqrt();
+                corr[(i, j)] = if denom > 0.0 {
+                    (cov[(i, j)] / denom).clamp(-1.0, 1.0)
+                } else {
+                    f64::NAN
+                };
+            }
+        }
+    }
+    Some(corr)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_matrix_serde_round_trip_checks_shape() {
+        let m = Matrix::from_rows(&[vec![1.0, 2.0], vec![3.0, 4.0], vec![5.0, 6.0]]).unwrap();
+        let json = serde_json::to_string(&m).unwrap();
+        assert_eq!(serde_json::from_str::<Matrix>(&json).unwrap(), m);
+
+        let err = serde_json::from_str::<Matrix>(r#"{"rows":2,"cols":2,"data":[1.0]}"#).unwrap_err();
+        assert!(err.to_string().contains("do not fill a 2x2 matrix"));
+    }
+
+    #[test]
+    fn test_symmetric_eigen_reconstructs_matrix() {
+        let m = Matrix::from_rows(&[vec![4.0, 1.0, 2.0], vec![1.0, 3.0, 0.5], vec![2.0, 0.5, 5.0]]).unwrap();
+        let (values, vectors) = m.symmetric_eigen().unwrap();
+        assert!(values.windows(2).all(|w| w[0] >= w[1]));
+
+        // V * diag(values) * V^T == m
+        let mut scaled = vectors.clone();
+        for r in 0..3 {
+            for c in 0..3 {
+                scaled[(r, c)] *= values[c];
+            }
+        }
+        let rebuilt = scaled.multiply(&vectors.transpose()).unwrap();
+        for r in 0..3 {
+            for c in 0..3 {
+                assert!((rebuilt[(r, c)] - m[(r, c)]).abs() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn test_pca_finds_dominant_direction() {
+        // Points along y = 2x with a little orthogonal noise
+        let rows: Vec<Vec<f64>> = (0..50)
+            .map(|i| {
+                let t = i as f64 - 25.0;
+                let noise = if i % 2 == 0 { 0.1 } else { -0.1 };
+                vec![t - 2.0 * noise, 2.0 * t + noise]
+            })
+            .collect();
+        let data = Matrix::from_rows(&rows).unwrap();
+        let pca = principal_components(&data, 2).unwrap();
+
+        let first = pca.components.row(0);
+        let expected = [1.0 / 5f64.sqrt(), 2.0 / 5f64.sqrt()];
+        assert!((first[0] - expected[0]).abs() < 1e-3 && (first[1] - expected[1]).abs() < 1e-3);
+        assert!(pca.explained_variance_ratio[0] > 0.999);
+        assert!((pca.explained_variance_ratio.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+
+        let projected = pca.transform(&data).unwrap();
+        assert_eq!((projected.rows(), projected.cols()), (50, 2));
+        assert!(principal_components(&data, 3).is_none());
+    }
+
+    #[test]
+    fn test_correlation_matrix() {
+        let data = Matrix::from_rows(&[
+            vec![1.0, 2.0, 5.0, 7.0],
+            vec![2.0, 4.0, 4.0, 7.0],
+            vec![3.0, 6.0, 3.0, 7.0],
+            vec![4.0, 8.0, 2.0, 7.0],
+        ])
+        .unwrap();
+        let corr = correlation_matrix(&data).unwrap();
+        assert!((corr[(0, 1)] - 1.0).abs() < 1e-12);
+        assert!((corr[(0, 2)] + 1.0).abs() < 1e-12);
+        assert!(corr[(0, 3)].is_nan());
+        assert_eq!(corr[(3, 3)], 1.0);
+        assert!(correlation_matrix(&Matrix::from_rows(&[vec![1.0, 2.0]]).unwrap()).is_none());
+    }
+}
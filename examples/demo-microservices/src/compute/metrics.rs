@@ -0,0 +1,228 @@
+// Metrics - per-operation counters and duration histograms
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// Instrumented compute operations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operation {
+    HeavyTask,
+    MultiplyMatrices,
+    FourierTransform,
+}
+
+impl Operation {
+    pub const ALL: [Operation; 3] = [
+        Operation::HeavyTask,
+        Operation::MultiplyMatrices,
+        Operation::FourierTransform,
+    ];
+
+    /// Label value used in exported metrics
+    pub fn name(&self) -> &'static str {
+        match self {
+            Operation::HeavyTask => "compute_heavy_task",
+            Operation::MultiplyMatrices => "multiply_matrices",
+            Operation::FourierTransform => "fourier_transform",
+        }
+    }
+
+    fn index(&self) -> usize {
+        match self {
+            Operation::HeavyTask => 0,
+            Operation::MultiplyMatrices => 1,
+            Operation::FourierTransform => 2,
+        }
+    }
+}
+
+/// Sink for compute engine measurements
+pub trait Metrics: Send + Sync {
+    /// Record one finished operation
+    fn record(&self, op: Operation, duration: Duration, success: bool);
+}
+
+/// Histogram bucket upper bounds in seconds
+pub const DURATION_BUCKETS: [f64; 10] = [
+    0.000_1, 0.000_5, 0.001, 0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0,
+];
+
+/// Counters and histogram for a single operation
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct OperationSnapshot {
+    pub calls: u64,
+    pub failures: u64,
+    /// Cumulative counts per entry of `DURATION_BUCKETS`
+    pub buckets: [u64; DURATION_BUCKETS.len()],
+    pub duration_sum_seconds: f64,
+}
+
+impl OperationSnapshot {
+    fn observe(&mut self, seconds: f64, success: bool) {
+        self.calls += 1;
+        if !success {
+            self.failures += 1;
+        }
+        self.duration_sum_seconds += seconds;
+        for (bucket, bound) in self.buckets.iter_mut().zip(DURATION_BUCKETS.iter()) {
+            if seconds <= *bound {
+                *bucket += 1;
+            }
+        }
+    }
+}
+
+/// In-process metrics registry
+#[derive(Debug, Default)]
+pub struct ComputeMetrics {
+    operations: Mutex<[OperationSnapshot; 3]>,
+}
+
+impl ComputeMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Current values for one operation
+    pub fn snapshot(&self, op: Operation) -> OperationSnapshot {
+        self.operations.lock().unwrap()[op.index()].clone()
+    }
+
+    /// Render all metrics in Prometheus text exposition format
+    #[cfg(feature = "prometheus")]
+    pub fn render_prometheus(&self) -> String {
+        prometheus::render(self)
+    }
+}
+
+impl Metrics for ComputeMetrics {
+    fn record(&self, op: Operation, duration: Duration, success: bool) {
+        self.operations.lock().unwrap()[op.index()].observe(duration.as_secs_f64(), success);
+    }
+}
+
+#[cfg(feature = "prometheus")]
+mod prometheus {
+    use std::fmt::Write;
+
+    use super::{ComputeMetrics, Operation, DURATION_BUCKETS};
+
+    pub fn render(metrics: &ComputeMetrics) -> String {
+        let mut out = String::new();
+
+        out.push_str("# HELP compute_operations_total Completed compute operations.\n");
+        out.push_str("# TYPE compute_operations_total counter\n");
+        for op in Operation::ALL {
+            let snap = metrics.snapshot(op);
+            let _ = writeln!(out, "compute_operations_total{{operation=\"{}\"}} {}", op.name(), snap.calls);
+        }
+
+        out.push_str("# HELP compute_operation_failures_total Failed compute operations.\n");
+        out.push_str("# TYPE compute_operation_failures_total counter\n");
+        for op in Operation::ALL {
+            let snap = metrics.snapshot(op);
+            let _ = writeln!(
+                out,
+                "compute_operation_failures_total{{operation=\"{}\"}} {}",
+                op.name(),
+                snap.failures
+            );
+        }
+
+        out.push_str("# HELP compute_operation_duration_seconds Compute operation latency.\n");
+        out.push_str("# TYPE compute_operation_duration_seconds histogram\n");
+        for op in Operation::ALL {
+            let snap = metrics.snapshot(op);
+            for (bound, count) in DURATION_BUCKETS.iter().zip(snap.buckets.iter()) {
+                let _ = writeln!(
+                    out,
+                    "compute_operation_duration_seconds_bucket{{operation=\"{}\",le=\"{}\"}} {}",
+                    op.name(),
+                    bound,
+                    count
+                );
+            }
+            let _ = writeln!(
+                out,
+                "compute_operation_duration_seconds_bucket{{operation=\"{}\",le=\"+Inf\"}} {}",
+                op.name(),
+                snap.calls
+            );
+            let _ = writeln!(
+                out,
+                "compute_operation_duration_seconds_sum{{operation=\"{}\"}} {}",
+                op.name(),
+                snap.duration_sum_seconds
+            );
+            let _ = writeln!(
+                out,
+                "compute_operation_duration_seconds_count{{operation=\"{}\"}} {}",
+                op.name(),
+                snap.calls
+            );
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ComputeConfig, ComputeEngine};
+    use std::sync::Arc;
+
+    fn engine_with_metrics() -> (ComputeEngine, Arc<ComputeMetrics>) {
+        let metrics = Arc::new(ComputeMetrics::new());
+        let engine = ComputeEngine::new(ComputeConfig {
+            max_workers: 1,
+            timeout_seconds: 30,
+            bind_addr: ComputeConfig::DEFAULT_BIND_ADDR,
+        })
+        .with_metrics(metrics.clone());
+        (engine, metrics)
+    }
+
+    #[test]
+    fn test_records_each_operation() {
+        let (engine, metrics) = engine_with_metrics();
+        engine.compute_heavy_task(&[1.0, 2.0]);
+        engine.compute_heavy_task(&[3.0]);
+        engine.multiply_matrices(&[vec![1.0]], &[vec![2.0]]);
+        engine.multiply_matrices(&[vec![1.0, 2.0]], &[vec![2.0]]);
+        engine.fourier_transform(&[1.0, 0.0, -1.0, 0.0]);
+
+        assert_eq!(metrics.snapshot(Operation::HeavyTask).calls, 2);
+        let matrices = metrics.snapshot(Operation::MultiplyMatrices);
+        assert_eq!(matrices.calls, 2);
+        assert_eq!(matrices.failures, 1);
+        assert_eq!(metrics.snapshot(Operation::FourierTransform).calls, 1);
+    }
+
+    #[test]
+    fn test_buckets_are_cumulative() {
+        let metrics = ComputeMetrics::new();
+        metrics.record(Operation::HeavyTask, Duration::from_millis(2), true);
+        metrics.record(Operation::HeavyTask, Duration::from_secs(10), true);
+
+        let snap = metrics.snapshot(Operation::HeavyTask);
+        assert_eq!(snap.buckets[DURATION_BUCKETS.len() - 1], 1);
+        assert_eq!(snap.buckets[3], 1); // le=0.005
+        assert_eq!(snap.buckets[0], 0);
+    }
+
+    #[cfg(feature = "prometheus")]
+    #[test]
+    fn test_prometheus_exposition() {
+        let (engine, metrics) = engine_with_metrics();
+        engine.fourier_transform(&[1.0, 2.0]);
+
+        let text = metrics.render_prometheus();
+        assert!(text.contains("# TYPE compute_operation_duration_seconds histogram"));
+        assert!(text.contains("compute_operations_total{operation=\"fourier_transform\"} 1"));
+        assert!(text.contains(
+            "compute_operation_duration_seconds_bucket{operation=\"fourier_transform\",le=\"+Inf\"} 1"
+        ));
+        assert!(text.contains("compute_operations_total{operation=\"multiply_matrices\"} 0"));
+    }
+}
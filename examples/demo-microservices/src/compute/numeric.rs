@@ -0,0 +1,110 @@
+// Numeric helpers - compensated summation with a fixed reduction order
+
+/// Floating-point mode for reductions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Precision {
+    /// Naive left-to-right summation; fastest, results may drift with input size
+    #[default]
+    Fast,
+    /// Neumaier-compensated sums over fixed-size blocks, combined in block order.
+    /// Results are bit-identical across builds and thread counts.
+    Deterministic,
+}
+
+/// Block size for deterministic reductions. Fixed so the reduction tree never
+/// depends on how many threads happen to be available.
+pub const BLOCK_SIZE: usize = 4096;
+
+/// Inputs shorter than this are summed on the calling thread
+const PARALLEL_THRESHOLD: usize = 8 * BLOCK_SIZE;
+
+/// Running Neumaier (improved Kahan) sum
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NeumaierSum {
+    sum: f64,
+    compensation: f64,
+}
+
+impl NeumaierSum {
+    pub fn add(&mut self, value: f64) {
+        let t = self.sum + value;
+        if self.sum.abs() >= value.abs() {
+            self.compensation += (self.sum - t) + value;
+        } else {
+            self.compensation += (value - t) + self.sum;
+        }
+        self.sum = t;
+    }
+
+    pub fn total(&self) -> f64 {
+        self.sum + self.compensation
+    }
+}
+
+/// Compensated sum of `values` in iteration order
+pub fn neumaier_sum<I: IntoIterator<Item = f64>>(values: I) -> f64 {
+    let mut acc = NeumaierSum::default();
+    for v in values {
+        acc.add(v);
+    }
+    acc.total()
+}
+
+/// Deterministic sum of `f(x)` over `data`, using up to `threads` workers.
+/// Each block is reduced independently and partials are combined in block order,
+/// so the result does not depend on `threads`.
+pub fn deterministic_sum<F>(data: &[f64], threads: usize, f: F) -> f64
+where
+    F: Fn(f64) -> f64 + Sync,
+{
+    let blocks: Vec<&[f64]> = data.chunks(BLOCK_SIZE).collect();
+    let block_sum = |block: &[f64]| neumaier_sum(block.iter().map(|&x| f(x)));
+
+    let partials: Vec<f64> = if threads <= 1 || data.len() < PARALLEL_THRESHOLD {
+        blocks.iter().map(|b| block_sum(b)).collect()
+    } else {
+        let per_thread = blocks.len().div_ceil(threads);
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = blocks
+                .chunks(per_thread)
+                .map(|group| scope.spawn(move || group.iter().map(|b| block_sum(b)).collect::<Vec<_>>()))
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|h| h.join().expect("reduction worker panicked"))
+                .collect()
+        })
+    };
+
+    neumaier_sum(partials)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_neumaier_recovers_lost_low_bits() {
+        let values = [1.0, 1e100, 1.0, -1e100];
+        assert_eq!(values.iter().sum::<f64>(), 0.0);
+        assert_eq!(neumaier_sum(values), 2.0);
+    }
+
+    #[test]
+    fn test_independent_of_thread_count() {
+        let data: Vec<f64> = (0..100_003).map(|i| ((i as f64) * 0.37).sin() * 1e-3 + 0.1).collect();
+        let one = deterministic_sum(&data, 1, |x| x * x);
+        for threads in [2, 3, 8, 16] {
+            assert_eq!(deterministic_sum(&data, threads, |x| x * x).to_bits(), one.to_bits());
+        }
+    }
+
+    #[test]
+    fn test_matches_exact_sum_closely() {
+        let data = vec![0.1; 1_000_000];
+        let naive: f64 = data.iter().sum();
+        let compensated = deterministic_sum(&data, 4, |x| x);
+        assert!((compensated - 100_000.0).abs() < (naive - 100_000.0).abs());
+        assert!((compensated - 100_000.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,288 @@
+// Persistent cache - content-addressed results on disk (feature = "persistent-cache")
+//
+// Entries live at `<dir>/<key[..2]>/<key>`, where the key is a BLAKE3 hash of the
+// operation name and input payload. Values are little-endian f64 sequences.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+/// On-disk result cache with least-recently-used eviction by total size
+#[derive(Debug)]
+pub struct PersistentCache {
+    dir: PathBuf,
+    max_bytes: u64,
+    /// Bytes stored, counted once at open and kept up to date by `put`
+    total_bytes: Mutex<u64>,
+}
+
+impl PersistentCache {
+    /// Default size budget before eviction kicks in
+    pub const DEFAULT_MAX_BYTES: u64 = 256 * 1024 * 1024;
+
+    /// Open (or create) a cache rooted at `dir`
+    pub fn open<P: AsRef<Path>>(dir: P, max_bytes: u64) -> io::Result<Self> {
+        fs::create_dir_all(dir.as_ref())?;
+        let cache = PersistentCache {
+            dir: dir.as_ref().to_path_buf(),
+            max_bytes,
+            total_bytes: Mutex::new(0),
+        };
+        *cache.total_bytes.lock().unwrap() = cache.entries()?.iter().map(|(_, len, _)| len).sum();
+        Ok(cache)
+    }
+
+    /// Content-addressed key for an operation and its input
+    pub fn key(op: &str, payload: &[f64]) -> String {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(op.as_bytes());
+        hasher.update(&[0]);
+        for value in payload {
+            hasher.update(&value.to_le_bytes());
+        }
+        hasher.finalize().to_hex().to_string()
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir.join(&key[..2]).join(key)
+    }
+
+    /// Look up a cached result; a hit refreshes the entry's recency
+    pub fn get(&self, op: &str, payload: &[f64]) -> Option<Vec<f64>> {
+        let path = self.entry_path(&Self::key(op, payload));
+        let bytes = fs::read(&path).ok()?;
+        if bytes.len() % 8 != 0 {
+            // Torn write from a crash; drop it rather than return garbage
+            if fs::remove_file(&path).is_ok() {
+                let mut total = self.total_bytes.lock().unwrap();
+                *total = total.saturating_sub(bytes.len() as u64);
+            }
+            return None;
+        }
+
+        if let Ok(file) = fs::File::options().write(true).open(&path) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+
+        Some(
+            bytes
+                .chunks_exact(8)
+                .map(|b| f64::from_le_bytes(b.try_into().unwrap()))
+                .collect(),
+        )
+    }
+
+    /// Store a result, evicting old entries if the cache is over budget
+    pub fn put(&self, op: &str, payload: &[f64], value: &[f64]) -> io::Result<()> {
+        let key = Self::key(op, payload);
+        let path = self.entry_path(&key);
+        fs::create_dir_all(path.parent().unwrap())?;
+
+        let bytes: Vec<u8> = value.iter().flat_map(|v| v.to_le_bytes()).collect();
+
+        // Write-then-rename so readers never see a partial entry. The temporary
+        // name is unique per writer so concurrent puts of one key can't collide.
+        let tmp = path.with_file_name(temp_name(&key));
+        fs::write(&tmp, &bytes)?;
+        let mut total = self.total_bytes.lock().unwrap();
+        let replaced = fs::metadata(&path).map(|meta| meta.len()).unwrap_or(0);
+        fs::rename(&tmp, &path)?;
+        *total = total.saturating_sub(replaced) + bytes.len() as u64;
+
+        // Only walk the shards when the budget is actually exceeded
+        if *total > self.max_bytes {
+            *total = self.evict()?;
+        }
+        Ok(())
+    }
+
+    /// Total bytes currently stored
+    pub fn size_bytes(&self) -> io::Result<u64> {
+        Ok(*self.total_bytes.lock().unwrap())
+    }
+
+    fn entries(&self) -> io::Result<Vec<(PathBuf, u64, SystemTime)>> {
+        let mut entries = Vec::new();
+        for shard in fs::read_dir(&self.dir)? {
+            let shard = shard?;
+            if !shard.file_type()?.is_dir() {
+                continue;
+            }
+            for entry in fs::read_dir(shard.path())? {
+                let entry = entry?;
+                let meta = entry.metadata()?;
+                if meta.is_file() && !entry.file_name().to_string_lossy().contains(TEMP_MARKER) {
+                    let modified = meta.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                    entries.push((entry.path(), meta.len(), modified));
+                }
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Remove least recently used entries until the cache fits; returns the bytes left
+    fn evict(&self) -> io::Result<u64> {
+        let mut entries = self.entries()?;
+        let mut total: u64 = entries.iter().map(|(_, len, _)| len).sum();
+        if total <= self.max_bytes {
+            return Ok(total);
+        }
+
+        entries.sort_by_key(|(_, _, modified)| *modified);
+        for (path, len, _) in entries {
+            if total <= self.max_bytes {
+                break;
+            }
+            if fs::remove_file(&path).is_ok() {
+                total -= len;
+            }
+        }
+        Ok(total)
+    }
+}
+
+const TEMP_MARKER: &str = ".tmp.";
+
+/// `<key>.tmp.<pid>.<n>`, distinct across processes and threads
+fn temp_name(key: &str) -> String {
+    static NEXT: AtomicU64 = AtomicU64::new(0);
+    let n = NEXT.fetch_add(1, Ordering::Relaxed);
+    format!("{key}{TEMP_MARKER}{}.{n}", std::process::id())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ComputeBackend, ComputeConfig, ComputeEngine};
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("cv-compute-cache-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_round_trip_and_key_separation() {
+        let dir = temp_dir("round-trip");
+        let cache = PersistentCache::open(&dir, PersistentCache::DEFAULT_MAX_BYTES).unwrap();
+
+        cache.put("statistics", &[1.0, 2.0], &[1.5, 0.5]).unwrap();
+        assert_eq!(cache.get("statistics", &[1.0, 2.0]), Some(vec![1.5, 0.5]));
+        assert_eq!(cache.get("heavy_task", &[1.0, 2.0]), None);
+        assert_eq!(cache.get("statistics", &[2.0, 1.0]), None);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let dir = temp_dir("evict");
+        // Room for two 8-byte entries
+        let cache = PersistentCache::open(&dir, 16).unwrap();
+
+        cache.put("op", &[1.0], &[1.0]).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        cache.put("op", &[2.0], &[2.0]).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        assert!(cache.get("op", &[1.0]).is_some()); // refresh entry 1
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        cache.put("op", &[3.0], &[3.0]).unwrap();
+
+        assert!(cache.get("op", &[2.0]).is_none());
+        assert!(cache.get("op", &[1.0]).is_some());
+        assert!(cache.size_bytes().unwrap() <= 16);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_concurrent_puts_of_one_key() {
+        let dir = temp_dir("concurrent");
+        let cache = PersistentCache::open(&dir, PersistentCache::DEFAULT_MAX_BYTES).unwrap();
+
+        std::thread::scope(|scope| {
+            for t in 0..8 {
+                let cache = &cache;
+                scope.spawn(move || {
+                    for _ in 0..50 {
+                        cache.put("op", &[1.0], &[t as f64]).unwrap();
+                    }
+                });
+            }
+        });
+
+        assert_eq!(cache.get("op", &[1.0]).map(|v| v.len()), Some(1));
+        assert_eq!(cache.size_bytes().unwrap(), 8);
+        let key = PersistentCache::key("op", &[1.0]);
+        assert_eq!(fs::read_dir(dir.join(&key[..2])).unwrap().count(), 1);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_size_is_counted_at_open_and_tracked() {
+        let dir = temp_dir("size");
+        let cache = PersistentCache::open(&dir, PersistentCache::DEFAULT_MAX_BYTES).unwrap();
+        cache.put("op", &[1.0], &[1.0, 2.0]).unwrap();
+        cache.put("op", &[2.0], &[1.0]).unwrap();
+        // Overwriting an entry replaces its size rather than adding to it
+        cache.put("op", &[1.0], &[1.0]).unwrap();
+        assert_eq!(cache.size_bytes().unwrap(), 16);
+        drop(cache);
+
+        let reopened = PersistentCache::open(&dir, PersistentCache::DEFAULT_MAX_BYTES).unwrap();
+        assert_eq!(reopened.size_bytes().unwrap(), 16);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_engine_results_survive_restart() {
+        let dir = temp_dir("engine");
+        let config = || ComputeConfig {
+            max_workers: 1,
+            timeout_seconds: 30,
+            bind_addr: ComputeConfig::DEFAULT_BIND_ADDR,
+        };
+        let data = [1.0, 2.0, 3.0, 4.0];
+
+        let first = ComputeEngine::with_persistent_cache(config(), &dir).unwrap();
+        let expected = first.calculate_statistics(&data);
+        drop(first);
+
+        let restarted = ComputeEngine::with_persistent_cache(config(), &dir).unwrap();
+        let key = PersistentCache::key("calculate_statistics", &data);
+        assert!(dir.join(&key[..2]).join(&key).exists());
+        assert_eq!(restarted.calculate_statistics(&data).mean, expected.mean);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_backends_do_not_share_entries() {
+        let dir = temp_dir("backend");
+        let config = ComputeConfig {
+            max_workers: 1,
+            timeout_seconds: 30,
+            bind_addr: ComputeConfig::DEFAULT_BIND_ADDR,
+        };
+        let data = [1.0, 0.0, -1.0, 0.0];
+
+        let cpu = ComputeEngine::with_persistent_cache(config, &dir)
+            .unwrap()
+            .with_backend(ComputeBackend::Cpu);
+        cpu.fourier_transform(&data);
+
+        let entry = |op: &str| {
+            let key = PersistentCache::key(op, &data);
+            dir.join(&key[..2]).join(&key).exists()
+        };
+        assert!(entry("fourier_transform:cpu"));
+        assert!(!entry("fourier_transform"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
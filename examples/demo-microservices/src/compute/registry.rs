@@ -0,0 +1,234 @@
+// Computation registry - named, versioned computations for old clients
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// A registered computation over a series of values
+pub type ComputationFn = Arc<dyn Fn(&[f64]) -> Result<Vec<f64>, ComputationError> + Send + Sync>;
+
+/// Errors from looking up or running a named computation
+#[derive(Debug, Clone, PartialEq)]
+pub enum ComputationError {
+    /// No computation is registered under this name
+    Unknown(String),
+    /// The name is registered, but not at this version
+    UnknownVersion { name: String, version: u32 },
+    /// A computation with this name and version already exists
+    AlreadyRegistered { name: String, version: u32 },
+    /// The computation rejected its input
+    InvalidInput(String),
+}
+
+impl fmt::Display for ComputationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ComputationError::Unknown(name) => write!(f, "unknown computation '{}'", name),
+            ComputationError::UnknownVersion { name, version } => {
+                write!(f, "computation '{}' has no version {}", name, version)
+            }
+            ComputationError::AlreadyRegistered { name, version } => {
+                write!(f, "computation '{}' v{} is already registered", name, version)
+            }
+            ComputationError::InvalidInput(reason) => write!(f, "invalid input: {}", reason),
+        }
+    }
+}
+
+impl std::error::Error for ComputationError {}
+
+/// One registered version of a computation
+pub struct Computation {
+    name: String,
+    version: u32,
+    deprecation: Option<String>,
+    calls: AtomicU64,
+    func: ComputationFn,
+}
+
+impl Computation {
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    /// Why the computation is deprecated and what to use instead, if it is
+    pub fn deprecation(&self) -> Option<&str> {
+        self.deprecation.as_deref()
+    }
+
+    /// How many times this version has been run
+    pub fn calls(&self) -> u64 {
+        self.calls.load(Ordering::Relaxed)
+    }
+}
+
+impl fmt::Debug for Computation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Computation")
+            .field("name", &self.name)
+            .field("version", &self.version)
+            .field("deprecation", &self.deprecation)
+            .field("calls", &self.calls())
+            .finish()
+    }
+}
+
+/// Named computations, each with one or more versions.
+/// Unversioned lookups resolve to the highest registered version.
+#[derive(Debug, Default)]
+pub struct ComputationRegistry {
+    entries: HashMap<String, Vec<Computation>>,
+}
+
+impl ComputationRegistry {
+    /// An empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A registry with the computations older clients still call
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::new();
+        registry
+            .register_deprecated(
+                "legacy",
+                1,
+                "replaces the removed legacy_computation (2x + 1 per value); compute it client-side",
+                |input| Ok(input.iter().map(|x| x * 2.0 + 1.0).collect()),
+            )
+            .expect("builtin computations are unique");
+        registry
+    }
+
+    /// Register `func` as `name` at `version`
+    pub fn register<F>(&mut self, name: &str, version: u32, func: F) -> Result<(), ComputationError>
+    where
+        F: Fn(&[f64]) -> Result<Vec<f64>, ComputationError> + Send + Sync + 'static,
+    {
+        self.insert(name, version, None, Arc::new(func))
+    }
+
+    /// Register a computation that logs a deprecation warning every time it runs
+    pub fn register_deprecated<F>(
+        &mut self,
+        name: &str,
+        version: u32,
+        note: &str,
+        func: F,
+    ) -> Result<(), ComputationError>
+    where
+        F: Fn(&[f64]) -> Result<Vec<f64>, ComputationError> + Send + Sync + 'static,
+    {
+        self.insert(name, version, Some(note.to_string()), Arc::new(func))
+    }
+
+    fn insert(
+        &mut self,
+        name: &str,
+        version: u32,
+        deprecation: Option<String>,
+        func: ComputationFn,
+    ) -> Result<(), ComputationError> {
+        let versions = self.entries.entry(name.to_string()).or_default();
+        if versions.iter().any(|c| c.version == version) {
+            return Err(ComputationError::AlreadyRegistered {
+                name: name.to_string(),
+                version,
+            });
+        }
+        versions.push(Computation {
+            name: name.to_string(),
+            version,
+            deprecation,
+            calls: AtomicU64::new(0),
+            func,
+        });
+        versions.sort_by_key(|c| c.version);
+        Ok(())
+    }
+
+    /// Look up `name` at `version`, or its latest version when `None`
+    pub fn get(&self, name: &str, version: Option<u32>) -> Result<&Computation, ComputationError> {
+        let versions = self
+            .entries
+            .get(name)
+            .ok_or_else(|| ComputationError::Unknown(name.to_string()))?;
+        match version {
+            None => versions.last().ok_or_else(|| ComputationError::Unknown(name.to_string())),
+            Some(v) => versions
+                .iter()
+                .find(|c| c.version == v)
+                .ok_or(ComputationError::UnknownVersion {
+                    name: name.to_string(),
+                    version: v,
+                }),
+        }
+    }
+
+    /// Run a computation, warning through tracing if it is deprecated
+    pub fn run(&self, name: &str, version: Option<u32>, input: &[f64]) -> Result<Vec<f64>, ComputationError> {
+        let computation = self.get(name, version)?;
+        computation.calls.fetch_add(1, Ordering::Relaxed);
+
+        #[cfg(feature = "tracing")]
+        if let Some(note) = &computation.deprecation {
+            tracing::warn!(
+                computation = name,
+                version = computation.version,
+                calls = computation.calls(),
+                "deprecated computation called: {}",
+                note
+            );
+        }
+
+        (computation.func)(input)
+    }
+
+    /// Every registered computation, by name then version
+    pub fn list(&self) -> Vec<&Computation> {
+        let mut all: Vec<&Computation> = self.entries.values().flatten().collect();
+        all.sort_by(|a, b| a.name.cmp(&b.name).then(a.version.cmp(&b.version)));
+        all
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latest_version_wins_unless_pinned() {
+        let mut registry = ComputationRegistry::with_builtins();
+        registry.register("legacy", 2, |input| Ok(input.to_vec())).unwrap();
+
+        assert_eq!(registry.run("legacy", None, &[1.0]).unwrap(), vec![1.0]);
+        assert_eq!(registry.run("legacy", Some(1), &[1.0, 2.0]).unwrap(), vec![3.0, 5.0]);
+
+        let v1 = registry.get("legacy", Some(1)).unwrap();
+        assert!(v1.deprecation().is_some());
+        assert_eq!(v1.calls(), 1);
+    }
+
+    #[test]
+    fn test_registry_errors() {
+        let mut registry = ComputationRegistry::with_builtins();
+        assert_eq!(
+            registry.run("missing", None, &[]),
+            Err(ComputationError::Unknown("missing".to_string()))
+        );
+        assert!(matches!(
+            registry.run("legacy", Some(9), &[]),
+            Err(ComputationError::UnknownVersion { version: 9, .. })
+        ));
+        assert!(matches!(
+            registry.register("legacy", 1, |_| Ok(vec![])),
+            Err(ComputationError::AlreadyRegistered { .. })
+        ));
+        assert_eq!(registry.list().len(), 1);
+    }
+}
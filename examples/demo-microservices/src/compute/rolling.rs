@@ -0,0 +1,312 @@
+// Rolling windows - incremental statistics over sliding windows of a stream
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, VecDeque};
+
+use super::Statistics;
+
+/// Sliding-window statistics over a stream of samples.
+///
+/// Every `stride` samples, once `size` samples have been seen, a [`Statistics`]
+/// for the latest `size` samples is emitted. Mean and standard deviation are
+/// updated in O(1) per sample, min/max through monotonic queues in O(1)
+/// amortized, and the median from two heaps split around it in O(log size)
+/// amortized. Evicted samples stay in the heaps until they surface at a top
+/// (lazy deletion). Samples are expected to be finite.
+#[derive(Debug, Clone)]
+pub struct RollingWindow {
+    size: usize,
+    stride: usize,
+    window: VecDeque<f64>,
+    /// The lower half of the window, largest on top
+    low: BinaryHeap<Sample>,
+    /// The upper half of the window, smallest on top
+    high: BinaryHeap<Reverse<Sample>>,
+    /// Samples in `low` and `high` that are still in the window
+    low_live: usize,
+    high_live: usize,
+    /// (sample index, value), values non-increasing
+    max_queue: VecDeque<(u64, f64)>,
+    /// (sample index, value), values non-decreasing
+    min_queue: VecDeque<(u64, f64)>,
+    mean: f64,
+    m2: f64,
+    seen: u64,
+    evictions_since_refresh: usize,
+}
+
+impl RollingWindow {
+    /// A window of `size` samples advancing by `stride`; `None` if either is zero
+    pub fn new(size: usize, stride: usize) -> Option<RollingWindow> {
+        if size == 0 || stride == 0 {
+            return None;
+        }
+        Some(RollingWindow {
+            size,
+            stride,
+            window: VecDeque::with_capacity(size),
+            low: BinaryHeap::with_capacity(size),
+            high: BinaryHeap::with_capacity(size),
+            low_live: 0,
+            high_live: 0,
+            max_queue: VecDeque::new(),
+            min_queue: VecDeque::new(),
+            mean: 0.0,
+            m2: 0.0,
+            seen: 0,
+            evictions_since_refresh: 0,
+        })
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    /// Add a sample; returns the window's statistics when one is due
+    pub fn push(&mut self, value: f64) -> Option<Statistics> {
+        if self.window.len() == self.size {
+            self.evict();
+        }
+
+        let index = self.seen;
+        self.seen += 1;
+        self.window.push_back(value);
+
+        // Welford update
+        let n = self.window.len() as f64;
+        let delta = value - self.mean;
+        self.mean += delta / n;
+        self.m2 += delta * (value - self.mean);
+
+        let sample = Sample { value, index };
+        if self.low.peek().is_some_and(|top| sample > *top) {
+            self.high.push(Reverse(sample));
+            self.high_live += 1;
+        } else {
+            self.low.push(sample);
+            self.low_live += 1;
+        }
+        self.rebalance();
+
+        while self.max_queue.back().is_some_and(|&(_, v)| v <= value) {
+            self.max_queue.pop_back();
+        }
+        self.max_queue.push_back((index, value));
+        while self.min_queue.back().is_some_and(|&(_, v)| v >= value) {
+            self.min_queue.pop_back();
+        }
+        self.min_queue.push_back((index, value));
+
+        let full = self.seen >= self.size as u64;
+        (full && (self.seen - self.size as u64).is_multiple_of(self.stride as u64)).then(|| self.statistics())
+    }
+
+    /// Push every sample, collecting the windows emitted along the way
+    pub fn extend(&mut self, samples: &[f64]) -> Vec<Statistics> {
+        samples.iter().filter_map(|&x| self.push(x)).collect()
+    }
+
+    /// Statistics of the samples currently in the window (possibly not yet full)
+    pub fn statistics(&self) -> Statistics {
+        if self.window.is_empty() {
+            return Statistics::default();
+        }
+        let len = self.window.len();
+        let lower = self.low.peek().map_or(f64::NAN, |s| s.value);
+        let median = if len.is_multiple_of(2) {
+            (lower + self.high.peek().map_or(f64::NAN, |s| s.0.value)) / 2.0
+        } else {
+            lower
+        };
+        Statistics {
+            mean: self.mean,
+            median,
+            std_dev: (self.m2.max(0.0) / len as f64).sqrt(),
+            min: self.min_queue.front().map_or(f64::NAN, |&(_, v)| v),
+            max: self.max_queue.front().map_or(f64::NAN, |&(_, v)| v),
+        }
+    }
+
+    fn evict(&mut self) {
+        let Some(old) = self.window.pop_front() else { return };
+        let old_index = self.seen - self.size as u64;
+
+        // Reverse Welford update
+        let n = self.window.len() as f64;
+        if n == 0.0 {
+            self.mean = 0.0;
+            self.m2 = 0.0;
+        } else {
+            let delta = old - self.mean;
+            self.mean -= delta / n;
+            self.m2 -= delta * (old - self.mean);
+        }
+
+        // The heap tops are always live, so comparing against the lower top
+        // tells which half the evicted sample sits in
+        let sample = Sample { value: old, index: old_index };
+        if self.low.peek().is_some_and(|top| sample <= *top) {
+            self.low_live -= 1;
+        } else {
+            self.high_live -= 1;
+        }
+        self.prune();
+        self.rebalance();
+
+        if self.max_queue.front().is_some_and(|&(i, _)| i == old_index) {
+            self.max_queue.pop_front();
+        }
+        if self.min_queue.front().is_some_and(|&(i, _)| i == old_index) {
+            self.min_queue.pop_front();
+        }
+
+        // Removals accumulate rounding error; recompute exactly once per window
+        // length so the cost stays O(1) amortized
+        self.evictions_since_refresh += 1;
+        if self.evictions_since_refresh >= self.size {
+            self.evictions_since_refresh = 0;
+            let len = self.window.len() as f64;
+            if len > 0.0 {
+                self.mean = self.window.iter().sum::<f64>() / len;
+                self.m2 = self.window.iter().map(|x| (x - self.mean).powi(2)).sum();
+            }
+
+            // Evicted samples buried below the tops are only dropped here,
+            // which keeps the heaps within twice the window length
+            let first = self.first_index();
+            self.low.retain(|s| s.index >= first);
+            self.high.retain(|s| s.0.index >= first);
+        }
+    }
+
+    /// Sample index of the oldest value in the window
+    fn first_index(&self) -> u64 {
+        self.seen - self.window.len() as u64
+    }
+
+    /// Pop evicted samples off the heap tops
+    fn prune(&mut self) {
+        let first = self.first_index();
+        while self.low.peek().is_some_and(|s| s.index < first) {
+            self.low.pop();
+        }
+        while self.high.peek().is_some_and(|s| s.0.index < first) {
+            self.high.pop();
+        }
+    }
+
+    /// Restore `low_live == high_live` or `low_live == high_live + 1`
+    fn rebalance(&mut self) {
+        while self.low_live > self.high_live + 1 {
+            let Some(top) = self.low.pop() else { break };
+            self.high.push(Reverse(top));
+            self.low_live -= 1;
+            self.high_live += 1;
+            self.prune();
+        }
+        while self.high_live > self.low_live {
+            let Some(Reverse(top)) = self.high.pop() else { break };
+            self.low.push(top);
+            self.high_live -= 1;
+            self.low_live += 1;
+            self.prune();
+        }
+    }
+}
+
+/// A sample ordered by value, then by arrival so that equal values stay distinct
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+    value: f64,
+    index: u64,
+}
+
+impl Ord for Sample {
+    fn cmp(&self, other: &Sample) -> Ordering {
+        self.value.total_cmp(&other.value).then(self.index.cmp(&other.index))
+    }
+}
+
+impl PartialOrd for Sample {
+    fn partial_cmp(&self, other: &Sample) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Sample {
+    fn eq(&self, other: &Sample) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl Eq for Sample {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn naive(data: &[f64]) -> Statistics {
+        let mut sorted = data.to_vec();
+        sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let n = data.len() as f64;
+        let mean = data.iter().sum::<f64>() / n;
+        let len = sorted.len();
+        Statistics {
+            mean,
+            median: if len.is_multiple_of(2) {
+                (sorted[len / 2 - 1] + sorted[len / 2]) / 2.0
+            } else {
+                sorted[len / 2]
+            },
+            std_dev: (data.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n).sqrt(),
+            min: sorted[0],
+            max: sorted[len - 1],
+        }
+    }
+
+    #[test]
+    fn test_rolling_matches_recomputing_each_window() {
+        let data: Vec<f64> = (0..500).map(|i| ((i * 37) % 101) as f64 * 0.5 - 10.0).collect();
+        for (size, stride) in [(1, 1), (10, 1), (16, 5), (32, 40)] {
+            let windows = RollingWindow::new(size, stride).unwrap().extend(&data);
+            let starts: Vec<usize> = (0..=data.len() - size).step_by(stride).collect();
+            assert_eq!(windows.len(), starts.len());
+
+            for (stats, &start) in windows.iter().zip(&starts) {
+                let expected = naive(&data[start..start + size]);
+                assert!((stats.mean - expected.mean).abs() < 1e-9);
+                assert!((stats.std_dev - expected.std_dev).abs() < 1e-9);
+                assert_eq!(stats.median, expected.median);
+                assert_eq!((stats.min, stats.max), (expected.min, expected.max));
+            }
+        }
+    }
+
+    #[test]
+    fn test_rolling_median_with_repeated_values_bounds_the_heaps() {
+        let data: Vec<f64> = (0..2000).map(|i| ((i * 7919) % 13) as f64).collect();
+        let mut window = RollingWindow::new(25, 1).unwrap();
+        for (i, &x) in data.iter().enumerate() {
+            let Some(stats) = window.push(x) else { continue };
+            assert_eq!(stats.median, naive(&data[i + 1 - 25..=i]).median);
+            assert!(window.low.len() + window.high.len() <= 2 * 25);
+        }
+    }
+
+    #[test]
+    fn test_rolling_rejects_empty_window_and_waits_until_full() {
+        assert!(RollingWindow::new(0, 1).is_none());
+        assert!(RollingWindow::new(4, 0).is_none());
+
+        let mut window = RollingWindow::new(3, 2).unwrap();
+        assert!(window.push(1.0).is_none());
+        assert!(window.push(2.0).is_none());
+        assert_eq!(window.push(3.0).map(|s| s.median), Some(2.0));
+        assert!(window.push(4.0).is_none());
+        assert_eq!(window.push(5.0).map(|s| s.max), Some(5.0));
+    }
+}
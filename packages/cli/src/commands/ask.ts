@@ -0,0 +1,219 @@
+/**
+ * cv ask command
+ * Answer a question about the codebase, optionally as JSON matching a schema
+ *
+ * `cv ask --schema endpoints.json "list all HTTP endpoints"` prints only the
+ * JSON answer on stdout, so it can be piped or saved; progress goes to stderr.
+ */
+
+import { Command } from 'commander';
+import chalk from 'chalk';
+import ora from 'ora';
+import * as path from 'path';
+import {
+  configManager,
+  createAIManager,
+  createVectorManager,
+  createGraphManager,
+  createGitManager,
+  loadContextUsage,
+  loadKeywordIndex,
+  loadScoreDistribution,
+  saveContextUsage,
+  contextSources,
+  loadPromptTemplates,
+  loadJsonSchema,
+  StructuredOutputError,
+  DEFAULT_STRUCTURED_ATTEMPTS
+} from '@cv-git/core';
+import { findRepoRoot } from '@cv-git/shared';
+import { loadCitationFormatter, printSources, sourcesToJson } from '../utils/citations.js';
+import { addGlobalOptions, createJsonReporter, ErrorCode, ExitCode } from '../utils/output.js';
+import { createModelClient, fallbackNotice } from '../utils/model-client.js';
+import { resolveEmbeddingProvider } from '../utils/embedding-provider.js';
+import { vectorStoreOptions } from '../utils/vector-store.js';
+import { applyReranker } from '../utils/reranker.js';
+import { applyQueryExpansion } from '../utils/query-expansion.js';
+import { ensureFreshIndex } from '../utils/watch-daemon.js';
+
+export function askCommand(): Command {
+  const cmd = new Command('ask');
+
+  cmd
+    .description('Answer a question about the codebase, as prose or as JSON matching --schema')
+    .argument('<question>', 'Question about the code')
+    .option('-m, --model <model>', 'Model to use, optionally as provider/model (anthropic/claude-sonnet-4-5, gemini/gemini-2.5-pro, openrouter/openai/gpt-4o, ollama/qwen2.5-coder:14b)')
+    .option('--schema <file>', 'JSON Schema the answer must match; prints the answer as JSON')
+    .option('--retries <n>', 'Times to ask again when the answer does not match the schema', String(DEFAULT_STRUCTURED_ATTEMPTS - 1))
+    .option('--expand-query <mode>', 'Also search reformulations of the query: off, synonyms or llm (default: retrieval.expansion)')
+    .option('-c, --context-limit <n>', 'Max code chunks to include (default: retrieval.topK)')
+    .option('--no-stream', 'Print the answer when it is complete instead of token by token');
+
+  addGlobalOptions(cmd);
+
+  cmd.action(async (question: string, options) => {
+    // With --schema, stdout carries only the answer
+    const report = createJsonReporter('ask', options);
+    const schemaFile: string | undefined = options.schema;
+    const spinner = ora({ text: 'Initializing...', isSilent: Boolean(report) || options.quiet }).start();
+
+    try {
+      const repoRoot = await findRepoRoot();
+      if (!repoRoot) {
+        report?.fail('Not in a CV-Git repository', ErrorCode.NOT_INITIALIZED);
+        spinner.fail(chalk.red('Not in a CV-Git repository'));
+        console.error(chalk.gray('Run `cv init` first'));
+        process.exit(ExitCode.Error);
+      }
+
+      // A bad schema should fail before anything is retrieved or sent
+      const schema = schemaFile ? await loadJsonSchema(path.resolve(schemaFile)) : undefined;
+
+      const config = await configManager.load(repoRoot);
+      // Structured answers go through the chat client's tool calling
+      const chatClient = await createModelClient(options.model, {
+        provider: 'anthropic',
+        model: config.ai.model,
+        anthropicApiKey: config.ai.apiKey,
+        fallback: config.ai.fallback
+      });
+
+      const embeddingProvider = await resolveEmbeddingProvider(config).catch(() => undefined);
+      spinner.text = 'Checking for pending changes...';
+      await ensureFreshIndex(repoRoot);
+
+      spinner.text = 'Connecting to services...';
+      let vector = undefined;
+      if (embeddingProvider && config.vector) {
+        try {
+          vector = createVectorManager({
+            url: config.vector.url,
+            ...vectorStoreOptions(config, repoRoot),
+            provider: embeddingProvider,
+            collections: config.vector.collections
+          });
+          await vector.connect();
+        } catch {
+          console.error(chalk.gray('  ⚠ Could not connect to vector DB - continuing without semantic search'));
+          vector = undefined;
+        }
+      }
+      const graph = createGraphManager(config.graph.url, config.graph.database);
+      await graph.connect();
+      const git = createGitManager(repoRoot);
+
+      const ai = createAIManager(
+        {
+          provider: 'anthropic',
+          model: config.ai.model,
+          apiKey: '',
+          chatClient,
+          retrieval: config.retrieval
+        },
+        vector,
+        graph,
+        git
+      );
+      ai.setPromptTemplates(await loadPromptTemplates(repoRoot, config.repository?.name));
+      const contextUsage = await loadContextUsage(repoRoot);
+      ai.setContextUsage(contextUsage);
+      ai.setKeywordIndex(await loadKeywordIndex(repoRoot));
+      applyReranker(ai, config);
+      applyQueryExpansion(ai, config, 'ask', options.expandQuery);
+      ai.setScoreDistribution(await loadScoreDistribution(repoRoot, vector?.getEmbeddingInfo().model));
+
+      const close = async () => {
+        await graph.close();
+        if (vector) await vector.close();
+      };
+
+      spinner.text = 'Gathering context...';
+      const maxChunks = options.contextLimit ? Math.max(1, parseInt(options.contextLimit, 10) || 1) : undefined;
+      const context = await ai.gatherContext(question, { maxChunks });
+      if (context.chunks.length === 0 && context.symbols.length === 0) {
+        await close();
+        report?.fail('No relevant code found', ErrorCode.NO_RESULTS);
+        spinner.fail(chalk.yellow('No relevant code found; run `cv sync` or rephrase the question'));
+        process.exit(ExitCode.Error);
+      }
+      const sources = contextSources(context.chunks);
+
+      if (schema) {
+        const attempts = Math.max(0, parseInt(options.retries, 10) || 0) + 1;
+        spinner.text = `Asking ${chatClient.getProvider()}/${chatClient.getModel()}...`;
+        let result;
+        try {
+          result = await ai.askStructured(question, context, schema, {
+            maxAttempts: attempts,
+            onRetry: (attempt, errors) => {
+              spinner.text = `Answer ${attempt} did not match the schema (${errors[0]}${errors.length > 1 ? `, +${errors.length - 1} more` : ''}); asking again...`;
+            }
+          });
+        } catch (error: any) {
+          await close();
+          if (!(error instanceof StructuredOutputError)) throw error;
+          report?.fail(error.message, 'SCHEMA_MISMATCH');
+          spinner.fail(chalk.red(error.message));
+          error.errors.slice(0, 10).forEach((problem: string) => console.error(chalk.gray(`  • ${problem}`)));
+          if (options.verbose && error.lastReply !== undefined) {
+            console.error(chalk.gray(JSON.stringify(error.lastReply, null, 2)));
+          }
+          process.exit(ExitCode.Error);
+        }
+        spinner.stop();
+        await saveContextUsage(repoRoot, contextUsage);
+        await close();
+
+        if (report) {
+          report.result({
+            question,
+            answer: result.value,
+            attempts: result.attempts,
+            mode: result.mode,
+            model: `${chatClient.getProvider()}/${chatClient.getModel()}`,
+            sources: sourcesToJson(sources, repoRoot)
+          });
+          return;
+        }
+        process.stdout.write(JSON.stringify(result.value, null, 2) + '\n');
+        if (!options.quiet) {
+          const retried = result.attempts > 1 ? `, after ${result.attempts} attempts` : '';
+          console.error(chalk.gray(`Matched ${path.basename(schemaFile!)} from ${context.chunks.length} code chunks${retried}`));
+          const notice = fallbackNotice(chatClient);
+          if (notice) console.error(chalk.gray(notice));
+        }
+        return;
+      }
+
+      spinner.succeed(chalk.green(`Found ${context.chunks.length} code chunks and ${context.symbols.length} symbols`));
+      if (report) {
+        const answer = await ai.ask(question, context);
+        report.result({ question, answer, sources: sourcesToJson(sources, repoRoot) });
+      } else {
+        console.log();
+        if (options.stream) {
+          await ai.ask(question, context, { onToken: token => process.stdout.write(token) });
+          console.log();
+        } else {
+          console.log(await ai.ask(question, context));
+        }
+        const notice = fallbackNotice(chatClient);
+        if (notice) console.log(chalk.gray(notice));
+        printSources(sources, await loadCitationFormatter(repoRoot));
+        console.log();
+      }
+
+      await saveContextUsage(repoRoot, contextUsage);
+      await close();
+    } catch (error: any) {
+      report?.fail(error.message, error.message.includes('API key') ? ErrorCode.NO_CREDENTIALS : ErrorCode.OPERATION_FAILED);
+      spinner.fail(chalk.red(error.message));
+      if (process.env.CV_DEBUG) {
+        console.error(chalk.gray(error.stack));
+      }
+      process.exit(ExitCode.Error);
+    }
+  });
+
+  return cmd;
+}
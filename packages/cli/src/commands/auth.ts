@@ -6,7 +6,7 @@
  *
  * Categories:
  * - git: GitHub, GitLab, Bitbucket
- * - ai: Anthropic, OpenAI, OpenRouter
+ * - ai: Anthropic, OpenAI, OpenRouter, Gemini
  * - dns: Cloudflare
  * - devops: AWS, DigitalOcean
  */
@@ -21,15 +21,19 @@ import { homedir, hostname } from 'os';
 import { join, dirname } from 'path';
 import {
   CredentialManager,
+  CredentialStorageInfo,
+  StorageBackend,
+  BaseCredential,
   CredentialType,
   GitPlatform,
   GitPlatformTokenCredential,
   AnthropicAPICredential,
   OpenAIAPICredential,
   OpenRouterAPICredential,
+  GeminiAPICredential,
 } from '@cv-git/credentials';
-import { GitHubAdapter, GitLabAdapter, BitbucketAdapter } from '@cv-git/platform';
-import { getPreferences } from '../config.js';
+import { GitHubAdapter, GitLabAdapter, BitbucketAdapter, createFetch, type FetchLike, type TlsOptions } from '@cv-git/platform';
+import { getConfig, getPreferences } from '../config.js';
 import { getRequiredServices } from '../utils/preference-picker.js';
 import { openBrowser } from './auth-utils.js';
 import {
@@ -48,6 +52,7 @@ import {
   testDigitalOceanSpaces,
 } from './auth/devops/digitalocean.js';
 import { setupNPM, testNPM, configureNPMCLI } from './auth/publish/npm.js';
+import { setupLocalEmbeddings, testLocalEmbeddings } from './auth/ai/local.js';
 import { setupCVHub, testCVHub, setupControlfab, testControlfab, writeHookCredentials, CV_HUB_CONFIG } from './auth/git/cv-hub.js';
 import { addGlobalOptions } from '../utils/output.js';
 import { readCredentials, getMachineName, findCredentialFile, writeCredentialField } from '../utils/cv-hub-credentials.js';
@@ -216,12 +221,14 @@ export function authCommand(): Command {
       console.log();
     });
 
-  // cv auth status — Show CV-Hub connection info
+  // cv auth status — Credential storage and CV-Hub connection info
   cmd
     .command('status')
-    .description('Show CV-Hub authentication status and machine info')
+    .description('Show where credentials are stored, CV-Hub authentication status and machine info')
     .action(async () => {
-      console.log(chalk.bold('\n🔐 CV-Hub Authentication Status\n'));
+      await showStorageStatus();
+
+      console.log(chalk.bold('🔐 CV-Hub Authentication Status\n'));
 
       const creds = await readCredentials();
       const credFile = await findCredentialFile();
@@ -296,35 +303,70 @@ export function authCommand(): Command {
       await runTest(service, credentials);
     });
 
-  // cv auth remove <type> <name>
+  // cv auth remove <service|type> [name]
   cmd
-    .command('remove <type> <name>')
-    .description('Remove a credential')
-    .action(async (type: string, name: string) => {
+    .command('remove [service] [name]')
+    .description('Remove stored credentials (e.g. anthropic, github, or <type> <name>)')
+    .option('--all', 'Remove every stored credential')
+    .option('-y, --yes', 'Do not ask for confirmation')
+    .action(async (service: string | undefined, name: string | undefined, cmdOptions: { all?: boolean; yes?: boolean }) => {
       const credentials = new CredentialManager();
       await credentials.init();
 
-      const { confirm } = await inquirer.prompt([
-        {
-          type: 'confirm',
-          name: 'confirm',
-          message: `Are you sure you want to remove ${type}:${name}?`,
-          default: false,
-        },
-      ]);
+      if (!service && !cmdOptions.all) {
+        console.log(chalk.red('Give a service (e.g. anthropic), a type and name, or --all.'));
+        console.log(chalk.gray('Run ') + chalk.cyan('cv auth list') + chalk.gray(' to see stored credentials.'));
+        process.exitCode = 1;
+        return;
+      }
+
+      const stored = await credentials.list();
+      const targets = cmdOptions.all
+        ? stored
+        : stored.filter((cred) => matchesCredential(cred, service!) && (!name || cred.name === name));
 
-      if (!confirm) {
-        console.log(chalk.gray('Cancelled.'));
+      if (targets.length === 0) {
+        console.log(chalk.yellow(`No stored credentials match ${[service, name].filter(Boolean).join(' ')}.`));
+        console.log(chalk.gray('Run ') + chalk.cyan('cv auth list') + chalk.gray(' to see stored credentials.'));
+        process.exitCode = 1;
         return;
       }
 
-      const spinner = ora('Removing credential...').start();
+      const labels = targets.map((cred) => `${cred.type}:${cred.name}`);
+      if (!cmdOptions.yes) {
+        const { confirm } = await inquirer.prompt([
+          {
+            type: 'confirm',
+            name: 'confirm',
+            message: targets.length === 1
+              ? `Are you sure you want to remove ${labels[0]}?`
+              : `Are you sure you want to remove ${targets.length} credentials (${labels.join(', ')})?`,
+            default: false,
+          },
+        ]);
 
-      try {
-        await credentials.delete(type as CredentialType, name);
-        spinner.succeed(chalk.green(`Removed ${type}:${name}`));
-      } catch (error: any) {
-        spinner.fail(chalk.red(`Failed to remove: ${error.message}`));
+        if (!confirm) {
+          console.log(chalk.gray('Cancelled.'));
+          return;
+        }
+      }
+
+      const spinner = ora('Removing credentials...').start();
+
+      const failed: string[] = [];
+      for (const cred of targets) {
+        try {
+          await credentials.delete(cred.type, cred.name);
+        } catch (error: any) {
+          failed.push(`${cred.type}:${cred.name} (${error.message})`);
+        }
+      }
+
+      if (failed.length > 0) {
+        spinner.fail(chalk.red(`Failed to remove: ${failed.join(', ')}`));
+        process.exitCode = 1;
+      } else {
+        spinner.succeed(chalk.green(`Removed ${labels.join(', ')} from ${credentials.getStorageBackend()}`));
       }
     });
 
@@ -498,6 +540,73 @@ export function authCommand(): Command {
   return cmd;
 }
 
+/**
+ * Whether a stored credential belongs to a service given on the command line:
+ * its type (anthropic_api), provider (anthropic), platform (github) or name
+ */
+function matchesCredential(cred: BaseCredential, service: string): boolean {
+  const target = service.toLowerCase();
+  return (
+    cred.type === target ||
+    cred.type.split('_')[0] === target ||
+    cred.metadata?.platform === target ||
+    cred.name === service
+  );
+}
+
+/**
+ * Credential storage section of `cv auth status`
+ */
+async function showStorageStatus(): Promise<void> {
+  console.log(chalk.bold('\n🔑 Credential Storage\n'));
+
+  const credentials = new CredentialManager();
+  let info: CredentialStorageInfo;
+  try {
+    info = await credentials.getStorageInfo();
+  } catch (error: any) {
+    console.log(chalk.red(`  ✗ Credential storage unavailable: ${error.message}`));
+    console.log();
+    return;
+  }
+
+  const backends: Record<string, string> = {
+    [StorageBackend.KEYCHAIN]: 'OS keychain',
+    [StorageBackend.ENCRYPTED_FILE]: 'Encrypted file (AES-256-GCM)',
+    [StorageBackend.PLAIN_FILE]: 'Plaintext file',
+  };
+  const label = backends[info.backend] || info.backend;
+  const detail = info.location ? chalk.gray(` — ${info.location}`) : '';
+
+  if (info.backend === StorageBackend.PLAIN_FILE) {
+    console.log(chalk.yellow(`  ⚠ Backend: ${label}`) + detail);
+  } else {
+    console.log(chalk.green(`  ✓ Backend: ${label}`) + detail);
+  }
+  if (info.fallbackReason) {
+    console.log(chalk.gray(`    ${info.fallbackReason}`));
+  }
+  if (info.backend === StorageBackend.ENCRYPTED_FILE) {
+    console.log(chalk.gray(process.env.CV_CREDENTIALS_PASSPHRASE
+      ? '    Encrypted with CV_CREDENTIALS_PASSPHRASE'
+      : '    Encrypted with a generated key; set CV_CREDENTIALS_PASSPHRASE to use a passphrase'));
+  }
+  if (info.migratedFromPlainFile > 0) {
+    console.log(chalk.green(`  ✓ Moved ${info.migratedFromPlainFile} credential(s) out of the old plaintext file`));
+  }
+
+  const stored = await credentials.list();
+  if (stored.length === 0) {
+    console.log(chalk.yellow('  ⚠ No credentials stored'));
+    console.log(chalk.gray('    Run: cv auth setup'));
+  } else {
+    const types = [...new Set(stored.map((cred) => cred.type.split('_')[0]))];
+    console.log(chalk.green(`  ✓ ${stored.length} credential(s): ${types.join(', ')}`));
+  }
+
+  console.log();
+}
+
 /**
  * PAT-paste login flow: prompt for token → validate → write credentials
  */
@@ -649,6 +758,12 @@ async function runSetup(
     case 'openrouter':
       await setupOpenRouter(credentials, autoBrowser);
       return true;
+    case 'gemini':
+      await setupGemini(credentials, autoBrowser);
+      return true;
+    case 'local':
+      await setupLocalEmbeddings();
+      return true;
 
     // DNS providers
     case 'cloudflare':
@@ -705,7 +820,11 @@ async function runTest(service: string, credentials: CredentialManager): Promise
           console.log(chalk.gray('Run: ') + chalk.cyan('cv auth setup gitlab'));
           return;
         }
-        const adapter = new GitLabAdapter(credentials);
+        // Self-hosted instance and TLS settings from `cv auth setup gitlab`
+        const { platform } = await getConfig().load();
+        const adapter = platform.type === GitPlatform.GITLAB
+          ? new GitLabAdapter(credentials, { webUrl: platform.url, apiUrl: platform.api, tls: platform.tls })
+          : new GitLabAdapter(credentials);
         const user = await adapter.validateToken(token);
         spinner.succeed(chalk.green('GitLab authentication valid'));
         console.log(
@@ -792,6 +911,25 @@ async function runTest(service: string, credentials: CredentialManager): Promise
         break;
       }
 
+      case 'gemini': {
+        const key = await credentials.getGeminiKey();
+        if (!key) {
+          spinner.fail(chalk.red('Gemini API key not found'));
+          console.log(chalk.gray('Run: ') + chalk.cyan('cv auth setup gemini'));
+          return;
+        }
+        const response = await fetch('https://generativelanguage.googleapis.com/v1beta/models?pageSize=1', {
+          headers: { 'x-goog-api-key': key },
+        });
+        if (response.ok) {
+          spinner.succeed(chalk.green('Gemini API key valid'));
+          console.log(chalk.gray('  Key: ') + chalk.white(key.substring(0, 12) + '...'));
+        } else {
+          spinner.fail(chalk.red(`Gemini API key rejected (HTTP ${response.status})`));
+        }
+        break;
+      }
+
       case 'cloudflare': {
         spinner.stop();
         await testCloudflare(credentials);
@@ -823,11 +961,17 @@ async function runTest(service: string, credentials: CredentialManager): Promise
         break;
       }
 
+      case 'local': {
+        spinner.stop();
+        await testLocalEmbeddings();
+        break;
+      }
+
       default:
         spinner.fail(chalk.red(`Unknown service: ${service}`));
         console.log(chalk.gray('\nAvailable services:'));
         console.log(chalk.gray('  Git: github, gitlab, bitbucket, cv-hub, controlfab'));
-        console.log(chalk.gray('  AI: anthropic, openai, openrouter'));
+        console.log(chalk.gray('  AI: anthropic, openai, openrouter, gemini, local'));
         console.log(chalk.gray('  DNS: cloudflare'));
         console.log(chalk.gray('  DevOps: aws, digitalocean, digitalocean-spaces'));
         console.log(chalk.gray('  Publish: npm'));
@@ -1160,10 +1304,54 @@ async function setupOpenRouter(credentials: CredentialManager, autoBrowser: bool
   console.log(chalk.green('✅ OpenRouter authentication configured!\n'));
 }
 
+async function setupGemini(credentials: CredentialManager, autoBrowser: boolean = true): Promise<void> {
+  console.log(chalk.bold('──────────────────────────────────────────'));
+  console.log(chalk.bold.cyan('Google Gemini Authentication'));
+  console.log(chalk.bold('──────────────────────────────────────────\n'));
+
+  const url = 'https://aistudio.google.com/app/apikey';
+
+  if (autoBrowser) {
+    console.log(chalk.cyan('Opening browser to get API key...'));
+    await openBrowser(url);
+    console.log();
+  }
+
+  console.log(chalk.gray('URL: ') + chalk.blue(url));
+  console.log(chalk.gray('Create an API key in Google AI Studio (starts with ') + chalk.white('AIza') + chalk.gray(')'));
+  console.log();
+
+  const { apiKey } = await inquirer.prompt([
+    {
+      type: 'password',
+      name: 'apiKey',
+      message: 'Enter your Gemini API key:',
+      validate: (input: string) => {
+        if (!input || !input.trim()) {
+          return 'API key is required';
+        }
+        return true;
+      },
+    },
+  ]);
+
+  await credentials.store<GeminiAPICredential>({
+    type: CredentialType.GEMINI_API,
+    name: 'default',
+    apiKey: apiKey.trim(),
+  });
+
+  console.log(chalk.green('✅ Gemini authentication configured!\n'));
+}
+
 /**
  * Detect GitLab token type by testing various API endpoints
  */
-async function detectGitLabTokenType(token: string): Promise<{
+async function detectGitLabTokenType(
+  token: string,
+  apiUrl: string = 'https://gitlab.com/api/v4',
+  fetch: FetchLike = globalThis.fetch
+): Promise<{
   type: 'personal' | 'group' | 'project' | 'unknown';
   username?: string;
   groupPath?: string;
@@ -1177,7 +1365,7 @@ async function detectGitLabTokenType(token: string): Promise<{
 
   // Try /user endpoint - only works with Personal Access Tokens
   try {
-    const userResponse = await fetch('https://gitlab.com/api/v4/user', { headers });
+    const userResponse = await fetch(`${apiUrl}/user`, { headers });
     if (userResponse.ok) {
       const user = (await userResponse.json()) as { username: string };
 
@@ -1185,7 +1373,7 @@ async function detectGitLabTokenType(token: string): Promise<{
       let scopes: string[] = [];
       try {
         const tokenResponse = await fetch(
-          'https://gitlab.com/api/v4/personal_access_tokens/self',
+          `${apiUrl}/personal_access_tokens/self`,
           { headers }
         );
         if (tokenResponse.ok) {
@@ -1205,7 +1393,7 @@ async function detectGitLabTokenType(token: string): Promise<{
   // If /user fails, try to detect group/project token by checking accessible groups
   try {
     const groupsResponse = await fetch(
-      'https://gitlab.com/api/v4/groups?min_access_level=10&per_page=1',
+      `${apiUrl}/groups?min_access_level=10&per_page=1`,
       { headers }
     );
     if (groupsResponse.ok) {
@@ -1223,7 +1411,7 @@ async function detectGitLabTokenType(token: string): Promise<{
   // Try to detect project token
   try {
     const projectsResponse = await fetch(
-      'https://gitlab.com/api/v4/projects?membership=true&per_page=1',
+      `${apiUrl}/projects?membership=true&per_page=1`,
       { headers }
     );
     if (projectsResponse.ok) {
@@ -1243,6 +1431,86 @@ async function detectGitLabTokenType(token: string): Promise<{
   return { type: 'unknown', scopes: [] };
 }
 
+/**
+ * Ask which GitLab instance to use; self-hosted ones may need TLS settings
+ */
+async function promptGitLabInstance(): Promise<{
+  webUrl: string;
+  apiUrl: string;
+  tls?: TlsOptions;
+  isGitLabCom: boolean;
+}> {
+  const { platform } = await getConfig().load();
+  const current = platform.type === GitPlatform.GITLAB && platform.url ? platform.url : 'https://gitlab.com';
+
+  const { instanceUrl } = await inquirer.prompt([
+    {
+      type: 'input',
+      name: 'instanceUrl',
+      message: 'GitLab instance URL:',
+      default: current,
+      validate: (input: string) => {
+        try {
+          return ['http:', 'https:'].includes(new URL(input).protocol) || 'Use an http(s) URL';
+        } catch {
+          return 'Enter a URL such as https://gitlab.example.com';
+        }
+      },
+    },
+  ]);
+  const webUrl = instanceUrl.trim().replace(/\/+$/, '');
+  const isGitLabCom = new URL(webUrl).hostname === 'gitlab.com';
+  if (isGitLabCom) {
+    return { webUrl, apiUrl: 'https://gitlab.com/api/v4', isGitLabCom };
+  }
+
+  const existing = platform.type === GitPlatform.GITLAB && platform.url === webUrl ? platform.tls : undefined;
+  const answers = await inquirer.prompt([
+    {
+      type: 'input',
+      name: 'caFile',
+      message: 'CA certificate file for this instance (PEM, blank for system CAs):',
+      default: existing?.caFile ?? '',
+      validate: (input: string) => !input.trim() || existsSync(input.trim()) || 'File not found',
+    },
+    {
+      type: 'confirm',
+      name: 'insecure',
+      message: 'Skip TLS certificate verification? (not recommended)',
+      default: existing?.insecure ?? false,
+    },
+  ]);
+  const tls: TlsOptions = {
+    ...(answers.caFile.trim() ? { caFile: answers.caFile.trim() } : {}),
+    ...(answers.insecure ? { insecure: true } : {}),
+  };
+
+  return {
+    webUrl,
+    apiUrl: `${webUrl}/api/v4`,
+    tls: Object.keys(tls).length > 0 ? tls : undefined,
+    isGitLabCom,
+  };
+}
+
+/**
+ * Point the platform config at a self-hosted GitLab instance
+ */
+async function saveGitLabInstance(instance: { webUrl: string; apiUrl: string; tls?: TlsOptions }): Promise<void> {
+  const config = getConfig();
+  const current = await config.load();
+  await config.update({
+    platform: {
+      ...current.platform,
+      type: GitPlatform.GITLAB,
+      url: instance.webUrl,
+      api: instance.apiUrl,
+      tls: instance.tls,
+    },
+  });
+  console.log(chalk.gray(`  Platform set to GitLab at ${instance.webUrl} (~/.cv/config.json)`));
+}
+
 async function setupGitLab(credentials: CredentialManager, autoBrowser: boolean = true): Promise<void> {
   console.log(chalk.bold('──────────────────────────────────────────'));
   console.log(chalk.bold.cyan('GitLab Authentication'));
@@ -1271,6 +1539,8 @@ async function setupGitLab(credentials: CredentialManager, autoBrowser: boolean
     return;
   }
 
+  const instance = await promptGitLabInstance();
+
   console.log(chalk.yellow('GitLab Token Types:\n'));
   console.log(chalk.green('  Personal Access Token (Recommended)'));
   console.log(chalk.gray('    - Full access to all your projects and groups'));
@@ -1289,7 +1559,7 @@ async function setupGitLab(credentials: CredentialManager, autoBrowser: boolean
   console.log();
 
   const url =
-    'https://gitlab.com/-/user_settings/personal_access_tokens?scopes=api,read_user,read_repository,write_repository';
+    `${instance.webUrl}/-/user_settings/personal_access_tokens?scopes=api,read_user,read_repository,write_repository`;
 
   if (autoBrowser) {
     console.log(chalk.cyan('Opening browser to create Personal Access Token...'));
@@ -1313,7 +1583,8 @@ async function setupGitLab(credentials: CredentialManager, autoBrowser: boolean
         if (!input || !input.trim()) {
           return 'Token is required';
         }
-        if (!input.startsWith('glpat-')) {
+        // Self-hosted instances may use custom token prefixes (or none, before 14.5)
+        if (instance.isGitLabCom && !input.startsWith('glpat-')) {
           return 'Invalid GitLab token format (should start with glpat-)';
         }
         return true;
@@ -1324,7 +1595,10 @@ async function setupGitLab(credentials: CredentialManager, autoBrowser: boolean
   const spinner = ora('Detecting token type...').start();
 
   try {
-    const tokenInfo = await detectGitLabTokenType(token);
+    const tokenInfo = await detectGitLabTokenType(token, instance.apiUrl, createFetch(instance.tls));
+    if (!instance.isGitLabCom && tokenInfo.type !== 'unknown') {
+      await saveGitLabInstance(instance);
+    }
 
     if (tokenInfo.type === 'personal') {
       spinner.succeed(chalk.green(`Personal Access Token detected`));
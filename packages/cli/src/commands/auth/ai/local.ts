@@ -0,0 +1,106 @@
+/**
+ * Local Embeddings Setup
+ *
+ * Selects the in-process embedding model so `cv sync` and semantic search
+ * work without an API key or network. Nothing is stored in the credential
+ * store; the model is downloaded once into ~/.cv/models.
+ */
+
+import chalk from 'chalk';
+import inquirer from 'inquirer';
+import ora from 'ora';
+import {
+  configManager,
+  DEFAULT_LOCAL_EMBEDDING_MODEL,
+  LOCAL_EMBEDDING_MODELS,
+  LocalEmbedder,
+  getLocalEmbeddingModel,
+  getLocalModelsDir,
+  isLocalEmbeddingRuntimeAvailable,
+} from '@cv-git/core';
+import { findRepoRoot } from '@cv-git/shared';
+import { getPreferences } from '../../../config.js';
+
+const RUNTIME_INSTALL = 'npm install -g @huggingface/transformers';
+
+/**
+ * Set up local embeddings: pick a model, download it and make it the default
+ */
+export async function setupLocalEmbeddings(): Promise<void> {
+  console.log(chalk.bold('──────────────────────────────────────────'));
+  console.log(chalk.bold.cyan('Local Embeddings'));
+  console.log(chalk.bold('──────────────────────────────────────────\n'));
+
+  if (!(await isLocalEmbeddingRuntimeAvailable())) {
+    console.log(chalk.yellow('The local embedding runtime is not installed.'));
+    console.log(chalk.gray('Install it with: ') + chalk.cyan(RUNTIME_INSTALL));
+    console.log(chalk.gray('Then run: ') + chalk.cyan('cv auth setup local') + '\n');
+    return;
+  }
+
+  const { modelName } = await inquirer.prompt([
+    {
+      type: 'list',
+      name: 'modelName',
+      message: 'Embedding model:',
+      default: DEFAULT_LOCAL_EMBEDDING_MODEL,
+      choices: LOCAL_EMBEDDING_MODELS.map(m => ({
+        name: `${m.name} ${chalk.gray(`(${m.dimension} dims)`)}`,
+        value: m.name,
+      })),
+    },
+  ]);
+
+  const spinner = ora(`Downloading ${modelName} to ${getLocalModelsDir()}...`).start();
+  try {
+    const embedder = new LocalEmbedder(modelName);
+    await embedder.embed('cv-git local embedding check');
+    spinner.succeed(chalk.green(`Model ${modelName} ready`));
+  } catch (error: any) {
+    spinner.fail(chalk.red(`Could not load ${modelName}: ${error.message}`));
+    return;
+  }
+
+  await getPreferences().set('embeddingProvider', 'local');
+
+  const repoRoot = await findRepoRoot();
+  if (repoRoot) {
+    await configManager.load(repoRoot);
+    await configManager.update({
+      embedding: {
+        ...configManager.get().embedding,
+        provider: 'local',
+        model: modelName,
+        dimensions: getLocalEmbeddingModel(modelName)!.dimension,
+      },
+    });
+    console.log(chalk.gray('  Repository embedding provider set to ') + chalk.white('local'));
+    console.log(chalk.gray('  Re-index with: ') + chalk.cyan('cv sync --full'));
+  }
+
+  console.log(chalk.green('✅ Local embeddings configured (no API key required)\n'));
+}
+
+/**
+ * Check that the runtime and a model are usable offline
+ */
+export async function testLocalEmbeddings(modelName: string = DEFAULT_LOCAL_EMBEDDING_MODEL): Promise<void> {
+  if (!(await isLocalEmbeddingRuntimeAvailable())) {
+    console.log(chalk.red('✗ Local embedding runtime not installed'));
+    console.log(chalk.gray('Install it with: ') + chalk.cyan(RUNTIME_INSTALL));
+    process.exitCode = 1;
+    return;
+  }
+
+  const spinner = ora(`Loading ${modelName}...`).start();
+  try {
+    const vector = await new LocalEmbedder(modelName).embed('test');
+    spinner.succeed(chalk.green('Local embeddings working'));
+    console.log(chalk.gray('  Model: ') + chalk.white(modelName));
+    console.log(chalk.gray('  Dimensions: ') + chalk.white(String(vector.length)));
+    console.log(chalk.gray('  Cache: ') + chalk.white(getLocalModelsDir()));
+  } catch (error: any) {
+    spinner.fail(chalk.red(`Local embeddings failed: ${error.message}`));
+    process.exitCode = 1;
+  }
+}
@@ -4,7 +4,7 @@
  * Organizes authentication providers into logical categories:
  * - dns: DNS providers (Cloudflare)
  * - devops: Cloud infrastructure (AWS, DigitalOcean)
- * - ai: AI/LLM services (Anthropic, OpenAI, OpenRouter)
+ * - ai: AI/LLM services (Anthropic, OpenAI, OpenRouter, local embeddings)
  * - git: Git platforms (GitHub, GitLab, Bitbucket)
  */
 
@@ -70,7 +70,7 @@ export const AUTH_CATEGORIES: AuthCategory[] = [
   {
     id: 'ai',
     name: 'AI Services',
-    description: 'AI/LLM providers (Anthropic, OpenAI, OpenRouter)',
+    description: 'AI/LLM providers (Anthropic, OpenAI, OpenRouter, Gemini)',
     providers: [
       {
         id: 'anthropic',
@@ -87,6 +87,16 @@ export const AUTH_CATEGORIES: AuthCategory[] = [
         name: 'OpenRouter',
         description: 'Multi-model AI gateway',
       },
+      {
+        id: 'gemini',
+        name: 'Google Gemini',
+        description: 'Gemini API access',
+      },
+      {
+        id: 'local',
+        name: 'Local Embeddings',
+        description: 'In-process embedding model (no API key, works offline)',
+      },
     ],
   },
   {
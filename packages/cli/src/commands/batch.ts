@@ -0,0 +1,186 @@
+/**
+ * cv batch command
+ * Run an AI operation (docs, review, summarize) across many files with
+ * rate limiting, a token budget, resumable state and an aggregated report
+ */
+
+import { Command } from 'commander';
+import chalk from 'chalk';
+import { promises as fs } from 'fs';
+import * as path from 'path';
+import { glob } from 'glob';
+import {
+  configManager,
+  createAIManager,
+  BatchRunner,
+  BatchTask,
+  formatBatchReport,
+  batchStatePath,
+  AIManager
+} from '@cv-git/core';
+import { findRepoRoot, getCVDir } from '@cv-git/shared';
+import { addGlobalOptions, createOutput } from '../utils/output.js';
+import { getAnthropicApiKey } from '../utils/credentials.js';
+
+const OPERATIONS = ['docs', 'review', 'summarize'] as const;
+type BatchOperation = typeof OPERATIONS[number];
+
+/** Files larger than this are truncated before being sent */
+const MAX_FILE_CHARS = 60_000;
+
+const DEFAULT_IGNORE = ['**/node_modules/**', '**/.git/**', '**/dist/**', '**/build/**', '**/.cv/**'];
+
+export function batchCommand(): Command {
+  const cmd = new Command('batch');
+
+  cmd
+    .description('Run docs/review/summarize across many files with a rate-limited, resumable queue')
+    .argument('<operation>', `Operation to run (${OPERATIONS.join(', ')})`)
+    .argument('<patterns...>', 'File globs relative to the repository root')
+    .option('--name <name>', 'Batch name, used for the resumable state file (default: operation)')
+    .option('--resume', 'Continue a previous batch with the same name, skipping finished files')
+    .option('--concurrency <n>', 'Files processed at once', '2')
+    .option('--rpm <n>', 'Maximum requests started per minute', '30')
+    .option('--token-budget <n>', 'Stop starting new files after roughly this many tokens')
+    .option('--report <file>', 'Write the aggregated markdown report to a file');
+
+  addGlobalOptions(cmd);
+
+  cmd.action(async (operation: string, patterns: string[], options) => {
+    const output = createOutput(options);
+
+    try {
+      if (!OPERATIONS.includes(operation as BatchOperation)) {
+        output.error(`Unknown batch operation "${operation}". Use one of: ${OPERATIONS.join(', ')}`);
+        process.exit(1);
+      }
+
+      const repoRoot = await findRepoRoot();
+      if (!repoRoot) {
+        console.error(chalk.red('Not in a CV-Git repository. Run `cv init` first.'));
+        process.exit(1);
+      }
+
+      const config = await configManager.load(repoRoot);
+      const anthropicApiKey = await getAnthropicApiKey(config.ai.apiKey);
+      if (!anthropicApiKey) {
+        output.error('Anthropic API key not found. Run `cv auth setup anthropic`.');
+        process.exit(1);
+      }
+
+      const files = Array.from(new Set(
+        (await Promise.all(patterns.map(p => glob(p, { cwd: repoRoot, nodir: true, ignore: DEFAULT_IGNORE }))))
+          .flat()
+      )).sort();
+
+      if (files.length === 0) {
+        output.warn('No files matched the given patterns');
+        return;
+      }
+
+      const name = options.name || operation;
+      let statePath: string;
+      try {
+        statePath = batchStatePath(path.join(getCVDir(repoRoot), 'batch'), name);
+      } catch (error: any) {
+        output.error(error.message);
+        process.exit(1);
+      }
+      if (!options.resume) {
+        await fs.rm(statePath, { force: true });
+      }
+
+      const ai = createAIManager({
+        provider: 'anthropic',
+        model: config.ai.model,
+        apiKey: anthropicApiKey,
+        maxTokens: config.ai.maxTokens
+      });
+
+      const runner = new BatchRunner(
+        name,
+        operation,
+        files,
+        createTask(operation as BatchOperation, repoRoot, ai),
+        {
+          statePath,
+          concurrency: parseInt(options.concurrency, 10) || 2,
+          requestsPerMinute: parseInt(options.rpm, 10) || 30,
+          tokenBudget: options.tokenBudget ? parseInt(options.tokenBudget, 10) : undefined,
+          onItemComplete: (result, progress) => {
+            if (options.json || options.quiet) return;
+            const mark = result.status === 'done'
+              ? chalk.green('✓')
+              : result.status === 'failed' ? chalk.red('✗') : chalk.yellow('−');
+            const detail = result.status === 'done' ? chalk.gray(`~${result.tokens} tokens`) : chalk.gray(result.error || '');
+            console.log(`${mark} [${progress.completed}/${progress.total}] ${result.item} ${detail}`);
+          }
+        }
+      );
+
+      if (!options.json && !options.quiet) {
+        console.log(chalk.cyan(`Running ${operation} on ${files.length} file(s)${options.resume ? ' (resuming)' : ''}...`));
+        console.log();
+      }
+
+      const report = await runner.run();
+
+      if (options.report) {
+        await fs.writeFile(path.resolve(options.report), formatBatchReport(report), 'utf-8');
+      }
+
+      if (options.json) {
+        output.json(report);
+      } else if (!options.quiet) {
+        console.log();
+        console.log(chalk.bold('Batch complete'));
+        console.log(chalk.gray(`  ${report.done} done, ${report.failed} failed, ${report.skipped} skipped` +
+          (report.resumed > 0 ? `, ${report.resumed} from previous run` : '')));
+        console.log(chalk.gray(`  ~${report.tokensUsed} tokens, ${(report.durationMs / 1000).toFixed(1)}s`));
+        if (report.budgetExhausted || report.failed > 0) {
+          console.log(chalk.yellow(`  Re-run with --resume${options.name ? ` --name ${name}` : ''} to retry unfinished files`));
+        }
+        if (options.report) {
+          console.log(chalk.gray(`  Report written to ${options.report}`));
+        }
+      }
+
+      if (report.failed > 0) {
+        process.exitCode = 1;
+      }
+    } catch (error: any) {
+      output.error('Batch failed', error);
+      process.exit(1);
+    }
+  });
+
+  return cmd;
+}
+
+function createTask(operation: BatchOperation, repoRoot: string, ai: AIManager): BatchTask {
+  return async (file: string) => {
+    let content = await fs.readFile(path.join(repoRoot, file), 'utf-8');
+    if (content.length > MAX_FILE_CHARS) {
+      content = content.slice(0, MAX_FILE_CHARS) + '\n... (truncated)';
+    }
+
+    const prompt = buildPrompt(operation, file, content);
+    const output = await ai.chat([{ role: 'user', content: prompt, timestamp: Date.now() }]);
+
+    // Rough estimate: ~4 characters per token
+    return { output, tokens: Math.ceil((prompt.length + output.length) / 4) };
+  };
+}
+
+function buildPrompt(operation: BatchOperation, file: string, content: string): string {
+  const source = `File: ${file}\n\n\`\`\`\n${content}\n\`\`\``;
+
+  switch (operation) {
+    case 'docs':
+      return `You are documenting a codebase. Write concise reference documentation in markdown for the public API of this file: purpose, exported symbols, parameters, return values and usage notes.\n\n${source}`;
+    case 'review':
+      return `You are an expert code reviewer. Review this file for bugs, security issues, and maintainability problems. List concrete findings with line references and severity; say "No issues found" if there are none.\n\n${source}`;
+    case 'summarize':
+      return `Summarize what this file does in 3-5 sentences, then list its key responsibilities and dependencies as bullets.\n\n${source}`;
+  }
+}
@@ -0,0 +1,224 @@
+/**
+ * cv blame-explain command
+ * Explain why a block of code is the way it is, from its git history
+ *
+ * Blames the range, loads the message and diff of each commit behind it,
+ * adds related indexed code, and asks the model for an explanation that
+ * cites the commits.
+ */
+
+import { Command } from 'commander';
+import chalk from 'chalk';
+import ora from 'ora';
+import { existsSync } from 'fs';
+import * as path from 'path';
+import {
+  citedCommits,
+  collectBlameHistory,
+  configManager,
+  contextSources,
+  createAIManager,
+  createGitManager,
+  createGraphManager,
+  createVectorManager,
+  loadKeywordIndex,
+  parseLineRange,
+  touchedSymbols,
+  BLAME_SHA_LENGTH,
+  UNCOMMITTED_SHA,
+  loadPromptTemplates
+} from '@cv-git/core';
+import { findRepoRoot } from '@cv-git/shared';
+import { loadCitationFormatter, printSources, sourcesToJson } from '../utils/citations.js';
+import { addGlobalOptions } from '../utils/output.js';
+import { getAnthropicApiKey } from '../utils/credentials.js';
+import { createModelClient, fallbackNotice } from '../utils/model-client.js';
+import { resolveEmbeddingProvider } from '../utils/embedding-provider.js';
+import { vectorStoreOptions } from '../utils/vector-store.js';
+
+export function blameExplainCommand(): Command {
+  const cmd = new Command('blame-explain');
+
+  cmd
+    .description('Explain the history behind a line range (git blame + commits + related code)')
+    .argument('<range>', 'File and lines, e.g. src/compute/engine.rs:120-160')
+    .option('-m, --model <model>', 'Model to use, optionally as provider/model')
+    .option('--max-commits <n>', 'Most commits to include', '8')
+    .option('--no-context', 'Skip related code from the index')
+    .option('--no-stream', 'Disable streaming output');
+
+  addGlobalOptions(cmd);
+
+  cmd.action(async (range: string, options) => {
+    const json = Boolean(options.json);
+    let spinner = ora({ text: 'Initializing...', isSilent: json }).start();
+
+    try {
+      const repoRoot = await findRepoRoot();
+      if (!repoRoot) {
+        spinner.fail(chalk.red('Not in a CV-Git repository'));
+        console.error(chalk.gray('Run `cv init` first'));
+        process.exit(1);
+      }
+
+      const target = parseLineRange(range);
+      // Accept paths relative to the working directory too
+      const absolute = path.resolve(process.cwd(), target.file);
+      target.file = path.relative(repoRoot, existsSync(absolute) ? absolute : path.join(repoRoot, target.file))
+        .split(path.sep).join('/');
+
+      const config = await configManager.load(repoRoot);
+      const chatClient = options.model || config.ai.fallback
+        ? await createModelClient(options.model, {
+            provider: 'anthropic',
+            model: config.ai.model,
+            anthropicApiKey: config.ai.apiKey,
+            fallback: config.ai.fallback
+          })
+        : undefined;
+      const anthropicApiKey = await getAnthropicApiKey(config.ai.apiKey);
+      if (!anthropicApiKey && !chatClient) {
+        spinner.fail(chalk.red('Anthropic API key not found'));
+        console.error(chalk.gray('  cv auth setup anthropic'));
+        process.exit(1);
+      }
+
+      const git = createGitManager(repoRoot);
+      spinner.text = 'Reading blame history...';
+      const history = await collectBlameHistory(git, target, {
+        maxCommits: Math.max(1, parseInt(options.maxCommits, 10) || 8)
+      });
+      if (history.lines.length === 0) {
+        spinner.fail(chalk.red(`No lines to blame in ${target.file}:${target.startLine}-${target.endLine}`));
+        process.exit(1);
+      }
+
+      // Related code: the symbols the range overlaps, searched in the index
+      let vector = undefined;
+      let graph = undefined;
+      let context = undefined;
+      if (options.context) {
+        spinner.text = 'Gathering related code...';
+        const embeddingProvider = await resolveEmbeddingProvider(config).catch(() => undefined);
+        if (embeddingProvider && config.vector) {
+          try {
+            vector = createVectorManager({
+              url: config.vector.url,
+              ...vectorStoreOptions(config, repoRoot),
+              provider: embeddingProvider,
+              collections: config.vector.collections
+            });
+            await vector.connect();
+          } catch {
+            vector = undefined;
+          }
+        }
+        graph = createGraphManager(config.graph.url, config.graph.database);
+        await graph.connect();
+      }
+
+      const ai = createAIManager(
+        {
+          provider: 'anthropic',
+          model: config.ai.model,
+          apiKey: anthropicApiKey ?? '',
+          chatClient,
+          retrieval: config.retrieval
+        },
+        vector,
+        graph,
+        git
+      );
+
+      ai.setPromptTemplates(await loadPromptTemplates(repoRoot, config.repository?.name));
+
+      if (graph) {
+        ai.setKeywordIndex(await loadKeywordIndex(repoRoot));
+        const fileSymbols = await graph.getFileSymbols(target.file).catch(() => []);
+        const symbols = touchedSymbols(fileSymbols, [[target.startLine, target.endLine]]);
+        const query = symbols.length > 0
+          ? symbols.map(s => s.name).join(' ')
+          : history.lines.map(line => line.text.trim()).join(' ').slice(0, 500);
+        context = await ai.gatherContext(query);
+        // The blamed code itself is already in the prompt
+        context.chunks = context.chunks.filter(chunk =>
+          chunk.payload.file !== target.file ||
+          chunk.payload.endLine < target.startLine ||
+          chunk.payload.startLine > target.endLine
+        );
+      }
+      spinner.succeed(chalk.green(
+        `${history.lines.length} lines from ${history.commits.length} commit${history.commits.length === 1 ? '' : 's'}` +
+        (context ? `, ${context.chunks.length} related chunks` : '')
+      ));
+
+      const sources = context ? contextSources(context.chunks) : [];
+      const close = async () => {
+        if (graph) await graph.close();
+        if (vector) await vector.close();
+      };
+
+      if (json) {
+        const explanation = await ai.explainBlame(history, context);
+        console.log(JSON.stringify({
+          file: history.file,
+          startLine: history.startLine,
+          endLine: history.endLine,
+          explanation,
+          commits: history.commits.map(({ diff, ...commit }) => commit),
+          cited: citedCommits(explanation, history),
+          sources: sourcesToJson(sources, repoRoot)
+        }, null, 2));
+        await close();
+        return;
+      }
+
+      console.log();
+      console.log(chalk.bold.cyan(`Commits behind ${history.file}:${history.startLine}-${history.endLine}:`));
+      for (const commit of history.commits) {
+        const ranges = commit.ranges.map(([a, b]) => a === b ? `${a}` : `${a}-${b}`).join(', ');
+        if (commit.sha === UNCOMMITTED_SHA) {
+          console.log(`  ${chalk.yellow('uncommitted')} ${chalk.gray(`lines ${ranges}`)}`);
+          continue;
+        }
+        const date = new Date(commit.date).toISOString().slice(0, 10);
+        console.log(`  ${chalk.yellow(commit.sha.slice(0, BLAME_SHA_LENGTH))} ${commit.summary} ` +
+          chalk.gray(`(${commit.author}, ${date}; lines ${ranges})`));
+      }
+      console.log();
+      console.log(chalk.bold.cyan('Explanation:'));
+      console.log(chalk.gray('─'.repeat(80)));
+      console.log();
+
+      if (options.stream) {
+        await ai.explainBlame(history, context, {
+          onToken: (token) => process.stdout.write(token),
+          onComplete: () => console.log(),
+          onError: (error) => console.error(chalk.red(`\nError: ${error.message}`))
+        });
+      } else {
+        spinner = ora('Asking the model...').start();
+        const explanation = await ai.explainBlame(history, context);
+        spinner.stop();
+        console.log(explanation);
+      }
+      console.log();
+      console.log(chalk.gray('─'.repeat(80)));
+
+      const notice = fallbackNotice(chatClient);
+      if (notice) console.log(chalk.gray(notice));
+      printSources(sources, await loadCitationFormatter(repoRoot));
+      console.log(chalk.gray(`\nInspect a commit with: git show <sha> -- ${history.file}`));
+      await close();
+    } catch (error: any) {
+      spinner.fail(chalk.red('Blame explanation failed'));
+      console.error(chalk.red(`Error: ${error.message}`));
+      if (process.env.CV_DEBUG) {
+        console.error(chalk.gray(error.stack));
+      }
+      process.exit(1);
+    }
+  });
+
+  return cmd;
+}
@@ -1,6 +1,7 @@
 /**
  * CV Cache Command
- * Manage the content-addressed embedding cache and in-memory caches
+ * Manage the content-addressed embedding cache, the summary/explanation
+ * caches and in-memory caches
  */
 
 import { Command } from 'commander';
@@ -12,10 +13,15 @@ import path from 'path';
 import {
   configManager,
   createVectorManager,
-  getGlobalCache
+  createSummaryCache,
+  getGlobalCache,
+  SUMMARY_CACHE_KINDS,
+  SummaryCacheKind,
+  SummaryCacheStats
 } from '@cv-git/core';
 import { findRepoRoot } from '@cv-git/shared';
-import { getEmbeddingCredentials } from '../utils/credentials.js';
+import { resolveEmbeddingProvider } from '../utils/embedding-provider.js';
+import { vectorStoreOptions } from '../utils/vector-store.js';
 
 /**
  * Format bytes to human-readable string
@@ -35,19 +41,44 @@ function formatPercent(ratio: number): string {
   return `${(ratio * 100).toFixed(1)}%`;
 }
 
+const CACHE_KINDS = ['embeddings', ...SUMMARY_CACHE_KINDS, 'all'];
+
+/**
+ * Print summary and explanation cache statistics
+ */
+function printSummaryCacheStats(stats: SummaryCacheStats[]): void {
+  console.log(chalk.bold.cyan('\nSummary Cache Statistics\n'));
+  console.log(chalk.gray('─'.repeat(50)));
+
+  const table = new Table({
+    head: ['Kind', 'Entries', 'Size', 'Hits', 'Prompt versions'].map(h => chalk.bold(h)),
+    chars: { 'mid': '', 'left-mid': '', 'mid-mid': '', 'right-mid': '' }
+  });
+
+  for (const s of stats) {
+    const versions = Object.entries(s.promptVersions)
+      .map(([version, count]) => `${version} (${count})`)
+      .join(', ');
+    table.push([s.kind, s.entries.toLocaleString(), formatBytes(s.sizeBytes), s.hits.toLocaleString(), versions || '-']);
+  }
+
+  console.log(table.toString());
+  console.log(chalk.gray('Entries are keyed by git blob hash and prompt version; edited files miss automatically.\n'));
+}
+
 /**
  * Create the cache command with subcommands
  */
 export function createCacheCommand(): Command {
   const cache = new Command('cache')
-    .description('Manage content-addressed embedding cache');
+    .description('Manage embedding, summary and explanation caches');
 
   // ═══════════════════════════════════════════════════════════════════════════
   // cv cache stats - Show cache statistics
   // ═══════════════════════════════════════════════════════════════════════════
   cache
     .command('stats')
-    .description('Show embedding cache statistics')
+    .description('Show embedding, summary and explanation cache statistics')
     .option('--json', 'Output as JSON')
     .action(async (options) => {
       try {
@@ -58,30 +89,31 @@ export function createCacheCommand(): Command {
         }
 
         const config = await configManager.load(repoRoot);
-        const embeddingCreds = await getEmbeddingCredentials({
-          openRouterKey: config.embedding?.apiKey,
-          openaiKey: config.ai?.apiKey
-        });
-
-        const vector = createVectorManager({
-          url: config.vector.url,
-          openrouterApiKey: embeddingCreds.openrouterApiKey,
-          openaiApiKey: embeddingCreds.openaiApiKey,
-          collections: config.vector.collections,
-          cacheDir: path.join(repoRoot, '.cv', 'embeddings')
-        });
 
-        await vector.connect();
-        const stats = await vector.getCacheStats();
-        await vector.close();
-
-        if (!stats) {
-          console.log(chalk.yellow('Embedding cache is not enabled.'));
-          process.exit(0);
+        let stats = null;
+        try {
+          const vector = createVectorManager({
+            url: config.vector.url,
+            ...vectorStoreOptions(config, repoRoot),
+            provider: await resolveEmbeddingProvider(config),
+            collections: config.vector.collections,
+            cacheDir: path.join(repoRoot, '.cv', 'embeddings')
+          });
+          await vector.connect();
+          stats = await vector.getCacheStats();
+          await vector.close();
+        } catch (error: any) {
+          if (!options.json) {
+            console.log(chalk.yellow(`Embedding cache unavailable: ${error.message}`));
+          }
         }
+        const summaryStats = await createSummaryCache(repoRoot).stats();
 
         if (options.json) {
-          console.log(JSON.stringify(stats, null, 2));
+          console.log(JSON.stringify({ embeddings: stats, summaries: summaryStats }, null, 2));
+        } else if (!stats) {
+          console.log(chalk.yellow('Embedding cache is not enabled.'));
+          printSummaryCacheStats(summaryStats);
         } else {
           console.log(chalk.bold.cyan('\nEmbedding Cache Statistics\n'));
           console.log(chalk.gray('─'.repeat(50)));
@@ -102,6 +134,15 @@ export function createCacheCommand(): Command {
 
           console.log(table.toString());
 
+          // Vectors kept for other models are reused when switching back
+          const otherModels = Object.entries(stats.entriesByModel).filter(([model]) => model !== stats.model);
+          if (otherModels.length > 0) {
+            console.log(chalk.gray('\nAlso cached:'));
+            for (const [model, entries] of otherModels) {
+              console.log(`  ${model}: ${entries.toLocaleString()} entries`);
+            }
+          }
+
           // Cost savings estimate
           const tokensSaved = stats.cacheHits * 500;  // Assume ~500 tokens per chunk
           const costPerToken = 0.00002 / 1000;  // text-embedding-3-small rate
@@ -114,7 +155,7 @@ export function createCacheCommand(): Command {
             console.log(`  Est. cost saved:   ~$${costSaved.toFixed(4)}`);
           }
 
-          console.log('');
+          printSummaryCacheStats(summaryStats);
         }
 
       } catch (error: any) {
@@ -128,7 +169,8 @@ export function createCacheCommand(): Command {
   // ═══════════════════════════════════════════════════════════════════════════
   cache
     .command('clear')
-    .description('Clear the embedding cache')
+    .description('Clear a cache (embeddings by default)')
+    .option('-k, --kind <kind>', `Cache to clear: ${CACHE_KINDS.join(', ')}`, 'embeddings')
     .option('-f, --force', 'Skip confirmation')
     .action(async (options) => {
       try {
@@ -138,6 +180,14 @@ export function createCacheCommand(): Command {
           process.exit(1);
         }
 
+        const kind: string = options.kind;
+        if (!CACHE_KINDS.includes(kind)) {
+          console.error(chalk.red(`Unknown cache kind: ${kind}`));
+          console.error(chalk.gray(`Valid kinds: ${CACHE_KINDS.join(', ')}`));
+          process.exit(1);
+        }
+        const label = kind === 'all' ? 'all caches' : `the ${kind} cache`;
+
         if (!options.force) {
           const readline = await import('readline');
           const rl = readline.createInterface({
@@ -146,7 +196,7 @@ export function createCacheCommand(): Command {
           });
 
           const answer = await new Promise<string>(resolve => {
-            rl.question(chalk.yellow('Are you sure you want to clear the embedding cache? (y/N) '), resolve);
+            rl.question(chalk.yellow(`Are you sure you want to clear ${label}? (y/N) `), resolve);
           });
           rl.close();
 
@@ -156,18 +206,22 @@ export function createCacheCommand(): Command {
           }
         }
 
-        const spinner = ora('Clearing embedding cache...').start();
+        const spinner = ora(`Clearing ${label}...`).start();
 
-        const config = await configManager.load(repoRoot);
-        const embeddingCreds = await getEmbeddingCredentials({
-          openRouterKey: config.embedding?.apiKey,
-          openaiKey: config.ai?.apiKey
-        });
+        if (kind !== 'embeddings') {
+          const removed = await createSummaryCache(repoRoot)
+            .clear(kind === 'all' ? undefined : kind as SummaryCacheKind);
+          if (kind !== 'all') {
+            spinner.succeed(chalk.green(`Cleared ${removed.toLocaleString()} cached ${kind}`));
+            return;
+          }
+        }
 
+        const config = await configManager.load(repoRoot);
         const vector = createVectorManager({
           url: config.vector.url,
-          openrouterApiKey: embeddingCreds.openrouterApiKey,
-          openaiApiKey: embeddingCreds.openaiApiKey,
+          ...vectorStoreOptions(config, repoRoot),
+          provider: await resolveEmbeddingProvider(config),
           collections: config.vector.collections,
           cacheDir: path.join(repoRoot, '.cv', 'embeddings')
         });
@@ -176,7 +230,7 @@ export function createCacheCommand(): Command {
         await vector.clearCache();
         await vector.close();
 
-        spinner.succeed(chalk.green('Embedding cache cleared'));
+        spinner.succeed(chalk.green(kind === 'all' ? 'All caches cleared' : 'Embedding cache cleared'));
 
       } catch (error: any) {
         console.error(chalk.red(`Error: ${error.message}`));
@@ -202,15 +256,10 @@ export function createCacheCommand(): Command {
         }
 
         const config = await configManager.load(repoRoot);
-        const embeddingCreds = await getEmbeddingCredentials({
-          openRouterKey: config.embedding?.apiKey,
-          openaiKey: config.ai?.apiKey
-        });
-
         const vector = createVectorManager({
           url: config.vector.url,
-          openrouterApiKey: embeddingCreds.openrouterApiKey,
-          openaiApiKey: embeddingCreds.openaiApiKey,
+          ...vectorStoreOptions(config, repoRoot),
+          provider: await resolveEmbeddingProvider(config),
           collections: config.vector.collections,
           cacheDir: path.join(repoRoot, '.cv', 'embeddings')
         });
@@ -263,15 +312,10 @@ export function createCacheCommand(): Command {
         }
 
         const config = await configManager.load(repoRoot);
-        const embeddingCreds = await getEmbeddingCredentials({
-          openRouterKey: config.embedding?.apiKey,
-          openaiKey: config.ai?.apiKey
-        });
-
         const vector = createVectorManager({
           url: config.vector.url,
-          openrouterApiKey: embeddingCreds.openrouterApiKey,
-          openaiApiKey: embeddingCreds.openaiApiKey,
+          ...vectorStoreOptions(config, repoRoot),
+          provider: await resolveEmbeddingProvider(config),
           collections: config.vector.collections,
           cacheDir: path.join(repoRoot, '.cv', 'embeddings')
         });
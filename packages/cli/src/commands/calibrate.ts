@@ -0,0 +1,196 @@
+/**
+ * cv calibrate command
+ * Tune retrieval settings (min-score, top-k, re-ranking) from relevance
+ * judgments on sample queries in this repository
+ */
+
+import { Command } from 'commander';
+import chalk from 'chalk';
+import inquirer from 'inquirer';
+import {
+  configManager,
+  createVectorManager,
+  calibrateRetrieval,
+  loadContextUsage,
+  DEFAULT_RETRIEVAL_SETTINGS,
+  QueryJudgment,
+  VectorManager
+} from '@cv-git/core';
+import { findRepoRoot, CVConfig, VectorSearchResult, CodeChunkPayload } from '@cv-git/shared';
+import { addGlobalOptions, createOutput } from '../utils/output.js';
+import { loadCitationFormatter } from '../utils/citations.js';
+import { resolveEmbeddingProvider } from '../utils/embedding-provider.js';
+import { vectorStoreOptions } from '../utils/vector-store.js';
+
+type Chunk = VectorSearchResult<CodeChunkPayload>;
+
+/**
+ * Connect to the index with the repository's embedding provider
+ */
+async function connectVector(config: CVConfig, repoRoot: string): Promise<VectorManager> {
+  const vector = createVectorManager({
+    url: config.vector.url,
+    ...vectorStoreOptions(config, repoRoot),
+    provider: await resolveEmbeddingProvider(config),
+    collections: config.vector.collections
+  });
+  await vector.connect();
+  return vector;
+}
+
+/**
+ * Suggest queries from docstrings of indexed chunks
+ */
+async function suggestQueries(vector: VectorManager, count: number): Promise<string[]> {
+  const { points } = await vector.scroll(vector.getCollectionNames().codeChunks, 200);
+  const docstrings = points
+    .map(p => (p.payload.docstring || p.payload.summary) as string | undefined)
+    .filter((d): d is string => !!d && d.length > 20)
+    .map(d => d.split(/(?<=\.)\s/)[0].replace(/\s+/g, ' ').trim().slice(0, 100));
+
+  const unique = [...new Set(docstrings)];
+  const picked: string[] = [];
+  while (picked.length < count && unique.length > 0) {
+    picked.push(unique.splice(Math.floor(Math.random() * unique.length), 1)[0]);
+  }
+  return picked;
+}
+
+function toJudgment(query: string, chunks: Chunk[], relevantIds: Set<string>): QueryJudgment {
+  return {
+    query,
+    results: chunks.map(c => ({ score: c.score, relevant: relevantIds.has(c.id) }))
+  };
+}
+
+export function calibrateCommand(): Command {
+  const cmd = new Command('calibrate');
+
+  cmd
+    .description('Tune min-score, top-k and re-ranking by judging results for sample queries')
+    .option('-q, --query <query...>', 'Sample queries to judge (default: prompt, with suggestions from the index)')
+    .option('-n, --samples <number>', 'Number of sample queries', '5')
+    .option('-c, --candidates <number>', 'Results to judge per query', '8')
+    .option('--dry-run', 'Show the proposed settings without saving them')
+    .option('-y, --yes', 'Save without confirmation');
+
+  addGlobalOptions(cmd);
+
+  cmd.action(async (options) => {
+    const output = createOutput(options);
+    let vector: VectorManager | undefined;
+
+    try {
+      const repoRoot = await findRepoRoot();
+      if (!repoRoot) {
+        console.error(chalk.red('Not in a CV-Git repository'));
+        console.error(chalk.gray('Run `cv init` first'));
+        process.exit(1);
+      }
+
+      const config = await configManager.load(repoRoot);
+      const spinner = output.spinner('Connecting to vector database...').start();
+      vector = await connectVector(config, repoRoot);
+      spinner.succeed('Connected to vector database');
+
+      const samples = parseInt(options.samples, 10);
+      const candidates = parseInt(options.candidates, 10);
+      const cite = await loadCitationFormatter(repoRoot);
+      const usage = await loadContextUsage(repoRoot);
+
+      let queries: string[] = options.query || [];
+      if (queries.length === 0) {
+        const suggestions = await suggestQueries(vector, samples);
+        console.log(chalk.gray('\nEnter questions you would ask about this codebase (leave blank to stop).\n'));
+        for (let i = 0; i < samples; i++) {
+          const { query } = await inquirer.prompt([{
+            type: 'input',
+            name: 'query',
+            message: `Query ${i + 1}:`,
+            default: suggestions[i]
+          }]);
+          if (!query.trim()) break;
+          queries.push(query.trim());
+        }
+      }
+
+      if (queries.length === 0) {
+        console.log(chalk.yellow('No queries to judge.'));
+        return;
+      }
+
+      const judgments: QueryJudgment[] = [];
+      const reranked: QueryJudgment[] = [];
+
+      for (const query of queries) {
+        const chunks = await vector.searchCode(query, candidates, { minScore: 0 });
+        if (chunks.length === 0) {
+          console.log(chalk.yellow(`No results for "${query}"`));
+          continue;
+        }
+
+        console.log();
+        const { relevant } = await inquirer.prompt([{
+          type: 'checkbox',
+          name: 'relevant',
+          message: `Which results help answer ${chalk.cyan(`"${query}"`)}?`,
+          pageSize: candidates,
+          choices: chunks.map(c => ({
+            name: `${chalk.gray(c.score.toFixed(3))}  ${cite({ file: c.payload.file, line: c.payload.startLine, endLine: c.payload.endLine })}` +
+              (c.payload.symbolName ? chalk.gray(`  ${c.payload.symbolName}`) : ''),
+            value: c.id
+          }))
+        }]);
+
+        const relevantIds = new Set<string>(relevant);
+        judgments.push(toJudgment(query, chunks, relevantIds));
+        reranked.push(toJudgment(query, usage.rerank(chunks, chunks.length), relevantIds));
+      }
+
+      const result = calibrateRetrieval(judgments, reranked);
+      if (!result) {
+        console.log(chalk.yellow('\nNo results were marked relevant; keeping current settings.'));
+        return;
+      }
+
+      const current = { ...DEFAULT_RETRIEVAL_SETTINGS, ...config.retrieval };
+      console.log(chalk.bold('\nProposed retrieval settings:\n'));
+      console.log(`  min-score  ${chalk.gray(current.minScore.toFixed(2))} → ${chalk.green(result.minScore.toFixed(2))}`);
+      console.log(`  top-k      ${chalk.gray(String(current.topK))} → ${chalk.green(String(result.topK))}`);
+      console.log(`  rerank     ${chalk.gray(String(current.rerank))} → ${chalk.green(String(result.rerank))}`);
+      console.log(chalk.gray(
+        `\n  ${result.relevant} of ${result.judged} results relevant; ` +
+        `precision ${(result.precision * 100).toFixed(0)}%, recall ${(result.recall * 100).toFixed(0)}% at this min-score\n`
+      ));
+
+      if (options.dryRun) return;
+
+      if (!options.yes) {
+        const { save } = await inquirer.prompt([{
+          type: 'confirm',
+          name: 'save',
+          message: 'Save to .cv/config.json?',
+          default: true
+        }]);
+        if (!save) return;
+      }
+
+      await configManager.update({
+        retrieval: {
+          minScore: result.minScore,
+          topK: result.topK,
+          rerank: result.rerank,
+          calibratedAt: new Date().toISOString()
+        }
+      });
+      console.log(chalk.green('✓ Retrieval settings saved'));
+    } catch (error: any) {
+      console.error(chalk.red(`Calibration failed: ${error.message}`));
+      process.exit(1);
+    } finally {
+      if (vector) await vector.close();
+    }
+  });
+
+  return cmd;
+}
@@ -11,25 +11,52 @@ import {
   configManager,
   createVectorManager,
   createGraphManager,
-  createOpenRouterClient,
+  AIClient,
   OPENROUTER_MODELS,
   OpenRouterMessage,
   VectorManager,
   GraphManager,
+  ChatSessionStore,
+  ChatSessionMessage,
+  createChatSessionStore,
+  toModelContent,
+  planChatHistory,
+  buildChatSummaryPrompt,
+  chatHistoryMessages,
 } from '@cv-git/core';
 import { findRepoRoot } from '@cv-git/shared';
-import { CredentialManager } from '@cv-git/credentials';
 import { addGlobalOptions, createOutput } from '../utils/output.js';
+import { resolveEmbeddingProvider } from '../utils/embedding-provider.js';
+import { vectorStoreOptions } from '../utils/vector-store.js';
+import { ensureFreshIndex } from '../utils/watch-daemon.js';
+import { createModelClient, fallbackNotice } from '../utils/model-client.js';
 
 interface ChatOptions {
   model?: string;
   noContext?: boolean;
   contextLimit?: string;
+  session?: string;
+  resume?: string;
+  continue?: boolean;
+  list?: boolean;
+  save?: boolean;
+  stream?: boolean;
   verbose?: boolean;
   quiet?: boolean;
   json?: boolean;
 }
 
+/**
+ * A persisted session this chat is attached to. Other clients (editors via
+ * MCP) may append to the same session; `seen` tracks how far we've caught up.
+ */
+interface SessionBinding {
+  store: ChatSessionStore;
+  id: string;
+  seen: number;
+  own: Set<number>;
+}
+
 // System prompt for code-aware chat
 const SYSTEM_PROMPT = `You are an expert software engineer assistant with access to a codebase knowledge graph.
 
@@ -47,9 +74,15 @@ export function chatCommand(): Command {
   cmd
     .description('Interactive AI chat with codebase context')
     .argument('[question]', 'One-shot question (omit for interactive mode)')
-    .option('-m, --model <model>', 'Model to use (e.g., claude-sonnet-4-5, gpt-4o, llama-3.1-70b)')
+    .option('-m, --model <model>', 'Model to use: an OpenRouter model (claude-sonnet-4-5, gpt-4o) or provider/model (anthropic/claude-sonnet-4-5, gemini/gemini-2.5-pro, ollama/qwen2.5-coder:14b)')
     .option('--no-context', 'Disable automatic context injection')
-    .option('-c, --context-limit <n>', 'Max code chunks to include', '5');
+    .option('-c, --context-limit <n>', 'Max code chunks to include', '5')
+    .option('-s, --session <id>', 'Continue a saved session (id, unique prefix, or "latest")')
+    .option('-r, --resume <id>', 'Same as --session')
+    .option('--continue', 'Continue the most recent session')
+    .option('--list', 'List saved sessions')
+    .option('--no-save', 'Do not persist this conversation')
+    .option('--no-stream', 'Print each response when it is complete instead of token by token');
 
   addGlobalOptions(cmd);
 
@@ -64,39 +97,19 @@ export function chatCommand(): Command {
         process.exit(1);
       }
 
-      // Load configuration
-      const config = await configManager.load(repoRoot);
-
-      // Get API keys
-      let openrouterApiKey = process.env.OPENROUTER_API_KEY;
-      let openaiApiKey = process.env.OPENAI_API_KEY;
-
-      try {
-        const credentials = new CredentialManager();
-        await credentials.init();
-
-        if (!openrouterApiKey) {
-          openrouterApiKey = await credentials.getOpenRouterKey() || undefined;
-        }
-        if (!openaiApiKey) {
-          openaiApiKey = await credentials.getOpenAIKey() || undefined;
-        }
-      } catch {
-        // Credential manager not available
+      if (options.list) {
+        await printSessions(repoRoot, Boolean(options.json));
+        return;
       }
 
-      if (!openrouterApiKey) {
-        console.error(chalk.red('OpenRouter API key not found.'));
-        console.error(chalk.gray('Run: cv auth setup openrouter'));
-        console.error(chalk.gray('Or set: export OPENROUTER_API_KEY=sk-or-...'));
-        process.exit(1);
-      }
+      // Load configuration
+      const config = await configManager.load(repoRoot);
 
-      // Initialize OpenRouter client
-      const model = options.model || 'claude-sonnet-4-5';
-      const client = createOpenRouterClient({
-        apiKey: openrouterApiKey,
-        model,
+      // Bare model names go to OpenRouter; `anthropic/...`, `gemini/...`, `ollama/...` pick a provider
+      const client = await createModelClient(options.model, {
+        provider: 'openrouter',
+        model: 'claude-sonnet-4-5',
+        fallback: config.ai.fallback,
       });
 
       // Initialize vector manager for context (if available)
@@ -104,15 +117,13 @@ export function chatCommand(): Command {
       let graph: GraphManager | null = null;
 
       if (options.noContext !== true) {
-        const embeddingKey = openrouterApiKey || openaiApiKey;
-        if (embeddingKey && config.vector) {
+        if (config.vector) {
           try {
             vector = createVectorManager({
               url: config.vector.url,
-              openrouterApiKey: openrouterApiKey,
-              openaiApiKey: openaiApiKey,
-              collections: config.vector.collections,
-              embeddingModel: config.embedding?.model
+              ...vectorStoreOptions(config, repoRoot),
+              provider: await resolveEmbeddingProvider(config),
+              collections: config.vector.collections
             });
             await vector.connect();
           } catch (e) {
@@ -130,9 +141,34 @@ export function chatCommand(): Command {
         }
       }
 
+      // Attach to a persisted session shared with editor clients
+      const resumeId = options.resume ?? options.session;
+      let resumed = false;
+      let binding: SessionBinding | null = null;
+      if (options.save !== false) {
+        const store = createChatSessionStore(repoRoot);
+        let session = resumeId
+          ? await store.get(resumeId)
+          : options.continue ? await store.get('latest') : null;
+        if (!session && resumeId) {
+          console.error(chalk.red(`Chat session not found: ${resumeId}`));
+          console.error(chalk.gray('Run: cv chat --list'));
+          process.exit(1);
+        }
+        resumed = !!session;
+        session = session ?? await store.create({ model: client.getModel() });
+        binding = { store, id: session.id, seen: 0, own: new Set() };
+      } else if (resumeId || options.continue) {
+        console.error(chalk.red('--session, --resume and --continue cannot be combined with --no-save'));
+        process.exit(1);
+      }
+
       // Show startup info
       console.log();
-      console.log(chalk.bold.cyan('cv chat') + chalk.gray(` - using ${client.getModel()}`));
+      console.log(chalk.bold.cyan('cv chat') + chalk.gray(` - using ${client.getProvider()}/${client.getModel()}`));
+      if (binding) {
+        console.log(chalk.gray(`  Session ${binding.id}` + (resumed ? ' (resumed)' : '')));
+      }
       if (vector) {
         console.log(chalk.green('✓') + chalk.gray(' Knowledge graph context enabled'));
       } else {
@@ -142,13 +178,14 @@ export function chatCommand(): Command {
 
       // One-shot mode
       if (question) {
-        await handleSingleQuestion(question, client, vector, graph, parseInt(options.contextLimit || '5', 10));
+        if (vector) await ensureFreshIndex(repoRoot);
+        await handleSingleQuestion(question, client, vector, graph, parseInt(options.contextLimit || '5', 10), binding, options.stream !== false);
         await cleanup(vector, graph);
         return;
       }
 
       // Interactive mode
-      await interactiveChat(client, vector, graph, parseInt(options.contextLimit || '5', 10));
+      await interactiveChat(repoRoot, client, vector, graph, parseInt(options.contextLimit || '5', 10), binding, options.stream !== false);
       await cleanup(vector, graph);
 
     } catch (error: any) {
@@ -160,11 +197,46 @@ export function chatCommand(): Command {
     }
   });
 
+  // List saved sessions
+  cmd
+    .command('sessions')
+    .description('List saved chat sessions (shared with editor clients)')
+    .option('--json', 'Output as JSON')
+    .action(async (opts: { json?: boolean }) => {
+      const repoRoot = await findRepoRoot();
+      if (!repoRoot) {
+        console.error(chalk.red('Not in a CV-Git repository. Run `cv init` first.'));
+        process.exit(1);
+      }
+      await printSessions(repoRoot, Boolean(opts.json));
+    });
+
   // Add subcommand to list available models
   cmd
     .command('models')
     .description('List available models')
-    .action(() => {
+    .option('-p, --provider <provider>', 'List the models a provider serves: anthropic, gemini, openrouter, ollama or lmstudio')
+    .action(async (opts: { provider?: string }) => {
+      if (opts.provider) {
+        try {
+          const client = await createModelClient(opts.provider, { provider: 'openrouter' });
+          const models = client.listModels ? await client.listModels() : [];
+          if (models.length === 0) {
+            console.log(chalk.yellow(`No models reported by ${client.getProvider()}`));
+            return;
+          }
+          console.log(chalk.bold(`\n${client.getProvider()} models:\n`));
+          for (const model of models.sort()) {
+            console.log(chalk.white(`  ${client.getProvider()}/${model}`));
+          }
+          console.log();
+        } catch (error: any) {
+          console.error(chalk.red(`Error: ${error.message}`));
+          process.exit(1);
+        }
+        return;
+      }
+
       console.log(chalk.bold('\nAvailable Models:\n'));
 
       const categories: Record<string, string[]> = {
@@ -187,22 +259,52 @@ export function chatCommand(): Command {
 
       console.log(chalk.gray('Use with: cv chat -m <model>'));
       console.log(chalk.gray('Example: cv chat -m gpt-4o "explain the auth flow"'));
+      console.log(chalk.gray('Other providers: cv chat -m anthropic/claude-sonnet-4-5, cv chat -m gemini/gemini-2.5-pro'));
+      console.log(chalk.gray('List a provider\'s models: cv chat models --provider gemini'));
       console.log();
     });
 
   return cmd;
 }
 
+/**
+ * List saved sessions, most recent first
+ */
+async function printSessions(repoRoot: string, json: boolean): Promise<void> {
+  const sessions = await createChatSessionStore(repoRoot).list();
+  if (json) {
+    console.log(JSON.stringify(sessions, null, 2));
+    return;
+  }
+  if (sessions.length === 0) {
+    console.log(chalk.gray('No saved chat sessions.'));
+    return;
+  }
+
+  console.log(chalk.bold('\nChat Sessions:\n'));
+  for (const s of sessions) {
+    const when = new Date(s.updatedAt).toLocaleString();
+    const via = s.lastClient ? ` via ${s.lastClient}` : '';
+    console.log(`  ${chalk.cyan(s.id)}  ${s.title || chalk.gray('(empty)')}`);
+    console.log(chalk.gray(`    ${s.messageCount} message(s), updated ${when}${via}`));
+  }
+  console.log(chalk.gray('\nResume with: cv chat --resume <id>, or cv chat --continue for the latest\n'));
+}
+
 /**
  * Handle a single question (one-shot mode)
  */
 async function handleSingleQuestion(
   question: string,
-  client: ReturnType<typeof createOpenRouterClient>,
+  client: AIClient,
   vector: VectorManager | null,
   graph: GraphManager | null,
-  contextLimit: number
+  contextLimit: number,
+  binding: SessionBinding | null,
+  stream: boolean
 ): Promise<void> {
+  const history = binding ? await loadHistory(binding, client) : [];
+
   // Gather context
   let context = '';
   if (vector) {
@@ -216,27 +318,125 @@ async function handleSingleQuestion(
     ? `<codebase_context>\n${context}\n</codebase_context>\n\n${question}`
     : question;
 
-  // Stream response
-  process.stdout.write(chalk.cyan('Assistant: '));
+  const response = await respond(client, [...history, { role: 'user', content: userMessage }], stream);
+  console.log('\n');
+
+  if (binding) {
+    await saveTurn(binding, question, context, response, client.getModel());
+  }
+}
 
-  await client.chatStream(
-    [{ role: 'user', content: userMessage }],
-    SYSTEM_PROMPT,
-    {
+/**
+ * Print the assistant's reply, token by token when streaming
+ */
+async function respond(
+  client: AIClient,
+  messages: OpenRouterMessage[],
+  stream: boolean
+): Promise<string> {
+  let response: string;
+  if (!stream) {
+    const spinner = ora('Thinking...').start();
+    try {
+      response = await client.chat(messages, SYSTEM_PROMPT);
+      spinner.stop();
+      process.stdout.write(chalk.cyan('Assistant: ') + response);
+    } catch (error) {
+      spinner.stop();
+      throw error;
+    }
+  } else {
+    process.stdout.write(chalk.cyan('Assistant: '));
+    response = await client.chatStream(messages, SYSTEM_PROMPT, {
       onToken: (token) => process.stdout.write(token),
-      onComplete: () => console.log('\n'),
+    });
+  }
+
+  const notice = fallbackNotice(client);
+  if (notice) process.stdout.write('\n' + chalk.gray(notice));
+  return response;
+}
+
+/**
+ * Pull messages appended since we last looked, skipping the ones we wrote
+ */
+async function syncSession(binding: SessionBinding): Promise<ChatSessionMessage[]> {
+  const incoming = await binding.store.messagesSince(binding.id, binding.seen);
+  for (const message of incoming) {
+    binding.seen = Math.max(binding.seen, message.revision);
+  }
+  return incoming.filter(m => !binding.own.has(m.revision));
+}
+
+/**
+ * Persist a question/answer pair to the shared session
+ */
+async function saveTurn(
+  binding: SessionBinding,
+  question: string,
+  context: string,
+  response: string,
+  model: string
+): Promise<void> {
+  try {
+    const session = await binding.store.append(binding.id, [
+      { role: 'user', content: question, context: context || undefined, client: 'cli' },
+      { role: 'assistant', content: response, client: 'cli' },
+    ], { model });
+    binding.own.add(session.revision);
+  } catch (error: any) {
+    console.error(chalk.yellow(`Could not save to session ${binding.id}: ${error.message}`));
+  }
+}
+
+/**
+ * Earlier turns of a resumed session. When they are long, the older ones
+ * are sent as a summary, which is stored with the session and extended
+ * only when more turns need folding in.
+ */
+async function loadHistory(
+  binding: SessionBinding,
+  client: AIClient
+): Promise<OpenRouterMessage[]> {
+  const plan = planChatHistory(await syncSession(binding));
+  if (plan.summarize.length === 0) return chatHistoryMessages(plan);
+
+  const through = plan.summarize[plan.summarize.length - 1].revision;
+  let summary = (await binding.store.get(binding.id))?.summary;
+  if (!summary || summary.throughRevision < through) {
+    const spinner = ora('Summarizing earlier conversation...').start();
+    try {
+      const covered = summary?.throughRevision ?? 0;
+      const text = await client.chat([{
+        role: 'user',
+        content: buildChatSummaryPrompt(plan.summarize.filter(m => m.revision > covered), summary?.text)
+      }]);
+      summary = { text: text.trim(), throughRevision: through };
+      await binding.store.setSummary(binding.id, summary.text, through);
+      spinner.stop();
+    } catch (error: any) {
+      spinner.warn(chalk.yellow(`Could not summarize earlier messages, continuing with the latest ones: ${error.message}`));
+      return chatHistoryMessages({ summarize: [], recent: plan.recent });
     }
-  );
+  }
+  return chatHistoryMessages(plan, summary.text);
+}
+
+function toOpenRouterMessage(message: ChatSessionMessage): OpenRouterMessage {
+  return { role: message.role, content: toModelContent(message) };
 }
 
 /**
  * Interactive chat mode
  */
 async function interactiveChat(
-  client: ReturnType<typeof createOpenRouterClient>,
+  repoRoot: string,
+  client: AIClient,
   vector: VectorManager | null,
   graph: GraphManager | null,
-  contextLimit: number
+  contextLimit: number,
+  binding: SessionBinding | null,
+  stream: boolean
 ): Promise<void> {
   const rl = readline.createInterface({
     input: process.stdin,
@@ -244,6 +444,13 @@ async function interactiveChat(
   });
 
   const messages: OpenRouterMessage[] = [];
+  if (binding) {
+    const history = await loadHistory(binding, client);
+    messages.push(...history);
+    if (history.length > 0) {
+      console.log(chalk.gray(`Loaded ${history.length} earlier message(s) from this session.`));
+    }
+  }
 
   console.log(chalk.gray('Type your questions. Commands: /help, /clear, /model <name>, /quit\n'));
 
@@ -258,7 +465,7 @@ async function interactiveChat(
 
       // Handle commands
       if (trimmed.startsWith('/')) {
-        await handleCommand(trimmed, client, messages, rl);
+        await handleCommand(trimmed, client, messages, rl, binding);
         if (trimmed === '/quit' || trimmed === '/exit') {
           return;
         }
@@ -266,10 +473,21 @@ async function interactiveChat(
         return;
       }
 
+      // Pick up anything an editor client added to the session meanwhile
+      if (binding) {
+        for (const message of await syncSession(binding)) {
+          const speaker = message.role === 'user' ? 'You' : 'Assistant';
+          console.log(chalk.gray(`[${message.client}] ${speaker}: `) + message.content + '\n');
+          messages.push(toOpenRouterMessage(message));
+        }
+      }
+
       // Gather context for this message
       let context = '';
       if (vector) {
         const spinner = ora('Searching...').start();
+        // Edits saved since the last question are synced by a running `cv watch`
+        await ensureFreshIndex(repoRoot);
         context = await gatherContext(trimmed, vector, graph, contextLimit);
         spinner.stop();
         // Clear spinner line
@@ -283,20 +501,13 @@ async function interactiveChat(
 
       messages.push({ role: 'user', content: userMessage });
 
-      // Stream response
-      process.stdout.write(chalk.cyan('Assistant: '));
-
       try {
-        const response = await client.chatStream(
-          messages,
-          SYSTEM_PROMPT,
-          {
-            onToken: (token) => process.stdout.write(token),
-          }
-        );
-
+        const response = await respond(client, messages, stream);
         console.log('\n');
         messages.push({ role: 'assistant', content: response });
+        if (binding) {
+          await saveTurn(binding, trimmed, context, response, client.getModel());
+        }
       } catch (error: any) {
         console.log();
         console.error(chalk.red(`Error: ${error.message}`));
@@ -320,9 +531,10 @@ async function interactiveChat(
  */
 async function handleCommand(
   command: string,
-  client: ReturnType<typeof createOpenRouterClient>,
+  client: AIClient,
   messages: OpenRouterMessage[],
-  rl: readline.Interface
+  rl: readline.Interface,
+  binding: SessionBinding | null
 ): Promise<void> {
   const parts = command.split(' ');
   const cmd = parts[0].toLowerCase();
@@ -332,7 +544,8 @@ async function handleCommand(
       console.log(chalk.gray(`
 Commands:
   /help           Show this help
-  /clear          Clear conversation history
+  /clear          Clear conversation history (starts a new session)
+  /session        Show the current session id
   /model <name>   Switch model (e.g., /model gpt-4o)
   /models         List available models
   /quit           Exit chat
@@ -341,7 +554,19 @@ Commands:
 
     case '/clear':
       messages.length = 0;
-      console.log(chalk.gray('Conversation cleared.\n'));
+      if (binding) {
+        const session = await binding.store.create({ model: client.getModel() });
+        Object.assign(binding, { id: session.id, seen: 0, own: new Set<number>() });
+        console.log(chalk.gray(`Conversation cleared. New session ${session.id}\n`));
+      } else {
+        console.log(chalk.gray('Conversation cleared.\n'));
+      }
+      break;
+
+    case '/session':
+      console.log(chalk.gray(binding
+        ? `Session ${binding.id} (continue elsewhere with cv chat --session ${binding.id})\n`
+        : 'This conversation is not being saved (--no-save).\n'));
       break;
 
     case '/model':
@@ -355,8 +580,14 @@ Commands:
 
     case '/models':
       console.log(chalk.gray('\nAvailable models:'));
-      for (const [alias, full] of Object.entries(OPENROUTER_MODELS)) {
-        console.log(chalk.gray(`  ${alias} → ${full}`));
+      if (client.getProvider() === 'openrouter' || !client.listModels) {
+        for (const [alias, full] of Object.entries(OPENROUTER_MODELS)) {
+          console.log(chalk.gray(`  ${alias} → ${full}`));
+        }
+      } else {
+        for (const model of await client.listModels()) {
+          console.log(chalk.gray(`  ${model}`));
+        }
       }
       console.log();
       break;
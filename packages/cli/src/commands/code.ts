@@ -32,12 +32,23 @@ import {
   CodeAssistant,
   CodePhase,
   Edit,
+  EditResult,
+  ConflictResolver,
 } from '@cv-git/core';
 import { findRepoRoot, loadWorkspace, findWorkspaceRoot, CVWorkspace } from '@cv-git/shared';
 import { CredentialManager } from '@cv-git/credentials';
 import { addGlobalOptions, createOutput } from '../utils/output.js';
+import { resolveEmbeddingProvider } from '../utils/embedding-provider.js';
+import { vectorStoreOptions, usesEmbeddedVectorStore } from '../utils/vector-store.js';
 import { ensureInfrastructure, checkSyncState } from '../utils/infrastructure.js';
-import { getEditPromptText, parseEditAction, formatEditSummary, EditAction } from '../utils/prompts.js';
+import {
+  getEditPromptText,
+  parseEditAction,
+  formatEditSummary,
+  EditAction,
+  getConflictPromptText,
+  parseConflictResolution,
+} from '../utils/prompts.js';
 import { divider, labeledDivider, statusLine, colorizeDiff } from '../utils/formatting.js';
 
 interface CodeOptions {
@@ -105,7 +116,6 @@ export function codeCommand(): Command {
 
       // Get API keys
       let openrouterApiKey = process.env.OPENROUTER_API_KEY;
-      let openaiApiKey = process.env.OPENAI_API_KEY;
 
       try {
         const credentials = new CredentialManager();
@@ -114,9 +124,6 @@ export function codeCommand(): Command {
         if (!openrouterApiKey) {
           openrouterApiKey = await credentials.getOpenRouterKey() || undefined;
         }
-        if (!openaiApiKey) {
-          openaiApiKey = await credentials.getOpenAIKey() || undefined;
-        }
       } catch {
         // Credential manager not available
       }
@@ -265,15 +272,13 @@ export function codeCommand(): Command {
         }
       }
 
-      const embeddingKey = openrouterApiKey || openaiApiKey;
-      if (embeddingKey && infra.qdrant.available && infra.qdrant.url) {
+      if ((infra.qdrant.available && infra.qdrant.url) || usesEmbeddedVectorStore(config)) {
         try {
           vector = createVectorManager({
-            url: infra.qdrant.url,
-            openrouterApiKey: openrouterApiKey,
-            openaiApiKey: openaiApiKey,
-            collections: config.vector?.collections || { codeChunks: 'code_chunks', docstrings: 'docstrings', commits: 'commits' },
-            embeddingModel: config.embedding?.model
+            url: infra.qdrant.url || config.vector.url,
+            ...vectorStoreOptions(config, repoRoot),
+            provider: await resolveEmbeddingProvider(config),
+            collections: config.vector?.collections || { codeChunks: 'code_chunks', docstrings: 'docstrings', commits: 'commits' }
           });
           await vector.connect();
         } catch (e) {
@@ -962,6 +967,43 @@ function promptForEditAction(
   });
 }
 
+/**
+ * Resolve merge conflicts hunk by hunk when a file changed after the edit was proposed
+ */
+function createConflictResolver(rl: readline.Interface): ConflictResolver {
+  return async (file, conflict, index, total) => {
+    if (index === 1) {
+      console.log(chalk.yellow(`\n${file} changed since this edit was proposed; ${total} hunk(s) conflict with your changes.`));
+    }
+    console.log(chalk.gray(`\n@@ ${file}:${conflict.oursLine} @@`));
+    console.log(chalk.cyan('<<<<<<< yours'));
+    for (const line of conflict.ours) console.log(chalk.red(`- ${line}`));
+    console.log(chalk.cyan('======='));
+    for (const line of conflict.theirs) console.log(chalk.green(`+ ${line}`));
+    console.log(chalk.cyan('>>>>>>> proposed'));
+
+    for (;;) {
+      const resolution = parseConflictResolution(await question(rl, getConflictPromptText(index, total)));
+      if (resolution !== undefined) return resolution;
+    }
+  };
+}
+
+/**
+ * Print the outcome of applying a single edit
+ */
+function reportApplyResult(edit: Edit, result: EditResult | undefined): boolean {
+  if (!result?.success) {
+    console.log(chalk.red(`✗ Failed: ${result?.error}\n`));
+    return false;
+  }
+  const merged = result.merged
+    ? chalk.gray(` (merged with your changes${result.conflictsResolved ? `, ${result.conflictsResolved} conflict(s) resolved` : ''})`)
+    : '';
+  console.log(chalk.green(`✓ Applied ${edit.file}`) + merged + '\n');
+  return true;
+}
+
 /**
  * Show and optionally apply edits with interactive confirmation
  */
@@ -1017,10 +1059,14 @@ async function showAndApplyEdits(
     const results = await assistant.applyEdits({ autoApprove: true });
     const success = results.filter(r => r.success).length;
     console.log(chalk.green(`✓ Applied ${success} edit(s)`));
+    for (const r of results.filter(r => !r.success)) {
+      console.log(chalk.red(`  - ${r.edit.file}: ${r.error}`));
+    }
     return;
   }
 
   // Interactive mode - prompt for each edit
+  const resolveConflict = createConflictResolver(rl);
   let applied = 0;
   let rejected = 0;
   let skipped = 0;
@@ -1038,13 +1084,8 @@ async function showAndApplyEdits(
     // If applyAll was selected, apply without prompting
     if (applyAll) {
       assistant.approveEdit(edit.id);
-      const results = await assistant.applyEdits({ editIds: [edit.id] });
-      if (results[0]?.success) {
-        console.log(chalk.green(`✓ Applied ${edit.file}`));
-        applied++;
-      } else {
-        console.log(chalk.red(`✗ Failed: ${results[0]?.error}`));
-      }
+      const results = await assistant.applyEdits({ editIds: [edit.id], resolveConflict });
+      if (reportApplyResult(edit, results[0])) applied++;
       continue;
     }
 
@@ -1063,13 +1104,8 @@ async function showAndApplyEdits(
     switch (action) {
       case 'yes':
         assistant.approveEdit(edit.id);
-        const applyResult = await assistant.applyEdits({ editIds: [edit.id] });
-        if (applyResult[0]?.success) {
-          console.log(chalk.green(`✓ Applied ${edit.file}\n`));
-          applied++;
-        } else {
-          console.log(chalk.red(`✗ Failed: ${applyResult[0]?.error}\n`));
-        }
+        const applyResult = await assistant.applyEdits({ editIds: [edit.id], resolveConflict });
+        if (reportApplyResult(edit, applyResult[0])) applied++;
         break;
 
       case 'no':
@@ -1081,13 +1117,8 @@ async function showAndApplyEdits(
       case 'all':
         applyAll = true;
         assistant.approveEdit(edit.id);
-        const allResult = await assistant.applyEdits({ editIds: [edit.id] });
-        if (allResult[0]?.success) {
-          console.log(chalk.green(`✓ Applied ${edit.file}`));
-          applied++;
-        } else {
-          console.log(chalk.red(`✗ Failed: ${allResult[0]?.error}`));
-        }
+        const allResult = await assistant.applyEdits({ editIds: [edit.id], resolveConflict });
+        if (reportApplyResult(edit, allResult[0])) applied++;
         break;
 
       case 'skip':
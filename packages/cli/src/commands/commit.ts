@@ -14,11 +14,14 @@ import * as fs from 'fs';
 import * as path from 'path';
 import * as readline from 'readline';
 import { addGlobalOptions } from '../utils/output.js';
+import { resolveEmbeddingProvider } from '../utils/embedding-provider.js';
+import { vectorStoreOptions } from '../utils/vector-store.js';
 import { CredentialManager, CredentialType, GitPlatform } from '@cv-git/credentials';
 import {
   createCommitAnalyzer,
   CommitAnalysis,
-  GeneratedCommitMessage
+  GeneratedCommitMessage,
+  loadPromptTemplates
 } from '@cv-git/core';
 
 /**
@@ -68,6 +71,7 @@ interface CommitOptions {
   message?: string;
   all?: boolean;
   amend?: boolean;
+  verify?: boolean;
   generate?: boolean;
   dryRun?: boolean;
   type?: string;
@@ -84,6 +88,7 @@ export function commitCommand(): Command {
     .option('-m, --message <message>', 'Commit message')
     .option('-a, --all', 'Automatically stage modified and deleted files')
     .option('--amend', 'Amend the previous commit')
+    .option('--no-verify', 'Bypass the pre-commit and commit-msg hooks')
     .option('-g, --generate', 'Generate commit message using AI + knowledge graph')
     .option('--dry-run', 'Show generated message without committing')
     .option('--type <type>', 'Override commit type (feat, fix, refactor, etc.)')
@@ -220,24 +225,53 @@ async function handleGenerateCommit(
     });
 
     // Import GitManager dynamically
-    const { createGitManager, createGraphManager, configManager } = await import('@cv-git/core');
+    const { createGitManager, createGraphManager, createVectorManager, configManager } = await import('@cv-git/core');
     const git = createGitManager(repoRoot);
 
-    // Try to connect to graph if CV is initialized
+    // Try to connect to graph and vector index if CV is initialized
     let graph: any = undefined;
+    let vector: any = undefined;
     if (cvInitialized) {
-      try {
-        const config = await configManager.load(repoRoot);
-        graph = createGraphManager(config.graph.url, config.graph.database);
-        await graph.connect();
-        if (spinner) spinner.text = 'Analyzing with knowledge graph context...';
-      } catch {
-        // Graph not available, continue without it
+      const config = await configManager.load(repoRoot).catch(() => undefined);
+      if (config) {
+        analyzer.setPromptTemplates(await loadPromptTemplates(repoRoot, config.repository?.name));
+
+        try {
+          graph = createGraphManager(config.graph.url, config.graph.database);
+          await graph.connect();
+          if (spinner) spinner.text = 'Analyzing with knowledge graph context...';
+        } catch {
+          // Graph not available, continue without it
+          graph = undefined;
+        }
+
+        const embeddingProvider = await resolveEmbeddingProvider(config).catch(() => undefined);
+        if (embeddingProvider && config.vector) {
+          try {
+            vector = createVectorManager({
+              url: config.vector.url,
+              ...vectorStoreOptions(config, repoRoot),
+              provider: embeddingProvider,
+              collections: config.vector.collections
+            });
+            await vector.connect();
+          } catch {
+            // Index not available, describe the diff alone
+            vector = undefined;
+          }
+        }
       }
     }
 
-    // Analyze staged changes
-    const analysis = await analyzer.analyzeStaged(git, graph);
+    // Analyze staged changes (plus HEAD's when amending)
+    const analysis = await analyzer.analyzeStaged(git, graph, { amend: options.amend });
+
+    // Pull related code from the index so the message reflects its surroundings
+    if (vector) {
+      if (spinner) spinner.text = 'Retrieving related code...';
+      analysis.relatedCode = await analyzer.gatherRelatedContext(analysis, vector);
+      await vector.close();
+    }
 
     if (spinner) spinner.text = 'Generating commit message...';
 
@@ -313,7 +347,7 @@ async function handleGenerateCommit(
       await commitWithMessage(repoRoot, generated.fullMessage, options, extraArgs);
     } else if (choice === 'e' || choice === 'edit') {
       // Let user edit with default editor
-      const editedMessage = await editMessage(generated.fullMessage);
+      const editedMessage = await editMessage(repoRoot, generated.fullMessage);
       if (editedMessage) {
         await commitWithMessage(repoRoot, editedMessage, options, extraArgs);
       } else {
@@ -352,12 +386,14 @@ async function handleGenerateCommit(
 /**
  * Edit message with system editor
  */
-async function editMessage(message: string): Promise<string | null> {
-  const tmpFile = path.join(process.cwd(), '.cv-commit-msg.tmp');
+async function editMessage(repoRoot: string, message: string): Promise<string | null> {
+  // Keep the draft inside the git dir so `--all` or a later `git add .` can't pick it up
+  const gitDir = execSync('git rev-parse --absolute-git-dir', { cwd: repoRoot, encoding: 'utf-8' }).trim();
+  const tmpFile = path.join(gitDir, 'CV_COMMIT_EDITMSG');
 
   try {
     // Write message to temp file
-    fs.writeFileSync(tmpFile, message);
+    fs.writeFileSync(tmpFile, `${message}\n\n# Edit the generated message. Lines starting with '#' are ignored;\n# an empty message cancels the commit.\n`);
 
     // Get editor
     const editor = process.env.EDITOR || process.env.VISUAL || 'vi';
@@ -368,7 +404,11 @@ async function editMessage(message: string): Promise<string | null> {
     });
 
     // Read edited message
-    const edited = fs.readFileSync(tmpFile, 'utf-8').trim();
+    const edited = fs.readFileSync(tmpFile, 'utf-8')
+      .split('\n')
+      .filter(line => !line.startsWith('#'))
+      .join('\n')
+      .trim();
 
     // Clean up
     fs.unlinkSync(tmpFile);
@@ -480,6 +520,10 @@ async function gitCommit(
       args.push('--amend');
     }
 
+    if (options.verify === false) {
+      args.push('--no-verify');
+    }
+
     if (options.message) {
       args.push('-m', options.message);
     }
@@ -491,6 +535,7 @@ async function gitCommit(
       !arg.startsWith('--all') &&
       !arg.startsWith('-a') &&
       !arg.startsWith('--amend') &&
+      !arg.startsWith('--no-verify') &&
       !arg.startsWith('--generate') &&
       !arg.startsWith('-g') &&
       !arg.startsWith('--dry-run') &&
@@ -0,0 +1,132 @@
+/**
+ * cv complexity command
+ * Rank functions by cyclomatic and cognitive complexity
+ *
+ * Both metrics are computed from the syntax tree during `cv sync`; indexes
+ * built before cognitive complexity was recorded need `cv sync --force`.
+ */
+
+import { Command } from 'commander';
+import chalk from 'chalk';
+import Table from 'cli-table3';
+import ora from 'ora';
+import {
+  complexityReport,
+  COMPLEXITY_SORT_FIELDS,
+  ComplexitySortField,
+  configManager,
+  createGraphManager,
+  formatComplexityCsv,
+  generateRepoId
+} from '@cv-git/core';
+import { findRepoRoot } from '@cv-git/shared';
+import { loadCitationFormatter } from '../utils/citations.js';
+import {
+  addGlobalOptions,
+  addJsonlOption,
+  createJsonReporter,
+  createOutput,
+  ErrorCode,
+  ExitCode
+} from '../utils/output.js';
+
+const FORMATS = ['text', 'json', 'csv'];
+
+export function complexityCommand(): Command {
+  const cmd = new Command('complexity')
+    .description('Rank functions by cyclomatic and cognitive complexity')
+    .option('--top <n>', 'Number of functions to show', '20')
+    .option('--sort <field>', `Sort by ${COMPLEXITY_SORT_FIELDS.join(', ')}`, 'cognitive')
+    .option('--format <format>', `Output format (${FORMATS.join(', ')})`, 'text')
+    .option('--file <path>', 'Only functions under this path')
+    .option('--min <n>', 'Only functions scoring at least this on the sort field');
+
+  addGlobalOptions(cmd);
+  addJsonlOption(cmd);
+
+  cmd.action(async (options) => {
+    const report = createJsonReporter('complexity', options.format === 'json' ? { ...options, json: true } : options);
+    const output = createOutput(options);
+    const format = report ? 'json' : options.format;
+    if (!FORMATS.includes(format)) {
+      output.error(`Unknown format "${options.format}". Use one of: ${FORMATS.join(', ')}`);
+      process.exit(ExitCode.Error);
+    }
+    if (!COMPLEXITY_SORT_FIELDS.includes(options.sort)) {
+      const message = `Unknown sort field "${options.sort}". Use one of: ${COMPLEXITY_SORT_FIELDS.join(', ')}`;
+      report?.fail(message, ErrorCode.INVALID_INPUT);
+      output.error(message);
+      process.exit(ExitCode.Error);
+    }
+
+    const repoRoot = await findRepoRoot();
+    if (!repoRoot) {
+      report?.fail('Not in a CV-Git repository. Run `cv init` first.', ErrorCode.NOT_INITIALIZED);
+      output.error('Not in a CV-Git repository. Run `cv init` first.');
+      process.exit(ExitCode.Error);
+    }
+
+    const spinner = format === 'text' ? ora('Loading function complexity...').start() : null;
+    try {
+      const config = await configManager.load(repoRoot);
+      const repoId = config.repository.repoId || generateRepoId(repoRoot);
+      const graph = createGraphManager({ url: config.graph.url, repoId });
+      await graph.connect();
+
+      const sort = options.sort as ComplexitySortField;
+      const entries = await complexityReport(graph, {
+        sort,
+        top: Math.max(1, parseInt(options.top, 10) || 20),
+        file: options.file,
+        min: options.min !== undefined ? Number(options.min) : undefined
+      });
+      await graph.close();
+      spinner?.stop();
+
+      if (report) {
+        entries.forEach(entry => report.event('function', entry));
+        report.result({ sort, functions: entries });
+        return;
+      }
+      if (format === 'csv') {
+        process.stdout.write(formatComplexityCsv(entries));
+        return;
+      }
+
+      if (entries.length === 0) {
+        console.log(chalk.gray('No functions found. Run `cv sync` to index the repository.'));
+        return;
+      }
+
+      const cite = await loadCitationFormatter(repoRoot);
+      const table = new Table({
+        head: ['Function', 'Cognitive', 'Cyclomatic', 'Lines', 'Location'].map(h => chalk.cyan(h))
+      });
+      const color = (value: number | null, high: number, medium: number) => value === null
+        ? chalk.gray('-')
+        : value >= high ? chalk.red(value) : value >= medium ? chalk.yellow(value) : chalk.green(value);
+      for (const entry of entries) {
+        table.push([
+          entry.name,
+          color(entry.cognitive, 25, 15),
+          color(entry.cyclomatic, 20, 10),
+          entry.lines,
+          chalk.gray(cite({ file: entry.file, line: entry.startLine, endLine: entry.endLine }))
+        ]);
+      }
+      console.log();
+      console.log(chalk.bold.cyan(`Most complex functions (by ${sort}):`));
+      console.log(table.toString());
+      if (entries.some(entry => entry.cognitive === null)) {
+        console.log(chalk.gray('Cognitive complexity is missing for some functions; run `cv sync --force` to compute it.'));
+      }
+    } catch (error: any) {
+      spinner?.fail('Failed to load complexity');
+      report?.fail(error.message, ErrorCode.FALKORDB_ERROR);
+      output.error(error.message, error);
+      process.exit(ExitCode.Error);
+    }
+  });
+
+  return cmd;
+}
@@ -6,9 +6,15 @@
 import { Command } from 'commander';
 import chalk from 'chalk';
 import { getConfig } from '../config.js';
+import { validateCitationFormat } from '../utils/citations.js';
 import { spawn } from 'child_process';
 import Table from 'cli-table3';
+import { findRepoRoot } from '@cv-git/shared';
 import {
+  configManager,
+  flattenConfig,
+  ConfigSource,
+  REPO_CONFIG_FILE,
   loadCVGitConfig,
   saveCVGitConfig,
   detectPrivilegeMode,
@@ -75,6 +81,14 @@ export function configCommand(): Command {
           else if (!isNaN(Number(value)) && value !== '') parsedValue = Number(value);
         }
 
+        if (key === 'citations.format') {
+          const problem = validateCitationFormat(String(parsedValue));
+          if (problem) {
+            console.error(chalk.red('✗'), problem);
+            process.exit(1);
+          }
+        }
+
         await config.setNested(key, parsedValue);
         console.log(chalk.green('✓'), `Set ${chalk.cyan(key)} = ${formatValue(parsedValue)}`);
       } catch (error: any) {
@@ -141,6 +155,57 @@ export function configCommand(): Command {
       }
     });
 
+  // cv config show
+  cmd
+    .command('show')
+    .description(`Show repository settings from ${REPO_CONFIG_FILE} and .cv/config.json`)
+    .option('--resolved', 'Show every effective setting, including defaults, with where it comes from')
+    .option('--json', 'Output as JSON')
+    .action(async (options) => {
+      try {
+        const repoRoot = await findRepoRoot();
+        if (!repoRoot) {
+          console.error(chalk.red('✗ Not in a CV-Git repository. Run `cv init` first.'));
+          process.exit(1);
+        }
+
+        await configManager.load(repoRoot);
+        const { config, sources } = configManager.getResolved();
+        const settings = Object.entries(flattenConfig(config))
+          .filter(([key]) => options.resolved || sources[key] !== 'default')
+          .map(([key, value]) => ({ key, value: isSecretKey(key) ? maskSecret(value) : value, source: sources[key] }));
+
+        if (options.json) {
+          console.log(JSON.stringify(
+            Object.fromEntries(settings.map(({ key, value, source }) => [key, { value, source: SOURCE_LABELS[source] }])),
+            null,
+            2
+          ));
+          return;
+        }
+
+        if (settings.length === 0) {
+          console.log(chalk.gray(`All settings are defaults. Add team settings to ${REPO_CONFIG_FILE}; see --resolved for the full list.`));
+          return;
+        }
+
+        let section = '';
+        for (const { key, value, source } of settings) {
+          const [top, ...rest] = key.split('.');
+          if (top !== section) {
+            section = top;
+            console.log(chalk.bold.cyan(`\n${top}:`));
+          }
+          const shown = Array.isArray(value) ? chalk.gray(JSON.stringify(value)) : formatValue(value);
+          console.log(`  ${rest.join('.') || top} = ${shown} ${SOURCE_COLORS[source](`(${SOURCE_LABELS[source]})`)}`);
+        }
+        console.log();
+      } catch (error: any) {
+        console.error(chalk.red('✗ Error loading config:'), error.message);
+        process.exit(1);
+      }
+    });
+
   // cv config reset
   cmd
     .command('reset')
@@ -361,6 +426,27 @@ function formatValue(value: any): string {
   return chalk.white(value);
 }
 
+const SOURCE_LABELS: Record<ConfigSource, string> = {
+  default: 'default',
+  repo: REPO_CONFIG_FILE,
+  local: '.cv/config.json',
+};
+
+const SOURCE_COLORS: Record<ConfigSource, (text: string) => string> = {
+  default: chalk.gray,
+  repo: chalk.cyan,
+  local: chalk.yellow,
+};
+
+function isSecretKey(key: string): boolean {
+  return /(apiKey|token|password|secret)$/i.test(key);
+}
+
+function maskSecret(value: unknown): string {
+  const text = String(value ?? '');
+  return text.length > 8 ? `${'*'.repeat(8)}${text.slice(-4)}` : '*'.repeat(text.length);
+}
+
 /**
  * Print key-value pair
  */
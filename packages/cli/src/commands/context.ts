@@ -16,13 +16,14 @@ import {
   configManager,
   createVectorManager,
   createGraphManager,
+  loadScoreDistribution,
+  resolveMinScore,
 } from '@cv-git/core';
 import { findRepoRoot, VectorSearchResult, CodeChunkPayload, SymbolNode } from '@cv-git/shared';
 import { PRDClient } from '@cv-git/prd-client';
 import { addGlobalOptions, createOutput } from '../utils/output.js';
-import { getEmbeddingCredentials } from '../utils/credentials.js';
-import { getPreferences } from '../config.js';
-import { ensureOllama } from '../utils/infrastructure.js';
+import { resolveEmbeddingProvider } from '../utils/embedding-provider.js';
+import { vectorStoreOptions } from '../utils/vector-store.js';
 
 interface ContextOptions {
   limit: string;
@@ -35,7 +36,7 @@ interface ContextOptions {
   includeTests: boolean;
   includeDocs: boolean;
   prdUrl: string;
-  minScore: string;
+  minScore?: string;
 }
 
 export function contextCommand(): Command {
@@ -54,7 +55,7 @@ export function contextCommand(): Command {
     .option('--include-tests', 'Include test cases for PRD requirements (requires --prd)')
     .option('--include-docs', 'Include documentation for PRD requirements (requires --prd)')
     .option('--prd-url <url>', 'cv-prd API URL', 'http://localhost:8000')
-    .option('--min-score <score>', 'Minimum similarity score (0-1)', '0.5');
+    .option('--min-score <score>', 'Minimum similarity score (0-1; default: retrieval.minScore, else adapted to the embedding model at sync)');
 
   addGlobalOptions(cmd);
 
@@ -78,58 +79,11 @@ export function contextCommand(): Command {
 
       const config = await configManager.load(repoRoot);
 
-      // Load user preferences to determine embedding provider
-      const prefsManager = getPreferences();
-      const prefs = await prefsManager.load();
-      const embeddingProvider = config.embedding?.provider || prefs.embeddingProvider || 'ollama';
-
-      // Get embedding credentials based on provider
-      let ollamaUrl: string | undefined;
-      let lmstudioUrl: string | undefined;
-      let openrouterApiKey: string | undefined;
-      let openaiApiKey: string | undefined;
-
-      if (embeddingProvider === 'ollama') {
-        log('Checking Ollama...');
-        const ollamaInfo = await ensureOllama({ silent: true });
-        if (ollamaInfo) {
-          ollamaUrl = ollamaInfo.url;
-          log(`Using Ollama at ${ollamaUrl}...`);
-        } else {
-          log('Ollama not available, checking cloud providers...');
-          const embeddingCreds = await getEmbeddingCredentials({
-            openRouterKey: config.embedding?.apiKey,
-            openaiKey: config.ai?.apiKey
-          });
-          openrouterApiKey = embeddingCreds.openrouterApiKey;
-          openaiApiKey = embeddingCreds.openaiApiKey;
-        }
-      } else if (embeddingProvider === 'lmstudio') {
-        log('Checking LM Studio...');
-        const { isLMStudioRunning, getLMStudioUrl } = await import('@cv-git/core');
-        const lmUrl = getLMStudioUrl();
-        if (await isLMStudioRunning(lmUrl)) {
-          lmstudioUrl = lmUrl;
-          log(`Using LM Studio at ${lmUrl}...`);
-        } else {
-          log('LM Studio not available, checking cloud providers...');
-          const embeddingCreds = await getEmbeddingCredentials({
-            openRouterKey: config.embedding?.apiKey,
-            openaiKey: config.ai?.apiKey
-          });
-          openrouterApiKey = embeddingCreds.openrouterApiKey;
-          openaiApiKey = embeddingCreds.openaiApiKey;
-        }
-      } else {
-        const embeddingCreds = await getEmbeddingCredentials({
-          openRouterKey: config.embedding?.apiKey,
-          openaiKey: config.ai?.apiKey
-        });
-        openrouterApiKey = embeddingCreds.openrouterApiKey;
-        openaiApiKey = embeddingCreds.openaiApiKey;
-      }
-
-      if (!ollamaUrl && !lmstudioUrl && !openrouterApiKey && !openaiApiKey) {
+      // Resolve the embedding provider the index was built with
+      let provider;
+      try {
+        provider = await resolveEmbeddingProvider(config, { onStatus: message => log(`${message}...`) });
+      } catch {
         if (spinner) spinner.fail(chalk.red('No embedding provider available'));
         else console.error('Error: Run `cv ai setup` or ensure Ollama/LM Studio is running');
         process.exit(1);
@@ -137,15 +91,11 @@ export function contextCommand(): Command {
 
       // Initialize managers
       log('Connecting to vector database...');
-      const useLocal = !!(ollamaUrl || lmstudioUrl);
       const vector = createVectorManager({
         url: config.vector.url,
-        ollamaUrl,
-        lmstudioUrl,
-        openrouterApiKey: useLocal ? undefined : openrouterApiKey,
-        openaiApiKey: useLocal ? undefined : openaiApiKey,
-        collections: config.vector.collections,
-        vectorSize: (embeddingProvider === 'ollama' || embeddingProvider === 'lmstudio') ? 768 : 1536
+        ...vectorStoreOptions(config, repoRoot),
+        provider,
+        collections: config.vector.collections
       });
       await vector.connect();
 
@@ -164,7 +114,13 @@ export function contextCommand(): Command {
       // Gather context
       log('Searching for relevant code...');
       const limit = parseInt(options.limit, 10);
-      const minScore = parseFloat(options.minScore);
+      const minScore = options.minScore !== undefined
+        ? parseFloat(options.minScore)
+        : resolveMinScore(
+            config.retrieval,
+            await loadScoreDistribution(repoRoot, vector.getEmbeddingInfo().model),
+            0.5
+          );
       const depth = parseInt(options.depth, 10);
 
       const chunks = await vector.searchCode(query, limit, { minScore });
@@ -0,0 +1,115 @@
+/**
+ * cv deadcode command
+ * List functions, methods and types nothing in the repository references
+ *
+ * Uses the call graph from `cv sync` and a scan of the sources for other
+ * references (callbacks, re-exports). Intentional entry points go in
+ * `deadcode.allow` in `.cvgit.toml`, or `--allow` for a single run.
+ */
+
+import { Command } from 'commander';
+import chalk from 'chalk';
+import ora from 'ora';
+import { promises as fs } from 'fs';
+import * as path from 'path';
+import {
+  configManager,
+  createGraphManager,
+  DEAD_CODE_CONFIDENCE,
+  DeadCodeConfidence,
+  detectDeadCode,
+  generateRepoId
+} from '@cv-git/core';
+import { findRepoRoot } from '@cv-git/shared';
+import { loadCitationFormatter } from '../utils/citations.js';
+import { addGlobalOptions, createOutput } from '../utils/output.js';
+
+const CONFIDENCE_COLOR: Record<DeadCodeConfidence, (text: string) => string> = {
+  high: chalk.red,
+  medium: chalk.yellow,
+  low: chalk.gray
+};
+
+export function deadcodeCommand(): Command {
+  const cmd = new Command('deadcode')
+    .description('Find functions and types nothing references')
+    .option('--confidence <level>', `Lowest confidence to report (${DEAD_CODE_CONFIDENCE.join(', ')})`, 'medium')
+    .option('--file <path>', 'Only check symbols under this path')
+    .option('--allow <pattern...>', 'Also skip these names or file globs')
+    .option('--limit <n>', 'Maximum symbols to list', '100')
+    .option('--check', 'Exit with status 1 when dead code is found');
+
+  addGlobalOptions(cmd);
+
+  cmd.action(async (options) => {
+    const output = createOutput(options);
+    const level = options.confidence as DeadCodeConfidence;
+    if (!DEAD_CODE_CONFIDENCE.includes(level)) {
+      output.error(`Unknown confidence "${options.confidence}". Use one of: ${DEAD_CODE_CONFIDENCE.join(', ')}`);
+      process.exit(1);
+    }
+
+    const repoRoot = await findRepoRoot();
+    if (!repoRoot) {
+      output.error('Not in a CV-Git repository. Run `cv init` first.');
+      process.exit(1);
+    }
+
+    const spinner = output.isJson ? null : ora('Checking symbol references...').start();
+    let found = 0;
+    try {
+      const config = await configManager.load(repoRoot);
+      const repoId = config.repository.repoId || generateRepoId(repoRoot);
+      const graph = createGraphManager({ url: config.graph.url, repoId });
+      await graph.connect();
+
+      const report = await detectDeadCode(graph, {
+        allow: [...(config.deadcode?.allow ?? []), ...(options.allow ?? [])],
+        file: options.file,
+        readFile: file => fs.readFile(path.join(repoRoot, file), 'utf-8').catch(() => null)
+      });
+      await graph.close();
+      spinner?.stop();
+
+      const maxRank = DEAD_CODE_CONFIDENCE.indexOf(level);
+      const dead = report.dead.filter(symbol => DEAD_CODE_CONFIDENCE.indexOf(symbol.confidence) <= maxRank);
+      const limit = Math.max(1, parseInt(options.limit, 10) || 100);
+      found = dead.length;
+
+      if (output.isJson) {
+        output.json({ scanned: report.scanned, allowed: report.allowed, dead: dead.slice(0, limit) });
+      } else if (dead.length === 0) {
+        console.log(chalk.green(`✓ No unreferenced symbols at ${level} confidence or above (${report.scanned} checked)`));
+      } else {
+        const cite = await loadCitationFormatter(repoRoot);
+        console.log();
+        console.log(chalk.bold.cyan(`Likely dead code (${dead.length}):`));
+        for (const confidence of DEAD_CODE_CONFIDENCE.slice(0, maxRank + 1)) {
+          const group = dead.slice(0, limit).filter(symbol => symbol.confidence === confidence);
+          if (group.length === 0) continue;
+          console.log();
+          console.log(CONFIDENCE_COLOR[confidence](`  ${confidence} confidence`));
+          for (const symbol of group) {
+            console.log(`    ${chalk.white(symbol.name)} ${chalk.gray(`(${symbol.kind}) - ${symbol.reason}`)}`);
+            console.log(chalk.gray(`      ${cite({ file: symbol.file, line: symbol.startLine, endLine: symbol.endLine })}`));
+          }
+        }
+        if (dead.length > limit) {
+          console.log(chalk.gray(`\n  ... and ${dead.length - limit} more (use --limit)`));
+        }
+        console.log();
+        console.log(chalk.gray(`${report.scanned} symbols checked` +
+          (report.allowed > 0 ? `, ${report.allowed} skipped by the allowlist` : '')));
+        console.log(chalk.gray('Keep intentional entry points with `deadcode.allow` in .cvgit.toml'));
+      }
+    } catch (error: any) {
+      spinner?.fail('Dead code check failed');
+      output.error(error.message, error);
+      process.exit(1);
+    }
+
+    if (options.check && found > 0) process.exit(1);
+  });
+
+  return cmd;
+}
@@ -0,0 +1,176 @@
+/**
+ * cv diff-summary command
+ * Summarize a branch or commit range for review or a PR description
+ *
+ * Changed files are grouped by package (or directory) and each group is
+ * summarized by the model; API/behavior changes and risky files are found
+ * from the diff itself, so they are listed even when the model is vague.
+ */
+
+import { Command } from 'commander';
+import chalk from 'chalk';
+import ora from 'ora';
+import {
+  behaviorFiles,
+  clusterChanges,
+  configManager,
+  createAIManager,
+  createGitManager,
+  detectApiChanges,
+  formatDiffSummaryMarkdown,
+  loadPackages,
+  parseCommitRange,
+  rankRiskyFiles,
+  DiffSummary,
+  loadPromptTemplates
+} from '@cv-git/core';
+import { findRepoRoot } from '@cv-git/shared';
+import { addGlobalOptions, createOutput } from '../utils/output.js';
+import { getAnthropicApiKey } from '../utils/credentials.js';
+import { createModelClient, fallbackNotice } from '../utils/model-client.js';
+
+const FORMATS = ['text', 'markdown', 'json'];
+
+export function diffSummaryCommand(): Command {
+  const cmd = new Command('diff-summary')
+    .description('Summarize the changes in a branch or commit range, grouped by area')
+    .argument('<range>', 'Commit range, e.g. main..feature (a single ref means ref..HEAD)')
+    .option('-m, --model <model>', 'Model to use, optionally as provider/model')
+    .option('--format <format>', `Output format (${FORMATS.join(', ')})`, 'text')
+    .option('--max-areas <n>', 'Most areas to summarize separately; the rest are grouped as "other"', '12')
+    .option('--risky <n>', 'Number of risky files to list', '10');
+
+  addGlobalOptions(cmd);
+
+  cmd.action(async (range: string, options) => {
+    const output = createOutput(options);
+    const format = output.isJson ? 'json' : options.format;
+    if (!FORMATS.includes(format)) {
+      output.error(`Unknown format "${options.format}". Use one of: ${FORMATS.join(', ')}`);
+      process.exit(1);
+    }
+
+    const repoRoot = await findRepoRoot();
+    if (!repoRoot) {
+      output.error('Not in a CV-Git repository. Run `cv init` first.');
+      process.exit(1);
+    }
+
+    // Markdown and JSON go to stdout for piping; progress goes to stderr
+    const spinner = ora({ text: 'Reading commits...', stream: process.stderr, isSilent: format === 'json' }).start();
+    try {
+      const config = await configManager.load(repoRoot);
+      const chatClient = options.model || config.ai.fallback
+        ? await createModelClient(options.model, {
+            provider: 'anthropic',
+            model: config.ai.model,
+            anthropicApiKey: config.ai.apiKey,
+            fallback: config.ai.fallback
+          })
+        : undefined;
+      const anthropicApiKey = await getAnthropicApiKey(config.ai.apiKey);
+      if (!anthropicApiKey && !chatClient) {
+        spinner.fail(chalk.red('Anthropic API key not found'));
+        console.error(chalk.gray('  cv auth setup anthropic'));
+        process.exit(1);
+      }
+
+      const git = createGitManager(repoRoot);
+      const revisions = parseCommitRange(range);
+      const commits = await git.getChurnHistory({ range: revisions.log });
+      const diff = await git.getRawDiff(revisions.diff);
+      if (!diff.trim()) {
+        spinner.info(`No changes in ${range}`);
+        return;
+      }
+
+      const clusters = clusterChanges(diff, commits, await loadPackages(repoRoot), {
+        maxClusters: Math.max(1, parseInt(options.maxAreas, 10) || 12)
+      });
+      const apiChanges = detectApiChanges(diff);
+
+      const ai = createAIManager(
+        {
+          provider: 'anthropic',
+          model: config.ai.model,
+          apiKey: anthropicApiKey ?? '',
+          chatClient
+        },
+        undefined,
+        undefined,
+        git
+      );
+      ai.setPromptTemplates(await loadPromptTemplates(repoRoot, config.repository?.name));
+      const { overview } = await ai.summarizeChanges(range, clusters, apiChanges, (cluster, index) => {
+        spinner.text = `Summarizing ${cluster.area} (${index + 1}/${clusters.length})...`;
+      });
+      spinner.succeed(`${commits.length} commits in ${clusters.length} area${clusters.length === 1 ? '' : 's'}`);
+
+      const summary: DiffSummary = {
+        range,
+        commits: commits.length,
+        overview,
+        clusters,
+        apiChanges,
+        behaviorFiles: behaviorFiles(clusters),
+        risky: rankRiskyFiles(clusters, apiChanges, Math.max(0, parseInt(options.risky, 10) || 10))
+      };
+
+      if (format === 'json') {
+        output.json({
+          ...summary,
+          clusters: summary.clusters.map(({ sections, ...cluster }) => cluster)
+        });
+        return;
+      }
+      if (format === 'markdown') {
+        process.stdout.write(formatDiffSummaryMarkdown(summary));
+        return;
+      }
+
+      console.log();
+      console.log(chalk.bold.cyan(`Summary of ${range}`));
+      console.log(summary.overview);
+      for (const cluster of summary.clusters) {
+        console.log();
+        console.log(chalk.bold(cluster.area) + chalk.gray(
+          ` ${cluster.files.length} file${cluster.files.length === 1 ? '' : 's'}, ` +
+          chalk.green(`+${cluster.insertions}`) + ' ' + chalk.red(`-${cluster.deletions}`)
+        ));
+        if (cluster.summary) console.log(cluster.summary);
+      }
+
+      if (summary.apiChanges.length > 0 || summary.behaviorFiles.length > 0) {
+        console.log();
+        console.log(chalk.bold.cyan('API and behavior changes:'));
+        for (const change of summary.apiChanges) {
+          const color = change.kind === 'added' ? chalk.green : change.kind === 'removed' ? chalk.red : chalk.yellow;
+          console.log(`  ${color(change.kind.padEnd(7))} ${change.signature} ${chalk.gray(change.file)}`);
+          if (change.previous) console.log(chalk.gray(`          was ${change.previous}`));
+        }
+        for (const file of summary.behaviorFiles) {
+          console.log(`  ${chalk.yellow('config '.padEnd(7))} ${file}`);
+        }
+      }
+
+      if (summary.risky.length > 0) {
+        console.log();
+        console.log(chalk.bold.cyan('Risky files:'));
+        for (const file of summary.risky) {
+          console.log(`  ${file.file} ${chalk.gray(`(+${file.insertions} -${file.deletions})`)}`);
+          console.log(chalk.gray(`    ${file.reasons.join('; ')}`));
+        }
+      }
+
+      const notice = fallbackNotice(chatClient);
+      if (notice) console.log(chalk.gray(notice));
+      console.log(chalk.gray(`\nFor a PR description: cv diff-summary ${range} --format markdown`));
+    } catch (error: any) {
+      spinner.fail(chalk.red('Diff summary failed'));
+      output.error(error.message, error);
+      process.exit(1);
+    }
+  });
+
+  return cmd;
+}
@@ -7,17 +7,51 @@ import { Command } from 'commander';
 import chalk from 'chalk';
 import ora from 'ora';
 import * as readline from 'readline';
+import * as path from 'path';
 import {
   configManager,
   createAIManager,
   createVectorManager,
   createGraphManager,
-  createGitManager
+  createGitManager,
+  loadContextUsage,
+  loadKeywordIndex,
+  loadScoreDistribution,
+  saveContextUsage,
+  parseExpandDepth,
+  contextSources,
+  createEditParser,
+  createPatchEngine,
+  uncommittedFiles,
+  recordChangeSet,
+  PatchError,
+  AgentRunner,
+  AgentPlan,
+  AgentStep,
+  AIManager,
+  CommandResult,
+  GitManager,
+  StepDecision,
+  StepOutcome,
+  SandboxPolicy,
+  SandboxError,
+  loadSandboxPolicy,
+  isCommandAllowed,
+  runSandboxed,
+  loadPromptTemplates
 } from '@cv-git/core';
 import { findRepoRoot } from '@cv-git/shared';
-import { Plan } from '@cv-git/shared';
-import { addGlobalOptions } from '../utils/output.js';
-import { getAnthropicApiKey, getEmbeddingCredentials } from '../utils/credentials.js';
+import { Plan, Context } from '@cv-git/shared';
+import { addGlobalOptions, addJsonlOption, createJsonReporter, ErrorCode, ExitCode, JsonReporter } from '../utils/output.js';
+import { loadCitationFormatter, printSources, sourcesToJson } from '../utils/citations.js';
+import { checkIndexDrift, DriftCheckResult } from '../utils/drift.js';
+import { getAnthropicApiKey } from '../utils/credentials.js';
+import { createModelClient, fallbackNotice } from '../utils/model-client.js';
+import { resolveEmbeddingProvider } from '../utils/embedding-provider.js';
+import { vectorStoreOptions } from '../utils/vector-store.js';
+import { applyReranker } from '../utils/reranker.js';
+import { applyQueryExpansion } from '../utils/query-expansion.js';
+import { colorizeDiff } from '../utils/formatting.js';
 
 export function doCommand(): Command {
   const cmd = new Command('do');
@@ -25,58 +59,86 @@ export function doCommand(): Command {
   cmd
     .description('Execute a task with AI assistance')
     .argument('<task>', 'Task description in natural language')
+    .option('-m, --model <model>', 'Model to use, optionally as provider/model (anthropic/claude-sonnet-4-5, gemini/gemini-2.5-pro, openrouter/openai/gpt-4o, ollama/qwen2.5-coder:14b)')
     .option('--plan-only', 'Only generate the plan, do not generate code')
+    .option('--plan', 'Agent mode: plan read/edit/run steps, then execute them one at a time with approval')
     .option('--yes', 'Skip approval prompts')
-    .option('--prd <refs>', 'Include PRD context (e.g., PRD-123 or comma-separated list)');
+    .option('--dry-run', 'Show the generated changes as a diff without applying them')
+    .option('--force', 'Apply changes even to files with uncommitted changes')
+    .option('--prd <refs>', 'Include PRD context (e.g., PRD-123 or comma-separated list)')
+    .option('--expand-query <mode>', 'Also search reformulations of the query: off, synonyms or llm (default: retrieval.expansion)')
+    .option('--expand-depth <n>', 'Hops of callers/callees/imports to include around matches (0-5)')
+    .option('--refresh-stale', 'Re-index retrieved files that changed since the last sync')
+    .option('--no-stream', 'Print the generated code when it is complete instead of token by token');
 
   addGlobalOptions(cmd);
+  addJsonlOption(cmd);
 
   cmd.action(async (task: string, options) => {
-      let spinner = ora('Initializing...').start();
+      // JSON goes to stdout; keep human-facing progress off it
+      const report = createJsonReporter('do', options);
+      const json = Boolean(report);
+      // Nobody can answer prompts while stdout carries JSON
+      if (report && !options.planOnly && !options.yes && !options.dryRun) {
+        report.fail('--json and --jsonl cannot ask for approval; add --yes, --dry-run or --plan-only', ErrorCode.INVALID_INPUT);
+      }
+      let spinner = ora({ text: 'Initializing...', isSilent: json }).start();
 
       try {
         // Find repository root
         const repoRoot = await findRepoRoot();
         if (!repoRoot) {
+          report?.fail('Not in a CV-Git repository', ErrorCode.NOT_INITIALIZED);
           spinner.fail(chalk.red('Not in a CV-Git repository'));
           console.error(chalk.gray('Run `cv init` first'));
-          process.exit(1);
+          process.exit(ExitCode.Error);
         }
 
         // Load configuration
         const config = await configManager.load(repoRoot);
 
+        // `--model provider/model` sends prompts to that provider instead of Claude;
+        // a configured fallback chain also routes them through a chat client
+        const chatClient = options.model || config.ai.fallback
+          ? await createModelClient(options.model, {
+              provider: 'anthropic',
+              model: config.ai.model,
+              anthropicApiKey: config.ai.apiKey,
+              fallback: config.ai.fallback
+            })
+          : undefined;
+
         // Check for API keys (CredentialManager -> config -> env var)
         const anthropicApiKey = await getAnthropicApiKey(config.ai.apiKey);
-        if (!anthropicApiKey) {
+        if (!anthropicApiKey && !chatClient) {
+          report?.fail('Anthropic API key not found', ErrorCode.NO_CREDENTIALS);
           spinner.fail(chalk.red('Anthropic API key not found'));
           console.error();
           console.error(chalk.yellow('Set your Anthropic API key:'));
           console.error(chalk.gray('  cv auth setup anthropic'));
           console.error(chalk.gray('  export ANTHROPIC_API_KEY=sk-ant-...'));
-          process.exit(1);
+          process.exit(ExitCode.Error);
         }
 
-        // Get embedding credentials (OpenRouter preferred, fallback to OpenAI)
-        const embeddingCreds = await getEmbeddingCredentials();
+        // Resolve the embedding provider the index was built with
+        const embeddingProvider = await resolveEmbeddingProvider(config).catch(() => undefined);
 
         // Initialize components
         spinner.text = 'Connecting to services...';
 
         // Vector manager (optional)
         let vector = undefined;
-        if ((embeddingCreds.openrouterApiKey || embeddingCreds.openaiApiKey) && config.vector) {
+        if (embeddingProvider && config.vector) {
           try {
             vector = createVectorManager({
               url: config.vector.url,
-              openrouterApiKey: embeddingCreds.openrouterApiKey,
-              openaiApiKey: embeddingCreds.openaiApiKey,
-              collections: config.vector.collections,
-              embeddingModel: config.embedding?.model
+              ...vectorStoreOptions(config, repoRoot),
+              provider: embeddingProvider,
+              collections: config.vector.collections
             });
             await vector.connect();
           } catch (error) {
-            console.log(chalk.gray('  ⚠ Could not connect to vector DB'));
+            console.error(chalk.gray('  ⚠ Could not connect to vector DB'));
           }
         }
 
@@ -92,15 +154,27 @@ export function doCommand(): Command {
           {
             provider: 'anthropic',
             model: config.ai.model,
-            apiKey: anthropicApiKey,
+            apiKey: anthropicApiKey ?? '',
+            chatClient,
             prdUrl: config.cvprd?.url || process.env.CVPRD_URL,
-            prdApiKey: config.cvprd?.apiKey
+            prdApiKey: config.cvprd?.apiKey,
+            retrieval: config.retrieval
           },
           vector,
           graph,
           git
         );
 
+        ai.setPromptTemplates(await loadPromptTemplates(repoRoot, config.repository?.name));
+
+        // Prefer chunk paths/kinds that past answers actually cited
+        const contextUsage = await loadContextUsage(repoRoot);
+        ai.setContextUsage(contextUsage);
+        ai.setKeywordIndex(await loadKeywordIndex(repoRoot));
+        applyReranker(ai, config);
+        applyQueryExpansion(ai, config, 'do', options.expandQuery);
+        ai.setScoreDistribution(await loadScoreDistribution(repoRoot, vector?.getEmbeddingInfo().model));
+
         // Parse PRD refs from option
         const prdRefs = options.prd
           ? options.prd.split(',').map((r: string) => r.trim())
@@ -108,10 +182,22 @@ export function doCommand(): Command {
 
         // Step 1: Gather context
         spinner.text = 'Gathering context...';
-        const context = await ai.gatherContext(task, {
+        const contextOptions = {
           includeGitStatus: true,
-          prdRefs
+          prdRefs,
+          expandDepth: options.expandDepth !== undefined ? parseExpandDepth(options.expandDepth) : undefined
+        };
+        let context = await ai.gatherContext(task, contextOptions);
+
+        // Warn when retrieved code has changed since it was indexed
+        const drift = await checkIndexDrift({
+          repoRoot, context, git, graph, vector,
+          refresh: options.refreshStale,
+          silent: json
         });
+        if (drift.refreshed) {
+          context = await ai.gatherContext(task, contextOptions);
+        }
 
         let contextMsg = `Found ${context.chunks.length} code chunks and ${context.symbols.length} symbols`;
         if (context.prdContext) {
@@ -119,11 +205,35 @@ export function doCommand(): Command {
         }
         spinner.succeed(chalk.green(contextMsg));
 
+        if (options.plan && !options.planOnly) {
+          await runAgentMode(task, context, { ai, git, repoRoot, options, report });
+          await saveContextUsage(repoRoot, contextUsage);
+          await graph.close();
+          if (vector) await vector.close();
+          return;
+        }
+
         // Step 2: Generate plan
-        spinner = ora('Generating plan...').start();
+        spinner = ora({ text: 'Generating plan...', isSilent: json }).start();
         const plan = await ai.generatePlan(task, context);
         spinner.succeed(chalk.green('Plan generated'));
 
+        if (report) {
+          const planJson = buildPlanJson(plan, context, drift, repoRoot);
+          if (options.planOnly) {
+            report.result(planJson);
+            await graph.close();
+            if (vector) await vector.close();
+            return;
+          }
+          report.event('plan', planJson);
+          await runJsonTask(report, task, context, { ai, git, repoRoot, options });
+          await saveContextUsage(repoRoot, contextUsage);
+          await graph.close();
+          if (vector) await vector.close();
+          return;
+        }
+
         // Display plan
         displayPlan(plan);
 
@@ -154,44 +264,110 @@ export function doCommand(): Command {
 
         spinner = ora('Generating code...').start();
 
-        const generatedCode = await ai.generateCode(task, context, {
-          onToken: (token) => {
-            if (!spinner.isSpinning) {
+        let generatedCode: string;
+        if (options.stream) {
+          // Show tokens as they arrive; the spinner only covers the wait for the first one
+          generatedCode = await ai.generateCode(task, context, {
+            onToken: (token) => {
+              if (spinner.isSpinning) {
+                spinner.stop();
+              }
               process.stdout.write(token);
             }
-          },
-          onComplete: () => {
-            if (spinner.isSpinning) {
-              spinner.stop();
-            }
-            console.log();
-            console.log();
-            console.log(chalk.gray('─'.repeat(80)));
+          });
+          if (spinner.isSpinning) {
+            spinner.stop();
           }
-        });
-
-        if (spinner.isSpinning) {
+          console.log();
+        } else {
+          generatedCode = await ai.generateCode(task, context);
           spinner.stop();
           console.log(generatedCode);
-          console.log();
-          console.log(chalk.gray('─'.repeat(80)));
         }
+        console.log();
+        console.log(chalk.gray('─'.repeat(80)));
+        const notice = fallbackNotice(chatClient);
+        if (notice) console.log(chalk.gray(notice));
+
+        printSources(contextSources(context.chunks), await loadCitationFormatter(repoRoot));
 
         console.log();
         console.log(chalk.green('✓ Code generated successfully'));
         console.log();
-        console.log(chalk.bold('Next steps:'));
-        console.log(chalk.gray('  1. Review the generated code above'));
-        console.log(chalk.gray('  2. Apply the changes manually to your files'));
-        console.log(chalk.gray('  3. Test the changes'));
-        console.log(chalk.gray('  4. Commit when ready: git commit -m "..."'));
-        console.log();
+
+        await saveContextUsage(repoRoot, contextUsage);
+
+        // Step 5: Turn the edit blocks into a patch and preview it
+        const edits = createEditParser().parseResponse(generatedCode, 'cv-do');
+        const engine = createPatchEngine(repoRoot);
+        const patch = edits.length > 0 ? await engine.prepare(edits) : { files: [] };
+
+        if (patch.files.length === 0) {
+          console.log(chalk.bold('Next steps:'));
+          console.log(chalk.gray('  1. Review the generated code above'));
+          console.log(chalk.gray('  2. Apply the changes manually to your files'));
+          console.log(chalk.gray('  3. Test the changes'));
+          console.log(chalk.gray('  4. Commit when ready: git commit -m "..."'));
+          console.log();
+        } else {
+          console.log(chalk.bold.cyan(`Patch (${patch.files.length} file${patch.files.length === 1 ? '' : 's'}):`));
+          console.log();
+          for (const file of patch.files) {
+            console.log(colorizeDiff(file.diff.trimEnd()));
+            console.log();
+          }
+
+          if (options.dryRun) {
+            console.log(chalk.gray('(dry run - no files changed)'));
+          } else {
+            // Never overwrite work that isn't committed yet
+            const dirty = uncommittedFiles(patch, await git.getStatus());
+            if (dirty.length > 0 && !options.force) {
+              console.error(chalk.red('Refusing to modify files with uncommitted changes:'));
+              for (const file of dirty) console.error(chalk.gray(`  • ${file}`));
+              console.error(chalk.gray('Commit or stash them first, or pass --force'));
+              await graph.close();
+              if (vector) await vector.close();
+              process.exit(ExitCode.Error);
+            }
+
+            const apply = options.yes || await askForApproval(`Apply changes to ${patch.files.length} file(s)?`);
+            if (apply) {
+              try {
+                await engine.apply(patch);
+                console.log(chalk.green(`✓ Applied changes to ${patch.files.length} file(s)`));
+                for (const file of patch.files) {
+                  const mark = file.before === null ? chalk.green('A') : file.after === null ? chalk.red('D') : chalk.yellow('M');
+                  console.log(`  ${mark} ${file.file}`);
+                }
+
+                try {
+                  const head = await git.getLastCommitSha().catch(() => undefined);
+                  const changeSet = await recordChangeSet(repoRoot, patch.files, { command: 'do', description: task, head });
+                  console.log(chalk.gray(`Undo with: cv undo ${changeSet.id}`));
+                } catch (error: any) {
+                  console.error(chalk.yellow(`⚠ Could not record change set for undo: ${error.message}`));
+                }
+              } catch (error: any) {
+                if (!(error instanceof PatchError)) throw error;
+                console.error(chalk.red(error.message));
+                await graph.close();
+                if (vector) await vector.close();
+                process.exit(ExitCode.Error);
+              }
+            } else {
+              console.log(chalk.yellow('Changes not applied'));
+            }
+          }
+          console.log();
+        }
 
         // Close connections
         await graph.close();
         if (vector) await vector.close();
 
       } catch (error: any) {
+        report?.fail(error.message, error.message.includes('API key') ? ErrorCode.NO_CREDENTIALS : ErrorCode.OPERATION_FAILED);
         if (spinner) {
           spinner.fail(chalk.red('Task execution failed'));
         }
@@ -212,13 +388,286 @@ export function doCommand(): Command {
           console.error(chalk.gray(error.stack));
         }
 
-        process.exit(1);
+        process.exit(ExitCode.Error);
       }
     });
 
   return cmd;
 }
 
+/**
+ * Agent mode: plan explicit steps, then execute them one at a time
+ */
+async function runAgentMode(
+  task: string,
+  context: Context,
+  { ai, git, repoRoot, options, report }: {
+    ai: AIManager;
+    git: GitManager;
+    repoRoot: string;
+    options: any;
+    report: JsonReporter | null;
+  }
+): Promise<void> {
+  const spinner = ora({ text: 'Planning steps...', isSilent: Boolean(report) }).start();
+  const plan = await ai.generateAgentPlan(task, context);
+  spinner.succeed(chalk.green(`Planned ${plan.steps.length} step${plan.steps.length === 1 ? '' : 's'}`));
+  displayAgentPlan(plan);
+
+  if (options.dryRun) {
+    console.log(chalk.gray('(dry run - no steps executed)'));
+    report?.result({ task, steps: plan.steps, completed: false, outcomes: [], logFile: null });
+    return;
+  }
+  report?.event('plan', { task, steps: plan.steps });
+
+  const sandbox = await loadSandboxPolicy(repoRoot);
+  const head = await git.getLastCommitSha().catch(() => undefined);
+  const runner = new AgentRunner({
+    repoRoot,
+    head,
+    generateEdit: async (step, notes) => {
+      const editSpinner = ora(`Generating edits for step ${step.index}...`).start();
+      try {
+        return await ai.generateAgentEdit(plan, step, notes, context);
+      } finally {
+        editSpinner.stop();
+      }
+    },
+    runCommand: command => runAgentCommand(command, repoRoot, sandbox, Boolean(options.yes), report ? process.stderr : process.stdout),
+    approve: (step, patch) => options.yes
+      ? Promise.resolve('approve')
+      : askStepDecision(patch ? `Apply step ${step.index}?` : `Run step ${step.index}?`),
+    uncommitted: options.force
+      ? undefined
+      : async patch => uncommittedFiles(patch, await git.getStatus()),
+    onEvent: event => {
+      if (event.type === 'step-end') report?.event('step', outcomeToJson(event.outcome));
+      if (event.type === 'step-start') {
+        console.log();
+        console.log(chalk.bold(`Step ${event.step.index}/${plan.steps.length} `) + formatAgentStep(event.step));
+      } else if (event.type === 'step-preview') {
+        for (const file of event.patch.files) {
+          console.log(colorizeDiff(file.diff.trimEnd()));
+          console.log();
+        }
+      } else {
+        const { outcome } = event;
+        if (outcome.status === 'done') {
+          const undo = outcome.changeSet ? chalk.gray(` (undo with: cv undo ${outcome.changeSet})`) : '';
+          console.log(chalk.green(`✓ Step ${outcome.step.index} done`) + undo);
+        } else if (outcome.status === 'skipped') {
+          console.log(chalk.yellow(`- Step ${outcome.step.index} skipped`));
+        } else {
+          console.log(chalk.red(`✗ Step ${outcome.step.index} failed: ${outcome.error}`));
+        }
+      }
+    }
+  });
+
+  const result = await runner.run(plan);
+
+  console.log();
+  const done = result.outcomes.filter(o => o.status === 'done').length;
+  const summary = `${done}/${plan.steps.length} steps done`;
+  console.log(result.completed ? chalk.green(`✓ ${summary}`) : chalk.yellow(`⚠ Stopped early: ${summary}`));
+  console.log(chalk.gray(`Audit log: ${path.relative(repoRoot, result.logFile)}`));
+  if (report) {
+    report.result({
+      task,
+      runId: result.runId,
+      steps: plan.steps,
+      completed: result.completed,
+      outcomes: result.outcomes.map(outcomeToJson),
+      logFile: result.logFile
+    }, result.completed ? ExitCode.Success : ExitCode.CheckFailed);
+  } else if (!result.completed) {
+    process.exitCode = ExitCode.CheckFailed;
+  }
+}
+
+function outcomeToJson(outcome: StepOutcome) {
+  return {
+    index: outcome.step.index,
+    kind: outcome.step.kind,
+    status: outcome.status,
+    files: outcome.files ?? [],
+    changeSet: outcome.changeSet ?? null,
+    error: outcome.error ?? null
+  };
+}
+
+/**
+ * `cv do --json/--jsonl`: generate the code and its patch, and apply it with
+ * --yes (a --dry-run only reports it)
+ */
+async function runJsonTask(
+  report: JsonReporter,
+  task: string,
+  context: Context,
+  { ai, git, repoRoot, options }: { ai: AIManager; git: GitManager; repoRoot: string; options: any }
+): Promise<void> {
+  const code = await ai.generateCode(task, context, report.streaming && options.stream
+    ? { onToken: text => report.event('token', { text }) }
+    : undefined);
+
+  const edits = createEditParser().parseResponse(code, 'cv-do');
+  const engine = createPatchEngine(repoRoot);
+  const patch = edits.length > 0 ? await engine.prepare(edits) : { files: [] };
+  const files = patch.files.map(file => ({
+    file: file.file,
+    status: file.before === null ? 'added' : file.after === null ? 'deleted' : 'modified',
+    diff: file.diff
+  }));
+
+  let changeSet: string | null = null;
+  const apply = patch.files.length > 0 && !options.dryRun;
+  if (apply) {
+    const dirty = uncommittedFiles(patch, await git.getStatus());
+    if (dirty.length > 0 && !options.force) {
+      report.fail(`Refusing to modify files with uncommitted changes: ${dirty.join(', ')} (pass --force)`, ErrorCode.UNCOMMITTED_CHANGES);
+    }
+    try {
+      await engine.apply(patch);
+    } catch (error: any) {
+      if (!(error instanceof PatchError)) throw error;
+      report.fail(error.message, ErrorCode.OPERATION_FAILED);
+    }
+    const head = await git.getLastCommitSha().catch(() => undefined);
+    changeSet = (await recordChangeSet(repoRoot, patch.files, { command: 'do', description: task, head }).catch(() => null))?.id ?? null;
+  }
+
+  report.result({
+    task,
+    code,
+    files,
+    applied: apply,
+    changeSet,
+    sources: sourcesToJson(contextSources(context.chunks), repoRoot)
+  });
+}
+
+function formatAgentStep(step: AgentStep): string {
+  const kind = step.kind === 'edit' ? chalk.yellow('[EDIT]') : step.kind === 'run' ? chalk.magenta('[RUN]') : chalk.cyan('[READ]');
+  let text = `${kind} ${step.description}`;
+  if (step.command) text += chalk.gray(`\n     $ ${step.command}`);
+  else if (step.files.length > 0) text += chalk.gray(`\n     ${step.files.join(', ')}`);
+  return text;
+}
+
+function displayAgentPlan(plan: AgentPlan): void {
+  console.log();
+  console.log(chalk.bold.cyan('Agent Plan:'));
+  console.log(chalk.gray('─'.repeat(80)));
+  for (const step of plan.steps) {
+    console.log(`  ${step.index}. ${formatAgentStep(step)}`);
+  }
+  console.log(chalk.gray('─'.repeat(80)));
+}
+
+/**
+ * Run a step's shell command in the sandbox, streaming its output.
+ * Commands outside the repository's allowlist need explicit confirmation.
+ */
+async function runAgentCommand(
+  command: string,
+  repoRoot: string,
+  policy: SandboxPolicy,
+  nonInteractive: boolean,
+  out: NodeJS.WritableStream = process.stdout
+): Promise<CommandResult> {
+  if (!isCommandAllowed(command, policy)) {
+    const hint = 'add it to [sandbox] allow in .cvgit.toml to run it unattended';
+    if (nonInteractive) {
+      console.log(chalk.red(`  Not in the sandbox allowlist; ${hint}`));
+      return { exitCode: 126, output: `Command not in the sandbox allowlist: ${command}` };
+    }
+    console.log(chalk.yellow(`  Not in the sandbox allowlist (${hint})`));
+    if (!await askForApproval(`Run "${command}" anyway?`)) {
+      return { exitCode: 126, output: `Command declined: ${command}` };
+    }
+  }
+
+  try {
+    const result = await runSandboxed(command, {
+      repoRoot,
+      policy,
+      onOutput: chunk => out.write(chalk.gray(chunk))
+    });
+    if (result.timedOut) {
+      console.log(chalk.red(`  Timed out after ${policy.timeoutMs / 1000}s`));
+    }
+    return result;
+  } catch (error: any) {
+    if (!(error instanceof SandboxError)) throw error;
+    console.log(chalk.red(`  ${error.message}`));
+    return { exitCode: 126, output: error.message };
+  }
+}
+
+async function askStepDecision(question: string): Promise<StepDecision> {
+  const rl = readline.createInterface({
+    input: process.stdin,
+    output: process.stdout
+  });
+
+  return new Promise(resolve => {
+    rl.question(chalk.cyan(`${question} [y]es / [n]o, skip / [a]ll / [q]uit: `), answer => {
+      rl.close();
+      const choice = answer.trim().toLowerCase();
+      if (choice === 'y' || choice === 'yes') resolve('approve');
+      else if (choice === 'a' || choice === 'all') resolve('all');
+      else if (choice === 'q' || choice === 'quit') resolve('abort');
+      else resolve('skip');
+    });
+  });
+}
+
+/**
+ * Machine-readable plan for review in PR comments or automation
+ */
+function buildPlanJson(plan: Plan, context: Context, drift: DriftCheckResult, repoRoot: string) {
+  const filesToTouch = new Map<string, Set<string>>();
+  for (const step of plan.steps) {
+    if (!step.file || step.file === 'unknown') continue;
+    if (!filesToTouch.has(step.file)) filesToTouch.set(step.file, new Set());
+    filesToTouch.get(step.file)!.add(step.type);
+  }
+
+  const commands = new Set<string>();
+  for (const step of plan.steps) {
+    step.commands?.forEach(c => commands.add(c));
+  }
+  plan.commands?.forEach(c => commands.add(c));
+
+  const tokens = plan.estimatedTokens;
+
+  return {
+    task: plan.task,
+    estimatedComplexity: plan.estimatedComplexity,
+    steps: plan.steps.map((step, idx) => ({
+      index: idx + 1,
+      type: step.type,
+      file: step.file,
+      description: step.description,
+      details: step.details,
+      commands: step.commands || []
+    })),
+    filesToTouch: Array.from(filesToTouch, ([file, changes]) => ({ file, changes: Array.from(changes) })),
+    commands: Array.from(commands),
+    risks: plan.risks || [],
+    estimatedTokens: tokens
+      ? { ...tokens, total: tokens.prompt + tokens.maxCompletion }
+      : undefined,
+    context: {
+      chunks: context.chunks.length,
+      symbols: context.symbols.length,
+      staleFiles: drift.drifted.map(d => d.file),
+      sources: sourcesToJson(contextSources(context.chunks), repoRoot)
+    }
+  };
+}
+
 /**
  * Display a plan
  */
@@ -244,8 +693,23 @@ function displayPlan(plan: Plan): void {
     if (step.details) {
       console.log(chalk.gray(`     ${step.details}`));
     }
+    step.commands?.forEach(c => console.log(chalk.gray(`     $ ${c}`)));
   });
 
+  if (plan.commands && plan.commands.length > 0) {
+    console.log();
+    console.log(chalk.bold('Commands:'));
+    plan.commands.forEach(c => console.log(chalk.gray(`  $ ${c}`)));
+  }
+
+  if (plan.estimatedTokens) {
+    console.log();
+    console.log(
+      chalk.bold('Estimated tokens:'),
+      chalk.gray(`~${plan.estimatedTokens.prompt} prompt + up to ${plan.estimatedTokens.maxCompletion} completion`)
+    );
+  }
+
   if (plan.risks && plan.risks.length > 0) {
     console.log();
     console.log(chalk.yellow('⚠  Risks:'));
@@ -0,0 +1,213 @@
+/**
+ * cv docgen command
+ * Generate or update doc comments for public functions and types
+ *
+ * Items come from the symbol graph (`cv sync`); the model writes the docs one
+ * file at a time and they are inserted in each language's style (rustdoc,
+ * TSDoc, Javadoc, Go comments, Python docstrings). All changes are shown as
+ * one patch before it is applied, and recorded for `cv undo`.
+ */
+
+import { Command } from 'commander';
+import chalk from 'chalk';
+import ora from 'ora';
+import * as readline from 'readline';
+import { promises as fs } from 'fs';
+import * as path from 'path';
+import {
+  applyDocComments,
+  buildDocgenPrompt,
+  configManager,
+  createGitManager,
+  createGraphManager,
+  createPatchEngine,
+  DOCGEN_SYSTEM_PROMPT,
+  DocTarget,
+  Edit,
+  findDocTargets,
+  generateRepoId,
+  groupDocTargets,
+  parseDocgenResponse,
+  recordChangeSet,
+  uncommittedFiles
+} from '@cv-git/core';
+import { findRepoRoot } from '@cv-git/shared';
+import { createModelClient, fallbackNotice } from '../utils/model-client.js';
+import { colorizeDiff } from '../utils/formatting.js';
+
+export function docgenCommand(): Command {
+  const cmd = new Command('docgen');
+
+  cmd
+    .description('Generate doc comments for public functions and types (rustdoc, TSDoc, docstrings, ...)')
+    .option('--path <path>', 'Only document items in files under this path')
+    .option('--missing-only', 'Skip items that already have a doc comment')
+    .option('--ignore <pattern...>', 'Also skip these names, qualified names or file globs')
+    .option('--limit <n>', 'Maximum items to document in one run', '50')
+    .option('--dry-run', 'Show the patch without applying it')
+    .option('-y, --yes', 'Apply without confirmation')
+    .option('--force', 'Also modify files with uncommitted changes')
+    .option('-m, --model <model>', 'Model: a Claude model or provider/model (e.g. ollama/qwen2.5-coder:14b)');
+
+  cmd.action(async (options) => {
+    const spinner = ora('Finding undocumented items...').start();
+
+    const repoRoot = await findRepoRoot();
+    if (!repoRoot) {
+      spinner.fail(chalk.red('Not in a CV-Git repository'));
+      console.error(chalk.gray('Run `cv init` first'));
+      process.exit(1);
+    }
+
+    try {
+      const config = await configManager.load(repoRoot);
+      const readFile = (file: string) => fs.readFile(path.join(repoRoot, file), 'utf-8').catch(() => null);
+
+      const graph = createGraphManager({ url: config.graph.url, repoId: config.repository.repoId || generateRepoId(repoRoot) });
+      await graph.connect();
+      const found = await findDocTargets(graph, {
+        path: options.path ? path.relative(repoRoot, path.resolve(process.cwd(), options.path)).split(path.sep).join('/') : undefined,
+        ignore: [...(config.docgen?.ignore ?? []), ...(options.ignore ?? [])],
+        missingOnly: options.missingOnly,
+        readFile
+      });
+      await graph.close();
+
+      if (found.length === 0) {
+        spinner.succeed(options.missingOnly
+          ? 'Every public item already has a doc comment'
+          : 'No public items found (run `cv sync` if files were added)');
+        return;
+      }
+
+      const limit = Math.max(1, parseInt(options.limit, 10) || 50);
+      const targets = found.slice(0, limit);
+      const missing = targets.filter(target => !target.documented).length;
+      spinner.succeed(`${targets.length} item(s) to document` +
+        (missing < targets.length ? ` (${missing} missing, ${targets.length - missing} to update)` : '') +
+        (found.length > limit ? chalk.gray(` - ${found.length - limit} more left for the next run (--limit)`) : ''));
+
+      const client = await createModelClient(options.model, {
+        provider: 'anthropic',
+        model: config.ai.model,
+        anthropicApiKey: config.ai.apiKey,
+        fallback: config.ai.fallback
+      });
+
+      // One request per file (or per 20 items of a large file)
+      const docsByFile = new Map<string, Array<{ startLine: number; doc: string }>>();
+      const groups = groupDocTargets(targets);
+      let failed = 0;
+      for (const [i, group] of groups.entries()) {
+        const file = group[0].file;
+        const progress = ora(`[${i + 1}/${groups.length}] ${file}`).start();
+        const source = await readFile(file);
+        if (source === null) {
+          progress.warn(`${file}: no longer readable`);
+          continue;
+        }
+        try {
+          const response = await client.chat(
+            [{ role: 'user', content: buildDocgenPrompt(file, source, group) }],
+            DOCGEN_SYSTEM_PROMPT
+          );
+          const docs = parseDocgenResponse(response, group);
+          const byName = new Map<string, DocTarget>(group.map(target => [target.qualifiedName, target]));
+          const items = docsByFile.get(file) ?? [];
+          for (const { qualifiedName, doc } of docs) items.push({ startLine: byName.get(qualifiedName)!.startLine, doc });
+          docsByFile.set(file, items);
+          progress.succeed(`[${i + 1}/${groups.length}] ${file} (${docs.length}/${group.length})`);
+        } catch (error: any) {
+          failed++;
+          progress.fail(`[${i + 1}/${groups.length}] ${file}: ${error.message}`);
+        }
+      }
+
+      const notice = fallbackNotice(client);
+      if (notice) console.log(chalk.gray(notice));
+
+      const edits: Edit[] = [];
+      for (const [file, items] of docsByFile) {
+        const source = await readFile(file);
+        if (source === null || items.length === 0) continue;
+        const language = targets.find(target => target.file === file)!.language;
+        edits.push({
+          id: `docgen-${edits.length + 1}`,
+          file,
+          type: 'modify',
+          newContent: applyDocComments(source, language, items),
+          status: 'pending',
+          description: `Doc comments for ${items.length} item(s)`
+        });
+      }
+
+      const engine = createPatchEngine(repoRoot);
+      const patch = edits.length > 0 ? await engine.prepare(edits) : { files: [] };
+      if (patch.files.length === 0) {
+        console.log(chalk.yellow('No doc comments were generated'));
+        process.exit(failed > 0 ? 1 : 0);
+      }
+
+      console.log();
+      console.log(chalk.bold.cyan(`Patch (${patch.files.length} file${patch.files.length === 1 ? '' : 's'}):`));
+      console.log();
+      for (const file of patch.files) {
+        console.log(colorizeDiff(file.diff.trimEnd()));
+        console.log();
+      }
+
+      if (options.dryRun) {
+        console.log(chalk.gray('(dry run - no files changed)'));
+        return;
+      }
+
+      const git = createGitManager(repoRoot);
+      const dirty = uncommittedFiles(patch, await git.getStatus());
+      if (dirty.length > 0 && !options.force) {
+        console.error(chalk.red('Refusing to modify files with uncommitted changes:'));
+        for (const file of dirty) console.error(chalk.gray(`  • ${file}`));
+        console.error(chalk.gray('Commit or stash them first, or pass --force'));
+        process.exit(1);
+      }
+
+      if (!options.yes && !await askForApproval(`Apply doc comments to ${patch.files.length} file(s)?`)) {
+        console.log(chalk.gray('No files changed'));
+        return;
+      }
+
+      await engine.apply(patch);
+      console.log(chalk.green(`✓ Documented ${edits.reduce((n, edit) => n + (docsByFile.get(edit.file)?.length ?? 0), 0)} item(s) in ${patch.files.length} file(s)`));
+      try {
+        const head = await git.getLastCommitSha().catch(() => undefined);
+        const changeSet = await recordChangeSet(repoRoot, patch.files, { command: 'docgen', description: 'Generated doc comments', head });
+        console.log(chalk.gray(`Undo with: cv undo ${changeSet.id}`));
+      } catch (error: any) {
+        console.error(chalk.yellow(`⚠ Could not record change set for undo: ${error.message}`));
+      }
+      if (failed > 0) process.exit(1);
+    } catch (error: any) {
+      spinner.fail(chalk.red('Doc generation failed'));
+      console.error(chalk.red(error.message));
+      process.exit(1);
+    }
+  });
+
+  return cmd;
+}
+
+/**
+ * Ask for user approval
+ */
+async function askForApproval(question: string): Promise<boolean> {
+  const rl = readline.createInterface({
+    input: process.stdin,
+    output: process.stdout
+  });
+
+  return new Promise(resolve => {
+    rl.question(chalk.cyan(`${question} (y/N): `), answer => {
+      rl.close();
+      resolve(answer.toLowerCase() === 'y' || answer.toLowerCase() === 'yes');
+    });
+  });
+}
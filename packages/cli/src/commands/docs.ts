@@ -19,7 +19,8 @@ import {
 import { findRepoRoot, DocumentType } from '@cv-git/shared';
 import { glob } from 'glob';
 import { promises as fs } from 'fs';
-import { getEmbeddingCredentials } from '../utils/credentials.js';
+import { resolveEmbeddingProvider } from '../utils/embedding-provider.js';
+import { vectorStoreOptions } from '../utils/vector-store.js';
 import * as path from 'path';
 
 /**
@@ -88,15 +89,10 @@ export function createDocsCommand(): Command {
         // Initialize vector if available
         let vector;
         try {
-          const embeddingCreds = await getEmbeddingCredentials({
-            openRouterKey: config.embedding?.apiKey,
-            openaiKey: config.ai?.apiKey
-          });
-
           vector = createVectorManager({
             url: config.vector.url,
-            openrouterApiKey: embeddingCreds.openrouterApiKey,
-            openaiApiKey: embeddingCreds.openaiApiKey,
+            ...vectorStoreOptions(config, repoRoot),
+            provider: await resolveEmbeddingProvider(config),
             collections: config.vector.collections,
             cacheDir: path.join(repoRoot, '.cv', 'embeddings')
           });
@@ -194,15 +190,10 @@ export function createDocsCommand(): Command {
 
             // Try to initialize vector manager
             try {
-              const embeddingCreds = await getEmbeddingCredentials({
-                openRouterKey: config.embedding?.apiKey,
-                openaiKey: config.ai?.apiKey
-              });
-
               vector = createVectorManager({
                 url: config.vector.url,
-                openrouterApiKey: embeddingCreds.openrouterApiKey,
-                openaiApiKey: embeddingCreds.openaiApiKey,
+                ...vectorStoreOptions(config, repoRoot),
+                provider: await resolveEmbeddingProvider(config),
                 collections: config.vector.collections,
                 cacheDir: path.join(repoRoot, '.cv', 'embeddings')
               });
@@ -848,15 +839,10 @@ export function createDocsCommand(): Command {
 
         const config = await configManager.load(repoRoot);
 
-        const embeddingCreds = await getEmbeddingCredentials({
-          openRouterKey: config.embedding?.apiKey,
-          openaiKey: config.ai?.apiKey
-        });
-
         const vector = createVectorManager({
           url: config.vector.url,
-          openrouterApiKey: embeddingCreds.openrouterApiKey,
-          openaiApiKey: embeddingCreds.openaiApiKey,
+          ...vectorStoreOptions(config, repoRoot),
+          provider: await resolveEmbeddingProvider(config),
           collections: config.vector.collections,
           cacheDir: path.join(repoRoot, '.cv', 'embeddings')
         });
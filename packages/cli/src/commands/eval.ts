@@ -0,0 +1,332 @@
+/**
+ * cv eval command
+ * Evaluate retrieval quality against the repository's own dataset
+ *
+ * `cv eval` (`cv eval run`) runs the queries in `.cv/eval/queries.yaml`
+ * against the live index and fails below the suite's recall/MRR thresholds,
+ * for CI; `--next` runs them against the index `cv index reembed` is
+ * building, before it replaces the current one. `cv eval providers <modelA> <modelB>` embeds the same sample of
+ * indexed chunks with each model and reports recall, MRR, latency and cost
+ * side by side.
+ */
+
+import { Command } from 'commander';
+import chalk from 'chalk';
+import * as path from 'path';
+import { promises as fs } from 'fs';
+import {
+  configManager,
+  createVectorManager,
+  resolveProvider,
+  VectorManager,
+  loadEvalDataset,
+  getEvalDatasetPath,
+  evaluateProvider,
+  formatProviderComparison,
+  EvalCorpusChunk,
+  EvalDataset,
+  ProviderEvalResult,
+  DEFAULT_EVAL_K,
+  loadEvalSuite,
+  runEvalSuite,
+  formatSuiteReport,
+  openRetriever,
+  EvalSuite,
+  EvalThresholds,
+  SuiteReport,
+  loadReembedState,
+  reembedConfig
+} from '@cv-git/core';
+import { findRepoRoot } from '@cv-git/shared';
+import { addGlobalOptions, createOutput, ExitCode } from '../utils/output.js';
+import { getEmbeddingCredentials } from '../utils/credentials.js';
+import { resolveEmbeddingProvider } from '../utils/embedding-provider.js';
+import { vectorStoreOptions } from '../utils/vector-store.js';
+
+/** Chunks embedded per provider unless --max-chunks says otherwise */
+const DEFAULT_MAX_CHUNKS = 2000;
+
+export function evalCommand(): Command {
+  const cmd = new Command('eval').description('Evaluate retrieval quality on this repository');
+
+  const run = new Command('run')
+    .description('Run the retrieval regression suite against the current index; fails below its thresholds')
+    .argument('[suite]', 'Suite file (default: .cv/eval/queries.yaml)')
+    .option('-k, --k <n>', 'Results considered per query (default: the suite\'s k)')
+    .option('--min-recall <n>', 'Fail below this mean recall@k (overrides the suite)')
+    .option('--min-mrr <n>', 'Fail below this MRR (overrides the suite)')
+    .option('--report <file>', 'Write the results as markdown (e.g. for a CI job summary)')
+    .option('--next', 'Query the index `cv index reembed` is building for its new model');
+
+  addGlobalOptions(run);
+
+  run.action(async (suiteFile: string | undefined, options) => {
+    const output = createOutput(options);
+
+    try {
+      const repoRoot = await findRepoRoot();
+      if (!repoRoot) {
+        output.error('Not in a CV-Git repository. Run `cv init` first.');
+        process.exit(ExitCode.Error);
+      }
+
+      let suite: EvalSuite;
+      try {
+        suite = await loadEvalSuite(repoRoot, suiteFile);
+      } catch (error: any) {
+        output.error(error.message);
+        console.error(chalk.gray('Create .cv/eval/queries.yaml like:'));
+        console.error(chalk.gray('  thresholds: { recall: 0.8, mrr: 0.5 }'));
+        console.error(chalk.gray('  queries:'));
+        console.error(chalk.gray('    - query: where are sessions stored?'));
+        console.error(chalk.gray('      expected: [src/chat/index.ts, src/chat/store.ts:SessionStore]'));
+        process.exit(ExitCode.Error);
+      }
+
+      const thresholds: EvalThresholds = {};
+      if (options.minRecall !== undefined) thresholds.recall = parseFloat(options.minRecall);
+      if (options.minMrr !== undefined) thresholds.mrr = parseFloat(options.minMrr);
+
+      let config = await configManager.load(repoRoot);
+      if (options.next) {
+        const state = await loadReembedState(repoRoot);
+        if (!state || state.swappedAt) {
+          output.error('No embedding model migration in progress (start one with `cv index reembed --model <model>`)');
+          process.exit(ExitCode.Error);
+        }
+        config = reembedConfig(config, state);
+      }
+      const spinner = output.spinner(`Running ${suite.cases.length} queries${options.next ? ` against ${config.embedding.model}` : ''}...`);
+      spinner.start();
+      // The same search, min-score threshold and re-ranking as the library API
+      const retriever = await openRetriever({
+        repoRoot,
+        provider: await resolveEmbeddingProvider(config),
+        index: options.next ? 'next' : 'current'
+      });
+      let report: SuiteReport;
+      try {
+        report = await runEvalSuite(
+          suite,
+          async (query, k) => (await retriever.retrieve(query, { limit: k }))
+            .map(c => ({ file: c.file, symbolName: c.symbolName, score: c.score })),
+          { k: options.k ? parseInt(options.k, 10) || undefined : undefined, thresholds }
+        );
+      } finally {
+        await retriever.close();
+      }
+      spinner.succeed(`Ran ${suite.cases.length} queries`);
+
+      if (options.report) {
+        await fs.writeFile(path.resolve(options.report), formatSuiteReport(report) + '\n', 'utf-8');
+      }
+
+      const exitCode = report.failures.length > 0 ? ExitCode.CheckFailed : ExitCode.Success;
+      if (options.json) {
+        output.json({ ...report, passed: report.failures.length === 0 });
+        process.exit(exitCode);
+      }
+
+      console.log();
+      printSuiteReport(report, !!options.verbose);
+      if (options.report) {
+        console.log(chalk.gray(`Report written to ${options.report}`));
+      }
+      process.exit(exitCode);
+    } catch (error: any) {
+      output.error('Evaluation failed', error);
+      process.exit(ExitCode.Error);
+    }
+  });
+
+  const providers = new Command('providers')
+    .description('Compare embedding models side by side on the evaluation dataset')
+    .argument('<models...>', 'Two or more embedding models (e.g. text-embedding-3-small nomic-embed-text)')
+    .option('--dataset <file>', 'Evaluation dataset (default: .cv/eval/dataset.json)')
+    .option('-k, --k <n>', 'Results considered per query', String(DEFAULT_EVAL_K))
+    .option('--max-chunks <n>', 'Indexed chunks to embed per model (relevant chunks are always kept)', String(DEFAULT_MAX_CHUNKS))
+    .option('--report <file>', 'Write the comparison as markdown');
+
+  addGlobalOptions(providers);
+
+  providers.action(async (models: string[], options) => {
+    const output = createOutput(options);
+
+    try {
+      if (models.length < 2) {
+        output.error('Give at least two embedding models to compare');
+        process.exit(1);
+      }
+
+      const repoRoot = await findRepoRoot();
+      if (!repoRoot) {
+        console.error(chalk.red('Not in a CV-Git repository. Run `cv init` first.'));
+        process.exit(1);
+      }
+
+      const config = await configManager.load(repoRoot);
+      const datasetPath = options.dataset ? path.resolve(options.dataset) : getEvalDatasetPath(repoRoot);
+      let dataset: EvalDataset;
+      try {
+        dataset = await loadEvalDataset(datasetPath);
+      } catch (error: any) {
+        output.error(`Could not load evaluation dataset: ${error.message}`);
+        console.error(chalk.gray('Create .cv/eval/dataset.json with cases like:'));
+        console.error(chalk.gray('  [{ "query": "where are sessions stored?", "expected": ["src/chat/index.ts"] }]'));
+        process.exit(1);
+      }
+
+      const creds = await getEmbeddingCredentials();
+      const connect = async (embeddingModel?: string): Promise<VectorManager> => {
+        const vector = createVectorManager({
+          url: config.vector.url,
+          ...vectorStoreOptions(config, repoRoot),
+          provider: resolveProvider({
+            openrouterApiKey: creds.openrouterApiKey,
+            openaiApiKey: creds.openaiApiKey,
+            model: embeddingModel
+          }),
+          collections: config.vector.collections
+        });
+        await vector.connect();
+        return vector;
+      };
+
+      const spinner = output.spinner('Loading indexed chunks...');
+      spinner.start();
+      const base = await connect(config.embedding?.model);
+      const corpus = await loadCorpus(base, dataset, parseInt(options.maxChunks, 10) || DEFAULT_MAX_CHUNKS);
+      await base.close();
+      spinner.succeed(`${corpus.length} chunks, ${dataset.cases.length} queries`);
+
+      if (corpus.length === 0) {
+        output.error('No indexed chunks found. Run `cv sync` first.');
+        process.exit(1);
+      }
+
+      const k = parseInt(options.k, 10) || DEFAULT_EVAL_K;
+      const results: ProviderEvalResult[] = [];
+      for (const model of models) {
+        const modelSpinner = output.spinner(`Evaluating ${model}...`);
+        modelSpinner.start();
+        try {
+          const vector = await connect(model);
+          const info = vector.getEmbeddingInfo();
+          results.push(await evaluateProvider(
+            { name: info.provider, model: info.model, embedder: vector },
+            corpus,
+            dataset,
+            { k }
+          ));
+          await vector.close();
+          modelSpinner.succeed(`Evaluated ${model}`);
+        } catch (error: any) {
+          modelSpinner.fail(`${model}: ${error.message}`);
+        }
+      }
+
+      if (options.report) {
+        await fs.writeFile(path.resolve(options.report), formatProviderComparison(results) + '\n', 'utf-8');
+      }
+
+      if (options.json) {
+        output.json({ dataset: datasetPath, chunks: corpus.length, results });
+        return;
+      }
+
+      console.log();
+      printComparison(results);
+      if (options.report) {
+        console.log(chalk.gray(`\nReport written to ${options.report}`));
+      }
+      if (results.length < models.length) {
+        process.exitCode = 1;
+      }
+    } catch (error: any) {
+      output.error('Evaluation failed', error);
+      process.exit(1);
+    }
+  });
+
+  cmd.addCommand(run, { isDefault: true });
+  cmd.addCommand(providers);
+  return cmd;
+}
+
+/**
+ * Indexed chunks to embed: every chunk from an expected file, then others up to `max`
+ */
+async function loadCorpus(vector: VectorManager, dataset: EvalDataset, max: number): Promise<EvalCorpusChunk[]> {
+  const expectedFiles = new Set(dataset.cases.flatMap(c => c.expected.map(e => e.split(':')[0])));
+  const relevant: EvalCorpusChunk[] = [];
+  const others: EvalCorpusChunk[] = [];
+
+  let offset: string | undefined;
+  do {
+    const page = await vector.scroll(vector.getCollectionNames().codeChunks, 256, offset);
+    for (const point of page.points) {
+      const payload = point.payload as any;
+      if (typeof payload?.text !== 'string' || typeof payload.file !== 'string') continue;
+      const chunk = { id: String(point.id), file: payload.file, symbolName: payload.symbolName, text: payload.text };
+      (expectedFiles.has(chunk.file) ? relevant : others).push(chunk);
+    }
+    offset = page.next_page_offset;
+  } while (offset && relevant.length + others.length < max * 4);
+
+  return [...relevant, ...others.slice(0, Math.max(0, max - relevant.length))];
+}
+
+/**
+ * Each query's rank and recall, with what came back for the ones that missed
+ */
+function printSuiteReport(report: SuiteReport, verbose: boolean): void {
+  for (const c of report.cases) {
+    const ok = c.missing.length === 0;
+    const rank = c.firstRelevantRank ? `rank ${c.firstRelevantRank}` : 'not found';
+    console.log(`${ok ? chalk.green('✓') : chalk.red('✗')} ${c.query} ${chalk.gray(`(${rank}, recall ${c.recall.toFixed(2)})`)}`);
+    if (!ok || verbose) {
+      for (const missing of c.missing) console.log(chalk.red(`    missing ${missing}`));
+      if (c.results.length === 0) {
+        console.log(chalk.yellow('    no results (check retrieval.minScore)'));
+      }
+      for (const [i, hit] of c.results.slice(0, verbose ? report.k : 3).entries()) {
+        const name = hit.symbolName ? `${hit.file}:${hit.symbolName}` : hit.file;
+        console.log(chalk.gray(`    ${i + 1}. ${name}${hit.score !== undefined ? ` (${hit.score.toFixed(3)})` : ''}`));
+      }
+    }
+  }
+
+  const threshold = (value?: number) => (value === undefined ? '' : chalk.gray(` (min ${value})`));
+  console.log();
+  console.log(`recall@${report.k}  ${report.recallAtK.toFixed(3)}${threshold(report.thresholds.recall)}`);
+  console.log(`MRR        ${report.mrr.toFixed(3)}${threshold(report.thresholds.mrr)}`);
+  console.log();
+  if (report.failures.length > 0) {
+    for (const failure of report.failures) console.log(chalk.red(`✗ ${failure}`));
+  } else {
+    console.log(chalk.green('✓ Retrieval eval passed'));
+  }
+}
+
+function printComparison(results: ProviderEvalResult[]): void {
+  if (results.length === 0) return;
+
+  const best = (pick: (r: ProviderEvalResult) => number, higher: boolean) =>
+    results.reduce((a, b) => (higher ? pick(b) > pick(a) : pick(b) < pick(a)) ? b : a);
+  const topRecall = best(r => r.recallAtK, true);
+  const topMrr = best(r => r.mrr, true);
+  const fastest = best(r => r.meanLatencyMs, false);
+
+  console.log(chalk.bold(`Provider comparison (k=${results[0].k})`));
+  for (const r of results) {
+    const mark = (winner: ProviderEvalResult, text: string) => winner === r ? chalk.green(text) : text;
+    const cost = r.estimatedCostUsd === null ? chalk.gray('cost unknown') : `$${r.estimatedCostUsd.toFixed(4)}`;
+    console.log();
+    console.log(chalk.cyan(`  ${r.model}`) + chalk.gray(` (${r.name})`));
+    console.log(`    recall@${r.k}  ${mark(topRecall, r.recallAtK.toFixed(3))}`);
+    console.log(`    MRR        ${mark(topMrr, r.mrr.toFixed(3))}`);
+    console.log(`    latency    ${mark(fastest, `${r.meanLatencyMs.toFixed(0)}ms`)} mean, ${r.p95LatencyMs.toFixed(0)}ms p95`);
+    console.log(`    cost       ${cost} ${chalk.gray(`(~${r.tokens} tokens)`)}`);
+  }
+  console.log();
+}
@@ -12,11 +12,30 @@ import {
   createVectorManager,
   createGraphManager,
   createGitManager,
-  createRLMRouter
+  loadContextUsage,
+  loadKeywordIndex,
+  loadScoreDistribution,
+  saveContextUsage,
+  createRLMRouter,
+  createSummaryCache,
+  parseExpandDepth,
+  contextSources,
+  loadPromptTemplates
 } from '@cv-git/core';
 import { findRepoRoot } from '@cv-git/shared';
-import { addGlobalOptions } from '../utils/output.js';
-import { getAnthropicApiKey, getEmbeddingCredentials } from '../utils/credentials.js';
+import { loadCitationFormatter, parseCitation, printSources, sourcesToJson } from '../utils/citations.js';
+import { addGlobalOptions, addJsonlOption, createJsonReporter, ErrorCode, ExitCode } from '../utils/output.js';
+import { addFixtureOptions, createFixtureSession } from '../utils/fixtures.js';
+import { checkIndexDrift } from '../utils/drift.js';
+import { getAnthropicApiKey } from '../utils/credentials.js';
+import { createModelClient, fallbackNotice } from '../utils/model-client.js';
+import { resolveEmbeddingProvider } from '../utils/embedding-provider.js';
+import { vectorStoreOptions } from '../utils/vector-store.js';
+import { applyReranker } from '../utils/reranker.js';
+import { applyQueryExpansion } from '../utils/query-expansion.js';
+import { ensureFreshIndex } from '../utils/watch-daemon.js';
+import { OpenWorkspace, openWorkspaceIndexes, withWorkspaceRoots } from '../utils/workspace.js';
+import { resolvePackageScope } from '../utils/packages.js';
 
 export function explainCommand(): Command {
   const cmd = new Command('explain');
@@ -24,59 +43,97 @@ export function explainCommand(): Command {
   cmd
     .description('Explain code, files, or concepts using AI')
     .argument('<target>', 'What to explain (symbol name, file path, or concept)')
+    .option('-m, --model <model>', 'Model to use, optionally as provider/model (anthropic/claude-sonnet-4-5, gemini/gemini-2.5-pro, openrouter/openai/gpt-4o, ollama/qwen2.5-coder:14b)')
     .option('--no-stream', 'Disable streaming output')
     .option('--deep', 'Use RLM-powered deep reasoning for complex queries')
     .option('--trace', 'Show reasoning trace (only with --deep)')
-    .option('--max-depth <n>', 'Maximum recursion depth for deep reasoning (default: 5)', '5');
+    .option('--max-depth <n>', 'Maximum recursion depth for deep reasoning (default: 5)', '5')
+    .option('--expand-query <mode>', 'Also search reformulations of the query: off, synonyms or llm (default: retrieval.expansion)')
+    .option('--expand-depth <n>', 'Hops of callers/callees/imports to include around matches (0-5)')
+    .option('--refresh-stale', 'Re-index retrieved files that changed since the last sync')
+    .option('--workspace', 'Also search the repositories registered with `cv workspace add`')
+    .option('--package <name>', 'Only search code of this monorepo package')
+    .option('--all-packages', 'Search the whole repository, not just the package of the current directory')
+    .option('--no-cache', 'Ask the model even if this code was explained before');
 
   addGlobalOptions(cmd);
+  addJsonlOption(cmd);
+  addFixtureOptions(cmd);
 
   cmd.action(async (target: string, options) => {
-      let spinner = ora('Initializing...').start();
+      // The answer and its sources go to stdout as JSON; keep progress off it
+      const report = createJsonReporter('explain', options);
+      const json = Boolean(report);
+      let spinner = ora({ text: 'Initializing...', isSilent: json }).start();
 
       try {
         // Find repository root
         const repoRoot = await findRepoRoot();
         if (!repoRoot) {
+          report?.fail('Not in a CV-Git repository', ErrorCode.NOT_INITIALIZED);
           spinner.fail(chalk.red('Not in a CV-Git repository'));
           console.error(chalk.gray('Run `cv init` first'));
-          process.exit(1);
+          process.exit(ExitCode.Error);
         }
 
         // Load configuration
         const config = await configManager.load(repoRoot);
 
+        // `--model provider/model` sends prompts to that provider instead of Claude;
+        // a configured fallback chain also routes them through a chat client
+        const chatClient = options.model || config.ai.fallback
+          ? await createModelClient(options.model, {
+              provider: 'anthropic',
+              model: config.ai.model,
+              anthropicApiKey: config.ai.apiKey,
+              fallback: config.ai.fallback
+            })
+          : undefined;
+
         // Check for API keys (CredentialManager -> config -> env var)
         const anthropicApiKey = await getAnthropicApiKey(config.ai.apiKey);
-        if (!anthropicApiKey) {
+        if (!anthropicApiKey && !chatClient) {
+          report?.fail('Anthropic API key not found', ErrorCode.NO_CREDENTIALS);
           spinner.fail(chalk.red('Anthropic API key not found'));
           console.error();
           console.error(chalk.yellow('Set your Anthropic API key:'));
           console.error(chalk.gray('  cv auth setup anthropic'));
           console.error(chalk.gray('  export ANTHROPIC_API_KEY=sk-ant-...'));
-          process.exit(1);
+          process.exit(ExitCode.Error);
         }
 
-        // Get embedding credentials (OpenRouter preferred, fallback to OpenAI)
-        const embeddingCreds = await getEmbeddingCredentials();
+        // Resolve the embedding provider the index was built with
+        const embeddingProvider = await resolveEmbeddingProvider(config).catch(() => undefined);
+
+        // Let a running `cv watch` sync pending edits first
+        spinner.text = 'Checking for pending changes...';
+        await ensureFreshIndex(repoRoot);
 
         // Initialize components
         spinner.text = 'Connecting to services...';
 
         // Vector manager (optional but recommended)
         let vector = undefined;
-        if ((embeddingCreds.openrouterApiKey || embeddingCreds.openaiApiKey) && config.vector) {
+        if (embeddingProvider && config.vector) {
           try {
             vector = createVectorManager({
               url: config.vector.url,
-              openrouterApiKey: embeddingCreds.openrouterApiKey,
-              openaiApiKey: embeddingCreds.openaiApiKey,
-              collections: config.vector.collections,
-              embeddingModel: config.embedding?.model
+              ...vectorStoreOptions(config, repoRoot),
+              provider: embeddingProvider,
+              collections: config.vector.collections
             });
             await vector.connect();
           } catch (error) {
-            console.log(chalk.gray('  ⚠ Could not connect to vector DB - continuing without semantic search'));
+            console.error(chalk.gray('  ⚠ Could not connect to vector DB - continuing without semantic search'));
+          }
+        }
+
+        // Indexes of the other workspace repositories
+        let workspace: OpenWorkspace | undefined;
+        if (options.workspace) {
+          workspace = await openWorkspaceIndexes(repoRoot, config, { silent: json });
+          if (workspace.members.length === 0) {
+            console.error(chalk.gray('  ⚠ No workspace repositories registered - run `cv workspace add <path>`'));
           }
         }
 
@@ -92,13 +149,43 @@ export function explainCommand(): Command {
           {
             provider: 'anthropic',
             model: config.ai.model,
-            apiKey: anthropicApiKey
+            apiKey: anthropicApiKey ?? '',
+            chatClient,
+            retrieval: config.retrieval
           },
           vector,
           graph,
           git
         );
 
+        ai.setPromptTemplates(await loadPromptTemplates(repoRoot, config.repository?.name));
+        if (workspace) ai.setWorkspace(workspace.indexes);
+
+        // Monorepos: --package, else the package the command runs in
+        const scope = await resolvePackageScope(repoRoot, options);
+        if (scope) {
+          ai.setPackageScope(scope.package);
+          if (scope.automatic && !json) {
+            console.error(chalk.gray(`  Scoped to package ${scope.package.name} (--all-packages to search the whole repository)`));
+          }
+        }
+
+        const fixture = createFixtureSession(options, repoRoot, 'explain', [target]);
+        fixture.attach(ai, vector);
+
+        // Prefer chunk paths/kinds that past answers actually cited
+        const contextUsage = await loadContextUsage(repoRoot);
+        ai.setContextUsage(contextUsage);
+        ai.setKeywordIndex(await loadKeywordIndex(repoRoot));
+        applyReranker(ai, config);
+        applyQueryExpansion(ai, config, 'explain', options.expandQuery);
+        ai.setScoreDistribution(await loadScoreDistribution(repoRoot, vector?.getEmbeddingInfo().model));
+
+        // Reuse the previous answer while the retrieved code is unchanged
+        if (options.cache !== false) {
+          ai.setSummaryCache(createSummaryCache(repoRoot));
+        }
+
         // Use RLM Router for deep reasoning if --deep flag is set
         if (options.deep) {
           spinner.text = 'Starting deep reasoning...';
@@ -120,12 +207,34 @@ export function explainCommand(): Command {
 
             spinner.succeed(chalk.green(`Deep reasoning complete (depth: ${result.depth})`));
 
+            if (report) {
+              const sources = result.sources.map(source => {
+                const citation = parseCitation(source);
+                return {
+                  file: citation.file,
+                  startLine: citation.line ?? 1,
+                  endLine: citation.endLine ?? citation.line ?? 1
+                };
+              });
+              report.result({
+                target,
+                answer: result.answer,
+                depth: result.depth,
+                sources: sourcesToJson(sources, repoRoot)
+              });
+              await graph.close();
+              if (vector) await vector.close();
+              await workspace?.close();
+              return;
+            }
+
             // Show sources if any
             if (result.sources.length > 0) {
               console.log();
               console.log(chalk.bold.cyan('Sources:'));
+              const cite = await loadCitationFormatter(repoRoot);
               result.sources.slice(0, 10).forEach(source => {
-                console.log(chalk.gray(`  • ${source}`));
+                console.log(chalk.gray(`  • ${cite(source)}`));
               });
             }
 
@@ -154,6 +263,7 @@ export function explainCommand(): Command {
             // Close connections
             await graph.close();
             if (vector) await vector.close();
+            await workspace?.close();
             return;
 
           } catch (error: any) {
@@ -161,17 +271,38 @@ export function explainCommand(): Command {
             console.error(chalk.red(`Error: ${error.message}`));
             await graph.close();
             if (vector) await vector.close();
-            process.exit(1);
+            await workspace?.close();
+            report?.fail(`Deep reasoning failed: ${error.message}`, ErrorCode.API_ERROR);
+            process.exit(ExitCode.Error);
           }
         }
 
         spinner.text = 'Gathering context...';
 
         // Gather context for the target
-        const context = await ai.gatherContext(target);
+        const contextOptions = {
+          expandDepth: options.expandDepth !== undefined ? parseExpandDepth(options.expandDepth) : undefined
+        };
+        let context = await ai.gatherContext(target, contextOptions);
+
+        // Warn when retrieved code has changed since it was indexed
+        const drift = await checkIndexDrift({
+          repoRoot, context, git, graph, vector,
+          refresh: options.refreshStale,
+          silent: json
+        });
+        if (drift.refreshed) {
+          context = await ai.gatherContext(target, contextOptions);
+        }
 
         if (context.chunks.length === 0 && context.symbols.length === 0) {
           spinner.warn(chalk.yellow('No relevant code found'));
+          if (report) {
+            await graph.close();
+            if (vector) await vector.close();
+            await workspace?.close();
+            report.fail('No relevant code found', ErrorCode.NO_RESULTS);
+          }
           console.log();
           console.log(chalk.gray('Tips:'));
           console.log(chalk.gray('  • Make sure you have run `cv sync`'));
@@ -181,7 +312,8 @@ export function explainCommand(): Command {
 
           await graph.close();
           if (vector) await vector.close();
-          process.exit(1);
+          await workspace?.close();
+          process.exit(ExitCode.Error);
         }
 
         spinner.succeed(
@@ -190,6 +322,30 @@ export function explainCommand(): Command {
           )
         );
 
+        const sources = withWorkspaceRoots(contextSources(context.chunks), workspace?.members ?? []);
+
+        if (report) {
+          const jsonSources = sourcesToJson(sources, repoRoot);
+          report.event('context', { sources: jsonSources, symbols: context.symbols.length });
+          // --jsonl streams the answer as it is written
+          const explanation = await ai.explain(target, context, report.streaming && options.stream
+            ? { onToken: text => report.event('token', { text }) }
+            : undefined);
+          report.result({
+            target,
+            explanation,
+            sources: jsonSources,
+            symbols: context.symbols.map(s => ({ name: s.name, kind: s.kind, file: s.file, startLine: s.startLine }))
+          });
+
+          await fixture.save();
+          await saveContextUsage(repoRoot, contextUsage);
+          await graph.close();
+          if (vector) await vector.close();
+          await workspace?.close();
+          return;
+        }
+
         // Show context summary
         console.log();
         console.log(chalk.bold.cyan('Context:'));
@@ -198,7 +354,7 @@ export function explainCommand(): Command {
           context.chunks.slice(0, 3).forEach(chunk => {
             console.log(
               chalk.gray(
-                `     • ${chunk.payload.symbolName || 'code'} in ${chunk.payload.file}`
+                `     • ${chunk.payload.symbolName || 'code'} in ${chunk.payload.repo ? `${chunk.payload.repo}:` : ''}${chunk.payload.file}`
               )
             );
           });
@@ -239,11 +395,22 @@ export function explainCommand(): Command {
           console.log(chalk.gray('─'.repeat(80)));
         }
 
+        const notice = fallbackNotice(chatClient);
+        if (notice) console.log(chalk.gray(notice));
+
+        printSources(sources, await loadCitationFormatter(repoRoot));
+        console.log();
+
+        await fixture.save();
+        await saveContextUsage(repoRoot, contextUsage);
+
         // Close connections
         await graph.close();
         if (vector) await vector.close();
+        await workspace?.close();
 
       } catch (error: any) {
+        report?.fail(error.message, error.message.includes('API key') ? ErrorCode.NO_CREDENTIALS : ErrorCode.OPERATION_FAILED);
         if (spinner) {
           spinner.fail(chalk.red('Explanation failed'));
         }
@@ -264,7 +431,7 @@ export function explainCommand(): Command {
           console.error(chalk.gray(error.stack));
         }
 
-        process.exit(1);
+        process.exit(ExitCode.Error);
       }
     });
 
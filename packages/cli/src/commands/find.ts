@@ -1,6 +1,9 @@
 /**
  * cv find command
  * Semantic search over codebase
+ *
+ * Offline, when the index's embeddings come from a cloud provider, the
+ * keyword index answers instead.
  */
 
 import { Command } from 'commander';
@@ -9,14 +12,32 @@ import {
   configManager,
   createVectorManager,
   getStorageInfo,
-  loadVectorsOnly
+  loadVectorsOnly,
+  checkIndexCompatibility,
+  IndexCompatibilityError,
+  loadScoreDistribution,
+  resolveMinScore,
+  loadKeywordIndex,
+  keywordHitToResult,
+  packageForFile,
+  OfflineError,
+  RepoPackage,
+  VectorManager
 } from '@cv-git/core';
 import { findRepoRoot, getCVDir } from '@cv-git/shared';
 import { VectorSearchResult, CodeChunkPayload } from '@cv-git/shared';
-import { addGlobalOptions, createOutput } from '../utils/output.js';
-import { getEmbeddingCredentials } from '../utils/credentials.js';
-import { getPreferences } from '../config.js';
-import { ensureOllama } from '../utils/infrastructure.js';
+import { loadCitationFormatter, CitationFormatter } from '../utils/citations.js';
+import {
+  addGlobalOptions,
+  addJsonlOption,
+  createJsonReporter,
+  createOutput,
+  ErrorCode,
+  ExitCode
+} from '../utils/output.js';
+import { resolveEmbeddingProvider } from '../utils/embedding-provider.js';
+import { vectorStoreOptions } from '../utils/vector-store.js';
+import { resolvePackageScope } from '../utils/packages.js';
 
 export function findCommand(): Command {
   const cmd = new Command('find');
@@ -27,11 +48,15 @@ export function findCommand(): Command {
     .option('-l, --limit <number>', 'Maximum number of results', '10')
     .option('--language <lang>', 'Filter by programming language')
     .option('--file <path>', 'Filter by file path (partial match)')
-    .option('--min-score <score>', 'Minimum similarity score (0-1)', '0.5');
+    .option('--package <name>', 'Filter by monorepo package')
+    .option('--all-packages', 'Search the whole repository, not just the package of the current directory')
+    .option('--min-score <score>', 'Minimum similarity score (0-1; default: retrieval.minScore, else adapted to the embedding model at sync)');
 
   addGlobalOptions(cmd);
+  addJsonlOption(cmd);
 
   cmd.action(async (query: string, options) => {
+      const report = createJsonReporter('find', options);
       const output = createOutput(options);
       const spinner = output.spinner('Initializing semantic search...').start();
 
@@ -39,122 +64,128 @@ export function findCommand(): Command {
         // Find repository root
         const repoRoot = await findRepoRoot();
         if (!repoRoot) {
+          report?.fail('Not in a CV-Git repository', ErrorCode.NOT_INITIALIZED);
           spinner.fail(chalk.red('Not in a CV-Git repository'));
           console.error(chalk.gray('Run `cv init` first'));
-          process.exit(1);
+          process.exit(ExitCode.Error);
         }
 
         // Load configuration
         const config = await configManager.load(repoRoot);
 
-        // Load user preferences to determine embedding provider
-        const prefsManager = getPreferences();
-        const prefs = await prefsManager.load();
-        const embeddingProvider = config.embedding?.provider || prefs.embeddingProvider || 'ollama';
-
-        // Get embedding credentials
-        let ollamaUrl: string | undefined;
-        let lmstudioUrl: string | undefined;
-        let openrouterApiKey: string | undefined;
-        let openaiApiKey: string | undefined;
-
-        if (embeddingProvider === 'ollama') {
-          spinner.text = 'Checking Ollama...';
-          const ollamaInfo = await ensureOllama({ silent: true });
-          if (ollamaInfo) {
-            ollamaUrl = ollamaInfo.url;
-            spinner.text = `Using Ollama at ${ollamaUrl}...`;
-          } else {
-            spinner.text = 'Ollama not available, checking cloud providers...';
-            const embeddingCreds = await getEmbeddingCredentials({
-              openRouterKey: config.embedding?.apiKey,
-              openaiKey: config.ai?.apiKey
-            });
-            openrouterApiKey = embeddingCreds.openrouterApiKey;
-            openaiApiKey = embeddingCreds.openaiApiKey;
-          }
-        } else if (embeddingProvider === 'lmstudio') {
-          spinner.text = 'Checking LM Studio...';
-          const { isLMStudioRunning, getLMStudioUrl } = await import('@cv-git/core');
-          const lmUrl = getLMStudioUrl();
-          if (await isLMStudioRunning(lmUrl)) {
-            lmstudioUrl = lmUrl;
-            spinner.text = `Using LM Studio at ${lmUrl}...`;
-          } else {
-            spinner.text = 'LM Studio not available, checking cloud providers...';
-            const embeddingCreds = await getEmbeddingCredentials({
-              openRouterKey: config.embedding?.apiKey,
-              openaiKey: config.ai?.apiKey
-            });
-            openrouterApiKey = embeddingCreds.openrouterApiKey;
-            openaiApiKey = embeddingCreds.openaiApiKey;
-          }
-        } else {
-          const embeddingCreds = await getEmbeddingCredentials({
-            openRouterKey: config.embedding?.apiKey,
-            openaiKey: config.ai?.apiKey
+        const limit = parseInt(options.limit, 10);
+        const scope = await resolvePackageScope(repoRoot, options);
+
+        // Resolve the embedding provider the index was built with; offline, an
+        // index embedded by a cloud provider is searched by keywords instead
+        let results: VectorSearchResult<CodeChunkPayload>[];
+        let minScore: number | null = null;
+        let offline: OfflineError | undefined;
+        let vector: VectorManager | undefined;
+        try {
+          const provider = await resolveEmbeddingProvider(config, {
+            onStatus: message => { spinner.text = `${message}...`; }
           });
-          openrouterApiKey = embeddingCreds.openrouterApiKey;
-          openaiApiKey = embeddingCreds.openaiApiKey;
-          spinner.text = `Using ${embeddingCreds.provider} for embeddings...`;
-        }
 
-        // Initialize vector manager with proper credentials
-        spinner.text = 'Connecting to Qdrant...';
-        const useLocal = !!(ollamaUrl || lmstudioUrl);
-        const vector = createVectorManager({
-          url: config.vector.url,
-          ollamaUrl,
-          lmstudioUrl,
-          openrouterApiKey: useLocal ? undefined : openrouterApiKey,
-          openaiApiKey: useLocal ? undefined : openaiApiKey,
-          collections: config.vector.collections,
-          vectorSize: (embeddingProvider === 'ollama' || embeddingProvider === 'lmstudio') ? 768 : 1536
-        });
-
-        await vector.connect();
-        spinner.succeed('Connected to vector database');
-
-        // Check if vectors are available in database
-        let hasVectors = false;
-        try {
-          const collectionInfo = await vector.getCollectionInfo('code_chunks');
-          hasVectors = (collectionInfo?.points_count || 0) > 0;
-        } catch {
-          // Collection might not exist
-        }
+          // Initialize vector manager with the resolved provider
+          spinner.text = 'Connecting to Qdrant...';
+          vector = createVectorManager({
+            url: config.vector.url,
+            ...vectorStoreOptions(config, repoRoot),
+            provider,
+            collections: config.vector.collections
+          });
+
+          await vector.connect();
+          spinner.succeed('Connected to vector database');
+
+          // Check if vectors are available in database
+          let hasVectors = false;
+          try {
+            const collectionInfo = await vector.getCollectionInfo('code_chunks');
+            hasVectors = (collectionInfo?.points_count || 0) > 0;
+          } catch {
+            // Collection might not exist
+          }
 
-        // Auto-load from .cv/ storage if needed
-        if (!hasVectors) {
-          const storageInfo = await getStorageInfo(repoRoot);
-          if (storageInfo && storageInfo.stats.vectors > 0) {
-            spinner.start(`Loading ${storageInfo.stats.vectors} vectors from .cv/ storage...`);
-            try {
-              const loadedCount = await loadVectorsOnly(repoRoot, vector);
-              spinner.succeed(`Loaded ${loadedCount} vectors from local storage`);
-            } catch (loadError: any) {
-              spinner.warn(`Could not load vectors: ${loadError.message}`);
+          // Auto-load from .cv/ storage if needed
+          if (!hasVectors) {
+            const storageInfo = await getStorageInfo(repoRoot);
+            if (storageInfo && storageInfo.stats.vectors > 0) {
+              spinner.start(`Loading ${storageInfo.stats.vectors} vectors from .cv/ storage...`);
+              try {
+                const loadedCount = await loadVectorsOnly(repoRoot, vector);
+                spinner.succeed(`Loaded ${loadedCount} vectors from local storage`);
+
+                const { commitsBehind, warnings } = checkIndexCompatibility(repoRoot, storageInfo);
+                if (commitsBehind) {
+                  console.log(chalk.yellow(`  Index is ${commitsBehind} commit(s) behind HEAD; run "cv sync" to catch up`));
+                }
+                warnings.forEach(w => console.log(chalk.gray(`  ${w}`)));
+              } catch (loadError: any) {
+                if (loadError instanceof IndexCompatibilityError) {
+                  spinner.warn('Refusing to load .cv/ index built for a different setup:');
+                  loadError.compatibility.problems.forEach(p => console.log(chalk.yellow(`  • ${p}`)));
+                  console.log(chalk.gray('  Run "cv sync --force" to rebuild it for this repository.'));
+                } else {
+                  spinner.warn(`Could not load vectors: ${loadError.message}`);
+                }
+              }
+            } else if (storageInfo && storageInfo.stats.vectors === 0) {
+              spinner.warn('No vectors in storage. Run "cv sync --force" with embedding API key first.');
+            } else {
+              spinner.warn('No local storage found. Run "cv sync" first.');
             }
-          } else if (storageInfo && storageInfo.stats.vectors === 0) {
-            spinner.warn('No vectors in storage. Run "cv sync --force" with embedding API key first.');
-          } else {
-            spinner.warn('No local storage found. Run "cv sync" first.');
           }
-        }
 
-        // Perform search
-        spinner.start('Searching...');
+          // Perform search
+          spinner.start('Searching...');
+
+          minScore = options.minScore !== undefined
+            ? parseFloat(options.minScore)
+            : resolveMinScore(
+                config.retrieval,
+                await loadScoreDistribution(repoRoot, vector.getEmbeddingInfo().model),
+                0.5
+              );
+
+          results = await vector.searchCode(query, limit, {
+            language: options.language,
+            file: options.file,
+            package: scope?.package.name,
+            minScore
+          });
+        } catch (error) {
+          if (!(error instanceof OfflineError)) throw error;
+          offline = error;
+          spinner.start('Searching keywords...');
+          const keywordResults = await findByKeywords(repoRoot, query, limit, {
+            language: options.language,
+            file: options.file,
+            package: scope?.package
+          });
+          if (!keywordResults) {
+            throw new Error(`${error.message}, and there is no keyword index to fall back to. Run "cv sync" first.`);
+          }
+          results = keywordResults;
+        }
 
-        const limit = parseInt(options.limit, 10);
-        const minScore = parseFloat(options.minScore);
+        spinner.stop();
 
-        const results = await vector.searchCode(query, limit, {
-          language: options.language,
-          file: options.file,
-          minScore
-        });
+        if (report) {
+          const matches = results.map(result => searchResultToJson(result));
+          matches.forEach(match => report.event('match', match));
+          report.result({ query, minScore, package: scope?.package.name ?? null, semantic: !offline, results: matches });
+          await vector?.close();
+          return;
+        }
 
-        spinner.stop();
+        if (offline) {
+          console.log(chalk.gray(`Keyword matches only: ${offline.message}`));
+        }
+        if (scope?.automatic) {
+          console.log(chalk.gray(`Scoped to package ${scope.package.name} (--all-packages to search the whole repository)`));
+        }
 
         // Display results
         if (results.length === 0) {
@@ -166,12 +197,14 @@ export function findCommand(): Command {
           console.log(chalk.gray('  • Removing filters'));
           console.log();
         } else {
-          displaySearchResults(query, results);
+          displaySearchResults(query, results, await loadCitationFormatter(repoRoot));
         }
 
-        await vector.close();
+        await vector?.close();
 
       } catch (error: any) {
+        const code = error.message.includes('ECONNREFUSED') ? ErrorCode.QDRANT_ERROR : ErrorCode.OPERATION_FAILED;
+        report?.fail(error.message, code);
         spinner.fail(chalk.red('Search failed'));
         console.error(chalk.red(`Error: ${error.message}`));
 
@@ -187,19 +220,58 @@ export function findCommand(): Command {
           console.error(chalk.gray('  cv sync'));
         }
 
-        process.exit(1);
+        process.exit(ExitCode.Error);
       }
     });
 
   return cmd;
 }
 
+/**
+ * BM25 matches filtered like searchCode(), scored relative to the best one
+ * so they read like similarities; null without a keyword index
+ */
+export async function findByKeywords(
+  repoRoot: string,
+  query: string,
+  limit: number,
+  filters: { language?: string; file?: string; package?: RepoPackage }
+): Promise<VectorSearchResult<CodeChunkPayload>[] | null> {
+  const index = await loadKeywordIndex(repoRoot);
+  if (index.size === 0) return null;
+  const hits = index.search(query, limit * 5)
+    .filter(hit => !filters.language || hit.doc.language === filters.language)
+    .filter(hit => !filters.file || hit.doc.file.includes(filters.file))
+    .filter(hit => !filters.package || packageForFile([filters.package], hit.doc.file))
+    .slice(0, limit);
+  const best = hits[0]?.score || 1;
+  return hits.map(hit => ({ ...keywordHitToResult(hit), score: hit.score / best }));
+}
+
+/**
+ * A search result in `--json` output
+ */
+function searchResultToJson(result: VectorSearchResult<CodeChunkPayload>) {
+  const payload = result.payload;
+  return {
+    file: payload.file,
+    startLine: payload.startLine,
+    endLine: payload.endLine,
+    symbolName: payload.symbolName ?? null,
+    symbolKind: payload.symbolKind ?? null,
+    language: payload.language ?? null,
+    score: result.score,
+    text: payload.text
+  };
+}
+
 /**
  * Display search results
  */
 function displaySearchResults(
   query: string,
-  results: VectorSearchResult<CodeChunkPayload>[]
+  results: VectorSearchResult<CodeChunkPayload>[],
+  cite: CitationFormatter
 ): void {
   console.log();
   console.log(chalk.bold.cyan(`Search results for: "${query}"`));
@@ -219,7 +291,7 @@ function displaySearchResults(
 
     // File and location
     console.log(
-      chalk.cyan(`   ${payload.file}:${payload.startLine}-${payload.endLine}`) +
+      chalk.cyan(`   ${cite({ file: payload.file, line: payload.startLine, endLine: payload.endLine })}`) +
       (payload.language ? chalk.gray(` • ${payload.language}`) : '')
     );
 
@@ -7,6 +7,7 @@ import { Command } from 'commander';
 import chalk from 'chalk';
 import Table from 'cli-table3';
 import ora from 'ora';
+import { promises as fs } from 'fs';
 import {
   configManager,
   createGraphManager,
@@ -15,9 +16,20 @@ import {
   createSemanticGraphService,
   generateRepoId,
   GraphService,
-  SemanticGraphService
+  SemanticGraphService,
+  CallTreeEntry,
+  collectSymbolGraph,
+  findSymbols,
+  formatSymbolGraph,
+  SYMBOL_GRAPH_FORMATS,
+  SymbolGraphFormat,
+  SymbolGraphRelation,
+  traverseCalls
 } from '@cv-git/core';
-import { findRepoRoot } from '@cv-git/shared';
+import { findRepoRoot, SymbolNode } from '@cv-git/shared';
+import { CitationFormatter, loadCitationFormatter } from '../utils/citations.js';
+import { resolveEmbeddingProvider } from '../utils/embedding-provider.js';
+import { vectorStoreOptions } from '../utils/vector-store.js';
 
 export function graphCommand(): Command {
   const cmd = new Command('graph');
@@ -214,6 +226,8 @@ export function graphCommand(): Command {
           return;
         }
 
+        const cite = await loadCitationFormatter((await findRepoRoot()) || process.cwd());
+
         // Show callers or callees for specific symbol
         if (options.callers) {
           const callers = await graph.getCallers(symbol);
@@ -229,7 +243,7 @@ export function graphCommand(): Command {
 
           for (const caller of callers) {
             console.log(chalk.white('  ▸'), chalk.yellow(caller.name));
-            console.log(chalk.gray(`    ${caller.file}:${caller.startLine}`));
+            console.log(chalk.gray(`    ${cite({ file: caller.file, line: caller.startLine })}`));
           }
 
           console.log();
@@ -248,7 +262,7 @@ export function graphCommand(): Command {
 
           for (const callee of callees) {
             console.log(chalk.white('  ▸'), chalk.yellow(callee.name));
-            console.log(chalk.gray(`    ${callee.file}:${callee.startLine}`));
+            console.log(chalk.gray(`    ${cite({ file: callee.file, line: callee.startLine })}`));
           }
 
           console.log();
@@ -256,6 +270,150 @@ export function graphCommand(): Command {
       });
     });
 
+  // Subcommands: cv graph callers / cv graph callees
+  for (const direction of ['callers', 'callees'] as const) {
+    cmd
+      .command(`${direction} <symbol>`)
+      .description(direction === 'callers'
+        ? 'Show what calls a symbol (name, Type::method or qualified name)'
+        : 'Show what a symbol calls (name, Type::method or qualified name)')
+      .option('--depth <n>', 'Follow calls this many hops', '1')
+      .option('--json', 'Output as JSON')
+      .action(async (symbol, options) => {
+        await withGraph(async (graph) => {
+          const roots = await findSymbols(graph, symbol);
+          if (roots.length === 0) {
+            console.log(chalk.yellow(`No symbol found for: ${symbol}`));
+            console.log(chalk.gray('Make sure you have run `cv sync` to build the graph'));
+            return;
+          }
+
+          const depth = Math.max(1, parseInt(options.depth, 10) || 1);
+          const entries = await traverseCalls(graph, roots, direction, depth);
+
+          if (options.json) {
+            console.log(JSON.stringify({
+              symbol,
+              matches: roots.map(root => root.qualifiedName),
+              [direction]: entries.map(entry => ({
+                qualifiedName: entry.symbol.qualifiedName,
+                name: entry.symbol.name,
+                kind: entry.symbol.kind,
+                file: entry.symbol.file,
+                line: entry.symbol.startLine,
+                depth: entry.depth,
+                via: entry.via
+              }))
+            }, null, 2));
+            return;
+          }
+
+          const cite = await loadCitationFormatter((await findRepoRoot()) || process.cwd());
+          console.log();
+          for (const root of roots) {
+            console.log(chalk.bold(direction === 'callers'
+              ? `Callers of ${chalk.cyan(root.qualifiedName)}:`
+              : `${chalk.cyan(root.qualifiedName)} calls:`));
+            printCallTree(entries, root.qualifiedName, 1, cite);
+            console.log();
+          }
+          if (entries.length === 0) {
+            console.log(chalk.yellow(`No ${direction} found`));
+            console.log();
+          }
+        });
+      });
+  }
+
+  // Subcommand: cv graph implementations
+  cmd
+    .command('implementations <symbol>')
+    .alias('impls')
+    .description('Show what extends or implements a class, interface or trait')
+    .option('--json', 'Output as JSON')
+    .action(async (symbol, options) => {
+      await withGraph(async (graph) => {
+        const roots = await findSymbols(graph, symbol);
+        if (roots.length === 0) {
+          console.log(chalk.yellow(`No symbol found for: ${symbol}`));
+          return;
+        }
+
+        const results = await Promise.all(roots.map(async root => ({
+          symbol: root,
+          subtypes: await graph.getSubtypes(root.qualifiedName) as Array<{ symbol: SymbolNode; type: string }>
+        })));
+
+        if (options.json) {
+          console.log(JSON.stringify(results.map(result => ({
+            qualifiedName: result.symbol.qualifiedName,
+            subtypes: result.subtypes.map(sub => ({
+              qualifiedName: sub.symbol.qualifiedName,
+              name: sub.symbol.name,
+              file: sub.symbol.file,
+              line: sub.symbol.startLine,
+              type: sub.type
+            }))
+          })), null, 2));
+          return;
+        }
+
+        const cite = await loadCitationFormatter((await findRepoRoot()) || process.cwd());
+        console.log();
+        for (const result of results) {
+          console.log(chalk.bold(`${chalk.cyan(result.symbol.qualifiedName)}:`));
+          if (result.subtypes.length === 0) {
+            console.log(chalk.gray('  Nothing extends or implements it'));
+          }
+          for (const sub of result.subtypes) {
+            console.log(chalk.white('  ▸'), chalk.yellow(sub.symbol.name), chalk.gray(sub.type));
+            console.log(chalk.gray(`    ${cite({ file: sub.symbol.file, line: sub.symbol.startLine })}`));
+          }
+          console.log();
+        }
+      });
+    });
+
+  // Subcommand: cv graph export
+  cmd
+    .command('export')
+    .description('Export the symbol and call graph')
+    .option('--format <format>', `Output format (${SYMBOL_GRAPH_FORMATS.join(', ')})`, 'dot')
+    .option('-o, --output <file>', 'Write to a file instead of stdout')
+    .option('--relations <list>', 'Edges to include (calls, inherits)', 'calls,inherits')
+    .option('--file <path>', 'Only symbols connected to files under this path')
+    .action(async (options) => {
+      const format = String(options.format).toLowerCase() as SymbolGraphFormat;
+      if (!SYMBOL_GRAPH_FORMATS.includes(format)) {
+        console.error(chalk.red(`Unknown format: ${options.format} (use ${SYMBOL_GRAPH_FORMATS.join(', ')})`));
+        process.exit(1);
+      }
+      const relations = String(options.relations).split(',').map(r => r.trim()).filter(Boolean);
+      const unknown = relations.filter(r => r !== 'calls' && r !== 'inherits');
+      if (unknown.length > 0) {
+        console.error(chalk.red(`Unknown relation: ${unknown.join(', ')} (use calls, inherits)`));
+        process.exit(1);
+      }
+
+      await withGraph(async (graph) => {
+        const symbolGraph = await collectSymbolGraph(graph, {
+          relations: relations as SymbolGraphRelation[],
+          file: options.file
+        });
+        const rendered = formatSymbolGraph(symbolGraph, format);
+
+        if (options.output) {
+          await fs.writeFile(options.output, rendered);
+          console.error(chalk.green(`✓ Wrote ${symbolGraph.nodes.length} symbols and ${symbolGraph.edges.length} edges to ${options.output}`));
+          if (format === 'dot') {
+            console.error(chalk.gray(`  Render it with: dot -Tsvg ${options.output} -o graph.svg`));
+          }
+        } else {
+          process.stdout.write(rendered);
+        }
+      });
+    });
+
   // Subcommand: cv graph imports
   cmd
     .command('imports [file]')
@@ -967,7 +1125,12 @@ async function withSemanticService(fn: (service: SemanticGraphService) => Promis
     const config = await configManager.load(repoRoot);
     const repoId = config.repository.repoId || generateRepoId(repoRoot);
     const graph = createGraphManager({ url: config.graph.url, repoId });
-    const vector = createVectorManager(config.vector.url);
+    const vector = createVectorManager({
+      url: config.vector.url,
+      ...vectorStoreOptions(config, repoRoot),
+      provider: await resolveEmbeddingProvider(config),
+      collections: config.vector.collections
+    });
 
     await graph.connect();
     await vector.connect();
@@ -992,6 +1155,18 @@ async function withSemanticService(fn: (service: SemanticGraphService) => Promis
   }
 }
 
+/**
+ * Helper: Print the call tree below `parent`, indented by depth
+ */
+function printCallTree(entries: CallTreeEntry[], parent: string, depth: number, cite: CitationFormatter): void {
+  for (const entry of entries.filter(e => e.via === parent && e.depth === depth)) {
+    const indent = '  '.repeat(depth);
+    console.log(chalk.white(`${indent}▸`), chalk.yellow(entry.symbol.name), chalk.gray(`(${entry.symbol.kind})`));
+    console.log(chalk.gray(`${indent}  ${cite({ file: entry.symbol.file, line: entry.symbol.startLine })}`));
+    printCallTree(entries, entry.symbol.qualifiedName, depth + 1, cite);
+  }
+}
+
 /**
  * Helper: Get color for symbol kind
  */
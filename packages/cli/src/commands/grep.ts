@@ -0,0 +1,191 @@
+/**
+ * cv grep command
+ * Ranked code search over the index with grep-style filters and context
+ *
+ * Candidates come from semantic search and the keyword index; --lang,
+ * --path and --symbol-kind narrow them, and --regex keeps only chunks with a
+ * matching line, so a fuzzy query ("retry with backoff") can be pinned down
+ * precisely (--regex 'sleep|delay').
+ */
+
+import { Command } from 'commander';
+import chalk from 'chalk';
+import {
+  configManager,
+  createVectorManager,
+  grepResults,
+  GrepMatch,
+  KeywordHit,
+  loadKeywordIndex,
+  loadScoreDistribution,
+  resolveMinScore
+} from '@cv-git/core';
+import { findRepoRoot, CodeChunkPayload, SymbolKind, VectorSearchResult } from '@cv-git/shared';
+import { loadCitationFormatter, CitationFormatter } from '../utils/citations.js';
+import {
+  addGlobalOptions,
+  addJsonlOption,
+  createJsonReporter,
+  createOutput,
+  ErrorCode,
+  ExitCode
+} from '../utils/output.js';
+import { resolveEmbeddingProvider } from '../utils/embedding-provider.js';
+import { vectorStoreOptions } from '../utils/vector-store.js';
+
+const SYMBOL_KINDS: SymbolKind[] = ['function', 'method', 'class', 'interface', 'type', 'variable', 'constant', 'enum', 'struct'];
+
+/** Candidates fetched per retriever for each result shown, since filters drop some */
+const CANDIDATE_FACTOR = 5;
+
+export function grepCommand(): Command {
+  const cmd = new Command('grep');
+
+  cmd
+    .description('Search indexed code by meaning and keywords, with grep-style filters and context lines')
+    .argument('<query>', 'What to look for, in natural language or identifiers')
+    .option('-e, --regex <pattern>', 'Only chunks with a line matching this regular expression')
+    .option('-i, --ignore-case', 'Case-insensitive --regex')
+    .option('--lang <language>', 'Only code in this language')
+    .option('--path <glob...>', "Only files matching these globs (e.g. 'src/compute/**'; prefix with ! to exclude)")
+    .option('--symbol-kind <kind...>', `Only chunks of these symbol kinds (${SYMBOL_KINDS.join(', ')})`)
+    .option('-C, --context <lines>', 'Context lines around each matching line', '2')
+    .option('-l, --limit <number>', 'Maximum number of chunks', '20')
+    .option('--min-score <score>', 'Minimum semantic similarity (0-1; default: retrieval.minScore, else adapted to the embedding model)');
+
+  addGlobalOptions(cmd);
+  addJsonlOption(cmd);
+
+  cmd.action(async (query: string, options) => {
+    const report = createJsonReporter('grep', options);
+    const output = createOutput(options);
+    const usageError = (message: string): never => {
+      report?.fail(message, ErrorCode.INVALID_INPUT);
+      output.error(message);
+      process.exit(ExitCode.Error);
+    };
+
+    let regex: RegExp | undefined;
+    if (options.regex) {
+      try {
+        regex = new RegExp(options.regex, options.ignoreCase ? 'i' : '');
+      } catch (error: any) {
+        usageError(`Invalid --regex: ${error.message}`);
+      }
+    }
+    const kinds: string[] = options.symbolKind ?? [];
+    const unknownKind = kinds.find(kind => !(SYMBOL_KINDS as string[]).includes(kind));
+    if (unknownKind) {
+      usageError(`Unknown --symbol-kind "${unknownKind}". Use one of: ${SYMBOL_KINDS.join(', ')}`);
+    }
+    const limit = parseInt(options.limit, 10);
+    const context = parseInt(options.context, 10);
+    if (!(limit > 0) || !(context >= 0)) {
+      usageError('--limit must be a positive number and --context zero or more');
+    }
+
+    const repoRoot = await findRepoRoot();
+    if (!repoRoot) {
+      report?.fail('Not in a CV-Git repository. Run `cv init` first.', ErrorCode.NOT_INITIALIZED);
+      output.error('Not in a CV-Git repository. Run `cv init` first.');
+      process.exit(ExitCode.Error);
+    }
+
+    const spinner = output.spinner('Searching...').start();
+    try {
+      const config = await configManager.load(repoRoot);
+      const candidates = limit * CANDIDATE_FACTOR;
+
+      // Keyword matches work without an embedding provider or vector store
+      const keywordIndex = await loadKeywordIndex(repoRoot);
+      const keyword: KeywordHit[] = keywordIndex.size > 0 ? keywordIndex.search(query, candidates) : [];
+
+      let semantic: VectorSearchResult<CodeChunkPayload>[] = [];
+      let semanticError: string | undefined;
+      try {
+        const provider = await resolveEmbeddingProvider(config);
+        const vector = createVectorManager({
+          url: config.vector.url,
+          ...vectorStoreOptions(config, repoRoot),
+          provider,
+          collections: config.vector.collections
+        });
+        await vector.connect();
+        const minScore = options.minScore !== undefined
+          ? parseFloat(options.minScore)
+          : resolveMinScore(config.retrieval, await loadScoreDistribution(repoRoot, vector.getEmbeddingInfo().model), 0.5);
+        semantic = await vector.searchCode(query, candidates, { language: options.lang?.toLowerCase(), minScore });
+        await vector.close();
+      } catch (error: any) {
+        semanticError = error.message.split('\n')[0];
+      }
+      spinner.stop();
+
+      if (keywordIndex.size === 0 && semanticError) {
+        throw new Error(`Nothing to search: no keyword index and no vector store (${semanticError}). Run \`cv sync\` first.`);
+      }
+      if (semanticError) {
+        console.error(chalk.gray(`Semantic search unavailable, keyword matches only: ${semanticError}`));
+      }
+
+      const matches = grepResults(query, semantic, keyword, {
+        language: options.lang,
+        paths: options.path,
+        symbolKinds: kinds as SymbolKind[],
+        regex
+      }, { limit, context });
+
+      if (report) {
+        matches.forEach(match => report.event('match', match));
+        report.result({ query, regex: options.regex ?? null, semantic: !semanticError, matches });
+        return;
+      }
+
+      if (matches.length === 0) {
+        console.log(chalk.yellow('No matches'));
+        console.log(chalk.gray(regex
+          ? 'The regex is applied to the best-ranked chunks only; try a broader query, a higher --limit or fewer filters.'
+          : 'Try different keywords or fewer filters.'));
+        return;
+      }
+      printMatches(matches, regex, await loadCitationFormatter(repoRoot));
+    } catch (error: any) {
+      spinner.fail(chalk.red('Search failed'));
+      report?.fail(error.message);
+      output.error(error.message, error);
+      process.exit(ExitCode.Error);
+    }
+  });
+
+  return cmd;
+}
+
+/**
+ * grep -n style: a header per chunk, `line:` for matches and `line-` for
+ * context, `--` between runs
+ */
+function printMatches(matches: GrepMatch[], regex: RegExp | undefined, cite: CitationFormatter): void {
+  const width = String(Math.max(...matches.flatMap(m => m.hunks.flat().map(l => l.line)))).length;
+  const highlight = (text: string) => regex
+    ? text.replace(new RegExp(regex.source, `${regex.flags.replace('g', '')}g`), found => chalk.red.bold(found))
+    : text;
+
+  for (const match of matches) {
+    const label = match.symbolName
+      ? `${chalk.bold(match.symbolName)} ${chalk.gray(`(${match.symbolKind ?? 'code'}, ${(match.score * 100).toFixed(0)}%)`)}`
+      : chalk.gray(`(${(match.score * 100).toFixed(0)}%)`);
+    console.log(`${chalk.cyan(cite({ file: match.file, line: match.startLine, endLine: match.endLine }))}  ${label}`);
+
+    match.hunks.forEach((hunk, i) => {
+      if (i > 0) console.log(chalk.gray('--'));
+      for (const line of hunk) {
+        const number = String(line.line).padStart(width);
+        console.log(line.match
+          ? `${chalk.green(number)}${chalk.gray(':')} ${highlight(line.text)}`
+          : chalk.gray(`${number}- ${line.text}`));
+      }
+    });
+    console.log();
+  }
+  console.log(chalk.gray(`${matches.length} match${matches.length === 1 ? '' : 'es'}`));
+}
@@ -1,16 +1,25 @@
 /**
  * cv hooks command
- * Manage git hooks for automatic knowledge graph sync
+ * Manage git hooks for automatic knowledge graph sync, AI commit messages
+ * and (opt-in) pre-commit/pre-push reviews
  */
 
 import { Command } from 'commander';
 import chalk from 'chalk';
 import { promises as fs } from 'fs';
 import * as path from 'path';
+import { execFileSync } from 'child_process';
 import { findRepoRoot } from '@cv-git/shared';
+import { REVIEW_SEVERITIES } from '@cv-git/core';
 import { addGlobalOptions } from '../utils/output.js';
-
-const HOOK_MARKER = '# CV-GIT HOOK';
+import {
+  HOOK_MARKER,
+  BYPASS_ENV,
+  CheckHookOptions,
+  preCommitHook,
+  prePushHook,
+  combineHooks
+} from '../utils/review-hooks.js';
 
 const POST_COMMIT_HOOK = `#!/bin/sh
 ${HOOK_MARKER} - DO NOT EDIT THIS LINE
@@ -93,21 +102,49 @@ export function hooksCommand(): Command {
     .option('--post-checkout', 'Only install post-checkout hook (auto-sync)')
     .option('--prepare-commit-msg', 'Only install prepare-commit-msg hook (AI commit messages)')
     .option('--ai-commit', 'Alias for --prepare-commit-msg')
-    .action(async (options: { postCommit?: boolean; postMerge?: boolean; postCheckout?: boolean; prepareCommitMsg?: boolean; aiCommit?: boolean }) => {
+    .option('--pre-commit', 'Install pre-commit hook (fast AI review of staged changes; not installed by default)')
+    .option('--pre-push', 'Install pre-push hook (fast AI review of pushed commits; not installed by default)')
+    .option('--secret-scan', 'Also block pre-commit/pre-push on secrets found by cv redact')
+    .option('--budget <seconds>', 'Seconds the pre-commit/pre-push review may take before it is skipped', '20')
+    .option('--fail-on <severity>', `Severity that blocks a commit or push (${REVIEW_SEVERITIES.join(', ')})`, 'critical')
+    .action(async (options: {
+      postCommit?: boolean;
+      postMerge?: boolean;
+      postCheckout?: boolean;
+      prepareCommitMsg?: boolean;
+      aiCommit?: boolean;
+      preCommit?: boolean;
+      prePush?: boolean;
+      secretScan?: boolean;
+      budget: string;
+      failOn: string;
+    }) => {
       try {
+        const budget = parseFloat(options.budget);
+        if (!(budget > 0)) {
+          console.error(chalk.red(`Invalid --budget "${options.budget}" (expected a number of seconds)`));
+          process.exit(1);
+        }
+        if (!(REVIEW_SEVERITIES as string[]).includes(options.failOn)) {
+          console.error(chalk.red(`Unknown --fail-on "${options.failOn}" (expected ${REVIEW_SEVERITIES.join(', ')})`));
+          process.exit(1);
+        }
+        const checks: CheckHookOptions = { budget, failOn: options.failOn, secretScan: !!options.secretScan };
+
         const repoRoot = await findRepoRoot();
         if (!repoRoot) {
           console.error(chalk.red('Not in a git repository'));
           process.exit(1);
         }
 
-        const hooksDir = path.join(repoRoot, '.git', 'hooks');
+        const hooksDir = resolveHooksDir(repoRoot);
 
         // Ensure hooks directory exists
         await fs.mkdir(hooksDir, { recursive: true });
 
         const wantsPrepareCommitMsg = options.prepareCommitMsg || options.aiCommit;
-        const installAll = !options.postCommit && !options.postMerge && !options.postCheckout && !wantsPrepareCommitMsg;
+        const installAll = !options.postCommit && !options.postMerge && !options.postCheckout && !wantsPrepareCommitMsg &&
+          !options.preCommit && !options.prePush;
         let installed = 0;
 
         // Install post-commit hook
@@ -138,6 +175,19 @@ export function hooksCommand(): Command {
           if (result) installed++;
         }
 
+        // Review hooks call the AI provider, so they are opt-in
+        if (options.preCommit) {
+          const hookPath = path.join(hooksDir, 'pre-commit');
+          const result = await installHook(hookPath, preCommitHook(checks), 'pre-commit');
+          if (result) installed++;
+        }
+
+        if (options.prePush) {
+          const hookPath = path.join(hooksDir, 'pre-push');
+          const result = await installHook(hookPath, prePushHook(checks), 'pre-push');
+          if (result) installed++;
+        }
+
         if (installed > 0) {
           console.log(chalk.green(`\n✓ Installed ${installed} hook(s)`));
           console.log(chalk.gray('\nHook behavior:'));
@@ -155,6 +205,12 @@ export function hooksCommand(): Command {
             console.log(chalk.gray('    When you run `git commit`, a message will be auto-generated'));
             console.log(chalk.gray('    Edit in your editor before confirming'));
           }
+          if (options.preCommit || options.prePush) {
+            const hooks = [options.preCommit && 'pre-commit', options.prePush && 'pre-push'].filter(Boolean).join(', ');
+            console.log(chalk.cyan(`  • ${hooks}: AI review, blocking on ${options.failOn} findings${options.secretScan ? ' and secrets' : ''}`));
+            console.log(chalk.gray(`    Skipped after ${budget}s, without an API key or when the provider is unreachable`));
+            console.log(chalk.gray(`    Bypass once with ${BYPASS_ENV}=1 (or --no-verify)`));
+          }
         } else {
           console.log(chalk.yellow('\nNo hooks installed (all already present)'));
         }
@@ -171,7 +227,9 @@ export function hooksCommand(): Command {
     .description('Remove cv-git hooks')
     .option('--prepare-commit-msg', 'Only remove prepare-commit-msg hook')
     .option('--ai-commit', 'Alias for --prepare-commit-msg')
-    .action(async (options: { prepareCommitMsg?: boolean; aiCommit?: boolean }) => {
+    .option('--pre-commit', 'Only remove pre-commit hook')
+    .option('--pre-push', 'Only remove pre-push hook')
+    .action(async (options: { prepareCommitMsg?: boolean; aiCommit?: boolean; preCommit?: boolean; prePush?: boolean }) => {
       try {
         const repoRoot = await findRepoRoot();
         if (!repoRoot) {
@@ -179,13 +237,17 @@ export function hooksCommand(): Command {
           process.exit(1);
         }
 
-        const hooksDir = path.join(repoRoot, '.git', 'hooks');
+        const hooksDir = resolveHooksDir(repoRoot);
         let removed = 0;
 
-        const wantsPrepareCommitMsgOnly = options.prepareCommitMsg || options.aiCommit;
-        const hooks = wantsPrepareCommitMsgOnly
-          ? ['prepare-commit-msg']
-          : ['post-commit', 'post-merge', 'post-checkout', 'prepare-commit-msg'];
+        const selected = [
+          (options.prepareCommitMsg || options.aiCommit) && 'prepare-commit-msg',
+          options.preCommit && 'pre-commit',
+          options.prePush && 'pre-push',
+        ].filter((hook): hook is string => !!hook);
+        const hooks = selected.length > 0
+          ? selected
+          : ['post-commit', 'post-merge', 'post-checkout', 'prepare-commit-msg', 'pre-commit', 'pre-push'];
 
         for (const hookName of hooks) {
           const hookPath = path.join(hooksDir, hookName);
@@ -218,7 +280,7 @@ export function hooksCommand(): Command {
           process.exit(1);
         }
 
-        const hooksDir = path.join(repoRoot, '.git', 'hooks');
+        const hooksDir = resolveHooksDir(repoRoot);
 
         // Check if hooks directory exists
         try {
@@ -318,7 +380,7 @@ export function hooksCommand(): Command {
           process.exit(1);
         }
 
-        const hooksDir = path.join(repoRoot, '.git', 'hooks');
+        const hooksDir = resolveHooksDir(repoRoot);
 
         console.log(chalk.bold('\nGit Hooks Status:\n'));
 
@@ -326,10 +388,12 @@ export function hooksCommand(): Command {
           'post-commit': 'auto-sync after commit',
           'post-merge': 'auto-sync after merge/pull',
           'post-checkout': 'auto-sync after branch checkout',
-          'prepare-commit-msg': 'AI commit message generation'
+          'prepare-commit-msg': 'AI commit message generation',
+          'pre-commit': 'AI review of staged changes',
+          'pre-push': 'AI review of pushed commits'
         };
 
-        for (const hookName of ['post-commit', 'post-merge', 'post-checkout', 'prepare-commit-msg']) {
+        for (const hookName of ['post-commit', 'post-merge', 'post-checkout', 'prepare-commit-msg', 'pre-commit', 'pre-push']) {
           const hookPath = path.join(hooksDir, hookName);
           const status = await getHookStatus(hookPath);
 
@@ -362,13 +426,42 @@ export function hooksCommand(): Command {
   return cmd;
 }
 
+/**
+ * Resolve the hooks directory git actually runs from. Honors core.hooksPath
+ * and worktrees (where .git is a file), falling back to .git/hooks.
+ */
+function resolveHooksDir(repoRoot: string): string {
+  try {
+    const hooksPath = execFileSync('git', ['rev-parse', '--git-path', 'hooks'], {
+      cwd: repoRoot,
+      encoding: 'utf-8',
+      stdio: ['ignore', 'pipe', 'ignore'],
+    }).trim();
+    if (hooksPath) {
+      return path.resolve(repoRoot, hooksPath);
+    }
+  } catch {
+    // Not a git checkout git can read - use the conventional location
+  }
+  return path.join(repoRoot, '.git', 'hooks');
+}
+
+/**
+ * Hooks are run by sh (Git for Windows bundles one), which rejects CRLF line
+ * endings - normalize content written or carried over from editors on Windows
+ */
+function toLF(content: string): string {
+  return content.replace(/\r\n/g, '\n');
+}
+
 /**
  * Install a hook, preserving existing non-cv hooks
  */
 async function installHook(hookPath: string, hookContent: string, hookName: string): Promise<boolean> {
   try {
     // Check if hook already exists
-    const existingContent = await fs.readFile(hookPath, 'utf-8').catch(() => null);
+    const rawContent = await fs.readFile(hookPath, 'utf-8').catch(() => null);
+    const existingContent = rawContent === null ? null : toLF(rawContent);
 
     if (existingContent) {
       // Check if it's our hook
@@ -379,11 +472,7 @@ async function installHook(hookPath: string, hookContent: string, hookName: stri
 
       // There's an existing hook that's not ours
       // Prepend our hook and call the original
-      const combinedHook = hookContent + `
-# Original hook preserved below
-${existingContent.replace(/^#!.*\n/, '')}
-`;
-      await fs.writeFile(hookPath, combinedHook, { mode: 0o755 });
+      await fs.writeFile(hookPath, combineHooks(hookContent, existingContent), { mode: 0o755 });
       console.log(chalk.green(`  ${hookName}: installed (preserved existing hook)`));
       return true;
     }
@@ -404,7 +493,8 @@ ${existingContent.replace(/^#!.*\n/, '')}
  */
 async function uninstallHook(hookPath: string, hookName: string): Promise<boolean> {
   try {
-    const existingContent = await fs.readFile(hookPath, 'utf-8').catch(() => null);
+    const rawContent = await fs.readFile(hookPath, 'utf-8').catch(() => null);
+    const existingContent = rawContent === null ? null : toLF(rawContent);
 
     if (!existingContent) {
       return false;
@@ -460,8 +550,8 @@ function getHookDescription(hookName: string): string {
     'post-commit': 'auto-sync after commit',
     'post-merge': 'auto-sync after merge/pull',
     'prepare-commit-msg': 'AI commit message generation',
-    'pre-commit': 'runs before commit',
-    'pre-push': 'runs before push',
+    'pre-commit': 'AI review of staged changes',
+    'pre-push': 'AI review of pushed commits',
     'commit-msg': 'validates commit message',
     'post-checkout': 'runs after checkout',
     'post-rewrite': 'runs after rebase/amend',
@@ -0,0 +1,105 @@
+/**
+ * cv hotspots command
+ * Rank files and functions by churn times complexity
+ *
+ * History comes from git; complexity from the last `cv sync`. Pass the
+ * results on to a review with `cv review --hotspots`.
+ */
+
+import { Command } from 'commander';
+import chalk from 'chalk';
+import Table from 'cli-table3';
+import ora from 'ora';
+import {
+  collectHotspots,
+  configManager,
+  createGitManager,
+  createGraphManager,
+  generateRepoId
+} from '@cv-git/core';
+import { findRepoRoot } from '@cv-git/shared';
+import { loadCitationFormatter } from '../utils/citations.js';
+import { addGlobalOptions, createOutput } from '../utils/output.js';
+
+export function hotspotsCommand(): Command {
+  const cmd = new Command('hotspots')
+    .description('Find risky code: files and functions that change often and are complex')
+    .option('--top <n>', 'Number of files to show', '20')
+    .option('--since <date>', 'Only mine history since this date (e.g. "12 months ago")')
+    .option('--max-commits <n>', 'Most recent commits to mine', '5000')
+    .option('--file <path>', 'Only files under this path')
+    .option('--functions', 'Also rank the functions in the hotspot files');
+
+  addGlobalOptions(cmd);
+
+  cmd.action(async (options) => {
+    const output = createOutput(options);
+    const repoRoot = await findRepoRoot();
+    if (!repoRoot) {
+      output.error('Not in a CV-Git repository. Run `cv init` first.');
+      process.exit(1);
+    }
+
+    const spinner = output.isJson ? null : ora('Mining commit history...').start();
+    try {
+      const config = await configManager.load(repoRoot);
+      const repoId = config.repository.repoId || generateRepoId(repoRoot);
+      const graph = createGraphManager({ url: config.graph.url, repoId });
+      await graph.connect();
+
+      const top = Math.max(1, parseInt(options.top, 10) || 20);
+      const result = await collectHotspots(createGitManager(repoRoot), graph, {
+        since: options.since,
+        maxCommits: Math.max(1, parseInt(options.maxCommits, 10) || 5000),
+        file: options.file
+      });
+      await graph.close();
+      spinner?.stop();
+
+      const files = result.files.slice(0, top);
+      const topFiles = new Set(files.map(file => file.file));
+      const functions = result.functions.filter(fn => topFiles.has(fn.file)).slice(0, top);
+
+      if (output.isJson) {
+        output.json({ commits: result.commits, files, functions: options.functions ? functions : undefined });
+        return;
+      }
+      if (files.length === 0) {
+        console.log(chalk.gray('No hotspots found. Hotspots need commit history and an index (`cv sync`).'));
+        return;
+      }
+
+      const cite = await loadCitationFormatter(repoRoot);
+      const table = new Table({
+        head: ['Risk', 'File', 'Commits', 'Fixes', 'Authors', 'Complexity'].map(h => chalk.cyan(h))
+      });
+      for (const file of files) {
+        const risk = file.score >= 60 ? chalk.red(file.score) : file.score >= 30 ? chalk.yellow(file.score) : chalk.green(file.score);
+        table.push([risk, cite(file.file), file.commits, file.fixes, file.authors, file.complexity]);
+      }
+      console.log();
+      console.log(chalk.bold.cyan(`Hotspots (${result.commits} commits mined):`));
+      console.log(table.toString());
+
+      if (options.functions && functions.length > 0) {
+        console.log();
+        console.log(chalk.bold.cyan('Riskiest functions:'));
+        for (const fn of functions) {
+          const cognitive = fn.cognitive !== null ? `, cognitive ${fn.cognitive}` : '';
+          console.log(`  ${chalk.yellow(String(fn.score).padStart(5))}  ${chalk.white(fn.name)} ` +
+            chalk.gray(`(cyclomatic ${fn.cyclomatic}${cognitive})`));
+          console.log(chalk.gray(`         ${cite({ file: fn.file, line: fn.startLine, endLine: fn.endLine })}`));
+        }
+      }
+
+      console.log();
+      console.log(chalk.gray('Review a change with these in mind: cv review --hotspots'));
+    } catch (error: any) {
+      spinner?.fail('Failed to compute hotspots');
+      output.error(error.message, error);
+      process.exit(1);
+    }
+  });
+
+  return cmd;
+}
@@ -15,7 +15,8 @@ import {
 } from '@cv-git/core';
 import { findRepoRoot, getCVDir } from '@cv-git/shared';
 import { addGlobalOptions, createOutput } from '../utils/output.js';
-import { getEmbeddingCredentials } from '../utils/credentials.js';
+import { resolveEmbeddingProvider } from '../utils/embedding-provider.js';
+import { vectorStoreOptions } from '../utils/vector-store.js';
 
 interface PRDExportManifest {
   version: string;
@@ -252,15 +253,10 @@ export function importCommand(): Command {
         spinner = output.spinner('Setting up vector embeddings...').start();
 
         try {
-          const embeddingCreds = await getEmbeddingCredentials({
-            openRouterKey: config.embedding?.apiKey,
-            openaiKey: config.ai?.apiKey
-          });
-
           const vector = createVectorManager({
             url: config.vector.url,
-            openrouterApiKey: embeddingCreds.openrouterApiKey,
-            openaiApiKey: embeddingCreds.openaiApiKey,
+            ...vectorStoreOptions(config, repoRoot),
+            provider: await resolveEmbeddingProvider(config),
             collections: config.vector.collections
           });
 
@@ -0,0 +1,171 @@
+/**
+ * cv lsp command
+ * Language server for in-editor semantic search, explanations and review
+ *
+ * Editors start `cv lsp` and talk to it over stdin/stdout (see lsp/server.ts
+ * for what it offers). Set `reviewOnSave: false` in the client's
+ * initialization options to review only on demand.
+ */
+
+import { Command } from 'commander';
+import * as path from 'path';
+import {
+  configManager,
+  createAIManager,
+  createVectorManager,
+  createGraphManager,
+  createGitManager,
+  createSummaryCache,
+  loadScoreDistribution,
+  resolveMinScore,
+  loadReviewPolicies,
+  applicablePolicies,
+  splitDiff,
+  ReviewFinding,
+  loadPromptTemplates
+} from '@cv-git/core';
+import { findRepoRoot } from '@cv-git/shared';
+import { getAnthropicApiKey } from '../utils/credentials.js';
+import { createModelClient } from '../utils/model-client.js';
+import { resolveEmbeddingProvider } from '../utils/embedding-provider.js';
+import { vectorStoreOptions } from '../utils/vector-store.js';
+import { applyReranker } from '../utils/reranker.js';
+import { applyQueryExpansion } from '../utils/query-expansion.js';
+import { encodeMessage, MessageReader } from '../lsp/protocol.js';
+import { LspBackend, LspServer } from '../lsp/server.js';
+
+export function lspCommand(): Command {
+  const cmd = new Command('lsp')
+    .description('Language server: semantic workspace symbols, "Explain with cv" and review diagnostics')
+    .option('-m, --model <model>', 'Model for explanations and reviews, optionally as provider/model')
+    .option('--stdio', 'Talk over stdin/stdout (the default; accepted for editors that pass it)');
+
+  cmd.action(async (options) => {
+    // stdout carries the protocol; keep library progress output off it
+    console.log = console.error;
+    console.info = console.error;
+
+    const server = new LspServer({
+      createBackend: rootPath => createBackend(rootPath, options.model),
+      resolveRoot: folder => findRepoRoot(folder),
+      send: message => process.stdout.write(encodeMessage(message)),
+      onExit: code => process.exit(code),
+    });
+
+    const reader = new MessageReader(error => console.error(`cv lsp: ${error.message}`));
+    process.stdin.on('data', (chunk: Buffer) => {
+      for (const message of reader.push(chunk)) void server.handle(message);
+    });
+    // The editor went away without `exit`
+    process.stdin.on('end', () => process.exit(1));
+  });
+
+  return cmd;
+}
+
+/**
+ * Connect the index, graph and model for the workspace the editor opened
+ */
+async function createBackend(repoRoot: string, model?: string): Promise<LspBackend> {
+  if (!(await findRepoRoot(repoRoot))) {
+    throw new Error(`${repoRoot} is not a CV-Git repository; run \`cv init\` and \`cv sync\` first`);
+  }
+
+  const config = await configManager.load(repoRoot);
+  const chatClient = model || config.ai.fallback
+    ? await createModelClient(model, {
+        provider: 'anthropic',
+        model: config.ai.model,
+        anthropicApiKey: config.ai.apiKey,
+        fallback: config.ai.fallback
+      })
+    : undefined;
+  const anthropicApiKey = await getAnthropicApiKey(config.ai.apiKey);
+
+  let vector = undefined;
+  const embeddingProvider = await resolveEmbeddingProvider(config).catch(() => undefined);
+  if (embeddingProvider && config.vector) {
+    try {
+      vector = createVectorManager({
+        url: config.vector.url,
+        ...vectorStoreOptions(config, repoRoot),
+        provider: embeddingProvider,
+        collections: config.vector.collections
+      });
+      await vector.connect();
+    } catch (error: any) {
+      console.error(`cv lsp: could not connect to the vector store, semantic search is off (${error.message})`);
+      vector = undefined;
+    }
+  }
+
+  const graph = createGraphManager(config.graph.url, config.graph.database);
+  await graph.connect();
+  const git = createGitManager(repoRoot);
+
+  const ai = createAIManager(
+    {
+      provider: 'anthropic',
+      model: config.ai.model,
+      apiKey: anthropicApiKey ?? '',
+      chatClient,
+      retrieval: config.retrieval
+    },
+    vector,
+    graph,
+    git
+  );
+  ai.setPromptTemplates(await loadPromptTemplates(repoRoot, config.repository?.name));
+  applyReranker(ai, config);
+  applyQueryExpansion(ai, config, 'lsp');
+  const distribution = await loadScoreDistribution(repoRoot, vector?.getEmbeddingInfo().model);
+  ai.setScoreDistribution(distribution);
+  ai.setSummaryCache(createSummaryCache(repoRoot));
+
+  const requireModel = () => {
+    if (!anthropicApiKey && !chatClient) {
+      throw new Error('Anthropic API key not found; run `cv auth setup anthropic` or pass --model');
+    }
+  };
+
+  // Saving without edits should not pay for the same review twice
+  let lastReview: { diff: string; findings: ReviewFinding[] } | null = null;
+
+  return {
+    async search(query, limit) {
+      if (!vector) {
+        throw new Error('Semantic search needs the vector index; run `cv sync`');
+      }
+      const results = await vector.searchCode(query, limit, {
+        minScore: resolveMinScore(config.retrieval, distribution, 0.5)
+      });
+      return results.map(result => ({
+        name: result.payload.symbolName ?? `${path.basename(result.payload.file)}:${result.payload.startLine}`,
+        kind: result.payload.symbolKind,
+        file: result.payload.file,
+        startLine: result.payload.startLine,
+        endLine: result.payload.endLine
+      }));
+    },
+
+    async explain(target) {
+      requireModel();
+      return ai.explain(target);
+    },
+
+    async review(files) {
+      const diff = await git.getRawDiff(['HEAD', '--', ...files]);
+      if (!diff.trim()) return [];
+      if (lastReview?.diff === diff) return lastReview.findings;
+
+      requireModel();
+      const policies = applicablePolicies(
+        await loadReviewPolicies(repoRoot, config.review?.policies),
+        splitDiff(diff).map(section => section.file)
+      );
+      const review = await ai.reviewChanges(diff, undefined, { policies });
+      lastReview = { diff, findings: review.findings };
+      return review.findings;
+    }
+  };
+}
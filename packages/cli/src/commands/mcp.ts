@@ -0,0 +1,65 @@
+/**
+ * cv mcp command
+ * Serve the repository index to editors and agents over the Model Context Protocol
+ *
+ * `cv mcp serve` offers semantic_search, get_symbol, get_callers,
+ * get_file_chunk and repo_stats (`--all` adds the full cv_* tool set) over
+ * stdio, for clients that spawn the server (Claude Desktop, Zed), or over
+ * HTTP with server-sent events (`--sse`) for clients that connect to one.
+ */
+
+import { Command } from 'commander';
+import chalk from 'chalk';
+import * as path from 'path';
+import { findRepoRoot } from '@cv-git/shared';
+
+export function mcpCommand(): Command {
+  const cmd = new Command('mcp')
+    .description('Model Context Protocol server for editors and agents');
+
+  cmd
+    .command('serve')
+    .description('Serve the repository index as MCP tools (stdio by default)')
+    .option('--sse', 'Serve over HTTP with server-sent events instead of stdio')
+    .option('--port <port>', 'Port for --sse', '3333')
+    .option('--host <host>', 'Address for --sse to bind (use 0.0.0.0 to allow other machines)', '127.0.0.1')
+    .option('--all', 'Offer every cv-git tool, including ones that change code or call AI providers')
+    .option('--repo <path>', 'Repository to serve (default: the current one)')
+    .action(async (options) => {
+      // On stdio, stdout carries the protocol: everything else goes to stderr
+      try {
+        if (options.repo) process.chdir(path.resolve(options.repo));
+        const repoRoot = await findRepoRoot();
+        if (!repoRoot) {
+          console.error(chalk.red('Not in a CV-Git repository'));
+          console.error(chalk.gray('Run `cv init` first, or pass --repo <path>'));
+          process.exit(1);
+        }
+
+        const port = parseInt(options.port, 10);
+        if (options.sse && !(port > 0 && port < 65536)) {
+          console.error(chalk.red(`Invalid --port "${options.port}"`));
+          process.exit(1);
+        }
+
+        const { serve } = await import('@cv-git/mcp-server/server');
+        await serve({
+          transport: options.sse ? 'sse' : 'stdio',
+          port,
+          host: options.host,
+          indexOnly: !options.all,
+        });
+
+        if (options.sse) {
+          console.error(chalk.green(`✓ MCP server for ${repoRoot}`));
+          console.error(`  ${chalk.cyan(`http://${options.host}:${port}/sse`)}`);
+          console.error(chalk.gray('  Press Ctrl+C to stop'));
+        }
+      } catch (error: any) {
+        console.error(chalk.red(`Error: ${error.message}`));
+        process.exit(1);
+      }
+    });
+
+  return cmd;
+}
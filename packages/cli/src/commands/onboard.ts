@@ -0,0 +1,189 @@
+/**
+ * cv onboard command
+ * A guided tour of the repository for new contributors
+ *
+ * Modules, entry points, calls between modules and a reading order come from
+ * the tracked files, the symbol graph and git history (see ai/onboard.ts);
+ * the model adds an overview, each module's responsibility and a data flow
+ * narrative. The tour is printed as markdown, written to a file, or walked
+ * through section by section with --tui.
+ */
+
+import { Command } from 'commander';
+import chalk from 'chalk';
+import ora from 'ora';
+import { promises as fs } from 'fs';
+import * as path from 'path';
+import * as readline from 'readline';
+import {
+  applyOnboardResponse,
+  buildOnboardPrompt,
+  buildRepoTour,
+  configManager,
+  createGitManager,
+  createGraphManager,
+  formatTourMarkdown,
+  generateRepoId,
+  loadCodebaseSummary,
+  loadTourGraph,
+  ONBOARD_SYSTEM_PROMPT,
+  RepoTour,
+  TourGraph,
+  TourSection,
+  tourSections
+} from '@cv-git/core';
+import { findRepoRoot } from '@cv-git/shared';
+import { createModelClient, fallbackNotice } from '../utils/model-client.js';
+import { Walkthrough } from '../tui/walkthrough.js';
+
+/** Commits read for churn, authors and recent activity */
+const HISTORY_COMMITS = 2000;
+
+export function onboardCommand(): Command {
+  const cmd = new Command('onboard');
+
+  cmd
+    .description('Generate a repo tour: modules, entry points, data flow and the first files to read')
+    .option('--tui', 'Walk through the tour section by section in the terminal')
+    .option('-o, --output <file>', 'Write the markdown tour to a file')
+    .option('--since <date>', 'History to read for activity and authors (e.g. "6 months ago")', '1 year ago')
+    .option('--max-modules <n>', 'Most modules to describe', '12')
+    .option('--no-ai', 'Only the facts from the index and history, without model-written descriptions')
+    .option('-m, --model <model>', 'Model: a Claude model or provider/model (e.g. ollama/qwen2.5-coder:14b)');
+
+  cmd.action(async (options) => {
+    if (options.tui && (!process.stdin.isTTY || !process.stdout.isTTY)) {
+      console.error(chalk.red('cv onboard --tui needs an interactive terminal'));
+      process.exit(1);
+    }
+    const spinner = ora('Reading the index and history...').start();
+
+    const repoRoot = await findRepoRoot();
+    if (!repoRoot) {
+      spinner.fail(chalk.red('Not in a CV-Git repository'));
+      console.error(chalk.gray('Run `cv init` first'));
+      process.exit(1);
+    }
+
+    try {
+      const config = await configManager.load(repoRoot);
+      const git = createGitManager(repoRoot);
+      const files = await git.getTrackedFiles();
+      const commits = await git.getChurnHistory({ since: options.since, maxCount: HISTORY_COMMITS }).catch(() => []);
+
+      let tourGraph: TourGraph = { symbols: [], calls: [] };
+      try {
+        const graph = createGraphManager({ url: config.graph.url, repoId: config.repository.repoId || generateRepoId(repoRoot) });
+        await graph.connect();
+        tourGraph = await loadTourGraph(graph).finally(() => graph.close());
+      } catch (error: any) {
+        spinner.warn(`Symbol graph unavailable (${error.message}); the tour will have no call flows`);
+        spinner.start('Reading history...');
+      }
+
+      // Module descriptions from `cv summary`, when one was generated
+      const summary = await loadCodebaseSummary(repoRoot).catch(() => null);
+      const descriptions: Record<string, string> = {};
+      for (const module of summary?.architecture.coreModules ?? []) {
+        if (module.description) descriptions[module.path.replace(/^\.\//, '').replace(/\/$/, '')] = module.description;
+      }
+
+      let tour: RepoTour = buildRepoTour({
+        files,
+        graph: tourGraph,
+        commits,
+        descriptions,
+        maxModules: Math.max(1, parseInt(options.maxModules, 10) || 12)
+      });
+      if (tour.modules.length === 0) {
+        spinner.fail(chalk.red('No source files found'));
+        process.exit(1);
+      }
+      spinner.succeed(`${tour.modules.length} module(s), ${tour.entryPoints.length} entry point(s), ${tour.flows.length} cross-module flow(s)`);
+      if (summary?.naturalLanguageSummary) tour.overview = summary.naturalLanguageSummary;
+
+      if (options.ai !== false) {
+        const step = ora('Writing the tour...').start();
+        try {
+          const client = await createModelClient(options.model, {
+            provider: 'anthropic',
+            model: config.ai.model,
+            anthropicApiKey: config.ai.apiKey,
+            fallback: config.ai.fallback
+          });
+          const readme = await readReadme(repoRoot, files);
+          const response = await client.chat(
+            [{ role: 'user', content: buildOnboardPrompt(tour, readme) }],
+            ONBOARD_SYSTEM_PROMPT
+          );
+          tour = applyOnboardResponse(tour, response);
+          step.succeed('Tour written');
+          const notice = fallbackNotice(client);
+          if (notice) console.error(chalk.gray(notice));
+        } catch (error: any) {
+          step.warn(`No model-written descriptions (${error.message}); pass --no-ai to skip the model`);
+        }
+      }
+
+      const name = path.basename(repoRoot);
+      if (options.output) {
+        await fs.writeFile(path.resolve(process.cwd(), options.output), formatTourMarkdown(tour, name));
+        console.log(chalk.green(`✓ Tour written to ${options.output}`));
+      }
+      if (options.tui) {
+        runWalkthrough(tourSections(tour, name));
+      } else if (!options.output) {
+        console.log();
+        console.log(formatTourMarkdown(tour, name));
+      }
+    } catch (error: any) {
+      spinner.fail(chalk.red('Onboarding tour failed'));
+      console.error(chalk.red(error.message));
+      process.exit(1);
+    }
+  });
+
+  return cmd;
+}
+
+async function readReadme(repoRoot: string, files: string[]): Promise<string | undefined> {
+  const readme = files.find(file => /^readme(\.md|\.rst|\.txt)?$/i.test(file));
+  return readme ? fs.readFile(path.join(repoRoot, readme), 'utf-8').catch(() => undefined) : undefined;
+}
+
+/**
+ * Show the walkthrough on the alternate screen until it is quit
+ */
+function runWalkthrough(sections: TourSection[]): void {
+  const out = process.stdout;
+  const stdin = process.stdin;
+
+  let restored = false;
+  const restore = () => {
+    if (restored) return;
+    restored = true;
+    stdin.setRawMode(false);
+    // Show the cursor and leave the alternate screen
+    out.write('\x1b[?25h\x1b[?1049l');
+  };
+  // No newline after the last row, which would scroll the screen
+  const draw = () => out.write(`\x1b[H${view.render(out.columns, out.rows).join('\r\n')}`);
+
+  const view = new Walkthrough({
+    sections,
+    onChange: draw,
+    onQuit: () => {
+      restore();
+      process.exit(0);
+    }
+  });
+
+  out.write('\x1b[?1049h\x1b[?25l');
+  readline.emitKeypressEvents(stdin);
+  stdin.setRawMode(true);
+  stdin.resume();
+  stdin.on('keypress', (input: string | undefined, key) => view.key(input, key ?? {}));
+  out.on('resize', draw);
+  process.on('exit', restore);
+  draw();
+}
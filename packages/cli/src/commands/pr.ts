@@ -2,6 +2,10 @@
  * cv pr - Pull Request Management Command
  *
  * Create, list, and manage pull requests using platform adapters.
+ * The generated changelog lives between marker comments in the PR body, so
+ * `cv pr update` (e.g. from CI on every push) refreshes it in place; the
+ * `cv pr describe` summary works the same way. `cv pr review` runs the
+ * review engine on the PR's diff and can post findings as inline comments.
  */
 
 import { Command } from 'commander';
@@ -16,10 +20,82 @@ import {
   PullRequestState,
   type PullRequest,
 } from '@cv-git/platform';
-import { getConfig } from '../config.js';
+import {
+  applicablePolicies,
+  behaviorFiles,
+  clusterChanges,
+  configManager,
+  createAIManager,
+  detectApiChanges,
+  filterDiff,
+  formatDiffSummaryMarkdown,
+  groupFindings,
+  hasFindingsAtOrAbove,
+  loadPackages,
+  loadReviewPolicies,
+  planPullRequestReview,
+  postedFindingIds,
+  rankRiskyFiles,
+  splitDiff,
+  REVIEW_SEVERITIES,
+} from '@cv-git/core';
+import { findRepoRoot } from '@cv-git/shared';
+import { getConfig, toAdapterConfig } from '../config.js';
+import {
+  buildChangelogSection,
+  buildSummarySection,
+  upsertGeneratedSection,
+  detectCIPullRequest,
+  PR_SUMMARY_START,
+  PR_SUMMARY_END,
+} from '../utils/pr-description.js';
+import { getAnthropicApiKey } from '../utils/credentials.js';
+import { createModelClient, fallbackNotice } from '../utils/model-client.js';
 
 const git = simpleGit();
 
+/**
+ * AI manager for the repository's configured model (or `--model`)
+ */
+async function createReviewAI(repoRoot: string, model?: string) {
+  const config = await configManager.load(repoRoot);
+  const chatClient = model || config.ai.fallback
+    ? await createModelClient(model, {
+        provider: 'anthropic',
+        model: config.ai.model,
+        anthropicApiKey: config.ai.apiKey,
+        fallback: config.ai.fallback,
+      })
+    : undefined;
+  const anthropicApiKey = await getAnthropicApiKey(config.ai.apiKey);
+  if (!anthropicApiKey && !chatClient) {
+    throw new Error('Anthropic API key not found. Run: cv auth setup anthropic');
+  }
+  const ai = createAIManager({
+    provider: 'anthropic',
+    model: config.ai.model,
+    apiKey: anthropicApiKey ?? '',
+    chatClient,
+  });
+  return { ai, config, chatClient };
+}
+
+/**
+ * Platform adapter with the review operations, or an error naming the platform
+ */
+async function reviewPlatform() {
+  const config = await getConfig().load();
+  const credentials = new CredentialManager();
+  await credentials.init();
+
+  const platform = createPlatformAdapter(toAdapterConfig(config.platform), credentials);
+  await platform.init();
+  if (!platform.getPRDiff) {
+    throw new Error(`Pull request diffs are not supported on ${platform.getPlatformName()} yet`);
+  }
+  return platform;
+}
+
 export function prCommand(): Command {
   const cmd = new Command('pr').description('Create, list, view, and merge pull requests');
 
@@ -101,9 +177,9 @@ export function prCommand(): Command {
               type: 'editor',
               name: 'inputBody',
               message: 'PR description (opens editor):',
-              default: `## Summary\n\n<!-- Describe your changes -->\n\n## Changes\n${log.all
-                .map((c: any) => `- ${c.message}`)
-                .join('\n')}\n`,
+              default: `## Summary\n\n<!-- Describe your changes -->\n\n${buildChangelogSection(
+                log.all.map((c) => ({ hash: c.hash, message: c.message }))
+              )}\n`,
             },
           ]);
           body = inputBody;
@@ -114,7 +190,7 @@ export function prCommand(): Command {
         const credentials = new CredentialManager();
         await credentials.init();
 
-        const platform = createPlatformAdapter(config.platform, credentials);
+        const platform = createPlatformAdapter(toAdapterConfig(config.platform), credentials);
         await platform.init();
 
         // Create PR
@@ -139,6 +215,239 @@ export function prCommand(): Command {
       }
     });
 
+  // cv pr update
+  cmd
+    .command('update [number]')
+    .description('Refresh the generated changelog section of a PR body')
+    .option('--ci', 'Non-interactive: detect the PR from the CI environment, exit quietly if none')
+    .option('--dry-run', 'Print the new body without updating the PR')
+    .action(async (number: string | undefined, options) => {
+      try {
+        const config = await getConfig().load();
+        const credentials = new CredentialManager();
+        await credentials.init();
+
+        const platform = createPlatformAdapter(toAdapterConfig(config.platform), credentials);
+        await platform.init();
+
+        // Explicit number, then the CI pipeline's PR, then the open PR for this branch
+        let prNumber = number ? parseInt(number, 10) : options.ci ? detectCIPullRequest() : undefined;
+        if (prNumber === undefined) {
+          const head = await git.revparse(['--abbrev-ref', 'HEAD']);
+          const open = await platform.listPRs({ state: PullRequestState.OPEN, limit: 100 });
+          prNumber = open.find((p) => p.head === head)?.number;
+        }
+        if (prNumber === undefined) {
+          if (options.ci) {
+            console.log(chalk.gray('No open pull request for this build; nothing to update.'));
+            return;
+          }
+          console.log(chalk.yellow('\n⚠️  No open pull request found for this branch.\n'));
+          return;
+        }
+
+        const pr = await platform.getPR(prNumber);
+        if (pr.state !== PullRequestState.OPEN) {
+          console.log(chalk.gray(`PR #${pr.number} is ${pr.state}; leaving its description alone.`));
+          return;
+        }
+
+        // CI checkouts are often shallow; make sure the base is available to diff against
+        await git.fetch('origin', pr.base).catch(() => undefined);
+        const log = await git.log({ from: `origin/${pr.base}`, to: 'HEAD', '--no-merges': null });
+
+        const body = upsertGeneratedSection(
+          pr.body || '',
+          buildChangelogSection(log.all.map((c) => ({ hash: c.hash, message: c.message })))
+        );
+
+        if (options.dryRun) {
+          console.log(body);
+          return;
+        }
+        if (body === (pr.body || '')) {
+          console.log(chalk.gray(`PR #${pr.number} description already up to date.`));
+          return;
+        }
+
+        const spinner = ora(`Updating PR #${pr.number}...`).start();
+        await platform.updatePR(pr.number, { body });
+        spinner.succeed(chalk.green(`Updated changelog for PR #${pr.number} (${log.all.length} commits)`));
+      } catch (error: any) {
+        console.error(chalk.red(`\n❌ Failed to update PR: ${error.message}\n`));
+        if (options.ci) process.exitCode = 1;
+      }
+    });
+
+  // cv pr review
+  cmd
+    .command('review <number>')
+    .description('Review a pull request with AI, optionally posting findings as inline comments')
+    .option('-m, --model <model>', 'Model to use, optionally as provider/model')
+    .option('--files <glob...>', 'Only review files matching these globs')
+    .option('--no-policies', 'Skip house rules from the review policy file')
+    .option('--post', 'Post the review to the PR (inline comments on lines in the diff)')
+    .option('--fail-on <severity>', `Exit with code 1 if any finding is at or above this severity (${REVIEW_SEVERITIES.join(', ')}, none)`, 'none')
+    .option('--json', 'Output the review as JSON')
+    .action(async (number: string, options) => {
+      if (options.failOn !== 'none' && !(REVIEW_SEVERITIES as string[]).includes(options.failOn)) {
+        console.error(chalk.red(`Unknown --fail-on "${options.failOn}" (expected ${REVIEW_SEVERITIES.join(', ')} or none)`));
+        process.exit(1);
+      }
+      const spinner = ora({ text: 'Fetching pull request...', isSilent: options.json }).start();
+      try {
+        const repoRoot = await findRepoRoot();
+        if (!repoRoot) {
+          spinner.fail(chalk.red('Not in a CV-Git repository. Run `cv init` first.'));
+          process.exit(1);
+        }
+
+        const platform = await reviewPlatform();
+        if (options.post && !platform.createPRReview) {
+          throw new Error(`Posting reviews is not supported on ${platform.getPlatformName()} yet`);
+        }
+        const prNumber = parseInt(number, 10);
+        const pr = await platform.getPR(prNumber);
+        const diff = filterDiff(await platform.getPRDiff!(prNumber), options.files);
+        if (!diff.trim()) {
+          spinner.warn(chalk.yellow(`PR #${pr.number} has no changes to review`));
+          return;
+        }
+
+        const { ai, config, chatClient } = await createReviewAI(repoRoot, options.model);
+        const policies = options.policies === false
+          ? []
+          : applicablePolicies(
+            await loadReviewPolicies(repoRoot, config.review?.policies),
+            splitDiff(diff).map((section) => section.file)
+          );
+
+        spinner.text = `Reviewing PR #${pr.number} (${splitDiff(diff).length} files)...`;
+        const review = await ai.reviewChanges(diff, undefined, { policies });
+        spinner.succeed(`Reviewed PR #${pr.number}: ${pr.title}`);
+
+        // Findings already posted by an earlier run stay out of this one
+        const posted = options.post && platform.listPRComments
+          ? postedFindingIds((await platform.listPRComments(prNumber)).map((c) => c.body))
+          : new Set<string>();
+        const plan = planPullRequestReview(review, diff, posted);
+        const failed = options.failOn !== 'none' && hasFindingsAtOrAbove(review.findings, options.failOn);
+
+        if (options.json && !options.post) {
+          console.log(JSON.stringify({ number: pr.number, ...review, comments: plan.comments.length }, null, 2));
+          if (failed) process.exitCode = 1;
+          return;
+        }
+
+        if (!options.json) {
+          console.log();
+          console.log(review.summary);
+          console.log();
+          for (const group of groupFindings(review.findings)) {
+            console.log(chalk.bold(`${group.severity.toUpperCase()} (${group.findings.length})`));
+            for (const finding of group.findings) {
+              console.log(`  ${chalk.cyan(`${finding.file}:${finding.line}`)}  ${finding.title}`);
+            }
+          }
+          const notice = fallbackNotice(chatClient);
+          if (notice) console.log(chalk.gray(notice));
+        }
+
+        if (options.post) {
+          if (plan.comments.length === 0 && plan.skipped.length > 0 && plan.skipped.length === review.findings.length) {
+            if (!options.json) console.log(chalk.gray(`\nAll ${plan.skipped.length} findings are already on PR #${pr.number}.`));
+          } else {
+            const postSpinner = ora({ text: 'Posting review...', isSilent: options.json }).start();
+            const submitted = await platform.createPRReview!(prNumber, {
+              body: plan.body,
+              comments: plan.comments,
+              commitSha: pr.headSha,
+            });
+            postSpinner.succeed(chalk.green(`Posted review with ${submitted.comments} inline comment${submitted.comments === 1 ? '' : 's'}`));
+            if (options.json) {
+              console.log(JSON.stringify({ number: pr.number, ...review, comments: submitted.comments, url: submitted.url }, null, 2));
+            } else {
+              console.log(chalk.gray(`  ${submitted.url}`));
+            }
+          }
+        } else if (!options.json && review.findings.length > 0) {
+          console.log(chalk.gray(`\nPost as inline comments: cv pr review ${pr.number} --post`));
+        }
+        console.log();
+
+        if (failed) process.exitCode = 1;
+      } catch (error: any) {
+        spinner.fail(chalk.red(`Failed to review PR: ${error.message}`));
+        process.exitCode = 1;
+      }
+    });
+
+  // cv pr describe
+  cmd
+    .command('describe <number>')
+    .description('Generate a summary of a pull request and put it in the description')
+    .option('-m, --model <model>', 'Model to use, optionally as provider/model')
+    .option('--dry-run', 'Print the new body without updating the PR')
+    .action(async (number: string, options) => {
+      const spinner = ora('Fetching pull request...').start();
+      try {
+        const repoRoot = await findRepoRoot();
+        if (!repoRoot) {
+          spinner.fail(chalk.red('Not in a CV-Git repository. Run `cv init` first.'));
+          process.exit(1);
+        }
+
+        const platform = await reviewPlatform();
+        const prNumber = parseInt(number, 10);
+        const pr = await platform.getPR(prNumber);
+        const diff = await platform.getPRDiff!(prNumber);
+        if (!diff.trim()) {
+          spinner.warn(chalk.yellow(`PR #${pr.number} has no changes to describe`));
+          return;
+        }
+        const commits = await platform.getCommits(pr.base, pr.head).catch(() => []);
+
+        // Same summary as `cv diff-summary`, from the PR's diff
+        const { ai, chatClient } = await createReviewAI(repoRoot, options.model);
+        const clusters = clusterChanges(diff, [], await loadPackages(repoRoot));
+        const apiChanges = detectApiChanges(diff);
+        const range = `${pr.base}...${pr.head}`;
+        const { overview } = await ai.summarizeChanges(range, clusters, apiChanges, (cluster, index) => {
+          spinner.text = `Summarizing ${cluster.area} (${index + 1}/${clusters.length})...`;
+        });
+        const markdown = formatDiffSummaryMarkdown({
+          range,
+          commits: commits.length || pr.commits || 0,
+          overview,
+          clusters,
+          apiChanges,
+          behaviorFiles: behaviorFiles(clusters),
+          risky: rankRiskyFiles(clusters, apiChanges),
+        });
+        spinner.stop();
+
+        const body = upsertGeneratedSection(pr.body || '', buildSummarySection(markdown), {
+          start: PR_SUMMARY_START,
+          end: PR_SUMMARY_END,
+          prepend: true,
+        });
+
+        if (options.dryRun) {
+          console.log(body);
+          return;
+        }
+        const updateSpinner = ora(`Updating PR #${pr.number}...`).start();
+        await platform.updatePR(pr.number, { body });
+        updateSpinner.succeed(chalk.green(`Updated the description of PR #${pr.number}`));
+        console.log(chalk.gray(`  ${pr.url}`));
+        const notice = fallbackNotice(chatClient);
+        if (notice) console.log(chalk.gray(notice));
+      } catch (error: any) {
+        spinner.fail(chalk.red(`Failed to describe PR: ${error.message}`));
+        process.exitCode = 1;
+      }
+    });
+
   // cv pr list
   cmd
     .command('list')
@@ -151,7 +460,7 @@ export function prCommand(): Command {
         const credentials = new CredentialManager();
         await credentials.init();
 
-        const platform = createPlatformAdapter(config.platform, credentials);
+        const platform = createPlatformAdapter(toAdapterConfig(config.platform), credentials);
         await platform.init();
 
         const spinner = ora('Fetching pull requests...').start();
@@ -217,7 +526,7 @@ export function prCommand(): Command {
         const credentials = new CredentialManager();
         await credentials.init();
 
-        const platform = createPlatformAdapter(config.platform, credentials);
+        const platform = createPlatformAdapter(toAdapterConfig(config.platform), credentials);
         await platform.init();
 
         const spinner = ora('Fetching pull request...').start();
@@ -291,7 +600,7 @@ export function prCommand(): Command {
         const credentials = new CredentialManager();
         await credentials.init();
 
-        const platform = createPlatformAdapter(config.platform, credentials);
+        const platform = createPlatformAdapter(toAdapterConfig(config.platform), credentials);
         await platform.init();
 
         const { confirm } = await inquirer.prompt([
@@ -30,6 +30,7 @@ import {
   GraphManager,
 } from '@cv-git/core';
 import { discoverCvPrd } from '../utils/services.js';
+import { vectorStoreOptions } from '../utils/vector-store.js';
 
 /**
  * Find git repository root by looking for .git directory
@@ -605,16 +606,11 @@ export function createPRDCommand(): Command {
           // Graph/Vector mode - search imported PRD chunks in Qdrant
           spinner.text = 'Searching imported requirements...';
 
-          const { getEmbeddingCredentials } = await import('../utils/credentials.js');
-          const embeddingCreds = await getEmbeddingCredentials({
-            openRouterKey: config.embedding?.apiKey,
-            openaiKey: config.ai?.apiKey
-          });
-
+          const { resolveEmbeddingProvider } = await import('../utils/embedding-provider.js');
           const vector = createVectorManager({
             url: config.vector.url,
-            openrouterApiKey: embeddingCreds.openrouterApiKey,
-            openaiApiKey: embeddingCreds.openaiApiKey,
+            ...vectorStoreOptions(config, repoRoot),
+            provider: await resolveEmbeddingProvider(config),
             collections: config.vector.collections
           });
 
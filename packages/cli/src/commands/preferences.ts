@@ -62,6 +62,8 @@ export function preferencesCommand(): Command {
         lmstudio: 'LM Studio (Local)',
         openai: 'OpenAI',
         openrouter: 'OpenRouter',
+        azure: 'Azure OpenAI',
+        local: 'Local (In-process)',
       };
 
       const table = new Table({
@@ -170,9 +172,9 @@ export function preferencesCommand(): Command {
 
         case 'embedding-provider':
         case 'embeddingProvider':
-          if (!['openai', 'openrouter'].includes(value)) {
+          if (!['openai', 'openrouter', 'ollama', 'lmstudio', 'azure', 'local'].includes(value)) {
             console.log(chalk.red(`Invalid embedding provider: ${value}`));
-            console.log(chalk.gray('Valid options: openai, openrouter'));
+            console.log(chalk.gray('Valid options: openai, openrouter, ollama, lmstudio, azure, local'));
             return;
           }
           prefs.embeddingProvider = value as EmbeddingProvider;
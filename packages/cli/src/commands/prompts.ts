@@ -0,0 +1,149 @@
+/**
+ * cv prompts command
+ * Inspect the repository's prompt templates in `.cv/prompts/`
+ *
+ * `cv prompts list` shows which templates are loaded; `cv prompts show
+ * <name>` renders the system prompt and prompt a command would send, with
+ * keyword-matched context, without calling a model.
+ */
+
+import { Command } from 'commander';
+import chalk from 'chalk';
+import {
+  configManager,
+  createAIManager,
+  createCommitAnalyzer,
+  createGitManager,
+  loadKeywordIndex,
+  loadPromptTemplates,
+  PROMPT_TEMPLATE_NAMES,
+  PROMPTS_DIR,
+  PromptTemplateName
+} from '@cv-git/core';
+import { findRepoRoot, getCVDir } from '@cv-git/shared';
+import * as path from 'path';
+import { addGlobalOptions, createOutput } from '../utils/output.js';
+
+async function requireRepoRoot(): Promise<string> {
+  const repoRoot = await findRepoRoot();
+  if (!repoRoot) {
+    console.error(chalk.red('Not in a CV-Git repository'));
+    console.error(chalk.gray('Run `cv init` first'));
+    process.exit(1);
+  }
+  return repoRoot;
+}
+
+export function promptsCommand(): Command {
+  const cmd = new Command('prompts')
+    .description(`Inspect prompt templates (.cv/${PROMPTS_DIR}/)`);
+
+  const list = new Command('list')
+    .alias('ls')
+    .description('List loaded templates and the commands they apply to');
+  addGlobalOptions(list);
+  list.action(async (options) => {
+    const output = createOutput(options);
+    try {
+      const repoRoot = await requireRepoRoot();
+      const templates = await loadPromptTemplates(repoRoot);
+      const files = templates.files();
+      const commands = PROMPT_TEMPLATE_NAMES.map(name => ({
+        name,
+        prompt: files.includes(`${name}.md`) ? `${name}.md` : null,
+        system: files.includes(`${name}.system.md`) ? `${name}.system.md` : files.includes('system.md') ? 'system.md' : null
+      }));
+
+      if (output.isJson) {
+        output.json({ dir: path.join(getCVDir(repoRoot), PROMPTS_DIR), files, commands });
+        return;
+      }
+      if (files.length === 0) {
+        console.log(chalk.gray(`No prompt templates. Add them to .cv/${PROMPTS_DIR}/ (system.md, explain.md, review.md, do.md, commit.md).`));
+        return;
+      }
+
+      console.log(chalk.bold(`Templates in .cv/${PROMPTS_DIR}/:`));
+      for (const command of commands) {
+        const prompt = command.prompt ? chalk.green(command.prompt) : chalk.gray('built-in');
+        const system = command.system ? chalk.green(command.system) : chalk.gray('none');
+        console.log(`  ${command.name.padEnd(8)} prompt: ${prompt}  system: ${system}`);
+      }
+      if (files.includes('system.md')) {
+        console.log(chalk.gray('  Other AI requests use system.md.'));
+      }
+    } catch (error: any) {
+      output.error(error.message, error);
+      process.exit(1);
+    }
+  });
+
+  const show = new Command('show')
+    .description('Render the system prompt and prompt a command would send')
+    .argument('<name>', `Command: ${PROMPT_TEMPLATE_NAMES.join(', ')}`)
+    .argument('[input]', 'Target (explain) or task (do); review and commit use the staged diff');
+  addGlobalOptions(show);
+  show.action(async (name: string, input: string | undefined, options) => {
+    const output = createOutput(options);
+    try {
+      if (!(PROMPT_TEMPLATE_NAMES as readonly string[]).includes(name)) {
+        throw new Error(`Unknown prompt "${name}"; use one of ${PROMPT_TEMPLATE_NAMES.join(', ')}`);
+      }
+      const command = name as PromptTemplateName;
+      const repoRoot = await requireRepoRoot();
+      const config = await configManager.load(repoRoot);
+      const templates = await loadPromptTemplates(repoRoot, config.repository?.name);
+      const git = createGitManager(repoRoot);
+
+      let rendered: { system?: string; prompt: string };
+      if (command === 'commit') {
+        const analyzer = createCommitAnalyzer({ repoRoot, provider: 'none' });
+        analyzer.setPromptTemplates(templates);
+        rendered = analyzer.renderPrompt(await analyzer.analyzeStaged(git));
+      } else {
+        const ai = createAIManager(
+          {
+            provider: 'anthropic',
+            model: config.ai.model,
+            apiKey: '',
+            retrieval: config.retrieval
+          },
+          undefined,
+          undefined,
+          git
+        );
+        ai.setPromptTemplates(templates);
+        ai.setKeywordIndex(await loadKeywordIndex(repoRoot));
+
+        let target = input;
+        if (command === 'review') {
+          target = await git.getRawDiff('--staged');
+          if (!target.trim()) target = await git.getRawDiff();
+          if (!target.trim()) throw new Error('No changes to review');
+        } else if (!target) {
+          throw new Error(`cv prompts show ${command} needs the ${command === 'explain' ? 'target' : 'task'} to render`);
+        }
+        // Keyword matches stand in for the retrieval the command would do
+        const context = await ai.gatherContext(command === 'review' ? target.slice(0, 2000) : target);
+        rendered = ai.renderPrompt(command, target, context);
+      }
+
+      if (output.isJson) {
+        output.json({ name: command, templates: templates.files(), ...rendered });
+        return;
+      }
+      console.log(chalk.bold.cyan('System prompt'));
+      console.log(rendered.system ?? chalk.gray('(none)'));
+      console.log();
+      console.log(chalk.bold.cyan('Prompt'));
+      console.log(rendered.prompt);
+    } catch (error: any) {
+      output.error(error.message, error);
+      process.exit(1);
+    }
+  });
+
+  cmd.addCommand(list);
+  cmd.addCommand(show);
+  return cmd;
+}
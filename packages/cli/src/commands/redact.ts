@@ -0,0 +1,170 @@
+/**
+ * cv redact command
+ * Preview the secrets and personal data masked before text is sent to AI providers
+ *
+ * `cv redact --check` scans tracked files (or the given paths, or the staged
+ * diff) with the same rules applied to every provider request and lists what
+ * would be masked. Without --check the redacted text is printed.
+ */
+
+import { Command } from 'commander';
+import chalk from 'chalk';
+import * as path from 'path';
+import { promises as fs } from 'fs';
+import {
+  configManager,
+  createGitManager,
+  Redactor,
+  RedactionConfig
+} from '@cv-git/core';
+import { findRepoRoot } from '@cv-git/shared';
+import { addGlobalOptions, createOutput } from '../utils/output.js';
+
+/** Files larger than this are skipped (generated or vendored) */
+const MAX_FILE_BYTES = 1024 * 1024;
+
+interface Source {
+  name: string;
+  text: string;
+}
+
+export function redactCommand(): Command {
+  const cmd = new Command('redact')
+    .description('Preview what is masked before code is sent to AI providers')
+    .argument('[paths...]', 'Files or directories (default: all tracked files)')
+    .option('--check', 'List what would be masked instead of printing the redacted text')
+    .option('--staged', 'Use the staged diff')
+    .option('--diff <ref>', 'Use the diff against a ref')
+    .option('--stdin', 'Read text from stdin')
+    .option('--exit-code', 'With --check, exit with code 1 when anything would be masked (for hooks and CI)');
+
+  addGlobalOptions(cmd);
+
+  cmd.action(async (paths: string[], options) => {
+    const output = createOutput(options);
+
+    try {
+      const repoRoot = await findRepoRoot();
+      let redaction: RedactionConfig = {};
+      if (repoRoot) {
+        try {
+          redaction = (await configManager.load(repoRoot)).redaction ?? {};
+        } catch {
+          // Uninitialized repository: built-in rules only
+        }
+      }
+      const redactor = new Redactor(redaction);
+
+      const sources = await collectSources(paths, options, repoRoot);
+      const results = sources.map(source => ({ source, ...redactor.redact(source.text) }));
+
+      if (!options.check) {
+        if (output.isJson) {
+          output.json(results.map(r => ({ file: r.source.name, text: r.text, matches: r.matches })));
+        } else {
+          process.stdout.write(results.map(r => r.text).join('\n'));
+        }
+        return;
+      }
+
+      const found = results.filter(r => r.matches.length > 0);
+
+      if (output.isJson) {
+        output.json({
+          enabled: redaction.enabled !== false,
+          scanned: sources.length,
+          files: found.map(r => ({ file: r.source.name, matches: r.matches })),
+          total: found.reduce((n, r) => n + r.matches.length, 0),
+        });
+        if (options.exitCode && found.length > 0) process.exit(1);
+        return;
+      }
+
+      if (redaction.enabled === false) {
+        console.log(chalk.yellow('Redaction is disabled (redaction.enabled: false); nothing below is masked.'));
+      }
+
+      if (found.length === 0) {
+        console.log(chalk.green(`✓ Nothing to mask in ${sources.length} file${sources.length === 1 ? '' : 's'}`));
+        return;
+      }
+
+      for (const result of found) {
+        console.log(chalk.bold(result.source.name));
+        for (const match of result.matches) {
+          console.log(`  ${chalk.gray(`${match.line}:`)} ${chalk.yellow(match.rule.padEnd(20))} ${match.preview}`);
+        }
+      }
+
+      const total = found.reduce((n, r) => n + r.matches.length, 0);
+      console.log();
+      console.log(`${total} value${total === 1 ? '' : 's'} in ${found.length} of ${sources.length} files would be masked.`);
+      console.log(chalk.gray('Add patterns or allow-list false positives under "redaction" in .cv/config.json.'));
+      if (options.exitCode) process.exit(1);
+    } catch (error: any) {
+      output.error('Redaction check failed', error);
+      process.exit(1);
+    }
+  });
+
+  return cmd;
+}
+
+async function collectSources(paths: string[], options: any, repoRoot: string | null): Promise<Source[]> {
+  if (options.stdin) {
+    const chunks: Buffer[] = [];
+    for await (const chunk of process.stdin) chunks.push(chunk as Buffer);
+    return [{ name: '<stdin>', text: Buffer.concat(chunks).toString('utf-8') }];
+  }
+
+  if (options.staged || options.diff) {
+    if (!repoRoot) throw new Error('Not in a git repository');
+    const git = createGitManager(repoRoot);
+    const text = await git.getRawDiff(options.staged ? ['--cached'] : [options.diff]);
+    return [{ name: options.staged ? 'staged diff' : `diff ${options.diff}`, text }];
+  }
+
+  const base = repoRoot ?? process.cwd();
+  let files: string[];
+  if (paths.length === 0) {
+    if (!repoRoot) throw new Error('Not in a git repository; give files to check');
+    files = (await createGitManager(repoRoot).getTrackedFiles()).map(f => path.join(repoRoot, f));
+  } else {
+    files = [];
+    for (const p of paths) files.push(...await walk(path.resolve(p)));
+  }
+
+  const sources: Source[] = [];
+  for (const file of files) {
+    const text = await readText(file);
+    if (text !== null) sources.push({ name: path.relative(base, file) || file, text });
+  }
+  return sources;
+}
+
+async function walk(target: string): Promise<string[]> {
+  const stat = await fs.stat(target);
+  if (!stat.isDirectory()) return [target];
+
+  const files: string[] = [];
+  for (const entry of await fs.readdir(target, { withFileTypes: true })) {
+    if (entry.name === '.git' || entry.name === 'node_modules') continue;
+    files.push(...await walk(path.join(target, entry.name)));
+  }
+  return files;
+}
+
+/**
+ * File contents, or null for binary, oversized or unreadable files
+ */
+async function readText(file: string): Promise<string | null> {
+  try {
+    const stat = await fs.stat(file);
+    if (!stat.isFile() || stat.size > MAX_FILE_BYTES) return null;
+    const buffer = await fs.readFile(file);
+    if (buffer.subarray(0, 8000).includes(0)) return null;
+    return buffer.toString('utf-8');
+  } catch {
+    return null;
+  }
+}
@@ -0,0 +1,307 @@
+/**
+ * cv refactor command
+ * Cross-file refactoring planned from the symbol graph
+ *
+ * The files come from the graph (definitions, members, callers,
+ * implementations, tests; see ai/refactor.ts). The model's edits become one
+ * patch, which is applied temporarily and checked with the build (and the
+ * tests with --test); a failing build goes back to the model with its errors.
+ * The working tree is restored after each check, and the verified patch is
+ * then shown for approval and recorded for `cv undo`.
+ */
+
+import { Command } from 'commander';
+import chalk from 'chalk';
+import ora from 'ora';
+import * as readline from 'readline';
+import { promises as fs } from 'fs';
+import * as path from 'path';
+import {
+  buildRefactorPrompt,
+  collectRefactorScope,
+  configManager,
+  createEditParser,
+  createGitManager,
+  createGraphManager,
+  createPatchEngine,
+  detectBuildCommand,
+  detectTestCommand,
+  generateRepoId,
+  isTestFile,
+  loadSandboxPolicy,
+  Patch,
+  PatchError,
+  recordChangeSet,
+  RefactorFailure,
+  REFACTOR_SYSTEM_PROMPT,
+  reversePatch,
+  runSandboxed,
+  SandboxError,
+  SandboxPolicy,
+  uncommittedFiles
+} from '@cv-git/core';
+import { findRepoRoot } from '@cv-git/shared';
+import { createModelClient, fallbackNotice } from '../utils/model-client.js';
+import { colorizeDiff } from '../utils/formatting.js';
+
+/** Longest file sent whole, in characters */
+const MAX_FILE_CHARS = 60_000;
+
+export function refactorCommand(): Command {
+  const cmd = new Command('refactor');
+
+  cmd
+    .description('Refactor across files: plan from the call graph, verify with the build, apply after approval')
+    .argument('<instruction>', 'What to change, naming the symbols (e.g. "extract the caching logic in ComputeEngine into a Cache trait")')
+    .option('--verify <command>', 'Build command to verify the patch with (default: detected from Cargo.toml, go.mod, tsconfig.json, ...)')
+    .option('--no-verify', 'Skip the build check')
+    .option('--test', 'Also run the tests (test.command in .cvgit.toml, or detected)')
+    .option('-n, --attempts <n>', 'Generate-and-verify attempts before giving up', '2')
+    .option('--max-files <n>', 'Most files to send to the model', '15')
+    .option('--dry-run', 'Only show the verified patch')
+    .option('-y, --yes', 'Apply without confirmation')
+    .option('--force', 'Also modify files with uncommitted changes')
+    .option('-m, --model <model>', 'Model: a Claude model or provider/model (e.g. openai/gpt-4o)');
+
+  cmd.action(async (instruction: string, options) => {
+    const spinner = ora('Finding the affected code in the graph...').start();
+
+    const repoRoot = await findRepoRoot();
+    if (!repoRoot) {
+      spinner.fail(chalk.red('Not in a CV-Git repository'));
+      console.error(chalk.gray('Run `cv init` first'));
+      process.exit(1);
+    }
+
+    try {
+      const config = await configManager.load(repoRoot);
+      const git = createGitManager(repoRoot);
+      const readFile = (file: string) => fs.readFile(path.join(repoRoot, file), 'utf-8').catch(() => null);
+      const tracked = await git.getTrackedFiles();
+
+      const graph = createGraphManager({ url: config.graph.url, repoId: config.repository.repoId || generateRepoId(repoRoot) });
+      await graph.connect();
+      const scope = await collectRefactorScope(graph, instruction, {
+        testFiles: tracked.filter(isTestFile),
+        readFile,
+        maxFiles: Math.max(1, parseInt(options.maxFiles, 10) || 15)
+      }).finally(() => graph.close());
+
+      if (scope.symbols.length === 0) {
+        spinner.fail(chalk.red('None of the symbols in the instruction are in the graph'));
+        console.error(chalk.gray('Name them as in the code, in backticks if needed (`ComputeEngine`, `compute_heavy_task`), and run `cv sync` after adding files'));
+        process.exit(1);
+      }
+      spinner.succeed(`${scope.symbols.length} symbol(s), ${scope.files.length} file(s) in scope` +
+        (scope.omitted > 0 ? chalk.gray(` (${scope.omitted} more left out; raise --max-files)`) : ''));
+      for (const entry of scope.files) {
+        console.log(chalk.gray(`  ${entry.file}  ${entry.reasons.join(', ')}`));
+      }
+
+      const contents: Array<{ file: string; text: string }> = [];
+      for (const entry of scope.files) {
+        const text = await readFile(entry.file);
+        if (text !== null) contents.push({ file: entry.file, text: text.slice(0, MAX_FILE_CHARS) });
+      }
+
+      // Verification commands, run in the sandbox
+      const rootFiles = tracked.filter(file => !file.includes('/'));
+      const commands: string[] = [];
+      if (options.verify !== false) {
+        const build = typeof options.verify === 'string' ? options.verify : detectBuildCommand(rootFiles);
+        if (build) commands.push(build);
+        else console.log(chalk.yellow('⚠ No build command found; pass --verify "<command>" to check the patch'));
+      }
+      if (options.test) {
+        const test = config.test?.command?.replace(/\{file\}/g, '') ?? detectTestCommand(rootFiles);
+        if (test) commands.push(test.trim());
+      }
+      const sandbox = commands.length > 0 ? await loadSandboxPolicy(repoRoot) : undefined;
+
+      const client = await createModelClient(options.model, {
+        provider: 'anthropic',
+        model: config.ai.model,
+        anthropicApiKey: config.ai.apiKey,
+        maxTokens: 16384,
+        fallback: config.ai.fallback
+      });
+      const engine = createPatchEngine(repoRoot);
+      const attempts = Math.max(1, parseInt(options.attempts, 10) || 2);
+
+      let patch: Patch | null = null;
+      let failure: RefactorFailure | undefined;
+      let verified = false;
+      for (let attempt = 1; attempt <= attempts; attempt++) {
+        const step = ora(`Planning the refactoring (attempt ${attempt}/${attempts})...`).start();
+        const response = await client.chat(
+          [{ role: 'user', content: buildRefactorPrompt(instruction, scope, contents, failure) }],
+          REFACTOR_SYSTEM_PROMPT
+        );
+        const edits = createEditParser().parseResponse(response, 'cv-refactor');
+        if (edits.length === 0) {
+          step.fail('The model returned no edits');
+          console.log(response.trim());
+          process.exit(1);
+        }
+
+        try {
+          patch = await engine.prepare(edits);
+        } catch (error: any) {
+          if (!(error instanceof PatchError)) throw error;
+          step.fail(`The edits don't apply cleanly (attempt ${attempt}/${attempts})`);
+          failure = { command: 'patch', output: error.message, diff: '' };
+          patch = null;
+          continue;
+        }
+        step.succeed(`Patch for ${patch.files.length} file(s)`);
+
+        if (!sandbox) break;
+        if (!options.force) {
+          const dirty = uncommittedFiles(patch, await git.getStatus());
+          if (dirty.length > 0) {
+            console.error(chalk.red('Refusing to modify files with uncommitted changes:'));
+            for (const file of dirty) console.error(chalk.gray(`  • ${file}`));
+            console.error(chalk.gray('Commit or stash them first, or pass --force'));
+            process.exit(1);
+          }
+        }
+
+        const check = await verifyPatch(engine, patch, commands, repoRoot, sandbox);
+        if (check === null) {
+          verified = true;
+          break;
+        }
+        failure = { ...check, diff: patch.files.map(file => file.diff).join('\n') };
+        console.log(chalk.red(`✗ ${check.command} failed (attempt ${attempt}/${attempts})`));
+        console.log(chalk.gray(check.output.trimEnd().split('\n').slice(-15).map(line => `  ${line}`).join('\n')));
+      }
+
+      const notice = fallbackNotice(client);
+      if (notice) console.log(chalk.gray(notice));
+
+      if (!patch) {
+        console.error(chalk.red(`No applicable patch after ${attempts} attempt(s)`));
+        if (failure) console.error(chalk.gray(failure.output));
+        process.exit(1);
+      }
+
+      console.log();
+      console.log(chalk.bold.cyan(`Patch (${patch.files.length} file${patch.files.length === 1 ? '' : 's'}):`));
+      console.log();
+      for (const file of patch.files) {
+        console.log(colorizeDiff(file.diff.trimEnd()));
+        console.log();
+      }
+      if (verified) console.log(chalk.green(`✓ Verified: ${commands.join(' && ')}`));
+      else if (sandbox) console.log(chalk.yellow(`⚠ The patch still fails verification after ${attempts} attempt(s)`));
+
+      if (options.dryRun) {
+        console.log(chalk.gray('(dry run - no files changed)'));
+        if (sandbox && !verified) process.exit(1);
+        return;
+      }
+
+      if (!options.force) {
+        const dirty = uncommittedFiles(patch, await git.getStatus());
+        if (dirty.length > 0) {
+          console.error(chalk.red('Refusing to modify files with uncommitted changes:'));
+          for (const file of dirty) console.error(chalk.gray(`  • ${file}`));
+          console.error(chalk.gray('Commit or stash them first, or pass --force'));
+          process.exit(1);
+        }
+      }
+
+      const question = sandbox && !verified
+        ? `Apply the unverified patch to ${patch.files.length} file(s) anyway?`
+        : `Apply changes to ${patch.files.length} file(s)?`;
+      const approved = (options.yes && (verified || !sandbox)) || await askForApproval(question);
+      if (!approved) {
+        console.log(chalk.gray('No files changed'));
+        if (sandbox && !verified) process.exit(1);
+        return;
+      }
+
+      await engine.apply(patch);
+      console.log(chalk.green(`✓ Applied changes to ${patch.files.length} file(s)`));
+      for (const file of patch.files) {
+        const mark = file.before === null ? chalk.green('A') : file.after === null ? chalk.red('D') : chalk.yellow('M');
+        console.log(`  ${mark} ${file.file}`);
+      }
+      try {
+        const head = await git.getLastCommitSha().catch(() => undefined);
+        const changeSet = await recordChangeSet(repoRoot, patch.files, { command: 'refactor', description: instruction, head });
+        console.log(chalk.gray(`Undo with: cv undo ${changeSet.id}`));
+      } catch (error: any) {
+        console.error(chalk.yellow(`⚠ Could not record change set for undo: ${error.message}`));
+      }
+    } catch (error: any) {
+      spinner.fail(chalk.red('Refactoring failed'));
+      console.error(chalk.red(error.message));
+      process.exit(1);
+    }
+  });
+
+  return cmd;
+}
+
+/**
+ * Apply the patch, run each command, and restore the working tree;
+ * null when every command passed
+ */
+async function verifyPatch(
+  engine: ReturnType<typeof createPatchEngine>,
+  patch: Patch,
+  commands: string[],
+  repoRoot: string,
+  policy: SandboxPolicy
+): Promise<{ command: string; output: string } | null> {
+  const spinner = ora(`Verifying: ${commands[0]}`).start();
+  await engine.apply(patch);
+  // Ctrl+C during a long build must not leave the patch applied
+  const restore = () => engine.apply(reversePatch(patch));
+  const onInterrupt = () => {
+    restore().finally(() => process.exit(130));
+  };
+  process.once('SIGINT', onInterrupt);
+
+  try {
+    for (const command of commands) {
+      spinner.text = `Verifying: ${command}`;
+      let result;
+      try {
+        result = await runSandboxed(command, { repoRoot, policy });
+      } catch (error: any) {
+        if (!(error instanceof SandboxError)) throw error;
+        spinner.fail(error.message);
+        return { command, output: error.message };
+      }
+      if (result.exitCode !== 0) {
+        spinner.stop();
+        const output = result.timedOut ? `${result.output}\nTimed out after ${policy.timeoutMs / 1000}s` : result.output;
+        return { command, output };
+      }
+    }
+    spinner.succeed(`Verified: ${commands.join(' && ')}`);
+    return null;
+  } finally {
+    process.removeListener('SIGINT', onInterrupt);
+    await restore();
+  }
+}
+
+/**
+ * Ask for user approval
+ */
+async function askForApproval(question: string): Promise<boolean> {
+  const rl = readline.createInterface({
+    input: process.stdin,
+    output: process.stdout
+  });
+
+  return new Promise(resolve => {
+    rl.question(chalk.cyan(`${question} (y/N): `), answer => {
+      rl.close();
+      resolve(answer.toLowerCase() === 'y' || answer.toLowerCase() === 'yes');
+    });
+  });
+}
@@ -14,7 +14,7 @@ import { promises as fs } from 'fs';
 import * as path from 'path';
 import { CredentialManager } from '@cv-git/credentials';
 import { createPlatformAdapter, type Release } from '@cv-git/platform';
-import { getConfig } from '../config.js';
+import { getConfig, toAdapterConfig } from '../config.js';
 
 const git = simpleGit();
 
@@ -96,7 +96,7 @@ export function releaseCommand(): Command {
         const credentials = new CredentialManager();
         await credentials.init();
 
-        const platform = createPlatformAdapter(config.platform, credentials);
+        const platform = createPlatformAdapter(toAdapterConfig(config.platform), credentials);
         await platform.init();
 
         // Create git tag if it doesn't exist
@@ -147,7 +147,7 @@ export function releaseCommand(): Command {
         const credentials = new CredentialManager();
         await credentials.init();
 
-        const platform = createPlatformAdapter(config.platform, credentials);
+        const platform = createPlatformAdapter(toAdapterConfig(config.platform), credentials);
         await platform.init();
 
         const spinner = ora('Fetching releases...').start();
@@ -204,7 +204,7 @@ export function releaseCommand(): Command {
         const credentials = new CredentialManager();
         await credentials.init();
 
-        const platform = createPlatformAdapter(config.platform, credentials);
+        const platform = createPlatformAdapter(toAdapterConfig(config.platform), credentials);
         await platform.init();
 
         const spinner = ora('Fetching release...').start();
@@ -277,7 +277,7 @@ export function releaseCommand(): Command {
         const credentials = new CredentialManager();
         await credentials.init();
 
-        const platform = createPlatformAdapter(config.platform, credentials);
+        const platform = createPlatformAdapter(toAdapterConfig(config.platform), credentials);
         await platform.init();
 
         const spinner = ora('Deleting release...').start();
@@ -392,7 +392,7 @@ export function releaseCommand(): Command {
         try {
           const credentials = new CredentialManager();
           await credentials.init();
-          const platform = createPlatformAdapter(config.platform, credentials);
+          const platform = createPlatformAdapter(toAdapterConfig(config.platform), credentials);
           await platform.init();
 
           const release = await platform.getRelease(`v${version}`);
@@ -11,11 +11,98 @@ import {
   createAIManager,
   createVectorManager,
   createGraphManager,
-  createGitManager
+  createGitManager,
+  loadScoreDistribution,
+  contextSources,
+  collectHotspots,
+  filterDiff,
+  touchedLines,
+  touchedSymbols,
+  groupFindings,
+  hasFindingsAtOrAbove,
+  toSarif,
+  REVIEW_SEVERITIES,
+  loadReviewPolicies,
+  applicablePolicies,
+  policyReport,
+  splitDiff,
+  loadFindingsStore,
+  saveFindingsStore,
+  recordFindings,
+  withoutDismissed,
+  PolicyReport,
+  ReviewFinding,
+  ReviewSeverity,
+  loadPromptTemplates
 } from '@cv-git/core';
-import { findRepoRoot } from '@cv-git/shared';
-import { addGlobalOptions } from '../utils/output.js';
-import { getAnthropicApiKey, getEmbeddingCredentials } from '../utils/credentials.js';
+import { findRepoRoot, SymbolNode } from '@cv-git/shared';
+import { addGlobalOptions, addJsonlOption, createJsonReporter, ErrorCode, ExitCode } from '../utils/output.js';
+import { CitationFormatter, loadCitationFormatter, printSources, sourcesToJson } from '../utils/citations.js';
+import { addFixtureOptions, createFixtureSession } from '../utils/fixtures.js';
+import { getAnthropicApiKey } from '../utils/credentials.js';
+import { resolveEmbeddingProvider } from '../utils/embedding-provider.js';
+import { vectorStoreOptions } from '../utils/vector-store.js';
+import { applyReranker } from '../utils/reranker.js';
+import { applyQueryExpansion } from '../utils/query-expansion.js';
+
+const SEVERITY_STYLE: Record<ReviewSeverity, (text: string) => string> = {
+  critical: chalk.red.bold,
+  high: chalk.red,
+  medium: chalk.yellow,
+  low: chalk.cyan,
+  info: chalk.gray
+};
+
+/**
+ * Print findings grouped by severity, each with a clickable location
+ */
+function printFindings(findings: ReviewFinding[], cite: CitationFormatter): void {
+  for (const group of groupFindings(findings)) {
+    const style = SEVERITY_STYLE[group.severity];
+    console.log(style(`● ${group.severity.toUpperCase()} (${group.findings.length})`));
+    for (const finding of group.findings) {
+      const location = cite({ file: finding.file, line: finding.line, endLine: finding.endLine });
+      console.log(`  ${location}  ${chalk.bold(finding.title)}`);
+      if (finding.message && finding.message !== finding.title) {
+        console.log(chalk.gray(`    ${finding.message.replace(/\n/g, '\n    ')}`));
+      }
+      if (finding.suggestion) {
+        console.log(chalk.green(`    ↳ ${finding.suggestion.replace(/\n/g, '\n      ')}`));
+      }
+    }
+    console.log();
+  }
+}
+
+/**
+ * Resolve to undefined when the promise takes longer than `seconds`
+ */
+async function withinBudget<T>(promise: Promise<T>, seconds?: number): Promise<T | undefined> {
+  if (seconds === undefined) return promise;
+  let timer: NodeJS.Timeout | undefined;
+  const timeout = new Promise<undefined>(resolve => {
+    timer = setTimeout(() => resolve(undefined), seconds * 1000);
+  });
+  try {
+    return await Promise.race([promise, timeout]);
+  } finally {
+    clearTimeout(timer);
+  }
+}
+
+/**
+ * Print pass/fail for each house rule
+ */
+function printPolicyReport(report: PolicyReport[]): void {
+  if (report.length === 0) return;
+  console.log(chalk.bold.cyan('Policies:'));
+  for (const rule of report) {
+    const mark = rule.status === 'pass' ? chalk.green('✓') : chalk.red('✗');
+    const count = rule.findings > 0 ? chalk.red(` (${rule.findings} finding${rule.findings === 1 ? '' : 's'})`) : '';
+    console.log(`  ${mark} ${rule.name} ${chalk.gray(`[${rule.id}]`)}${count}`);
+  }
+  console.log();
+}
 
 export function reviewCommand(): Command {
   const cmd = new Command('review');
@@ -24,20 +111,66 @@ export function reviewCommand(): Command {
     .description('Review code changes with AI')
     .argument('[ref]', 'Git ref to review (default: HEAD)', 'HEAD')
     .option('--staged', 'Review staged changes instead of a commit')
-    .option('--context', 'Include related code context in review');
+    .option('--range <rev1..rev2>', 'Review the changes between two revisions (e.g. origin/main..HEAD before pushing)')
+    .option('--files <glob...>', 'Only review files matching these globs')
+    .option('--policies <file>', 'Review policy file (default: .cv/review-policies.yaml)')
+    .option('--no-policies', 'Skip house rules from the review policy file')
+    .option('--format <format>', 'Output format: text, json or sarif', 'text')
+    .option('--fail-on <severity>', `Exit with code 1 if any finding is at or above this severity (${REVIEW_SEVERITIES.join(', ')}, none)`, 'none')
+    .option('--context', 'Include related code context in review')
+    .option('--hotspots [n]', 'Flag changed files among the top n churn x complexity hotspots (default 20) for extra scrutiny')
+    .option('--fast', 'Hook mode: no context or hotspots, and skip the AI review (exit 0) when no API key is set or the provider is unreachable')
+    .option('--budget <seconds>', 'Give up on the AI review after this many seconds and exit 0');
 
   addGlobalOptions(cmd);
+  addJsonlOption(cmd);
+  addFixtureOptions(cmd);
 
   cmd.action(async (ref: string, options) => {
-      let spinner = ora('Initializing...').start();
+      const format: string = options.json || options.jsonl ? 'json' : options.format;
+      if (!['text', 'json', 'sarif'].includes(format)) {
+        console.error(chalk.red(`Unknown --format "${format}" (expected text, json or sarif)`));
+        process.exit(ExitCode.Error);
+      }
+      const report = createJsonReporter('review', format === 'json' ? { ...options, json: true } : {});
+      const usageError = (message: string): never => {
+        report?.fail(message, ErrorCode.INVALID_INPUT);
+        console.error(chalk.red(message));
+        process.exit(ExitCode.Error);
+      };
+      if (options.failOn !== 'none' && !(REVIEW_SEVERITIES as string[]).includes(options.failOn)) {
+        usageError(`Unknown --fail-on "${options.failOn}" (expected ${REVIEW_SEVERITIES.join(', ')} or none)`);
+      }
+      const budget = options.budget !== undefined ? parseFloat(options.budget) : undefined;
+      if (budget !== undefined && !(budget > 0)) {
+        usageError(`Invalid --budget "${options.budget}" (expected a number of seconds)`);
+      }
+      // Hook mode gives up without blocking; JSON consumers learn why
+      const skip = (reason: string): never => {
+        report?.result({ ref, skipped: reason, summary: null, findings: [] });
+        process.exit(ExitCode.Success);
+      };
+      if (options.fast) {
+        options.context = false;
+        options.hotspots = false;
+      }
+
+      // JSON and SARIF go to stdout; keep progress off it
+      const json = format !== 'text';
+      let spinner = ora({ text: 'Initializing...', isSilent: json }).start();
 
       try {
         // Find repository root
         const repoRoot = await findRepoRoot();
+        if (!repoRoot && options.fast) {
+          spinner.info(chalk.gray('Not in a CV-Git repository - skipping AI review'));
+          skip('Not in a CV-Git repository');
+        }
         if (!repoRoot) {
+          report?.fail('Not in a CV-Git repository', ErrorCode.NOT_INITIALIZED);
           spinner.fail(chalk.red('Not in a CV-Git repository'));
           console.error(chalk.gray('Run `cv init` first'));
-          process.exit(1);
+          process.exit(ExitCode.Error);
         }
 
         // Load configuration
@@ -45,17 +178,22 @@ export function reviewCommand(): Command {
 
         // Check for API keys (CredentialManager -> config -> env var)
         const anthropicApiKey = await getAnthropicApiKey(config.ai.apiKey);
+        if (!anthropicApiKey && options.fast) {
+          spinner.info(chalk.gray('No Anthropic API key - skipping AI review'));
+          skip('No Anthropic API key');
+        }
         if (!anthropicApiKey) {
+          report?.fail('Anthropic API key not found', ErrorCode.NO_CREDENTIALS);
           spinner.fail(chalk.red('Anthropic API key not found'));
           console.error();
           console.error(chalk.yellow('Set your Anthropic API key:'));
           console.error(chalk.gray('  cv auth setup anthropic'));
           console.error(chalk.gray('  export ANTHROPIC_API_KEY=sk-ant-...'));
-          process.exit(1);
+          process.exit(ExitCode.Error);
         }
 
-        // Get embedding credentials (OpenRouter preferred, fallback to OpenAI)
-        const embeddingCreds = await getEmbeddingCredentials();
+        // Resolve the embedding provider the index was built with
+        const embeddingProvider = await resolveEmbeddingProvider(config).catch(() => undefined);
 
         // Initialize components
         spinner.text = 'Connecting to services...';
@@ -67,42 +205,62 @@ export function reviewCommand(): Command {
         spinner.text = 'Getting code changes...';
         let diff: string;
 
-        if (options.staged) {
+        if (options.range) {
+          if (!options.range.includes('..')) {
+            spinner.stop();
+            usageError(`Invalid range "${options.range}" (expected <rev1>..<rev2>)`);
+          }
+          ref = options.range;
+          diff = await git.getRawDiff(options.range);
+        } else if (options.staged) {
+          ref = 'staged';
           diff = await git.getRawDiff('--staged');
         } else {
           diff = await git.getRawDiff(ref);
         }
+        diff = filterDiff(diff, options.files);
 
         if (!diff || diff.trim().length === 0) {
           spinner.warn(chalk.yellow('No changes to review'));
+          if (format === 'sarif') {
+            console.log(JSON.stringify(toSarif([], { toolVersion: cmd.parent?.version() }), null, 2));
+            process.exit(0);
+          }
+          if (report) {
+            report.result({ ref, summary: null, findings: [], sources: [] });
+            process.exit(ExitCode.Success);
+          }
           console.log();
           console.log(chalk.gray('Tips:'));
           console.log(chalk.gray('  • Make some changes and stage them: git add .'));
           console.log(chalk.gray('  • Review staged changes: cv review --staged'));
           console.log(chalk.gray('  • Review a specific commit: cv review <commit-sha>'));
+          console.log(chalk.gray('  • Review what you are about to push: cv review --range origin/main..HEAD'));
           console.log();
           process.exit(0);
         }
 
         spinner.succeed(chalk.green('Changes retrieved'));
 
-        // Optional: gather context
+        const fixture = createFixtureSession(options, repoRoot, 'review', [ref]);
+
+        // Optional: gather context for the symbols the diff touches
         let context = undefined;
+        let symbols: SymbolNode[] = [];
         if (options.context) {
           // Vector manager (optional)
           let vector = undefined;
-          if ((embeddingCreds.openrouterApiKey || embeddingCreds.openaiApiKey) && config.vector) {
+          if (embeddingProvider && config.vector) {
             try {
               vector = createVectorManager({
                 url: config.vector.url,
-                openrouterApiKey: embeddingCreds.openrouterApiKey,
-                openaiApiKey: embeddingCreds.openaiApiKey,
-                collections: config.vector.collections,
-                embeddingModel: config.embedding?.model
+                ...vectorStoreOptions(config, repoRoot),
+                provider: embeddingProvider,
+                collections: config.vector.collections
               });
               await vector.connect();
             } catch (error) {
-              console.log(chalk.gray('  ⚠ Could not connect to vector DB'));
+              console.error(chalk.gray('  ⚠ Could not connect to vector DB'));
             }
           }
 
@@ -115,16 +273,31 @@ export function reviewCommand(): Command {
             {
               provider: 'anthropic',
               model: config.ai.model,
-              apiKey: anthropicApiKey
+              apiKey: anthropicApiKey,
+              retrieval: config.retrieval
             },
             vector,
             graph,
             git
           );
-
-          spinner = ora('Gathering code context...').start();
-          context = await contextAI.gatherContext('code review');
-          spinner.succeed(chalk.green('Context gathered'));
+          fixture.attach(contextAI, vector);
+          applyReranker(contextAI, config);
+          applyQueryExpansion(contextAI, config, 'review');
+          contextAI.setScoreDistribution(await loadScoreDistribution(repoRoot, vector?.getEmbeddingInfo().model));
+
+          spinner = ora({ text: 'Gathering code context...', isSilent: json }).start();
+          for (const [file, ranges] of touchedLines(diff)) {
+            const fileSymbols = await graph.getFileSymbols(file).catch(() => []);
+            symbols.push(...touchedSymbols(fileSymbols, ranges));
+          }
+          // Search by the touched symbols, then rerank by how well candidates relate to the change itself
+          const query = symbols.length > 0
+            ? symbols.slice(0, 20).map(s => s.name).join(' ')
+            : 'code review';
+          context = await contextAI.gatherContext(query, { rerankQuery: diff.slice(0, 4000) });
+          spinner.succeed(chalk.green(
+            `Context gathered (${symbols.length} touched symbols, ${context.chunks.length} related chunks)`
+          ));
 
           await graph.close();
           if (vector) await vector.close();
@@ -141,32 +314,127 @@ export function reviewCommand(): Command {
           undefined,
           git
         );
+        ai.setPromptTemplates(await loadPromptTemplates(repoRoot, config.repository?.name));
+        fixture.attach(ai);
+
+        const sources = context ? contextSources(context.chunks) : [];
+
+        // House rules that apply to the files in this diff
+        const policies = options.policies === false
+          ? []
+          : applicablePolicies(
+            await loadReviewPolicies(repoRoot, typeof options.policies === 'string' ? options.policies : config.review?.policies),
+            splitDiff(diff).map(section => section.file)
+          );
+
+        // Hotspots (cv hotspots) among the changed files
+        let hotspots = undefined;
+        if (options.hotspots) {
+          spinner = ora({ text: 'Ranking hotspots...', isSilent: json }).start();
+          const top = options.hotspots === true ? 20 : Math.max(1, parseInt(options.hotspots, 10) || 20);
+          const graph = createGraphManager(config.graph.url, config.graph.database);
+          await graph.connect();
+          const ranked = await collectHotspots(git, graph);
+          await graph.close();
+
+          const changed = new Set(splitDiff(diff).map(section => section.file));
+          const files = ranked.files.slice(0, top).filter(file => changed.has(file.file));
+          const touched = new Set(files.map(file => file.file));
+          hotspots = { files, functions: ranked.functions.filter(fn => touched.has(fn.file)).slice(0, 10) };
+          spinner.succeed(chalk.green(files.length > 0
+            ? `${files.length} changed file${files.length === 1 ? ' is a hotspot' : 's are hotspots'}: ${files.map(f => f.file).join(', ')}`
+            : `No changed files among the top ${top} hotspots`));
+        }
+
+        spinner = ora({ text: 'Analyzing changes...', isSilent: json }).start();
+        const review = await withinBudget(ai.reviewChanges(diff, context, { symbols, policies, hotspots }), budget);
+        if (!review) {
+          spinner.info(chalk.gray(`AI review did not finish within ${budget}s - skipped`));
+          skip(`AI review did not finish within ${budget}s`);
+        }
+        spinner.stop();
+        await fixture.save();
+
+        // Remember findings for triage in `cv tui`; dismissed ones are not reported again
+        const store = recordFindings(await loadFindingsStore(repoRoot), review.findings, ref);
+        await saveFindingsStore(repoRoot, store).catch(() => undefined);
+        const findings = withoutDismissed(review.findings, store);
+        const dismissed = review.findings.length - findings.length;
+        const rules = policyReport(policies, findings);
+
+        // CI gate: non-zero exit when findings reach the threshold
+        const failed = options.failOn !== 'none' && hasFindingsAtOrAbove(findings, options.failOn);
+
+        if (format === 'sarif') {
+          console.log(JSON.stringify(toSarif(findings, {
+            toolVersion: cmd.parent?.version(),
+            rules: policies.map(p => ({ id: p.id, description: p.name }))
+          }), null, 2));
+          process.exit(failed ? ExitCode.CheckFailed : ExitCode.Success);
+        }
+        if (report) {
+          findings.forEach(finding => report.event('finding', finding));
+          report.result({
+            ref,
+            summary: review.summary,
+            findings,
+            dismissed,
+            failOn: options.failOn,
+            policies: rules,
+            hotspots: hotspots?.files,
+            sources: sourcesToJson(sources, repoRoot)
+          }, failed ? ExitCode.CheckFailed : ExitCode.Success);
+          process.exit();
+        }
+
+        const cite = await loadCitationFormatter(repoRoot);
 
-        // Generate review
         console.log();
-        console.log(chalk.bold.cyan('Code Review:'));
+        console.log(chalk.bold.cyan(`Code Review: ${ref}`));
         console.log(chalk.gray('─'.repeat(80)));
         console.log();
 
-        spinner = ora('Analyzing changes...').start();
-        const review = await ai.reviewCode(diff, context);
-        spinner.stop();
-
-        console.log(review);
+        if (review.summary) {
+          console.log(review.summary);
+          console.log();
+        }
+        printFindings(findings, cite);
+        if (dismissed > 0) {
+          console.log(chalk.gray(`${dismissed} dismissed finding${dismissed === 1 ? '' : 's'} hidden (triage with cv tui)`));
+          console.log();
+        }
+        printPolicyReport(rules);
+        printSources(sources, cite);
         console.log();
         console.log(chalk.gray('─'.repeat(80)));
         console.log();
 
         // Summary
-        console.log(chalk.bold('Review complete! 🎉'));
-        console.log();
-        console.log(chalk.gray('Next steps:'));
-        console.log(chalk.gray('  • Address any issues raised'));
-        console.log(chalk.gray('  • Run tests: npm test / pytest'));
-        console.log(chalk.gray('  • Commit if ready: git commit'));
+        const counts = groupFindings(findings).map(g => `${g.findings.length} ${g.severity}`);
+        console.log(chalk.bold(counts.length > 0 ? `Review complete: ${counts.join(', ')}` : 'Review complete: no findings 🎉'));
         console.log();
+        if (!options.fast) {
+          console.log(chalk.gray('Next steps:'));
+          console.log(chalk.gray('  • Address any issues raised'));
+          console.log(chalk.gray('  • Run tests: npm test / pytest'));
+          console.log(chalk.gray('  • Commit if ready: git commit'));
+          console.log();
+        }
+
+        if (failed) {
+          console.error(chalk.red(`Findings at or above "${options.failOn}" severity`));
+          process.exit(ExitCode.CheckFailed);
+        }
 
       } catch (error: any) {
+        // A hook must not block commits because the provider is down or the repo is not set up
+        if (options.fast) {
+          spinner.info(chalk.gray(`Skipping AI review: ${error.message}`));
+          skip(error.message);
+        }
+
+        report?.fail(error.message, error.message.includes('API key') ? ErrorCode.NO_CREDENTIALS : ErrorCode.OPERATION_FAILED);
+
         if (spinner) {
           spinner.fail(chalk.red('Review failed'));
         }
@@ -192,7 +460,7 @@ export function reviewCommand(): Command {
           console.error(chalk.gray(error.stack));
         }
 
-        process.exit(1);
+        process.exit(ExitCode.Error);
       }
     });
 
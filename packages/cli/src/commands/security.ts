@@ -0,0 +1,243 @@
+/**
+ * cv security command
+ * Security review: pattern detectors plus taint-aware model review
+ *
+ * `cv security scan` flags hard-coded secrets, SQL built from strings and
+ * command injection sinks (see security/scan.ts), then has the model review
+ * each flagged region with its enclosing function and callers from the symbol
+ * graph. Findings carry a CWE and go to the review findings store, so `cv tui`
+ * triages them and dismissed ones stay hidden; `--format sarif` feeds code
+ * scanning.
+ */
+
+import { Command } from 'commander';
+import chalk from 'chalk';
+import ora from 'ora';
+import { promises as fs } from 'fs';
+import * as path from 'path';
+import {
+  buildSecurityPrompt,
+  candidateFinding,
+  collectSecurityContexts,
+  configManager,
+  createGitManager,
+  createGraphManager,
+  generateRepoId,
+  groupFindings,
+  groupSecurityContexts,
+  hasFindingsAtOrAbove,
+  isSecurityScanned,
+  loadFindingsStore,
+  parseSecurityResponse,
+  recordFindings,
+  REVIEW_SEVERITIES,
+  ReviewSeverity,
+  saveFindingsStore,
+  scanSource,
+  SECURITY_SYSTEM_PROMPT,
+  SecurityCandidate,
+  SecurityFindingResult,
+  securitySarifRules,
+  splitDiff,
+  toSarif,
+  withoutDismissed
+} from '@cv-git/core';
+import { findRepoRoot } from '@cv-git/shared';
+import { createModelClient, fallbackNotice } from '../utils/model-client.js';
+import { loadCitationFormatter } from '../utils/citations.js';
+import { ExitCode } from '../utils/output.js';
+
+/** Larger files are generated or data, not code to review */
+const MAX_FILE_BYTES = 1024 * 1024;
+
+const SEVERITY_STYLE: Record<ReviewSeverity, (text: string) => string> = {
+  critical: chalk.red.bold,
+  high: chalk.red,
+  medium: chalk.yellow,
+  low: chalk.cyan,
+  info: chalk.gray
+};
+
+const CONFIDENCE_LABEL: Record<SecurityFindingResult['confidence'], string> = {
+  confirmed: 'confirmed',
+  uncertain: 'possible',
+  unverified: 'pattern match'
+};
+
+export function securityCommand(): Command {
+  const cmd = new Command('security').description('Security review of the codebase');
+
+  const scan = new Command('scan')
+    .description('Find secrets, SQL injection and command injection: detectors flag code, the model traces tainted input through callers')
+    .argument('[paths...]', 'Only scan files under these paths')
+    .option('--staged', 'Only scan files with staged changes')
+    .option('--range <rev1..rev2>', 'Only scan files changed between two revisions')
+    .option('--no-ai', 'Report detector matches without the model review')
+    .option('--max-regions <n>', 'Most flagged regions sent to the model; the rest are reported as pattern matches', '100')
+    .option('--format <format>', 'Output format: text, json or sarif', 'text')
+    .option('--json', 'Same as --format json')
+    .option('--fail-on <severity>', `Exit with code 1 if any finding is at or above this severity (${REVIEW_SEVERITIES.join(', ')}, none)`, 'none')
+    .option('-m, --model <model>', 'Model: a Claude model or provider/model (e.g. openai/gpt-4o)');
+
+  scan.action(async (paths: string[], options) => {
+    const format: string = options.json ? 'json' : options.format;
+    if (!['text', 'json', 'sarif'].includes(format)) {
+      console.error(chalk.red(`Unknown --format "${format}" (expected text, json or sarif)`));
+      process.exit(ExitCode.Error);
+    }
+    if (options.failOn !== 'none' && !(REVIEW_SEVERITIES as string[]).includes(options.failOn)) {
+      console.error(chalk.red(`Unknown --fail-on "${options.failOn}" (expected ${REVIEW_SEVERITIES.join(', ')} or none)`));
+      process.exit(ExitCode.Error);
+    }
+
+    // JSON and SARIF go to stdout; keep progress off it
+    const quiet = format !== 'text';
+    let spinner = ora({ text: 'Scanning...', isSilent: quiet }).start();
+
+    const repoRoot = await findRepoRoot();
+    if (!repoRoot) {
+      spinner.fail(chalk.red('Not in a CV-Git repository'));
+      console.error(chalk.gray('Run `cv init` first'));
+      process.exit(ExitCode.Error);
+    }
+
+    try {
+      const config = await configManager.load(repoRoot);
+      const git = createGitManager(repoRoot);
+      const ignore = config.security?.ignore ?? [];
+
+      let files = await git.getTrackedFiles();
+      let ref = 'HEAD';
+      if (options.staged || options.range) {
+        ref = options.staged ? 'staged' : options.range;
+        const changed = new Set(splitDiff(await git.getRawDiff(options.staged ? '--staged' : options.range)).map(s => s.file));
+        files = files.filter(file => changed.has(file));
+      }
+      if (paths.length > 0) {
+        const prefixes = paths.map(p => path.relative(repoRoot, path.resolve(process.cwd(), p)).split(path.sep).join('/'));
+        files = files.filter(file => prefixes.some(prefix => !prefix || file === prefix || file.startsWith(`${prefix}/`)));
+      }
+      files = files.filter(file => isSecurityScanned(file, ignore));
+
+      const readFile = (file: string) => readText(path.join(repoRoot, file));
+      const candidates: SecurityCandidate[] = [];
+      for (const file of files) {
+        const text = await readFile(file);
+        if (text !== null) candidates.push(...scanSource(file, text, { ignore, redaction: config.redaction }));
+      }
+      spinner.succeed(`Scanned ${files.length} file(s): ${candidates.length} flagged location(s)`);
+
+      let findings: SecurityFindingResult[] = candidates.map(candidateFinding);
+      let safe = 0;
+      if (options.ai !== false && candidates.length > 0) {
+        const limit = Math.max(1, parseInt(options.maxRegions, 10) || 100);
+        const reviewed = candidates.slice(0, limit);
+
+        spinner = ora({ text: 'Reading callers from the symbol graph...', isSilent: quiet }).start();
+        let graph: ReturnType<typeof createGraphManager> | undefined;
+        try {
+          graph = createGraphManager({ url: config.graph.url, repoId: config.repository.repoId || generateRepoId(repoRoot) });
+          await graph.connect();
+        } catch {
+          graph = undefined;
+        }
+        const contexts = await collectSecurityContexts(reviewed, { readFile, graph, redaction: config.redaction });
+        await graph?.close();
+        if (graph) spinner.succeed(`Collected ${contexts.reduce((n, c) => n + c.callers.length, 0)} caller(s) for ${contexts.length} region(s)`);
+        else spinner.warn('Symbol graph unavailable; reviewing without callers (run `cv sync`)');
+
+        const client = await createModelClient(options.model, {
+          provider: 'anthropic',
+          model: config.ai.model,
+          anthropicApiKey: config.ai.apiKey,
+          maxTokens: 8192,
+          fallback: config.ai.fallback
+        });
+
+        findings = candidates.slice(limit).map(candidateFinding);
+        const groups = groupSecurityContexts(contexts);
+        for (const [i, group] of groups.entries()) {
+          spinner = ora({ text: `Reviewing flagged code [${i + 1}/${groups.length}]...`, isSilent: quiet }).start();
+          try {
+            const response = await client.chat([{ role: 'user', content: buildSecurityPrompt(group) }], SECURITY_SYSTEM_PROMPT);
+            const result = parseSecurityResponse(response, group);
+            findings.push(...result.findings);
+            safe += result.safe;
+            spinner.succeed(`Reviewed [${i + 1}/${groups.length}]: ${result.findings.length} finding(s), ${result.safe} safe`);
+          } catch (error: any) {
+            // Keep the detector findings when the review fails
+            findings.push(...group.map(context => candidateFinding(context.candidate)));
+            spinner.warn(`Review [${i + 1}/${groups.length}] failed (${error.message}); reporting pattern matches`);
+          }
+        }
+        const notice = fallbackNotice(client);
+        if (notice && !quiet) console.log(chalk.gray(notice));
+      }
+
+      // Shared with `cv review`: triage in `cv tui`, dismissed findings stay hidden
+      const store = recordFindings(await loadFindingsStore(repoRoot), findings, `security ${ref}`);
+      await saveFindingsStore(repoRoot, store).catch(() => undefined);
+      const shown = withoutDismissed(findings, store) as SecurityFindingResult[];
+      const dismissed = findings.length - shown.length;
+      const failed = options.failOn !== 'none' && hasFindingsAtOrAbove(shown, options.failOn);
+      const exitCode = failed ? ExitCode.CheckFailed : ExitCode.Success;
+
+      if (format === 'sarif') {
+        console.log(JSON.stringify(toSarif(shown, { toolVersion: cmd.parent?.version(), rules: securitySarifRules() }), null, 2));
+        process.exit(exitCode);
+      }
+      if (format === 'json') {
+        console.log(JSON.stringify({ ref, files: files.length, flagged: candidates.length, safe, dismissed, findings: shown }, null, 2));
+        process.exit(exitCode);
+      }
+
+      const cite = await loadCitationFormatter(repoRoot);
+      console.log();
+      for (const group of groupFindings(shown)) {
+        const style = SEVERITY_STYLE[group.severity];
+        console.log(style(`● ${group.severity.toUpperCase()} (${group.findings.length})`));
+        for (const finding of group.findings as SecurityFindingResult[]) {
+          const location = cite({ file: finding.file, line: finding.line, endLine: finding.endLine });
+          console.log(`  ${location}  ${chalk.bold(finding.title)} ${chalk.gray(`[${finding.cwe}, ${CONFIDENCE_LABEL[finding.confidence]}]`)}`);
+          if (finding.message) console.log(chalk.gray(`    ${finding.message.replace(/\n/g, '\n    ')}`));
+          if (finding.taint) console.log(chalk.magenta(`    ⇢ ${finding.taint}`));
+          if (finding.suggestion) console.log(chalk.green(`    ↳ ${finding.suggestion.replace(/\n/g, '\n      ')}`));
+        }
+        console.log();
+      }
+
+      const counts = groupFindings(shown).map(g => `${g.findings.length} ${g.severity}`);
+      console.log(chalk.bold(counts.length > 0 ? `Security scan: ${counts.join(', ')}` : 'Security scan: no findings'));
+      if (safe > 0) console.log(chalk.gray(`${safe} flagged location(s) judged safe by the review`));
+      if (dismissed > 0) console.log(chalk.gray(`${dismissed} dismissed finding(s) hidden (triage with cv tui)`));
+      if (options.ai === false && shown.length > 0) console.log(chalk.gray('Pattern matches only; run without --no-ai to trace tainted input'));
+
+      if (failed) {
+        console.error(chalk.red(`Findings at or above "${options.failOn}" severity`));
+        process.exit(ExitCode.CheckFailed);
+      }
+    } catch (error: any) {
+      spinner.fail(chalk.red('Security scan failed'));
+      console.error(chalk.red(error.message));
+      process.exit(ExitCode.Error);
+    }
+  });
+
+  cmd.addCommand(scan);
+  return cmd;
+}
+
+/**
+ * File contents, or null for binary, oversized or unreadable files
+ */
+async function readText(file: string): Promise<string | null> {
+  try {
+    const stat = await fs.stat(file);
+    if (!stat.isFile() || stat.size > MAX_FILE_BYTES) return null;
+    const buffer = await fs.readFile(file);
+    if (buffer.subarray(0, 8000).includes(0)) return null;
+    return buffer.toString('utf-8');
+  } catch {
+    return null;
+  }
+}
@@ -0,0 +1,258 @@
+/**
+ * cv serve command
+ * Local web UI for searching and browsing the index and chatting with the repo
+ *
+ * Serves the page and API described in web/server.ts. Search and browsing
+ * work from the keyword index alone; semantic ranking is added when the
+ * vector store is reachable, and chat connects the model on first use.
+ */
+
+import { Command } from 'commander';
+import chalk from 'chalk';
+import { promises as fs } from 'fs';
+import * as path from 'path';
+import {
+  AIClient,
+  configManager,
+  createVectorManager,
+  getKeywordIndexPath,
+  keywordHitToResult,
+  KeywordIndex,
+  loadFindingsStore,
+  loadKeywordIndex,
+  loadScoreDistribution,
+  loadSyncHistory,
+  reciprocalRankFusion,
+  resolveMinScore,
+  OpenRouterMessage,
+  VectorManager
+} from '@cv-git/core';
+import { findRepoRoot, CodeChunkPayload, VectorSearchResult } from '@cv-git/shared';
+import { resolveEmbeddingProvider } from '../utils/embedding-provider.js';
+import { vectorStoreOptions } from '../utils/vector-store.js';
+import { createModelClient } from '../utils/model-client.js';
+import { startWebServer, WebBackend, WebChunk, WebCitation } from '../web/server.js';
+
+const SYSTEM_PROMPT = `You are an expert software engineer answering questions about a codebase.
+Numbered excerpts of the relevant code are provided with each question. Cite the excerpts you rely on as [1], [2], ... right after the statement they support, and say so when the excerpts don't answer the question.`;
+
+/** Code excerpts sent with each chat question */
+const CHAT_SOURCES = 8;
+
+/** Files checked against the last sync for the health dashboard */
+const MAX_STALE_CHECKS = 5000;
+
+export function serveCommand(): Command {
+  const cmd = new Command('serve');
+
+  cmd
+    .description('Local web UI: semantic search, chunk browsing, chat with citations and an index health dashboard')
+    .option('--port <port>', 'Port to listen on', '4000')
+    .option('--host <host>', 'Address to bind (use 0.0.0.0 to share with other machines)', '127.0.0.1')
+    .option('-m, --model <model>', 'Chat model: an OpenRouter model or provider/model (as for cv chat)');
+
+  cmd.action(async (options) => {
+    const repoRoot = await findRepoRoot();
+    if (!repoRoot) {
+      console.error(chalk.red('Not in a CV-Git repository. Run `cv init` first.'));
+      process.exit(1);
+    }
+    const port = parseInt(options.port, 10);
+    if (!(port > 0 && port < 65536)) {
+      console.error(chalk.red(`Invalid --port "${options.port}"`));
+      process.exit(1);
+    }
+
+    try {
+      const backend = await createBackend(repoRoot, options.model);
+      await startWebServer({ backend, host: options.host, port });
+    } catch (error: any) {
+      console.error(chalk.red(`Error: ${error.code === 'EADDRINUSE' ? `port ${port} is already in use (try --port)` : error.message}`));
+      process.exit(1);
+    }
+
+    const shown = options.host === '0.0.0.0' || options.host === '::' ? 'localhost' : options.host;
+    console.log(chalk.green(`✓ cv-git web UI for ${repoRoot}`));
+    console.log(`  ${chalk.cyan(`http://${shown}:${port}/`)}`);
+    if (shown === 'localhost') {
+      console.log(chalk.yellow('  Listening on all interfaces: anyone who can reach this machine can search the code'));
+    }
+    console.log(chalk.gray('  Press Ctrl+C to stop'));
+  });
+
+  return cmd;
+}
+
+function toWebChunk(result: VectorSearchResult<CodeChunkPayload>, score?: number): WebChunk {
+  const { payload } = result;
+  return {
+    file: payload.file,
+    startLine: payload.startLine,
+    endLine: payload.endLine,
+    symbolName: payload.symbolName,
+    symbolKind: payload.symbolKind,
+    language: payload.language,
+    score,
+    text: payload.text
+  };
+}
+
+async function createBackend(repoRoot: string, model?: string): Promise<WebBackend> {
+  const config = await configManager.load(repoRoot);
+
+  // Reloaded when `cv sync` rewrites it while the server runs
+  let keywordIndex: KeywordIndex = await loadKeywordIndex(repoRoot);
+  let keywordMtime = 0;
+  const currentIndex = async (): Promise<KeywordIndex> => {
+    const mtime = await fs.stat(getKeywordIndexPath(repoRoot)).then(stat => stat.mtimeMs, () => 0);
+    if (mtime !== keywordMtime) {
+      keywordMtime = mtime;
+      keywordIndex = await loadKeywordIndex(repoRoot);
+    }
+    return keywordIndex;
+  };
+
+  let vector: VectorManager | undefined;
+  let vectorError: string | undefined;
+  let minScore = 0.5;
+  try {
+    vector = createVectorManager({
+      url: config.vector.url,
+      ...vectorStoreOptions(config, repoRoot),
+      provider: await resolveEmbeddingProvider(config),
+      collections: config.vector.collections
+    });
+    await vector.connect();
+    minScore = resolveMinScore(config.retrieval, await loadScoreDistribution(repoRoot, vector.getEmbeddingInfo().model), 0.5);
+  } catch (error: any) {
+    vector = undefined;
+    vectorError = error.message.split('\n')[0];
+    console.error(chalk.gray(`Vector store unavailable, keyword search only: ${vectorError}`));
+  }
+
+  /** Semantic and keyword rankings fused, best first */
+  const rank = async (query: string, limit: number) => {
+    const index = await currentIndex();
+    const rankings: VectorSearchResult<CodeChunkPayload>[][] = [];
+    if (vector) rankings.push(await vector.searchCode(query, limit * 2, { minScore }));
+    if (index.size > 0) rankings.push(index.search(query, limit * 2).map(keywordHitToResult));
+    return reciprocalRankFusion(rankings, result => result.id)
+      .filter(({ item }) => !item.payload.synthetic)
+      .slice(0, limit);
+  };
+
+  let client: AIClient | undefined;
+
+  return {
+    async search(query, limit) {
+      return (await rank(query, limit)).map(({ item, score }) => toWebChunk(item, score));
+    },
+
+    async files() {
+      const byFile = new Map<string, { file: string; language: string; chunks: number }>();
+      for (const doc of (await currentIndex()).documents()) {
+        const entry = byFile.get(doc.file) ?? { file: doc.file, language: doc.language, chunks: 0 };
+        entry.chunks++;
+        byFile.set(doc.file, entry);
+      }
+      return [...byFile.values()].sort((a, b) => a.file.localeCompare(b.file));
+    },
+
+    async chunks(file) {
+      return (await currentIndex()).documents()
+        .filter(doc => doc.file === file)
+        .sort((a, b) => a.startLine - b.startLine)
+        .map(doc => ({
+          file: doc.file,
+          startLine: doc.startLine,
+          endLine: doc.endLine,
+          symbolName: doc.symbolName,
+          symbolKind: doc.symbolKind,
+          language: doc.language,
+          text: doc.text
+        }));
+    },
+
+    async health() {
+      const docs = (await currentIndex()).documents();
+      const files = [...new Set(docs.map(doc => doc.file))];
+      const languages: Record<string, number> = {};
+      for (const doc of docs) languages[doc.language] = (languages[doc.language] ?? 0) + 1;
+
+      const history = await loadSyncHistory(repoRoot, 10);
+      const lastSync = history[0];
+
+      // Indexed files edited or removed since the last sync
+      const stale = { modified: 0, deleted: 0, examples: [] as string[] };
+      if (lastSync) {
+        for (const file of files.slice(0, MAX_STALE_CHECKS)) {
+          const mtime = await fs.stat(path.join(repoRoot, file)).then(stat => stat.mtimeMs, () => null);
+          if (mtime !== null && mtime <= lastSync.timestamp) continue;
+          if (mtime === null) stale.deleted++;
+          else stale.modified++;
+          if (stale.examples.length < 5) stale.examples.push(file);
+        }
+      }
+
+      let points: number | undefined;
+      if (vector) {
+        points = await vector.getCollectionInfo(vector.getCollectionNames().codeChunks)
+          .then(info => info.points_count as number, () => undefined);
+      }
+
+      const findings = (await loadFindingsStore(repoRoot)).findings;
+      const countStatus = (status: string) => findings.filter(finding => finding.status === status).length;
+
+      return {
+        repo: path.basename(repoRoot),
+        index: { chunks: docs.length, files: files.length, languages },
+        vector: vector
+          ? { connected: true, ...vector.getEmbeddingInfo(), points }
+          : { connected: false, error: vectorError },
+        lastSync: lastSync
+          ? { timestamp: lastSync.timestamp, type: lastSync.type, success: lastSync.success, duration: lastSync.duration, errors: lastSync.errors.length }
+          : null,
+        recentSyncs: history.map(sync => ({
+          timestamp: sync.timestamp,
+          type: sync.type,
+          success: sync.success,
+          duration: sync.duration,
+          filesProcessed: sync.stats.filesProcessed
+        })),
+        stale,
+        findings: { open: countStatus('open'), accepted: countStatus('accepted'), dismissed: countStatus('dismissed') }
+      };
+    },
+
+    async chat(messages, handlers) {
+      const question = messages[messages.length - 1].content;
+      const hits = await rank(question, CHAT_SOURCES);
+      const sources: WebCitation[] = hits.map(({ item }, i) => ({
+        index: i + 1,
+        file: item.payload.file,
+        startLine: item.payload.startLine,
+        endLine: item.payload.endLine,
+        symbolName: item.payload.symbolName
+      }));
+      handlers.onSources(sources);
+
+      client ??= await createModelClient(model, {
+        provider: 'openrouter',
+        model: 'claude-sonnet-4-5',
+        fallback: config.ai.fallback
+      });
+
+      const excerpts = hits.map(({ item }, i) => {
+        const { file, startLine, endLine, symbolName, language, text } = item.payload;
+        return `[${i + 1}] ${file}:${startLine}-${endLine}${symbolName ? ` (${symbolName})` : ''}\n\`\`\`${language}\n${text}\n\`\`\``;
+      });
+      const prompt: OpenRouterMessage[] = messages.map((message, i) => ({
+        role: message.role,
+        content: i === messages.length - 1 && excerpts.length > 0
+          ? `<codebase_context>\n${excerpts.join('\n\n')}\n</codebase_context>\n\n${message.content}`
+          : message.content
+      }));
+      return client.chatStream(prompt, SYSTEM_PROMPT, { onToken: handlers.onToken });
+    }
+  };
+}
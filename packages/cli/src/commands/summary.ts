@@ -16,11 +16,11 @@ import {
   readManifest
 } from '@cv-git/core';
 import { findRepoRoot, getCVDir } from '@cv-git/shared';
-import { CredentialManager } from '@cv-git/credentials';
 import { addGlobalOptions, createOutput } from '../utils/output.js';
-import { ensureFalkorDB, ensureQdrant, ensureOllama } from '../utils/infrastructure.js';
+import { ensureFalkorDB, ensureQdrant } from '../utils/infrastructure.js';
 import { getAnthropicApiKey } from '../utils/credentials.js';
-import { getPreferences } from '../config.js';
+import { resolveEmbeddingProvider } from '../utils/embedding-provider.js';
+import { vectorStoreOptions, usesEmbeddedVectorStore } from '../utils/vector-store.js';
 
 export function summaryCommand(): Command {
   const cmd = new Command('summary');
@@ -91,58 +91,20 @@ export function summaryCommand(): Command {
 
       // Set up vector manager (optional)
       let vector = undefined;
-      const prefsManager = getPreferences();
-      const prefs = await prefsManager.load();
-      const embeddingProvider = config.embedding?.provider || prefs.embeddingProvider || 'ollama';
-
-      // Try to get embedding credentials
-      let openaiApiKey = config.ai?.apiKey || process.env.OPENAI_API_KEY;
-      let openrouterApiKey = process.env.OPENROUTER_API_KEY;
-      let ollamaUrl: string | undefined;
-      let lmstudioUrl: string | undefined;
-
-      try {
-        const credentials = new CredentialManager();
-        await credentials.init();
-        if (!openaiApiKey) {
-          openaiApiKey = await credentials.getOpenAIKey() || undefined;
-        }
-        if (!openrouterApiKey) {
-          openrouterApiKey = await credentials.getOpenRouterKey() || undefined;
-        }
-      } catch {
-        // Credential manager not available
-      }
-
-      // Set up embedding provider
-      if (embeddingProvider === 'ollama') {
-        const ollamaInfo = await ensureOllama({ silent: true });
-        if (ollamaInfo) {
-          ollamaUrl = ollamaInfo.url;
-        }
-      } else if (embeddingProvider === 'lmstudio') {
-        const { isLMStudioRunning, getLMStudioUrl } = await import('@cv-git/core');
-        const lmUrl = getLMStudioUrl();
-        if (await isLMStudioRunning(lmUrl)) {
-          lmstudioUrl = lmUrl;
-        }
-      }
+      const provider = await resolveEmbeddingProvider(config).catch(() => undefined);
 
       // Set up Qdrant if we have embedding capability
-      const hasEmbeddingCapability = ollamaUrl || lmstudioUrl || openaiApiKey || openrouterApiKey;
-      if (hasEmbeddingCapability && config.vector) {
+      if (provider && config.vector) {
         try {
-          const qdrantInfo = await ensureQdrant({ silent: true });
+          const qdrantInfo = usesEmbeddedVectorStore(config)
+            ? { url: config.vector.url }
+            : await ensureQdrant({ silent: true });
           if (qdrantInfo) {
-            const useLocal = !!(ollamaUrl || lmstudioUrl);
             vector = createVectorManager({
               url: qdrantInfo.url,
+              ...vectorStoreOptions(config, repoRoot),
               repoId,
-              ollamaUrl,
-              lmstudioUrl,
-              openrouterApiKey: useLocal ? undefined : openrouterApiKey,
-              openaiApiKey: useLocal ? undefined : openaiApiKey,
-              vectorSize: (embeddingProvider === 'ollama' || embeddingProvider === 'lmstudio') ? 768 : 1536
+              provider
             });
             await vector.connect();
           }
@@ -5,6 +5,7 @@
 
 import { Command } from 'commander';
 import chalk from 'chalk';
+import Table from 'cli-table3';
 import { Ora } from 'ora';
 import { execSync, spawn } from 'child_process';
 import {
@@ -14,10 +15,23 @@ import {
   createGraphManager,
   createVectorManager,
   createSyncEngine,
+  createAnthropicFileSummarizer,
   exportToStorage,
   generateRepoId,
+  isOffline,
   readManifest,
-  createCodebaseSummaryService
+  createCodebaseSummaryService,
+  loadSyncHistory,
+  loadCvIgnore,
+  selectSyncFiles,
+  getDefaultIndexedLanguages,
+  CVIGNORE_FILE,
+  EmbeddingProvider,
+  SkippedFile,
+  SkipReason,
+  EmbedProgress,
+  SyncEngine,
+  SyncReport
 } from '@cv-git/core';
 import {
   findRepoRoot,
@@ -27,15 +41,25 @@ import {
   CVWorkspace,
   WorkspaceRepo,
   getCVDir,
+  formatDuration,
 } from '@cv-git/shared';
 import * as fs from 'fs/promises';
 import * as path from 'path';
-import { CredentialManager } from '@cv-git/credentials';
-import { addGlobalOptions, createOutput } from '../utils/output.js';
+import {
+  addGlobalOptions,
+  addJsonlOption,
+  createJsonReporter,
+  createOutput,
+  ErrorCode,
+  ExitCode,
+  JsonReporter
+} from '../utils/output.js';
 import { checkCredentials, displayCompactStatus } from '../utils/config-check.js';
+import { progressBar } from '../utils/formatting.js';
 import { getAnthropicApiKey } from '../utils/credentials.js';
-import { ensureFalkorDB, ensureQdrant, ensureOllama, isDockerAvailable } from '../utils/infrastructure.js';
-import { getPreferences } from '../config.js';
+import { ensureFalkorDB, ensureQdrant, isDockerAvailable } from '../utils/infrastructure.js';
+import { resolveEmbeddingProvider } from '../utils/embedding-provider.js';
+import { vectorStoreOptions, usesEmbeddedVectorStore } from '../utils/vector-store.js';
 
 export function syncCommand(): Command {
   const cmd = new Command('sync');
@@ -48,17 +72,26 @@ export function syncCommand(): Command {
     .option('--force', 'Force full rebuild (clears graph first)')
     .option('--reset-delta', 'Reset delta tracking (forces full sync next time)')
     .option('--max-files <number>', 'Maximum number of files to process per run (for large repos)', parseInt)
-    .option('--batch-size <number>', 'Batch size for embedding generation (default: 50)', parseInt)
+    .option('--batch-size <number>', 'Batch size for embedding generation (default: the provider\'s request limit)', parseInt)
+    .option('--concurrency <number>', 'Embedding requests in flight at once (default: the provider\'s concurrency limit)', parseInt)
     .option('--continue', 'Continue from where the last chunked sync left off')
     .option('--no-embeddings', 'Skip vector embeddings (graph-only sync)')
     .option('--summaries', 'Generate hierarchical summaries for changed symbols (default: enabled)')
     .option('--no-summaries', 'Skip summary generation')
     .option('--summary-strategy <strategy>', 'Summary cost strategy: free, budget, quality (default: free)', 'free')
-    .option('--summary-budget <cents>', 'Maximum LLM budget in cents for summary generation (default: 5)', parseInt);
+    .option('--summary-budget <cents>', 'Maximum LLM budget in cents for summary generation (default: 5)', parseInt)
+    .option('--no-oversized-summaries', 'Skip oversized/generated files instead of indexing a summary chunk')
+    .option('--report', 'Print what changed in the index: files, chunks embedded, tokens and time per phase')
+    .option('--show-skipped', 'List tracked files excluded from the index and why, without syncing');
 
   addGlobalOptions(cmd);
+  addJsonlOption(cmd);
+  cmd.addCommand(syncHistoryCommand());
 
   cmd.action(async (options) => {
+      const report = createJsonReporter('sync', options);
+      // The sync engine reports progress with console.log
+      report?.captureLogs();
       const output = createOutput(options);
       let spinner: any;
 
@@ -66,8 +99,15 @@ export function syncCommand(): Command {
         // Find repository root
         const repoRoot = await findRepoRoot();
         if (!repoRoot) {
+          report?.fail('Not in a CV-Git repository. Run `cv init` first.', ErrorCode.NOT_INITIALIZED);
           console.error(chalk.red('Not in a CV-Git repository. Run `cv init` first.'));
-          process.exit(1);
+          process.exit(ExitCode.Error);
+        }
+
+        if (options.showSkipped) {
+          const config = await configManager.load(repoRoot);
+          await showSkippedFiles(repoRoot, config, report);
+          return;
         }
 
         // Check if this is a workspace
@@ -80,11 +120,20 @@ export function syncCommand(): Command {
         spinner.succeed('Configuration loaded');
         displayCompactStatus(credStatus);
 
+        // A shared index is rebuilt by the server's own sync; locally only the overlay is synced
+        if (config.index?.remote && (options.full || options.force || options.maxFiles || options.continue)) {
+          const message = `--full, --force and chunked syncs rebuild the whole index; this repository uses the shared index at ${config.index.remote}`;
+          report?.fail(message, ErrorCode.INVALID_INPUT);
+          console.error(chalk.red(message));
+          process.exit(ExitCode.Error);
+        }
+
         if (workspace) {
           // Workspace mode - sync all repos
           console.log(chalk.cyan(`\nWorkspace: ${workspace.name}`));
           console.log(chalk.gray(`Repos: ${workspace.repos.map(r => r.name).join(', ')}\n`));
           await syncWorkspace(workspace, config, options, output);
+          report?.result({ type: 'workspace', workspace: workspace.name, repos: workspace.repos.map(r => r.name) });
           return;
         }
 
@@ -101,20 +150,22 @@ export function syncCommand(): Command {
         // Git manager
         const git = createGitManager(repoRoot);
         if (!(await git.isGitRepo())) {
+          report?.fail('Not a git repository', ErrorCode.NOT_GIT_REPO);
           spinner.fail(chalk.red('Not a git repository'));
-          process.exit(1);
+          process.exit(ExitCode.Error);
         }
 
         // Parser
-        const parser = createParser();
+        const parser = createParser({ chunking: config.sync?.chunking });
 
         // Graph manager - auto-start FalkorDB if configured for embedded mode
         spinner.text = 'Setting up FalkorDB...';
 
         const falkorInfo = await ensureFalkorDB({ silent: true });
         if (!falkorInfo) {
+          report?.fail('FalkorDB not available (Docker required)', ErrorCode.FALKORDB_ERROR);
           spinner.fail('FalkorDB not available (Docker required)');
-          process.exit(1);
+          process.exit(ExitCode.Error);
         }
 
         const graphUrl = falkorInfo.url;
@@ -135,160 +186,72 @@ export function syncCommand(): Command {
         await graph.connect();
         spinner.succeed(`Connected to FalkorDB: ${graph.getDatabaseName()}`);
 
-        // Vector manager - check embedding provider preference
+        // Vector manager - resolve the embedding provider from config and preferences
         let vector = undefined;
-        let ollamaUrl: string | undefined;
-        let lmstudioUrl: string | undefined;
-        let openaiApiKey = config.ai.apiKey || process.env.OPENAI_API_KEY;
-        let openrouterApiKey = process.env.OPENROUTER_API_KEY;
-
-        // Load user preferences to determine embedding provider
-        const prefsManager = getPreferences();
-        const prefs = await prefsManager.load();
-        const embeddingProvider = config.embedding?.provider || prefs.embeddingProvider || 'ollama';
-
-        output.debug(`Embedding provider preference: ${embeddingProvider}`);
+        let embeddingProvider: EmbeddingProvider | undefined;
 
-        // Try to get cloud API keys from credential manager if not in config/env
-        let anthropicApiKey: string | undefined;
-        try {
-          const credentials = new CredentialManager();
-          await credentials.init();
-
-          if (!openaiApiKey) {
-            const credKey = await credentials.getOpenAIKey();
-            output.debug(`OpenAI credential lookup: ${credKey ? 'found' : 'not found'}`);
-            openaiApiKey = credKey || undefined;
-          }
-
-          if (!openrouterApiKey) {
-            const routerKey = await credentials.getOpenRouterKey();
-            output.debug(`OpenRouter credential lookup: ${routerKey ? 'found' : 'not found'}`);
-            openrouterApiKey = routerKey || undefined;
-          }
-
-          // Get Anthropic key for codebase summary generation
-          anthropicApiKey = config.ai?.apiKey || await getAnthropicApiKey() || undefined;
-        } catch (credError: any) {
-          output.debug(`Credential manager error: ${credError.message}`);
-        }
+        // Get Anthropic key for codebase summary generation (none offline: summaries wait for the next sync)
+        const anthropicApiKey = isOffline() ? undefined : config.ai?.apiKey || await getAnthropicApiKey() || undefined;
 
-        // Set up embedding provider based on preference
-        if (embeddingProvider === 'ollama') {
-          // Ollama for local embeddings (default)
-          spinner = output.spinner('Setting up Ollama for embeddings...').start();
-
-          const ollamaInfo = await ensureOllama({
-            silent: true,
-            pullModel: true,
-            model: config.embedding?.model || 'nomic-embed-text',
-          });
-
-          if (ollamaInfo) {
-            ollamaUrl = ollamaInfo.url;
-            if (ollamaInfo.started) {
-              spinner.succeed(`Ollama started on ${ollamaUrl}`);
-            } else {
-              spinner.succeed(`Using Ollama at ${ollamaUrl}`);
-            }
-
-            if (!ollamaInfo.modelReady) {
-              spinner = output.spinner('Downloading embedding model...').start();
-              // Model will be pulled on first use
-              spinner.succeed('Embedding model ready');
-            }
-
-            // Set environment for VectorManager
-            process.env.CV_EMBEDDING_PROVIDER = 'ollama';
-            process.env.CV_OLLAMA_URL = ollamaUrl;
-            process.env.CV_EMBEDDING_MODEL = config.embedding?.model || 'nomic-embed-text';
-          } else {
-            spinner.warn('Ollama not available (Docker required)');
-            // Fall back to cloud providers if Ollama unavailable
-            if (openrouterApiKey) {
-              output.info('Falling back to OpenRouter for embeddings...');
-              if (!process.env.OPENROUTER_API_KEY) {
-                process.env.OPENROUTER_API_KEY = openrouterApiKey;
-              }
-              process.env.CV_EMBEDDING_MODEL = 'openai/text-embedding-3-small';
-            } else if (openaiApiKey) {
-              output.info('Falling back to OpenAI for embeddings...');
-            } else {
-              output.info('Continuing without vector embeddings...');
-            }
-          }
-        } else if (embeddingProvider === 'lmstudio') {
-          // LM Studio for local embeddings
-          const { isLMStudioRunning, getLMStudioUrl } = await import('@cv-git/core');
-          const lmUrl = getLMStudioUrl();
-          const lmAvailable = await isLMStudioRunning(lmUrl);
-          if (lmAvailable) {
-            lmstudioUrl = lmUrl;
-            output.info(`Using LM Studio at ${lmUrl}`);
-            process.env.CV_EMBEDDING_PROVIDER = 'lmstudio';
-            process.env.CV_LMSTUDIO_URL = lmUrl;
-          } else {
-            output.warn('LM Studio not running. Start with: lms server start');
-            if (openrouterApiKey) {
-              output.info('Falling back to OpenRouter for embeddings...');
-              if (!process.env.OPENROUTER_API_KEY) {
-                process.env.OPENROUTER_API_KEY = openrouterApiKey;
-              }
-            }
-          }
-        } else if (embeddingProvider === 'openrouter' && openrouterApiKey) {
-          // Use OpenRouter for embeddings
-          if (!process.env.OPENROUTER_API_KEY) {
-            process.env.OPENROUTER_API_KEY = openrouterApiKey;
-          }
-          if (!process.env.CV_EMBEDDING_MODEL) {
-            process.env.CV_EMBEDDING_MODEL = 'openai/text-embedding-3-small';
+        if (options.embeddings !== false) {
+          spinner = output.spinner('Setting up embeddings...').start();
+          try {
+            embeddingProvider = await resolveEmbeddingProvider(config, {
+              pullModel: true,
+              onStatus: message => { spinner.text = message; }
+            });
+            spinner.succeed(`Embeddings: ${embeddingProvider.name} (${embeddingProvider.model})`);
+          } catch (error: any) {
+            spinner.warn(error.message.split('\n')[0]);
+            output.info('Continuing without vector embeddings...');
           }
         }
 
         // Set up Qdrant if we have any embedding capability
         const skipEmbeddings = options.embeddings === false;
-        const hasEmbeddingCapability = ollamaUrl || lmstudioUrl || openaiApiKey || openrouterApiKey;
+        const hasEmbeddingCapability = !!embeddingProvider;
 
         if (skipEmbeddings) {
           output.info('Skipping vector embeddings (--no-embeddings)');
         } else if (hasEmbeddingCapability && config.vector) {
-          spinner = output.spinner('Setting up Qdrant...').start();
-
-          const qdrantInfo = await ensureQdrant({ silent: true });
+          const storeOptions = vectorStoreOptions(config, repoRoot);
           let qdrantUrl = '';
 
-          if (qdrantInfo) {
-            qdrantUrl = qdrantInfo.url;
-            if (qdrantInfo.started) {
-              spinner.succeed(`Qdrant started on ${qdrantUrl}`);
-            } else {
-              spinner.succeed(`Using Qdrant at ${qdrantUrl}`);
-            }
+          if (usesEmbeddedVectorStore(config)) {
+            // Embedded index under .cv/index - no server to start
+            qdrantUrl = config.vector.url;
+          } else {
+            spinner = output.spinner('Setting up Qdrant...').start();
+            const qdrantInfo = await ensureQdrant({ silent: true });
+
+            if (qdrantInfo) {
+              qdrantUrl = qdrantInfo.url;
+              if (qdrantInfo.started) {
+                spinner.succeed(`Qdrant started on ${qdrantUrl}`);
+              } else {
+                spinner.succeed(`Using Qdrant at ${qdrantUrl}`);
+              }
 
-            // Update config with the actual URL we're using
-            if (qdrantUrl !== config.vector.url) {
-              await configManager.update({ vector: { ...config.vector, url: qdrantUrl } });
+              // Update config with the actual URL we're using
+              if (qdrantUrl !== config.vector.url) {
+                await configManager.update({ vector: { ...config.vector, url: qdrantUrl } });
+              }
+            } else {
+              spinner.warn('Qdrant not available (Docker required)');
+              output.info('Continuing without vector search...');
             }
-          } else {
-            spinner.warn('Qdrant not available (Docker required)');
-            output.info('Continuing without vector search...');
           }
 
           if (qdrantUrl) {
             try {
-              spinner = output.spinner('Connecting to Qdrant...').start();
+              spinner = output.spinner(`Connecting to ${storeOptions.storeType} vector store...`).start();
               // Create vector manager with repo-specific collections for isolation
-              const useLocal = !!(ollamaUrl || lmstudioUrl);
               vector = createVectorManager({
                 url: qdrantUrl,
+                ...storeOptions,
                 repoId,
-                ollamaUrl,
-                lmstudioUrl,
-                openrouterApiKey: useLocal ? undefined : openrouterApiKey,
-                openaiApiKey: useLocal ? undefined : openaiApiKey,
-                cacheDir: path.join(repoRoot, '.cv', 'embeddings'),
-                vectorSize: (embeddingProvider === 'ollama' || embeddingProvider === 'lmstudio') ? 768 : 1536,
+                provider: embeddingProvider,
+                cacheDir: path.join(repoRoot, '.cv', 'embeddings')
               });
               await vector.connect();
 
@@ -304,9 +267,9 @@ export function syncCommand(): Command {
                 }
               }
 
-              spinner.succeed(`Connected to Qdrant (collections: ${repoId}_*)`);
+              spinner.succeed(`Connected to ${storeOptions.storeType} vector store (collections: ${repoId}_*)`);
             } catch (error: any) {
-              spinner.warn(`Could not connect to Qdrant: ${error.message}`);
+              spinner.warn(`Could not connect to ${storeOptions.storeType} vector store: ${error.message}`);
               output.info('Continuing without vector search...');
               vector = undefined;
             }
@@ -319,11 +282,25 @@ export function syncCommand(): Command {
         // Sync engine
         const syncEngine = createSyncEngine(repoRoot, git, parser, graph, vector);
 
+        syncEngine.setEmbeddingOptions({
+          batchSize: options.batchSize ?? config.embedding?.batchSize,
+          concurrency: options.concurrency ?? config.embedding?.concurrency,
+          onProgress: report || output.isJson ? undefined : embedProgressRenderer()
+        });
+
+        // Oversized/generated files get a summary chunk; use the LLM only when a paid strategy is chosen
+        if (options.oversizedSummaries === false) {
+          syncEngine.setOversizedFileOptions({ enabled: false });
+        } else if (anthropicApiKey && options.summaryStrategy !== 'free') {
+          syncEngine.setFileSummarizer(createAnthropicFileSummarizer(anthropicApiKey));
+        }
+
         // Handle delta reset
         if (options.resetDelta) {
           spinner = output.spinner('Resetting delta tracking...').start();
           await syncEngine.resetDelta();
           spinner.succeed('Delta tracking reset. Next sync will be a full sync.');
+          report?.result({ type: 'reset-delta' });
           await graph.close();
           if (vector) await vector.close();
           return;
@@ -384,6 +361,7 @@ export function syncCommand(): Command {
             }
           }
 
+          report?.result({ type: 'chunked', progress: result.progress, report: await syncEngine.getSyncReport() });
           await graph.close();
           if (vector) await vector.close();
           return;
@@ -422,6 +400,8 @@ export function syncCommand(): Command {
 
             const graphStats = await graph.getStats();
             displaySyncResults(syncState, graphStats);
+            if (options.report) await printLatestSyncReport(syncEngine);
+            report?.result({ type: 'incremental', report: await syncEngine.getSyncReport(), graph: graphStats });
             await graph.close();
             if (vector) await vector.close();
             return;
@@ -450,6 +430,7 @@ export function syncCommand(): Command {
 
           const graphStats = await graph.getStats();
           displayDeltaSyncResults(syncState, graphStats);
+          if (options.report) await printLatestSyncReport(syncEngine);
 
           // Export to .cv/ if anything changed
           if (syncState.delta.added.length > 0 ||
@@ -476,6 +457,16 @@ export function syncCommand(): Command {
             await generateCodebaseSummary(repoRoot, config, graph, vector, anthropicApiKey, output);
           }
 
+          report?.result({
+            type: 'delta',
+            changes: {
+              added: syncState.delta.added,
+              modified: syncState.delta.modified,
+              deleted: syncState.delta.deleted
+            },
+            report: await syncEngine.getSyncReport(),
+            graph: graphStats
+          });
           await graph.close();
           if (vector) await vector.close();
           return;
@@ -504,6 +495,7 @@ export function syncCommand(): Command {
 
         const graphStats = await graph.getStats();
         displaySyncResults(syncState, graphStats);
+        if (options.report) await printLatestSyncReport(syncEngine);
 
         // Export to .cv/ files for portability
         console.log();
@@ -541,6 +533,8 @@ export function syncCommand(): Command {
           output.debug(importError.stack);
         }
 
+        report?.result({ type: 'full', report: await syncEngine.getSyncReport(), graph: graphStats });
+
         // Close connections
         await graph.close();
         if (vector) {
@@ -548,6 +542,7 @@ export function syncCommand(): Command {
         }
 
       } catch (error: any) {
+        report?.fail(`Sync failed: ${error.message}`, error.message.includes('ECONNREFUSED') ? ErrorCode.SERVICE_UNAVAILABLE : ErrorCode.OPERATION_FAILED);
         if (spinner) {
           spinner.fail(chalk.red('Sync failed'));
         } else {
@@ -573,13 +568,161 @@ export function syncCommand(): Command {
           console.error(chalk.gray('  pnpm install'));
         }
 
-        process.exit(1);
+        process.exit(ExitCode.Error);
       }
     });
 
   return cmd;
 }
 
+/**
+ * cv sync history - past sync reports
+ */
+function syncHistoryCommand(): Command {
+  const cmd = new Command('history');
+
+  cmd
+    .description('Show past sync reports (index churn, embedding spend, duration)')
+    .option('-n, --limit <number>', 'Number of syncs to show', '20');
+
+  addGlobalOptions(cmd);
+
+  cmd.action(async (options) => {
+    const report = createJsonReporter('sync history', options);
+    const repoRoot = await findRepoRoot();
+    if (!repoRoot) {
+      report?.fail('Not in a CV-Git repository. Run `cv init` first.', ErrorCode.NOT_INITIALIZED);
+      console.error(chalk.red('Not in a CV-Git repository. Run `cv init` first.'));
+      process.exit(ExitCode.Error);
+    }
+
+    const history = await loadSyncHistory(repoRoot, parseInt(options.limit, 10));
+    if (report) {
+      report.result({ syncs: history });
+      return;
+    }
+    if (history.length === 0) {
+      console.log(chalk.yellow('No sync history yet. Run `cv sync` first.'));
+      return;
+    }
+
+    const table = new Table({
+      head: ['When', 'Type', '+', '~', '-', 'Chunks', 'Cached', 'Tokens', 'Cost', 'Duration'].map(h => chalk.bold(h)),
+      chars: { 'mid': '', 'left-mid': '', 'mid-mid': '', 'right-mid': '' }
+    });
+    for (const sync of history) {
+      table.push([
+        new Date(sync.timestamp).toLocaleString(),
+        sync.success ? sync.type : chalk.red(sync.type),
+        sync.changes?.added ?? '-',
+        sync.changes?.modified ?? '-',
+        sync.changes?.deleted ?? '-',
+        sync.embeddings?.chunks ?? '-',
+        sync.embeddings?.cached ?? '-',
+        sync.embeddings ? sync.embeddings.estimatedTokens.toLocaleString() : '-',
+        sync.summaryStats ? `${sync.summaryStats.estimatedCostCents}¢` : '-',
+        `${sync.duration.toFixed(1)}s`
+      ]);
+    }
+    console.log(table.toString());
+
+    const tokens = history.reduce((sum, r) => sum + (r.embeddings?.estimatedTokens || 0), 0);
+    console.log(chalk.gray(`
+${history.length} sync(s), ~${tokens.toLocaleString()} embedding tokens`));
+  });
+
+  return cmd;
+}
+
+/**
+ * cv sync --show-skipped - audit the index exclusion rules
+ */
+async function showSkippedFiles(repoRoot: string, config: any, report: JsonReporter | null): Promise<void> {
+  const files = await createGitManager(repoRoot).getTrackedFiles();
+  const ignore = await loadCvIgnore(repoRoot);
+  const selection = selectSyncFiles(files, ignore, {
+    excludePatterns: config.sync?.excludePatterns,
+    includeLanguages: config.sync?.includeLanguages?.length ? config.sync.includeLanguages : getDefaultIndexedLanguages()
+  });
+
+  if (report) {
+    selection.skipped.forEach(skipped => report.event('skipped', skipped));
+    report.result({ type: 'show-skipped', tracked: files.length, indexed: selection.files.length, skipped: selection.skipped });
+    return;
+  }
+
+  // Group by the rule that excluded each file
+  const groups = new Map<string, SkippedFile[]>();
+  for (const skipped of selection.skipped) {
+    const key = `${skipped.reason}\0${skipped.detail}`;
+    if (!groups.has(key)) groups.set(key, []);
+    groups.get(key)!.push(skipped);
+  }
+
+  const labels: Record<SkipReason, string> = {
+    'default': 'built-in',
+    'cvignore': CVIGNORE_FILE,
+    'exclude-pattern': 'sync.excludePatterns',
+    'binary': 'binary',
+    'language': 'not indexed'
+  };
+  const SHOWN = 10;
+
+  for (const group of [...groups.values()].sort((a, b) => b.length - a.length)) {
+    const first = group[0];
+    const where = first.line !== undefined ? `${labels[first.reason]}:${first.line}` : labels[first.reason];
+    console.log(`${chalk.bold(first.detail)} ${chalk.gray(`(${where})`)} ${chalk.yellow(`${group.length} file${group.length === 1 ? '' : 's'}`)}`);
+    for (const skipped of group.slice(0, SHOWN)) {
+      console.log(chalk.gray(`  ${skipped.file}`));
+    }
+    if (group.length > SHOWN) {
+      console.log(chalk.gray(`  … and ${group.length - SHOWN} more`));
+    }
+  }
+
+  console.log();
+  console.log(`${selection.files.length} of ${files.length} tracked files indexed, ${selection.skipped.length} skipped.`);
+  console.log(chalk.gray(`Add gitignore-style rules to ${CVIGNORE_FILE} to exclude more; \`!pattern\` re-includes a file.`));
+}
+
+/**
+ * Print the report the sync just saved
+ */
+async function printLatestSyncReport(syncEngine: SyncEngine): Promise<void> {
+  const report = await syncEngine.getSyncReport();
+  if (report) displaySyncReport(report);
+}
+
+function displaySyncReport(report: SyncReport): void {
+  console.log();
+  console.log(chalk.bold('Sync Report:'));
+  console.log(chalk.gray('─'.repeat(50)));
+
+  if (report.changes) {
+    const { added, modified, deleted } = report.changes;
+    console.log(chalk.cyan('  Files:             '),
+      `${chalk.green(`+${added}`)} ${chalk.yellow(`~${modified}`)} ${chalk.red(`-${deleted}`)}`);
+  }
+  if (report.embeddings) {
+    const e = report.embeddings;
+    console.log(chalk.cyan('  Chunks embedded:   '), `${e.chunks - e.cached} (${e.cached} from cache)`);
+    console.log(chalk.cyan('  Embedding tokens:  '), `~${e.estimatedTokens.toLocaleString()} (${e.provider}/${e.model})`);
+  }
+  if (report.summaryStats) {
+    console.log(chalk.cyan('  Summaries:         '),
+      `${report.summaryStats.generated} (${report.summaryStats.llmCalls} LLM calls, ${report.summaryStats.estimatedCostCents}¢)`);
+  }
+
+  const phases = Object.entries(report.phases || {}).filter(([, ms]) => ms && ms > 0);
+  if (phases.length > 0) {
+    console.log(chalk.cyan('  Phases:'));
+    for (const [phase, ms] of phases) {
+      console.log(`    ${phase.padEnd(18)} ${((ms as number) / 1000).toFixed(1)}s`);
+    }
+  }
+  console.log(chalk.cyan('  Total:             '), `${report.duration.toFixed(1)}s`);
+}
+
 /**
  * Sync all repos in a workspace
  */
@@ -595,29 +738,17 @@ async function syncWorkspace(
   const falkorInfo = await ensureFalkorDB({ silent: true });
   if (!falkorInfo) {
     spinner.fail('FalkorDB not available (Docker required)');
-    process.exit(1);
+    process.exit(ExitCode.Error);
   }
   spinner.succeed(`Using FalkorDB at ${falkorInfo.url}`);
 
   // Set up Qdrant if we have API keys
-  let openaiApiKey = config.ai?.apiKey || process.env.OPENAI_API_KEY;
-  let openrouterApiKey = process.env.OPENROUTER_API_KEY;
-
-  try {
-    const credentials = new CredentialManager();
-    await credentials.init();
-    if (!openaiApiKey) {
-      openaiApiKey = await credentials.getOpenAIKey() || undefined;
-    }
-    if (!openrouterApiKey) {
-      openrouterApiKey = await credentials.getOpenRouterKey() || undefined;
-    }
-  } catch {
-    // Credential manager not available
-  }
+  const embeddingProvider = await resolveEmbeddingProvider(config).catch(() => undefined);
 
   let qdrantUrl: string | null = null;
-  if (openaiApiKey || openrouterApiKey) {
+  if (embeddingProvider && usesEmbeddedVectorStore(config)) {
+    qdrantUrl = config.vector.url;
+  } else if (embeddingProvider) {
     const qdrantInfo = await ensureQdrant({ silent: true });
     if (qdrantInfo) {
       qdrantUrl = qdrantInfo.url;
@@ -634,12 +765,12 @@ async function syncWorkspace(
 
   // Set up vector if available
   let vector: any = null;
-  if (qdrantUrl && (openaiApiKey || openrouterApiKey)) {
+  if (qdrantUrl && embeddingProvider) {
     try {
       vector = createVectorManager({
         url: qdrantUrl,
-        openrouterApiKey,
-        openaiApiKey,
+        ...vectorStoreOptions(config, workspace.root),
+        provider: embeddingProvider,
         collections: config.vector?.collections || { codeChunks: 'code_chunks', docstrings: 'docstrings', commits: 'commits' },
         cacheDir: path.join(workspace.root, '.cv', 'embeddings')  // Content-addressed cache
       });
@@ -758,7 +889,7 @@ async function syncSingleRepoInWorkspace(
   const lastCommit = commits.length > 0 ? commits[0].sha : undefined;
 
   // Create parser
-  const parser = createParser();
+  const parser = createParser({ chunking: config.sync?.chunking });
 
   // Create sync engine with repo prefix for file paths
   const syncEngine = createSyncEngine(repoPath, git, parser, graph, vector);
@@ -780,6 +911,30 @@ async function syncSingleRepoInWorkspace(
   };
 }
 
+/**
+ * Progress bar with throughput and ETA for the embedding phase; redrawn in
+ * place on a terminal, a line per quarter otherwise
+ */
+function embedProgressRenderer(): (progress: EmbedProgress) => void {
+  const interactive = !!process.stdout.isTTY;
+  let lastQuarter = 0;
+  return progress => {
+    const line = `  ${progressBar(progress.done, progress.total)} ${progress.done}/${progress.total} chunks` +
+      chalk.gray(` · ${progress.rate.toFixed(1)} chunks/s` +
+        (progress.etaMs !== undefined && progress.done < progress.total ? ` · ETA ${formatDuration(Math.round(progress.etaMs))}` : ''));
+    const finished = progress.done >= progress.total;
+    if (interactive) {
+      process.stdout.write(`\r${line}\x1b[K${finished ? '\n' : ''}`);
+      return;
+    }
+    const quarter = Math.floor((progress.done / Math.max(1, progress.total)) * 4);
+    if (quarter > lastQuarter || finished) {
+      lastQuarter = quarter;
+      console.log(line);
+    }
+  };
+}
+
 function displaySyncResults(syncState: any, graphStats?: { fileCount: number; symbolCount: number }): void {
   console.log();
   console.log(chalk.bold('Sync Results:'));
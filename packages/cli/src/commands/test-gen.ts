@@ -0,0 +1,270 @@
+/**
+ * cv test-gen command
+ * Generate unit tests for a function or file and iterate until they pass
+ *
+ * `cv test-gen src/compute/engine.rs::multiply_matrices` gathers the
+ * function and its callers from the graph, follows the layout of the
+ * repository's existing tests, runs the test command (`test.command` in
+ * .cvgit.toml, else detected from Cargo.toml, go.mod, package.json, ...) in
+ * the sandbox and feeds failures back to the model, up to --attempts times.
+ * The written tests are recorded for `cv undo`.
+ */
+
+import { Command } from 'commander';
+import chalk from 'chalk';
+import ora from 'ora';
+import { existsSync, promises as fs } from 'fs';
+import * as path from 'path';
+import {
+  configManager,
+  createGitManager,
+  createGraphManager,
+  generateRepoId,
+  buildTestGenPrompt,
+  detectTestCommand,
+  isTestFile,
+  loadSandboxPolicy,
+  parseTestTarget,
+  pickTestExamples,
+  planTestLayout,
+  recordChangeSet,
+  runSandboxed,
+  runTestGeneration,
+  SandboxError,
+  testCommandFor,
+  TestGenContext,
+  TEST_GEN_SYSTEM_PROMPT
+} from '@cv-git/core';
+import { detectLanguage, findRepoRoot, SymbolNode } from '@cv-git/shared';
+import { createModelClient, fallbackNotice } from '../utils/model-client.js';
+
+/** Callers shown to the model */
+const MAX_CALLERS = 5;
+
+/** Lines of each caller shown */
+const MAX_CALLER_LINES = 40;
+
+/** Longest example test file shown, in characters */
+const MAX_EXAMPLE_CHARS = 6000;
+
+export function testGenCommand(): Command {
+  const cmd = new Command('test-gen');
+
+  cmd
+    .description('Generate unit tests for a function (file::name) or file in the project\'s style, run them and fix failures')
+    .argument('<target>', 'File, or file::function (e.g. src/compute/engine.rs::multiply_matrices)')
+    .option('-n, --attempts <n>', 'Generate-and-run attempts before giving up', '3')
+    .option('--command <command>', 'Test command ({file} is replaced by the test file); default: test.command or detected')
+    .option('--no-run', 'Write the tests without running them')
+    .option('--keep', 'Keep the last attempt when the tests still fail')
+    .option('--dry-run', 'Print the first prompt without calling the model')
+    .option('-m, --model <model>', 'Model: a Claude model or provider/model (e.g. ollama/qwen2.5-coder:14b)');
+
+  cmd.action(async (targetArg: string, options) => {
+    const spinner = ora('Gathering context...').start();
+
+    const repoRoot = await findRepoRoot();
+    if (!repoRoot) {
+      spinner.fail(chalk.red('Not in a CV-Git repository'));
+      console.error(chalk.gray('Run `cv init` first'));
+      process.exit(1);
+    }
+
+    let graph: ReturnType<typeof createGraphManager> | undefined;
+    try {
+      const target = parseTestTarget(targetArg);
+      // Accept paths relative to the working directory too
+      const absolute = path.resolve(process.cwd(), target.file);
+      target.file = path.relative(repoRoot, existsSync(absolute) ? absolute : path.join(repoRoot, target.file))
+        .split(path.sep).join('/');
+
+      const sourcePath = path.join(repoRoot, target.file);
+      const source = await fs.readFile(sourcePath, 'utf-8').catch(() => null);
+      if (source === null) throw new Error(`${target.file} not found`);
+      const sourceLines = source.split('\n');
+
+      const config = await configManager.load(repoRoot);
+      const git = createGitManager(repoRoot);
+
+      // The function and its callers from the graph; the whole file without a symbol
+      let code = source;
+      let startLine = 1;
+      const callers: TestGenContext['callers'] = [];
+      if (target.symbol) {
+        graph = createGraphManager({ url: config.graph.url, repoId: config.repository.repoId || generateRepoId(repoRoot) });
+        await graph.connect();
+        const symbols = await graph.getFileSymbols(target.file);
+        const symbol = findSymbol(symbols, target.symbol);
+        if (!symbol) {
+          const names = symbols.filter(s => s.kind === 'function' || s.kind === 'method').map(s => s.name).slice(0, 10);
+          throw new Error(`No function "${target.symbol}" in ${target.file}` +
+            (names.length ? ` (found: ${names.join(', ')})` : ' (run `cv sync` if the file is new)'));
+        }
+        code = sourceLines.slice(symbol.startLine - 1, symbol.endLine).join('\n');
+        startLine = symbol.startLine;
+
+        for (const caller of (await graph.getCallers(symbol.qualifiedName)).slice(0, MAX_CALLERS)) {
+          if (isTestFile(caller.file) && caller.file !== target.file) continue;
+          const text = await fs.readFile(path.join(repoRoot, caller.file), 'utf-8').catch(() => null);
+          if (text === null) continue;
+          const lines = text.split('\n').slice(caller.startLine - 1, caller.endLine);
+          callers.push({
+            name: caller.name,
+            file: caller.file,
+            code: lines.slice(0, MAX_CALLER_LINES).join('\n') + (lines.length > MAX_CALLER_LINES ? '\n...' : '')
+          });
+        }
+        await graph.close();
+        graph = undefined;
+      }
+
+      // Test conventions from the tests already in the repository
+      const tracked = await git.getTrackedFiles();
+      const testFiles = tracked.filter(isTestFile);
+      const layout = planTestLayout(target.file, testFiles, source);
+      const examples = [];
+      for (const file of pickTestExamples(target.file, testFiles)) {
+        const text = await fs.readFile(path.join(repoRoot, file), 'utf-8').catch(() => null);
+        if (text !== null) examples.push({ file, text: text.slice(0, MAX_EXAMPLE_CHARS) });
+      }
+
+      const testPath = path.join(repoRoot, layout.testFile);
+      const original = await fs.readFile(testPath, 'utf-8').catch(() => null);
+      const rootFiles = tracked.filter(file => !file.includes('/'));
+      const baseCommand = options.command ?? config.test?.command ?? detectTestCommand(rootFiles);
+      const testCommand = baseCommand ? testCommandFor(baseCommand, layout.testFile) : undefined;
+      if (options.run && !testCommand) {
+        throw new Error('No test command found. Pass --command or set test.command in .cvgit.toml (or use --no-run)');
+      }
+
+      const context: TestGenContext = {
+        target,
+        language: detectLanguage(target.file),
+        code,
+        startLine,
+        callers,
+        layout,
+        existingTests: layout.placement === 'file' ? original ?? undefined : undefined,
+        examples,
+        testCommand: options.run ? testCommand : undefined
+      };
+
+      if (options.dryRun) {
+        spinner.stop();
+        console.log(buildTestGenPrompt(context));
+        return;
+      }
+
+      spinner.text = 'Connecting to the model...';
+      const client = await createModelClient(options.model, {
+        provider: 'anthropic',
+        model: config.ai.model,
+        anthropicApiKey: config.ai.apiKey,
+        maxTokens: 8192,
+        fallback: config.ai.fallback
+      });
+      const sandbox = options.run ? await loadSandboxPolicy(repoRoot) : undefined;
+      const attempts = Math.max(1, parseInt(options.attempts, 10) || 3);
+
+      spinner.succeed(`Tests for ${target.symbol ?? target.file} go in ${layout.testFile}` +
+        (layout.placement === 'inline' ? ' (inline test module)' : ''));
+
+      let attemptSpinner = ora(`Generating tests (attempt 1/${attempts})...`).start();
+      const result = await runTestGeneration({
+        context,
+        attempts,
+        original,
+        generate: prompt => client.chat([{ role: 'user', content: prompt }], TEST_GEN_SYSTEM_PROMPT),
+        write: async content => {
+          await fs.mkdir(path.dirname(testPath), { recursive: true });
+          await fs.writeFile(testPath, content);
+          if (sandbox) attemptSpinner.text = `Running ${testCommand}...`;
+        },
+        runTests: sandbox && testCommand
+          ? async () => {
+              try {
+                const run = await runSandboxed(testCommand, { repoRoot, policy: sandbox });
+                return {
+                  exitCode: run.exitCode,
+                  output: run.timedOut ? `${run.output}\nTimed out after ${sandbox.timeoutMs / 1000}s` : run.output
+                };
+              } catch (error: any) {
+                if (!(error instanceof SandboxError)) throw error;
+                return { exitCode: 126, output: error.message };
+              }
+            }
+          : undefined,
+        onAttempt: (attempt, index) => {
+          if (attempt.exitCode === null) {
+            attemptSpinner.succeed(`Wrote tests to ${layout.testFile} (not run)`);
+          } else if (attempt.exitCode === 0) {
+            attemptSpinner.succeed(`Tests pass (attempt ${index}/${attempts})`);
+          } else {
+            attemptSpinner.fail(`Tests failed with exit code ${attempt.exitCode} (attempt ${index}/${attempts})`);
+            console.log(chalk.gray(indent(tail(attempt.output, 15))));
+            if (index < attempts) {
+              attemptSpinner = ora(`Fixing the failures (attempt ${index + 1}/${attempts})...`).start();
+            }
+          }
+        }
+      });
+
+      const notice = fallbackNotice(client);
+      if (notice) console.log(chalk.gray(notice));
+
+      const ran = result.attempts[result.attempts.length - 1]?.exitCode !== null;
+      if (ran && !result.passed && !options.keep) {
+        if (original === null) await fs.rm(testPath, { force: true });
+        else await fs.writeFile(testPath, original);
+        console.log();
+        console.log(chalk.yellow(`⚠ The tests still fail after ${result.attempts.length} attempt(s); ${layout.testFile} restored`));
+        console.log(chalk.gray('Use --keep to keep the last attempt, or --attempts to try longer'));
+        process.exit(1);
+      }
+
+      try {
+        const head = await git.getLastCommitSha().catch(() => undefined);
+        const changeSet = await recordChangeSet(
+          repoRoot,
+          [{ file: layout.testFile, before: original, after: result.content }],
+          { command: 'test-gen', description: `Tests for ${targetArg}`, head }
+        );
+        console.log(chalk.gray(`Undo with: cv undo ${changeSet.id}`));
+      } catch (error: any) {
+        console.error(chalk.yellow(`⚠ Could not record change set for undo: ${error.message}`));
+      }
+
+      if (ran && !result.passed) {
+        console.log(chalk.yellow(`⚠ Kept failing tests in ${layout.testFile}`));
+        process.exit(1);
+      }
+    } catch (error: any) {
+      if (graph) await graph.close().catch(() => {});
+      spinner.fail(chalk.red('Test generation failed'));
+      console.error(chalk.red(error.message));
+      process.exit(1);
+    }
+  });
+
+  return cmd;
+}
+
+/**
+ * The symbol a target names: `name`, a qualified name or its last segments
+ */
+function findSymbol(symbols: SymbolNode[], name: string): SymbolNode | undefined {
+  const callable = symbols.filter(s => s.kind === 'function' || s.kind === 'method');
+  const normalized = name.replace(/::|#/g, '.');
+  return callable.find(s => s.qualifiedName === name || s.qualifiedName.replace(/::|#/g, '.') === normalized)
+    ?? callable.find(s => s.qualifiedName.replace(/::|#/g, '.').endsWith(`.${normalized}`))
+    ?? callable.find(s => s.name === name.split(/::|\.|#/).pop());
+}
+
+function tail(text: string, lines: number): string {
+  const all = text.trimEnd().split('\n');
+  return all.slice(-lines).join('\n');
+}
+
+function indent(text: string): string {
+  return text.split('\n').map(line => `  ${line}`).join('\n');
+}
@@ -0,0 +1,198 @@
+/**
+ * cv tui command
+ * Interactive terminal UI for search, chat and review triage
+ *
+ * The panes and keys are described in tui/app.ts. This command connects the
+ * index and model, puts the terminal in raw mode on the alternate screen and
+ * repaints it whenever the app state changes.
+ */
+
+import { Command } from 'commander';
+import chalk from 'chalk';
+import { promises as fs } from 'fs';
+import * as path from 'path';
+import * as readline from 'readline';
+import {
+  AIClient,
+  configManager,
+  createVectorManager,
+  grepResults,
+  loadFindingsStore,
+  loadKeywordIndex,
+  loadScoreDistribution,
+  resolveMinScore,
+  saveFindingsStore,
+  setFindingStatus,
+  OpenRouterMessage,
+  VectorManager
+} from '@cv-git/core';
+import { findRepoRoot } from '@cv-git/shared';
+import { resolveEmbeddingProvider } from '../utils/embedding-provider.js';
+import { vectorStoreOptions } from '../utils/vector-store.js';
+import { createModelClient } from '../utils/model-client.js';
+import { ChatTurn, TuiApp, TuiBackend } from '../tui/app.js';
+
+const SYSTEM_PROMPT = `You are an expert software engineer assistant answering questions about a codebase.
+The files the user selected are provided with their message. Reference specific files and line numbers, and be concise: answers are shown in a small terminal pane.`;
+
+/** Per file sent as chat context, so one large file can't crowd out the rest */
+const MAX_FILE_CHARS = 20000;
+
+/** More than the preview pane shows, so hunks cover whole chunks */
+const PREVIEW_CONTEXT = 1000;
+
+export function tuiCommand(): Command {
+  const cmd = new Command('tui');
+
+  cmd
+    .description('Interactive terminal UI: search with previews, chat about selected files, and triage review findings')
+    .option('-m, --model <model>', 'Chat model: an OpenRouter model or provider/model (as for cv chat)')
+    .option('-l, --limit <number>', 'Maximum number of search results', '30');
+
+  cmd.action(async (options) => {
+    if (!process.stdin.isTTY || !process.stdout.isTTY) {
+      console.error(chalk.red('cv tui needs an interactive terminal'));
+      process.exit(1);
+    }
+    const repoRoot = await findRepoRoot();
+    if (!repoRoot) {
+      console.error(chalk.red('Not in a CV-Git repository. Run `cv init` first.'));
+      process.exit(1);
+    }
+
+    const backend = await createBackend(repoRoot, options.model, parseInt(options.limit, 10) || 30);
+    runTui(backend);
+  });
+
+  return cmd;
+}
+
+/**
+ * Search over the keyword index and, when available, the vector store; chat
+ * through the configured model, connected on first use
+ */
+async function createBackend(repoRoot: string, model: string | undefined, limit: number): Promise<TuiBackend> {
+  const config = await configManager.load(repoRoot);
+  const keywordIndex = await loadKeywordIndex(repoRoot);
+
+  let vector: VectorManager | undefined;
+  let minScore = 0.5;
+  try {
+    vector = createVectorManager({
+      url: config.vector.url,
+      ...vectorStoreOptions(config, repoRoot),
+      provider: await resolveEmbeddingProvider(config),
+      collections: config.vector.collections
+    });
+    await vector.connect();
+    minScore = resolveMinScore(config.retrieval, await loadScoreDistribution(repoRoot, vector.getEmbeddingInfo().model), 0.5);
+  } catch {
+    // Keyword search only
+    vector = undefined;
+  }
+  if (!vector && keywordIndex.size === 0) {
+    console.error(chalk.yellow('No index found; search will return nothing until you run `cv sync`.'));
+  }
+
+  let client: AIClient | undefined;
+
+  return {
+    async search(query) {
+      const candidates = limit * 3;
+      const keyword = keywordIndex.size > 0 ? keywordIndex.search(query, candidates) : [];
+      const semantic = vector ? await vector.searchCode(query, candidates, { minScore }) : [];
+      return grepResults(query, semantic, keyword, {}, { limit, context: PREVIEW_CONTEXT });
+    },
+
+    async chat(turns: ChatTurn[], files, onToken) {
+      client ??= await createModelClient(model, {
+        provider: 'openrouter',
+        model: 'claude-sonnet-4-5',
+        fallback: config.ai.fallback
+      });
+
+      const context = await Promise.all(files.map(async file => {
+        const text = await fs.readFile(path.join(repoRoot, file), 'utf-8').catch(() => '');
+        const clipped = text.length > MAX_FILE_CHARS ? `${text.slice(0, MAX_FILE_CHARS)}\n... (truncated)` : text;
+        return `### ${file}\n\`\`\`\n${clipped}\n\`\`\``;
+      }));
+      const messages: OpenRouterMessage[] = turns.map((turn, i) => ({
+        role: turn.role,
+        content: i === turns.length - 1 && context.length > 0
+          ? `<codebase_context>\n${context.join('\n\n')}\n</codebase_context>\n\n${turn.content}`
+          : turn.content
+      }));
+      return client.chatStream(messages, SYSTEM_PROMPT, { onToken });
+    },
+
+    async findings() {
+      return (await loadFindingsStore(repoRoot)).findings;
+    },
+
+    async setFindingStatus(id, status) {
+      // Reload first so findings recorded by a concurrent `cv review` are kept
+      const store = setFindingStatus(await loadFindingsStore(repoRoot), id, status);
+      await saveFindingsStore(repoRoot, store);
+      return store.findings;
+    },
+
+    async readLines(file, from, to) {
+      const text = await fs.readFile(path.join(repoRoot, file), 'utf-8');
+      return text.split('\n').slice(from - 1, to);
+    }
+  };
+}
+
+/**
+ * Take over the terminal until the app quits
+ */
+function runTui(backend: TuiBackend): void {
+  const out = process.stdout;
+  const stdin = process.stdin;
+
+  // Library logging would scribble over the screen; errors reach the status bar instead
+  const noop = () => undefined;
+  console.log = noop;
+  console.info = noop;
+  console.warn = noop;
+
+  let restored = false;
+  const restore = () => {
+    if (restored) return;
+    restored = true;
+    stdin.setRawMode(false);
+    // Show the cursor and leave the alternate screen
+    out.write('\x1b[?25h\x1b[?1049l');
+  };
+
+  let queued = false;
+  const draw = () => {
+    if (queued || restored) return;
+    queued = true;
+    setImmediate(() => {
+      queued = false;
+      if (restored) return;
+      // No newline after the last row, which would scroll the screen
+      out.write(`\x1b[H${app.render(out.columns, out.rows).join('\r\n')}`);
+    });
+  };
+
+  const app = new TuiApp({
+    backend,
+    onChange: draw,
+    onQuit: () => {
+      restore();
+      process.exit(0);
+    }
+  });
+
+  out.write('\x1b[?1049h\x1b[?25l');
+  readline.emitKeypressEvents(stdin);
+  stdin.setRawMode(true);
+  stdin.resume();
+  stdin.on('keypress', (input: string | undefined, key) => void app.key(input, key ?? {}));
+  out.on('resize', draw);
+  process.on('exit', restore);
+
+  void app.start();
+}
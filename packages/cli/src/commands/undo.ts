@@ -5,6 +5,10 @@
  * Inspired by Jujutsu's operation log
  * Provides easy recovery from mistakes by tracking operations
  * and providing simple undo functionality.
+ *
+ * Changes applied by `cv do` / `cv code` are recorded as change sets and
+ * are undone first: `cv undo` reverts the latest one made on the current
+ * HEAD, `cv undo <changeset-id>` a specific one.
  */
 
 import { Command } from 'commander';
@@ -12,7 +16,13 @@ import chalk from 'chalk';
 import { execSync } from 'child_process';
 import * as fs from 'fs';
 import * as path from 'path';
-import { addGlobalOptions, createOutput } from '../utils/output.js';
+import {
+  ChangeSet,
+  findChangeSet,
+  listChangeSets,
+  undoChangeSet,
+} from '@cv-git/core';
+import { addGlobalOptions, createOutput, OutputManager } from '../utils/output.js';
 
 /**
  * Find git repository root
@@ -35,6 +45,8 @@ function findGitRoot(startDir: string = process.cwd()): string | null {
 interface UndoOptions {
   hard?: boolean;
   steps?: string;
+  list?: boolean;
+  force?: boolean;
   verbose?: boolean;
   quiet?: boolean;
   json?: boolean;
@@ -53,10 +65,12 @@ export function undoCommand(): Command {
   const cmd = new Command('undo');
 
   cmd
-    .description('Undo the last operation (uses git reflog)')
-    .argument('[target]', 'Reflog entry to restore (e.g., HEAD@{1}, HEAD@{5})')
+    .description('Undo the last AI change set or operation (uses git reflog)')
+    .argument('[target]', 'Change set id, or reflog entry to restore (e.g., HEAD@{1}, HEAD@{5})')
     .option('--hard', 'Discard uncommitted changes (use with caution)')
     .option('-n, --steps <n>', 'Number of operations to undo (default: 1)')
+    .option('--list', 'List recorded AI change sets')
+    .option('--force', 'Revert a change set even over files edited since')
     .option('-v, --verbose', 'Show detailed information');
 
   addGlobalOptions(cmd);
@@ -71,6 +85,23 @@ export function undoCommand(): Command {
         process.exit(1);
       }
 
+      if (options.list) {
+        const changeSets = await listChangeSets(repoRoot);
+        if (options.json) {
+          output.json({ changeSets });
+        } else {
+          printChangeSets(changeSets);
+        }
+        return;
+      }
+
+      // AI change sets come before git history
+      const changeSet = await resolveChangeSet(repoRoot, target, options);
+      if (changeSet) {
+        await revertChangeSet(repoRoot, changeSet, options, output);
+        return;
+      }
+
       // If no target specified, show recent operations and suggest undo
       if (!target) {
         const steps = parseInt(options.steps || '1', 10);
@@ -189,6 +220,89 @@ export function undoCommand(): Command {
   return cmd;
 }
 
+/**
+ * The change set to undo: the one named by target, or with no target the
+ * latest one not yet undone that was applied on the current HEAD
+ */
+async function resolveChangeSet(
+  repoRoot: string,
+  target: string | undefined,
+  options: UndoOptions
+): Promise<ChangeSet | null> {
+  if (target) {
+    if (/^[0-9a-z]+$/.test(target)) {
+      return findChangeSet(repoRoot, target);
+    }
+    return null;
+  }
+  if (options.steps) return null;
+
+  let head: string;
+  try {
+    head = execSync('git rev-parse HEAD', { cwd: repoRoot, encoding: 'utf-8' }).trim();
+  } catch {
+    return null;
+  }
+  const changeSets = await listChangeSets(repoRoot);
+  return changeSets.find(cs => !cs.undoneAt && cs.head === head) ?? null;
+}
+
+async function revertChangeSet(
+  repoRoot: string,
+  changeSet: ChangeSet,
+  options: UndoOptions,
+  output: OutputManager
+): Promise<void> {
+  const result = await undoChangeSet(repoRoot, changeSet, { force: options.force });
+  const reverted = result.reverted.length > 0 || result.conflicts.length === 0;
+
+  if (options.json) {
+    output.json({
+      success: reverted,
+      changeSet: result.changeSet.id,
+      reverted: result.reverted,
+      conflicts: result.conflicts,
+    });
+    if (!reverted) process.exit(1);
+    return;
+  }
+
+  if (!reverted) {
+    console.error(chalk.red(`Cannot undo change set ${changeSet.id}: files changed since it was applied`));
+    for (const conflict of result.conflicts) {
+      console.error(chalk.gray(`  • ${conflict.file} (${conflict.reason})`));
+    }
+    console.error(chalk.gray('\nNo files were changed. Use --force to revert anyway.'));
+    process.exit(1);
+  }
+
+  if (!options.quiet) {
+    console.log(chalk.green(`✓ Undid change set ${changeSet.id} (cv ${changeSet.command}: ${changeSet.description})`));
+    for (const file of result.reverted) {
+      console.log(chalk.gray(`  ${file}`));
+    }
+    for (const conflict of result.conflicts) {
+      console.log(chalk.yellow(`  ⚠ ${conflict.file}: ${conflict.reason}, overwritten`));
+    }
+  }
+}
+
+function printChangeSets(changeSets: ChangeSet[]): void {
+  if (changeSets.length === 0) {
+    console.log(chalk.gray('No change sets recorded'));
+    return;
+  }
+
+  console.log(chalk.cyan('Recorded change sets:\n'));
+  for (const cs of changeSets) {
+    const when = new Date(cs.createdAt).toLocaleString();
+    const state = cs.undoneAt ? chalk.gray(' (undone)') : '';
+    console.log(`  ${chalk.yellow(cs.id)} ${chalk.dim(when)} cv ${cs.command}: ${cs.description}${state}`);
+    console.log(chalk.gray(`    ${cs.files.map(f => f.file).join(', ')}`));
+  }
+  console.log(chalk.gray('\nUse "cv undo <id>" to revert one'));
+}
+
 /**
  * cv reflog command - show operation history
  */
@@ -0,0 +1,167 @@
+/**
+ * cv usage command
+ * Token usage and cost of AI requests made by cv
+ *
+ * Every provider request records its prompt, completion and embedding token
+ * counts in ~/.cv/usage.jsonl; `cv usage` totals them by command, by day
+ * and by model.
+ */
+
+import { Command } from 'commander';
+import chalk from 'chalk';
+import Table from 'cli-table3';
+import {
+  loadUsage,
+  summarizeUsage,
+  getUsageLogPath,
+  UsageRecord,
+  UsageTotals
+} from '@cv-git/core';
+import { findRepoRoot } from '@cv-git/shared';
+import {
+  addGlobalOptions,
+  addJsonlOption,
+  createJsonReporter,
+  createOutput,
+  ErrorCode,
+  ExitCode
+} from '../utils/output.js';
+
+const DEFAULT_SINCE = '30d';
+
+export function usageCommand(): Command {
+  const cmd = new Command('usage')
+    .description('Show token usage and cost of AI requests')
+    .option('--since <when>', 'Only requests after a date (2026-01-31) or age (7d, 12h)', DEFAULT_SINCE)
+    .option('--all', 'Include all recorded usage')
+    .option('--repo', 'Only usage in the current repository')
+    .option('--log', 'Include the individual records (with --json; --jsonl always streams them)');
+
+  addGlobalOptions(cmd);
+  addJsonlOption(cmd);
+
+  cmd.action(async (options) => {
+    const report = createJsonReporter('usage', options);
+    const output = createOutput(options);
+
+    try {
+      let since: Date | undefined;
+      if (!options.all) {
+        since = parseSince(options.since);
+        if (!since) {
+          const message = `Invalid --since "${options.since}". Use a date (2026-01-31) or an age (7d, 12h).`;
+          report?.fail(message, ErrorCode.INVALID_INPUT);
+          output.error(message);
+          process.exit(ExitCode.Error);
+        }
+      }
+
+      let records = loadUsage(getUsageLogPath(), since);
+      if (options.repo) {
+        const repoRoot = await findRepoRoot();
+        if (!repoRoot) {
+          report?.fail('Not in a repository', ErrorCode.NOT_GIT_REPO);
+          output.error('Not in a repository');
+          process.exit(ExitCode.Error);
+        }
+        records = records.filter(r => r.repo === repoRoot);
+      }
+
+      const summary = summarizeUsage(records);
+
+      if (report) {
+        records.forEach(record => report.event('record', record));
+        report.result({
+          since: since?.toISOString() ?? null,
+          ...summary,
+          ...(options.log && !report.streaming ? { records } : {}),
+        });
+        return;
+      }
+
+      if (records.length === 0) {
+        console.log(chalk.gray(`No AI usage recorded${since ? ` since ${since.toLocaleDateString()}` : ''}.`));
+        return;
+      }
+
+      console.log();
+      console.log(chalk.bold('AI usage') + chalk.gray(since ? ` since ${since.toLocaleDateString()}` : ''));
+      console.log();
+      printTable('Command', summary.byCommand);
+      console.log();
+      printTable('Day', summary.byDay, true);
+      console.log();
+      printTable('Model', summary.byModel);
+      console.log();
+
+      const total = summary.total;
+      console.log(
+        chalk.bold('Total: ') +
+        `${total.requests.toLocaleString()} requests, ` +
+        `${formatTokens(total.promptTokens + total.completionTokens)} tokens, ` +
+        chalk.green(formatCost(total.costUsd))
+      );
+      if (total.unpriced > 0) {
+        console.log(chalk.gray(`Cost excludes models without a known price (shown as "?").`));
+      }
+      if (records.some((r: UsageRecord) => r.estimated)) {
+        console.log(chalk.gray('Some token counts were estimated where the provider did not report them.'));
+      }
+      console.log();
+    } catch (error: any) {
+      report?.fail(`Failed to read usage: ${error.message}`);
+      output.error('Failed to read usage', error);
+      process.exit(ExitCode.Error);
+    }
+  });
+
+  return cmd;
+}
+
+/**
+ * `7d` / `12h` ago, or an absolute date
+ */
+export function parseSince(value: string, now: number = Date.now()): Date | undefined {
+  const age = value.match(/^(\d+)\s*([dhw])$/i);
+  if (age) {
+    const hours = { h: 1, d: 24, w: 24 * 7 }[age[2].toLowerCase() as 'h' | 'd' | 'w'];
+    return new Date(now - Number(age[1]) * hours * 3600_000);
+  }
+  const date = new Date(value);
+  return Number.isNaN(date.getTime()) ? undefined : date;
+}
+
+function printTable(label: string, rows: Record<string, UsageTotals>, chronological = false): void {
+  const table = new Table({
+    head: [label, 'Requests', 'Prompt', 'Completion', 'Cost'].map(h => chalk.bold(h)),
+    colAligns: ['left', 'right', 'right', 'right', 'right'],
+    chars: { 'mid': '', 'left-mid': '', 'mid-mid': '', 'right-mid': '' }
+  });
+
+  const entries = Object.entries(rows);
+  if (chronological) entries.sort(([a], [b]) => a.localeCompare(b));
+  else entries.sort(([, a], [, b]) => b.costUsd - a.costUsd || b.promptTokens - a.promptTokens);
+
+  for (const [key, totals] of entries) {
+    const cost = formatCost(totals.costUsd) + (totals.unpriced > 0 ? ' (?)' : '');
+    table.push([
+      key,
+      totals.requests.toLocaleString(),
+      formatTokens(totals.promptTokens),
+      formatTokens(totals.completionTokens),
+      totals.unpriced > 0 && totals.costUsd === 0 ? '?' : cost,
+    ]);
+  }
+
+  console.log(table.toString());
+}
+
+function formatTokens(count: number): string {
+  if (count >= 1_000_000) return `${(count / 1_000_000).toFixed(2)}M`;
+  if (count >= 10_000) return `${(count / 1000).toFixed(1)}k`;
+  return count.toLocaleString();
+}
+
+function formatCost(usd: number): string {
+  return usd > 0 && usd < 0.01 ? `$${usd.toFixed(4)}` : `$${usd.toFixed(2)}`;
+}
@@ -0,0 +1,864 @@
+/**
+ * cv index command
+ * Manage where this repository's vector index is stored, prune it, move it
+ * to another embedding model, report its size, and serve it to the team
+ */
+
+import { Command } from 'commander';
+import chalk from 'chalk';
+import inquirer from 'inquirer';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { spawn } from 'child_process';
+import { randomBytes } from 'crypto';
+import {
+  branchCondition,
+  catchUpReembed,
+  clearReembedState,
+  collectVectorGarbage,
+  configManager,
+  createGitManager,
+  createVectorManager,
+  createVectorStore,
+  DEFAULT_HNSW_OPTIONS,
+  DEFAULT_TRAIN_POINTS,
+  defaultSubvectors,
+  directorySize,
+  EMBEDDING_MODELS,
+  EmbeddingProviderName,
+  forgetBranches,
+  generateRepoId,
+  getCurrentNamespace,
+  getVectorIndexDir,
+  INDEX_PROTOCOL_VERSION,
+  IndexServerInfo,
+  loadBranchIndex,
+  loadReembedState,
+  migrateVectorStore,
+  planReembed,
+  readSharedSyncState,
+  reembedConfig,
+  ReembedState,
+  resolveVectorStoreType,
+  runReembed,
+  sampleScoreDistribution,
+  saveReembedState,
+  saveScoreDistribution,
+  startIndexServer,
+  VECTOR_STORE_TYPES,
+  vectorBytes,
+  VectorCodecInfo,
+  VectorManager,
+  VectorStore,
+  VectorStoreType
+} from '@cv-git/core';
+import { findRepoRoot, formatBytes, getCVDir, CVConfig } from '@cv-git/shared';
+import { addGlobalOptions, createOutput } from '../utils/output.js';
+import { ensureQdrant } from '../utils/infrastructure.js';
+import { resolveEmbeddingProvider } from '../utils/embedding-provider.js';
+import { vectorStoreOptions } from '../utils/vector-store.js';
+
+/** Set in the detached process `cv index reembed --background` starts */
+const REEMBED_DAEMON_ENV = 'CV_REEMBED_DAEMON';
+
+/** Default port of `cv index serve` */
+const INDEX_SERVER_PORT = 7433;
+
+/**
+ * Collections that belong to this repository: the configured names plus
+ * repo-scoped ones (<repoId>_code_chunks, ...) and the collections they were
+ * moved to by `cv index reembed`
+ */
+async function repositoryCollections(store: VectorStore, config: CVConfig, repoRoot: string): Promise<string[]> {
+  const repoId = config.repository?.repoId || generateRepoId(repoRoot);
+  const configured = [...Object.values(config.vector.collections || {}), ...Object.values(config.vector.aliases || {})];
+  const all = await store.listCollections();
+  return all.filter(name =>
+    configured.some(c => name === c || name.startsWith(`${c}__`)) || name.startsWith(`${repoId}_`)
+  );
+}
+
+/**
+ * The repository's collections under the names commands use, each once:
+ * swapped-in and half-built model collections (<name>__<model>) stand for
+ * their name
+ */
+async function indexedCollections(store: VectorStore, config: CVConfig, repoRoot: string): Promise<string[]> {
+  const aliases = config.vector.aliases || {};
+  const names = await repositoryCollections(store, config, repoRoot);
+  const indexed = new Set(names.filter(name => !/__[a-z0-9_]+$/.test(name)));
+  for (const [name, target] of Object.entries(aliases)) {
+    if (names.includes(target)) indexed.add(name);
+  }
+  return [...indexed].sort();
+}
+
+/**
+ * Qdrant URL for a store of the given type (started if needed)
+ */
+async function storeUrl(type: VectorStoreType, config: CVConfig): Promise<string> {
+  if (type === 'qdrant') {
+    const qdrant = await ensureQdrant({ silent: true });
+    if (qdrant) return qdrant.url;
+  }
+  return config.vector.url;
+}
+
+/**
+ * Open a store of the given type for this repository
+ */
+async function openStore(type: VectorStoreType, config: CVConfig, repoRoot: string): Promise<VectorStore> {
+  const store = await createVectorStore({
+    type,
+    url: await storeUrl(type, config),
+    indexDir: getVectorIndexDir(repoRoot),
+    hnsw: config.vector.hnsw,
+    quantization: config.vector.quantization
+  });
+  await store.connect();
+  return store;
+}
+
+function migrateSubcommand(): Command {
+  const cmd = new Command('migrate');
+
+  cmd
+    .description('Copy the vector index to another backend and switch to it')
+    .requiredOption('--to <backend>', `Target backend (${VECTOR_STORE_TYPES.join(', ')})`)
+    .option('--from <backend>', 'Source backend (default: the configured one)')
+    .option('--keep-config', 'Copy only; leave vector.provider unchanged')
+    .option('--dry-run', 'List what would be copied')
+    .option('-y, --yes', 'Skip confirmation');
+
+  addGlobalOptions(cmd);
+
+  cmd.action(async (options) => {
+    const output = createOutput(options);
+    let source: VectorStore | undefined;
+    let target: VectorStore | undefined;
+
+    try {
+      const repoRoot = await findRepoRoot();
+      if (!repoRoot) {
+        console.error(chalk.red('Not in a CV-Git repository'));
+        console.error(chalk.gray('Run `cv init` first'));
+        process.exit(1);
+      }
+
+      for (const backend of [options.to, options.from].filter(Boolean)) {
+        if (!VECTOR_STORE_TYPES.includes(backend)) {
+          console.error(chalk.red(`Unknown vector backend: ${backend}`));
+          console.error(chalk.gray(`Valid backends: ${VECTOR_STORE_TYPES.join(', ')}`));
+          process.exit(1);
+        }
+      }
+
+      const config = await configManager.load(repoRoot);
+      const from: VectorStoreType = options.from || resolveVectorStoreType(config.vector.provider);
+      const to: VectorStoreType = options.to;
+      if (from === to) {
+        console.log(chalk.yellow(`Index is already stored in ${to}.`));
+        return;
+      }
+
+      const spinner = output.spinner(`Opening ${from} vector store...`).start();
+      source = await openStore(from, config, repoRoot);
+      const collections = await repositoryCollections(source, config, repoRoot);
+      spinner.stop();
+
+      if (collections.length === 0) {
+        console.log(chalk.yellow(`No collections for this repository in ${from}.`));
+        console.log(chalk.gray('Run `cv sync` to build the index.'));
+        return;
+      }
+
+      console.log(chalk.bold(`\nCollections to copy from ${from} to ${to}:\n`));
+      for (const name of collections) {
+        const stats = await source.getCollectionStats(name);
+        console.log(`  ${name.padEnd(40)} ${chalk.gray(`${stats?.pointsCount ?? 0} points`)}`);
+      }
+      console.log();
+
+      if (options.dryRun) return;
+
+      if (!options.yes) {
+        const { proceed } = await inquirer.prompt([{
+          type: 'confirm',
+          name: 'proceed',
+          message: `Replace these collections in ${to}?`,
+          default: true
+        }]);
+        if (!proceed) return;
+      }
+
+      const copySpinner = output.spinner(`Opening ${to} vector store...`).start();
+      target = await openStore(to, config, repoRoot);
+      const result = await migrateVectorStore(source, target, {
+        collections,
+        onProgress: ({ collection, copied, total }) => {
+          copySpinner.text = `Copying ${collection} (${copied}/${total})`;
+        }
+      });
+      copySpinner.succeed(`Copied ${result.totalPoints} points in ${result.collections.length} collection(s) to ${to}`);
+
+      if (!options.keepConfig) {
+        await configManager.update({ vector: { ...config.vector, provider: to } });
+        console.log(chalk.green(`✓ vector.provider set to ${to}`));
+      }
+      if (from !== 'qdrant') {
+        console.log(chalk.gray(`The ${from} index under .cv/index can be deleted once you're happy with ${to}.`));
+      }
+    } catch (error: any) {
+      console.error(chalk.red(`Migration failed: ${error.message}`));
+      process.exit(1);
+    } finally {
+      if (source) await source.close();
+      if (target) await target.close();
+    }
+  });
+
+  return cmd;
+}
+
+function gcSubcommand(): Command {
+  const cmd = new Command('gc');
+
+  cmd
+    .description('Prune vectors of deleted files, commits and branches, then compact the index')
+    .option('--dry-run', 'Report what would be removed')
+    .option('--keep-branches', 'Keep vectors of deleted branches')
+    .option('--no-compact', 'Skip compaction and ANN graph rebuild');
+
+  addGlobalOptions(cmd);
+
+  cmd.action(async (options) => {
+    const output = createOutput(options);
+    let store: VectorStore | undefined;
+
+    try {
+      const repoRoot = await findRepoRoot();
+      if (!repoRoot) {
+        console.error(chalk.red('Not in a CV-Git repository'));
+        console.error(chalk.gray('Run `cv init` first'));
+        process.exit(1);
+      }
+
+      const config = await configManager.load(repoRoot);
+      const type = resolveVectorStoreType(config.vector.provider);
+      const indexDir = getVectorIndexDir(repoRoot);
+      const embedded = type !== 'qdrant';
+      const bytesBefore = embedded ? await directorySize(indexDir) : 0;
+
+      const spinner = output.spinner(`Opening ${type} vector store...`).start();
+      store = await openStore(type, config, repoRoot);
+      const collections = await repositoryCollections(store, config, repoRoot);
+
+      // What still exists: tracked files, reachable commits, local branches
+      const git = createGitManager(repoRoot);
+      const liveFiles = await git.getTrackedFiles();
+      const currentNamespace = getCurrentNamespace(repoRoot) ?? undefined;
+      const liveNamespaces = options.keepBranches
+        ? undefined
+        : [...await git.getLocalBranches(), ...(currentNamespace ? [currentNamespace] : [])];
+
+      spinner.text = 'Scanning vectors...';
+      const result = await collectVectorGarbage(store, {
+        collections,
+        liveFiles,
+        commitExists: sha => git.commitExists(sha),
+        liveNamespaces,
+        currentNamespace,
+        dryRun: options.dryRun,
+        onProgress: ({ collection, scanned, total }) => {
+          spinner.text = `Scanning ${collection} (${scanned}/${total})`;
+        }
+      });
+
+      let compacted = false;
+      if (!options.dryRun) {
+        if (liveNamespaces) {
+          const live = new Set(liveNamespaces);
+          const { branches } = await loadBranchIndex(repoRoot);
+          const stale = [...new Set([...result.removedNamespaces, ...Object.keys(branches).filter(b => !live.has(b))])];
+          if (stale.length > 0) await forgetBranches(repoRoot, stale);
+        }
+        if (options.compact && store.compact) {
+          spinner.text = embedded ? 'Compacting storage and rebuilding ANN graphs...' : 'Compacting...';
+          await store.compact();
+          compacted = true;
+        }
+      }
+      await store.close();
+      store = undefined;
+      spinner.stop();
+
+      const bytesAfter = embedded && !options.dryRun ? await directorySize(indexDir) : bytesBefore;
+
+      if (output.isJson) {
+        output.json({
+          dryRun: !!options.dryRun,
+          backend: type,
+          ...result,
+          compacted,
+          ...(embedded && { bytesBefore, bytesAfter, bytesReclaimed: Math.max(0, bytesBefore - bytesAfter) })
+        });
+        return;
+      }
+
+      if (result.collections.length === 0) {
+        console.log(chalk.yellow(`No collections for this repository in ${type}.`));
+        return;
+      }
+
+      console.log(chalk.bold(`\n${options.dryRun ? 'Would remove' : 'Removed'} from ${type}:\n`));
+      for (const c of result.collections) {
+        const removed = c.orphanedFiles + c.orphanedCommits + c.orphanedBranches;
+        const parts = [
+          c.orphanedFiles && `${c.orphanedFiles} deleted files`,
+          c.orphanedCommits && `${c.orphanedCommits} lost commits`,
+          c.orphanedBranches && `${c.orphanedBranches} deleted branches`,
+          c.retagged && `${c.retagged} untagged`
+        ].filter(Boolean);
+        console.log(`  ${c.name.padEnd(40)} ${String(removed).padStart(7)} of ${c.scanned} points ${chalk.gray(parts.join(', '))}`);
+      }
+      if (result.removedNamespaces.length > 0) {
+        console.log(chalk.gray(`\n  Deleted branches: ${result.removedNamespaces.join(', ')}`));
+      }
+      console.log();
+
+      if (options.dryRun) {
+        console.log(chalk.gray('Run without --dry-run to remove them.'));
+      } else if (embedded) {
+        const reclaimed = Math.max(0, bytesBefore - bytesAfter);
+        console.log(chalk.green(`✓ ${result.deleted} vectors removed, ${formatBytes(reclaimed)} reclaimed`) +
+          chalk.gray(` (${formatBytes(bytesBefore)} → ${formatBytes(bytesAfter)})`));
+        if (!compacted) console.log(chalk.gray('Space is returned once the index is compacted: run `cv index gc` without --no-compact.'));
+      } else {
+        console.log(chalk.green(`✓ ${result.deleted} vectors removed`));
+        console.log(chalk.gray('Qdrant reclaims the space in its background optimizer.'));
+      }
+    } catch (error: any) {
+      console.error(chalk.red(`Garbage collection failed: ${error.message}`));
+      process.exit(1);
+    } finally {
+      if (store) await store.close();
+    }
+  });
+
+  return cmd;
+}
+
+interface CollectionSize {
+  name: string;
+  points: number;
+  dimension: number;
+  codec: VectorCodecInfo['codec'];
+  trained: boolean;
+  /** Stored vectors under the active codec */
+  vectorBytes: number;
+  /** Vectors and graph held in memory to search; 0 when searches read from disk */
+  memoryBytes: number;
+  /** Stored vectors under each codec */
+  codecs: Record<VectorCodecInfo['codec'], number>;
+}
+
+function statsSubcommand(): Command {
+  const cmd = new Command('stats');
+
+  cmd
+    .description('Show the index size per collection and what each vector codec would use');
+
+  addGlobalOptions(cmd);
+
+  cmd.action(async (options) => {
+    const output = createOutput(options);
+    let store: VectorStore | undefined;
+
+    try {
+      const repoRoot = await findRepoRoot();
+      if (!repoRoot) {
+        console.error(chalk.red('Not in a CV-Git repository'));
+        console.error(chalk.gray('Run `cv init` first'));
+        process.exit(1);
+      }
+
+      const config = await configManager.load(repoRoot);
+      const type = resolveVectorStoreType(config.vector.provider);
+      const embedded = type !== 'qdrant';
+      const quantization = config.vector.quantization;
+      const hnsw = { ...DEFAULT_HNSW_OPTIONS, ...config.vector.hnsw };
+      const trainPoints = quantization?.trainPoints ?? DEFAULT_TRAIN_POINTS;
+
+      const spinner = output.spinner(`Opening ${type} vector store...`);
+      spinner.start();
+      store = await openStore(type, config, repoRoot);
+
+      const sizes: CollectionSize[] = [];
+      for (const name of await repositoryCollections(store, config, repoRoot)) {
+        const stats = await store.getCollectionStats(name);
+        if (!stats) continue;
+        const dimension = stats.vectorSize;
+        const info = await store.getCodec?.(name) ??
+          { codec: 'f32' as const, bytesPerVector: vectorBytes('f32', dimension), trained: true };
+        const subvectors = quantization?.subvectors ?? defaultSubvectors(dimension);
+        const stored = stats.pointsCount * info.bytesPerVector;
+
+        // Quantized codes and Qdrant's vectors stay in RAM; embedded f32 collections
+        // load an HNSW graph (vectors plus ~2m links per node) once past minPoints
+        let memoryBytes = 0;
+        if (info.codec !== 'f32' || !embedded) memoryBytes = stored;
+        else if (hnsw.enabled !== false && stats.pointsCount >= hnsw.minPoints) memoryBytes = stored + stats.pointsCount * hnsw.m * 2 * 4;
+
+        sizes.push({
+          name,
+          points: stats.pointsCount,
+          dimension,
+          codec: info.codec,
+          trained: info.trained,
+          vectorBytes: stored,
+          memoryBytes,
+          codecs: {
+            f32: stats.pointsCount * vectorBytes('f32', dimension),
+            int8: stats.pointsCount * vectorBytes('int8', dimension),
+            pq: stats.pointsCount * vectorBytes('pq', dimension, subvectors)
+          }
+        });
+      }
+      await store.close();
+      store = undefined;
+      const diskBytes = embedded ? await directorySize(getVectorIndexDir(repoRoot)) : undefined;
+      spinner.stop();
+
+      if (output.isJson) {
+        output.json({ backend: type, quantization: quantization?.codec ?? 'none', diskBytes, collections: sizes });
+        return;
+      }
+
+      if (sizes.length === 0) {
+        console.log(chalk.yellow(`No collections for this repository in ${type}.`));
+        return;
+      }
+
+      const configured = quantization?.codec && quantization.codec !== 'none' ? quantization.codec : 'f32';
+      console.log(chalk.bold(`\nVector index: ${type}`) + chalk.gray(
+        (diskBytes !== undefined ? `, ${formatBytes(diskBytes)} on disk` : '') + `, codec ${configured}`
+      ));
+      console.log();
+      console.log(chalk.gray(
+        `  ${'Collection'.padEnd(40)} ${'Points'.padStart(8)} ${'Dims'.padStart(5)}  ${'Codec'.padEnd(6)}` +
+        ` ${'Vectors'.padStart(10)} ${'Memory'.padStart(10)} │ ${'f32'.padStart(10)} ${'int8'.padStart(10)} ${'pq'.padStart(10)}`
+      ));
+      for (const c of sizes) {
+        const codec = c.trained ? c.codec : `${c.codec}*`;
+        const alternatives = (['f32', 'int8', 'pq'] as const).map(name => {
+          const text = formatBytes(c.codecs[name]).padStart(10);
+          return name === c.codec ? chalk.bold(text) : chalk.gray(text);
+        });
+        console.log(
+          `  ${c.name.padEnd(40)} ${String(c.points).padStart(8)} ${String(c.dimension).padStart(5)}  ${codec.padEnd(6)}` +
+          ` ${formatBytes(c.vectorBytes).padStart(10)} ${(c.memoryBytes ? formatBytes(c.memoryBytes) : '-').padStart(10)} │ ${alternatives.join(' ')}`
+        );
+      }
+
+      const total = (pick: (c: CollectionSize) => number) => formatBytes(sizes.reduce((n, c) => n + pick(c), 0));
+      console.log(chalk.gray(`\n  Vectors: ${total(c => c.vectorBytes)} stored; ${total(c => c.codecs.f32)} as f32, ` +
+        `${total(c => c.codecs.int8)} as int8, ${total(c => c.codecs.pq)} as pq`));
+      if (sizes.some(c => !c.trained)) {
+        console.log(chalk.gray(`  * stored f32 until the collection reaches ${trainPoints} points`));
+      }
+      if (embedded && configured !== 'f32' && sizes.some(c => c.codec === 'f32' && c.points >= (trainPoints))) {
+        console.log(chalk.gray('  Collections created before vector.quantization was set are converted by `cv index gc`.'));
+      }
+      if (configured === 'f32') {
+        console.log(chalk.gray('  Set vector.quantization.codec to int8 or pq in .cv/config.json to shrink the index.'));
+      }
+    } catch (error: any) {
+      console.error(chalk.red(`Index stats failed: ${error.message}`));
+      process.exit(1);
+    } finally {
+      if (store) await store.close();
+    }
+  });
+
+  return cmd;
+}
+
+function isRunning(pid?: number): boolean {
+  if (!pid || pid === process.pid) return false;
+  try {
+    process.kill(pid, 0);
+    return true;
+  } catch {
+    return false;
+  }
+}
+
+function printReembedStatus(state: ReembedState): void {
+  const phase = state.swappedAt ? 'swapped' : isRunning(state.pid) ? `building (pid ${state.pid})` : 'paused';
+  console.log(chalk.bold(`\nEmbedding migration ${state.from.model} → ${state.model}: ${phase}\n`));
+  for (const c of state.collections) {
+    const progress = c.done ? chalk.green('done') : `${c.copied}/${c.total}`;
+    console.log(`  ${c.name.padEnd(40)} ${progress.padStart(12)} ${chalk.gray(`→ ${c.target}`)}`);
+  }
+  console.log();
+}
+
+function reembedSubcommand(): Command {
+  const cmd = new Command('reembed');
+
+  cmd
+    .description('Re-embed the index with another model beside the current one, then switch to it')
+    .option('--model <model>', 'Embedding model to move to')
+    .option('--provider <provider>', 'Embedding provider for the model (default: from the model name)')
+    .option('--batch-size <n>', 'Points embedded per request', '64')
+    .option('--rate <n>', 'Most chunks embedded per minute')
+    .option('--background', 'Build in a detached process (log: .cv/reembed.log)')
+    .option('--no-swap', 'Build the new index but keep using the current one')
+    .option('--status', 'Show migration progress')
+    .option('--abort', 'Stop the migration and delete the new index')
+    .option('--drop-old', 'After the swap, delete the previous model\'s collections')
+    .option('-y, --yes', 'Skip confirmation');
+
+  addGlobalOptions(cmd);
+
+  cmd.action(async (options) => {
+    const output = createOutput(options);
+    let store: VectorStore | undefined;
+    let embedder: VectorManager | undefined;
+
+    try {
+      const repoRoot = await findRepoRoot();
+      if (!repoRoot) {
+        console.error(chalk.red('Not in a CV-Git repository'));
+        console.error(chalk.gray('Run `cv init` first'));
+        process.exit(1);
+      }
+
+      const config = await configManager.load(repoRoot);
+      const type = resolveVectorStoreType(config.vector.provider);
+      let state = await loadReembedState(repoRoot);
+
+      if (options.status) {
+        if (output.isJson) {
+          output.json(state ? { ...state, running: isRunning(state.pid) } : null);
+        } else if (state) {
+          printReembedStatus(state);
+        } else {
+          console.log(chalk.gray('No embedding model migration in progress.'));
+        }
+        return;
+      }
+
+      if (options.abort || options.dropOld) {
+        if (!state || (options.abort ? !!state.swappedAt : !state.swappedAt)) {
+          console.error(chalk.red(options.abort
+            ? 'No embedding model migration to abort'
+            : 'No swapped migration; --drop-old deletes the previous model\'s collections after `cv index reembed` switches'));
+          process.exit(1);
+        }
+        if (isRunning(state.pid)) {
+          console.error(chalk.red(`The migration is still running (pid ${state.pid})`));
+          process.exit(1);
+        }
+        const doomed = state.collections.map(c => (options.abort ? c.target : c.source));
+        if (!options.yes) {
+          const { proceed } = await inquirer.prompt([{
+            type: 'confirm',
+            name: 'proceed',
+            message: `Delete ${doomed.length} collection(s) (${doomed.join(', ')})?`,
+            default: !!options.dropOld
+          }]);
+          if (!proceed) return;
+        }
+        store = await openStore(type, config, repoRoot);
+        for (const name of doomed) await store.deleteCollection(name);
+        await clearReembedState(repoRoot);
+        console.log(chalk.green(`✓ Deleted ${doomed.length} collection(s) of ${options.abort ? state.model : state.from.model}`));
+        return;
+      }
+
+      if (state?.swappedAt) {
+        console.error(chalk.red(`The index already moved to ${state.model}; the ${state.from.model} collections are still there`));
+        console.error(chalk.gray('Delete them with `cv index reembed --drop-old` before the next migration.'));
+        process.exit(1);
+      }
+      if (state && options.model && options.model !== state.model) {
+        console.error(chalk.red(`A migration to ${state.model} is in progress`));
+        console.error(chalk.gray('Resume it with `cv index reembed`, or cancel it with `cv index reembed --abort`.'));
+        process.exit(1);
+      }
+      if (state && isRunning(state.pid)) {
+        console.log(chalk.yellow(`Migration to ${state.model} is already running (pid ${state.pid})`));
+        console.log(chalk.gray('Check on it with `cv index reembed --status`.'));
+        return;
+      }
+
+      const model: string | undefined = state?.model ?? options.model;
+      if (!model) {
+        console.error(chalk.red('Which model? Pass --model <model>'));
+        process.exit(1);
+      }
+      if (!state && model === config.embedding.model) {
+        console.log(chalk.yellow(`The index is already embedded with ${model}.`));
+        return;
+      }
+
+      // The provider the index will use once it switches
+      const providerName: EmbeddingProviderName = state?.provider ?? options.provider ??
+        EMBEDDING_MODELS[model]?.provider ?? config.embedding.provider;
+      const spinner = output.spinner(`Setting up ${model}...`);
+      spinner.start();
+      const provider = await resolveEmbeddingProvider(
+        { ...config, embedding: { ...config.embedding, provider: providerName, model } },
+        { pullModel: true, onStatus: message => { spinner.text = message; } }
+      );
+      await provider.init?.();
+      if (provider.model !== model) {
+        spinner.fail(`${model} is not available from ${providerName} (got ${provider.name}/${provider.model})`);
+        process.exit(1);
+      }
+
+      // Embeds like sync does (same text, cache under .cv/embeddings) and
+      // writes to the collections by their own names, beside those in use
+      embedder = createVectorManager({
+        url: await storeUrl(type, config),
+        ...vectorStoreOptions(config, repoRoot),
+        aliases: undefined,
+        provider,
+        cacheDir: path.join(repoRoot, '.cv', 'embeddings')
+      });
+      await embedder.connect();
+      store = embedder.getStore()!;
+
+      if (!state) {
+        spinner.text = 'Counting indexed points...';
+        const collections: Array<{ name: string; points: number }> = [];
+        for (const name of await indexedCollections(store, config, repoRoot)) {
+          const stats = await store.getCollectionStats(config.vector.aliases?.[name] ?? name);
+          collections.push({ name, points: stats?.pointsCount ?? 0 });
+        }
+        spinner.stop();
+        if (collections.every(c => c.points === 0)) {
+          console.log(chalk.yellow('The index is empty; change embedding.model and run `cv sync` instead.'));
+          return;
+        }
+
+        state = planReembed({
+          collections,
+          aliases: config.vector.aliases,
+          model,
+          provider: provider.name,
+          dimensions: provider.dimension,
+          from: { model: config.embedding.model, provider: config.embedding.provider }
+        });
+        console.log(chalk.bold(`\nRe-embedding with ${provider.name}/${model} (${provider.dimension} dimensions):\n`));
+        for (const c of state.collections) {
+          console.log(`  ${c.name.padEnd(40)} ${chalk.gray(`${c.total} points → ${c.target}`)}`);
+        }
+        console.log(chalk.gray(`\n  ${config.embedding.model} keeps serving searches and \`cv sync\` until the new index is complete.\n`));
+
+        if (!options.yes && !process.env[REEMBED_DAEMON_ENV]) {
+          const { proceed } = await inquirer.prompt([{
+            type: 'confirm',
+            name: 'proceed',
+            message: 'Start the migration?',
+            default: true
+          }]);
+          if (!proceed) return;
+        }
+        await saveReembedState(repoRoot, state);
+      } else {
+        spinner.stop();
+      }
+
+      if (options.background && !process.env[REEMBED_DAEMON_ENV]) {
+        await embedder.close();
+        embedder = undefined;
+        store = undefined;
+        const logPath = path.join(getCVDir(repoRoot), 'reembed.log');
+        const log = fs.openSync(logPath, 'a');
+        const child = spawn(process.execPath, process.argv.slice(1).filter(arg => arg !== '--background'), {
+          cwd: repoRoot,
+          detached: true,
+          stdio: ['ignore', log, log],
+          env: { ...process.env, [REEMBED_DAEMON_ENV]: '1' }
+        });
+        child.unref();
+        fs.closeSync(log);
+        console.log(chalk.green(`✓ Building the ${model} index in the background (pid ${child.pid})`));
+        console.log(chalk.gray(`  Log: ${logPath}`));
+        console.log(chalk.gray('  Progress: cv index reembed --status'));
+        return;
+      }
+
+      const controller = new AbortController();
+      process.once('SIGINT', () => controller.abort());
+      state.pid = process.pid;
+      await saveReembedState(repoRoot, state);
+
+      const progress = output.spinner('Re-embedding...');
+      progress.start();
+      await runReembed(state, {
+        store,
+        embedder,
+        batchSize: Math.max(1, parseInt(options.batchSize, 10) || 64),
+        rateLimit: options.rate ? parseInt(options.rate, 10) || undefined : undefined,
+        signal: controller.signal,
+        save: current => saveReembedState(repoRoot, current),
+        onProgress: ({ collection, copied, total }) => {
+          progress.text = `Re-embedding ${collection} (${copied}/${total})`;
+          if (process.env[REEMBED_DAEMON_ENV]) console.log(`${new Date().toISOString()} ${collection} ${copied}/${total}`);
+        }
+      });
+      state.pid = undefined;
+      await saveReembedState(repoRoot, state);
+
+      if (controller.signal.aborted) {
+        progress.warn('Paused; run `cv index reembed` again to resume');
+        return;
+      }
+      const copied = state.collections.reduce((n, c) => n + c.copied, 0);
+      progress.succeed(`Embedded ${copied} points with ${model}`);
+
+      if (options.swap === false) {
+        console.log(chalk.gray(`Compare with \`cv eval --next\`, then switch with \`cv index reembed\`.`));
+        return;
+      }
+
+      // Points `cv sync` wrote meanwhile, then one config write moves every command over
+      const swap = output.spinner('Catching up with changes since the migration started...');
+      swap.start();
+      const { updated, deleted } = await catchUpReembed(state, { store, embedder, batchSize: 64 });
+      const next = reembedConfig(config, state);
+      await configManager.update({ embedding: next.embedding, vector: next.vector });
+      state.swappedAt = new Date().toISOString();
+      await saveReembedState(repoRoot, state);
+      swap.succeed(`Switched to ${model}` + (updated + deleted > 0 ? ` (${updated} updated, ${deleted} removed since the start)` : ''));
+
+      // Keep the min-score threshold calibrated for the new model
+      const code = state.collections.find(c => c.name === config.vector.collections.codeChunks);
+      if (code) {
+        const distribution = await sampleScoreDistribution(embedder, code.target).catch(() => null);
+        if (distribution) await saveScoreDistribution(repoRoot, distribution);
+      }
+
+      console.log(chalk.gray(`The ${state.from.model} collections are kept; delete them with \`cv index reembed --drop-old\`.`));
+    } catch (error: any) {
+      console.error(chalk.red(`Re-embedding failed: ${error.message}`));
+      console.error(chalk.gray('Run `cv index reembed` again to resume.'));
+      process.exit(1);
+    } finally {
+      if (embedder) await embedder.close();
+      else if (store) await store.close();
+    }
+  });
+
+  return cmd;
+}
+
+function serveSubcommand(): Command {
+  const cmd = new Command('serve');
+
+  cmd
+    .description('Serve this index to teammates over HTTP; their clones set index.remote to query it')
+    .option('--port <port>', 'Port to listen on', String(INDEX_SERVER_PORT))
+    .option('--host <host>', 'Address to bind (use 0.0.0.0 to serve other machines)', '127.0.0.1')
+    .option('--token <token...>', 'Bearer tokens to accept (default: CV_INDEX_TOKEN, or one generated now)');
+
+  cmd.action(async (options) => {
+    try {
+      const repoRoot = await findRepoRoot();
+      if (!repoRoot) {
+        console.error(chalk.red('Not in a CV-Git repository'));
+        console.error(chalk.gray('Run `cv init` first'));
+        process.exit(1);
+      }
+      const port = parseInt(options.port, 10);
+      if (!(port > 0 && port < 65536)) {
+        console.error(chalk.red(`Invalid --port "${options.port}"`));
+        process.exit(1);
+      }
+
+      const config = await configManager.load(repoRoot);
+      if (config.index?.remote) {
+        console.error(chalk.red(`This repository queries the shared index at ${config.index.remote}; serve from the clone that syncs it`));
+        process.exit(1);
+      }
+      const state = await readSharedSyncState(repoRoot);
+      if (!state) {
+        console.error(chalk.red('Nothing to serve yet. Run `cv sync` first.'));
+        process.exit(1);
+      }
+
+      const envToken = process.env.CV_INDEX_TOKEN;
+      const tokens: string[] = options.token ?? (envToken ? [envToken] : [randomBytes(24).toString('base64url')]);
+
+      const type = resolveVectorStoreType(config.vector.provider);
+      const store = await createVectorStore({
+        type,
+        url: await storeUrl(type, config),
+        indexDir: getVectorIndexDir(repoRoot),
+        hnsw: config.vector.hnsw,
+        quantization: config.vector.quantization,
+        aliases: config.vector.aliases
+      });
+      await store.connect();
+
+      // A branch-namespaced index is served as the branch checked out here
+      const namespace = config.vector.branchNamespaces !== false ? getCurrentNamespace(repoRoot) : null;
+      const scope = namespace && (await loadBranchIndex(repoRoot)).branches[namespace]
+        ? { must: [branchCondition(namespace)] }
+        : undefined;
+
+      let dimensions: number | undefined;
+      for (const name of await repositoryCollections(store, config, repoRoot)) {
+        dimensions ??= (await store.getCollectionStats(name))?.vectorSize;
+      }
+
+      const info = async (): Promise<IndexServerInfo> => ({
+        version: INDEX_PROTOCOL_VERSION,
+        storeType: store.type,
+        commit: (await readSharedSyncState(repoRoot))?.commit,
+        embedding: { model: config.embedding?.model, dimensions }
+      });
+
+      await startIndexServer({
+        store,
+        tokens,
+        scope,
+        info,
+        state: () => readSharedSyncState(repoRoot),
+        host: options.host,
+        port
+      });
+
+      const shown = options.host === '0.0.0.0' || options.host === '::' ? os.hostname() : options.host;
+      console.log(chalk.green(`✓ Serving the ${type} index of ${repoRoot}${scope ? ` (branch ${namespace})` : ''}`));
+      console.log(`  ${chalk.cyan(`http://${shown}:${port}`)}  at commit ${state.commit.slice(0, 8)}`);
+      if (!options.token && !envToken) {
+        console.log(`  Token: ${chalk.bold(tokens[0])}  ${chalk.gray('(pass --token or set CV_INDEX_TOKEN to keep one across restarts)')}`);
+      }
+      console.log(chalk.gray(`  Clients: set index.remote to http://${shown}:${port} and CV_INDEX_TOKEN to the token`));
+      console.log(chalk.gray('  Keep it current with `cv sync` (or `cv watch`) here; press Ctrl+C to stop'));
+    } catch (error: any) {
+      console.error(chalk.red(`Error: ${error.code === 'EADDRINUSE' ? `port ${options.port} is already in use (try --port)` : error.message}`));
+      process.exit(1);
+    }
+  });
+
+  return cmd;
+}
+
+export function indexCommand(): Command {
+  const cmd = new Command('index');
+
+  cmd.description('Manage the vector index storage');
+  cmd.addCommand(migrateSubcommand());
+  cmd.addCommand(gcSubcommand());
+  cmd.addCommand(reembedSubcommand());
+  cmd.addCommand(statsSubcommand());
+  cmd.addCommand(serveSubcommand());
+
+  return cmd;
+}
@@ -3,7 +3,9 @@
  * File watcher for automatic knowledge graph sync
  *
  * Watches for file changes and automatically syncs the knowledge graph
- * to keep it current during development.
+ * to keep it current during development. `--daemon` runs it in the
+ * background; either way it serves .cv/watch.sock so `cv explain` and
+ * `cv chat` can wait for pending changes before querying.
  */
 
 import { Command } from 'commander';
@@ -11,10 +13,21 @@ import chalk from 'chalk';
 import ora from 'ora';
 import { watch, FSWatcher } from 'chokidar';
 import ignore, { Ignore } from 'ignore';
-import { spawnSync, execSync } from 'child_process';
+import { spawn, execSync } from 'child_process';
 import * as fs from 'fs';
 import * as path from 'path';
+import { toPosixPath } from '@cv-git/shared';
 import { addGlobalOptions } from '../utils/output.js';
+import {
+  getWatchLogPath,
+  requestWatchDaemon,
+  startWatchServer,
+  stopWatchServer,
+  WatchDaemonStatus
+} from '../utils/watch-daemon.js';
+
+/** Set in the detached child started by `cv watch --daemon` */
+const DAEMON_ENV = 'CV_WATCH_DAEMON';
 
 /**
  * Find git repository root
@@ -34,6 +47,26 @@ function findGitRoot(startDir: string = process.cwd()): string | null {
   return null;
 }
 
+/**
+ * Run `cv sync --incremental` in a child process.
+ * On Windows `cv` is a .cmd shim, which only resolves through the shell.
+ */
+function runIncrementalSync(repoRoot: string): Promise<{ status: number | null; stderr: string }> {
+  // Async so the daemon socket keeps answering while a sync runs
+  return new Promise(resolve => {
+    const child = spawn('cv', ['sync', '--incremental'], {
+      cwd: repoRoot,
+      stdio: ['ignore', 'pipe', 'pipe'],
+      shell: process.platform === 'win32',
+    });
+    let stderr = '';
+    child.stdout?.resume();
+    child.stderr?.on('data', chunk => { stderr += chunk; });
+    child.on('error', error => resolve({ status: null, stderr: error.message }));
+    child.on('close', status => resolve({ status, stderr }));
+  });
+}
+
 /**
  * Check if CV is initialized in a git repo
  */
@@ -113,26 +146,80 @@ interface WatchOptions {
   quiet?: boolean;
 }
 
+/**
+ * Repository root for watch commands, exiting when it is not usable
+ */
+function requireRepo(): string {
+  const repoRoot = findGitRoot();
+  if (!repoRoot) {
+    console.error(chalk.red('Not in a git repository'));
+    process.exit(128);
+  }
+
+  if (!isCVInitialized(repoRoot)) {
+    console.error(chalk.red('CV not initialized. Run `cv init` first.'));
+    process.exit(1);
+  }
+  return repoRoot;
+}
+
+/**
+ * Re-run this command detached, logging to .cv/watch.log
+ */
+async function startDaemon(repoRoot: string): Promise<void> {
+  const running = await requestWatchDaemon<WatchDaemonStatus>(repoRoot, 'status');
+  if (running) {
+    console.log(chalk.yellow(`cv watch is already running (pid ${running.pid})`));
+    return;
+  }
+
+  const logPath = getWatchLogPath(repoRoot);
+  const log = fs.openSync(logPath, 'a');
+  const args = process.argv.slice(1).filter(arg => arg !== '--daemon');
+  const child = spawn(process.execPath, args, {
+    cwd: repoRoot,
+    detached: true,
+    stdio: ['ignore', log, log],
+    env: { ...process.env, [DAEMON_ENV]: '1' },
+  });
+  child.unref();
+  fs.closeSync(log);
+
+  // Wait for the socket so the next command can rely on it
+  const spinner = ora('Starting watch daemon...').start();
+  for (let attempt = 0; attempt < 50; attempt++) {
+    await new Promise(resolve => setTimeout(resolve, 200));
+    const status = await requestWatchDaemon<WatchDaemonStatus>(repoRoot, 'status', 500);
+    if (status) {
+      spinner.succeed(`Watch daemon running (pid ${status.pid})`);
+      console.log(chalk.gray(`  Log: ${logPath}`));
+      console.log(chalk.gray('  Stop with: cv watch stop'));
+      return;
+    }
+  }
+  spinner.fail(`Watch daemon did not start; see ${logPath}`);
+  process.exit(1);
+}
+
 export function watchCommand(): Command {
   const cmd = new Command('watch');
 
   cmd
     .description('Watch for file changes and automatically sync knowledge graph')
     .option('-d, --debounce <ms>', 'Debounce interval in milliseconds', '500')
-    .option('--no-initial-sync', 'Skip initial sync on start');
+    .option('--no-initial-sync', 'Skip initial sync on start')
+    .option('--daemon', 'Run in the background (see `cv watch status` / `cv watch stop`)');
 
   addGlobalOptions(cmd);
+  cmd.addCommand(watchStatusCommand());
+  cmd.addCommand(watchStopCommand());
 
-  cmd.action(async (options: WatchOptions & { debounce?: string; initialSync?: boolean }) => {
-    const repoRoot = findGitRoot();
-    if (!repoRoot) {
-      console.error(chalk.red('Not in a git repository'));
-      process.exit(128);
-    }
+  cmd.action(async (options: WatchOptions & { debounce?: string; initialSync?: boolean; daemon?: boolean }) => {
+    const repoRoot = requireRepo();
 
-    if (!isCVInitialized(repoRoot)) {
-      console.error(chalk.red('CV not initialized. Run `cv init` first.'));
-      process.exit(1);
+    if (options.daemon && !process.env[DAEMON_ENV]) {
+      await startDaemon(repoRoot);
+      return;
     }
 
     const debounceMs = parseInt(options.debounce || '500', 10);
@@ -142,14 +229,22 @@ export function watchCommand(): Command {
     console.log(chalk.gray(`Debounce: ${debounceMs}ms`));
     console.log();
 
+    const startedAt = Date.now();
+    let syncCount = 0;
+    let lastSyncAt: number | undefined;
+    let lastError: string | undefined;
+
+    const recordSync = (result: { status: number | null; stderr: string }) => {
+      syncCount++;
+      lastSyncAt = Date.now();
+      lastError = result.status === 0 ? undefined : (result.stderr.trim().split('\n').pop() || `exit ${result.status}`);
+    };
+
     // Initial sync if requested
     if (options.initialSync !== false) {
       const spinner = ora('Running initial sync...').start();
-      const result = spawnSync('cv', ['sync', '--incremental'], {
-        cwd: repoRoot,
-        stdio: ['inherit', 'pipe', 'pipe'],
-        encoding: 'utf-8',
-      });
+      const result = await runIncrementalSync(repoRoot);
+      recordSync(result);
       if (result.status === 0) {
         spinner.succeed('Initial sync complete');
       } else {
@@ -198,11 +293,8 @@ export function watchCommand(): Command {
       const spinner = ora(`Syncing: ${summary}`).start();
 
       try {
-        const result = spawnSync('cv', ['sync', '--incremental'], {
-          cwd: repoRoot,
-          stdio: ['inherit', 'pipe', 'pipe'],
-          encoding: 'utf-8',
-        });
+        const result = await runIncrementalSync(repoRoot);
+        recordSync(result);
 
         if (result.status === 0) {
           spinner.succeed(`Synced: ${summary}`);
@@ -235,8 +327,8 @@ export function watchCommand(): Command {
      * Handle file change event
      */
     const handleFileChange = (event: 'add' | 'change' | 'unlink', filePath: string) => {
-      // Get relative path
-      const relativePath = path.relative(repoRoot, filePath);
+      // Get relative path (forward slashes, matching git ls-files on Windows too)
+      const relativePath = toPosixPath(path.relative(repoRoot, filePath));
 
       // Skip if ignored
       if (ig.ignores(relativePath)) {
@@ -355,26 +447,122 @@ export function watchCommand(): Command {
         console.log();
       });
 
+    /**
+     * Sync pending changes now and wait until none are left
+     */
+    const flush = async () => {
+      if (syncTimer) {
+        clearTimeout(syncTimer);
+        syncTimer = null;
+      }
+      while (pendingChanges.size > 0 || syncInProgress) {
+        if (syncInProgress) {
+          await new Promise(resolve => setTimeout(resolve, 100));
+        } else {
+          await processPendingChanges();
+        }
+      }
+    };
+
     // Handle shutdown
     const shutdown = async () => {
       console.log();
       console.log(chalk.gray('Stopping watcher...'));
 
       // Process any pending changes before exit
-      if (pendingChanges.size > 0) {
-        if (syncTimer) clearTimeout(syncTimer);
-        await processPendingChanges();
-      }
+      await flush();
 
       await watcher.close();
       await gitWatcher.close();
+      await stopWatchServer(repoRoot, server);
       console.log(chalk.green('Watcher stopped'));
       process.exit(0);
     };
 
+    // Serve status/flush requests from other commands
+    let server: Awaited<ReturnType<typeof startWatchServer>>;
+    try {
+      server = await startWatchServer(repoRoot, {
+        status: () => ({
+          pid: process.pid,
+          repoRoot,
+          startedAt,
+          pending: pendingChanges.size,
+          syncing: syncInProgress,
+          syncCount,
+          lastSyncAt,
+          lastError,
+        }),
+        flush,
+        stop: shutdown,
+      });
+    } catch (error: any) {
+      await watcher.close();
+      await gitWatcher.close();
+      console.error(chalk.red(error.message));
+      process.exit(1);
+    }
+
     process.on('SIGINT', shutdown);
     process.on('SIGTERM', shutdown);
   });
 
   return cmd;
 }
+
+/**
+ * cv watch status - report on a running watcher
+ */
+function watchStatusCommand(): Command {
+  const cmd = new Command('status');
+
+  cmd
+    .description('Show whether a watcher is running for this repository')
+    .option('--json', 'Output as JSON');
+
+  cmd.action(async (options: { json?: boolean }) => {
+    const repoRoot = requireRepo();
+    const status = await requestWatchDaemon<WatchDaemonStatus>(repoRoot, 'status');
+
+    if (options.json) {
+      console.log(JSON.stringify(status ? { running: true, ...status } : { running: false }, null, 2));
+      return;
+    }
+    if (!status) {
+      console.log(chalk.gray('cv watch is not running. Start it with: cv watch --daemon'));
+      return;
+    }
+
+    console.log(chalk.green(`cv watch running (pid ${status.pid})`));
+    console.log(chalk.gray(`  Since:   ${new Date(status.startedAt).toLocaleString()}`));
+    console.log(chalk.gray(`  Syncs:   ${status.syncCount}` +
+      (status.lastSyncAt ? `, last ${new Date(status.lastSyncAt).toLocaleTimeString()}` : '')));
+    console.log(chalk.gray(`  Pending: ${status.pending} file(s)${status.syncing ? ', syncing now' : ''}`));
+    if (status.lastError) {
+      console.log(chalk.yellow(`  Last sync failed: ${status.lastError}`));
+    }
+  });
+
+  return cmd;
+}
+
+/**
+ * cv watch stop - stop a running watcher after it syncs pending changes
+ */
+function watchStopCommand(): Command {
+  const cmd = new Command('stop');
+
+  cmd
+    .description('Stop the watcher for this repository')
+    .action(async () => {
+      const repoRoot = requireRepo();
+      const reply = await requestWatchDaemon(repoRoot, 'stop');
+      if (reply) {
+        console.log(chalk.green('cv watch stopped'));
+      } else {
+        console.log(chalk.gray('cv watch is not running'));
+      }
+    });
+
+  return cmd;
+}
@@ -0,0 +1,121 @@
+/**
+ * cv workspace command
+ * Register sibling repositories whose indexes `--workspace` queries search
+ *
+ * Paths are stored in `workspace.repos`, relative to this repository, and
+ * can be shared with the team through `.cvgit.toml`. Each repository keeps
+ * its own index; run `cv init` and `cv sync` in it first.
+ */
+
+import { Command } from 'commander';
+import chalk from 'chalk';
+import Table from 'cli-table3';
+import {
+  addWorkspaceRepo,
+  configManager,
+  listWorkspaceMembers,
+  removeWorkspaceRepo
+} from '@cv-git/core';
+import { findRepoRoot } from '@cv-git/shared';
+import { addGlobalOptions, createOutput } from '../utils/output.js';
+
+async function requireRepoRoot(): Promise<string> {
+  const repoRoot = await findRepoRoot();
+  if (!repoRoot) {
+    console.error(chalk.red('Not in a CV-Git repository'));
+    console.error(chalk.gray('Run `cv init` first'));
+    process.exit(1);
+  }
+  return repoRoot;
+}
+
+export function workspaceCommand(): Command {
+  const cmd = new Command('workspace')
+    .description('Search sibling repositories alongside this one (--workspace)');
+
+  const add = new Command('add')
+    .description('Register a sibling repository')
+    .argument('<path>', 'Path to the repository');
+  addGlobalOptions(add);
+  add.action(async (target: string, options) => {
+    const output = createOutput(options);
+    try {
+      const repoRoot = await requireRepoRoot();
+      const config = await configManager.load(repoRoot);
+      const repos = await addWorkspaceRepo(repoRoot, config.workspace?.repos, target);
+      await configManager.update({ workspace: { ...config.workspace, repos } });
+
+      const member = (await listWorkspaceMembers(repoRoot, repos)).at(-1)!;
+      if (output.isJson) {
+        output.json(member);
+        return;
+      }
+      console.log(chalk.green(`✓ Added ${member.name} (${member.path})`));
+      if (!member.initialized) {
+        console.log(chalk.yellow(`  ${member.name} has no index yet. Run \`cv init && cv sync\` in ${member.root}`));
+      }
+      console.log(chalk.gray('  Search it with `cv explain --workspace "..."`'));
+    } catch (error: any) {
+      output.error(error.message, error);
+      process.exit(1);
+    }
+  });
+
+  const remove = new Command('remove')
+    .alias('rm')
+    .description('Unregister a repository')
+    .argument('<repo>', 'Repository name or path');
+  addGlobalOptions(remove);
+  remove.action(async (target: string, options) => {
+    const output = createOutput(options);
+    try {
+      const repoRoot = await requireRepoRoot();
+      const config = await configManager.load(repoRoot);
+      const repos = await removeWorkspaceRepo(repoRoot, config.workspace?.repos, target);
+      await configManager.update({ workspace: { ...config.workspace, repos } });
+      output.success(`Removed ${target} from the workspace`, { repos });
+    } catch (error: any) {
+      output.error(error.message, error);
+      process.exit(1);
+    }
+  });
+
+  const list = new Command('list')
+    .alias('ls')
+    .description('List registered repositories');
+  addGlobalOptions(list);
+  list.action(async (options) => {
+    const output = createOutput(options);
+    try {
+      const repoRoot = await requireRepoRoot();
+      const config = await configManager.load(repoRoot);
+      const members = await listWorkspaceMembers(repoRoot, config.workspace?.repos);
+
+      if (output.isJson) {
+        output.json(members);
+        return;
+      }
+      if (members.length === 0) {
+        console.log(chalk.gray('No repositories registered. Add one with `cv workspace add <path>`.'));
+        return;
+      }
+
+      const table = new Table({ head: ['Name', 'Path', 'Status'].map(h => chalk.cyan(h)) });
+      for (const member of members) {
+        const status = !member.exists
+          ? chalk.red('missing')
+          : member.initialized ? chalk.green('indexed') : chalk.yellow('not initialized');
+        table.push([member.name, member.path, status]);
+      }
+      console.log(table.toString());
+    } catch (error: any) {
+      output.error(error.message, error);
+      process.exit(1);
+    }
+  });
+
+  cmd.addCommand(add);
+  cmd.addCommand(remove);
+  cmd.addCommand(list);
+  return cmd;
+}
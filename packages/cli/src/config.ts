@@ -8,6 +8,7 @@ import { promises as fs } from 'fs';
 import * as path from 'path';
 import * as os from 'os';
 import { GitPlatform } from '@cv-git/credentials';
+import type { PlatformConfig as AdapterPlatformConfig } from '@cv-git/platform';
 import { getFalkorDbUrl, getQdrantUrl } from '@cv-git/core';
 
 export interface CVGitConfig {
@@ -19,6 +20,12 @@ export interface CVGitConfig {
   vector: VectorConfig;
   features: FeaturesConfig;
   hub?: HubConfig;
+  citations?: CitationsConfig;
+}
+
+export interface CitationsConfig {
+  /** plain, vscode, cursor, idea, osc8, auto, or a template with {path}, {abs}, {line} */
+  format: string;
 }
 
 export interface HubConfig {
@@ -28,8 +35,17 @@ export interface HubConfig {
 
 export interface PlatformConfig {
   type: GitPlatform;
+  /** Web URL, e.g. https://gitlab.example.com for a self-hosted instance */
   url?: string;
+  /** API URL (defaults to the platform's, or <url>/api/v4 for GitLab) */
   api?: string;
+  /** TLS settings for self-hosted instances */
+  tls?: {
+    /** Extra CA certificate(s) to trust (PEM file) */
+    caFile?: string;
+    /** Skip certificate verification */
+    insecure?: boolean;
+  };
 }
 
 export interface CredentialsConfig {
@@ -61,12 +77,24 @@ export interface FeaturesConfig {
   autoMerge: boolean;
 }
 
+/**
+ * Platform settings in the shape the platform adapters take
+ */
+export function toAdapterConfig(platform: PlatformConfig): AdapterPlatformConfig {
+  return {
+    type: platform.type,
+    webUrl: platform.url,
+    apiUrl: platform.api,
+    tls: platform.tls,
+  };
+}
+
 /**
  * User Preferences - stored separately from config
  * These are the user's preferred choices for interfaces/providers
  */
 export type AIProvider = 'anthropic' | 'openai' | 'openrouter';
-export type EmbeddingProvider = 'ollama' | 'lmstudio' | 'openai' | 'openrouter';
+export type EmbeddingProvider = 'ollama' | 'lmstudio' | 'openai' | 'openrouter' | 'azure' | 'local';
 export type GitPlatformType = 'cv-hub' | 'github' | 'gitlab' | 'bitbucket';
 
 export interface UserPreferences {
@@ -110,6 +138,9 @@ const DEFAULT_CONFIG: CVGitConfig = {
     aiCodeReview: true,
     autoMerge: false,
   },
+  citations: {
+    format: 'auto',
+  },
   hub: {
     url: 'https://api.hub.controlvector.io/api',
   },
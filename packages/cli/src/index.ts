@@ -30,6 +30,9 @@ import { initCommand } from './commands/init.js';
 import { syncCommand } from './commands/sync.js';
 import { doCommand } from './commands/do.js';
 import { findCommand } from './commands/find.js';
+import { grepCommand } from './commands/grep.js';
+import { calibrateCommand } from './commands/calibrate.js';
+import { indexCommand } from './commands/vector-index.js';
 import { explainCommand } from './commands/explain.js';
 import { reviewCommand } from './commands/review.js';
 import { graphCommand } from './commands/graph.js';
@@ -44,6 +47,7 @@ import { cloneCommand } from './commands/clone.js';
 import { cloneGroupCommand } from './commands/clone-group.js';
 import { contextCommand } from './commands/context.js';
 import { chatCommand } from './commands/chat.js';
+import { tuiCommand } from './commands/tui.js';
 import { pushCommand } from './commands/push.js';
 import { pullCommand } from './commands/pull.js';
 import { watchCommand } from './commands/watch.js';
@@ -84,13 +88,36 @@ import { agentCommand } from './commands/agent.js';
 import { agentHookCommand } from './commands/agent-hook.js';
 import { deployCommand } from './commands/deploy.js';
 import { aiCommand } from './commands/ai-setup.js';
+import { batchCommand } from './commands/batch.js';
+import { evalCommand } from './commands/eval.js';
+import { usageCommand } from './commands/usage.js';
+import { redactCommand } from './commands/redact.js';
+import { workspaceCommand } from './commands/workspace.js';
+import { deadcodeCommand } from './commands/deadcode.js';
+import { complexityCommand } from './commands/complexity.js';
+import { hotspotsCommand } from './commands/hotspots.js';
+import { blameExplainCommand } from './commands/blame-explain.js';
+import { diffSummaryCommand } from './commands/diff-summary.js';
+import { mcpCommand } from './commands/mcp.js';
+import { lspCommand } from './commands/lsp.js';
+import { serveCommand } from './commands/serve.js';
+import { testGenCommand } from './commands/test-gen.js';
+import { docgenCommand } from './commands/docgen.js';
+import { refactorCommand } from './commands/refactor.js';
+import { onboardCommand } from './commands/onboard.js';
+import { securityCommand } from './commands/security.js';
+import { promptsCommand } from './commands/prompts.js';
+import { askCommand } from './commands/ask.js';
+import { startUsageTracking, initOfflineMode } from '@cv-git/core';
+import { printOfflineNotice } from './utils/offline.js';
 
 const program = new Command();
 
 program
   .name('cv')
   .description('AI-Native Version Control with Knowledge Graph & Secure Credentials')
-  .version(CLI_VERSION);
+  .version(CLI_VERSION)
+  .option('--offline', 'Make no cloud provider calls; keyword search, graph and analysis commands still work');
 
 // Add commands
 program.addCommand(configCommand());        // Configuration management
@@ -105,6 +132,7 @@ program.addCommand(cloneCommand());          // Clone and initialize
 program.addCommand(cloneGroupCommand());     // Clone entire group/subgroup
 program.addCommand(contextCommand());        // Generate AI context
 program.addCommand(chatCommand());           // AI chat with codebase context
+program.addCommand(tuiCommand());            // Terminal UI for search, chat and review triage
 program.addCommand(pushCommand());           // Git push with auto-sync
 program.addCommand(pullCommand());           // Git pull with auto-sync
 program.addCommand(watchCommand());          // File watcher with auto-sync
@@ -116,7 +144,11 @@ program.addCommand(initCommand());
 program.addCommand(syncCommand());
 program.addCommand(doCommand());
 program.addCommand(findCommand());
+program.addCommand(grepCommand());            // Ranked search with regex/path/kind filters
+program.addCommand(calibrateCommand());       // Retrieval threshold tuning
+program.addCommand(indexCommand());           // Vector index backends
 program.addCommand(explainCommand());
+program.addCommand(askCommand());             // Questions about the code, optionally as schema-checked JSON
 program.addCommand(reviewCommand());
 program.addCommand(graphCommand());
 program.addCommand(gitCommand());
@@ -153,6 +185,38 @@ program.addCommand(agentCommand());         // Task listener + Claude Code launc
 program.addCommand(agentHookCommand());     // Claude Code hook handler (cv agent-hook)
 program.addCommand(deployCommand());         // Deploy management (cv deploy)
 program.addCommand(aiCommand());             // AI provider setup (cv ai setup/status)
+program.addCommand(batchCommand());          // Repo-wide batch AI operations (cv batch)
+program.addCommand(evalCommand());           // Retrieval evaluation (cv eval providers)
+program.addCommand(usageCommand());          // Token usage and cost (cv usage)
+program.addCommand(redactCommand());         // Secret redaction preview (cv redact --check)
+program.addCommand(workspaceCommand());      // Sibling repositories for --workspace queries
+program.addCommand(deadcodeCommand());       // Unreferenced functions and types
+program.addCommand(complexityCommand());     // Cyclomatic and cognitive complexity ranking
+program.addCommand(hotspotsCommand());       // Churn x complexity risk ranking
+program.addCommand(blameExplainCommand());   // Why a line range looks the way it does
+program.addCommand(diffSummaryCommand());    // Clustered summary of a branch or commit range
+program.addCommand(mcpCommand());            // Serve the index to editors and agents over MCP
+program.addCommand(lspCommand());            // Language server for editors
+program.addCommand(serveCommand());          // Local web UI for search, browsing and chat
+program.addCommand(testGenCommand());        // Generate and run unit tests for a function
+program.addCommand(docgenCommand());         // Doc comments for public functions and types
+program.addCommand(refactorCommand());       // Graph-driven cross-file refactoring
+program.addCommand(onboardCommand());        // Repo tour for new contributors
+program.addCommand(securityCommand());       // Security scan: detectors plus taint-aware review
+program.addCommand(promptsCommand());        // Prompt templates in .cv/prompts/
+
+// Record token usage of AI requests under the subcommand that made them; settle offline mode
+program.hook('preAction', (_program, actionCommand) => {
+  const names: string[] = [];
+  for (let cmd: Command | null = actionCommand; cmd && cmd !== program; cmd = cmd.parent) {
+    names.unshift(cmd.name());
+  }
+  startUsageTracking(names.join(' '));
+
+  // --offline, CV_OFFLINE=1, or no network detected
+  const offline = initOfflineMode(program.opts().offline);
+  if (offline) printOfflineNotice(offline, actionCommand.opts());
+});
 
 // Error handler
 program.exitOverride((err) => {
@@ -0,0 +1,119 @@
+/**
+ * Language Server Protocol framing
+ *
+ * LSP messages are JSON-RPC bodies preceded by a `Content-Length` header:
+ *
+ *   Content-Length: 52\r\n
+ *   \r\n
+ *   {"jsonrpc":"2.0","id":1,"method":"initialize",...}
+ *
+ * Only the parts of the protocol `cv lsp` uses are typed here.
+ */
+
+export interface LspMessage {
+  jsonrpc: '2.0';
+  id?: number | string | null;
+  method?: string;
+  params?: any;
+  result?: any;
+  error?: { code: number; message: string; data?: unknown };
+}
+
+export interface Position {
+  /** 0-based */
+  line: number;
+  character: number;
+}
+
+export interface Range {
+  start: Position;
+  end: Position;
+}
+
+export interface Diagnostic {
+  range: Range;
+  severity: DiagnosticSeverity;
+  code?: string;
+  source: string;
+  message: string;
+}
+
+export enum DiagnosticSeverity {
+  Error = 1,
+  Warning = 2,
+  Information = 3,
+  Hint = 4,
+}
+
+/** LSP SymbolKind values used for cv-git symbol kinds */
+export enum LspSymbolKind {
+  File = 1,
+  Class = 5,
+  Method = 6,
+  Enum = 10,
+  Interface = 11,
+  Function = 12,
+  Variable = 13,
+  Constant = 14,
+  Struct = 23,
+  TypeParameter = 26,
+}
+
+export const ErrorCodes = {
+  ParseError: -32700,
+  InvalidRequest: -32600,
+  MethodNotFound: -32601,
+  InternalError: -32603,
+  ServerNotInitialized: -32002,
+} as const;
+
+/**
+ * Frame a message for the wire
+ */
+export function encodeMessage(message: LspMessage): Buffer {
+  const body = Buffer.from(JSON.stringify(message), 'utf-8');
+  return Buffer.concat([Buffer.from(`Content-Length: ${body.length}\r\n\r\n`, 'ascii'), body]);
+}
+
+/**
+ * Splits a byte stream into messages. Feed it chunks as they arrive; it
+ * returns every message completed so far and keeps the remainder. Malformed
+ * messages are skipped and reported to `onError`.
+ */
+export class MessageReader {
+  private buffer = Buffer.alloc(0);
+
+  constructor(private onError: (error: Error) => void = () => {}) {}
+
+  push(chunk: Buffer): LspMessage[] {
+    this.buffer = Buffer.concat([this.buffer, chunk]);
+    const messages: LspMessage[] = [];
+
+    for (;;) {
+      const headerEnd = this.buffer.indexOf('\r\n\r\n');
+      if (headerEnd === -1) break;
+
+      const headers = this.buffer.subarray(0, headerEnd).toString('ascii');
+      const length = headers.match(/^Content-Length:\s*(\d+)\s*$/im);
+      if (!length) {
+        this.buffer = this.buffer.subarray(headerEnd + 4);
+        this.onError(new Error('Message without Content-Length header'));
+        continue;
+      }
+
+      const bodyStart = headerEnd + 4;
+      const bodyEnd = bodyStart + parseInt(length[1], 10);
+      if (this.buffer.length < bodyEnd) break;
+
+      const body = this.buffer.subarray(bodyStart, bodyEnd).toString('utf-8');
+      this.buffer = this.buffer.subarray(bodyEnd);
+      try {
+        messages.push(JSON.parse(body));
+      } catch (error: any) {
+        this.onError(new Error(`Message is not JSON: ${error.message}`));
+      }
+    }
+
+    return messages;
+  }
+}
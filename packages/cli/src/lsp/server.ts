@@ -0,0 +1,445 @@
+/**
+ * cv-git Language Server
+ *
+ * Brings cv-git into editors that speak LSP (VS Code, Neovim, Helix, ...):
+ * - workspace/symbol runs semantic search, so "Go to symbol in workspace"
+ *   finds code by meaning
+ * - an "Explain with cv" code action explains the selection (or the word at
+ *   the cursor) and opens the answer as a Markdown document
+ * - `cv review` findings for the changed files are published as diagnostics,
+ *   after each save and on the "Review changes with cv" action
+ *
+ * The server knows nothing about vectors or models; it calls an LspBackend,
+ * created once the client has told it the workspace root.
+ */
+
+import { promises as fs } from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { fileURLToPath, pathToFileURL } from 'url';
+import type { ReviewFinding, ReviewSeverity } from '@cv-git/core';
+import type { SymbolKind } from '@cv-git/shared';
+import {
+  Diagnostic,
+  DiagnosticSeverity,
+  ErrorCodes,
+  LspMessage,
+  LspSymbolKind,
+  Range,
+} from './protocol.js';
+
+export const EXPLAIN_COMMAND = 'cv.explain';
+export const REVIEW_COMMAND = 'cv.review';
+
+/** Most workspace symbols returned for one query */
+const SEARCH_LIMIT = 30;
+
+export interface LspSearchHit {
+  name: string;
+  kind?: SymbolKind;
+  /** Workspace-relative path */
+  file: string;
+  startLine: number;
+  endLine: number;
+}
+
+export interface LspBackend {
+  search(query: string, limit: number): Promise<LspSearchHit[]>;
+  explain(target: string): Promise<string>;
+  /** Review the uncommitted changes to `files` (all changed files when empty) */
+  review(files: string[]): Promise<ReviewFinding[]>;
+}
+
+export interface LspServerOptions {
+  createBackend: (rootPath: string) => Promise<LspBackend>;
+  /** Repository root for the folder the editor opened (e.g. a parent of it) */
+  resolveRoot?: (folder: string) => Promise<string | null>;
+  send: (message: LspMessage) => void;
+  /** Called on `exit`; the code is 0 after a `shutdown` request */
+  onExit?: (code: number) => void;
+  /** Where explanations are written before they are shown (default: the OS temp dir) */
+  explanationDir?: string;
+}
+
+interface ClientState {
+  rootPath: string;
+  canShowDocument: boolean;
+  reviewOnSave: boolean;
+}
+
+const SEVERITY: Record<ReviewSeverity, DiagnosticSeverity> = {
+  critical: DiagnosticSeverity.Error,
+  high: DiagnosticSeverity.Error,
+  medium: DiagnosticSeverity.Warning,
+  low: DiagnosticSeverity.Information,
+  info: DiagnosticSeverity.Hint,
+};
+
+const SYMBOL_KIND: Record<SymbolKind, LspSymbolKind> = {
+  function: LspSymbolKind.Function,
+  method: LspSymbolKind.Method,
+  class: LspSymbolKind.Class,
+  interface: LspSymbolKind.Interface,
+  type: LspSymbolKind.TypeParameter,
+  variable: LspSymbolKind.Variable,
+  constant: LspSymbolKind.Constant,
+  enum: LspSymbolKind.Enum,
+  struct: LspSymbolKind.Struct,
+};
+
+export function toLspSymbolKind(kind?: SymbolKind): LspSymbolKind {
+  return kind ? SYMBOL_KIND[kind] ?? LspSymbolKind.Function : LspSymbolKind.File;
+}
+
+/**
+ * Review findings as diagnostics, keyed by workspace-relative file.
+ * `lineLength` gives the length of a 0-based line where the document is
+ * known, so the range covers the whole line.
+ */
+export function findingsToDiagnostics(
+  findings: ReviewFinding[],
+  lineLength: (file: string, line: number) => number | undefined = () => undefined
+): Map<string, Diagnostic[]> {
+  const byFile = new Map<string, Diagnostic[]>();
+  for (const finding of findings) {
+    const start = Math.max(0, finding.line - 1);
+    const end = Math.max(start, (finding.endLine ?? finding.line) - 1);
+    const diagnostics = byFile.get(finding.file) ?? [];
+    diagnostics.push({
+      range: {
+        start: { line: start, character: 0 },
+        end: { line: end, character: lineLength(finding.file, end) ?? 1000 },
+      },
+      severity: SEVERITY[finding.severity] ?? DiagnosticSeverity.Warning,
+      code: finding.rule ?? finding.id,
+      source: 'cv',
+      message: [
+        finding.title,
+        finding.message && finding.message !== finding.title ? finding.message : '',
+        finding.suggestion ? `Suggestion: ${finding.suggestion}` : '',
+      ].filter(Boolean).join('\n'),
+    });
+    byFile.set(finding.file, diagnostics);
+  }
+  return byFile;
+}
+
+/**
+ * What to explain: the selected text, or the identifier under the cursor
+ * when nothing is selected
+ */
+export function explainTarget(text: string, range: Range, file: string): string | null {
+  const lines = text.split('\n');
+  const { start, end } = range;
+
+  if (start.line !== end.line || start.character !== end.character) {
+    const selected = start.line === end.line
+      ? (lines[start.line] ?? '').slice(start.character, end.character)
+      : [
+        (lines[start.line] ?? '').slice(start.character),
+        ...lines.slice(start.line + 1, end.line),
+        (lines[end.line] ?? '').slice(0, end.character),
+      ].join('\n');
+    if (selected.trim()) {
+      return start.line === end.line && /^[\w$.:]+$/.test(selected.trim())
+        ? `${selected.trim()} (in ${file})`
+        : `this code from ${file} (lines ${start.line + 1}-${end.line + 1}):\n\n${selected}`;
+    }
+  }
+
+  const line = lines[start.line] ?? '';
+  const isWord = (c: string | undefined) => c !== undefined && /[\w$]/.test(c);
+  let from = start.character;
+  let to = start.character;
+  while (isWord(line[from - 1])) from--;
+  while (isWord(line[to])) to++;
+  const word = line.slice(from, to);
+  return word ? `${word} (in ${file})` : null;
+}
+
+export class LspServer {
+  private client: ClientState | null = null;
+  private backend: Promise<LspBackend> | null = null;
+  private documents = new Map<string, string>();
+  private shutdownRequested = false;
+  private nextRequestId = 1;
+  private pending = new Map<number | string, (message: LspMessage) => void>();
+
+  /** Files with published diagnostics, so a clean review clears them */
+  private diagnosed = new Set<string>();
+  private reviewing: Promise<void> | null = null;
+  private queuedReview: Set<string> | null = null;
+
+  constructor(private options: LspServerOptions) {}
+
+  /**
+   * Handle one message from the client
+   */
+  async handle(message: LspMessage): Promise<void> {
+    // Response to a request we sent
+    if (message.method === undefined) {
+      if (message.id !== undefined && message.id !== null) {
+        this.pending.get(message.id)?.(message);
+        this.pending.delete(message.id);
+      }
+      return;
+    }
+
+    const isRequest = message.id !== undefined && message.id !== null;
+    try {
+      const result = await this.dispatch(message.method, message.params ?? {}, isRequest);
+      if (isRequest) this.options.send({ jsonrpc: '2.0', id: message.id, result: result ?? null });
+    } catch (error: any) {
+      if (isRequest) {
+        this.options.send({
+          jsonrpc: '2.0',
+          id: message.id,
+          error: { code: typeof error.code === 'number' ? error.code : ErrorCodes.InternalError, message: error.message },
+        });
+      } else {
+        this.log(`${message.method} failed: ${error.message}`);
+      }
+    }
+  }
+
+  private async dispatch(method: string, params: any, isRequest: boolean): Promise<unknown> {
+    if (method === 'initialize') return this.initialize(params);
+    if (method === 'exit') {
+      this.options.onExit?.(this.shutdownRequested ? 0 : 1);
+      return undefined;
+    }
+    if (!this.client) {
+      if (!isRequest) return undefined;
+      throw Object.assign(new Error('Server not initialized'), { code: ErrorCodes.ServerNotInitialized });
+    }
+
+    switch (method) {
+      case 'initialized':
+        return undefined;
+
+      case 'shutdown':
+        this.shutdownRequested = true;
+        return null;
+
+      case 'textDocument/didOpen':
+        this.documents.set(params.textDocument.uri, params.textDocument.text);
+        return undefined;
+
+      case 'textDocument/didChange': {
+        // Full sync: the last change holds the whole text
+        const changes = params.contentChanges ?? [];
+        if (changes.length > 0) this.documents.set(params.textDocument.uri, changes[changes.length - 1].text);
+        return undefined;
+      }
+
+      case 'textDocument/didClose':
+        this.documents.delete(params.textDocument.uri);
+        return undefined;
+
+      case 'textDocument/didSave':
+        if (this.client.reviewOnSave) {
+          const file = this.toFile(params.textDocument.uri);
+          if (file) void this.scheduleReview([file]);
+        }
+        return undefined;
+
+      case 'workspace/symbol':
+        return this.workspaceSymbols(String(params.query ?? ''));
+
+      case 'textDocument/codeAction':
+        return this.codeActions(params.textDocument.uri, params.range);
+
+      case 'workspace/executeCommand':
+        return this.executeCommand(params.command, params.arguments ?? []);
+
+      default:
+        if (!isRequest || method.startsWith('$/')) return undefined;
+        throw Object.assign(new Error(`Unhandled method ${method}`), { code: ErrorCodes.MethodNotFound });
+    }
+  }
+
+  private async initialize(params: any) {
+    const folder = params.rootUri ? fileURLToPath(params.rootUri) : params.rootPath ?? process.cwd();
+    this.client = {
+      rootPath: (await this.options.resolveRoot?.(folder)) ?? folder,
+      canShowDocument: Boolean(params.capabilities?.window?.showDocument?.support),
+      reviewOnSave: params.initializationOptions?.reviewOnSave !== false,
+    };
+
+    return {
+      capabilities: {
+        // 1 = full document sync
+        textDocumentSync: { openClose: true, change: 1, save: true },
+        workspaceSymbolProvider: true,
+        codeActionProvider: { codeActionKinds: ['refactor'] },
+        executeCommandProvider: { commands: [EXPLAIN_COMMAND, REVIEW_COMMAND] },
+      },
+      serverInfo: { name: 'cv-git' },
+    };
+  }
+
+  private getBackend(): Promise<LspBackend> {
+    if (!this.backend) {
+      this.backend = this.options.createBackend(this.client!.rootPath);
+      // Let a later call retry, e.g. after the user has run `cv init`
+      this.backend.catch(() => { this.backend = null; });
+    }
+    return this.backend;
+  }
+
+  private async workspaceSymbols(query: string) {
+    // Editors send an empty query when the picker opens
+    if (!query.trim()) return [];
+    const hits = await (await this.getBackend()).search(query, SEARCH_LIMIT);
+    return hits.map(hit => ({
+      name: hit.name,
+      kind: toLspSymbolKind(hit.kind),
+      location: {
+        uri: this.toUri(hit.file),
+        range: {
+          start: { line: Math.max(0, hit.startLine - 1), character: 0 },
+          end: { line: Math.max(0, hit.endLine - 1), character: 0 },
+        },
+      },
+      containerName: hit.file,
+    }));
+  }
+
+  private codeActions(uri: string, range: Range) {
+    if (!this.toFile(uri)) return [];
+    return [
+      {
+        title: 'Explain with cv',
+        kind: 'refactor',
+        command: { title: 'Explain with cv', command: EXPLAIN_COMMAND, arguments: [uri, range] },
+      },
+      {
+        title: 'Review changes with cv',
+        kind: 'refactor',
+        command: { title: 'Review changes with cv', command: REVIEW_COMMAND, arguments: [uri] },
+      },
+    ];
+  }
+
+  private async executeCommand(command: string, args: any[]) {
+    if (command === REVIEW_COMMAND) {
+      const file = typeof args[0] === 'string' ? this.toFile(args[0]) : null;
+      await this.scheduleReview(file ? [file] : []);
+      return null;
+    }
+
+    if (command === EXPLAIN_COMMAND) {
+      const [uri, range] = args as [string, Range];
+      const file = this.toFile(uri);
+      const text = this.documents.get(uri) ?? (file ? await fs.readFile(path.join(this.client!.rootPath, file), 'utf-8') : '');
+      const target = file ? explainTarget(text, range, file) : null;
+      if (!target) {
+        this.showMessage(2, 'cv: select code or place the cursor on a name to explain it');
+        return null;
+      }
+
+      this.showMessage(3, 'cv: explaining…');
+      const explanation = await (await this.getBackend()).explain(target);
+      await this.showExplanation(target, explanation);
+      return null;
+    }
+
+    throw Object.assign(new Error(`Unknown command ${command}`), { code: ErrorCodes.InvalidRequest });
+  }
+
+  /**
+   * Open the explanation as a Markdown document, or show it as a message
+   * when the client cannot open documents
+   */
+  private async showExplanation(target: string, explanation: string): Promise<void> {
+    const title = target.split('\n')[0].replace(/:$/, '');
+    if (!this.client!.canShowDocument) {
+      this.showMessage(3, `${title}\n\n${explanation}`);
+      return;
+    }
+
+    const dir = this.options.explanationDir ?? path.join(os.tmpdir(), 'cv-lsp');
+    await fs.mkdir(dir, { recursive: true });
+    const name = title.replace(/[^\w.-]+/g, '-').replace(/^-+|-+$/g, '').slice(0, 80) || 'explanation';
+    const file = path.join(dir, `${name}.md`);
+    await fs.writeFile(file, `# ${title}\n\n${explanation}\n`);
+    await this.request('window/showDocument', { uri: pathToFileURL(file).href, takeFocus: true });
+  }
+
+  /**
+   * Run one review at a time; saves during a review are reviewed together
+   * once it finishes
+   */
+  private scheduleReview(files: string[]): Promise<void> {
+    if (this.reviewing) {
+      if (!this.queuedReview) this.queuedReview = new Set();
+      files.forEach(file => this.queuedReview!.add(file));
+      return this.reviewing;
+    }
+
+    this.reviewing = this.runReview(files).finally(() => {
+      this.reviewing = null;
+      const queued = this.queuedReview;
+      this.queuedReview = null;
+      if (queued) void this.scheduleReview([...queued]);
+    });
+    return this.reviewing;
+  }
+
+  private async runReview(files: string[]): Promise<void> {
+    let findings: ReviewFinding[];
+    try {
+      findings = await (await this.getBackend()).review(files);
+    } catch (error: any) {
+      this.log(`Review failed: ${error.message}`);
+      return;
+    }
+
+    const diagnostics = findingsToDiagnostics(findings, (file, line) =>
+      this.documents.get(this.toUri(file))?.split('\n')[line]?.length
+    );
+    // Clear files that were reviewed (or diagnosed before) and are now clean
+    const cleared = files.length > 0 ? files : [...this.diagnosed];
+    for (const file of [...cleared, ...diagnostics.keys()]) {
+      const list = diagnostics.get(file) ?? [];
+      this.options.send({
+        jsonrpc: '2.0',
+        method: 'textDocument/publishDiagnostics',
+        params: { uri: this.toUri(file), diagnostics: list },
+      });
+      if (list.length > 0) this.diagnosed.add(file);
+      else this.diagnosed.delete(file);
+    }
+  }
+
+  private request(method: string, params: unknown): Promise<LspMessage> {
+    const id = this.nextRequestId++;
+    return new Promise(resolve => {
+      this.pending.set(id, resolve);
+      this.options.send({ jsonrpc: '2.0', id, method, params });
+    });
+  }
+
+  /** 1 = error, 2 = warning, 3 = info */
+  private showMessage(type: number, message: string): void {
+    this.options.send({ jsonrpc: '2.0', method: 'window/showMessage', params: { type, message } });
+  }
+
+  private log(message: string): void {
+    this.options.send({ jsonrpc: '2.0', method: 'window/logMessage', params: { type: 1, message } });
+  }
+
+  private toUri(file: string): string {
+    return pathToFileURL(path.join(this.client!.rootPath, file)).href;
+  }
+
+  /**
+   * Workspace-relative path of a file URI, or null outside the workspace
+   */
+  private toFile(uri: string): string | null {
+    if (!uri.startsWith('file:')) return null;
+    const relative = path.relative(this.client!.rootPath, fileURLToPath(uri));
+    if (!relative || relative.startsWith('..') || path.isAbsolute(relative)) return null;
+    return relative.split(path.sep).join('/');
+  }
+}
@@ -0,0 +1,8 @@
+/**
+ * Entry point of the static binaries: installs the bundled local embedding
+ * runtime, then runs the CLI. Built by `pnpm bundle:static`.
+ */
+
+// Evaluated in order, so the runtime is in place before any command runs
+import './runtime.js';
+import '../index.js';
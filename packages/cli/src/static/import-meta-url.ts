@@ -0,0 +1,8 @@
+/**
+ * Stands in for import.meta.url in the static bundle, where esbuild would
+ * leave it empty; @huggingface/transformers resolves its paths from it.
+ */
+
+import { pathToFileURL } from 'url';
+
+export const importMetaUrl = pathToFileURL(__filename).href;
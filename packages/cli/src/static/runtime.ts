@@ -0,0 +1,19 @@
+/**
+ * Local embedding runtime for the static binaries.
+ *
+ * A static Node cannot load native add-ons, so the bundle replaces
+ * onnxruntime-node with onnxruntime-web and runs models on its WASM
+ * backend, with the WASM binary inlined into the executable.
+ */
+
+import * as transformers from '@huggingface/transformers';
+import { setLocalEmbeddingRuntime } from '@cv-git/core';
+import ortWasm from './ort-wasm.wasm';
+
+const wasm = (transformers.env.backends.onnx as any).wasm;
+wasm.wasmBinary = ortWasm;
+// Worker threads would load the WASM from disk, which the executable does not ship
+wasm.numThreads = 1;
+wasm.proxy = false;
+
+setLocalEmbeddingRuntime(transformers);
@@ -0,0 +1,7 @@
+/**
+ * Stands in for sharp (native) in the static binaries, which embed text only
+ */
+
+export default function sharp(): never {
+  throw new Error('Image decoding is not available in the static cv binary');
+}
@@ -0,0 +1,5 @@
+/** WebAssembly binaries, inlined by esbuild's binary loader */
+declare module '*.wasm' {
+  const bytes: Uint8Array;
+  export default bytes;
+}
@@ -0,0 +1,345 @@
+/**
+ * cv tui application
+ *
+ * One screen with four panes, cycled with Tab:
+ * - Search: a query box; Enter runs a ranked hybrid search (as `cv grep`)
+ * - Results: ranked chunks with a preview of the highlighted one; Space puts
+ *   its file in the chat scope
+ * - Findings: `cv review` findings from the findings store; `a` accepts,
+ *   `d` dismisses and `o` reopens, written back to the store immediately
+ * - Chat: questions answered with the selected files as context
+ *
+ * Like the language server, the app knows nothing about terminals, vectors or
+ * models: keys come in through key(), the screen is drawn by render(), and
+ * the work is done by a TuiBackend.
+ */
+
+import type { GrepMatch, ReviewFindingStatus, StoredFinding } from '@cv-git/core';
+import { renderTui } from './render.js';
+
+export type TuiPane = 'search' | 'results' | 'findings' | 'chat';
+
+/** Tab order */
+export const TUI_PANES: TuiPane[] = ['search', 'results', 'findings', 'chat'];
+
+export interface ChatTurn {
+  role: 'user' | 'assistant';
+  content: string;
+}
+
+export interface TuiBackend {
+  search(query: string): Promise<GrepMatch[]>;
+  /** Answer the last user turn, with the files (workspace-relative) as context */
+  chat(turns: ChatTurn[], files: string[], onToken: (token: string) => void): Promise<string>;
+  findings(): Promise<StoredFinding[]>;
+  /** Update the store and return its findings */
+  setFindingStatus(id: string, status: ReviewFindingStatus): Promise<StoredFinding[]>;
+  /** 1-based, inclusive; fewer lines if the file is shorter */
+  readLines(file: string, from: number, to: number): Promise<string[]>;
+}
+
+/** What readline's keypress event reports */
+export interface TuiKey {
+  name?: string;
+  ctrl?: boolean;
+  meta?: boolean;
+  shift?: boolean;
+  sequence?: string;
+}
+
+export interface TuiState {
+  focus: TuiPane;
+  /** Which list the middle pane shows; follows focus between results and findings */
+  list: 'results' | 'findings';
+  query: string;
+  searching: boolean;
+  results: GrepMatch[];
+  resultCursor: number;
+  /** Files in the chat scope */
+  selected: string[];
+  findings: StoredFinding[];
+  findingCursor: number;
+  showDismissed: boolean;
+  /** Code around the highlighted finding, by finding ID */
+  findingCode: Map<string, Array<{ line: number; text: string }>>;
+  chat: ChatTurn[];
+  chatInput: string;
+  chatting: boolean;
+  /** One-line message in the status bar */
+  status: string;
+}
+
+export interface TuiAppOptions {
+  backend: TuiBackend;
+  /** The state changed; redraw */
+  onChange: () => void;
+  /** Ctrl-C, or q in a list */
+  onQuit: () => void;
+}
+
+/** Lines of code shown on each side of a finding */
+const FINDING_CONTEXT = 6;
+
+export class TuiApp {
+  readonly state: TuiState = {
+    focus: 'search',
+    list: 'results',
+    query: '',
+    searching: false,
+    results: [],
+    resultCursor: 0,
+    selected: [],
+    findings: [],
+    findingCursor: 0,
+    showDismissed: false,
+    findingCode: new Map(),
+    chat: [],
+    chatInput: '',
+    chatting: false,
+    status: 'Type a query and press Enter · Tab switches panes · Ctrl-C quits',
+  };
+
+  constructor(private options: TuiAppOptions) {}
+
+  /**
+   * Load the findings store
+   */
+  async start(): Promise<void> {
+    try {
+      this.state.findings = await this.options.backend.findings();
+    } catch (error: any) {
+      this.state.status = `Could not load review findings: ${error.message}`;
+    }
+    this.changed();
+  }
+
+  render(width: number, height: number): string[] {
+    return renderTui({ state: this.state, findings: this.visibleFindings(), scope: this.chatScope() }, width, height);
+  }
+
+  /** Findings in the list: all with `h`, otherwise open and accepted ones */
+  visibleFindings(): StoredFinding[] {
+    return this.state.showDismissed
+      ? this.state.findings
+      : this.state.findings.filter(finding => finding.status !== 'dismissed');
+  }
+
+  async key(input: string | undefined, key: TuiKey = {}): Promise<void> {
+    const state = this.state;
+    if (key.ctrl && key.name === 'c') {
+      this.options.onQuit();
+      return;
+    }
+    if (key.name === 'tab') {
+      const step = key.shift ? TUI_PANES.length - 1 : 1;
+      this.focus(TUI_PANES[(TUI_PANES.indexOf(state.focus) + step) % TUI_PANES.length]);
+      return;
+    }
+
+    switch (state.focus) {
+      case 'search':
+        if (key.name === 'return') {
+          await this.search();
+        } else {
+          state.query = editLine(state.query, input, key);
+          this.changed();
+        }
+        return;
+      case 'chat':
+        if (key.name === 'return') {
+          await this.ask();
+        } else {
+          state.chatInput = editLine(state.chatInput, input, key);
+          this.changed();
+        }
+        return;
+      case 'results':
+        return this.resultsKey(input, key);
+      case 'findings':
+        return this.findingsKey(input, key);
+    }
+  }
+
+  private focus(pane: TuiPane): void {
+    this.state.focus = pane;
+    if (pane === 'results' || pane === 'findings') this.state.list = pane;
+    if (pane === 'findings') void this.loadFindingCode();
+    this.changed();
+  }
+
+  private resultsKey(input: string | undefined, key: TuiKey): void {
+    const state = this.state;
+    const moved = moveCursor(state.resultCursor, state.results.length, input, key);
+    if (moved !== undefined) {
+      state.resultCursor = moved;
+    } else if (input === ' ' || key.name === 'return') {
+      const match = state.results[state.resultCursor];
+      if (match) this.toggleSelected(match.file);
+      if (key.name === 'return' && match) this.focus('chat');
+    } else if (input === 'q') {
+      this.options.onQuit();
+      return;
+    } else if (input === '/') {
+      this.focus('search');
+      return;
+    }
+    this.changed();
+  }
+
+  private async findingsKey(input: string | undefined, key: TuiKey): Promise<void> {
+    const state = this.state;
+    const visible = this.visibleFindings();
+    const moved = moveCursor(state.findingCursor, visible.length, input, key);
+    const finding = visible[state.findingCursor];
+
+    if (moved !== undefined) {
+      state.findingCursor = moved;
+      void this.loadFindingCode();
+    } else if (input === 'a' || input === 'd' || input === 'o') {
+      if (!finding) return;
+      const status: ReviewFindingStatus = input === 'a' ? 'accepted' : input === 'd' ? 'dismissed' : 'open';
+      try {
+        state.findings = await this.options.backend.setFindingStatus(finding.id, status);
+        state.status = `${status === 'open' ? 'Reopened' : status[0].toUpperCase() + status.slice(1)}: ${finding.title}`;
+      } catch (error: any) {
+        state.status = `Could not update the findings store: ${error.message}`;
+      }
+      state.findingCursor = Math.min(state.findingCursor, Math.max(0, this.visibleFindings().length - 1));
+      void this.loadFindingCode();
+    } else if (input === 'h') {
+      const id = finding?.id;
+      state.showDismissed = !state.showDismissed;
+      state.findingCursor = Math.max(0, this.visibleFindings().findIndex(f => f.id === id));
+      state.status = state.showDismissed ? 'Showing dismissed findings' : 'Hiding dismissed findings';
+    } else if (input === ' ') {
+      if (finding) this.toggleSelected(finding.file);
+    } else if (input === 'q') {
+      this.options.onQuit();
+      return;
+    } else if (input === '/') {
+      this.focus('search');
+      return;
+    }
+    this.changed();
+  }
+
+  private toggleSelected(file: string): void {
+    const state = this.state;
+    state.selected = state.selected.includes(file)
+      ? state.selected.filter(f => f !== file)
+      : [...state.selected, file];
+    state.status = state.selected.length > 0
+      ? `Chat scope: ${state.selected.join(', ')}`
+      : 'Chat scope: none (the highlighted result is used)';
+  }
+
+  private async search(): Promise<void> {
+    const state = this.state;
+    const query = state.query.trim();
+    if (!query || state.searching) return;
+
+    state.searching = true;
+    state.status = `Searching for "${query}"...`;
+    this.changed();
+    try {
+      state.results = await this.options.backend.search(query);
+      state.resultCursor = 0;
+      state.status = `${state.results.length} result${state.results.length === 1 ? '' : 's'} · ↑↓ to browse, Space adds a file to the chat scope`;
+      if (state.results.length > 0) {
+        state.focus = 'results';
+        state.list = 'results';
+      }
+    } catch (error: any) {
+      state.status = `Search failed: ${error.message}`;
+    } finally {
+      state.searching = false;
+      this.changed();
+    }
+  }
+
+  /** Files the next chat message is scoped to */
+  chatScope(): string[] {
+    const state = this.state;
+    if (state.selected.length > 0) return state.selected;
+    const highlighted = state.results[state.resultCursor];
+    return highlighted ? [highlighted.file] : [];
+  }
+
+  private async ask(): Promise<void> {
+    const state = this.state;
+    const question = state.chatInput.trim();
+    if (!question || state.chatting) return;
+
+    state.chat.push({ role: 'user', content: question });
+    const reply: ChatTurn = { role: 'assistant', content: '' };
+    const turns = [...state.chat];
+    state.chat.push(reply);
+    state.chatInput = '';
+    state.chatting = true;
+    state.status = 'Thinking...';
+    this.changed();
+
+    try {
+      reply.content = await this.options.backend.chat(turns, this.chatScope(), token => {
+        reply.content += token;
+        this.changed();
+      });
+      state.status = 'Ready';
+    } catch (error: any) {
+      state.chat.pop();
+      state.status = `Chat failed: ${error.message}`;
+    } finally {
+      state.chatting = false;
+      this.changed();
+    }
+  }
+
+  private async loadFindingCode(): Promise<void> {
+    const finding = this.visibleFindings()[this.state.findingCursor];
+    if (!finding || this.state.findingCode.has(finding.id)) return;
+
+    const from = Math.max(1, finding.line - FINDING_CONTEXT);
+    const to = (finding.endLine ?? finding.line) + FINDING_CONTEXT;
+    try {
+      const lines = await this.options.backend.readLines(finding.file, from, to);
+      this.state.findingCode.set(finding.id, lines.map((text, i) => ({ line: from + i, text })));
+    } catch {
+      // Deleted or moved since the review
+      this.state.findingCode.set(finding.id, []);
+    }
+    this.changed();
+  }
+
+  private changed(): void {
+    this.options.onChange();
+  }
+}
+
+/**
+ * New cursor for arrow/j/k/page/home/end keys, or undefined for other keys
+ */
+function moveCursor(cursor: number, count: number, input: string | undefined, key: TuiKey): number | undefined {
+  let next: number;
+  if (key.name === 'up' || input === 'k') next = cursor - 1;
+  else if (key.name === 'down' || input === 'j') next = cursor + 1;
+  else if (key.name === 'pageup') next = cursor - 10;
+  else if (key.name === 'pagedown') next = cursor + 10;
+  else if (key.name === 'home' || input === 'g') next = 0;
+  else if (key.name === 'end' || input === 'G') next = count - 1;
+  else return undefined;
+  return Math.max(0, Math.min(count - 1, next));
+}
+
+/**
+ * Apply a key to a single-line text input
+ */
+function editLine(text: string, input: string | undefined, key: TuiKey): string {
+  if (key.name === 'backspace') return text.slice(0, -1);
+  if (key.name === 'escape') return '';
+  if (key.ctrl && key.name === 'u') return '';
+  if (key.ctrl && key.name === 'w') return text.replace(/\S+\s*$/, '');
+  if (key.ctrl || key.meta || !input) return text;
+  // Printable characters only; pasted newlines become spaces
+  const printable = input.replace(/[\r\n\t]/g, ' ').replace(/[\u0000-\u001f\u007f]/g, '');
+  return text + printable;
+}
@@ -0,0 +1,263 @@
+/**
+ * cv tui rendering
+ *
+ * Draws the app state as exactly `height` lines of exactly `width` columns,
+ * so the command can repaint the whole screen each time:
+ *
+ *   Search ──────────────────────────────────────────
+ *   > retry with backoff
+ *   Results (12) · Findings (3) │ Preview src/pool.rs:10-18
+ *   ● src/pool.rs:10 drain       │ 10 pub fn drain(...)
+ *     src/net/client.rs:4 retry  │ 11     ...
+ *   Chat · src/pool.rs ──────────────────────────────
+ *   You: why does drain sleep?
+ *   AI: ...
+ *   > _
+ *   status                                  key hints
+ */
+
+import chalk from 'chalk';
+import type { ReviewSeverity, StoredFinding } from '@cv-git/core';
+import type { TuiPane, TuiState } from './app.js';
+
+export interface TuiView {
+  state: TuiState;
+  /** Findings shown in the list */
+  findings: StoredFinding[];
+  /** Files the next chat message is scoped to */
+  scope: string[];
+}
+
+export const MIN_WIDTH = 50;
+export const MIN_HEIGHT = 14;
+
+const SEVERITY_STYLE: Record<ReviewSeverity, (text: string) => string> = {
+  critical: chalk.red.bold,
+  high: chalk.red,
+  medium: chalk.yellow,
+  low: chalk.cyan,
+  info: chalk.gray
+};
+
+const STATUS_MARK: Record<StoredFinding['status'], string> = {
+  open: '○',
+  accepted: '✓',
+  dismissed: '✗'
+};
+
+const KEY_HINTS: Record<TuiPane, string> = {
+  search: 'Enter search · Tab next pane · Ctrl-C quit',
+  results: '↑↓ move · Space scope · Enter chat · / search · q quit',
+  findings: 'a accept · d dismiss · o reopen · h dismissed · Space scope',
+  chat: 'Enter send · Esc clear · Tab next pane'
+};
+
+/**
+ * Truncate (with …) or pad plain text to exactly `width` columns
+ */
+export function fit(text: string, width: number): string {
+  if (width <= 0) return '';
+  const chars = Array.from(text.replace(/\t/g, '  ').replace(/[\u0000-\u001f\u007f]/g, ''));
+  if (chars.length > width) return chars.slice(0, width - 1).join('') + '…';
+  return chars.join('') + ' '.repeat(width - chars.length);
+}
+
+/**
+ * Word-wrap plain text to `width` columns, splitting words longer than a line
+ */
+export function wrap(text: string, width: number): string[] {
+  const lines: string[] = [];
+  for (const paragraph of text.split('\n')) {
+    let line = '';
+    for (const word of paragraph.split(/\s+/).filter(Boolean)) {
+      let rest = word;
+      while (rest.length > width) {
+        if (line) {
+          lines.push(line);
+          line = '';
+        }
+        lines.push(rest.slice(0, width));
+        rest = rest.slice(width);
+      }
+      if (!rest) continue;
+      if (!line) line = rest;
+      else if (line.length + 1 + rest.length <= width) line += ` ${rest}`;
+      else {
+        lines.push(line);
+        line = rest;
+      }
+    }
+    lines.push(line);
+  }
+  return lines;
+}
+
+/**
+ * First visible row of a list so the cursor stays in view, centred when it can
+ */
+export function scrollStart(cursor: number, count: number, rows: number): number {
+  if (count <= rows) return 0;
+  return Math.max(0, Math.min(count - rows, cursor - Math.floor(rows / 2)));
+}
+
+function header(label: string, width: number, focused: boolean): string {
+  const text = fit(` ${label} `, width);
+  return focused ? chalk.bold.black.bgCyan(text) : chalk.bold.bgBlackBright.white(text);
+}
+
+export function renderTui(view: TuiView, width: number, height: number): string[] {
+  const { state } = view;
+  if (width < MIN_WIDTH || height < MIN_HEIGHT) {
+    const message = fit(`Terminal too small for cv tui (${MIN_WIDTH}x${MIN_HEIGHT} minimum)`, width);
+    return [message, ...Array.from({ length: Math.max(0, height - 1) }, () => ' '.repeat(width))];
+  }
+
+  // Fixed rows: search header and input, list header, chat header and input, status
+  const chatRows = Math.max(3, Math.floor((height - 6) * 0.3));
+  const listRows = height - 6 - chatRows;
+  const leftWidth = Math.max(24, Math.floor(width * 0.42));
+  const rightWidth = width - leftWidth - 1;
+  const lines: string[] = [];
+
+  // Search
+  lines.push(header(state.searching ? 'Search (searching...)' : 'Search', width, state.focus === 'search'));
+  lines.push(input(state.query, width, state.focus === 'search', 'Search the codebase: a question, identifiers or both'));
+
+  // Results or findings, with a preview of the highlighted one
+  const showingFindings = state.list === 'findings';
+  const open = state.findings.filter(finding => finding.status === 'open').length;
+  const tabs = showingFindings
+    ? `Findings (${open} open) · results ${state.results.length}`
+    : `Results (${state.results.length}) · findings ${open} open`;
+  const listFocused = state.focus === 'results' || state.focus === 'findings';
+  const left = showingFindings ? findingRows(view, leftWidth, listRows) : resultRows(view, leftWidth, listRows);
+  const preview = showingFindings ? findingPreview(view, rightWidth, listRows) : resultPreview(view, rightWidth, listRows);
+  lines.push(`${header(tabs, leftWidth, listFocused)}${chalk.bgBlackBright(' ')}${header(preview.title, rightWidth, false)}`);
+  for (let i = 0; i < listRows; i++) {
+    lines.push(`${left[i] ?? ' '.repeat(leftWidth)}${chalk.gray('│')}${preview.rows[i] ?? ' '.repeat(rightWidth)}`);
+  }
+
+  // Chat
+  const scope = view.scope.length > 0 ? view.scope.join(', ') : 'no files selected';
+  lines.push(header(`Chat · ${scope}${state.chatting ? ' (answering...)' : ''}`, width, state.focus === 'chat'));
+  lines.push(...chatRowsFor(state, width, chatRows));
+  lines.push(input(state.chatInput, width, state.focus === 'chat', 'Ask about the files in scope (Space adds the highlighted file)'));
+
+  // Status bar
+  const hints = KEY_HINTS[state.focus];
+  const statusWidth = Math.max(0, width - hints.length - 1);
+  lines.push(chalk.inverse(`${fit(state.status, statusWidth)} ${fit(hints, width - statusWidth - 1)}`));
+  return lines;
+}
+
+function input(text: string, width: number, focused: boolean, placeholder: string): string {
+  if (!text && !focused) return chalk.gray(fit(`  ${placeholder}`, width));
+  // Keep the end of long input in view, where the cursor is
+  const room = width - 3;
+  const shown = text.length > room ? text.slice(text.length - room) : text;
+  return `${chalk.cyan('>')} ${fit(`${shown}${focused ? '█' : ''}`, width - 2)}`;
+}
+
+function resultRows(view: TuiView, width: number, rows: number): string[] {
+  const { state } = view;
+  if (state.results.length === 0) {
+    return [chalk.gray(fit(state.query ? ' No results' : ' Results appear here', width))];
+  }
+  const start = scrollStart(state.resultCursor, state.results.length, rows);
+  return state.results.slice(start, start + rows).map((match, i) => {
+    const mark = state.selected.includes(match.file) ? '●' : ' ';
+    const text = fit(`${mark} ${match.file}:${match.startLine} ${match.symbolName ?? ''}`, width);
+    if (start + i !== state.resultCursor) return state.selected.includes(match.file) ? chalk.green(text) : text;
+    return state.focus === 'results' ? chalk.inverse(text) : chalk.bold(text);
+  });
+}
+
+function findingRows(view: TuiView, width: number, rows: number): string[] {
+  const { state, findings } = view;
+  if (findings.length === 0) {
+    return [chalk.gray(fit(' No review findings (run cv review)', width))];
+  }
+  const start = scrollStart(state.findingCursor, findings.length, rows);
+  return findings.slice(start, start + rows).map((finding, i) => {
+    const text = fit(`${STATUS_MARK[finding.status]} ${finding.severity.padEnd(8)} ${finding.file}:${finding.line} ${finding.title}`, width);
+    if (start + i === state.findingCursor) {
+      return state.focus === 'findings' ? chalk.inverse(text) : chalk.bold(text);
+    }
+    return finding.status === 'dismissed' ? chalk.gray.strikethrough(text) : SEVERITY_STYLE[finding.severity](text);
+  });
+}
+
+interface Preview {
+  title: string;
+  rows: string[];
+}
+
+function codeRows(
+  code: Array<{ line: number; text: string; match?: boolean }>,
+  width: number,
+  rows: number,
+  style: (text: string) => string
+): string[] {
+  if (code.length === 0) return [];
+  const numberWidth = String(code[code.length - 1].line).length;
+  // Start just above the first marked line when the code doesn't fit
+  const first = Math.max(0, code.findIndex(line => line.match));
+  const start = code.length > rows ? Math.max(0, Math.min(code.length - rows, first - 2)) : 0;
+  return code.slice(start, start + rows).map(line => {
+    const number = String(line.line).padStart(numberWidth);
+    const text = fit(` ${line.text}`, width - numberWidth);
+    return line.match ? `${style(number)}${text}` : `${chalk.gray(number)}${chalk.dim(text)}`;
+  });
+}
+
+function resultPreview(view: TuiView, width: number, rows: number): Preview {
+  const match = view.state.results[view.state.resultCursor];
+  if (!match) return { title: 'Preview', rows: [] };
+  const code = match.hunks.flat();
+  return {
+    title: `Preview ${match.file}:${match.startLine}-${match.endLine}`,
+    rows: codeRows(code, width, rows, chalk.green.bold),
+  };
+}
+
+function findingPreview(view: TuiView, width: number, rows: number): Preview {
+  const finding = view.findings[view.state.findingCursor];
+  if (!finding) return { title: 'Finding', rows: [] };
+
+  const style = SEVERITY_STYLE[finding.severity];
+  const detail: string[] = [
+    chalk.bold(fit(finding.title, width)),
+    chalk.gray(fit(`${finding.severity} · ${finding.status} · ${finding.rule ?? 'review'} · seen ${finding.lastSeen.slice(0, 10)} on ${finding.ref}`, width)),
+  ];
+  if (finding.message && finding.message !== finding.title) {
+    detail.push(...wrap(finding.message, width).map(line => fit(line, width)));
+  }
+  if (finding.suggestion) {
+    detail.push(...wrap(`↳ ${finding.suggestion}`, width).map(line => chalk.green(fit(line, width))));
+  }
+  detail.push(' '.repeat(width));
+
+  const last = finding.endLine ?? finding.line;
+  const code = (view.state.findingCode.get(finding.id) ?? [])
+    .map(line => ({ ...line, match: line.line >= finding.line && line.line <= last }));
+  const codeRoom = Math.max(0, rows - detail.length);
+  return {
+    title: `Finding ${finding.file}:${finding.line}${finding.endLine ? `-${finding.endLine}` : ''}`,
+    rows: [...detail.slice(0, rows), ...codeRows(code, width, codeRoom, style)],
+  };
+}
+
+function chatRowsFor(state: TuiState, width: number, rows: number): string[] {
+  if (state.chat.length === 0) {
+    return [chalk.gray(fit(' Answers use the selected files, or the highlighted result, as context', width))];
+  }
+  const lines: string[] = [];
+  for (const turn of state.chat) {
+    const prefix = turn.role === 'user' ? 'You: ' : 'AI: ';
+    const style = turn.role === 'user' ? chalk.cyan : (text: string) => text;
+    const content = turn.content || (state.chatting ? '…' : '');
+    lines.push(...wrap(`${prefix}${content}`, width).map(line => style(fit(line, width))));
+  }
+  // Newest at the bottom
+  return lines.slice(-rows);
+}
@@ -0,0 +1,87 @@
+/**
+ * cv onboard walkthrough
+ *
+ * The repository tour one section at a time: → / Enter / Space for the next
+ * section, ← / Backspace for the previous one, ↑↓ to scroll a long section,
+ * q to quit. Like TuiApp, it knows nothing about terminals: keys come in
+ * through key() and render() returns the screen's lines.
+ */
+
+import chalk from 'chalk';
+import type { TourSection } from '@cv-git/core';
+import type { TuiKey } from './app.js';
+import { fit, wrap } from './render.js';
+
+export interface WalkthroughOptions {
+  sections: TourSection[];
+  onChange: () => void;
+  onQuit: () => void;
+}
+
+export class Walkthrough {
+  section = 0;
+  scroll = 0;
+
+  constructor(private options: WalkthroughOptions) {}
+
+  key(input: string | undefined, key: TuiKey = {}): void {
+    const last = this.options.sections.length - 1;
+    if ((key.ctrl && key.name === 'c') || input === 'q' || key.name === 'escape') {
+      this.options.onQuit();
+      return;
+    }
+    if (key.name === 'right' || key.name === 'return' || input === ' ' || input === 'n') {
+      if (this.section === last) {
+        if (key.name === 'return') this.options.onQuit();
+        return;
+      }
+      this.go(this.section + 1);
+    } else if (key.name === 'left' || key.name === 'backspace' || input === 'p') {
+      this.go(Math.max(0, this.section - 1));
+    } else if (key.name === 'down' || input === 'j') {
+      this.scroll++;
+    } else if (key.name === 'up' || input === 'k') {
+      this.scroll = Math.max(0, this.scroll - 1);
+    } else if (input && /^[1-9]$/.test(input) && Number(input) - 1 <= last) {
+      this.go(Number(input) - 1);
+    } else {
+      return;
+    }
+    this.options.onChange();
+  }
+
+  private go(section: number): void {
+    this.section = section;
+    this.scroll = 0;
+  }
+
+  render(width: number, height: number): string[] {
+    const { sections } = this.options;
+    const current = sections[this.section];
+    const body: string[] = [];
+    for (const line of current.lines) {
+      const heading = line.match(/^#+\s+(.*)$/);
+      const text = heading ? heading[1] : line;
+      const indent = text.match(/^(\s*(?:[-*]|\d+\.)\s+)/)?.[1].length ?? 0;
+      wrap(text.replace(/`([^`]*)`/g, '$1'), Math.max(10, width - 4 - indent)).forEach((row, i) => {
+        const padded = fit(`  ${i > 0 ? ' '.repeat(indent) : ''}${row}`, width);
+        body.push(heading ? chalk.bold.cyan(padded) : text.startsWith('_') ? chalk.gray(padded) : padded);
+      });
+    }
+
+    const rows = height - 3;
+    this.scroll = Math.max(0, Math.min(this.scroll, body.length - rows));
+    const shown = body.slice(this.scroll, this.scroll + rows);
+    const more = body.length > this.scroll + rows ? ' ↓ more' : '';
+
+    const lines = [chalk.bold.black.bgCyan(fit(` ${current.title}  (${this.section + 1}/${sections.length})${more}`, width)), ' '.repeat(width)];
+    for (let i = 0; i < rows; i++) lines.push(shown[i] ?? ' '.repeat(width));
+
+    const next = sections[this.section + 1];
+    const hints = next ? `→ ${next.title} · ← back · ↑↓ scroll · q quit` : 'Enter finish · ← back · q quit';
+    const steps = sections.map((section, i) => (i === this.section ? `[${i + 1}]` : `${i + 1}`)).join(' ');
+    const stepsWidth = Math.max(0, width - hints.length - 1);
+    lines.push(chalk.inverse(`${fit(` ${steps}`, stepsWidth)} ${fit(hints, width - stepsWidth - 1)}`));
+    return lines;
+  }
+}
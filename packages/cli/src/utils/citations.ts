@@ -0,0 +1,180 @@
+/**
+ * Source citation links
+ * Renders `file:line` references in the format configured by `citations.format`
+ * so they are clickable in the user's editor or terminal.
+ *
+ * Formats:
+ *   plain   - src/app.ts:12 (most terminals and editors detect these)
+ *   vscode  - vscode://file/<absolute>:12
+ *   cursor  - cursor://file/<absolute>:12
+ *   idea    - idea://open?file=<absolute>&line=12
+ *   osc8    - `src/app.ts:12` as a terminal hyperlink (OSC 8) to file://<absolute>
+ *   auto    - osc8 in terminals known to support it, plain otherwise
+ * Any other value containing `{` is a template with {path}, {abs} and {line}.
+ */
+
+import chalk from 'chalk';
+import * as path from 'path';
+import { pathToFileURL } from 'url';
+import { getConfig } from '../config.js';
+
+export type CitationFormat = 'plain' | 'vscode' | 'cursor' | 'idea' | 'osc8' | 'auto';
+
+export interface Citation {
+  /** Workspace-relative path */
+  file: string;
+  line?: number;
+  endLine?: number;
+  /** Workspace repository the file belongs to; shown as `repo:path` */
+  repo?: string;
+  /** Root `file` is relative to (default: the current repository) */
+  root?: string;
+}
+
+export interface CitationOptions {
+  /** Configured format name or template; defaults to auto */
+  format?: string;
+  repoRoot: string;
+  /** Whether output goes to a terminal (only then is OSC 8 used) */
+  isTTY?: boolean;
+  env?: NodeJS.ProcessEnv;
+}
+
+const KNOWN_FORMATS: CitationFormat[] = ['plain', 'vscode', 'cursor', 'idea', 'osc8', 'auto'];
+
+/**
+ * Parse `path`, `path:12` or `path:12-20`
+ */
+export function parseCitation(source: string): Citation {
+  const match = source.match(/^(.*?):(\d+)(?:-(\d+))?$/);
+  if (!match) return { file: source };
+  return {
+    file: match[1],
+    line: parseInt(match[2], 10),
+    endLine: match[3] ? parseInt(match[3], 10) : undefined
+  };
+}
+
+/**
+ * Whether the terminal renders OSC 8 hyperlinks
+ */
+export function terminalSupportsHyperlinks(env: NodeJS.ProcessEnv = process.env, isTTY = !!process.stdout.isTTY): boolean {
+  if (!isTTY || env.CI || env.TERM === 'dumb') return false;
+  if (env.FORCE_HYPERLINK) return env.FORCE_HYPERLINK !== '0';
+  if (env.WT_SESSION || env.KITTY_WINDOW_ID || env.WEZTERM_PANE) return true;
+  if (['iTerm.app', 'vscode', 'WezTerm', 'ghostty'].includes(env.TERM_PROGRAM || '')) return true;
+  // GNOME Terminal and other VTE terminals since 0.50
+  return !!env.VTE_VERSION && parseInt(env.VTE_VERSION, 10) >= 5000;
+}
+
+/**
+ * Check a `citations.format` value; returns an error message or null
+ */
+export function validateCitationFormat(format: string): string | null {
+  if ((KNOWN_FORMATS as string[]).includes(format) || format.includes('{')) return null;
+  return `Unknown citations.format "${format}" (expected ${KNOWN_FORMATS.join(', ')} or a template with {path}, {abs}, {line})`;
+}
+
+function osc8(url: string, label: string): string {
+  return `\u001b]8;;${url}\u001b\\${label}\u001b]8;;\u001b\\`;
+}
+
+/**
+ * Render a citation as a link in the configured format
+ */
+export function formatCitation(citation: Citation, options: CitationOptions): string {
+  const env = options.env ?? process.env;
+  const relative = citation.file.replace(/\\/g, '/');
+  const absolute = path.resolve(citation.root ?? options.repoRoot, citation.file);
+  const line = citation.line ?? 1;
+  const qualified = citation.repo ? `${citation.repo}:${relative}` : relative;
+  const label = citation.line === undefined
+    ? qualified
+    : `${qualified}:${citation.line}${citation.endLine && citation.endLine !== citation.line ? `-${citation.endLine}` : ''}`;
+
+  let format = options.format || 'auto';
+  if (format === 'auto') {
+    format = terminalSupportsHyperlinks(env, options.isTTY ?? !!process.stdout.isTTY) ? 'osc8' : 'plain';
+  }
+
+  switch (format) {
+    case 'plain':
+      return label;
+    case 'vscode':
+    case 'cursor':
+      return `${format}://file/${absolute.replace(/\\/g, '/').replace(/^\//, '')}:${line}`;
+    case 'idea':
+      return `idea://open?file=${encodeURIComponent(absolute)}&line=${line}`;
+    case 'osc8':
+      return osc8(pathToFileURL(absolute).href, label);
+    default:
+      if (format.includes('{')) {
+        return format
+          .replace(/\{path\}/g, relative)
+          .replace(/\{abs\}/g, absolute)
+          .replace(/\{line\}/g, String(line));
+      }
+      return label;
+  }
+}
+
+export type CitationFormatter = (citation: Citation | string) => string;
+
+/**
+ * Formatter bound to a repository and the user's `citations.format`
+ * (CV_CITATION_FORMAT overrides it for a single run)
+ */
+export async function loadCitationFormatter(repoRoot: string): Promise<CitationFormatter> {
+  let format = process.env.CV_CITATION_FORMAT;
+  if (!format) {
+    try {
+      format = (await getConfig().load()).citations?.format;
+    } catch {
+      // Unreadable user config: fall back to auto
+    }
+  }
+  return citation => formatCitation(
+    typeof citation === 'string' ? parseCitation(citation) : citation,
+    { format, repoRoot }
+  );
+}
+
+export interface SourceLocation {
+  /** Workspace repository, when not the current one */
+  repo?: string;
+  /** Absolute root of that repository */
+  root?: string;
+  file: string;
+  startLine: number;
+  endLine: number;
+  symbolName?: string;
+  score?: number;
+}
+
+/**
+ * Print a "Sources" section listing the code an answer was based on
+ */
+export function printSources(sources: SourceLocation[], cite: CitationFormatter): void {
+  if (sources.length === 0) return;
+  console.log();
+  console.log(chalk.bold.cyan('Sources:'));
+  for (const source of sources) {
+    const link = cite({ file: source.file, line: source.startLine, endLine: source.endLine, repo: source.repo, root: source.root });
+    console.log(chalk.gray(`  • ${link}${source.symbolName ? ` (${source.symbolName})` : ''}`));
+  }
+}
+
+/**
+ * Sources for --json output, with absolute paths editors can open directly
+ */
+export function sourcesToJson(sources: SourceLocation[], repoRoot: string) {
+  return sources.map(source => ({
+    ...(source.repo && { repo: source.repo }),
+    file: source.file,
+    path: path.resolve(source.root ?? repoRoot, source.file),
+    startLine: source.startLine,
+    endLine: source.endLine,
+    symbolName: source.symbolName,
+    score: source.score
+  }));
+}
@@ -119,6 +119,38 @@ export async function getAnthropicApiKey(configApiKey?: string): Promise<string
   return null;
 }
 
+/**
+ * Get Gemini API key with fallback order:
+ * 1. CredentialManager (keychain/file storage)
+ * 2. Config value (if provided)
+ * 3. Environment variable (GEMINI_API_KEY, then GOOGLE_API_KEY)
+ */
+export async function getGeminiApiKey(configApiKey?: string): Promise<string | null> {
+  // 1. Try CredentialManager first
+  try {
+    const manager = await getCredentialManager();
+    const key = await manager.getGeminiKey();
+    if (key) {
+      return key;
+    }
+  } catch (error) {
+    // Credential manager failed, continue to fallbacks
+  }
+
+  // 2. Try config value
+  if (configApiKey) {
+    return configApiKey;
+  }
+
+  // 3. Try environment variables
+  const envKey = process.env.GEMINI_API_KEY || process.env.GOOGLE_API_KEY;
+  if (envKey) {
+    return envKey;
+  }
+
+  return null;
+}
+
 /**
  * Embedding credentials with provider info
  */
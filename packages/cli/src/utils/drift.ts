@@ -0,0 +1,90 @@
+/**
+ * Index Drift Warnings
+ * Shared by AI commands to flag answers built on stale indexed code
+ */
+
+import chalk from 'chalk';
+import {
+  configManager,
+  createParser,
+  createSyncEngine,
+  detectIndexDrift,
+  DriftedFile,
+  GitManager,
+  GraphManager,
+  VectorManager
+} from '@cv-git/core';
+import { Context } from '@cv-git/shared';
+
+export interface DriftCheck {
+  repoRoot: string;
+  context: Context;
+  git: GitManager;
+  graph?: GraphManager;
+  vector?: VectorManager;
+  /** Re-index drifted files instead of only warning */
+  refresh?: boolean;
+  /** Don't print warnings (e.g. --json output) */
+  silent?: boolean;
+}
+
+export interface DriftCheckResult {
+  /** Files were re-indexed; context should be gathered again */
+  refreshed: boolean;
+  drifted: DriftedFile[];
+}
+
+/**
+ * Warn about (or re-index) files whose retrieved chunks are out of date
+ */
+export async function checkIndexDrift(check: DriftCheck): Promise<DriftCheckResult> {
+  let drifted: DriftedFile[];
+  try {
+    drifted = await detectIndexDrift(check.repoRoot, check.context, {
+      git: check.git,
+      graph: check.graph
+    });
+  } catch {
+    // Drift detection is advisory; never block an answer on it
+    return { refreshed: false, drifted: [] };
+  }
+
+  if (drifted.length === 0) return { refreshed: false, drifted };
+
+  if (check.refresh && check.graph) {
+    const modified = drifted.filter(d => d.reason === 'modified').map(d => d.file);
+    if (modified.length > 0) {
+      const config = await configManager.load(check.repoRoot);
+      const parser = createParser({ chunking: config.sync?.chunking });
+      const sync = createSyncEngine(check.repoRoot, check.git, parser, check.graph, check.vector);
+      await sync.incrementalSync(modified);
+      if (!check.silent) {
+        console.log(chalk.gray(`  ↻ Re-indexed ${modified.length} changed file(s) before answering`));
+      }
+      return { refreshed: true, drifted };
+    }
+  }
+
+  if (check.silent) return { refreshed: false, drifted };
+
+  console.log();
+  console.log(chalk.yellow(`⚠ ${drifted.length} file(s) changed since they were indexed:`));
+  for (const d of drifted.slice(0, 5)) {
+    const note = d.reason === 'deleted' ? 'deleted' : `modified ${formatAge(d.modifiedAt! - d.indexedAt)} after indexing`;
+    console.log(chalk.yellow(`  • ${d.file} (${note})`));
+  }
+  if (drifted.length > 5) {
+    console.log(chalk.yellow(`  … and ${drifted.length - 5} more`));
+  }
+  console.log(chalk.gray('  Answer may reflect old code. Run `cv sync` or pass --refresh-stale.'));
+
+  return { refreshed: false, drifted };
+}
+
+function formatAge(ms: number): string {
+  const minutes = Math.round(ms / 60000);
+  if (minutes < 60) return `${Math.max(minutes, 1)}m`;
+  const hours = Math.round(minutes / 60);
+  if (hours < 48) return `${hours}h`;
+  return `${Math.round(hours / 24)}d`;
+}
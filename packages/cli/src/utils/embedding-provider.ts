@@ -0,0 +1,120 @@
+/**
+ * Embedding provider resolution for CLI commands
+ *
+ * Gathers what each backend needs (running local servers, stored API keys,
+ * Azure settings) and hands it to core's resolveProvider(), so every
+ * command that embeds text picks the same backend the index was built with.
+ * Offline, only the local backends are tried: an index built with cloud
+ * embeddings can't be queried by meaning, and callers fall back to keyword
+ * search.
+ */
+
+import {
+  resolveProvider,
+  getLMStudioUrl,
+  isLMStudioRunning,
+  isLocalEmbeddingRuntimeAvailable,
+  isOffline,
+  OfflineError,
+  EmbeddingProvider,
+  EmbeddingProviderName
+} from '@cv-git/core';
+import { CVConfig } from '@cv-git/shared';
+import { getEmbeddingCredentials } from './credentials.js';
+import { ensureOllama } from './infrastructure.js';
+import { getPreferences } from '../config.js';
+
+export interface ResolveEmbeddingProviderOptions {
+  /** Progress messages (spinner text, info lines) */
+  onStatus?: (message: string) => void;
+  /** Pull the Ollama model if it is missing */
+  pullModel?: boolean;
+}
+
+/**
+ * Azure settings from config, falling back to AZURE_OPENAI_* environment variables
+ */
+function azureSettings(config: CVConfig) {
+  const endpoint = config.embedding?.azure?.endpoint || process.env.AZURE_OPENAI_ENDPOINT;
+  const apiKey = (config.embedding?.provider === 'azure' ? config.embedding.apiKey : undefined) ||
+    process.env.AZURE_OPENAI_API_KEY;
+  const deployment = config.embedding?.azure?.deployment || process.env.AZURE_OPENAI_EMBEDDING_DEPLOYMENT;
+  if (!endpoint || !apiKey || !deployment) return undefined;
+  return {
+    endpoint,
+    apiKey,
+    deployment,
+    apiVersion: config.embedding?.azure?.apiVersion || process.env.AZURE_OPENAI_API_VERSION
+  };
+}
+
+/**
+ * Resolve the repository's embedding backend. A preferred local backend
+ * that isn't running falls back to cloud credentials; throws when nothing
+ * is available (an OfflineError when the rest would need network).
+ */
+export async function resolveEmbeddingProvider(
+  config: CVConfig,
+  options: ResolveEmbeddingProviderOptions = {}
+): Promise<EmbeddingProvider> {
+  const status = options.onStatus || (() => {});
+  const prefs = await getPreferences().load();
+  const preferred: EmbeddingProviderName = config.embedding?.provider || prefs.embeddingProvider || 'ollama';
+  const model = config.embedding?.model;
+
+  if (preferred === 'local') {
+    if (await isLocalEmbeddingRuntimeAvailable()) {
+      status(`Using local embedding model ${model?.startsWith('local/') ? model : 'default'}`);
+      return resolveProvider({ provider: 'local', model });
+    }
+    status('Local embedding runtime not installed (run: cv auth setup local), checking cloud providers...');
+  } else if (preferred === 'ollama') {
+    status('Checking Ollama...');
+    const ollamaInfo = await ensureOllama({
+      silent: true,
+      pullModel: isOffline() ? false : options.pullModel,
+      model: model || 'nomic-embed-text'
+    });
+    if (ollamaInfo) {
+      status(`Using Ollama at ${ollamaInfo.url}`);
+      return resolveProvider({ provider: 'ollama', model, ollamaUrl: ollamaInfo.url });
+    }
+    status('Ollama not available, checking cloud providers...');
+  } else if (preferred === 'lmstudio') {
+    status('Checking LM Studio...');
+    const lmUrl = getLMStudioUrl();
+    if (await isLMStudioRunning(lmUrl)) {
+      status(`Using LM Studio at ${lmUrl}`);
+      return resolveProvider({ provider: 'lmstudio', model, lmstudioUrl: lmUrl });
+    }
+    status('LM Studio not available, checking cloud providers...');
+  }
+
+  if (isOffline()) {
+    throw new OfflineError(`Embedding with ${preferred}`);
+  }
+
+  const azure = azureSettings(config);
+  if (preferred === 'azure') {
+    return resolveProvider({ provider: 'azure', model, azure });
+  }
+
+  const creds = await getEmbeddingCredentials({
+    openRouterKey: config.embedding?.apiKey,
+    openaiKey: config.ai?.apiKey
+  });
+
+  // Keep an explicit cloud choice when its key exists; otherwise take whatever is configured
+  const cloud = (preferred === 'openai' && creds.openaiApiKey) || (preferred === 'openrouter' && creds.openrouterApiKey)
+    ? preferred
+    : undefined;
+  const provider = resolveProvider({
+    provider: cloud,
+    model,
+    openrouterApiKey: creds.openrouterApiKey,
+    openaiApiKey: creds.openaiApiKey,
+    azure
+  });
+  status(`Using ${provider.name} for embeddings`);
+  return provider;
+}
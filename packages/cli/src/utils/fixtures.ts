@@ -0,0 +1,59 @@
+/**
+ * Fixture Recording
+ * Hidden --record-fixture support for capturing a command's pipeline
+ */
+
+import { Command, Option } from 'commander';
+import chalk from 'chalk';
+import {
+  AIManager,
+  VectorManager,
+  FixtureRecorder,
+  resolveFixturePath
+} from '@cv-git/core';
+
+/**
+ * Add the hidden --record-fixture option to a command
+ */
+export function addFixtureOptions(cmd: Command): Command {
+  cmd.addOption(
+    new Option('--record-fixture [path]', 'Record retrieval, prompts and responses to a replayable fixture')
+      .hideHelp()
+  );
+  return cmd;
+}
+
+export interface FixtureSession {
+  /** Route an AI/vector manager's events into the fixture */
+  attach(ai: AIManager, vector?: VectorManager): void;
+  /** Write the fixture (no-op when not recording) */
+  save(): Promise<void>;
+}
+
+/**
+ * Start a recording session if --record-fixture was passed
+ */
+export function createFixtureSession(
+  options: { recordFixture?: string | boolean },
+  repoRoot: string,
+  command: string,
+  args: string[]
+): FixtureSession {
+  if (!options.recordFixture) {
+    return { attach: () => {}, save: async () => {} };
+  }
+
+  const recorder = new FixtureRecorder(command, args);
+
+  return {
+    attach(ai, vector) {
+      ai.setFixtureRecorder(recorder);
+      vector?.setFixtureRecorder(recorder);
+    },
+    async save() {
+      const fixturePath = resolveFixturePath(repoRoot, command, options.recordFixture);
+      await recorder.save(fixturePath);
+      console.error(chalk.gray(`Fixture recorded: ${fixturePath}`));
+    }
+  };
+}
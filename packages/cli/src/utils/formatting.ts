@@ -4,18 +4,38 @@
  */
 
 import chalk from 'chalk';
+import { supportsUnicode } from '@cv-git/shared';
+
+// Legacy Windows consoles render box drawing and symbols as '?', so fall back to ASCII
+const UNICODE = supportsUnicode();
 
 // Box drawing characters
-const BOX = {
-  topLeft: '┌',
-  topRight: '┐',
-  bottomLeft: '└',
-  bottomRight: '┘',
-  horizontal: '─',
-  vertical: '│',
-  teeRight: '├',
-  teeLeft: '┤',
-};
+const BOX = UNICODE
+  ? {
+      topLeft: '┌',
+      topRight: '┐',
+      bottomLeft: '└',
+      bottomRight: '┘',
+      horizontal: '─',
+      vertical: '│',
+      teeRight: '├',
+      teeLeft: '┤',
+    }
+  : {
+      topLeft: '+',
+      topRight: '+',
+      bottomLeft: '+',
+      bottomRight: '+',
+      horizontal: '-',
+      vertical: '|',
+      teeRight: '+',
+      teeLeft: '+',
+    };
+
+// Status symbols
+export const SYMBOLS = UNICODE
+  ? { success: '✓', error: '✗', warning: '⚠', info: 'ℹ', pending: '○', bullet: '•' }
+  : { success: '√', error: '×', warning: '!', info: 'i', pending: 'o', bullet: '*' };
 
 // Terminal width (default to 80 if not available)
 function getTerminalWidth(): number {
@@ -31,9 +51,9 @@ export function divider(
 ): string {
   const w = width || Math.min(getTerminalWidth(), 80);
   const chars = {
-    light: '─',
-    heavy: '━',
-    double: '═',
+    light: BOX.horizontal,
+    heavy: UNICODE ? '━' : '=',
+    double: UNICODE ? '═' : '=',
   };
   return chalk.gray(chars[style].repeat(w));
 }
@@ -49,8 +69,8 @@ export function labeledDivider(
 ): string {
   const w = width || Math.min(getTerminalWidth(), 80);
   const chars = {
-    light: '─',
-    heavy: '━',
+    light: BOX.horizontal,
+    heavy: UNICODE ? '━' : '=',
   };
   const char = chars[style];
 
@@ -209,19 +229,29 @@ export function statusLine(
   message: string
 ): string {
   const icons = {
-    success: chalk.green('✓'),
-    error: chalk.red('✗'),
-    warning: chalk.yellow('⚠'),
-    info: chalk.blue('ℹ'),
-    pending: chalk.gray('○'),
+    success: chalk.green(SYMBOLS.success),
+    error: chalk.red(SYMBOLS.error),
+    warning: chalk.yellow(SYMBOLS.warning),
+    info: chalk.blue(SYMBOLS.info),
+    pending: chalk.gray(SYMBOLS.pending),
   };
   return `${icons[status]} ${message}`;
 }
 
+/**
+ * Format a progress bar, e.g. [██████░░░░] 60%
+ */
+export function progressBar(done: number, total: number, width: number = 24): string {
+  const ratio = total > 0 ? Math.min(1, done / total) : 1;
+  const filled = Math.round(ratio * width);
+  const [full, empty] = UNICODE ? ['█', '░'] : ['#', '-'];
+  return `[${chalk.cyan(full.repeat(filled))}${chalk.gray(empty.repeat(width - filled))}] ${String(Math.round(ratio * 100)).padStart(3)}%`;
+}
+
 /**
  * Strip ANSI escape codes for length calculation
  */
-function stripAnsi(str: string): string {
+export function stripAnsi(str: string): string {
   // eslint-disable-next-line no-control-regex
   return str.replace(/\x1B\[[0-9;]*[a-zA-Z]/g, '');
 }
@@ -304,18 +334,18 @@ export function formatDiffBox(
 
   // Build the formatted output
   const lines: string[] = [];
-  lines.push(color('┌' + '─'.repeat(76) + '┐'));
-  lines.push(color('│') + ' ' + header + ' '.repeat(Math.max(0, 75 - stripAnsi(header).length)) + color('│'));
-  lines.push(color('├' + '─'.repeat(76) + '┤'));
+  lines.push(color(BOX.topLeft + BOX.horizontal.repeat(76) + BOX.topRight));
+  lines.push(color(BOX.vertical) + ' ' + header + ' '.repeat(Math.max(0, 75 - stripAnsi(header).length)) + color(BOX.vertical));
+  lines.push(color(BOX.teeRight + BOX.horizontal.repeat(76) + BOX.teeLeft));
 
   // Add diff lines
   for (const diffLine of coloredDiff.split('\n')) {
     const visibleLen = stripAnsi(diffLine).length;
     const padding = Math.max(0, 75 - visibleLen);
-    lines.push(color('│') + ' ' + diffLine + ' '.repeat(padding) + color('│'));
+    lines.push(color(BOX.vertical) + ' ' + diffLine + ' '.repeat(padding) + color(BOX.vertical));
   }
 
-  lines.push(color('└' + '─'.repeat(76) + '┘'));
+  lines.push(color(BOX.bottomLeft + BOX.horizontal.repeat(76) + BOX.bottomRight));
 
   return lines.join('\n');
 }
@@ -0,0 +1,122 @@
+/**
+ * Model selection for AI commands
+ *
+ * Resolves `--model provider/model` (e.g. `anthropic/claude-sonnet-4-5`,
+ * `gemini/gemini-2.5-pro`, `ollama/qwen2.5-coder:14b`) to a client for that
+ * provider, with the API key looked up the usual way (cv auth, config, env).
+ * With `ai.fallback` configured, the client tries each model of the chain in
+ * turn when a provider is rate limited or down. Offline, only local
+ * providers (Ollama, LM Studio) can be used.
+ */
+
+import chalk from 'chalk';
+import {
+  AIClient,
+  AIProvider,
+  FallbackClient,
+  OfflineError,
+  createAIClient,
+  isOffline,
+  parseFallbackChain,
+  parseModelSpec,
+  providerErrorStatus,
+} from '@cv-git/core';
+import { getAnthropicApiKey, getGeminiApiKey, getOpenAIApiKey, getOpenRouterApiKey } from './credentials.js';
+
+export interface ModelClientDefaults {
+  /** Provider for a bare model name such as `claude-sonnet-4-5` */
+  provider: Exclude<AIProvider, 'auto'>;
+  /** Model when the spec names none */
+  model?: string;
+  maxTokens?: number;
+  /** Keys from config, tried after cv auth */
+  anthropicApiKey?: string;
+  openRouterApiKey?: string;
+  /** Models to fall back to (`ai.fallback`) */
+  fallback?: string[] | string;
+}
+
+const KEY_SETUP: Record<string, string> = {
+  anthropic: 'cv auth setup anthropic (or set ANTHROPIC_API_KEY)',
+  gemini: 'cv auth setup gemini (or set GEMINI_API_KEY)',
+  openai: 'cv auth setup openai (or set OPENAI_API_KEY)',
+  openrouter: 'cv auth setup openrouter (or set OPENROUTER_API_KEY=sk-or-...)',
+};
+
+/**
+ * API key for a cloud provider; undefined for local providers
+ */
+async function providerApiKey(provider: string, defaults: ModelClientDefaults): Promise<string | undefined> {
+  let key: string | null = null;
+  if (provider === 'anthropic') key = await getAnthropicApiKey(defaults.anthropicApiKey);
+  else if (provider === 'gemini') key = await getGeminiApiKey();
+  else if (provider === 'openai') key = await getOpenAIApiKey();
+  else if (provider === 'openrouter') key = await getOpenRouterApiKey(defaults.openRouterApiKey);
+  else return undefined;
+
+  if (!key) {
+    throw new Error(`No ${provider} API key found. Run: ${KEY_SETUP[provider]}`);
+  }
+  return key;
+}
+
+/**
+ * Client for a single model spec
+ */
+async function createSingleClient(spec: string | undefined, defaults: ModelClientDefaults): Promise<AIClient> {
+  const parsed = parseModelSpec(spec);
+  const provider = parsed.provider ?? defaults.provider;
+  // A provider's own default model applies when only the provider was named
+  const model = parsed.model ?? (parsed.provider && parsed.provider !== defaults.provider ? undefined : defaults.model);
+  if (isOffline() && provider in KEY_SETUP) {
+    throw new OfflineError(`Using ${provider} models`, 'Use a local one, e.g. --model ollama/qwen2.5-coder:14b');
+  }
+
+  return createAIClient({
+    provider,
+    model,
+    apiKey: await providerApiKey(provider, defaults),
+    maxTokens: defaults.maxTokens,
+  });
+}
+
+/**
+ * Client for a `--model` value, wrapped in a fallback chain when one is configured
+ */
+export async function createModelClient(spec: string | undefined, defaults: ModelClientDefaults): Promise<AIClient> {
+  const fallbacks = parseFallbackChain(defaults.fallback);
+  if (fallbacks.length === 0) {
+    return createSingleClient(spec, defaults);
+  }
+
+  const primary = spec?.trim() || (defaults.model ? `${defaults.provider}/${defaults.model}` : defaults.provider);
+  const chain = [primary, ...fallbacks.filter(f => f !== primary)];
+  return new FallbackClient(
+    chain.map((model, i) => ({
+      spec: model,
+      // Fallback entries name their provider; the command default applies to the first only
+      create: () => createSingleClient(model, i === 0 ? defaults : { ...defaults, model: undefined }),
+    })),
+    {
+      onFallback: ({ from, to, error }) => {
+        console.error(chalk.yellow(`↪ ${from} failed (${describeFailure(error)}); trying ${to}`));
+      },
+    }
+  );
+}
+
+/**
+ * Model that answered, when a fallback chain had to skip the first one
+ */
+export function fallbackNotice(client: AIClient | undefined): string | undefined {
+  if (!(client instanceof FallbackClient)) return undefined;
+  const answeredBy = client.getAnsweredBy();
+  return answeredBy && answeredBy !== client.getChain()[0] ? `Answered by ${answeredBy}` : undefined;
+}
+
+function describeFailure(error: Error): string {
+  const status = providerErrorStatus(error);
+  if (status === 429) return 'rate limited';
+  if (status !== undefined) return `HTTP ${status}`;
+  return error.message.split('\n')[0].slice(0, 80);
+}
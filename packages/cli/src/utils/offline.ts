@@ -0,0 +1,38 @@
+/**
+ * Offline mode for CLI commands
+ *
+ * `cv --offline <command>` (or CV_OFFLINE=1, or no network at all) refuses
+ * cloud provider calls up front; this prints what still works.
+ */
+
+import chalk from 'chalk';
+import { OfflineReason, describeOfflineReason } from '@cv-git/core';
+
+/** What keeps working without network, and what doesn't */
+export const OFFLINE_FEATURES = {
+  available: [
+    'cv grep, cv graph, cv complexity, cv deadcode and cv hotspots',
+    'cv find with keyword matches (semantic too with ollama, lmstudio or local embeddings)',
+    'AI commands with a local model (--model ollama/<model> or lmstudio/<model>)'
+  ],
+  unavailable: [
+    'cloud models (anthropic, openai, openrouter, gemini)',
+    'cloud embeddings (cv sync updates the graph and keyword index only)',
+    'CV-Hub, push/pull and the shared team index'
+  ]
+};
+
+/**
+ * Notice on stderr that the command runs offline; --verbose lists what needs network
+ */
+export function printOfflineNotice(
+  reason: OfflineReason,
+  options: { json?: boolean; quiet?: boolean; verbose?: boolean } = {}
+): void {
+  if (options.json || options.quiet) return;
+  console.error(chalk.yellow(`Offline (${describeOfflineReason(reason)}): `) +
+    chalk.gray('cloud models and embeddings are unavailable; keyword search, graph and analysis work.'));
+  if (!options.verbose) return;
+  OFFLINE_FEATURES.available.forEach(feature => console.error(chalk.gray(`  ✓ ${feature}`)));
+  OFFLINE_FEATURES.unavailable.forEach(feature => console.error(chalk.gray(`  ✗ ${feature}`)));
+}
@@ -5,6 +5,8 @@
 
 import chalk from 'chalk';
 import ora from 'ora';
+import { format } from 'util';
+import { SYMBOLS, stripAnsi } from './formatting.js';
 
 export interface OutputOptions {
   json?: boolean;
@@ -28,7 +30,7 @@ export class OutputManager {
     if (this.options.json && data) {
       this.json({ success: true, message, data });
     } else {
-      console.log(chalk.green('✓'), message);
+      console.log(chalk.green(SYMBOLS.success), message);
       if (data && this.options.verbose) {
         console.log(chalk.gray(JSON.stringify(data, null, 2)));
       }
@@ -48,7 +50,7 @@ export class OutputManager {
         stack: this.options.verbose ? error?.stack : undefined,
       });
     } else {
-      console.error(chalk.red('✗'), message);
+      console.error(chalk.red(SYMBOLS.error), message);
       if (error && this.options.verbose) {
         console.error(chalk.gray(error.stack || error.message || error));
       }
@@ -64,7 +66,7 @@ export class OutputManager {
     if (this.options.json && data) {
       this.json({ warning: true, message, data });
     } else {
-      console.log(chalk.yellow('⚠'), message);
+      console.log(chalk.yellow(SYMBOLS.warning), message);
       if (data && this.options.verbose) {
         console.log(chalk.gray(JSON.stringify(data, null, 2)));
       }
@@ -80,7 +82,7 @@ export class OutputManager {
     if (this.options.json && data) {
       this.json({ info: true, message, data });
     } else {
-      console.log(chalk.cyan('ℹ'), message);
+      console.log(chalk.cyan(SYMBOLS.info), message);
       if (data && this.options.verbose) {
         console.log(chalk.gray(JSON.stringify(data, null, 2)));
       }
@@ -210,7 +212,7 @@ export function addGlobalOptions(command: any): any {
  */
 export function createOutput(options: any): OutputManager {
   return new OutputManager({
-    json: options.json,
+    json: options.json || options.jsonl,
     quiet: options.quiet,
     verbose: options.verbose,
   });
@@ -228,6 +230,7 @@ export enum ErrorCode {
   // Git
   NOT_GIT_REPO = 'NOT_GIT_REPO',
   GIT_ERROR = 'GIT_ERROR',
+  UNCOMMITTED_CHANGES = 'UNCOMMITTED_CHANGES',
 
   // CV-Git
   NOT_INITIALIZED = 'NOT_INITIALIZED',
@@ -248,6 +251,9 @@ export enum ErrorCode {
   PLATFORM_ERROR = 'PLATFORM_ERROR',
   API_ERROR = 'API_ERROR',
   NETWORK_ERROR = 'NETWORK_ERROR',
+
+  // Results
+  NO_RESULTS = 'NO_RESULTS',
 }
 
 /**
@@ -277,3 +283,103 @@ export function handleError(error: any, output: OutputManager): never {
   }
   process.exit(1);
 }
+
+/**
+ * Version of the --json/--jsonl document layout. Fields may be added within a
+ * version; it changes when a field is renamed or removed or changes type.
+ */
+export const JSON_SCHEMA_VERSION = 1;
+
+/**
+ * Exit codes of commands that support --json/--jsonl, in every output mode
+ */
+export enum ExitCode {
+  Success = 0,
+  /** The command ran and its check failed: review findings at --fail-on, an incomplete `cv do` */
+  CheckFailed = 1,
+  /** The command could not run: bad arguments, not initialized, a service or provider error */
+  Error = 2,
+}
+
+/**
+ * Add --jsonl to a command (--json comes with addGlobalOptions)
+ */
+export function addJsonlOption(command: any): any {
+  return command.option('--jsonl', 'Stream newline-delimited JSON events');
+}
+
+/**
+ * Machine-readable output for --json and --jsonl.
+ *
+ * --json prints one document when the command finishes:
+ *
+ *   { "schemaVersion": 1, "command": "find", "ok": true, "data": { ... } }
+ *
+ * --jsonl prints one event per line while the command runs, ending with the
+ * same document as a "result" event:
+ *
+ *   {"schemaVersion":1,"command":"find","type":"result","ok":true,"data":{...}}
+ *
+ * `exitCode` repeats the process exit code and `ok` is true when it is 0.
+ * Commands that could not run have `error: { code, message }` instead of data.
+ */
+export class JsonReporter {
+  constructor(readonly command: string, readonly mode: 'json' | 'jsonl') {}
+
+  get streaming(): boolean {
+    return this.mode === 'jsonl';
+  }
+
+  /**
+   * Progress or a partial result; only written with --jsonl
+   */
+  event(type: string, data?: unknown): void {
+    if (!this.streaming) return;
+    this.write({ type, ...(data !== undefined ? { data } : {}) });
+  }
+
+  /**
+   * With --jsonl, stream console.log progress (e.g. from the sync engine) as
+   * "log" events
+   */
+  captureLogs(): void {
+    if (!this.streaming) return;
+    console.log = (...args: unknown[]) => {
+      const message = stripAnsi(format(...args)).trim();
+      if (message) this.event('log', { message });
+    };
+  }
+
+  /**
+   * The final document. Sets the exit code without exiting, so callers can
+   * close connections first.
+   */
+  result(data: unknown, exitCode: ExitCode = ExitCode.Success): void {
+    this.write({ ...(this.streaming ? { type: 'result' } : {}), ok: exitCode === ExitCode.Success, exitCode, data });
+    process.exitCode = exitCode;
+  }
+
+  /**
+   * A failure document, then exit
+   */
+  fail(message: string, code: ErrorCode | string = ErrorCode.OPERATION_FAILED, exitCode: ExitCode = ExitCode.Error): never {
+    this.write({ ...(this.streaming ? { type: 'result' } : {}), ok: false, exitCode, error: { code, message } });
+    process.exit(exitCode);
+  }
+
+  private write(fields: Record<string, unknown>): void {
+    const document = { schemaVersion: JSON_SCHEMA_VERSION, command: this.command, ...fields };
+    process.stdout.write((this.streaming ? JSON.stringify(document) : JSON.stringify(document, null, 2)) + '\n');
+  }
+}
+
+/**
+ * A JsonReporter when --json or --jsonl was passed. stdout then carries only
+ * the reporter's documents; console.log output moves to stderr.
+ */
+export function createJsonReporter(command: string, options: { json?: boolean; jsonl?: boolean }): JsonReporter | null {
+  if (!options.json && !options.jsonl) return null;
+  console.log = console.error;
+  console.info = console.error;
+  return new JsonReporter(command, options.jsonl ? 'jsonl' : 'json');
+}
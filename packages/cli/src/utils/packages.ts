@@ -0,0 +1,38 @@
+/**
+ * Monorepo package scoping for CLI searches
+ */
+
+import { RepoPackage, findPackage, loadPackages, packageForDirectory } from '@cv-git/core';
+
+export interface PackageScope {
+  package: RepoPackage;
+  /** Picked from the working directory rather than `--package` */
+  automatic: boolean;
+}
+
+/**
+ * Package a search is scoped to: `--package <name>`, else the package the
+ * command runs in. `--all-packages` searches the whole repository.
+ * @throws when `--package` names no detected package
+ */
+export async function resolvePackageScope(
+  repoRoot: string,
+  options: { package?: string; allPackages?: boolean },
+  cwd: string = process.cwd()
+): Promise<PackageScope | undefined> {
+  if (options.allPackages && !options.package) return undefined;
+
+  const packages = await loadPackages(repoRoot);
+  if (options.package) {
+    const pkg = findPackage(packages, options.package);
+    if (!pkg) {
+      throw new Error(packages.length > 0
+        ? `Unknown package "${options.package}". Packages: ${packages.map(p => p.name).sort().join(', ')}`
+        : `No packages detected in this repository. Run \`cv sync --force\` to detect them.`);
+    }
+    return { package: pkg, automatic: false };
+  }
+
+  const pkg = packageForDirectory(packages, repoRoot, cwd);
+  return pkg ? { package: pkg, automatic: true } : undefined;
+}
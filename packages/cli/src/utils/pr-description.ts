@@ -0,0 +1,104 @@
+/**
+ * PR Description Sections
+ * Keeps generated sections (the changelog, the `cv pr describe` summary)
+ * inside a PR body between marker comments, so re-running after new pushes
+ * rewrites each section in place and leaves the author's own text untouched.
+ */
+
+import * as fs from 'fs';
+
+export const PR_SECTION_START = '<!-- cv-git:changelog:start -->';
+export const PR_SECTION_END = '<!-- cv-git:changelog:end -->';
+export const PR_SUMMARY_START = '<!-- cv-git:summary:start -->';
+export const PR_SUMMARY_END = '<!-- cv-git:summary:end -->';
+
+export interface PRCommit {
+  hash: string;
+  message: string;
+}
+
+const TYPE_HEADINGS: Record<string, string> = {
+  feat: 'Features',
+  fix: 'Fixes',
+  perf: 'Performance',
+  refactor: 'Refactoring',
+  docs: 'Documentation',
+  test: 'Tests',
+};
+
+/**
+ * Render the changelog section (markers included) for a list of commits,
+ * oldest first. Conventional-commit types are grouped under headings.
+ */
+export function buildChangelogSection(commits: PRCommit[]): string {
+  const groups = new Map<string, string[]>();
+  for (const commit of [...commits].reverse()) {
+    const subject = commit.message.split('\n')[0].trim();
+    const match = subject.match(/^(\w+)(\([^)]*\))?!?:\s*(.+)$/);
+    const heading = (match && TYPE_HEADINGS[match[1]]) || 'Other';
+    const text = match && TYPE_HEADINGS[match[1]] ? match[3] : subject;
+    const lines = groups.get(heading) ?? [];
+    lines.push(`- ${text} (${commit.hash.slice(0, 7)})`);
+    groups.set(heading, lines);
+  }
+
+  const parts = [PR_SECTION_START, `## Changes (${commits.length} commit${commits.length === 1 ? '' : 's'})`];
+  const order = [...Object.values(TYPE_HEADINGS), 'Other'];
+  for (const heading of order) {
+    const lines = groups.get(heading);
+    if (!lines) continue;
+    // Skip the sub-heading when everything falls into one group
+    if (groups.size > 1) parts.push('', `### ${heading}`);
+    parts.push('', ...lines);
+  }
+  parts.push(PR_SECTION_END);
+  return parts.join('\n');
+}
+
+/**
+ * Wrap a generated summary (markdown) in its markers
+ */
+export function buildSummarySection(markdown: string): string {
+  return `${PR_SUMMARY_START}\n${markdown.trim()}\n${PR_SUMMARY_END}`;
+}
+
+/**
+ * Replace the generated section in `body`, or add it when absent (after the
+ * author's text, or before it with `prepend`). Markers default to the
+ * changelog's.
+ */
+export function upsertGeneratedSection(
+  body: string,
+  section: string,
+  options: { start?: string; end?: string; prepend?: boolean } = {}
+): string {
+  const startMarker = options.start ?? PR_SECTION_START;
+  const endMarker = options.end ?? PR_SECTION_END;
+  const start = body.indexOf(startMarker);
+  const end = body.indexOf(endMarker, start);
+  if (start !== -1 && end !== -1) {
+    return body.slice(0, start) + section + body.slice(end + endMarker.length);
+  }
+  const trimmed = body.trim();
+  if (!trimmed) return `${section}\n`;
+  return options.prepend ? `${section}\n\n${trimmed}\n` : `${body.trimEnd()}\n\n${section}\n`;
+}
+
+/**
+ * PR number of the pipeline's pull request, when running in CI
+ * (GitHub Actions, GitLab merge request pipelines, Bitbucket Pipelines)
+ */
+export function detectCIPullRequest(env: NodeJS.ProcessEnv = process.env): number | undefined {
+  if (env.GITHUB_EVENT_PATH) {
+    try {
+      const event = JSON.parse(fs.readFileSync(env.GITHUB_EVENT_PATH, 'utf-8'));
+      const number = event.pull_request?.number ?? event.number;
+      if (typeof number === 'number') return number;
+    } catch {
+      // Not a pull_request event
+    }
+  }
+  const raw = env.CI_MERGE_REQUEST_IID || env.BITBUCKET_PR_ID;
+  const parsed = raw ? parseInt(raw, 10) : NaN;
+  return Number.isNaN(parsed) ? undefined : parsed;
+}
@@ -85,3 +85,32 @@ export function formatEditSummary(
 
   return parts.join(', ') || chalk.gray('no changes');
 }
+
+/**
+ * Get the prompt text for resolving a merge conflict
+ */
+export function getConflictPromptText(index: number, total: number): string {
+  return chalk.yellow(
+    `Resolve conflict ${index}/${total}: ` +
+    `keep [${chalk.bold('o')}]urs / take [${chalk.bold('t')}]heirs / [${chalk.bold('b')}]oth / [${chalk.bold('c')}]ancel file: `
+  );
+}
+
+/**
+ * Parse a conflict resolution; null means cancel, undefined means ask again
+ */
+export function parseConflictResolution(input: string): 'ours' | 'theirs' | 'both' | null | undefined {
+  switch (input.trim().toLowerCase()[0]) {
+    case 'o':
+      return 'ours';
+    case 't':
+      return 'theirs';
+    case 'b':
+      return 'both';
+    case 'c':
+    case 'q':
+      return null;
+    default:
+      return undefined;
+  }
+}
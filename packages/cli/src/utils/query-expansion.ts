@@ -0,0 +1,20 @@
+/**
+ * Query expansion setup for CLI commands
+ */
+
+import chalk from 'chalk';
+import { AIManager, resolveQueryExpansion } from '@cv-git/core';
+import { CVConfig } from '@cv-git/shared';
+
+/**
+ * Turn on query expansion for `command` as configured under
+ * `retrieval.expansion`, or as `--expand-query` overrides it.
+ * An unknown mode is reported and expansion is left off.
+ */
+export function applyQueryExpansion(ai: AIManager, config: CVConfig, command: string, override?: string): void {
+  try {
+    ai.setQueryExpansion(resolveQueryExpansion(config.retrieval?.expansion, command, override));
+  } catch (error: any) {
+    console.error(chalk.gray(`  ⚠ Query expansion disabled: ${error.message}`));
+  }
+}
@@ -0,0 +1,20 @@
+/**
+ * Rerank stage setup for CLI commands
+ */
+
+import chalk from 'chalk';
+import { AIManager, createReranker } from '@cv-git/core';
+import { CVConfig } from '@cv-git/shared';
+
+/**
+ * Attach the reranker configured under `rerank` to an AI manager.
+ * A provider that can't be set up (e.g. no API key) is reported and skipped
+ * so retrieval still works without it.
+ */
+export function applyReranker(ai: AIManager, config: CVConfig): void {
+  try {
+    ai.setReranker(createReranker(config.rerank), config.rerank?.candidates);
+  } catch (error: any) {
+    console.error(chalk.gray(`  ⚠ Reranking disabled: ${error.message}`));
+  }
+}
@@ -0,0 +1,102 @@
+/**
+ * Review Hook Scripts
+ * The pre-commit and pre-push scripts `cv hooks install` writes, and how an
+ * existing hook is kept running after ours.
+ */
+
+/** Marks a hook (or the top of a combined one) as written by cv-git */
+export const HOOK_MARKER = '# CV-GIT HOOK';
+
+/** Set to skip the cv-git checks in pre-commit and pre-push (other hooks still run) */
+export const BYPASS_ENV = 'CV_SKIP_HOOKS';
+
+export interface CheckHookOptions {
+  /** Seconds the AI review may take before it is skipped */
+  budget: number;
+  /** Block when findings reach this severity */
+  failOn: string;
+  /** Block on secrets found by cv redact */
+  secretScan: boolean;
+}
+
+/**
+ * pre-commit: review the staged diff. `cv review --fast` exits 0 when there
+ * is no API key or the provider is unreachable, so commits are never blocked
+ * by the network; only findings (or secrets) block.
+ */
+export function preCommitHook(options: CheckHookOptions): string {
+  const secretScan = options.secretScan ? `
+  if ! cv redact --check --staged --exit-code; then
+    echo "cv-git: the staged changes contain secrets (see above). Bypass with ${BYPASS_ENV}=1" >&2
+    exit 1
+  fi
+` : '';
+  return `#!/bin/sh
+${HOOK_MARKER} - DO NOT EDIT THIS LINE
+# Fast AI review of staged changes before committing
+# Bypass with ${BYPASS_ENV}=1 git commit (or git commit --no-verify)
+
+if [ -z "$${BYPASS_ENV}" ] && command -v cv >/dev/null 2>&1; then
+${secretScan}
+  if ! cv review --staged --fast --budget ${options.budget} --fail-on ${options.failOn}; then
+    echo "cv-git: commit blocked by review findings. Bypass with ${BYPASS_ENV}=1" >&2
+    exit 1
+  fi
+fi
+`;
+}
+
+/**
+ * pre-push: review the commits each pushed ref adds. Git passes
+ * "<local ref> <local sha> <remote ref> <remote sha>" per ref on stdin; a
+ * new branch is compared with the remote's default branch. The refs are
+ * handed on to a preserved hook (git-lfs reads them too).
+ */
+export function prePushHook(options: CheckHookOptions): string {
+  const secretScan = options.secretScan ? `
+    if ! cv redact --check --diff "$BASE..$LOCAL_SHA" --exit-code </dev/null; then
+      echo "cv-git: $LOCAL_REF contains secrets (see above). Bypass with ${BYPASS_ENV}=1" >&2
+      exit 1
+    fi
+` : '';
+  return `#!/bin/sh
+${HOOK_MARKER} - DO NOT EDIT THIS LINE
+# Fast AI review of the commits being pushed
+# Bypass with ${BYPASS_ENV}=1 git push (or git push --no-verify)
+
+CV_PUSH_REFS=$(cat)
+
+if [ -z "$${BYPASS_ENV}" ] && command -v cv >/dev/null 2>&1; then
+  while read -r LOCAL_REF LOCAL_SHA REMOTE_REF REMOTE_SHA; do
+    # Deleting a remote branch: nothing to review
+    case "$LOCAL_SHA" in *[!0]*) ;; *) continue ;; esac
+
+    case "$REMOTE_SHA" in
+      *[!0]*) BASE="$REMOTE_SHA" ;;
+      *) BASE=$(git rev-parse --verify --quiet "refs/remotes/$1/HEAD") || continue ;;
+    esac
+${secretScan}
+    if ! cv review --range "$BASE..$LOCAL_SHA" --fast --budget ${options.budget} --fail-on ${options.failOn} </dev/null; then
+      echo "cv-git: push blocked by review findings in $LOCAL_REF. Bypass with ${BYPASS_ENV}=1" >&2
+      exit 1
+    fi
+  done <<CV_PUSH_REFS_EOF
+$CV_PUSH_REFS
+CV_PUSH_REFS_EOF
+fi
+
+exec 0<<CV_PUSH_REFS_EOF
+$CV_PUSH_REFS
+CV_PUSH_REFS_EOF
+`;
+}
+
+/**
+ * Our hook followed by an existing one, which runs when ours does not exit
+ */
+export function combineHooks(ours: string, existing: string): string {
+  return ours + `
+# Original hook preserved below
+${existing.replace(/^#!.*\n/, '')}
+`;
+}
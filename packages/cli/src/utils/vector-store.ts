@@ -0,0 +1,59 @@
+/**
+ * Vector store selection for CLI commands
+ */
+
+import {
+  getVectorIndexDir,
+  isEmbeddedVectorStore,
+  resolveVectorStoreType,
+  HnswOptions,
+  QuantizationOptions,
+  RemoteStoreOptions,
+  VectorStoreType
+} from '@cv-git/core';
+import { CVConfig } from '@cv-git/shared';
+
+/**
+ * The shared index this repository queries (config.index.remote), if any
+ */
+export function remoteIndexOptions(config: CVConfig): RemoteStoreOptions | undefined {
+  const url = config.index?.remote;
+  return url ? { url, token: config.index?.token || process.env.CV_INDEX_TOKEN } : undefined;
+}
+
+/**
+ * createVectorManager() options selecting the repository's vector store.
+ * A shared index serves one branch, so its clients don't namespace by branch.
+ */
+export function vectorStoreOptions(
+  config: CVConfig,
+  repoRoot: string
+): {
+  storeType: VectorStoreType;
+  indexDir: string;
+  hnsw?: HnswOptions;
+  quantization?: QuantizationOptions;
+  aliases?: Record<string, string>;
+  remote?: RemoteStoreOptions;
+  repoRoot: string;
+  branchNamespaces: boolean;
+} {
+  return {
+    storeType: resolveVectorStoreType(config.vector?.provider),
+    indexDir: getVectorIndexDir(repoRoot),
+    hnsw: config.vector?.hnsw,
+    quantization: config.vector?.quantization,
+    aliases: config.vector?.aliases,
+    remote: remoteIndexOptions(config),
+    repoRoot,
+    branchNamespaces: config.vector?.branchNamespaces !== false && !remoteIndexOptions(config)
+  };
+}
+
+/**
+ * True when vectors live under .cv/index or on a shared index server, so no
+ * Qdrant server is needed
+ */
+export function usesEmbeddedVectorStore(config: CVConfig): boolean {
+  return !!remoteIndexOptions(config) || isEmbeddedVectorStore(resolveVectorStoreType(config.vector?.provider));
+}
@@ -0,0 +1,188 @@
+/**
+ * cv watch daemon socket
+ *
+ * A running `cv watch` listens on a local socket (.cv/watch.sock, or a named
+ * pipe on Windows) so other commands can ask it to finish pending syncs
+ * before they query the index. Requests and replies are one JSON object
+ * per line:
+ *
+ *   {"method":"status"}  -> WatchDaemonStatus
+ *   {"method":"flush"}   -> {"ok":true} once no changes are pending
+ *   {"method":"stop"}    -> {"ok":true}, then the daemon exits
+ */
+
+import * as net from 'net';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { createHash } from 'crypto';
+import { getCVDir } from '@cv-git/shared';
+
+export type WatchDaemonMethod = 'status' | 'flush' | 'stop';
+
+export interface WatchDaemonStatus {
+  pid: number;
+  repoRoot: string;
+  startedAt: number;
+  /** Files changed but not yet synced */
+  pending: number;
+  syncing: boolean;
+  syncCount: number;
+  lastSyncAt?: number;
+  lastError?: string;
+}
+
+export interface WatchDaemonHandlers {
+  status(): WatchDaemonStatus;
+  /** Resolve once pending changes are synced */
+  flush(): Promise<void>;
+  stop(): Promise<void>;
+}
+
+/** Unix socket paths are limited to ~104 bytes on macOS */
+const MAX_SOCKET_PATH = 100;
+
+export function getWatchSocketPath(repoRoot: string): string {
+  const hash = createHash('sha256').update(path.resolve(repoRoot)).digest('hex').slice(0, 12);
+  if (process.platform === 'win32') {
+    return `\\\\.\\pipe\\cv-watch-${hash}`;
+  }
+  const local = path.join(getCVDir(repoRoot), 'watch.sock');
+  return local.length <= MAX_SOCKET_PATH ? local : path.join(os.tmpdir(), `cv-watch-${hash}.sock`);
+}
+
+export function getWatchPidPath(repoRoot: string): string {
+  return path.join(getCVDir(repoRoot), 'watch.pid');
+}
+
+export function getWatchLogPath(repoRoot: string): string {
+  return path.join(getCVDir(repoRoot), 'watch.log');
+}
+
+/**
+ * Send one request to the repository's watch daemon.
+ * Resolves null when no daemon is listening or it does not answer in time.
+ */
+export function requestWatchDaemon<T = any>(
+  repoRoot: string,
+  method: WatchDaemonMethod,
+  timeoutMs: number = 2000
+): Promise<T | null> {
+  return new Promise(resolve => {
+    const socket = net.connect(getWatchSocketPath(repoRoot));
+    let buffer = '';
+    let settled = false;
+
+    const finish = (value: T | null) => {
+      if (settled) return;
+      settled = true;
+      clearTimeout(timer);
+      socket.destroy();
+      resolve(value);
+    };
+    const timer = setTimeout(() => finish(null), timeoutMs);
+
+    socket.setEncoding('utf-8');
+    socket.on('connect', () => socket.write(JSON.stringify({ method }) + '\n'));
+    socket.on('data', chunk => {
+      buffer += chunk;
+      const newline = buffer.indexOf('\n');
+      if (newline < 0) return;
+      try {
+        finish(JSON.parse(buffer.slice(0, newline)));
+      } catch {
+        finish(null);
+      }
+    });
+    socket.on('error', () => finish(null));
+    socket.on('close', () => finish(null));
+  });
+}
+
+/**
+ * Wait for a running `cv watch` to sync pending changes, so the caller
+ * queries a fresh index. Returns false when no daemon is running.
+ */
+export async function ensureFreshIndex(repoRoot: string, timeoutMs: number = 30000): Promise<boolean> {
+  const reply = await requestWatchDaemon<{ ok: boolean }>(repoRoot, 'flush', timeoutMs);
+  return reply?.ok === true;
+}
+
+/**
+ * Listen for daemon requests. A socket left behind by a crashed daemon is
+ * removed; a live one means another watcher already serves this repo.
+ */
+export async function startWatchServer(repoRoot: string, handlers: WatchDaemonHandlers): Promise<net.Server> {
+  const socketPath = getWatchSocketPath(repoRoot);
+
+  const server = net.createServer(socket => {
+    let buffer = '';
+    socket.setEncoding('utf-8');
+    socket.on('error', () => socket.destroy());
+    socket.on('data', async chunk => {
+      buffer += chunk;
+      const newline = buffer.indexOf('\n');
+      if (newline < 0) return;
+      const line = buffer.slice(0, newline);
+      buffer = buffer.slice(newline + 1);
+
+      let reply: unknown;
+      let stopAfter = false;
+      try {
+        const { method } = JSON.parse(line) as { method: WatchDaemonMethod };
+        if (method === 'status') {
+          reply = handlers.status();
+        } else if (method === 'flush') {
+          await handlers.flush();
+          reply = { ok: true };
+        } else if (method === 'stop') {
+          reply = { ok: true };
+          stopAfter = true;
+        } else {
+          reply = { ok: false, error: `Unknown method: ${method}` };
+        }
+      } catch (error: any) {
+        reply = { ok: false, error: error.message };
+      }
+
+      // Stop only once the reply is written; stop() exits the process
+      const done = stopAfter ? () => { void handlers.stop(); } : undefined;
+      if (socket.destroyed) {
+        done?.();
+      } else {
+        socket.end(JSON.stringify(reply) + '\n', done);
+      }
+    });
+  });
+
+  const listen = () => new Promise<void>((resolve, reject) => {
+    server.once('error', reject);
+    server.listen(socketPath, () => {
+      server.off('error', reject);
+      resolve();
+    });
+  });
+
+  try {
+    await listen();
+  } catch (error: any) {
+    if (error.code !== 'EADDRINUSE') throw error;
+    if (await requestWatchDaemon(repoRoot, 'status')) {
+      throw new Error('cv watch is already running for this repository (see `cv watch status`)');
+    }
+    if (process.platform !== 'win32') fs.rmSync(socketPath, { force: true });
+    await listen();
+  }
+
+  fs.writeFileSync(getWatchPidPath(repoRoot), String(process.pid));
+  return server;
+}
+
+/**
+ * Close the server and remove the pid and socket files
+ */
+export async function stopWatchServer(repoRoot: string, server: net.Server): Promise<void> {
+  await new Promise<void>(resolve => server.close(() => resolve()));
+  fs.rmSync(getWatchPidPath(repoRoot), { force: true });
+  if (process.platform !== 'win32') fs.rmSync(getWatchSocketPath(repoRoot), { force: true });
+}
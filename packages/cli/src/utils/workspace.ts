@@ -0,0 +1,86 @@
+/**
+ * Workspace indexes for `--workspace` queries
+ * Opens the vector index of every registered sibling repository with that
+ * repository's own configuration and embedding model.
+ */
+
+import chalk from 'chalk';
+import {
+  ConfigManager,
+  createVectorManager,
+  listWorkspaceMembers,
+  VectorManager,
+  WorkspaceIndex,
+  WorkspaceMember
+} from '@cv-git/core';
+import { CVConfig } from '@cv-git/shared';
+import { resolveEmbeddingProvider } from './embedding-provider.js';
+import { vectorStoreOptions } from './vector-store.js';
+import type { SourceLocation } from './citations.js';
+
+export interface OpenWorkspace {
+  members: WorkspaceMember[];
+  indexes: WorkspaceIndex[];
+  close(): Promise<void>;
+}
+
+/**
+ * Connect to the indexes of the repositories in `workspace.repos`.
+ * Repositories that are missing, not initialized or unreachable are skipped
+ * with a warning.
+ */
+export async function openWorkspaceIndexes(
+  repoRoot: string,
+  config: CVConfig,
+  options: { silent?: boolean } = {}
+): Promise<OpenWorkspace> {
+  const members = await listWorkspaceMembers(repoRoot, config.workspace?.repos);
+  const warn = (message: string) => {
+    if (!options.silent) console.error(chalk.gray(`  ⚠ ${message}`));
+  };
+
+  const opened: Array<{ name: string; vector: VectorManager }> = [];
+  for (const member of members) {
+    if (!member.exists) {
+      warn(`${member.name}: ${member.root} is not a git repository - skipped`);
+      continue;
+    }
+    if (!member.initialized) {
+      warn(`${member.name}: not initialized (run \`cv init\` and \`cv sync\` there) - skipped`);
+      continue;
+    }
+    try {
+      const memberConfig = await new ConfigManager().read(member.root);
+      const provider = await resolveEmbeddingProvider(memberConfig);
+      const vector = createVectorManager({
+        url: memberConfig.vector.url,
+        ...vectorStoreOptions(memberConfig, member.root),
+        provider,
+        collections: memberConfig.vector.collections
+      });
+      await vector.connect();
+      opened.push({ name: member.name, vector });
+    } catch (error: any) {
+      warn(`${member.name}: ${error.message} - skipped`);
+    }
+  }
+
+  return {
+    members,
+    indexes: opened,
+    close: async () => {
+      await Promise.all(opened.map(index => index.vector.close().catch(() => undefined)));
+    }
+  };
+}
+
+/**
+ * Sources with the repository root their paths are relative to, so
+ * citations of workspace repositories open the right file
+ */
+export function withWorkspaceRoots<T extends SourceLocation>(sources: T[], members: WorkspaceMember[]): T[] {
+  const roots = new Map(members.map(member => [member.name, member.root]));
+  return sources.map(source => source.repo && roots.has(source.repo)
+    ? { ...source, root: roots.get(source.repo) }
+    : source);
+}
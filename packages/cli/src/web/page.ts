@@ -0,0 +1,322 @@
+/**
+ * cv serve single-page UI
+ *
+ * Plain HTML, CSS and JavaScript with no build step or external assets, so
+ * the page works offline and ships inside the CLI bundle. Written with
+ * String.raw so regular expressions keep their backslashes; the script avoids
+ * backticks and template placeholders.
+ */
+
+export const WEB_PAGE = String.raw`<!doctype html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>cv-git</title>
+<style>
+  :root { --bg: #0f1117; --panel: #171a23; --line: #262b38; --text: #d7dbe6; --dim: #8a91a5; --accent: #5cc8ff; --ok: #56d364; --warn: #e3b341; --bad: #f85149; }
+  * { box-sizing: border-box; }
+  body { margin: 0; font: 14px/1.5 system-ui, sans-serif; background: var(--bg); color: var(--text); }
+  header { display: flex; align-items: center; gap: 24px; padding: 10px 20px; border-bottom: 1px solid var(--line); background: var(--panel); }
+  header h1 { font-size: 16px; margin: 0; }
+  nav button { background: none; border: 0; color: var(--dim); font: inherit; padding: 6px 10px; cursor: pointer; border-radius: 6px; }
+  nav button.active { color: var(--text); background: var(--line); }
+  main { padding: 20px; max-width: 1200px; margin: 0 auto; }
+  section { display: none; }
+  section.active { display: block; }
+  input, textarea { width: 100%; background: var(--panel); color: var(--text); border: 1px solid var(--line); border-radius: 6px; padding: 9px 12px; font: inherit; }
+  input:focus, textarea:focus { outline: 1px solid var(--accent); }
+  a { color: var(--accent); cursor: pointer; text-decoration: none; }
+  .chunk { border: 1px solid var(--line); border-radius: 6px; margin: 12px 0; overflow: hidden; }
+  .chunk-head { display: flex; justify-content: space-between; padding: 6px 12px; background: var(--panel); border-bottom: 1px solid var(--line); }
+  .dim { color: var(--dim); }
+  pre { margin: 0; padding: 10px 0; overflow-x: auto; font: 12.5px/1.5 ui-monospace, monospace; }
+  pre .ln { display: inline-block; width: 4em; padding-right: 1em; text-align: right; color: var(--dim); user-select: none; }
+  .kw { color: #ff7b72; } .str { color: #a5d6ff; } .num { color: #79c0ff; } .com { color: #8b949e; font-style: italic; }
+  .browse { display: grid; grid-template-columns: 320px 1fr; gap: 20px; }
+  .files { max-height: calc(100vh - 160px); overflow-y: auto; }
+  .files div { padding: 3px 8px; border-radius: 4px; cursor: pointer; white-space: nowrap; overflow: hidden; text-overflow: ellipsis; }
+  .files div:hover, .files div.active { background: var(--line); }
+  .messages { min-height: 300px; }
+  .msg { padding: 10px 14px; border-radius: 8px; margin: 10px 0; white-space: pre-wrap; }
+  .msg.user { background: var(--line); }
+  .msg.assistant { border: 1px solid var(--line); }
+  .msg pre { background: var(--panel); border-radius: 6px; padding: 8px 12px; white-space: pre; }
+  .msg code { background: var(--panel); padding: 1px 4px; border-radius: 3px; }
+  .sources { font-size: 12px; margin-top: 8px; }
+  .cards { display: grid; grid-template-columns: repeat(auto-fill, minmax(260px, 1fr)); gap: 16px; }
+  .card { background: var(--panel); border: 1px solid var(--line); border-radius: 8px; padding: 14px 16px; }
+  .card h3 { margin: 0 0 8px; font-size: 13px; color: var(--dim); font-weight: 500; text-transform: uppercase; letter-spacing: .04em; }
+  .big { font-size: 26px; font-weight: 600; }
+  .ok { color: var(--ok); } .warn { color: var(--warn); } .bad { color: var(--bad); }
+  table { width: 100%; border-collapse: collapse; font-size: 13px; }
+  td, th { text-align: left; padding: 4px 8px; border-bottom: 1px solid var(--line); }
+</style>
+</head>
+<body>
+<header>
+  <h1>cv-git</h1>
+  <nav>
+    <button data-tab="search" class="active">Search</button>
+    <button data-tab="browse">Browse</button>
+    <button data-tab="chat">Chat</button>
+    <button data-tab="health">Index health</button>
+  </nav>
+</header>
+<main>
+  <section id="search" class="active">
+    <input id="query" placeholder="Search the codebase: a question, identifiers or both, then Enter" autofocus>
+    <p id="search-status" class="dim"></p>
+    <div id="results"></div>
+  </section>
+  <section id="browse">
+    <div class="browse">
+      <div>
+        <input id="file-filter" placeholder="Filter files">
+        <div id="files" class="files"></div>
+      </div>
+      <div id="file-chunks"><p class="dim">Pick a file to see its indexed chunks.</p></div>
+    </div>
+  </section>
+  <section id="chat">
+    <div id="messages" class="messages"><p class="dim">Answers come from the indexed code and cite it as [1], [2], ... - click a citation to open the code.</p></div>
+    <textarea id="chat-input" rows="3" placeholder="Ask about the codebase (Enter to send, Shift+Enter for a new line)"></textarea>
+  </section>
+  <section id="health"><div id="health-cards" class="cards"></div></section>
+</main>
+<script>
+(function () {
+  var $ = function (id) { return document.getElementById(id); };
+  var KEYWORDS = new Set(('abstract as async await break case catch class const continue def default defer del delete do elif else enum ' +
+    'except export extends false final finally fn for from func function go if impl import in interface is lambda let loop match mod ' +
+    'mut new nil None not null or package pass private protected pub public raise return self static struct super switch this throw ' +
+    'trait true True False try type typeof use val var void where while with yield').split(' '));
+  var HASH_COMMENTS = /^(python|ruby|shell|bash|yaml|toml|perl|r|elixir|makefile)$/;
+
+  function escapeHtml(text) {
+    return text.replace(/&/g, '&amp;').replace(/</g, '&lt;').replace(/>/g, '&gt;').replace(/"/g, '&quot;');
+  }
+
+  // Comments, strings, numbers and keywords: enough to make code readable
+  function highlight(code, language) {
+    var comment = HASH_COMMENTS.test(language || '') ? '#[^\n]*|' : '';
+    var re = new RegExp('(' + comment + '\\/\\/[^\\n]*|\\/\\*[\\s\\S]*?\\*\\/)|' +
+      '("(?:[^"\\\\\\n]|\\\\.)*"|\'(?:[^\'\\\\\\n]|\\\\.)*\'|\x60(?:[^\x60\\\\]|\\\\.)*\x60)|' +
+      '(\\b\\d[\\d_.xXa-fA-F]*\\b)|([A-Za-z_$][\\w$]*)', 'g');
+    var out = '', last = 0, m;
+    while ((m = re.exec(code))) {
+      out += escapeHtml(code.slice(last, m.index));
+      var text = escapeHtml(m[0]);
+      if (m[1]) out += '<span class="com">' + text + '</span>';
+      else if (m[2]) out += '<span class="str">' + text + '</span>';
+      else if (m[3]) out += '<span class="num">' + text + '</span>';
+      else if (KEYWORDS.has(m[4])) out += '<span class="kw">' + text + '</span>';
+      else out += text;
+      last = re.lastIndex;
+    }
+    return out + escapeHtml(code.slice(last));
+  }
+
+  // Highlight the whole chunk (comments span lines), then number the lines
+  function codeBlock(text, startLine, language) {
+    return '<pre>' + highlight(text, language).split('\n').map(function (line, i) {
+      return '<span class="ln">' + (startLine + i) + '</span>' + line;
+    }).join('\n') + '</pre>';
+  }
+
+  function chunkCard(chunk) {
+    var title = escapeHtml(chunk.file) + ':' + chunk.startLine + '-' + chunk.endLine +
+      (chunk.symbolName ? ' <span class="dim">' + escapeHtml(chunk.symbolName) + (chunk.symbolKind ? ' (' + escapeHtml(chunk.symbolKind) + ')' : '') + '</span>' : '');
+    var score = chunk.score !== undefined ? '<span class="dim">' + Math.round(chunk.score * 100) + '%</span>' : '';
+    return '<div class="chunk" id="L' + chunk.startLine + '"><div class="chunk-head"><a data-file="' + escapeHtml(chunk.file) +
+      '" data-line="' + chunk.startLine + '">' + title + '</a>' + score + '</div>' + codeBlock(chunk.text, chunk.startLine, chunk.language) + '</div>';
+  }
+
+  async function api(path) {
+    var res = await fetch(path);
+    var body = await res.json();
+    if (!res.ok) throw new Error(body.error || res.statusText);
+    return body;
+  }
+
+  function showTab(name) {
+    document.querySelectorAll('nav button').forEach(function (b) { b.classList.toggle('active', b.dataset.tab === name); });
+    document.querySelectorAll('section').forEach(function (s) { s.classList.toggle('active', s.id === name); });
+    if (name === 'browse' && !filesLoaded) loadFiles();
+    if (name === 'health') loadHealth();
+  }
+  document.querySelectorAll('nav button').forEach(function (b) { b.onclick = function () { showTab(b.dataset.tab); }; });
+
+  // Any link to a file location opens it in Browse
+  document.body.addEventListener('click', function (event) {
+    var link = event.target.closest('a[data-file]');
+    if (!link) return;
+    event.preventDefault();
+    openFile(link.dataset.file, parseInt(link.dataset.line, 10));
+  });
+
+  // Search
+  $('query').addEventListener('keydown', async function (event) {
+    if (event.key !== 'Enter' || !this.value.trim()) return;
+    $('search-status').textContent = 'Searching...';
+    try {
+      var body = await api('/api/search?q=' + encodeURIComponent(this.value.trim()));
+      $('search-status').textContent = body.results.length + ' result' + (body.results.length === 1 ? '' : 's');
+      $('results').innerHTML = body.results.map(chunkCard).join('');
+    } catch (error) {
+      $('search-status').textContent = 'Search failed: ' + error.message;
+    }
+  });
+
+  // Browse
+  var files = [], filesLoaded = false;
+  async function loadFiles() {
+    try {
+      files = (await api('/api/files')).files;
+      filesLoaded = true;
+      renderFiles();
+    } catch (error) {
+      $('files').innerHTML = '<p class="bad">' + escapeHtml(error.message) + '</p>';
+    }
+  }
+  function renderFiles(active) {
+    var filter = $('file-filter').value.toLowerCase();
+    $('files').innerHTML = files.filter(function (f) { return f.file.toLowerCase().includes(filter); }).slice(0, 2000).map(function (f) {
+      return '<div data-path="' + escapeHtml(f.file) + '"' + (f.file === active ? ' class="active"' : '') + ' title="' + escapeHtml(f.file) + '">' +
+        escapeHtml(f.file) + ' <span class="dim">' + f.chunks + '</span></div>';
+    }).join('');
+  }
+  $('file-filter').addEventListener('input', function () { renderFiles(); });
+  $('files').addEventListener('click', function (event) {
+    var row = event.target.closest('div[data-path]');
+    if (row) openFile(row.dataset.path);
+  });
+  async function openFile(file, line) {
+    showTab('browse');
+    renderFiles(file);
+    $('file-chunks').innerHTML = '<p class="dim">Loading...</p>';
+    try {
+      var chunks = (await api('/api/chunks?file=' + encodeURIComponent(file))).chunks;
+      $('file-chunks').innerHTML = '<h3>' + escapeHtml(file) + '</h3>' + chunks.map(chunkCard).join('');
+      var target = line && chunks.filter(function (c) { return c.startLine <= line && line <= c.endLine; })[0];
+      if (target) $('L' + target.startLine).scrollIntoView({ block: 'start' });
+    } catch (error) {
+      $('file-chunks').innerHTML = '<p class="bad">' + escapeHtml(error.message) + '</p>';
+    }
+  }
+
+  // Chat, streamed as server-sent events
+  var history = [], chatting = false;
+  function renderAnswer(text, sources) {
+    var byIndex = {};
+    sources.forEach(function (s) { byIndex[s.index] = s; });
+    var cite = function (html) {
+      return html.replace(/\[(\d+)\]/g, function (all, n) {
+        var s = byIndex[n];
+        return s ? '<a data-file="' + escapeHtml(s.file) + '" data-line="' + s.startLine + '" title="' + escapeHtml(s.file + ':' + s.startLine) + '">[' + n + ']</a>' : all;
+      });
+    };
+    return text.split(/\x60\x60\x60/).map(function (part, i) {
+      if (i % 2 === 1) {
+        var newline = part.indexOf('\n');
+        var language = newline > 0 ? part.slice(0, newline).trim() : '';
+        return '<pre>' + highlight(newline >= 0 ? part.slice(newline + 1) : part, language) + '</pre>';
+      }
+      return cite(escapeHtml(part).replace(/\x60([^\x60\n]+)\x60/g, '<code>$1</code>'));
+    }).join('');
+  }
+  function addMessage(role) {
+    if (history.length === 0) $('messages').innerHTML = '';
+    var el = document.createElement('div');
+    el.className = 'msg ' + role;
+    $('messages').appendChild(el);
+    return el;
+  }
+  async function ask(question) {
+    chatting = true;
+    history.push({ role: 'user', content: question });
+    addMessage('user').textContent = question;
+    var el = addMessage('assistant');
+    var answer = '', sources = [];
+    el.innerHTML = '<span class="dim">Thinking...</span>';
+    try {
+      var res = await fetch('/api/chat', { method: 'POST', headers: { 'Content-Type': 'application/json' }, body: JSON.stringify({ messages: history }) });
+      if (!res.ok) throw new Error((await res.json()).error || res.statusText);
+      var reader = res.body.getReader(), decoder = new TextDecoder(), buffer = '';
+      for (;;) {
+        var read = await reader.read();
+        if (read.done) break;
+        buffer += decoder.decode(read.value, { stream: true });
+        var events = buffer.split('\n\n');
+        buffer = events.pop();
+        events.forEach(function (raw) {
+          var event = (raw.match(/^event: (.*)$/m) || [])[1];
+          var data = JSON.parse((raw.match(/^data: (.*)$/m) || [])[1] || 'null');
+          if (event === 'sources') sources = data;
+          else if (event === 'token') answer += data;
+          else if (event === 'error') throw new Error(data.message);
+        });
+        el.innerHTML = renderAnswer(answer, sources);
+      }
+      if (sources.length > 0) {
+        el.innerHTML += '<div class="sources dim">Sources: ' + sources.map(function (s) {
+          return '<a data-file="' + escapeHtml(s.file) + '" data-line="' + s.startLine + '">[' + s.index + '] ' + escapeHtml(s.file) + ':' + s.startLine + '-' + s.endLine + '</a>';
+        }).join(' · ') + '</div>';
+      }
+      history.push({ role: 'assistant', content: answer });
+    } catch (error) {
+      history.pop();
+      el.innerHTML = '<span class="bad">' + escapeHtml(error.message) + '</span>';
+    }
+    chatting = false;
+  }
+  $('chat-input').addEventListener('keydown', function (event) {
+    if (event.key !== 'Enter' || event.shiftKey) return;
+    event.preventDefault();
+    var question = this.value.trim();
+    if (!question || chatting) return;
+    this.value = '';
+    ask(question);
+  });
+
+  // Index health
+  function ago(timestamp) {
+    var minutes = Math.round((Date.now() - timestamp) / 60000);
+    if (minutes < 60) return minutes + ' min ago';
+    if (minutes < 48 * 60) return Math.round(minutes / 60) + ' h ago';
+    return Math.round(minutes / 1440) + ' days ago';
+  }
+  function card(title, html) { return '<div class="card"><h3>' + title + '</h3>' + html + '</div>'; }
+  async function loadHealth() {
+    try {
+      var h = await api('/api/health');
+      var languages = Object.keys(h.index.languages).sort(function (a, b) { return h.index.languages[b] - h.index.languages[a]; });
+      var sync = h.lastSync;
+      var cards = [
+        card('Indexed chunks', '<div class="big">' + h.index.chunks + '</div><div class="dim">' + h.index.files + ' files</div>'),
+        card('Languages', '<table>' + languages.slice(0, 8).map(function (l) { return '<tr><td>' + escapeHtml(l) + '</td><td>' + h.index.languages[l] + '</td></tr>'; }).join('') + '</table>'),
+        card('Vector store', h.vector.connected
+          ? '<div class="big ok">Connected</div><div class="dim">' + escapeHtml(h.vector.model) + ' · ' + h.vector.dimensions + ' dims' + (h.vector.points !== undefined ? ' · ' + h.vector.points + ' vectors' : '') + '</div>'
+          : '<div class="big bad">Unavailable</div><div class="dim">' + escapeHtml(h.vector.error || '') + ' - search uses keywords only</div>'),
+        card('Last sync', sync
+          ? '<div class="big ' + (sync.success ? 'ok' : 'bad') + '">' + ago(sync.timestamp) + '</div><div class="dim">' + sync.type + ' · ' + (sync.duration / 1000).toFixed(1) + 's · ' + sync.errors + ' errors</div>'
+          : '<div class="big warn">Never</div><div class="dim">Run cv sync</div>'),
+        card('Changed since sync', '<div class="big ' + (h.stale.modified + h.stale.deleted > 0 ? 'warn' : 'ok') + '">' + (h.stale.modified + h.stale.deleted) + '</div><div class="dim">' +
+          h.stale.modified + ' modified, ' + h.stale.deleted + ' deleted</div>' + h.stale.examples.map(function (f) { return '<div class="dim">' + escapeHtml(f) + '</div>'; }).join('')),
+        card('Review findings', '<div class="big">' + h.findings.open + ' open</div><div class="dim">' + h.findings.accepted + ' accepted, ' + h.findings.dismissed + ' dismissed</div>'),
+      ];
+      if (h.recentSyncs.length > 0) {
+        cards.push(card('Recent syncs', '<table><tr><th>When</th><th>Type</th><th>Files</th><th>Time</th></tr>' + h.recentSyncs.map(function (s) {
+          return '<tr><td class="' + (s.success ? '' : 'bad') + '">' + ago(s.timestamp) + '</td><td>' + s.type + '</td><td>' + s.filesProcessed + '</td><td>' + (s.duration / 1000).toFixed(1) + 's</td></tr>';
+        }).join('') + '</table>'));
+      }
+      $('health-cards').innerHTML = cards.join('');
+    } catch (error) {
+      $('health-cards').innerHTML = '<p class="bad">' + escapeHtml(error.message) + '</p>';
+    }
+  }
+})();
+</script>
+</body>
+</html>
+`;
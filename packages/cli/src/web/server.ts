@@ -0,0 +1,234 @@
+/**
+ * cv serve web server
+ *
+ * A local HTTP server for teammates who prefer a browser to the CLI:
+ *   GET  /                     the single-page UI (web/page.ts)
+ *   GET  /api/search?q=&limit= ranked hybrid search
+ *   GET  /api/files            indexed files with their chunk counts
+ *   GET  /api/chunks?file=     a file's indexed chunks, in file order
+ *   GET  /api/health           index health for the dashboard
+ *   POST /api/chat             {messages} -> server-sent events: `sources`
+ *                              (the numbered citations), `token`..., `done`
+ *                              or `error`
+ *
+ * Like the language server, it knows nothing about vectors or models and
+ * calls a WebBackend. Requests whose Host header names another site are
+ * refused (DNS rebinding), as are cross-origin posts, so a web page the user
+ * visits can't query the index through their browser.
+ */
+
+import * as http from 'http';
+import { WEB_PAGE } from './page.js';
+
+export interface WebChunk {
+  file: string;
+  startLine: number;
+  endLine: number;
+  symbolName?: string;
+  symbolKind?: string;
+  language: string;
+  /** Fused rank score for search hits; absent when browsing */
+  score?: number;
+  text: string;
+}
+
+export interface WebFile {
+  file: string;
+  language: string;
+  chunks: number;
+}
+
+export interface WebChatMessage {
+  role: 'user' | 'assistant';
+  content: string;
+}
+
+export interface WebCitation {
+  /** As cited in the answer: [1], [2], ... */
+  index: number;
+  file: string;
+  startLine: number;
+  endLine: number;
+  symbolName?: string;
+}
+
+export interface WebChatHandlers {
+  /** Called once, before the first token */
+  onSources(sources: WebCitation[]): void;
+  onToken(token: string): void;
+}
+
+export interface WebBackend {
+  search(query: string, limit: number): Promise<WebChunk[]>;
+  files(): Promise<WebFile[]>;
+  chunks(file: string): Promise<WebChunk[]>;
+  health(): Promise<Record<string, unknown>>;
+  /** Answer the last user message; resolves with the full answer */
+  chat(messages: WebChatMessage[], handlers: WebChatHandlers): Promise<string>;
+}
+
+export interface WebServerOptions {
+  backend: WebBackend;
+  /** Address the server is bound to; with 0.0.0.0 or :: any Host is accepted */
+  host: string;
+}
+
+/** Largest request body accepted (chat history) */
+const MAX_BODY_BYTES = 1024 * 1024;
+
+const LOCAL_HOSTS = new Set(['localhost', '127.0.0.1', '[::1]', '::1']);
+
+class HttpError extends Error {
+  constructor(readonly status: number, message: string) {
+    super(message);
+  }
+}
+
+/**
+ * Whether a Host header names this server rather than some other site
+ */
+export function isAllowedHost(hostHeader: string | undefined, boundHost: string): boolean {
+  if (boundHost === '0.0.0.0' || boundHost === '::') return true;
+  if (!hostHeader) return false;
+  const name = hostHeader.replace(/:\d+$/, '').toLowerCase();
+  return LOCAL_HOSTS.has(name) || name === boundHost.toLowerCase();
+}
+
+function sendJson(res: http.ServerResponse, status: number, body: unknown): void {
+  res.writeHead(status, { 'Content-Type': 'application/json; charset=utf-8', 'Cache-Control': 'no-store' });
+  res.end(JSON.stringify(body));
+}
+
+async function readJsonBody(req: http.IncomingMessage): Promise<any> {
+  if (!/^application\/json\b/.test(req.headers['content-type'] ?? '')) {
+    throw new HttpError(415, 'Expected an application/json body');
+  }
+  const parts: Buffer[] = [];
+  let size = 0;
+  for await (const part of req) {
+    size += part.length;
+    if (size > MAX_BODY_BYTES) throw new HttpError(413, 'Request body too large');
+    parts.push(part);
+  }
+  try {
+    return JSON.parse(Buffer.concat(parts).toString('utf-8'));
+  } catch {
+    throw new HttpError(400, 'Malformed JSON body');
+  }
+}
+
+function parseMessages(body: any): WebChatMessage[] {
+  const messages = Array.isArray(body?.messages) ? body.messages : null;
+  if (!messages || messages.length === 0) throw new HttpError(400, 'Expected {"messages": [...]}');
+  for (const message of messages) {
+    if ((message?.role !== 'user' && message?.role !== 'assistant') || typeof message.content !== 'string') {
+      throw new HttpError(400, 'Each message needs a role (user or assistant) and string content');
+    }
+  }
+  if (messages[messages.length - 1].role !== 'user') throw new HttpError(400, 'The last message must be from the user');
+  return messages.map((message: WebChatMessage) => ({ role: message.role, content: message.content }));
+}
+
+/**
+ * Stream a chat answer as server-sent events
+ */
+async function streamChat(res: http.ServerResponse, backend: WebBackend, messages: WebChatMessage[]): Promise<void> {
+  res.writeHead(200, {
+    'Content-Type': 'text/event-stream; charset=utf-8',
+    'Cache-Control': 'no-store',
+    Connection: 'keep-alive',
+  });
+  const send = (event: string, data: unknown) => {
+    if (!res.writableEnded) res.write(`event: ${event}\ndata: ${JSON.stringify(data)}\n\n`);
+  };
+
+  try {
+    const answer = await backend.chat(messages, {
+      onSources: sources => send('sources', sources),
+      onToken: token => send('token', token),
+    });
+    send('done', { answer });
+  } catch (error: any) {
+    send('error', { message: error.message });
+  }
+  res.end();
+}
+
+/**
+ * Request handler for the web UI and its API
+ */
+export function createWebHandler(options: WebServerOptions): http.RequestListener {
+  const { backend } = options;
+
+  return async (req, res) => {
+    const url = new URL(req.url ?? '/', 'http://localhost');
+    try {
+      if (!isAllowedHost(req.headers.host, options.host)) {
+        throw new HttpError(403, 'Unexpected Host header');
+      }
+      const route = `${req.method} ${url.pathname}`;
+
+      if (req.method === 'POST') {
+        const origin = req.headers.origin;
+        if (origin && origin !== `http://${req.headers.host}`) {
+          throw new HttpError(403, 'Cross-origin requests are not allowed');
+        }
+      }
+
+      switch (route) {
+        case 'GET /':
+          res.writeHead(200, { 'Content-Type': 'text/html; charset=utf-8', 'Cache-Control': 'no-store' });
+          res.end(WEB_PAGE);
+          return;
+
+        case 'GET /api/search': {
+          const query = url.searchParams.get('q')?.trim();
+          if (!query) throw new HttpError(400, 'Missing q');
+          const limit = Math.min(100, Math.max(1, parseInt(url.searchParams.get('limit') ?? '', 10) || 20));
+          sendJson(res, 200, { query, results: await backend.search(query, limit) });
+          return;
+        }
+
+        case 'GET /api/files':
+          sendJson(res, 200, { files: await backend.files() });
+          return;
+
+        case 'GET /api/chunks': {
+          const file = url.searchParams.get('file');
+          if (!file) throw new HttpError(400, 'Missing file');
+          const chunks = await backend.chunks(file);
+          if (chunks.length === 0) throw new HttpError(404, `${file} is not indexed`);
+          sendJson(res, 200, { file, chunks });
+          return;
+        }
+
+        case 'GET /api/health':
+          sendJson(res, 200, await backend.health());
+          return;
+
+        case 'POST /api/chat':
+          await streamChat(res, backend, parseMessages(await readJsonBody(req)));
+          return;
+
+        default:
+          throw new HttpError(404, 'Not found');
+      }
+    } catch (error: any) {
+      const status = error instanceof HttpError ? error.status : 500;
+      if (!res.headersSent) sendJson(res, status, { error: error.message });
+      else res.end();
+    }
+  };
+}
+
+/**
+ * Listen on host:port; resolves with the server once it is accepting connections
+ */
+export async function startWebServer(options: WebServerOptions & { port: number }): Promise<http.Server> {
+  const server = http.createServer(createWebHandler(options));
+  await new Promise<void>((resolve, reject) => {
+    server.once('error', reject);
+    server.listen(options.port, options.host, () => resolve());
+  });
+  return server;
+}
@@ -0,0 +1,304 @@
+/**
+ * Agent Mode
+ *
+ * `cv do --plan` breaks a task into explicit steps, then executes them one
+ * at a time:
+ *
+ *   read  load files into the working notes the later steps are given
+ *   edit  ask the model for edit blocks for this step only, preview the
+ *         patch and apply it atomically (recorded as a change set)
+ *   run   run a shell command and keep its output in the notes
+ *
+ * Every step needs approval (or "all" for the rest of the run), and every
+ * decision and result is appended to `.cv/agent/<run-id>.jsonl`.
+ */
+
+import { promises as fs } from 'fs';
+import * as path from 'path';
+import * as crypto from 'crypto';
+import { getCVDir } from '@cv-git/shared';
+import { createEditParser } from '../code/edit-parser.js';
+import { Patch, PatchEngine, PatchError } from '../code/patch-engine.js';
+import { recordChangeSet } from '../code/changesets.js';
+
+export type AgentStepKind = 'read' | 'edit' | 'run';
+
+export const AGENT_STEP_KINDS: AgentStepKind[] = ['read', 'edit', 'run'];
+
+export interface AgentStep {
+  /** 1-based position in the plan */
+  index: number;
+  kind: AgentStepKind;
+  description: string;
+  /** Files to read, or files the edit is expected to touch */
+  files: string[];
+  /** Shell command for run steps */
+  command?: string;
+}
+
+export interface AgentPlan {
+  task: string;
+  steps: AgentStep[];
+}
+
+/** 'all' approves this step and every one after it */
+export type StepDecision = 'approve' | 'skip' | 'abort' | 'all';
+
+export interface CommandResult {
+  exitCode: number;
+  output: string;
+}
+
+export interface StepOutcome {
+  step: AgentStep;
+  status: 'done' | 'skipped' | 'failed';
+  /** Files changed by an edit step */
+  files?: string[];
+  /** Change set to pass to `cv undo` */
+  changeSet?: string;
+  error?: string;
+}
+
+export interface AgentRunResult {
+  runId: string;
+  outcomes: StepOutcome[];
+  /** False when the user aborted or a step failed before the end of the plan */
+  completed: boolean;
+  /** Audit log for the run */
+  logFile: string;
+}
+
+export type AgentEvent =
+  | { type: 'step-start'; step: AgentStep }
+  | { type: 'step-preview'; step: AgentStep; patch: Patch }
+  | { type: 'step-end'; outcome: StepOutcome };
+
+export interface AgentRunnerOptions {
+  repoRoot: string;
+  /** Model reply with edit blocks for one edit step, given the notes so far */
+  generateEdit: (step: AgentStep, notes: string) => Promise<string>;
+  runCommand: (command: string) => Promise<CommandResult>;
+  /** Asked before a read or run step, and after the preview of an edit step */
+  approve: (step: AgentStep, patch?: Patch) => Promise<StepDecision>;
+  onEvent?: (event: AgentEvent) => void;
+  /** Files in the patch with uncommitted changes; edits to them are refused */
+  uncommitted?: (patch: Patch) => Promise<string[]>;
+  /** HEAD when the run started, stored with each change set */
+  head?: string;
+}
+
+/** Longest file or command output kept in the notes */
+const MAX_NOTE_CHARS = 20000;
+
+/**
+ * Parse the model's step plan. A reply without a usable plan becomes a
+ * single edit step for the whole task, so the run can still proceed.
+ */
+export function parseAgentPlan(response: string, task: string): AgentPlan {
+  const fenced = response.match(/```(?:json)?\s*\n([\s\S]*?)\n```/);
+  const candidate = fenced ? fenced[1] : response.match(/\{[\s\S]*\}/)?.[0];
+
+  let parsed: any;
+  try {
+    parsed = candidate ? JSON.parse(candidate) : undefined;
+  } catch {
+    parsed = undefined;
+  }
+
+  const steps: AgentStep[] = [];
+  for (const raw of Array.isArray(parsed?.steps) ? parsed.steps : []) {
+    if (!raw || typeof raw !== 'object') continue;
+    const kind = String(raw.kind ?? raw.type ?? '').toLowerCase() as AgentStepKind;
+    if (!AGENT_STEP_KINDS.includes(kind)) continue;
+    const files = (Array.isArray(raw.files) ? raw.files : raw.file ? [raw.file] : [])
+      .map((f: unknown) => String(f).trim())
+      .filter(Boolean);
+    const command = typeof raw.command === 'string' ? raw.command.trim() : '';
+    if (kind === 'read' && files.length === 0) continue;
+    if (kind === 'run' && !command) continue;
+    steps.push({
+      index: steps.length + 1,
+      kind,
+      description: String(raw.description || (kind === 'run' ? command : files.join(', '))).trim(),
+      files,
+      ...(kind === 'run' ? { command } : {})
+    });
+  }
+
+  if (steps.length === 0) {
+    steps.push({ index: 1, kind: 'edit', description: task, files: [] });
+  }
+  return { task, steps };
+}
+
+function clip(text: string): string {
+  return text.length > MAX_NOTE_CHARS
+    ? `${text.slice(0, MAX_NOTE_CHARS)}\n... (${text.length - MAX_NOTE_CHARS} more characters)`
+    : text;
+}
+
+/**
+ * Append-only JSONL log of an agent run
+ */
+export class AgentAuditLog {
+  readonly file: string;
+
+  constructor(repoRoot: string, readonly runId: string) {
+    this.file = path.join(getCVDir(repoRoot), 'agent', `${runId}.jsonl`);
+  }
+
+  async record(type: string, data: Record<string, unknown> = {}): Promise<void> {
+    await fs.mkdir(path.dirname(this.file), { recursive: true });
+    await fs.appendFile(this.file, JSON.stringify({ time: new Date().toISOString(), type, ...data }) + '\n');
+  }
+}
+
+/**
+ * Executes an agent plan step by step
+ */
+export class AgentRunner {
+  private engine: PatchEngine;
+  private notes: string[] = [];
+  /** Files this run already changed; they may be edited again */
+  private touched = new Set<string>();
+
+  constructor(private options: AgentRunnerOptions) {
+    this.engine = new PatchEngine(options.repoRoot);
+  }
+
+  async run(plan: AgentPlan): Promise<AgentRunResult> {
+    const runId = `${Date.now().toString(36)}${crypto.randomBytes(2).toString('hex')}`;
+    const log = new AgentAuditLog(this.options.repoRoot, runId);
+    const outcomes: StepOutcome[] = [];
+    let approveAll = false;
+    let completed = true;
+
+    await log.record('plan', { task: plan.task, steps: plan.steps });
+
+    for (const step of plan.steps) {
+      this.options.onEvent?.({ type: 'step-start', step });
+
+      const ask = async (patch?: Patch): Promise<StepDecision> => {
+        if (approveAll) return 'approve';
+        const decision = await this.options.approve(step, patch);
+        await log.record('decision', { step: step.index, decision });
+        if (decision === 'all') approveAll = true;
+        return decision;
+      };
+
+      let outcome: StepOutcome | null;
+      try {
+        if (step.kind === 'edit') {
+          outcome = await this.editStep(plan, step, ask, log);
+        } else {
+          const decision = await ask();
+          outcome = decision === 'abort'
+            ? null
+            : decision === 'skip'
+              ? { step, status: 'skipped' }
+              : step.kind === 'read' ? await this.readStep(step) : await this.runStep(step);
+        }
+      } catch (error: any) {
+        outcome = { step, status: 'failed', error: error.message };
+      }
+
+      const aborted = outcome === null;
+      if (outcome === null) outcome = { step, status: 'skipped' };
+      outcomes.push(outcome);
+      await log.record('step', {
+        step: step.index,
+        kind: step.kind,
+        status: outcome.status,
+        ...(outcome.files ? { files: outcome.files } : {}),
+        ...(outcome.changeSet ? { changeSet: outcome.changeSet } : {}),
+        ...(outcome.error ? { error: outcome.error } : {})
+      });
+      this.options.onEvent?.({ type: 'step-end', outcome });
+
+      if (aborted || outcome.status === 'failed') {
+        completed = false;
+        break;
+      }
+    }
+
+    await log.record('end', { completed });
+    return { runId, outcomes, completed, logFile: log.file };
+  }
+
+  private async readStep(step: AgentStep): Promise<StepOutcome> {
+    for (const file of step.files) {
+      try {
+        const content = await fs.readFile(path.resolve(this.options.repoRoot, file), 'utf-8');
+        this.notes.push(`### ${file}\n\`\`\`\n${clip(content)}\n\`\`\``);
+      } catch (error: any) {
+        // A missing file is information too (the plan may be about to create it)
+        this.notes.push(`### ${file}\n(could not read: ${error.code ?? error.message})`);
+      }
+    }
+    return { step, status: 'done', files: step.files };
+  }
+
+  private async runStep(step: AgentStep): Promise<StepOutcome> {
+    const result = await this.options.runCommand(step.command!);
+    this.notes.push(`### $ ${step.command} (exit ${result.exitCode})\n\`\`\`\n${clip(result.output)}\n\`\`\``);
+    return result.exitCode === 0
+      ? { step, status: 'done' }
+      : { step, status: 'failed', error: `Command exited with code ${result.exitCode}` };
+  }
+
+  /**
+   * Generate, preview and apply one edit step; null when the user aborts
+   */
+  private async editStep(
+    plan: AgentPlan,
+    step: AgentStep,
+    ask: (patch?: Patch) => Promise<StepDecision>,
+    log: AgentAuditLog
+  ): Promise<StepOutcome | null> {
+    const reply = await this.options.generateEdit(step, this.notes.join('\n\n'));
+    const edits = createEditParser().parseResponse(reply, `agent-${step.index}`);
+    if (edits.length === 0) {
+      return { step, status: 'failed', error: 'The model proposed no edits for this step' };
+    }
+
+    const patch = await this.engine.prepare(edits);
+    if (patch.files.length === 0) {
+      return { step, status: 'done', files: [] };
+    }
+    this.options.onEvent?.({ type: 'step-preview', step, patch });
+
+    const decision = await ask(patch);
+    if (decision === 'abort') return null;
+    if (decision === 'skip') return { step, status: 'skipped' };
+
+    const files = patch.files.map(f => f.file);
+    if (this.options.uncommitted) {
+      const dirty = (await this.options.uncommitted(patch)).filter(f => !this.touched.has(f));
+      if (dirty.length > 0) {
+        return { step, status: 'failed', error: `Refusing to modify files with uncommitted changes: ${dirty.join(', ')}` };
+      }
+    }
+
+    try {
+      await this.engine.apply(patch);
+    } catch (error: any) {
+      if (error instanceof PatchError) return { step, status: 'failed', error: error.message };
+      throw error;
+    }
+    files.forEach(f => this.touched.add(f));
+    this.notes.push(`### Applied in step ${step.index}\n\`\`\`diff\n${clip(patch.files.map(f => f.diff).join(''))}\n\`\`\``);
+
+    let changeSet: string | undefined;
+    try {
+      changeSet = (await recordChangeSet(this.options.repoRoot, patch.files, {
+        command: 'do',
+        description: `${plan.task} (step ${step.index})`,
+        head: this.options.head
+      })).id;
+    } catch (error: any) {
+      await log.record('warning', { step: step.index, message: `Could not record change set: ${error.message}` });
+    }
+
+    return { step, status: 'done', files, changeSet };
+  }
+}
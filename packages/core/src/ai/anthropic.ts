@@ -0,0 +1,266 @@
+/**
+ * Anthropic Client
+ * Direct access to Claude models via the Anthropic Messages API
+ *
+ * Uses the `@anthropic-ai/sdk` package for chat, streaming and tool use.
+ * API key from `cv auth setup anthropic` or ANTHROPIC_API_KEY.
+ */
+
+import Anthropic from '@anthropic-ai/sdk';
+import {
+  AIClient,
+  AIMessage,
+  AIStreamHandler,
+  AITool,
+  AIToolCall,
+  AIToolMessage,
+  AIToolResult,
+} from './types.js';
+import { limitedFetch } from './http.js';
+
+export interface AnthropicOptions {
+  apiKey: string;
+  model?: string;
+  maxTokens?: number;
+  temperature?: number;
+  baseUrl?: string;
+}
+
+export const DEFAULT_ANTHROPIC_MODEL = 'claude-sonnet-4-5';
+
+const DEFAULT_ANTHROPIC_URL = 'https://api.anthropic.com';
+
+/** Required header for the REST endpoints the SDK version in use does not wrap */
+const ANTHROPIC_VERSION = '2023-06-01';
+
+/**
+ * Anthropic API client
+ */
+export class AnthropicClient implements AIClient {
+  private client: Anthropic;
+  private model: string;
+  private maxTokens: number;
+  private temperature: number;
+  private baseUrl: string;
+
+  constructor(private options: AnthropicOptions) {
+    this.baseUrl = (options.baseUrl || DEFAULT_ANTHROPIC_URL).replace(/\/$/, '');
+    this.client = new Anthropic({
+      apiKey: options.apiKey,
+      baseURL: this.baseUrl,
+      fetch: limitedFetch('anthropic'),
+      maxRetries: 0,
+    });
+    this.model = options.model || DEFAULT_ANTHROPIC_MODEL;
+    this.maxTokens = options.maxTokens || 8192;
+    this.temperature = options.temperature ?? 0.7;
+  }
+
+  getProvider(): string {
+    return 'anthropic';
+  }
+
+  getModel(): string {
+    return this.model;
+  }
+
+  setModel(model: string): void {
+    this.model = model;
+  }
+
+  /**
+   * Check that the API key is accepted
+   */
+  async isReady(): Promise<boolean> {
+    try {
+      const response = await this.fetchModels();
+      return response.ok;
+    } catch {
+      return false;
+    }
+  }
+
+  /**
+   * List model ids available to this API key
+   */
+  async listModels(): Promise<string[]> {
+    try {
+      const response = await this.fetchModels();
+      if (!response.ok) return [];
+      const data = await response.json() as { data?: Array<{ id: string }> };
+      return (data.data || []).map(m => m.id);
+    } catch {
+      return [];
+    }
+  }
+
+  /**
+   * Chat completion (non-streaming)
+   */
+  async chat(messages: AIMessage[], systemPrompt?: string): Promise<string> {
+    const response = await this.client.messages.create({
+      model: this.model,
+      max_tokens: this.maxTokens,
+      temperature: this.temperature,
+      ...this.system(messages, systemPrompt),
+      messages: this.buildMessages(messages),
+    });
+
+    return textOf(response.content);
+  }
+
+  /**
+   * Chat completion with streaming
+   */
+  async chatStream(
+    messages: AIMessage[],
+    systemPrompt?: string,
+    handler?: AIStreamHandler
+  ): Promise<string> {
+    let fullText = '';
+
+    try {
+      const stream = await this.client.messages.create({
+        model: this.model,
+        max_tokens: this.maxTokens,
+        temperature: this.temperature,
+        ...this.system(messages, systemPrompt),
+        messages: this.buildMessages(messages),
+        stream: true,
+      });
+
+      for await (const event of stream) {
+        if (event.type === 'content_block_delta' && event.delta.type === 'text_delta') {
+          fullText += event.delta.text;
+          handler?.onToken?.(event.delta.text);
+        }
+      }
+
+      handler?.onComplete?.(fullText);
+      return fullText;
+    } catch (error) {
+      handler?.onError?.(error as Error);
+      throw error;
+    }
+  }
+
+  /**
+   * Simple completion (single prompt)
+   */
+  async complete(prompt: string, handler?: AIStreamHandler): Promise<string> {
+    return this.chatStream([{ role: 'user', content: prompt }], undefined, handler);
+  }
+
+  /**
+   * Chat completion that may answer with tool calls
+   */
+  async chatWithTools(
+    messages: AIToolMessage[],
+    tools: AITool[],
+    systemPrompt?: string
+  ): Promise<AIToolResult> {
+    const response = await this.client.messages.create({
+      model: this.model,
+      max_tokens: this.maxTokens,
+      temperature: this.temperature,
+      ...this.system(messages, systemPrompt),
+      messages: this.buildToolMessages(messages),
+      tools: tools.map(tool => ({
+        name: tool.name,
+        description: tool.description,
+        input_schema: tool.parameters as Anthropic.Tool.InputSchema,
+      })),
+    });
+
+    const toolCalls: AIToolCall[] = [];
+    for (const block of response.content) {
+      if (block.type === 'tool_use') {
+        toolCalls.push({ id: block.id, name: block.name, arguments: (block.input ?? {}) as Record<string, unknown> });
+      }
+    }
+    return { text: textOf(response.content), toolCalls };
+  }
+
+  private fetchModels(): Promise<Response> {
+    return fetch(`${this.baseUrl}/v1/models?limit=1000`, {
+      headers: {
+        'x-api-key': this.options.apiKey,
+        'anthropic-version': ANTHROPIC_VERSION,
+      },
+    });
+  }
+
+  /**
+   * The Messages API takes the system prompt separately; system messages
+   * in the history are folded into it
+   */
+  private system(messages: AIToolMessage[], systemPrompt?: string): { system?: string } {
+    const parts = [
+      ...(systemPrompt ? [systemPrompt] : []),
+      ...messages.filter(m => m.role === 'system').map(m => m.content),
+    ];
+    return parts.length > 0 ? { system: parts.join('\n\n') } : {};
+  }
+
+  private buildMessages(messages: AIMessage[]): Anthropic.MessageParam[] {
+    return messages
+      .filter(m => m.role !== 'system')
+      .map(m => ({ role: m.role === 'user' ? 'user' as const : 'assistant' as const, content: m.content }));
+  }
+
+  /**
+   * Tool calls are content blocks of the assistant turn; their results are
+   * tool_result blocks of the following user turn
+   */
+  private buildToolMessages(messages: AIToolMessage[]): Anthropic.MessageParam[] {
+    const result: Anthropic.MessageParam[] = [];
+    for (const message of messages) {
+      if (message.role === 'system') continue;
+
+      if (message.role === 'tool') {
+        const block: Anthropic.ToolResultBlockParam = {
+          type: 'tool_result',
+          tool_use_id: message.toolCallId,
+          content: message.content,
+        };
+        const last = result[result.length - 1];
+        if (last?.role === 'user' && Array.isArray(last.content)) {
+          last.content.push(block);
+        } else {
+          result.push({ role: 'user', content: [block] });
+        }
+        continue;
+      }
+
+      if (message.role === 'assistant' && 'toolCalls' in message) {
+        result.push({
+          role: 'assistant',
+          content: [
+            ...(message.content ? [{ type: 'text' as const, text: message.content }] : []),
+            ...message.toolCalls.map(call => ({
+              type: 'tool_use' as const,
+              id: call.id,
+              name: call.name,
+              input: call.arguments,
+            })),
+          ],
+        });
+        continue;
+      }
+
+      result.push({ role: message.role === 'user' ? 'user' : 'assistant', content: message.content });
+    }
+    return result;
+  }
+}
+
+function textOf(content: Anthropic.ContentBlock[]): string {
+  return content.map(block => (block.type === 'text' ? block.text : '')).join('');
+}
+
+/**
+ * Create an Anthropic client
+ */
+export function createAnthropicClient(options: AnthropicOptions): AnthropicClient {
+  return new AnthropicClient(options);
+}
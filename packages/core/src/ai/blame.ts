@@ -0,0 +1,76 @@
+/**
+ * Blame Explanations
+ *
+ * Prompt for `cv blame-explain`: the blamed lines, the commits that
+ * introduced them (message and diff) and related indexed code. The model
+ * cites commits as `[abc1234]` so each claim can be checked with `git show`.
+ */
+
+import type { Context } from '@cv-git/shared';
+import { UNCOMMITTED_SHA } from '../git/blame.js';
+import type { BlameHistory } from '../git/blame.js';
+import { formatChunkCitation } from './context-packer.js';
+
+/** Length of the commit SHAs the model is asked to cite */
+export const BLAME_SHA_LENGTH = 7;
+
+/**
+ * Prompt asking why the blamed lines are the way they are
+ */
+export function buildBlamePrompt(history: BlameHistory, context?: Context): string {
+  const { file, startLine, endLine } = history;
+  const language = file.split('.').pop() ?? '';
+  let prompt = `You are an expert software engineer doing code archaeology. Explain why the code below is the way it is, `;
+  prompt += `using its git history.\n\n`;
+
+  prompt += `## Code (${file}:${startLine}-${endLine})\n`;
+  prompt += `\`\`\`${language}\n`;
+  for (const line of history.lines) {
+    const sha = line.sha === UNCOMMITTED_SHA ? 'uncommitted' : line.sha.slice(0, BLAME_SHA_LENGTH);
+    prompt += `${String(line.line).padStart(5)} ${sha.padEnd(BLAME_SHA_LENGTH)} | ${line.text}\n`;
+  }
+  prompt += `\`\`\`\n\n`;
+
+  if (history.commits.length > 0) {
+    prompt += `## Commits That Introduced These Lines\n\n`;
+    for (const commit of history.commits) {
+      const ranges = commit.ranges.map(([a, b]) => a === b ? `${a}` : `${a}-${b}`).join(', ');
+      if (commit.sha === UNCOMMITTED_SHA) {
+        prompt += `### uncommitted (lines ${ranges})\nLocal changes that are not committed yet.\n\n`;
+        continue;
+      }
+      const date = new Date(commit.date).toISOString().slice(0, 10);
+      prompt += `### ${commit.sha.slice(0, BLAME_SHA_LENGTH)} - ${commit.author}, ${date} (lines ${ranges})\n`;
+      prompt += `${commit.message || commit.summary}\n`;
+      if (commit.diff) {
+        prompt += `\`\`\`diff\n${commit.diff}\n\`\`\`\n`;
+      }
+      prompt += `\n`;
+    }
+  }
+
+  if (context?.chunks && context.chunks.length > 0) {
+    prompt += `## Related Code\n\n`;
+    for (const chunk of context.chunks) {
+      prompt += `### ${formatChunkCitation(chunk.payload)}\n`;
+      prompt += `\`\`\`${chunk.payload.language}\n${chunk.payload.text}\n\`\`\`\n\n`;
+    }
+  }
+
+  prompt += `\nExplain:\n`;
+  prompt += `1. What the code does\n`;
+  prompt += `2. How it came to be: which change introduced which part, and the reason the commits give\n`;
+  prompt += `3. Constraints or trade-offs that explain anything surprising, and whether they still seem to apply\n\n`;
+  prompt += `Cite commits by their short SHA in brackets, like [abc1234], and code as \`path:start-end\`. `;
+  prompt += `Say so when the history does not explain something rather than guessing.`;
+  return prompt;
+}
+
+/**
+ * Short SHAs of the history's commits cited in an explanation
+ */
+export function citedCommits(explanation: string, history: BlameHistory): string[] {
+  return history.commits
+    .map(commit => commit.sha)
+    .filter(sha => sha !== UNCOMMITTED_SHA && explanation.includes(sha.slice(0, BLAME_SHA_LENGTH)));
+}
@@ -14,7 +14,10 @@ import Anthropic from '@anthropic-ai/sdk';
 import { SymbolNode, SymbolKind, ParsedFile } from '@cv-git/shared';
 import { GraphManager } from '../graph/index.js';
 import { GitManager } from '../git/index.js';
-import { CodeParser } from '../parser/index.js';
+import { VectorManager } from '../vector/index.js';
+import { CodeParser, isTestFile } from '../parser/index.js';
+import { limitedFetch, providerFetch } from './http.js';
+import { NO_PROMPT_TEMPLATES, PromptTemplates } from './prompt-templates.js';
 import * as fs from 'fs/promises';
 import * as path from 'path';
 
@@ -78,6 +81,25 @@ export interface CommitAnalysis {
 
   // Raw data for AI prompt
   rawDiff: string;
+
+  // Indexed code related to the change (see gatherRelatedContext)
+  relatedCode?: RelatedCode[];
+}
+
+/**
+ * Indexed code chunk shown to the model alongside the diff
+ */
+export interface RelatedCode {
+  file: string;
+  startLine: number;
+  endLine: number;
+  symbolName?: string;
+  text: string;
+}
+
+export interface AnalyzeStagedOptions {
+  /** Describe the staged changes together with HEAD, as `git commit --amend` would commit them */
+  amend?: boolean;
 }
 
 /**
@@ -112,6 +134,7 @@ export class CommitAnalyzer {
   private maxTokens: number;
   private repoRoot: string;
   private parser: CodeParser;
+  private promptTemplates: PromptTemplates = NO_PROMPT_TEMPLATES;
 
   constructor(options: CommitAnalyzerOptions) {
     this.provider = options.provider || 'anthropic';
@@ -125,7 +148,7 @@ export class CommitAnalyzer {
       if (!options.apiKey) {
         throw new Error('API key required for Anthropic provider');
       }
-      this.anthropicClient = new Anthropic({ apiKey: options.apiKey });
+      this.anthropicClient = new Anthropic({ apiKey: options.apiKey, fetch: limitedFetch('anthropic'), maxRetries: 0 });
       this.model = options.model || 'claude-3-5-sonnet-20241022';
     } else if (this.provider === 'openrouter') {
       if (!options.apiKey) {
@@ -139,15 +162,42 @@ export class CommitAnalyzer {
     }
   }
 
+  /**
+   * Send the prompt through `.cv/prompts/commit.md` and the system templates
+   */
+  setPromptTemplates(templates: PromptTemplates | undefined): void {
+    this.promptTemplates = templates ?? NO_PROMPT_TEMPLATES;
+  }
+
+  /**
+   * System prompt and prompt generateMessage() sends for an analysis
+   */
+  renderPrompt(analysis: CommitAnalysis): { system?: string; prompt: string } {
+    const context = analysis.relatedCode
+      ?.map(chunk => `### ${chunk.file}:${chunk.startLine}-${chunk.endLine}\n\`\`\`\n${chunk.text}\n\`\`\``)
+      .join('\n\n');
+    return {
+      system: this.promptTemplates.renderSystem('commit'),
+      prompt: this.promptTemplates.renderPrompt('commit', this.buildPrompt(analysis), { input: analysis.rawDiff, context })
+    };
+  }
+
   /**
    * Analyze staged changes
    */
   async analyzeStaged(
     git: GitManager,
-    graph?: GraphManager
+    graph?: GraphManager,
+    options: AnalyzeStagedOptions = {}
   ): Promise<CommitAnalysis> {
-    // Get staged diff
-    const rawDiff = await git.getRawDiff('--staged');
+    // Get staged diff (against HEAD's parent when amending, so HEAD's own changes are included)
+    let rawDiff = '';
+    if (options.amend) {
+      rawDiff = await git.getRawDiff(['--staged', 'HEAD~1']).catch(() => '');
+    }
+    if (!rawDiff.trim()) {
+      rawDiff = await git.getRawDiff('--staged');
+    }
 
     if (!rawDiff.trim()) {
       throw new Error('No staged changes to analyze. Stage your changes with `git add` first.');
@@ -158,7 +208,9 @@ export class CommitAnalyzer {
 
     // Get current status to identify staged files
     const status = await git.getStatus();
-    const stagedFiles = status.staged;
+    const stagedFiles = options.amend
+      ? [...new Set([...status.staged, ...filesChanged])]
+      : status.staged;
 
     // Analyze symbols in changed files
     const symbolAnalysis = await this.analyzeSymbolChanges(stagedFiles, git, graph);
@@ -211,7 +263,7 @@ export class CommitAnalyzer {
       return this.generateTemplateMessage(analysis);
     }
 
-    const prompt = this.buildPrompt(analysis);
+    const { system, prompt } = this.renderPrompt(analysis);
     let responseText: string;
 
     if (this.provider === 'anthropic' && this.anthropicClient) {
@@ -219,6 +271,7 @@ export class CommitAnalyzer {
         model: this.model,
         max_tokens: this.maxTokens,
         temperature: 0.3,
+        ...(system && { system }),
         messages: [{ role: 'user', content: prompt }]
       });
 
@@ -229,7 +282,7 @@ export class CommitAnalyzer {
       responseText = content.text;
 
     } else if (this.provider === 'openrouter' && this.openRouterApiKey) {
-      responseText = await this.callOpenRouter(prompt);
+      responseText = await this.callOpenRouter(prompt, system);
 
     } else {
       throw new Error(`Invalid provider configuration: ${this.provider}`);
@@ -241,8 +294,8 @@ export class CommitAnalyzer {
   /**
    * Call OpenRouter API for message generation
    */
-  private async callOpenRouter(prompt: string): Promise<string> {
-    const response = await fetch(`${this.openRouterBaseUrl}/chat/completions`, {
+  private async callOpenRouter(prompt: string, system?: string): Promise<string> {
+    const response = await providerFetch('openrouter', `${this.openRouterBaseUrl}/chat/completions`, {
       method: 'POST',
       headers: {
         'Authorization': `Bearer ${this.openRouterApiKey}`,
@@ -254,7 +307,7 @@ export class CommitAnalyzer {
         model: this.model,
         max_tokens: this.maxTokens,
         temperature: 0.3,
-        messages: [{ role: 'user', content: prompt }]
+        messages: [...(system ? [{ role: 'system', content: system }] : []), { role: 'user', content: prompt }]
       })
     });
 
@@ -329,6 +382,42 @@ export class CommitAnalyzer {
     };
   }
 
+  /**
+   * Find indexed code related to the change so the message can describe
+   * it in terms of the surrounding code, not just the diff
+   */
+  async gatherRelatedContext(
+    analysis: CommitAnalysis,
+    vector: VectorManager,
+    limit: number = 5
+  ): Promise<RelatedCode[]> {
+    const symbols = [...analysis.symbolsAdded, ...analysis.symbolsModified, ...analysis.symbolsDeleted]
+      .slice(0, 10)
+      .map(s => s.name);
+    const changedLines = analysis.rawDiff
+      .split('\n')
+      .filter(line => /^[+-](?![+-])/.test(line))
+      .map(line => line.slice(1).trim())
+      .filter(Boolean)
+      .slice(0, 40);
+    const query = [...symbols, ...analysis.filesChanged.slice(0, 10), ...changedLines].join('\n').slice(0, 2000);
+    if (!query.trim()) return [];
+
+    try {
+      const results = await vector.searchCode(query, limit);
+      return results.map(r => ({
+        file: r.payload.file,
+        startLine: r.payload.startLine,
+        endLine: r.payload.endLine,
+        symbolName: r.payload.symbolName,
+        text: r.payload.text
+      }));
+    } catch {
+      // Index unavailable, describe the diff alone
+      return [];
+    }
+  }
+
   /**
    * Combined: Analyze and generate in one call
    */
@@ -562,12 +651,8 @@ export class CommitAnalyzer {
     filesChanged: string[]
   ): CommitType {
     // Check for test files
-    const hasTestChanges = filesChanged.some(f =>
-      f.includes('.test.') || f.includes('.spec.') || f.includes('/tests/') || f.includes('__tests__')
-    );
-    if (hasTestChanges && filesChanged.every(f =>
-      f.includes('.test.') || f.includes('.spec.') || f.includes('/tests/') || f.includes('__tests__')
-    )) {
+    const hasTestChanges = filesChanged.some(f => isTestFile(f));
+    if (hasTestChanges && filesChanged.every(f => isTestFile(f))) {
       return 'test';
     }
 
@@ -757,6 +842,15 @@ export class CommitAnalyzer {
       }
     }
 
+    if (analysis.relatedCode && analysis.relatedCode.length > 0) {
+      prompt += `\n## Related Code (from the repository index)\n`;
+      for (const chunk of analysis.relatedCode) {
+        const name = chunk.symbolName ? ` (${chunk.symbolName})` : '';
+        const text = chunk.text.split('\n').slice(0, 20).join('\n');
+        prompt += `\n### ${chunk.file}:${chunk.startLine}-${chunk.endLine}${name}\n\`\`\`\n${text}\n\`\`\`\n`;
+      }
+    }
+
     prompt += `
 ## Suggested Classification
 - Type: ${analysis.suggestedType}
@@ -0,0 +1,294 @@
+/**
+ * Context Packing
+ *
+ * Fits retrieved chunks into a token budget for the model. Chunks are
+ * taken best score first; overlapping line ranges from the same file are
+ * merged so the same code is not paid for twice; the last chunk that does
+ * not fit is cut at a line boundary. Every packed chunk keeps its file and
+ * line range, so prompts and answers can still cite it.
+ *
+ * Tokens are counted with tiktoken encodings when the optional js-tiktoken
+ * package is installed, and estimated at ~4 characters per token otherwise.
+ */
+
+import type { CodeChunkPayload, VectorSearchResult } from '@cv-git/shared';
+
+type Chunk = VectorSearchResult<CodeChunkPayload>;
+
+export interface TokenCounter {
+  /** tiktoken encoding name, or 'estimate' */
+  readonly encoding: string;
+  count(text: string): number;
+}
+
+export interface PackOptions {
+  /** Tokens the packed chunks may use, including their headers */
+  budget: number;
+  counter?: TokenCounter;
+  /** Smallest remainder worth filling with a cut-down chunk */
+  minPartialTokens?: number;
+}
+
+export interface PackResult {
+  chunks: Chunk[];
+  tokens: number;
+  /** Chunks folded into an overlapping chunk of the same file */
+  merged: number;
+  /** Chunks left out entirely */
+  dropped: number;
+  /** Chunks cut to fit the remaining budget */
+  truncated: number;
+}
+
+/** Context windows by model-name prefix; the longest matching prefix wins */
+export const MODEL_CONTEXT_WINDOWS: Record<string, number> = {
+  'claude': 200000,
+  'gpt-4o': 128000,
+  'gpt-4-turbo': 128000,
+  'gpt-4.1': 1000000,
+  'gpt-4': 8192,
+  'gpt-3.5': 16385,
+  'o1': 200000,
+  'o3': 200000,
+  'o4': 200000,
+  'gemini': 1000000,
+  'llama': 8192,
+  'qwen': 32768,
+  'mistral': 32768,
+  'deepseek': 64000
+};
+
+const DEFAULT_CONTEXT_WINDOW = 8192;
+
+/** Default context budget; larger windows still get this much, smaller ones get what fits */
+export const DEFAULT_CONTEXT_TOKENS = 16000;
+
+/** Tokens kept free for instructions, the query and other prompt sections */
+const PROMPT_RESERVE_TOKENS = 2000;
+
+const TIKTOKEN_PACKAGE = 'js-tiktoken';
+
+const CHUNK_HEADER_TOKENS = 8;
+
+export function getContextWindow(model: string): number {
+  const name = model.toLowerCase().replace(/^[^/]+\//, '');
+  let best = '';
+  for (const prefix of Object.keys(MODEL_CONTEXT_WINDOWS)) {
+    if (name.startsWith(prefix) && prefix.length > best.length) best = prefix;
+  }
+  return best ? MODEL_CONTEXT_WINDOWS[best] : DEFAULT_CONTEXT_WINDOW;
+}
+
+/**
+ * Context budget for a model: the configured value, capped so the packed
+ * chunks, the rest of the prompt and the reply fit in the window
+ */
+export function resolveContextBudget(model: string, maxOutputTokens: number, configured?: number): number {
+  const available = getContextWindow(model) - maxOutputTokens - PROMPT_RESERVE_TOKENS;
+  return Math.max(0, Math.min(configured ?? DEFAULT_CONTEXT_TOKENS, available));
+}
+
+/**
+ * tiktoken encoding for a model. Claude and open models have their own
+ * tokenizers; cl100k_base counts are close enough to budget with.
+ */
+export function encodingForModel(model: string): 'o200k_base' | 'cl100k_base' {
+  const name = model.toLowerCase().replace(/^[^/]+\//, '');
+  return /^(gpt-4o|gpt-4\.1|o\d)/.test(name) ? 'o200k_base' : 'cl100k_base';
+}
+
+export const estimateTokenCounter: TokenCounter = {
+  encoding: 'estimate',
+  // Rough estimate: ~4 characters per token
+  count: (text: string) => Math.ceil(text.length / 4)
+};
+
+const counters = new Map<string, Promise<TokenCounter>>();
+
+/**
+ * Token counter for a model; falls back to the estimate when js-tiktoken
+ * is not installed
+ */
+export function getTokenCounter(model: string): Promise<TokenCounter> {
+  const encoding = encodingForModel(model);
+  let counter = counters.get(encoding);
+  if (!counter) {
+    counter = (async () => {
+      try {
+        // Kept out of static imports so the CLI runs without the optional package
+        const tiktoken: any = await import(TIKTOKEN_PACKAGE);
+        const encoder = tiktoken.getEncoding(encoding);
+        return { encoding, count: (text: string) => encoder.encode(text).length };
+      } catch {
+        return estimateTokenCounter;
+      }
+    })();
+    counters.set(encoding, counter);
+  }
+  return counter;
+}
+
+/**
+ * Citation header rendered above a chunk in prompts
+ */
+export function formatChunkCitation(payload: CodeChunkPayload): string {
+  return `${payload.repo ? `${payload.repo}:` : ''}${payload.file}:${payload.startLine}-${payload.endLine}`;
+}
+
+export interface ContextSource {
+  /** Workspace repository, when not the current one */
+  repo?: string;
+  file: string;
+  startLine: number;
+  endLine: number;
+  symbolName?: string;
+  score: number;
+}
+
+/**
+ * Locations the chunks passed to the model came from, best first, one per range
+ */
+export function contextSources(chunks: Chunk[]): ContextSource[] {
+  const seen = new Set<string>();
+  const sources: ContextSource[] = [];
+  for (const { payload, score } of chunks) {
+    const key = formatChunkCitation(payload);
+    if (seen.has(key)) continue;
+    seen.add(key);
+    sources.push({
+      ...(payload.repo && { repo: payload.repo }),
+      file: payload.file,
+      startLine: payload.startLine,
+      endLine: payload.endLine,
+      symbolName: payload.symbolName,
+      score
+    });
+  }
+  return sources;
+}
+
+function chunkTokens(chunk: Chunk, counter: TokenCounter): number {
+  return counter.count(formatChunkCitation(chunk.payload)) + counter.count(chunk.payload.text) + CHUNK_HEADER_TOKENS;
+}
+
+function lineCount(payload: CodeChunkPayload): number {
+  return payload.endLine - payload.startLine + 1;
+}
+
+/**
+ * Join two overlapping chunks of one file into one covering both ranges.
+ * Returns null when a chunk's text does not match its line range (e.g.
+ * summaries of oversized files), since the lines can't be spliced then.
+ */
+function mergeChunks(kept: Chunk, other: Chunk): Chunk | null {
+  const a = kept.payload;
+  const b = other.payload;
+  if (a.synthetic || b.synthetic) return null;
+  const aLines = a.text.split('\n');
+  const bLines = b.text.split('\n');
+  if (aLines.length !== lineCount(a) || bLines.length !== lineCount(b)) return null;
+
+  const startLine = Math.min(a.startLine, b.startLine);
+  const endLine = Math.max(a.endLine, b.endLine);
+  const lines: string[] = [];
+  for (let line = startLine; line <= endLine; line++) {
+    lines.push(line >= a.startLine && line <= a.endLine
+      ? aLines[line - a.startLine]
+      : bLines[line - b.startLine]);
+  }
+
+  return {
+    ...kept,
+    payload: {
+      ...a,
+      startLine,
+      endLine,
+      text: lines.join('\n'),
+      // The merged range is no longer a single symbol unless one contains the other
+      symbolName: a.startLine === startLine && a.endLine === endLine ? a.symbolName : undefined
+    }
+  };
+}
+
+function overlaps(a: CodeChunkPayload, b: CodeChunkPayload): boolean {
+  return a.file === b.file && a.repo === b.repo && a.startLine <= b.endLine && b.startLine <= a.endLine;
+}
+
+/**
+ * Cut a chunk to the lines that fit in `budget` tokens
+ */
+function truncateChunk(chunk: Chunk, budget: number, counter: TokenCounter): Chunk | null {
+  const lines = chunk.payload.text.split('\n');
+  if (chunk.payload.synthetic || lines.length !== lineCount(chunk.payload)) return null;
+
+  let used = counter.count(formatChunkCitation(chunk.payload)) + CHUNK_HEADER_TOKENS;
+  let kept = 0;
+  while (kept < lines.length) {
+    const cost = counter.count(lines[kept] + '\n');
+    if (used + cost > budget) break;
+    used += cost;
+    kept++;
+  }
+  if (kept === 0) return null;
+
+  return {
+    ...chunk,
+    payload: {
+      ...chunk.payload,
+      endLine: chunk.payload.startLine + kept - 1,
+      text: lines.slice(0, kept).join('\n')
+    }
+  };
+}
+
+/**
+ * Pack chunks into a token budget, best score first
+ */
+export function packChunks(chunks: Chunk[], options: PackOptions): PackResult {
+  const counter = options.counter ?? estimateTokenCounter;
+  const minPartial = options.minPartialTokens ?? 64;
+  const result: PackResult = { chunks: [], tokens: 0, merged: 0, dropped: 0, truncated: 0 };
+
+  // Fold overlapping ranges into the best-scoring chunk covering them
+  const ranked = [...chunks].sort((a, b) => b.score - a.score);
+  const unique: Chunk[] = [];
+  for (const chunk of ranked) {
+    const index = unique.findIndex(kept => overlaps(kept.payload, chunk.payload));
+    if (index < 0) {
+      unique.push(chunk);
+      continue;
+    }
+    const kept = unique[index];
+    const contained = kept.payload.startLine <= chunk.payload.startLine && kept.payload.endLine >= chunk.payload.endLine;
+    if (!contained) {
+      const joined = mergeChunks(kept, chunk);
+      if (!joined) {
+        unique.push(chunk);
+        continue;
+      }
+      unique[index] = joined;
+    }
+    result.merged++;
+  }
+
+  for (const chunk of unique) {
+    const remaining = options.budget - result.tokens;
+    const cost = chunkTokens(chunk, counter);
+    if (cost <= remaining) {
+      result.chunks.push(chunk);
+      result.tokens += cost;
+      continue;
+    }
+    const partial = remaining >= minPartial ? truncateChunk(chunk, remaining, counter) : null;
+    const partialCost = partial ? chunkTokens(partial, counter) : Infinity;
+    if (partial && partialCost <= remaining) {
+      result.chunks.push(partial);
+      result.tokens += partialCost;
+      result.truncated++;
+    } else {
+      result.dropped++;
+    }
+  }
+
+  return result;
+}
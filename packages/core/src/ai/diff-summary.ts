@@ -0,0 +1,360 @@
+/**
+ * Range Summaries
+ *
+ * `cv diff-summary main..feature` groups the files a branch changes into
+ * areas (monorepo packages, else top-level directories), has the model
+ * summarize each area and the whole range, and adds two deterministic
+ * sections: API/behavior changes (exported declarations added, removed or
+ * changed, plus schema/config files) and risky files (large or sensitive
+ * changes, API changes without test changes).
+ */
+
+import type { ChurnCommit } from '../git/hotspots.js';
+import { packageForFile } from '../sync/packages.js';
+import type { RepoPackage } from '../sync/packages.js';
+import { splitDiff } from './review.js';
+import type { DiffSection } from './review.js';
+
+export interface DiffClusterFile {
+  file: string;
+  insertions: number;
+  deletions: number;
+}
+
+export interface DiffCluster {
+  /** Package name or directory */
+  area: string;
+  files: DiffClusterFile[];
+  insertions: number;
+  deletions: number;
+  /** Subjects of the range's commits that touched the area */
+  commits: string[];
+  sections: DiffSection[];
+  /** Filled in by the model */
+  summary?: string;
+}
+
+export interface ApiChange {
+  file: string;
+  kind: 'added' | 'removed' | 'changed';
+  /** Declaration as it reads after the change (before, for removals) */
+  signature: string;
+  /** Declaration before a change */
+  previous?: string;
+}
+
+export interface RiskyFile {
+  file: string;
+  insertions: number;
+  deletions: number;
+  reasons: string[];
+  score: number;
+}
+
+export interface DiffSummary {
+  range: string;
+  commits: number;
+  overview: string;
+  clusters: DiffCluster[];
+  apiChanges: ApiChange[];
+  /** Schema, migration and config files, whose changes alter behavior */
+  behaviorFiles: string[];
+  risky: RiskyFile[];
+}
+
+/** Exported/public declarations, per language */
+const DECLARATION = new RegExp([
+  String.raw`^export\s+(default\s+)?(async\s+)?(function\*?|class|interface|type|const|let|enum|abstract\s+class)\s+\w+`,
+  String.raw`^pub(\([^)]*\))?\s+(async\s+)?(unsafe\s+)?(fn|struct|enum|trait|type|const|static|mod)\s+\w+`,
+  String.raw`^func\s+(\([^)]*\)\s*)?[A-Z]\w*`,
+  String.raw`^type\s+[A-Z]\w*\s+`,
+  String.raw`^(public|protected)\s+[\w<>\[\], ?]*\(?`,
+  String.raw`^(async\s+)?def\s+[a-zA-Z]\w*\s*\(`,
+  String.raw`^class\s+[A-Z]\w*`
+].join('|'));
+
+const BEHAVIOR_FILE = /(^|\/)(package\.json|Cargo\.toml|go\.mod|pyproject\.toml|.*\.proto|openapi\.[\w.]+|swagger\.[\w.]+|.*schema\.[\w.]+|migrations?\/.+|.*\.sql|Dockerfile|.*\.ya?ml|\.env\.example)$/i;
+
+const SENSITIVE_PATH = /(auth|security|crypto|secret|token|password|permission|payment|billing|migration)/i;
+
+const TEST_PATH = /(^|\/)(tests?|__tests__|spec)\/|\.(test|spec)\.[^/]+$|_test\.(go|py)$|(^|\/)test_[^/]+\.py$/;
+
+/**
+ * Revisions for a range argument: `git log` takes it as given, the diff is
+ * taken from the merge base (`a...b`) so changes on the base branch since
+ * the fork are left out. A single revision means `rev..HEAD`.
+ */
+export function parseCommitRange(range: string): { log: string; diff: string } {
+  const threeDot = range.match(/^(.*?)\.\.\.(.*)$/);
+  if (threeDot) {
+    const [, from, to] = threeDot;
+    return { log: `${from || 'HEAD'}..${to || 'HEAD'}`, diff: `${from || 'HEAD'}...${to || 'HEAD'}` };
+  }
+  const twoDot = range.match(/^(.*?)\.\.(.*)$/);
+  const [from, to] = twoDot ? [twoDot[1] || 'HEAD', twoDot[2] || 'HEAD'] : [range, 'HEAD'];
+  return { log: `${from}..${to}`, diff: `${from}...${to}` };
+}
+
+/**
+ * Group changed files by package (or directory) with the commits and diff
+ * sections that belong to each
+ */
+export function clusterChanges(
+  diff: string,
+  commits: ChurnCommit[],
+  packages: RepoPackage[] = [],
+  options: { maxClusters?: number } = {}
+): DiffCluster[] {
+  const maxClusters = options.maxClusters ?? 12;
+  const clusters = new Map<string, DiffCluster>();
+  const get = (area: string) => {
+    let cluster = clusters.get(area);
+    if (!cluster) {
+      cluster = { area, files: [], insertions: 0, deletions: 0, commits: [], sections: [] };
+      clusters.set(area, cluster);
+    }
+    return cluster;
+  };
+
+  for (const section of splitDiff(diff)) {
+    const cluster = get(areaOf(section.file, packages));
+    const { insertions, deletions } = countLines(section.text);
+    cluster.files.push({ file: section.file, insertions, deletions });
+    cluster.insertions += insertions;
+    cluster.deletions += deletions;
+    cluster.sections.push(section);
+  }
+
+  for (const commit of commits) {
+    const areas = new Set(commit.files.map(file => areaOf(file.path, packages)));
+    for (const area of areas) {
+      const cluster = clusters.get(area);
+      if (cluster && !cluster.commits.includes(commit.subject)) cluster.commits.push(commit.subject);
+    }
+  }
+
+  const ranked = [...clusters.values()].sort((a, b) =>
+    (b.insertions + b.deletions) - (a.insertions + a.deletions) || a.area.localeCompare(b.area)
+  );
+  if (ranked.length <= maxClusters) return ranked;
+
+  // Fold the smallest areas together
+  const kept = ranked.slice(0, maxClusters - 1);
+  const other: DiffCluster = { area: 'other', files: [], insertions: 0, deletions: 0, commits: [], sections: [] };
+  for (const cluster of ranked.slice(maxClusters - 1)) {
+    other.files.push(...cluster.files);
+    other.insertions += cluster.insertions;
+    other.deletions += cluster.deletions;
+    other.sections.push(...cluster.sections);
+    for (const subject of cluster.commits) {
+      if (!other.commits.includes(subject)) other.commits.push(subject);
+    }
+  }
+  return [...kept, other];
+}
+
+/**
+ * Area a file belongs to: its package (plus the first directory inside it),
+ * else its first two directories
+ */
+export function areaOf(file: string, packages: RepoPackage[] = []): string {
+  const pkg = packageForFile(packages, file);
+  if (pkg && pkg.dir !== '.') {
+    const inner = file.slice(pkg.dir.length + 1).split('/').slice(0, -1).filter(dir => dir !== 'src');
+    return inner.length > 0 ? `${pkg.name}/${inner[0]}` : pkg.name;
+  }
+  const dirs = file.split('/').slice(0, -1);
+  return dirs.length === 0 ? '(root)' : dirs.slice(0, 2).join('/');
+}
+
+/**
+ * Exported declarations the diff adds, removes or changes
+ */
+export function detectApiChanges(diff: string): ApiChange[] {
+  const changes: ApiChange[] = [];
+  for (const section of splitDiff(diff)) {
+    if (TEST_PATH.test(section.file)) continue;
+    const added = new Map<string, string>();
+    const removed = new Map<string, string>();
+    for (const line of section.text.split('\n')) {
+      if (line.startsWith('+++') || line.startsWith('---')) continue;
+      const sign = line[0];
+      if (sign !== '+' && sign !== '-') continue;
+      const code = line.slice(1).trim();
+      if (!DECLARATION.test(code)) continue;
+      const name = declarationName(code);
+      if (!name) continue;
+      (sign === '+' ? added : removed).set(name, code.replace(/\s*\{\s*$/, ''));
+    }
+    for (const [name, signature] of added) {
+      const previous = removed.get(name);
+      if (previous === undefined) {
+        changes.push({ file: section.file, kind: 'added', signature });
+      } else if (normalize(previous) !== normalize(signature)) {
+        changes.push({ file: section.file, kind: 'changed', signature, previous });
+      }
+    }
+    for (const [name, signature] of removed) {
+      if (!added.has(name)) changes.push({ file: section.file, kind: 'removed', signature });
+    }
+  }
+  return changes;
+}
+
+function declarationName(code: string): string | undefined {
+  const keyword = /\b(?:function\*?|class|interface|type|const|let|enum|fn|struct|trait|static|mod|def|func(?:\s+\([^)]*\))?)\s+(\w+)/;
+  const call = /(\w+)\s*\(/;
+  // Java/C# members: the name is the identifier before the parameter list
+  const match = /^(public|protected)\b/.test(code)
+    ? code.match(call) ?? code.match(keyword)
+    : code.match(keyword) ?? code.match(call);
+  return match?.[1];
+}
+
+function normalize(signature: string): string {
+  return signature.replace(/\s+/g, ' ').replace(/[;{]\s*$/, '').trim();
+}
+
+/**
+ * Files whose changes deserve the closest look
+ */
+export function rankRiskyFiles(clusters: DiffCluster[], apiChanges: ApiChange[], limit: number = 10): RiskyFile[] {
+  const files = clusters.flatMap(cluster => cluster.files);
+  const testedAreas = new Set(clusters.filter(c => c.files.some(f => TEST_PATH.test(f.file))).map(c => c.area));
+  const areaOfFile = new Map(clusters.flatMap(c => c.files.map(f => [f.file, c.area] as const)));
+
+  const risky: RiskyFile[] = [];
+  for (const file of files) {
+    if (TEST_PATH.test(file.file)) continue;
+    const reasons: string[] = [];
+    let score = 0;
+    const lines = file.insertions + file.deletions;
+    if (lines >= 200) {
+      reasons.push(`${lines} lines changed`);
+      score += 2;
+    } else if (lines >= 80) {
+      reasons.push(`${lines} lines changed`);
+      score += 1;
+    }
+    const api = apiChanges.filter(change => change.file === file.file);
+    if (api.some(change => change.kind !== 'added')) {
+      reasons.push(`changes or removes ${api.filter(c => c.kind !== 'added').length} exported declaration(s)`);
+      score += 2;
+    }
+    if (SENSITIVE_PATH.test(file.file)) {
+      reasons.push('security- or data-sensitive path');
+      score += 2;
+    }
+    if (BEHAVIOR_FILE.test(file.file)) {
+      reasons.push('schema, dependency or config file');
+      score += 1;
+    }
+    if (score > 0 && !testedAreas.has(areaOfFile.get(file.file)!)) {
+      reasons.push('no test changes in this area');
+      score += 1;
+    }
+    if (score > 0) risky.push({ ...file, reasons, score });
+  }
+
+  return risky
+    .sort((a, b) => b.score - a.score || (b.insertions + b.deletions) - (a.insertions + a.deletions))
+    .slice(0, limit);
+}
+
+/**
+ * Changed files whose effect is behavioral rather than code (schemas,
+ * migrations, dependencies, config)
+ */
+export function behaviorFiles(clusters: DiffCluster[]): string[] {
+  return clusters.flatMap(cluster => cluster.files.map(f => f.file)).filter(file => BEHAVIOR_FILE.test(file));
+}
+
+/**
+ * Prompt summarizing one area of the range
+ */
+export function buildClusterSummaryPrompt(cluster: DiffCluster, maxDiffChars: number = 12000): string {
+  let prompt = `Summarize the changes to "${cluster.area}" on a branch, for a reviewer.\n\n`;
+  if (cluster.commits.length > 0) {
+    prompt += `## Commits\n${cluster.commits.slice(0, 30).map(subject => `- ${subject}`).join('\n')}\n\n`;
+  }
+  prompt += `## Diff\n\`\`\`diff\n`;
+  let used = 0;
+  for (const section of cluster.sections) {
+    if (used + section.text.length > maxDiffChars) {
+      prompt += `... (${cluster.sections.length - cluster.sections.indexOf(section)} more files not shown)\n`;
+      break;
+    }
+    prompt += `${section.text}\n`;
+    used += section.text.length;
+  }
+  prompt += `\`\`\`\n\n`;
+  prompt += `In 2-4 sentences (or a few bullets), say what changed and why it matters. `;
+  prompt += `Call out changed behavior or public interfaces. Do not restate the file list.`;
+  return prompt;
+}
+
+/**
+ * Prompt for the overview of the whole range, from the area summaries
+ */
+export function buildRangeOverviewPrompt(range: string, clusters: DiffCluster[], apiChanges: ApiChange[]): string {
+  let prompt = `Write a short overview (one paragraph) of the branch ${range} for a pull request description.\n\n`;
+  prompt += `## Areas\n`;
+  for (const cluster of clusters) {
+    prompt += `### ${cluster.area} (+${cluster.insertions} -${cluster.deletions})\n${cluster.summary ?? ''}\n\n`;
+  }
+  if (apiChanges.length > 0) {
+    prompt += `## API changes\n`;
+    prompt += apiChanges.slice(0, 20).map(change => `- ${change.kind}: ${change.signature} (${change.file})`).join('\n');
+    prompt += `\n\n`;
+  }
+  prompt += `Lead with the purpose of the change, then the most important effects. No headings.`;
+  return prompt;
+}
+
+/**
+ * Markdown for pasting into a pull request description
+ */
+export function formatDiffSummaryMarkdown(summary: DiffSummary): string {
+  const lines: string[] = [];
+  lines.push('## Summary', '', summary.overview.trim(), '');
+  lines.push(`_${summary.commits} commits, ${summary.clusters.reduce((n, c) => n + c.files.length, 0)} files changed (\`${summary.range}\`)_`, '');
+
+  lines.push('## Changes by area', '');
+  for (const cluster of summary.clusters) {
+    lines.push(`### ${cluster.area} (${cluster.files.length} file${cluster.files.length === 1 ? '' : 's'}, +${cluster.insertions} −${cluster.deletions})`, '');
+    if (cluster.summary) lines.push(cluster.summary.trim(), '');
+  }
+
+  if (summary.apiChanges.length > 0 || summary.behaviorFiles.length > 0) {
+    lines.push('## API and behavior changes', '');
+    for (const change of summary.apiChanges) {
+      const detail = change.kind === 'changed' ? ` (was \`${change.previous}\`)` : '';
+      lines.push(`- **${change.kind}** \`${change.signature}\` in \`${change.file}\`${detail}`);
+    }
+    for (const file of summary.behaviorFiles) {
+      lines.push(`- \`${file}\` changed`);
+    }
+    lines.push('');
+  }
+
+  if (summary.risky.length > 0) {
+    lines.push('## Risky files', '');
+    lines.push('| File | Lines | Why |', '|---|---|---|');
+    for (const file of summary.risky) {
+      lines.push(`| \`${file.file}\` | +${file.insertions} −${file.deletions} | ${file.reasons.join('; ')} |`);
+    }
+    lines.push('');
+  }
+
+  return lines.join('\n');
+}
+
+function countLines(text: string): { insertions: number; deletions: number } {
+  let insertions = 0;
+  let deletions = 0;
+  for (const line of text.split('\n')) {
+    if (line.startsWith('+') && !line.startsWith('+++')) insertions++;
+    else if (line.startsWith('-') && !line.startsWith('---')) deletions++;
+  }
+  return { insertions, deletions };
+}
@@ -0,0 +1,361 @@
+/**
+ * Doc Comment Generation
+ *
+ * `cv docgen` finds public functions and types in the symbol graph, asks the
+ * model for their documentation one file at a time and writes it back in
+ * the language's own form: `///` rustdoc above Rust items and their
+ * attributes, `/** *\/` TSDoc and Javadoc, `//` comments that
+ * start with the name in Go and docstrings as the first statement of Python
+ * bodies. Existing doc comments are replaced unless only missing ones are
+ * asked for.
+ *
+ * Finding targets reads the graph and the sources; rendering and inserting
+ * are pure, so the command previews the result as a patch before applying it.
+ */
+
+import { detectLanguage, matchGlob } from '@cv-git/shared';
+import type { GraphManager } from '../graph/index.js';
+import { isPublicSymbol } from '../graph/deadcode.js';
+
+export interface DocTarget {
+  qualifiedName: string;
+  name: string;
+  kind: string;
+  file: string;
+  startLine: number;
+  endLine: number;
+  language: string;
+  /** Whether the item already has a doc comment */
+  documented: boolean;
+}
+
+export interface DocTargetOptions {
+  /** Only items in files under this path */
+  path?: string;
+  /** Names, qualified names or file globs to skip (`docgen.ignore`) */
+  ignore?: string[];
+  /** Skip items that already have a doc comment */
+  missingOnly?: boolean;
+  /** Read a repository file (null when it cannot be read) */
+  readFile: (file: string) => Promise<string | null>;
+}
+
+/** Line range of a doc comment, 0-based and inclusive */
+export interface DocCommentRange {
+  start: number;
+  end: number;
+}
+
+export interface GeneratedDoc {
+  qualifiedName: string;
+  /** Documentation text without comment markers */
+  doc: string;
+}
+
+const DOCUMENTED_KINDS = new Set(['function', 'method', 'class', 'interface', 'type', 'enum', 'struct']);
+
+const DOCUMENTED_LANGUAGES = new Set(['typescript', 'python', 'go', 'rust', 'java', 'kotlin', 'c', 'cpp']);
+
+/** Test files are left alone */
+const TEST_FILE = /(^|\/)(tests?|__tests__|spec|benches|examples?)\/|\.(test|spec)\.[^/]+$|_test\.go$|(^|\/)test_[^/]+\.py$|_test\.py$/;
+
+/** Lines of each item sent to the model */
+const MAX_ITEM_LINES = 80;
+
+/**
+ * Public items to document, in file and line order
+ */
+export async function findDocTargets(graph: Pick<GraphManager, 'query'>, options: DocTargetOptions): Promise<DocTarget[]> {
+  const rows = await graph.query(
+    'MATCH (s:Symbol) RETURN s.qualifiedName as qualifiedName, s.name as name, s.kind as kind, s.file as file, ' +
+    's.startLine as startLine, s.endLine as endLine, s.visibility as visibility'
+  );
+  const prefix = options.path?.replace(/\\/g, '/').replace(/^\.\//, '').replace(/^\.$/, '');
+
+  const symbols = rows.map(row => ({
+    qualifiedName: String(row.qualifiedName),
+    name: String(row.name ?? ''),
+    kind: String(row.kind ?? ''),
+    file: String(row.file ?? ''),
+    startLine: Number(row.startLine ?? 0),
+    endLine: Number(row.endLine ?? 0),
+    visibility: String(row.visibility ?? 'public')
+  })).filter(symbol =>
+    DOCUMENTED_KINDS.has(symbol.kind) &&
+    symbol.startLine > 0 &&
+    (!prefix || symbol.file.startsWith(prefix)) &&
+    !TEST_FILE.test(symbol.file) &&
+    !isIgnored(symbol, options.ignore)
+  );
+
+  const targets: DocTarget[] = [];
+  const byFile = new Map<string, typeof symbols>();
+  for (const symbol of symbols) byFile.set(symbol.file, [...(byFile.get(symbol.file) ?? []), symbol]);
+
+  for (const [file, fileSymbols] of [...byFile.entries()].sort((a, b) => a[0].localeCompare(b[0]))) {
+    const language = detectLanguage(file);
+    if (!DOCUMENTED_LANGUAGES.has(language)) continue;
+    const content = await options.readFile(file);
+    if (content === null) continue;
+    const lines = content.split('\n');
+
+    for (const symbol of fileSymbols.sort((a, b) => a.startLine - b.startLine)) {
+      // Stale graph entries point past the end of the file or at another line
+      if (symbol.startLine > lines.length || !lines[symbol.startLine - 1].includes(symbol.name)) continue;
+      if (!isPublicSymbol(symbol, language, lines) || isPrivateName(symbol.name, language)) continue;
+      const documented = findDocComment(lines, symbol.startLine, language) !== null;
+      if (documented && options.missingOnly) continue;
+      targets.push({
+        qualifiedName: symbol.qualifiedName,
+        name: symbol.name,
+        kind: symbol.kind,
+        file,
+        startLine: symbol.startLine,
+        endLine: symbol.endLine,
+        language,
+        documented
+      });
+    }
+  }
+  return targets;
+}
+
+/**
+ * Dunder methods, constructors and similar, documented by convention elsewhere
+ */
+function isPrivateName(name: string, language: string): boolean {
+  if (language === 'python') return /^__\w+__$/.test(name) && name !== '__init__';
+  return name === 'constructor' || name.startsWith('#');
+}
+
+/**
+ * `docgen.ignore` patterns match a name, a qualified name or a file glob
+ */
+function isIgnored(symbol: { name: string; qualifiedName: string; file: string }, ignore: string[] = []): boolean {
+  return ignore.some(pattern =>
+    matchGlob(symbol.name, pattern) ||
+    matchGlob(symbol.qualifiedName, pattern) ||
+    matchGlob(symbol.file, pattern) ||
+    (pattern.endsWith('/') && symbol.file.startsWith(pattern))
+  );
+}
+
+/**
+ * Attribute or decorator lines (`#[derive(..)]`, `@Override`) directly above
+ * a definition; returns the 0-based line the item's preamble starts at
+ */
+function preambleStart(lines: string[], startLine: number): number {
+  let start = startLine - 1;
+  for (let i = startLine - 2; i >= 0; i--) {
+    const line = lines[i].trim();
+    if (/^#\[/.test(line) || /^@[\w.]/.test(line)) {
+      start = i;
+    } else {
+      break;
+    }
+  }
+  return start;
+}
+
+/**
+ * Line after a Python signature, which may span several lines
+ */
+function pythonBodyStart(lines: string[], startLine: number): number {
+  for (let i = startLine - 1; i < lines.length; i++) {
+    if (/:\s*(#.*)?$/.test(lines[i])) return i + 1;
+  }
+  return startLine;
+}
+
+/**
+ * The item's existing doc comment, or null
+ *
+ * @param startLine - 1-based line of the definition
+ */
+export function findDocComment(lines: string[], startLine: number, language: string): DocCommentRange | null {
+  if (language === 'python') {
+    let i = pythonBodyStart(lines, startLine);
+    while (i < lines.length && !lines[i].trim()) i++;
+    const quote = lines[i]?.trim().match(/^[rRuU]?("""|''')/)?.[1];
+    if (!quote) return null;
+    const rest = lines[i].trim().replace(/^[rRuU]?("""|''')/, '');
+    if (rest.includes(quote)) return { start: i, end: i };
+    for (let j = i + 1; j < lines.length; j++) {
+      if (lines[j].includes(quote)) return { start: i, end: j };
+    }
+    return null;
+  }
+
+  const end = preambleStart(lines, startLine) - 1;
+  const last = lines[end]?.trim() ?? '';
+  if (!last) return null;
+
+  if (language === 'rust' && last.startsWith('///')) {
+    let start = end;
+    while (start > 0 && lines[start - 1].trim().startsWith('///')) start--;
+    return { start, end };
+  }
+  if (language === 'go' && last.startsWith('//')) {
+    let start = end;
+    while (start > 0 && lines[start - 1].trim().startsWith('//')) start--;
+    return { start, end };
+  }
+  if (last.endsWith('*/')) {
+    for (let start = end; start >= 0; start--) {
+      const line = lines[start].trim();
+      if (line.startsWith('/**')) return { start, end };
+      if (line.startsWith('/*')) return null;
+    }
+  }
+  return null;
+}
+
+/**
+ * Documentation text as a comment in the language's doc style
+ */
+export function renderDocComment(doc: string, language: string, indent: string): string[] {
+  const text = doc.replace(/\r\n/g, '\n').trim().split('\n').map(line => line.trimEnd());
+  switch (language) {
+    case 'rust':
+      return text.map(line => `${indent}///${line ? ` ${line}` : ''}`);
+    case 'go':
+      return text.map(line => `${indent}//${line ? ` ${line}` : ''}`);
+    case 'python': {
+      const body = text.map(line => line.replace(/"""/g, '\\"\\"\\"'));
+      if (body.length === 1) return [`${indent}"""${body[0]}"""`];
+      return [`${indent}"""${body[0]}`, ...body.slice(1).map(line => (line ? `${indent}${line}` : '')), `${indent}"""`];
+    }
+    default:
+      return [
+        `${indent}/**`,
+        ...text.map(line => `${indent} *${line ? ` ${line.replace(/\*\//g, '*\\/')}` : ''}`),
+        `${indent} */`
+      ];
+  }
+}
+
+/**
+ * Source with the generated docs inserted, replacing existing doc comments
+ */
+export function applyDocComments(source: string, language: string, items: Array<{ startLine: number; doc: string }>): string {
+  const lines = source.split('\n');
+  // Bottom-up so earlier line numbers stay valid
+  for (const item of [...items].sort((a, b) => b.startLine - a.startLine)) {
+    const definition = lines[item.startLine - 1];
+    if (definition === undefined || !item.doc.trim()) continue;
+    const existing = findDocComment(lines, item.startLine, language);
+
+    if (language === 'python') {
+      const bodyStart = pythonBodyStart(lines, item.startLine);
+      const next = lines.slice(bodyStart).find(line => line.trim());
+      const ownIndent = definition.match(/^\s*/)![0];
+      const bodyIndent = next && next.match(/^\s*/)![0].length > ownIndent.length ? next.match(/^\s*/)![0] : `${ownIndent}    `;
+      const comment = renderDocComment(item.doc, language, bodyIndent);
+      if (existing) lines.splice(existing.start, existing.end - existing.start + 1, ...comment);
+      else lines.splice(bodyStart, 0, ...comment);
+      continue;
+    }
+
+    const comment = renderDocComment(item.doc, language, definition.match(/^\s*/)![0]);
+    if (existing) lines.splice(existing.start, existing.end - existing.start + 1, ...comment);
+    else lines.splice(preambleStart(lines, item.startLine), 0, ...comment);
+  }
+  return lines.join('\n');
+}
+
+const STYLE_GUIDE: Record<string, string> = {
+  rust: 'rustdoc: a one-line summary, then details in Markdown; add `# Errors`, `# Panics` or `# Safety` sections only when they apply, and an `# Examples` section for public functions whose use isn\'t obvious.',
+  go: 'Go doc comments: full sentences that begin with the item\'s name ("Multiply returns ...").',
+  python: 'PEP 257 docstrings: a one-line summary ending in a period, then Args:, Returns: and Raises: sections (Google style) when they apply.',
+  typescript: 'TSDoc: a summary sentence, then @param name - description, @returns and @throws tags when they apply; no types in the tags.',
+  javascript: 'JSDoc: a summary sentence, then @param {Type} name description, @returns and @throws tags when they apply.',
+  java: 'Javadoc: a summary sentence, then @param, @return and @throws tags when they apply.',
+  kotlin: 'KDoc: a summary sentence, then @param, @return and @throws tags when they apply.',
+  c: 'Doxygen: a summary sentence, then @param, @return when they apply.',
+  cpp: 'Doxygen: a summary sentence, then @param, @return and @throws when they apply.',
+};
+
+export const DOCGEN_SYSTEM_PROMPT = `You write concise, accurate documentation comments for code.
+Describe what each item does and how to use it, not how it is implemented; don't restate the signature or invent behavior the code doesn't show.
+Reply with JSON only.`;
+
+/**
+ * Prompt for the items of one file
+ */
+export function buildDocgenPrompt(file: string, source: string, targets: DocTarget[]): string {
+  const language = targets[0]?.language ?? detectLanguage(file);
+  // JavaScript shares the TypeScript parser but documents types in JSDoc tags
+  const style = language === 'typescript' && /\.[cm]?jsx?$/.test(file) ? 'javascript' : language;
+  const lines = source.split('\n');
+  const parts = [
+    `Write documentation for these items in ${file}.`,
+    `Style: ${STYLE_GUIDE[style] ?? 'the language\'s usual doc comment conventions.'}`,
+    'Give the text only, without comment markers (no ///, /** */, # or quotes); wrap lines at about 80 characters.',
+    ''
+  ];
+  for (const target of targets) {
+    const body = lines.slice(target.startLine - 1, target.endLine);
+    const shown = body.length > MAX_ITEM_LINES ? [...body.slice(0, MAX_ITEM_LINES), '...'] : body;
+    parts.push(`### ${target.qualifiedName} (${target.kind}, line ${target.startLine})`);
+    parts.push('```' + language, shown.join('\n'), '```', '');
+  }
+  const context = lines.slice(0, 40).filter(line => /^\s*(use|import|from|package|mod|#include)\b/.test(line));
+  if (context.length > 0) parts.push('The file\'s imports, for context:', '```' + language, context.join('\n'), '```', '');
+  parts.push('Reply as {"docs": [{"id": "<the ### name>", "doc": "<documentation text>"}]}.');
+  return parts.join('\n');
+}
+
+/**
+ * Docs from the model's reply, for the requested items only
+ */
+export function parseDocgenResponse(response: string, targets: DocTarget[]): GeneratedDoc[] {
+  const fenced = response.match(/```(?:json)?\s*\n([\s\S]*?)\n```/);
+  const candidate = fenced ? fenced[1] : response.match(/\{[\s\S]*\}/)?.[0];
+  let parsed: any;
+  try {
+    parsed = candidate ? JSON.parse(candidate) : undefined;
+  } catch {
+    parsed = undefined;
+  }
+  if (!parsed || !Array.isArray(parsed.docs)) {
+    throw new Error('The model did not return documentation as JSON');
+  }
+
+  const wanted = new Set(targets.map(target => target.qualifiedName));
+  const docs = new Map<string, string>();
+  for (const raw of parsed.docs) {
+    const id = String(raw?.id ?? '').trim();
+    const doc = typeof raw?.doc === 'string' ? stripCommentMarkers(raw.doc) : '';
+    if (wanted.has(id) && doc && !docs.has(id)) docs.set(id, doc);
+  }
+  return [...docs.entries()].map(([qualifiedName, doc]) => ({ qualifiedName, doc }));
+}
+
+/**
+ * Some models wrap the text in comment markers anyway
+ */
+function stripCommentMarkers(doc: string): string {
+  let lines = doc.trim().split('\n');
+  if (/^\/\*\*/.test(lines[0]) || /^"""/.test(lines[0])) {
+    lines[0] = lines[0].replace(/^(\/\*\*|""")\s?/, '');
+    lines[lines.length - 1] = lines[lines.length - 1].replace(/\s?(\*\/|""")$/, '');
+    lines = lines.map(line => line.replace(/^\s*\*(?!\/) ?/, ''));
+  } else if (lines.every(line => /^\s*(\/\/\/?|#)/.test(line))) {
+    lines = lines.map(line => line.replace(/^\s*(\/\/\/?|#) ?/, ''));
+  }
+  return lines.join('\n').trim();
+}
+
+/**
+ * Targets grouped by file, for one request per file
+ */
+export function groupDocTargets(targets: DocTarget[], maxPerRequest = 20): DocTarget[][] {
+  const groups: DocTarget[][] = [];
+  const byFile = new Map<string, DocTarget[]>();
+  for (const target of targets) byFile.set(target.file, [...(byFile.get(target.file) ?? []), target]);
+  for (const fileTargets of byFile.values()) {
+    for (let i = 0; i < fileTargets.length; i += maxPerRequest) groups.push(fileTargets.slice(i, i + maxPerRequest));
+  }
+  return groups.sort((a, b) => a[0].file.localeCompare(b[0].file) || a[0].startLine - b[0].startLine);
+}
+
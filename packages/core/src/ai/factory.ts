@@ -16,23 +16,66 @@ import {
 import { OpenRouterClient, createOpenRouterClient, OPENROUTER_MODELS } from './openrouter.js';
 import { OllamaClient, createOllamaClient, isOllamaRunning } from './ollama.js';
 import { LMStudioClient, createLMStudioClient, isLMStudioRunning } from './lmstudio.js';
+import { createAnthropicClient } from './anthropic.js';
+import { createGeminiClient } from './gemini.js';
+import { createOpenAIChatClient } from './openai.js';
 
-export type AIProvider = 'openrouter' | 'ollama' | 'lmstudio' | 'auto';
+export type AIProvider = 'anthropic' | 'gemini' | 'openai' | 'openrouter' | 'ollama' | 'lmstudio' | 'auto';
+
+/** Providers that can be named in a `provider/model` spec */
+export const MODEL_PROVIDERS: Exclude<AIProvider, 'auto'>[] = ['anthropic', 'gemini', 'openai', 'openrouter', 'ollama', 'lmstudio'];
+
+const PROVIDER_ALIASES: Record<string, Exclude<AIProvider, 'auto'>> = {
+  claude: 'anthropic',
+  google: 'gemini',
+};
+
+export interface ModelSpec {
+  /** Undefined when the spec names only a model */
+  provider?: Exclude<AIProvider, 'auto'>;
+  model?: string;
+}
 
 export interface AIClientOptions {
   provider?: AIProvider;
   model?: string;
-  apiKey?: string;        // Required for OpenRouter
+  apiKey?: string;        // Required for Anthropic, Gemini, OpenAI and OpenRouter
   ollamaUrl?: string;     // Optional Ollama URL (default: localhost:11434)
   lmstudioUrl?: string;   // Optional LM Studio URL (default: localhost:1234/v1)
   maxTokens?: number;
   temperature?: number;
 }
 
+/**
+ * Split a `--model` value into provider and model
+ *
+ * `anthropic/claude-sonnet-4-5`, `gemini/gemini-2.5-pro`, `openai/gpt-4o`,
+ * `ollama/qwen2.5-coder:14b` and `openrouter/openai/gpt-4o` pick the
+ * provider explicitly (`google/` and `claude/` are accepted as aliases);
+ * a bare provider name uses its default model. Anything else is a model
+ * name for the command's default provider, so OpenRouter ids such as
+ * `meta-llama/llama-3.1-70b-instruct` keep working.
+ */
+export function parseModelSpec(spec: string | undefined): ModelSpec {
+  const value = spec?.trim();
+  if (!value) return {};
+
+  const slash = value.indexOf('/');
+  const head = (slash === -1 ? value : value.slice(0, slash)).toLowerCase();
+  const provider = PROVIDER_ALIASES[head] ?? MODEL_PROVIDERS.find(p => p === head);
+  if (!provider) return { model: value };
+
+  const model = slash === -1 ? '' : value.slice(slash + 1).trim();
+  return model ? { provider, model } : { provider };
+}
+
 /**
  * Create an AI client based on options
  *
  * Provider selection:
+ * - 'anthropic': Use the Anthropic API directly (requires API key)
+ * - 'gemini': Use the Google Gemini API (requires API key)
+ * - 'openai': Use the OpenAI API directly (requires API key)
  * - 'openrouter': Use OpenRouter cloud API (requires API key)
  * - 'ollama': Use local Ollama instance
  * - 'auto': Try Ollama first, fall back to OpenRouter if available
@@ -58,6 +101,42 @@ export async function createAIClient(options: AIClientOptions): Promise<AIClient
     });
   }
 
+  if (provider === 'anthropic') {
+    if (!options.apiKey) {
+      throw new Error('Anthropic API key required. Run: cv auth setup anthropic (or set ANTHROPIC_API_KEY)');
+    }
+    return createAnthropicClient({
+      apiKey: options.apiKey,
+      model: options.model,
+      maxTokens: options.maxTokens,
+      temperature: options.temperature,
+    });
+  }
+
+  if (provider === 'gemini') {
+    if (!options.apiKey) {
+      throw new Error('Gemini API key required. Run: cv auth setup gemini (or set GEMINI_API_KEY)');
+    }
+    return createGeminiClient({
+      apiKey: options.apiKey,
+      model: options.model,
+      maxTokens: options.maxTokens,
+      temperature: options.temperature,
+    });
+  }
+
+  if (provider === 'openai') {
+    if (!options.apiKey) {
+      throw new Error('OpenAI API key required. Run: cv auth setup openai (or set OPENAI_API_KEY)');
+    }
+    return createOpenAIChatClient({
+      apiKey: options.apiKey,
+      model: options.model,
+      maxTokens: options.maxTokens,
+      temperature: options.temperature,
+    });
+  }
+
   if (provider === 'openrouter') {
     if (!options.apiKey) {
       throw new Error('OpenRouter API key required. Set OPENROUTER_API_KEY or use --provider ollama');
@@ -0,0 +1,240 @@
+/**
+ * Model Fallback Chains
+ *
+ * Wraps an ordered list of models (`openrouter/claude-sonnet-4-5 ->
+ * openai/gpt-4o -> ollama/llama3`) behind one AIClient. A request goes to
+ * the first model; when it fails with a rate limit, timeout, connection
+ * error or 5xx, the next model is tried. Other errors (bad request, invalid
+ * key) are returned as they are, since another model would not fix them.
+ *
+ * A streamed answer only falls back before its first token, so nothing is
+ * printed twice.
+ */
+
+import {
+  AIClient,
+  AIMessage,
+  AIStreamHandler,
+  AITool,
+  AIToolMessage,
+  AIToolResult,
+} from './types.js';
+
+export interface FallbackModel {
+  /** `provider/model` label used in logs */
+  spec: string;
+  /** Creates the client on first use; may throw (e.g. missing API key) */
+  create: () => Promise<AIClient>;
+}
+
+export interface FallbackEvent {
+  /** Model that failed */
+  from: string;
+  /** Model tried next */
+  to: string;
+  error: Error;
+}
+
+export interface FallbackOptions {
+  /** Called each time a model is skipped */
+  onFallback?: (event: FallbackEvent) => void;
+}
+
+/** HTTP statuses worth retrying on another model */
+const RETRYABLE_STATUS = new Set([408, 409, 425, 429, 500, 502, 503, 504, 520, 522, 524, 529]);
+
+const NETWORK_CODES = new Set([
+  'ETIMEDOUT', 'ECONNRESET', 'ECONNREFUSED', 'EPIPE', 'EAI_AGAIN', 'ENOTFOUND', 'EHOSTUNREACH', 'ENETUNREACH',
+  'UND_ERR_CONNECT_TIMEOUT', 'UND_ERR_HEADERS_TIMEOUT', 'UND_ERR_BODY_TIMEOUT', 'UND_ERR_SOCKET',
+]);
+
+/**
+ * Split a chain written as `a -> b -> c` (or given as a list) into model specs
+ */
+export function parseFallbackChain(chain: string | string[] | undefined): string[] {
+  if (!chain) return [];
+  const parts = Array.isArray(chain) ? chain : chain.split(/\s*(?:->|,)\s*/);
+  return parts.map(part => part.trim()).filter(Boolean);
+}
+
+/**
+ * HTTP status of a provider error: SDK errors carry `status`, the fetch-based
+ * clients put it in the message (`... API error: 503 - ...`)
+ */
+export function providerErrorStatus(error: unknown): number | undefined {
+  const err = error as { status?: unknown; statusCode?: unknown; message?: unknown } | undefined;
+  if (typeof err?.status === 'number') return err.status;
+  if (typeof err?.statusCode === 'number') return err.statusCode;
+  const match = typeof err?.message === 'string' ? err.message.match(/\berror:?\s+(\d{3})\b/i) : null;
+  return match ? Number(match[1]) : undefined;
+}
+
+/**
+ * Whether another model might succeed where this one failed
+ */
+export function isRetryableProviderError(error: unknown): boolean {
+  const status = providerErrorStatus(error);
+  if (status !== undefined) return RETRYABLE_STATUS.has(status);
+
+  const err = error as { name?: string; code?: string; cause?: { code?: string }; message?: string } | undefined;
+  if (!err) return false;
+  if (err.name === 'AbortError' || err.name === 'TimeoutError') return true;
+  if (err.name === 'APIConnectionError' || err.name === 'APIConnectionTimeoutError') return true;
+  // Offline, a local model later in the chain may still answer
+  if (err.name === 'OfflineError') return true;
+  if (err.code && NETWORK_CODES.has(err.code)) return true;
+  if (err.cause?.code && NETWORK_CODES.has(err.cause.code)) return true;
+  return /fetch failed|timed? ?out|socket hang up|overloaded|rate limit/i.test(err.message ?? '');
+}
+
+/** A model that lacks a capability the request needs; the next one is tried */
+class UnsupportedRequestError extends Error {}
+
+/**
+ * AIClient that tries each model of a chain in turn
+ */
+export class FallbackClient implements AIClient {
+  private clients = new Map<number, AIClient>();
+  private answeredBy?: { spec: string; client: AIClient };
+
+  constructor(private models: FallbackModel[], private options: FallbackOptions = {}) {
+    if (models.length === 0) {
+      throw new Error('A fallback chain needs at least one model');
+    }
+  }
+
+  /** Models in the chain, in order */
+  getChain(): string[] {
+    return this.models.map(m => m.spec);
+  }
+
+  /** Model that answered the last request */
+  getAnsweredBy(): string | undefined {
+    return this.answeredBy?.spec;
+  }
+
+  getProvider(): string {
+    return (this.answeredBy?.client ?? this.clients.get(0))?.getProvider() ?? this.models[0].spec.split('/')[0];
+  }
+
+  getModel(): string {
+    return (this.answeredBy?.client ?? this.clients.get(0))?.getModel() ?? this.models[0].spec;
+  }
+
+  /**
+   * Switching model replaces the chain with that model on the first provider
+   */
+  setModel(model: string): void {
+    const first = this.models[0];
+    const existing = this.clients.get(0);
+    this.models = [{
+      spec: model,
+      create: async () => {
+        const client = existing ?? await first.create();
+        client.setModel(model);
+        return client;
+      },
+    }];
+    this.clients = new Map();
+    this.answeredBy = undefined;
+  }
+
+  async isReady(): Promise<boolean> {
+    for (let i = 0; i < this.models.length; i++) {
+      try {
+        if (await (await this.client(i)).isReady()) return true;
+      } catch {
+        // Try the next model
+      }
+    }
+    return false;
+  }
+
+  chat(messages: AIMessage[], systemPrompt?: string): Promise<string> {
+    return this.attempt(client => client.chat(messages, systemPrompt));
+  }
+
+  async chatStream(messages: AIMessage[], systemPrompt?: string, handler?: AIStreamHandler): Promise<string> {
+    try {
+      return await this.attempt((client, commit) =>
+        client.chatStream(messages, systemPrompt, {
+          onToken: token => {
+            // Part of the answer is on screen; a failure now is reported, not retried
+            commit();
+            handler?.onToken?.(token);
+          },
+          onComplete: handler?.onComplete,
+        })
+      );
+    } catch (error) {
+      handler?.onError?.(error as Error);
+      throw error;
+    }
+  }
+
+  complete(prompt: string, handler?: AIStreamHandler): Promise<string> {
+    return this.chatStream([{ role: 'user', content: prompt }], undefined, handler);
+  }
+
+  chatWithTools(messages: AIToolMessage[], tools: AITool[], systemPrompt?: string): Promise<AIToolResult> {
+    return this.attempt(async client => {
+      if (!client.chatWithTools) {
+        throw new UnsupportedRequestError(`${client.getProvider()} does not support tool calls`);
+      }
+      return client.chatWithTools(messages, tools, systemPrompt);
+    });
+  }
+
+  async listModels(): Promise<string[]> {
+    const client = await this.client(0);
+    return client.listModels ? client.listModels() : [];
+  }
+
+  private async client(index: number): Promise<AIClient> {
+    let client = this.clients.get(index);
+    if (!client) {
+      client = await this.models[index].create();
+      this.clients.set(index, client);
+    }
+    return client;
+  }
+
+  /**
+   * Run a request against each model until one succeeds or an error is not
+   * worth retrying. `commit` marks the request as past the point of retrying.
+   */
+  private async attempt<T>(request: (client: AIClient, commit: () => void) => Promise<T>): Promise<T> {
+    let lastError: unknown;
+
+    for (let i = 0; i < this.models.length; i++) {
+      const hasNext = i < this.models.length - 1;
+      const fallBack = (error: unknown) => {
+        lastError = error;
+        this.options.onFallback?.({ from: this.models[i].spec, to: this.models[i + 1].spec, error: error as Error });
+      };
+
+      let client: AIClient;
+      try {
+        client = await this.client(i);
+      } catch (error) {
+        // A model that cannot be set up (e.g. no API key) is skipped
+        if (!hasNext) throw error;
+        fallBack(error);
+        continue;
+      }
+
+      let committed = false;
+      try {
+        const result = await request(client, () => { committed = true; });
+        this.answeredBy = { spec: this.models[i].spec, client };
+        return result;
+      } catch (error) {
+        const retry = error instanceof UnsupportedRequestError || isRetryableProviderError(error);
+        if (committed || !retry || !hasNext) throw error;
+        fallBack(error);
+      }
+    }
+
+    throw lastError;
+  }
+}
@@ -0,0 +1,154 @@
+/**
+ * Gemini Client Tests
+ * All HTTP calls are mocked.
+ */
+
+import { describe, it, expect, vi, beforeEach } from 'vitest';
+import { GeminiClient } from './gemini.js';
+
+// Mock fetch globally
+const mockFetch = vi.fn();
+vi.stubGlobal('fetch', mockFetch);
+
+const reply = (parts: unknown[]) => ({
+  ok: true,
+  json: async () => ({ candidates: [{ content: { role: 'model', parts } }] }),
+});
+
+describe('GeminiClient', () => {
+  let client: GeminiClient;
+
+  beforeEach(() => {
+    mockFetch.mockReset();
+    client = new GeminiClient({
+      apiKey: 'test-key',
+      baseUrl: 'https://gemini.test/v1beta',
+      model: 'models/gemini-test',
+    });
+  });
+
+  it('returns gemini as provider and strips the models/ prefix', () => {
+    expect(client.getProvider()).toBe('gemini');
+    expect(client.getModel()).toBe('gemini-test');
+  });
+
+  describe('listModels', () => {
+    it('lists models that support generateContent', async () => {
+      mockFetch.mockResolvedValueOnce({
+        ok: true,
+        json: async () => ({
+          models: [
+            { name: 'models/gemini-2.5-pro', supportedGenerationMethods: ['generateContent', 'countTokens'] },
+            { name: 'models/text-embedding-004', supportedGenerationMethods: ['embedContent'] },
+          ],
+        }),
+      });
+
+      expect(await client.listModels()).toEqual(['gemini-2.5-pro']);
+      expect(mockFetch.mock.calls[0][1].headers['x-goog-api-key']).toBe('test-key');
+    });
+
+    it('returns an empty list when the request fails', async () => {
+      mockFetch.mockRejectedValueOnce(new Error('offline'));
+      expect(await client.listModels()).toEqual([]);
+    });
+  });
+
+  describe('chat', () => {
+    it('sends the system prompt separately and maps assistant turns to model', async () => {
+      mockFetch.mockResolvedValueOnce(reply([{ text: 'Hello ' }, { text: 'world' }]));
+
+      const result = await client.chat(
+        [
+          { role: 'user', content: 'Hi' },
+          { role: 'assistant', content: 'Hey' },
+          { role: 'user', content: 'Again' },
+        ],
+        'Be brief'
+      );
+
+      expect(result).toBe('Hello world');
+      const [url, opts] = mockFetch.mock.calls[0];
+      expect(url).toBe('https://gemini.test/v1beta/models/gemini-test:generateContent');
+      const body = JSON.parse(opts.body);
+      expect(body.systemInstruction).toEqual({ parts: [{ text: 'Be brief' }] });
+      expect(body.contents.map((c: any) => c.role)).toEqual(['user', 'model', 'user']);
+    });
+
+    it('throws on API error', async () => {
+      mockFetch.mockResolvedValueOnce({ ok: false, status: 403, text: async () => 'API key not valid' });
+
+      await expect(client.chat([{ role: 'user', content: 'Hi' }])).rejects.toThrow('Gemini API error: 403');
+    });
+  });
+
+  describe('chatStream', () => {
+    it('streams text from SSE chunks', async () => {
+      const sse = [
+        'data: {"candidates":[{"content":{"parts":[{"text":"Hel"}]}}]}\r\n\r\n',
+        'data: {"candidates":[{"content":{"parts":[{"text":"lo"}]}}]}\r\n\r\n',
+      ].join('');
+      const encoded = new TextEncoder().encode(sse);
+      mockFetch.mockResolvedValueOnce({
+        ok: true,
+        body: new ReadableStream({
+          start(controller) {
+            controller.enqueue(encoded.slice(0, 30));
+            controller.enqueue(encoded.slice(30));
+            controller.close();
+          },
+        }),
+      });
+
+      const tokens: string[] = [];
+      const result = await client.chatStream([{ role: 'user', content: 'Hi' }], undefined, {
+        onToken: token => tokens.push(token),
+      });
+
+      expect(result).toBe('Hello');
+      expect(tokens).toEqual(['Hel', 'lo']);
+      expect(mockFetch.mock.calls[0][0]).toContain(':streamGenerateContent?alt=sse');
+    });
+  });
+
+  describe('chatWithTools', () => {
+    it('declares functions and returns the calls the model made', async () => {
+      mockFetch.mockResolvedValueOnce(reply([
+        { functionCall: { name: 'read_file', args: { path: 'src/a.ts' } } },
+      ]));
+
+      const result = await client.chatWithTools(
+        [{ role: 'user', content: 'Open a.ts' }],
+        [{ name: 'read_file', description: 'Read a file', parameters: { type: 'object', properties: { path: { type: 'string' } } } }]
+      );
+
+      expect(result.toolCalls).toEqual([{ id: 'call_1', name: 'read_file', arguments: { path: 'src/a.ts' } }]);
+      const body = JSON.parse(mockFetch.mock.calls[0][1].body);
+      expect(body.tools[0].functionDeclarations[0].name).toBe('read_file');
+    });
+
+    it('sends tool results back as functionResponse parts', async () => {
+      mockFetch.mockResolvedValueOnce(reply([{ text: 'It exports foo' }]));
+
+      const result = await client.chatWithTools(
+        [
+          { role: 'user', content: 'Open a.ts' },
+          { role: 'assistant', content: '', toolCalls: [{ id: 'call_1', name: 'read_file', arguments: { path: 'src/a.ts' } }] },
+          { role: 'tool', toolCallId: 'call_1', name: 'read_file', content: 'export const foo = 1;' },
+        ],
+        []
+      );
+
+      expect(result).toEqual({ text: 'It exports foo', toolCalls: [] });
+      const body = JSON.parse(mockFetch.mock.calls[0][1].body);
+      expect(body.contents[1]).toEqual({
+        role: 'model',
+        parts: [{ functionCall: { name: 'read_file', args: { path: 'src/a.ts' } } }],
+      });
+      expect(body.contents[2]).toEqual({
+        role: 'user',
+        parts: [{ functionResponse: { name: 'read_file', response: { content: 'export const foo = 1;' } } }],
+      });
+    });
+  });
+});
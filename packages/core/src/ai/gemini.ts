@@ -0,0 +1,282 @@
+/**
+ * Gemini Client
+ * Google Gemini models via the Generative Language API
+ *
+ * Default URL: https://generativelanguage.googleapis.com/v1beta
+ * (configurable via CV_GEMINI_URL). API key from `cv auth setup gemini`
+ * or GEMINI_API_KEY.
+ */
+
+import {
+  AIClient,
+  AIMessage,
+  AIStreamHandler,
+  AITool,
+  AIToolCall,
+  AIToolMessage,
+  AIToolResult,
+} from './types.js';
+import { getGeminiUrl } from '../config/service-urls.js';
+import { readSSE } from './stream.js';
+import { providerFetch } from './http.js';
+
+export interface GeminiOptions {
+  apiKey: string;
+  model?: string;
+  maxTokens?: number;
+  temperature?: number;
+  baseUrl?: string;
+}
+
+export const DEFAULT_GEMINI_MODEL = 'gemini-2.5-pro';
+
+interface GeminiPart {
+  text?: string;
+  functionCall?: { name: string; args?: Record<string, unknown> };
+  functionResponse?: { name: string; response: Record<string, unknown> };
+}
+
+interface GeminiContent {
+  role: 'user' | 'model';
+  parts: GeminiPart[];
+}
+
+interface GeminiResponse {
+  candidates?: Array<{ content?: { parts?: GeminiPart[] } }>;
+  promptFeedback?: { blockReason?: string };
+}
+
+/**
+ * Gemini API client
+ */
+export class GeminiClient implements AIClient {
+  private model: string;
+  private maxTokens: number;
+  private temperature: number;
+  private baseUrl: string;
+
+  constructor(private options: GeminiOptions) {
+    this.baseUrl = options.baseUrl ? options.baseUrl.replace(/\/$/, '') : getGeminiUrl();
+    this.model = normalizeModel(options.model || DEFAULT_GEMINI_MODEL);
+    this.maxTokens = options.maxTokens || 8192;
+    this.temperature = options.temperature ?? 0.7;
+  }
+
+  getProvider(): string {
+    return 'gemini';
+  }
+
+  getModel(): string {
+    return this.model;
+  }
+
+  setModel(model: string): void {
+    this.model = normalizeModel(model);
+  }
+
+  /**
+   * Check that the API key is accepted
+   */
+  async isReady(): Promise<boolean> {
+    try {
+      const response = await fetch(`${this.baseUrl}/models?pageSize=1`, { headers: this.headers() });
+      return response.ok;
+    } catch {
+      return false;
+    }
+  }
+
+  /**
+   * List models that support content generation
+   */
+  async listModels(): Promise<string[]> {
+    try {
+      const response = await fetch(`${this.baseUrl}/models?pageSize=1000`, { headers: this.headers() });
+      if (!response.ok) return [];
+      const data = await response.json() as {
+        models?: Array<{ name: string; supportedGenerationMethods?: string[] }>;
+      };
+      return (data.models || [])
+        .filter(m => !m.supportedGenerationMethods || m.supportedGenerationMethods.includes('generateContent'))
+        .map(m => m.name.replace(/^models\//, ''));
+    } catch {
+      return [];
+    }
+  }
+
+  /**
+   * Chat completion (non-streaming)
+   */
+  async chat(messages: AIMessage[], systemPrompt?: string): Promise<string> {
+    const data = await this.generate('generateContent', this.buildRequest(messages, systemPrompt));
+    return textOf(data);
+  }
+
+  /**
+   * Chat completion with streaming (server-sent events)
+   */
+  async chatStream(
+    messages: AIMessage[],
+    systemPrompt?: string,
+    handler?: AIStreamHandler
+  ): Promise<string> {
+    let fullText = '';
+
+    try {
+      const response = await this.post('streamGenerateContent?alt=sse', this.buildRequest(messages, systemPrompt));
+      if (!response.body) {
+        throw new Error('No response body');
+      }
+
+      for await (const data of readSSE(response.body)) {
+        let chunk: GeminiResponse;
+        try {
+          chunk = JSON.parse(data);
+        } catch {
+          continue;
+        }
+        const token = textOf(chunk);
+        if (token) {
+          fullText += token;
+          handler?.onToken?.(token);
+        }
+      }
+
+      handler?.onComplete?.(fullText);
+      return fullText;
+    } catch (error) {
+      handler?.onError?.(error as Error);
+      throw error;
+    }
+  }
+
+  /**
+   * Simple completion (single prompt)
+   */
+  async complete(prompt: string, handler?: AIStreamHandler): Promise<string> {
+    return this.chatStream([{ role: 'user', content: prompt }], undefined, handler);
+  }
+
+  /**
+   * Chat completion that may answer with function calls
+   */
+  async chatWithTools(
+    messages: AIToolMessage[],
+    tools: AITool[],
+    systemPrompt?: string
+  ): Promise<AIToolResult> {
+    const data = await this.generate('generateContent', {
+      ...this.buildRequest(messages, systemPrompt),
+      ...(tools.length > 0 ? {
+        tools: [{
+          functionDeclarations: tools.map(tool => ({
+            name: tool.name,
+            description: tool.description,
+            parameters: tool.parameters,
+          })),
+        }],
+      } : {}),
+    });
+
+    // Gemini does not assign call ids; number them so results can be matched up
+    const toolCalls: AIToolCall[] = [];
+    for (const part of data.candidates?.[0]?.content?.parts || []) {
+      if (part.functionCall) {
+        toolCalls.push({
+          id: `call_${toolCalls.length + 1}`,
+          name: part.functionCall.name,
+          arguments: part.functionCall.args || {},
+        });
+      }
+    }
+    return { text: textOf(data), toolCalls };
+  }
+
+  private headers(): Record<string, string> {
+    return { 'x-goog-api-key': this.options.apiKey };
+  }
+
+  private async post(method: string, body: unknown): Promise<Response> {
+    const response = await providerFetch('gemini', `${this.baseUrl}/models/${this.model}:${method}`, {
+      method: 'POST',
+      headers: { ...this.headers(), 'Content-Type': 'application/json' },
+      body: JSON.stringify(body),
+    });
+
+    if (!response.ok) {
+      const error = await response.text();
+      throw new Error(`Gemini API error: ${response.status} - ${error}`);
+    }
+    return response;
+  }
+
+  private async generate(method: string, body: unknown): Promise<GeminiResponse> {
+    const data = await (await this.post(method, body)).json() as GeminiResponse;
+    if (data.promptFeedback?.blockReason) {
+      throw new Error(`Gemini blocked the prompt: ${data.promptFeedback.blockReason}`);
+    }
+    return data;
+  }
+
+  /**
+   * Request body: system prompt and system messages become the system
+   * instruction, assistant turns use the 'model' role, and tool results are
+   * functionResponse parts of a user turn
+   */
+  private buildRequest(messages: AIToolMessage[], systemPrompt?: string): Record<string, unknown> {
+    const system = [
+      ...(systemPrompt ? [systemPrompt] : []),
+      ...messages.filter(m => m.role === 'system').map(m => m.content),
+    ];
+
+    const contents: GeminiContent[] = [];
+    const push = (role: GeminiContent['role'], part: GeminiPart) => {
+      const last = contents[contents.length - 1];
+      // Consecutive turns of the same role are merged
+      if (last?.role === role) last.parts.push(part);
+      else contents.push({ role, parts: [part] });
+    };
+
+    for (const message of messages) {
+      if (message.role === 'system') continue;
+      if (message.role === 'tool') {
+        push('user', { functionResponse: { name: message.name, response: { content: message.content } } });
+      } else if (message.role === 'assistant') {
+        if (message.content) push('model', { text: message.content });
+        if ('toolCalls' in message) {
+          for (const call of message.toolCalls) {
+            push('model', { functionCall: { name: call.name, args: call.arguments } });
+          }
+        }
+      } else {
+        push('user', { text: message.content });
+      }
+    }
+
+    return {
+      contents,
+      ...(system.length > 0 ? { systemInstruction: { parts: [{ text: system.join('\n\n') }] } } : {}),
+      generationConfig: {
+        maxOutputTokens: this.maxTokens,
+        temperature: this.temperature,
+      },
+    };
+  }
+}
+
+function normalizeModel(model: string): string {
+  return model.replace(/^models\//, '');
+}
+
+function textOf(data: GeminiResponse): string {
+  return (data.candidates?.[0]?.content?.parts || [])
+    .map(part => part.text || '')
+    .join('');
+}
+
+/**
+ * Create a Gemini client
+ */
+export function createGeminiClient(options: GeminiOptions): GeminiClient {
+  return new GeminiClient(options);
+}
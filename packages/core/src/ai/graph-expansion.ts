@@ -0,0 +1,155 @@
+/**
+ * Graph Expansion
+ * Walks callers/callees/imports outward from retrieved chunks, hop by hop,
+ * keeping the closest relations that fit in a token budget
+ */
+
+import { SymbolNode, FileNode } from '@cv-git/shared';
+import { GraphManager } from '../graph/index.js';
+
+export interface GraphExpansionOptions {
+  /** Number of hops to follow (0 = only the matched symbols) */
+  depth: number;
+  /** Approximate token budget for everything pulled in by expansion */
+  tokenBudget?: number;
+  /** Max neighbours followed per symbol/file per hop */
+  maxPerNode?: number;
+  /** Follow file IMPORTS edges as well as CALLS */
+  includeImports?: boolean;
+}
+
+export interface GraphExpansionResult {
+  symbols: SymbolNode[];
+  files: FileNode[];
+  /** Hop distance for each included symbol, keyed by qualifiedName */
+  hops: Map<string, number>;
+  /** Estimated tokens used by the included symbols and files */
+  tokensUsed: number;
+  /** Number of candidates dropped because the budget ran out */
+  pruned: number;
+}
+
+export const DEFAULT_EXPAND_DEPTH = 1;
+export const DEFAULT_EXPAND_TOKEN_BUDGET = 4000;
+
+/**
+ * Parse an --expand-depth value, falling back to the default for bad input
+ */
+export function parseExpandDepth(value: string | number | undefined): number {
+  if (value === undefined) return DEFAULT_EXPAND_DEPTH;
+  const depth = typeof value === 'number' ? value : parseInt(value, 10);
+  if (!Number.isFinite(depth) || depth < 0) return DEFAULT_EXPAND_DEPTH;
+  return Math.min(depth, 5);
+}
+
+/**
+ * Estimate the prompt cost of a symbol as rendered in context
+ */
+export function estimateSymbolTokens(symbol: SymbolNode): number {
+  const text = [
+    symbol.qualifiedName,
+    symbol.file,
+    symbol.signature || '',
+    symbol.docstring || ''
+  ].join(' ');
+  // Rough estimate: ~4 characters per token
+  return Math.ceil(text.length / 4) + 4;
+}
+
+function estimateFileTokens(file: FileNode): number {
+  return Math.ceil(file.path.length / 4) + 8;
+}
+
+/**
+ * Expand outward from seed symbols and files through the knowledge graph.
+ * Candidates are admitted breadth-first, so closer hops always win when the
+ * token budget is tight.
+ */
+export async function expandGraphContext(
+  graph: GraphManager,
+  seeds: { symbols: SymbolNode[]; files: string[] },
+  options: GraphExpansionOptions
+): Promise<GraphExpansionResult> {
+  const budget = options.tokenBudget ?? DEFAULT_EXPAND_TOKEN_BUDGET;
+  const maxPerNode = options.maxPerNode ?? 3;
+  const includeImports = options.includeImports ?? true;
+
+  const result: GraphExpansionResult = {
+    symbols: [],
+    files: [],
+    hops: new Map(),
+    tokensUsed: 0,
+    pruned: 0
+  };
+  const seenFiles = new Set<string>();
+
+  const admitSymbol = (symbol: SymbolNode, hop: number): boolean => {
+    if (result.hops.has(symbol.qualifiedName)) return false;
+    const cost = estimateSymbolTokens(symbol);
+    // Seeds are always kept; they are the retrieved chunks themselves
+    if (hop > 0 && result.tokensUsed + cost > budget) {
+      result.pruned++;
+      return false;
+    }
+    result.hops.set(symbol.qualifiedName, hop);
+    result.symbols.push(symbol);
+    result.tokensUsed += cost;
+    return true;
+  };
+
+  const admitFile = async (path: string, hop: number): Promise<boolean> => {
+    if (seenFiles.has(path)) return false;
+    seenFiles.add(path);
+    const file = await graph.getFileNode(path);
+    if (!file) return false;
+    const cost = estimateFileTokens(file);
+    if (hop > 0 && result.tokensUsed + cost > budget) {
+      result.pruned++;
+      return false;
+    }
+    result.files.push(file);
+    result.tokensUsed += cost;
+    return true;
+  };
+
+  let symbolFrontier: SymbolNode[] = [];
+  for (const symbol of seeds.symbols) {
+    if (admitSymbol(symbol, 0)) symbolFrontier.push(symbol);
+  }
+
+  let fileFrontier: string[] = [];
+  for (const path of seeds.files) {
+    if (await admitFile(path, 0)) fileFrontier.push(path);
+  }
+
+  for (let hop = 1; hop <= options.depth; hop++) {
+    const nextSymbols: SymbolNode[] = [];
+    const nextFiles: string[] = [];
+
+    for (const symbol of symbolFrontier) {
+      const [callers, callees] = await Promise.all([
+        graph.getCallers(symbol.qualifiedName),
+        graph.getCallees(symbol.qualifiedName)
+      ]);
+
+      for (const neighbour of [...callers.slice(0, maxPerNode), ...callees.slice(0, maxPerNode)]) {
+        if (admitSymbol(neighbour, hop)) nextSymbols.push(neighbour);
+      }
+    }
+
+    if (includeImports) {
+      for (const path of fileFrontier) {
+        const deps = await graph.getFileDependencies(path);
+        for (const dep of deps.slice(0, maxPerNode)) {
+          if (await admitFile(dep, hop)) nextFiles.push(dep);
+        }
+      }
+    }
+
+    if (nextSymbols.length === 0 && nextFiles.length === 0) break;
+    symbolFrontier = nextSymbols;
+    fileFrontier = nextFiles;
+  }
+
+  return result;
+}
@@ -0,0 +1,277 @@
+/**
+ * Provider HTTP Layer
+ *
+ * Every request to a model or embedding API goes through a limiter shared
+ * by all clients of that provider, so a bulk `cv sync` cannot flood a key
+ * with requests. Each provider has:
+ *
+ * - a concurrency cap (requests in flight at once)
+ * - a token bucket (requests per minute, with a burst of `maxConcurrent`)
+ * - retries with exponential backoff and full jitter on 429, 5xx and
+ *   connection errors, waiting at least as long as `Retry-After` asks
+ *
+ * A 429 pauses the whole provider, not just the request that got it, since
+ * the other requests in flight share the same key. Request bodies are
+ * redacted (security/redact.ts) before they are sent. Offline (offline.ts),
+ * requests to anything but this machine are refused before they are sent.
+ *
+ * Limits come from `rateLimits` in `.cv/config.json`:
+ *
+ *   "rateLimits": { "openrouter": { "requestsPerMinute": 120, "maxConcurrent": 2 } }
+ */
+
+import { sleep } from '@cv-git/shared';
+import { captureUsage } from './usage.js';
+import { redactRequestInit } from '../security/redact.js';
+import { OfflineError, assertOnline, isLocalUrl, isNoNetworkError, setOffline } from './offline.js';
+
+export interface ProviderLimits {
+  /** Requests started per minute; 0 for no limit */
+  requestsPerMinute?: number;
+  /** Requests in flight at once */
+  maxConcurrent?: number;
+  /** Retries after the first attempt */
+  maxRetries?: number;
+  /** Backoff before the first retry, doubled each attempt (ms) */
+  baseDelayMs?: number;
+  /** Longest backoff between attempts (ms) */
+  maxDelayMs?: number;
+}
+
+type ResolvedLimits = Required<ProviderLimits>;
+
+const BASE_LIMITS: ResolvedLimits = {
+  requestsPerMinute: 0,
+  maxConcurrent: 4,
+  maxRetries: 4,
+  baseDelayMs: 500,
+  maxDelayMs: 30000,
+};
+
+/**
+ * Defaults sized for the lowest paid tiers. Local servers have no request
+ * limit and work through one or two requests at a time; their errors (model
+ * not loaded, out of memory) do not clear up on retry.
+ */
+export const DEFAULT_PROVIDER_LIMITS: Record<string, ProviderLimits> = {
+  openai: { requestsPerMinute: 500, maxConcurrent: 8 },
+  openrouter: { requestsPerMinute: 200, maxConcurrent: 4 },
+  azure: { requestsPerMinute: 240, maxConcurrent: 4 },
+  anthropic: { requestsPerMinute: 50, maxConcurrent: 4 },
+  gemini: { requestsPerMinute: 60, maxConcurrent: 4 },
+  ollama: { maxConcurrent: 2, maxRetries: 0 },
+  lmstudio: { maxConcurrent: 1, maxRetries: 0 },
+};
+
+/** Statuses that mean "try again later" */
+const RETRY_STATUS = new Set([408, 429, 500, 502, 503, 504, 529]);
+
+/** A longer `Retry-After` (e.g. a daily quota) is reported instead of waited out */
+const MAX_RETRY_AFTER_MS = 120000;
+
+let overrides: Record<string, ProviderLimits> = {};
+const limiters = new Map<string, ProviderLimiter>();
+
+/**
+ * Concurrency cap and token bucket for one provider
+ */
+export class ProviderLimiter {
+  readonly limits: ResolvedLimits;
+  private active = 0;
+  private waiting: Array<() => void> = [];
+  private tokens: number;
+  private refilledAt = Date.now();
+  private pausedUntil = 0;
+
+  constructor(limits: ProviderLimits = {}) {
+    this.limits = { ...BASE_LIMITS, ...stripUndefined(limits) };
+    this.limits.maxConcurrent = Math.max(1, this.limits.maxConcurrent);
+    this.tokens = this.capacity();
+  }
+
+  /**
+   * Run `fn` once a slot and a request token are free
+   */
+  async run<T>(fn: () => Promise<T>): Promise<T> {
+    await this.acquire();
+    try {
+      await this.waitForToken();
+      return await fn();
+    } finally {
+      this.release();
+    }
+  }
+
+  /**
+   * Hold back every request to this provider for `ms`
+   */
+  pause(ms: number): void {
+    this.pausedUntil = Math.max(this.pausedUntil, Date.now() + ms);
+  }
+
+  /** Requests currently in flight */
+  get inFlight(): number {
+    return this.active;
+  }
+
+  private capacity(): number {
+    return Math.min(this.limits.maxConcurrent, Math.max(1, this.limits.requestsPerMinute));
+  }
+
+  private acquire(): Promise<void> {
+    if (this.active < this.limits.maxConcurrent) {
+      this.active++;
+      return Promise.resolve();
+    }
+    // The slot is handed over by release(), so `active` stays counted
+    return new Promise(resolve => this.waiting.push(resolve));
+  }
+
+  private release(): void {
+    const next = this.waiting.shift();
+    if (next) next();
+    else this.active--;
+  }
+
+  private async waitForToken(): Promise<void> {
+    for (;;) {
+      const now = Date.now();
+      if (now < this.pausedUntil) {
+        await sleep(this.pausedUntil - now);
+        continue;
+      }
+
+      const rpm = this.limits.requestsPerMinute;
+      if (rpm <= 0) return;
+
+      this.tokens = Math.min(this.capacity(), this.tokens + ((now - this.refilledAt) * rpm) / 60000);
+      this.refilledAt = now;
+      if (this.tokens >= 1) {
+        this.tokens -= 1;
+        return;
+      }
+      await sleep(Math.ceil(((1 - this.tokens) * 60000) / rpm));
+    }
+  }
+}
+
+/**
+ * Set per-provider limits (from config); replaces earlier overrides
+ */
+export function configureProviderLimits(limits: Record<string, ProviderLimits> | undefined): void {
+  overrides = limits ?? {};
+  limiters.clear();
+}
+
+/**
+ * Shared limiter for a provider
+ */
+export function getProviderLimiter(provider: string): ProviderLimiter {
+  let limiter = limiters.get(provider);
+  if (!limiter) {
+    limiter = new ProviderLimiter({
+      ...DEFAULT_PROVIDER_LIMITS[provider],
+      ...stripUndefined(overrides[provider] ?? {}),
+    });
+    limiters.set(provider, limiter);
+  }
+  return limiter;
+}
+
+/**
+ * Milliseconds a `Retry-After` (or `retry-after-ms`) header asks to wait
+ */
+export function parseRetryAfter(headers: Headers, now: number = Date.now()): number | undefined {
+  const ms = headers.get('retry-after-ms');
+  if (ms && Number.isFinite(Number(ms))) return Math.max(0, Number(ms));
+
+  const value = headers.get('retry-after');
+  if (!value) return undefined;
+  const seconds = Number(value);
+  if (Number.isFinite(seconds)) return Math.max(0, seconds * 1000);
+  const date = Date.parse(value);
+  return Number.isNaN(date) ? undefined : Math.max(0, date - now);
+}
+
+/**
+ * Backoff before retry `attempt` (0-based): full jitter over an exponential
+ * window, but never shorter than the server asked for
+ */
+export function retryDelay(attempt: number, limits: ProviderLimits = {}, retryAfterMs?: number): number {
+  const { baseDelayMs, maxDelayMs } = { ...BASE_LIMITS, ...stripUndefined(limits) };
+  const window = Math.min(maxDelayMs, baseDelayMs * 2 ** attempt);
+  const jittered = Math.random() * window;
+  return retryAfterMs !== undefined ? Math.max(retryAfterMs, jittered) : jittered;
+}
+
+/**
+ * fetch() through the provider's limiter, retrying rate limits, server
+ * errors and dropped connections. The last response is returned as is, so
+ * callers report errors the way they did before.
+ */
+export async function providerFetch(
+  provider: string,
+  input: string | URL | Request,
+  init?: RequestInit
+): Promise<Response> {
+  assertOnline(`The ${provider} API`, requestUrl(input));
+  const limiter = getProviderLimiter(provider);
+  const { maxRetries } = limiter.limits;
+  init = redactRequestInit(provider, init);
+
+  for (let attempt = 0; ; attempt++) {
+    let response: Response;
+    try {
+      response = await limiter.run(() => fetch(input, init));
+    } catch (error) {
+      // A timed-out or cancelled signal would abort the retry too
+      if (attempt >= maxRetries || init?.signal?.aborted || !isConnectionError(error)) {
+        // No route at all: later requests fail at once instead of each waiting on DNS
+        if (isNoNetworkError(error) && !isLocalUrl(requestUrl(input))) {
+          setOffline(true, 'unreachable');
+          throw new OfflineError(`The ${provider} API`);
+        }
+        throw error;
+      }
+      await sleep(retryDelay(attempt, limiter.limits));
+      continue;
+    }
+
+    if (!RETRY_STATUS.has(response.status) || attempt >= maxRetries) {
+      void captureUsage(provider, requestUrl(input), init, response);
+      return response;
+    }
+
+    const retryAfter = parseRetryAfter(response.headers);
+    if (retryAfter !== undefined && retryAfter > MAX_RETRY_AFTER_MS) {
+      return response;
+    }
+    const delay = retryDelay(attempt, limiter.limits, retryAfter);
+    if (response.status === 429) limiter.pause(delay);
+    await response.body?.cancel().catch(() => {});
+    await sleep(delay);
+  }
+}
+
+/**
+ * fetch implementation for SDK clients (OpenAI, Anthropic); pass it with
+ * `maxRetries: 0` so retries happen here only
+ */
+export function limitedFetch(provider: string): typeof fetch {
+  return ((input: string | URL | Request, init?: RequestInit) => providerFetch(provider, input, init)) as typeof fetch;
+}
+
+function requestUrl(input: string | URL | Request): string {
+  return typeof input === 'string' ? input : input instanceof URL ? input.href : input.url;
+}
+
+function isConnectionError(error: unknown): boolean {
+  const err = error as { name?: string; code?: string; cause?: { code?: string } } | undefined;
+  if (err?.name === 'AbortError' || err?.name === 'TimeoutError') return false;
+  const code = err?.code ?? err?.cause?.code;
+  return err?.name === 'TypeError' || /^(ECONN|ETIMEDOUT|EPIPE|EAI_AGAIN|UND_ERR)/.test(code ?? '');
+}
+
+function stripUndefined<T extends object>(value: T): Partial<T> {
+  return Object.fromEntries(Object.entries(value).filter(([, v]) => v !== undefined)) as Partial<T>;
+}
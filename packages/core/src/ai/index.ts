@@ -15,7 +15,9 @@ export {
   SymbolChange,
   BreakingChange,
   CommitAnalyzerOptions,
-  CommitAIProvider
+  CommitAIProvider,
+  RelatedCode,
+  AnalyzeStagedOptions
 } from './commit-analyzer.js';
 import {
   Context,
@@ -30,17 +32,300 @@ import {
 } from '@cv-git/shared';
 import { VectorManager } from '../vector/index.js';
 import { GraphManager } from '../graph/index.js';
-import { GitManager } from '../git/index.js';
+import { GitManager, Hotspot, FunctionHotspot, buildHotspotPrompt } from '../git/index.js';
 import { PRDClient, AIContext as PRDContext } from '@cv-git/prd-client';
+import { expandGraphContext } from './graph-expansion.js';
+import { FixtureRecorder, FixtureReplayer } from '../fixtures/index.js';
+import { ContextUsageModel } from '../context/usage-model.js';
+import { KeywordIndex, keywordHitToResult, reciprocalRankFusion } from '../context/keyword-index.js';
+import { Reranker, rerankChunks, RERANK_CANDIDATE_FACTOR } from '../context/reranker.js';
+import { ScoreDistribution, resolveMinScore } from '../context/score-distribution.js';
+import { QueryExpander, QueryExpansionOptions } from '../context/query-expansion.js';
+import { packChunks, getTokenCounter, resolveContextBudget, formatChunkCitation } from './context-packer.js';
+import { SummaryCache, gitBlobHash, combineBlobHashes } from '../services/summary-cache.js';
+import { WorkspaceIndex, searchWorkspaceCode } from '../workspace/index.js';
+import { RepoPackage, packageForFile } from '../sync/packages.js';
+import { ReviewResult, parseReviewResult, REVIEW_SEVERITIES } from './review.js';
+import { ReviewPolicy, buildPolicyPrompt, applyPolicies } from './review-policies.js';
+import { AgentPlan, AgentStep, parseAgentPlan } from './agent.js';
+import { buildBlamePrompt } from './blame.js';
+import type { BlameHistory } from '../git/blame.js';
+import {
+  DiffCluster,
+  DiffSummary,
+  ApiChange,
+  buildClusterSummaryPrompt,
+  buildRangeOverviewPrompt
+} from './diff-summary.js';
+import { AIClient } from './types.js';
+import { EDIT_BLOCK_INSTRUCTIONS } from '../code/edit-parser.js';
+import { limitedFetch } from './http.js';
+import { OfflineError, isOffline } from './offline.js';
+import { NO_PROMPT_TEMPLATES, PromptTemplateName, PromptTemplates } from './prompt-templates.js';
+import { JsonSchema, StructuredOptions, StructuredResult, generateStructured } from './structured-output.js';
+
+export {
+  expandGraphContext,
+  parseExpandDepth,
+  estimateSymbolTokens,
+  GraphExpansionOptions,
+  GraphExpansionResult,
+  DEFAULT_EXPAND_DEPTH,
+  DEFAULT_EXPAND_TOKEN_BUDGET
+} from './graph-expansion.js';
+export {
+  packChunks,
+  getTokenCounter,
+  getContextWindow,
+  resolveContextBudget,
+  encodingForModel,
+  estimateTokenCounter,
+  formatChunkCitation,
+  contextSources,
+  ContextSource,
+  TokenCounter,
+  PackOptions,
+  PackResult,
+  MODEL_CONTEXT_WINDOWS,
+  DEFAULT_CONTEXT_TOKENS
+} from './context-packer.js';
+export {
+  splitDiff,
+  filterDiff,
+  touchedLines,
+  touchedSymbols,
+  parseReviewResult,
+  sortFindings,
+  groupFindings,
+  findingId,
+  hasFindingsAtOrAbove,
+  toSarif,
+  REVIEW_SEVERITIES,
+  DEFAULT_REVIEW_RULE,
+  SarifOptions,
+  ReviewSeverity,
+  ReviewFinding,
+  ReviewResult,
+  DiffSection
+} from './review.js';
+export {
+  parseReviewPolicies,
+  loadReviewPolicies,
+  policyMatchesFile,
+  applicablePolicies,
+  buildPolicyPrompt,
+  applyPolicies,
+  policyReport,
+  ReviewPolicy,
+  PolicyReport,
+  ReviewPolicyError,
+  REVIEW_POLICIES_FILE
+} from './review-policies.js';
+export {
+  getFindingsStorePath,
+  loadFindingsStore,
+  saveFindingsStore,
+  recordFindings,
+  setFindingStatus,
+  withoutDismissed,
+  ReviewFindingStatus,
+  StoredFinding,
+  FindingsStore
+} from './review-store.js';
+export {
+  parseTestTarget,
+  planTestLayout,
+  pickTestExamples,
+  detectTestCommand,
+  testCommandFor,
+  buildTestGenPrompt,
+  extractTestCode,
+  applyTestCode,
+  runTestGeneration,
+  TEST_GEN_SYSTEM_PROMPT,
+  TestTarget,
+  TestLayout,
+  TestGenContext,
+  TestGenAttempt,
+  TestGenRunOptions,
+  TestGenResult
+} from './test-gen.js';
+export {
+  findDocTargets,
+  findDocComment,
+  renderDocComment,
+  applyDocComments,
+  buildDocgenPrompt,
+  parseDocgenResponse,
+  groupDocTargets,
+  DOCGEN_SYSTEM_PROMPT,
+  DocTarget,
+  DocTargetOptions,
+  DocCommentRange,
+  GeneratedDoc
+} from './docgen.js';
+export {
+  extractSymbolMentions,
+  collectRefactorScope,
+  buildRefactorPrompt,
+  reversePatch,
+  detectBuildCommand,
+  REFACTOR_REASONS,
+  REFACTOR_SYSTEM_PROMPT,
+  RefactorReason,
+  RefactorScope,
+  RefactorScopeFile,
+  RefactorScopeOptions,
+  RefactorFailure
+} from './refactor.js';
+export {
+  moduleOf,
+  loadTourGraph,
+  buildRepoTour,
+  buildOnboardPrompt,
+  applyOnboardResponse,
+  tourSections,
+  formatTourMarkdown,
+  ONBOARD_SYSTEM_PROMPT,
+  TourSymbol,
+  TourGraph,
+  TourModule,
+  TourEntryPoint,
+  TourFlow,
+  TourReading,
+  TourSection,
+  RepoTour,
+  RepoTourInput
+} from './onboard.js';
+export {
+  parseAgentPlan,
+  AgentRunner,
+  AgentAuditLog,
+  AGENT_STEP_KINDS,
+  AgentStepKind,
+  AgentStep,
+  AgentPlan,
+  AgentEvent,
+  AgentRunnerOptions,
+  AgentRunResult,
+  StepDecision,
+  StepOutcome,
+  CommandResult
+} from './agent.js';
+export { buildBlamePrompt, citedCommits, BLAME_SHA_LENGTH } from './blame.js';
+export {
+  parseCommitRange,
+  clusterChanges,
+  areaOf,
+  detectApiChanges,
+  rankRiskyFiles,
+  behaviorFiles,
+  buildClusterSummaryPrompt,
+  buildRangeOverviewPrompt,
+  formatDiffSummaryMarkdown,
+  DiffCluster,
+  DiffClusterFile,
+  DiffSummary,
+  ApiChange,
+  RiskyFile
+} from './diff-summary.js';
+export {
+  commentableLines,
+  postedFindingIds,
+  formatFindingComment,
+  planPullRequestReview,
+  InlineReviewComment,
+  PullRequestReviewPlan
+} from './pr-review.js';
+export { readSSE, readNDJSON } from './stream.js';
+export {
+  ProviderLimits,
+  ProviderLimiter,
+  DEFAULT_PROVIDER_LIMITS,
+  configureProviderLimits,
+  getProviderLimiter,
+  parseRetryAfter,
+  retryDelay,
+  providerFetch,
+  limitedFetch
+} from './http.js';
+export {
+  OfflineError,
+  OfflineReason,
+  setOffline,
+  getOfflineReason,
+  isOffline,
+  initOfflineMode,
+  detectOffline,
+  isLocalUrl,
+  assertOnline,
+  isNoNetworkError,
+  describeOfflineReason
+} from './offline.js';
+export {
+  PromptTemplates,
+  PromptTemplateError,
+  PromptTemplateName,
+  PromptVariables,
+  TemplateNode,
+  PROMPT_TEMPLATE_NAMES,
+  PROMPT_VARIABLES,
+  PROMPTS_DIR,
+  NO_PROMPT_TEMPLATES,
+  parseTemplate,
+  renderTemplate,
+  loadPromptTemplates
+} from './prompt-templates.js';
+export {
+  JsonSchema,
+  SchemaError,
+  StructuredOutputError,
+  StructuredResult,
+  StructuredOptions,
+  DEFAULT_STRUCTURED_ATTEMPTS,
+  loadJsonSchema,
+  checkJsonSchema,
+  validateJsonSchema,
+  generateStructured,
+  parseJsonReply
+} from './structured-output.js';
+export {
+  startUsageTracking,
+  isTrackingUsage,
+  recordUsage,
+  flushUsage,
+  captureUsage,
+  parseUsage,
+  estimateCost,
+  loadUsage,
+  summarizeUsage,
+  getUsageLogPath,
+  MODEL_PRICING,
+  UsageKind,
+  UsageRecord,
+  UsageTotals,
+  UsageSummary
+} from './usage.js';
 
 export interface AIManagerOptions {
   provider: 'anthropic';
   model: string;
   apiKey: string;
+  /** Provider client to send prompts through instead of the Anthropic SDK (see `--model provider/model`) */
+  chatClient?: AIClient;
   maxTokens?: number;
   temperature?: number;
   prdUrl?: string;
   prdApiKey?: string;
+  /** Retrieval settings from config (see `cv calibrate`) */
+  retrieval?: {
+    minScore?: number;
+    minScorePercentile?: number;
+    topK?: number;
+    rerank?: boolean;
+    hybrid?: boolean;
+    contextTokens?: number;
+  };
 }
 
 export interface StreamHandler {
@@ -49,12 +334,30 @@ export interface StreamHandler {
   onError?: (error: Error) => void;
 }
 
+/**
+ * Bump when buildExplainPrompt changes so cached explanations are regenerated
+ */
+export const EXPLAIN_PROMPT_VERSION = 'explain-v2';
+
 export class AIManager {
   private client: Anthropic;
+  private chatClient?: AIClient;
   private model: string;
   private maxTokens: number;
   private temperature: number;
   private prdClient?: PRDClient;
+  private fixtureRecorder?: FixtureRecorder;
+  private fixtureReplayer?: FixtureReplayer;
+  private contextUsage?: ContextUsageModel;
+  private keywordIndex?: KeywordIndex;
+  private reranker?: Reranker;
+  private rerankCandidates?: number;
+  private queryExpander?: QueryExpander;
+  private scoreDistribution?: ScoreDistribution | null;
+  private summaryCache?: SummaryCache;
+  private workspace: WorkspaceIndex[] = [];
+  private packageScope?: RepoPackage;
+  private promptTemplates: PromptTemplates = NO_PROMPT_TEMPLATES;
 
   constructor(
     private options: AIManagerOptions,
@@ -62,8 +365,9 @@ export class AIManager {
     private graph?: GraphManager,
     private git?: GitManager
   ) {
-    this.client = new Anthropic({ apiKey: options.apiKey });
-    this.model = options.model || 'claude-3-5-sonnet-20241022';
+    this.client = new Anthropic({ apiKey: options.apiKey, fetch: limitedFetch('anthropic'), maxRetries: 0 });
+    this.chatClient = options.chatClient;
+    this.model = options.chatClient?.getModel() || options.model || 'claude-3-5-sonnet-20241022';
     this.maxTokens = options.maxTokens || 4096;
     this.temperature = options.temperature || 0.7;
 
@@ -76,6 +380,116 @@ export class AIManager {
     }
   }
 
+  /**
+   * Record retrieval results, prompts and responses into a fixture
+   */
+  setFixtureRecorder(recorder: FixtureRecorder | undefined): void {
+    this.fixtureRecorder = recorder;
+  }
+
+  /**
+   * Serve retrieval results and responses from a recorded fixture instead
+   * of the vector DB, graph and provider
+   */
+  setFixtureReplayer(replayer: FixtureReplayer | undefined): void {
+    this.fixtureReplayer = replayer;
+  }
+
+  /**
+   * Re-rank retrieved chunks by how often similar chunks were cited in past
+   * answers, and record citations from the answers this manager produces
+   */
+  setContextUsage(model: ContextUsageModel | undefined): void {
+    this.contextUsage = model;
+  }
+
+  /**
+   * Fuse BM25 keyword matches with vector search so exact identifiers are found
+   */
+  setKeywordIndex(index: KeywordIndex | undefined): void {
+    this.keywordIndex = index;
+  }
+
+  /**
+   * Rerank retrieved candidates with a cross-encoder or provider rerank API
+   * before the chunks passed to the model are chosen
+   *
+   * @param candidates - chunks fetched for reranking (default: 3x maxChunks)
+   */
+  setReranker(reranker: Reranker | undefined, candidates?: number): void {
+    this.reranker = reranker;
+    this.rerankCandidates = candidates;
+  }
+
+  /**
+   * Also search for reformulations of each query (synonyms or the model's)
+   * and fuse the rankings; `off` or undefined searches the query alone
+   */
+  setQueryExpansion(options: QueryExpansionOptions | undefined): void {
+    this.queryExpander = options && options.mode !== 'off'
+      ? new QueryExpander(options, prompt => this.expansionComplete(prompt))
+      : undefined;
+  }
+
+  /**
+   * Derive minScore from the embedding model's sampled score distribution
+   * when config does not set one
+   */
+  setScoreDistribution(distribution: ScoreDistribution | null | undefined): void {
+    this.scoreDistribution = distribution;
+  }
+
+  /**
+   * Reuse explanations while the retrieved code and prompt template are unchanged
+   */
+  setSummaryCache(cache: SummaryCache | undefined): void {
+    this.summaryCache = cache;
+  }
+
+  /**
+   * Also retrieve code from these workspace repositories' indexes; their
+   * chunks are tagged with the repository name
+   */
+  setWorkspace(indexes: WorkspaceIndex[]): void {
+    this.workspace = indexes;
+  }
+
+  /**
+   * Only retrieve code of this monorepo package from the current repository
+   */
+  setPackageScope(pkg: RepoPackage | undefined): void {
+    this.packageScope = pkg;
+  }
+
+  /**
+   * Send prompts and system prompts through the repository's `.cv/prompts/` templates
+   */
+  setPromptTemplates(templates: PromptTemplates | undefined): void {
+    this.promptTemplates = templates ?? NO_PROMPT_TEMPLATES;
+  }
+
+  /**
+   * System prompt and prompt a command would send for `input`, for `cv prompts show`
+   */
+  renderPrompt(command: Exclude<PromptTemplateName, 'commit'>, input: string, context: Context): { system?: string; prompt: string } {
+    const prompt = command === 'explain' ? this.buildExplainPrompt(input, context)
+      : command === 'review' ? this.buildReviewPrompt(input, context)
+      : this.buildCodeGenerationPrompt(input, context);
+    return { system: this.promptTemplates.renderSystem(command), prompt: this.templated(command, prompt, input, context) };
+  }
+
+  /**
+   * A built-in prompt run through the command's template
+   */
+  private templated(command: PromptTemplateName, prompt: string, input: string, context?: Context): string {
+    return this.promptTemplates.renderPrompt(command, prompt, {
+      input,
+      context: context?.chunks
+        .map(chunk => `### ${formatChunkCitation(chunk.payload)}\n\`\`\`${chunk.payload.language}\n${chunk.payload.text}\n\`\`\``)
+        .join('\n\n')
+    });
+  }
+
   /**
    * Gather relevant context for a query
    */
@@ -87,8 +501,21 @@ export class AIManager {
       includeGitStatus?: boolean;
       specificFiles?: string[];
       prdRefs?: string[];
+      /** Hops of callers/callees/imports to pull in around retrieved chunks */
+      expandDepth?: number;
+      /** Approximate token budget for graph-expanded context */
+      expandTokenBudget?: number;
+      /** Text candidates are reranked against (default: the query) */
+      rerankQuery?: string;
+      /** Tokens the retrieved chunks may use (default: retrieval.contextTokens, capped by the model window) */
+      tokenBudget?: number;
     }
   ): Promise<Context> {
+    const replayed = this.fixtureReplayer?.nextRetrieval();
+    if (replayed) {
+      return replayed;
+    }
+
     const context: Context = {
       chunks: [],
       symbols: [],
@@ -99,22 +526,96 @@ export class AIManager {
     // Extract PRD refs from query if not provided
     const prdRefs = options?.prdRefs || PRDClient.extractPRDReferences(query);
 
-    const maxChunks = options?.maxChunks || 10;
+    const retrieval = this.options.retrieval;
+    const maxChunks = options?.maxChunks || retrieval?.topK || 10;
     const maxSymbols = options?.maxSymbols || 20;
+    const rerank = !!this.contextUsage && retrieval?.rerank !== false;
+    // Offline, keyword matches are all there is unless embeddings are local
+    const hybrid = !!this.keywordIndex && this.keywordIndex.size > 0 && (retrieval?.hybrid !== false || isOffline());
+    // Over-fetch when re-ranking so down-weighted chunks can be replaced
+    const fetchLimit = this.reranker
+      ? Math.max(this.rerankCandidates || maxChunks * RERANK_CANDIDATE_FACTOR, maxChunks)
+      : rerank ? maxChunks * 2 : maxChunks;
+
+    // Each reformulation is searched like the query; rankings are fused below
+    const queries = this.queryExpander ? await this.queryExpander.expand(query) : [query];
+    const vectorLists: VectorSearchResult<CodeChunkPayload>[][] = [];
 
     // 1. Vector search for relevant code chunks
-    if (this.vector) {
+    if (this.vector || this.workspace.length > 0) {
       try {
-        context.chunks = await this.vector.searchCode(query, maxChunks, {
-          minScore: 0.25  // Lowered from 0.6 to be more lenient with semantic matches
-        });
+        // Lenient default until a sync has sampled the index; `cv calibrate` pins it per repo
+        const minScore = resolveMinScore(retrieval, this.scoreDistribution, 0.25);
+        const scope = this.packageScope?.name;
+        const primary = this.vector && {
+          searchCode: (q: string, limit: number, opts?: { minScore?: number }) =>
+            this.vector!.searchCode(q, limit, { ...opts, package: scope }),
+          getEmbeddingInfo: () => this.vector!.getEmbeddingInfo()
+        };
+        for (const q of queries) {
+          vectorLists.push(this.workspace.length > 0
+            ? await searchWorkspaceCode(
+                [...(primary ? [{ vector: primary }] : []), ...this.workspace],
+                q, fetchLimit, { minScore }
+              )
+            : await primary!.searchCode(q, fetchLimit, { minScore }));
+        }
+      } catch (error) {
+        if (!(error instanceof OfflineError)) console.error('Vector search failed:', error);
+      }
+    }
+
+    // 1b. Keyword search, fused by rank; scores become the fused score
+    const keywordLists: VectorSearchResult<CodeChunkPayload>[][] = [];
+    if (hybrid && this.keywordIndex) {
+      const scope = this.packageScope;
+      for (const q of queries) {
+        keywordLists.push(this.keywordIndex.search(q, scope ? fetchLimit * 4 : fetchLimit)
+          .filter(hit => !scope || packageForFile([scope], hit.doc.file))
+          .slice(0, fetchLimit)
+          .map(keywordHitToResult));
+      }
+    }
+    const rankings = [...(vectorLists.length > 0 ? vectorLists : [context.chunks]), ...keywordLists];
+    context.chunks = rankings.length > 1
+      ? reciprocalRankFusion(rankings, chunk => chunk.id)
+          .slice(0, fetchLimit)
+          .map(({ item, score }) => ({ ...item, score }))
+      : rankings[0];
+
+    // 1c. Cross-encoder rerank; scores become the reranker's relevance
+    if (this.reranker && context.chunks.length > 0) {
+      try {
+        context.chunks = await rerankChunks(this.reranker, options?.rerankQuery || query, context.chunks);
+      } catch (error) {
+        console.error('Rerank failed:', error);
+      }
+    }
+
+    if (rerank && this.contextUsage) {
+      context.chunks = this.contextUsage.rerank(context.chunks, maxChunks);
+    } else {
+      context.chunks = context.chunks.slice(0, maxChunks);
+    }
+
+    // Fit the chosen chunks into the model's context budget
+    if (context.chunks.length > 0) {
+      const budget = options?.tokenBudget ??
+        resolveContextBudget(this.model, this.maxTokens, retrieval?.contextTokens);
+      context.chunks = packChunks(context.chunks, { budget, counter: await getTokenCounter(this.model) }).chunks;
+    }
+
+    // 2-3. Depth-controlled graph expansion when requested
+    if (this.graph && context.chunks.length > 0 && options?.expandDepth !== undefined) {
+      try {
+        await this.expandContext(context, options.expandDepth, maxSymbols, options.expandTokenBudget);
       } catch (error) {
-        console.error('Vector search failed:', error);
+        console.error('Graph expansion failed:', error);
       }
     }
 
     // 2. Graph queries for related symbols
-    if (this.graph && context.chunks.length > 0) {
+    if (this.graph && context.chunks.length > 0 && options?.expandDepth === undefined) {
       try {
         // Get symbols from matched chunks
         const symbolNames = context.chunks
@@ -155,7 +656,7 @@ export class AIManager {
     }
 
     // 3. Get file information
-    if (this.graph && context.chunks.length > 0) {
+    if (this.graph && context.chunks.length > 0 && options?.expandDepth === undefined) {
       try {
         const filePaths = Array.from(
           new Set(context.chunks.map(c => c.payload.file))
@@ -200,9 +701,46 @@ export class AIManager {
       }
     }
 
+    this.fixtureRecorder?.record({ type: 'retrieval', query, context });
+
     return context;
   }
 
+  /**
+   * Fill context symbols/files by walking the graph outward from the matched chunks
+   */
+  private async expandContext(
+    context: Context,
+    depth: number,
+    maxSymbols: number,
+    tokenBudget?: number
+  ): Promise<void> {
+    if (!this.graph) return;
+
+    const seeds: SymbolNode[] = [];
+    for (const chunk of context.chunks) {
+      const symbolName = chunk.payload.symbolName;
+      if (!symbolName) continue;
+      const results = await this.graph.query(
+        'MATCH (s:Symbol {name: $name, file: $file}) RETURN s LIMIT 1',
+        { name: symbolName, file: chunk.payload.file }
+      );
+      if (results.length > 0) {
+        seeds.push(results[0].s as SymbolNode);
+      }
+    }
+
+    const files = Array.from(new Set(context.chunks.map(c => c.payload.file)));
+    const expansion = await expandGraphContext(
+      this.graph,
+      { symbols: seeds, files },
+      { depth, tokenBudget }
+    );
+
+    context.symbols = expansion.symbols.slice(0, Math.max(maxSymbols, seeds.length));
+    context.files = expansion.files;
+  }
+
   /**
    * Explain code or concept
    */
@@ -216,11 +754,68 @@ export class AIManager {
       context = await this.gatherContext(target);
     }
 
+    // Fixtures must see every provider call, so they bypass the cache
+    const cache = this.fixtureRecorder || this.fixtureReplayer ? undefined : this.summaryCache;
+    const cacheKey = cache ? this.explanationCacheKey(target, context) : '';
+    const cached = cache ? await cache.get('explanations', cacheKey, EXPLAIN_PROMPT_VERSION) : null;
+    if (cached !== null) {
+      streamHandler?.onToken?.(cached);
+      streamHandler?.onComplete?.(cached);
+      return cached;
+    }
+
     // Build prompt
-    const prompt = this.buildExplainPrompt(target, context);
+    const prompt = this.templated('explain', this.buildExplainPrompt(target, context), target, context);
 
     // Call Claude
-    return await this.complete(prompt, streamHandler);
+    const explanation = await this.complete(prompt, streamHandler, 'explain');
+    this.contextUsage?.record(context.chunks, explanation);
+    if (cache && explanation.trim()) {
+      await cache.set('explanations', cacheKey, EXPLAIN_PROMPT_VERSION, explanation, this.model);
+    }
+    return explanation;
+  }
+
+  /**
+   * Key an explanation by the blobs of the code it was given, plus target, model and templates
+   */
+  private explanationCacheKey(target: string, context: Context): string {
+    const blobs = [
+      ...context.chunks.map(c => gitBlobHash(c.payload.text)),
+      ...context.symbols.map(s => gitBlobHash(`${s.qualifiedName}:${s.startLine}-${s.endLine}:${s.signature ?? ''}`))
+    ];
+    return combineBlobHashes(blobs, `${target}\0${this.model}\0${this.promptTemplates.fingerprint}`);
+  }
+
+  /**
+   * Explain why a blamed line range is the way it is, from its commits
+   */
+  async explainBlame(
+    history: BlameHistory,
+    context?: Context,
+    streamHandler?: StreamHandler
+  ): Promise<string> {
+    return await this.complete(buildBlamePrompt(history, context), streamHandler);
+  }
+
+  /**
+   * Summarize each cluster of a range (filling in `summary`), then the
+   * range as a whole
+   */
+  async summarizeChanges(
+    range: string,
+    clusters: DiffCluster[],
+    apiChanges: ApiChange[],
+    onCluster?: (cluster: DiffCluster, index: number) => void
+  ): Promise<Pick<DiffSummary, 'overview' | 'clusters'>> {
+    for (const [index, cluster] of clusters.entries()) {
+      onCluster?.(cluster, index);
+      cluster.summary = (await this.complete(buildClusterSummaryPrompt(cluster))).trim();
+    }
+    const overview = clusters.length === 1
+      ? clusters[0].summary ?? ''
+      : (await this.complete(buildRangeOverviewPrompt(range, clusters, apiChanges))).trim();
+    return { overview, clusters };
   }
 
   /**
@@ -233,13 +828,19 @@ export class AIManager {
     }
 
     // Build prompt
-    const prompt = this.buildPlanPrompt(task, context);
+    const prompt = this.templated('do', this.buildPlanPrompt(task, context), task, context);
 
     // Call Claude
-    const response = await this.complete(prompt);
+    const response = await this.complete(prompt, undefined, 'do');
 
     // Parse the response into a Plan
-    return this.parsePlanFromResponse(response, task);
+    const plan = this.parsePlanFromResponse(response, task);
+    plan.estimatedTokens = {
+      // Rough estimate: ~4 characters per token
+      prompt: Math.ceil(this.buildCodeGenerationPrompt(task, context).length / 4),
+      maxCompletion: this.maxTokens
+    };
+    return plan;
   }
 
   /**
@@ -256,10 +857,36 @@ export class AIManager {
     }
 
     // Build prompt
-    const prompt = this.buildCodeGenerationPrompt(task, context);
+    const prompt = this.templated('do', this.buildCodeGenerationPrompt(task, context), task, context);
 
     // Call Claude
-    return await this.complete(prompt, streamHandler);
+    const code = await this.complete(prompt, streamHandler, 'do');
+    this.contextUsage?.record(context.chunks, code);
+    return code;
+  }
+
+  /**
+   * Break a task into read/edit/run steps for agent mode
+   */
+  async generateAgentPlan(task: string, context: Context): Promise<AgentPlan> {
+    const prompt = this.templated('do', this.buildAgentPlanPrompt(task, context), task, context);
+    return parseAgentPlan(await this.complete(prompt, undefined, 'do'), task);
+  }
+
+  /**
+   * Generate the edit blocks for a single agent step
+   */
+  async generateAgentEdit(
+    plan: AgentPlan,
+    step: AgentStep,
+    notes: string,
+    context: Context,
+    streamHandler?: StreamHandler
+  ): Promise<string> {
+    const prompt = this.templated('do', this.buildAgentEditPrompt(plan, step, notes, context), plan.task, context);
+    const code = await this.complete(prompt, streamHandler, 'do');
+    this.contextUsage?.record(context.chunks, code);
+    return code;
   }
 
   /**
@@ -270,10 +897,74 @@ export class AIManager {
     context?: Context
   ): Promise<string> {
     // Build prompt for code review
-    const prompt = this.buildReviewPrompt(diff, context);
+    const prompt = this.templated('review', this.buildReviewPrompt(diff, context), diff, context);
 
     // Call Claude
-    return await this.complete(prompt);
+    return await this.complete(prompt, undefined, 'review');
+  }
+
+  /**
+   * Review changes and return findings with severities and file/line locations
+   */
+  async reviewChanges(
+    diff: string,
+    context?: Context,
+    options?: {
+      /** Symbols the diff touches, listed so the review can reason about their callers */
+      symbols?: SymbolNode[];
+      /** House rules to check the changes against */
+      policies?: ReviewPolicy[];
+      /** Defect-prone files and functions (`cv review --hotspots`) to scrutinize */
+      hotspots?: { files: Hotspot[]; functions: FunctionHotspot[] };
+    }
+  ): Promise<ReviewResult> {
+    const policies = options?.policies ?? [];
+    let prompt = this.buildReviewPrompt(diff, context, options?.symbols);
+    if (policies.length > 0) {
+      prompt += `\n\n${buildPolicyPrompt(policies)}`;
+    }
+    if (options?.hotspots && options.hotspots.files.length > 0) {
+      prompt += `\n\n${buildHotspotPrompt(options.hotspots.files, options.hotspots.functions)}`;
+    }
+    // The template can't drop the output format the findings are parsed from
+    prompt = this.templated('review', prompt, diff, context);
+    prompt += `\n\nRespond with ONLY a JSON object, no other text:\n`;
+    prompt += `{"summary": "<one paragraph overall assessment>", "findings": [{"severity": "${REVIEW_SEVERITIES.join('|')}", `;
+    prompt += `"file": "<path as in the diff>", "line": <line in the new file>, "endLine": <optional>, `;
+    prompt += `"title": "<short title>", "message": "<what is wrong and why>", "suggestion": "<optional fix>"`;
+    prompt += policies.length > 0 ? `, "rule": "<house rule id, if the finding violates one>"}]}\n` : `}]}\n`;
+    prompt += `Only report real problems in the changed code; use an empty findings array if there are none.`;
+
+    const result = parseReviewResult(await this.complete(prompt, undefined, 'review'));
+    return policies.length > 0 ? { ...result, findings: applyPolicies(result.findings, policies) } : result;
+  }
+
+  /**
+   * Answer a question about the code
+   */
+  async ask(question: string, context: Context, streamHandler?: StreamHandler): Promise<string> {
+    const answer = await this.complete(this.buildAskPrompt(question, context), streamHandler);
+    this.contextUsage?.record(context.chunks, answer);
+    return answer;
+  }
+
+  /**
+   * Answer a question as JSON matching `schema`, asking again when the reply
+   * doesn't match. Needs a chat client, for its tool calling.
+   */
+  async askStructured(
+    question: string,
+    context: Context,
+    schema: JsonSchema,
+    options: Omit<StructuredOptions, 'systemPrompt'> = {}
+  ): Promise<StructuredResult> {
+    if (!this.chatClient) {
+      throw new Error('Structured answers need a chat client; pass one in AIManagerOptions.chatClient');
+    }
+    return generateStructured(this.chatClient, this.buildAskPrompt(question, context), schema, {
+      ...options,
+      systemPrompt: this.promptTemplates.renderSystem()
+    });
   }
 
   /**
@@ -288,49 +979,83 @@ export class AIManager {
       content: msg.content
     }));
 
-    if (streamHandler) {
-      return await this.streamComplete(anthropicMessages, streamHandler);
-    } else {
-      const response = await this.client.messages.create({
-        model: this.model,
-        max_tokens: this.maxTokens,
-        temperature: this.temperature,
-        messages: anthropicMessages
-      });
-
-      return response.content[0].type === 'text' ? response.content[0].text : '';
-    }
+    return await this.send(anthropicMessages, streamHandler, this.promptTemplates.renderSystem());
   }
 
   /**
-   * Complete a prompt with Claude
+   * Complete a prompt with Claude, with the system prompt of `command`
    */
   private async complete(
     prompt: string,
-    streamHandler?: StreamHandler
+    streamHandler?: StreamHandler,
+    command?: PromptTemplateName
   ): Promise<string> {
     const messages = [{ role: 'user' as const, content: prompt }];
 
-    if (streamHandler) {
-      return await this.streamComplete(messages, streamHandler);
-    }
+    return await this.send(messages, streamHandler, this.promptTemplates.renderSystem(command));
+  }
 
+  /**
+   * Short completion for query reformulations. Not recorded into fixtures:
+   * replay serves the retrieval itself, so it never expands a query.
+   */
+  private async expansionComplete(prompt: string): Promise<string> {
+    const messages = [{ role: 'user' as const, content: prompt }];
+    if (this.chatClient) {
+      return this.chatClient.chat(messages);
+    }
     const response = await this.client.messages.create({
       model: this.model,
-      max_tokens: this.maxTokens,
-      temperature: this.temperature,
+      max_tokens: 300,
+      temperature: 0.3,
       messages
     });
-
     return response.content[0].type === 'text' ? response.content[0].text : '';
   }
 
+  /**
+   * Send messages to Claude (or the fixture replayer), recording if enabled
+   */
+  private async send(
+    messages: Array<{ role: 'user' | 'assistant'; content: string }>,
+    streamHandler?: StreamHandler,
+    system?: string
+  ): Promise<string> {
+    this.fixtureRecorder?.record({ type: 'prompt', model: this.model, messages });
+
+    let text: string;
+    if (this.fixtureReplayer) {
+      text = this.fixtureReplayer.nextResponse(messages);
+      streamHandler?.onToken?.(text);
+      streamHandler?.onComplete?.(text);
+    } else if (this.chatClient) {
+      text = streamHandler
+        ? await this.chatClient.chatStream(messages, system, streamHandler)
+        : await this.chatClient.chat(messages, system);
+    } else if (streamHandler) {
+      text = await this.streamComplete(messages, streamHandler, system);
+    } else {
+      const response = await this.client.messages.create({
+        model: this.model,
+        max_tokens: this.maxTokens,
+        temperature: this.temperature,
+        ...(system && { system }),
+        messages
+      });
+      text = response.content[0].type === 'text' ? response.content[0].text : '';
+    }
+
+    this.fixtureRecorder?.record({ type: 'response', text });
+    return text;
+  }
+
   /**
    * Stream completion from Claude
    */
   private async streamComplete(
     messages: Array<{ role: 'user' | 'assistant'; content: string }>,
-    streamHandler: StreamHandler
+    streamHandler: StreamHandler,
+    system?: string
   ): Promise<string> {
     let fullText = '';
 
@@ -339,6 +1064,7 @@ export class AIManager {
         model: this.model,
         max_tokens: this.maxTokens,
         temperature: this.temperature,
+        ...(system && { system }),
         messages,
         stream: true
       });
@@ -377,8 +1103,13 @@ export class AIManager {
 
     if (context.chunks.length > 0) {
       prompt += `## Relevant Code\n\n`;
-      for (const chunk of context.chunks.slice(0, 5)) {
-        prompt += `### ${chunk.payload.file}:${chunk.payload.startLine}\n`;
+      for (const chunk of context.chunks) {
+        prompt += `### ${formatChunkCitation(chunk.payload)}\n`;
+        if (chunk.payload.synthetic) {
+          // Oversized/generated files are indexed as a summary, not source
+          prompt += `Summary (file too large to include in full): ${chunk.payload.text}\n\n`;
+          continue;
+        }
         if (chunk.payload.symbolName) {
           prompt += `Symbol: ${chunk.payload.symbolName} (${chunk.payload.symbolKind})\n`;
         }
@@ -405,11 +1136,44 @@ export class AIManager {
     prompt += `2. How it works (key logic)\n`;
     prompt += `3. How it fits into the larger system\n`;
     prompt += `4. Any important design decisions or patterns\n\n`;
+    prompt += `When you refer to the code above, cite it by its heading as \`path:start-end\`.\n`;
     prompt += `Keep it concise but thorough.`;
 
     return prompt;
   }
 
+  /**
+   * Build prompt for a question about the code
+   */
+  private buildAskPrompt(question: string, context: Context): string {
+    let prompt = `You are an expert software engineer answering a question about a codebase from the code below.\n\n`;
+    prompt += `Question: ${question}\n\n`;
+
+    if (context.chunks.length > 0) {
+      prompt += `## Relevant Code\n\n`;
+      for (const chunk of context.chunks) {
+        prompt += `### ${formatChunkCitation(chunk.payload)}\n`;
+        if (chunk.payload.synthetic) {
+          prompt += `Summary (file too large to include in full): ${chunk.payload.text}\n\n`;
+          continue;
+        }
+        prompt += `\`\`\`${chunk.payload.language}\n${chunk.payload.text}\n\`\`\`\n\n`;
+      }
+    }
+
+    if (context.symbols.length > 0) {
+      prompt += `## Related Symbols\n\n`;
+      for (const symbol of context.symbols.slice(0, 20)) {
+        prompt += `- ${symbol.name} (${symbol.kind}) in ${symbol.file}:${symbol.startLine}\n`;
+      }
+      prompt += `\n`;
+    }
+
+    prompt += `Answer only from the code shown; say so when it is not enough to answer fully. `;
+    prompt += `Cite code by its heading as \`path:start-end\`.`;
+    return prompt;
+  }
+
   /**
    * Build prompt for plan generation
    */
@@ -440,9 +1204,11 @@ export class AIManager {
     prompt += `      "description": "Clear description of the step",\n`;
     prompt += `      "type": "create|modify|delete|rename",\n`;
     prompt += `      "file": "path/to/file",\n`;
-    prompt += `      "details": "Additional details if needed"\n`;
+    prompt += `      "details": "Additional details if needed",\n`;
+    prompt += `      "commands": ["Shell commands this step needs, if any"]\n`;
     prompt += `    }\n`;
     prompt += `  ],\n`;
+    prompt += `  "commands": ["Commands to run after the changes, e.g. tests"],\n`;
     prompt += `  "estimatedComplexity": "low|medium|high",\n`;
     prompt += `  "risks": ["Any potential risks or concerns"]\n`;
     prompt += `}\n\n`;
@@ -451,6 +1217,68 @@ export class AIManager {
     return prompt;
   }
 
+  /**
+   * Build prompt for an agent step plan
+   */
+  private buildAgentPlanPrompt(task: string, context: Context): string {
+    let prompt = `You are an expert software engineer working in a repository. Break the following task into explicit steps:\n\n`;
+    prompt += `Task: ${task}\n\n`;
+
+    if (context.prdContext) {
+      prompt += `## Requirements Context\n\n`;
+      prompt += PRDClient.formatContextForPrompt(context.prdContext);
+      prompt += `\n`;
+    }
+
+    if (context.chunks.length > 0) {
+      prompt += `## Relevant Code\n\n`;
+      for (const chunk of context.chunks) {
+        prompt += `### ${formatChunkCitation(chunk.payload)}\n`;
+        prompt += `\`\`\`${chunk.payload.language}\n${chunk.payload.text}\n\`\`\`\n\n`;
+      }
+    }
+
+    prompt += `\nEach step is one of:\n`;
+    prompt += `- "read": load files you need to see in full before editing\n`;
+    prompt += `- "edit": change one or a few closely related files\n`;
+    prompt += `- "run": run a shell command in the repository root, e.g. a build or the tests\n\n`;
+    prompt += `Read before you edit, keep each edit step small, and end with a step that checks the result when the project has tests.\n\n`;
+    prompt += `Respond with ONLY a JSON object, no other text:\n`;
+    prompt += `{"steps": [{"kind": "read|edit|run", "description": "<what and why>", "files": ["path/to/file"], "command": "<run steps only>"}]}`;
+
+    return prompt;
+  }
+
+  /**
+   * Build prompt for one agent edit step
+   */
+  private buildAgentEditPrompt(plan: AgentPlan, step: AgentStep, notes: string, context: Context): string {
+    let prompt = `You are an expert software engineer carrying out a plan one step at a time.\n\n`;
+    prompt += `Task: ${plan.task}\n\n`;
+    prompt += `## Plan\n`;
+    for (const s of plan.steps) {
+      const marker = s.index === step.index ? '→' : s.index < step.index ? '✓' : ' ';
+      prompt += `${marker} ${s.index}. [${s.kind}] ${s.description}${s.files.length > 0 ? ` (${s.files.join(', ')})` : ''}\n`;
+    }
+    prompt += `\n`;
+
+    if (notes) {
+      prompt += `## Results of Earlier Steps\n\n${notes}\n\n`;
+    } else if (context.chunks.length > 0) {
+      prompt += `## Relevant Code\n\n`;
+      for (const chunk of context.chunks) {
+        prompt += `### ${formatChunkCitation(chunk.payload)}\n`;
+        prompt += `\`\`\`${chunk.payload.language}\n${chunk.payload.text}\n\`\`\`\n\n`;
+      }
+    }
+
+    prompt += `Make ONLY the changes for step ${step.index}: ${step.description}\n\n`;
+    prompt += EDIT_BLOCK_INSTRUCTIONS;
+    prompt += `Briefly explain the changes before the blocks.`;
+
+    return prompt;
+  }
+
   /**
    * Build prompt for code generation
    */
@@ -467,8 +1295,8 @@ export class AIManager {
 
     if (context.chunks.length > 0) {
       prompt += `## Existing Code Context\n\n`;
-      for (const chunk of context.chunks.slice(0, 5)) {
-        prompt += `### ${chunk.payload.file}\n`;
+      for (const chunk of context.chunks) {
+        prompt += `### ${formatChunkCitation(chunk.payload)}\n`;
         prompt += `\`\`\`${chunk.payload.language}\n${chunk.payload.text}\n\`\`\`\n\n`;
       }
     }
@@ -481,7 +1309,8 @@ export class AIManager {
     if (context.prdContext) {
       prompt += `5. Ensure all requirements from the PRD are addressed\n`;
     }
-    prompt += `\nFormat your response clearly with file paths and code blocks.`;
+    prompt += `\n${EDIT_BLOCK_INSTRUCTIONS}`;
+    prompt += `Briefly explain the changes before the blocks.`;
 
     return prompt;
   }
@@ -489,15 +1318,23 @@ export class AIManager {
   /**
    * Build prompt for code review
    */
-  private buildReviewPrompt(diff: string, context?: Context): string {
+  private buildReviewPrompt(diff: string, context?: Context, symbols?: SymbolNode[]): string {
     let prompt = `You are an expert code reviewer. Review the following changes:\n\n`;
     prompt += `## Diff\n\`\`\`diff\n${diff}\n\`\`\`\n\n`;
 
+    if (symbols && symbols.length > 0) {
+      prompt += `## Touched Symbols\n`;
+      for (const symbol of symbols) {
+        prompt += `- ${symbol.kind} ${symbol.qualifiedName} (${symbol.file}:${symbol.startLine}-${symbol.endLine})\n`;
+      }
+      prompt += `\n`;
+    }
+
     if (context?.chunks && context.chunks.length > 0) {
       prompt += `## Related Code\n\n`;
-      for (const chunk of context.chunks.slice(0, 3)) {
-        prompt += `### ${chunk.payload.file}\n`;
-        prompt += `\`\`\`${chunk.payload.language}\n${chunk.payload.text.split('\n').slice(0, 15).join('\n')}\n\`\`\`\n\n`;
+      for (const chunk of context.chunks) {
+        prompt += `### ${formatChunkCitation(chunk.payload)}\n`;
+        prompt += `\`\`\`${chunk.payload.language}\n${chunk.payload.text}\n\`\`\`\n\n`;
       }
     }
 
@@ -527,7 +1364,8 @@ export class AIManager {
           steps: parsed.steps || [],
           estimatedComplexity: parsed.estimatedComplexity || 'medium',
           affectedFiles: parsed.steps?.map((s: PlanStep) => s.file) || [],
-          risks: parsed.risks || []
+          risks: parsed.risks || [],
+          commands: parsed.commands || []
         };
       }
     } catch (error) {
@@ -164,6 +164,38 @@ describe('LMStudioClient', () => {
     });
   });
 
+  describe('chatStream', () => {
+    it('streams tokens from SSE events split across chunks', async () => {
+      const sse = [
+        'data: {"choices":[{"delta":{"content":"Hel"}}]}\n\n',
+        'data: {"choices":[{"delta":{"content":"lo"}}]}\n\n',
+        'data: [DONE]\n\n',
+      ].join('');
+      const encoded = new TextEncoder().encode(sse);
+      mockFetch.mockResolvedValueOnce({
+        ok: true,
+        body: new ReadableStream({
+          start(controller) {
+            // Break mid-line so the client has to buffer
+            controller.enqueue(encoded.slice(0, 20));
+            controller.enqueue(encoded.slice(20, 61));
+            controller.enqueue(encoded.slice(61));
+            controller.close();
+          },
+        }),
+      });
+
+      const tokens: string[] = [];
+      const result = await client.chatStream([{ role: 'user', content: 'Hi' }], undefined, {
+        onToken: token => tokens.push(token),
+      });
+
+      expect(result).toBe('Hello');
+      expect(tokens).toEqual(['Hel', 'lo']);
+      expect(JSON.parse(mockFetch.mock.calls[0][1].body).stream).toBe(true);
+    });
+  });
+
   describe('embed', () => {
     it('calls /v1/embeddings with input as array', async () => {
       mockFetch.mockResolvedValueOnce({
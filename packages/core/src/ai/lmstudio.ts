@@ -10,6 +10,8 @@
 
 import { AIClient, AIMessage, AIStreamHandler, RECOMMENDED_MODELS } from './types.js';
 import { getLMStudioUrl } from '../config/service-urls.js';
+import { readSSE } from './stream.js';
+import { providerFetch } from './http.js';
 
 export interface LMStudioOptions {
   baseUrl?: string;
@@ -124,7 +126,7 @@ export class LMStudioClient implements AIClient {
     const controller = new AbortController();
     const timeout = setTimeout(() => controller.abort(), this.timeoutMs);
 
-    const response = await fetch(`${this.baseUrl}/chat/completions`, {
+    const response = await providerFetch('lmstudio', `${this.baseUrl}/chat/completions`, {
       method: 'POST',
       headers: {
         'Content-Type': 'application/json',
@@ -165,7 +167,7 @@ export class LMStudioClient implements AIClient {
     const controller = new AbortController();
     const timeout = setTimeout(() => controller.abort(), this.timeoutMs);
 
-    const response = await fetch(`${this.baseUrl}/chat/completions`, {
+    const response = await providerFetch('lmstudio', `${this.baseUrl}/chat/completions`, {
       method: 'POST',
       headers: {
         'Content-Type': 'application/json',
@@ -190,35 +192,21 @@ export class LMStudioClient implements AIClient {
     }
 
     let fullText = '';
-    const reader = response.body?.getReader();
-    if (!reader) {
+    if (!response.body) {
       throw new Error('No response body');
     }
 
-    const decoder = new TextDecoder();
-
     try {
-      while (true) {
-        const { done, value } = await reader.read();
-        if (done) break;
-
-        const chunk = decoder.decode(value);
-        const lines = chunk.split('\n').filter(l => l.trim() && l.startsWith('data: '));
-
-        for (const line of lines) {
-          const jsonStr = line.slice(6); // Remove 'data: ' prefix
-          if (jsonStr === '[DONE]') continue;
-
-          try {
-            const json = JSON.parse(jsonStr);
-            const token = json.choices?.[0]?.delta?.content || '';
-            if (token) {
-              fullText += token;
-              handler?.onToken?.(token);
-            }
-          } catch {
-            // Skip non-JSON lines
+      for await (const data of readSSE(response.body)) {
+        try {
+          const json = JSON.parse(data);
+          const token = json.choices?.[0]?.delta?.content || '';
+          if (token) {
+            fullText += token;
+            handler?.onToken?.(token);
           }
+        } catch {
+          // Skip non-JSON events
         }
       }
 
@@ -257,7 +245,7 @@ export class LMStudioClient implements AIClient {
     // First embedding request may be slow (model loading)
     const timeout = setTimeout(() => controller.abort(), 60_000);
 
-    const response = await fetch(`${this.baseUrl}/embeddings`, {
+    const response = await providerFetch('lmstudio', `${this.baseUrl}/embeddings`, {
       method: 'POST',
       headers: {
         'Content-Type': 'application/json',
@@ -0,0 +1,115 @@
+/**
+ * Offline Mode
+ *
+ * With `--offline` (or CV_OFFLINE=1), or when the machine has no network
+ * at all, requests to cloud providers are refused before they are sent
+ * instead of hanging on DNS and retrying. Local servers (Ollama, LM Studio,
+ * a Qdrant or FalkorDB on localhost) keep working, so does everything that
+ * only reads the repository and .cv/: keyword search, the graph,
+ * complexity and dead-code analysis.
+ *
+ * A request that still fails with a DNS or "network unreachable" error
+ * after its retries also switches the process offline, so the next request
+ * fails at once with the same message. CV_OFFLINE=0 turns the detection off.
+ */
+
+import * as os from 'os';
+import { CVError } from '../errors.js';
+
+export type OfflineReason = 'flag' | 'no-network' | 'unreachable';
+
+/**
+ * Connection errors that mean there is no route to the internet at all.
+ * ENOTFOUND is left out: a mistyped endpoint gets it too.
+ */
+const NO_NETWORK_CODES = new Set(['EAI_AGAIN', 'ENETUNREACH', 'ENETDOWN']);
+
+let offline: OfflineReason | undefined;
+
+export class OfflineError extends CVError {
+  readonly reason: OfflineReason;
+
+  /** `feature` names what needed the network; `hint` says what to use instead */
+  constructor(public readonly feature: string, hint?: string) {
+    const reason = offline ?? 'flag';
+    super(
+      `${feature} needs network access, and cv is offline (${describeOfflineReason(reason)})${hint ? `. ${hint}` : ''}`,
+      'OFFLINE',
+      { feature, reason }
+    );
+    this.name = 'OfflineError';
+    this.reason = reason;
+  }
+}
+
+/**
+ * Go offline for the rest of the process (`--offline`), or back online
+ */
+export function setOffline(value: boolean, reason: OfflineReason = 'flag'): void {
+  offline = value ? reason : undefined;
+}
+
+/** Why the process is offline, or undefined when it is online */
+export function getOfflineReason(): OfflineReason | undefined {
+  return offline;
+}
+
+export function isOffline(): boolean {
+  return offline !== undefined;
+}
+
+/**
+ * Offline state for a command from `--offline` and CV_OFFLINE, falling back
+ * to detectOffline(). Returns the reason when the process went offline.
+ */
+export function initOfflineMode(flag?: boolean): OfflineReason | undefined {
+  const env = process.env.CV_OFFLINE?.toLowerCase();
+  if (flag || env === '1' || env === 'true') setOffline(true, 'flag');
+  else if (env !== '0' && env !== 'false' && detectOffline()) setOffline(true, 'no-network');
+  return offline;
+}
+
+/**
+ * True when no interface has an address other than loopback and link-local,
+ * i.e. there is no network to reach a provider through
+ */
+export function detectOffline(interfaces: NodeJS.Dict<os.NetworkInterfaceInfo[]> = os.networkInterfaces()): boolean {
+  const addresses = Object.values(interfaces).flatMap(list => list ?? []);
+  return !addresses.some(info => !info.internal && !info.address.startsWith('fe80:') && !info.address.startsWith('169.254.'));
+}
+
+/**
+ * Whether a URL points at this machine, so it is reachable offline
+ */
+export function isLocalUrl(url: string): boolean {
+  let host: string;
+  try {
+    host = new URL(url).hostname.replace(/^\[|\]$/g, '');
+  } catch {
+    return false;
+  }
+  return host === 'localhost' || host.endsWith('.localhost') || host === '::1' || host === '0.0.0.0' ||
+    /^127\./.test(host) || host === os.hostname();
+}
+
+/**
+ * Throw an OfflineError for `feature` when offline and `url` (if given) is not local
+ */
+export function assertOnline(feature: string, url?: string): void {
+  if (offline && !(url && isLocalUrl(url))) throw new OfflineError(feature);
+}
+
+/**
+ * Whether a request failure means the network is gone rather than one server being down
+ */
+export function isNoNetworkError(error: unknown): boolean {
+  const err = error as { code?: string; cause?: { code?: string } } | undefined;
+  const code = err?.code ?? err?.cause?.code;
+  return !!code && NO_NETWORK_CODES.has(code);
+}
+
+export function describeOfflineReason(reason: OfflineReason): string {
+  if (reason === 'no-network') return 'no network detected; set CV_OFFLINE=0 to try anyway';
+  if (reason === 'unreachable') return 'the network became unreachable';
+  return '--offline';
+}
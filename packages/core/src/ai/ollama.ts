@@ -8,6 +8,8 @@
 
 import { AIClient, AIMessage, AIStreamHandler, RECOMMENDED_MODELS } from './types.js';
 import { getOllamaUrl } from '../config/service-urls.js';
+import { readNDJSON } from './stream.js';
+import { providerFetch } from './http.js';
 
 export interface OllamaOptions {
   baseUrl?: string;
@@ -144,7 +146,7 @@ export class OllamaClient implements AIClient {
   async chat(messages: AIMessage[], systemPrompt?: string): Promise<string> {
     const ollamaMessages = this.buildMessages(messages, systemPrompt);
 
-    const response = await fetch(`${this.baseUrl}/api/chat`, {
+    const response = await providerFetch('ollama', `${this.baseUrl}/api/chat`, {
       method: 'POST',
       headers: { 'Content-Type': 'application/json' },
       body: JSON.stringify({
@@ -177,7 +179,7 @@ export class OllamaClient implements AIClient {
   ): Promise<string> {
     const ollamaMessages = this.buildMessages(messages, systemPrompt);
 
-    const response = await fetch(`${this.baseUrl}/api/chat`, {
+    const response = await providerFetch('ollama', `${this.baseUrl}/api/chat`, {
       method: 'POST',
       headers: { 'Content-Type': 'application/json' },
       body: JSON.stringify({
@@ -199,32 +201,16 @@ export class OllamaClient implements AIClient {
     }
 
     let fullText = '';
-    const reader = response.body?.getReader();
-    if (!reader) {
+    if (!response.body) {
       throw new Error('No response body');
     }
 
-    const decoder = new TextDecoder();
-
     try {
-      while (true) {
-        const { done, value } = await reader.read();
-        if (done) break;
-
-        const chunk = decoder.decode(value);
-        const lines = chunk.split('\n').filter(l => l.trim());
-
-        for (const line of lines) {
-          try {
-            const json = JSON.parse(line);
-            const token = json.message?.content || '';
-            if (token) {
-              fullText += token;
-              handler?.onToken?.(token);
-            }
-          } catch {
-            // Skip non-JSON lines
-          }
+      for await (const json of readNDJSON(response.body)) {
+        const token = json.message?.content || '';
+        if (token) {
+          fullText += token;
+          handler?.onToken?.(token);
         }
       }
 
@@ -275,7 +261,7 @@ export class OllamaClient implements AIClient {
    * Generate embeddings (if model supports it)
    */
   async embed(text: string): Promise<number[]> {
-    const response = await fetch(`${this.baseUrl}/api/embeddings`, {
+    const response = await providerFetch('ollama', `${this.baseUrl}/api/embeddings`, {
       method: 'POST',
       headers: { 'Content-Type': 'application/json' },
       body: JSON.stringify({
@@ -0,0 +1,471 @@
+/**
+ * Repository Tour
+ *
+ * `cv onboard` turns the index into a guided tour for someone new to the
+ * codebase: the major modules and what they do, where execution starts, how
+ * the modules call each other (the CLI calling into the compute engine, the
+ * engine into storage), and which files to read first. The facts come from
+ * the tracked files, the symbol graph and git history; the model only writes
+ * the prose around them, so the tour is still useful without it.
+ */
+
+import { detectLanguage } from '@cv-git/shared';
+import type { GraphManager } from '../graph/index.js';
+import type { ChurnCommit } from '../git/hotspots.js';
+import { isTestFile } from '../parser/language-packs.js';
+
+export interface TourSymbol {
+  qualifiedName: string;
+  name: string;
+  kind: string;
+  file: string;
+}
+
+export interface TourGraph {
+  symbols: TourSymbol[];
+  /** CALLS edges between qualified names */
+  calls: Array<{ source: string; target: string }>;
+}
+
+export interface TourModule {
+  /** Directory, e.g. `packages/core` or `src/engine` */
+  path: string;
+  files: number;
+  symbols: number;
+  languages: string[];
+  /** Most-called symbols, by calls from outside their own file */
+  keySymbols: string[];
+  /** Lines added and deleted in the history read */
+  churn: number;
+  /** Most frequent committers, most active first */
+  authors: string[];
+  /** What the module is for; from the model or the stored codebase summary */
+  responsibility?: string;
+}
+
+export interface TourEntryPoint {
+  file: string;
+  /** e.g. `executable`, `library root`, `server` */
+  kind: string;
+  symbol?: string;
+}
+
+export interface TourFlow {
+  from: string;
+  to: string;
+  /** Call edges from one module into the other */
+  calls: number;
+  /** A few of them, as `caller → callee` */
+  examples: string[];
+}
+
+export interface TourReading {
+  file: string;
+  why: string;
+}
+
+export interface RepoTour {
+  /** Short description of the whole repository, from the model */
+  overview?: string;
+  modules: TourModule[];
+  /** Modules left out by maxModules */
+  omittedModules: number;
+  entryPoints: TourEntryPoint[];
+  flows: TourFlow[];
+  /** Narrative of how data moves through the modules, from the model */
+  dataFlow?: string;
+  reading: TourReading[];
+  recentCommits: Array<{ sha: string; subject: string; author: string; date: number }>;
+}
+
+export interface RepoTourInput {
+  /** Tracked files */
+  files: string[];
+  graph: TourGraph;
+  /** Churn history, newest first */
+  commits: ChurnCommit[];
+  /** Module descriptions already known, by module path (e.g. from `cv summary`) */
+  descriptions?: Record<string, string>;
+  /** Most modules to describe (default 12) */
+  maxModules?: number;
+  /** Most files to suggest reading (default 8) */
+  maxReading?: number;
+}
+
+/** Directories whose children are the modules of a monorepo */
+const WORKSPACE_DIRS = new Set(['packages', 'crates', 'services', 'apps', 'libs', 'modules', 'cmd', 'internal', 'pkg', 'plugins']);
+
+/** Directories that hold a single package's sources */
+const SOURCE_DIRS = new Set(['src', 'lib']);
+
+const ENTRY_FILES: Array<{ pattern: RegExp; kind: string }> = [
+  { pattern: /(^|\/)src\/bin\/[^/]+\.rs$/, kind: 'executable' },
+  { pattern: /(^|\/)main\.(rs|go|py|ts|js|java|kt|c|cc|cpp)$/, kind: 'executable' },
+  { pattern: /(^|\/)__main__\.py$/, kind: 'executable' },
+  { pattern: /(^|\/)(manage|wsgi|asgi)\.py$/, kind: 'server' },
+  { pattern: /(^|\/)(server|app)\.(ts|js|py|go)$/, kind: 'server' },
+  { pattern: /(^|\/)cli\.(ts|js|py)$/, kind: 'executable' },
+  { pattern: /(^|\/)src\/(lib\.rs|index\.(ts|js))$/, kind: 'library root' },
+  { pattern: /^(index\.(ts|js)|__init__\.py)$/, kind: 'library root' },
+];
+
+/** Entry points listed before the modules' most-used files */
+const MAX_ENTRY_READING = 3;
+
+/**
+ * The module a file belongs to: a workspace member (`packages/core`), a
+ * directory under a source root (`src/engine`), or a top-level directory
+ */
+export function moduleOf(file: string): string {
+  const parts = file.split('/');
+  if (parts.length === 1) return '.';
+  if (WORKSPACE_DIRS.has(parts[0]) && parts.length > 2) return `${parts[0]}/${parts[1]}`;
+  if (SOURCE_DIRS.has(parts[0]) && parts.length > 2) return `${parts[0]}/${parts[1]}`;
+  return parts[0];
+}
+
+/**
+ * Symbols and call edges, read from the graph in two queries
+ */
+export async function loadTourGraph(graph: Pick<GraphManager, 'query'>): Promise<TourGraph> {
+  const symbolRows = await graph.query(
+    'MATCH (s:Symbol) RETURN s.qualifiedName as qualifiedName, s.name as name, s.kind as kind, s.file as file'
+  );
+  const callRows = await graph.query(
+    'MATCH (a:Symbol)-[:CALLS]->(b:Symbol) RETURN a.qualifiedName as source, b.qualifiedName as target'
+  );
+  return {
+    symbols: symbolRows.map(row => ({
+      qualifiedName: String(row.qualifiedName),
+      name: String(row.name ?? ''),
+      kind: String(row.kind ?? ''),
+      file: String(row.file ?? '')
+    })),
+    calls: callRows.map(row => ({ source: String(row.source), target: String(row.target) }))
+  };
+}
+
+/**
+ * Modules, entry points, cross-module calls and a reading order, from the
+ * files, graph and history alone
+ */
+export function buildRepoTour(input: RepoTourInput): RepoTour {
+  const maxModules = Math.max(1, input.maxModules ?? 12);
+  const maxReading = Math.max(1, input.maxReading ?? 8);
+  const sources = input.files.filter(file => !isTestFile(file));
+  const tracked = new Set(sources);
+  const bySymbol = new Map(input.graph.symbols.map(symbol => [symbol.qualifiedName, symbol]));
+
+  // Calls into each symbol and file from other files
+  const fanIn = new Map<string, number>();
+  const fileFanIn = new Map<string, number>();
+  const flows = new Map<string, TourFlow>();
+  for (const { source, target } of input.graph.calls) {
+    const from = bySymbol.get(source);
+    const to = bySymbol.get(target);
+    if (!from || !to || from.file === to.file) continue;
+    fanIn.set(target, (fanIn.get(target) ?? 0) + 1);
+    fileFanIn.set(to.file, (fileFanIn.get(to.file) ?? 0) + 1);
+
+    const fromModule = moduleOf(from.file);
+    const toModule = moduleOf(to.file);
+    if (fromModule === toModule || isTestFile(from.file)) continue;
+    const key = `${fromModule}\0${toModule}`;
+    const flow = flows.get(key) ?? { from: fromModule, to: toModule, calls: 0, examples: [] };
+    flow.calls++;
+    const example = `${from.name} → ${to.name}`;
+    if (flow.examples.length < 3 && !flow.examples.includes(example)) flow.examples.push(example);
+    flows.set(key, flow);
+  }
+
+  // Code files only; docs and config don't make a module
+  const modules = new Map<string, TourModule>();
+  const languageCounts = new Map<string, Map<string, number>>();
+  for (const file of sources) {
+    const language = detectLanguage(file);
+    if (language === 'unknown') continue;
+    const path = moduleOf(file);
+    const module = modules.get(path) ?? { path, files: 0, symbols: 0, languages: [], keySymbols: [], churn: 0, authors: [] };
+    module.files++;
+    modules.set(path, module);
+    const counts = languageCounts.get(path) ?? new Map<string, number>();
+    counts.set(language, (counts.get(language) ?? 0) + 1);
+    languageCounts.set(path, counts);
+  }
+  const moduleSymbols = new Map<string, TourSymbol[]>();
+  for (const symbol of input.graph.symbols) {
+    const module = modules.get(moduleOf(symbol.file));
+    if (!module || isTestFile(symbol.file)) continue;
+    module.symbols++;
+    const list = moduleSymbols.get(module.path) ?? [];
+    list.push(symbol);
+    moduleSymbols.set(module.path, list);
+  }
+
+  const authorCounts = new Map<string, Map<string, number>>();
+  for (const commit of input.commits) {
+    const touched = new Set<string>();
+    for (const change of commit.files) {
+      const module = modules.get(moduleOf(change.path));
+      if (!module) continue;
+      module.churn += change.added + change.deleted;
+      touched.add(module.path);
+    }
+    for (const path of touched) {
+      const counts = authorCounts.get(path) ?? new Map<string, number>();
+      counts.set(commit.author, (counts.get(commit.author) ?? 0) + 1);
+      authorCounts.set(path, counts);
+    }
+  }
+
+  const incoming = new Map<string, number>();
+  for (const flow of flows.values()) incoming.set(flow.to, (incoming.get(flow.to) ?? 0) + flow.calls);
+
+  for (const module of modules.values()) {
+    module.languages = rankKeys(languageCounts.get(module.path) ?? new Map()).slice(0, 3);
+    module.keySymbols = (moduleSymbols.get(module.path) ?? [])
+      .filter(symbol => (fanIn.get(symbol.qualifiedName) ?? 0) > 0)
+      .sort((a, b) => (fanIn.get(b.qualifiedName) ?? 0) - (fanIn.get(a.qualifiedName) ?? 0) || a.name.localeCompare(b.name))
+      .slice(0, 5)
+      .map(symbol => symbol.name);
+    module.authors = rankKeys(authorCounts.get(module.path) ?? new Map()).slice(0, 3);
+    const description = input.descriptions?.[module.path];
+    if (description) module.responsibility = description;
+  }
+
+  // Most depended-on modules first, then the largest
+  const ordered = [...modules.values()]
+    .sort((a, b) => (incoming.get(b.path) ?? 0) - (incoming.get(a.path) ?? 0) || b.symbols - a.symbols || b.files - a.files || a.path.localeCompare(b.path));
+  const shown = ordered.slice(0, maxModules);
+  const shownPaths = new Set(shown.map(module => module.path));
+
+  const entryPoints = findEntryPoints(sources, input.graph.symbols);
+
+  const reading: TourReading[] = [];
+  const suggest = (file: string, why: string) => {
+    if (reading.length < maxReading && !reading.some(entry => entry.file === file)) reading.push({ file, why });
+  };
+  for (const entry of entryPoints.slice(0, MAX_ENTRY_READING)) {
+    suggest(entry.file, entry.symbol ? `${entry.kind}: starts at ${entry.symbol}` : entry.kind);
+  }
+  for (const module of shown) {
+    const file = [...fileFanIn.entries()]
+      .filter(([candidate]) => tracked.has(candidate) && moduleOf(candidate) === module.path)
+      .sort((a, b) => b[1] - a[1] || a[0].localeCompare(b[0]))[0];
+    if (file) suggest(file[0], `most used file in ${module.path} (${file[1]} call(s) from other files)`);
+  }
+
+  return {
+    modules: shown,
+    omittedModules: ordered.length - shown.length,
+    entryPoints,
+    flows: [...flows.values()]
+      .filter(flow => shownPaths.has(flow.from) && shownPaths.has(flow.to))
+      .sort((a, b) => b.calls - a.calls || a.from.localeCompare(b.from) || a.to.localeCompare(b.to))
+      .slice(0, 15),
+    reading,
+    recentCommits: input.commits.slice(0, 10).map(({ sha, subject, author, date }) => ({ sha, subject, author, date }))
+  };
+}
+
+function findEntryPoints(files: string[], symbols: TourSymbol[]): TourEntryPoint[] {
+  const mains = new Map<string, string>();
+  for (const symbol of symbols) {
+    if (symbol.name === 'main' && (symbol.kind === 'function' || symbol.kind === 'method')) mains.set(symbol.file, symbol.name);
+  }
+
+  const entries: TourEntryPoint[] = [];
+  for (const file of files) {
+    const match = ENTRY_FILES.find(entry => entry.pattern.test(file));
+    if (match) entries.push({ file, kind: match.kind, ...(mains.has(file) ? { symbol: mains.get(file) } : {}) });
+    else if (mains.has(file)) entries.push({ file, kind: 'executable', symbol: mains.get(file) });
+  }
+  // Executables before servers before library roots; shallow paths first
+  const rank = (entry: TourEntryPoint) => ['executable', 'server', 'library root'].indexOf(entry.kind);
+  return entries.sort((a, b) =>
+    rank(a) - rank(b) || a.file.split('/').length - b.file.split('/').length || a.file.localeCompare(b.file)
+  );
+}
+
+function rankKeys(counts: Map<string, number>): string[] {
+  return [...counts.entries()].sort((a, b) => b[1] - a[1] || a[0].localeCompare(b[0])).map(([key]) => key);
+}
+
+export const ONBOARD_SYSTEM_PROMPT = `You are a senior engineer writing an onboarding tour of a codebase for a new team member.
+Base every statement on the facts provided (modules, symbols, call flows, history, README). Be concrete and concise; name files and symbols.`;
+
+/**
+ * Prompt with the tour's facts, asking for the prose as JSON
+ */
+export function buildOnboardPrompt(tour: RepoTour, readme?: string): string {
+  const parts: string[] = ['Write an onboarding tour of this repository.', ''];
+  if (readme?.trim()) {
+    parts.push('## README (excerpt)', readme.trim().slice(0, 4000), '');
+  }
+
+  parts.push('## Modules');
+  for (const module of tour.modules) {
+    const facts = [`${module.files} file(s)`, `${module.symbols} symbol(s)`];
+    if (module.languages.length) facts.push(module.languages.join('/'));
+    if (module.keySymbols.length) facts.push(`key symbols: ${module.keySymbols.join(', ')}`);
+    parts.push(`- ${module.path}: ${facts.join('; ')}${module.responsibility ? ` — ${module.responsibility}` : ''}`);
+  }
+
+  if (tour.entryPoints.length) {
+    parts.push('', '## Entry points');
+    for (const entry of tour.entryPoints.slice(0, 10)) parts.push(`- ${entry.file} (${entry.kind}${entry.symbol ? `, ${entry.symbol}` : ''})`);
+  }
+  if (tour.flows.length) {
+    parts.push('', '## Calls between modules');
+    for (const flow of tour.flows) parts.push(`- ${flow.from} → ${flow.to}: ${flow.calls} call(s), e.g. ${flow.examples.join('; ')}`);
+  }
+  if (tour.reading.length) {
+    parts.push('', '## Suggested first files');
+    for (const entry of tour.reading) parts.push(`- ${entry.file}: ${entry.why}`);
+  }
+  if (tour.recentCommits.length) {
+    parts.push('', '## Recent commits');
+    for (const commit of tour.recentCommits) parts.push(`- ${commit.subject} (${commit.author})`);
+  }
+
+  parts.push(
+    '',
+    'Respond with JSON only, in this shape:',
+    '```json',
+    '{"overview": "2-4 sentences on what the repository is and how it is organised",',
+    ' "modules": [{"path": "<module path from the list>", "responsibility": "one sentence"}],',
+    ' "dataFlow": "a short paragraph tracing a typical request or computation through the modules",',
+    ' "reading": [{"file": "<file from the suggested list>", "why": "what the reader learns there"}]}',
+    '```',
+    'Order "reading" as the files should be read.'
+  );
+  return parts.join('\n');
+}
+
+/**
+ * Merge the model's prose into the tour; modules and files it wasn't given are ignored
+ */
+export function applyOnboardResponse(tour: RepoTour, response: string): RepoTour {
+  const fenced = response.match(/```(?:json)?\s*\n([\s\S]*?)\n```/);
+  const candidate = fenced ? fenced[1] : response.match(/\{[\s\S]*\}/)?.[0];
+  let parsed: any;
+  try {
+    parsed = candidate ? JSON.parse(candidate) : undefined;
+  } catch {
+    parsed = undefined;
+  }
+  if (!parsed || typeof parsed !== 'object') {
+    throw new Error('The model did not return the tour as JSON');
+  }
+
+  const text = (value: unknown) => (typeof value === 'string' && value.trim() ? value.trim() : undefined);
+  const responsibilities = new Map<string, string>();
+  for (const raw of Array.isArray(parsed.modules) ? parsed.modules : []) {
+    const path = text(raw?.path);
+    const responsibility = text(raw?.responsibility);
+    if (path && responsibility) responsibilities.set(path, responsibility);
+  }
+
+  // The model may reorder and explain the suggested files, but not add new ones
+  const known = new Map(tour.reading.map(entry => [entry.file, entry]));
+  const reading: TourReading[] = [];
+  for (const raw of Array.isArray(parsed.reading) ? parsed.reading : []) {
+    const file = text(raw?.file);
+    if (!file || !known.has(file) || reading.some(entry => entry.file === file)) continue;
+    reading.push({ file, why: text(raw?.why) ?? known.get(file)!.why });
+  }
+  for (const entry of tour.reading) {
+    if (!reading.some(existing => existing.file === entry.file)) reading.push(entry);
+  }
+
+  return {
+    ...tour,
+    overview: text(parsed.overview) ?? tour.overview,
+    dataFlow: text(parsed.dataFlow) ?? tour.dataFlow,
+    modules: tour.modules.map(module => ({ ...module, responsibility: responsibilities.get(module.path) ?? module.responsibility })),
+    reading
+  };
+}
+
+export interface TourSection {
+  title: string;
+  /** Markdown lines */
+  lines: string[];
+}
+
+/**
+ * The tour as titled sections, the unit of the interactive walkthrough
+ */
+export function tourSections(tour: RepoTour, name: string = 'this repository'): TourSection[] {
+  const sections: TourSection[] = [];
+
+  const overview: string[] = [];
+  if (tour.overview) overview.push(tour.overview, '');
+  const totalFiles = tour.modules.reduce((n, module) => n + module.files, 0);
+  overview.push(`${tour.modules.length + tour.omittedModules} module(s), ${totalFiles}+ source file(s).`);
+  sections.push({ title: `Welcome to ${name}`, lines: overview });
+
+  const modules: string[] = [];
+  for (const module of tour.modules) {
+    modules.push(`### \`${module.path}\``);
+    if (module.responsibility) modules.push(module.responsibility);
+    const facts = [`${module.files} file(s)`, `${module.symbols} symbol(s)`];
+    if (module.languages.length) facts.push(module.languages.join(', '));
+    modules.push(`- ${facts.join(' · ')}`);
+    if (module.keySymbols.length) modules.push(`- Key symbols: ${module.keySymbols.map(symbol => `\`${symbol}\``).join(', ')}`);
+    if (module.authors.length) modules.push(`- Most active: ${module.authors.join(', ')}`);
+    modules.push('');
+  }
+  if (tour.omittedModules > 0) modules.push(`_${tour.omittedModules} smaller module(s) not shown._`);
+  sections.push({ title: 'Modules', lines: trimEnd(modules) });
+
+  sections.push({
+    title: 'Entry points',
+    lines: tour.entryPoints.length
+      ? tour.entryPoints.slice(0, 10).map(entry => `- \`${entry.file}\` — ${entry.kind}${entry.symbol ? ` (\`${entry.symbol}\`)` : ''}`)
+      : ['No entry points recognised (no main, bin, server or index files).']
+  });
+
+  const flow: string[] = [];
+  if (tour.dataFlow) flow.push(tour.dataFlow, '');
+  if (tour.flows.length) {
+    for (const edge of tour.flows) {
+      flow.push(`- \`${edge.from}\` → \`${edge.to}\`: ${edge.calls} call(s), e.g. ${edge.examples.map(example => `\`${example}\``).join(', ')}`);
+    }
+  } else {
+    flow.push('No calls between modules in the symbol graph (run `cv sync`).');
+  }
+  sections.push({ title: 'Data flow', lines: flow });
+
+  sections.push({
+    title: 'Start reading here',
+    lines: tour.reading.length
+      ? tour.reading.map((entry, i) => `${i + 1}. \`${entry.file}\` — ${entry.why}`)
+      : ['No suggestions: the symbol graph is empty (run `cv sync`).']
+  });
+
+  if (tour.recentCommits.length) {
+    sections.push({
+      title: 'Recent activity',
+      lines: tour.recentCommits.map(commit =>
+        `- ${new Date(commit.date).toISOString().slice(0, 10)} \`${commit.sha.slice(0, 7)}\` ${commit.subject} — ${commit.author}`
+      )
+    });
+  }
+  return sections;
+}
+
+/**
+ * The tour as one markdown document
+ */
+export function formatTourMarkdown(tour: RepoTour, name?: string): string {
+  const sections = tourSections(tour, name);
+  const parts = [`# ${sections[0].title}`, '', ...sections[0].lines];
+  for (const section of sections.slice(1)) parts.push('', `## ${section.title}`, '', ...section.lines);
+  return parts.join('\n') + '\n';
+}
+
+function trimEnd(lines: string[]): string[] {
+  while (lines.length > 0 && lines[lines.length - 1] === '') lines.pop();
+  return lines;
+}
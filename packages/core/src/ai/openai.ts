@@ -0,0 +1,48 @@
+/**
+ * OpenAI Chat Client
+ * Direct access to OpenAI models with the same client as OpenRouter
+ *
+ * API key from `cv auth setup openai` or OPENAI_API_KEY.
+ */
+
+import OpenAI from 'openai';
+import { OpenRouterClient, OpenRouterOptions } from './openrouter.js';
+import { limitedFetch } from './http.js';
+
+export type OpenAIChatOptions = OpenRouterOptions;
+
+export const DEFAULT_OPENAI_MODEL = 'gpt-4o';
+
+/**
+ * OpenAI API client; model ids are used as given (no OpenRouter aliases)
+ */
+export class OpenAIChatClient extends OpenRouterClient {
+  constructor(options: OpenAIChatOptions) {
+    // OpenAI rejects max_tokens above the model's output limit
+    super({ ...options, maxTokens: options.maxTokens || 16384 });
+  }
+
+  protected createClient(apiKey: string): OpenAI {
+    return new OpenAI({ apiKey, fetch: limitedFetch('openai'), maxRetries: 0 });
+  }
+
+  protected defaultModel(): string {
+    return DEFAULT_OPENAI_MODEL;
+  }
+
+  protected resolveModel(model: string): string {
+    // Accept OpenRouter-style ids such as openai/gpt-4o
+    return model.replace(/^openai\//, '');
+  }
+
+  getProvider(): string {
+    return 'openai';
+  }
+}
+
+/**
+ * Create an OpenAI chat client
+ */
+export function createOpenAIChatClient(options: OpenAIChatOptions): OpenAIChatClient {
+  return new OpenAIChatClient(options);
+}
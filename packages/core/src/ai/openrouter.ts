@@ -4,7 +4,17 @@
  */
 
 import OpenAI from 'openai';
-import { AIClient, AIMessage, AIStreamHandler, RECOMMENDED_MODELS } from './types.js';
+import {
+  AIClient,
+  AIMessage,
+  AIStreamHandler,
+  AITool,
+  AIToolCall,
+  AIToolMessage,
+  AIToolResult,
+  RECOMMENDED_MODELS,
+} from './types.js';
+import { limitedFetch } from './http.js';
 
 export interface OpenRouterOptions {
   apiKey: string;
@@ -55,27 +65,43 @@ export const OPENROUTER_MODELS = {
 export type ModelAlias = keyof typeof OPENROUTER_MODELS;
 
 export class OpenRouterClient implements AIClient {
-  private client: OpenAI;
-  private model: string;
-  private maxTokens: number;
-  private temperature: number;
+  protected client: OpenAI;
+  protected model: string;
+  protected maxTokens: number;
+  protected temperature: number;
 
   constructor(options: OpenRouterOptions) {
-    this.client = new OpenAI({
-      apiKey: options.apiKey,
+    this.client = this.createClient(options.apiKey);
+
+    // Resolve model alias or use directly
+    this.model = this.resolveModel(options.model || this.defaultModel());
+
+    this.maxTokens = options.maxTokens || 128000;
+    this.temperature = options.temperature || 0.7;
+  }
+
+  /**
+   * SDK client for the OpenAI-compatible endpoint
+   */
+  protected createClient(apiKey: string): OpenAI {
+    return new OpenAI({
+      apiKey,
       baseURL: 'https://openrouter.ai/api/v1',
       defaultHeaders: {
         'HTTP-Referer': 'https://github.com/anthropics/cv-git',
         'X-Title': 'cv-git',
       },
+      fetch: limitedFetch('openrouter'),
+      maxRetries: 0,
     });
+  }
 
-    // Resolve model alias or use directly
-    const modelInput = options.model || 'claude-sonnet-4-5';
-    this.model = OPENROUTER_MODELS[modelInput as ModelAlias] || modelInput;
+  protected defaultModel(): string {
+    return 'claude-sonnet-4-5';
+  }
 
-    this.maxTokens = options.maxTokens || 128000;
-    this.temperature = options.temperature || 0.7;
+  protected resolveModel(model: string): string {
+    return OPENROUTER_MODELS[model as ModelAlias] || model;
   }
 
   /**
@@ -96,7 +122,7 @@ export class OpenRouterClient implements AIClient {
    * Set a different model
    */
   setModel(model: string): void {
-    this.model = OPENROUTER_MODELS[model as ModelAlias] || model;
+    this.model = this.resolveModel(model);
   }
 
   /**
@@ -169,6 +195,8 @@ export class OpenRouterClient implements AIClient {
         max_tokens: this.maxTokens,
         temperature: this.temperature,
         stream: true,
+        // Final chunk carries token counts for usage tracking
+        stream_options: { include_usage: true },
       });
 
       for await (const chunk of stream) {
@@ -198,6 +226,74 @@ export class OpenRouterClient implements AIClient {
       handler
     );
   }
+
+  /**
+   * List model ids available through OpenRouter
+   */
+  async listModels(): Promise<string[]> {
+    try {
+      const models = await this.client.models.list();
+      return models.data.map(m => m.id);
+    } catch {
+      return [];
+    }
+  }
+
+  /**
+   * Chat completion that may answer with tool calls (OpenAI function calling)
+   */
+  async chatWithTools(
+    messages: AIToolMessage[],
+    tools: AITool[],
+    systemPrompt?: string
+  ): Promise<AIToolResult> {
+    const openaiMessages: OpenAI.ChatCompletionMessageParam[] = [];
+
+    if (systemPrompt) {
+      openaiMessages.push({ role: 'system', content: systemPrompt });
+    }
+
+    for (const msg of messages) {
+      if (msg.role === 'tool') {
+        openaiMessages.push({ role: 'tool', tool_call_id: msg.toolCallId, content: msg.content });
+      } else if (msg.role === 'assistant' && 'toolCalls' in msg) {
+        openaiMessages.push({
+          role: 'assistant',
+          content: msg.content || null,
+          tool_calls: msg.toolCalls.map(call => ({
+            id: call.id,
+            type: 'function' as const,
+            function: { name: call.name, arguments: JSON.stringify(call.arguments) },
+          })),
+        });
+      } else {
+        openaiMessages.push({ role: msg.role, content: msg.content } as OpenAI.ChatCompletionMessageParam);
+      }
+    }
+
+    const response = await this.client.chat.completions.create({
+      model: this.model,
+      messages: openaiMessages,
+      max_tokens: this.maxTokens,
+      temperature: this.temperature,
+      tools: tools.map(tool => ({
+        type: 'function' as const,
+        function: { name: tool.name, description: tool.description, parameters: tool.parameters },
+      })),
+    });
+
+    const message = response.choices[0]?.message;
+    const toolCalls: AIToolCall[] = (message?.tool_calls || []).map(call => {
+      let args: Record<string, unknown> = {};
+      try {
+        args = JSON.parse(call.function.arguments || '{}');
+      } catch {
+        // Leave malformed arguments empty; the caller reports the failed call
+      }
+      return { id: call.id, name: call.function.name, arguments: args };
+    });
+    return { text: message?.content || '', toolCalls };
+  }
 }
 
 /**
@@ -0,0 +1,150 @@
+/**
+ * Pull Request Reviews
+ *
+ * Turns review findings into inline comments for a hosted pull request.
+ * Platforms only accept comments on lines the diff shows, so findings
+ * elsewhere go into the review body instead. Each comment carries a marker
+ * with the finding ID (see findingId), so re-running `cv pr review --post`
+ * after a push does not repeat comments that are already on the PR.
+ */
+
+import { REVIEW_SEVERITIES, splitDiff } from './review.js';
+import type { ReviewFinding, ReviewResult } from './review.js';
+
+export interface InlineReviewComment {
+  /** Path after the change */
+  path: string;
+  /** Last line of the commented range (new side) */
+  line: number;
+  /** First line, for multi-line comments */
+  startLine?: number;
+  /** Old-side line of an unchanged (context) line; some platforms need both */
+  oldLine?: number;
+  body: string;
+}
+
+export interface PullRequestReviewPlan {
+  body: string;
+  comments: InlineReviewComment[];
+  /** Findings that are already on the PR */
+  skipped: ReviewFinding[];
+}
+
+const FINDING_MARKER = /<!-- cv-git:finding:([0-9a-f]+) -->/g;
+
+const SEVERITY_BADGE: Record<string, string> = {
+  critical: '🔴 **Critical**',
+  high: '🟠 **High**',
+  medium: '🟡 **Medium**',
+  low: '🔵 **Low**',
+  info: '⚪ **Info**'
+};
+
+/**
+ * New-side line numbers each file's diff shows (added and context lines),
+ * the lines a platform accepts inline comments on, each mapped to its
+ * old-side line (undefined for added lines)
+ */
+export function commentableLines(diff: string): Map<string, Map<number, number | undefined>> {
+  const lines = new Map<string, Map<number, number | undefined>>();
+  for (const section of splitDiff(diff)) {
+    const shown = new Map<number, number | undefined>();
+    let line = 0;
+    let oldLine = 0;
+    for (const text of section.text.split('\n')) {
+      const hunk = text.match(/^@@ -(\d+)(?:,\d+)? \+(\d+)(?:,\d+)? @@/);
+      if (hunk) {
+        oldLine = parseInt(hunk[1], 10);
+        line = parseInt(hunk[2], 10);
+      } else if (line === 0 || text === '' || text.startsWith('\\')) {
+        continue;
+      } else if (text.startsWith('-')) {
+        oldLine++;
+      } else if (text.startsWith('+')) {
+        shown.set(line++, undefined);
+      } else {
+        shown.set(line++, oldLine++);
+      }
+    }
+    if (shown.size > 0) lines.set(section.file, shown);
+  }
+  return lines;
+}
+
+/**
+ * Finding IDs already posted, from the bodies of existing comments
+ */
+export function postedFindingIds(bodies: string[]): Set<string> {
+  const ids = new Set<string>();
+  for (const body of bodies) {
+    for (const match of body.matchAll(FINDING_MARKER)) ids.add(match[1]);
+  }
+  return ids;
+}
+
+/**
+ * Markdown for one finding, ending with its ID marker
+ */
+export function formatFindingComment(finding: ReviewFinding): string {
+  let body = `${SEVERITY_BADGE[finding.severity] ?? finding.severity}: ${finding.title}`;
+  if (finding.message && finding.message !== finding.title) body += `\n\n${finding.message}`;
+  if (finding.suggestion) body += `\n\n**Suggestion:** ${finding.suggestion}`;
+  if (finding.rule) body += `\n\n<sub>Rule: ${finding.rule}</sub>`;
+  return `${body}\n\n<!-- cv-git:finding:${finding.id} -->`;
+}
+
+/**
+ * Split a review into inline comments and a summary body, leaving out
+ * findings whose IDs are in `posted`
+ */
+export function planPullRequestReview(
+  review: ReviewResult,
+  diff: string,
+  posted: Set<string> = new Set()
+): PullRequestReviewPlan {
+  const shown = commentableLines(diff);
+  const comments: InlineReviewComment[] = [];
+  const outside: ReviewFinding[] = [];
+  const skipped: ReviewFinding[] = [];
+
+  for (const finding of review.findings) {
+    if (posted.has(finding.id)) {
+      skipped.push(finding);
+      continue;
+    }
+    const lines = shown.get(finding.file);
+    const end = finding.endLine ?? finding.line;
+    if (!lines?.has(end)) {
+      outside.push(finding);
+      continue;
+    }
+    // A range comment needs both ends in the diff; otherwise comment on the last line
+    const multiLine = end > finding.line && lines.has(finding.line);
+    comments.push({
+      path: finding.file,
+      line: end,
+      ...(multiLine ? { startLine: finding.line } : {}),
+      ...(lines.get(end) !== undefined ? { oldLine: lines.get(end) } : {}),
+      body: formatFindingComment(finding)
+    });
+  }
+
+  const counts = REVIEW_SEVERITIES
+    .map(severity => [severity, review.findings.filter(f => f.severity === severity && !posted.has(f.id)).length] as const)
+    .filter(([, count]) => count > 0)
+    .map(([severity, count]) => `${count} ${severity}`);
+
+  let body = `## cv review\n\n${review.summary || 'No summary.'}\n`;
+  body += `\n${counts.length > 0 ? `Findings: ${counts.join(', ')}` : 'No new findings.'}\n`;
+  if (outside.length > 0) {
+    body += `\n### Outside the diff\n\n`;
+    for (const finding of outside) {
+      const location = finding.endLine && finding.endLine !== finding.line
+        ? `${finding.file}:${finding.line}-${finding.endLine}`
+        : `${finding.file}:${finding.line}`;
+      body += `- \`${location}\` ${formatFindingComment(finding).replace(/\n\n/g, '\n  ')}\n`;
+    }
+  }
+
+  return { body, comments, skipped };
+}
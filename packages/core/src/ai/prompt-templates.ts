@@ -0,0 +1,256 @@
+/**
+ * Prompt Templates
+ *
+ * Repository-specific instructions for the model, kept in `.cv/prompts/`:
+ *
+ *   system.md          system prompt for every request
+ *   explain.md         prompt of `cv explain` (also review.md, do.md, commit.md)
+ *   review.system.md   system prompt of `cv review` instead of system.md,
+ *                      which it can include as `{{system}}`
+ *
+ * Templates are plain text with `{{variable}}` placeholders and
+ * `{{#if variable}} ... {{/if}}` sections, shown only when the variable is
+ * not empty; `{{! ... }}` is a comment. A command template usually adds to
+ * the built-in prompt (`{{prompt}}`), or replaces it using `{{input}}` and
+ * `{{context}}`. Every file is checked when it is loaded, so a typo in a
+ * variable fails the command instead of reaching the model.
+ */
+
+import * as fs from 'fs/promises';
+import * as path from 'path';
+import { createHash } from 'crypto';
+import { getCVDir } from '@cv-git/shared';
+
+export const PROMPTS_DIR = 'prompts';
+
+/** Commands whose prompt can be templated */
+export const PROMPT_TEMPLATE_NAMES = ['explain', 'review', 'do', 'commit'] as const;
+
+export type PromptTemplateName = typeof PROMPT_TEMPLATE_NAMES[number];
+
+/** Variables every template can use */
+const COMMON_VARIABLES = ['repo', 'language', 'command', 'date'];
+
+/** Variables of each kind of template */
+export const PROMPT_VARIABLES = {
+  /** system.md */
+  system: COMMON_VARIABLES,
+  /** <command>.system.md; `system` is system.md, rendered */
+  commandSystem: [...COMMON_VARIABLES, 'system'],
+  /** <command>.md; `prompt` is the built-in prompt, `input` the target, task or diff */
+  command: [...COMMON_VARIABLES, 'input', 'context', 'prompt']
+};
+
+export interface PromptVariables {
+  /** Target, task or diff the command was given */
+  input?: string;
+  /** Retrieved code, formatted as the built-in prompts show it */
+  context?: string;
+}
+
+export type TemplateNode =
+  | { type: 'text'; text: string }
+  | { type: 'var'; name: string }
+  | { type: 'if'; name: string; body: TemplateNode[] };
+
+export class PromptTemplateError extends Error {
+  constructor(message: string) {
+    super(message);
+    this.name = 'PromptTemplateError';
+  }
+}
+
+const TAG = /\{\{\s*(.*?)\s*\}\}/gs;
+const NAME = /^[a-z][a-zA-Z0-9_]*$/;
+
+/**
+ * Parse a template, checking that it only uses `variables` and that every
+ * section is closed
+ */
+export function parseTemplate(source: string, file: string, variables: string[]): TemplateNode[] {
+  const root: TemplateNode[] = [];
+  const stack: Array<{ name: string; body: TemplateNode[]; line: number }> = [];
+  const lineAt = (index: number) => source.slice(0, index).split('\n').length;
+  let body = root;
+  let last = 0;
+
+  const known = (name: string, index: number) => {
+    if (!NAME.test(name) || !variables.includes(name)) {
+      throw new PromptTemplateError(
+        `${file}:${lineAt(index)}: unknown variable "${name}" (available: ${variables.join(', ')})`
+      );
+    }
+  };
+
+  for (const match of source.matchAll(TAG)) {
+    const index = match.index!;
+    if (index > last) body.push({ type: 'text', text: source.slice(last, index) });
+    last = index + match[0].length;
+    const tag = match[1];
+
+    if (tag.startsWith('!')) continue;
+    if (tag.startsWith('#if ')) {
+      const name = tag.slice(4).trim();
+      known(name, index);
+      const section = { name, body: [] as TemplateNode[], line: lineAt(index) };
+      body.push({ type: 'if', name, body: section.body });
+      stack.push(section);
+      body = section.body;
+    } else if (tag === '/if') {
+      if (!stack.pop()) throw new PromptTemplateError(`${file}:${lineAt(index)}: {{/if}} without {{#if}}`);
+      body = stack.length > 0 ? stack[stack.length - 1].body : root;
+    } else if (tag.startsWith('#') || tag.startsWith('/')) {
+      throw new PromptTemplateError(`${file}:${lineAt(index)}: unknown tag "{{${tag}}}" (use {{#if name}} ... {{/if}})`);
+    } else {
+      known(tag, index);
+      body.push({ type: 'var', name: tag });
+    }
+  }
+
+  const rest = source.slice(last);
+  const open = rest.indexOf('{{');
+  if (open !== -1) {
+    throw new PromptTemplateError(`${file}:${lineAt(last + open)}: "{{" is never closed`);
+  }
+  if (rest) body.push({ type: 'text', text: rest });
+  if (stack.length > 0) {
+    throw new PromptTemplateError(`${file}:${stack[stack.length - 1].line}: {{#if ${stack[stack.length - 1].name}}} is never closed`);
+  }
+  return root;
+}
+
+/**
+ * Render a parsed template; missing variables are empty
+ */
+export function renderTemplate(nodes: TemplateNode[], values: Record<string, string | undefined>): string {
+  return nodes.map(node => {
+    if (node.type === 'text') return node.text;
+    if (node.type === 'var') return values[node.name] ?? '';
+    return values[node.name]?.trim() ? renderTemplate(node.body, values) : '';
+  }).join('');
+}
+
+/**
+ * Whether a template uses a variable anywhere
+ */
+function usesVariable(nodes: TemplateNode[], name: string): boolean {
+  return nodes.some(node => node.type !== 'text' && (node.name === name || (node.type === 'if' && usesVariable(node.body, name))));
+}
+
+export class PromptTemplates {
+  constructor(
+    private templates: Map<string, TemplateNode[]>,
+    private base: { repo: string; language?: string },
+    /** Hash of the template sources, so cached answers change with them */
+    readonly fingerprint: string = ''
+  ) {}
+
+  /** Template files that were loaded, e.g. `system.md`, `explain.md` */
+  files(): string[] {
+    return [...this.templates.keys()].sort();
+  }
+
+  get size(): number {
+    return this.templates.size;
+  }
+
+  private values(command?: string): Record<string, string | undefined> {
+    return {
+      repo: this.base.repo,
+      language: this.base.language,
+      command,
+      date: new Date().toISOString().slice(0, 10)
+    };
+  }
+
+  /**
+   * The prompt `command` sends: its template rendered around the built-in
+   * prompt, or the built-in prompt when there is no template
+   */
+  renderPrompt(command: PromptTemplateName, prompt: string, variables: PromptVariables = {}): string {
+    const template = this.templates.get(`${command}.md`);
+    if (!template) return prompt;
+    return renderTemplate(template, { ...this.values(command), ...variables, prompt }).trim();
+  }
+
+  /**
+   * System prompt for `command` (or a request no command template covers);
+   * undefined when there is none
+   */
+  renderSystem(command?: PromptTemplateName): string | undefined {
+    const global = this.templates.get('system.md');
+    const system = global ? renderTemplate(global, this.values(command)).trim() : undefined;
+    const own = command ? this.templates.get(`${command}.system.md`) : undefined;
+    const rendered = own ? renderTemplate(own, { ...this.values(command), system }).trim() : system;
+    return rendered || undefined;
+  }
+}
+
+/** No templates: every prompt is the built-in one */
+export const NO_PROMPT_TEMPLATES = new PromptTemplates(new Map(), { repo: '' });
+
+/**
+ * Template kind of a file in .cv/prompts/, or an error naming the files allowed
+ */
+function templateKind(file: string): keyof typeof PROMPT_VARIABLES {
+  if (file === 'system.md') return 'system';
+  const match = file.match(/^(.+?)(\.system)?\.md$/);
+  if (match && (PROMPT_TEMPLATE_NAMES as readonly string[]).includes(match[1])) {
+    return match[2] ? 'commandSystem' : 'command';
+  }
+  throw new PromptTemplateError(
+    `${PROMPTS_DIR}/${file}: not a prompt template; use system.md, ` +
+    `${PROMPT_TEMPLATE_NAMES.map(name => `${name}.md`).join(', ')} or <name>.system.md`
+  );
+}
+
+/**
+ * Main language of the repository, from the last sync
+ */
+async function primaryLanguage(repoRoot: string): Promise<string | undefined> {
+  try {
+    const state = JSON.parse(await fs.readFile(path.join(getCVDir(repoRoot), 'sync_state.json'), 'utf-8'));
+    const languages = Object.entries((state?.languages ?? {}) as Record<string, number>);
+    return languages.sort((a, b) => b[1] - a[1])[0]?.[0];
+  } catch {
+    return undefined;
+  }
+}
+
+/**
+ * Load and check `.cv/prompts/`; no directory means no templates
+ *
+ * @param repo - repository name for `{{repo}}` (default: the directory name)
+ */
+export async function loadPromptTemplates(repoRoot: string, repo?: string): Promise<PromptTemplates> {
+  const dir = path.join(getCVDir(repoRoot), PROMPTS_DIR);
+  let entries: string[];
+  try {
+    entries = (await fs.readdir(dir)).filter(file => file.endsWith('.md')).sort();
+  } catch (error: any) {
+    if (error.code === 'ENOENT') return NO_PROMPT_TEMPLATES;
+    throw new PromptTemplateError(`Cannot read ${dir}: ${error.message}`);
+  }
+  if (entries.length === 0) return NO_PROMPT_TEMPLATES;
+
+  const templates = new Map<string, TemplateNode[]>();
+  const hash = createHash('sha256');
+  for (const file of entries) {
+    const kind = templateKind(file);
+    const source = await fs.readFile(path.join(dir, file), 'utf-8');
+    const nodes = parseTemplate(source, `${PROMPTS_DIR}/${file}`, PROMPT_VARIABLES[kind]);
+    if (kind === 'command' && !usesVariable(nodes, 'prompt') && !usesVariable(nodes, 'input')) {
+      throw new PromptTemplateError(
+        `${PROMPTS_DIR}/${file}: uses neither {{prompt}} nor {{input}}, so the model would never see what to work on`
+      );
+    }
+    templates.set(file, nodes);
+    hash.update(`${file}\0${source}\0`);
+  }
+
+  return new PromptTemplates(
+    templates,
+    { repo: repo || path.basename(repoRoot), language: await primaryLanguage(repoRoot) },
+    hash.digest('hex').slice(0, 16)
+  );
+}
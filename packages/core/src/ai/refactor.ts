@@ -0,0 +1,226 @@
+/**
+ * Graph-driven Refactoring
+ *
+ * `cv refactor "extract the caching logic in ComputeEngine into a Cache trait"`
+ * looks up the symbols the instruction names, then follows the symbol graph
+ * to everything the change has to keep consistent: the members of a type,
+ * every caller, the types that implement or extend it, what it implements,
+ * and the tests that mention it. Those files go to the model whole, it
+ * answers with edit blocks for all of them, and the command verifies the
+ * resulting patch with the build before anyone is asked to approve it.
+ */
+
+import type { SymbolNode } from '@cv-git/shared';
+import type { GraphManager } from '../graph/index.js';
+import { findSymbols } from '../graph/export.js';
+import { isTestFile } from '../parser/language-packs.js';
+import { EDIT_BLOCK_INSTRUCTIONS } from '../code/edit-parser.js';
+import type { Patch } from '../code/patch-engine.js';
+
+/** Why a file is part of the refactoring, most direct first */
+export const REFACTOR_REASONS = ['definition', 'implementation', 'supertype', 'caller', 'test', 'callee'] as const;
+export type RefactorReason = typeof REFACTOR_REASONS[number];
+
+export interface RefactorScopeFile {
+  file: string;
+  reasons: RefactorReason[];
+  /** Names of the symbols that pulled the file in */
+  symbols: string[];
+}
+
+export interface RefactorScope {
+  /** Symbols the instruction names */
+  symbols: SymbolNode[];
+  /** Files to send, in priority order */
+  files: RefactorScopeFile[];
+  /** Files left out by maxFiles */
+  omitted: number;
+}
+
+export interface RefactorScopeOptions {
+  /** Tracked test files, searched for the symbols' names */
+  testFiles?: string[];
+  readFile?: (file: string) => Promise<string | null>;
+  /** Most files to include (default 15) */
+  maxFiles?: number;
+}
+
+/** Mentions looked up in the graph */
+const MAX_MENTIONS = 12;
+
+/** Symbols kept per mention */
+const MAX_MATCHES = 5;
+
+const TYPE_KINDS = new Set(['class', 'struct', 'interface', 'enum', 'type']);
+
+/**
+ * Names in an instruction that look like code: `backticked`, CamelCase,
+ * snake_case, `Type::method` or `Type.method`
+ */
+export function extractSymbolMentions(instruction: string): string[] {
+  const mentions = new Set<string>();
+  for (const match of instruction.matchAll(/`([^`\s]+)`/g)) {
+    mentions.add(match[1].replace(/\(\)$/, ''));
+  }
+  const prose = instruction.replace(/`[^`]*`/g, ' ');
+  for (const match of prose.matchAll(/[A-Za-z_][\w]*(?:(?:::|\.)[A-Za-z_]\w*)*/g)) {
+    const word = match[0];
+    const codeLike =
+      /[a-z0-9][A-Z]/.test(word) ||           // ComputeEngine, getUser
+      /^_?[a-z0-9]+_[a-z0-9_]+$/i.test(word) || // compute_heavy_task, MAX_SIZE
+      /::|\w\.\w/.test(word);                 // Engine::run, engine.run
+    if (codeLike && !/^(e\.g|i\.e)$/i.test(word)) mentions.add(word);
+  }
+  return [...mentions].slice(0, MAX_MENTIONS);
+}
+
+type ScopeGraph = Pick<GraphManager, 'query' | 'getFileSymbols' | 'getCallers' | 'getCallees' | 'getSubtypes' | 'getSupertypes'>;
+
+/**
+ * The symbols an instruction names and every file that has to change with them
+ */
+export async function collectRefactorScope(
+  graph: ScopeGraph,
+  instruction: string,
+  options: RefactorScopeOptions = {}
+): Promise<RefactorScope> {
+  const files = new Map<string, RefactorScopeFile>();
+  const add = (file: string | undefined, reason: RefactorReason, symbol: string) => {
+    if (!file) return;
+    const entry = files.get(file) ?? { file, reasons: [], symbols: [] };
+    if (!entry.reasons.includes(reason)) entry.reasons.push(reason);
+    if (!entry.symbols.includes(symbol)) entry.symbols.push(symbol);
+    files.set(file, entry);
+  };
+
+  const symbols: SymbolNode[] = [];
+  const seen = new Set<string>();
+  for (const mention of extractSymbolMentions(instruction)) {
+    for (const symbol of await findSymbols(graph, mention, MAX_MATCHES)) {
+      if (seen.has(symbol.qualifiedName)) continue;
+      seen.add(symbol.qualifiedName);
+      symbols.push(symbol);
+    }
+  }
+
+  for (const symbol of symbols) {
+    add(symbol.file, isTestFile(symbol.file) ? 'test' : 'definition', symbol.name);
+
+    // A type changes with its members, and with their callers
+    const members = TYPE_KINDS.has(symbol.kind)
+      ? (await graph.getFileSymbols(symbol.file)).filter(member => member.qualifiedName.startsWith(`${symbol.qualifiedName}.`))
+      : [];
+    for (const target of [symbol, ...members]) {
+      for (const caller of await graph.getCallers(target.qualifiedName)) {
+        add(caller.file, isTestFile(caller.file) ? 'test' : 'caller', target.name);
+      }
+      for (const callee of await graph.getCallees(target.qualifiedName)) {
+        add(callee.file, 'callee', target.name);
+      }
+    }
+
+    for (const { symbol: sub } of await graph.getSubtypes(symbol.qualifiedName)) add(sub.file, 'implementation', symbol.name);
+    for (const { symbol: base } of await graph.getSupertypes(symbol.qualifiedName)) add(base.file, 'supertype', symbol.name);
+  }
+
+  // Tests that use the names without a call edge (fixtures, assertions on types)
+  if (options.readFile && symbols.length > 0) {
+    const names = [...new Set(symbols.map(symbol => symbol.name))].filter(name => name.length > 2);
+    const pattern = names.length > 0 ? new RegExp(`\\b(${names.map(escapeRegExp).join('|')})\\b`) : null;
+    for (const file of options.testFiles ?? []) {
+      if (!pattern || files.has(file)) continue;
+      const text = await options.readFile(file);
+      const match = text?.match(pattern);
+      if (match) add(file, 'test', match[1]);
+    }
+  }
+
+  const rank = (entry: RefactorScopeFile) => Math.min(...entry.reasons.map(reason => REFACTOR_REASONS.indexOf(reason)));
+  const ordered = [...files.values()].sort((a, b) => rank(a) - rank(b) || a.file.localeCompare(b.file));
+  const maxFiles = Math.max(1, options.maxFiles ?? 15);
+  return { symbols, files: ordered.slice(0, maxFiles), omitted: Math.max(0, ordered.length - maxFiles) };
+}
+
+export interface RefactorFailure {
+  /** Verification command, or `patch` when the edits didn't apply */
+  command: string;
+  output: string;
+  /** Diff of the attempt that failed; empty when it didn't apply */
+  diff: string;
+}
+
+/** Verification output kept for the next prompt */
+const MAX_FAILURE_CHARS = 6000;
+
+export const REFACTOR_SYSTEM_PROMPT = `You are an expert software engineer performing a refactoring across a codebase.
+Change behavior only where the instruction asks; keep every call site, implementation and test consistent so the project still builds and its tests pass.
+Follow the existing code style. Touch only the files that need to change.`;
+
+/**
+ * Prompt with the instruction, the graph context and the files to edit
+ *
+ * @param failure - the previous attempt, when the build rejected it
+ */
+export function buildRefactorPrompt(
+  instruction: string,
+  scope: RefactorScope,
+  contents: Array<{ file: string; text: string }>,
+  failure?: RefactorFailure
+): string {
+  const parts: string[] = [`Refactoring: ${instruction}`, ''];
+
+  parts.push('## Symbols');
+  for (const symbol of scope.symbols) {
+    parts.push(`- ${symbol.qualifiedName} (${symbol.kind}, ${symbol.file}:${symbol.startLine}-${symbol.endLine})`);
+  }
+  parts.push('', '## Files and why they are included');
+  for (const entry of scope.files) {
+    parts.push(`- ${entry.file}: ${entry.reasons.join(', ')} of ${entry.symbols.join(', ')}`);
+  }
+  if (scope.omitted > 0) {
+    parts.push(`- ... ${scope.omitted} more file(s) with callers were left out; keep public signatures they rely on working`);
+  }
+
+  parts.push('', '## Code');
+  for (const { file, text } of contents) {
+    parts.push(`### ${file}`, '```', text, '```', '');
+  }
+
+  if (failure) {
+    const output = failure.output.length > MAX_FAILURE_CHARS ? `...\n${failure.output.slice(-MAX_FAILURE_CHARS)}` : failure.output;
+    parts.push('## Previous attempt');
+    parts.push('Your previous changes were checked with `' + failure.command + '`, which failed:');
+    parts.push('```', output.trim(), '```');
+    if (failure.diff.trim()) parts.push('The changes:', '```diff', failure.diff.trim(), '```');
+    parts.push('The code above is the original, unchanged. Write the complete refactoring again, fixing these errors.', '');
+  }
+
+  parts.push(EDIT_BLOCK_INSTRUCTIONS + 'Briefly explain the plan before the blocks.');
+  return parts.join('\n');
+}
+
+/**
+ * A patch that undoes `patch` once it has been applied
+ */
+export function reversePatch(patch: Patch): Patch {
+  return { files: patch.files.map(file => ({ file: file.file, before: file.after, after: file.before, diff: '' })) };
+}
+
+/**
+ * Build command for a repository with these files at its root
+ */
+export function detectBuildCommand(rootFiles: string[]): string | undefined {
+  const has = (name: string) => rootFiles.includes(name);
+  if (has('Cargo.toml')) return 'cargo build --all-targets';
+  if (has('go.mod')) return 'go build ./... && go vet ./...';
+  if (has('tsconfig.json')) return 'npx tsc --noEmit';
+  if (has('package.json')) return 'npm run build --if-present';
+  if (has('pom.xml')) return 'mvn -q compile';
+  if (has('build.gradle') || has('build.gradle.kts')) return './gradlew assemble';
+  if (has('pyproject.toml') || has('setup.py')) return 'python -m compileall -q .';
+  return undefined;
+}
+
+function escapeRegExp(value: string): string {
+  return value.replace(/[.*+?^${}()|[\]\\]/g, '\\$&');
+}
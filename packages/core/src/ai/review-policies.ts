@@ -0,0 +1,185 @@
+/**
+ * Review Policies
+ *
+ * House rules for `cv review`, kept in `.cv/review-policies.yaml`:
+ *
+ *   rules:
+ *     - id: no-unwrap-in-lib
+ *       name: No unwrap in library code
+ *       severity: high
+ *       files: ["src/**\/*.rs", "!src/bin/**"]
+ *       criteria: |
+ *         Library code must not call .unwrap() or .expect(); propagate errors instead.
+ *
+ * Each rule that applies to the changed files is given to the model with its
+ * criteria, and the review reports a pass or fail for every rule.
+ */
+
+import * as fs from 'fs/promises';
+import * as path from 'path';
+import { parse } from 'yaml';
+import { minimatch } from 'minimatch';
+import { getCVDir } from '@cv-git/shared';
+import { REVIEW_SEVERITIES, ReviewFinding, ReviewSeverity, findingId, sortFindings } from './review.js';
+
+export const REVIEW_POLICIES_FILE = 'review-policies.yaml';
+
+const RULE_ID = /^[a-z0-9][a-z0-9._-]*$/i;
+
+export interface ReviewPolicy {
+  id: string;
+  name: string;
+  /** What the rule requires, in plain language */
+  criteria: string;
+  /** Severity of violations (default: medium) */
+  severity: ReviewSeverity;
+  /** Globs of files the rule applies to; `!` excludes (default: all files) */
+  files?: string[];
+}
+
+export interface PolicyReport {
+  id: string;
+  name: string;
+  status: 'pass' | 'fail';
+  findings: number;
+}
+
+export class ReviewPolicyError extends Error {
+  constructor(message: string) {
+    super(message);
+    this.name = 'ReviewPolicyError';
+  }
+}
+
+/**
+ * Parse and validate a policy file
+ */
+export function parseReviewPolicies(content: string, source: string = REVIEW_POLICIES_FILE): ReviewPolicy[] {
+  let doc: any;
+  try {
+    doc = parse(content);
+  } catch (error: any) {
+    throw new ReviewPolicyError(`Invalid YAML in ${source}: ${error.message}`);
+  }
+  if (doc === null || doc === undefined) return [];
+
+  const rules = Array.isArray(doc) ? doc : doc.rules;
+  if (!Array.isArray(rules)) {
+    throw new ReviewPolicyError(`${source}: expected a "rules" list`);
+  }
+
+  const policies: ReviewPolicy[] = [];
+  const seen = new Set<string>();
+  rules.forEach((rule: any, index: number) => {
+    const where = `${source}: rule ${index + 1}`;
+    if (!rule || typeof rule !== 'object') {
+      throw new ReviewPolicyError(`${where} must be a mapping`);
+    }
+    const id = String(rule.id ?? '').trim();
+    if (!RULE_ID.test(id)) {
+      throw new ReviewPolicyError(`${where}: "id" must be letters, digits, '.', '_' or '-'`);
+    }
+    if (seen.has(id)) {
+      throw new ReviewPolicyError(`${where}: duplicate id "${id}"`);
+    }
+    seen.add(id);
+    const criteria = typeof rule.criteria === 'string' ? rule.criteria.trim() : '';
+    if (!criteria) {
+      throw new ReviewPolicyError(`${where} (${id}): "criteria" is required`);
+    }
+    const severity = rule.severity === undefined ? 'medium' : String(rule.severity).toLowerCase();
+    if (!(REVIEW_SEVERITIES as string[]).includes(severity)) {
+      throw new ReviewPolicyError(`${where} (${id}): severity must be one of ${REVIEW_SEVERITIES.join(', ')}`);
+    }
+    const files = rule.files === undefined
+      ? undefined
+      : (Array.isArray(rule.files) ? rule.files : [rule.files]).map(String);
+
+    policies.push({
+      id,
+      name: typeof rule.name === 'string' && rule.name.trim() ? rule.name.trim() : id,
+      criteria,
+      severity: severity as ReviewSeverity,
+      files
+    });
+  });
+
+  return policies;
+}
+
+/**
+ * Load `.cv/review-policies.yaml` (or the given file); no file means no policies
+ */
+export async function loadReviewPolicies(repoRoot: string, file?: string): Promise<ReviewPolicy[]> {
+  const filePath = file ? path.resolve(repoRoot, file) : path.join(getCVDir(repoRoot), REVIEW_POLICIES_FILE);
+  let content: string;
+  try {
+    content = await fs.readFile(filePath, 'utf-8');
+  } catch (error: any) {
+    if (error.code === 'ENOENT' && !file) return [];
+    throw new ReviewPolicyError(`Cannot read review policies ${filePath}: ${error.message}`);
+  }
+  return parseReviewPolicies(content, path.relative(repoRoot, filePath) || filePath);
+}
+
+/**
+ * Whether a rule applies to a file
+ */
+export function policyMatchesFile(policy: ReviewPolicy, file: string): boolean {
+  if (!policy.files || policy.files.length === 0) return true;
+  const options = (glob: string) => ({ dot: true, matchBase: !glob.includes('/') });
+  const include = policy.files.filter(g => !g.startsWith('!'));
+  const exclude = policy.files.filter(g => g.startsWith('!')).map(g => g.slice(1));
+  const included = include.length === 0 || include.some(g => minimatch(file, g, options(g)));
+  return included && !exclude.some(g => minimatch(file, g, options(g)));
+}
+
+/**
+ * Rules that apply to at least one of the changed files
+ */
+export function applicablePolicies(policies: ReviewPolicy[], files: string[]): ReviewPolicy[] {
+  return policies.filter(policy => files.some(file => policyMatchesFile(policy, file)));
+}
+
+/**
+ * Prompt section listing each rule with its criteria
+ */
+export function buildPolicyPrompt(policies: ReviewPolicy[]): string {
+  if (policies.length === 0) return '';
+  let prompt = `## House Rules\n`;
+  prompt += `Check the changes against each rule below. Report every violation as a finding with "rule" set to the rule id.\n\n`;
+  for (const policy of policies) {
+    prompt += `### ${policy.id}: ${policy.name}\n${policy.criteria}\n`;
+    if (policy.files && policy.files.length > 0) {
+      prompt += `Applies to: ${policy.files.join(', ')}\n`;
+    }
+    prompt += `\n`;
+  }
+  return prompt;
+}
+
+/**
+ * Give rule violations the rule's severity (and the ID that goes with it)
+ * and drop rule ids the model made up
+ */
+export function applyPolicies(findings: ReviewFinding[], policies: ReviewPolicy[]): ReviewFinding[] {
+  const byId = new Map(policies.map(policy => [policy.id, policy]));
+  return sortFindings(findings.map(finding => {
+    if (!finding.rule) return finding;
+    const policy = byId.get(finding.rule);
+    const updated = policy
+      ? { ...finding, severity: policy.severity }
+      : { ...finding, rule: undefined };
+    return { ...updated, id: findingId(updated) };
+  }));
+}
+
+/**
+ * Pass/fail for each rule that was checked
+ */
+export function policyReport(policies: ReviewPolicy[], findings: ReviewFinding[]): PolicyReport[] {
+  return policies.map(policy => {
+    const count = findings.filter(f => f.rule === policy.id).length;
+    return { id: policy.id, name: policy.name, status: count > 0 ? 'fail' : 'pass', findings: count };
+  });
+}
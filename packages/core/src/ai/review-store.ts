@@ -0,0 +1,106 @@
+/**
+ * Review Findings Store
+ *
+ * `cv review` records every finding it reports in .cv/review/findings.json,
+ * keyed by the stable finding ID, and `cv tui` triages them: an accepted
+ * finding is a real problem to fix, a dismissed one is a false positive or
+ * won't-fix. Dismissed findings are hidden from later reviews and don't count
+ * towards --fail-on, so the same false positive isn't reported on every run.
+ */
+
+import { promises as fs } from 'fs';
+import * as path from 'path';
+import { getCVDir } from '@cv-git/shared';
+import { ReviewFinding, sortFindings } from './review.js';
+
+export type ReviewFindingStatus = 'open' | 'accepted' | 'dismissed';
+
+export interface StoredFinding extends ReviewFinding {
+  status: ReviewFindingStatus;
+  /** Ref of the review that last reported it (HEAD, staged, a range) */
+  ref: string;
+  /** ISO timestamps */
+  firstSeen: string;
+  lastSeen: string;
+  /** When the status last changed */
+  triagedAt?: string;
+}
+
+export interface FindingsStore {
+  version: 1;
+  findings: StoredFinding[];
+}
+
+export function getFindingsStorePath(repoRoot: string): string {
+  return path.join(getCVDir(repoRoot), 'review', 'findings.json');
+}
+
+/**
+ * Load the findings store; empty if none is saved
+ */
+export async function loadFindingsStore(repoRoot: string): Promise<FindingsStore> {
+  try {
+    const state = JSON.parse(await fs.readFile(getFindingsStorePath(repoRoot), 'utf-8')) as FindingsStore;
+    if (state.version === 1 && Array.isArray(state.findings)) return state;
+  } catch {
+    // Missing or unreadable - start fresh
+  }
+  return { version: 1, findings: [] };
+}
+
+export async function saveFindingsStore(repoRoot: string, store: FindingsStore): Promise<void> {
+  const target = getFindingsStorePath(repoRoot);
+  await fs.mkdir(path.dirname(target), { recursive: true });
+  await fs.writeFile(`${target}.tmp`, JSON.stringify(store, null, 2), 'utf-8');
+  await fs.rename(`${target}.tmp`, target);
+}
+
+/**
+ * Merge a review's findings into the store. Known findings keep their status
+ * and first-seen time but take the latest location and wording.
+ */
+export function recordFindings(
+  store: FindingsStore,
+  findings: ReviewFinding[],
+  ref: string,
+  now: Date = new Date()
+): FindingsStore {
+  const byId = new Map(store.findings.map(finding => [finding.id, finding]));
+  for (const finding of findings) {
+    const known = byId.get(finding.id);
+    byId.set(finding.id, {
+      ...finding,
+      status: known?.status ?? 'open',
+      ref,
+      firstSeen: known?.firstSeen ?? now.toISOString(),
+      lastSeen: now.toISOString(),
+      triagedAt: known?.triagedAt
+    });
+  }
+  return { version: 1, findings: sortFindings([...byId.values()]) };
+}
+
+/**
+ * Set a finding's status; returns the store unchanged for an unknown ID
+ */
+export function setFindingStatus(
+  store: FindingsStore,
+  id: string,
+  status: ReviewFindingStatus,
+  now: Date = new Date()
+): FindingsStore {
+  return {
+    version: 1,
+    findings: store.findings.map(finding => finding.id === id
+      ? { ...finding, status, triagedAt: now.toISOString() }
+      : finding)
+  };
+}
+
+/**
+ * Findings from a review minus those dismissed in the store
+ */
+export function withoutDismissed(findings: ReviewFinding[], store: FindingsStore): ReviewFinding[] {
+  const dismissed = new Set(store.findings.filter(f => f.status === 'dismissed').map(f => f.id));
+  return findings.filter(finding => !dismissed.has(finding.id));
+}
@@ -0,0 +1,312 @@
+/**
+ * Code Review Findings
+ *
+ * Scopes a unified diff to the files under review, works out which lines
+ * (and so which symbols) it touches, and turns the model's JSON review into
+ * findings with a severity and a file/line location. Findings can be
+ * exported as SARIF for code-scanning UIs.
+ *
+ * Finding IDs hash the file, rule and normalized title but not the line, so
+ * the same problem keeps its ID across runs even as code around it moves.
+ */
+
+import { createHash } from 'crypto';
+import { minimatch } from 'minimatch';
+import type { SymbolNode } from '@cv-git/shared';
+
+export type ReviewSeverity = 'critical' | 'high' | 'medium' | 'low' | 'info';
+
+/** Most severe first */
+export const REVIEW_SEVERITIES: ReviewSeverity[] = ['critical', 'high', 'medium', 'low', 'info'];
+
+export interface ReviewFinding {
+  /** Stable across runs for the same problem (see findingId) */
+  id: string;
+  severity: ReviewSeverity;
+  /** Workspace-relative path */
+  file: string;
+  line: number;
+  endLine?: number;
+  title: string;
+  message: string;
+  suggestion?: string;
+  /** Rule the finding was reported against */
+  rule?: string;
+}
+
+export interface ReviewResult {
+  summary: string;
+  findings: ReviewFinding[];
+}
+
+export interface DiffSection {
+  /** Path after the change (the old path for deletions) */
+  file: string;
+  text: string;
+}
+
+/**
+ * Split a unified diff into per-file sections
+ */
+export function splitDiff(diff: string): DiffSection[] {
+  const sections: DiffSection[] = [];
+  let current: { header: string; lines: string[] } | null = null;
+
+  const flush = () => {
+    if (!current) return;
+    const match = current.header.match(/^diff --git a\/(.+) b\/(.+)$/);
+    const text = current.lines.join('\n');
+    let file = match ? match[2] : '';
+    if (/^\+\+\+ \/dev\/null$/m.test(text) && match) file = match[1];
+    sections.push({ file, text });
+  };
+
+  for (const line of diff.split('\n')) {
+    if (line.startsWith('diff --git ')) {
+      flush();
+      current = { header: line, lines: [line] };
+    } else if (current) {
+      current.lines.push(line);
+    }
+  }
+  flush();
+
+  return sections;
+}
+
+/**
+ * Keep only the files matching any of the globs (all files when none are given).
+ * Globs without a slash match the file name anywhere in the tree.
+ */
+export function filterDiff(diff: string, globs: string[] = []): string {
+  if (globs.length === 0) return diff;
+  const kept = splitDiff(diff).filter(section =>
+    globs.some(glob => minimatch(section.file, glob, { dot: true, matchBase: !glob.includes('/') }))
+  );
+  return kept.map(section => section.text.endsWith('\n') ? section.text : `${section.text}\n`).join('');
+}
+
+/**
+ * Line ranges added or changed on the new side of each file
+ */
+export function touchedLines(diff: string): Map<string, Array<[number, number]>> {
+  const touched = new Map<string, Array<[number, number]>>();
+
+  for (const section of splitDiff(diff)) {
+    const ranges: Array<[number, number]> = [];
+    let line = 0;
+    let start = -1;
+
+    const close = () => {
+      if (start !== -1) ranges.push([start, line - 1]);
+      start = -1;
+    };
+
+    for (const text of section.text.split('\n')) {
+      const hunk = text.match(/^@@ -\d+(?:,\d+)? \+(\d+)(?:,\d+)? @@/);
+      if (hunk) {
+        close();
+        line = parseInt(hunk[1], 10);
+      } else if (line === 0) {
+        // File header before the first hunk
+        continue;
+      } else if (text.startsWith('+')) {
+        if (start === -1) start = line;
+        line++;
+      } else if (text.startsWith('-')) {
+        // A removal touches the line now at its position
+        const last = ranges[ranges.length - 1];
+        if (start === -1 && (!last || last[1] < line)) ranges.push([line, line]);
+      } else if (!text.startsWith('\\')) {
+        close();
+        line++;
+      }
+    }
+    close();
+
+    if (ranges.length > 0) touched.set(section.file, ranges);
+  }
+
+  return touched;
+}
+
+/**
+ * Symbols whose definition overlaps a touched line range
+ */
+export function touchedSymbols(symbols: SymbolNode[], ranges: Array<[number, number]>): SymbolNode[] {
+  return symbols.filter(symbol =>
+    ranges.some(([start, end]) => symbol.startLine <= end && symbol.endLine >= start)
+  );
+}
+
+function normalizeSeverity(value: unknown): ReviewSeverity {
+  const severity = String(value ?? '').toLowerCase();
+  if ((REVIEW_SEVERITIES as string[]).includes(severity)) return severity as ReviewSeverity;
+  if (severity === 'error' || severity === 'major') return 'high';
+  if (severity === 'warning' || severity === 'minor') return 'medium';
+  if (severity === 'blocker') return 'critical';
+  return 'info';
+}
+
+/**
+ * Deterministic ID for a finding, so repeated runs deduplicate
+ */
+export function findingId(finding: Pick<ReviewFinding, 'file' | 'title' | 'rule'>): string {
+  const title = finding.title.toLowerCase().replace(/[^a-z0-9]+/g, ' ').trim();
+  return createHash('sha256')
+    .update(`${finding.file}\0${finding.rule ?? ''}\0${title}`)
+    .digest('hex')
+    .slice(0, 16);
+}
+
+function positiveInt(value: unknown): number | undefined {
+  const n = typeof value === 'number' ? value : parseInt(String(value), 10);
+  return Number.isFinite(n) && n > 0 ? Math.floor(n) : undefined;
+}
+
+/**
+ * Most severe first, then by location
+ */
+export function sortFindings<T extends ReviewFinding>(findings: T[]): T[] {
+  return [...findings].sort((a, b) =>
+    REVIEW_SEVERITIES.indexOf(a.severity) - REVIEW_SEVERITIES.indexOf(b.severity) ||
+    a.file.localeCompare(b.file) ||
+    a.line - b.line
+  );
+}
+
+/**
+ * Parse the model's JSON review. Replies that aren't JSON are kept as the
+ * summary with no findings, so a malformed response still reaches the user.
+ */
+export function parseReviewResult(response: string): ReviewResult {
+  const fenced = response.match(/```(?:json)?\s*\n([\s\S]*?)\n```/);
+  const candidate = fenced ? fenced[1] : response.match(/\{[\s\S]*\}/)?.[0];
+
+  let parsed: any;
+  try {
+    parsed = candidate ? JSON.parse(candidate) : undefined;
+  } catch {
+    parsed = undefined;
+  }
+  if (!parsed || typeof parsed !== 'object' || !Array.isArray(parsed.findings)) {
+    return { summary: response.trim(), findings: [] };
+  }
+
+  const findings = new Map<string, ReviewFinding>();
+  for (const raw of parsed.findings) {
+    if (!raw || typeof raw !== 'object' || !raw.file) continue;
+    const line = positiveInt(raw.line) ?? 1;
+    const endLine = positiveInt(raw.endLine);
+    const finding: Omit<ReviewFinding, 'id'> = {
+      severity: normalizeSeverity(raw.severity),
+      file: String(raw.file).replace(/^[ab]\//, ''),
+      line,
+      endLine: endLine && endLine > line ? endLine : undefined,
+      title: String(raw.title || raw.message || 'Finding').trim(),
+      message: String(raw.message || raw.title || '').trim(),
+      suggestion: raw.suggestion ? String(raw.suggestion).trim() : undefined,
+      rule: raw.rule ? String(raw.rule).trim() : undefined
+    };
+    const id = findingId(finding);
+    // The model sometimes repeats a finding; keep the first
+    if (!findings.has(id)) findings.set(id, { id, ...finding });
+  }
+
+  return {
+    summary: typeof parsed.summary === 'string' ? parsed.summary.trim() : '',
+    findings: sortFindings([...findings.values()])
+  };
+}
+
+/**
+ * Whether any finding is at or above the threshold severity
+ */
+export function hasFindingsAtOrAbove(findings: ReviewFinding[], threshold: ReviewSeverity): boolean {
+  const limit = REVIEW_SEVERITIES.indexOf(threshold);
+  return findings.some(f => REVIEW_SEVERITIES.indexOf(f.severity) <= limit);
+}
+
+const SARIF_LEVEL: Record<ReviewSeverity, 'error' | 'warning' | 'note'> = {
+  critical: 'error',
+  high: 'error',
+  medium: 'warning',
+  low: 'note',
+  info: 'note'
+};
+
+/** Rule for findings not reported against a named rule */
+export const DEFAULT_REVIEW_RULE = 'cv-review';
+
+export interface SarifOptions {
+  toolVersion?: string;
+  /** Descriptions for named rules, shown in the code-scanning UI */
+  rules?: Array<{
+    id: string;
+    description: string;
+    /** e.g. `security`, `external/cwe/cwe-89` */
+    tags?: string[];
+    /** GitHub code scanning's 0-10 score, e.g. `8.0` */
+    securitySeverity?: string;
+  }>;
+}
+
+/**
+ * SARIF 2.1.0 log for the findings, with paths relative to the checkout root
+ */
+export function toSarif(findings: ReviewFinding[], options: SarifOptions = {}): object {
+  const descriptions = new Map((options.rules ?? []).map(rule => [rule.id, rule.description]));
+  const properties = new Map((options.rules ?? [])
+    .filter(rule => rule.tags || rule.securitySeverity)
+    .map(rule => [rule.id, {
+      ...(rule.tags ? { tags: rule.tags } : {}),
+      ...(rule.securitySeverity ? { 'security-severity': rule.securitySeverity } : {})
+    }]));
+  const ruleIds = [...new Set([DEFAULT_REVIEW_RULE, ...descriptions.keys(), ...findings.map(f => f.rule ?? DEFAULT_REVIEW_RULE)])];
+
+  return {
+    $schema: 'https://json.schemastore.org/sarif-2.1.0.json',
+    version: '2.1.0',
+    runs: [{
+      tool: {
+        driver: {
+          name: 'cv-git',
+          informationUri: 'https://github.com/controlVector/cv-git',
+          ...(options.toolVersion ? { version: options.toolVersion } : {}),
+          rules: ruleIds.map(id => ({
+            id,
+            shortDescription: { text: descriptions.get(id) ?? (id === DEFAULT_REVIEW_RULE ? 'AI code review finding' : id) },
+            ...(properties.has(id) ? { properties: properties.get(id) } : {})
+          }))
+        }
+      },
+      results: findings.map(finding => ({
+        ruleId: finding.rule ?? DEFAULT_REVIEW_RULE,
+        ruleIndex: ruleIds.indexOf(finding.rule ?? DEFAULT_REVIEW_RULE),
+        level: SARIF_LEVEL[finding.severity],
+        message: {
+          text: finding.message && finding.message !== finding.title
+            ? `${finding.title}\n\n${finding.message}${finding.suggestion ? `\n\nSuggestion: ${finding.suggestion}` : ''}`
+            : finding.title
+        },
+        locations: [{
+          physicalLocation: {
+            artifactLocation: { uri: finding.file.replace(/\\/g, '/'), uriBaseId: '%SRCROOT%' },
+            region: { startLine: finding.line, ...(finding.endLine ? { endLine: finding.endLine } : {}) }
+          }
+        }],
+        partialFingerprints: { 'cvFindingHash/v1': finding.id },
+        properties: { severity: finding.severity }
+      }))
+    }]
+  };
+}
+
+/**
+ * Findings by severity, most severe first, omitting empty groups
+ */
+export function groupFindings(findings: ReviewFinding[]): Array<{ severity: ReviewSeverity; findings: ReviewFinding[] }> {
+  return REVIEW_SEVERITIES
+    .map(severity => ({ severity, findings: findings.filter(f => f.severity === severity) }))
+    .filter(group => group.findings.length > 0);
+}
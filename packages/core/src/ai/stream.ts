@@ -0,0 +1,94 @@
+/**
+ * Streaming Response Readers
+ *
+ * Providers stream completions either as Server-Sent Events (OpenAI-compatible
+ * APIs such as LM Studio) or as newline-delimited JSON (Ollama). Network
+ * chunks do not line up with events: a chunk can end in the middle of a line,
+ * or in the middle of a multi-byte character. These readers buffer until a
+ * full event is available.
+ */
+
+/**
+ * Decoded text of a response body, chunk by chunk
+ */
+async function* readText(body: ReadableStream<Uint8Array>): AsyncGenerator<string> {
+  const reader = body.getReader();
+  const decoder = new TextDecoder();
+  try {
+    while (true) {
+      const { done, value } = await reader.read();
+      if (done) break;
+      const text = decoder.decode(value, { stream: true });
+      if (text) yield text;
+    }
+    const rest = decoder.decode();
+    if (rest) yield rest;
+  } finally {
+    reader.releaseLock();
+  }
+}
+
+/**
+ * Complete lines of a response body, without line terminators
+ */
+async function* readLines(body: ReadableStream<Uint8Array>): AsyncGenerator<string> {
+  let buffer = '';
+  for await (const text of readText(body)) {
+    buffer += text;
+    // Hold back a trailing '\r': it may be the first half of '\r\n'
+    const held = buffer.endsWith('\r') ? '\r' : '';
+    const lines = (held ? buffer.slice(0, -1) : buffer).split(/\r\n|\r|\n/);
+    buffer = lines.pop()! + held;
+    yield* lines;
+  }
+  if (buffer) yield buffer.replace(/\r$/, '');
+}
+
+/**
+ * Data payloads of a Server-Sent Events stream, ending at `[DONE]`
+ *
+ * Comments and fields other than `data` are ignored; an event with several
+ * `data:` lines yields them joined with newlines.
+ */
+export async function* readSSE(body: ReadableStream<Uint8Array>): AsyncGenerator<string> {
+  let data: string[] = [];
+
+  for await (const line of readLines(body)) {
+    if (line === '') {
+      if (data.length > 0) {
+        const payload = data.join('\n');
+        data = [];
+        if (payload === '[DONE]') return;
+        yield payload;
+      }
+      continue;
+    }
+    if (line.startsWith(':')) continue;
+
+    const colon = line.indexOf(':');
+    const field = colon === -1 ? line : line.slice(0, colon);
+    let value = colon === -1 ? '' : line.slice(colon + 1);
+    if (value.startsWith(' ')) value = value.slice(1);
+    if (field === 'data') data.push(value);
+  }
+
+  // Stream closed without a blank line after the last event
+  if (data.length > 0) {
+    const payload = data.join('\n');
+    if (payload !== '[DONE]') yield payload;
+  }
+}
+
+/**
+ * Parsed objects of a newline-delimited JSON stream; unparseable lines are skipped
+ */
+export async function* readNDJSON<T = any>(body: ReadableStream<Uint8Array>): AsyncGenerator<T> {
+  for await (const line of readLines(body)) {
+    if (!line.trim()) continue;
+    try {
+      yield JSON.parse(line) as T;
+    } catch {
+      // Skip non-JSON lines
+    }
+  }
+}
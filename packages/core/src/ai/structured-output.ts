@@ -0,0 +1,377 @@
+/**
+ * Structured Output
+ *
+ * Answers as JSON matching a user-supplied JSON Schema (`cv ask --schema`).
+ * Providers with tool calling (Anthropic, OpenAI-compatible, Gemini) get the
+ * schema as the parameters of an `answer` tool, so their structured-output
+ * support shapes the reply; other models are asked for JSON in the prompt.
+ * Either way the reply is checked against the schema here, and a reply that
+ * doesn't match is sent back with the errors for another attempt.
+ *
+ * The validator covers the parts of JSON Schema that describe data: type,
+ * enum, const, properties, required, additionalProperties, items, lengths,
+ * pattern, minimum/maximum, anyOf/oneOf/allOf and local `$ref`s.
+ */
+
+import * as fs from 'fs/promises';
+import { AIClient, AIMessage, AITool, AIToolMessage } from './types.js';
+
+export type JsonSchema = Record<string, any>;
+
+/** Default number of replies requested before giving up */
+export const DEFAULT_STRUCTURED_ATTEMPTS = 3;
+
+const ANSWER_TOOL = 'answer';
+
+const SCHEMA_TYPES = new Set(['object', 'array', 'string', 'number', 'integer', 'boolean', 'null']);
+
+/** Keywords that only annotate or name the schema; tool parameters leave them out */
+const META_KEYWORDS = new Set(['$schema', '$id', '$defs', 'definitions', '$comment']);
+
+/** Keywords Gemini's function declarations reject */
+const GEMINI_UNSUPPORTED = new Set(['additionalProperties', 'const', 'pattern', 'minLength', 'maxLength', 'examples', 'default']);
+
+/** Nesting of `$ref`s inlined into tool parameters before the rest is left open */
+const MAX_REF_DEPTH = 8;
+
+export class SchemaError extends Error {
+  constructor(message: string) {
+    super(message);
+    this.name = 'SchemaError';
+  }
+}
+
+export class StructuredOutputError extends Error {
+  constructor(
+    message: string,
+    /** Schema errors of the last reply */
+    readonly errors: string[],
+    /** Last reply, parsed if it was JSON */
+    readonly lastReply: unknown
+  ) {
+    super(message);
+    this.name = 'StructuredOutputError';
+  }
+}
+
+export interface StructuredResult {
+  value: unknown;
+  /** Replies it took, 1 when the first one matched */
+  attempts: number;
+  /** Whether the reply came through tool calling or JSON in the text */
+  mode: 'tool' | 'json';
+}
+
+export interface StructuredOptions {
+  systemPrompt?: string;
+  /** Replies to request before giving up (default: 3) */
+  maxAttempts?: number;
+  /** Called with the errors of a reply before asking again */
+  onRetry?: (attempt: number, errors: string[]) => void;
+}
+
+/**
+ * Read and check a JSON Schema file
+ */
+export async function loadJsonSchema(file: string): Promise<JsonSchema> {
+  let source: string;
+  try {
+    source = await fs.readFile(file, 'utf-8');
+  } catch (error: any) {
+    throw new SchemaError(`Cannot read schema ${file}: ${error.message}`);
+  }
+  let schema: unknown;
+  try {
+    schema = JSON.parse(source);
+  } catch (error: any) {
+    throw new SchemaError(`${file} is not valid JSON: ${error.message}`);
+  }
+  const problems = checkJsonSchema(schema);
+  if (problems.length > 0) {
+    throw new SchemaError(`${file} is not a usable JSON Schema: ${problems.join('; ')}`);
+  }
+  return schema as JsonSchema;
+}
+
+/**
+ * Problems with a schema itself: unknown types and `$ref`s that don't resolve
+ */
+export function checkJsonSchema(schema: unknown): string[] {
+  if (!isObject(schema)) return ['the schema must be a JSON object'];
+  const problems: string[] = [];
+
+  const walk = (node: unknown, at: string) => {
+    if (Array.isArray(node)) {
+      node.forEach((item, index) => walk(item, `${at}/${index}`));
+      return;
+    }
+    if (!isObject(node)) return;
+    if (typeof node.type === 'string' || Array.isArray(node.type)) {
+      for (const type of [node.type].flat()) {
+        if (!SCHEMA_TYPES.has(type)) problems.push(`${at}: unknown type "${type}"`);
+      }
+    }
+    if (typeof node.$ref === 'string') {
+      try {
+        resolveRef(schema, node.$ref);
+      } catch (error: any) {
+        problems.push(`${at}: ${error.message}`);
+      }
+    }
+    if (typeof node.pattern === 'string') {
+      try {
+        new RegExp(node.pattern, 'u');
+      } catch {
+        problems.push(`${at}: invalid pattern ${node.pattern}`);
+      }
+    }
+    for (const [key, value] of Object.entries(node)) {
+      if (key !== 'enum' && key !== 'const' && key !== 'default' && key !== 'examples') walk(value, `${at}/${key}`);
+    }
+  };
+  walk(schema, '#');
+  return problems;
+}
+
+/**
+ * Where a value doesn't match the schema, as `$.path: problem`; empty when it matches
+ */
+export function validateJsonSchema(value: unknown, schema: JsonSchema | boolean, root: JsonSchema | boolean = schema, at = '$'): string[] {
+  if (typeof schema === 'boolean') return schema ? [] : [`${at}: no value is allowed here`];
+  if (typeof schema.$ref === 'string') {
+    return validateJsonSchema(value, resolveRef(root as JsonSchema, schema.$ref), root, at);
+  }
+
+  const errors: string[] = [];
+  const types = [schema.type ?? []].flat() as string[];
+  if (types.length > 0 && !types.some(type => hasType(value, type))) {
+    return [`${at}: expected ${types.join(' or ')}, got ${typeName(value)}`];
+  }
+  if (schema.enum && !schema.enum.some((option: unknown) => deepEqual(option, value))) {
+    errors.push(`${at}: expected one of ${schema.enum.map((option: unknown) => JSON.stringify(option)).join(', ')}`);
+  }
+  if ('const' in schema && !deepEqual(schema.const, value)) {
+    errors.push(`${at}: expected ${JSON.stringify(schema.const)}`);
+  }
+
+  if (typeof value === 'string') {
+    const length = [...value].length;
+    if (schema.minLength !== undefined && length < schema.minLength) errors.push(`${at}: shorter than ${schema.minLength} characters`);
+    if (schema.maxLength !== undefined && length > schema.maxLength) errors.push(`${at}: longer than ${schema.maxLength} characters`);
+    if (schema.pattern && !new RegExp(schema.pattern, 'u').test(value)) errors.push(`${at}: does not match ${schema.pattern}`);
+  }
+
+  if (typeof value === 'number') {
+    if (schema.minimum !== undefined && value < schema.minimum) errors.push(`${at}: less than ${schema.minimum}`);
+    if (schema.maximum !== undefined && value > schema.maximum) errors.push(`${at}: greater than ${schema.maximum}`);
+    if (typeof schema.exclusiveMinimum === 'number' && value <= schema.exclusiveMinimum) errors.push(`${at}: not greater than ${schema.exclusiveMinimum}`);
+    if (typeof schema.exclusiveMaximum === 'number' && value >= schema.exclusiveMaximum) errors.push(`${at}: not less than ${schema.exclusiveMaximum}`);
+  }
+
+  if (Array.isArray(value)) {
+    if (schema.minItems !== undefined && value.length < schema.minItems) errors.push(`${at}: fewer than ${schema.minItems} items`);
+    if (schema.maxItems !== undefined && value.length > schema.maxItems) errors.push(`${at}: more than ${schema.maxItems} items`);
+    if (schema.uniqueItems && value.some((item, i) => value.findIndex(other => deepEqual(item, other)) !== i)) {
+      errors.push(`${at}: items are not unique`);
+    }
+    if (schema.items !== undefined && !Array.isArray(schema.items)) {
+      value.forEach((item, i) => errors.push(...validateJsonSchema(item, schema.items, root, `${at}[${i}]`)));
+    }
+  }
+
+  if (isObject(value)) {
+    for (const key of schema.required ?? []) {
+      if (!(key in value)) errors.push(`${at}: missing required property "${key}"`);
+    }
+    const properties: Record<string, JsonSchema> = schema.properties ?? {};
+    for (const [key, item] of Object.entries(value)) {
+      const path = /^[A-Za-z_$][\w$]*$/.test(key) ? `${at}.${key}` : `${at}[${JSON.stringify(key)}]`;
+      if (key in properties) {
+        errors.push(...validateJsonSchema(item, properties[key], root, path));
+      } else if (schema.additionalProperties === false) {
+        errors.push(`${at}: unexpected property "${key}"`);
+      } else if (isObject(schema.additionalProperties)) {
+        errors.push(...validateJsonSchema(item, schema.additionalProperties, root, path));
+      }
+    }
+  }
+
+  for (const part of schema.allOf ?? []) {
+    errors.push(...validateJsonSchema(value, part, root, at));
+  }
+  if (schema.anyOf && !schema.anyOf.some((part: JsonSchema) => validateJsonSchema(value, part, root, at).length === 0)) {
+    errors.push(`${at}: matches none of the anyOf schemas`);
+  }
+  if (schema.oneOf) {
+    const matching = schema.oneOf.filter((part: JsonSchema) => validateJsonSchema(value, part, root, at).length === 0).length;
+    if (matching !== 1) errors.push(`${at}: matches ${matching} of the oneOf schemas, expected exactly 1`);
+  }
+  return errors;
+}
+
+/**
+ * Ask `client` for a reply matching `schema`, sending schema errors back
+ * until it matches or the attempts run out
+ */
+export async function generateStructured(
+  client: AIClient,
+  prompt: string,
+  schema: JsonSchema,
+  options: StructuredOptions = {}
+): Promise<StructuredResult> {
+  const maxAttempts = Math.max(1, options.maxAttempts ?? DEFAULT_STRUCTURED_ATTEMPTS);
+  // Tool parameters must be an object; anything else is wrapped in one
+  const wrapped = schema.type !== 'object' && !(schema.type === undefined && schema.properties);
+  const tool: AITool = {
+    name: ANSWER_TOOL,
+    description: 'Give the answer. Call this exactly once with the complete answer.',
+    parameters: toolParameters(
+      wrapped ? { type: 'object', properties: { answer: schema }, required: ['answer'] } : { type: 'object', ...schema },
+      schema,
+      client.getProvider()
+    ),
+  };
+  const useTools = typeof client.chatWithTools === 'function';
+
+  const messages: AIToolMessage[] = [{
+    role: 'user',
+    content: useTools
+      ? `${prompt}\n\nAnswer by calling the ${ANSWER_TOOL} tool.`
+      : `${prompt}\n\nRespond with ONLY a JSON value, no other text, matching this JSON Schema:\n\`\`\`json\n${JSON.stringify(schema, null, 2)}\n\`\`\``,
+  }];
+
+  let errors: string[] = [];
+  let lastReply: unknown;
+  for (let attempt = 1; attempt <= maxAttempts; attempt++) {
+    let feedback: (text: string) => AIToolMessage;
+    let mode: StructuredResult['mode'] = 'json';
+
+    if (useTools) {
+      const result = await client.chatWithTools!(messages, [tool], options.systemPrompt);
+      const call = result.toolCalls.find(candidate => candidate.name === ANSWER_TOOL);
+      if (call) {
+        mode = 'tool';
+        lastReply = wrapped ? call.arguments.answer : call.arguments;
+        messages.push({ role: 'assistant', content: result.text, toolCalls: [call] });
+        feedback = content => ({ role: 'tool', toolCallId: call.id, name: ANSWER_TOOL, content });
+      } else {
+        // Answered in text instead of calling the tool
+        lastReply = parseJsonReply(result.text);
+        messages.push({ role: 'assistant', content: result.text });
+        feedback = content => ({ role: 'user', content });
+      }
+    } else {
+      const text = await client.chat(messages as AIMessage[], options.systemPrompt);
+      lastReply = parseJsonReply(text);
+      messages.push({ role: 'assistant', content: text });
+      feedback = content => ({ role: 'user', content });
+    }
+
+    errors = lastReply !== undefined ? validateJsonSchema(lastReply, schema)
+      : mode === 'tool' ? [`$: the ${ANSWER_TOOL} tool was called without an answer`]
+      : ['$: the reply is not JSON'];
+    if (errors.length === 0) return { value: lastReply, attempts: attempt, mode };
+    if (attempt === maxAttempts) break;
+
+    options.onRetry?.(attempt, errors);
+    messages.push(feedback(
+      `The answer does not match the schema:\n${errors.slice(0, 20).map(error => `- ${error}`).join('\n')}\n\n` +
+      (useTools ? `Call the ${ANSWER_TOOL} tool again with a corrected, complete answer.` : 'Reply again with only the corrected JSON.')
+    ));
+  }
+
+  throw new StructuredOutputError(
+    `The answer did not match the schema after ${maxAttempts} attempt${maxAttempts === 1 ? '' : 's'}: ${errors.slice(0, 3).join('; ')}`,
+    errors,
+    lastReply
+  );
+}
+
+/**
+ * JSON in a reply: the whole text, a fenced block, or the outermost object or array
+ */
+export function parseJsonReply(text: string): unknown {
+  const fenced = text.match(/```(?:json)?\s*\n([\s\S]*?)\n```/)?.[1];
+  const candidates = [text.trim(), fenced, text.match(/[{[][\s\S]*[}\]]/)?.[0]];
+  for (const candidate of candidates) {
+    if (!candidate) continue;
+    try {
+      return JSON.parse(candidate);
+    } catch {
+      // Try the next candidate
+    }
+  }
+  return undefined;
+}
+
+/**
+ * Schema as tool parameters: `$ref`s inlined and meta keywords dropped, plus
+ * the keywords Gemini rejects for Gemini. The reply is still validated
+ * against the full schema.
+ */
+function toolParameters(node: unknown, root: JsonSchema, provider: string, depth = 0): any {
+  if (Array.isArray(node)) return node.map(item => toolParameters(item, root, provider, depth));
+  if (!isObject(node)) return node;
+  if (typeof node.$ref === 'string') {
+    return depth >= MAX_REF_DEPTH ? {} : toolParameters(resolveRef(root, node.$ref), root, provider, depth + 1);
+  }
+  const result: Record<string, unknown> = {};
+  for (const [key, value] of Object.entries(node)) {
+    if (META_KEYWORDS.has(key) || (provider === 'gemini' && GEMINI_UNSUPPORTED.has(key))) continue;
+    // Property names and enum values are data, not schemas
+    result[key] = key === 'enum' || key === 'const' ? value
+      : key === 'properties' && isObject(value)
+        ? Object.fromEntries(Object.entries(value).map(([name, item]) => [name, toolParameters(item, root, provider, depth)]))
+        : toolParameters(value, root, provider, depth);
+  }
+  return result;
+}
+
+/**
+ * Schema a local `$ref` (`#/$defs/Endpoint`) points at
+ */
+function resolveRef(root: JsonSchema, ref: string): JsonSchema {
+  if (ref !== '#' && !ref.startsWith('#/')) {
+    throw new SchemaError(`only local $refs are supported, not "${ref}"`);
+  }
+  let node: any = root;
+  for (const part of ref.slice(2).split('/').filter(Boolean)) {
+    node = node?.[part.replace(/~1/g, '/').replace(/~0/g, '~')];
+  }
+  if (!isObject(node) && typeof node !== 'boolean') {
+    throw new SchemaError(`$ref "${ref}" does not resolve`);
+  }
+  return node as JsonSchema;
+}
+
+function isObject(value: unknown): value is Record<string, any> {
+  return typeof value === 'object' && value !== null && !Array.isArray(value);
+}
+
+function hasType(value: unknown, type: string): boolean {
+  if (type === 'integer') return Number.isInteger(value);
+  if (type === 'number') return typeof value === 'number' && Number.isFinite(value);
+  if (type === 'array') return Array.isArray(value);
+  if (type === 'object') return isObject(value);
+  if (type === 'null') return value === null;
+  return typeof value === type;
+}
+
+function typeName(value: unknown): string {
+  if (value === null) return 'null';
+  if (Array.isArray(value)) return 'array';
+  if (Number.isInteger(value)) return 'integer';
+  return typeof value;
+}
+
+function deepEqual(a: unknown, b: unknown): boolean {
+  if (a === b) return true;
+  if (Array.isArray(a) && Array.isArray(b)) {
+    return a.length === b.length && a.every((item, i) => deepEqual(item, b[i]));
+  }
+  if (isObject(a) && isObject(b)) {
+    const keys = Object.keys(a);
+    return keys.length === Object.keys(b).length && keys.every(key => key in b && deepEqual(a[key], b[key]));
+  }
+  return false;
+}
@@ -0,0 +1,327 @@
+/**
+ * Test Generation
+ *
+ * `cv test-gen src/compute/engine.rs::multiply_matrices` writes unit tests
+ * for a function (or a whole file) the way the project already writes them:
+ * where its tests live (a `#[cfg(test)]` module, `tests/`, `foo.test.ts` next
+ * to the source, `foo_test.go`) comes from the existing test files, and a
+ * couple of them are shown to the model as examples. The tests are then run
+ * with the project's test command and regenerated with the failure output
+ * until they pass or the attempts run out.
+ *
+ * Everything here is pure or takes callbacks; the command supplies the
+ * model, the file system and the sandboxed test run.
+ */
+
+import * as path from 'path';
+import { restoreRedactedOrThrow } from '../security/redact.js';
+
+export interface TestTarget {
+  file: string;
+  /** Function or method; the whole file when absent */
+  symbol?: string;
+}
+
+/**
+ * `path/to/file.rs::name` or `path/to/file.rs`. Anything after the first
+ * `::` is the symbol, so `engine.rs::Matrix::multiply` targets
+ * `Matrix::multiply`.
+ */
+export function parseTestTarget(target: string): TestTarget {
+  const at = target.indexOf('::');
+  if (at < 0) return { file: target.replace(/^\.\//, '') };
+  const symbol = target.slice(at + 2).trim();
+  return { file: target.slice(0, at).replace(/^\.\//, ''), symbol: symbol || undefined };
+}
+
+export interface TestLayout {
+  /** `inline`: appended to the source file; `file`: a separate test file */
+  placement: 'inline' | 'file';
+  /** Workspace-relative file the tests are written to */
+  testFile: string;
+  /** The convention, for the prompt */
+  convention: string;
+}
+
+/** Directory holding the most test files among `files`, or undefined */
+function commonTestDir(files: string[]): string | undefined {
+  const counts = new Map<string, number>();
+  for (const file of files) {
+    const dir = path.posix.dirname(file);
+    counts.set(dir, (counts.get(dir) ?? 0) + 1);
+  }
+  return [...counts.entries()].sort((a, b) => b[1] - a[1] || a[0].localeCompare(b[0]))[0]?.[0];
+}
+
+/**
+ * Where tests for `file` go, following the tests already in the repository
+ *
+ * @param testFiles - tracked test files (see isTestFile in parser/language-packs.ts)
+ * @param source - the file's current content
+ */
+export function planTestLayout(file: string, testFiles: string[], source: string): TestLayout {
+  const ext = path.posix.extname(file);
+  const dir = path.posix.dirname(file);
+  const stem = path.posix.basename(file, ext);
+  const sameKind = testFiles.filter(test => path.posix.extname(test) === ext);
+
+  switch (ext) {
+    case '.rs': {
+      const integration = sameKind.filter(test => /(^|\/)tests\/[^/]+\.rs$/.test(test));
+      if (source.includes('#[cfg(test)]') || integration.length === 0) {
+        return {
+          placement: 'inline',
+          testFile: file,
+          convention: 'a #[cfg(test)] module at the end of the source file, with `use super::*;`',
+        };
+      }
+      const crateRoot = integration[0].replace(/tests\/[^/]+\.rs$/, '');
+      return {
+        placement: 'file',
+        testFile: `${crateRoot}tests/${stem}.rs`,
+        convention: 'an integration test file under tests/ using the crate\'s public API',
+      };
+    }
+
+    case '.go':
+      return {
+        placement: 'file',
+        testFile: path.posix.join(dir, `${stem}_test.go`),
+        convention: 'a _test.go file in the same package, with table-driven tests where it fits',
+      };
+
+    case '.py': {
+      const testsDir = commonTestDir(sameKind.filter(test => /(^|\/)tests?\//.test(test)));
+      return {
+        placement: 'file',
+        testFile: path.posix.join(testsDir ?? dir, `test_${stem}.py`),
+        convention: `a test_*.py module${testsDir ? ` in ${testsDir}/` : ' next to the source'} for pytest`,
+      };
+    }
+
+    case '.java':
+    case '.kt': {
+      const name = `${stem.charAt(0).toUpperCase()}${stem.slice(1)}Test${ext}`;
+      const mirrored = dir.replace(/(^|\/)src\/main\//, '$1src/test/');
+      return {
+        placement: 'file',
+        testFile: path.posix.join(mirrored !== dir ? mirrored : dir, name),
+        convention: `a ${name} class${mirrored !== dir ? ' in the mirrored src/test/ package' : ''}`,
+      };
+    }
+
+    default: {
+      // JavaScript and TypeScript: colocated or in a tests directory, .test or .spec
+      const jsTests = testFiles.filter(test => /\.(test|spec)\.[cm]?[jt]sx?$/.test(test));
+      const suffix = jsTests.filter(test => /\.spec\./.test(test)).length > jsTests.length / 2 ? 'spec' : 'test';
+      const colocated = jsTests.filter(test => !/(^|\/)(tests?|__tests__)\//.test(test));
+      const testsDir = commonTestDir(jsTests.filter(test => /(^|\/)(tests?|__tests__)\//.test(test)));
+      const separate = testsDir !== undefined && colocated.length < jsTests.length / 2;
+      const name = `${stem}.${suffix}${ext || '.ts'}`;
+      return {
+        placement: 'file',
+        testFile: separate ? path.posix.join(testsDir!, name) : path.posix.join(dir, name),
+        convention: separate
+          ? `a ${name} file in ${testsDir}/, importing the code under test by relative path`
+          : `a ${name} file next to the source`,
+      };
+    }
+  }
+}
+
+/**
+ * Existing test files to show as examples: same extension, nearest first
+ */
+export function pickTestExamples(file: string, testFiles: string[], limit = 2): string[] {
+  const ext = path.posix.extname(file);
+  const parts = file.split('/');
+  const shared = (other: string) => {
+    const otherParts = other.split('/');
+    let i = 0;
+    while (i < parts.length && parts[i] === otherParts[i]) i++;
+    return i;
+  };
+  return testFiles
+    .filter(test => path.posix.extname(test) === ext && test !== file)
+    .sort((a, b) => shared(b) - shared(a) || a.localeCompare(b))
+    .slice(0, limit);
+}
+
+/**
+ * Test command for a repository with these files at its root
+ */
+export function detectTestCommand(rootFiles: string[]): string | undefined {
+  const has = (name: string) => rootFiles.includes(name);
+  if (has('Cargo.toml')) return 'cargo test';
+  if (has('go.mod')) return 'go test ./...';
+  if (has('package.json')) return 'npm test';
+  if (has('pyproject.toml') || has('pytest.ini') || has('setup.py') || has('tox.ini')) return 'pytest';
+  if (has('pom.xml')) return 'mvn -q test';
+  if (has('build.gradle') || has('build.gradle.kts')) return './gradlew test';
+  return undefined;
+}
+
+/**
+ * The test command with `{file}` replaced by the test file
+ */
+export function testCommandFor(command: string, testFile: string): string {
+  return command.replace(/\{file\}/g, testFile);
+}
+
+export interface TestGenContext {
+  target: TestTarget;
+  language: string;
+  /** The function, or the whole file */
+  code: string;
+  startLine: number;
+  /** Call sites, to show how the function is used */
+  callers: Array<{ name: string; file: string; code: string }>;
+  layout: TestLayout;
+  /** Current content of the test file when it is a separate, existing file */
+  existingTests?: string;
+  examples: Array<{ file: string; text: string }>;
+  testCommand?: string;
+}
+
+export interface TestGenAttempt {
+  /** Generated test code (the module or test file) */
+  code: string;
+  /** null when the tests were not run */
+  exitCode: number | null;
+  output: string;
+}
+
+/** Failure output kept for the next prompt; tests print the useful part last */
+const MAX_FAILURE_CHARS = 6000;
+
+export const TEST_GEN_SYSTEM_PROMPT = `You write unit tests that match the conventions of the project they are added to.
+Test observable behavior, including edge cases and error paths, with the project's existing test framework and helpers; add no new dependencies.
+Reply with exactly one fenced code block and nothing else.`;
+
+/**
+ * Prompt for one attempt; `previous` is the failed attempt to fix
+ */
+export function buildTestGenPrompt(context: TestGenContext, previous?: TestGenAttempt): string {
+  const { target, layout } = context;
+  const subject = target.symbol ? `\`${target.symbol}\` in ${target.file}` : target.file;
+  const parts: string[] = [`Write unit tests for ${subject}.`, ''];
+
+  parts.push(`Code under test (${target.file}, from line ${context.startLine}):`);
+  parts.push('```' + context.language, context.code, '```', '');
+
+  if (context.callers.length > 0) {
+    parts.push('How it is called:');
+    for (const caller of context.callers) {
+      parts.push(`${caller.name} (${caller.file}):`, '```' + context.language, caller.code, '```');
+    }
+    parts.push('');
+  }
+
+  for (const example of context.examples) {
+    parts.push(`Existing test file ${example.file}, for style and helpers:`);
+    parts.push('```' + context.language, example.text, '```', '');
+  }
+
+  parts.push(`The tests go in ${layout.convention}.`);
+  if (layout.placement === 'inline') {
+    const name = `${(target.symbol ?? path.posix.basename(target.file, path.posix.extname(target.file)))
+      .replace(/\W+/g, '_').toLowerCase()}_tests`;
+    parts.push(`Reply with only the test module, named \`${name}\`, to append to ${target.file}; don't repeat the code under test.`);
+  } else if (context.existingTests !== undefined) {
+    parts.push(`${layout.testFile} already exists. Reply with its complete new content, keeping every existing test:`);
+    parts.push('```' + context.language, context.existingTests, '```');
+  } else {
+    parts.push(`Reply with the complete content of ${layout.testFile}.`);
+  }
+  if (context.testCommand) parts.push(`They will be run with \`${context.testCommand}\`.`);
+
+  if (previous) {
+    const output = previous.output.length > MAX_FAILURE_CHARS
+      ? `...\n${previous.output.slice(-MAX_FAILURE_CHARS)}`
+      : previous.output;
+    parts.push('', 'Your previous tests failed:', '```' + context.language, previous.code, '```');
+    parts.push(`Output (exit code ${previous.exitCode}):`, '```', output.trim(), '```');
+    parts.push('Fix the tests. If a failure shows a real bug in the code under test, keep the test and say so in a comment on it.');
+  }
+  return parts.join('\n');
+}
+
+/**
+ * The code block of a model reply; the whole reply when it has no fence
+ */
+export function extractTestCode(response: string): string {
+  const blocks = [...response.matchAll(/```[\w+#-]*[^\S\n]*\n([\s\S]*?)\n?```/g)].map(match => match[1]);
+  const code = blocks.length > 0 ? blocks.reduce((a, b) => (b.length > a.length ? b : a)) : response;
+  if (!code.trim()) throw new Error('The model returned no test code');
+  return code.replace(/\s+$/, '') + '\n';
+}
+
+/**
+ * New content of the test file for an attempt
+ *
+ * @param original - the test file before test-gen touched it (null if new)
+ */
+export function applyTestCode(layout: TestLayout, original: string | null, code: string): string {
+  if (layout.placement === 'inline') {
+    return `${(original ?? '').replace(/\s+$/, '')}\n\n${code}`;
+  }
+  return code;
+}
+
+export interface TestGenRunOptions {
+  context: TestGenContext;
+  attempts: number;
+  /** Test file content before test-gen (null if it doesn't exist) */
+  original: string | null;
+  generate: (prompt: string) => Promise<string>;
+  write: (content: string) => Promise<void>;
+  /** Run the test command; omit to only generate */
+  runTests?: () => Promise<{ exitCode: number; output: string }>;
+  onAttempt?: (attempt: TestGenAttempt, index: number) => void;
+}
+
+export interface TestGenResult {
+  passed: boolean;
+  attempts: TestGenAttempt[];
+  /** Content of the test file after the last attempt */
+  content: string;
+}
+
+/**
+ * Generate, write and run tests, feeding failures back until they pass.
+ * Redaction masks the model copied from the prompt are written as the
+ * secrets they stand for; a mask the prompt's sources can't resolve fails
+ * the run before anything is written.
+ */
+export async function runTestGeneration(options: TestGenRunOptions): Promise<TestGenResult> {
+  const attempts: TestGenAttempt[] = [];
+  let content = options.original ?? '';
+  const { context } = options;
+  const sources = [
+    options.original ?? '',
+    context.code,
+    ...context.callers.map(caller => caller.code),
+    ...context.examples.map(example => example.text)
+  ].join('\n');
+
+  for (let i = 0; i < Math.max(1, options.attempts); i++) {
+    const prompt = buildTestGenPrompt(options.context, attempts[attempts.length - 1]);
+    const code = extractTestCode(await options.generate(prompt));
+    content = restoreRedactedOrThrow(applyTestCode(context.layout, options.original, code), sources);
+    await options.write(content);
+
+    if (!options.runTests) {
+      const attempt = { code, exitCode: null, output: '' };
+      attempts.push(attempt);
+      options.onAttempt?.(attempt, i + 1);
+      return { passed: false, attempts, content };
+    }
+
+    const result = await options.runTests();
+    const attempt = { code, exitCode: result.exitCode, output: result.output };
+    attempts.push(attempt);
+    options.onAttempt?.(attempt, i + 1);
+    if (result.exitCode === 0) return { passed: true, attempts, content };
+  }
+  return { passed: false, attempts, content };
+}
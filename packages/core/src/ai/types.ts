@@ -1,6 +1,6 @@
 /**
  * AI Client Types
- * Unified interface for AI providers (OpenRouter, Ollama, etc.)
+ * Unified interface for AI providers (Anthropic, Gemini, OpenRouter, Ollama, etc.)
  */
 
 /**
@@ -20,6 +20,42 @@ export interface AIStreamHandler {
   onError?: (error: Error) => void;
 }
 
+/**
+ * A function the model may call, described by a JSON Schema for its arguments
+ */
+export interface AITool {
+  name: string;
+  description: string;
+  parameters: Record<string, unknown>;
+}
+
+/**
+ * A call the model asked for
+ */
+export interface AIToolCall {
+  /** Provider-assigned id, echoed back with the result */
+  id: string;
+  name: string;
+  arguments: Record<string, unknown>;
+}
+
+/**
+ * Message in a conversation that uses tools: plain messages, assistant turns
+ * that requested calls, and the results of those calls
+ */
+export type AIToolMessage =
+  | AIMessage
+  | { role: 'assistant'; content: string; toolCalls: AIToolCall[] }
+  | { role: 'tool'; toolCallId: string; name: string; content: string };
+
+/**
+ * Reply to a tool-enabled chat: text, calls to run, or both
+ */
+export interface AIToolResult {
+  text: string;
+  toolCalls: AIToolCall[];
+}
+
 /**
  * Unified AI client interface
  * All providers must implement this interface
@@ -63,6 +99,20 @@ export interface AIClient {
    * Simple completion (single prompt)
    */
   complete(prompt: string, handler?: AIStreamHandler): Promise<string>;
+
+  /**
+   * Chat completion that may answer with tool calls (providers with tool support)
+   */
+  chatWithTools?(
+    messages: AIToolMessage[],
+    tools: AITool[],
+    systemPrompt?: string
+  ): Promise<AIToolResult>;
+
+  /**
+   * Models this provider can serve
+   */
+  listModels?(): Promise<string[]>;
 }
 
 /**
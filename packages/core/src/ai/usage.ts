@@ -0,0 +1,392 @@
+/**
+ * Token Usage and Cost Tracking
+ *
+ * Provider responses report how many tokens a request used (`usage`,
+ * `usageMetadata`, Ollama's `eval_count`). The provider HTTP layer hands
+ * every successful response to captureUsage(), which reads those counts
+ * (from JSON or from a copy of the event stream) and prices them.
+ *
+ * Counts are aggregated per provider, model and kind while a command runs
+ * and appended to ~/.cv/usage.jsonl when the process exits, one line per
+ * model, so `cv usage` can total spend across repositories by command,
+ * day and model.
+ */
+
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { readNDJSON, readSSE } from './stream.js';
+
+export type UsageKind = 'chat' | 'embedding';
+
+export interface UsageRecord {
+  /** ISO time the command finished */
+  timestamp: string;
+  /** `cv` subcommand, e.g. "sync" or "pr review" */
+  command: string;
+  /** Repository root the command ran in */
+  repo?: string;
+  provider: string;
+  model: string;
+  kind: UsageKind;
+  requests: number;
+  promptTokens: number;
+  completionTokens: number;
+  /** USD; null when the model has no known price */
+  costUsd: number | null;
+  /** Some counts were estimated from request size */
+  estimated?: boolean;
+}
+
+export interface UsageTotals {
+  requests: number;
+  promptTokens: number;
+  completionTokens: number;
+  costUsd: number;
+  /** Records whose model has no known price */
+  unpriced: number;
+}
+
+export interface UsageSummary {
+  total: UsageTotals;
+  byCommand: Record<string, UsageTotals>;
+  byDay: Record<string, UsageTotals>;
+  byModel: Record<string, UsageTotals>;
+}
+
+/**
+ * USD per million tokens (input, output), matched by model name prefix after
+ * provider prefixes and date suffixes are removed
+ */
+export const MODEL_PRICING: Record<string, { input: number; output: number }> = {
+  'claude-opus-4': { input: 15, output: 75 },
+  'claude-sonnet-4': { input: 3, output: 15 },
+  'claude-haiku-4': { input: 1, output: 5 },
+  'claude-3-7-sonnet': { input: 3, output: 15 },
+  'claude-3-5-sonnet': { input: 3, output: 15 },
+  'claude-3-5-haiku': { input: 0.8, output: 4 },
+  'claude-3-opus': { input: 15, output: 75 },
+  'claude-3-haiku': { input: 0.25, output: 1.25 },
+  'gpt-4o-mini': { input: 0.15, output: 0.6 },
+  'gpt-4o': { input: 2.5, output: 10 },
+  'gpt-4.1-nano': { input: 0.1, output: 0.4 },
+  'gpt-4.1-mini': { input: 0.4, output: 1.6 },
+  'gpt-4.1': { input: 2, output: 8 },
+  'gpt-4-turbo': { input: 10, output: 30 },
+  'o3-mini': { input: 1.1, output: 4.4 },
+  'o4-mini': { input: 1.1, output: 4.4 },
+  'gemini-2.5-pro': { input: 1.25, output: 10 },
+  'gemini-2.5-flash': { input: 0.3, output: 2.5 },
+  'gemini-2.0-flash': { input: 0.1, output: 0.4 },
+  'gemini-1.5-pro': { input: 1.25, output: 5 },
+  'gemini-1.5-flash': { input: 0.075, output: 0.3 },
+  'deepseek-chat': { input: 0.27, output: 1.1 },
+  'deepseek-coder': { input: 0.27, output: 1.1 },
+  'llama-3.1-70b': { input: 0.3, output: 0.3 },
+  'text-embedding-3-small': { input: 0.02, output: 0 },
+  'text-embedding-3-large': { input: 0.13, output: 0 },
+  'text-embedding-ada-002': { input: 0.1, output: 0 },
+};
+
+/** Providers that run on this machine */
+const FREE_PROVIDERS = new Set(['ollama', 'lmstudio', 'local']);
+
+const PRICING_KEYS = Object.keys(MODEL_PRICING).sort((a, b) => b.length - a.length);
+
+/**
+ * USD for a request; null for a cloud model with no known price
+ */
+export function estimateCost(
+  provider: string,
+  model: string,
+  promptTokens: number,
+  completionTokens: number
+): number | null {
+  if (FREE_PROVIDERS.has(provider)) return 0;
+
+  const name = model.toLowerCase()
+    .replace(/^models\//, '')
+    .replace(/^.*\//, '')
+    .replace(/-(\d{8}|latest|preview.*)$/, '');
+  const key = PRICING_KEYS.find(k => name.startsWith(k));
+  if (!key) return null;
+
+  const price = MODEL_PRICING[key];
+  return (promptTokens * price.input + completionTokens * price.output) / 1_000_000;
+}
+
+// ========== Recording ==========
+
+interface PendingUsage {
+  provider: string;
+  model: string;
+  kind: UsageKind;
+  requests: number;
+  promptTokens: number;
+  completionTokens: number;
+  estimated: boolean;
+}
+
+let session: { command: string; repo?: string; file: string } | null = null;
+const pending = new Map<string, PendingUsage>();
+
+export function getUsageLogPath(): string {
+  return process.env.CV_USAGE_FILE || path.join(os.homedir(), '.cv', 'usage.jsonl');
+}
+
+/**
+ * Start recording usage for a command; written out when the process exits
+ */
+export function startUsageTracking(command: string, options: { repo?: string; file?: string } = {}): void {
+  if (process.env.CV_USAGE === 'off') return;
+  const first = session === null;
+  session = { command, repo: options.repo ?? findRepoDir(process.cwd()), file: options.file ?? getUsageLogPath() };
+  if (first) {
+    process.once('exit', () => flushUsage());
+  }
+}
+
+/**
+ * Nearest directory containing `.cv` or `.git`; synchronous so tracking can
+ * start before a command's action runs
+ */
+function findRepoDir(start: string): string | undefined {
+  for (let dir = path.resolve(start); ; dir = path.dirname(dir)) {
+    // ~/.cv holds user settings, not a repository
+    const cv = dir !== os.homedir() && fs.existsSync(path.join(dir, '.cv'));
+    if (cv || fs.existsSync(path.join(dir, '.git'))) return dir;
+    if (path.dirname(dir) === dir) return undefined;
+  }
+}
+
+/**
+ * Whether provider responses are being inspected for usage
+ */
+export function isTrackingUsage(): boolean {
+  return session !== null;
+}
+
+/**
+ * Add a request's token counts to the running totals
+ */
+export function recordUsage(usage: {
+  provider: string;
+  model: string;
+  kind: UsageKind;
+  promptTokens: number;
+  completionTokens?: number;
+  estimated?: boolean;
+}): void {
+  if (!session) return;
+  const key = `${usage.provider}\0${usage.model}\0${usage.kind}`;
+  const entry = pending.get(key) ?? {
+    provider: usage.provider,
+    model: usage.model,
+    kind: usage.kind,
+    requests: 0,
+    promptTokens: 0,
+    completionTokens: 0,
+    estimated: false,
+  };
+  entry.requests++;
+  entry.promptTokens += usage.promptTokens;
+  entry.completionTokens += usage.completionTokens ?? 0;
+  entry.estimated = entry.estimated || !!usage.estimated;
+  pending.set(key, entry);
+}
+
+/**
+ * Append the running totals to the usage log (synchronous, so it can run
+ * from an exit handler)
+ */
+export function flushUsage(): UsageRecord[] {
+  if (!session || pending.size === 0) return [];
+
+  const timestamp = new Date().toISOString();
+  const records: UsageRecord[] = [...pending.values()].map(entry => ({
+    timestamp,
+    command: session!.command,
+    ...(session!.repo ? { repo: session!.repo } : {}),
+    provider: entry.provider,
+    model: entry.model,
+    kind: entry.kind,
+    requests: entry.requests,
+    promptTokens: entry.promptTokens,
+    completionTokens: entry.completionTokens,
+    costUsd: estimateCost(entry.provider, entry.model, entry.promptTokens, entry.completionTokens),
+    ...(entry.estimated ? { estimated: true } : {}),
+  }));
+  pending.clear();
+
+  try {
+    fs.mkdirSync(path.dirname(session.file), { recursive: true });
+    fs.appendFileSync(session.file, records.map(r => JSON.stringify(r)).join('\n') + '\n');
+  } catch {
+    // Usage logging must never fail a command
+  }
+  return records;
+}
+
+// ========== Capture from provider responses ==========
+
+interface TokenCounts {
+  model?: string;
+  promptTokens?: number;
+  completionTokens?: number;
+}
+
+/**
+ * Token counts in one response body or stream event, in any provider's format
+ */
+export function parseUsage(data: any): TokenCounts {
+  if (!data || typeof data !== 'object') return {};
+  const counts: TokenCounts = {};
+  const model = data.model ?? data.message?.model ?? data.modelVersion;
+  if (typeof model === 'string') counts.model = model;
+
+  // OpenAI, OpenRouter, LM Studio, Azure; Anthropic (message and message_delta)
+  const usage = data.usage ?? data.message?.usage;
+  if (usage) {
+    counts.promptTokens = usage.prompt_tokens ?? usage.input_tokens;
+    counts.completionTokens = usage.completion_tokens ?? usage.output_tokens;
+  }
+  // Gemini
+  if (data.usageMetadata) {
+    counts.promptTokens = data.usageMetadata.promptTokenCount;
+    counts.completionTokens = data.usageMetadata.candidatesTokenCount;
+  }
+  // Ollama
+  if (data.prompt_eval_count !== undefined || data.eval_count !== undefined) {
+    counts.promptTokens = data.prompt_eval_count;
+    counts.completionTokens = data.eval_count;
+  }
+  return counts;
+}
+
+/**
+ * Record the usage of a successful provider response without consuming it
+ */
+export async function captureUsage(
+  provider: string,
+  url: string,
+  init: RequestInit | undefined,
+  response: Response
+): Promise<void> {
+  if (!session || !response.ok || typeof response.clone !== 'function') return;
+
+  const body = typeof init?.body === 'string' ? init.body : undefined;
+  let requestModel: string | undefined;
+  try {
+    requestModel = body ? JSON.parse(body).model : undefined;
+  } catch {
+    // Not JSON
+  }
+  requestModel ??= url.match(/\/models\/([^/:?]+)/)?.[1] ?? url.match(/\/deployments\/([^/?]+)/)?.[1];
+  const kind: UsageKind = /\/(embeddings?|embed)\b|:(batchEmbed|embed)Contents?\b/.test(url) ? 'embedding' : 'chat';
+
+  let counts: TokenCounts = {};
+  try {
+    counts = await readCounts(response.clone());
+  } catch {
+    // Unreadable body; fall back to an estimate
+  }
+  recordUsage({
+    provider,
+    model: requestModel ?? counts.model ?? 'unknown',
+    kind,
+    // ~4 characters per token when the provider reports nothing
+    promptTokens: counts.promptTokens ?? Math.ceil((body?.length ?? 0) / 4),
+    completionTokens: counts.completionTokens ?? 0,
+    estimated: counts.promptTokens === undefined,
+  });
+}
+
+async function readCounts(response: Response): Promise<TokenCounts> {
+  const type = response.headers.get('content-type') || '';
+  if (!response.body) return {};
+
+  if (type.includes('text/event-stream')) {
+    return mergeCounts(readSSE(response.body), data => {
+      try {
+        return JSON.parse(data);
+      } catch {
+        return undefined;
+      }
+    });
+  }
+  if (type.includes('ndjson')) {
+    return mergeCounts(readNDJSON(response.body), data => data);
+  }
+  return parseUsage(await response.json());
+}
+
+/**
+ * Stream events report counts as running totals; keep the largest
+ */
+async function mergeCounts<T>(events: AsyncIterable<T>, decode: (event: T) => unknown): Promise<TokenCounts> {
+  const merged: TokenCounts = {};
+  for await (const event of events) {
+    const counts = parseUsage(decode(event));
+    merged.model ??= counts.model;
+    if (counts.promptTokens !== undefined) merged.promptTokens = Math.max(merged.promptTokens ?? 0, counts.promptTokens);
+    if (counts.completionTokens !== undefined) {
+      merged.completionTokens = Math.max(merged.completionTokens ?? 0, counts.completionTokens);
+    }
+  }
+  return merged;
+}
+
+// ========== Reporting ==========
+
+/**
+ * Records in the usage log, oldest first
+ */
+export function loadUsage(file: string = getUsageLogPath(), since?: Date): UsageRecord[] {
+  let content: string;
+  try {
+    content = fs.readFileSync(file, 'utf-8');
+  } catch {
+    return [];
+  }
+
+  const records: UsageRecord[] = [];
+  for (const line of content.split('\n')) {
+    if (!line.trim()) continue;
+    try {
+      const record = JSON.parse(line) as UsageRecord;
+      if (!since || new Date(record.timestamp) >= since) records.push(record);
+    } catch {
+      // Skip a line truncated by an interrupted write
+    }
+  }
+  return records;
+}
+
+/**
+ * Totals overall and by command, by day (local date) and by provider/model
+ */
+export function summarizeUsage(records: UsageRecord[]): UsageSummary {
+  const summary: UsageSummary = { total: emptyTotals(), byCommand: {}, byDay: {}, byModel: {} };
+
+  for (const record of records) {
+    const date = new Date(record.timestamp);
+    const day = `${date.getFullYear()}-${String(date.getMonth() + 1).padStart(2, '0')}-${String(date.getDate()).padStart(2, '0')}`;
+    for (const totals of [
+      summary.total,
+      (summary.byCommand[record.command] ??= emptyTotals()),
+      (summary.byDay[day] ??= emptyTotals()),
+      (summary.byModel[`${record.provider}/${record.model}`] ??= emptyTotals()),
+    ]) {
+      totals.requests += record.requests;
+      totals.promptTokens += record.promptTokens;
+      totals.completionTokens += record.completionTokens;
+      if (record.costUsd === null) totals.unpriced++;
+      else totals.costUsd += record.costUsd;
+    }
+  }
+  return summary;
+}
+
+function emptyTotals(): UsageTotals {
+  return { requests: 0, promptTokens: 0, completionTokens: 0, costUsd: 0, unpriced: 0 };
+}
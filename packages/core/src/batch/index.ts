@@ -0,0 +1,260 @@
+/**
+ * Batch Runner
+ * Runs one AI operation across many files with a rate-limited work queue,
+ * a global token budget, and on-disk state so interrupted batches resume
+ */
+
+import { promises as fs } from 'fs';
+import * as path from 'path';
+
+export type BatchItemStatus = 'done' | 'failed' | 'skipped';
+
+export interface BatchItemResult {
+  item: string;
+  status: BatchItemStatus;
+  output?: string;
+  error?: string;
+  tokens: number;
+  durationMs: number;
+  completedAt: number;
+}
+
+export interface BatchState {
+  name: string;
+  operation: string;
+  createdAt: number;
+  updatedAt: number;
+  tokensUsed: number;
+  results: Record<string, BatchItemResult>;
+}
+
+export interface BatchTaskResult {
+  output: string;
+  /** Tokens consumed by this item (estimated if the provider doesn't report usage) */
+  tokens: number;
+}
+
+export type BatchTask = (item: string) => Promise<BatchTaskResult>;
+
+export interface BatchOptions {
+  /** Items processed at once */
+  concurrency?: number;
+  /** Upper bound on task starts per minute */
+  requestsPerMinute?: number;
+  /** Stop starting new items once this many tokens have been used (across resumes) */
+  tokenBudget?: number;
+  /** Where state is persisted; omit to run without resumability */
+  statePath?: string;
+  onItemComplete?: (result: BatchItemResult, progress: { completed: number; total: number }) => void;
+}
+
+export interface BatchReport {
+  name: string;
+  operation: string;
+  total: number;
+  done: number;
+  failed: number;
+  skipped: number;
+  /** Items already done in a previous run and not re-processed */
+  resumed: number;
+  tokensUsed: number;
+  budgetExhausted: boolean;
+  durationMs: number;
+  results: BatchItemResult[];
+}
+
+/**
+ * State file of the batch `name` under `batchDir`. Names become file names,
+ * so separators and `..` are rejected rather than escaping the directory.
+ */
+export function batchStatePath(batchDir: string, name: string): string {
+  if (!name || name.includes('/') || name.includes('\\') || name.includes('..')) {
+    throw new Error(`Invalid batch name "${name}": it must not be empty or contain "/", "\\" or ".."`);
+  }
+  return path.join(batchDir, `${name}.json`);
+}
+
+/**
+ * Load persisted batch state, or undefined if there is none
+ */
+export async function loadBatchState(statePath: string): Promise<BatchState | undefined> {
+  try {
+    return JSON.parse(await fs.readFile(statePath, 'utf-8')) as BatchState;
+  } catch {
+    return undefined;
+  }
+}
+
+async function saveBatchState(statePath: string, state: BatchState): Promise<void> {
+  await fs.mkdir(path.dirname(statePath), { recursive: true });
+  const tmp = `${statePath}.tmp`;
+  await fs.writeFile(tmp, JSON.stringify(state, null, 2), 'utf-8');
+  await fs.rename(tmp, statePath);
+}
+
+/**
+ * Spaces task starts so no more than `perMinute` begin in any minute
+ */
+class StartRateLimiter {
+  private nextStart = 0;
+
+  constructor(private perMinute: number) {}
+
+  async wait(): Promise<void> {
+    if (this.perMinute <= 0) return;
+    const interval = 60_000 / this.perMinute;
+    const now = Date.now();
+    const startAt = Math.max(now, this.nextStart);
+    this.nextStart = startAt + interval;
+    if (startAt > now) {
+      await new Promise(resolve => setTimeout(resolve, startAt - now));
+    }
+  }
+}
+
+export class BatchRunner {
+  private concurrency: number;
+  private limiter: StartRateLimiter;
+
+  constructor(
+    private name: string,
+    private operation: string,
+    private items: string[],
+    private task: BatchTask,
+    private options: BatchOptions = {}
+  ) {
+    this.concurrency = Math.max(1, options.concurrency ?? 2);
+    this.limiter = new StartRateLimiter(options.requestsPerMinute ?? 30);
+  }
+
+  async run(): Promise<BatchReport> {
+    const startTime = Date.now();
+    const statePath = this.options.statePath;
+
+    let state = statePath ? await loadBatchState(statePath) : undefined;
+    if (!state || state.operation !== this.operation) {
+      state = {
+        name: this.name,
+        operation: this.operation,
+        createdAt: Date.now(),
+        updatedAt: Date.now(),
+        tokensUsed: 0,
+        results: {}
+      };
+    }
+
+    // Only successful items are final; failed and skipped ones are retried on resume
+    const resumed = this.items.filter(item => state!.results[item]?.status === 'done');
+    const queue = this.items.filter(item => state!.results[item]?.status !== 'done');
+    const budget = this.options.tokenBudget;
+    let budgetExhausted = false;
+    let completed = resumed.length;
+
+    // Serialise state writes so concurrent workers don't interleave them
+    let saving: Promise<void> = Promise.resolve();
+    const persist = () => {
+      if (!statePath) return saving;
+      state!.updatedAt = Date.now();
+      const snapshot = JSON.parse(JSON.stringify(state)) as BatchState;
+      saving = saving.then(() => saveBatchState(statePath, snapshot));
+      return saving;
+    };
+
+    const finish = (result: BatchItemResult) => {
+      state!.results[result.item] = result;
+      state!.tokensUsed += result.tokens;
+      completed++;
+      this.options.onItemComplete?.(result, { completed, total: this.items.length });
+      return persist();
+    };
+
+    const worker = async () => {
+      while (queue.length > 0) {
+        if (budget !== undefined && state!.tokensUsed >= budget) {
+          budgetExhausted = true;
+          const item = queue.shift()!;
+          await finish({ item, status: 'skipped', error: 'token budget exhausted', tokens: 0, durationMs: 0, completedAt: Date.now() });
+          continue;
+        }
+
+        const item = queue.shift()!;
+        await this.limiter.wait();
+        const itemStart = Date.now();
+
+        try {
+          const { output, tokens } = await this.task(item);
+          await finish({ item, status: 'done', output, tokens, durationMs: Date.now() - itemStart, completedAt: Date.now() });
+        } catch (error: any) {
+          await finish({
+            item,
+            status: 'failed',
+            error: error?.message || String(error),
+            tokens: 0,
+            durationMs: Date.now() - itemStart,
+            completedAt: Date.now()
+          });
+        }
+      }
+    };
+
+    await Promise.all(Array.from({ length: Math.min(this.concurrency, queue.length || 1) }, worker));
+    await persist();
+
+    const results = this.items
+      .map(item => state!.results[item])
+      .filter((r): r is BatchItemResult => !!r);
+
+    return {
+      name: this.name,
+      operation: this.operation,
+      total: this.items.length,
+      done: results.filter(r => r.status === 'done').length,
+      failed: results.filter(r => r.status === 'failed').length,
+      skipped: results.filter(r => r.status === 'skipped').length,
+      resumed: resumed.length,
+      tokensUsed: state.tokensUsed,
+      budgetExhausted,
+      durationMs: Date.now() - startTime,
+      results
+    };
+  }
+}
+
+/**
+ * Render a batch report as markdown
+ */
+export function formatBatchReport(report: BatchReport): string {
+  const lines: string[] = [];
+  lines.push(`# Batch ${report.operation}: ${report.name}`);
+  lines.push('');
+  lines.push(`- Items: ${report.total} (${report.done} done, ${report.failed} failed, ${report.skipped} skipped)`);
+  if (report.resumed > 0) {
+    lines.push(`- Resumed: ${report.resumed} item(s) carried over from a previous run`);
+  }
+  lines.push(`- Tokens used: ~${report.tokensUsed}`);
+  if (report.budgetExhausted) {
+    lines.push('- Token budget exhausted; re-run with --resume to continue');
+  }
+  lines.push('');
+
+  for (const result of report.results) {
+    if (result.status === 'done') {
+      lines.push(`## ${result.item}`);
+      lines.push('');
+      lines.push(result.output?.trim() || '_No output_');
+      lines.push('');
+    }
+  }
+
+  const problems = report.results.filter(r => r.status !== 'done');
+  if (problems.length > 0) {
+    lines.push('## Not completed');
+    lines.push('');
+    for (const result of problems) {
+      lines.push(`- \`${result.item}\` — ${result.status}: ${result.error}`);
+    }
+    lines.push('');
+  }
+
+  return lines.join('\n');
+}
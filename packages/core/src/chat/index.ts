@@ -0,0 +1,319 @@
+/**
+ * Chat Session Store
+ * Persistent conversations shared by `cv chat` and editor clients (MCP/JSON-RPC).
+ *
+ * Sessions live at .cv/chat/sessions/<id>.json. Every append takes the session's
+ * file lock, re-reads the latest state and bumps a revision counter, so several
+ * clients can write to the same conversation without losing messages. Clients
+ * catch up by asking for messages newer than the last revision they saw.
+ */
+
+import { promises as fs } from 'fs';
+import * as crypto from 'crypto';
+import * as path from 'path';
+import { getCVDir } from '@cv-git/shared';
+import { withLock } from '../sync/file-lock.js';
+
+export type ChatSessionRole = 'user' | 'assistant';
+
+export interface ChatSessionMessage {
+  role: ChatSessionRole;
+  content: string;
+  /** Retrieved codebase context sent alongside a user message */
+  context?: string;
+  /** Which client wrote the message (cli, vscode, mcp, ...) */
+  client: string;
+  timestamp: number;
+  /** Session revision at which the message was appended */
+  revision: number;
+}
+
+export interface ChatSession {
+  id: string;
+  title?: string;
+  model?: string;
+  createdAt: number;
+  updatedAt: number;
+  revision: number;
+  messages: ChatSessionMessage[];
+  /** Condensed form of the earlier messages, reused until more turns need folding in */
+  summary?: ChatHistorySummary;
+}
+
+export interface ChatHistorySummary {
+  text: string;
+  /** Last revision the summary covers */
+  throughRevision: number;
+}
+
+export interface ChatSessionSummary {
+  id: string;
+  title?: string;
+  model?: string;
+  updatedAt: number;
+  messageCount: number;
+  lastClient?: string;
+}
+
+export interface NewChatSessionMessage {
+  role: ChatSessionRole;
+  content: string;
+  context?: string;
+  client?: string;
+}
+
+export class ChatSessionConflictError extends Error {
+  constructor(public sessionId: string, public expectedRevision: number, public actualRevision: number) {
+    super(`Chat session ${sessionId} is at revision ${actualRevision}, expected ${expectedRevision}`);
+    this.name = 'ChatSessionConflictError';
+  }
+}
+
+export interface ChatHistoryOptions {
+  /** Prior turns are sent verbatim while they fit in this many characters (default ~6k tokens) */
+  maxChars?: number;
+  /** Messages always sent verbatim when history is summarized */
+  keepRecent?: number;
+}
+
+export interface ChatHistoryPlan {
+  /** Older messages to send as a summary */
+  summarize: ChatSessionMessage[];
+  /** Recent messages to send as they are */
+  recent: ChatSessionMessage[];
+}
+
+export const DEFAULT_CHAT_HISTORY_CHARS = 24000;
+export const DEFAULT_CHAT_KEEP_RECENT = 6;
+
+/**
+ * Split prior turns into what is summarized and what is sent verbatim.
+ * Short histories are sent whole; long ones keep the last few messages,
+ * starting at a user message so the recent part reads as whole turns.
+ */
+export function planChatHistory(messages: ChatSessionMessage[], options: ChatHistoryOptions = {}): ChatHistoryPlan {
+  const maxChars = options.maxChars ?? DEFAULT_CHAT_HISTORY_CHARS;
+  const keepRecent = options.keepRecent ?? DEFAULT_CHAT_KEEP_RECENT;
+
+  const total = messages.reduce((sum, m) => sum + toModelContent(m).length, 0);
+  if (total <= maxChars || messages.length <= keepRecent) {
+    return { summarize: [], recent: messages };
+  }
+
+  let split = messages.length - keepRecent;
+  while (split < messages.length && messages[split].role !== 'user') split++;
+  return { summarize: messages.slice(0, split), recent: messages.slice(split) };
+}
+
+/**
+ * Prompt asking the model to condense earlier turns, extending a previous summary if there is one
+ */
+export function buildChatSummaryPrompt(messages: ChatSessionMessage[], previous?: string): string {
+  let prompt = `Summarize this conversation between a developer and an assistant about their codebase, `;
+  prompt += `so it can continue without the full transcript. Keep file paths, symbol names, decisions and open questions. `;
+  prompt += `Write at most a few short paragraphs.\n\n`;
+  if (previous) {
+    prompt += `## Summary so far\n${previous}\n\n## Later messages\n`;
+  }
+  for (const message of messages) {
+    // Retrieved context is left out; the answers already say what mattered
+    prompt += `${message.role === 'user' ? 'Developer' : 'Assistant'}: ${message.content}\n\n`;
+  }
+  return prompt;
+}
+
+/**
+ * Messages to send ahead of a new question: the summary (if any) as an opening exchange, then recent turns
+ */
+export function chatHistoryMessages(
+  plan: ChatHistoryPlan,
+  summary?: string
+): Array<{ role: ChatSessionRole; content: string }> {
+  const history: Array<{ role: ChatSessionRole; content: string }> = [];
+  if (plan.summarize.length > 0 && summary) {
+    history.push(
+      { role: 'user', content: `Summary of our conversation so far:\n\n${summary}` },
+      { role: 'assistant', content: 'Understood. I will continue from there.' }
+    );
+  }
+  history.push(...plan.recent.map(m => ({ role: m.role, content: toModelContent(m) })));
+  return history;
+}
+
+/**
+ * Render a stored message the way it is sent to the model
+ */
+export function toModelContent(message: ChatSessionMessage): string {
+  return message.context
+    ? `<codebase_context>\n${message.context}\n</codebase_context>\n\n${message.content}`
+    : message.content;
+}
+
+export class ChatSessionStore {
+  constructor(private dir: string) {}
+
+  private sessionPath(id: string): string {
+    return path.join(this.dir, `${id}.json`);
+  }
+
+  private async read(id: string): Promise<ChatSession | null> {
+    try {
+      return JSON.parse(await fs.readFile(this.sessionPath(id), 'utf-8')) as ChatSession;
+    } catch {
+      return null;
+    }
+  }
+
+  private async write(session: ChatSession): Promise<void> {
+    await fs.mkdir(this.dir, { recursive: true });
+    const target = this.sessionPath(session.id);
+    const tmp = `${target}.${process.pid}.tmp`;
+    await fs.writeFile(tmp, JSON.stringify(session, null, 2), 'utf-8');
+    await fs.rename(tmp, target);
+  }
+
+  /**
+   * Resolve a full id, unique id prefix, or "latest"
+   */
+  async resolveId(idOrPrefix: string): Promise<string | null> {
+    if (idOrPrefix === 'latest') {
+      return (await this.list())[0]?.id ?? null;
+    }
+    const ids = (await this.listIds()).filter(id => id.startsWith(idOrPrefix));
+    if (ids.includes(idOrPrefix)) return idOrPrefix;
+    return ids.length === 1 ? ids[0] : null;
+  }
+
+  private async listIds(): Promise<string[]> {
+    try {
+      return (await fs.readdir(this.dir))
+        .filter(f => f.endsWith('.json'))
+        .map(f => f.slice(0, -'.json'.length));
+    } catch {
+      return [];
+    }
+  }
+
+  async create(options: { title?: string; model?: string } = {}): Promise<ChatSession> {
+    const now = Date.now();
+    const session: ChatSession = {
+      id: `${now.toString(36)}-${crypto.randomBytes(3).toString('hex')}`,
+      title: options.title,
+      model: options.model,
+      createdAt: now,
+      updatedAt: now,
+      revision: 0,
+      messages: []
+    };
+    await this.write(session);
+    return session;
+  }
+
+  async get(idOrPrefix: string): Promise<ChatSession | null> {
+    const id = await this.resolveId(idOrPrefix);
+    return id ? this.read(id) : null;
+  }
+
+  /**
+   * Sessions, most recently updated first
+   */
+  async list(): Promise<ChatSessionSummary[]> {
+    const sessions = await Promise.all((await this.listIds()).map(id => this.read(id)));
+    return sessions
+      .filter((s): s is ChatSession => !!s)
+      .sort((a, b) => b.updatedAt - a.updatedAt)
+      .map(s => ({
+        id: s.id,
+        title: s.title,
+        model: s.model,
+        updatedAt: s.updatedAt,
+        messageCount: s.messages.length,
+        lastClient: s.messages[s.messages.length - 1]?.client
+      }));
+  }
+
+  /**
+   * Append messages atomically. With `expectedRevision`, fail instead of
+   * appending if another client has written since that revision.
+   */
+  async append(
+    id: string,
+    messages: NewChatSessionMessage[],
+    options: { expectedRevision?: number; model?: string } = {}
+  ): Promise<ChatSession> {
+    return withLock(this.sessionPath(id), async () => {
+      const session = await this.read(id);
+      if (!session) {
+        throw new Error(`Chat session not found: ${id}`);
+      }
+      if (options.expectedRevision !== undefined && options.expectedRevision !== session.revision) {
+        throw new ChatSessionConflictError(id, options.expectedRevision, session.revision);
+      }
+
+      const revision = session.revision + 1;
+      const now = Date.now();
+      session.messages.push(...messages.map(m => ({
+        role: m.role,
+        content: m.content,
+        ...(m.context ? { context: m.context } : {}),
+        client: m.client || 'cli',
+        timestamp: now,
+        revision
+      })));
+      session.revision = revision;
+      session.updatedAt = now;
+      if (options.model) session.model = options.model;
+      if (!session.title) {
+        const firstUser = session.messages.find(m => m.role === 'user');
+        if (firstUser) session.title = firstUser.content.split('\n')[0].slice(0, 60);
+      }
+
+      await this.write(session);
+      return session;
+    }, { timeout: 10000 });
+  }
+
+  /**
+   * Store the summary of the session's messages up to `throughRevision`
+   */
+  async setSummary(id: string, text: string, throughRevision: number): Promise<void> {
+    await withLock(this.sessionPath(id), async () => {
+      const session = await this.read(id);
+      if (!session) {
+        throw new Error(`Chat session not found: ${id}`);
+      }
+      // Keep whichever summary covers more of the conversation
+      if (session.summary && session.summary.throughRevision > throughRevision) return;
+      session.summary = { text, throughRevision };
+      await this.write(session);
+    }, { timeout: 10000 });
+  }
+
+  /**
+   * Messages appended after `revision`, for clients catching up
+   */
+  async messagesSince(id: string, revision: number): Promise<ChatSessionMessage[]> {
+    const session = await this.read(id);
+    return session ? session.messages.filter(m => m.revision > revision) : [];
+  }
+
+  async delete(id: string): Promise<boolean> {
+    try {
+      await fs.unlink(this.sessionPath(id));
+      return true;
+    } catch {
+      return false;
+    }
+  }
+}
+
+/**
+ * Directory holding a repository's chat sessions
+ */
+export function getChatSessionDir(repoRoot: string): string {
+  return path.join(getCVDir(repoRoot), 'chat', 'sessions');
+}
+
+export function createChatSessionStore(repoRoot: string): ChatSessionStore {
+  return new ChatSessionStore(getChatSessionDir(repoRoot));
+}
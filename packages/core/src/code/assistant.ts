@@ -14,6 +14,8 @@ import { ContextManager, createContextManager } from './context-manager.js';
 import { SessionManager, createSessionManager } from './session-manager.js';
 import { FileOperations, createFileOperations } from './file-ops.js';
 import { EditParser, createEditParser } from './edit-parser.js';
+import { ConflictResolver } from './merge.js';
+import { ChangeSet, FileChange, recordChangeSet } from './changesets.js';
 import {
   CodeSession,
   CodeMessage,
@@ -94,8 +96,10 @@ export class CodeAssistant {
   private fileOps: FileOperations;
   private editParser: EditParser;
   private aiClient: AIClient;
+  private git: GitManager;
   private repoRoot: string;
   private options: CodeOptions;
+  private lastChangeSet: ChangeSet | null = null;
 
   constructor(
     repoRoot: string,
@@ -108,6 +112,7 @@ export class CodeAssistant {
     this.repoRoot = repoRoot;
     this.options = options;
     this.aiClient = aiClient;
+    this.git = git;
 
     // Debug: Check graph state when passed to CodeAssistant
     if (process.env.CV_DEBUG && graph) {
@@ -210,6 +215,7 @@ export class CodeAssistant {
     callbacks?.onStatus?.('parsing', 'Parsing edits...');
     const messageId = uuidv4();
     const edits = this.editParser.parseResponse(fullResponse, messageId);
+    await this.recordBaseContent(edits);
 
     // 6. Update session
     const userMsg: CodeMessage = {
@@ -256,6 +262,19 @@ export class CodeAssistant {
     };
   }
 
+  /**
+   * Remember each file as the AI saw it, so drift by apply time can be merged
+   */
+  private async recordBaseContent(edits: Edit[]): Promise<void> {
+    for (const edit of edits) {
+      try {
+        edit.baseContent = await this.fileOps.readFile(edit.file);
+      } catch {
+        // New file
+      }
+    }
+  }
+
   /**
    * Build system prompt with context
    */
@@ -300,6 +319,8 @@ export class CodeAssistant {
   async applyEdits(options?: {
     editIds?: string[];
     autoApprove?: boolean;
+    /** Resolves hunks that conflict with changes made since the edit was proposed */
+    resolveConflict?: ConflictResolver;
   }): Promise<EditResult[]> {
     const pendingEdits = this.session.getPendingEdits();
     const results: EditResult[] = [];
@@ -315,7 +336,7 @@ export class CodeAssistant {
       }
 
       // Apply the edit
-      const result = await this.fileOps.applyEdit(edit);
+      const result = await this.fileOps.applyEdit(edit, { resolveConflict: options?.resolveConflict });
       results.push(result);
 
       if (result.success) {
@@ -326,9 +347,61 @@ export class CodeAssistant {
     // Save session
     await this.session.save();
 
+    await this.recordChanges(results);
+
     return results;
   }
 
+  /**
+   * Record successfully applied edits as a change set for `cv undo`.
+   * Recording is best-effort; a failure never undoes the edits themselves.
+   */
+  private async recordChanges(results: EditResult[]): Promise<void> {
+    this.lastChangeSet = null;
+    const changes: FileChange[] = [];
+    const readBack = async (file: string): Promise<string | null> => {
+      try {
+        return await this.fileOps.readFile(file);
+      } catch {
+        return null;
+      }
+    };
+
+    for (const result of results) {
+      if (!result.success) continue;
+      const { edit } = result;
+      const before = edit.originalContent ?? null;
+      if (edit.type === 'delete') {
+        changes.push({ file: edit.file, before, after: null });
+      } else if (edit.type === 'rename' && edit.newPath) {
+        changes.push({ file: edit.file, before, after: null });
+        changes.push({ file: edit.newPath, before: null, after: await readBack(edit.newPath) });
+      } else {
+        changes.push({ file: edit.file, before, after: await readBack(edit.file) });
+      }
+    }
+    if (changes.length === 0) return;
+
+    try {
+      const head = await this.git.getLastCommitSha().catch(() => undefined);
+      const description = results
+        .map(r => r.edit.description)
+        .find((d): d is string => !!d) ?? `${changes.length} file(s) changed`;
+      this.lastChangeSet = await recordChangeSet(this.repoRoot, changes, { command: 'code', description, head });
+    } catch (error: any) {
+      if (process.env.CV_DEBUG) {
+        console.log(`[CodeAssistant] Could not record change set: ${error.message}`);
+      }
+    }
+  }
+
+  /**
+   * Change set recorded by the last applyEdits call, if any
+   */
+  getLastChangeSet(): ChangeSet | null {
+    return this.lastChangeSet;
+  }
+
   /**
    * Approve an edit (mark as ready to apply)
    */
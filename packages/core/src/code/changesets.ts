@@ -0,0 +1,208 @@
+/**
+ * CV Code - Change Sets
+ *
+ * Every set of AI-applied file changes (`cv do`, `cv code`) is recorded
+ * under `.cv/changesets/` with the before/after hash of each file and the
+ * content needed to restore it, so `cv undo` can roll it back later.
+ *
+ *   .cv/changesets/<id>.json        change set metadata
+ *   .cv/changesets/blobs/<sha256>   file contents, shared between change sets
+ *
+ * A file that was edited again after the change set was applied is reported
+ * as a conflict instead of being overwritten.
+ */
+
+import { promises as fs } from 'fs';
+import * as path from 'path';
+import * as crypto from 'crypto';
+import { getCVDir } from '@cv-git/shared';
+import { Patch, PatchEngine, contentHash } from './patch-engine.js';
+
+export interface ChangeSetFile {
+  file: string;
+  /** sha256 of the content before/after, null where the file did not exist */
+  beforeHash: string | null;
+  afterHash: string | null;
+}
+
+export interface ChangeSet {
+  id: string;
+  createdAt: number;
+  /** Command that applied the changes, e.g. "do" or "code" */
+  command: string;
+  description: string;
+  /** HEAD when the changes were applied */
+  head?: string;
+  files: ChangeSetFile[];
+  /** Set once the change set has been rolled back */
+  undoneAt?: number;
+}
+
+export interface ChangeSetConflict {
+  file: string;
+  reason: string;
+}
+
+export interface UndoResult {
+  changeSet: ChangeSet;
+  reverted: string[];
+  conflicts: ChangeSetConflict[];
+}
+
+export interface FileChange {
+  file: string;
+  before: string | null;
+  after: string | null;
+}
+
+function changeSetDir(repoRoot: string): string {
+  return path.join(getCVDir(repoRoot), 'changesets');
+}
+
+function blobPath(repoRoot: string, hash: string): string {
+  return path.join(changeSetDir(repoRoot), 'blobs', hash);
+}
+
+async function writeAtomic(file: string, content: string): Promise<void> {
+  const tmp = `${file}.${process.pid}.tmp`;
+  await fs.writeFile(tmp, content);
+  await fs.rename(tmp, file);
+}
+
+async function saveChangeSet(repoRoot: string, changeSet: ChangeSet): Promise<void> {
+  await writeAtomic(path.join(changeSetDir(repoRoot), `${changeSet.id}.json`), JSON.stringify(changeSet, null, 2));
+}
+
+/**
+ * Record a set of applied file changes
+ */
+export async function recordChangeSet(
+  repoRoot: string,
+  changes: FileChange[],
+  meta: { command: string; description: string; head?: string }
+): Promise<ChangeSet> {
+  await fs.mkdir(path.join(changeSetDir(repoRoot), 'blobs'), { recursive: true });
+
+  const files: ChangeSetFile[] = [];
+  for (const change of changes) {
+    const beforeHash = contentHash(change.before);
+    const afterHash = contentHash(change.after);
+    for (const [hash, content] of [[beforeHash, change.before], [afterHash, change.after]] as const) {
+      if (hash === null || content === null) continue;
+      const blob = blobPath(repoRoot, hash);
+      try {
+        await fs.access(blob);
+      } catch {
+        await writeAtomic(blob, content);
+      }
+    }
+    files.push({ file: change.file, beforeHash, afterHash });
+  }
+
+  const createdAt = Date.now();
+  const changeSet: ChangeSet = {
+    id: `${createdAt.toString(36)}${crypto.randomBytes(2).toString('hex')}`,
+    createdAt,
+    command: meta.command,
+    description: meta.description,
+    head: meta.head,
+    files
+  };
+  await saveChangeSet(repoRoot, changeSet);
+  return changeSet;
+}
+
+/**
+ * All recorded change sets, newest first
+ */
+export async function listChangeSets(repoRoot: string): Promise<ChangeSet[]> {
+  let entries: string[];
+  try {
+    entries = await fs.readdir(changeSetDir(repoRoot));
+  } catch {
+    return [];
+  }
+
+  const changeSets: ChangeSet[] = [];
+  for (const entry of entries) {
+    if (!entry.endsWith('.json')) continue;
+    try {
+      changeSets.push(JSON.parse(await fs.readFile(path.join(changeSetDir(repoRoot), entry), 'utf-8')));
+    } catch {
+      // Skip unreadable entries
+    }
+  }
+  return changeSets.sort((a, b) => b.createdAt - a.createdAt);
+}
+
+/**
+ * Find a change set by id or unique id prefix
+ */
+export async function findChangeSet(repoRoot: string, id: string): Promise<ChangeSet | null> {
+  const matches = (await listChangeSets(repoRoot)).filter(cs => cs.id.startsWith(id));
+  if (matches.length > 1 && !matches.some(cs => cs.id === id)) {
+    throw new Error(`Change set id "${id}" is ambiguous (${matches.map(cs => cs.id).join(', ')})`);
+  }
+  return matches.find(cs => cs.id === id) ?? matches[0] ?? null;
+}
+
+/**
+ * Roll back a change set. All files are reverted together or none are:
+ * if any file was edited since (or its recorded content is gone), nothing
+ * changes and the conflicts are returned, unless `force` restores anyway.
+ */
+export async function undoChangeSet(
+  repoRoot: string,
+  changeSet: ChangeSet,
+  options: { force?: boolean } = {}
+): Promise<UndoResult> {
+  if (changeSet.undoneAt) {
+    throw new Error(`Change set ${changeSet.id} was already undone`);
+  }
+
+  const engine = new PatchEngine(repoRoot);
+  const patch: Patch = { files: [] };
+  const conflicts: ChangeSetConflict[] = [];
+
+  for (const entry of changeSet.files) {
+    let current: string | null;
+    try {
+      current = await fs.readFile(path.join(repoRoot, entry.file), 'utf-8');
+    } catch {
+      current = null;
+    }
+
+    if (contentHash(current) !== entry.afterHash) {
+      const reason = current === null
+        ? 'deleted since the change was applied'
+        : entry.afterHash === null
+          ? 'recreated since the change deleted it'
+          : 'edited since the change was applied';
+      conflicts.push({ file: entry.file, reason });
+      if (!options.force) continue;
+    }
+
+    let before: string | null = null;
+    if (entry.beforeHash !== null) {
+      try {
+        before = await fs.readFile(blobPath(repoRoot, entry.beforeHash), 'utf-8');
+      } catch {
+        conflicts.push({ file: entry.file, reason: 'recorded content is missing' });
+        continue;
+      }
+    }
+    if (before !== current) {
+      patch.files.push({ file: entry.file, before: current, after: before, diff: '' });
+    }
+  }
+
+  const blocking = options.force ? conflicts.filter(c => c.reason === 'recorded content is missing') : conflicts;
+  if (blocking.length > 0) {
+    return { changeSet, reverted: [], conflicts };
+  }
+
+  await engine.apply(patch);
+  const undone = { ...changeSet, undoneAt: Date.now() };
+  await saveChangeSet(repoRoot, undone);
+  return { changeSet: undone, reverted: patch.files.map(f => f.file), conflicts };
+}
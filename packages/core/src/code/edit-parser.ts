@@ -8,6 +8,15 @@
 import { v4 as uuidv4 } from 'uuid';
 import { Edit, SearchReplaceBlock, FileDiff, DiffHunk, DiffLine } from './types.js';
 
+/**
+ * How the model should write file changes so they can be applied as a patch
+ */
+export const EDIT_BLOCK_INSTRUCTIONS =
+  `Write each change as a code block whose info string is the file path, so it can be applied as a patch:\n` +
+  `- New file: the complete file content\n` +
+  `- Existing file: one or more blocks of\n<<<<<<< SEARCH\n(exact lines to find, including indentation)\n=======\n(replacement lines)\n>>>>>>> REPLACE\n` +
+  `- Deleted file: <<<<<<< DELETE followed by >>>>>>> DELETE\n`;
+
 /**
  * Parser for AI-generated code edits
  */
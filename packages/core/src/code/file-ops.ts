@@ -7,7 +7,17 @@
 import { promises as fs } from 'fs';
 import * as path from 'path';
 import * as crypto from 'crypto';
-import { Edit, EditResult } from './types.js';
+import { Edit, EditResult, SearchReplaceBlock } from './types.js';
+import { ConflictResolution, ConflictResolver, merge3, resolveMerge } from './merge.js';
+import { restoreRedacted, restoreRedactedOrThrow } from '../security/redact.js';
+
+export interface ApplyEditOptions {
+  /**
+   * Called for each conflicting hunk when the file changed since the edit was proposed.
+   * Without one, conflicting edits fail and the file is left untouched.
+   */
+  resolveConflict?: ConflictResolver;
+}
 
 /**
  * Manages file operations with safety features
@@ -22,17 +32,19 @@ export class FileOperations {
   }
 
   /**
-   * Apply an edit to the filesystem
+   * Apply an edit to the filesystem. Redaction masks the model echoed from
+   * its context are restored to the secrets they stand for; an edit with a
+   * mask that can't be restored is rejected.
    */
-  async applyEdit(edit: Edit): Promise<EditResult> {
+  async applyEdit(edit: Edit, options: ApplyEditOptions = {}): Promise<EditResult> {
     const filePath = path.join(this.repoRoot, edit.file);
 
     try {
       switch (edit.type) {
         case 'create':
-          return await this.handleCreate(filePath, edit);
+          return await this.handleCreate(filePath, edit, options);
         case 'modify':
-          return await this.handleModify(filePath, edit);
+          return await this.handleModify(filePath, edit, options);
         case 'delete':
           return await this.handleDelete(filePath, edit);
         case 'rename':
@@ -52,30 +64,47 @@ export class FileOperations {
   /**
    * Handle file creation
    */
-  private async handleCreate(filePath: string, edit: Edit): Promise<EditResult> {
+  private async handleCreate(
+    filePath: string,
+    edit: Edit,
+    options: ApplyEditOptions
+  ): Promise<EditResult> {
     // Check if file already exists
     const exists = await this.fileExists(filePath);
 
     if (exists) {
       // File exists - treat as modify with full replacement
       const originalContent = await fs.readFile(filePath, 'utf-8');
-      const backupPath = await this.createBackup(filePath, originalContent);
+      let newContent = restoreRedactedOrThrow(edit.newContent || '', edit.baseContent ?? originalContent);
+      let merge: { merged: boolean; conflictsResolved: number } | undefined;
+
+      // A file that appeared or changed after the edit was proposed is merged, not overwritten
+      const base = edit.baseContent ?? '';
+      if (originalContent !== base) {
+        const result = await this.mergeWithWorkingCopy(edit, base, originalContent, newContent, options);
+        newContent = result.content;
+        merge = { merged: true, conflictsResolved: result.conflictsResolved };
+      }
 
-      await fs.writeFile(filePath, edit.newContent || '');
+      const backupPath = await this.createBackup(filePath, originalContent);
+      await fs.writeFile(filePath, newContent);
 
       return {
         edit: { ...edit, type: 'modify', status: 'applied', originalContent },
         success: true,
         backupPath,
         appliedAt: Date.now(),
+        ...merge,
       };
     }
 
+    const content = restoreRedactedOrThrow(edit.newContent || '', '');
+
     // Create directory if needed
     await fs.mkdir(path.dirname(filePath), { recursive: true });
 
     // Write new file
-    await fs.writeFile(filePath, edit.newContent || '');
+    await fs.writeFile(filePath, content);
 
     return {
       edit: { ...edit, status: 'applied' },
@@ -87,43 +116,31 @@ export class FileOperations {
   /**
    * Handle file modification with search/replace
    */
-  private async handleModify(filePath: string, edit: Edit): Promise<EditResult> {
+  private async handleModify(
+    filePath: string,
+    edit: Edit,
+    options: ApplyEditOptions
+  ): Promise<EditResult> {
     // Read current content
     const originalContent = await fs.readFile(filePath, 'utf-8');
 
-    // Create backup
-    const backupPath = await this.createBackup(filePath, originalContent);
-
     let newContent: string;
-
-    if (edit.searchReplaceBlocks && edit.searchReplaceBlocks.length > 0) {
-      // Apply search/replace blocks sequentially
-      newContent = originalContent;
-
-      for (const block of edit.searchReplaceBlocks) {
-        if (!newContent.includes(block.search)) {
-          // Try to find approximate match
-          const approxMatch = this.findApproximateMatch(newContent, block.search);
-          if (approxMatch) {
-            throw new Error(
-              `Search block not found exactly in ${edit.file}.\n` +
-              `Expected:\n${block.search.slice(0, 200)}...\n` +
-              `Found similar at line ${approxMatch.line}:\n${approxMatch.text.slice(0, 200)}...`
-            );
-          }
-          throw new Error(
-            `Search block not found in ${edit.file}:\n${block.search.slice(0, 200)}...`
-          );
-        }
-        newContent = newContent.replace(block.search, block.replace);
-      }
-    } else if (edit.newContent !== undefined) {
-      // Full file replacement
-      newContent = edit.newContent;
+    let merge: { merged: boolean; conflictsResolved: number } | undefined;
+
+    if (edit.baseContent !== undefined && originalContent !== edit.baseContent) {
+      // The working copy drifted since the edit was proposed: apply it to the base
+      // it was written against, then merge that into the user's version
+      const proposed = restoreRedactedOrThrow(this.buildProposedContent(edit, edit.baseContent), edit.baseContent);
+      const result = await this.mergeWithWorkingCopy(edit, edit.baseContent, originalContent, proposed, options);
+      newContent = result.content;
+      merge = { merged: true, conflictsResolved: result.conflictsResolved };
     } else {
-      throw new Error('No search/replace blocks or new content provided');
+      newContent = restoreRedactedOrThrow(this.buildProposedContent(edit, originalContent), originalContent);
     }
 
+    // Create backup
+    const backupPath = await this.createBackup(filePath, originalContent);
+
     // Write updated content
     await fs.writeFile(filePath, newContent);
 
@@ -132,15 +149,95 @@ export class FileOperations {
       success: true,
       backupPath,
       appliedAt: Date.now(),
+      ...merge,
     };
   }
 
+  /**
+   * Content after applying the edit's search/replace blocks or full replacement to `content`
+   */
+  buildProposedContent(edit: Edit, content: string): string {
+    if (edit.searchReplaceBlocks && edit.searchReplaceBlocks.length > 0) {
+      // Apply search/replace blocks sequentially
+      let newContent = content;
+      for (const block of edit.searchReplaceBlocks) {
+        newContent = this.applySearchReplace(edit.file, newContent, block);
+      }
+      return newContent;
+    }
+    if (edit.newContent !== undefined) {
+      // Full file replacement
+      return edit.newContent;
+    }
+    throw new Error('No search/replace blocks or new content provided');
+  }
+
+  private applySearchReplace(file: string, content: string, block: SearchReplaceBlock): string {
+    // The model quotes the file as it saw it, with secrets masked
+    const search = restoreRedacted(block.search, content).text;
+    if (!content.includes(search)) {
+      // Try to find approximate match
+      const approxMatch = this.findApproximateMatch(content, block.search);
+      if (approxMatch) {
+        throw new Error(
+          `Search block not found exactly in ${file}.\n` +
+          `Expected:\n${block.search.slice(0, 200)}...\n` +
+          `Found similar at line ${approxMatch.line}:\n${approxMatch.text.slice(0, 200)}...`
+        );
+      }
+      throw new Error(
+        `Search block not found in ${file}:\n${block.search.slice(0, 200)}...`
+      );
+    }
+    return content.replace(search, block.replace);
+  }
+
+  /**
+   * Three-way merge of the proposed content into a working copy that changed.
+   * Throws, leaving the file untouched, if a conflict is unresolved.
+   */
+  private async mergeWithWorkingCopy(
+    edit: Edit,
+    base: string,
+    current: string,
+    proposed: string,
+    options: ApplyEditOptions
+  ): Promise<{ content: string; conflictsResolved: number }> {
+    const result = merge3(base, current, proposed);
+    if (result.conflicts === 0) {
+      return { content: result.content, conflictsResolved: 0 };
+    }
+
+    if (!options.resolveConflict) {
+      throw new Error(
+        `${edit.file} changed since the edit was proposed and ${result.conflicts} hunk(s) ` +
+        `conflict with your changes; file left untouched`
+      );
+    }
+
+    const resolutions: ConflictResolution[] = [];
+    let index = 0;
+    for (const hunk of result.hunks) {
+      if (hunk.kind !== 'conflict') continue;
+      const resolution = await options.resolveConflict(edit.file, hunk, ++index, result.conflicts);
+      if (!resolution) {
+        throw new Error(`Conflict resolution cancelled; ${edit.file} left untouched`);
+      }
+      resolutions.push(resolution);
+    }
+
+    return { content: resolveMerge(result, resolutions), conflictsResolved: resolutions.length };
+  }
+
   /**
    * Handle file deletion
    */
   private async handleDelete(filePath: string, edit: Edit): Promise<EditResult> {
     // Read and backup before delete
     const originalContent = await fs.readFile(filePath, 'utf-8');
+    if (edit.baseContent !== undefined && originalContent !== edit.baseContent) {
+      throw new Error(`${edit.file} changed since the deletion was proposed; not deleting your changes`);
+    }
     const backupPath = await this.createBackup(filePath, originalContent);
 
     // Delete the file
@@ -8,8 +8,14 @@
 export * from './types.js';
 
 // Core components
-export { EditParser, createEditParser } from './edit-parser.js';
+export { EditParser, createEditParser, EDIT_BLOCK_INSTRUCTIONS } from './edit-parser.js';
 export { FileOperations, createFileOperations } from './file-ops.js';
+export type { ApplyEditOptions } from './file-ops.js';
+export * from './merge.js';
 export { ContextManager, createContextManager } from './context-manager.js';
 export { SessionManager, createSessionManager } from './session-manager.js';
 export { CodeAssistant, createCodeAssistant } from './assistant.js';
+export { PatchEngine, createPatchEngine, PatchError, unifiedDiff, uncommittedFiles, contentHash } from './patch-engine.js';
+export type { Patch, FilePatch, AppliedFile } from './patch-engine.js';
+export { recordChangeSet, listChangeSets, findChangeSet, undoChangeSet } from './changesets.js';
+export type { ChangeSet, ChangeSetFile, ChangeSetConflict, UndoResult, FileChange } from './changesets.js';
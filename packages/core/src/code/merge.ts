@@ -0,0 +1,241 @@
+/**
+ * CV Code - Three-Way Merge
+ *
+ * Line-based diff3 merge used when a file changed on disk after an edit was
+ * proposed. The recorded base is what the AI saw, "ours" is the working copy
+ * and "theirs" is the proposed result; regions changed on only one side merge
+ * cleanly, regions changed on both sides become conflicts.
+ */
+
+/**
+ * A region where the working copy and the proposed edit disagree
+ */
+export interface MergeConflict {
+  base: string[];
+  /** Working copy lines */
+  ours: string[];
+  /** Proposed edit lines */
+  theirs: string[];
+  /** 1-based line in the working copy where the conflict starts */
+  oursLine: number;
+}
+
+export type MergeHunk =
+  | { kind: 'clean'; lines: string[] }
+  | ({ kind: 'conflict' } & MergeConflict);
+
+export interface MergeResult {
+  hunks: MergeHunk[];
+  conflicts: number;
+  /** Merged content, with conflict markers around unresolved hunks */
+  content: string;
+}
+
+export type ConflictResolution = 'ours' | 'theirs' | 'both';
+
+/**
+ * Decides one conflict; null cancels the whole file
+ */
+export type ConflictResolver = (
+  file: string,
+  conflict: MergeConflict,
+  index: number,
+  total: number
+) => Promise<ConflictResolution | null>;
+
+/**
+ * Regions whose sides differ by more than this many inserted and deleted
+ * lines are matched as a whole-region replacement instead of searched
+ */
+export const MAX_EDIT_DISTANCE = 10_000;
+
+/**
+ * For each line of `a`, the index of its match in `b` (or -1), from a
+ * shortest edit script. Myers' O(ND) diff with linear-space bisection, so
+ * two large files that differ in a few places cost little time or memory.
+ */
+export function matchLines(a: string[], b: string[]): number[] {
+  const matches = new Array<number>(a.length).fill(-1);
+  matchRegion(a, 0, a.length, b, 0, b.length, matches);
+  return matches;
+}
+
+function matchRegion(
+  a: string[], startA: number, endA: number,
+  b: string[], startB: number, endB: number,
+  matches: number[]
+): void {
+  while (startA < endA && startB < endB && a[startA] === b[startB]) {
+    matches[startA++] = startB++;
+  }
+  while (endA > startA && endB > startB && a[endA - 1] === b[endB - 1]) {
+    matches[--endA] = --endB;
+  }
+  if (startA === endA || startB === endB) return;
+
+  const split = bisect(a, startA, endA, b, startB, endB);
+  if (!split) return;
+  const [x, y] = split;
+  matchRegion(a, startA, x, b, startB, y, matches);
+  matchRegion(a, x, endA, b, y, endB, matches);
+}
+
+/**
+ * A point on a shortest edit path through the region, found where the
+ * forward and backward searches meet; null if none lies within
+ * MAX_EDIT_DISTANCE edits or the point would not split the region
+ */
+function bisect(
+  a: string[], startA: number, endA: number,
+  b: string[], startB: number, endB: number
+): [number, number] | null {
+  const n = endA - startA;
+  const m = endB - startB;
+  const maxD = Math.min(Math.ceil((n + m) / 2), Math.ceil(MAX_EDIT_DISTANCE / 2));
+  const offset = maxD + 1;
+  // Furthest x on each diagonal k = x - y, searching forward and backward
+  const forward = new Int32Array(2 * offset + 1).fill(-1);
+  const backward = new Int32Array(2 * offset + 1).fill(-1);
+  forward[offset + 1] = 0;
+  backward[offset + 1] = 0;
+  const delta = n - m;
+  const odd = delta % 2 !== 0;
+
+  const meet = (x: number, y: number): [number, number] | null =>
+    (x === 0 && y === 0) || (x === n && y === m) ? null : [startA + x, startB + y];
+
+  // Diagonals that ran off the edge of the region are not searched again
+  let kStart = 0;
+  let kEnd = 0;
+  let rStart = 0;
+  let rEnd = 0;
+  for (let d = 0; d <= maxD; d++) {
+    for (let k = -d + kStart; k <= d - kEnd; k += 2) {
+      let x = k === -d || (k !== d && forward[offset + k - 1] < forward[offset + k + 1])
+        ? forward[offset + k + 1]
+        : forward[offset + k - 1] + 1;
+      let y = x - k;
+      while (x < n && y < m && a[startA + x] === b[startB + y]) {
+        x++;
+        y++;
+      }
+      forward[offset + k] = x;
+      if (x > n) {
+        kEnd += 2;
+      } else if (y > m) {
+        kStart += 2;
+      } else if (odd) {
+        const r = offset + delta - k;
+        if (r >= 0 && r < backward.length && backward[r] !== -1 && x >= n - backward[r]) {
+          return meet(x, y);
+        }
+      }
+    }
+
+    for (let k = -d + rStart; k <= d - rEnd; k += 2) {
+      let x = k === -d || (k !== d && backward[offset + k - 1] < backward[offset + k + 1])
+        ? backward[offset + k + 1]
+        : backward[offset + k - 1] + 1;
+      let y = x - k;
+      while (x < n && y < m && a[endA - x - 1] === b[endB - y - 1]) {
+        x++;
+        y++;
+      }
+      backward[offset + k] = x;
+      if (x > n) {
+        rEnd += 2;
+      } else if (y > m) {
+        rStart += 2;
+      } else if (!odd) {
+        const f = offset + delta - k;
+        if (f >= 0 && f < forward.length && forward[f] !== -1 && forward[f] >= n - x) {
+          const fx = forward[f];
+          return meet(fx, fx - (f - offset));
+        }
+      }
+    }
+  }
+  return null;
+}
+
+function sameLines(a: string[], b: string[]): boolean {
+  return a.length === b.length && a.every((line, i) => line === b[i]);
+}
+
+function conflictMarkers(conflict: MergeConflict): string[] {
+  return ['<<<<<<< working copy', ...conflict.ours, '=======', ...conflict.theirs, '>>>>>>> proposed edit'];
+}
+
+/**
+ * Merge `ours` and `theirs`, both derived from `base`
+ */
+export function merge3(base: string, ours: string, theirs: string): MergeResult {
+  const o = base.split('\n');
+  const a = ours.split('\n');
+  const b = theirs.split('\n');
+  const toA = matchLines(o, a);
+  const toB = matchLines(o, b);
+
+  const hunks: MergeHunk[] = [];
+  const pushClean = (lines: string[]) => {
+    if (lines.length === 0) return;
+    const last = hunks[hunks.length - 1];
+    if (last?.kind === 'clean') last.lines.push(...lines);
+    else hunks.push({ kind: 'clean', lines: [...lines] });
+  };
+
+  let io = 0;
+  let ia = 0;
+  let ib = 0;
+  while (io < o.length || ia < a.length || ib < b.length) {
+    if (io < o.length && toA[io] === ia && toB[io] === ib) {
+      pushClean([o[io]]);
+      io++;
+      ia++;
+      ib++;
+      continue;
+    }
+
+    // Next base line both sides kept ends the unstable region
+    let next = io;
+    while (next < o.length && (toA[next] < 0 || toB[next] < 0)) next++;
+    const endA = next < o.length ? toA[next] : a.length;
+    const endB = next < o.length ? toB[next] : b.length;
+
+    const baseLines = o.slice(io, next);
+    const oursLines = a.slice(ia, endA);
+    const theirsLines = b.slice(ib, endB);
+
+    if (sameLines(oursLines, baseLines)) {
+      pushClean(theirsLines);
+    } else if (sameLines(theirsLines, baseLines) || sameLines(oursLines, theirsLines)) {
+      pushClean(oursLines);
+    } else {
+      hunks.push({ kind: 'conflict', base: baseLines, ours: oursLines, theirs: theirsLines, oursLine: ia + 1 });
+    }
+
+    io = next;
+    ia = endA;
+    ib = endB;
+  }
+
+  const conflicts = hunks.filter(h => h.kind === 'conflict').length;
+  const lines = hunks.flatMap(h => h.kind === 'clean' ? h.lines : conflictMarkers(h));
+  return { hunks, conflicts, content: lines.join('\n') };
+}
+
+/**
+ * Merged content with each conflict resolved in order
+ */
+export function resolveMerge(result: MergeResult, resolutions: ConflictResolution[]): string {
+  let index = 0;
+  const lines = result.hunks.flatMap(hunk => {
+    if (hunk.kind === 'clean') return hunk.lines;
+    const resolution = resolutions[index++];
+    if (resolution === 'ours') return hunk.ours;
+    if (resolution === 'theirs') return hunk.theirs;
+    if (resolution === 'both') return [...hunk.ours, ...hunk.theirs];
+    return conflictMarkers(hunk);
+  });
+  return lines.join('\n');
+}
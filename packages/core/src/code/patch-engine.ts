@@ -0,0 +1,346 @@
+/**
+ * CV Code - Patch Engine
+ *
+ * Turns proposed edits into a patch of whole-file before/after contents,
+ * renders it as a unified diff for preview, and applies it atomically:
+ * every new file content is staged next to its target first, then all are
+ * moved into place, and if any step fails the files already changed are
+ * restored so the working tree ends up exactly as it started.
+ */
+
+import { promises as fs } from 'fs';
+import * as path from 'path';
+import * as crypto from 'crypto';
+import type { WorkingTreeStatus } from '@cv-git/shared';
+import { Edit } from './types.js';
+import { FileOperations } from './file-ops.js';
+import { matchLines } from './merge.js';
+import { restoreRedactedOrThrow } from '../security/redact.js';
+
+export interface FilePatch {
+  /** Workspace-relative path */
+  file: string;
+  /** Content before the patch; null if the patch creates the file */
+  before: string | null;
+  /** Content after the patch; null if the patch deletes the file */
+  after: string | null;
+  /** Unified diff of before → after */
+  diff: string;
+}
+
+export interface Patch {
+  files: FilePatch[];
+}
+
+export interface AppliedFile {
+  file: string;
+  /** sha256 of the content before/after, null where the file did not exist */
+  beforeHash: string | null;
+  afterHash: string | null;
+}
+
+export class PatchError extends Error {
+  constructor(message: string, public readonly files: string[] = []) {
+    super(message);
+    this.name = 'PatchError';
+  }
+}
+
+export function contentHash(content: string | null): string | null {
+  return content === null ? null : crypto.createHash('sha256').update(content).digest('hex');
+}
+
+function splitLines(content: string): string[] {
+  if (content === '') return [];
+  const lines = content.split('\n');
+  if (lines[lines.length - 1] === '') lines.pop();
+  return lines;
+}
+
+function hunkRange(start: number, count: number): string {
+  // Unified diff numbers an empty range by the line before it
+  const first = count === 0 ? start : start + 1;
+  return count === 1 ? `${first}` : `${first},${count}`;
+}
+
+/**
+ * Unified diff between two versions of a file (null for a missing file)
+ */
+export function unifiedDiff(file: string, before: string | null, after: string | null, context: number = 3): string {
+  const a = splitLines(before ?? '');
+  const b = splitLines(after ?? '');
+  const matches = matchLines(a, b);
+
+  // Edit script: ' ' keep, '-' remove, '+' add, with positions in a and b
+  const ops: Array<{ op: ' ' | '-' | '+'; line: string; i: number; j: number }> = [];
+  let j = 0;
+  for (let i = 0; i < a.length; i++) {
+    if (matches[i] === -1) {
+      ops.push({ op: '-', line: a[i], i, j });
+      continue;
+    }
+    while (j < matches[i]) {
+      ops.push({ op: '+', line: b[j], i, j });
+      j++;
+    }
+    ops.push({ op: ' ', line: a[i], i, j });
+    j++;
+  }
+  while (j < b.length) {
+    ops.push({ op: '+', line: b[j], i: a.length, j });
+    j++;
+  }
+
+  const header = [
+    `--- ${before === null ? '/dev/null' : `a/${file}`}`,
+    `+++ ${after === null ? '/dev/null' : `b/${file}`}`
+  ];
+  const changed = ops.map((o, k) => (o.op === ' ' ? -1 : k)).filter(k => k !== -1);
+  if (changed.length === 0) return '';
+
+  const out = [...header];
+  let k = 0;
+  while (k < changed.length) {
+    // Grow the hunk while the next change is within two contexts' reach
+    let end = k;
+    while (end + 1 < changed.length && changed[end + 1] - changed[end] <= context * 2) end++;
+    const from = Math.max(0, changed[k] - context);
+    const to = Math.min(ops.length - 1, changed[end] + context);
+    const slice = ops.slice(from, to + 1);
+
+    const oldCount = slice.filter(o => o.op !== '+').length;
+    const newCount = slice.filter(o => o.op !== '-').length;
+    out.push(`@@ -${hunkRange(slice[0].i, oldCount)} +${hunkRange(slice[0].j, newCount)} @@`);
+    for (const o of slice) out.push(`${o.op}${o.line}`);
+    k = end + 1;
+  }
+
+  return out.join('\n') + '\n';
+}
+
+/**
+ * Files in the patch that have uncommitted changes (including untracked files it would overwrite)
+ */
+export function uncommittedFiles(patch: Patch, status: WorkingTreeStatus): string[] {
+  const dirty = new Set([
+    ...status.modified,
+    ...status.added,
+    ...status.deleted,
+    ...status.staged,
+    ...status.untracked,
+    ...status.renamed.flatMap(r => [r.from, r.to])
+  ]);
+  return patch.files.map(f => f.file).filter(file => dirty.has(file));
+}
+
+export class PatchEngine {
+  private fileOps: FileOperations;
+
+  constructor(private repoRoot: string) {
+    this.fileOps = new FileOperations(repoRoot);
+  }
+
+  /**
+   * Resolve a workspace-relative path, refusing anything outside the repository or inside .git
+   */
+  private resolve(file: string): string {
+    const absolute = path.resolve(this.repoRoot, file);
+    const relative = path.relative(this.repoRoot, absolute);
+    if (!relative || relative.startsWith('..') || path.isAbsolute(relative) ||
+        relative.split(path.sep)[0] === '.git') {
+      throw new PatchError(`Refusing to touch ${file}: outside the working tree`, [file]);
+    }
+    return absolute;
+  }
+
+  private async read(file: string): Promise<string | null> {
+    try {
+      return await fs.readFile(this.resolve(file), 'utf-8');
+    } catch (error: any) {
+      if (error.code === 'ENOENT' || error.code === 'ENOTDIR') return null;
+      throw error;
+    }
+  }
+
+  /**
+   * Compute the patch for a set of edits without touching any file.
+   * Fails as a whole if any edit can't be applied to the current content,
+   * or would write a redaction mask the model echoed back in place of a
+   * secret that can't be told apart from the file's others.
+   */
+  async prepare(edits: Edit[]): Promise<Patch> {
+    const before = new Map<string, string | null>();
+    const after = new Map<string, string | null>();
+    const order: string[] = [];
+    const errors: string[] = [];
+
+    const current = async (file: string): Promise<string | null> => {
+      const normalized = path.normalize(file).replace(/\\/g, '/');
+      if (!before.has(normalized)) {
+        const content = await this.read(normalized);
+        before.set(normalized, content);
+        after.set(normalized, content);
+        order.push(normalized);
+      }
+      return after.get(normalized)!;
+    };
+    const set = (file: string, content: string | null) => {
+      after.set(path.normalize(file).replace(/\\/g, '/'), content);
+    };
+
+    for (const edit of edits) {
+      try {
+        const content = await current(edit.file);
+        switch (edit.type) {
+          case 'create':
+            set(edit.file, restoreRedactedOrThrow(edit.newContent ?? '', content ?? ''));
+            break;
+          case 'modify':
+            if (content === null) throw new Error(`${edit.file} does not exist`);
+            set(edit.file, restoreRedactedOrThrow(this.fileOps.buildProposedContent(edit, content), content));
+            break;
+          case 'delete':
+            if (content === null) throw new Error(`${edit.file} does not exist`);
+            set(edit.file, null);
+            break;
+          case 'rename': {
+            if (content === null) throw new Error(`${edit.file} does not exist`);
+            if (!edit.newPath) throw new Error('New path required for rename');
+            if (await current(edit.newPath) !== null) throw new Error(`${edit.newPath} already exists`);
+            set(edit.newPath, content);
+            set(edit.file, null);
+            break;
+          }
+        }
+      } catch (error: any) {
+        errors.push(error instanceof PatchError ? error.message : `${edit.file}: ${error.message}`);
+      }
+    }
+
+    if (errors.length > 0) {
+      throw new PatchError(`Cannot build patch:\n  ${errors.join('\n  ')}`, edits.map(e => e.file));
+    }
+
+    const files: FilePatch[] = [];
+    for (const file of order) {
+      const b = before.get(file)!;
+      const a = after.get(file)!;
+      if (a === b) continue;
+      files.push({ file, before: b, after: a, diff: unifiedDiff(file, b, a) });
+    }
+    return { files };
+  }
+
+  /**
+   * Apply the patch all-or-nothing. Refuses if any file changed since the
+   * patch was prepared; on failure, restores every file it already changed.
+   */
+  async apply(patch: Patch): Promise<AppliedFile[]> {
+    // Nothing may have moved underneath the preview
+    const drifted: string[] = [];
+    for (const f of patch.files) {
+      if (await this.read(f.file) !== f.before) drifted.push(f.file);
+    }
+    if (drifted.length > 0) {
+      throw new PatchError(`Files changed since the patch was prepared: ${drifted.join(', ')}`, drifted);
+    }
+
+    const suffix = `.cv-patch-${process.pid}-${crypto.randomBytes(4).toString('hex')}`;
+    const staged: Array<{ patch: FilePatch; target: string; temp?: string }> = [];
+    const createdDirs: string[] = [];
+    const done: typeof staged = [];
+
+    const cleanup = async () => {
+      for (const s of staged) {
+        if (s.temp) await fs.rm(s.temp, { force: true }).catch(() => {});
+      }
+    };
+
+    try {
+      // Stage: write every new content next to its target
+      for (const f of patch.files) {
+        const target = this.resolve(f.file);
+        const entry: { patch: FilePatch; target: string; temp?: string } = { patch: f, target };
+        staged.push(entry);
+        if (f.after === null) continue;
+        createdDirs.push(...await mkdirp(path.dirname(target)));
+        entry.temp = target + suffix;
+        await fs.writeFile(entry.temp, f.after);
+        if (f.before !== null) {
+          // Keep the file mode of what we're replacing
+          const { mode } = await fs.stat(target);
+          await fs.chmod(entry.temp, mode);
+        }
+      }
+
+      // Commit: move staged content into place, deletions last
+      const ordered = [...staged.filter(s => s.temp), ...staged.filter(s => !s.temp)];
+      for (const s of ordered) {
+        if (s.temp) {
+          await fs.rename(s.temp, s.target);
+        } else {
+          await fs.unlink(s.target);
+        }
+        done.push(s);
+      }
+    } catch (error: any) {
+      // Roll back whatever was already moved into place
+      const failures: string[] = [];
+      for (const s of done.reverse()) {
+        try {
+          if (s.patch.before === null) {
+            await fs.rm(s.target, { force: true });
+          } else {
+            await fs.writeFile(s.target, s.patch.before);
+          }
+        } catch {
+          failures.push(s.patch.file);
+        }
+      }
+      await cleanup();
+      for (const dir of createdDirs.reverse()) {
+        await fs.rmdir(dir).catch(() => {});
+      }
+      const restored = failures.length > 0
+        ? `; could not restore ${failures.join(', ')}`
+        : '; no files were changed';
+      throw new PatchError(`Failed to apply patch: ${error.message}${restored}`, failures);
+    }
+
+    return patch.files.map(f => ({
+      file: f.file,
+      beforeHash: contentHash(f.before),
+      afterHash: contentHash(f.after)
+    }));
+  }
+}
+
+/**
+ * mkdir -p that reports which directories it created, outermost first
+ */
+async function mkdirp(dir: string): Promise<string[]> {
+  const missing: string[] = [];
+  let current = dir;
+  while (true) {
+    try {
+      const stat = await fs.stat(current);
+      if (!stat.isDirectory()) throw new Error(`${current} is not a directory`);
+      break;
+    } catch (error: any) {
+      if (error.code !== 'ENOENT') throw error;
+      missing.unshift(current);
+      const parent = path.dirname(current);
+      if (parent === current) break;
+      current = parent;
+    }
+  }
+  for (const d of missing) await fs.mkdir(d);
+  return missing;
+}
+
+/**
+ * Create a PatchEngine instance
+ */
+export function createPatchEngine(repoRoot: string): PatchEngine {
+  return new PatchEngine(repoRoot);
+}
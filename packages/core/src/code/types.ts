@@ -152,6 +152,9 @@ export interface Edit {
   /** Original content (for modifications) */
   originalContent?: string;
 
+  /** File content when the edit was proposed; the base for three-way merges */
+  baseContent?: string;
+
   /** New content (for create/full replace) */
   newContent?: string;
 
@@ -202,6 +205,12 @@ export interface EditResult {
 
   /** Timestamp of application */
   appliedAt?: number;
+
+  /** True when the edit was merged into a working copy that changed after it was proposed */
+  merged?: boolean;
+
+  /** Conflicts resolved while merging */
+  conflictsResolved?: number;
 }
 
 // ============================================================================
@@ -7,6 +7,9 @@ import * as fs from 'fs/promises';
 import { CVConfig, ConfigError } from '@cv-git/shared';
 import { getCVDir, ensureDir, loadSharedCredentials } from '@cv-git/shared';
 import { generateRepoId, getGraphDatabaseName } from '../storage/repo-id.js';
+import { configureProviderLimits } from '../ai/http.js';
+import { configureRedaction } from '../security/redact.js';
+import { loadRepoConfig, resolveConfigLayers, omitRepoValues, ConfigSource } from './repo-config.js';
 
 // Re-export service URL utilities
 export * from './service-urls.js';
@@ -127,6 +130,9 @@ const DEFAULT_CONFIG: CVConfig = {
 export class ConfigManager {
   private config: CVConfig | null = null;
   private configPath: string | null = null;
+  /** Settings from .cvgit.toml, kept out of .cv/config.json on save */
+  private repoConfig: Partial<CVConfig> = {};
+  private sources: Record<string, ConfigSource> = {};
 
   /**
    * Initialize configuration for a repository
@@ -171,9 +177,15 @@ export class ConfigManager {
       const data = await fs.readFile(configPath, 'utf-8');
       const config = JSON.parse(data) as CVConfig;
 
-      // Merge with defaults to handle missing fields
-      this.config = this.mergeWithDefaults(config);
+      // Defaults, then the team's .cvgit.toml, then this user's changes
+      const repoConfig = await loadRepoConfig(repoRoot);
+      const resolved = resolveConfigLayers(DEFAULT_CONFIG, repoConfig, config);
+      this.config = resolved.config;
+      this.sources = resolved.sources;
+      this.repoConfig = repoConfig;
       this.configPath = configPath;
+      configureProviderLimits(this.config.rateLimits);
+      configureRedaction(this.config.redaction);
 
       // Auto-migrate legacy configs that use hardcoded 'cv-git' database
       if (this.config.graph.database === 'cv-git') {
@@ -193,10 +205,31 @@ export class ConfigManager {
       if (error.code === 'ENOENT') {
         throw new ConfigError(`CV-Git not initialized in ${repoRoot}. Run 'cv init' first.`);
       }
+      if (error instanceof ConfigError) throw error;
       throw new ConfigError(`Failed to load config: ${error.message}`, error);
     }
   }
 
+  /**
+   * Resolved configuration of another repository (e.g. a workspace member)
+   * without making it the loaded one
+   */
+  async read(repoRoot: string): Promise<CVConfig> {
+    const configPath = path.join(getCVDir(repoRoot), 'config.json');
+    let config: CVConfig;
+    try {
+      config = JSON.parse(await fs.readFile(configPath, 'utf-8')) as CVConfig;
+    } catch (error: any) {
+      if (error.code === 'ENOENT') {
+        throw new ConfigError(`CV-Git not initialized in ${repoRoot}. Run 'cv init' first.`);
+      }
+      throw new ConfigError(`Failed to load config: ${error.message}`, error);
+    }
+    const resolved = resolveConfigLayers(DEFAULT_CONFIG, await loadRepoConfig(repoRoot), config).config;
+    resolved.repository.repoId ??= generateRepoId(repoRoot);
+    return resolved;
+  }
+
   /**
    * Save configuration to disk
    */
@@ -205,7 +238,7 @@ export class ConfigManager {
       throw new ConfigError('No configuration loaded');
     }
 
-    await fs.writeFile(this.configPath, JSON.stringify(this.config, null, 2));
+    await fs.writeFile(this.configPath, JSON.stringify(omitRepoValues(this.config, this.repoConfig), null, 2));
   }
 
   /**
@@ -218,6 +251,14 @@ export class ConfigManager {
     return this.config;
   }
 
+  /**
+   * Loaded configuration with where each setting came from
+   * (built-in default, .cvgit.toml or .cv/config.json)
+   */
+  getResolved(): { config: CVConfig; sources: Record<string, ConfigSource>; repoConfig: Partial<CVConfig> } {
+    return { config: this.get(), sources: this.sources, repoConfig: this.repoConfig };
+  }
+
   /**
    * Update configuration
    */
@@ -299,13 +340,6 @@ export class ConfigManager {
     throw new ConfigError(`Unknown service: ${service}`);
   }
 
-  /**
-   * Merge configuration with defaults
-   */
-  private mergeWithDefaults(config: Partial<CVConfig>): CVConfig {
-    return this.deepMerge(DEFAULT_CONFIG, config) as CVConfig;
-  }
-
   /**
    * Deep merge two objects
    */
@@ -327,6 +361,23 @@ export class ConfigManager {
 // Export singleton instance
 export const configManager = new ConfigManager();
 
+// Re-export repository configuration (.cvgit.toml)
+export {
+  REPO_CONFIG_FILE,
+  REPO_CONFIG_SCHEMA,
+  readRepoConfigFile,
+  parseRepoConfig,
+  loadRepoConfig,
+  validateRepoConfig,
+  formatRepoConfigIssues,
+  resolveConfigLayers,
+  omitRepoValues,
+  flattenConfig,
+  type RepoConfigIssue,
+  type ConfigSource,
+  type ResolvedConfig
+} from './repo-config.js';
+
 // Re-export privilege configuration utilities
 export {
   type PrivilegeMode,
@@ -0,0 +1,467 @@
+/**
+ * Repository configuration (.cvgit.toml)
+ *
+ * Settings committed with the repository, shared by everyone who clones it.
+ * They are validated against REPO_CONFIG_SCHEMA and layered between the
+ * built-in defaults and each user's `.cv/config.json`.
+ */
+
+import * as path from 'path';
+import * as fs from 'fs/promises';
+import { CVConfig, ConfigError } from '@cv-git/shared';
+import { parse as parseToml, TomlError } from 'smol-toml';
+
+export const REPO_CONFIG_FILE = '.cvgit.toml';
+
+/**
+ * Read and parse `.cvgit.toml` from the repository root; a missing file is empty
+ */
+export async function readRepoConfigFile(repoRoot: string, lines?: Map<string, number>): Promise<Record<string, any>> {
+  const file = path.join(repoRoot, REPO_CONFIG_FILE);
+  let content: string;
+  try {
+    content = await fs.readFile(file, 'utf-8');
+  } catch (error: any) {
+    if (error.code === 'ENOENT') return {};
+    throw new ConfigError(`Cannot read ${REPO_CONFIG_FILE}: ${error.message}`, error);
+  }
+
+  try {
+    return parseRepoConfig(content, lines);
+  } catch (error: any) {
+    if (error instanceof TomlError) {
+      const message = error.message.split('\n')[0].replace(/^Invalid TOML document: /, '');
+      throw new ConfigError(`${REPO_CONFIG_FILE}:${error.line}: ${message}`, error);
+    }
+    throw error;
+  }
+}
+
+/**
+ * Parse `.cvgit.toml` content. `lines`, when given, receives the line of
+ * each table header and key (`sync.chunking.strategy` -> 12).
+ */
+export function parseRepoConfig(content: string, lines?: Map<string, number>): Record<string, any> {
+  const doc = parseToml(content);
+  if (lines) keyLines(content, lines);
+  return doc;
+}
+
+const KEY_PART = /[A-Za-z0-9_-]+|"((?:[^"\\]|\\.)*)"|'([^']*)'/g;
+const KEY = String.raw`(?:[A-Za-z0-9_-]+|"(?:[^"\\]|\\.)*"|'[^']*')`;
+const HEADER_LINE = new RegExp(String.raw`^\s*\[\[?\s*(${KEY}(?:\s*\.\s*${KEY})*)\s*\]\]?\s*(?:#.*)?$`);
+const KEY_LINE = new RegExp(String.raw`^\s*(${KEY}(?:\s*\.\s*${KEY})*)\s*=`);
+
+function splitKey(key: string): string[] {
+  return [...key.matchAll(KEY_PART)].map(([part, basic, literal]) =>
+    basic !== undefined ? JSON.parse(`"${basic}"`) : literal ?? part
+  );
+}
+
+/**
+ * Record where each table header and key is defined, skipping the inside of
+ * multi-line strings. The document already parsed, so this only has to
+ * find keys, not check them.
+ */
+function keyLines(content: string, lines: Map<string, number>): void {
+  let table: string[] = [];
+  let inString: string | null = null;
+
+  content.split('\n').forEach((text, i) => {
+    const line = i + 1;
+    if (!inString) {
+      const header = HEADER_LINE.exec(text);
+      const key = header ? null : KEY_LINE.exec(text);
+      if (header) table = splitKey(header[1]);
+      const keys = header ? table : key ? [...table, ...splitKey(key[1])] : null;
+      if (keys && !lines.has(keys.join('.'))) lines.set(keys.join('.'), line);
+    }
+    for (const quote of text.match(/"""|'''/g) ?? []) {
+      if (!inString) inString = quote;
+      else if (inString === quote) inString = null;
+    }
+  });
+}
+
+// ========== Schema ==========
+
+type FieldSpec =
+  | { type: 'string' | 'boolean' | 'strings' | 'regex' | 'regexes' | 'chain' }
+  | { type: 'integer' | 'number'; min?: number; max?: number }
+  | { type: 'enum'; values: readonly (string | number)[] }
+  | { type: 'table'; fields: Record<string, FieldSpec> }
+  /** Table with keys of your choosing, e.g. [rateLimits.openai] */
+  | { type: 'map'; value: FieldSpec };
+
+const str: FieldSpec = { type: 'string' };
+const bool: FieldSpec = { type: 'boolean' };
+const strings: FieldSpec = { type: 'strings' };
+const int = (min: number, max?: number): FieldSpec => ({ type: 'integer', min, max });
+const num = (min: number, max?: number): FieldSpec => ({ type: 'number', min, max });
+const oneOf = (...values: Array<string | number>): FieldSpec => ({ type: 'enum', values });
+const table = (fields: Record<string, FieldSpec>): FieldSpec => ({ type: 'table', fields });
+
+/**
+ * Settings a team can share in `.cvgit.toml`. Keys mirror `.cv/config.json`;
+ * machine-specific settings (service URLs, database names) and API keys
+ * stay out.
+ */
+export const REPO_CONFIG_SCHEMA: Record<string, FieldSpec> = {
+  ai: table({
+    provider: str,
+    model: str,
+    maxTokens: int(1),
+    temperature: num(0, 2),
+    fallback: { type: 'chain' },
+  }),
+  embedding: table({
+    provider: oneOf('openrouter', 'openai', 'ollama', 'lmstudio', 'azure', 'local'),
+    model: str,
+    dimensions: int(1),
+  }),
+  sync: table({
+    autoSync: bool,
+    syncOnCommit: bool,
+    excludePatterns: strings,
+    includeLanguages: strings,
+    chunking: table({
+      strategy: oneOf('function', 'class', 'sliding-window'),
+      windowLines: int(1),
+      overlapLines: int(0),
+    }),
+  }),
+  retrieval: table({
+    minScore: num(0, 1),
+    minScorePercentile: num(0, 100),
+    topK: int(1),
+    rerank: bool,
+    hybrid: bool,
+    contextTokens: int(1),
+  }),
+  rerank: table({
+    provider: oneOf('none', 'local', 'cohere', 'jina', 'voyage'),
+    model: str,
+    url: str,
+    candidates: int(1),
+  }),
+  docs: table({
+    enabled: bool,
+    patterns: strings,
+    excludePatterns: strings,
+    chunkByHeading: oneOf(1, 2, 3),
+    inferTypes: bool,
+  }),
+  review: table({
+    policies: str,
+  }),
+  workspace: table({
+    repos: strings,
+  }),
+  deadcode: table({
+    allow: strings,
+  }),
+  test: table({
+    command: str,
+  }),
+  docgen: table({
+    ignore: strings,
+  }),
+  security: table({
+    ignore: strings,
+  }),
+  redaction: table({
+    enabled: bool,
+    entropy: bool,
+    emails: bool,
+    local: bool,
+    patterns: { type: 'map', value: { type: 'regex' } },
+    allow: { type: 'regexes' },
+  }),
+  rateLimits: {
+    type: 'map',
+    value: table({
+      requestsPerMinute: int(0),
+      maxConcurrent: int(1),
+      maxRetries: int(0),
+      baseDelayMs: int(0),
+      maxDelayMs: int(0),
+    }),
+  },
+  // Read by the agent sandbox (sandbox/index.ts), not part of CVConfig
+  sandbox: table({
+    allow: strings,
+    env: strings,
+    network: bool,
+    timeout: num(Number.MIN_VALUE),
+  }),
+};
+
+/** Tables that are not part of CVConfig */
+const NON_CONFIG_TABLES = new Set(['sandbox']);
+
+export interface RepoConfigIssue {
+  /** Dotted setting, e.g. `sync.chunking.strategy` */
+  path: string;
+  line?: number;
+  message: string;
+}
+
+/**
+ * Check a parsed `.cvgit.toml` against the schema. `lines` (from parseRepoConfig)
+ * locates each problem.
+ */
+export function validateRepoConfig(doc: Record<string, any>, lines?: Map<string, number>): RepoConfigIssue[] {
+  const issues: RepoConfigIssue[] = [];
+  const report = (keys: string[], message: string) => {
+    const path = keys.join('.');
+    issues.push({ path, line: lineOf(keys, lines), message });
+  };
+
+  const check = (value: unknown, spec: FieldSpec, keys: string[]): void => {
+    const name = keys.join('.');
+
+    if (spec.type === 'table' || spec.type === 'map') {
+      if (!isPlainObject(value)) {
+        report(keys, `[${name}] must be a table (got ${describeValue(value)})`);
+        return;
+      }
+      for (const [key, child] of Object.entries(value)) {
+        const childKeys = [...keys, key];
+        if (/^api[_-]?key$/i.test(key)) {
+          report(childKeys, `${childKeys.join('.')} must not be committed; use \`cv auth setup\` or an environment variable`);
+        } else if (spec.type === 'map') {
+          check(child, spec.value, childKeys);
+        } else if (spec.fields[key]) {
+          check(child, spec.fields[key], childKeys);
+        } else {
+          report(childKeys, unknownSetting(childKeys, Object.keys(spec.fields)));
+        }
+      }
+      return;
+    }
+
+    const problem = checkValue(value, spec);
+    if (problem) {
+      // A regex error already says what is wrong with the value
+      const got = problem.startsWith('a valid regular expression') ? '' : ` (got ${describeValue(value)})`;
+      report(keys, `${name} must be ${problem}${got}`);
+    }
+  };
+
+  for (const [key, value] of Object.entries(doc)) {
+    const spec = REPO_CONFIG_SCHEMA[key];
+    if (spec) check(value, spec, [key]);
+    else report([key], unknownSetting([key], Object.keys(REPO_CONFIG_SCHEMA)));
+  }
+
+  return issues.sort((a, b) => (a.line ?? 0) - (b.line ?? 0));
+}
+
+/**
+ * What a value should be, or undefined when it is valid
+ */
+function checkValue(value: unknown, spec: FieldSpec): string | undefined {
+  switch (spec.type) {
+    case 'string':
+      return typeof value === 'string' ? undefined : 'a string';
+    case 'boolean':
+      return typeof value === 'boolean' ? undefined : 'true or false';
+    case 'strings':
+      return Array.isArray(value) && value.every(v => typeof v === 'string') ? undefined : 'an array of strings';
+    case 'chain':
+      return typeof value === 'string' || (Array.isArray(value) && value.every(v => typeof v === 'string'))
+        ? undefined
+        : 'a model chain ("a -> b") or an array of models';
+    case 'regex':
+    case 'regexes': {
+      const list = spec.type === 'regex' ? [value] : value;
+      if (!Array.isArray(list) || list.some(v => typeof v !== 'string')) {
+        return spec.type === 'regex' ? 'a regular expression string' : 'an array of regular expression strings';
+      }
+      for (const source of list as string[]) {
+        try {
+          new RegExp(source);
+        } catch (error: any) {
+          return `a valid regular expression: ${error.message}`;
+        }
+      }
+      return undefined;
+    }
+    case 'integer':
+    case 'number': {
+      const ok = typeof value === 'number' && Number.isFinite(value) &&
+        (spec.type === 'number' || Number.isInteger(value)) &&
+        (spec.min === undefined || value >= spec.min) &&
+        (spec.max === undefined || value <= spec.max);
+      if (ok) return undefined;
+      const kind = spec.type === 'integer' ? 'an integer' : 'a number';
+      if (spec.min === Number.MIN_VALUE) return `a positive ${spec.type}`;
+      if (spec.max !== undefined) return `${kind} from ${spec.min} to ${spec.max}`;
+      return spec.min !== undefined ? `${kind} of at least ${spec.min}` : kind;
+    }
+    case 'enum':
+      return spec.values.includes(value as string | number)
+        ? undefined
+        : `one of ${spec.values.map(v => JSON.stringify(v)).join(', ')}`;
+    default:
+      return undefined;
+  }
+}
+
+function unknownSetting(keys: string[], known: string[]): string {
+  const key = keys[keys.length - 1];
+  const suggestion = known.find(k => k.toLowerCase() === key.toLowerCase().replace(/[_-]/g, '')) ??
+    known.find(k => editDistance(k.toLowerCase(), key.toLowerCase()) <= 2);
+  const parent = keys.slice(0, -1).join('.');
+  const hint = suggestion ? `; did you mean "${[...keys.slice(0, -1), suggestion].join('.')}"?` : '';
+  return `unknown setting "${keys.join('.')}"${parent ? ` in [${parent}]` : ''}${hint}`;
+}
+
+function editDistance(a: string, b: string): number {
+  const row = Array.from({ length: b.length + 1 }, (_, i) => i);
+  for (let i = 1; i <= a.length; i++) {
+    let prev = row[0];
+    row[0] = i;
+    for (let j = 1; j <= b.length; j++) {
+      const next = row[j];
+      row[j] = Math.min(row[j] + 1, row[j - 1] + 1, prev + (a[i - 1] === b[j - 1] ? 0 : 1));
+      prev = next;
+    }
+  }
+  return row[b.length];
+}
+
+/** Line of a key, or of the nearest enclosing table (inline tables share their parent's line) */
+function lineOf(keys: string[], lines?: Map<string, number>): number | undefined {
+  for (let n = keys.length; n > 0; n--) {
+    const line = lines?.get(keys.slice(0, n).join('.'));
+    if (line !== undefined) return line;
+  }
+  return undefined;
+}
+
+function describeValue(value: unknown): string {
+  if (Array.isArray(value)) return 'an array';
+  if (isPlainObject(value)) return 'a table';
+  return typeof value === 'string' ? JSON.stringify(value) : String(value);
+}
+
+function isPlainObject(value: unknown): value is Record<string, any> {
+  return typeof value === 'object' && value !== null && !Array.isArray(value);
+}
+
+/**
+ * Issues as one message, one `file:line: problem` per line
+ */
+export function formatRepoConfigIssues(issues: RepoConfigIssue[]): string {
+  return issues
+    .map(issue => `${REPO_CONFIG_FILE}${issue.line !== undefined ? `:${issue.line}` : ''}: ${issue.message}`)
+    .join('\n');
+}
+
+/**
+ * Validated `.cvgit.toml` settings in CVConfig form (without [sandbox]);
+ * throws a ConfigError listing every problem
+ */
+export async function loadRepoConfig(repoRoot: string): Promise<Partial<CVConfig>> {
+  const lines = new Map<string, number>();
+  const doc = await readRepoConfigFile(repoRoot, lines);
+  const issues = validateRepoConfig(doc, lines);
+  if (issues.length > 0) {
+    throw new ConfigError(`Invalid ${REPO_CONFIG_FILE}:\n${formatRepoConfigIssues(issues)}`, issues);
+  }
+  return Object.fromEntries(Object.entries(doc).filter(([key]) => !NON_CONFIG_TABLES.has(key))) as Partial<CVConfig>;
+}
+
+// ========== Layering ==========
+
+/** Where a resolved setting came from */
+export type ConfigSource = 'default' | 'repo' | 'local';
+
+export interface ResolvedConfig<T> {
+  config: T;
+  /** Source of every leaf setting, by dotted path */
+  sources: Record<string, ConfigSource>;
+}
+
+/**
+ * Merge built-in defaults, `.cvgit.toml` and `.cv/config.json`.
+ *
+ * `.cv/config.json` is written with every default filled in, so a value
+ * there only overrides the repository file when it differs from the
+ * built-in default; everything else the team sets applies.
+ */
+export function resolveConfigLayers<T extends object>(
+  defaults: T,
+  repo: Record<string, any>,
+  local: Record<string, any>
+): ResolvedConfig<T> {
+  const defaultLeaves = flattenConfig(defaults);
+  const repoLeaves = flattenConfig(repo);
+  const localLeaves = flattenConfig(local);
+
+  const overrides: Record<string, any> = {};
+  for (const [path, value] of Object.entries(localLeaves)) {
+    if (path in defaultLeaves && sameValue(defaultLeaves[path], value)) continue;
+    setPath(overrides, path, value);
+  }
+
+  const config = mergeLayers(mergeLayers(defaults, repo), overrides);
+
+  const overrideLeaves = flattenConfig(overrides);
+  const sources: Record<string, ConfigSource> = {};
+  for (const path of Object.keys(flattenConfig(config))) {
+    sources[path] = path in overrideLeaves ? 'local' : path in repoLeaves ? 'repo' : 'default';
+  }
+  return { config, sources };
+}
+
+/**
+ * A copy of `config` without the settings that still equal the repository
+ * file's, so saving `.cv/config.json` doesn't pin the team's values
+ */
+export function omitRepoValues<T extends object>(config: T, repo: Record<string, any>): T {
+  const copy = JSON.parse(JSON.stringify(config));
+  for (const [path, value] of Object.entries(flattenConfig(repo))) {
+    const keys = path.split('.');
+    const parent = keys.slice(0, -1).reduce<any>((node, key) => node?.[key], copy);
+    const last = keys[keys.length - 1];
+    if (parent && sameValue(parent[last], value)) delete parent[last];
+  }
+  return copy;
+}
+
+/**
+ * Leaf settings by dotted path; arrays are leaves
+ */
+export function flattenConfig(value: Record<string, any>, prefix = ''): Record<string, any> {
+  const leaves: Record<string, any> = {};
+  for (const [key, child] of Object.entries(value)) {
+    const path = prefix ? `${prefix}.${key}` : key;
+    if (isPlainObject(child)) Object.assign(leaves, flattenConfig(child, path));
+    else if (child !== undefined) leaves[path] = child;
+  }
+  return leaves;
+}
+
+function mergeLayers(target: any, source: any): any {
+  const result = { ...target };
+  for (const key in source) {
+    result[key] = isPlainObject(source[key]) ? mergeLayers(target?.[key] || {}, source[key]) : source[key];
+  }
+  return result;
+}
+
+function setPath(target: Record<string, any>, path: string, value: unknown): void {
+  const keys = path.split('.');
+  let node = target;
+  for (const key of keys.slice(0, -1)) {
+    if (!isPlainObject(node[key])) node[key] = {};
+    node = node[key];
+  }
+  node[keys[keys.length - 1]] = value;
+}
+
+function sameValue(a: unknown, b: unknown): boolean {
+  return JSON.stringify(a) === JSON.stringify(b);
+}
@@ -13,6 +13,7 @@ const DEFAULT_URLS = {
   qdrant: 'http://localhost:6333',
   ollama: 'http://localhost:11434',
   lmstudio: 'http://localhost:1234/v1',
+  gemini: 'https://generativelanguage.googleapis.com/v1beta',
 } as const;
 
 /**
@@ -115,6 +116,26 @@ export function getLMStudioUrl(configUrl?: string): string {
   return DEFAULT_URLS.lmstudio;
 }
 
+/**
+ * Get the Gemini (Generative Language) API URL
+ *
+ * Priority: CV_GEMINI_URL env var > config > default
+ *
+ * @param configUrl - URL from config file (optional)
+ */
+export function getGeminiUrl(configUrl?: string): string {
+  const envUrl = process.env.CV_GEMINI_URL;
+  if (envUrl) {
+    return envUrl.replace(/\/$/, '');
+  }
+
+  if (configUrl) {
+    return configUrl.replace(/\/$/, '');
+  }
+
+  return DEFAULT_URLS.gemini;
+}
+
 /**
  * Get all service URLs
  *
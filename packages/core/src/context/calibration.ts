@@ -0,0 +1,142 @@
+/**
+ * Retrieval Calibration
+ *
+ * Turns a handful of relevance judgments ("was this result useful for this
+ * query?") into retrieval settings for the repository: the similarity cut-off
+ * (minScore), how many chunks to keep (topK), and whether usage re-ranking
+ * helps. Scores from different embedding models sit in very different ranges,
+ * so a fixed minScore is wrong for most repositories; measuring beats guessing.
+ */
+
+export interface JudgedResult {
+  score: number;
+  relevant: boolean;
+}
+
+export interface QueryJudgment {
+  query: string;
+  /** Results in the order they were ranked */
+  results: JudgedResult[];
+}
+
+export interface RetrievalSettings {
+  minScore: number;
+  topK: number;
+  rerank: boolean;
+}
+
+export interface CalibrationResult extends RetrievalSettings {
+  /** Share of kept results that were relevant */
+  precision: number;
+  /** Share of relevant results that were kept */
+  recall: number;
+  f1: number;
+  /** Number of judged results */
+  judged: number;
+  /** Number of results judged relevant */
+  relevant: number;
+}
+
+export const DEFAULT_RETRIEVAL_SETTINGS: RetrievalSettings = {
+  minScore: 0.25,
+  topK: 10,
+  rerank: true
+};
+
+const MIN_TOP_K = 3;
+const MAX_TOP_K = 20;
+
+/** Share of queries whose relevant results must fit within topK */
+const TOP_K_COVERAGE = 0.9;
+
+function round2Down(value: number): number {
+  return Math.floor(value * 100) / 100;
+}
+
+/**
+ * Threshold that best separates relevant from irrelevant results (max F1;
+ * ties go to the lower threshold so recall is not given up for nothing)
+ */
+export function calibrateMinScore(
+  judgments: QueryJudgment[]
+): { minScore: number; precision: number; recall: number; f1: number } | null {
+  const results = judgments.flatMap(j => j.results);
+  const totalRelevant = results.filter(r => r.relevant).length;
+  if (totalRelevant === 0) return null;
+
+  const thresholds = [...new Set(results.map(r => r.score))].sort((a, b) => a - b);
+  let best = { minScore: 0, precision: 0, recall: 0, f1: -1 };
+
+  for (const threshold of thresholds) {
+    const kept = results.filter(r => r.score >= threshold);
+    const truePositives = kept.filter(r => r.relevant).length;
+    const precision = kept.length > 0 ? truePositives / kept.length : 0;
+    const recall = truePositives / totalRelevant;
+    const f1 = precision + recall > 0 ? (2 * precision * recall) / (precision + recall) : 0;
+    if (f1 > best.f1) {
+      best = { minScore: threshold, precision, recall, f1 };
+    }
+  }
+
+  return { ...best, minScore: round2Down(best.minScore) };
+}
+
+/**
+ * Smallest topK that still reaches the last relevant result above minScore
+ * for most queries
+ */
+export function calibrateTopK(judgments: QueryJudgment[], minScore: number): number {
+  const depths = judgments
+    .map(j => {
+      let depth = 0;
+      j.results.forEach((r, i) => {
+        if (r.relevant && r.score >= minScore) depth = i + 1;
+      });
+      return depth;
+    })
+    .filter(depth => depth > 0)
+    .sort((a, b) => a - b);
+
+  if (depths.length === 0) return DEFAULT_RETRIEVAL_SETTINGS.topK;
+  const index = Math.min(depths.length - 1, Math.ceil(depths.length * TOP_K_COVERAGE) - 1);
+  return Math.min(MAX_TOP_K, Math.max(MIN_TOP_K, depths[index]));
+}
+
+/**
+ * Mean share of relevant results within the first k
+ */
+export function precisionAtK(judgments: QueryJudgment[], k: number): number {
+  if (judgments.length === 0) return 0;
+  const total = judgments.reduce((sum, j) => {
+    const top = j.results.slice(0, k);
+    return sum + (top.length > 0 ? top.filter(r => r.relevant).length / top.length : 0);
+  }, 0);
+  return total / judgments.length;
+}
+
+/**
+ * Derive retrieval settings from judgments. `reranked` holds the same
+ * judgments in usage-reranked order; re-ranking stays on unless it makes
+ * the top results worse.
+ */
+export function calibrateRetrieval(
+  judgments: QueryJudgment[],
+  reranked?: QueryJudgment[]
+): CalibrationResult | null {
+  const threshold = calibrateMinScore(judgments);
+  if (!threshold) return null;
+
+  const topK = calibrateTopK(judgments, threshold.minScore);
+  const rerank = reranked
+    ? precisionAtK(reranked, topK) >= precisionAtK(judgments, topK)
+    : DEFAULT_RETRIEVAL_SETTINGS.rerank;
+
+  const results = judgments.flatMap(j => j.results);
+  return {
+    ...threshold,
+    topK,
+    rerank,
+    judged: results.length,
+    relevant: results.filter(r => r.relevant).length
+  };
+}
@@ -0,0 +1,149 @@
+/**
+ * Code Grep
+ *
+ * `cv grep` ranks indexed chunks like `cv explain` does (semantic search and
+ * the keyword index fused with reciprocal rank fusion), then filters them the
+ * way grep users expect: by language, path glob and symbol kind, and
+ * optionally by a regular expression that must match in the chunk. Matching
+ * lines are shown with surrounding context lines, clipped to the chunk.
+ */
+
+import { minimatch } from 'minimatch';
+import type { CodeChunkPayload, SymbolKind, VectorSearchResult } from '@cv-git/shared';
+import { KeywordHit, keywordHitToResult, reciprocalRankFusion, tokenize } from './keyword-index.js';
+
+export interface GrepFilters {
+  language?: string;
+  /** Globs; a glob without a slash matches the file name anywhere in the tree */
+  paths?: string[];
+  symbolKinds?: SymbolKind[];
+  /** Post-filter: only chunks with a line matching it */
+  regex?: RegExp;
+}
+
+export interface GrepLine {
+  /** 1-based line in the file */
+  line: number;
+  text: string;
+  /** A matching line, as opposed to context */
+  match: boolean;
+}
+
+export interface GrepMatch {
+  file: string;
+  startLine: number;
+  endLine: number;
+  symbolName?: string;
+  symbolKind?: SymbolKind;
+  language: string;
+  /** Fused rank score, 1 for a chunk ranked first by every retriever */
+  score: number;
+  /** Runs of matching and context lines; runs are separated by a gap */
+  hunks: GrepLine[][];
+}
+
+/**
+ * Whether a chunk passes the language, path and symbol kind filters
+ */
+export function matchesGrepFilters(payload: CodeChunkPayload, filters: GrepFilters): boolean {
+  if (filters.language && payload.language?.toLowerCase() !== filters.language.toLowerCase()) return false;
+  if (filters.symbolKinds?.length && (!payload.symbolKind || !filters.symbolKinds.includes(payload.symbolKind))) {
+    return false;
+  }
+  if (filters.paths?.length) {
+    const included = filters.paths.filter(glob => !glob.startsWith('!'));
+    const excluded = filters.paths.filter(glob => glob.startsWith('!')).map(glob => glob.slice(1));
+    const matches = (glob: string) => minimatch(payload.file, glob, { dot: true, matchBase: !glob.includes('/') });
+    if (included.length > 0 && !included.some(matches)) return false;
+    if (excluded.some(matches)) return false;
+  }
+  return true;
+}
+
+/**
+ * Matching lines of a chunk with `context` lines around them.
+ *
+ * With a regex, lines it matches; otherwise lines sharing a term with the
+ * query, falling back to the chunk's first line (usually the signature).
+ * Returns null when a regex is given and no line matches.
+ */
+export function grepChunk(
+  text: string,
+  startLine: number,
+  query: string,
+  regex: RegExp | undefined,
+  context: number
+): GrepLine[][] | null {
+  const lines = text.split('\n');
+  if (lines.length > 1 && lines[lines.length - 1] === '') lines.pop();
+
+  let matched: number[];
+  if (regex) {
+    const test = new RegExp(regex.source, regex.flags.replace(/[gy]/g, ''));
+    matched = lines.flatMap((line, i) => (test.test(line) ? [i] : []));
+    if (matched.length === 0) return null;
+  } else {
+    const terms = new Set(tokenize(query));
+    matched = lines.flatMap((line, i) => (tokenize(line).some(term => terms.has(term)) ? [i] : []));
+    if (matched.length === 0) {
+      const first = lines.findIndex(line => line.trim() !== '');
+      matched = [Math.max(0, first)];
+    }
+  }
+
+  const matchSet = new Set(matched);
+  const hunks: GrepLine[][] = [];
+  let current: GrepLine[] = [];
+  let lastShown = -2;
+
+  for (const index of matched) {
+    const from = Math.max(0, index - context);
+    const to = Math.min(lines.length - 1, index + context);
+    if (from > lastShown + 1 && current.length > 0) {
+      hunks.push(current);
+      current = [];
+    }
+    for (let i = Math.max(from, lastShown + 1); i <= to; i++) {
+      current.push({ line: startLine + i, text: lines[i], match: matchSet.has(i) });
+    }
+    lastShown = Math.max(lastShown, to);
+  }
+  if (current.length > 0) hunks.push(current);
+  return hunks;
+}
+
+/**
+ * Fuse semantic and keyword rankings, apply the filters and cut to `limit`
+ */
+export function grepResults(
+  query: string,
+  semantic: VectorSearchResult<CodeChunkPayload>[],
+  keyword: KeywordHit[],
+  filters: GrepFilters,
+  options: { limit: number; context: number }
+): GrepMatch[] {
+  const rankings = [semantic, keyword.map(keywordHitToResult)].filter(ranking => ranking.length > 0);
+  const fused = reciprocalRankFusion(rankings, chunk => chunk.id);
+
+  const matches: GrepMatch[] = [];
+  for (const { item, score } of fused) {
+    const payload = item.payload;
+    if (payload.synthetic || !matchesGrepFilters(payload, filters)) continue;
+
+    const hunks = grepChunk(payload.text, payload.startLine, query, filters.regex, options.context);
+    if (!hunks) continue;
+
+    matches.push({
+      file: payload.file,
+      startLine: payload.startLine,
+      endLine: payload.endLine,
+      symbolName: payload.symbolName,
+      symbolKind: payload.symbolKind,
+      language: payload.language,
+      score,
+      hunks,
+    });
+    if (matches.length >= options.limit) break;
+  }
+  return matches;
+}
@@ -21,6 +21,90 @@ export {
   loadTransitionState,
 } from './transition-model.js';
 export { ClaudeMdGenerator, ClaudeMdOptions } from './claude-md-generator.js';
+export {
+  ContextUsageModel,
+  ContextUsageState,
+  UsageCounts,
+  isChunkCited,
+  loadContextUsage,
+  saveContextUsage,
+  getContextUsagePath,
+  MIN_USAGE_WEIGHT,
+  MIN_ANSWERS_FOR_WEIGHTING,
+} from './usage-model.js';
+export {
+  KeywordIndex,
+  KeywordDocument,
+  KeywordHit,
+  Bm25Params,
+  DEFAULT_BM25_PARAMS,
+  RRF_K,
+  tokenize,
+  toKeywordDocument,
+  keywordHitToResult,
+  reciprocalRankFusion,
+  loadKeywordIndex,
+  saveKeywordIndex,
+  getKeywordIndexPath,
+} from './keyword-index.js';
+export {
+  GrepFilters,
+  GrepLine,
+  GrepMatch,
+  matchesGrepFilters,
+  grepChunk,
+  grepResults,
+} from './code-grep.js';
+export {
+  Reranker,
+  RerankOptions,
+  RerankProviderName,
+  LocalCrossEncoderReranker,
+  ApiReranker,
+  createReranker,
+  rerankChunks,
+  rerankDocument,
+  LOCAL_RERANK_MODELS,
+  DEFAULT_RERANK_MODELS,
+  RERANK_CANDIDATE_FACTOR,
+} from './reranker.js';
+export {
+  QueryExpansionMode,
+  QueryExpansionOptions,
+  QueryExpansionConfig,
+  QueryExpander,
+  QUERY_EXPANSION_MODES,
+  DEFAULT_EXPANDED_QUERIES,
+  MAX_EXPANDED_QUERIES,
+  buildSynonymMap,
+  expandWithSynonyms,
+  buildExpansionPrompt,
+  parseExpansions,
+  resolveQueryExpansion,
+} from './query-expansion.js';
+export {
+  ScoreDistribution,
+  computeScoreDistribution,
+  scoreAtPercentile,
+  resolveMinScore,
+  sampleScoreDistribution,
+  loadScoreDistribution,
+  saveScoreDistribution,
+  getScoreDistributionPath,
+  DEFAULT_MIN_SCORE_PERCENTILE,
+  MIN_DISTRIBUTION_POINTS,
+} from './score-distribution.js';
+export {
+  calibrateRetrieval,
+  calibrateMinScore,
+  calibrateTopK,
+  precisionAtK,
+  DEFAULT_RETRIEVAL_SETTINGS,
+  JudgedResult,
+  QueryJudgment,
+  RetrievalSettings,
+  CalibrationResult,
+} from './calibration.js';
 
 export interface ContextRequest {
   // The task or query to gather context for
@@ -0,0 +1,256 @@
+/**
+ * Keyword Index
+ *
+ * BM25 over the same code chunks that are embedded. Embeddings rank code by
+ * meaning, so a query for an exact identifier (`gradient_descent`) can come
+ * back with unrelated maths code; the keyword index finds the chunks that
+ * actually contain it. gatherContext fuses both rankings with reciprocal
+ * rank fusion, which needs no score calibration between the two.
+ *
+ * `cv sync` keeps the index at .cv/keyword-index.json. Only chunk documents
+ * are stored; postings are rebuilt in memory when the index is first searched.
+ */
+
+import { promises as fs } from 'fs';
+import * as path from 'path';
+import { getCVDir } from '@cv-git/shared';
+import type { CodeChunk, CodeChunkPayload, VectorSearchResult } from '@cv-git/shared';
+
+export interface KeywordDocument {
+  id: string;
+  file: string;
+  language: string;
+  symbolName?: string;
+  symbolKind?: CodeChunkPayload['symbolKind'];
+  startLine: number;
+  endLine: number;
+  text: string;
+}
+
+export interface KeywordHit {
+  doc: KeywordDocument;
+  score: number;
+}
+
+export interface Bm25Params {
+  /** Term-frequency saturation */
+  k1: number;
+  /** Document length normalisation */
+  b: number;
+}
+
+export const DEFAULT_BM25_PARAMS: Bm25Params = { k1: 1.2, b: 0.75 };
+
+/** Rank offset in reciprocal rank fusion; 60 is the value from the original paper */
+export const RRF_K = 60;
+
+interface KeywordIndexState {
+  version: 1;
+  docs: KeywordDocument[];
+}
+
+const TOKEN = /[A-Za-z_$][A-Za-z0-9_$]*|\d+/g;
+/** Split points inside identifiers: snake_case, camelCase and HTTPServer-style acronyms */
+const IDENTIFIER_PARTS = /_+|\$+|(?<=[a-z0-9])(?=[A-Z])|(?<=[A-Z])(?=[A-Z][a-z])/;
+
+/**
+ * Lower-cased terms of a text. Identifiers are kept whole and also split
+ * into their words, so `gradient_descent` matches itself most strongly but
+ * `gradient` still finds it.
+ */
+export function tokenize(text: string): string[] {
+  const terms: string[] = [];
+  for (const [token] of text.matchAll(TOKEN)) {
+    if (token.length < 2) continue;
+    terms.push(token.toLowerCase());
+    const parts = token.split(IDENTIFIER_PARTS).filter(part => part.length >= 2);
+    if (parts.length > 1) {
+      for (const part of parts) terms.push(part.toLowerCase());
+    }
+  }
+  return terms;
+}
+
+export function toKeywordDocument(chunk: CodeChunk): KeywordDocument {
+  return {
+    id: chunk.id,
+    file: chunk.file,
+    language: chunk.language,
+    symbolName: chunk.symbolName,
+    symbolKind: chunk.symbolKind,
+    startLine: chunk.startLine,
+    endLine: chunk.endLine,
+    text: chunk.text
+  };
+}
+
+/**
+ * Chunk search result for a keyword hit, shaped like a vector search result
+ */
+export function keywordHitToResult(hit: KeywordHit): VectorSearchResult<CodeChunkPayload> {
+  return {
+    id: hit.doc.id,
+    score: hit.score,
+    payload: {
+      ...hit.doc,
+      imports: [],
+      lastModified: 0
+    }
+  };
+}
+
+export class KeywordIndex {
+  private docs = new Map<string, KeywordDocument>();
+  /** term -> chunk id -> term frequency; null until the next search */
+  private postings: Map<string, Map<string, number>> | null = null;
+  private lengths = new Map<string, number>();
+  private averageLength = 0;
+
+  constructor(docs: KeywordDocument[] = [], private params: Bm25Params = DEFAULT_BM25_PARAMS) {
+    for (const doc of docs) this.docs.set(doc.id, doc);
+  }
+
+  get size(): number {
+    return this.docs.size;
+  }
+
+  /**
+   * Every indexed chunk, in insertion order
+   */
+  documents(): KeywordDocument[] {
+    return [...this.docs.values()];
+  }
+
+  /**
+   * Add or replace chunks
+   */
+  add(docs: KeywordDocument[]): void {
+    for (const doc of docs) this.docs.set(doc.id, doc);
+    if (docs.length > 0) this.postings = null;
+  }
+
+  /**
+   * Drop every chunk of the given files
+   * @returns number of chunks removed
+   */
+  removeFiles(files: string[]): number {
+    if (files.length === 0) return 0;
+    const remove = new Set(files);
+    let removed = 0;
+    for (const [id, doc] of this.docs) {
+      if (remove.has(doc.file)) {
+        this.docs.delete(id);
+        removed++;
+      }
+    }
+    if (removed > 0) this.postings = null;
+    return removed;
+  }
+
+  private build(): Map<string, Map<string, number>> {
+    const postings = new Map<string, Map<string, number>>();
+    this.lengths.clear();
+    let total = 0;
+
+    for (const doc of this.docs.values()) {
+      // Symbol and file names count as part of the chunk
+      const terms = tokenize(`${doc.symbolName || ''} ${doc.file} ${doc.text}`);
+      this.lengths.set(doc.id, terms.length);
+      total += terms.length;
+      for (const term of terms) {
+        let docsWithTerm = postings.get(term);
+        if (!docsWithTerm) postings.set(term, docsWithTerm = new Map());
+        docsWithTerm.set(doc.id, (docsWithTerm.get(doc.id) || 0) + 1);
+      }
+    }
+
+    this.averageLength = this.docs.size > 0 ? total / this.docs.size : 0;
+    this.postings = postings;
+    return postings;
+  }
+
+  /**
+   * Chunks ranked by BM25 score, best first
+   */
+  search(query: string, limit: number): KeywordHit[] {
+    const postings = this.postings ?? this.build();
+    const { k1, b } = this.params;
+    const n = this.docs.size;
+    const scores = new Map<string, number>();
+
+    for (const term of new Set(tokenize(query))) {
+      const docsWithTerm = postings.get(term);
+      if (!docsWithTerm) continue;
+      const idf = Math.log(1 + (n - docsWithTerm.size + 0.5) / (docsWithTerm.size + 0.5));
+      for (const [id, tf] of docsWithTerm) {
+        const norm = 1 - b + b * (this.lengths.get(id)! / (this.averageLength || 1));
+        scores.set(id, (scores.get(id) || 0) + idf * (tf * (k1 + 1)) / (tf + k1 * norm));
+      }
+    }
+
+    return [...scores]
+      .sort((x, y) => y[1] - x[1])
+      .slice(0, limit)
+      .map(([id, score]) => ({ doc: this.docs.get(id)!, score }));
+  }
+
+  toJSON(): KeywordIndexState {
+    return { version: 1, docs: this.documents() };
+  }
+}
+
+/**
+ * Fuse ranked lists: each item scores Σ 1/(k + rank) over the lists it
+ * appears in. Scores are scaled so an item ranked first in every list gets 1.
+ *
+ * @param key - identity of an item across lists
+ * @returns items best first; the first list's copy is kept for duplicates
+ */
+export function reciprocalRankFusion<T>(
+  rankings: T[][],
+  key: (item: T) => string,
+  k: number = RRF_K
+): Array<{ item: T; score: number }> {
+  const fused = new Map<string, { item: T; score: number }>();
+  const best = rankings.length / (k + 1);
+
+  for (const ranking of rankings) {
+    ranking.forEach((item, rank) => {
+      const id = key(item);
+      const entry = fused.get(id) ?? { item, score: 0 };
+      entry.score += 1 / (k + rank + 1);
+      fused.set(id, entry);
+    });
+  }
+
+  return [...fused.values()]
+    .map(entry => ({ item: entry.item, score: best > 0 ? entry.score / best : 0 }))
+    .sort((a, b) => b.score - a.score);
+}
+
+export function getKeywordIndexPath(repoRoot: string): string {
+  return path.join(getCVDir(repoRoot), 'keyword-index.json');
+}
+
+/**
+ * Load the keyword index for a repository; empty if none is saved
+ */
+export async function loadKeywordIndex(repoRoot: string): Promise<KeywordIndex> {
+  try {
+    const raw = await fs.readFile(getKeywordIndexPath(repoRoot), 'utf-8');
+    const state = JSON.parse(raw) as KeywordIndexState;
+    if (state.version === 1) {
+      return new KeywordIndex(state.docs);
+    }
+  } catch {
+    // Missing or unreadable - start fresh
+  }
+  return new KeywordIndex();
+}
+
+export async function saveKeywordIndex(repoRoot: string, index: KeywordIndex): Promise<void> {
+  const target = getKeywordIndexPath(repoRoot);
+  await fs.mkdir(path.dirname(target), { recursive: true });
+  await fs.writeFile(`${target}.tmp`, JSON.stringify(index.toJSON()), 'utf-8');
+  await fs.rename(`${target}.tmp`, target);
+}
@@ -0,0 +1,234 @@
+/**
+ * Query Expansion
+ *
+ * A single query embeds one way of saying it: "auth" finds code that says
+ * auth, but not the `login()` handler or the `credentials` module. With
+ * expansion on, gatherContext also searches for a few reformulations of the
+ * query and fuses the rankings (reciprocal rank fusion), so a chunk that is
+ * near any of them can make it into the context.
+ *
+ * `synonyms` rewrites the query with a built-in map of programming synonyms
+ * (plus `retrieval.expansion.synonyms`) and costs nothing; `llm` asks the
+ * model for reformulations and falls back to synonyms when that fails.
+ */
+
+export type QueryExpansionMode = 'off' | 'synonyms' | 'llm';
+
+export const QUERY_EXPANSION_MODES: QueryExpansionMode[] = ['off', 'synonyms', 'llm'];
+
+/** Reformulations searched besides the query (default) */
+export const DEFAULT_EXPANDED_QUERIES = 3;
+
+/** Most reformulations searched; each one is another embedding and search */
+export const MAX_EXPANDED_QUERIES = 4;
+
+export interface QueryExpansionOptions {
+  mode: QueryExpansionMode;
+  /** Reformulations per query, 1-4 (default: 3) */
+  queries?: number;
+  /** Extra synonym groups: each term maps to words that mean the same in this codebase */
+  synonyms?: Record<string, string[]>;
+}
+
+/** `retrieval.expansion` in config */
+export interface QueryExpansionConfig {
+  /** Mode for commands not listed in `commands` (default: off) */
+  mode?: QueryExpansionMode;
+  queries?: number;
+  synonyms?: Record<string, string[]>;
+  /** Mode per command, e.g. `{ "explain": "llm", "find": "synonyms" }` */
+  commands?: Record<string, QueryExpansionMode>;
+}
+
+/**
+ * Words that are used interchangeably in code, WordNet-style: each group is
+ * one meaning. A term in several groups gets the synonyms of all of them.
+ */
+const SYNONYM_GROUPS: string[][] = [
+  ['auth', 'authentication', 'login', 'signin', 'credentials'],
+  ['authorization', 'permission', 'access', 'role', 'acl'],
+  ['session', 'token', 'cookie', 'jwt'],
+  ['user', 'account', 'member', 'profile'],
+  ['password', 'passphrase', 'secret'],
+  ['config', 'configuration', 'settings', 'options', 'preferences'],
+  ['db', 'database', 'storage', 'persistence', 'repository'],
+  ['query', 'search', 'lookup'],
+  ['fetch', 'retrieve', 'load', 'read'],
+  ['save', 'store', 'write', 'persist'],
+  ['create', 'add', 'insert'],
+  ['update', 'modify', 'edit'],
+  ['delete', 'remove', 'destroy', 'drop'],
+  ['error', 'exception', 'failure', 'fault'],
+  ['retry', 'backoff', 'reattempt'],
+  ['timeout', 'deadline', 'expiry'],
+  ['validate', 'check', 'verify', 'sanitize'],
+  ['parse', 'decode', 'deserialize'],
+  ['serialize', 'encode', 'stringify', 'marshal'],
+  ['http', 'request', 'endpoint', 'route', 'api'],
+  ['handler', 'callback', 'listener', 'hook'],
+  ['send', 'emit', 'dispatch', 'publish'],
+  ['receive', 'subscribe', 'consume', 'listen'],
+  ['message', 'event', 'notification'],
+  ['queue', 'job', 'task', 'worker'],
+  ['cache', 'memoize', 'memo'],
+  ['log', 'logger', 'logging', 'trace'],
+  ['metrics', 'telemetry', 'stats', 'monitoring'],
+  ['start', 'init', 'initialize', 'bootstrap', 'setup'],
+  ['stop', 'shutdown', 'close', 'teardown', 'dispose'],
+  ['test', 'spec', 'fixture'],
+  ['build', 'compile', 'bundle'],
+  ['deploy', 'release', 'publish'],
+  ['encrypt', 'cipher', 'crypto'],
+  ['hash', 'digest', 'checksum'],
+  ['payment', 'billing', 'invoice', 'charge'],
+  ['email', 'mail', 'smtp'],
+  ['upload', 'attachment'],
+  ['render', 'draw', 'display'],
+  ['component', 'widget', 'element'],
+  ['client', 'sdk', 'wrapper'],
+  ['server', 'service', 'backend'],
+  ['throttle', 'ratelimit'],
+  ['migrate', 'migration', 'schema'],
+];
+
+/**
+ * Synonym map: every word of a group maps to the rest of it
+ */
+export function buildSynonymMap(extra: Record<string, string[]> = {}): Map<string, string[]> {
+  const map = new Map<string, string[]>();
+  const link = (term: string, words: string[]) => {
+    const key = term.toLowerCase();
+    const known = map.get(key) ?? [];
+    for (const word of words.map(w => w.toLowerCase())) {
+      if (word !== key && !known.includes(word)) known.push(word);
+    }
+    map.set(key, known);
+  };
+  for (const group of SYNONYM_GROUPS) {
+    for (const term of group) link(term, group);
+  }
+  // Configured groups come first: they are this codebase's own vocabulary
+  for (const [term, words] of Object.entries(extra)) {
+    const group = [term, ...words];
+    for (const word of group) {
+      const key = word.toLowerCase();
+      const own = group.map(w => w.toLowerCase()).filter(w => w !== key);
+      map.set(key, [...own, ...(map.get(key) ?? []).filter(w => !own.includes(w))]);
+    }
+  }
+  return map;
+}
+
+/**
+ * Reformulations of `query` with synonyms substituted: the i-th one uses
+ * each known term's i-th synonym. Empty when no term has synonyms.
+ */
+export function expandWithSynonyms(query: string, count: number, synonyms: Map<string, string[]> = buildSynonymMap()): string[] {
+  const words = query.split(/(\W+)/);
+  const replaceable = words
+    .map((word, index) => {
+      const lower = word.toLowerCase();
+      // "logins" has the synonyms of "login"
+      return { index, options: synonyms.get(lower) ?? (lower.endsWith('s') ? synonyms.get(lower.slice(0, -1)) : undefined) ?? [] };
+    })
+    .filter(entry => entry.options.length > 0);
+  if (replaceable.length === 0) return [];
+
+  const longest = Math.max(...replaceable.map(entry => entry.options.length));
+  const expansions: string[] = [];
+  for (let i = 0; i < longest && expansions.length < count; i++) {
+    const rewritten = [...words];
+    for (const entry of replaceable) {
+      rewritten[entry.index] = entry.options[i % entry.options.length];
+    }
+    const text = rewritten.join('');
+    if (text.toLowerCase() !== query.toLowerCase() && !expansions.includes(text)) expansions.push(text);
+  }
+  return expansions;
+}
+
+/**
+ * Prompt asking the model for reformulations, one per line
+ */
+export function buildExpansionPrompt(query: string, count: number): string {
+  return `Rewrite this search query over a codebase ${count} different ways, so that code using other words for ` +
+    `the same thing is found too: synonyms, likely function, class or file names, related technical terms.\n\n` +
+    `Query: ${query}\n\n` +
+    `Reply with exactly ${count} lines, one query per line, and nothing else.`;
+}
+
+/**
+ * Reformulations in a model reply: one per line, numbering and quotes removed
+ */
+export function parseExpansions(reply: string, query: string, count: number): string[] {
+  const seen = new Set([query.trim().toLowerCase()]);
+  const expansions: string[] = [];
+  for (const line of reply.split('\n')) {
+    const text = line.replace(/^\s*(?:[-*•]|\d+[.)])\s*/, '').replace(/^["'`]+|["'`]+$/g, '').trim();
+    if (!text || text.endsWith(':') || text.length > 200 || seen.has(text.toLowerCase())) continue;
+    seen.add(text.toLowerCase());
+    expansions.push(text);
+    if (expansions.length === count) break;
+  }
+  return expansions;
+}
+
+/**
+ * Turns one query into the queries to search
+ */
+export class QueryExpander {
+  readonly mode: QueryExpansionMode;
+  readonly count: number;
+  private synonyms: Map<string, string[]>;
+  private cache = new Map<string, string[]>();
+
+  /**
+   * @param complete - sends a prompt to the model (for `llm`)
+   */
+  constructor(options: QueryExpansionOptions, private complete?: (prompt: string) => Promise<string>) {
+    this.mode = options.mode;
+    this.count = Math.min(MAX_EXPANDED_QUERIES, Math.max(1, Math.round(options.queries ?? DEFAULT_EXPANDED_QUERIES)));
+    this.synonyms = buildSynonymMap(options.synonyms);
+  }
+
+  /**
+   * The query followed by its reformulations
+   */
+  async expand(query: string): Promise<string[]> {
+    if (this.mode === 'off' || !query.trim()) return [query];
+    const cached = this.cache.get(query);
+    if (cached) return cached;
+
+    let expansions: string[] = [];
+    if (this.mode === 'llm' && this.complete) {
+      try {
+        expansions = parseExpansions(await this.complete(buildExpansionPrompt(query, this.count)), query, this.count);
+      } catch {
+        // No model, or it failed: synonyms still widen the search
+      }
+    }
+    if (expansions.length === 0) {
+      expansions = expandWithSynonyms(query, this.count, this.synonyms);
+    }
+
+    const queries = [query, ...expansions];
+    this.cache.set(query, queries);
+    return queries;
+  }
+}
+
+/**
+ * Expansion settings of `command`: `--expand-query` first, then
+ * `retrieval.expansion.commands`, then `retrieval.expansion.mode`
+ */
+export function resolveQueryExpansion(
+  config: QueryExpansionConfig | undefined,
+  command: string,
+  override?: string
+): QueryExpansionOptions {
+  if (override !== undefined && !QUERY_EXPANSION_MODES.includes(override as QueryExpansionMode)) {
+    throw new Error(`Unknown query expansion "${override}"; use ${QUERY_EXPANSION_MODES.join(', ')}`);
+  }
+  const mode = (override as QueryExpansionMode | undefined) ?? config?.commands?.[command] ?? config?.mode ?? 'off';
+  return { mode, queries: config?.queries, synonyms: config?.synonyms };
+}
@@ -0,0 +1,211 @@
+/**
+ * Rerankers
+ *
+ * Vector and keyword search score each chunk on its own, so the top-k they
+ * return is often padded with chunks that merely share vocabulary with the
+ * query. A reranker reads the query and each candidate together and scores
+ * how well the chunk answers it. gatherContext over-fetches candidates,
+ * reranks them, and only then picks the chunks passed to the model.
+ *
+ * `local` runs a small ONNX cross-encoder in-process via the same optional
+ * @huggingface/transformers runtime as local embeddings; `cohere`, `jina`
+ * and `voyage` call the provider's rerank API.
+ */
+
+import type { CodeChunkPayload, VectorSearchResult } from '@cv-git/shared';
+import { getLocalModelsDir } from '../vector/local-embeddings.js';
+import { providerFetch } from '../ai/http.js';
+
+export type RerankProviderName = 'local' | 'cohere' | 'jina' | 'voyage';
+
+export interface Reranker {
+  readonly name: RerankProviderName;
+  readonly model: string;
+  /** Relevance of each document to the query, in input order; higher is better */
+  score(query: string, documents: string[]): Promise<number[]>;
+}
+
+export interface RerankOptions {
+  provider: RerankProviderName | 'none';
+  model?: string;
+  apiKey?: string;
+  /** Override the provider's API endpoint */
+  url?: string;
+}
+
+/** Candidates fetched for the reranker, as a multiple of the chunks kept */
+export const RERANK_CANDIDATE_FACTOR = 3;
+
+/** Characters of each chunk sent to the reranker; cross-encoders truncate at ~512 tokens anyway */
+const MAX_DOCUMENT_CHARS = 2000;
+
+export const LOCAL_RERANK_MODELS: Array<{ name: string; repo: string }> = [
+  { name: 'local/ms-marco-minilm-l6-v2', repo: 'Xenova/ms-marco-MiniLM-L-6-v2' },
+  { name: 'local/bge-reranker-base', repo: 'Xenova/bge-reranker-base' }
+];
+
+const RERANK_APIS: Record<Exclude<RerankProviderName, 'local'>, { url: string; model: string; envKey: string }> = {
+  cohere: { url: 'https://api.cohere.com/v2/rerank', model: 'rerank-v3.5', envKey: 'COHERE_API_KEY' },
+  jina: { url: 'https://api.jina.ai/v1/rerank', model: 'jina-reranker-v2-base-multilingual', envKey: 'JINA_API_KEY' },
+  voyage: { url: 'https://api.voyageai.com/v1/rerank', model: 'rerank-2', envKey: 'VOYAGE_API_KEY' }
+};
+
+export const DEFAULT_RERANK_MODELS: Record<RerankProviderName, string> = {
+  local: LOCAL_RERANK_MODELS[0].name,
+  cohere: RERANK_APIS.cohere.model,
+  jina: RERANK_APIS.jina.model,
+  voyage: RERANK_APIS.voyage.model
+};
+
+const RUNTIME_PACKAGE = '@huggingface/transformers';
+
+/**
+ * In-process cross-encoder; loads lazily on first use
+ */
+export class LocalCrossEncoderReranker implements Reranker {
+  readonly name = 'local' as const;
+  readonly model: string;
+  private repo: string;
+  private loaded: Promise<{ tokenizer: any; model: any }> | null = null;
+
+  constructor(modelName: string = DEFAULT_RERANK_MODELS.local) {
+    const bare = modelName.replace(/^local\//, '').toLowerCase();
+    const known = LOCAL_RERANK_MODELS.find(m => m.name === `local/${bare}` || m.repo.toLowerCase() === bare);
+    this.model = known?.name ?? modelName;
+    // Any Hugging Face repo with ONNX sequence-classification weights works
+    this.repo = known?.repo ?? modelName.replace(/^local\//, '');
+  }
+
+  private load(): Promise<{ tokenizer: any; model: any }> {
+    if (!this.loaded) {
+      this.loaded = (async () => {
+        let runtime: any;
+        try {
+          runtime = await import(RUNTIME_PACKAGE);
+        } catch {
+          throw new Error(
+            `Local reranking needs the optional ${RUNTIME_PACKAGE} package.\n` +
+            `Install it with: npm install -g ${RUNTIME_PACKAGE}`
+          );
+        }
+        runtime.env.cacheDir = getLocalModelsDir();
+        if (process.env.CV_OFFLINE === '1') {
+          runtime.env.allowRemoteModels = false;
+        }
+        const [tokenizer, model] = await Promise.all([
+          runtime.AutoTokenizer.from_pretrained(this.repo),
+          runtime.AutoModelForSequenceClassification.from_pretrained(this.repo, { dtype: 'q8' })
+        ]);
+        return { tokenizer, model };
+      })();
+      this.loaded.catch(() => { this.loaded = null; });
+    }
+    return this.loaded;
+  }
+
+  async score(query: string, documents: string[]): Promise<number[]> {
+    if (documents.length === 0) return [];
+    const { tokenizer, model } = await this.load();
+    const inputs = tokenizer(new Array(documents.length).fill(query), {
+      text_pair: documents,
+      padding: true,
+      truncation: true
+    });
+    const { logits } = await model(inputs);
+    // One relevance logit per pair; squash to 0-1 so scores read like similarities
+    return (logits.tolist() as number[][]).map(([logit]) => 1 / (1 + Math.exp(-logit)));
+  }
+}
+
+/**
+ * Provider rerank APIs. Cohere, Jina and Voyage take the same request and
+ * return `{ index, relevance_score }` pairs, sorted by relevance.
+ */
+export class ApiReranker implements Reranker {
+  readonly model: string;
+  private url: string;
+
+  constructor(
+    readonly name: Exclude<RerankProviderName, 'local'>,
+    private apiKey: string,
+    options: { model?: string; url?: string } = {}
+  ) {
+    this.model = options.model || RERANK_APIS[name].model;
+    this.url = options.url || RERANK_APIS[name].url;
+  }
+
+  async score(query: string, documents: string[]): Promise<number[]> {
+    if (documents.length === 0) return [];
+    const response = await providerFetch(this.name, this.url, {
+      method: 'POST',
+      headers: {
+        'Content-Type': 'application/json',
+        Authorization: `Bearer ${this.apiKey}`
+      },
+      body: JSON.stringify({ model: this.model, query, documents })
+    });
+    if (!response.ok) {
+      const detail = await response.text().catch(() => '');
+      throw new Error(`${this.name} rerank failed (${response.status}): ${detail.slice(0, 200)}`);
+    }
+
+    const body = await response.json() as {
+      results?: Array<{ index: number; relevance_score: number }>;
+      data?: Array<{ index: number; relevance_score: number }>;
+    };
+    // Documents the provider left out rank last
+    const scores = new Array<number>(documents.length).fill(-1);
+    for (const result of body.results ?? body.data ?? []) {
+      scores[result.index] = result.relevance_score;
+    }
+    return scores;
+  }
+}
+
+/**
+ * Build the configured reranker; undefined when reranking is off.
+ * Throws when an API provider has no key.
+ */
+export function createReranker(options: RerankOptions | undefined): Reranker | undefined {
+  if (!options || options.provider === 'none') return undefined;
+  if (options.provider === 'local') {
+    return new LocalCrossEncoderReranker(options.model);
+  }
+  if (!(options.provider in RERANK_APIS)) {
+    throw new Error(`Unknown rerank provider: ${options.provider}`);
+  }
+  const apiKey = options.apiKey || process.env[RERANK_APIS[options.provider].envKey];
+  if (!apiKey) {
+    throw new Error(
+      `${options.provider} reranking needs an API key: set rerank.apiKey or ${RERANK_APIS[options.provider].envKey}`
+    );
+  }
+  return new ApiReranker(options.provider, apiKey, { model: options.model, url: options.url });
+}
+
+/**
+ * Text a chunk is reranked on: its location and symbol, then its code
+ */
+export function rerankDocument(payload: CodeChunkPayload): string {
+  const symbol = payload.symbolName ? ` ${payload.symbolKind || 'symbol'} ${payload.symbolName}` : '';
+  return `${payload.file}${symbol}\n${payload.text}`.slice(0, MAX_DOCUMENT_CHARS);
+}
+
+/**
+ * Reorder chunks by reranker relevance; scores become the reranker's
+ *
+ * @param limit - chunks to keep (default: all)
+ */
+export async function rerankChunks(
+  reranker: Reranker,
+  query: string,
+  chunks: VectorSearchResult<CodeChunkPayload>[],
+  limit: number = chunks.length
+): Promise<VectorSearchResult<CodeChunkPayload>[]> {
+  if (chunks.length === 0) return chunks;
+  const scores = await reranker.score(query, chunks.map(chunk => rerankDocument(chunk.payload)));
+  return chunks
+    .map((chunk, i) => ({ ...chunk, score: scores[i] }))
+    .sort((a, b) => b.score - a.score)
+    .slice(0, limit);
+}
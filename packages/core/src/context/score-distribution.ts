@@ -0,0 +1,166 @@
+/**
+ * Score Distribution
+ *
+ * Similarity scores mean different things per embedding model: one model
+ * puts unrelated code at 0.1, another at 0.6, so no fixed minScore works
+ * for both. At sync time we sample the index and record how similar
+ * unrelated chunks are (cosine similarity of random chunk pairs). At query
+ * time the cut-off is a percentile of that background distribution, e.g.
+ * "keep results more similar than 90% of random pairs".
+ *
+ * Distributions are stored per embedding model in
+ * .cv/score-distribution.json. A minScore set in config (by hand or by
+ * `cv calibrate`) always takes precedence.
+ */
+
+import { promises as fs } from 'fs';
+import * as path from 'path';
+import { getCVDir } from '@cv-git/shared';
+import { cosineSimilarity } from '../vector/store.js';
+import type { VectorManager } from '../vector/index.js';
+
+export interface ScoreDistribution {
+  /** Embedding model the vectors came from */
+  model: string;
+  dimension: number;
+  /** Vectors sampled from the index */
+  points: number;
+  /** Random pairs scored */
+  pairs: number;
+  /** Similarity at each percentile 0..100 */
+  quantiles: number[];
+  sampledAt: string;
+}
+
+interface ScoreDistributionState {
+  version: 1;
+  models: Record<string, ScoreDistribution>;
+}
+
+/** Default percentile of the background distribution used as minScore */
+export const DEFAULT_MIN_SCORE_PERCENTILE = 90;
+
+/** Fewer vectors than this give too noisy a distribution to trust */
+export const MIN_DISTRIBUTION_POINTS = 20;
+
+const DEFAULT_SAMPLE_POINTS = 500;
+const DEFAULT_SAMPLE_PAIRS = 5000;
+
+/**
+ * Background score distribution of a set of vectors
+ *
+ * @param random - source of randomness for pair selection (tests pass a seeded one)
+ */
+export function computeScoreDistribution(
+  vectors: number[][],
+  model: string,
+  options: { pairs?: number; random?: () => number } = {}
+): ScoreDistribution | null {
+  if (vectors.length < 2) return null;
+  const random = options.random ?? Math.random;
+  const maxPairs = (vectors.length * (vectors.length - 1)) / 2;
+  const pairs = Math.min(options.pairs ?? DEFAULT_SAMPLE_PAIRS, maxPairs);
+
+  const scores: number[] = [];
+  while (scores.length < pairs) {
+    const i = Math.floor(random() * vectors.length);
+    const j = Math.floor(random() * vectors.length);
+    if (i === j) continue;
+    scores.push(cosineSimilarity(vectors[i], vectors[j]));
+  }
+  scores.sort((a, b) => a - b);
+
+  const quantiles: number[] = [];
+  for (let p = 0; p <= 100; p++) {
+    quantiles.push(scores[Math.min(scores.length - 1, Math.round((p / 100) * (scores.length - 1)))]);
+  }
+
+  return {
+    model,
+    dimension: vectors[0].length,
+    points: vectors.length,
+    pairs: scores.length,
+    quantiles,
+    sampledAt: new Date().toISOString()
+  };
+}
+
+/**
+ * Similarity at a (fractional) percentile of the distribution
+ */
+export function scoreAtPercentile(distribution: ScoreDistribution, percentile: number): number {
+  const p = Math.max(0, Math.min(100, percentile));
+  const lower = Math.floor(p);
+  const upper = Math.ceil(p);
+  const { quantiles } = distribution;
+  return quantiles[lower] + (quantiles[upper] - quantiles[lower]) * (p - lower);
+}
+
+/**
+ * minScore for a query: the configured value when set, otherwise the
+ * percentile of the model's distribution, otherwise `fallback`
+ */
+export function resolveMinScore(
+  retrieval: { minScore?: number; minScorePercentile?: number } | undefined,
+  distribution: ScoreDistribution | null | undefined,
+  fallback: number
+): number {
+  if (retrieval?.minScore !== undefined) return retrieval.minScore;
+  if (distribution && distribution.points >= MIN_DISTRIBUTION_POINTS) {
+    return scoreAtPercentile(distribution, retrieval?.minScorePercentile ?? DEFAULT_MIN_SCORE_PERCENTILE);
+  }
+  return fallback;
+}
+
+/**
+ * Sample vectors from a collection and compute its distribution
+ */
+export async function sampleScoreDistribution(
+  vector: VectorManager,
+  collection: string,
+  options: { points?: number; pairs?: number } = {}
+): Promise<ScoreDistribution | null> {
+  const wanted = options.points ?? DEFAULT_SAMPLE_POINTS;
+  const vectors: number[][] = [];
+  let offset: string | undefined;
+  do {
+    const page = await vector.scroll(collection, Math.min(100, wanted - vectors.length), offset);
+    for (const point of page.points) {
+      if (point.vector?.length) vectors.push(point.vector);
+    }
+    offset = page.next_page_offset;
+  } while (offset && vectors.length < wanted);
+
+  return computeScoreDistribution(vectors, vector.getEmbeddingInfo().model, { pairs: options.pairs });
+}
+
+export function getScoreDistributionPath(repoRoot: string): string {
+  return path.join(getCVDir(repoRoot), 'score-distribution.json');
+}
+
+async function readState(repoRoot: string): Promise<ScoreDistributionState> {
+  try {
+    const state = JSON.parse(await fs.readFile(getScoreDistributionPath(repoRoot), 'utf-8')) as ScoreDistributionState;
+    if (state.version === 1 && state.models) return state;
+  } catch {
+    // Missing or unreadable - start fresh
+  }
+  return { version: 1, models: {} };
+}
+
+/**
+ * Stored distribution for an embedding model; null if none was sampled
+ */
+export async function loadScoreDistribution(repoRoot: string, model: string | undefined): Promise<ScoreDistribution | null> {
+  if (!model) return null;
+  return (await readState(repoRoot)).models[model] ?? null;
+}
+
+export async function saveScoreDistribution(repoRoot: string, distribution: ScoreDistribution): Promise<void> {
+  const state = await readState(repoRoot);
+  state.models[distribution.model] = distribution;
+  const target = getScoreDistributionPath(repoRoot);
+  await fs.mkdir(path.dirname(target), { recursive: true });
+  await fs.writeFile(`${target}.tmp`, JSON.stringify(state, null, 2), 'utf-8');
+  await fs.rename(`${target}.tmp`, target);
+}
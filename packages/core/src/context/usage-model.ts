@@ -0,0 +1,170 @@
+/**
+ * Context Usage Model
+ *
+ * Learns which retrieved chunks actually get used. After each answer we
+ * record, per file path and per chunk kind, how often a chunk was retrieved
+ * and how often the answer cited it (mentioned its file or symbol). Paths and
+ * kinds that keep getting retrieved but never cited are down-weighted when
+ * ranking future retrievals, so context precision improves per repository.
+ *
+ * State is a small JSON file at .cv/context-usage.json.
+ */
+
+import { promises as fs } from 'fs';
+import * as path from 'path';
+import { getCVDir } from '@cv-git/shared';
+import type { VectorSearchResult, CodeChunkPayload } from '@cv-git/shared';
+
+export interface UsageCounts {
+  retrieved: number;
+  cited: number;
+}
+
+export interface ContextUsageState {
+  version: 1;
+  paths: Record<string, UsageCounts>;
+  kinds: Record<string, UsageCounts>;
+  answers: number;
+}
+
+/** Pseudo-observations pulling sparse keys towards the repository-wide cite rate */
+const PRIOR_STRENGTH = 5;
+
+/** Never push a chunk below this fraction of its similarity score */
+export const MIN_USAGE_WEIGHT = 0.5;
+
+/** Answers to observe before weights are applied at all */
+export const MIN_ANSWERS_FOR_WEIGHTING = 5;
+
+type Chunk = VectorSearchResult<CodeChunkPayload>;
+
+function kindKey(chunk: Chunk): string {
+  return chunk.payload.synthetic ? 'synthetic' : chunk.payload.symbolKind || 'code';
+}
+
+function escapeRegExp(text: string): string {
+  return text.replace(/[.*+?^${}()|[\]\\]/g, '\\$&');
+}
+
+/**
+ * Whether an answer refers to a chunk, by file path or symbol name
+ */
+export function isChunkCited(chunk: Chunk, answer: string): boolean {
+  const { file, symbolName } = chunk.payload;
+  if (file && (answer.includes(file) || answer.includes(path.posix.basename(file)))) {
+    return true;
+  }
+  if (symbolName && symbolName.length >= 3) {
+    return new RegExp(`\\b${escapeRegExp(symbolName)}\\b`).test(answer);
+  }
+  return false;
+}
+
+export class ContextUsageModel {
+  private state: ContextUsageState;
+
+  constructor(state?: ContextUsageState) {
+    this.state = state ?? { version: 1, paths: {}, kinds: {}, answers: 0 };
+  }
+
+  /**
+   * Record which of the retrieved chunks an answer cited.
+   * Returns the number of cited chunks.
+   */
+  record(chunks: Chunk[], answer: string): number {
+    if (chunks.length === 0) return 0;
+
+    let citedCount = 0;
+    for (const chunk of chunks) {
+      const cited = isChunkCited(chunk, answer);
+      if (cited) citedCount++;
+      bump(this.state.paths, chunk.payload.file, cited);
+      bump(this.state.kinds, kindKey(chunk), cited);
+    }
+    this.state.answers++;
+    return citedCount;
+  }
+
+  /**
+   * Multiplier in [MIN_USAGE_WEIGHT, 1] for a chunk's similarity score
+   */
+  weight(chunk: Chunk): number {
+    if (this.state.answers < MIN_ANSWERS_FOR_WEIGHTING) return 1;
+
+    const baseline = this.baselineRate();
+    if (baseline === 0) return 1;
+
+    const factor = (counts?: UsageCounts) => {
+      if (!counts) return 1;
+      const rate = (counts.cited + PRIOR_STRENGTH * baseline) / (counts.retrieved + PRIOR_STRENGTH);
+      return Math.min(1, rate / baseline);
+    };
+
+    const weight = factor(this.state.paths[chunk.payload.file]) * factor(this.state.kinds[kindKey(chunk)]);
+    return Math.max(MIN_USAGE_WEIGHT, weight);
+  }
+
+  /**
+   * Re-rank chunks by usage-weighted score and keep the top `limit`
+   */
+  rerank(chunks: Chunk[], limit: number): Chunk[] {
+    return chunks
+      .map(chunk => ({ chunk, weighted: chunk.score * this.weight(chunk) }))
+      .sort((a, b) => b.weighted - a.weighted)
+      .slice(0, limit)
+      .map(({ chunk }) => chunk);
+  }
+
+  /**
+   * Fraction of all retrieved chunks that were cited
+   */
+  baselineRate(): number {
+    let retrieved = 0;
+    let cited = 0;
+    for (const counts of Object.values(this.state.kinds)) {
+      retrieved += counts.retrieved;
+      cited += counts.cited;
+    }
+    return retrieved === 0 ? 0 : cited / retrieved;
+  }
+
+  getState(): ContextUsageState {
+    return this.state;
+  }
+}
+
+function bump(table: Record<string, UsageCounts>, key: string, cited: boolean): void {
+  const counts = table[key] ?? (table[key] = { retrieved: 0, cited: 0 });
+  counts.retrieved++;
+  if (cited) counts.cited++;
+}
+
+export function getContextUsagePath(repoRoot: string): string {
+  return path.join(getCVDir(repoRoot), 'context-usage.json');
+}
+
+/**
+ * Load the usage model for a repository; starts empty if none is saved
+ */
+export async function loadContextUsage(repoRoot: string): Promise<ContextUsageModel> {
+  try {
+    const raw = await fs.readFile(getContextUsagePath(repoRoot), 'utf-8');
+    const state = JSON.parse(raw) as ContextUsageState;
+    if (state.version === 1) {
+      return new ContextUsageModel(state);
+    }
+  } catch {
+    // Missing or unreadable - start fresh
+  }
+  return new ContextUsageModel();
+}
+
+export async function saveContextUsage(repoRoot: string, model: ContextUsageModel): Promise<void> {
+  try {
+    const target = getContextUsagePath(repoRoot);
+    await fs.mkdir(path.dirname(target), { recursive: true });
+    await fs.writeFile(target, JSON.stringify(model.getState()), 'utf-8');
+  } catch (error) {
+    console.warn('[ContextUsage] Failed to save state:', error);
+  }
+}
@@ -0,0 +1,226 @@
+/**
+ * Retrieval Evaluation
+ * Scores embedding providers against a repository's own evaluation dataset.
+ *
+ * The dataset (.cv/eval/dataset.json) lists queries with the files or
+ * `file:symbol` entries a good retrieval should return. Each provider embeds
+ * the same corpus of indexed chunks and answers every query with an in-memory
+ * cosine search, so results differ only by the embedding model.
+ */
+
+import { promises as fs } from 'fs';
+import * as path from 'path';
+import { getCVDir } from '@cv-git/shared';
+
+export interface EvalCase {
+  query: string;
+  /** Relevant results: `path/to/file.ts` or `path/to/file.ts:symbolName` */
+  expected: string[];
+}
+
+export interface EvalDataset {
+  cases: EvalCase[];
+}
+
+export interface EvalCorpusChunk {
+  id: string;
+  file: string;
+  symbolName?: string;
+  text: string;
+}
+
+/** Anything that can embed text; VectorManager satisfies this */
+export interface EvalEmbedder {
+  embed(text: string): Promise<number[]>;
+  embedBatch(texts: string[]): Promise<number[][]>;
+}
+
+export interface EvalProvider {
+  name: string;
+  model: string;
+  embedder: EvalEmbedder;
+  /** USD per million input tokens; looked up from `model` when omitted */
+  pricePerMillionTokens?: number;
+}
+
+export interface EvalCaseResult {
+  query: string;
+  /** 1-based rank of the first relevant result, or null if none in top-k */
+  firstRelevantRank: number | null;
+  recall: number;
+  latencyMs: number;
+}
+
+export interface ProviderEvalResult {
+  name: string;
+  model: string;
+  k: number;
+  recallAtK: number;
+  mrr: number;
+  meanLatencyMs: number;
+  p95LatencyMs: number;
+  /** Estimated tokens embedded (corpus + queries) */
+  tokens: number;
+  /** Null when the model's price is unknown */
+  estimatedCostUsd: number | null;
+  cases: EvalCaseResult[];
+}
+
+/** USD per million tokens for hosted embedding models; local models are free */
+export const EMBEDDING_PRICES: Record<string, number> = {
+  'text-embedding-3-small': 0.02,
+  'text-embedding-3-large': 0.13,
+  'text-embedding-ada-002': 0.10,
+};
+
+const LOCAL_MODELS = ['nomic-embed-text', 'mxbai-embed-large', 'all-minilm', 'snowflake-arctic-embed'];
+
+export const DEFAULT_EVAL_K = 10;
+
+/**
+ * Price per million tokens for a model name, with or without a provider prefix
+ */
+export function embeddingPrice(model: string): number | undefined {
+  const bare = model.includes('/') ? model.slice(model.lastIndexOf('/') + 1) : model;
+  if (bare in EMBEDDING_PRICES) return EMBEDDING_PRICES[bare];
+  if (LOCAL_MODELS.some(m => bare.startsWith(m)) || model.includes('gguf')) return 0;
+  return undefined;
+}
+
+export function getEvalDatasetPath(repoRoot: string): string {
+  return path.join(getCVDir(repoRoot), 'eval', 'dataset.json');
+}
+
+/**
+ * Load an evaluation dataset: a JSON array of cases or `{ "cases": [...] }`
+ */
+export async function loadEvalDataset(file: string): Promise<EvalDataset> {
+  const parsed = JSON.parse(await fs.readFile(file, 'utf-8'));
+  const cases: unknown[] = Array.isArray(parsed) ? parsed : parsed?.cases;
+  if (!Array.isArray(cases)) {
+    throw new Error(`${file}: expected an array of cases or { "cases": [...] }`);
+  }
+
+  return {
+    cases: cases.map((c: any, i) => {
+      if (typeof c?.query !== 'string' || !Array.isArray(c.expected) || c.expected.length === 0) {
+        throw new Error(`${file}: case ${i + 1} needs a "query" string and a non-empty "expected" array`);
+      }
+      return { query: c.query, expected: c.expected.map(String) };
+    })
+  };
+}
+
+function cosine(a: number[], b: number[]): number {
+  let dot = 0;
+  let na = 0;
+  let nb = 0;
+  for (let i = 0; i < a.length; i++) {
+    dot += a[i] * b[i];
+    na += a[i] * a[i];
+    nb += b[i] * b[i];
+  }
+  return na === 0 || nb === 0 ? 0 : dot / Math.sqrt(na * nb);
+}
+
+function matches(chunk: Pick<EvalCorpusChunk, 'file' | 'symbolName'>, expected: string): boolean {
+  return expected === chunk.file || (!!chunk.symbolName && expected === `${chunk.file}:${chunk.symbolName}`);
+}
+
+/**
+ * Score one ranked result list against a case's expected entries
+ */
+export function scoreRanking(
+  ranked: Array<Pick<EvalCorpusChunk, 'file' | 'symbolName'>>,
+  expected: string[]
+): { firstRelevantRank: number | null; recall: number; found: string[] } {
+  const found = new Set<string>();
+  let firstRelevantRank: number | null = null;
+  ranked.forEach((chunk, i) => {
+    const hits = expected.filter(e => matches(chunk, e));
+    if (hits.length > 0 && firstRelevantRank === null) firstRelevantRank = i + 1;
+    hits.forEach(h => found.add(h));
+  });
+  return { firstRelevantRank, recall: expected.length > 0 ? found.size / expected.length : 0, found: [...found] };
+}
+
+function percentile(sorted: number[], p: number): number {
+  if (sorted.length === 0) return 0;
+  return sorted[Math.min(sorted.length - 1, Math.ceil(p * sorted.length) - 1)];
+}
+
+// Rough estimate: ~4 characters per token
+function estimateTokens(text: string): number {
+  return Math.ceil(text.length / 4);
+}
+
+/**
+ * Embed the corpus with one provider and score every dataset case
+ */
+export async function evaluateProvider(
+  provider: EvalProvider,
+  corpus: EvalCorpusChunk[],
+  dataset: EvalDataset,
+  options: { k?: number; batchSize?: number } = {}
+): Promise<ProviderEvalResult> {
+  const k = options.k ?? DEFAULT_EVAL_K;
+  const batchSize = options.batchSize ?? 64;
+
+  const vectors: number[][] = [];
+  for (let i = 0; i < corpus.length; i += batchSize) {
+    vectors.push(...await provider.embedder.embedBatch(corpus.slice(i, i + batchSize).map(c => c.text)));
+  }
+
+  let tokens = corpus.reduce((sum, c) => sum + estimateTokens(c.text), 0);
+  const cases: EvalCaseResult[] = [];
+
+  for (const evalCase of dataset.cases) {
+    const start = Date.now();
+    const queryVector = await provider.embedder.embed(evalCase.query);
+    const latencyMs = Date.now() - start;
+    tokens += estimateTokens(evalCase.query);
+
+    const ranked = corpus
+      .map((chunk, i) => ({ chunk, score: cosine(queryVector, vectors[i]) }))
+      .sort((a, b) => b.score - a.score)
+      .slice(0, k);
+
+    const { firstRelevantRank, recall } = scoreRanking(ranked.map(r => r.chunk), evalCase.expected);
+    cases.push({ query: evalCase.query, firstRelevantRank, recall, latencyMs });
+  }
+
+  const latencies = cases.map(c => c.latencyMs).sort((a, b) => a - b);
+  const count = Math.max(cases.length, 1);
+  const price = provider.pricePerMillionTokens ?? embeddingPrice(provider.model);
+
+  return {
+    name: provider.name,
+    model: provider.model,
+    k,
+    recallAtK: cases.reduce((sum, c) => sum + c.recall, 0) / count,
+    mrr: cases.reduce((sum, c) => sum + (c.firstRelevantRank ? 1 / c.firstRelevantRank : 0), 0) / count,
+    meanLatencyMs: latencies.reduce((sum, l) => sum + l, 0) / count,
+    p95LatencyMs: percentile(latencies, 0.95),
+    tokens,
+    estimatedCostUsd: price === undefined ? null : (tokens / 1_000_000) * price,
+    cases
+  };
+}
+
+/**
+ * Markdown table comparing providers side by side
+ */
+export function formatProviderComparison(results: ProviderEvalResult[]): string {
+  const lines = [
+    `| Provider | Model | Recall@${results[0]?.k ?? DEFAULT_EVAL_K} | MRR | Mean latency | p95 latency | Tokens | Est. cost |`,
+    '|---|---|---|---|---|---|---|---|'
+  ];
+  for (const r of results) {
+    const cost = r.estimatedCostUsd === null ? 'unknown' : `$${r.estimatedCostUsd.toFixed(4)}`;
+    lines.push(
+      `| ${r.name} | ${r.model} | ${r.recallAtK.toFixed(3)} | ${r.mrr.toFixed(3)} | ` +
+      `${r.meanLatencyMs.toFixed(0)}ms | ${r.p95LatencyMs.toFixed(0)}ms | ${r.tokens} | ${cost} |`
+    );
+  }
+  return lines.join('\n');
+}
@@ -0,0 +1,209 @@
+/**
+ * Retrieval Regression Suite
+ *
+ * `cv eval` runs queries from `.cv/eval/queries.yaml` against the live index,
+ * through the same search, min-score threshold and re-ranking as the other
+ * commands, and fails when recall or MRR drop below the suite's thresholds:
+ *
+ *   k: 10
+ *   thresholds:
+ *     recall: 0.8
+ *     mrr: 0.5
+ *   queries:
+ *     - query: where is the minimum similarity score applied?
+ *       expected:
+ *         - packages/core/src/context/score-distribution.ts
+ *         - packages/core/src/retrieval/index.ts:Retriever.retrieve
+ *
+ * A query that returns nothing scores zero, so a threshold that filters out
+ * every result fails the suite instead of passing silently.
+ */
+
+import { promises as fs } from 'fs';
+import * as path from 'path';
+import { parse } from 'yaml';
+import { getCVDir } from '@cv-git/shared';
+import { DEFAULT_EVAL_K, EvalCase, scoreRanking } from './index.js';
+
+export const EVAL_SUITE_FILE = 'queries.yaml';
+
+export interface EvalThresholds {
+  /** Lowest acceptable mean recall@k (0-1) */
+  recall?: number;
+  /** Lowest acceptable mean reciprocal rank (0-1) */
+  mrr?: number;
+}
+
+export interface EvalSuite {
+  k: number;
+  thresholds: EvalThresholds;
+  cases: EvalCase[];
+}
+
+/** A search result as the suite sees it */
+export interface EvalHit {
+  file: string;
+  symbolName?: string;
+  score?: number;
+}
+
+export interface SuiteCaseResult {
+  query: string;
+  expected: string[];
+  /** 1-based rank of the first relevant result, or null if none in top-k */
+  firstRelevantRank: number | null;
+  recall: number;
+  /** Expected entries not in the top k */
+  missing: string[];
+  /** What the search returned, best first */
+  results: EvalHit[];
+  latencyMs: number;
+}
+
+export interface SuiteReport {
+  k: number;
+  recallAtK: number;
+  mrr: number;
+  thresholds: EvalThresholds;
+  /** One message per threshold missed; empty when the suite passes */
+  failures: string[];
+  cases: SuiteCaseResult[];
+}
+
+export class EvalSuiteError extends Error {
+  constructor(message: string) {
+    super(message);
+    this.name = 'EvalSuiteError';
+  }
+}
+
+export function getEvalSuitePath(repoRoot: string): string {
+  return path.join(getCVDir(repoRoot), 'eval', EVAL_SUITE_FILE);
+}
+
+/**
+ * Parse and validate a suite file. A bare list of queries is a suite with
+ * the default k and no thresholds.
+ */
+export function parseEvalSuite(content: string, source: string = EVAL_SUITE_FILE): EvalSuite {
+  let doc: any;
+  try {
+    doc = parse(content);
+  } catch (error: any) {
+    throw new EvalSuiteError(`Invalid YAML in ${source}: ${error.message}`);
+  }
+
+  const queries = Array.isArray(doc) ? doc : doc?.queries ?? doc?.cases;
+  if (!Array.isArray(queries) || queries.length === 0) {
+    throw new EvalSuiteError(`${source}: expected a non-empty "queries" list`);
+  }
+
+  const k = Array.isArray(doc) || doc.k === undefined ? DEFAULT_EVAL_K : Number(doc.k);
+  if (!Number.isInteger(k) || k < 1) {
+    throw new EvalSuiteError(`${source}: "k" must be a positive whole number`);
+  }
+
+  const thresholds: EvalThresholds = {};
+  for (const name of ['recall', 'mrr'] as const) {
+    const value = Array.isArray(doc) ? undefined : doc.thresholds?.[name];
+    if (value === undefined || value === null) continue;
+    if (typeof value !== 'number' || value < 0 || value > 1) {
+      throw new EvalSuiteError(`${source}: thresholds.${name} must be a number from 0 to 1`);
+    }
+    thresholds[name] = value;
+  }
+
+  const cases = queries.map((entry: any, index: number): EvalCase => {
+    const where = `${source}: query ${index + 1}`;
+    const query = typeof entry?.query === 'string' ? entry.query.trim() : '';
+    if (!query) {
+      throw new EvalSuiteError(`${where}: "query" is required`);
+    }
+    const expected = entry.expected === undefined ? [] : (Array.isArray(entry.expected) ? entry.expected : [entry.expected]);
+    if (expected.length === 0) {
+      throw new EvalSuiteError(`${where} ("${query}"): "expected" needs at least one file or file:symbol`);
+    }
+    return { query, expected: expected.map((e: unknown) => String(e).trim().replace(/^\.\//, '')) };
+  });
+
+  return { k, thresholds, cases };
+}
+
+/**
+ * Load `.cv/eval/queries.yaml`, or the given file
+ */
+export async function loadEvalSuite(repoRoot: string, file?: string): Promise<EvalSuite> {
+  const filePath = file ? path.resolve(repoRoot, file) : getEvalSuitePath(repoRoot);
+  let content: string;
+  try {
+    content = await fs.readFile(filePath, 'utf-8');
+  } catch (error: any) {
+    throw new EvalSuiteError(`Cannot read eval suite ${filePath}: ${error.message}`);
+  }
+  return parseEvalSuite(content, path.relative(repoRoot, filePath) || filePath);
+}
+
+/**
+ * Run every query through `search` and score the results against the
+ * suite's thresholds (or `overrides`)
+ */
+export async function runEvalSuite(
+  suite: EvalSuite,
+  search: (query: string, k: number) => Promise<EvalHit[]>,
+  overrides: { k?: number; thresholds?: EvalThresholds } = {}
+): Promise<SuiteReport> {
+  const k = overrides.k ?? suite.k;
+  const thresholds = { ...suite.thresholds, ...overrides.thresholds };
+
+  const cases: SuiteCaseResult[] = [];
+  for (const evalCase of suite.cases) {
+    const start = Date.now();
+    const results = (await search(evalCase.query, k)).slice(0, k);
+    const latencyMs = Date.now() - start;
+    const { firstRelevantRank, recall, found } = scoreRanking(results, evalCase.expected);
+    cases.push({
+      query: evalCase.query,
+      expected: evalCase.expected,
+      firstRelevantRank,
+      recall,
+      missing: evalCase.expected.filter(e => !found.includes(e)),
+      results,
+      latencyMs
+    });
+  }
+
+  const count = Math.max(cases.length, 1);
+  const recallAtK = cases.reduce((sum, c) => sum + c.recall, 0) / count;
+  const mrr = cases.reduce((sum, c) => sum + (c.firstRelevantRank ? 1 / c.firstRelevantRank : 0), 0) / count;
+
+  const failures: string[] = [];
+  if (thresholds.recall !== undefined && recallAtK < thresholds.recall) {
+    failures.push(`recall@${k} ${recallAtK.toFixed(3)} is below ${thresholds.recall}`);
+  }
+  if (thresholds.mrr !== undefined && mrr < thresholds.mrr) {
+    failures.push(`MRR ${mrr.toFixed(3)} is below ${thresholds.mrr}`);
+  }
+
+  return { k, recallAtK, mrr, thresholds, failures, cases };
+}
+
+/**
+ * Markdown summary for CI job summaries and PR comments
+ */
+export function formatSuiteReport(report: SuiteReport): string {
+  const threshold = (value?: number) => (value === undefined ? '' : ` (min ${value})`);
+  const lines = [
+    `**Retrieval eval: ${report.failures.length === 0 ? 'passed' : 'failed'}**`,
+    '',
+    `- recall@${report.k}: ${report.recallAtK.toFixed(3)}${threshold(report.thresholds.recall)}`,
+    `- MRR: ${report.mrr.toFixed(3)}${threshold(report.thresholds.mrr)}`,
+    '',
+    '| Query | First relevant rank | Recall | Missing |',
+    '|---|---|---|---|'
+  ];
+  for (const c of report.cases) {
+    const missing = c.missing.map(m => `\`${m}\``).join(', ') || '—';
+    lines.push(`| ${c.query.replace(/\|/g, '\\|')} | ${c.firstRelevantRank ?? '—'} | ${c.recall.toFixed(2)} | ${missing} |`);
+  }
+  return lines.join('\n');
+}
@@ -0,0 +1,157 @@
+/**
+ * Command Fixtures
+ * Record the full pipeline of a command (retrieval, prompts, provider
+ * responses, embedding calls) into a snapshot that can be replayed for
+ * deterministic end-to-end tests
+ */
+
+import { promises as fs } from 'fs';
+import * as path from 'path';
+import { Context } from '@cv-git/shared';
+
+export const FIXTURE_VERSION = 1;
+
+export type FixtureEntry =
+  | {
+      type: 'retrieval';
+      query: string;
+      context: Context;
+    }
+  | {
+      type: 'prompt';
+      model: string;
+      messages: Array<{ role: 'user' | 'assistant'; content: string }>;
+    }
+  | {
+      type: 'response';
+      text: string;
+    }
+  | {
+      type: 'embedding';
+      provider: string;
+      model: string;
+      inputs: number;
+      dimensions: number[];
+      cached: number;
+    };
+
+export interface CommandFixture {
+  version: number;
+  command: string;
+  args: string[];
+  recordedAt: number;
+  entries: FixtureEntry[];
+}
+
+export class FixtureError extends Error {
+  constructor(message: string) {
+    super(message);
+    this.name = 'FixtureError';
+  }
+}
+
+/**
+ * Collects pipeline events while a command runs
+ */
+export class FixtureRecorder {
+  private entries: FixtureEntry[] = [];
+
+  constructor(
+    private command: string,
+    private args: string[] = []
+  ) {}
+
+  record(entry: FixtureEntry): void {
+    // Deep copy so later mutation of the context doesn't leak into the snapshot
+    this.entries.push(JSON.parse(JSON.stringify(entry)));
+  }
+
+  toFixture(): CommandFixture {
+    return {
+      version: FIXTURE_VERSION,
+      command: this.command,
+      args: this.args,
+      recordedAt: Date.now(),
+      entries: this.entries
+    };
+  }
+
+  async save(filePath: string): Promise<void> {
+    await fs.mkdir(path.dirname(filePath), { recursive: true });
+    await fs.writeFile(filePath, JSON.stringify(this.toFixture(), null, 2) + '\n', 'utf-8');
+  }
+}
+
+/**
+ * Serves recorded retrieval results and provider responses in order
+ */
+export class FixtureReplayer {
+  private responseIndex = 0;
+  private retrievalIndex = 0;
+  private prompts: FixtureEntry[] = [];
+
+  constructor(private fixture: CommandFixture) {
+    if (fixture.version !== FIXTURE_VERSION) {
+      throw new FixtureError(`Unsupported fixture version ${fixture.version}`);
+    }
+  }
+
+  static async load(filePath: string): Promise<FixtureReplayer> {
+    const raw = await fs.readFile(filePath, 'utf-8');
+    return new FixtureReplayer(JSON.parse(raw) as CommandFixture);
+  }
+
+  /**
+   * Next recorded retrieval, or undefined when the fixture has none left
+   */
+  nextRetrieval(): Context | undefined {
+    const retrievals = this.ofType('retrieval');
+    const entry = retrievals[this.retrievalIndex++];
+    return entry ? JSON.parse(JSON.stringify(entry.context)) : undefined;
+  }
+
+  /**
+   * Next recorded provider response; replay fails loudly when the command
+   * makes more calls than were recorded
+   */
+  nextResponse(messages: Array<{ role: 'user' | 'assistant'; content: string }>): string {
+    this.prompts.push({ type: 'prompt', model: 'replay', messages });
+    const responses = this.ofType('response');
+    const entry = responses[this.responseIndex++];
+    if (!entry) {
+      throw new FixtureError(
+        `Fixture for "${this.fixture.command}" has no response #${this.responseIndex}`
+      );
+    }
+    return entry.text;
+  }
+
+  /**
+   * Prompts recorded in the fixture, for comparing against replayed prompts
+   */
+  get recordedPrompts(): Array<Extract<FixtureEntry, { type: 'prompt' }>> {
+    return this.ofType('prompt');
+  }
+
+  /**
+   * Prompts the command produced during replay
+   */
+  get replayedPrompts(): Array<Extract<FixtureEntry, { type: 'prompt' }>> {
+    return this.prompts as Array<Extract<FixtureEntry, { type: 'prompt' }>>;
+  }
+
+  private ofType<T extends FixtureEntry['type']>(type: T): Array<Extract<FixtureEntry, { type: T }>> {
+    return this.fixture.entries.filter(e => e.type === type) as Array<Extract<FixtureEntry, { type: T }>>;
+  }
+}
+
+/**
+ * Resolve where a fixture for `command` should be written
+ */
+export function resolveFixturePath(repoRoot: string, command: string, target?: string | boolean): string {
+  if (typeof target === 'string' && target.length > 0) {
+    return path.resolve(target);
+  }
+  const stamp = new Date().toISOString().replace(/[:.]/g, '-');
+  return path.join(repoRoot, '.cv', 'fixtures', `${command}-${stamp}.json`);
+}
@@ -0,0 +1,154 @@
+/**
+ * Blame History
+ *
+ * Who last changed each line of a range (`git blame --line-porcelain`), and
+ * the messages and diffs of the commits that introduced them, for
+ * `cv blame-explain` to reason about why the code looks the way it does.
+ */
+
+import type { GitManager } from './index.js';
+
+/** SHA git blame reports for uncommitted lines */
+export const UNCOMMITTED_SHA = '0000000000000000000000000000000000000000';
+
+export interface BlameLine {
+  sha: string;
+  /** Line number in the current file */
+  line: number;
+  author: string;
+  authorEmail: string;
+  /** Author time (ms since epoch) */
+  date: number;
+  summary: string;
+  text: string;
+}
+
+export interface BlameCommit {
+  sha: string;
+  author: string;
+  authorEmail: string;
+  date: number;
+  summary: string;
+  /** Current-file line ranges this commit last touched */
+  ranges: Array<[number, number]>;
+  /** Full commit message (filled in by collectBlameHistory) */
+  message?: string;
+  /** The commit's diff of the blamed file, truncated */
+  diff?: string;
+}
+
+export interface BlameHistory {
+  file: string;
+  startLine: number;
+  endLine: number;
+  lines: BlameLine[];
+  /** Commits behind the range, most lines first */
+  commits: BlameCommit[];
+}
+
+/**
+ * Parse `path:120-160` (or `path:120`) into a file and line range
+ * @throws when no line range is given
+ */
+export function parseLineRange(target: string): { file: string; startLine: number; endLine: number } {
+  const match = target.match(/^(.+?):(\d+)(?:-(\d+))?$/);
+  if (!match) {
+    throw new Error(`Expected <file>:<start>-<end> (e.g. src/engine.rs:120-160), got "${target}"`);
+  }
+  const startLine = Number(match[2]);
+  const endLine = match[3] ? Number(match[3]) : startLine;
+  if (startLine < 1 || endLine < startLine) {
+    throw new Error(`Invalid line range ${startLine}-${endLine}`);
+  }
+  return { file: match[1], startLine, endLine };
+}
+
+/**
+ * Parse `git blame --line-porcelain` output
+ */
+export function parseBlamePorcelain(raw: string): BlameLine[] {
+  const lines: BlameLine[] = [];
+  let current: Partial<BlameLine> | null = null;
+
+  for (const row of raw.split('\n')) {
+    const header = row.match(/^([0-9a-f]{40}) \d+ (\d+)/);
+    if (header) {
+      current = { sha: header[1], line: Number(header[2]), author: '', authorEmail: '', date: 0, summary: '' };
+      continue;
+    }
+    if (!current) continue;
+
+    if (row.startsWith('\t')) {
+      lines.push({ ...(current as BlameLine), text: row.slice(1) });
+      current = null;
+    } else if (row.startsWith('author ')) {
+      current.author = row.slice('author '.length);
+    } else if (row.startsWith('author-mail ')) {
+      current.authorEmail = row.slice('author-mail '.length).replace(/^<|>$/g, '');
+    } else if (row.startsWith('author-time ')) {
+      current.date = Number(row.slice('author-time '.length)) * 1000;
+    } else if (row.startsWith('summary ')) {
+      current.summary = row.slice('summary '.length);
+    }
+  }
+
+  return lines;
+}
+
+/**
+ * Group blamed lines by commit; commits covering the most lines first
+ */
+export function groupBlame(lines: BlameLine[]): BlameCommit[] {
+  const commits = new Map<string, BlameCommit & { count: number }>();
+  for (const line of lines) {
+    let commit = commits.get(line.sha);
+    if (!commit) {
+      commit = {
+        sha: line.sha,
+        author: line.author,
+        authorEmail: line.authorEmail,
+        date: line.date,
+        summary: line.summary,
+        ranges: [],
+        count: 0
+      };
+      commits.set(line.sha, commit);
+    }
+    commit.count++;
+    const last = commit.ranges[commit.ranges.length - 1];
+    if (last && last[1] === line.line - 1) {
+      last[1] = line.line;
+    } else {
+      commit.ranges.push([line.line, line.line]);
+    }
+  }
+  return [...commits.values()]
+    .sort((a, b) => b.count - a.count || b.date - a.date)
+    .map(({ count, ...commit }) => commit);
+}
+
+/**
+ * Blame a range and load the messages and diffs of the commits behind it
+ */
+export async function collectBlameHistory(
+  git: Pick<GitManager, 'getBlame' | 'getCommitPatch'>,
+  target: { file: string; startLine: number; endLine: number },
+  options: { maxCommits?: number; maxDiffChars?: number } = {}
+): Promise<BlameHistory> {
+  const maxCommits = options.maxCommits ?? 8;
+  const maxDiffChars = options.maxDiffChars ?? 4000;
+
+  const lines = await git.getBlame(target.file, target.startLine, target.endLine);
+  const commits = groupBlame(lines).slice(0, maxCommits);
+
+  for (const commit of commits) {
+    if (commit.sha === UNCOMMITTED_SHA) continue;
+    const patch = await git.getCommitPatch(commit.sha, target.file);
+    commit.message = patch.message.trim();
+    commit.diff = patch.diff.length > maxDiffChars
+      ? `${patch.diff.slice(0, maxDiffChars)}\n... (diff truncated)`
+      : patch.diff;
+  }
+
+  return { ...target, lines, commits };
+}
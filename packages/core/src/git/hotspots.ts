@@ -0,0 +1,234 @@
+/**
+ * Change Hotspots
+ *
+ * Files that change often and are hard to follow are where defects cluster.
+ * Churn mined from `git log --numstat` (commits, distinct authors, commits
+ * that look like bug fixes) is multiplied by the complexity `cv sync`
+ * recorded for the file's functions:
+ *
+ *   churn = (commits + fix commits) * (1 + log2(authors))
+ *   score = churn * sum of cyclomatic complexity
+ *
+ * Scores are scaled so the riskiest file is 100. Functions inside hotspot
+ * files are ranked by the file's churn times their own complexity.
+ */
+
+import { complexityReport, ComplexityEntry } from '../graph/complexity.js';
+import type { GraphManager } from '../graph/index.js';
+import type { GitManager } from './index.js';
+
+/** Subjects that usually mean a defect was fixed */
+export const BUG_FIX_PATTERN = /\b(fix(e[sd]|ing)?|bug(fix)?s?|hotfix|regression|crash(es)?|defect|broken|revert)\b/i;
+
+const RECORD = '\x1e';
+const FIELD = '\x1f';
+
+/** `git log` format parsed by parseChurnLog */
+export const CHURN_LOG_FORMAT = `${RECORD}%H${FIELD}%an${FIELD}%ae${FIELD}%ct${FIELD}%s`;
+
+export interface ChurnCommit {
+  sha: string;
+  author: string;
+  authorEmail: string;
+  /** Commit time (ms since epoch) */
+  date: number;
+  subject: string;
+  files: Array<{ path: string; added: number; deleted: number }>;
+}
+
+export interface FileChurn {
+  file: string;
+  commits: number;
+  authors: number;
+  fixes: number;
+  linesChanged: number;
+  lastChanged: number;
+}
+
+export interface Hotspot extends FileChurn {
+  /** Sum of the cyclomatic complexity of the file's functions */
+  complexity: number;
+  functions: number;
+  churn: number;
+  /** 0-100, relative to the riskiest file */
+  score: number;
+}
+
+export interface FunctionHotspot {
+  qualifiedName: string;
+  name: string;
+  file: string;
+  startLine: number;
+  endLine: number;
+  cyclomatic: number;
+  cognitive: number | null;
+  /** 0-100, relative to the riskiest function */
+  score: number;
+}
+
+/**
+ * Parse `git log --numstat --format=CHURN_LOG_FORMAT`
+ */
+export function parseChurnLog(raw: string): ChurnCommit[] {
+  const commits: ChurnCommit[] = [];
+  for (const record of raw.split(RECORD)) {
+    if (!record.trim()) continue;
+    const [header, ...stats] = record.split('\n');
+    const [sha, author, authorEmail, time, subject] = header.split(FIELD);
+    if (!sha) continue;
+
+    const files: ChurnCommit['files'] = [];
+    for (const line of stats) {
+      const match = line.match(/^(\d+|-)\t(\d+|-)\t(.+)$/);
+      if (!match) continue;
+      files.push({
+        path: renamedPath(match[3]),
+        added: match[1] === '-' ? 0 : Number(match[1]),
+        deleted: match[2] === '-' ? 0 : Number(match[2])
+      });
+    }
+    commits.push({ sha, author, authorEmail, date: Number(time) * 1000, subject: subject ?? '', files });
+  }
+  return commits;
+}
+
+/**
+ * New path of a numstat rename (`a => b`, `src/{old => new}/x.ts`)
+ */
+function renamedPath(path: string): string {
+  const braced = path.match(/^(.*)\{(.*) => (.*)\}(.*)$/);
+  if (braced) return `${braced[1]}${braced[3]}${braced[4]}`.replace(/\/\//g, '/');
+  const plain = path.match(/^(.*) => (.*)$/);
+  return plain ? plain[2] : path;
+}
+
+/**
+ * Commits, authors and fix commits per file
+ */
+export function fileChurn(commits: ChurnCommit[]): FileChurn[] {
+  const byFile = new Map<string, FileChurn & { authorSet: Set<string> }>();
+  for (const commit of commits) {
+    const fix = BUG_FIX_PATTERN.test(commit.subject);
+    for (const file of commit.files) {
+      let churn = byFile.get(file.path);
+      if (!churn) {
+        churn = { file: file.path, commits: 0, authors: 0, fixes: 0, linesChanged: 0, lastChanged: 0, authorSet: new Set() };
+        byFile.set(file.path, churn);
+      }
+      churn.commits++;
+      if (fix) churn.fixes++;
+      churn.linesChanged += file.added + file.deleted;
+      churn.lastChanged = Math.max(churn.lastChanged, commit.date);
+      churn.authorSet.add((commit.authorEmail || commit.author).toLowerCase());
+    }
+  }
+  return [...byFile.values()].map(({ authorSet, ...churn }) => ({ ...churn, authors: authorSet.size }));
+}
+
+/**
+ * Files ranked by churn times complexity; files without indexed functions
+ * are left out
+ */
+export function rankHotspots(churn: FileChurn[], functions: ComplexityEntry[]): Hotspot[] {
+  const complexity = new Map<string, { total: number; count: number }>();
+  for (const fn of functions) {
+    const entry = complexity.get(fn.file) ?? { total: 0, count: 0 };
+    entry.total += fn.cyclomatic;
+    entry.count++;
+    complexity.set(fn.file, entry);
+  }
+
+  const hotspots = churn
+    .filter(file => complexity.has(file.file))
+    .map(file => {
+      const { total, count } = complexity.get(file.file)!;
+      const churnScore = churnWeight(file);
+      return { ...file, complexity: total, functions: count, churn: round(churnScore), score: churnScore * total };
+    });
+
+  const max = Math.max(0, ...hotspots.map(h => h.score));
+  return hotspots
+    .map(h => ({ ...h, score: max > 0 ? round((h.score / max) * 100) : 0 }))
+    .sort((a, b) => b.score - a.score || b.commits - a.commits || a.file.localeCompare(b.file));
+}
+
+/**
+ * Functions in the given hotspot files, ranked by file churn times their
+ * own complexity
+ */
+export function rankFunctionHotspots(hotspots: Hotspot[], functions: ComplexityEntry[]): FunctionHotspot[] {
+  const churn = new Map(hotspots.map(h => [h.file, h.churn]));
+  const ranked = functions
+    .filter(fn => churn.has(fn.file))
+    .map(fn => ({
+      qualifiedName: fn.qualifiedName,
+      name: fn.name,
+      file: fn.file,
+      startLine: fn.startLine,
+      endLine: fn.endLine,
+      cyclomatic: fn.cyclomatic,
+      cognitive: fn.cognitive,
+      score: churn.get(fn.file)! * (fn.cognitive ?? fn.cyclomatic)
+    }));
+
+  const max = Math.max(0, ...ranked.map(fn => fn.score));
+  return ranked
+    .map(fn => ({ ...fn, score: max > 0 ? round((fn.score / max) * 100) : 0 }))
+    .sort((a, b) => b.score - a.score || b.cyclomatic - a.cyclomatic || a.qualifiedName.localeCompare(b.qualifiedName));
+}
+
+export interface HotspotOptions {
+  /** History window, as git understands it (`12 months ago`, `2024-01-01`) */
+  since?: string;
+  /** Most recent commits to mine */
+  maxCommits?: number;
+  /** Only files under this path */
+  file?: string;
+}
+
+/**
+ * Mine history and rank file and function hotspots
+ */
+export async function collectHotspots(
+  git: Pick<GitManager, 'getChurnHistory'>,
+  graph: Pick<GraphManager, 'query'>,
+  options: HotspotOptions = {}
+): Promise<{ files: Hotspot[]; functions: FunctionHotspot[]; commits: number }> {
+  const commits = await git.getChurnHistory({
+    since: options.since,
+    maxCount: options.maxCommits,
+    paths: options.file ? [options.file] : undefined
+  });
+  const functions = await complexityReport(graph, { file: options.file });
+  const files = rankHotspots(fileChurn(commits), functions);
+  return { files, functions: rankFunctionHotspots(files, functions), commits: commits.length };
+}
+
+function churnWeight(file: FileChurn): number {
+  return (file.commits + file.fixes) * (1 + Math.log2(Math.max(1, file.authors)));
+}
+
+function round(value: number): number {
+  return Math.round(value * 10) / 10;
+}
+
+/**
+ * Review prompt section naming the hotspots a change touches
+ */
+export function buildHotspotPrompt(files: Hotspot[], functions: FunctionHotspot[] = []): string {
+  if (files.length === 0) return '';
+  let prompt = `## Hotspots\n`;
+  prompt += `These files change often and are complex, so defects cluster here. Review changes to them with extra care.\n\n`;
+  for (const file of files) {
+    prompt += `- ${file.file} (risk ${file.score}/100: ${file.commits} commits, ${file.fixes} bug fixes, ` +
+      `${file.authors} authors, complexity ${file.complexity})\n`;
+  }
+  if (functions.length > 0) {
+    prompt += `\nRiskiest functions:\n`;
+    for (const fn of functions) {
+      prompt += `- ${fn.name} (${fn.file}:${fn.startLine}-${fn.endLine}, cyclomatic ${fn.cyclomatic}` +
+        `${fn.cognitive !== null ? `, cognitive ${fn.cognitive}` : ''})\n`;
+    }
+  }
+  return prompt;
+}
@@ -7,6 +7,8 @@ import { simpleGit, SimpleGit, StatusResult, DiffResult, LogResult } from 'simpl
 import * as path from 'path';
 import * as fs from 'fs/promises';
 import { GitError, WorkingTreeStatus, GitCommit, GitDiff } from '@cv-git/shared';
+import { CHURN_LOG_FORMAT, ChurnCommit, parseChurnLog } from './hotspots.js';
+import { BlameLine, parseBlamePorcelain } from './blame.js';
 
 const HOOK_MARKER = '# CV-GIT HOOK';
 
@@ -70,6 +72,18 @@ export class GitManager {
     }
   }
 
+  /**
+   * Get local branch names
+   */
+  async getLocalBranches(): Promise<string[]> {
+    try {
+      const branches = await this.git.branchLocal();
+      return branches.all;
+    } catch (error: any) {
+      throw new GitError(`Failed to list branches: ${error.message}`, error);
+    }
+  }
+
   /**
    * Get working tree status
    */
@@ -184,6 +198,51 @@ export class GitManager {
     }
   }
 
+  /**
+   * Commits with per-file line counts, newest first (for `cv hotspots` and
+   * `cv diff-summary`, which passes a revision range)
+   */
+  async getChurnHistory(options: { since?: string; maxCount?: number; paths?: string[]; range?: string } = {}): Promise<ChurnCommit[]> {
+    try {
+      const args = ['log', '--no-merges', '--numstat', `--format=${CHURN_LOG_FORMAT}`];
+      if (options.range) args.push(options.range);
+      if (options.since) args.push(`--since=${options.since}`);
+      if (options.maxCount) args.push(`--max-count=${options.maxCount}`);
+      if (options.paths?.length) args.push('--', ...options.paths);
+      return parseChurnLog(await this.git.raw(args));
+    } catch (error: any) {
+      throw new GitError(`Failed to read commit history: ${error.message}`, error);
+    }
+  }
+
+  /**
+   * Last commit to touch each line of a range
+   */
+  async getBlame(filePath: string, startLine: number, endLine: number): Promise<BlameLine[]> {
+    try {
+      return parseBlamePorcelain(await this.git.raw(['blame', '--line-porcelain', '-L', `${startLine},${endLine}`, '--', filePath]));
+    } catch (error: any) {
+      throw new GitError(`Failed to blame ${filePath}: ${error.message}`, error);
+    }
+  }
+
+  /**
+   * Full message and diff of a commit, optionally limited to one file
+   */
+  async getCommitPatch(sha: string, filePath?: string): Promise<{ message: string; diff: string }> {
+    try {
+      const args = ['show', '--format=%B%x1e', '--patch', sha];
+      if (filePath) args.push('--', filePath);
+      const raw = await this.git.raw(args);
+      const split = raw.indexOf('\x1e');
+      return split === -1
+        ? { message: raw, diff: '' }
+        : { message: raw.slice(0, split), diff: raw.slice(split + 1).replace(/^\n+/, '') };
+    } catch (error: any) {
+      throw new GitError(`Failed to show commit ${sha}: ${error.message}`, error);
+    }
+  }
+
   /**
    * Get diff between two commits
    */
@@ -207,9 +266,9 @@ export class GitManager {
   /**
    * Get raw diff text (for review, etc.)
    */
-  async getRawDiff(ref?: string): Promise<string> {
+  async getRawDiff(ref?: string | string[]): Promise<string> {
     try {
-      const args = ref ? [ref] : [];
+      const args = Array.isArray(ref) ? ref : ref ? [ref] : [];
       const diff = await this.git.diff(args);
       return diff || '';
     } catch (error: any) {
@@ -276,6 +335,31 @@ export class GitManager {
     }
   }
 
+  /**
+   * Get files whose working tree content differs from a commit: commits made
+   * since, plus staged and unstaged edits to tracked files
+   */
+  async getWorkingTreeChangesSince(commitSha: string): Promise<string[]> {
+    try {
+      const diff = await this.git.raw(['diff', '--name-only', '--no-renames', '-z', commitSha]);
+      return diff.split('\0').filter(f => f.length > 0);
+    } catch (error: any) {
+      throw new GitError(`Failed to get changed files: ${error.message}`, error);
+    }
+  }
+
+  /**
+   * Check that a commit exists (it may be gone after a rebase or shallow fetch)
+   */
+  async commitExists(commitSha: string): Promise<boolean> {
+    try {
+      await this.git.raw(['cat-file', '-e', `${commitSha}^{commit}`]);
+      return true;
+    } catch {
+      return false;
+    }
+  }
+
   /**
    * Get the git blob hash for a file
    * This is the SHA-1 hash of the file content as stored in git
@@ -300,6 +384,18 @@ export class GitManager {
     }
   }
 
+  /**
+   * Get the blob hash of a file's current working tree content
+   */
+  async getWorkingTreeHash(filePath: string): Promise<string> {
+    try {
+      const result = await this.git.raw(['hash-object', filePath]);
+      return result.trim();
+    } catch {
+      return '';
+    }
+  }
+
   /**
    * Get git blob hashes for multiple files in batch
    * More efficient than calling getFileHash for each file
@@ -494,3 +590,9 @@ ${existingContent.replace(/^#!.*\n/, '')}
 export function createGitManager(repoRoot: string): GitManager {
   return new GitManager(repoRoot);
 }
+
+// Churn x complexity ranking (cv hotspots)
+export * from './hotspots.js';
+
+// Line-range blame history (cv blame-explain)
+export * from './blame.js';
@@ -0,0 +1,103 @@
+/**
+ * Function Complexity Report
+ *
+ * Ranks the functions and methods recorded by `cv sync` by cyclomatic or
+ * cognitive complexity (see parser/complexity.ts), so refactors can start
+ * with the worst offenders.
+ */
+
+import type { GraphManager } from './index.js';
+
+export type ComplexitySortField = 'cognitive' | 'cyclomatic' | 'lines' | 'name';
+export const COMPLEXITY_SORT_FIELDS: ComplexitySortField[] = ['cognitive', 'cyclomatic', 'lines', 'name'];
+
+export interface ComplexityEntry {
+  qualifiedName: string;
+  name: string;
+  kind: string;
+  file: string;
+  startLine: number;
+  endLine: number;
+  lines: number;
+  cyclomatic: number;
+  /** Null for symbols indexed before cognitive complexity was recorded */
+  cognitive: number | null;
+}
+
+export interface ComplexityReportOptions {
+  /** Field to rank by, highest first (`name` sorts alphabetically) */
+  sort?: ComplexitySortField;
+  /** Number of functions to return */
+  top?: number;
+  /** Only functions in files under this path */
+  file?: string;
+  /** Only functions whose sort field is at least this */
+  min?: number;
+}
+
+/**
+ * Functions and methods ranked by complexity
+ */
+export async function complexityReport(
+  graph: Pick<GraphManager, 'query'>,
+  options: ComplexityReportOptions = {}
+): Promise<ComplexityEntry[]> {
+  const sort = options.sort ?? 'cognitive';
+  const rows = await graph.query(
+    "MATCH (s:Symbol) WHERE s.kind IN ['function', 'method'] " +
+    'RETURN s.qualifiedName as qualifiedName, s.name as name, s.kind as kind, s.file as file, ' +
+    's.startLine as startLine, s.endLine as endLine, s.complexity as complexity, s.cognitiveComplexity as cognitive'
+  );
+
+  const prefix = options.file?.replace(/\\/g, '/').replace(/^\.\//, '');
+  let entries: ComplexityEntry[] = rows
+    .map(row => {
+      const startLine = Number(row.startLine ?? 0);
+      const endLine = Number(row.endLine ?? startLine);
+      return {
+        qualifiedName: String(row.qualifiedName),
+        name: String(row.name ?? ''),
+        kind: String(row.kind ?? 'function'),
+        file: String(row.file ?? ''),
+        startLine,
+        endLine,
+        lines: endLine - startLine + 1,
+        cyclomatic: Number(row.complexity ?? 1),
+        cognitive: row.cognitive === null || row.cognitive === undefined ? null : Number(row.cognitive)
+      };
+    })
+    .filter(entry => !prefix || entry.file.startsWith(prefix));
+
+  if (options.min !== undefined && sort !== 'name') {
+    entries = entries.filter(entry => (sortValue(entry, sort) ?? 0) >= options.min!);
+  }
+
+  entries.sort((a, b) => sort === 'name'
+    ? a.name.localeCompare(b.name) || a.file.localeCompare(b.file)
+    : (sortValue(b, sort) ?? -1) - (sortValue(a, sort) ?? -1) ||
+      b.cyclomatic - a.cyclomatic ||
+      a.qualifiedName.localeCompare(b.qualifiedName));
+
+  return options.top !== undefined ? entries.slice(0, Math.max(0, options.top)) : entries;
+}
+
+function sortValue(entry: ComplexityEntry, sort: Exclude<ComplexitySortField, 'name'>): number | null {
+  return sort === 'lines' ? entry.lines : entry[sort];
+}
+
+/**
+ * CSV with a header row, for spreadsheets
+ */
+export function formatComplexityCsv(entries: ComplexityEntry[]): string {
+  const cell = (value: string | number | null) => {
+    const text = value === null ? '' : String(value);
+    return /[",\n]/.test(text) ? `"${text.replace(/"/g, '""')}"` : text;
+  };
+  const lines = ['name,kind,file,start_line,end_line,lines,cyclomatic,cognitive'];
+  for (const entry of entries) {
+    lines.push([
+      entry.name, entry.kind, entry.file, entry.startLine, entry.endLine, entry.lines, entry.cyclomatic, entry.cognitive
+    ].map(cell).join(','));
+  }
+  return lines.join('\n') + '\n';
+}
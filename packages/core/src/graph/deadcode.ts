@@ -0,0 +1,264 @@
+/**
+ * Dead Code Detection
+ *
+ * A symbol is likely dead when nothing in the graph calls it and its name
+ * appears nowhere in the indexed sources outside its own definition (so
+ * callbacks, dynamic dispatch and re-exports still count as uses). Findings
+ * are graded by how sure we can be:
+ *
+ * - high: private to its file or module (Rust/Java visibility, a leading `_`
+ *   in Python, not exported in TypeScript, lowercase in Go)
+ * - medium: public, so code outside this repository may still use it
+ * - low: a method of a type that extends or implements another, or an item
+ *   under a decorator/attribute, which a framework or trait may invoke
+ *
+ * Entry points (`main`, constructors, dunder methods, tests) are never
+ * reported, and `deadcode.allow` lists intentional ones.
+ */
+
+import * as path from 'path';
+import { detectLanguage, matchGlob } from '@cv-git/shared';
+import type { GraphManager } from './index.js';
+
+export type DeadCodeConfidence = 'high' | 'medium' | 'low';
+export const DEAD_CODE_CONFIDENCE: DeadCodeConfidence[] = ['high', 'medium', 'low'];
+
+export interface DeadSymbol {
+  qualifiedName: string;
+  name: string;
+  kind: string;
+  file: string;
+  startLine: number;
+  endLine: number;
+  language: string;
+  confidence: DeadCodeConfidence;
+  reason: string;
+}
+
+export interface DeadCodeReport {
+  /** Symbols checked */
+  scanned: number;
+  /** Likely dead, highest confidence first */
+  dead: DeadSymbol[];
+  /** Unreferenced symbols skipped by the allowlist */
+  allowed: number;
+}
+
+export interface DeadCodeOptions {
+  /** Names, qualified names or file globs of intentional entry points */
+  allow?: string[];
+  /** Only check symbols in files under this path */
+  file?: string;
+  /** Read a repository file (null when it cannot be read) */
+  readFile: (file: string) => Promise<string | null>;
+}
+
+interface GraphSymbol {
+  qualifiedName: string;
+  name: string;
+  kind: string;
+  file: string;
+  startLine: number;
+  endLine: number;
+  visibility: string;
+}
+
+const CHECKED_KINDS = new Set(['function', 'method', 'class', 'struct']);
+
+const ENTRY_POINTS = new Set(['main', 'init', 'constructor', '<init>', 'new', 'default', 'setup', 'teardown']);
+
+const TEST_FILE = /(^|\/)(tests?|__tests__|spec|benches|examples?)\/|\.(test|spec)\.[^/]+$|_test\.go$|(^|\/)test_[^/]+\.py$|_test\.py$/;
+
+const IDENTIFIER = /[A-Za-z_$][\w$]*/g;
+
+/**
+ * Symbols no call site or reference reaches
+ */
+export async function detectDeadCode(graph: Pick<GraphManager, 'query'>, options: DeadCodeOptions): Promise<DeadCodeReport> {
+  const symbolRows = await graph.query(
+    'MATCH (s:Symbol) RETURN s.qualifiedName as qualifiedName, s.name as name, s.kind as kind, s.file as file, ' +
+    's.startLine as startLine, s.endLine as endLine, s.visibility as visibility'
+  );
+  const symbols: GraphSymbol[] = symbolRows.map(row => ({
+    qualifiedName: String(row.qualifiedName),
+    name: String(row.name ?? ''),
+    kind: String(row.kind ?? ''),
+    file: String(row.file ?? ''),
+    startLine: Number(row.startLine ?? 0),
+    endLine: Number(row.endLine ?? 0),
+    visibility: String(row.visibility ?? 'public')
+  }));
+
+  const called = new Set((await graph.query(
+    'MATCH (:Symbol)-[:CALLS]->(s:Symbol) RETURN DISTINCT s.qualifiedName as id'
+  )).map(row => String(row.id)));
+  const subtypes = new Set((await graph.query(
+    'MATCH (s:Symbol)-[:INHERITS]->(:Symbol) RETURN DISTINCT s.qualifiedName as id'
+  )).map(row => String(row.id)));
+  const files = (await graph.query('MATCH (f:File) RETURN f.path as path')).map(row => String(row.path));
+
+  const prefix = options.file?.replace(/\\/g, '/').replace(/^\.\//, '');
+  const candidates = symbols.filter(symbol =>
+    CHECKED_KINDS.has(symbol.kind) &&
+    (!prefix || symbol.file.startsWith(prefix)) &&
+    !called.has(symbol.qualifiedName) &&
+    !TEST_FILE.test(symbol.file) &&
+    !isEntryPoint(symbol, detectLanguage(symbol.file))
+  );
+
+  // Where each candidate name appears in the sources
+  const names = new Set(candidates.map(symbol => symbol.name));
+  const references = new Map<string, Array<{ file: string; line: number }>>();
+  const contents = new Map<string, string[]>();
+  for (const file of new Set([...files, ...candidates.map(symbol => symbol.file)])) {
+    const content = await options.readFile(file);
+    if (content === null) continue;
+    const lines = content.split('\n');
+    contents.set(file, lines);
+    lines.forEach((text, i) => {
+      // `export { a, b }` lists declare, they do not use
+      if (/^\s*export\s*(type\s*)?\{[^}]*\}\s*;?\s*$/.test(text) && !/\bfrom\b/.test(text)) return;
+      for (const match of stripComment(text).matchAll(IDENTIFIER)) {
+        if (!names.has(match[0])) continue;
+        const list = references.get(match[0]) ?? [];
+        list.push({ file, line: i + 1 });
+        references.set(match[0], list);
+      }
+    });
+  }
+
+  const report: DeadCodeReport = { scanned: candidates.length, dead: [], allowed: 0 };
+  for (const symbol of candidates) {
+    const used = (references.get(symbol.name) ?? []).some(ref =>
+      ref.file !== symbol.file || ref.line < symbol.startLine || ref.line > symbol.endLine
+    );
+    if (used) continue;
+    if (isAllowed(symbol, options.allow)) {
+      report.allowed++;
+      continue;
+    }
+
+    const language = detectLanguage(symbol.file);
+    const lines = contents.get(symbol.file) ?? [];
+    const owner = symbol.kind === 'method' ? symbol.qualifiedName.replace(/\.[^.]+$/, '') : null;
+    const decorated = hasDecorator(lines, symbol.startLine);
+    if (decorated === 'test') continue;
+
+    let confidence: DeadCodeConfidence;
+    let reason: string;
+    if (owner && subtypes.has(owner)) {
+      confidence = 'low';
+      reason = 'may implement a method of a base type or trait';
+    } else if (decorated) {
+      confidence = 'low';
+      reason = 'has a decorator or attribute that may register it';
+    } else if (isPublicSymbol(symbol, language, lines)) {
+      confidence = 'medium';
+      reason = 'public, but not referenced in this repository';
+    } else {
+      confidence = 'high';
+      reason = 'private and never referenced';
+    }
+
+    report.dead.push({
+      qualifiedName: symbol.qualifiedName,
+      name: symbol.name,
+      kind: symbol.kind,
+      file: symbol.file,
+      startLine: symbol.startLine,
+      endLine: symbol.endLine,
+      language,
+      confidence,
+      reason
+    });
+  }
+
+  const rank = (c: DeadCodeConfidence) => DEAD_CODE_CONFIDENCE.indexOf(c);
+  report.dead.sort((a, b) =>
+    rank(a.confidence) - rank(b.confidence) || a.file.localeCompare(b.file) || a.startLine - b.startLine
+  );
+  return report;
+}
+
+/**
+ * Invoked by the runtime, a test runner or the language itself
+ */
+function isEntryPoint(symbol: GraphSymbol, language: string): boolean {
+  const { name } = symbol;
+  if (ENTRY_POINTS.has(name)) return true;
+  switch (language) {
+    case 'python':
+      return /^__\w+__$/.test(name) || name.startsWith('test_');
+    case 'go':
+      return /^(Test|Benchmark|Example|Fuzz)[A-Z_]/.test(name) || name === 'TestMain';
+    case 'java':
+      return name === symbol.qualifiedName.split(':').pop()?.split('.')[0] && symbol.kind === 'method';
+    default:
+      return false;
+  }
+}
+
+/**
+ * Visible outside its file or module
+ */
+export function isPublicSymbol(
+  symbol: Pick<GraphSymbol, 'name' | 'kind' | 'startLine' | 'visibility'>,
+  language: string,
+  lines: string[]
+): boolean {
+  switch (language) {
+    case 'go':
+      return /^[A-Z]/.test(symbol.name);
+    case 'python':
+      return !symbol.name.startsWith('_');
+    case 'typescript': {
+      const definition = lines[symbol.startLine - 1] ?? '';
+      if (symbol.kind === 'method') return !/^\s*(private|#)/.test(definition);
+      return /^\s*export\b/.test(definition) || lines.some(line =>
+        /^\s*export\s*\{/.test(line) && new RegExp(`\\b${escapeRegExp(symbol.name)}\\b`).test(line)
+      );
+    }
+    default:
+      return symbol.visibility === 'public';
+  }
+}
+
+/**
+ * Decorator (`@route`, `@Override`) or attribute (`#[test]`) directly above
+ * a definition; 'test' for test markers
+ */
+function hasDecorator(lines: string[], startLine: number): 'test' | boolean {
+  let found = false;
+  for (let i = startLine - 2; i >= 0 && i >= startLine - 6; i--) {
+    const line = lines[i]?.trim() ?? '';
+    if (/^#\[/.test(line) || /^@[\w.]/.test(line)) {
+      if (/^#\[(\w+::)*(test|bench)\b|^@(\w+\.)*(Test|ParameterizedTest|pytest\.fixture|Benchmark)\b/.test(line)) return 'test';
+      found = true;
+    } else if (line && !/^(\/\/|\/\*|\*|#(?!\[))/.test(line)) {
+      break;
+    }
+  }
+  return found;
+}
+
+/**
+ * `deadcode.allow` patterns match a name, a qualified name or a file glob
+ */
+function isAllowed(symbol: GraphSymbol, allow: string[] = []): boolean {
+  return allow.some(pattern =>
+    matchGlob(symbol.name, pattern) ||
+    matchGlob(symbol.qualifiedName, pattern) ||
+    matchGlob(symbol.file, pattern) ||
+    (pattern.endsWith('/') && symbol.file.startsWith(pattern)) ||
+    path.posix.basename(symbol.file) === pattern
+  );
+}
+
+function stripComment(line: string): string {
+  const index = line.search(/\/\/|(^|\s)#(?!\[)/);
+  return index >= 0 ? line.slice(0, index) : line;
+}
+
+function escapeRegExp(value: string): string {
+  return value.replace(/[.*+?^${}()|[\]\\]/g, '\\$&');
+}
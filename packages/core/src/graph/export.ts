@@ -0,0 +1,217 @@
+/**
+ * Symbol and Call Graph Queries
+ *
+ * `cv sync` records which symbols call each other (CALLS) and which classes,
+ * structs and interfaces extend or implement which (INHERITS). These helpers
+ * find symbols by the names people type (`compute_heavy_task`,
+ * `ComputeEngine::compute_heavy_task`, or a full qualified name), walk call
+ * chains, and export the graph as DOT, GraphML or JSON.
+ */
+
+import type { SymbolNode } from '@cv-git/shared';
+import type { GraphManager } from './index.js';
+
+export type SymbolGraphRelation = 'calls' | 'inherits';
+export type SymbolGraphFormat = 'dot' | 'graphml' | 'json';
+export const SYMBOL_GRAPH_FORMATS: SymbolGraphFormat[] = ['dot', 'graphml', 'json'];
+
+export interface SymbolGraphNode {
+  /** Qualified name (`file:Type.method`) */
+  id: string;
+  name: string;
+  kind: string;
+  file: string;
+  line: number;
+}
+
+export interface SymbolGraphEdge {
+  source: string;
+  target: string;
+  type: 'calls' | 'extends' | 'implements';
+}
+
+export interface SymbolGraph {
+  nodes: SymbolGraphNode[];
+  edges: SymbolGraphEdge[];
+}
+
+export interface CallTreeEntry {
+  symbol: SymbolNode;
+  /** Hops from the queried symbol */
+  depth: number;
+  /** Qualified name of the symbol one hop closer to the queried one */
+  via: string;
+}
+
+/**
+ * Symbols matching `query`: an exact qualified name, `Type::method` /
+ * `Type.method`, or a bare name
+ */
+export async function findSymbols(graph: Pick<GraphManager, 'query'>, query: string, limit: number = 20): Promise<SymbolNode[]> {
+  const member = query.includes(':') && !query.includes('::') ? query : query.replace(/::/g, '.');
+  const rows = await graph.query(
+    'MATCH (s:Symbol) WHERE s.qualifiedName = $query OR s.qualifiedName ENDS WITH $suffix OR s.name = $query ' +
+    `RETURN s LIMIT ${Math.max(1, Math.floor(limit))}`,
+    { query, suffix: `:${member}` }
+  );
+  const symbols = rows.map(row => row.s as SymbolNode);
+  const exact = symbols.find(symbol => symbol.qualifiedName === query);
+  return exact ? [exact] : symbols;
+}
+
+/**
+ * Callers (or callees) of `roots`, breadth first up to `depth` hops. Each
+ * symbol is listed once, at its shortest distance.
+ */
+export async function traverseCalls(
+  graph: Pick<GraphManager, 'getCallers' | 'getCallees'>,
+  roots: SymbolNode[],
+  direction: 'callers' | 'callees',
+  depth: number = 1
+): Promise<CallTreeEntry[]> {
+  const seen = new Set(roots.map(root => root.qualifiedName));
+  const entries: CallTreeEntry[] = [];
+  let frontier = roots.map(root => root.qualifiedName);
+
+  for (let level = 1; level <= depth && frontier.length > 0; level++) {
+    const next: string[] = [];
+    for (const qualifiedName of frontier) {
+      const neighbours = direction === 'callers'
+        ? await graph.getCallers(qualifiedName)
+        : await graph.getCallees(qualifiedName);
+      for (const symbol of neighbours) {
+        if (seen.has(symbol.qualifiedName)) continue;
+        seen.add(symbol.qualifiedName);
+        entries.push({ symbol, depth: level, via: qualifiedName });
+        next.push(symbol.qualifiedName);
+      }
+    }
+    frontier = next;
+  }
+
+  return entries;
+}
+
+/**
+ * Symbols connected by the chosen relations; `file` keeps edges with an end
+ * under that path
+ */
+export async function collectSymbolGraph(
+  graph: Pick<GraphManager, 'query'>,
+  options: { relations?: SymbolGraphRelation[]; file?: string } = {}
+): Promise<SymbolGraph> {
+  const relations = options.relations ?? ['calls', 'inherits'];
+
+  const nodeRows = await graph.query(
+    'MATCH (s:Symbol) RETURN s.qualifiedName as id, s.name as name, s.kind as kind, s.file as file, s.startLine as line'
+  );
+  const nodes = new Map<string, SymbolGraphNode>();
+  for (const row of nodeRows) {
+    nodes.set(String(row.id), {
+      id: String(row.id),
+      name: String(row.name ?? ''),
+      kind: String(row.kind ?? ''),
+      file: String(row.file ?? ''),
+      line: Number(row.line ?? 0)
+    });
+  }
+
+  let edges: SymbolGraphEdge[] = [];
+  if (relations.includes('calls')) {
+    const rows = await graph.query(
+      'MATCH (a:Symbol)-[:CALLS]->(b:Symbol) RETURN a.qualifiedName as source, b.qualifiedName as target'
+    );
+    edges.push(...rows.map(row => ({ source: String(row.source), target: String(row.target), type: 'calls' as const })));
+  }
+  if (relations.includes('inherits')) {
+    const rows = await graph.query(
+      'MATCH (a:Symbol)-[r:INHERITS]->(b:Symbol) RETURN a.qualifiedName as source, b.qualifiedName as target, r.type as type'
+    );
+    edges.push(...rows.map(row => ({
+      source: String(row.source),
+      target: String(row.target),
+      type: row.type === 'implements' ? 'implements' as const : 'extends' as const
+    })));
+  }
+
+  edges = edges.filter(edge => nodes.has(edge.source) && nodes.has(edge.target));
+  if (options.file) {
+    const prefix = options.file.replace(/\\/g, '/').replace(/^\.\//, '');
+    const under = (id: string) => nodes.get(id)!.file.startsWith(prefix);
+    edges = edges.filter(edge => under(edge.source) || under(edge.target));
+  }
+
+  const connected = new Set(edges.flatMap(edge => [edge.source, edge.target]));
+  return {
+    nodes: [...nodes.values()].filter(node => connected.has(node.id)).sort((a, b) => a.id.localeCompare(b.id)),
+    edges
+  };
+}
+
+/**
+ * Render a symbol graph for Graphviz, graph tools (yEd, Gephi) or scripts
+ */
+export function formatSymbolGraph(graph: SymbolGraph, format: SymbolGraphFormat): string {
+  switch (format) {
+    case 'dot':
+      return toDot(graph);
+    case 'graphml':
+      return toGraphML(graph);
+    case 'json':
+      return JSON.stringify(graph, null, 2);
+  }
+}
+
+function toDot(graph: SymbolGraph): string {
+  const escape = (value: string) => value.replace(/\\/g, '\\\\').replace(/"/g, '\\"');
+  const quote = (value: string) => `"${escape(value)}"`;
+  const lines = [
+    'digraph symbols {',
+    '  rankdir=LR;',
+    '  node [shape=box, fontname="Helvetica", fontsize=10];'
+  ];
+  for (const node of graph.nodes) {
+    const shape = ['class', 'interface', 'struct', 'type'].includes(node.kind) ? ', shape=component' : '';
+    const label = `"${escape(node.name)}\\n${escape(node.file)}:${node.line}"`;
+    lines.push(`  ${quote(node.id)} [label=${label}${shape}];`);
+  }
+  for (const edge of graph.edges) {
+    const style = edge.type === 'calls' ? '' : ` [style=dashed, arrowhead=empty, label=${quote(edge.type)}]`;
+    lines.push(`  ${quote(edge.source)} -> ${quote(edge.target)}${style};`);
+  }
+  lines.push('}');
+  return lines.join('\n') + '\n';
+}
+
+function toGraphML(graph: SymbolGraph): string {
+  const xml = (value: string | number) => String(value)
+    .replace(/&/g, '&amp;')
+    .replace(/</g, '&lt;')
+    .replace(/>/g, '&gt;')
+    .replace(/"/g, '&quot;');
+  const lines = [
+    '<?xml version="1.0" encoding="UTF-8"?>',
+    '<graphml xmlns="http://graphml.graphdrawing.org/xmlns">',
+    '  <key id="name" for="node" attr.name="name" attr.type="string"/>',
+    '  <key id="kind" for="node" attr.name="kind" attr.type="string"/>',
+    '  <key id="file" for="node" attr.name="file" attr.type="string"/>',
+    '  <key id="line" for="node" attr.name="line" attr.type="int"/>',
+    '  <key id="type" for="edge" attr.name="type" attr.type="string"/>',
+    '  <graph id="symbols" edgedefault="directed">'
+  ];
+  for (const node of graph.nodes) {
+    lines.push(`    <node id="${xml(node.id)}">`);
+    lines.push(`      <data key="name">${xml(node.name)}</data>`);
+    lines.push(`      <data key="kind">${xml(node.kind)}</data>`);
+    lines.push(`      <data key="file">${xml(node.file)}</data>`);
+    lines.push(`      <data key="line">${node.line}</data>`);
+    lines.push('    </node>');
+  }
+  graph.edges.forEach((edge, i) => {
+    lines.push(`    <edge id="e${i}" source="${xml(edge.source)}" target="${xml(edge.target)}">`);
+    lines.push(`      <data key="type">${edge.type}</data>`);
+    lines.push('    </edge>');
+  });
+  lines.push('  </graph>', '</graphml>');
+  return lines.join('\n') + '\n';
+}
@@ -651,6 +651,7 @@ export class GraphManager {
           s.isAsync = $isAsync,
           s.isStatic = $isStatic,
           s.complexity = $complexity,
+          s.cognitiveComplexity = $cognitiveComplexity,
           s.vectorId = $vectorId,
           s.src_start = $startLine,
           s.src_end = $endLine,
@@ -672,6 +673,7 @@ export class GraphManager {
       isAsync: symbol.isAsync,
       isStatic: symbol.isStatic,
       complexity: symbol.complexity,
+      cognitiveComplexity: symbol.cognitiveComplexity ?? 0,
       vectorId: symbol.vectorId || '',
       updatedAt: Date.now()
     });
@@ -1476,6 +1478,30 @@ export class GraphManager {
     return result.map(r => r.callee as SymbolNode);
   }
 
+  /**
+   * Get symbols that extend or implement a symbol
+   */
+  async getSubtypes(symbolQualifiedName: string): Promise<Array<{ symbol: SymbolNode; type: InheritsEdge['type'] }>> {
+    const result = await this.query(
+      'MATCH (sub:Symbol)-[r:INHERITS]->(s:Symbol {qualifiedName: $symbolQualifiedName}) RETURN sub, r.type as type',
+      { symbolQualifiedName }
+    );
+
+    return result.map(r => ({ symbol: r.sub as SymbolNode, type: r.type as InheritsEdge['type'] }));
+  }
+
+  /**
+   * Get the classes, interfaces and traits a symbol extends or implements
+   */
+  async getSupertypes(symbolQualifiedName: string): Promise<Array<{ symbol: SymbolNode; type: InheritsEdge['type'] }>> {
+    const result = await this.query(
+      'MATCH (s:Symbol {qualifiedName: $symbolQualifiedName})-[r:INHERITS]->(base:Symbol) RETURN base, r.type as type',
+      { symbolQualifiedName }
+    );
+
+    return result.map(r => ({ symbol: r.base as SymbolNode, type: r.type as InheritsEdge['type'] }));
+  }
+
   /**
    * Get file dependencies (imports)
    */
@@ -1795,3 +1821,12 @@ export function createGraphManager(urlOrOptions: string | GraphManagerOptions, d
 // Re-export backend types for consumers that need backend awareness
 export type { IGraphBackend, BackendType } from './backend.js';
 export { resolveBackendType, isEmbeddedBackend } from './backend-factory.js';
+
+// Symbol lookup, call traversal and graph export
+export * from './export.js';
+
+// Unreferenced symbol detection
+export * from './deadcode.js';
+
+// Per-function complexity ranking
+export * from './complexity.js';
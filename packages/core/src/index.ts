@@ -11,6 +11,10 @@ export * from './ai/index.js';
 export * from './ai/openrouter.js';
 export * from './ai/ollama.js';
 export * from './ai/lmstudio.js';
+export * from './ai/anthropic.js';
+export * from './ai/gemini.js';
+export * from './ai/openai.js';
+export * from './ai/fallback.js';
 export * from './ai/types.js';
 export * from './ai/factory.js';
 export * from './ai/system-capabilities.js';
@@ -21,6 +25,18 @@ export * from './storage/index.js';
 export * from './context/index.js';
 export * from './deps/index.js';
 export * from './services/index.js';
+export * from './fixtures/index.js';
+export * from './batch/index.js';
+export * from './chat/index.js';
+export * from './eval/index.js';
+export * from './eval/suite.js';
+export * from './sandbox/index.js';
+
+// Library retrieval API (retrieve / answer) for bots and scripts
+export * from './retrieval/index.js';
+
+// Sibling repositories searched with --workspace
+export * from './workspace/index.js';
 
 // Gateway (CV-Hub client)
 export * from './gateway/index.js';
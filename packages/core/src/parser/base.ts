@@ -12,6 +12,8 @@ import {
   Export,
   CodeChunk
 } from '@cv-git/shared';
+import { getChunkingRules } from './language-packs.js';
+import { cognitiveComplexity, cyclomaticComplexity } from './complexity.js';
 
 /**
  * Tree-sitter node interface
@@ -158,11 +160,14 @@ export abstract class BaseLanguageParser implements ILanguageParser {
   chunkCode(content: string, symbols: SymbolNode[], filePath: string): CodeChunk[] {
     const chunks: CodeChunk[] = [];
     const lines = content.split('\n');
+    const offsets = this.lineByteOffsets(lines);
+    const rules = getChunkingRules(this.getLanguage());
 
     // Chunk by symbol (preferred)
     for (const symbol of symbols) {
-      if (symbol.kind === 'function' || symbol.kind === 'method' || symbol.kind === 'class') {
+      if (rules.symbolKinds.includes(symbol.kind)) {
         const text = lines.slice(symbol.startLine - 1, symbol.endLine).join('\n');
+        const startByte = offsets[symbol.startLine - 1] ?? 0;
 
         chunks.push({
           id: this.generateChunkId(filePath, symbol.startLine, symbol.endLine),
@@ -170,6 +175,8 @@ export abstract class BaseLanguageParser implements ILanguageParser {
           language: this.getLanguage(),
           startLine: symbol.startLine,
           endLine: symbol.endLine,
+          startByte,
+          endByte: startByte + Buffer.byteLength(text, 'utf8'),
           text,
           symbolName: symbol.name,
           symbolKind: symbol.kind,
@@ -181,13 +188,15 @@ export abstract class BaseLanguageParser implements ILanguageParser {
     }
 
     // If no symbols or file is small, chunk the whole file
-    if (chunks.length === 0 && lines.length < 200) {
+    if (chunks.length === 0 && lines.length < rules.wholeFileMaxLines) {
       chunks.push({
         id: this.generateChunkId(filePath, 1, lines.length),
         file: filePath,
         language: this.getLanguage(),
         startLine: 1,
         endLine: lines.length,
+        startByte: 0,
+        endByte: Buffer.byteLength(content, 'utf8'),
         text: content
       });
     }
@@ -197,6 +206,19 @@ export abstract class BaseLanguageParser implements ILanguageParser {
 
   // ========== Helper Methods ==========
 
+  /**
+   * UTF-8 byte offset at which each line starts
+   */
+  protected lineByteOffsets(lines: string[]): number[] {
+    const offsets: number[] = [];
+    let offset = 0;
+    for (const line of lines) {
+      offsets.push(offset);
+      offset += Buffer.byteLength(line, 'utf8') + 1;
+    }
+    return offsets;
+  }
+
   /**
    * Find nodes by type recursively
    */
@@ -214,6 +236,17 @@ export abstract class BaseLanguageParser implements ILanguageParser {
     return results;
   }
 
+  /**
+   * Names of the base types in an extends/implements clause's children
+   * (`Base<T>`, `ns.Other` -> `Base`, `Other`)
+   */
+  protected getHeritageNames(types: TreeSitterNode[]): string[] {
+    return types
+      .flatMap(child => child.type === 'type_list' ? child.namedChildren : [child])
+      .map(child => child.text.replace(/<[\s\S]*>$/, '').split(/::|\./).pop()!.trim())
+      .filter(name => /^[A-Za-z_$][\w$]*$/.test(name));
+  }
+
   /**
    * Get docstring/comment above a node
    */
@@ -278,27 +311,14 @@ export abstract class BaseLanguageParser implements ILanguageParser {
    * Calculate cyclomatic complexity
    */
   protected calculateComplexity(node: TreeSitterNode): number {
-    // Simple cyclomatic complexity calculation
-    let complexity = 1;
-
-    const controlFlowTypes = [
-      'if_statement',
-      'elif_clause',
-      'else_clause',
-      'for_statement',
-      'while_statement',
-      'do_statement',
-      'switch_statement',
-      'case',
-      'catch_clause',
-      'ternary_expression',
-      'conditional_expression'
-    ];
-
-    const controlFlowNodes = this.findNodesByType(node, controlFlowTypes);
-    complexity += controlFlowNodes.length;
+    return cyclomaticComplexity(node);
+  }
 
-    return complexity;
+  /**
+   * Calculate cognitive complexity
+   */
+  protected calculateCognitiveComplexity(node: TreeSitterNode): number {
+    return cognitiveComplexity(node);
   }
 
   /**
@@ -0,0 +1,195 @@
+/**
+ * Chunking Strategies
+ * Decide how a parsed file is split into chunks for embedding.
+ *
+ * - function: one chunk per function/method/class symbol (the parsers' default)
+ * - class: one chunk per type with its methods inside, plus free functions
+ * - sliding-window: fixed-size line windows that overlap, ignoring structure
+ *
+ * Every strategy falls back to overlapping windows when a file has no usable
+ * symbols (unsupported languages, regex parsing misses), so no file is dropped.
+ */
+
+import { CodeChunk, SymbolKind, SymbolNode } from '@cv-git/shared';
+import { ChunkingRules, getChunkingRules } from './language-packs.js';
+
+export type ChunkingStrategyName = 'function' | 'class' | 'sliding-window';
+
+/**
+ * Chunking settings from `sync.chunking` in .cv/config.json
+ */
+export interface ChunkingConfig {
+  strategy: ChunkingStrategyName;
+  /** Lines per window for sliding-window chunking and fallbacks */
+  windowLines?: number;
+  /** Lines shared by consecutive windows */
+  overlapLines?: number;
+}
+
+export interface ChunkInput {
+  filePath: string;
+  language: string;
+  content: string;
+  symbols: SymbolNode[];
+}
+
+export interface ChunkingStrategy {
+  readonly name: ChunkingStrategyName;
+  chunk(input: ChunkInput): CodeChunk[];
+}
+
+export const DEFAULT_WINDOW_LINES = 60;
+export const DEFAULT_OVERLAP_LINES = 10;
+
+/** Kinds that own their members under class-level chunking */
+const CONTAINER_KINDS: SymbolKind[] = ['class', 'interface', 'struct', 'enum'];
+
+/** Containers longer than this are split into their members instead */
+const MAX_CONTAINER_LINES = 400;
+
+function lineByteOffsets(lines: string[]): number[] {
+  const offsets: number[] = [];
+  let offset = 0;
+  for (const line of lines) {
+    offsets.push(offset);
+    offset += Buffer.byteLength(line, 'utf8') + 1;
+  }
+  return offsets;
+}
+
+function makeChunk(
+  input: ChunkInput,
+  lines: string[],
+  offsets: number[],
+  startLine: number,
+  endLine: number,
+  symbol?: SymbolNode
+): CodeChunk {
+  const text = lines.slice(startLine - 1, endLine).join('\n');
+  const startByte = offsets[startLine - 1] ?? 0;
+  return {
+    id: `${input.filePath}:${startLine}:${endLine}`,
+    file: input.filePath,
+    language: input.language,
+    startLine,
+    endLine,
+    startByte,
+    endByte: startByte + Buffer.byteLength(text, 'utf8'),
+    text,
+    ...(symbol && {
+      symbolName: symbol.name,
+      symbolKind: symbol.kind,
+      summary: symbol.docstring,
+      docstring: symbol.docstring,
+      complexity: symbol.complexity
+    })
+  };
+}
+
+/**
+ * Overlapping line windows over the whole file
+ */
+export class SlidingWindowStrategy implements ChunkingStrategy {
+  readonly name = 'sliding-window' as const;
+  private readonly windowLines: number;
+  private readonly overlapLines: number;
+
+  constructor(windowLines = DEFAULT_WINDOW_LINES, overlapLines = DEFAULT_OVERLAP_LINES) {
+    this.windowLines = Math.max(1, Math.floor(windowLines));
+    // Overlap must leave the window room to advance
+    this.overlapLines = Math.min(Math.max(0, Math.floor(overlapLines)), this.windowLines - 1);
+  }
+
+  chunk(input: ChunkInput): CodeChunk[] {
+    const lines = input.content.split('\n');
+    if (input.content.trim().length === 0) return [];
+    const offsets = lineByteOffsets(lines);
+    const step = this.windowLines - this.overlapLines;
+    const chunks: CodeChunk[] = [];
+
+    for (let start = 1; start <= lines.length; start += step) {
+      const end = Math.min(start + this.windowLines - 1, lines.length);
+      chunks.push(makeChunk(input, lines, offsets, start, end));
+      if (end === lines.length) break;
+    }
+    return chunks;
+  }
+}
+
+/**
+ * One chunk per function, method or class symbol
+ */
+export class FunctionLevelStrategy implements ChunkingStrategy {
+  readonly name: ChunkingStrategyName = 'function';
+
+  constructor(protected readonly fallback: SlidingWindowStrategy = new SlidingWindowStrategy()) {}
+
+  chunk(input: ChunkInput): CodeChunk[] {
+    const rules = getChunkingRules(input.language);
+    const lines = input.content.split('\n');
+    const offsets = lineByteOffsets(lines);
+
+    const chunks = this.select(input.symbols, rules)
+      .map(symbol => makeChunk(input, lines, offsets, symbol.startLine, Math.min(symbol.endLine, lines.length), symbol));
+
+    return chunks.length > 0 ? chunks : this.fallbackChunks(input, lines, rules);
+  }
+
+  protected select(symbols: SymbolNode[], rules: ChunkingRules): SymbolNode[] {
+    return symbols.filter(s => rules.symbolKinds.includes(s.kind));
+  }
+
+  /** Small files are embedded whole; larger ones are windowed rather than skipped */
+  protected fallbackChunks(input: ChunkInput, lines: string[], rules: ChunkingRules): CodeChunk[] {
+    if (lines.length < rules.wholeFileMaxLines && input.content.trim().length > 0) {
+      return [makeChunk(input, lines, lineByteOffsets(lines), 1, lines.length)];
+    }
+    return this.fallback.chunk(input);
+  }
+}
+
+/**
+ * One chunk per type (methods stay inside their class) plus top-level functions
+ */
+export class ClassLevelStrategy extends FunctionLevelStrategy {
+  readonly name: ChunkingStrategyName = 'class';
+
+  protected select(symbols: SymbolNode[], rules: ChunkingRules): SymbolNode[] {
+    const containers = symbols.filter(
+      s => CONTAINER_KINDS.includes(s.kind) && s.endLine - s.startLine + 1 <= MAX_CONTAINER_LINES
+    );
+    const inContainer = (s: SymbolNode) =>
+      containers.some(c => c !== s && c.startLine <= s.startLine && s.endLine <= c.endLine);
+
+    // Oversized containers are dropped so their members become chunks of their own
+    const free = symbols.filter(
+      s => !CONTAINER_KINDS.includes(s.kind) && rules.symbolKinds.includes(s.kind) && !inContainer(s)
+    );
+    // Nested containers are already covered by their parent
+    const outer = containers.filter(c => !inContainer(c));
+    return [...outer, ...free].sort((a, b) => a.startLine - b.startLine);
+  }
+}
+
+/**
+ * Build the strategy selected in config
+ */
+export function createChunkingStrategy(config?: Partial<ChunkingConfig>): ChunkingStrategy {
+  const windows = new SlidingWindowStrategy(
+    config?.windowLines ?? DEFAULT_WINDOW_LINES,
+    config?.overlapLines ?? DEFAULT_OVERLAP_LINES
+  );
+
+  switch (config?.strategy ?? 'function') {
+    case 'function':
+      return new FunctionLevelStrategy(windows);
+    case 'class':
+      return new ClassLevelStrategy(windows);
+    case 'sliding-window':
+      return windows;
+    default:
+      throw new Error(
+        `Unknown chunking strategy "${config?.strategy}" (expected function, class or sliding-window)`
+      );
+  }
+}
@@ -0,0 +1,145 @@
+/**
+ * Function Complexity Metrics
+ *
+ * Computed from the tree-sitter AST while parsing, for every supported
+ * language:
+ *
+ * - Cyclomatic complexity (McCabe): 1 + the number of decision points
+ *   (branches, loops, cases, catches, ternaries and `&&` / `||` / `and` / `or`)
+ * - Cognitive complexity (SonarSource): how hard the control flow is to
+ *   follow. Each break in linear flow adds 1, plus 1 per level it is nested
+ *   in; `else if` / `else` add 1 without nesting; each run of like boolean
+ *   operators adds 1; nested functions and closures deepen nesting.
+ */
+
+import type { TreeSitterNode } from './base.js';
+
+const IF_TYPES = new Set(['if_statement', 'if_expression', 'if_let_expression']);
+
+const LOOP_TYPES = new Set([
+  'for_statement', 'for_in_statement', 'for_of_statement', 'enhanced_for_statement',
+  'while_statement', 'do_statement', 'for_expression', 'while_expression', 'while_let_expression', 'loop_expression'
+]);
+
+const SWITCH_TYPES = new Set([
+  'switch_statement', 'switch_expression', 'expression_switch_statement', 'type_switch_statement',
+  'select_statement', 'match_statement', 'match_expression'
+]);
+
+const CASE_TYPES = new Set([
+  'switch_case', 'case', 'case_clause', 'case_statement', 'switch_label',
+  'expression_case', 'type_case', 'communication_case', 'match_arm'
+]);
+
+const CATCH_TYPES = new Set(['catch_clause', 'except_clause', 'rescue']);
+
+const TERNARY_TYPES = new Set(['ternary_expression', 'conditional_expression']);
+
+const FUNCTION_TYPES = new Set([
+  'arrow_function', 'function_expression', 'function', 'function_declaration', 'function_definition',
+  'method_definition', 'lambda', 'lambda_expression', 'closure_expression', 'func_literal', 'function_item'
+]);
+
+const LOGICAL_OPERATORS = new Set(['&&', '||', 'and', 'or']);
+
+/**
+ * McCabe cyclomatic complexity of a function body
+ */
+export function cyclomaticComplexity(node: TreeSitterNode): number {
+  let complexity = 1;
+  walk(node, current => {
+    const type = current.type;
+    if (IF_TYPES.has(type) || LOOP_TYPES.has(type) || CATCH_TYPES.has(type) || TERNARY_TYPES.has(type) ||
+        type === 'elif_clause' || type === 'if_clause') {
+      complexity++;
+    } else if (CASE_TYPES.has(type) && !isDefaultCase(current)) {
+      complexity++;
+    } else if (logicalOperator(current)) {
+      complexity++;
+    }
+  });
+  return complexity;
+}
+
+/**
+ * SonarSource cognitive complexity of a function body
+ */
+export function cognitiveComplexity(node: TreeSitterNode): number {
+  let score = 0;
+
+  const visit = (current: TreeSitterNode, nesting: number, parent: TreeSitterNode | null): void => {
+    const type = current.type;
+    let childNesting = nesting;
+
+    if (IF_TYPES.has(type)) {
+      if (parent && isElseBranch(current, parent)) {
+        score += 1;
+      } else {
+        score += 1 + nesting;
+        childNesting = nesting + 1;
+      }
+      // Go and Java attach a plain `else` block directly as the alternative
+      const alternative = current.childForFieldName('alternative');
+      if (alternative && !IF_TYPES.has(alternative.type) && !['else_clause', 'elif_clause'].includes(alternative.type)) {
+        score += 1;
+      }
+    } else if (type === 'else_clause') {
+      const [only, ...rest] = current.namedChildren;
+      // `else if` counts on the inner if
+      if (!(only && rest.length === 0 && IF_TYPES.has(only.type))) score += 1;
+    } else if (type === 'elif_clause') {
+      score += 1;
+    } else if (LOOP_TYPES.has(type) || SWITCH_TYPES.has(type) || CATCH_TYPES.has(type) || TERNARY_TYPES.has(type)) {
+      score += 1 + nesting;
+      childNesting = nesting + 1;
+    } else if (FUNCTION_TYPES.has(type) && parent) {
+      childNesting = nesting + 1;
+    } else {
+      const operator = logicalOperator(current);
+      if (operator && (!parent || logicalOperator(parent) !== operator)) score += 1;
+    }
+
+    for (const child of current.namedChildren) {
+      visit(child, childNesting, current);
+    }
+  };
+
+  visit(node, 0, null);
+  return score;
+}
+
+/**
+ * `&&` / `||` / `and` / `or` of a binary expression, if it is one
+ */
+function logicalOperator(node: TreeSitterNode): string | undefined {
+  if (node.type !== 'binary_expression' && node.type !== 'boolean_operator') return undefined;
+  const operator = node.childForFieldName('operator')?.text
+    ?? node.children.find(child => LOGICAL_OPERATORS.has(child.text))?.text;
+  return operator && LOGICAL_OPERATORS.has(operator) ? operator : undefined;
+}
+
+/**
+ * An if that is the `else if` of its parent
+ */
+function isElseBranch(node: TreeSitterNode, parent: TreeSitterNode): boolean {
+  if (parent.type === 'else_clause') return true;
+  if (!IF_TYPES.has(parent.type)) return false;
+  const alternative = parent.childForFieldName('alternative');
+  return !!alternative && samePosition(alternative, node);
+}
+
+function isDefaultCase(node: TreeSitterNode): boolean {
+  const text = node.text.trimStart();
+  return text.startsWith('default') || (node.type === 'match_arm' && /^_\s*(=>|if\b)/.test(text));
+}
+
+function samePosition(a: TreeSitterNode, b: TreeSitterNode): boolean {
+  return a.type === b.type &&
+    a.startPosition.row === b.startPosition.row &&
+    a.startPosition.column === b.startPosition.column;
+}
+
+function walk(node: TreeSitterNode, fn: (node: TreeSitterNode) => void): void {
+  fn(node);
+  for (const child of node.namedChildren) walk(child, fn);
+}
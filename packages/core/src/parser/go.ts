@@ -98,6 +98,7 @@ export class GoParser extends BaseLanguageParser {
         isAsync: false, // Go doesn't have async/await
         isStatic: false,
         complexity: this.calculateComplexity(funcNode),
+        cognitiveComplexity: this.calculateCognitiveComplexity(funcNode),
         calls: this.extractCalls(funcNode),
         createdAt: Date.now(),
         updatedAt: Date.now()
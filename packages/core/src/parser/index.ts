@@ -11,8 +11,15 @@ import { ParsedFile, ParsedDocument } from '@cv-git/shared';
 import { ILanguageParser } from './base.js';
 import { createMarkdownParser, MarkdownParser } from './markdown.js';
 import { createSimpleParsers } from './simple.js';
+import { getLanguagePacks, getLanguagePackForExtension } from './language-packs.js';
+import { ChunkingConfig, ChunkingStrategy, createChunkingStrategy } from './chunking.js';
 import * as path from 'path';
 
+export interface ParserOptions {
+  /** Re-chunk parsed files with this strategy instead of the parsers' defaults */
+  chunking?: Partial<ChunkingConfig>;
+}
+
 // Track if tree-sitter is available
 let treeSitterAvailable = true;
 let treeSitterError: string | null = null;
@@ -25,9 +32,13 @@ export class CodeParser {
   private extensionMap: Map<string, string> = new Map();
   private markdownParser: MarkdownParser;
   private usingSimpleParsers: boolean = false;
+  private chunkingStrategy?: ChunkingStrategy;
 
-  constructor() {
+  constructor(options: ParserOptions = {}) {
     this.markdownParser = createMarkdownParser();
+    if (options.chunking) {
+      this.chunkingStrategy = createChunkingStrategy(options.chunking);
+    }
     this.initializeParsers();
   }
 
@@ -85,32 +96,12 @@ export class CodeParser {
    * Initialize tree-sitter based parsers
    */
   private initializeTreeSitterParsers(): void {
-    // Dynamic imports to catch native module errors
-    const { createTypeScriptParser } = require('./typescript.js');
-    const { createPythonParser } = require('./python.js');
-    const { createGoParser } = require('./go.js');
-    const { createRustParser } = require('./rust.js');
-    const { createJavaParser } = require('./java.js');
-
-    // TypeScript/JavaScript parser
-    const tsParser = createTypeScriptParser();
-    this.registerParser('typescript', tsParser);
-
-    // Python parser
-    const pythonParser = createPythonParser();
-    this.registerParser('python', pythonParser);
-
-    // Go parser
-    const goParser = createGoParser();
-    this.registerParser('go', goParser);
-
-    // Rust parser
-    const rustParser = createRustParser();
-    this.registerParser('rust', rustParser);
-
-    // Java parser
-    const javaParser = createJavaParser();
-    this.registerParser('java', javaParser);
+    // Each pack loads its grammar lazily so native module errors can be caught here
+    for (const pack of getLanguagePacks()) {
+      if (pack.loadTreeSitterParser) {
+        this.registerParser(pack.id, pack.loadTreeSitterParser());
+      }
+    }
 
     // Register markdown extensions
     for (const ext of this.markdownParser.getSupportedExtensions()) {
@@ -165,7 +156,25 @@ export class CodeParser {
     }
 
     // Parse the file
-    return await parser.parseFile(filePath, content);
+    const parsed = await parser.parseFile(filePath, content);
+
+    // A configured strategy replaces the parser's own symbol chunks
+    if (this.chunkingStrategy) {
+      parsed.chunks = this.chunkingStrategy.chunk({
+        filePath,
+        language: parsed.language,
+        content,
+        symbols: parsed.symbols
+      });
+    }
+    return parsed;
+  }
+
+  /**
+   * Chunking strategy applied to parsed files, if one was configured
+   */
+  getChunkingStrategy(): ChunkingStrategy | undefined {
+    return this.chunkingStrategy;
   }
 
   /**
@@ -175,7 +184,7 @@ export class CodeParser {
     const ext = path.extname(filePath);
 
     // Check extension map
-    const language = this.extensionMap.get(ext);
+    const language = this.extensionMap.get(ext) || getLanguagePackForExtension(ext)?.id;
 
     if (language) {
       return language;
@@ -240,8 +249,8 @@ export class CodeParser {
 /**
  * Create a parser instance
  */
-export function createParser(): CodeParser {
-  return new CodeParser();
+export function createParser(options: ParserOptions = {}): CodeParser {
+  return new CodeParser(options);
 }
 
 // Re-export base classes for extending
@@ -249,6 +258,31 @@ export function createParser(): CodeParser {
 // re-exported here because their top-level `import Parser from 'tree-sitter'`
 // would force esbuild to eagerly initialize the native modules at bundle load
 // time, crashing the CLI before Commander can process --version/--help.
-// They are loaded dynamically via require() inside initializeTreeSitterParsers().
+// They are loaded dynamically via require() inside each language pack's
+// loadTreeSitterParser() (see language-packs.ts).
 export { ILanguageParser, BaseLanguageParser, TreeSitterNode } from './base.js';
 export { MarkdownParser, createMarkdownParser, MarkdownParserConfig } from './markdown.js';
+export {
+  ChunkingStrategy,
+  ChunkingStrategyName,
+  ChunkingConfig,
+  ChunkInput,
+  FunctionLevelStrategy,
+  ClassLevelStrategy,
+  SlidingWindowStrategy,
+  createChunkingStrategy,
+  DEFAULT_WINDOW_LINES,
+  DEFAULT_OVERLAP_LINES
+} from './chunking.js';
+export {
+  LanguagePack,
+  SimplePatterns,
+  ChunkingRules,
+  registerLanguagePack,
+  getLanguagePacks,
+  getLanguagePack,
+  getLanguagePackForExtension,
+  getChunkingRules,
+  getDefaultIndexedLanguages,
+  isTestFile
+} from './language-packs.js';
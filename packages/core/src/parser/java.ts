@@ -12,6 +12,7 @@ import {
   Export,
   Parameter,
   CallInfo,
+  InheritanceInfo,
   Visibility
 } from '@cv-git/shared';
 
@@ -88,6 +89,7 @@ export class JavaParser extends BaseLanguageParser {
         isAsync: false,
         isStatic: false,
         complexity: this.calculateComplexity(classNode),
+        inherits: this.getHeritage(classNode),
         createdAt: Date.now(),
         updatedAt: Date.now()
       });
@@ -129,6 +131,7 @@ export class JavaParser extends BaseLanguageParser {
         isAsync: false,
         isStatic: false,
         complexity: 1,
+        inherits: this.getHeritage(intNode),
         createdAt: Date.now(),
         updatedAt: Date.now()
       });
@@ -213,6 +216,7 @@ export class JavaParser extends BaseLanguageParser {
         isAsync: false, // Java doesn't have async/await
         isStatic,
         complexity: this.calculateComplexity(methodNode),
+        cognitiveComplexity: this.calculateCognitiveComplexity(methodNode),
         calls: this.extractCalls(methodNode),
         createdAt: Date.now(),
         updatedAt: Date.now()
@@ -244,6 +248,7 @@ export class JavaParser extends BaseLanguageParser {
         isAsync: false,
         isStatic: false,
         complexity: this.calculateComplexity(constructorNode),
+        cognitiveComplexity: this.calculateCognitiveComplexity(constructorNode),
         calls: this.extractCalls(constructorNode),
         createdAt: Date.now(),
         updatedAt: Date.now()
@@ -380,6 +385,23 @@ export class JavaParser extends BaseLanguageParser {
 
   // ========== Java-specific Helper Methods ==========
 
+  /**
+   * `extends` superclass or interfaces, and `implements` interfaces
+   */
+  private getHeritage(node: TreeSitterNode): InheritanceInfo[] | undefined {
+    const inherits: InheritanceInfo[] = [];
+    for (const child of node.namedChildren) {
+      const type = child.type === 'super_interfaces' ? 'implements'
+        : child.type === 'superclass' || child.type === 'extends_interfaces' ? 'extends'
+        : null;
+      if (!type) continue;
+      for (const name of this.getHeritageNames(child.namedChildren)) {
+        inherits.push({ name, type });
+      }
+    }
+    return inherits.length > 0 ? inherits : undefined;
+  }
+
   private getClassName(node: TreeSitterNode): string | null {
     const nameNode = node.childForFieldName('name');
     return nameNode?.text || null;
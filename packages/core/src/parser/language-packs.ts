@@ -0,0 +1,291 @@
+/**
+ * Language Packs
+ * Declarative descriptors for everything language-specific: file extensions,
+ * comment syntax, tree-sitter grammar bindings, regex fallback patterns,
+ * chunking rules, and test-file detection.
+ *
+ * Adding a language means adding grammar bindings (optional) and a pack here;
+ * the parser manager, simple parser, sync engine, and commit analyzer all read
+ * from this registry.
+ */
+
+import { SymbolKind, registerLanguageExtensions } from '@cv-git/shared';
+import { minimatch } from 'minimatch';
+import { ILanguageParser, ParserConfig } from './base.js';
+
+/**
+ * Regex patterns used by the fallback SimpleParser
+ */
+export interface SimplePatterns {
+  function: RegExp;
+  class: RegExp;
+  import: RegExp;
+  export: RegExp;
+}
+
+/**
+ * Chunking rules for a language
+ */
+export interface ChunkingRules {
+  /** Symbol kinds that become their own chunk */
+  symbolKinds: SymbolKind[];
+  /** Files with no chunkable symbols are embedded whole below this line count */
+  wholeFileMaxLines: number;
+  /** Line count for fixed-size chunks when regex parsing finds no symbols */
+  fallbackChunkLines: number;
+}
+
+/**
+ * Declarative description of a supported language
+ */
+export interface LanguagePack {
+  /** Language identifier used throughout the index (e.g. 'rust') */
+  id: string;
+  /** Human-readable name */
+  displayName: string;
+  /** File extensions including the leading dot */
+  extensions: string[];
+  /** Comment syntax for docstring extraction */
+  commentPatterns?: ParserConfig['commentPatterns'];
+  /** Loads the tree-sitter backed parser; omit for regex-only languages */
+  loadTreeSitterParser?: () => ILanguageParser;
+  /** Patterns for the regex fallback parser */
+  simplePatterns: SimplePatterns;
+  /** Overrides for the default chunking rules */
+  chunking?: Partial<ChunkingRules>;
+  /** Glob patterns identifying test files */
+  testFilePatterns: string[];
+  /** Index this language when no includeLanguages are configured */
+  indexByDefault: boolean;
+}
+
+const DEFAULT_CHUNKING: ChunkingRules = {
+  symbolKinds: ['function', 'method', 'class'],
+  wholeFileMaxLines: 200,
+  fallbackChunkLines: 50
+};
+
+/**
+ * Test locations shared by every language
+ */
+const COMMON_TEST_PATTERNS = ['**/tests/**', '**/test/**', '**/__tests__/**'];
+
+const C_FAMILY_COMMENTS: ParserConfig['commentPatterns'] = {
+  singleLine: ['//'],
+  multiLineStart: ['/*'],
+  multiLineEnd: ['*/'],
+  docComment: ['/**', '///']
+};
+
+const BUILTIN_PACKS: LanguagePack[] = [
+  {
+    id: 'typescript',
+    displayName: 'TypeScript / JavaScript',
+    extensions: ['.ts', '.tsx', '.js', '.jsx', '.mjs', '.cjs'],
+    commentPatterns: C_FAMILY_COMMENTS,
+    loadTreeSitterParser: () => require('./typescript.js').createTypeScriptParser(),
+    simplePatterns: {
+      function: /(?:export\s+)?(?:async\s+)?function\s+(\w+)|(?:const|let|var)\s+(\w+)\s*=\s*(?:async\s+)?\(|(\w+)\s*:\s*(?:async\s+)?\([^)]*\)\s*(?:=>|{)/gm,
+      class: /(?:export\s+)?(?:abstract\s+)?class\s+(\w+)/gm,
+      import: /import\s+(?:{([^}]+)}|\*\s+as\s+(\w+)|(\w+))\s+from\s+['"]([^'"]+)['"]/gm,
+      export: /export\s+(?:(default)\s+)?(?:class|function|const|let|var|interface|type|enum)\s+(\w+)/gm
+    },
+    testFilePatterns: ['**/*.test.*', '**/*.spec.*'],
+    indexByDefault: true
+  },
+  {
+    id: 'python',
+    displayName: 'Python',
+    extensions: ['.py', '.pyi'],
+    commentPatterns: {
+      singleLine: ['#'],
+      multiLineStart: ['"""', "'''"],
+      multiLineEnd: ['"""', "'''"],
+      docComment: ['"""', "'''"]
+    },
+    loadTreeSitterParser: () => require('./python.js').createPythonParser(),
+    simplePatterns: {
+      function: /(?:async\s+)?def\s+(\w+)\s*\(/gm,
+      class: /class\s+(\w+)(?:\s*\([^)]*\))?:/gm,
+      import: /(?:from\s+(\S+)\s+)?import\s+([^#\n]+)/gm,
+      export: /^(?!_)(\w+)\s*=/gm
+    },
+    testFilePatterns: ['**/test_*.py', '**/*_test.py', '**/conftest.py'],
+    indexByDefault: true
+  },
+  {
+    id: 'go',
+    displayName: 'Go',
+    extensions: ['.go'],
+    commentPatterns: C_FAMILY_COMMENTS,
+    loadTreeSitterParser: () => require('./go.js').createGoParser(),
+    simplePatterns: {
+      function: /func\s+(?:\([^)]+\)\s+)?(\w+)\s*\(/gm,
+      class: /type\s+(\w+)\s+struct/gm,
+      import: /import\s+(?:\(\s*)?["']([^"']+)["']/gm,
+      export: /^[A-Z]\w*/gm
+    },
+    testFilePatterns: ['**/*_test.go'],
+    indexByDefault: true
+  },
+  {
+    id: 'rust',
+    displayName: 'Rust',
+    extensions: ['.rs'],
+    commentPatterns: {
+      singleLine: ['//'],
+      multiLineStart: ['/*'],
+      multiLineEnd: ['*/'],
+      docComment: ['///', '//!', '/**']
+    },
+    loadTreeSitterParser: () => require('./rust.js').createRustParser(),
+    simplePatterns: {
+      function: /(?:pub\s+)?(?:async\s+)?fn\s+(\w+)/gm,
+      class: /(?:pub\s+)?struct\s+(\w+)|(?:pub\s+)?enum\s+(\w+)|(?:pub\s+)?trait\s+(\w+)/gm,
+      import: /use\s+([^;]+);/gm,
+      export: /pub\s+(?:fn|struct|enum|trait|mod)\s+(\w+)/gm
+    },
+    testFilePatterns: ['**/tests/**/*.rs', '**/benches/**/*.rs'],
+    indexByDefault: true
+  },
+  {
+    id: 'java',
+    displayName: 'Java',
+    extensions: ['.java'],
+    commentPatterns: C_FAMILY_COMMENTS,
+    loadTreeSitterParser: () => require('./java.js').createJavaParser(),
+    simplePatterns: {
+      function: /(?:public|private|protected)?\s*(?:static\s+)?(?:\w+\s+)+(\w+)\s*\([^)]*\)\s*(?:throws\s+[^{]+)?{/gm,
+      class: /(?:public\s+)?(?:abstract\s+)?(?:class|interface|enum)\s+(\w+)/gm,
+      import: /import\s+(?:static\s+)?([^;]+);/gm,
+      export: /public\s+(?:class|interface|enum)\s+(\w+)/gm
+    },
+    testFilePatterns: ['**/src/test/**', '**/*Test.java', '**/*Tests.java'],
+    indexByDefault: false
+  },
+  {
+    id: 'c',
+    displayName: 'C',
+    extensions: ['.c', '.h'],
+    commentPatterns: C_FAMILY_COMMENTS,
+    simplePatterns: {
+      function: /^(?:static\s+)?(?:inline\s+)?(?:const\s+)?(?:unsigned\s+)?(?:signed\s+)?(?:void|int|char|short|long|float|double|size_t|bool|_Bool|\w+_t|\w+\s*\*)\s+\*?(\w+)\s*\([^)]*\)\s*\{/gm,
+      class: /(?:typedef\s+)?struct\s+(\w+)|typedef\s+(?:struct|union|enum)\s*\{[^}]*\}\s*(\w+)/gm,
+      import: /#include\s+[<"]([^>"]+)[>"]/gm,
+      export: /^(?!static\s)(?:void|int|char|short|long|float|double|size_t|bool|_Bool|\w+_t|\w+\s*\*)\s+\*?(\w+)\s*\(/gm
+    },
+    testFilePatterns: ['**/test_*.c', '**/*_test.c'],
+    indexByDefault: false
+  },
+  {
+    id: 'cpp',
+    displayName: 'C++',
+    extensions: ['.cpp', '.cc', '.cxx', '.hpp', '.hxx', '.hh'],
+    commentPatterns: C_FAMILY_COMMENTS,
+    simplePatterns: {
+      function: /^(?:static\s+)?(?:inline\s+)?(?:virtual\s+)?(?:const\s+)?(?:unsigned\s+)?(?:void|int|char|short|long|float|double|size_t|bool|auto|std::\w+|\w+_t|\w+\s*\*)\s+\*?(?:(\w+)::)?(\w+)\s*\([^)]*\)\s*(?:const\s*)?(?:override\s*)?(?:noexcept\s*)?\{/gm,
+      class: /(?:class|struct)\s+(\w+)(?:\s*:\s*(?:public|protected|private)\s+\w+)?/gm,
+      import: /#include\s+[<"]([^>"]+)[>"]/gm,
+      export: /^(?!static\s)(?:class|struct)\s+(\w+)/gm
+    },
+    testFilePatterns: ['**/*_test.cpp', '**/*_test.cc', '**/test_*.cpp'],
+    indexByDefault: false
+  },
+  {
+    id: 'kotlin',
+    displayName: 'Kotlin',
+    extensions: ['.kt', '.kts'],
+    commentPatterns: C_FAMILY_COMMENTS,
+    simplePatterns: {
+      function: /(?:(?:public|private|protected|internal|override|suspend|inline)\s+)*fun\s+(?:<[^>]+>\s+)?(?:\w+\.)?(\w+)\s*\(/gm,
+      class: /(?:(?:public|private|protected|internal|abstract|open|data|sealed|enum)\s+)*(?:class|interface|object)\s+(\w+)/gm,
+      import: /import\s+([\w.]+(?:\.\*)?)/gm,
+      export: /^(?!private\s|internal\s)(?:class|interface|object|fun)\s+(\w+)/gm
+    },
+    testFilePatterns: ['**/src/test/**', '**/*Test.kt'],
+    indexByDefault: false
+  }
+];
+
+const packs = new Map<string, LanguagePack>();
+const extensionIndex = new Map<string, LanguagePack>();
+
+/**
+ * Register a language pack (replaces any pack with the same id)
+ */
+export function registerLanguagePack(pack: LanguagePack): void {
+  packs.set(pack.id, pack);
+  for (const ext of pack.extensions) {
+    extensionIndex.set(ext.toLowerCase(), pack);
+  }
+  registerLanguageExtensions(pack.id, pack.extensions);
+}
+
+for (const pack of BUILTIN_PACKS) {
+  registerLanguagePack(pack);
+}
+
+/**
+ * Get all registered language packs
+ */
+export function getLanguagePacks(): LanguagePack[] {
+  return Array.from(packs.values());
+}
+
+/**
+ * Get a language pack by id
+ */
+export function getLanguagePack(language: string): LanguagePack | undefined {
+  return packs.get(language);
+}
+
+/**
+ * Find the pack that handles a file extension
+ */
+export function getLanguagePackForExtension(extension: string): LanguagePack | undefined {
+  return extensionIndex.get(extension.toLowerCase());
+}
+
+/**
+ * Resolve chunking rules for a language, applying defaults
+ */
+export function getChunkingRules(language: string): ChunkingRules {
+  return { ...DEFAULT_CHUNKING, ...packs.get(language)?.chunking };
+}
+
+/**
+ * Build the parser config a pack describes
+ */
+export function getParserConfig(pack: LanguagePack): ParserConfig {
+  return {
+    language: pack.id,
+    extensions: pack.extensions,
+    commentPatterns: pack.commentPatterns
+  };
+}
+
+/**
+ * Languages indexed when the user hasn't configured includeLanguages
+ */
+export function getDefaultIndexedLanguages(): string[] {
+  const languages = getLanguagePacks().filter(p => p.indexByDefault).map(p => p.id);
+  // JavaScript shares the TypeScript pack but is still accepted as a language name
+  return languages.includes('typescript') ? [...languages, 'javascript'] : languages;
+}
+
+/**
+ * Check whether a path is a test file according to the language packs
+ */
+export function isTestFile(filePath: string): boolean {
+  const normalized = filePath.replace(/\\/g, '/');
+  const options = { dot: true, matchBase: false };
+
+  if (COMMON_TEST_PATTERNS.some(p => minimatch(normalized, p, options))) {
+    return true;
+  }
+
+  const dot = normalized.lastIndexOf('.');
+  const pack = dot >= 0 ? getLanguagePackForExtension(normalized.slice(dot)) : undefined;
+  const candidates = pack ? [pack] : getLanguagePacks();
+  return candidates.some(p => p.testFilePatterns.some(pattern => minimatch(normalized, pattern, options)));
+}
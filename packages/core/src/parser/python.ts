@@ -12,6 +12,7 @@ import {
   Export,
   Parameter,
   CallInfo,
+  InheritanceInfo,
   Visibility
 } from '@cv-git/shared';
 
@@ -90,6 +91,7 @@ export class PythonParser extends BaseLanguageParser {
         isAsync,
         isStatic: false,
         complexity: this.calculateComplexity(funcNode),
+        cognitiveComplexity: this.calculateCognitiveComplexity(funcNode),
         calls: this.extractCalls(funcNode),
         createdAt: Date.now(),
         updatedAt: Date.now()
@@ -127,6 +129,7 @@ export class PythonParser extends BaseLanguageParser {
         isAsync: false,
         isStatic: false,
         complexity: this.calculateComplexity(classNode),
+        inherits: this.getBaseClasses(classNode),
         createdAt: Date.now(),
         updatedAt: Date.now()
       });
@@ -173,6 +176,7 @@ export class PythonParser extends BaseLanguageParser {
         isAsync,
         isStatic,
         complexity: this.calculateComplexity(methodNode),
+        cognitiveComplexity: this.calculateCognitiveComplexity(methodNode),
         calls: this.extractCalls(methodNode),
         createdAt: Date.now(),
         updatedAt: Date.now()
@@ -268,6 +272,16 @@ export class PythonParser extends BaseLanguageParser {
     return nameNode?.text || null;
   }
 
+  /**
+   * `class A(Base, mixins.Other, metaclass=ABCMeta)` -> Base, Other
+   */
+  private getBaseClasses(node: TreeSitterNode): InheritanceInfo[] | undefined {
+    const superclasses = node.childForFieldName('superclasses');
+    if (!superclasses) return undefined;
+    const names = this.getHeritageNames(superclasses.namedChildren).filter(name => name !== 'object');
+    return names.length > 0 ? names.map(name => ({ name, type: 'extends' as const })) : undefined;
+  }
+
   private getClassName(node: TreeSitterNode): string | null {
     const nameNode = node.childForFieldName('name');
     return nameNode?.text || null;
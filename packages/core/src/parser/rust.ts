@@ -58,6 +58,14 @@ export class RustParser extends BaseLanguageParser {
     symbols.push(...this.extractTraits(node, filePath, content));
     symbols.push(...this.extractImpls(node, filePath, content));
 
+    // `impl Trait for Type`: the type implements the trait (when defined in this file)
+    for (const { typeName, traitName } of this.getTraitImpls(node)) {
+      const target = symbols.find(s => s.name === typeName && (s.kind === 'struct' || s.kind === 'type'));
+      if (target) {
+        (target.inherits ??= []).push({ name: traitName, type: 'implements' });
+      }
+    }
+
     return symbols;
   }
 
@@ -96,6 +104,7 @@ export class RustParser extends BaseLanguageParser {
         isAsync,
         isStatic: false,
         complexity: this.calculateComplexity(funcNode),
+        cognitiveComplexity: this.calculateCognitiveComplexity(funcNode),
         calls: this.extractCalls(funcNode),
         createdAt: Date.now(),
         updatedAt: Date.now()
@@ -255,6 +264,7 @@ export class RustParser extends BaseLanguageParser {
           isAsync,
           isStatic: false,
           complexity: this.calculateComplexity(funcNode),
+          cognitiveComplexity: this.calculateCognitiveComplexity(funcNode),
           calls: this.extractCalls(funcNode),
           createdAt: Date.now(),
           updatedAt: Date.now()
@@ -385,6 +395,17 @@ export class RustParser extends BaseLanguageParser {
     return typeNode.text;
   }
 
+  private getTraitImpls(node: TreeSitterNode): Array<{ typeName: string; traitName: string }> {
+    const impls: Array<{ typeName: string; traitName: string }> = [];
+    for (const implNode of this.findNodesByType(node, ['impl_item'])) {
+      const traitNode = implNode.childForFieldName('trait');
+      const typeName = this.getImplTypeName(implNode);
+      const [traitName] = traitNode ? this.getHeritageNames([traitNode]) : [];
+      if (typeName && traitName) impls.push({ typeName, traitName });
+    }
+    return impls;
+  }
+
   private getFunctionSignature(node: TreeSitterNode, content: string): string {
     const lines = content.split('\n');
     const startLine = node.startPosition.row;
@@ -19,84 +19,39 @@ import {
   ImportType
 } from '@cv-git/shared';
 import { ILanguageParser, ParserConfig, TreeSitterNode } from './base.js';
+import { SimplePatterns, getChunkingRules, getLanguagePack, getLanguagePacks, getParserConfig } from './language-packs.js';
 
 /**
  * Simple regex-based parser for when tree-sitter is unavailable
  */
 export class SimpleParser implements ILanguageParser {
   private config: ParserConfig;
-  private patterns: {
-    function: RegExp;
-    class: RegExp;
-    import: RegExp;
-    export: RegExp;
-  };
+  private patterns: SimplePatterns;
 
   constructor(config: ParserConfig) {
     this.config = config;
     this.patterns = this.getPatterns(config.language);
   }
 
-  private getPatterns(language: string) {
-    switch (language) {
-      case 'typescript':
-      case 'javascript':
-        return {
-          function: /(?:export\s+)?(?:async\s+)?function\s+(\w+)|(?:const|let|var)\s+(\w+)\s*=\s*(?:async\s+)?\(|(\w+)\s*:\s*(?:async\s+)?\([^)]*\)\s*(?:=>|{)/gm,
-          class: /(?:export\s+)?(?:abstract\s+)?class\s+(\w+)/gm,
-          import: /import\s+(?:{([^}]+)}|\*\s+as\s+(\w+)|(\w+))\s+from\s+['"]([^'"]+)['"]/gm,
-          export: /export\s+(?:(default)\s+)?(?:class|function|const|let|var|interface|type|enum)\s+(\w+)/gm
-        };
-      case 'python':
-        return {
-          function: /(?:async\s+)?def\s+(\w+)\s*\(/gm,
-          class: /class\s+(\w+)(?:\s*\([^)]*\))?:/gm,
-          import: /(?:from\s+(\S+)\s+)?import\s+([^#\n]+)/gm,
-          export: /^(?!_)(\w+)\s*=/gm
-        };
-      case 'go':
-        return {
-          function: /func\s+(?:\([^)]+\)\s+)?(\w+)\s*\(/gm,
-          class: /type\s+(\w+)\s+struct/gm,
-          import: /import\s+(?:\(\s*)?["']([^"']+)["']/gm,
-          export: /^[A-Z]\w*/gm
-        };
-      case 'rust':
-        return {
-          function: /(?:pub\s+)?(?:async\s+)?fn\s+(\w+)/gm,
-          class: /(?:pub\s+)?struct\s+(\w+)|(?:pub\s+)?enum\s+(\w+)|(?:pub\s+)?trait\s+(\w+)/gm,
-          import: /use\s+([^;]+);/gm,
-          export: /pub\s+(?:fn|struct|enum|trait|mod)\s+(\w+)/gm
-        };
-      case 'java':
-        return {
-          function: /(?:public|private|protected)?\s*(?:static\s+)?(?:\w+\s+)+(\w+)\s*\([^)]*\)\s*(?:throws\s+[^{]+)?{/gm,
-          class: /(?:public\s+)?(?:abstract\s+)?(?:class|interface|enum)\s+(\w+)/gm,
-          import: /import\s+(?:static\s+)?([^;]+);/gm,
-          export: /public\s+(?:class|interface|enum)\s+(\w+)/gm
-        };
-      case 'c':
-        return {
-          function: /^(?:static\s+)?(?:inline\s+)?(?:const\s+)?(?:unsigned\s+)?(?:signed\s+)?(?:void|int|char|short|long|float|double|size_t|bool|_Bool|\w+_t|\w+\s*\*)\s+\*?(\w+)\s*\([^)]*\)\s*\{/gm,
-          class: /(?:typedef\s+)?struct\s+(\w+)|typedef\s+(?:struct|union|enum)\s*\{[^}]*\}\s*(\w+)/gm,
-          import: /#include\s+[<"]([^>"]+)[>"]/gm,
-          export: /^(?!static\s)(?:void|int|char|short|long|float|double|size_t|bool|_Bool|\w+_t|\w+\s*\*)\s+\*?(\w+)\s*\(/gm
-        };
-      case 'cpp':
-        return {
-          function: /^(?:static\s+)?(?:inline\s+)?(?:virtual\s+)?(?:const\s+)?(?:unsigned\s+)?(?:void|int|char|short|long|float|double|size_t|bool|auto|std::\w+|\w+_t|\w+\s*\*)\s+\*?(?:(\w+)::)?(\w+)\s*\([^)]*\)\s*(?:const\s*)?(?:override\s*)?(?:noexcept\s*)?\{/gm,
-          class: /(?:class|struct)\s+(\w+)(?:\s*:\s*(?:public|protected|private)\s+\w+)?/gm,
-          import: /#include\s+[<"]([^>"]+)[>"]/gm,
-          export: /^(?!static\s)(?:class|struct)\s+(\w+)/gm
-        };
-      default:
-        return {
-          function: /function\s+(\w+)/gm,
-          class: /class\s+(\w+)/gm,
-          import: /import|require|use|include/gm,
-          export: /export|public/gm
-        };
+  private getPatterns(language: string): SimplePatterns {
+    const pack = getLanguagePack(language);
+    if (pack) {
+      // Clone so each parser instance has its own lastIndex state
+      const { function: fn, class: cls, import: imp, export: exp } = pack.simplePatterns;
+      return {
+        function: new RegExp(fn.source, fn.flags),
+        class: new RegExp(cls.source, cls.flags),
+        import: new RegExp(imp.source, imp.flags),
+        export: new RegExp(exp.source, exp.flags)
+      };
     }
+
+    return {
+      function: /function\s+(\w+)/gm,
+      class: /class\s+(\w+)/gm,
+      import: /import|require|use|include/gm,
+      export: /export|public/gm
+    };
   }
 
   getLanguage(): string {
@@ -238,7 +193,7 @@ export class SimpleParser implements ILanguageParser {
       }
     } else {
       // Fallback: chunk by fixed line count
-      const chunkSize = 50;
+      const chunkSize = getChunkingRules(this.config.language).fallbackChunkLines;
       for (let i = 0; i < lines.length; i += chunkSize) {
         const endLine = Math.min(i + chunkSize, lines.length);
         chunks.push({
@@ -328,24 +283,13 @@ export class SimpleParser implements ILanguageParser {
 }
 
 /**
- * Create simple parsers for all supported languages
+ * Create simple parsers for all registered language packs
  */
 export function createSimpleParsers(): Map<string, ILanguageParser> {
   const parsers = new Map<string, ILanguageParser>();
 
-  const configs: ParserConfig[] = [
-    { language: 'typescript', extensions: ['.ts', '.tsx', '.js', '.jsx', '.mjs', '.cjs'] },
-    { language: 'python', extensions: ['.py', '.pyi'] },
-    { language: 'go', extensions: ['.go'] },
-    { language: 'rust', extensions: ['.rs'] },
-    { language: 'java', extensions: ['.java'] },
-    { language: 'c', extensions: ['.c', '.h'] },
-    { language: 'cpp', extensions: ['.cpp', '.cc', '.cxx', '.hpp', '.hxx', '.hh'] },
-  ];
-
-  for (const config of configs) {
-    const parser = new SimpleParser(config);
-    parsers.set(config.language, parser);
+  for (const pack of getLanguagePacks()) {
+    parsers.set(pack.id, new SimpleParser(getParserConfig(pack)));
   }
 
   return parsers;
@@ -12,6 +12,7 @@ import {
   Export,
   Parameter,
   CallInfo,
+  InheritanceInfo,
   Visibility
 } from '@cv-git/shared';
 
@@ -102,6 +103,7 @@ export class TypeScriptParser extends BaseLanguageParser {
         isAsync,
         isStatic: false,
         complexity: this.calculateComplexity(funcNode),
+        cognitiveComplexity: this.calculateCognitiveComplexity(funcNode),
         calls: this.extractCalls(funcNode),
         createdAt: Date.now(),
         updatedAt: Date.now()
@@ -139,6 +141,7 @@ export class TypeScriptParser extends BaseLanguageParser {
         isAsync: false,
         isStatic: false,
         complexity: this.calculateComplexity(classNode),
+        inherits: this.getClassHeritage(classNode),
         createdAt: Date.now(),
         updatedAt: Date.now()
       });
@@ -187,6 +190,7 @@ export class TypeScriptParser extends BaseLanguageParser {
         isAsync,
         isStatic,
         complexity: this.calculateComplexity(methodNode),
+        cognitiveComplexity: this.calculateCognitiveComplexity(methodNode),
         calls: this.extractCalls(methodNode),
         createdAt: Date.now(),
         updatedAt: Date.now()
@@ -223,6 +227,7 @@ export class TypeScriptParser extends BaseLanguageParser {
         isAsync: false,
         isStatic: false,
         complexity: 1,
+        inherits: this.getInterfaceHeritage(intNode),
         createdAt: Date.now(),
         updatedAt: Date.now()
       });
@@ -368,6 +373,35 @@ export class TypeScriptParser extends BaseLanguageParser {
     return nameNode?.text || null;
   }
 
+  /**
+   * `class A extends B implements C, D`
+   */
+  private getClassHeritage(node: TreeSitterNode): InheritanceInfo[] | undefined {
+    const heritage = node.namedChildren.find(child => child.type === 'class_heritage');
+    if (!heritage) return undefined;
+
+    const inherits: InheritanceInfo[] = [];
+    for (const clause of heritage.namedChildren) {
+      const type = clause.type === 'implements_clause' ? 'implements' : 'extends';
+      // The JavaScript grammar has the base class directly under class_heritage
+      const types = clause.type.endsWith('_clause') ? clause.namedChildren : [clause];
+      for (const name of this.getHeritageNames(types)) {
+        inherits.push({ name, type });
+      }
+    }
+    return inherits.length > 0 ? inherits : undefined;
+  }
+
+  /**
+   * `interface A extends B, C`
+   */
+  private getInterfaceHeritage(node: TreeSitterNode): InheritanceInfo[] | undefined {
+    const clause = node.namedChildren.find(child => child.type === 'extends_type_clause');
+    if (!clause) return undefined;
+    const names = this.getHeritageNames(clause.namedChildren);
+    return names.length > 0 ? names.map(name => ({ name, type: 'extends' as const })) : undefined;
+  }
+
   private getMethodName(node: TreeSitterNode): string | null {
     const nameNode = node.childForFieldName('name');
     return nameNode?.text || null;
@@ -0,0 +1,26 @@
+/**
+ * Worker behind retrieveSync() / answerSync(): runs the async call and
+ * wakes the waiting thread when it settles
+ */
+
+import { workerData, MessagePort } from 'worker_threads';
+import { retrieve, answer } from './index.js';
+
+const { method, args, signal, port } = workerData as {
+  method: 'retrieve' | 'answer';
+  args: any[];
+  signal: Int32Array;
+  port: MessagePort;
+};
+
+try {
+  const result = method === 'retrieve'
+    ? await retrieve(args[0], args[1], args[2])
+    : await answer(args[0], args[1]);
+  port.postMessage({ result });
+} catch (error: any) {
+  port.postMessage({ error: error?.message || String(error) });
+} finally {
+  Atomics.store(signal, 0, 1);
+  Atomics.notify(signal, 0);
+}
@@ -0,0 +1,305 @@
+/**
+ * Retrieval API
+ *
+ * A small, stable entry point for reusing a repository's index outside the
+ * CLI (chat bots, scripts, services):
+ *
+ *   retrieve(query, filters)  → Citation[]
+ *   answer(query, options)    → { text, citations, sources }
+ *
+ * Nothing here prints, prompts or touches the terminal. Credentials come
+ * from the environment or from explicit options, never from the CLI's
+ * keychain. Use openRetriever() to keep one connection across many calls;
+ * the one-shot functions open and close it each time.
+ *
+ * Blocking variants (retrieveSync, answerSync) run the async call in a
+ * worker thread and wait for it, for hosts that cannot await. They accept
+ * only serialisable options (no provider or client instances).
+ */
+
+import { receiveMessageOnPort, MessageChannel, Worker } from 'worker_threads';
+import { findRepoRoot, CVConfig, VectorSearchResult, CodeChunkPayload } from '@cv-git/shared';
+import { ConfigManager } from '../config/index.js';
+import { getLMStudioUrl, getOllamaUrl, getQdrantUrl } from '../config/service-urls.js';
+import { createVectorManager, VectorManager } from '../vector/index.js';
+import { resolveProvider, EmbeddingProvider } from '../vector/providers.js';
+import { getVectorIndexDir, resolveVectorStoreType } from '../vector/store-factory.js';
+import { loadReembedState, reembedConfig } from '../vector/reembed.js';
+import { ContextUsageModel, loadContextUsage } from '../context/usage-model.js';
+import { DEFAULT_RETRIEVAL_SETTINGS } from '../context/calibration.js';
+import { ScoreDistribution, loadScoreDistribution, resolveMinScore } from '../context/score-distribution.js';
+import { createAIClient } from '../ai/factory.js';
+import { AIClient } from '../ai/types.js';
+
+export interface Citation {
+  /** Repository-relative path */
+  file: string;
+  startLine: number;
+  endLine: number;
+  symbolName?: string;
+  language: string;
+  /** Similarity score (0-1) */
+  score: number;
+  /** Indexed chunk text */
+  text: string;
+}
+
+export interface RetrieveFilters {
+  language?: string;
+  /** Exact repository-relative path */
+  file?: string;
+  /** Default: retrieval.topK from config */
+  limit?: number;
+  /** Default: retrieval.minScore from config, else the threshold sampled at sync */
+  minScore?: number;
+}
+
+export interface RetrieverOptions {
+  /** Repository root (default: found from the current directory) */
+  repoRoot?: string;
+  /** Embedding provider; must match the one the index was built with */
+  provider?: EmbeddingProvider;
+  /** Qdrant URL override */
+  vectorUrl?: string;
+  /** 'next' searches the index `cv index reembed` is building for its new model */
+  index?: 'current' | 'next';
+}
+
+export interface AnswerOptions extends RetrieveFilters {
+  /** Chat client (default: Ollama if running, else OpenRouter via OPENROUTER_API_KEY) */
+  client?: AIClient;
+  model?: string;
+  /** Extra instructions appended to the system prompt */
+  instructions?: string;
+}
+
+export interface Answer {
+  text: string;
+  /** Sources the answer cites ([n] markers); all sources if it cites none */
+  citations: Citation[];
+  /** Every source given to the model, in [n] order */
+  sources: Citation[];
+}
+
+/**
+ * Embedding provider from the repository config and environment variables
+ */
+function providerFromEnvironment(config: CVConfig): EmbeddingProvider {
+  const provider = config.embedding?.provider;
+  const endpoint = config.embedding?.azure?.endpoint || process.env.AZURE_OPENAI_ENDPOINT;
+  const deployment = config.embedding?.azure?.deployment || process.env.AZURE_OPENAI_EMBEDDING_DEPLOYMENT;
+  const azureKey = process.env.AZURE_OPENAI_API_KEY;
+
+  return resolveProvider({
+    provider,
+    model: config.embedding?.model,
+    openrouterApiKey: process.env.OPENROUTER_API_KEY || (provider === 'openrouter' ? config.embedding.apiKey : undefined),
+    openaiApiKey: process.env.OPENAI_API_KEY || (provider === 'openai' ? config.embedding.apiKey : undefined),
+    ollamaUrl: provider === 'ollama' ? getOllamaUrl() : undefined,
+    lmstudioUrl: provider === 'lmstudio' ? getLMStudioUrl() : undefined,
+    azure: endpoint && deployment && azureKey
+      ? { endpoint, deployment, apiKey: azureKey, apiVersion: config.embedding?.azure?.apiVersion || process.env.AZURE_OPENAI_API_VERSION }
+      : undefined
+  });
+}
+
+function toCitation(result: VectorSearchResult<CodeChunkPayload>): Citation {
+  return {
+    file: result.payload.file,
+    startLine: result.payload.startLine,
+    endLine: result.payload.endLine,
+    symbolName: result.payload.symbolName,
+    language: result.payload.language,
+    score: result.score,
+    text: result.payload.text
+  };
+}
+
+function buildPrompt(question: string, sources: Citation[]): string {
+  const blocks = sources.map((s, i) =>
+    `[${i + 1}] ${s.file}:${s.startLine}-${s.endLine}${s.symbolName ? ` (${s.symbolName})` : ''}\n` +
+    '```' + s.language + '\n' + s.text + '\n```'
+  );
+  return `Sources:\n\n${blocks.join('\n\n')}\n\nQuestion: ${question}`;
+}
+
+/**
+ * Sources referenced by [n] markers in the answer
+ */
+export function citedSources(text: string, sources: Citation[]): Citation[] {
+  const cited = new Set<number>();
+  for (const match of text.matchAll(/\[(\d+(?:\s*,\s*\d+)*)\]/g)) {
+    for (const n of match[1].split(',')) {
+      const index = parseInt(n, 10) - 1;
+      if (index >= 0 && index < sources.length) cited.add(index);
+    }
+  }
+  return cited.size > 0 ? [...cited].sort((a, b) => a - b).map(i => sources[i]) : sources;
+}
+
+/**
+ * Connection to one repository's index
+ */
+export class Retriever {
+  private constructor(
+    private config: CVConfig,
+    private vector: VectorManager,
+    private usage: ContextUsageModel | null,
+    private distribution: ScoreDistribution | null
+  ) {}
+
+  static async open(options: RetrieverOptions = {}): Promise<Retriever> {
+    const repoRoot = options.repoRoot || await findRepoRoot();
+    if (!repoRoot) {
+      throw new Error('Not in a CV-Git repository (pass repoRoot)');
+    }
+
+    let config = await new ConfigManager().load(repoRoot);
+    if (options.index === 'next') {
+      const state = await loadReembedState(repoRoot);
+      if (!state || state.swappedAt) {
+        throw new Error('No embedding model migration in progress (start one with `cv index reembed`)');
+      }
+      config = reembedConfig(config, state);
+    }
+
+    const vector = createVectorManager({
+      url: getQdrantUrl(options.vectorUrl || config.vector.url),
+      storeType: resolveVectorStoreType(config.vector.provider),
+      indexDir: getVectorIndexDir(repoRoot),
+      hnsw: config.vector.hnsw,
+      quantization: config.vector.quantization,
+      repoRoot,
+      branchNamespaces: config.vector.branchNamespaces !== false,
+      provider: options.provider || providerFromEnvironment(config),
+      collections: config.vector.collections,
+      aliases: config.vector.aliases
+    });
+    await vector.connect();
+
+    const usage = config.retrieval?.rerank === false ? null : await loadContextUsage(repoRoot);
+    const distribution = await loadScoreDistribution(repoRoot, vector.getEmbeddingInfo().model);
+    return new Retriever(config, vector, usage, distribution);
+  }
+
+  /**
+   * Code chunks relevant to a query, best first
+   */
+  async retrieve(query: string, filters: RetrieveFilters = {}): Promise<Citation[]> {
+    const settings = { ...DEFAULT_RETRIEVAL_SETTINGS, ...this.config.retrieval };
+    const limit = filters.limit ?? settings.topK;
+    const results = await this.vector.searchCode(query, this.usage ? limit * 2 : limit, {
+      language: filters.language,
+      file: filters.file,
+      minScore: filters.minScore ?? resolveMinScore(this.config.retrieval, this.distribution, settings.minScore)
+    });
+    const ranked = this.usage ? this.usage.rerank(results, limit) : results.slice(0, limit);
+    return ranked.map(toCitation);
+  }
+
+  /**
+   * Answer a question from retrieved code, citing sources as [n]
+   */
+  async answer(query: string, options: AnswerOptions = {}): Promise<Answer> {
+    const sources = await this.retrieve(query, options);
+    if (sources.length === 0) {
+      return { text: 'No indexed code matches this question.', citations: [], sources };
+    }
+
+    const client = options.client || await createAIClient({
+      provider: 'auto',
+      model: options.model,
+      apiKey: process.env.OPENROUTER_API_KEY
+    });
+    const systemPrompt =
+      `You answer questions about the ${this.config.repository.name} codebase using only the numbered sources. ` +
+      'Cite the sources you use as [n]. If the sources do not answer the question, say so.' +
+      (options.instructions ? `\n\n${options.instructions}` : '');
+
+    const text = await client.chat([{ role: 'user', content: buildPrompt(query, sources) }], systemPrompt);
+    return { text, citations: citedSources(text, sources), sources };
+  }
+
+  async close(): Promise<void> {
+    await this.vector.close();
+  }
+}
+
+export function openRetriever(options: RetrieverOptions = {}): Promise<Retriever> {
+  return Retriever.open(options);
+}
+
+/**
+ * One-shot retrieve: opens the index, searches and closes it
+ */
+export async function retrieve(
+  query: string,
+  filters: RetrieveFilters = {},
+  options: RetrieverOptions = {}
+): Promise<Citation[]> {
+  const retriever = await Retriever.open(options);
+  try {
+    return await retriever.retrieve(query, filters);
+  } finally {
+    await retriever.close();
+  }
+}
+
+/**
+ * One-shot answer: opens the index, answers and closes it
+ */
+export async function answer(
+  query: string,
+  options: AnswerOptions & RetrieverOptions = {}
+): Promise<Answer> {
+  const retriever = await Retriever.open(options);
+  try {
+    return await retriever.answer(query, options);
+  } finally {
+    await retriever.close();
+  }
+}
+
+/**
+ * Run an exported async function in a worker and block until it settles
+ */
+function runBlocking<T>(method: 'retrieve' | 'answer', args: unknown[]): T {
+  const signal = new Int32Array(new SharedArrayBuffer(4));
+  const { port1, port2 } = new MessageChannel();
+  const worker = new Worker(new URL('./blocking-worker.js', import.meta.url), {
+    workerData: { method, args, signal, port: port2 },
+    transferList: [port2]
+  });
+
+  try {
+    Atomics.wait(signal, 0, 0);
+    const reply = receiveMessageOnPort(port1)?.message as { result?: T; error?: string } | undefined;
+    if (!reply) throw new Error(`${method} worker exited without a result`);
+    if (reply.error !== undefined) throw new Error(reply.error);
+    return reply.result as T;
+  } finally {
+    port1.close();
+    void worker.terminate();
+  }
+}
+
+/**
+ * Blocking retrieve(); options must be serialisable
+ */
+export function retrieveSync(
+  query: string,
+  filters: RetrieveFilters = {},
+  options: Omit<RetrieverOptions, 'provider'> = {}
+): Citation[] {
+  return runBlocking('retrieve', [query, filters, options]);
+}
+
+/**
+ * Blocking answer(); options must be serialisable
+ */
+export function answerSync(
+  query: string,
+  options: Omit<AnswerOptions, 'client'> & Omit<RetrieverOptions, 'provider'> = {}
+): Answer {
+  return runBlocking('answer', [query, options]);
+}
@@ -0,0 +1,263 @@
+/**
+ * Command Sandbox
+ *
+ * Runs shell commands proposed by the agent (builds, tests) with limited
+ * reach: the working directory must be inside the repository, only an
+ * allowlist of environment variables is passed through, network access is
+ * cut off unless the repository opts in, and the whole process group is
+ * killed when the timeout expires.
+ *
+ * Policy comes from the [sandbox] table of `.cvgit.toml`:
+ *
+ *   [sandbox]
+ *   allow = ["npm test", "npm run build", "cargo test *"]
+ *   env = ["NODE_ENV", "DATABASE_URL"]
+ *   network = false
+ *   timeout = 300   # seconds
+ *
+ * Commands matching `allow` run without an extra confirmation; commands
+ * that chain or redirect (`;`, `&&`, `|`, `>`, `$(...)`) never match.
+ *
+ * Network isolation uses a new network namespace on Linux (`unshare`) and
+ * `sandbox-exec` on macOS. Where neither is available, commands are
+ * refused unless `network = true`.
+ */
+
+import { spawn, spawnSync } from 'child_process';
+import * as path from 'path';
+import { ConfigError } from '@cv-git/shared';
+import { readRepoConfigFile, REPO_CONFIG_FILE } from '../config/repo-config.js';
+
+export interface SandboxPolicy {
+  /** Command patterns that run without confirmation; `*` matches any text */
+  allow: string[];
+  /** Environment variables passed through on top of the basics (PATH, HOME, ...) */
+  env: string[];
+  /** Allow network access */
+  network: boolean;
+  timeoutMs: number;
+}
+
+export interface SandboxResult {
+  exitCode: number;
+  /** Combined stdout and stderr, truncated at MAX_SANDBOX_OUTPUT */
+  output: string;
+  timedOut: boolean;
+  durationMs: number;
+}
+
+export interface SandboxRunOptions {
+  repoRoot: string;
+  /** Working directory, relative to the repository root (default: the root) */
+  cwd?: string;
+  policy: SandboxPolicy;
+  /** Called with output as it arrives */
+  onOutput?: (chunk: string) => void;
+}
+
+export class SandboxError extends Error {
+  constructor(message: string) {
+    super(message);
+    this.name = 'SandboxError';
+  }
+}
+
+export const DEFAULT_SANDBOX_POLICY: SandboxPolicy = {
+  allow: [],
+  env: [],
+  network: false,
+  timeoutMs: 5 * 60 * 1000
+};
+
+/** Output kept per command; the rest is dropped */
+export const MAX_SANDBOX_OUTPUT = 1024 * 1024;
+
+/** Passed through to every command so tools can find binaries, caches and a locale */
+const BASE_ENV = [
+  'PATH', 'HOME', 'USER', 'LOGNAME', 'SHELL', 'LANG', 'LC_ALL', 'LC_CTYPE', 'TERM', 'TMPDIR', 'TZ',
+  // Windows
+  'SYSTEMROOT', 'SYSTEMDRIVE', 'COMSPEC', 'PATHEXT', 'TEMP', 'TMP', 'USERPROFILE', 'APPDATA', 'LOCALAPPDATA'
+];
+
+/** Chaining, redirection and substitution take a command outside its pattern */
+const SHELL_OPERATORS = /[;&|<>`$()\n\r]/;
+
+/**
+ * Sandbox policy from `.cvgit.toml`, with defaults for anything unset
+ */
+export async function loadSandboxPolicy(repoRoot: string): Promise<SandboxPolicy> {
+  const config = await readRepoConfigFile(repoRoot);
+  return parseSandboxPolicy(config.sandbox);
+}
+
+/**
+ * Validate a [sandbox] table
+ */
+export function parseSandboxPolicy(table: unknown): SandboxPolicy {
+  if (table === undefined) return { ...DEFAULT_SANDBOX_POLICY };
+  if (typeof table !== 'object' || table === null || Array.isArray(table)) {
+    throw new ConfigError(`${REPO_CONFIG_FILE}: [sandbox] must be a table`);
+  }
+  const raw = table as Record<string, unknown>;
+
+  const strings = (key: string): string[] => {
+    const value = raw[key];
+    if (value === undefined) return [];
+    if (!Array.isArray(value) || value.some(v => typeof v !== 'string')) {
+      throw new ConfigError(`${REPO_CONFIG_FILE}: sandbox.${key} must be an array of strings`);
+    }
+    return value as string[];
+  };
+
+  if (raw.network !== undefined && typeof raw.network !== 'boolean') {
+    throw new ConfigError(`${REPO_CONFIG_FILE}: sandbox.network must be true or false`);
+  }
+  if (raw.timeout !== undefined && (typeof raw.timeout !== 'number' || !(raw.timeout > 0))) {
+    throw new ConfigError(`${REPO_CONFIG_FILE}: sandbox.timeout must be a positive number of seconds`);
+  }
+
+  return {
+    allow: strings('allow'),
+    env: strings('env'),
+    network: (raw.network as boolean | undefined) ?? DEFAULT_SANDBOX_POLICY.network,
+    timeoutMs: raw.timeout !== undefined ? Math.round((raw.timeout as number) * 1000) : DEFAULT_SANDBOX_POLICY.timeoutMs
+  };
+}
+
+/**
+ * Whether a command matches the allowlist
+ */
+export function isCommandAllowed(command: string, policy: SandboxPolicy): boolean {
+  const normalized = command.trim().replace(/\s+/g, ' ');
+  if (!normalized || SHELL_OPERATORS.test(normalized)) return false;
+  return policy.allow.some(pattern => {
+    const source = pattern.trim().replace(/\s+/g, ' ')
+      .split('*')
+      .map(part => part.replace(/[.+?^{}()|[\]\\]/g, '\\$&'))
+      .join('.*');
+    return new RegExp(`^${source}$`).test(normalized);
+  });
+}
+
+/**
+ * Environment for a sandboxed command: the basics plus the policy's allowlist
+ */
+export function sandboxEnv(policy: SandboxPolicy, env: NodeJS.ProcessEnv = process.env): NodeJS.ProcessEnv {
+  const allowed = new Set([...BASE_ENV, ...policy.env]);
+  const result: NodeJS.ProcessEnv = {};
+  for (const [key, value] of Object.entries(env)) {
+    // Windows environment names are case-insensitive
+    const name = process.platform === 'win32' ? key.toUpperCase() : key;
+    if (value !== undefined && (allowed.has(name) || allowed.has(key))) result[key] = value;
+  }
+  // No input can reach the command; keep test runners out of watch mode and prompts
+  result.CI = '1';
+  return result;
+}
+
+let isolationProbe: string[] | null | undefined;
+
+/**
+ * Command prefix that cuts off network access, or null when no mechanism works here
+ */
+export function networkIsolationPrefix(): string[] | null {
+  if (isolationProbe !== undefined) return isolationProbe;
+
+  const works = (argv: string[]) => {
+    const probe = spawnSync(argv[0], [...argv.slice(1), 'true'], { stdio: 'ignore', timeout: 5000 });
+    return probe.status === 0;
+  };
+
+  isolationProbe = null;
+  if (process.platform === 'linux') {
+    // Unprivileged user namespace with an empty network namespace (loopback only, down)
+    const prefix = ['unshare', '--user', '--map-root-user', '--net', '--'];
+    if (works(prefix)) isolationProbe = prefix;
+  } else if (process.platform === 'darwin') {
+    const prefix = ['sandbox-exec', '-p', '(version 1)(allow default)(deny network*)'];
+    if (works(prefix)) isolationProbe = prefix;
+  }
+  return isolationProbe;
+}
+
+/**
+ * Resolve the working directory, refusing anything outside the repository
+ */
+function resolveCwd(repoRoot: string, cwd?: string): string {
+  const root = path.resolve(repoRoot);
+  const resolved = path.resolve(root, cwd ?? '.');
+  const relative = path.relative(root, resolved);
+  if (relative.startsWith('..') || path.isAbsolute(relative)) {
+    throw new SandboxError(`Working directory ${cwd} is outside the repository`);
+  }
+  return resolved;
+}
+
+/**
+ * Run a command under the sandbox policy
+ */
+export async function runSandboxed(command: string, options: SandboxRunOptions): Promise<SandboxResult> {
+  const { policy } = options;
+  const cwd = resolveCwd(options.repoRoot, options.cwd);
+
+  const shell = process.platform === 'win32'
+    ? [process.env.COMSPEC || 'cmd.exe', '/d', '/s', '/c', command]
+    : ['/bin/sh', '-c', command];
+
+  let argv = shell;
+  if (!policy.network) {
+    const prefix = networkIsolationPrefix();
+    if (!prefix) {
+      throw new SandboxError(
+        `Cannot disable network access on this system; set network = true under [sandbox] in ${REPO_CONFIG_FILE} to run commands with network access`
+      );
+    }
+    argv = [...prefix, ...shell];
+  }
+
+  const started = Date.now();
+  return new Promise((resolve, reject) => {
+    const child = spawn(argv[0], argv.slice(1), {
+      cwd,
+      env: sandboxEnv(policy),
+      stdio: ['ignore', 'pipe', 'pipe'],
+      // Own process group, so the timeout can kill everything the command started
+      detached: process.platform !== 'win32',
+      windowsHide: true
+    });
+
+    let output = '';
+    let timedOut = false;
+    const onData = (data: Buffer) => {
+      const text = data.toString();
+      if (output.length < MAX_SANDBOX_OUTPUT) output += text.slice(0, MAX_SANDBOX_OUTPUT - output.length);
+      options.onOutput?.(text);
+    };
+    child.stdout!.on('data', onData);
+    child.stderr!.on('data', onData);
+
+    const timer = setTimeout(() => {
+      timedOut = true;
+      try {
+        if (process.platform !== 'win32' && child.pid) process.kill(-child.pid, 'SIGKILL');
+        else child.kill('SIGKILL');
+      } catch {
+        // Already gone
+      }
+    }, policy.timeoutMs);
+
+    child.on('error', error => {
+      clearTimeout(timer);
+      reject(new SandboxError(`Could not start command: ${error.message}`));
+    });
+    child.on('close', code => {
+      clearTimeout(timer);
+      resolve({
+        exitCode: timedOut ? 124 : code ?? 1,
+        output: timedOut ? `${output}\n(killed after ${policy.timeoutMs / 1000}s timeout)` : output,
+        timedOut,
+        durationMs: Date.now() - started
+      });
+    });
+  });
+}
@@ -1,9 +1,10 @@
 /**
  * AI Security Module
  *
- * STUB: Not yet implemented
+ * Implemented: secret redaction for provider requests (redact.ts) and the
+ * `cv security scan` detectors with model review of flagged code (scan.ts).
  *
- * This module will provide AI-powered security scanning and analysis.
+ * STUB: The scanner and analyzer classes below are not yet implemented.
  * Features planned:
  * - SAST (Static Application Security Testing)
  * - Secret detection and credential scanning
@@ -20,6 +21,8 @@
  */
 
 export * from './types.js';
+export * from './redact.js';
+export * from './scan.js';
 
 /**
  * Security Scanner placeholder class
@@ -0,0 +1,413 @@
+/**
+ * Security Scan
+ *
+ * `cv security scan` works in two passes. Pattern detectors flag candidate
+ * lines cheaply: hard-coded secrets (the redaction rules), SQL built by string
+ * concatenation or interpolation, and command execution through a shell or
+ * with a non-literal command. The model then reviews each flagged region
+ * together with its enclosing function and that function's callers from the
+ * symbol graph, and traces whether untrusted input can reach the sink, which
+ * is what separates an injection from a query built out of constants.
+ *
+ * Findings are ReviewFindings with a CWE, so they share the review store
+ * (triage in `cv tui`), --fail-on and SARIF output with `cv review`.
+ */
+
+import { detectLanguage, matchGlob } from '@cv-git/shared';
+import type { SymbolNode } from '@cv-git/shared';
+import type { GraphManager } from '../graph/index.js';
+import { isTestFile } from '../parser/language-packs.js';
+import { findingId, REVIEW_SEVERITIES, ReviewFinding, ReviewSeverity, sortFindings } from '../ai/review.js';
+import { Redactor, RedactionConfig } from './redact.js';
+
+export interface SecurityDetector {
+  id: string;
+  title: string;
+  cwe: string;
+  severity: ReviewSeverity;
+  description: string;
+  /** Tested on each line; `languages` as from detectLanguage, all when unset */
+  patterns: Array<{ pattern: RegExp; languages?: string[] }>;
+}
+
+/** SQL statements worth flagging when built from strings */
+const SQL = String.raw`(?:select\b[^"'\x60]*\bfrom|insert\s+into|update\s+[\w."\x60\[\]]+\s+set|delete\s+from)\b`;
+
+function sql(template: string): RegExp {
+  return new RegExp(template.replace(/SQL/g, SQL), 'i');
+}
+
+export const SECURITY_DETECTORS: SecurityDetector[] = [
+  {
+    id: 'sql-injection',
+    title: 'SQL built from strings',
+    cwe: 'CWE-89',
+    severity: 'high',
+    description: 'SQL query assembled by concatenation, interpolation or formatting instead of bound parameters',
+    patterns: [
+      // "SELECT ... WHERE id = " + id
+      { pattern: sql(String.raw`"[^"\n]*SQL[^"\n]*"\s*\+\s*[\w(]`) },
+      { pattern: sql(String.raw`'[^'\n]*SQL[^'\n]*'\s*\+\s*[\w(]`) },
+      // JavaScript templates; tagged templates such as sql`...` bind their values
+      { pattern: sql(String.raw`(?<![\w$.])\x60[^\x60]*SQL[^\x60]*\$\{`), languages: ['typescript'] },
+      // Python %-formatting, .format() and f-strings
+      { pattern: sql(String.raw`(?:"[^"\n]*SQL[^"\n]*"|'[^'\n]*SQL[^'\n]*')\s*%\s*[\w(]`), languages: ['python'] },
+      { pattern: sql(String.raw`(?:"[^"\n]*SQL[^"\n]*"|'[^'\n]*SQL[^'\n]*')\.format\(`), languages: ['python'] },
+      { pattern: sql(String.raw`\bf(?:"[^"\n]*SQL[^"\n]*|'[^'\n]*SQL[^'\n]*)\{`), languages: ['python'] },
+      { pattern: sql(String.raw`\bformat!\(\s*"[^"\n]*SQL[^"\n]*\{`), languages: ['rust'] },
+      { pattern: sql(String.raw`\bSprintf\(\s*"[^"\n]*SQL[^"\n]*%[sv]`), languages: ['go'] },
+      { pattern: sql(String.raw`\bString\.format\(\s*"[^"\n]*SQL[^"\n]*%s`), languages: ['java', 'kotlin'] },
+      { pattern: sql(String.raw`\$"[^"\n]*SQL[^"\n]*\{`), languages: ['csharp'] },
+    ],
+  },
+  {
+    id: 'command-injection',
+    title: 'Command execution with a non-literal command',
+    cwe: 'CWE-78',
+    severity: 'high',
+    description: 'A shell or process is started with a command assembled at runtime',
+    patterns: [
+      // child_process exec/execSync with a template, a concatenation or a variable
+      { pattern: /(?:(?<![\w$.])|child_process\.|\bcp\.)exec(?:Sync)?\s*\(\s*(?:\x60[^\x60]*\$\{|["'][^"'\n]*["']\s*\+|[A-Za-z_$][\w$.]*\s*[,)])/, languages: ['typescript'] },
+      { pattern: /\b(?:spawn|spawnSync|execFile|execFileSync)\s*\([^\n]*\bshell\s*:\s*true/, languages: ['typescript'] },
+      { pattern: /\bos\.(?:system|popen)\s*\(\s*(?!["'][^"'\n]*["']\s*\))/, languages: ['python'] },
+      { pattern: /\bsubprocess\.\w+\([^\n]*\bshell\s*=\s*True/, languages: ['python'] },
+      { pattern: /\bexec\.Command(?:Context)?\([^\n]*"(?:sh|bash|\/bin\/sh|\/bin\/bash|cmd(?:\.exe)?)"\s*,\s*"(?:-c|\/[cC])"/, languages: ['go'] },
+      { pattern: /\bCommand::new\(\s*"(?:sh|bash|\/bin\/sh|\/bin\/bash|cmd(?:\.exe)?)"\s*\)/, languages: ['rust'] },
+      { pattern: /\bRuntime\.getRuntime\(\)\.exec\(\s*(?!"[^"\n]*"\s*\))/, languages: ['java', 'kotlin'] },
+      { pattern: /\bnew\s+ProcessBuilder\([^\n]*"(?:sh|bash|\/bin\/sh|cmd(?:\.exe)?)"\s*,\s*"(?:-c|\/[cC])"/, languages: ['java', 'kotlin'] },
+      { pattern: /(?<![\w>.])(?:system|popen)\s*\(\s*(?!")/, languages: ['c', 'cpp'] },
+      { pattern: /\b(?:shell_exec|system|passthru|exec|popen|proc_open)\s*\(\s*[^'"\s)]/, languages: ['php'] },
+      { pattern: /(?:\bsystem|\bexec|%x)\s*[(\s]\s*"[^"\n]*#\{/, languages: ['ruby'] },
+    ],
+  },
+  {
+    id: 'hardcoded-secret',
+    title: 'Hard-coded secret',
+    cwe: 'CWE-798',
+    severity: 'high',
+    description: 'A credential, key or token committed in the source (found by the redaction rules)',
+    // Matched by the redaction rules instead of line patterns
+    patterns: [],
+  },
+];
+
+/** Secret rules that are rarely wrong */
+const CERTAIN_SECRETS = new Set(['private-key', 'aws-access-key', 'github-token', 'gitlab-token', 'slack-token', 'stripe-key', 'google-api-key', 'api-key']);
+
+/** Generated, vendored and lock files are not scanned */
+const SKIPPED_FILES = /(^|\/)(node_modules|vendor|dist|build|target|\.venv|third_party)\/|\.min\.(js|css)$|(^|\/)(package-lock\.json|pnpm-lock\.yaml|yarn\.lock|Cargo\.lock|go\.sum|poetry\.lock)$/;
+
+/** Lines that are only a comment */
+const COMMENT_LINE = /^\s*(\/\/|#(?!include|define|\[)|\*|\/\*|--|<!--)/;
+
+export interface SecurityCandidate {
+  /** `<detector>:<file>:<line>`; how the prompt and the model refer to it */
+  id: string;
+  detector: string;
+  file: string;
+  line: number;
+  /** The flagged line, trimmed, with secrets masked */
+  snippet: string;
+  /** e.g. the redaction rule for a secret */
+  detail?: string;
+}
+
+export interface SecurityScanOptions {
+  /** Detector IDs and file globs to skip (`security.ignore` in .cvgit.toml) */
+  ignore?: string[];
+  /** Redaction settings, for custom secret patterns and allow-lists */
+  redaction?: RedactionConfig;
+}
+
+/**
+ * Whether a file is scanned at all, given the ignore list
+ */
+export function isSecurityScanned(file: string, ignore: string[] = []): boolean {
+  if (SKIPPED_FILES.test(file)) return false;
+  return !ignore.some(entry => !SECURITY_DETECTORS.some(detector => detector.id === entry) && matchGlob(file, entry));
+}
+
+/**
+ * Lines in a file that a detector flags. Code detectors skip tests and
+ * comments; secrets are flagged anywhere, since a real key leaks from a test
+ * as easily as from production code.
+ */
+export function scanSource(file: string, text: string, options: SecurityScanOptions = {}): SecurityCandidate[] {
+  const ignore = options.ignore ?? [];
+  if (!isSecurityScanned(file, ignore)) return [];
+  const enabled = SECURITY_DETECTORS.filter(detector => !ignore.includes(detector.id));
+  const language = detectLanguage(file);
+  const redactor = new Redactor({ ...options.redaction, emails: false });
+  const lines = text.split('\n');
+  const candidates = new Map<string, SecurityCandidate>();
+  const add = (detector: string, line: number, detail?: string) => {
+    const id = `${detector}:${file}:${line}`;
+    if (candidates.has(id)) return;
+    const snippet = redactor.redact(lines[line - 1] ?? '').text.trim().slice(0, 200);
+    candidates.set(id, { id, detector, file, line, snippet, ...(detail ? { detail } : {}) });
+  };
+
+  if (!isTestFile(file)) {
+    for (const detector of enabled) {
+      const patterns = detector.patterns.filter(p => !p.languages || p.languages.includes(language));
+      if (patterns.length === 0) continue;
+      lines.forEach((line, i) => {
+        if (line.length > 2000 || COMMENT_LINE.test(line)) return;
+        if (patterns.some(p => p.pattern.test(line))) add(detector.id, i + 1);
+      });
+    }
+  }
+
+  if (enabled.some(detector => detector.id === 'hardcoded-secret')) {
+    for (const match of redactor.find(text)) add('hardcoded-secret', match.line, match.rule);
+  }
+  return [...candidates.values()].sort((a, b) => a.line - b.line || a.detector.localeCompare(b.detector));
+}
+
+export interface SecurityCodeBlock {
+  file: string;
+  /** First line of `text` */
+  from: number;
+  text: string;
+  /** Symbol the block is, when it is one */
+  symbol?: string;
+}
+
+export interface SecurityContext {
+  candidate: SecurityCandidate;
+  /** The enclosing function, or lines around the candidate */
+  region: SecurityCodeBlock;
+  /** Functions calling the enclosing one, where tainted input may come from */
+  callers: SecurityCodeBlock[];
+}
+
+/** Lines around a candidate outside any function, and longest region kept */
+const REGION_CONTEXT = 8;
+const MAX_REGION_LINES = 80;
+const MAX_CALLER_LINES = 40;
+const MAX_CALLERS = 3;
+
+const FUNCTION_KINDS = new Set(['function', 'method']);
+
+/**
+ * The code the model needs for each candidate: its enclosing function and
+ * that function's callers from the graph. Secrets get no callers, and are
+ * masked in every block so the review never sees them.
+ */
+export async function collectSecurityContexts(
+  candidates: SecurityCandidate[],
+  options: {
+    readFile: (file: string) => Promise<string | null>;
+    graph?: Pick<GraphManager, 'getFileSymbols' | 'getCallers'>;
+    redaction?: RedactionConfig;
+  }
+): Promise<SecurityContext[]> {
+  const redactor = new Redactor({ ...options.redaction, emails: false });
+  const texts = new Map<string, string[] | null>();
+  const linesOf = async (file: string) => {
+    if (!texts.has(file)) texts.set(file, (await options.readFile(file))?.split('\n') ?? null);
+    return texts.get(file)!;
+  };
+  const symbolsOf = new Map<string, SymbolNode[]>();
+  const fileSymbols = async (file: string) => {
+    if (!options.graph) return [];
+    if (!symbolsOf.has(file)) symbolsOf.set(file, await options.graph.getFileSymbols(file).catch(() => []));
+    return symbolsOf.get(file)!;
+  };
+  const block = (file: string, lines: string[], from: number, to: number, max: number, symbol?: string): SecurityCodeBlock => {
+    const start = Math.max(1, from);
+    const end = Math.min(lines.length, to, start + max - 1);
+    const text = redactor.redact(lines.slice(start - 1, end).join('\n')).text;
+    return { file, from: start, text, ...(symbol ? { symbol } : {}) };
+  };
+
+  const contexts: SecurityContext[] = [];
+  for (const candidate of candidates) {
+    const lines = await linesOf(candidate.file);
+    if (!lines) continue;
+
+    const enclosing = (await fileSymbols(candidate.file))
+      .filter(symbol => FUNCTION_KINDS.has(symbol.kind) && symbol.startLine <= candidate.line && symbol.endLine >= candidate.line)
+      .sort((a, b) => (a.endLine - a.startLine) - (b.endLine - b.startLine))[0];
+
+    let region: SecurityCodeBlock;
+    if (enclosing && enclosing.endLine - enclosing.startLine < MAX_REGION_LINES) {
+      region = block(candidate.file, lines, enclosing.startLine, enclosing.endLine, MAX_REGION_LINES, enclosing.qualifiedName);
+    } else {
+      const half = enclosing ? MAX_REGION_LINES / 2 : REGION_CONTEXT;
+      region = block(candidate.file, lines, candidate.line - half, candidate.line + half, MAX_REGION_LINES, enclosing?.qualifiedName);
+    }
+
+    const callers: SecurityCodeBlock[] = [];
+    if (enclosing && options.graph && candidate.detector !== 'hardcoded-secret') {
+      const found = (await options.graph.getCallers(enclosing.qualifiedName).catch(() => []))
+        .filter(caller => !isTestFile(caller.file))
+        .slice(0, MAX_CALLERS);
+      for (const caller of found) {
+        const callerLines = await linesOf(caller.file);
+        if (callerLines) callers.push(block(caller.file, callerLines, caller.startLine, caller.endLine, MAX_CALLER_LINES, caller.qualifiedName));
+      }
+    }
+    contexts.push({ candidate, region, callers });
+  }
+  return contexts;
+}
+
+/**
+ * Contexts in prompt-sized groups, keeping each file's candidates together
+ */
+export function groupSecurityContexts(contexts: SecurityContext[], size: number = 10): SecurityContext[][] {
+  const groups: SecurityContext[][] = [];
+  for (const context of [...contexts].sort((a, b) => a.candidate.file.localeCompare(b.candidate.file))) {
+    const last = groups[groups.length - 1];
+    if (last && last.length < size) last.push(context);
+    else groups.push([context]);
+  }
+  return groups;
+}
+
+export const SECURITY_SYSTEM_PROMPT = `You are an application security engineer reviewing code flagged by static detectors.
+For every flagged line, trace where its data comes from: follow parameters into the callers shown and decide whether untrusted input (HTTP requests, CLI arguments, environment, files, messages) can reach the sink without validation, escaping or parameterization.
+Mark a flag safe when the values are constants, allow-listed or bound as parameters, or when a secret is clearly a placeholder or test fixture. Be precise; do not speculate beyond the code shown.`;
+
+/**
+ * Prompt with each candidate, its region and its callers
+ */
+export function buildSecurityPrompt(contexts: SecurityContext[]): string {
+  const parts: string[] = ['Review these flagged locations.', ''];
+  for (const { candidate, region, callers } of contexts) {
+    const detector = SECURITY_DETECTORS.find(d => d.id === candidate.detector)!;
+    parts.push(`## ${candidate.id}`);
+    parts.push(`Detector: ${detector.title} (${detector.cwe})${candidate.detail ? `, rule ${candidate.detail}` : ''}`);
+    parts.push(`Flagged line ${candidate.line}: ${candidate.snippet}`);
+    parts.push(`### ${region.symbol ?? region.file} (${region.file}:${region.from})`, '```', numbered(region), '```');
+    for (const caller of callers) {
+      parts.push(`### Caller ${caller.symbol ?? caller.file} (${caller.file}:${caller.from})`, '```', numbered(caller), '```');
+    }
+    parts.push('');
+  }
+
+  parts.push(
+    'Respond with JSON only, one entry per flagged location:',
+    '```json',
+    '{"findings": [{"id": "<the ## heading>", "verdict": "vulnerable" | "uncertain" | "safe",',
+    '  "severity": "critical" | "high" | "medium" | "low", "title": "short title",',
+    '  "message": "why, citing lines", "taint": "source → ... → sink, when input reaches it",',
+    '  "suggestion": "the fix"}]}',
+    '```'
+  );
+  return parts.join('\n');
+}
+
+function numbered(block: SecurityCodeBlock): string {
+  return block.text.split('\n').map((line, i) => `${block.from + i}: ${line}`).join('\n');
+}
+
+export type SecurityConfidence = 'confirmed' | 'uncertain' | 'unverified';
+
+export interface SecurityFindingResult extends ReviewFinding {
+  cwe: string;
+  /** confirmed by the model, possible, or only matched by a detector (--no-ai, or the model skipped it) */
+  confidence: SecurityConfidence;
+  /** How untrusted input reaches the sink */
+  taint?: string;
+}
+
+/**
+ * A finding for a candidate, before or without the model's review
+ */
+export function candidateFinding(candidate: SecurityCandidate): SecurityFindingResult {
+  const detector = SECURITY_DETECTORS.find(d => d.id === candidate.detector)!;
+  const severity = candidate.detector === 'hardcoded-secret' && !CERTAIN_SECRETS.has(candidate.detail ?? '')
+    ? 'medium'
+    : detector.severity;
+  const title = candidate.detail ? `${detector.title} (${candidate.detail})` : detector.title;
+  return {
+    // The snippet keeps the ID stable as the code around it moves
+    id: findingId({ file: candidate.file, rule: detector.id, title: `${title} ${candidate.snippet}` }),
+    severity,
+    file: candidate.file,
+    line: candidate.line,
+    title,
+    message: `${detector.description}: ${candidate.snippet}`,
+    rule: detector.id,
+    cwe: detector.cwe,
+    confidence: 'unverified'
+  };
+}
+
+/**
+ * Findings from the model's verdicts. Safe verdicts are dropped; candidates
+ * the model didn't answer for stay as unverified detector findings.
+ */
+export function parseSecurityResponse(
+  response: string,
+  contexts: SecurityContext[]
+): { findings: SecurityFindingResult[]; safe: number } {
+  const fenced = response.match(/```(?:json)?\s*\n([\s\S]*?)\n```/);
+  const candidate = fenced ? fenced[1] : response.match(/\{[\s\S]*\}/)?.[0];
+  let parsed: any;
+  try {
+    parsed = candidate ? JSON.parse(candidate) : undefined;
+  } catch {
+    parsed = undefined;
+  }
+  if (!parsed || !Array.isArray(parsed.findings)) {
+    throw new Error('The model did not return its review as JSON');
+  }
+
+  const verdicts = new Map<string, any>();
+  for (const raw of parsed.findings) {
+    const id = String(raw?.id ?? '').replace(/^#+\s*/, '').trim();
+    if (id && !verdicts.has(id)) verdicts.set(id, raw);
+  }
+
+  const text = (value: unknown) => (typeof value === 'string' && value.trim() ? value.trim() : undefined);
+  const findings: SecurityFindingResult[] = [];
+  let safe = 0;
+  for (const { candidate: flagged } of contexts) {
+    const base = candidateFinding(flagged);
+    const raw = verdicts.get(flagged.id);
+    const verdict = String(raw?.verdict ?? '').toLowerCase();
+    if (verdict === 'safe') {
+      safe++;
+      continue;
+    }
+    if (verdict !== 'vulnerable' && verdict !== 'uncertain') {
+      findings.push(base);
+      continue;
+    }
+    const severity = String(raw.severity ?? '').toLowerCase();
+    findings.push({
+      ...base,
+      severity: (REVIEW_SEVERITIES as string[]).includes(severity) ? severity as ReviewSeverity : base.severity,
+      message: text(raw.message) ?? base.message,
+      suggestion: text(raw.suggestion),
+      taint: text(raw.taint),
+      confidence: verdict === 'vulnerable' ? 'confirmed' : 'uncertain'
+    });
+  }
+  return { findings: sortFindings(findings), safe };
+}
+
+/** GitHub code scanning's numeric severity for each level */
+const SECURITY_SEVERITY: Record<ReviewSeverity, string> = {
+  critical: '9.5',
+  high: '8.0',
+  medium: '5.5',
+  low: '3.0',
+  info: '0.0'
+};
+
+/**
+ * SARIF rule descriptions for the detectors, tagged with their CWE
+ */
+export function securitySarifRules(): Array<{ id: string; description: string; tags: string[]; securitySeverity: string }> {
+  return SECURITY_DETECTORS.map(detector => ({
+    id: detector.id,
+    description: `${detector.title} (${detector.cwe})`,
+    tags: ['security', `external/cwe/${detector.cwe.toLowerCase()}`],
+    securitySeverity: SECURITY_SEVERITY[detector.severity]
+  }));
+}
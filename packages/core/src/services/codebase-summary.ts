@@ -12,6 +12,7 @@
 import Anthropic from '@anthropic-ai/sdk';
 import { GraphManager } from '../graph/index.js';
 import { VectorManager } from '../vector/index.js';
+import { limitedFetch } from '../ai/http.js';
 import * as fs from 'fs/promises';
 import * as path from 'path';
 
@@ -141,7 +142,7 @@ export class CodebaseSummaryService {
     private graph: GraphManager,
     private vector?: VectorManager
   ) {
-    this.client = new Anthropic({ apiKey: options.apiKey });
+    this.client = new Anthropic({ apiKey: options.apiKey, fetch: limitedFetch('anthropic'), maxRetries: 0 });
     this.model = options.model || 'claude-sonnet-4-5-20250514';
     this.maxTokens = options.maxTokens || 4096;
     this.repoRoot = options.repoRoot;
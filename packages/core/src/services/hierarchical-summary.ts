@@ -14,6 +14,7 @@ import {
 } from '@cv-git/shared';
 import { VectorManager } from '../vector/index.js';
 import { GraphManager } from '../graph/index.js';
+import { SummaryCache, gitBlobHash } from './summary-cache.js';
 import * as crypto from 'crypto';
 import * as path from 'path';
 
@@ -22,8 +23,16 @@ export interface SummaryContext {
   generateSummary?: (prompt: string, maxTokens?: number) => Promise<string>;
   /** Use simple extraction instead of LLM */
   useFallback?: boolean;
+  /** Persistent cache for LLM responses, keyed by blob hash and prompt version */
+  cache?: SummaryCache;
 }
 
+/**
+ * Bump when a summary prompt changes so cached summaries are regenerated
+ */
+export const SYMBOL_SUMMARY_PROMPT_VERSION = 'symbol-v1';
+export const FILE_SUMMARY_PROMPT_VERSION = 'file-v1';
+
 export interface ProgressCallback {
   /** Called with progress updates */
   (progress: {
@@ -102,7 +111,9 @@ export class HierarchicalSummaryService {
     if (this.context.generateSummary && !this.context.useFallback) {
       // Use LLM for high-quality summary
       const prompt = this.buildSymbolSummaryPrompt(symbol, code);
-      const response = await this.context.generateSummary(prompt, options?.maxTokens || 150);
+      const response = await this.cachedSummary(
+        gitBlobHash(code), SYMBOL_SUMMARY_PROMPT_VERSION, prompt, options?.maxTokens || 150
+      );
       const parsed = this.parseSymbolSummaryResponse(response);
       summary = parsed.summary;
       keywords = parsed.keywords;
@@ -160,7 +171,9 @@ export class HierarchicalSummaryService {
     if (this.context.generateSummary && !this.context.useFallback) {
       // Use LLM for high-quality summary
       const prompt = this.buildFileSummaryPrompt(parsedFile, symbolTexts);
-      const response = await this.context.generateSummary(prompt, options?.maxTokens || 200);
+      const response = await this.cachedSummary(
+        gitBlobHash(parsedFile.content), FILE_SUMMARY_PROMPT_VERSION, prompt, options?.maxTokens || 200
+      );
       const parsed = this.parseFileSummaryResponse(response);
       summary = parsed.summary;
       keywords = parsed.keywords;
@@ -511,6 +524,27 @@ export class HierarchicalSummaryService {
     await this.vector.upsertSummaryBatch(items);
   }
 
+  /**
+   * Ask the LLM for a summary unless one is cached for this content and prompt
+   */
+  private async cachedSummary(
+    blobHash: string,
+    promptVersion: string,
+    prompt: string,
+    maxTokens: number
+  ): Promise<string> {
+    const cache = this.context.cache;
+    const cached = cache ? await cache.get('summaries', blobHash, promptVersion) : null;
+    if (cached !== null) return cached;
+
+    const response = await this.context.generateSummary!(prompt, maxTokens);
+    // Empty responses mean the LLM budget ran out; retry next time
+    if (cache && response.trim()) {
+      await cache.set('summaries', blobHash, promptVersion, response);
+    }
+    return response;
+  }
+
   // ========== Prompt Building Methods ==========
 
   private buildSymbolSummaryPrompt(symbol: SymbolNode, code: string): string {
@@ -13,6 +13,18 @@ export {
   CacheOptions
 } from './cache-service.js';
 
+export {
+  SummaryCache,
+  createSummaryCache,
+  gitBlobHash,
+  gitBlobHashFile,
+  combineBlobHashes,
+  SUMMARY_CACHE_KINDS,
+  SummaryCacheKind,
+  SummaryCacheEntry,
+  SummaryCacheStats
+} from './summary-cache.js';
+
 export {
   RLMRouter,
   createRLMRouter,
@@ -75,7 +87,9 @@ export {
 export {
   HierarchicalSummaryService,
   createHierarchicalSummaryService,
-  SummaryContext
+  SummaryContext,
+  SYMBOL_SUMMARY_PROMPT_VERSION,
+  FILE_SUMMARY_PROMPT_VERSION
 } from './hierarchical-summary.js';
 
 export {
@@ -13,6 +13,7 @@ import Anthropic from '@anthropic-ai/sdk';
 import { VectorManager } from '../vector/index.js';
 import { GraphManager } from '../graph/index.js';
 import { GitManager } from '../git/index.js';
+import { limitedFetch } from '../ai/http.js';
 import { SymbolNode } from '@cv-git/shared';
 import { loadCodebaseSummary, CodebaseSummary } from './codebase-summary.js';
 import { GraphService, createGraphService } from './graph-service.js';
@@ -147,7 +148,7 @@ export class RLMRouter {
     private graph?: GraphManager,
     private git?: GitManager
   ) {
-    this.client = new Anthropic({ apiKey: options.apiKey });
+    this.client = new Anthropic({ apiKey: options.apiKey, fetch: limitedFetch('anthropic'), maxRetries: 0 });
     this.model = options.model || 'claude-sonnet-4-5-20250514';
     this.maxDepth = options.maxDepth || 5;
     this.maxTokens = options.maxTokens || 4096;
@@ -0,0 +1,215 @@
+/**
+ * Summary Cache
+ *
+ * Persists LLM-generated summaries and explanations keyed by the git blob
+ * hash of their input and the version of the prompt template that produced
+ * them. A changed file gets a new blob hash and a changed prompt gets a new
+ * version, so stale entries are never returned and nothing else is evicted.
+ *
+ * Storage structure:
+ * .cv/
+ * └── cache/
+ *     ├── summaries.json      # File and symbol summaries
+ *     └── explanations.json   # cv explain answers
+ */
+
+import { createHash } from 'crypto';
+import { createReadStream, promises as fs } from 'fs';
+import path from 'path';
+
+export type SummaryCacheKind = 'summaries' | 'explanations';
+
+export const SUMMARY_CACHE_KINDS: SummaryCacheKind[] = ['summaries', 'explanations'];
+
+export interface SummaryCacheEntry {
+  value: string;
+  blobHash: string;
+  promptVersion: string;
+  model?: string;
+  createdAt: string;
+  hits: number;
+}
+
+interface SummaryCacheFile {
+  version: string;
+  entries: Record<string, SummaryCacheEntry>;
+}
+
+export interface SummaryCacheStats {
+  kind: SummaryCacheKind;
+  entries: number;
+  sizeBytes: number;
+  /** Lookups served from cache, across all runs */
+  hits: number;
+  /** Entry counts per prompt-template version */
+  promptVersions: Record<string, number>;
+}
+
+const CACHE_FILE_VERSION = '1';
+
+/**
+ * Git blob hash of some content (what `git hash-object` prints)
+ */
+export function gitBlobHash(content: string | Buffer): string {
+  const data = typeof content === 'string' ? Buffer.from(content, 'utf8') : content;
+  return createHash('sha1')
+    .update(`blob ${data.length}\0`)
+    .update(data)
+    .digest('hex');
+}
+
+/**
+ * Git blob hash of a file, streamed so large files are not loaded whole
+ */
+export async function gitBlobHashFile(filePath: string): Promise<string> {
+  const { size } = await fs.stat(filePath);
+  const hash = createHash('sha1').update(`blob ${size}\0`);
+  for await (const chunk of createReadStream(filePath)) {
+    hash.update(chunk as Buffer);
+  }
+  return hash.digest('hex');
+}
+
+/**
+ * Single key for an output derived from several blobs (e.g. an explanation
+ * built from many retrieved chunks); order-independent
+ */
+export function combineBlobHashes(hashes: string[], salt: string = ''): string {
+  return createHash('sha1')
+    .update(salt)
+    .update('\0')
+    .update([...hashes].sort().join('\n'))
+    .digest('hex');
+}
+
+export class SummaryCache {
+  private files = new Map<SummaryCacheKind, SummaryCacheFile>();
+
+  constructor(private cacheDir: string) {}
+
+  private filePath(kind: SummaryCacheKind): string {
+    return path.join(this.cacheDir, `${kind}.json`);
+  }
+
+  private key(blobHash: string, promptVersion: string): string {
+    return `${blobHash}:${promptVersion}`;
+  }
+
+  private async load(kind: SummaryCacheKind): Promise<SummaryCacheFile> {
+    const cached = this.files.get(kind);
+    if (cached) return cached;
+
+    let file: SummaryCacheFile = { version: CACHE_FILE_VERSION, entries: {} };
+    try {
+      const parsed = JSON.parse(await fs.readFile(this.filePath(kind), 'utf8'));
+      if (parsed.version === CACHE_FILE_VERSION && parsed.entries) {
+        file = parsed;
+      }
+    } catch {
+      // Missing or corrupt cache starts empty
+    }
+    this.files.set(kind, file);
+    return file;
+  }
+
+  private async save(kind: SummaryCacheKind): Promise<void> {
+    const file = this.files.get(kind);
+    if (!file) return;
+    await fs.mkdir(this.cacheDir, { recursive: true });
+    await fs.writeFile(this.filePath(kind), JSON.stringify(file, null, 2));
+  }
+
+  /**
+   * Cached value for this content and prompt version, or null
+   */
+  async get(kind: SummaryCacheKind, blobHash: string, promptVersion: string): Promise<string | null> {
+    const file = await this.load(kind);
+    const entry = file.entries[this.key(blobHash, promptVersion)];
+    if (!entry) return null;
+    entry.hits++;
+    await this.save(kind);
+    return entry.value;
+  }
+
+  async set(
+    kind: SummaryCacheKind,
+    blobHash: string,
+    promptVersion: string,
+    value: string,
+    model?: string
+  ): Promise<void> {
+    const file = await this.load(kind);
+    file.entries[this.key(blobHash, promptVersion)] = {
+      value,
+      blobHash,
+      promptVersion,
+      model,
+      createdAt: new Date().toISOString(),
+      hits: 0
+    };
+    await this.save(kind);
+  }
+
+  /**
+   * Return the cached value or generate, store and return a new one
+   */
+  async getOrCreate(
+    kind: SummaryCacheKind,
+    blobHash: string,
+    promptVersion: string,
+    generate: () => Promise<string>,
+    model?: string
+  ): Promise<string> {
+    const cached = await this.get(kind, blobHash, promptVersion);
+    if (cached !== null) return cached;
+    const value = await generate();
+    await this.set(kind, blobHash, promptVersion, value, model);
+    return value;
+  }
+
+  async stats(): Promise<SummaryCacheStats[]> {
+    const result: SummaryCacheStats[] = [];
+    for (const kind of SUMMARY_CACHE_KINDS) {
+      const file = await this.load(kind);
+      const entries = Object.values(file.entries);
+      const promptVersions: Record<string, number> = {};
+      for (const entry of entries) {
+        promptVersions[entry.promptVersion] = (promptVersions[entry.promptVersion] || 0) + 1;
+      }
+      let sizeBytes = 0;
+      try {
+        sizeBytes = (await fs.stat(this.filePath(kind))).size;
+      } catch {
+        // Not written yet
+      }
+      result.push({
+        kind,
+        entries: entries.length,
+        sizeBytes,
+        hits: entries.reduce((sum, e) => sum + e.hits, 0),
+        promptVersions
+      });
+    }
+    return result;
+  }
+
+  /**
+   * Remove cached entries of one kind (or all kinds); returns the number removed
+   */
+  async clear(kind?: SummaryCacheKind): Promise<number> {
+    let removed = 0;
+    for (const k of kind ? [kind] : SUMMARY_CACHE_KINDS) {
+      removed += Object.keys((await this.load(k)).entries).length;
+      this.files.set(k, { version: CACHE_FILE_VERSION, entries: {} });
+      await fs.rm(this.filePath(k), { force: true });
+    }
+    return removed;
+  }
+}
+
+/**
+ * Summary cache for a repository (.cv/cache)
+ */
+export function createSummaryCache(repoRoot: string): SummaryCache {
+  return new SummaryCache(path.join(repoRoot, '.cv', 'cache'));
+}
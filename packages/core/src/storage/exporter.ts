@@ -40,6 +40,7 @@ import {
   VectorCollection
 } from './vector-storage.js';
 import { getRepositoryInfo } from './repo-id.js';
+import { computeFingerprint } from './fingerprint.js';
 
 export interface ExportResult {
   manifest: StorageManifest;
@@ -117,6 +118,7 @@ export async function exportToStorage(
     syncDuration: (Date.now() - startTime) / 1000
   });
 
+  manifest.fingerprint = computeFingerprint(repoRoot);
   manifest = addNodeTypes(manifest, ['file', 'symbol']);
   manifest = addEdgeTypes(manifest, ['imports', 'calls', 'contains']);
 
@@ -186,6 +188,7 @@ async function exportSymbolNodes(graph: GraphManager): Promise<SymbolNode[]> {
            s.startLine as line,
            s.endLine as endLine,
            s.complexity as complexity,
+           s.cognitiveComplexity as cognitiveComplexity,
            s.docstring as docstring,
            s.signature as signature,
            s.exported as exported
@@ -204,6 +207,7 @@ async function exportSymbolNodes(graph: GraphManager): Promise<SymbolNode[]> {
       line: row.line || 0,
       endLine: row.endLine,
       complexity: row.complexity,
+      cognitiveComplexity: row.cognitiveComplexity,
       docstring: row.docstring,
       signature: row.signature,
       exported: row.exported
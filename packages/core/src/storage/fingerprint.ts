@@ -0,0 +1,133 @@
+/**
+ * Index Fingerprints
+ *
+ * A shared .cv/ index records which repository and commit it was built from
+ * (remote URL, root commit, indexed HEAD) alongside its embedding model.
+ * Before loading one, we check it against the local checkout so an index
+ * from another repository or embedding model is refused instead of being
+ * silently mixed in, and report how far behind the local HEAD it is.
+ */
+
+import { execFileSync } from 'child_process';
+import { CVError } from '../errors.js';
+import { IndexFingerprint, StorageManifest } from './types.js';
+
+export interface IndexCompatibility {
+  /** False when loading would mix incompatible data */
+  compatible: boolean;
+  /** Reasons the index is incompatible */
+  problems: string[];
+  /** Non-fatal observations (e.g. the indexed commit is not available locally) */
+  warnings: string[];
+  /** Commits on local HEAD since the indexed commit, when both are known */
+  commitsBehind?: number;
+}
+
+export class IndexCompatibilityError extends CVError {
+  constructor(public readonly compatibility: IndexCompatibility) {
+    super(
+      `Shared index does not match this repository: ${compatibility.problems.join('; ')}`,
+      'INDEX_INCOMPATIBLE',
+      { problems: compatibility.problems }
+    );
+    this.name = 'IndexCompatibilityError';
+  }
+}
+
+function git(repoRoot: string, args: string[]): string | undefined {
+  try {
+    return execFileSync('git', args, { cwd: repoRoot, encoding: 'utf-8', stdio: ['pipe', 'pipe', 'pipe'] }).trim();
+  } catch {
+    return undefined;
+  }
+}
+
+/**
+ * Reduce a remote URL to host/path so ssh, https and .git variants compare equal
+ */
+export function normalizeRemoteUrl(url: string): string {
+  let normalized = url.trim();
+  const scp = normalized.match(/^[\w.-]+@([^:/]+):(.+)$/);
+  if (scp) {
+    normalized = `${scp[1]}/${scp[2]}`;
+  } else {
+    normalized = normalized.replace(/^[a-z+]+:\/\//i, '').replace(/^[^@/]+@/, '');
+  }
+  normalized = normalized.replace(/\/+$/, '').replace(/\.git$/, '');
+  const slash = normalized.indexOf('/');
+  return slash === -1
+    ? normalized.toLowerCase()
+    : normalized.slice(0, slash).toLowerCase().replace(/:\d+$/, '') + normalized.slice(slash);
+}
+
+/** Strip a provider prefix (openai/text-embedding-3-small -> text-embedding-3-small) */
+function bareModel(model: string): string {
+  return model.includes('/') ? model.slice(model.lastIndexOf('/') + 1) : model;
+}
+
+/**
+ * Fingerprint of the local checkout
+ */
+export function computeFingerprint(repoRoot: string): IndexFingerprint {
+  const remote = git(repoRoot, ['remote', 'get-url', 'origin']);
+  // Repositories with merged histories can have several roots; the oldest sorted one is stable
+  const roots = git(repoRoot, ['rev-list', '--max-parents=0', 'HEAD']);
+  return {
+    remote: remote ? normalizeRemoteUrl(remote) : undefined,
+    rootCommit: roots ? roots.split('\n').sort()[0] : undefined,
+    commit: git(repoRoot, ['rev-parse', 'HEAD'])
+  };
+}
+
+/**
+ * Compare a shared index's manifest against the local repository and embedding setup
+ */
+export function checkIndexCompatibility(
+  repoRoot: string,
+  manifest: StorageManifest,
+  local: { embeddingModel?: string; embeddingDimensions?: number } = {}
+): IndexCompatibility {
+  const result: IndexCompatibility = { compatible: true, problems: [], warnings: [] };
+  const here = computeFingerprint(repoRoot);
+  const theirs = manifest.fingerprint;
+
+  if (!theirs) {
+    result.warnings.push('index has no fingerprint (built by an older cv-git); repository match not verified');
+  } else {
+    if (theirs.rootCommit && here.rootCommit && theirs.rootCommit !== here.rootCommit) {
+      result.problems.push(
+        `built from a different repository (root commit ${theirs.rootCommit.slice(0, 8)}, local ${here.rootCommit.slice(0, 8)})`
+      );
+    }
+    if (theirs.remote && here.remote && theirs.remote !== here.remote) {
+      result.problems.push(`built for remote ${theirs.remote}, local origin is ${here.remote}`);
+    }
+
+    if (theirs.commit && here.commit && theirs.commit !== here.commit) {
+      if (git(repoRoot, ['cat-file', '-e', `${theirs.commit}^{commit}`]) === undefined) {
+        result.warnings.push(`indexed commit ${theirs.commit.slice(0, 8)} is not in local history; try git fetch`);
+      } else {
+        const behind = git(repoRoot, ['rev-list', '--count', `${theirs.commit}..HEAD`]);
+        result.commitsBehind = behind ? parseInt(behind, 10) : undefined;
+      }
+    } else if (theirs.commit && theirs.commit === here.commit) {
+      result.commitsBehind = 0;
+    }
+  }
+
+  if (local.embeddingModel && manifest.embedding?.model &&
+      bareModel(local.embeddingModel) !== bareModel(manifest.embedding.model)) {
+    result.problems.push(
+      `embedded with ${manifest.embedding.model}, local model is ${local.embeddingModel}`
+    );
+  }
+  if (local.embeddingDimensions && manifest.embedding?.dimensions &&
+      local.embeddingDimensions !== manifest.embedding.dimensions) {
+    result.problems.push(
+      `vectors have ${manifest.embedding.dimensions} dimensions, local collections expect ${local.embeddingDimensions}`
+    );
+  }
+
+  result.compatible = result.problems.length === 0;
+  return result;
+}
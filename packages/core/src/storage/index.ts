@@ -10,6 +10,7 @@ export * from './manifest.js';
 export * from './graph-storage.js';
 export * from './vector-storage.js';
 export * from './repo-id.js';
+export * from './fingerprint.js';
 export * from './exporter.js';
 export * from './loader.js';
 export * from './authored.js';
@@ -35,6 +35,11 @@ import {
   getGraphDatabaseName,
   getVectorCollectionName
 } from './repo-id.js';
+import {
+  checkIndexCompatibility,
+  IndexCompatibility,
+  IndexCompatibilityError
+} from './fingerprint.js';
 
 export interface LoadResult {
   manifest: StorageManifest;
@@ -46,6 +51,8 @@ export interface LoadResult {
     vectors: number;
   };
   duration: number;
+  /** How the loaded index compares to the local checkout */
+  compatibility: IndexCompatibility;
 }
 
 export interface LoadOptions {
@@ -55,6 +62,29 @@ export interface LoadOptions {
   skipVectors?: boolean;
   /** Use repo-specific database/collections */
   isolateByRepo?: boolean;
+  /** Load even if the index was built for another repository or embedding model */
+  force?: boolean;
+}
+
+/**
+ * Refuse (unless forced) to load an index that does not match this checkout.
+ * Embedding compatibility is only checked when vectors will be loaded.
+ */
+function verifyCompatibility(
+  repoRoot: string,
+  manifest: StorageManifest,
+  vector: VectorManager | undefined,
+  options: LoadOptions
+): IndexCompatibility {
+  const embedding = vector && !options.skipVectors ? vector.getEmbeddingInfo() : undefined;
+  const compatibility = checkIndexCompatibility(repoRoot, manifest, {
+    embeddingModel: embedding?.model,
+    embeddingDimensions: embedding?.dimensions
+  });
+  if (!compatibility.compatible && !options.force) {
+    throw new IndexCompatibilityError(compatibility);
+  }
+  return compatibility;
 }
 
 /**
@@ -98,6 +128,7 @@ export async function loadFromStorage(
   if (!manifest) {
     throw new Error(`No manifest found in ${cvDir}. Run 'cv sync' first.`);
   }
+  const compatibility = verifyCompatibility(repoRoot, manifest, vector, options);
 
   const repoId = manifest.repository.id;
   const stats = {
@@ -148,6 +179,7 @@ export async function loadFromStorage(
           s.startLine = $line,
           s.endLine = $endLine,
           s.complexity = $complexity,
+          s.cognitiveComplexity = $cognitiveComplexity,
           s.docstring = $docstring,
           s.signature = $signature,
           s.exported = $exported,
@@ -160,6 +192,7 @@ export async function loadFromStorage(
       line: node.line,
       endLine: node.endLine || null,
       complexity: node.complexity || 0,
+      cognitiveComplexity: node.cognitiveComplexity || 0,
       docstring: node.docstring || '',
       signature: node.signature || '',
       exported: node.exported || false,
@@ -213,7 +246,7 @@ export async function loadFromStorage(
   const duration = (Date.now() - startTime) / 1000;
   console.log(`Load completed in ${duration.toFixed(2)}s`);
 
-  return { manifest, stats, duration };
+  return { manifest, stats, duration, compatibility };
 }
 
 /**
@@ -275,6 +308,7 @@ export async function loadVectorsOnly(
   if (!manifest) {
     throw new Error(`No manifest found in ${cvDir}`);
   }
+  verifyCompatibility(repoRoot, manifest, vector, { ...options, skipVectors: false });
 
   return loadVectors(cvDir, vector, manifest.repository.id, options.isolateByRepo);
 }
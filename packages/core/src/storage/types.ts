@@ -28,6 +28,17 @@ export interface StorageManifest {
   nodeTypes: NodeType[];
   /** Available edge types in this repo */
   edgeTypes: EdgeType[];
+  /** Repository and commit the index was built from (absent in older manifests) */
+  fingerprint?: IndexFingerprint;
+}
+
+export interface IndexFingerprint {
+  /** Normalized origin URL (host/owner/repo) */
+  remote?: string;
+  /** Root commit; identifies the repository across forks and renamed remotes */
+  rootCommit?: string;
+  /** HEAD when the index was exported */
+  commit?: string;
 }
 
 export interface RepositoryInfo {
@@ -86,6 +97,7 @@ export interface SymbolNode extends BaseNode {
   line: number;
   endLine?: number;
   complexity?: number;
+  cognitiveComplexity?: number;
   docstring?: string;
   signature?: string;
   exported?: boolean;
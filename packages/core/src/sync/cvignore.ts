@@ -0,0 +1,283 @@
+/**
+ * Index Exclusion Rules (.cvignore)
+ *
+ * Decides which tracked files `cv sync` indexes. Vendored dependencies,
+ * build output, generated code and lockfiles are skipped by built-in rules;
+ * a `.cvignore` file at the repository root adds more in gitignore syntax:
+ *
+ *   # Generated clients
+ *   src/api/generated/
+ *   *.pb.ts
+ *   !vendor/our-fork/        re-include a built-in exclusion
+ *
+ * Rules are applied in order (built-ins first) and the last match wins.
+ * Unlike git, `!` can re-include a file inside an excluded directory.
+ */
+
+import * as path from 'path';
+import { promises as fs } from 'fs';
+import { detectLanguage, matchGlob } from '@cv-git/shared';
+import { isBinaryExtension } from './file-utils.js';
+
+export const CVIGNORE_FILE = '.cvignore';
+
+/** Built-in rules, in .cvignore syntax */
+export const BUILTIN_IGNORE_RULES = `
+# Dependencies
+node_modules/
+bower_components/
+jspm_packages/
+vendor/
+third_party/
+venv/
+.venv/
+/env/
+.env/
+site-packages/
+**/lib/python*/
+__pycache__/
+*.egg-info/
+.pytest_cache/
+
+# Build output
+dist/
+/build/
+/out/
+target/
+.build/
+.next/
+.nuxt/
+coverage/
+
+# Version control, caches and editors
+.git/
+.cv/
+.cache/
+.tmp/
+/tmp/
+.idea/
+.vscode/
+
+# Generated code
+*.min.js
+*.min.css
+*.bundle.js
+*.map
+*.generated.*
+*_pb2.py
+*_pb2_grpc.py
+*.pb.go
+
+# Lockfiles (yarn.lock, Cargo.lock, poetry.lock, ...)
+*.lock
+package-lock.json
+npm-shrinkwrap.json
+pnpm-lock.yaml
+bun.lockb
+go.sum
+`;
+
+export interface IgnoreRule {
+  /** The rule as written */
+  pattern: string;
+  /** Re-includes matching files (`!pattern`) */
+  negated: boolean;
+  /** Only matches directories (trailing `/`) */
+  directoryOnly: boolean;
+  regex: RegExp;
+  source: 'default' | 'cvignore';
+  /** 1-based line in .cvignore */
+  line?: number;
+}
+
+export type SkipReason = 'default' | 'cvignore' | 'exclude-pattern' | 'binary' | 'language';
+
+export interface SkippedFile {
+  file: string;
+  reason: SkipReason;
+  /** Rule, pattern, extension or language that excluded the file */
+  detail: string;
+  /** Line of the deciding .cvignore rule */
+  line?: number;
+}
+
+export interface FileSelection {
+  files: string[];
+  skipped: SkippedFile[];
+}
+
+export interface FileSelectionOptions {
+  /** Configured sync.excludePatterns (anchored globs) */
+  excludePatterns?: string[];
+  /** Languages to index; empty means any supported language */
+  includeLanguages?: string[];
+}
+
+/**
+ * Parse rules in gitignore syntax
+ */
+export function parseIgnoreRules(content: string, source: IgnoreRule['source'] = 'cvignore'): IgnoreRule[] {
+  const rules: IgnoreRule[] = [];
+
+  content.split(/\r?\n/).forEach((raw, index) => {
+    // Trailing spaces are ignored unless escaped
+    let text = raw.replace(/(?<!\\)\s+$/, '');
+    if (!text || text.startsWith('#')) return;
+
+    let negated = false;
+    if (text.startsWith('!')) {
+      negated = true;
+      text = text.slice(1);
+    } else if (text.startsWith('\\!') || text.startsWith('\\#')) {
+      text = text.slice(1);
+    }
+
+    const directoryOnly = text.endsWith('/');
+    if (directoryOnly) text = text.slice(0, -1);
+    // A slash anywhere but the end anchors the rule to the repository root
+    const anchored = text.includes('/');
+    if (text.startsWith('/')) text = text.slice(1);
+    if (!text) return;
+
+    rules.push({
+      pattern: raw.trim(),
+      negated,
+      directoryOnly,
+      regex: new RegExp(`${anchored ? '^' : '(?:^|/)'}${globToRegex(text)}$`),
+      source,
+      line: source === 'cvignore' ? index + 1 : undefined,
+    });
+  });
+
+  return rules;
+}
+
+function globToRegex(glob: string): string {
+  let out = '';
+  for (let i = 0; i < glob.length; i++) {
+    const ch = glob[i];
+    if (ch === '*' && glob[i + 1] === '*') {
+      const atStart = i === 0 || glob[i - 1] === '/';
+      if (atStart && glob[i + 2] === '/') {
+        // `**/` matches zero or more directories
+        out += '(?:.*/)?';
+        i += 2;
+      } else {
+        out += '.*';
+        i += 1;
+      }
+    } else if (ch === '*') {
+      out += '[^/]*';
+    } else if (ch === '?') {
+      out += '[^/]';
+    } else if (ch === '[') {
+      const close = glob.indexOf(']', i + 2);
+      if (close === -1) {
+        out += '\\[';
+      } else {
+        const body = glob.slice(i + 1, close).replace(/^!/, '^').replace(/\\/g, '\\\\');
+        out += `[${body}]`;
+        i = close;
+      }
+    } else if (ch === '\\' && i + 1 < glob.length) {
+      out += escapeRegex(glob[++i]);
+    } else {
+      out += escapeRegex(ch);
+    }
+  }
+  return out;
+}
+
+function escapeRegex(ch: string): string {
+  return /[.+^${}()|[\]\\/]/.test(ch) ? `\\${ch}` : ch;
+}
+
+/**
+ * Built-in and .cvignore rules for one repository
+ */
+export class CvIgnore {
+  constructor(readonly rules: IgnoreRule[] = parseIgnoreRules(BUILTIN_IGNORE_RULES, 'default')) {}
+
+  /**
+   * The rule excluding `file`, or null when it is indexed
+   */
+  match(file: string): IgnoreRule | null {
+    const normalized = file.replace(/\\/g, '/');
+    const parts = normalized.split('/');
+    // The file's directories, then the file itself
+    const candidates = parts.map((_, i) => ({
+      path: parts.slice(0, i + 1).join('/'),
+      isDirectory: i < parts.length - 1,
+    }));
+
+    let decided: IgnoreRule | null = null;
+    for (const rule of this.rules) {
+      const hit = candidates.some(c => (c.isDirectory || !rule.directoryOnly) && rule.regex.test(c.path));
+      if (hit) decided = rule;
+    }
+    return decided && !decided.negated ? decided : null;
+  }
+
+  ignores(file: string): boolean {
+    return this.match(file) !== null;
+  }
+}
+
+/**
+ * Built-in rules followed by the repository's .cvignore, if any
+ */
+export async function loadCvIgnore(repoRoot: string): Promise<CvIgnore> {
+  const rules = parseIgnoreRules(BUILTIN_IGNORE_RULES, 'default');
+  try {
+    const content = await fs.readFile(path.join(repoRoot, CVIGNORE_FILE), 'utf-8');
+    rules.push(...parseIgnoreRules(content, 'cvignore'));
+  } catch (error: any) {
+    if (error.code !== 'ENOENT') throw error;
+  }
+  return new CvIgnore(rules);
+}
+
+/**
+ * Split files into those to index and those skipped, with the reason
+ */
+export function selectSyncFiles(files: string[], ignore: CvIgnore, options: FileSelectionOptions = {}): FileSelection {
+  const excludePatterns = options.excludePatterns ?? [];
+  const includeLanguages = options.includeLanguages ?? [];
+  const selection: FileSelection = { files: [], skipped: [] };
+
+  for (const file of files) {
+    const skip = (reason: SkipReason, detail: string, line?: number) =>
+      selection.skipped.push({ file, reason, detail, ...(line !== undefined && { line }) });
+
+    const rule = ignore.match(file);
+    if (rule) {
+      skip(rule.source, rule.pattern, rule.line);
+      continue;
+    }
+
+    const pattern = excludePatterns.find(p => matchGlob(file, p));
+    if (pattern) {
+      skip('exclude-pattern', pattern);
+      continue;
+    }
+
+    if (isBinaryExtension(file)) {
+      skip('binary', path.extname(file).toLowerCase());
+      continue;
+    }
+
+    const language = detectLanguage(file);
+    if (language === 'unknown') {
+      skip('language', path.extname(file).toLowerCase() || path.basename(file));
+      continue;
+    }
+    if (includeLanguages.length > 0 && !includeLanguages.includes(language)) {
+      skip('language', language);
+      continue;
+    }
+
+    selection.files.push(file);
+  }
+
+  return selection;
+}
@@ -12,6 +12,7 @@ import * as path from 'path';
 import { createHash } from 'crypto';
 import { getCVDir } from '@cv-git/shared';
 import { acquireLock, LockHandle } from './file-lock.js';
+import type { SharedSyncState } from '../vector/index-server.js';
 
 /**
  * Tracked file entry
@@ -59,6 +60,8 @@ interface DeltaState {
   version: string;
   lastSyncedAt: string;
   lastCommit: string;
+  /** Files that differed from lastCommit when it was recorded (uncommitted edits) */
+  dirtyFiles?: string[];
   files: Record<string, TrackedFile>;
   /** Progress for chunked syncing (large repos) */
   chunkedProgress?: ChunkedSyncProgress;
@@ -162,10 +165,13 @@ export class DeltaSyncManager {
    *
    * @param currentFiles - Map of file paths to their content
    * @param fileType - Type of files being synced
+   * @param unread - Paths that exist but were not read because git reports
+   *   them unchanged; tracked ones count as unchanged, untracked as added
    */
   async computeDelta(
     currentFiles: Map<string, string>,
-    fileType: 'code' | 'document' = 'code'
+    fileType: 'code' | 'document' = 'code',
+    unread: string[] = []
   ): Promise<SyncDelta> {
     await this.load();
 
@@ -176,7 +182,16 @@ export class DeltaSyncManager {
       unchanged: []
     };
 
-    const currentPaths = new Set(currentFiles.keys());
+    const currentPaths = new Set([...currentFiles.keys(), ...unread]);
+
+    for (const filePath of unread) {
+      if (currentFiles.has(filePath)) continue;
+      if (this.state!.files[filePath]) {
+        delta.unchanged.push(filePath);
+      } else {
+        delta.added.push(filePath);
+      }
+    }
 
     // Check each current file
     for (const [filePath, content] of currentFiles) {
@@ -252,12 +267,22 @@ export class DeltaSyncManager {
   /**
    * Update last commit that was synced
    */
-  async setLastCommit(commit: string): Promise<void> {
+  async setLastCommit(commit: string, dirtyFiles: string[] = []): Promise<void> {
     await this.load();
     this.state!.lastCommit = commit;
+    this.state!.dirtyFiles = dirtyFiles;
     this.dirty = true;
   }
 
+  /**
+   * Files that had uncommitted edits when the last commit was recorded;
+   * null for state written before these were tracked
+   */
+  async getDirtyFiles(): Promise<string[] | null> {
+    await this.load();
+    return this.state!.dirtyFiles ?? null;
+  }
+
   /**
    * Get last synced commit
    */
@@ -340,6 +365,23 @@ export class DeltaSyncManager {
     return false;
   }
 
+  /**
+   * Take over the tracked files of a shared index, so the next delta sync
+   * only processes files that differ from what it indexed
+   */
+  async adopt(shared: SharedSyncState): Promise<void> {
+    await this.load();
+    const now = new Date().toISOString();
+    this.state = {
+      version: '1.0',
+      lastSyncedAt: now,
+      lastCommit: shared.commit,
+      dirtyFiles: shared.dirtyFiles,
+      files: Object.fromEntries(shared.files.map(file => [file.path, { ...file, lastSyncedAt: now }]))
+    };
+    this.dirty = true;
+  }
+
   /**
    * Reset tracking state (force full sync)
    */
@@ -432,6 +474,25 @@ export class DeltaSyncManager {
   }
 }
 
+/**
+ * The last sync's commit and tracked files, read without taking the lock
+ * (for `cv index serve`); null before the first sync
+ */
+export async function readSharedSyncState(repoRoot: string): Promise<SharedSyncState | null> {
+  let state: DeltaState;
+  try {
+    state = JSON.parse(await fs.readFile(path.join(getCVDir(repoRoot), 'delta_state.json'), 'utf-8')) as DeltaState;
+  } catch {
+    return null;
+  }
+  if (!state.lastCommit) return null;
+  return {
+    commit: state.lastCommit,
+    dirtyFiles: state.dirtyFiles ?? [],
+    files: Object.values(state.files).map(f => ({ path: f.path, contentHash: f.contentHash, size: f.size, type: f.type }))
+  };
+}
+
 /**
  * Create a delta sync manager instance
  */
@@ -0,0 +1,91 @@
+/**
+ * Index Drift Detection
+ * Compares files behind retrieved chunks with the versions that were indexed,
+ * so AI answers can flag (or refresh) context built from stale code
+ */
+
+import { promises as fs } from 'fs';
+import * as path from 'path';
+import { Context } from '@cv-git/shared';
+import { GitManager } from '../git/index.js';
+import { GraphManager } from '../graph/index.js';
+
+export type DriftReason = 'modified' | 'deleted';
+
+export interface DriftedFile {
+  file: string;
+  reason: DriftReason;
+  /** When the file's chunks were indexed */
+  indexedAt: number;
+  /** Current working tree mtime (absent for deleted files) */
+  modifiedAt?: number;
+}
+
+export interface DriftCheckOptions {
+  /** Used to confirm modifications by blob hash instead of mtime alone */
+  git?: GitManager;
+  /** Used to look up indexed hashes for files not already in the context */
+  graph?: GraphManager;
+}
+
+/**
+ * Check the files referenced by a context against the index.
+ * A newer mtime alone is not enough when a hash is available: files that were
+ * touched but not changed (checkout, formatter no-op) are not reported.
+ */
+export async function detectIndexDrift(
+  repoRoot: string,
+  context: Pick<Context, 'chunks' | 'files'>,
+  options: DriftCheckOptions = {}
+): Promise<DriftedFile[]> {
+  // Oldest indexing time per file wins: any chunk may be stale
+  const indexedAt = new Map<string, number>();
+  for (const chunk of context.chunks) {
+    // Other workspace repositories are checked by their own syncs
+    if (chunk.payload.repo) continue;
+    const { file, lastModified } = chunk.payload;
+    const previous = indexedAt.get(file);
+    if (previous === undefined || lastModified < previous) {
+      indexedAt.set(file, lastModified);
+    }
+  }
+
+  const indexedHashes = new Map<string, string>();
+  for (const file of context.files) {
+    if (file.gitHash) indexedHashes.set(file.path, file.gitHash);
+  }
+
+  const drifted: DriftedFile[] = [];
+
+  for (const [file, indexedTime] of indexedAt) {
+    let mtime: number;
+    try {
+      mtime = (await fs.stat(path.join(repoRoot, file))).mtimeMs;
+    } catch {
+      drifted.push({ file, reason: 'deleted', indexedAt: indexedTime });
+      continue;
+    }
+
+    if (mtime <= indexedTime) continue;
+
+    if (options.git) {
+      let indexedHash = indexedHashes.get(file);
+      if (!indexedHash && options.graph) {
+        try {
+          indexedHash = (await options.graph.getFileNode(file))?.gitHash || undefined;
+        } catch {
+          // Graph unavailable - fall back to mtime
+        }
+      }
+
+      if (indexedHash) {
+        const currentHash = await options.git.getWorkingTreeHash(file);
+        if (currentHash && currentHash === indexedHash) continue;
+      }
+    }
+
+    drifted.push({ file, reason: 'modified', indexedAt: indexedTime, modifiedAt: mtime });
+  }
+
+  return drifted;
+}
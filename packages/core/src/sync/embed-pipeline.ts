@@ -0,0 +1,167 @@
+/**
+ * Embedding Pipeline
+ *
+ * `cv sync` embeds chunks as a three-stage pipeline joined by bounded
+ * channels:
+ *
+ *   chunks ──batch──▶ [batches] ──embed × concurrency──▶ [embedded] ──store──▶
+ *
+ * Chunks are grouped into batches up to the provider's request limit, up to
+ * `concurrency` batches are embedded at once, and a single writer stores
+ * them as they arrive. Each channel holds a few batches, so a slow stage
+ * holds the ones before it back instead of buffering the whole repository,
+ * and storing overlaps with embedding. Requests still go through the
+ * provider's rate limiter (see ai/http.ts), which caps them further.
+ */
+
+export interface EmbedPipelineSettings {
+  /** Chunks per embedding request (default: the provider's limit) */
+  batchSize?: number;
+  /** Batches embedded at once (default: the provider's concurrency limit) */
+  concurrency?: number;
+  onProgress?: (progress: EmbedProgress) => void;
+}
+
+export interface EmbedProgress {
+  /** Chunks stored so far */
+  done: number;
+  total: number;
+  elapsedMs: number;
+  /** Chunks stored per second */
+  rate: number;
+  /** Estimated time left; undefined until the first batch is stored */
+  etaMs?: number;
+}
+
+export interface EmbedPipelineOptions<T> {
+  batchSize: number;
+  concurrency: number;
+  /** Number of items the source yields, for progress */
+  total: number;
+  embed: (items: T[]) => Promise<number[][]>;
+  store: (items: T[], vectors: number[][]) => Promise<void>;
+  onProgress?: (progress: EmbedProgress) => void;
+  /** For tests */
+  now?: () => number;
+}
+
+/**
+ * Queue with a fixed capacity: send() waits while it is full, and receiving
+ * ends once it is closed and drained
+ */
+export class BoundedChannel<T> {
+  private items: T[] = [];
+  private closed = false;
+  private receivers: Array<(result: IteratorResult<T>) => void> = [];
+  private senders: Array<() => void> = [];
+
+  constructor(readonly capacity: number) {}
+
+  async send(item: T): Promise<void> {
+    while (this.items.length >= this.capacity && !this.closed) {
+      await new Promise<void>(resolve => this.senders.push(resolve));
+    }
+    if (this.closed) return;
+    const receiver = this.receivers.shift();
+    if (receiver) receiver({ value: item, done: false });
+    else this.items.push(item);
+  }
+
+  /** The next item, or undefined once closed and empty */
+  async receive(): Promise<T | undefined> {
+    if (this.items.length > 0) {
+      const item = this.items.shift()!;
+      this.senders.shift()?.();
+      return item;
+    }
+    if (this.closed) return undefined;
+    const result = await new Promise<IteratorResult<T>>(resolve => this.receivers.push(resolve));
+    return result.done ? undefined : result.value;
+  }
+
+  /** No more sends; pending receives finish with what is left */
+  close(): void {
+    this.closed = true;
+    for (const receiver of this.receivers.splice(0)) receiver({ value: undefined, done: true });
+    for (const sender of this.senders.splice(0)) sender();
+  }
+
+  async *[Symbol.asyncIterator](): AsyncIterator<T> {
+    for (let item = await this.receive(); item !== undefined; item = await this.receive()) {
+      yield item;
+    }
+  }
+}
+
+/**
+ * Embed and store every item of `source`; returns the number stored.
+ * The first error stops every stage and is rethrown.
+ */
+export async function runEmbedPipeline<T>(
+  source: Iterable<T> | AsyncIterable<T>,
+  options: EmbedPipelineOptions<T>
+): Promise<number> {
+  const batchSize = Math.max(1, options.batchSize);
+  const concurrency = Math.max(1, options.concurrency);
+  const now = options.now ?? Date.now;
+  const started = now();
+  const batches = new BoundedChannel<T[]>(concurrency * 2);
+  const embedded = new BoundedChannel<{ items: T[]; vectors: number[][] }>(concurrency);
+  let failure: unknown;
+
+  const fail = (error: unknown) => {
+    failure ??= error;
+    batches.close();
+    embedded.close();
+  };
+
+  const produce = async () => {
+    let batch: T[] = [];
+    for await (const item of source) {
+      if (failure) return;
+      batch.push(item);
+      if (batch.length >= batchSize) {
+        await batches.send(batch);
+        batch = [];
+      }
+    }
+    if (batch.length > 0) await batches.send(batch);
+  };
+
+  const embed = async () => {
+    for await (const items of batches) {
+      if (failure) return;
+      const vectors = await options.embed(items);
+      if (vectors.length !== items.length) {
+        throw new Error(`Expected ${items.length} embeddings, got ${vectors.length}`);
+      }
+      await embedded.send({ items, vectors });
+    }
+  };
+
+  let done = 0;
+  const store = async () => {
+    for await (const { items, vectors } of embedded) {
+      if (failure) return;
+      await options.store(items, vectors);
+      done += items.length;
+      const elapsedMs = now() - started;
+      const rate = elapsedMs > 0 ? (done / elapsedMs) * 1000 : 0;
+      options.onProgress?.({
+        done,
+        total: options.total,
+        elapsedMs,
+        rate,
+        etaMs: rate > 0 ? (Math.max(0, options.total - done) / rate) * 1000 : undefined
+      });
+    }
+  };
+
+  const producer = produce().then(() => batches.close(), fail);
+  const embedders = Promise.all(Array.from({ length: concurrency }, () => embed().catch(fail)))
+    .then(() => embedded.close());
+  await Promise.all([producer, embedders, store().catch(fail)]);
+
+  if (failure) throw failure;
+  return done;
+}
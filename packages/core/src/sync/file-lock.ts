@@ -27,6 +27,16 @@ export interface LockHandle {
   lockPath: string;
 }
 
+/**
+ * On Windows a lock file that is being deleted, or held open by a virus
+ * scanner or indexer, fails with these codes instead of EEXIST/ENOENT
+ */
+const WINDOWS_TRANSIENT_CODES = new Set(['EPERM', 'EBUSY', 'EACCES']);
+
+function isTransientWindowsError(err: NodeJS.ErrnoException): boolean {
+  return process.platform === 'win32' && !!err.code && WINDOWS_TRANSIENT_CODES.has(err.code);
+}
+
 const DEFAULT_OPTIONS: Required<LockOptions> = {
   timeout: 30000,
   retryInterval: 100,
@@ -76,20 +86,28 @@ export async function acquireLock(
         filePath,
         lockPath,
         release: async () => {
-          try {
-            await fs.unlink(lockPath);
-          } catch (error: unknown) {
-            // Ignore errors when releasing lock (file may already be gone)
-            const err = error as NodeJS.ErrnoException;
-            if (err.code !== 'ENOENT') {
-              console.warn(`Warning: Failed to release lock ${lockPath}: ${err.message}`);
+          for (let attempt = 0; ; attempt++) {
+            try {
+              await fs.unlink(lockPath);
+              return;
+            } catch (error: unknown) {
+              const err = error as NodeJS.ErrnoException;
+              if (isTransientWindowsError(err) && attempt < 5) {
+                await sleep(opts.retryInterval);
+                continue;
+              }
+              // Ignore errors when releasing lock (file may already be gone)
+              if (err.code !== 'ENOENT') {
+                console.warn(`Warning: Failed to release lock ${lockPath}: ${err.message}`);
+              }
+              return;
             }
           }
         },
       };
     } catch (error: unknown) {
       const err = error as NodeJS.ErrnoException;
-      if (err.code !== 'EEXIST') {
+      if (err.code !== 'EEXIST' && !isTransientWindowsError(err)) {
         // Unexpected error
         throw new Error(`Failed to create lock file: ${err.message}`);
       }
@@ -98,17 +116,20 @@ export async function acquireLock(
       try {
         const stat = await fs.stat(lockPath);
         const lockAge = Date.now() - stat.mtimeMs;
+        const ownerGone = await isLockOwnerGone(lockPath);
 
-        if (lockAge > opts.staleTimeout) {
+        if (lockAge > opts.staleTimeout || ownerGone) {
           // Lock is stale - try to remove it
-          console.warn(`Removing stale lock file (${Math.round(lockAge / 1000)}s old): ${lockPath}`);
+          console.warn(ownerGone
+            ? `Removing lock file left by exited process: ${lockPath}`
+            : `Removing stale lock file (${Math.round(lockAge / 1000)}s old): ${lockPath}`);
           try {
             await fs.unlink(lockPath);
             // Continue to retry acquiring the lock
           } catch (unlinkError: unknown) {
             // Another process may have removed it or acquired it
             const unlinkErr = unlinkError as NodeJS.ErrnoException;
-            if (unlinkErr.code !== 'ENOENT') {
+            if (unlinkErr.code !== 'ENOENT' && !isTransientWindowsError(unlinkErr)) {
               console.warn(`Failed to remove stale lock: ${unlinkErr.message}`);
             }
           }
@@ -119,8 +140,10 @@ export async function acquireLock(
           // Lock was removed by another process, retry
           continue;
         }
-        // Unexpected stat error
-        console.warn(`Failed to stat lock file: ${statErr.message}`);
+        if (!isTransientWindowsError(statErr)) {
+          // Unexpected stat error (transient Windows errors just wait and retry)
+          console.warn(`Failed to stat lock file: ${statErr.message}`);
+        }
       }
 
       // Check timeout
@@ -186,6 +209,31 @@ export async function getLockInfo(filePath: string): Promise<LockContent | null>
   }
 }
 
+/**
+ * True when the lock was written by a process on this host that no longer exists
+ */
+async function isLockOwnerGone(lockPath: string): Promise<boolean> {
+  let owner: LockContent;
+  try {
+    owner = JSON.parse(await fs.readFile(lockPath, 'utf-8')) as LockContent;
+  } catch {
+    // Unreadable or half-written - let the age check decide
+    return false;
+  }
+
+  if (owner.hostname !== os.hostname() || owner.pid === process.pid) {
+    return false;
+  }
+
+  try {
+    // Signal 0 probes for existence on every platform, including Windows
+    process.kill(owner.pid, 0);
+    return false;
+  } catch (error: unknown) {
+    return (error as NodeJS.ErrnoException).code === 'ESRCH';
+  }
+}
+
 function sleep(ms: number): Promise<void> {
   return new Promise(resolve => setTimeout(resolve, ms));
 }
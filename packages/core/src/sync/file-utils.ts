@@ -7,6 +7,7 @@
 
 import { promises as fs } from 'fs';
 import * as path from 'path';
+import { toLongPath } from '@cv-git/shared';
 
 /**
  * Default maximum file size in bytes (1MB)
@@ -127,10 +128,13 @@ export async function checkFileReadable(
     };
   }
 
+  // Long-path prefix lets deep node_modules-style trees resolve on Windows
+  const fsPath = toLongPath(filePath);
+
   // Check file stats
   let stats;
   try {
-    stats = await fs.stat(filePath);
+    stats = await fs.stat(fsPath);
   } catch (error: unknown) {
     const err = error as NodeJS.ErrnoException;
     return {
@@ -161,7 +165,7 @@ export async function checkFileReadable(
   if (stats.size > 0) {
     try {
       // Read just the beginning to check for binary content
-      const fd = await fs.open(filePath, 'r');
+      const fd = await fs.open(fsPath, 'r');
       const buffer = Buffer.alloc(Math.min(8192, stats.size));
       await fd.read(buffer, 0, buffer.length, 0);
       await fd.close();
@@ -208,7 +212,7 @@ export async function safeReadFile(
   }
 
   try {
-    const content = await fs.readFile(filePath, 'utf-8');
+    const content = await fs.readFile(toLongPath(filePath), 'utf-8');
     return { content };
   } catch (error: unknown) {
     const err = error as NodeJS.ErrnoException;
@@ -0,0 +1,54 @@
+/**
+ * Sync History
+ * Every sync report is appended to .cv/sync-history.jsonl so index size,
+ * churn and embedding spend can be compared across runs (`cv sync history`).
+ */
+
+import * as fs from 'fs/promises';
+import * as path from 'path';
+import { getCVDir } from '@cv-git/shared';
+import type { SyncReport } from './index.js';
+
+/** Reports kept; older lines are dropped on append */
+export const SYNC_HISTORY_LIMIT = 500;
+
+export function getSyncHistoryPath(repoRoot: string): string {
+  return path.join(getCVDir(repoRoot), 'sync-history.jsonl');
+}
+
+/**
+ * Append a report, keeping the newest SYNC_HISTORY_LIMIT entries
+ */
+export async function appendSyncHistory(repoRoot: string, report: SyncReport): Promise<void> {
+  const historyPath = getSyncHistoryPath(repoRoot);
+  await fs.appendFile(historyPath, JSON.stringify(report) + '\n');
+
+  const lines = (await fs.readFile(historyPath, 'utf-8')).split('\n').filter(Boolean);
+  if (lines.length > SYNC_HISTORY_LIMIT) {
+    await fs.writeFile(historyPath, lines.slice(-SYNC_HISTORY_LIMIT).join('\n') + '\n');
+  }
+}
+
+/**
+ * Past sync reports, newest first
+ */
+export async function loadSyncHistory(repoRoot: string, limit?: number): Promise<SyncReport[]> {
+  let content: string;
+  try {
+    content = await fs.readFile(getSyncHistoryPath(repoRoot), 'utf-8');
+  } catch {
+    return [];
+  }
+
+  const reports: SyncReport[] = [];
+  for (const line of content.split('\n')) {
+    if (!line.trim()) continue;
+    try {
+      reports.push(JSON.parse(line));
+    } catch {
+      // Skip a line truncated by an interrupted write
+    }
+  }
+  reports.reverse();
+  return limit !== undefined ? reports.slice(0, limit) : reports;
+}
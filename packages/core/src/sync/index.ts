@@ -18,14 +18,17 @@ import {
   HierarchicalSummaryOptions
 } from '@cv-git/shared';
 import { HierarchicalSummaryService, createHierarchicalSummaryService, CostControlOptions, DeltaSummaryResult } from '../services/hierarchical-summary.js';
-import { shouldSyncFile, detectLanguage, getCVDir } from '@cv-git/shared';
+import { SummaryCache, createSummaryCache } from '../services/summary-cache.js';
+import { detectLanguage, getCVDir } from '@cv-git/shared';
 import { minimatch } from 'minimatch';
 import { GitManager } from '../git/index.js';
-import { CodeParser } from '../parser/index.js';
+import { CodeParser, getDefaultIndexedLanguages } from '../parser/index.js';
 import { GraphManager } from '../graph/index.js';
-import { VectorManager } from '../vector/index.js';
+import { VectorManager, EmbeddingUsage } from '../vector/index.js';
+import { RemoteVectorStore } from '../vector/stores/remote-store.js';
 import { DeltaSyncManager, createDeltaSyncManager, SyncDelta } from './delta.js';
 import { ManifoldService } from '../services/manifold-service.js';
+import { NativeIndexer, findNativeIndexer } from './native-index.js';
 import * as fs from 'fs/promises';
 import * as path from 'path';
 
@@ -33,8 +36,29 @@ import * as path from 'path';
 export * from './delta.js';
 export * from './file-lock.js';
 export * from './file-utils.js';
+export * from './oversized.js';
+export * from './drift.js';
+export * from './history.js';
+export * from './native-index.js';
+export * from './cvignore.js';
+export * from './packages.js';
+export * from './embed-pipeline.js';
 
 import { safeReadFile, logSkippedFile } from './file-utils.js';
+import { FileSelection, loadCvIgnore, selectSyncFiles } from './cvignore.js';
+import { RepoPackage, detectPackages, loadPackages, packageForFile, savePackages } from './packages.js';
+import { getCurrentNamespace, loadBranchIndex, recordBranchSync } from '../vector/branches.js';
+import { appendSyncHistory } from './history.js';
+import { EmbedPipelineSettings, runEmbedPipeline } from './embed-pipeline.js';
+import { KeywordIndex, loadKeywordIndex, saveKeywordIndex, toKeywordDocument } from '../context/keyword-index.js';
+import { sampleScoreDistribution, saveScoreDistribution } from '../context/score-distribution.js';
+import {
+  OversizedFileOptions,
+  FileSummarizer,
+  classifyOversizedFile,
+  statOversizedCandidate,
+  summarizeOversizedFile
+} from './oversized.js';
 
 export interface SyncOptions {
   incremental?: boolean;
@@ -80,9 +104,6 @@ export interface SyncError {
   timestamp: number;
 }
 
-/**
- * Sync report saved to .cv/sync-report.json
- */
 export interface SummaryStats {
   generated: number;
   skipped: number;
@@ -90,6 +111,12 @@ export interface SummaryStats {
   estimatedCostCents: number;
 }
 
+/** Phases timed in SyncReport.phases */
+export type SyncPhase = 'scan' | 'parse' | 'graph' | 'embed' | 'keywords' | 'summaries' | 'commits';
+
+/**
+ * Sync report saved to .cv/sync-report.json and appended to .cv/sync-history.jsonl
+ */
 export interface SyncReport {
   timestamp: number;
   duration: number;
@@ -102,6 +129,22 @@ export interface SyncReport {
     vectorsCreated: number;
   };
   summaryStats?: SummaryStats;
+  /** Files added, updated and removed from the index */
+  changes?: {
+    added: number;
+    modified: number;
+    deleted: number;
+  };
+  /** Code chunks embedded during this sync */
+  embeddings?: {
+    provider: string;
+    model: string;
+    chunks: number;
+    cached: number;
+    estimatedTokens: number;
+  };
+  /** Milliseconds spent per phase */
+  phases?: Partial<Record<SyncPhase, number>>;
   errors: SyncError[];
   systemInfo?: {
     nodeVersion: string;
@@ -113,6 +156,14 @@ export interface SyncReport {
 export class SyncEngine {
   private delta: DeltaSyncManager;
   private manifold?: ManifoldService;
+  private oversized: OversizedFileOptions;
+  private summaryCache: SummaryCache;
+  private phases: Partial<Record<SyncPhase, number>> = {};
+  private usageAtStart?: EmbeddingUsage;
+  private packages?: RepoPackage[];
+  private embedding: EmbedPipelineSettings = {};
+  private nativeIndexer?: Promise<NativeIndexer | null>;
+  private nativeChunks = new Map<string, CodeChunk[]>();
 
   constructor(
     private repoRoot: string,
@@ -122,6 +173,8 @@ export class SyncEngine {
     private vector?: VectorManager
   ) {
     this.delta = createDeltaSyncManager(repoRoot);
+    this.summaryCache = createSummaryCache(repoRoot);
+    this.oversized = { cache: this.summaryCache };
   }
 
   /**
@@ -131,6 +184,36 @@ export class SyncEngine {
     this.manifold = manifold;
   }
 
+  /**
+   * Configure the summary tier for oversized/generated files.
+   * Pass a summarizer to use an LLM; otherwise a structural summary is extracted.
+   */
+  setOversizedFileOptions(options: OversizedFileOptions): void {
+    this.oversized = { ...this.oversized, ...options };
+  }
+
+  /**
+   * Use `indexer` for chunking instead of looking up cv-index (CV_INDEX_BIN or
+   * PATH); pass null to always chunk with the built-in parsers
+   */
+  setNativeIndexer(indexer: NativeIndexer | null): void {
+    this.nativeIndexer = Promise.resolve(indexer);
+  }
+
+  /**
+   * Set the LLM summarizer used for oversized files
+   */
+  setFileSummarizer(summarizer: FileSummarizer): void {
+    this.oversized.summarizer = summarizer;
+  }
+
+  /**
+   * Set the embedding batch size and parallelism, and follow its progress
+   */
+  setEmbeddingOptions(settings: EmbedPipelineSettings): void {
+    this.embedding = { ...this.embedding, ...settings };
+  }
+
   /**
    * Update manifold dimensions after sync completes
    * Best-effort: failures don't affect sync results
@@ -151,6 +234,182 @@ export class SyncEngine {
     }
   }
 
+  /**
+   * Reset per-run phase timings and embedding counters
+   */
+  private beginRun(): void {
+    this.phases = {};
+    this.usageAtStart = this.vector?.getEmbeddingUsage();
+  }
+
+  /**
+   * Run a step and add its duration to the phase
+   */
+  private async timed<T>(phase: SyncPhase, fn: () => Promise<T>): Promise<T> {
+    const start = Date.now();
+    try {
+      return await fn();
+    } finally {
+      this.recordPhase(phase, start);
+    }
+  }
+
+  private recordPhase(phase: SyncPhase, since: number): void {
+    this.phases[phase] = (this.phases[phase] || 0) + (Date.now() - since);
+  }
+
+  /**
+   * Embedding work done since beginRun()
+   */
+  private embeddingReport(): SyncReport['embeddings'] {
+    if (!this.vector || !this.usageAtStart) return undefined;
+    const usage = this.vector.getEmbeddingUsage();
+    const info = this.vector.getEmbeddingInfo();
+    return {
+      provider: info.provider,
+      model: info.model,
+      chunks: usage.requested - this.usageAtStart.requested,
+      cached: usage.cached - this.usageAtStart.cached,
+      estimatedTokens: usage.estimatedTokens - this.usageAtStart.estimatedTokens
+    };
+  }
+
+  /**
+   * Record HEAD as the last indexed commit, with the files that had
+   * uncommitted edits so the next delta rechecks them even if reverted
+   */
+  private async recordIndexedCommit(): Promise<void> {
+    const head = await this.git.getLastCommitSha();
+    let dirtyFiles: string[] = [];
+    try {
+      dirtyFiles = await this.git.getWorkingTreeChangesSince(head);
+    } catch {
+      // Without the list the next delta hashes every file
+      dirtyFiles = await this.git.getTrackedFiles();
+    }
+    await this.delta.setLastCommit(head, dirtyFiles);
+    await this.recordIndexedBranch(head);
+  }
+
+  /**
+   * Branch the code chunks are being indexed on, when vectors are
+   * namespaced by branch
+   */
+  private branchNamespace(): string | null {
+    if (!this.vector || !this.vector.isConnected() || !this.vector.isBranchScoped()) return null;
+    return getCurrentNamespace(this.repoRoot);
+  }
+
+  /**
+   * Detect monorepo packages among the tracked files and record them for
+   * incremental syncs and package-scoped searches
+   */
+  private async detectPackageBoundaries(files: string[]): Promise<void> {
+    try {
+      this.packages = await detectPackages(this.repoRoot, files);
+      await savePackages(this.repoRoot, this.packages);
+      if (this.packages.length > 0) {
+        console.log(`Found ${this.packages.length} packages`);
+      }
+    } catch (error: any) {
+      console.warn('Package boundaries not detected: ' + error.message);
+      this.packages = [];
+    }
+  }
+
+  /**
+   * Packages chunks are tagged with: those found by the last full sync
+   */
+  private async packageBoundaries(): Promise<RepoPackage[]> {
+    if (!this.packages) this.packages = await loadPackages(this.repoRoot);
+    return this.packages;
+  }
+
+  /**
+   * Mark the checked-out branch as indexed so searches scope to it
+   */
+  private async recordIndexedBranch(head?: string): Promise<void> {
+    const namespace = this.branchNamespace();
+    if (!namespace) return;
+    try {
+      await recordBranchSync(this.repoRoot, namespace, head ?? await this.git.getLastCommitSha());
+    } catch (error: any) {
+      console.warn('Branch index not updated: ' + error.message);
+    }
+  }
+
+  /**
+   * Put the checked-out branch on the vectors of files unchanged since the
+   * last sync, which may have run on another branch
+   */
+  private async tagUnchangedFiles(files: string[]): Promise<void> {
+    const namespace = this.branchNamespace();
+    if (!namespace || files.length === 0) return;
+    try {
+      const { last, branches } = await loadBranchIndex(this.repoRoot);
+      if (last === namespace) return;
+      const tagged = await this.timed('embed', () =>
+        this.vector!.addBranch('code_chunks', files, namespace, last && branches[last] ? last : undefined)
+      );
+      if (tagged > 0) {
+        console.log(`Reused ${tagged} vectors of unchanged files on ${namespace}`);
+      }
+    } catch (error: any) {
+      console.warn('Could not reuse vectors for this branch: ' + error.message);
+    }
+  }
+
+  /**
+   * Files that can differ from the index: changed in git since the last
+   * indexed commit, dirty when it was recorded, or not indexed yet.
+   * Null when that commit is unknown and every file has to be hashed.
+   */
+  private async filesChangedSinceLastIndex(currentFiles: string[]): Promise<Set<string> | null> {
+    const lastCommit = await this.delta.getLastCommit();
+    if (!lastCommit || !(await this.git.commitExists(lastCommit))) return null;
+
+    const dirtyFiles = await this.delta.getDirtyFiles();
+    if (!dirtyFiles) return null;
+
+    let changed: Set<string>;
+    try {
+      changed = new Set([...await this.git.getWorkingTreeChangesSince(lastCommit), ...dirtyFiles]);
+    } catch {
+      return null;
+    }
+
+    const candidates = new Set<string>();
+    for (const file of currentFiles) {
+      if (changed.has(file) || !(await this.delta.getTrackedFile(file))) {
+        candidates.add(file);
+      }
+    }
+    return candidates;
+  }
+
+  /**
+   * With a shared index (config.index.remote), track the files it was synced
+   * from so only files that differ locally are parsed and embedded into the
+   * overlay. When the server has moved to another commit, the overlay starts
+   * over from it.
+   */
+  private async followSharedIndex(): Promise<void> {
+    const shared = this.vector?.isConnected() ? this.vector.getSharedIndex() : null;
+    if (!shared || !(shared.base instanceof RemoteVectorStore)) return;
+
+    const remote = shared.base;
+    const state = await remote.syncState();
+    if (!state) throw new Error(`The shared index at ${remote.url} has not been synced yet`);
+    if (shared.getBaseCommit() === state.commit) return;
+    if (!(await this.git.commitExists(state.commit))) {
+      throw new Error(`The shared index is at commit ${state.commit.slice(0, 8)}, which this clone doesn't have; run git fetch`);
+    }
+
+    await shared.rebase(state.commit);
+    await this.delta.adopt(state);
+    console.log(`Using the shared index at ${remote.url} (commit ${state.commit.slice(0, 8)}); only local changes are embedded`);
+  }
+
   /**
    * Save sync report to .cv/sync-report.json
    * This is used for error tracking and bug reports
@@ -165,6 +424,7 @@ export class SyncEngine {
 
       // Save the report
       await fs.writeFile(reportPath, JSON.stringify(report, null, 2));
+      await appendSyncHistory(this.repoRoot, report);
 
       // If there were errors, also append to a rolling error log
       if (report.errors.length > 0) {
@@ -213,6 +473,7 @@ export class SyncEngine {
   async fullSync(options: SyncOptions = {}): Promise<SyncState> {
     const startTime = Date.now();
     const syncErrors: SyncError[] = [];
+    this.beginRun();
 
     console.log('Starting full sync...');
 
@@ -221,22 +482,17 @@ export class SyncEngine {
       console.log('Getting tracked files...');
       const allFiles = await this.git.getTrackedFiles();
       console.log(`Found ${allFiles.length} tracked files`);
+      await this.detectPackageBoundaries(allFiles);
 
-      // 2. Filter files to sync
-      // Always include critical patterns (merge with any custom patterns)
-      const defaultPatterns = this.getDefaultExcludePatterns();
-      const customPatterns = options.excludePatterns || [];
-      const excludePatterns = [...new Set([...defaultPatterns, ...customPatterns])];
-      const includeLanguages = options.includeLanguages || this.getDefaultIncludeLanguages();
-
-      const filesToSync = allFiles.filter(f =>
-        shouldSyncFile(f, excludePatterns, includeLanguages)
-      );
+      // 2. Filter files to sync (.cvignore, built-in rules, excludePatterns)
+      const { files: filesToSync } = await this.selectFiles(options, allFiles);
 
       console.log(`Syncing ${filesToSync.length} files`);
 
       // 3. Parse all files (with parallelization)
       console.log('Parsing files...');
+      const parseStart = Date.now();
+      await this.indexNatively(filesToSync);
       const parsedFiles: ParsedFile[] = [];
       const CONCURRENCY = 10; // Parse 10 files in parallel
 
@@ -268,24 +524,31 @@ export class SyncEngine {
         }
       }
 
+      this.recordPhase('parse', parseStart);
       console.log(`Successfully parsed ${parsedFiles.length} files`);
 
-      // 4. Update graph
+      // 4. Update graph (re-tagging this branch's vectors from scratch)
       console.log('Updating knowledge graph...');
+      const namespace = this.branchNamespace();
+      if (namespace) {
+        await this.timed('embed', () => this.vector!.removeBranch('code_chunks', null, namespace));
+      }
       await this.updateGraph(parsedFiles);
+      await this.updateKeywordIndex(parsedFiles, [], true);
+      await this.updateScoreDistribution();
 
       // 5. Sync commit history (if enabled)
       const syncCommits = options.syncCommits !== false; // default: true
       if (syncCommits) {
         console.log('Syncing commit history...');
         const commitDepth = options.commitDepth || 50;
-        await this.syncCommitHistory(commitDepth);
+        await this.timed('commits', () => this.syncCommitHistory(commitDepth));
       }
 
       // 6. Generate hierarchical summaries (if enabled)
       if (options.generateSummaries && this.vector && this.vector.isConnected()) {
         console.log('Generating hierarchical summaries...');
-        await this.generateHierarchicalSummaries(parsedFiles, options.summaryOptions);
+        await this.timed('summaries', () => this.generateHierarchicalSummaries(parsedFiles, options.summaryOptions));
       }
 
       // 7. Collect statistics
@@ -318,6 +581,7 @@ export class SyncEngine {
 
       // 8. Save sync state
       await this.saveSyncState(syncState);
+      await this.recordIndexedBranch();
 
       // 9. Save sync report for error tracking
       const syncReport: SyncReport = {
@@ -331,6 +595,9 @@ export class SyncEngine {
           symbolsCreated: stats.symbolCount,
           vectorsCreated: vectorCount
         },
+        changes: { added: parsedFiles.length, modified: 0, deleted: 0 },
+        embeddings: this.embeddingReport(),
+        phases: this.phases,
         errors: syncErrors,
         systemInfo: {
           nodeVersion: process.version,
@@ -373,19 +640,12 @@ export class SyncEngine {
 
     try {
       // Filter files to sync
-      // Always include critical patterns (merge with any custom patterns)
-      const defaultPatterns = this.getDefaultExcludePatterns();
-      const customPatterns = options.excludePatterns || [];
-      const excludePatterns = [...new Set([...defaultPatterns, ...customPatterns])];
-      const includeLanguages = options.includeLanguages || this.getDefaultIncludeLanguages();
-
-      const filesToSync = changedFiles.filter(f =>
-        shouldSyncFile(f, excludePatterns, includeLanguages)
-      );
+      const { files: filesToSync } = await this.selectFiles(options, changedFiles);
 
       console.log(`Syncing ${filesToSync.length} files`);
 
       // Parse changed files
+      await this.indexNatively(filesToSync);
       const parsedFiles: ParsedFile[] = [];
 
       for (const file of filesToSync) {
@@ -400,6 +660,8 @@ export class SyncEngine {
 
       // Update graph (will merge/upsert nodes)
       await this.updateGraph(parsedFiles);
+      await this.updateKeywordIndex(parsedFiles);
+      await this.updateScoreDistribution();
 
       // Get updated statistics
       const stats = await this.graph.getStats();
@@ -454,10 +716,13 @@ export class SyncEngine {
   async deltaSync(options: SyncOptions = {}): Promise<SyncState & { delta: SyncDelta }> {
     const startTime = Date.now();
     const syncErrors: SyncError[] = [];
+    this.beginRun();
 
     console.log('Starting delta sync...');
 
     try {
+      await this.followSharedIndex();
+
       // Check if full sync is needed
       const needsFull = await this.delta.needsFullSync();
       if (needsFull) {
@@ -465,15 +730,7 @@ export class SyncEngine {
         const fullResult = await this.fullSync(options);
 
         // Track all files for next delta
-        const allFiles = await this.git.getTrackedFiles();
-        const defaultPatterns = this.getDefaultExcludePatterns();
-        const customPatterns = options.excludePatterns || [];
-        const excludePatterns = [...new Set([...defaultPatterns, ...customPatterns])];
-        const includeLanguages = options.includeLanguages || this.getDefaultIncludeLanguages();
-
-        const filesToTrack = allFiles.filter(f =>
-          shouldSyncFile(f, excludePatterns, includeLanguages)
-        );
+        const { files: filesToTrack } = await this.selectFiles(options);
 
         // Read content and mark as synced (using safe file reading with size limits)
         const fileContents = new Map<string, string>();
@@ -488,7 +745,7 @@ export class SyncEngine {
         }
 
         await this.delta.markSynced(fileContents, 'code');
-        await this.delta.setLastCommit(await this.git.getLastCommitSha());
+        await this.recordIndexedCommit();
         await this.delta.close();
 
         return {
@@ -503,19 +760,20 @@ export class SyncEngine {
       }
 
       // Get all current files
-      const allFiles = await this.git.getTrackedFiles();
-      const defaultPatterns = this.getDefaultExcludePatterns();
-      const customPatterns = options.excludePatterns || [];
-      const excludePatterns = [...new Set([...defaultPatterns, ...customPatterns])];
-      const includeLanguages = options.includeLanguages || this.getDefaultIncludeLanguages();
-
-      const currentFiles = allFiles.filter(f =>
-        shouldSyncFile(f, excludePatterns, includeLanguages)
-      );
+      const scanStart = Date.now();
+      const { files: currentFiles } = await this.selectFiles(options);
+
+      // Only files git reports as changed since the last indexed commit need hashing
+      const candidates = await this.filesChangedSinceLastIndex(currentFiles);
+      const filesToRead = candidates ? currentFiles.filter(f => candidates.has(f)) : currentFiles;
+      const unread = candidates ? currentFiles.filter(f => !candidates.has(f)) : [];
+      if (candidates) {
+        console.log(`${filesToRead.length} of ${currentFiles.length} files changed since last indexed commit`);
+      }
 
-      // Read current file contents (using safe file reading with size limits)
+      // Read changed file contents (using safe file reading with size limits)
       const fileContents = new Map<string, string>();
-      for (const file of currentFiles) {
+      for (const file of filesToRead) {
         const absolutePath = path.join(this.repoRoot, file);
         const result = await safeReadFile(absolutePath);
         if ('content' in result) {
@@ -526,10 +784,13 @@ export class SyncEngine {
       }
 
       // Compute delta
-      const delta = await this.delta.computeDelta(fileContents, 'code');
+      const delta = await this.delta.computeDelta(fileContents, 'code', unread);
+      this.recordPhase('scan', scanStart);
 
       console.log(`Delta: ${delta.added.length} added, ${delta.modified.length} modified, ${delta.deleted.length} deleted, ${delta.unchanged.length} unchanged`);
 
+      await this.tagUnchangedFiles(delta.unchanged);
+
       // If nothing changed in files, still sync commit history
       if (delta.added.length === 0 && delta.modified.length === 0 && delta.deleted.length === 0) {
         console.log('No file changes detected');
@@ -539,9 +800,12 @@ export class SyncEngine {
         if (syncCommits) {
           console.log('Syncing commit history...');
           const commitDepth = options.commitDepth || 50;
-          await this.syncCommitHistory(commitDepth);
+          await this.timed('commits', () => this.syncCommitHistory(commitDepth));
         }
 
+        await this.recordIndexedCommit();
+        await this.delta.close();
+
         const stats = await this.graph.getStats();
         const prevState = await this.loadSyncState();
 
@@ -555,6 +819,24 @@ export class SyncEngine {
           }
         }
 
+        const duration = (Date.now() - startTime) / 1000;
+        await this.saveSyncReport({
+          timestamp: Date.now(),
+          duration,
+          type: 'delta',
+          success: true,
+          stats: {
+            filesProcessed: 0,
+            filesFailed: 0,
+            symbolsCreated: stats.symbolCount,
+            vectorsCreated: vectorCount
+          },
+          changes: { added: 0, modified: 0, deleted: 0 },
+          embeddings: this.embeddingReport(),
+          phases: this.phases,
+          errors: []
+        });
+
         return {
           lastFullSync: prevState?.lastFullSync || Date.now(),
           lastIncrementalSync: Date.now(),
@@ -565,7 +847,7 @@ export class SyncEngine {
           edgeCount: stats.relationshipCount,
           vectorCount,
           languages: prevState?.languages || {},
-          syncDuration: (Date.now() - startTime) / 1000,
+          syncDuration: duration,
           errors: [],
           delta
         };
@@ -576,6 +858,8 @@ export class SyncEngine {
       console.log(`Processing ${changedFiles.length} changed files...`);
 
       // Parse changed files
+      const parseStart = Date.now();
+      await this.indexNatively(changedFiles);
       const parsedFiles: ParsedFile[] = [];
       for (const file of changedFiles) {
         try {
@@ -591,6 +875,16 @@ export class SyncEngine {
         }
       }
 
+      this.recordPhase('parse', parseStart);
+
+      // Chunk IDs follow line ranges, so old chunks of changed files would linger.
+      // On a branch, an added file may still hold this branch's chunks from an earlier visit.
+      await this.pruneFileVectors([
+        ...(this.branchNamespace() ? delta.added : []),
+        ...delta.modified,
+        ...delta.deleted
+      ]);
+
       // Update graph with changed files
       if (parsedFiles.length > 0) {
         await this.updateGraph(parsedFiles);
@@ -601,7 +895,9 @@ export class SyncEngine {
       const generateSummaries = options.generateSummaries !== false;
       if (generateSummaries && parsedFiles.length > 0 && this.vector && this.vector.isConnected()) {
         console.log('Generating summaries for changed files...');
-        const summaryResult = await this.generateDeltaSummaries(parsedFiles, options.summaryCostControl);
+        const summaryResult = await this.timed('summaries', () =>
+          this.generateDeltaSummaries(parsedFiles, options.summaryCostControl)
+        );
         summaryStats = {
           generated: summaryResult.generated,
           skipped: summaryResult.skipped,
@@ -618,12 +914,13 @@ export class SyncEngine {
       if (syncCommits) {
         console.log('Syncing commit history...');
         const commitDepth = options.commitDepth || 50;
-        await this.syncCommitHistory(commitDepth);
+        await this.timed('commits', () => this.syncCommitHistory(commitDepth));
       }
 
       // Handle deleted files
       if (delta.deleted.length > 0) {
         console.log(`Removing ${delta.deleted.length} deleted files from graph...`);
+        const deleteStart = Date.now();
         for (const file of delta.deleted) {
           try {
             await this.graph.deleteFileNode(file);
@@ -637,10 +934,15 @@ export class SyncEngine {
           }
         }
 
+        this.recordPhase('graph', deleteStart);
+
         // Remove from delta tracking
         await this.delta.markDeleted(delta.deleted);
       }
 
+      await this.updateKeywordIndex(parsedFiles, delta.deleted);
+      await this.updateScoreDistribution();
+
       // Update delta tracking for synced files
       const syncedContents = new Map<string, string>();
       for (const file of changedFiles) {
@@ -649,7 +951,7 @@ export class SyncEngine {
         }
       }
       await this.delta.markSynced(syncedContents, 'code');
-      await this.delta.setLastCommit(await this.git.getLastCommitSha());
+      await this.recordIndexedCommit();
       await this.delta.close();
 
       // Get updated statistics
@@ -696,6 +998,13 @@ export class SyncEngine {
           vectorsCreated: vectorCount
         },
         summaryStats,
+        changes: {
+          added: delta.added.length,
+          modified: delta.modified.length,
+          deleted: delta.deleted.length
+        },
+        embeddings: this.embeddingReport(),
+        phases: this.phases,
         errors: syncErrors,
         systemInfo: {
           nodeVersion: process.version,
@@ -760,7 +1069,10 @@ export class SyncEngine {
         '**/CHANGELOG.md'
       ];
 
-      const docFiles = allFiles.filter(f => this.matchesDocPattern(f, docPatterns, excludePatterns));
+      const ignore = await loadCvIgnore(this.repoRoot);
+      const docFiles = allFiles.filter(f =>
+        !ignore.ignores(f) && this.matchesDocPattern(f, docPatterns, excludePatterns)
+      );
 
       // Read current file contents (using safe file reading with size limits)
       const fileContents = new Map<string, string>();
@@ -919,15 +1231,7 @@ export class SyncEngine {
 
     try {
       // Get all tracked files
-      const allFiles = await this.git.getTrackedFiles();
-      const defaultPatterns = this.getDefaultExcludePatterns();
-      const customPatterns = options.excludePatterns || [];
-      const excludePatterns = [...new Set([...defaultPatterns, ...customPatterns])];
-      const includeLanguages = options.includeLanguages || this.getDefaultIncludeLanguages();
-
-      const filesToSync = allFiles.filter(f =>
-        shouldSyncFile(f, excludePatterns, includeLanguages)
-      );
+      const { files: filesToSync } = await this.selectFiles(options);
 
       // Check for existing progress
       let progress = await this.delta.getChunkedProgress();
@@ -961,6 +1265,7 @@ export class SyncEngine {
       console.log(`Processing files ${startIndex + 1}-${endIndex} of ${filesToSync.length}`);
 
       // Parse files in this chunk
+      await this.indexNatively(chunkFiles);
       const parsedFiles: ParsedFile[] = [];
       const CONCURRENCY = 10;
 
@@ -1002,10 +1307,12 @@ export class SyncEngine {
         console.log('Updating knowledge graph...');
         await this.updateGraph(parsedFiles);
       }
+      await this.updateKeywordIndex(parsedFiles, [], startIndex === 0);
 
       // Check if complete
       const isComplete = endIndex >= filesToSync.length;
       if (isComplete) {
+        await this.updateScoreDistribution();
         await this.delta.completeChunkedSync();
         console.log('Chunked sync complete!');
 
@@ -1013,7 +1320,7 @@ export class SyncEngine {
         if (options.syncCommits !== false) {
           console.log('Syncing commit history...');
           const commitDepth = options.commitDepth || 50;
-          await this.syncCommitHistory(commitDepth);
+          await this.timed('commits', () => this.syncCommitHistory(commitDepth));
         }
       }
 
@@ -1043,7 +1350,7 @@ export class SyncEngine {
         }
       }
       await this.delta.markSynced(fileContents, 'code');
-      await this.delta.setLastCommit(await this.git.getLastCommitSha());
+      await this.recordIndexedCommit();
       await this.delta.close();
 
       const syncState: SyncState = {
@@ -1095,7 +1402,7 @@ export class SyncEngine {
       const summaryService = createHierarchicalSummaryService(
         this.vector,
         this.graph,
-        { useFallback: costControl?.costStrategy !== 'quality' }
+        { useFallback: costControl?.costStrategy !== 'quality', cache: this.summaryCache }
       );
 
       return await summaryService.generateDeltaSummaries(parsedFiles, costControl);
@@ -1109,23 +1416,59 @@ export class SyncEngine {
    */
   private async parseFile(filePath: string): Promise<ParsedFile> {
     const absolutePath = path.join(this.repoRoot, filePath);
+    const language = detectLanguage(filePath);
+
+    // Files too large to chunk usefully are indexed as a single summary chunk
+    const size = await statOversizedCandidate(absolutePath);
+    if (size !== null && classifyOversizedFile(size, null, this.oversized) === 'too-large') {
+      return summarizeOversizedFile(filePath, absolutePath, language, 'too-large', this.oversized);
+    }
+
     const result = await safeReadFile(absolutePath);
 
     if ('error' in result) {
       throw new Error(result.error);
     }
 
-    const language = detectLanguage(filePath);
+    if (classifyOversizedFile(size ?? result.content.length, result.content, this.oversized) === 'generated') {
+      return summarizeOversizedFile(filePath, absolutePath, language, 'generated', this.oversized);
+    }
+
     const parsed = await this.parser.parseFile(filePath, result.content, language);
     // Ensure absolutePath is correctly set (parser may not know the repo root)
     parsed.absolutePath = absolutePath;
+    // Symbols and imports still come from the parser; only chunking moves to cv-index
+    const native = this.nativeChunks.get(filePath);
+    if (native) {
+      parsed.chunks = native;
+    }
     return parsed;
   }
 
+  /**
+   * Chunk `files` with cv-index ahead of parsing them, when it is available.
+   * Files it can't handle, or any file if it fails to run, keep the parser's chunks.
+   */
+  private async indexNatively(files: string[]): Promise<void> {
+    this.nativeChunks = new Map();
+    this.nativeIndexer ??= findNativeIndexer();
+    const indexer = await this.nativeIndexer;
+    if (!indexer || files.length === 0) {
+      return;
+    }
+    try {
+      this.nativeChunks = await indexer.index(this.repoRoot, files);
+      console.log(`Chunked ${this.nativeChunks.size} files with ${indexer.command}`);
+    } catch (error: any) {
+      console.warn(`${indexer.command} failed, chunking with the built-in parsers: ${error.message}`);
+    }
+  }
+
   /**
    * Update graph with parsed files
    */
   private async updateGraph(parsedFiles: ParsedFile[]): Promise<void> {
+    const graphStart = Date.now();
     console.log('Creating file nodes...');
 
     // Get git hashes for all files in batch (more efficient than per-file)
@@ -1144,7 +1487,7 @@ export class SyncEngine {
         lastModified: stats.mtimeMs,
         size: stats.size,
         gitHash,
-        linesOfCode: file.content.split('\n').length,
+        linesOfCode: file.chunks[0]?.synthetic ? file.chunks[0].endLine : file.content.split('\n').length,
         complexity: file.symbols.reduce((sum, s) => sum + s.complexity, 0),
         createdAt: Date.now(),
         updatedAt: Date.now()
@@ -1199,10 +1542,12 @@ export class SyncEngine {
     // Build symbol index for faster call resolution
     const symbolIndex = new Map<string, string>(); // name -> qualifiedName
     const exportedSymbols = new Map<string, string>(); // name -> qualifiedName (exported only)
+    const uniqueSymbols = new Map<string, string | null>(); // name -> qualifiedName (null when ambiguous)
 
     for (const file of parsedFiles) {
       for (const symbol of file.symbols) {
         symbolIndex.set(`${file.path}:${symbol.name}`, symbol.qualifiedName);
+        uniqueSymbols.set(symbol.name, uniqueSymbols.has(symbol.name) ? null : symbol.qualifiedName);
 
         // Track exported symbols for cross-file resolution
         const isExported = file.exports.some(exp => exp.name === symbol.name);
@@ -1226,7 +1571,8 @@ export class SyncEngine {
               file,
               parsedFiles,
               symbolIndex,
-              exportedSymbols
+              exportedSymbols,
+              uniqueSymbols
             );
 
             if (calleeQualifiedName) {
@@ -1244,13 +1590,34 @@ export class SyncEngine {
       }
     }
 
+    console.log('Creating inheritance relationships...');
+
+    // Step 4b: Create INHERITS edges (extends / implements)
+    for (const file of parsedFiles) {
+      for (const symbol of file.symbols) {
+        for (const base of symbol.inherits ?? []) {
+          try {
+            const baseQualifiedName = this.resolveCallTargetFast(
+              base.name, file, parsedFiles, symbolIndex, exportedSymbols, uniqueSymbols
+            );
+            if (baseQualifiedName && baseQualifiedName !== symbol.qualifiedName) {
+              await this.graph.createInheritsEdge(symbol.qualifiedName, baseQualifiedName, { type: base.type });
+            }
+          } catch (error) {
+            // Base type might not exist, skip
+          }
+        }
+      }
+    }
+
     console.log('Graph update complete');
+    this.recordPhase('graph', graphStart);
 
     // Step 5: Generate and store vector embeddings (if VectorManager available)
     // Also links graph symbols to their vector chunk IDs
     if (this.vector && this.vector.isConnected()) {
       console.log('Generating vector embeddings...');
-      const { vectorCount, symbolToChunkMap } = await this.updateVectorEmbeddings(parsedFiles);
+      const { vectorCount, symbolToChunkMap } = await this.timed('embed', () => this.updateVectorEmbeddings(parsedFiles));
       if (process.env.CV_DEBUG) {
         console.log(`  Embedded ${vectorCount} chunks, linked ${symbolToChunkMap.size} symbols`);
       }
@@ -1265,7 +1632,8 @@ export class SyncEngine {
     currentFile: ParsedFile,
     allFiles: ParsedFile[],
     symbolIndex: Map<string, string>,
-    exportedSymbols: Map<string, string>
+    exportedSymbols: Map<string, string>,
+    uniqueSymbols?: Map<string, string | null>
   ): string | null {
     // Strategy 1: Look for symbol in the same file (O(1) with index)
     const localKey = `${currentFile.path}:${callee}`;
@@ -1293,6 +1661,13 @@ export class SyncEngine {
       return exportedSymbols.get(callee)!;
     }
 
+    // Strategy 4: A name defined only once in the repository (e.g. a method
+    // called on an instance from another file)
+    const unique = uniqueSymbols?.get(callee);
+    if (unique) {
+      return unique;
+    }
+
     // Could not resolve
     return null;
   }
@@ -1301,6 +1676,82 @@ export class SyncEngine {
    * Generate and store vector embeddings for code chunks
    * Also builds symbol→chunk mapping and links graph nodes to vectors
    */
+  /**
+   * Replace the keyword (BM25) entries of parsed and removed files;
+   * `reset` rebuilds the index from these files alone
+   */
+  private async updateKeywordIndex(parsedFiles: ParsedFile[], removedFiles: string[] = [], reset = false): Promise<void> {
+    if (!reset && parsedFiles.length === 0 && removedFiles.length === 0) return;
+    const start = Date.now();
+    try {
+      const index = reset ? new KeywordIndex() : await loadKeywordIndex(this.repoRoot);
+      index.removeFiles([...removedFiles, ...parsedFiles.map(f => f.path)]);
+      index.add(parsedFiles.flatMap(f => (f.chunks || []).map(toKeywordDocument)));
+      await saveKeywordIndex(this.repoRoot, index);
+    } catch (error: any) {
+      console.warn('Keyword index not updated: ' + error.message);
+    }
+    this.recordPhase('keywords', start);
+  }
+
+  /**
+   * Re-sample the similarity distribution that adaptive minScore is based on
+   */
+  private async updateScoreDistribution(): Promise<void> {
+    if (!this.vector || !this.vector.isConnected()) return;
+    try {
+      const distribution = await sampleScoreDistribution(this.vector, 'code_chunks');
+      if (distribution) {
+        await saveScoreDistribution(this.repoRoot, distribution);
+      }
+    } catch (error: any) {
+      console.warn('Score distribution not updated: ' + error.message);
+    }
+  }
+
+  /**
+   * Remove the code chunks of files that changed or no longer exist
+   */
+  private async pruneFileVectors(files: string[]): Promise<void> {
+    if (files.length === 0 || !this.vector || !this.vector.isConnected()) return;
+    try {
+      // With branch namespaces only this branch lets go; other branches keep their chunks
+      const namespace = this.branchNamespace();
+      const removed = await this.timed('embed', () => namespace
+        ? this.vector!.removeBranch('code_chunks', files, namespace)
+        : this.vector!.deleteByFiles('code_chunks', files));
+      if (removed > 0) {
+        console.log(`Removed ${removed} vectors of changed or deleted files`);
+      }
+    } catch (error: any) {
+      console.warn('Could not remove stale vectors: ' + error.message);
+    }
+  }
+
+  /**
+   * Embed chunks in batches, several at a time, handing each batch to
+   * `store` as soon as its vectors arrive
+   */
+  private async embedChunks<T>(
+    chunks: T[],
+    text: (chunk: T) => string,
+    store: (chunks: T[], vectors: number[][]) => Promise<void>
+  ): Promise<number> {
+    const vector = this.vector!;
+    const limit = vector.getEmbeddingBatchSize();
+    const batchSize = Math.min(this.embedding.batchSize ?? limit, limit);
+    const concurrency = this.embedding.concurrency ?? vector.getEmbeddingConcurrency();
+    console.log(`Embedding ${chunks.length} chunks (${batchSize} per request, ${concurrency} at a time)...`);
+    return runEmbedPipeline(chunks, {
+      batchSize,
+      concurrency,
+      total: chunks.length,
+      embed: batch => vector.embedBatch(batch.map(text)),
+      store,
+      onProgress: this.embedding.onProgress
+    });
+  }
+
   private async updateVectorEmbeddings(parsedFiles: ParsedFile[]): Promise<{ vectorCount: number; symbolToChunkMap: Map<string, string[]> }> {
     const symbolToChunkMap = new Map<string, string[]>();
 
@@ -1343,19 +1794,13 @@ export class SyncEngine {
         }
       }
 
-      // Prepare chunks for embedding (add context)
-      const textsToEmbed = allChunks.map(chunk =>
-        this.vector!.prepareCodeForEmbedding(chunk)
-      );
-
-      // Generate embeddings in batch
-      console.log('Generating embeddings...');
-      const embeddings = await this.vector.embedBatch(textsToEmbed);
-
-      // Prepare batch upsert items
-      const items = allChunks.map((chunk, idx) => {
+      // Embed with context added, storing each batch as it is embedded
+      const packages = await this.packageBoundaries();
+      const namespace = this.branchNamespace();
+      const filesByPath = new Map(parsedFiles.map(f => [f.path, f]));
+      const toItem = (chunk: CodeChunk, vector: number[]) => {
         // Find the file this chunk belongs to
-        const file = parsedFiles.find(f => f.path === chunk.file);
+        const file = filesByPath.get(chunk.file);
         const imports = file ? file.imports.map(i => i.source) : [];
 
         const payload: CodeChunkPayload = {
@@ -1366,24 +1811,37 @@ export class SyncEngine {
           symbolKind: chunk.symbolKind,
           startLine: chunk.startLine,
           endLine: chunk.endLine,
+          startByte: chunk.startByte,
+          endByte: chunk.endByte,
           text: chunk.text,
           summary: chunk.summary,
           docstring: chunk.docstring,
           imports,
           complexity: chunk.complexity,
-          lastModified: Date.now()
+          lastModified: Date.now(),
+          package: packageForFile(packages, chunk.file)?.name,
+          ...(chunk.synthetic && {
+            synthetic: true,
+            syntheticReason: chunk.syntheticReason,
+            originalSize: chunk.originalSize
+          })
         };
 
         return {
           id: chunk.id,
-          vector: embeddings[idx],
+          vector,
           payload
         };
-      });
+      };
 
-      // Upsert to Qdrant in batches
-      console.log('Storing embeddings in Qdrant...');
-      await this.vector.upsertBatch('code_chunks', items);
+      await this.embedChunks(allChunks, chunk => this.vector!.prepareCodeForEmbedding(chunk), async (chunks, vectors) => {
+        const items = chunks.map((chunk, idx) => toItem(chunk, vectors[idx]));
+        if (namespace) {
+          await this.vector!.upsertBranchChunks('code_chunks', items, namespace);
+        } else {
+          await this.vector!.upsertBatch('code_chunks', items);
+        }
+      });
 
       // Link graph symbols to vector IDs
       if (symbolToChunkMap.size > 0) {
@@ -1432,7 +1890,10 @@ export class SyncEngine {
         '**/CHANGELOG.md' // Often auto-generated
       ];
 
-      const docFiles = allFiles.filter(f => this.matchesDocPattern(f, docPatterns, excludePatterns));
+      const ignore = await loadCvIgnore(this.repoRoot);
+      const docFiles = allFiles.filter(f =>
+        !ignore.ignores(f) && this.matchesDocPattern(f, docPatterns, excludePatterns)
+      );
 
       console.log(`Found ${docFiles.length} documentation files`);
 
@@ -1650,17 +2111,7 @@ export class SyncEngine {
         // Collection might already exist
       }
 
-      // Prepare chunks for embedding
-      const textsToEmbed = allChunks.map(chunk =>
-        this.prepareDocumentForEmbedding(chunk)
-      );
-
-      // Generate embeddings
-      console.log('Generating document embeddings...');
-      const embeddings = await this.vector.embedBatch(textsToEmbed);
-
-      // Prepare batch upsert items
-      const items = allChunks.map((chunk, idx) => {
+      const toItem = (chunk: DocumentChunk, vector: number[]) => {
         // Find the doc this chunk belongs to
         const doc = parsedDocs.find(d => d.path === chunk.file);
 
@@ -1682,14 +2133,14 @@ export class SyncEngine {
 
         return {
           id: chunk.id,
-          vector: embeddings[idx],
+          vector,
           payload
         };
-      });
+      };
 
-      // Upsert to Qdrant
-      console.log('Storing document embeddings in Qdrant...');
-      await this.vector.upsertBatch('document_chunks', items);
+      await this.embedChunks(allChunks, chunk => this.vector!.prepareDocumentForEmbedding(chunk), (chunks, vectors) =>
+        this.vector!.upsertBatch('document_chunks', chunks.map((chunk, idx) => toItem(chunk, vectors[idx])))
+      );
 
       console.log(`✓ Stored ${allChunks.length} document embeddings`);
       return allChunks.length;
@@ -1700,24 +2151,6 @@ export class SyncEngine {
     }
   }
 
-  /**
-   * Prepare document chunk for embedding
-   */
-  private prepareDocumentForEmbedding(chunk: DocumentChunk): string {
-    const parts: string[] = [];
-    parts.push(`// Document Type: ${chunk.documentType}`);
-    parts.push(`// File: ${chunk.file}`);
-    if (chunk.heading) {
-      parts.push(`// Section: ${chunk.heading}`);
-    }
-    if (chunk.tags.length > 0) {
-      parts.push(`// Tags: ${chunk.tags.join(', ')}`);
-    }
-    parts.push('');
-    parts.push(chunk.text);
-    return parts.join('\n');
-  }
-
   /**
    * Generate hierarchical summaries for parsed files
    * Creates multi-level summaries (symbol, file, directory) for traversal-aware context
@@ -1736,7 +2169,7 @@ export class SyncEngine {
       const summaryService = createHierarchicalSummaryService(
         this.vector,
         this.graph,
-        { useFallback: true } // Use fallback extraction instead of LLM by default
+        { useFallback: true, cache: this.summaryCache } // Use fallback extraction instead of LLM by default
       );
 
       // Generate all summaries bottom-up
@@ -1828,64 +2261,23 @@ export class SyncEngine {
   }
 
   /**
-   * Get default exclude patterns
+   * Get default include languages
    */
-  private getDefaultExcludePatterns(): string[] {
-    return [
-      // JavaScript/Node
-      'node_modules/**',
-      '.next/**',
-      '.nuxt/**',
-      '*.min.js',
-      '*.bundle.js',
-
-      // Python virtualenvs
-      'venv/**',
-      '.venv/**',
-      'env/**',
-      '.env/**',
-      '**/lib/python*/**',        // Matches admin/lib/python3.10/...
-      '**/site-packages/**',
-      '__pycache__/**',
-      '*.pyc',
-      '.pytest_cache/**',
-      '*.egg-info/**',
-
-      // Build outputs
-      'dist/**',
-      'build/**',
-      'out/**',
-      'target/**',
-      '.build/**',
-
-      // Test files
-      '*.test.ts',
-      '*.test.js',
-      '*.spec.ts',
-      '*.spec.js',
-      'coverage/**',
-
-      // Version control & cache
-      '.git/**',
-      '.cache/**',
-      '.tmp/**',
-      'tmp/**',
-
-      // IDE/Editor
-      '.idea/**',
-      '.vscode/**',
-
-      // Vendor directories
-      'vendor/**',
-      'third_party/**',
-    ];
+  private getDefaultIncludeLanguages(): string[] {
+    return getDefaultIndexedLanguages();
   }
 
   /**
-   * Get default include languages
+   * Files to index (tracked files by default) and those skipped by the
+   * built-in rules, .cvignore, sync.excludePatterns or language
    */
-  private getDefaultIncludeLanguages(): string[] {
-    return ['typescript', 'javascript', 'python', 'go', 'rust'];
+  async selectFiles(options: SyncOptions = {}, files?: string[]): Promise<FileSelection> {
+    const candidates = files ?? await this.git.getTrackedFiles();
+    const ignore = await loadCvIgnore(this.repoRoot);
+    return selectSyncFiles(candidates, ignore, {
+      excludePatterns: options.excludePatterns,
+      includeLanguages: options.includeLanguages || this.getDefaultIncludeLanguages()
+    });
   }
 
   /**
@@ -0,0 +1,143 @@
+/**
+ * Native Indexer
+ *
+ * Runs the `cv-index` binary (crates/cv-index) over the files being synced and
+ * reads back the CodeChunks it prints as JSON lines. Sync uses these chunks in
+ * place of the ones from the JavaScript tree-sitter parsers when the binary is
+ * installed, and falls back to the parsers when it is not.
+ *
+ * CV_INDEX_BIN selects the binary (default: `cv-index` on PATH); set it to
+ * `off` to always use the built-in parsers.
+ */
+
+import { spawn } from 'child_process';
+import * as path from 'path';
+import * as readline from 'readline';
+import { CodeChunk } from '@cv-git/shared';
+
+/**
+ * Extensions cv-index understands (see `Language::from_path` in the crate)
+ */
+export const NATIVE_INDEX_EXTENSIONS = new Set(['.rs', '.ts', '.mts', '.cts', '.tsx', '.py', '.pyi', '.go']);
+
+/**
+ * Files passed to a single cv-index run, to stay under command-line limits
+ */
+const FILES_PER_RUN = 256;
+
+/**
+ * How long the `--help` probe may take before the binary is treated as absent
+ */
+const PROBE_TIMEOUT_MS = 5000;
+
+export interface NativeIndexer {
+  /** Command run for each batch of files */
+  command: string;
+  /**
+   * Chunks per file, keyed by the repository-relative path as given.
+   * Files cv-index failed on are left out, so callers keep their own chunks;
+   * a file it indexed without finding any symbols maps to an empty list.
+   */
+  index(repoRoot: string, files: string[]): Promise<Map<string, CodeChunk[]>>;
+}
+
+/**
+ * Whether cv-index can chunk `file`
+ */
+export function isNativelyIndexable(file: string): boolean {
+  return NATIVE_INDEX_EXTENSIONS.has(path.extname(file));
+}
+
+/**
+ * An indexer running `command` with `args` ahead of the file list
+ */
+export function createNativeIndexer(command: string, args: string[] = []): NativeIndexer {
+  return {
+    command,
+    index: async (repoRoot, files) => {
+      const chunks = new Map<string, CodeChunk[]>();
+      const indexable = files.filter(isNativelyIndexable);
+      for (let i = 0; i < indexable.length; i += FILES_PER_RUN) {
+        const batch = indexable.slice(i, i + FILES_PER_RUN);
+        const { chunks: found, ok } = await runIndexer(command, [...args, ...batch], repoRoot);
+        for (const file of batch) {
+          const fileChunks = found.get(file);
+          // Without a clean exit an absent file may have failed rather than
+          // had no symbols; leave it to the fallback parser
+          if (fileChunks || ok) {
+            chunks.set(file, fileChunks ?? []);
+          }
+        }
+      }
+      return chunks;
+    }
+  };
+}
+
+const probes = new Map<string, Promise<boolean>>();
+
+/**
+ * The cv-index binary configured by CV_INDEX_BIN or found on PATH, or null
+ * when it is disabled or cannot be run
+ */
+export async function findNativeIndexer(env: NodeJS.ProcessEnv = process.env): Promise<NativeIndexer | null> {
+  const configured = env.CV_INDEX_BIN?.trim();
+  if (configured === 'off') {
+    return null;
+  }
+  const command = configured || 'cv-index';
+
+  let probe = probes.get(command);
+  if (!probe) {
+    probe = canRun(command);
+    probes.set(command, probe);
+  }
+  return (await probe) ? createNativeIndexer(command) : null;
+}
+
+function canRun(command: string): Promise<boolean> {
+  return new Promise(resolve => {
+    const proc = spawn(command, ['--help'], { stdio: 'ignore' });
+    const timeoutId = setTimeout(() => proc.kill('SIGTERM'), PROBE_TIMEOUT_MS);
+    proc.on('error', () => {
+      clearTimeout(timeoutId);
+      resolve(false);
+    });
+    proc.on('close', code => {
+      clearTimeout(timeoutId);
+      resolve(code === 0);
+    });
+  });
+}
+
+function runIndexer(
+  command: string,
+  args: string[],
+  cwd: string
+): Promise<{ chunks: Map<string, CodeChunk[]>; ok: boolean }> {
+  return new Promise((resolve, reject) => {
+    const chunks = new Map<string, CodeChunk[]>();
+    const proc = spawn(command, args, { cwd, stdio: ['ignore', 'pipe', 'ignore'] });
+
+    const lines = readline.createInterface({ input: proc.stdout! });
+    lines.on('line', line => {
+      if (!line.trim()) return;
+      let chunk: CodeChunk;
+      try {
+        chunk = JSON.parse(line);
+      } catch {
+        return;
+      }
+      const list = chunks.get(chunk.file);
+      if (list) {
+        list.push(chunk);
+      } else {
+        chunks.set(chunk.file, [chunk]);
+      }
+    });
+
+    proc.on('error', reject);
+    // 'close' fires after stdout has ended, so every line has been read
+    proc.on('close', code => resolve({ chunks, ok: code === 0 }));
+  });
+}
@@ -0,0 +1,308 @@
+/**
+ * Oversized File Summarization
+ *
+ * Files that are too large to chunk usefully (generated code, giant configs,
+ * minified bundles) are indexed as a single synthetic summary chunk instead of
+ * being skipped. Retrieval can still point at them without pulling megabytes
+ * of source into the prompt.
+ */
+
+import Anthropic from '@anthropic-ai/sdk';
+import { promises as fs } from 'fs';
+import * as path from 'path';
+import { CodeChunk, ParsedFile, toLongPath } from '@cv-git/shared';
+import { formatBytes, getMaxFileSize, isBinaryContent, isBinaryExtension } from './file-utils.js';
+import { SummaryCache, gitBlobHashFile } from '../services/summary-cache.js';
+import { limitedFetch } from '../ai/http.js';
+
+/**
+ * Files above this size are summarized even if they are under the read limit (256KB)
+ */
+const DEFAULT_SUMMARIZE_THRESHOLD = 256 * 1024;
+
+/**
+ * Files above this size are skipped entirely rather than summarized (20MB)
+ */
+const DEFAULT_SUMMARIZE_HARD_LIMIT = 20 * 1024 * 1024;
+
+/**
+ * Bytes sampled from the start and end of a file for the summary prompt
+ */
+const SAMPLE_BYTES = 6 * 1024;
+
+/**
+ * Bump when buildOversizedSummaryPrompt changes so cached summaries are regenerated
+ */
+export const OVERSIZED_SUMMARY_PROMPT_VERSION = 'oversized-v1';
+
+/**
+ * Markers that identify machine-generated source
+ */
+const GENERATED_MARKERS = [
+  '@generated',
+  'DO NOT EDIT',
+  'Code generated by',
+  'This file was automatically generated',
+  'Autogenerated by',
+  'auto-generated',
+];
+
+/**
+ * Function used to produce the summary text (same shape as SummaryContext.generateSummary)
+ */
+export type FileSummarizer = (prompt: string, maxTokens?: number) => Promise<string>;
+
+export interface OversizedFileOptions {
+  /** Enable the summary tier (default: true) */
+  enabled?: boolean;
+  /** Size in bytes above which files are summarized instead of chunked */
+  summarizeThreshold?: number;
+  /** Size in bytes above which files are skipped entirely */
+  hardLimit?: number;
+  /** LLM summarizer; when absent a structural summary is extracted locally */
+  summarizer?: FileSummarizer;
+  /** Max tokens for the LLM summary (default: 300) */
+  maxTokens?: number;
+  /** Reuse LLM summaries while the file's blob hash is unchanged */
+  cache?: SummaryCache;
+}
+
+/**
+ * Why a file was routed to the summary tier
+ */
+export type OversizedReason = 'too-large' | 'generated';
+
+/**
+ * Get the summarize threshold from environment or default
+ */
+export function getSummarizeThreshold(): number {
+  const envSize = process.env.CV_SUMMARIZE_THRESHOLD;
+  if (envSize) {
+    const parsed = parseInt(envSize, 10);
+    if (!isNaN(parsed) && parsed > 0) {
+      return parsed;
+    }
+  }
+  return DEFAULT_SUMMARIZE_THRESHOLD;
+}
+
+/**
+ * Check whether content looks machine-generated (header markers or minified lines)
+ */
+export function isGeneratedContent(content: string): boolean {
+  const header = content.slice(0, 2048);
+  if (GENERATED_MARKERS.some(marker => header.includes(marker))) {
+    return true;
+  }
+
+  // Minified output: very few lines carrying most of the bytes
+  const sample = content.slice(0, 64 * 1024);
+  const lines = sample.split('\n');
+  const longLines = lines.filter(line => line.length > 1000).length;
+  return lines.length > 0 && longLines / lines.length > 0.5;
+}
+
+/**
+ * Decide whether a file should be summarized instead of chunked.
+ * Returns the reason, or null if the file should go through the normal parser.
+ */
+export function classifyOversizedFile(
+  size: number,
+  content: string | null,
+  options: OversizedFileOptions = {}
+): OversizedReason | null {
+  if (options.enabled === false) return null;
+
+  const threshold = options.summarizeThreshold ?? getSummarizeThreshold();
+  const hardLimit = options.hardLimit ?? DEFAULT_SUMMARIZE_HARD_LIMIT;
+
+  if (size > hardLimit) return null;
+  if (size > getMaxFileSize() || size > threshold) return 'too-large';
+  if (content && content.length > threshold / 4 && isGeneratedContent(content)) return 'generated';
+
+  return null;
+}
+
+/**
+ * Read the head and tail of a file without loading it fully
+ */
+async function readSample(absolutePath: string, size: number): Promise<{ head: string; tail: string }> {
+  const fd = await fs.open(absolutePath, 'r');
+  try {
+    const headBuf = Buffer.alloc(Math.min(SAMPLE_BYTES, size));
+    await fd.read(headBuf, 0, headBuf.length, 0);
+    if (isBinaryContent(headBuf)) {
+      throw new Error('Binary content detected');
+    }
+
+    let tail = '';
+    if (size > SAMPLE_BYTES * 2) {
+      const tailBuf = Buffer.alloc(SAMPLE_BYTES);
+      await fd.read(tailBuf, 0, tailBuf.length, size - SAMPLE_BYTES);
+      tail = tailBuf.toString('utf-8');
+    }
+
+    return { head: headBuf.toString('utf-8'), tail };
+  } finally {
+    await fd.close();
+  }
+}
+
+/**
+ * Count lines in a file by streaming it
+ */
+async function countLines(absolutePath: string): Promise<number> {
+  const handle = await fs.open(absolutePath, 'r');
+  try {
+    let lines = 1;
+    for await (const chunk of handle.createReadStream()) {
+      const buf = chunk as Buffer;
+      for (let i = 0; i < buf.length; i++) {
+        if (buf[i] === 10) lines++;
+      }
+    }
+    return lines;
+  } finally {
+    await handle.close();
+  }
+}
+
+/**
+ * Build the prompt sent to the summarizer
+ */
+export function buildOversizedSummaryPrompt(
+  filePath: string,
+  reason: OversizedReason,
+  size: number,
+  lineCount: number,
+  head: string,
+  tail: string
+): string {
+  let prompt = `Summarize the purpose and structure of this file for a code search index.\n`;
+  prompt += `The file is ${reason === 'generated' ? 'machine-generated' : 'too large to index in full'} `;
+  prompt += `(${formatBytes(size)}, ${lineCount} lines), so only samples are shown.\n\n`;
+  prompt += `File: ${filePath}\n\n`;
+  prompt += `## Beginning\n\`\`\`\n${head}\n\`\`\`\n\n`;
+  if (tail) {
+    prompt += `## End\n\`\`\`\n${tail}\n\`\`\`\n\n`;
+  }
+  prompt += `Describe in 3-6 sentences: what the file contains, what generates or consumes it, `;
+  prompt += `and the main top-level names or keys a developer might search for. No code blocks.`;
+  return prompt;
+}
+
+/**
+ * Structural summary used when no LLM summarizer is configured
+ */
+export function extractStructuralSummary(
+  filePath: string,
+  reason: OversizedReason,
+  size: number,
+  lineCount: number,
+  head: string
+): string {
+  const description = reason === 'generated' ? 'Machine-generated file' : 'Oversized file';
+  const identifiers = new Set<string>();
+  const identifierPattern = /(?:function|class|interface|type|struct|enum|def|fn|const|export)\s+([A-Za-z_][A-Za-z0-9_]*)|^\s*"?([A-Za-z_][A-Za-z0-9_-]*)"?\s*[:=]/gm;
+
+  let match;
+  while ((match = identifierPattern.exec(head)) !== null && identifiers.size < 20) {
+    identifiers.add(match[1] || match[2]);
+  }
+
+  let summary = `${description} ${path.basename(filePath)} (${formatBytes(size)}, ${lineCount} lines), indexed as a summary only.`;
+  if (identifiers.size > 0) {
+    summary += ` Top-level names: ${Array.from(identifiers).join(', ')}.`;
+  }
+  return summary;
+}
+
+/**
+ * Produce a ParsedFile containing a single synthetic summary chunk
+ */
+export async function summarizeOversizedFile(
+  filePath: string,
+  absolutePath: string,
+  language: string,
+  reason: OversizedReason,
+  options: OversizedFileOptions = {}
+): Promise<ParsedFile> {
+  const fsPath = toLongPath(absolutePath);
+  const stats = await fs.stat(fsPath);
+  const [{ head, tail }, lineCount] = await Promise.all([
+    readSample(fsPath, stats.size),
+    countLines(fsPath)
+  ]);
+
+  let summary: string;
+  if (options.summarizer) {
+    try {
+      const summarizer = options.summarizer;
+      const generate = async () => {
+        const prompt = buildOversizedSummaryPrompt(filePath, reason, stats.size, lineCount, head, tail);
+        return (await summarizer(prompt, options.maxTokens ?? 300)).trim();
+      };
+      summary = options.cache
+        ? await options.cache.getOrCreate(
+            'summaries', await gitBlobHashFile(fsPath), OVERSIZED_SUMMARY_PROMPT_VERSION, generate
+          )
+        : await generate();
+    } catch {
+      summary = extractStructuralSummary(filePath, reason, stats.size, lineCount, head);
+    }
+  } else {
+    summary = extractStructuralSummary(filePath, reason, stats.size, lineCount, head);
+  }
+
+  const chunk: CodeChunk = {
+    id: `${filePath}:1:${lineCount}`,
+    file: filePath,
+    language,
+    startLine: 1,
+    endLine: lineCount,
+    text: summary,
+    summary,
+    synthetic: true,
+    syntheticReason: reason,
+    originalSize: stats.size
+  };
+
+  return {
+    path: filePath,
+    absolutePath,
+    language,
+    content: '',
+    symbols: [],
+    imports: [],
+    exports: [],
+    chunks: [chunk]
+  };
+}
+
+/**
+ * Create a summarizer backed by Claude
+ */
+export function createAnthropicFileSummarizer(apiKey: string, model?: string): FileSummarizer {
+  const client = new Anthropic({ apiKey, fetch: limitedFetch('anthropic'), maxRetries: 0 });
+  return async (prompt: string, maxTokens: number = 300): Promise<string> => {
+    const response = await client.messages.create({
+      model: model || 'claude-3-5-haiku-20241022',
+      max_tokens: maxTokens,
+      messages: [{ role: 'user', content: prompt }]
+    });
+    return response.content[0].type === 'text' ? response.content[0].text : '';
+  };
+}
+
+/**
+ * Check whether a file is a candidate for the summary tier before reading it
+ */
+export async function statOversizedCandidate(absolutePath: string): Promise<number | null> {
+  if (isBinaryExtension(absolutePath)) return null;
+  try {
+    const stats = await fs.stat(toLongPath(absolutePath));
+    return stats.isFile() ? stats.size : null;
+  } catch {
+    return null;
+  }
+}
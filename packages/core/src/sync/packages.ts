@@ -0,0 +1,194 @@
+/**
+ * Monorepo Package Boundaries
+ *
+ * `cv sync` finds the packages of a monorepo from its workspace manifests:
+ *
+ * - package.json `workspaces` (or pnpm-workspace.yaml `packages`) members,
+ *   named by their package.json `name`
+ * - Cargo.toml `[workspace] members`, named by their `[package] name`
+ * - go.mod modules, when the repository has more than one
+ *
+ * Every code chunk is tagged with the package it belongs to
+ * (`payload.package`), so searches can be scoped with `--package` or to the
+ * package the command runs in. Boundaries are detected on a full sync and
+ * recorded in `.cv/packages.json`; incremental syncs reuse them.
+ */
+
+import * as fs from 'fs/promises';
+import * as path from 'path';
+import { getCVDir, matchGlob } from '@cv-git/shared';
+import { parse as parseToml } from 'smol-toml';
+
+export const PACKAGES_FILE = 'packages.json';
+
+export interface RepoPackage {
+  name: string;
+  /** Directory relative to the repository root (posix) */
+  dir: string;
+  kind: 'npm' | 'cargo' | 'go';
+}
+
+/**
+ * Packages declared by the workspace manifests among `files`
+ */
+export async function detectPackages(repoRoot: string, files: string[]): Promise<RepoPackage[]> {
+  const manifests = (name: string) => files
+    .filter(file => path.posix.basename(file) === name)
+    .map(file => path.posix.dirname(file))
+    .filter(dir => dir !== '.');
+  const read = (file: string) => fs.readFile(path.join(repoRoot, file), 'utf-8').catch(() => null);
+
+  const packages: RepoPackage[] = [];
+
+  // package.json / pnpm workspaces
+  const npmGlobs = await npmWorkspaceGlobs(read);
+  if (npmGlobs.length > 0) {
+    for (const dir of manifests('package.json').filter(dir => inWorkspace(dir, npmGlobs))) {
+      const manifest = await read(`${dir}/package.json`);
+      let name: string | undefined;
+      try {
+        name = manifest ? JSON.parse(manifest).name : undefined;
+      } catch {
+        // Unparseable manifest: fall back to the directory name
+      }
+      packages.push({ name: typeof name === 'string' && name ? name : path.posix.basename(dir), dir, kind: 'npm' });
+    }
+  }
+
+  // Cargo workspaces
+  const cargoGlobs = cargoWorkspaceGlobs(await read('Cargo.toml'));
+  if (cargoGlobs.length > 0) {
+    for (const dir of manifests('Cargo.toml').filter(dir => inWorkspace(dir, cargoGlobs))) {
+      const name = cargoPackageName(await read(`${dir}/Cargo.toml`));
+      packages.push({ name: name ?? path.posix.basename(dir), dir, kind: 'cargo' });
+    }
+  }
+
+  // Go modules: a single module is not a monorepo
+  const goMods = files.filter(file => path.posix.basename(file) === 'go.mod');
+  if (goMods.length > 1) {
+    for (const file of goMods) {
+      const dir = path.posix.dirname(file);
+      const module = (await read(file))?.match(/^module\s+("?)([^\s"]+)\1/m)?.[2];
+      packages.push({ name: module ?? path.posix.basename(dir === '.' ? repoRoot : dir), dir, kind: 'go' });
+    }
+  }
+
+  // Longest directory first, so nested packages win in packageForFile
+  return packages.sort((a, b) => b.dir.length - a.dir.length || a.name.localeCompare(b.name));
+}
+
+async function npmWorkspaceGlobs(read: (file: string) => Promise<string | null>): Promise<string[]> {
+  const globs: string[] = [];
+
+  const manifest = await read('package.json');
+  if (manifest) {
+    try {
+      const workspaces = JSON.parse(manifest).workspaces;
+      const list = Array.isArray(workspaces) ? workspaces : workspaces?.packages;
+      if (Array.isArray(list)) globs.push(...list.filter((g: unknown): g is string => typeof g === 'string'));
+    } catch {
+      // No usable workspaces field
+    }
+  }
+
+  // packages:
+  //   - 'packages/*'
+  const pnpm = await read('pnpm-workspace.yaml');
+  if (pnpm) {
+    let inPackages = false;
+    for (const line of pnpm.split('\n')) {
+      if (/^packages\s*:/.test(line)) {
+        inPackages = true;
+      } else if (inPackages && /^\s*-/.test(line)) {
+        globs.push(line.replace(/^\s*-\s*/, '').replace(/\s+#.*$/, '').trim().replace(/^(['"])(.*)\1$/, '$2'));
+      } else if (inPackages && /^\S/.test(line)) {
+        inPackages = false;
+      }
+    }
+  }
+
+  return globs;
+}
+
+function cargoWorkspaceGlobs(manifest: string | null): string[] {
+  if (!manifest) return [];
+  try {
+    const workspace = parseToml(manifest).workspace;
+    if (!Array.isArray(workspace?.members)) return [];
+    const exclude: unknown[] = Array.isArray(workspace.exclude) ? workspace.exclude : [];
+    return [...workspace.members, ...exclude.map(g => `!${g}`)].filter((g): g is string => typeof g === 'string');
+  } catch {
+    return [];
+  }
+}
+
+function cargoPackageName(manifest: string | null): string | undefined {
+  if (!manifest) return undefined;
+  try {
+    const name = parseToml(manifest).package?.name;
+    return typeof name === 'string' ? name : undefined;
+  } catch {
+    return undefined;
+  }
+}
+
+/**
+ * Whether `dir` matches the workspace member globs (`!glob` excludes)
+ */
+function inWorkspace(dir: string, globs: string[]): boolean {
+  let included = false;
+  for (const glob of globs) {
+    const negated = glob.startsWith('!');
+    const pattern = glob.slice(negated ? 1 : 0).replace(/^\.\//, '').replace(/\/+$/, '');
+    if (matchGlob(dir, pattern)) included = !negated;
+  }
+  return included;
+}
+
+/**
+ * Package containing `file` (a repository-relative path)
+ */
+export function packageForFile(packages: RepoPackage[], file: string): RepoPackage | undefined {
+  const normalized = file.replace(/\\/g, '/');
+  return packages
+    .filter(pkg => pkg.dir === '.' || normalized === pkg.dir || normalized.startsWith(`${pkg.dir}/`))
+    .sort((a, b) => b.dir.length - a.dir.length)[0];
+}
+
+/**
+ * Package a command run in `cwd` is scoped to; none at the repository root
+ */
+export function packageForDirectory(packages: RepoPackage[], repoRoot: string, cwd: string): RepoPackage | undefined {
+  const relative = path.relative(repoRoot, cwd).split(path.sep).join('/');
+  if (!relative || relative.startsWith('..') || path.isAbsolute(relative)) return undefined;
+  const pkg = packageForFile(packages, relative);
+  return pkg && pkg.dir !== '.' ? pkg : undefined;
+}
+
+/**
+ * Package named `query`: its full name, the last segment of its name
+ * (`web` for `@acme/web`) or its directory
+ */
+export function findPackage(packages: RepoPackage[], query: string): RepoPackage | undefined {
+  const dir = query.replace(/\\/g, '/').replace(/^\.\//, '').replace(/\/+$/, '');
+  return packages.find(pkg => pkg.name === query)
+    ?? packages.find(pkg => pkg.dir === dir)
+    ?? packages.find(pkg => pkg.name.split('/').pop() === query);
+}
+
+export async function loadPackages(repoRoot: string): Promise<RepoPackage[]> {
+  try {
+    const data = await fs.readFile(path.join(getCVDir(repoRoot), PACKAGES_FILE), 'utf-8');
+    const parsed = JSON.parse(data);
+    return Array.isArray(parsed.packages) ? parsed.packages : [];
+  } catch {
+    return [];
+  }
+}
+
+export async function savePackages(repoRoot: string, packages: RepoPackage[]): Promise<void> {
+  const cvDir = getCVDir(repoRoot);
+  await fs.mkdir(cvDir, { recursive: true });
+  await fs.writeFile(path.join(cvDir, PACKAGES_FILE), JSON.stringify({ packages }, null, 2));
+}
@@ -0,0 +1,50 @@
+/**
+ * Ambient type declarations for optional vector store packages.
+ * These packages are loaded via dynamic import() and may not be installed.
+ */
+
+declare module '@lancedb/lancedb' {
+  export function connect(uri: string): Promise<Connection>;
+
+  interface Connection {
+    tableNames(): Promise<string[]>;
+    openTable(name: string): Promise<Table>;
+    createTable(name: string, data: Record<string, unknown>[]): Promise<Table>;
+    dropTable(name: string): Promise<void>;
+    close?(): void;
+  }
+
+  interface Table {
+    countRows(filter?: string): Promise<number>;
+    delete(predicate: string): Promise<void>;
+    mergeInsert(on: string): MergeInsertBuilder;
+    vectorSearch(vector: number[]): VectorQuery;
+    query(): Query;
+  }
+
+  interface MergeInsertBuilder {
+    whenMatchedUpdateAll(): MergeInsertBuilder;
+    whenNotMatchedInsertAll(): MergeInsertBuilder;
+    execute(data: Record<string, unknown>[]): Promise<void>;
+  }
+
+  interface Query {
+    where(predicate: string): Query;
+    limit(limit: number): Query;
+    offset(offset: number): Query;
+    toArray(): Promise<any[]>;
+  }
+
+  interface VectorQuery extends Query {
+    distanceType(type: 'l2' | 'cosine' | 'dot'): VectorQuery;
+  }
+}
+
+declare module 'better-sqlite3' {
+  const Database: any;
+  export default Database;
+}
+
+declare module 'sqlite-vec' {
+  export function load(db: unknown): void;
+}
@@ -0,0 +1,113 @@
+/**
+ * Branch Namespaces for the Code Index
+ *
+ * Every code chunk point carries the branches it is indexed on
+ * (`payload.branches`). Points are keyed by chunk id and content hash, so a
+ * chunk that is the same on two branches is stored once and tagged with both;
+ * embeddings come from the content-addressed cache either way. `cv sync`
+ * moves the checked-out branch onto the points of the current tree, and code
+ * searches are filtered to it.
+ *
+ * A detached HEAD is namespaced by commit (`@<sha>`). Branches this
+ * repository has been synced on are recorded in `.cv/branch-index.json`;
+ * searches on a branch that was never synced are not filtered, so an index
+ * built before namespacing keeps working until the next sync.
+ */
+
+import { createHash } from 'crypto';
+import * as fs from 'fs';
+import * as path from 'path';
+import { getCVDir } from '@cv-git/shared';
+import type { VectorFilter, VectorFilterCondition } from './store.js';
+
+export const BRANCH_INDEX_FILE = 'branch-index.json';
+
+export interface BranchIndexEntry {
+  /** HEAD when the branch was last synced */
+  commit?: string;
+  syncedAt: number;
+}
+
+export interface BranchIndex {
+  /** Namespace of the most recent sync */
+  last?: string;
+  branches: Record<string, BranchIndexEntry>;
+}
+
+/**
+ * Namespace of the checked-out tree: the branch name, or `@<sha>` on a
+ * detached HEAD. Null outside a git repository.
+ */
+export function getCurrentNamespace(repoRoot: string): string | null {
+  try {
+    let gitDir = path.join(repoRoot, '.git');
+    if (fs.statSync(gitDir).isFile()) {
+      // Worktrees and submodules: "gitdir: <path>"
+      const target = fs.readFileSync(gitDir, 'utf-8').match(/^gitdir:\s*(.+)$/m)?.[1].trim();
+      if (!target) return null;
+      gitDir = path.resolve(repoRoot, target);
+    }
+
+    const head = fs.readFileSync(path.join(gitDir, 'HEAD'), 'utf-8').trim();
+    const ref = head.match(/^ref:\s*refs\/heads\/(.+)$/);
+    if (ref) return ref[1];
+    return /^[0-9a-f]{40,64}$/.test(head) ? `@${head.slice(0, 12)}` : null;
+  } catch {
+    return null;
+  }
+}
+
+export async function loadBranchIndex(repoRoot: string): Promise<BranchIndex> {
+  try {
+    const data = await fs.promises.readFile(path.join(getCVDir(repoRoot), BRANCH_INDEX_FILE), 'utf-8');
+    const parsed = JSON.parse(data) as BranchIndex;
+    return { last: parsed.last, branches: parsed.branches ?? {} };
+  } catch {
+    return { branches: {} };
+  }
+}
+
+async function saveBranchIndex(repoRoot: string, index: BranchIndex): Promise<void> {
+  const cvDir = getCVDir(repoRoot);
+  await fs.promises.mkdir(cvDir, { recursive: true });
+  await fs.promises.writeFile(path.join(cvDir, BRANCH_INDEX_FILE), JSON.stringify(index, null, 2));
+}
+
+/**
+ * Record that the index now describes `namespace` at `commit`
+ */
+export async function recordBranchSync(repoRoot: string, namespace: string, commit?: string): Promise<void> {
+  const index = await loadBranchIndex(repoRoot);
+  index.branches[namespace] = { commit, syncedAt: Date.now() };
+  index.last = namespace;
+  await saveBranchIndex(repoRoot, index);
+}
+
+/**
+ * Forget namespaces whose points were removed
+ */
+export async function forgetBranches(repoRoot: string, namespaces: string[]): Promise<void> {
+  const index = await loadBranchIndex(repoRoot);
+  for (const namespace of namespaces) delete index.branches[namespace];
+  if (index.last && !index.branches[index.last]) index.last = undefined;
+  await saveBranchIndex(repoRoot, index);
+}
+
+/**
+ * Point key for a chunk: the same chunk id with different text on another
+ * branch is a different point
+ */
+export function chunkPointKey(chunkId: string, text: string): string {
+  return `${chunkId}#${createHash('sha256').update(text).digest('hex').slice(0, 16)}`;
+}
+
+export function branchCondition(namespace: string): VectorFilterCondition {
+  return { key: 'branches', match: { value: namespace } };
+}
+
+/**
+ * `filter` restricted to points on `namespace`
+ */
+export function withBranchScope(filter: VectorFilter | undefined, namespace: string): VectorFilter {
+  return { ...filter, must: [...(filter?.must ?? []), branchCondition(namespace)] };
+}
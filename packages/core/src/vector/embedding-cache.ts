@@ -4,6 +4,9 @@
  * Implements deduplication for vector embeddings using content-based hashing.
  * Same text + same model = same embedding ID = stored once.
  *
+ * Entries of every model share one cache, so switching embedding model and
+ * back reuses the earlier vectors instead of re-embedding the repository.
+ *
  * Storage structure:
  * .cv/
  * └── embeddings/
@@ -40,6 +43,7 @@ export interface EmbeddingIndex {
 }
 
 export interface CacheStats {
+  /** Entries and bytes for the current model */
   totalEntries: number;
   totalSizeBytes: number;
   cacheHits: number;
@@ -47,6 +51,8 @@ export interface CacheStats {
   hitRate: number;
   model: string;
   dimensions: number;
+  /** Entries kept per model, including other models */
+  entriesByModel: Record<string, number>;
 }
 
 export interface EmbeddingCacheConfig {
@@ -68,6 +74,7 @@ export class EmbeddingCache {
   private indexPath: string;
   private vectorsDir: string;
   private dirty = false;
+  private saving: Promise<void> = Promise.resolve();
 
   constructor(config: EmbeddingCacheConfig) {
     this.config = {
@@ -117,10 +124,10 @@ export class EmbeddingCache {
       const data = await fs.readFile(this.indexPath, 'utf-8');
       this.index = JSON.parse(data);
 
-      // Validate index version and model
+      // Entries are keyed by model, so other models' vectors stay valid
       if (this.index && this.index.model !== this.config.model) {
-        console.warn(`Cache model mismatch: ${this.index.model} vs ${this.config.model}, starting fresh`);
-        this.index = null;
+        this.index.model = this.config.model;
+        this.dirty = true;
       }
     } catch (error: any) {
       if (error.code !== 'ENOENT') {
@@ -149,20 +156,29 @@ export class EmbeddingCache {
    * Save the index to disk
    */
   async saveIndex(): Promise<void> {
-    if (!this.dirty || !this.index) return;
-
-    this.index.stats.lastUpdated = new Date().toISOString();
-    await fs.writeFile(this.indexPath, JSON.stringify(this.index, null, 2));
-    this.dirty = false;
+    // Batches embedded in parallel save at once; one write at a time keeps the file whole
+    this.saving = this.saving.catch(() => {}).then(async () => {
+      if (!this.dirty || !this.index) return;
+      this.index.stats.lastUpdated = new Date().toISOString();
+      const data = JSON.stringify(this.index, null, 2);
+      this.dirty = false;
+      try {
+        await fs.writeFile(this.indexPath, data);
+      } catch (error) {
+        this.dirty = true;
+        throw error;
+      }
+    });
+    return this.saving;
   }
 
   /**
    * Check if an embedding exists in cache
    */
-  async has(text: string): Promise<boolean> {
+  async has(text: string, model: string = this.config.model): Promise<boolean> {
     if (!this.index) await this.initialize();
 
-    const id = EmbeddingCache.computeEmbeddingId(text, this.config.model);
+    const id = EmbeddingCache.computeEmbeddingId(text, model);
     return id in this.index!.entries;
   }
 
@@ -170,13 +186,14 @@ export class EmbeddingCache {
    * Get an embedding from cache
    * Returns null if not found
    */
-  async get(text: string): Promise<number[] | null> {
+  async get(text: string, model: string = this.config.model): Promise<number[] | null> {
     if (!this.index) await this.initialize();
 
-    const id = EmbeddingCache.computeEmbeddingId(text, this.config.model);
+    const id = EmbeddingCache.computeEmbeddingId(text, model);
     const metadata = this.index!.entries[id];
 
-    if (!metadata) {
+    // The short ID could collide; the full text hash must match too
+    if (!metadata || metadata.textHash !== this.computeTextHash(text)) {
       this.index!.stats.cacheMisses++;
       this.dirty = true;
       return null;
@@ -207,10 +224,10 @@ export class EmbeddingCache {
   /**
    * Store an embedding in cache
    */
-  async set(text: string, vector: number[]): Promise<string> {
+  async set(text: string, vector: number[], model: string = this.config.model): Promise<string> {
     if (!this.index) await this.initialize();
 
-    const id = EmbeddingCache.computeEmbeddingId(text, this.config.model);
+    const id = EmbeddingCache.computeEmbeddingId(text, model);
     const textHash = this.computeTextHash(text);
 
     // Write vector to binary file
@@ -229,7 +246,7 @@ export class EmbeddingCache {
 
     this.index!.entries[id] = {
       id,
-      model: this.config.model,
+      model,
       dimensions: vector.length,
       createdAt: existing?.createdAt ?? new Date().toISOString(),
       accessCount: existing?.accessCount ?? 0,
@@ -245,7 +262,7 @@ export class EmbeddingCache {
    * Get multiple embeddings, returning which ones need to be computed
    * Returns: { cached: Map<text, vector>, missing: string[] }
    */
-  async getBatch(texts: string[]): Promise<{
+  async getBatch(texts: string[], model: string = this.config.model): Promise<{
     cached: Map<string, number[]>;
     missing: string[];
     ids: Map<string, string>;  // text → embedding_id
@@ -257,10 +274,10 @@ export class EmbeddingCache {
     const ids = new Map<string, string>();
 
     for (const text of texts) {
-      const id = EmbeddingCache.computeEmbeddingId(text, this.config.model);
+      const id = EmbeddingCache.computeEmbeddingId(text, model);
       ids.set(text, id);
 
-      const vector = await this.get(text);
+      const vector = await this.get(text, model);
       if (vector) {
         cached.set(text, vector);
       } else {
@@ -274,11 +291,11 @@ export class EmbeddingCache {
   /**
    * Store multiple embeddings
    */
-  async setBatch(embeddings: Map<string, number[]>): Promise<Map<string, string>> {
+  async setBatch(embeddings: Map<string, number[]>, model: string = this.config.model): Promise<Map<string, string>> {
     const ids = new Map<string, string>();
 
     for (const [text, vector] of embeddings) {
-      const id = await this.set(text, vector);
+      const id = await this.set(text, vector, model);
       ids.set(text, id);
     }
 
@@ -296,14 +313,26 @@ export class EmbeddingCache {
     const misses = this.index!.stats.cacheMisses;
     const total = hits + misses;
 
+    const entriesByModel: Record<string, number> = {};
+    let totalEntries = 0;
+    let totalSizeBytes = 0;
+    for (const entry of Object.values(this.index!.entries)) {
+      entriesByModel[entry.model] = (entriesByModel[entry.model] || 0) + 1;
+      if (entry.model === this.config.model) {
+        totalEntries++;
+        totalSizeBytes += entry.dimensions * 4;
+      }
+    }
+
     return {
-      totalEntries: this.index!.stats.totalEntries,
-      totalSizeBytes: this.index!.stats.totalSizeBytes,
+      totalEntries,
+      totalSizeBytes,
       cacheHits: hits,
       cacheMisses: misses,
       hitRate: total > 0 ? hits / total : 0,
       model: this.config.model,
-      dimensions: this.config.dimensions
+      dimensions: this.config.dimensions,
+      entriesByModel
     };
   }
 
@@ -324,7 +353,9 @@ export class EmbeddingCache {
   }> {
     if (!this.index) await this.initialize();
 
-    const idsToExport = embeddingIds ?? Object.keys(this.index!.entries);
+    const idsToExport = embeddingIds ?? Object.values(this.index!.entries)
+      .filter(entry => entry.model === this.config.model)
+      .map(entry => entry.id);
     const embeddings: Array<{ id: string; textHash: string; vector: number[] }> = [];
 
     for (const id of idsToExport) {
@@ -456,7 +487,6 @@ export class EmbeddingCache {
 
     let evicted = 0;
     let currentSize = this.index!.stats.totalSizeBytes;
-    const bytesPerVector = this.config.dimensions * 4;  // float32
 
     for (const entry of entries) {
       if (currentSize <= target) break;
@@ -465,7 +495,7 @@ export class EmbeddingCache {
         await fs.unlink(path.join(this.vectorsDir, `${entry.id}.bin`));
         delete this.index!.entries[entry.id];
         this.index!.stats.totalEntries--;
-        currentSize -= bytesPerVector;
+        currentSize -= entry.dimensions * 4;  // float32
         evicted++;
       } catch {
         // Ignore missing files
@@ -0,0 +1,177 @@
+/**
+ * Vector Index Garbage Collection
+ *
+ * Syncs only prune vectors of files they see change, so points for files
+ * deleted while sync was off, commits lost to a rebase, and branches that
+ * no longer exist pile up. `collectVectorGarbage` removes them:
+ *
+ * - points whose `file` is no longer tracked (directory summaries are kept
+ *   while any file under the directory is); branch-namespaced points only
+ *   lose the current branch
+ * - commit points whose `sha` is gone
+ * - branch namespaces (`payload.branches`) of deleted branches; points left
+ *   on no branch are deleted
+ *
+ * Space is only returned to the disk once the store is compacted.
+ */
+
+import * as fs from 'fs/promises';
+import * as path from 'path';
+import type { VectorPoint, VectorStore } from './store.js';
+
+export interface VectorGcOptions {
+  /** Collections to scan */
+  collections: string[];
+  /** Files tracked in the repository */
+  liveFiles: Iterable<string>;
+  /** Check a commit still exists; commit points are kept when unset */
+  commitExists?: (sha: string) => Promise<boolean>;
+  /** Branch namespaces that still exist; namespaces are kept when unset */
+  liveNamespaces?: Iterable<string>;
+  /** Namespace `liveFiles` were listed on; other branches keep their files */
+  currentNamespace?: string;
+  /** Count what would be removed without changing the store */
+  dryRun?: boolean;
+  onProgress?: (progress: { collection: string; scanned: number; total: number }) => void;
+}
+
+export interface VectorGcCollectionResult {
+  name: string;
+  scanned: number;
+  /** Points deleted because their file is no longer tracked */
+  orphanedFiles: number;
+  /** Points deleted because their commit is gone */
+  orphanedCommits: number;
+  /** Points deleted because none of their branches exist */
+  orphanedBranches: number;
+  /** Points that lost a deleted branch but stay on another one */
+  retagged: number;
+}
+
+export interface VectorGcResult {
+  collections: VectorGcCollectionResult[];
+  /** Points deleted across all collections */
+  deleted: number;
+  retagged: number;
+  /** Deleted branch namespaces found on points */
+  removedNamespaces: string[];
+}
+
+const SCROLL_BATCH = 1000;
+const WRITE_BATCH = 256;
+
+/**
+ * Delete vectors whose source no longer exists
+ */
+export async function collectVectorGarbage(store: VectorStore, options: VectorGcOptions): Promise<VectorGcResult> {
+  const liveFiles = new Set(options.liveFiles);
+  const liveDirs = new Set<string>();
+  for (const file of liveFiles) {
+    for (let dir = path.posix.dirname(file); dir !== '.' && !liveDirs.has(dir); dir = path.posix.dirname(dir)) {
+      liveDirs.add(dir);
+    }
+  }
+  const liveNamespaces = options.liveNamespaces ? new Set(options.liveNamespaces) : null;
+  const commits = new Map<string, boolean>();
+  const removedNamespaces = new Set<string>();
+
+  const isLiveFile = (file: string) => file === '' || file === '.' || liveFiles.has(file) || liveDirs.has(file);
+  const isLiveCommit = async (sha: string) => {
+    if (!options.commitExists) return true;
+    if (!commits.has(sha)) commits.set(sha, await options.commitExists(sha));
+    return commits.get(sha)!;
+  };
+
+  const available = await store.listCollections();
+  const result: VectorGcResult = { collections: [], deleted: 0, retagged: 0, removedNamespaces: [] };
+
+  for (const name of options.collections.filter(c => available.includes(c))) {
+    const stats = await store.getCollectionStats(name);
+    const entry: VectorGcCollectionResult = {
+      name, scanned: 0, orphanedFiles: 0, orphanedCommits: 0, orphanedBranches: 0, retagged: 0
+    };
+    const deleteIds: Array<string | number> = [];
+    const retag = new Map<string, string[]>();
+
+    // Decide everything before writing: deletes would shift scroll offsets
+    let offset: string | undefined;
+    do {
+      const page = await store.scroll(name, { limit: SCROLL_BATCH, offset, withVector: false });
+      for (const point of page.points) {
+        const payload = point.payload;
+        if (typeof payload.sha === 'string' && Array.isArray(payload.filesChanged)) {
+          if (!await isLiveCommit(payload.sha)) {
+            deleteIds.push(point.id);
+            entry.orphanedCommits++;
+          }
+        } else if (Array.isArray(payload.branches)) {
+          // A file gone from this branch may still exist on another one
+          const fileLive = typeof payload.file !== 'string' || isLiveFile(payload.file);
+          const branches = payload.branches as string[];
+          const kept = branches.filter(b =>
+            (!liveNamespaces || liveNamespaces.has(b)) && (fileLive || b !== options.currentNamespace)
+          );
+          if (liveNamespaces) branches.filter(b => !liveNamespaces.has(b)).forEach(b => removedNamespaces.add(b));
+          if (kept.length === 0) {
+            deleteIds.push(point.id);
+            if (fileLive) entry.orphanedBranches++;
+            else entry.orphanedFiles++;
+          } else if (kept.length < branches.length) {
+            retag.set(String(point.id), kept);
+            entry.retagged++;
+          }
+        } else if (typeof payload.file === 'string' && !isLiveFile(payload.file)) {
+          deleteIds.push(point.id);
+          entry.orphanedFiles++;
+        }
+      }
+      entry.scanned += page.points.length;
+      options.onProgress?.({ collection: name, scanned: entry.scanned, total: stats?.pointsCount ?? entry.scanned });
+      offset = page.nextOffset;
+    } while (offset);
+
+    if (!options.dryRun) {
+      for (let i = 0; i < deleteIds.length; i += WRITE_BATCH) {
+        await store.delete(name, deleteIds.slice(i, i + WRITE_BATCH));
+      }
+      const retagIds = [...retag.keys()];
+      for (let i = 0; i < retagIds.length; i += WRITE_BATCH) {
+        const points = await store.retrieve(name, retagIds.slice(i, i + WRITE_BATCH), { withVector: true });
+        await store.upsert(name, points.map((point): VectorPoint => ({
+          ...point,
+          payload: { ...point.payload, branches: retag.get(String(point.id)) }
+        })));
+      }
+    }
+
+    result.collections.push(entry);
+    result.deleted += deleteIds.length;
+    result.retagged += entry.retagged;
+  }
+
+  result.removedNamespaces = [...removedNamespaces].sort();
+  return result;
+}
+
+/**
+ * Bytes used by the files under `dir` (0 when it does not exist)
+ */
+export async function directorySize(dir: string): Promise<number> {
+  let entries;
+  try {
+    entries = await fs.readdir(dir, { withFileTypes: true });
+  } catch {
+    return 0;
+  }
+
+  let total = 0;
+  for (const entry of entries) {
+    const full = path.join(dir, entry.name);
+    if (entry.isDirectory()) {
+      total += await directorySize(full);
+    } else if (entry.isFile()) {
+      total += (await fs.stat(full)).size;
+    }
+  }
+  return total;
+}
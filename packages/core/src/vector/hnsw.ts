@@ -0,0 +1,537 @@
+/**
+ * HNSW — Hierarchical Navigable Small World graph for approximate
+ * nearest-neighbour search (Malkov & Yashunin, 2016).
+ *
+ * Vectors are normalised on insert so cosine similarity is a dot product.
+ * Removal leaves a tombstone: the node still routes searches but is never
+ * returned; compact() rebuilds without tombstones.
+ *
+ * The serialised form is an index file (see index-file.ts) whose sections
+ * are the vectors, the neighbour lists with a per-node start table, the
+ * levels, the ids and the tombstones. A loaded index searches those
+ * sections in place: load() reads only the header and the tombstones, and a
+ * search reads the vectors and neighbour lists of the nodes it visits, so
+ * opening costs the same however large the graph. The first change copies
+ * it into the mutable arrays below. Version 1 files (header | f32 vectors |
+ * u32 neighbour lists) still load.
+ */
+
+import { IndexFile, StringTable, readIndexFile, writeIndexFile } from './index-file.js';
+
+export interface HnswParams {
+  /** Links per node on upper layers (layer 0 keeps 2·M) */
+  m: number;
+  /** Candidate list size while inserting */
+  efConstruction: number;
+}
+
+export interface HnswHit {
+  id: string;
+  /** Cosine similarity */
+  score: number;
+}
+
+export const DEFAULT_HNSW_PARAMS: HnswParams = { m: 16, efConstruction: 200 };
+
+const MAGIC = 'HNSW';
+const FORMAT_VERSION = 2;
+
+interface HnswHeader {
+  version: number;
+  dimension: number;
+  m: number;
+  efConstruction: number;
+  entryPoint: number;
+  maxLevel: number;
+  count: number;
+}
+
+/** Version 1 kept everything but the vectors and links in the header */
+interface HnswHeaderV1 extends Omit<HnswHeader, 'count'> {
+  ids: string[];
+  levels: number[];
+  deleted: number[];
+}
+
+/**
+ * A loaded graph, searched without unpacking it. Sections of `file`:
+ * `vectors` (count × dimension), `links` (per node and level: neighbour
+ * count, then the neighbours), `linkStart` (where each node's lists start
+ * in `links`), `levels` and `ids` (with its offsets and hash table, so
+ * has() looks an id up without reading the others).
+ */
+interface LoadedGraph {
+  count: number;
+  file: IndexFile<HnswHeader>;
+}
+
+/**
+ * Binary heap ordered by `before(a, b)` (true when a should pop first)
+ */
+class Heap<T> {
+  private items: T[] = [];
+
+  constructor(private before: (a: T, b: T) => boolean) {}
+
+  get size(): number {
+    return this.items.length;
+  }
+
+  peek(): T | undefined {
+    return this.items[0];
+  }
+
+  push(item: T): void {
+    const items = this.items;
+    items.push(item);
+    let i = items.length - 1;
+    while (i > 0) {
+      const parent = (i - 1) >> 1;
+      if (!this.before(items[i], items[parent])) break;
+      [items[i], items[parent]] = [items[parent], items[i]];
+      i = parent;
+    }
+  }
+
+  pop(): T | undefined {
+    const items = this.items;
+    const top = items[0];
+    const last = items.pop();
+    if (items.length > 0 && last !== undefined) {
+      items[0] = last;
+      let i = 0;
+      for (;;) {
+        const left = 2 * i + 1;
+        const right = left + 1;
+        let next = i;
+        if (left < items.length && this.before(items[left], items[next])) next = left;
+        if (right < items.length && this.before(items[right], items[next])) next = right;
+        if (next === i) break;
+        [items[i], items[next]] = [items[next], items[i]];
+        i = next;
+      }
+    }
+    return top;
+  }
+
+  toArray(): T[] {
+    return [...this.items];
+  }
+}
+
+interface Candidate {
+  node: number;
+  score: number;
+}
+
+function normalize(vector: ArrayLike<number>): Float32Array {
+  const out = new Float32Array(vector.length);
+  let norm = 0;
+  for (let i = 0; i < vector.length; i++) norm += vector[i] * vector[i];
+  norm = Math.sqrt(norm) || 1;
+  for (let i = 0; i < vector.length; i++) out[i] = vector[i] / norm;
+  return out;
+}
+
+function dot(a: Float32Array, b: Float32Array): number {
+  let sum = 0;
+  for (let i = 0; i < a.length; i++) sum += a[i] * b[i];
+  return sum;
+}
+
+export class HnswIndex {
+  readonly dimension: number;
+  readonly m: number;
+  readonly efConstruction: number;
+
+  private vectors: Float32Array[] = [];
+  private ids: string[] = [];
+  private levels: number[] = [];
+  private links: number[][][] = [];
+  private nodeById = new Map<string, number>();
+  private deleted = new Set<number>();
+  /** Set from deserialize() or load() until the first change */
+  private loaded: LoadedGraph | null = null;
+  private entryPoint = -1;
+  private maxLevel = -1;
+  private levelFactor: number;
+
+  constructor(dimension: number, params: Partial<HnswParams> = {}, private random: () => number = Math.random) {
+    this.dimension = dimension;
+    this.m = params.m ?? DEFAULT_HNSW_PARAMS.m;
+    this.efConstruction = params.efConstruction ?? DEFAULT_HNSW_PARAMS.efConstruction;
+    this.levelFactor = 1 / Math.log(this.m);
+  }
+
+  private get nodeCount(): number {
+    return this.loaded ? this.loaded.count : this.ids.length;
+  }
+
+  /** Live (non-deleted) points */
+  get size(): number {
+    return this.loaded ? this.loaded.count - this.deleted.size : this.nodeById.size;
+  }
+
+  /** Share of nodes that are tombstones */
+  get deletedRatio(): number {
+    return this.nodeCount === 0 ? 0 : this.deleted.size / this.nodeCount;
+  }
+
+  has(id: string): boolean {
+    if (this.loaded) return this.loaded.file.findString('ids', id, node => !this.deleted.has(node)) >= 0;
+    return this.nodeById.has(id);
+  }
+
+  private vector(node: number): Float32Array {
+    if (!this.loaded) return this.vectors[node];
+    return this.loaded.file.f32At('vectors', node * this.dimension, this.dimension);
+  }
+
+  private neighbours(node: number, level: number): ArrayLike<number> {
+    const loaded = this.loaded;
+    if (!loaded) return this.links[node][level] || [];
+    if (level > loaded.file.bytes('levels')[node]) return [];
+    const linkStart = loaded.file.u32('linkStart');
+    const lists = loaded.file.u32At('links', linkStart[node], linkStart[node + 1] - linkStart[node]);
+    let w = 0;
+    for (let l = 0; l < level; l++) w += 1 + lists[w];
+    return lists.subarray(w + 1, w + 1 + lists[w]);
+  }
+
+  private idOf(node: number): string {
+    return this.loaded ? this.loaded.file.stringAt('ids', node) : this.ids[node];
+  }
+
+  /**
+   * Copy a loaded graph into the mutable arrays (vectors stay views) and
+   * release its file
+   */
+  private unpack(): void {
+    const loaded = this.loaded;
+    if (!loaded) return;
+    this.loaded = null;
+    const { file } = loaded;
+    const vectors = file.f32('vectors');
+    const links = file.u32('links');
+    const linkStart = file.u32('linkStart');
+    const levels = file.bytes('levels');
+    this.ids = file.strings('ids').toArray();
+    this.levels = Array.from(levels);
+    file.close();
+
+    this.vectors = [];
+    this.links = [];
+    for (let node = 0; node < loaded.count; node++) {
+      this.vectors.push(vectors.subarray(node * this.dimension, (node + 1) * this.dimension));
+      const perLevel: number[][] = [];
+      let w = linkStart[node];
+      for (let l = 0; l <= levels[node]; l++) {
+        const length = links[w++];
+        perLevel.push(Array.from(links.subarray(w, w + length)));
+        w += length;
+      }
+      this.links.push(perLevel);
+    }
+    this.nodeById.clear();
+    this.ids.forEach((id, node) => {
+      if (!this.deleted.has(node)) this.nodeById.set(id, node);
+    });
+  }
+
+  /**
+   * Release the file of a loaded graph that is no longer needed
+   */
+  close(): void {
+    this.loaded?.file.close();
+  }
+
+  private maxLinks(level: number): number {
+    return level === 0 ? this.m * 2 : this.m;
+  }
+
+  private greedy(query: Float32Array, entry: number, level: number): number {
+    let current = entry;
+    let best = dot(query, this.vector(current));
+    for (let improved = true; improved;) {
+      improved = false;
+      const neighbours = this.neighbours(current, level);
+      for (let i = 0; i < neighbours.length; i++) {
+        const neighbour = neighbours[i];
+        const score = dot(query, this.vector(neighbour));
+        if (score > best) {
+          best = score;
+          current = neighbour;
+          improved = true;
+        }
+      }
+    }
+    return current;
+  }
+
+  /**
+   * Best-first search of one layer; returns up to ef nodes, best first
+   */
+  private searchLayer(query: Float32Array, entry: number, ef: number, level: number): Candidate[] {
+    const visited = new Set<number>([entry]);
+    const first = { node: entry, score: dot(query, this.vector(entry)) };
+    const candidates = new Heap<Candidate>((a, b) => a.score > b.score);
+    const results = new Heap<Candidate>((a, b) => a.score < b.score);
+    candidates.push(first);
+    results.push(first);
+
+    while (candidates.size > 0) {
+      const current = candidates.pop()!;
+      if (results.size >= ef && current.score < results.peek()!.score) break;
+
+      const neighbours = this.neighbours(current.node, level);
+      for (let i = 0; i < neighbours.length; i++) {
+        const neighbour = neighbours[i];
+        if (visited.has(neighbour)) continue;
+        visited.add(neighbour);
+        const score = dot(query, this.vector(neighbour));
+        if (results.size < ef || score > results.peek()!.score) {
+          candidates.push({ node: neighbour, score });
+          results.push({ node: neighbour, score });
+          if (results.size > ef) results.pop();
+        }
+      }
+    }
+
+    return results.toArray().sort((a, b) => b.score - a.score);
+  }
+
+  /**
+   * Keep the closest `max` links of a node
+   */
+  private prune(node: number, level: number, max: number): void {
+    const own = this.vectors[node];
+    this.links[node][level] = this.links[node][level]
+      .map(n => ({ n, score: dot(own, this.vectors[n]) }))
+      .sort((a, b) => b.score - a.score)
+      .slice(0, max)
+      .map(({ n }) => n);
+  }
+
+  /**
+   * Insert or replace a point
+   */
+  add(id: string, vector: ArrayLike<number>): void {
+    if (vector.length !== this.dimension) {
+      throw new Error(`HNSW: expected ${this.dimension} dimensions, got ${vector.length}`);
+    }
+    this.unpack();
+    if (this.nodeById.has(id)) this.remove(id);
+
+    const node = this.ids.length;
+    const level = Math.floor(-Math.log(1 - this.random()) * this.levelFactor);
+    const query = normalize(vector);
+    this.ids.push(id);
+    this.vectors.push(query);
+    this.levels.push(level);
+    this.links.push(Array.from({ length: level + 1 }, () => []));
+    this.nodeById.set(id, node);
+
+    if (this.entryPoint < 0) {
+      this.entryPoint = node;
+      this.maxLevel = level;
+      return;
+    }
+
+    let entry = this.entryPoint;
+    for (let l = this.maxLevel; l > level; l--) {
+      entry = this.greedy(query, entry, l);
+    }
+
+    for (let l = Math.min(level, this.maxLevel); l >= 0; l--) {
+      const found = this.searchLayer(query, entry, this.efConstruction, l);
+      const max = this.maxLinks(l);
+      const selected = found.slice(0, this.m).map(c => c.node);
+      this.links[node][l] = selected;
+      for (const neighbour of selected) {
+        const back = this.links[neighbour][l];
+        back.push(node);
+        if (back.length > max) this.prune(neighbour, l, max);
+      }
+      entry = found[0].node;
+    }
+
+    if (level > this.maxLevel) {
+      this.maxLevel = level;
+      this.entryPoint = node;
+    }
+  }
+
+  /**
+   * Tombstone a point; it keeps routing searches until compact()
+   */
+  remove(id: string): boolean {
+    this.unpack();
+    const node = this.nodeById.get(id);
+    if (node === undefined) return false;
+    this.nodeById.delete(id);
+    this.deleted.add(node);
+    return true;
+  }
+
+  /**
+   * Approximate k nearest neighbours; ef ≥ k trades speed for recall
+   */
+  search(vector: ArrayLike<number>, k: number, ef: number = Math.max(k, 64)): HnswHit[] {
+    if (this.entryPoint < 0 || k <= 0) return [];
+    const query = normalize(vector);
+
+    let entry = this.entryPoint;
+    for (let l = this.maxLevel; l > 0; l--) {
+      entry = this.greedy(query, entry, l);
+    }
+
+    // Tombstones take result slots, so widen the beam by their share
+    const beam = Math.ceil(Math.max(ef, k) / Math.max(1 - this.deletedRatio, 0.1));
+    return this.searchLayer(query, entry, beam, 0)
+      .filter(c => !this.deleted.has(c.node))
+      .slice(0, k)
+      .map(c => ({ id: this.idOf(c.node), score: c.score }));
+  }
+
+  /**
+   * Rebuild without tombstones
+   */
+  compact(): HnswIndex {
+    this.unpack();
+    const rebuilt = new HnswIndex(this.dimension, { m: this.m, efConstruction: this.efConstruction }, this.random);
+    for (const [id, node] of this.nodeById) {
+      rebuilt.add(id, this.vectors[node]);
+    }
+    return rebuilt;
+  }
+
+  serialize(): Buffer {
+    this.unpack();
+    const count = this.ids.length;
+    const vectors = new Float32Array(count * this.dimension);
+    this.vectors.forEach((v, i) => vectors.set(v, i * this.dimension));
+
+    const linkStart = new Uint32Array(count + 1);
+    let wordCount = 0;
+    this.links.forEach((perLevel, node) => {
+      linkStart[node] = wordCount;
+      for (const list of perLevel) wordCount += 1 + list.length;
+    });
+    linkStart[count] = wordCount;
+    const links = new Uint32Array(wordCount);
+    let w = 0;
+    for (const perLevel of this.links) {
+      for (const list of perLevel) {
+        links[w++] = list.length;
+        links.set(list, w);
+        w += list.length;
+      }
+    }
+
+    return writeIndexFile(MAGIC, {
+      version: FORMAT_VERSION,
+      dimension: this.dimension,
+      m: this.m,
+      efConstruction: this.efConstruction,
+      entryPoint: this.entryPoint,
+      maxLevel: this.maxLevel,
+      count
+    } satisfies HnswHeader, {
+      vectors,
+      linkStart,
+      links,
+      levels: Uint8Array.from(this.levels),
+      ...StringTable.sections('ids', this.ids),
+      deleted: Uint32Array.from(this.deleted)
+    });
+  }
+
+  /**
+   * Load a serialised index; the result searches `buffer` in place, so it
+   * must not be modified while the index is in use
+   */
+  static deserialize(buffer: Buffer, random?: () => number): HnswIndex {
+    let file: IndexFile<HnswHeader>;
+    try {
+      file = IndexFile.open<HnswHeader>(buffer, MAGIC);
+    } catch {
+      throw new Error('HNSW: not an index file');
+    }
+    if (file.header.version === 1) return HnswIndex.deserializeV1(buffer, file.header as unknown as HnswHeaderV1, random);
+    return HnswIndex.fromFile(file, random);
+  }
+
+  /**
+   * Open a saved index, reading its sections from the file as searches
+   * visit them; close() it when it is dropped
+   */
+  static async load(path: string, random?: () => number): Promise<HnswIndex> {
+    let file: IndexFile<HnswHeader>;
+    try {
+      file = IndexFile.openFile<HnswHeader>(path, MAGIC);
+    } catch (error: any) {
+      if (error.code) throw error;
+      throw new Error('HNSW: not an index file');
+    }
+    if (file.header.version === 1) {
+      file.close();
+      return HnswIndex.deserialize(await readIndexFile(path), random);
+    }
+    try {
+      return HnswIndex.fromFile(file, random);
+    } catch (error) {
+      file.close();
+      throw error;
+    }
+  }
+
+  private static fromFile(file: IndexFile<HnswHeader>, random?: () => number): HnswIndex {
+    const header = file.header;
+    if (header.version !== FORMAT_VERSION) {
+      throw new Error(`HNSW: unsupported index version ${header.version}`);
+    }
+
+    const index = new HnswIndex(header.dimension, { m: header.m, efConstruction: header.efConstruction }, random);
+    index.deleted = new Set(file.u32('deleted'));
+    index.loaded = { count: header.count, file };
+    index.entryPoint = header.entryPoint;
+    index.maxLevel = header.maxLevel;
+    return index;
+  }
+
+  private static deserializeV1(buffer: Buffer, header: HnswHeaderV1, random?: () => number): HnswIndex {
+    const index = new HnswIndex(header.dimension, { m: header.m, efConstruction: header.efConstruction }, random);
+    const headerLength = buffer.readUInt32LE(4);
+    let offset = 8 + headerLength;
+    offset += (4 - (offset % 4)) % 4;
+
+    const count = header.ids.length;
+    const vectorBytes = count * header.dimension * 4;
+    // Copy out so the arrays are aligned regardless of the buffer's offset
+    const vectors = new Float32Array(buffer.buffer.slice(buffer.byteOffset + offset, buffer.byteOffset + offset + vectorBytes));
+    offset += vectorBytes;
+    const words = new Uint32Array(buffer.buffer.slice(buffer.byteOffset + offset, buffer.byteOffset + buffer.length));
+
+    let w = 0;
+    for (let node = 0; node < count; node++) {
+      index.vectors.push(vectors.subarray(node * header.dimension, (node + 1) * header.dimension));
+      const perLevel: number[][] = [];
+      for (let l = 0; l <= header.levels[node]; l++) {
+        const length = words[w++];
+        perLevel.push(Array.from(words.subarray(w, w + length)));
+        w += length;
+      }
+      index.links.push(perLevel);
+    }
+
+    index.ids = header.ids;
+    index.levels = header.levels;
+    index.entryPoint = header.entryPoint;
+    index.maxLevel = header.maxLevel;
+    index.deleted = new Set(header.deleted);
+    header.ids.forEach((id, node) => {
+      if (!index.deleted.has(node)) index.nodeById.set(id, node);
+    });
+    return index;
+  }
+}
@@ -0,0 +1,355 @@
+/**
+ * Index files read in place
+ *
+ * Search structures (HNSW graphs, quantized codes) are stored as one file of
+ * fixed-layout sections that queries use directly, without parsing:
+ *
+ *   magic (4 bytes) | u32 header length | JSON header | pad | sections
+ *
+ * The header is small (counts, parameters and where each section starts);
+ * every section starts 8-byte aligned. Opening a file reads only the header;
+ * a search then reads the records it visits (one node's vector, one node's
+ * links) at their offsets, so the cost of opening a large index no longer
+ * grows with the number of points. String lists (point ids) are stored as a
+ * u32 offset table and UTF-8 bytes and decoded one at a time, only for the
+ * results a search returns; a hash table of positions finds one string
+ * without reading the rest.
+ *
+ * Node has no portable mmap, so records are read with positioned reads
+ * through a small cache of file pages; the pages come from the OS page cache
+ * on every start after the first.
+ */
+
+import * as fs from 'fs';
+
+const ALIGN = 8;
+/** File pages kept in memory by an index opened with openFile() */
+const PAGE_SIZE = 64 * 1024;
+const MAX_PAGES = 256;
+
+interface FileHeader {
+  /** section name → [byte offset after the header, byte length] */
+  sections: Record<string, [number, number]>;
+  [key: string]: unknown;
+}
+
+export type IndexSection = Uint8Array | Uint32Array | Float32Array;
+
+/**
+ * Lay out `header` and `sections` as an index file
+ */
+export function writeIndexFile(magic: string, header: Record<string, unknown>, sections: Record<string, IndexSection>): Buffer {
+  const table: Record<string, [number, number]> = {};
+  let size = 0;
+  for (const [name, data] of Object.entries(sections)) {
+    table[name] = [size, data.byteLength];
+    size = align(size + data.byteLength);
+  }
+  const json = Buffer.from(JSON.stringify({ ...header, sections: table }), 'utf-8');
+  const start = align(8 + json.length);
+
+  const out = Buffer.alloc(start + size);
+  out.write(magic, 0, 'ascii');
+  out.writeUInt32LE(json.length, 4);
+  json.copy(out, 8);
+  for (const [name, [offset]] of Object.entries(table)) {
+    const data = sections[name];
+    out.set(new Uint8Array(data.buffer, data.byteOffset, data.byteLength), start + offset);
+  }
+  return out;
+}
+
+function align(n: number): number {
+  return Math.ceil(n / ALIGN) * ALIGN;
+}
+
+/**
+ * Where the bytes of an index file come from
+ */
+interface ByteSource {
+  readonly size: number;
+  /** `length` bytes from `position`; a view where possible, so don't modify it */
+  read(position: number, length: number): Uint8Array;
+  close(): void;
+}
+
+class BufferSource implements ByteSource {
+  constructor(private buffer: Buffer) {}
+
+  get size(): number {
+    return this.buffer.length;
+  }
+
+  read(position: number, length: number): Uint8Array {
+    return new Uint8Array(this.buffer.buffer, this.buffer.byteOffset + position, length);
+  }
+
+  close(): void {}
+}
+
+/**
+ * Positioned reads from an open file. Reads within one page are served
+ * from the most recently used pages; larger ones go to the file directly.
+ * Every returned array starts at an offset with the file position's
+ * alignment, so aligned records can be viewed as typed arrays.
+ */
+class FileSource implements ByteSource {
+  private pages = new Map<number, Uint8Array>();
+
+  constructor(private fd: number, readonly size: number) {}
+
+  read(position: number, length: number): Uint8Array {
+    const first = Math.floor(position / PAGE_SIZE);
+    if (length > 0 && first === Math.floor((position + length - 1) / PAGE_SIZE)) {
+      const offset = position - first * PAGE_SIZE;
+      return this.page(first).subarray(offset, offset + length);
+    }
+    return this.readAt(position, length);
+  }
+
+  private page(index: number): Uint8Array {
+    let page = this.pages.get(index);
+    if (page) {
+      this.pages.delete(index);
+    } else {
+      page = this.readAt(index * PAGE_SIZE, Math.min(PAGE_SIZE, this.size - index * PAGE_SIZE));
+      if (this.pages.size >= MAX_PAGES) this.pages.delete(this.pages.keys().next().value!);
+    }
+    this.pages.set(index, page);
+    return page;
+  }
+
+  private readAt(position: number, length: number): Uint8Array {
+    if (this.fd < 0) throw new Error('Index file is closed');
+    const out = new Uint8Array(length);
+    for (let done = 0; done < length;) {
+      const bytesRead = fs.readSync(this.fd, out, done, length - done, position + done);
+      if (bytesRead === 0) throw new Error('Index file is truncated');
+      done += bytesRead;
+    }
+    return out;
+  }
+
+  close(): void {
+    if (this.fd >= 0) fs.closeSync(this.fd);
+    this.fd = -1;
+    this.pages.clear();
+  }
+}
+
+export class IndexFile<H> {
+  /** Whole sections read so far, by name */
+  private sections = new Map<string, IndexSection | StringTable>();
+
+  private constructor(private source: ByteSource, private start: number, readonly header: H & FileHeader) {}
+
+  /**
+   * Parse the header of a buffer written by writeIndexFile; the sections stay
+   * views into it. Throws when it is not a `magic` file.
+   */
+  static open<H>(buffer: Buffer, magic: string): IndexFile<H> {
+    if (buffer.length >= 8 && buffer.byteOffset % ALIGN !== 0) {
+      // Views need aligned offsets; copy into a buffer of its own
+      buffer = Buffer.from(new Uint8Array(buffer).buffer);
+    }
+    return IndexFile.fromSource<H>(new BufferSource(buffer), magic);
+  }
+
+  /**
+   * Open an index file, reading only its header; sections are read from the
+   * file when they are used. close() it when done.
+   */
+  static openFile<H>(file: string, magic: string): IndexFile<H> {
+    const fd = fs.openSync(file, 'r');
+    try {
+      return IndexFile.fromSource<H>(new FileSource(fd, fs.fstatSync(fd).size), magic);
+    } catch (error) {
+      fs.closeSync(fd);
+      throw error;
+    }
+  }
+
+  private static fromSource<H>(source: ByteSource, magic: string): IndexFile<H> {
+    const prefix = source.size >= 8 ? Buffer.from(source.read(0, 8)) : null;
+    if (!prefix || prefix.toString('ascii', 0, 4) !== magic) {
+      throw new Error(`Not a ${magic} index file`);
+    }
+    const length = prefix.readUInt32LE(4);
+    if (8 + length > source.size) throw new Error(`Index file is truncated (header)`);
+    const header = JSON.parse(Buffer.from(source.read(8, length)).toString('utf-8')) as H & FileHeader;
+    return new IndexFile(source, align(8 + length), header);
+  }
+
+  has(name: string): boolean {
+    return name in this.header.sections;
+  }
+
+  /**
+   * Bytes of `count` records of `unit` bytes from record `index` of a section
+   */
+  private read(name: string, unit: number, index?: number, count?: number): Uint8Array {
+    const section = this.header.sections[name];
+    if (!section) throw new Error(`Index file has no ${name} section`);
+    const [offset, length] = section;
+    if (this.start + offset + length > this.source.size) throw new Error(`Index file is truncated (${name})`);
+    if (index === undefined || count === undefined) return this.source.read(this.start + offset, length);
+    if (index < 0 || count < 0 || (index + count) * unit > length) {
+      throw new Error(`Index file read past the end of ${name}`);
+    }
+    return this.source.read(this.start + offset + index * unit, count * unit);
+  }
+
+  private whole<T extends IndexSection | StringTable>(name: string, load: () => T): T {
+    let section = this.sections.get(name) as T | undefined;
+    if (!section) {
+      section = load();
+      this.sections.set(name, section);
+    }
+    return section;
+  }
+
+  /** Whole sections, read on first use */
+  bytes(name: string): Uint8Array {
+    return this.whole(name, () => this.read(name, 1));
+  }
+
+  u32(name: string): Uint32Array {
+    return this.whole(name, () => {
+      const bytes = this.read(name, 4);
+      return new Uint32Array(bytes.buffer, bytes.byteOffset, bytes.byteLength / 4);
+    });
+  }
+
+  f32(name: string): Float32Array {
+    return this.whole(name, () => {
+      const bytes = this.read(name, 4);
+      return new Float32Array(bytes.buffer, bytes.byteOffset, bytes.byteLength / 4);
+    });
+  }
+
+  /** `count` values from value `index` of a section, read on their own */
+  u32At(name: string, index: number, count: number): Uint32Array {
+    const bytes = this.read(name, 4, index, count);
+    return new Uint32Array(bytes.buffer, bytes.byteOffset, count);
+  }
+
+  f32At(name: string, index: number, count: number): Float32Array {
+    const bytes = this.read(name, 4, index, count);
+    return new Float32Array(bytes.buffer, bytes.byteOffset, count);
+  }
+
+  strings(name: string): StringTable {
+    return this.whole(name, () =>
+      new StringTable(this.u32(`${name}.offsets`), (offset, length) => this.read(name, 1, offset, length))
+    );
+  }
+
+  /** String `i` of a string list, reading only its offsets and bytes */
+  stringAt(name: string, i: number): string {
+    const bytes = this.stringBytes(name, i);
+    return Buffer.from(bytes.buffer, bytes.byteOffset, bytes.byteLength).toString('utf-8');
+  }
+
+  /**
+   * Position of `value` in a string list (the first one `accept` takes), or
+   * -1. Probes the list's hash table; lists written without one are scanned.
+   */
+  findString(name: string, value: string, accept: (i: number) => boolean = () => true): number {
+    const wanted = Buffer.from(value, 'utf-8');
+    const matches = (i: number) => Buffer.from(this.stringBytes(name, i)).equals(wanted) && accept(i);
+
+    const hash = `${name}.hash`;
+    if (!this.has(hash)) {
+      const count = this.header.sections[`${name}.offsets`][1] / 4 - 1;
+      for (let i = 0; i < count; i++) {
+        if (matches(i)) return i;
+      }
+      return -1;
+    }
+    const slots = this.header.sections[hash][1] / 4;
+    for (let slot = fnv1a(wanted) & (slots - 1); ; slot = (slot + 1) & (slots - 1)) {
+      const entry = this.u32At(hash, slot, 1)[0];
+      if (entry === 0) return -1;
+      if (matches(entry - 1)) return entry - 1;
+    }
+  }
+
+  private stringBytes(name: string, i: number): Uint8Array {
+    const [start, end] = this.u32At(`${name}.offsets`, i, 2);
+    return this.read(name, 1, start, end - start);
+  }
+
+  /** Release the file; sections already read stay usable */
+  close(): void {
+    this.source.close();
+  }
+}
+
+/**
+ * Strings stored as an offset table and UTF-8 bytes, decoded on demand
+ */
+export class StringTable {
+  constructor(private offsets: Uint32Array, private data: (offset: number, length: number) => Uint8Array) {}
+
+  get length(): number {
+    return this.offsets.length - 1;
+  }
+
+  get(i: number): string {
+    const bytes = this.data(this.offsets[i], this.offsets[i + 1] - this.offsets[i]);
+    return Buffer.from(bytes.buffer, bytes.byteOffset, bytes.byteLength).toString('utf-8');
+  }
+
+  toArray(): string[] {
+    return Array.from({ length: this.length }, (_, i) => this.get(i));
+  }
+
+  /**
+   * Sections for writeIndexFile: `<name>`, `<name>.offsets` and
+   * `<name>.hash`, an open-addressing table (FNV-1a, linear probing) of
+   * position + 1 per string, 0 for an empty slot
+   */
+  static sections(name: string, strings: string[]): Record<string, IndexSection> {
+    const encoded = strings.map(s => Buffer.from(s, 'utf-8'));
+    const offsets = new Uint32Array(strings.length + 1);
+    encoded.forEach((b, i) => { offsets[i + 1] = offsets[i] + b.length; });
+
+    let slots = 2;
+    while (slots < strings.length * 2) slots *= 2;
+    const hash = new Uint32Array(slots);
+    encoded.forEach((b, i) => {
+      let slot = fnv1a(b) & (slots - 1);
+      while (hash[slot] !== 0) slot = (slot + 1) & (slots - 1);
+      hash[slot] = i + 1;
+    });
+    return { [name]: Buffer.concat(encoded), [`${name}.offsets`]: offsets, [`${name}.hash`]: hash };
+  }
+}
+
+function fnv1a(bytes: Uint8Array): number {
+  let hash = 0x811c9dc5;
+  for (let i = 0; i < bytes.length; i++) {
+    hash = Math.imul(hash ^ bytes[i], 0x01000193);
+  }
+  return hash >>> 0;
+}
+
+/**
+ * Read a whole file into a buffer whose sections can be viewed in place
+ */
+export async function readIndexFile(file: string): Promise<Buffer> {
+  const handle = await fs.promises.open(file, 'r');
+  try {
+    const { size } = await handle.stat();
+    const buffer = Buffer.from(new ArrayBuffer(size));
+    let read = 0;
+    while (read < size) {
+      const { bytesRead } = await handle.read(buffer, read, size - read, read);
+      if (bytesRead === 0) break;
+      read += bytesRead;
+    }
+    return buffer.subarray(0, read);
+  } finally {
+    await handle.close();
+  }
+}
@@ -0,0 +1,238 @@
+/**
+ * Shared index server
+ *
+ * `cv index serve` hosts a repository's vector index over HTTP so a team
+ * queries one index instead of each engineer embedding the same code:
+ *
+ *   GET  /v1/info                          commit indexed, embedding model, store
+ *   GET  /v1/state                         tracked files and their content hashes
+ *   GET  /v1/collections                   collection names
+ *   GET  /v1/collections/<name>            {stats, codec}; 404 when missing
+ *   POST /v1/collections/<name>/search     {vector, limit, filter} -> {points}
+ *   POST /v1/collections/<name>/retrieve   {ids, withVector} -> {points}
+ *   POST /v1/collections/<name>/scroll     {limit, offset, filter, withVector} -> page
+ *
+ * Every request needs `Authorization: Bearer <token>`. The index is
+ * read-only over HTTP: the server's own `cv sync` keeps it current, and
+ * clients keep their uncommitted changes in a local overlay (see
+ * stores/layered-store.ts), which /v1/state lets them compute.
+ */
+
+import * as http from 'http';
+import { createHash, timingSafeEqual } from 'crypto';
+import type { VectorFilter, VectorStore, VectorStoreType } from './store.js';
+import { matchesFilter } from './store.js';
+
+export interface IndexServerInfo {
+  /** Protocol version */
+  version: number;
+  storeType: VectorStoreType;
+  /** Commit the index was last synced at */
+  commit?: string;
+  embedding?: { model?: string; dimensions?: number };
+}
+
+/** A tracked file of the served index, as recorded by its sync */
+export interface SharedTrackedFile {
+  path: string;
+  contentHash: string;
+  size: number;
+  type: 'code' | 'document';
+}
+
+export interface SharedSyncState {
+  commit: string;
+  /** Files that differed from `commit` when it was synced */
+  dirtyFiles: string[];
+  files: SharedTrackedFile[];
+}
+
+export interface IndexServerOptions {
+  store: VectorStore;
+  /** Accepted bearer tokens */
+  tokens: string[];
+  /** Added to every query, e.g. to serve only the checked-out branch */
+  scope?: VectorFilter;
+  /** Read per request, so a sync on the server is picked up */
+  info: () => Promise<IndexServerInfo>;
+  state: () => Promise<SharedSyncState | null>;
+}
+
+export const INDEX_PROTOCOL_VERSION = 1;
+
+/** Most points one search or scroll page returns */
+const MAX_POINTS = 1000;
+
+/** Largest request body accepted (a query vector, a page of ids) */
+const MAX_BODY_BYTES = 4 * 1024 * 1024;
+
+class HttpError extends Error {
+  constructor(readonly status: number, message: string) {
+    super(message);
+  }
+}
+
+function digest(token: string): Buffer {
+  return createHash('sha256').update(token).digest();
+}
+
+/**
+ * Whether an Authorization header carries one of `tokens`
+ */
+export function isAuthorized(header: string | undefined, tokens: string[]): boolean {
+  const token = header?.match(/^Bearer\s+(\S+)$/i)?.[1];
+  if (!token) return false;
+  const given = digest(token);
+  // Compare every token in constant time so timing says nothing about them
+  return tokens.reduce((found, expected) => timingSafeEqual(given, digest(expected)) || found, false);
+}
+
+function sendJson(res: http.ServerResponse, status: number, body: unknown): void {
+  res.writeHead(status, { 'Content-Type': 'application/json; charset=utf-8', 'Cache-Control': 'no-store' });
+  res.end(JSON.stringify(body));
+}
+
+async function readJsonBody(req: http.IncomingMessage): Promise<any> {
+  if (!/^application\/json\b/.test(req.headers['content-type'] ?? '')) {
+    throw new HttpError(415, 'Expected an application/json body');
+  }
+  const parts: Buffer[] = [];
+  let size = 0;
+  for await (const part of req) {
+    size += part.length;
+    if (size > MAX_BODY_BYTES) throw new HttpError(413, 'Request body too large');
+    parts.push(part);
+  }
+  try {
+    return JSON.parse(Buffer.concat(parts).toString('utf-8')) ?? {};
+  } catch {
+    throw new HttpError(400, 'Malformed JSON body');
+  }
+}
+
+function decodeName(segment: string): string {
+  try {
+    return decodeURIComponent(segment);
+  } catch {
+    throw new HttpError(400, 'Malformed collection name');
+  }
+}
+
+function parseLimit(value: unknown, fallback: number): number {
+  const limit = typeof value === 'number' ? Math.floor(value) : fallback;
+  return Math.min(MAX_POINTS, Math.max(1, limit));
+}
+
+function parseFilter(value: unknown): VectorFilter | undefined {
+  if (value === undefined || value === null) return undefined;
+  if (typeof value !== 'object' || Array.isArray(value)) throw new HttpError(400, 'filter must be an object');
+  return value as VectorFilter;
+}
+
+function scoped(filter: VectorFilter | undefined, scope: VectorFilter | undefined): VectorFilter | undefined {
+  if (!scope) return filter;
+  return filter ? { must: [filter, scope] } : scope;
+}
+
+/**
+ * Request handler for the shared index API
+ */
+export function createIndexServerHandler(options: IndexServerOptions): http.RequestListener {
+  const { store, scope } = options;
+
+  return async (req, res) => {
+    const url = new URL(req.url ?? '/', 'http://localhost');
+    try {
+      if (!isAuthorized(req.headers.authorization, options.tokens)) {
+        res.setHeader('WWW-Authenticate', 'Bearer');
+        throw new HttpError(401, 'Missing or unknown token');
+      }
+
+      const match = url.pathname.match(/^\/v1\/collections\/([^/]+)(?:\/(search|retrieve|scroll))?$/);
+      const name = match ? decodeName(match[1]) : '';
+      const action = match?.[2];
+      const route = match ? `${req.method} collection${action ? `/${action}` : ''}` : `${req.method} ${url.pathname}`;
+
+      switch (route) {
+        case 'GET /v1/info':
+          sendJson(res, 200, await options.info());
+          return;
+
+        case 'GET /v1/state': {
+          const state = await options.state();
+          if (!state) throw new HttpError(404, 'The index has not been synced yet');
+          sendJson(res, 200, state);
+          return;
+        }
+
+        case 'GET /v1/collections':
+          sendJson(res, 200, { collections: await store.listCollections() });
+          return;
+
+        case 'GET collection': {
+          const stats = await store.getCollectionStats(name);
+          if (!stats) throw new HttpError(404, `No collection ${name}`);
+          sendJson(res, 200, { stats, codec: await store.getCodec?.(name) ?? null });
+          return;
+        }
+
+        case 'POST collection/search': {
+          const body = await readJsonBody(req);
+          if (!Array.isArray(body.vector) || !body.vector.every((v: unknown) => typeof v === 'number')) {
+            throw new HttpError(400, 'Expected {"vector": [numbers]}');
+          }
+          const points = await store.search(name, body.vector, {
+            limit: parseLimit(body.limit, 10),
+            filter: scoped(parseFilter(body.filter), scope)
+          });
+          sendJson(res, 200, { points });
+          return;
+        }
+
+        case 'POST collection/retrieve': {
+          const body = await readJsonBody(req);
+          if (!Array.isArray(body.ids) || body.ids.length > MAX_POINTS) {
+            throw new HttpError(400, `Expected {"ids": [...]} with at most ${MAX_POINTS} ids`);
+          }
+          const points = await store.retrieve(name, body.ids, { withVector: body.withVector === true });
+          sendJson(res, 200, { points: scope ? points.filter(p => matchesFilter(p.payload, scope)) : points });
+          return;
+        }
+
+        case 'POST collection/scroll': {
+          const body = await readJsonBody(req);
+          const page = await store.scroll(name, {
+            limit: parseLimit(body.limit, 100),
+            offset: typeof body.offset === 'string' ? body.offset : undefined,
+            filter: scoped(parseFilter(body.filter), scope),
+            withVector: body.withVector !== false
+          });
+          sendJson(res, 200, page);
+          return;
+        }
+
+        default:
+          if (match || url.pathname === '/v1/collections') {
+            throw new HttpError(405, 'The shared index is read-only; it is updated by cv sync on the server');
+          }
+          throw new HttpError(404, 'Not found');
+      }
+    } catch (error: any) {
+      const status = error instanceof HttpError ? error.status : 500;
+      if (!res.headersSent) sendJson(res, status, { error: error.message });
+      else res.end();
+    }
+  };
+}
+
+/**
+ * Listen on host:port; resolves with the server once it is accepting connections
+ */
+export async function startIndexServer(options: IndexServerOptions & { host: string; port: number }): Promise<http.Server> {
+  const server = http.createServer(createIndexServerHandler(options));
+  await new Promise<void>((resolve, reject) => {
+    server.once('error', reject);
+    server.listen(options.port, options.host, () => resolve());
+  });
+  return server;
+}
@@ -4,6 +4,7 @@
 
 import { describe, it, expect, vi, beforeEach } from 'vitest';
 import { VectorManager, VectorManagerOptions, createVectorManager } from './index.js';
+import { QdrantStore } from './stores/qdrant-store.js';
 
 // Mock Qdrant client to avoid actual connections
 vi.mock('@qdrant/js-client-rest', () => ({
@@ -262,7 +263,9 @@ describe('VectorManager Level-Aware Search', () => {
       scroll: vi.fn().mockResolvedValue({ points: [], next_page_offset: null }),
       getCollection: vi.fn().mockResolvedValue({ config: { params: { vectors: { size: 768 } } } })
     };
-    (manager as any).client = mockClient;
+    const store = new QdrantStore({ url: 'http://localhost:6333' });
+    (store as any).client = mockClient;
+    (manager as any).store = store;
   });
 
   describe('searchByLevel', () => {
@@ -1,9 +1,9 @@
 /**
  * Vector Database Manager
- * Manages embeddings and semantic search using Qdrant
+ * Manages embeddings and semantic search over a VectorStore (Qdrant or an embedded index)
  */
 
-import { QdrantClient } from '@qdrant/js-client-rest';
+import * as path from 'path';
 import OpenAI from 'openai';
 import {
   VectorSearchResult,
@@ -12,6 +12,7 @@ import {
   CommitPayload,
   VectorError,
   CodeChunk,
+  DocumentChunk,
   VectorPayload,
   HierarchicalSummaryPayload,
   HierarchyLevel
@@ -19,6 +20,18 @@ import {
 import { chunkArray } from '@cv-git/shared';
 import { EmbeddingCache, createEmbeddingCache, CacheStats } from './embedding-cache.js';
 import { getVectorCollectionName } from '../storage/repo-id.js';
+import { FixtureRecorder } from '../fixtures/index.js';
+import { LocalEmbedder, DEFAULT_LOCAL_EMBEDDING_MODEL } from './local-embeddings.js';
+import { EMBEDDING_BATCH_LIMITS, EMBEDDING_MODELS, EmbeddingProvider, EmbeddingProviderName, embedInBatches } from './providers.js';
+import type { VectorStore, VectorStoreType, VectorFilter } from './store.js';
+import { createVectorStore, resolveVectorStoreType } from './store-factory.js';
+import type { HnswOptions } from './stores/hnsw-store.js';
+import type { QuantizationOptions } from './quantization.js';
+import { AliasedVectorStore } from './stores/aliased-store.js';
+import { LayeredVectorStore } from './stores/layered-store.js';
+import { RemoteVectorStore, RemoteStoreOptions } from './stores/remote-store.js';
+import { getProviderLimiter, limitedFetch, providerFetch } from '../ai/http.js';
+import { branchCondition, chunkPointKey, getCurrentNamespace, loadBranchIndex, withBranchScope } from './branches.js';
 
 export interface VectorCollections {
   codeChunks: string;
@@ -28,27 +41,6 @@ export interface VectorCollections {
   summaries: string;  // Hierarchical summaries (symbol, file, directory, repo)
 }
 
-// Embedding model configurations with their vector dimensions
-const EMBEDDING_MODELS: Record<string, { dimension: number; provider: 'openai' | 'openrouter' | 'ollama' | 'lmstudio' }> = {
-  // OpenAI models (direct)
-  'text-embedding-3-small': { dimension: 1536, provider: 'openai' },
-  'text-embedding-3-large': { dimension: 3072, provider: 'openai' },
-  'text-embedding-ada-002': { dimension: 1536, provider: 'openai' },
-  // OpenRouter models (uses OpenAI-compatible API)
-  'openai/text-embedding-3-small': { dimension: 1536, provider: 'openrouter' },
-  'openai/text-embedding-3-large': { dimension: 3072, provider: 'openrouter' },
-  'openai/text-embedding-ada-002': { dimension: 1536, provider: 'openrouter' },
-  // Ollama models (local)
-  'nomic-embed-text': { dimension: 768, provider: 'ollama' },
-  'mxbai-embed-large': { dimension: 1024, provider: 'ollama' },
-  'all-minilm': { dimension: 384, provider: 'ollama' },
-  'snowflake-arctic-embed': { dimension: 1024, provider: 'ollama' },
-  // LM Studio models (local, OpenAI-compatible API)
-  // Model IDs are runtime-fetched; these are common defaults
-  'nomic-ai/nomic-embed-text-v1.5-gguf': { dimension: 768, provider: 'lmstudio' },
-  'text-embedding-bge-small-en-v1.5': { dimension: 384, provider: 'lmstudio' },
-};
-
 // Model fallback order for OpenRouter (preferred)
 const OPENROUTER_MODEL_ORDER = [
   'openai/text-embedding-3-small',
@@ -69,9 +61,37 @@ const OLLAMA_MODEL_ORDER = [
   'all-minilm'
 ];
 
+/**
+ * Batch embedding counters since the manager was created
+ */
+export interface EmbeddingUsage {
+  /** Texts passed to embedBatch() */
+  requested: number;
+  /** Served from the embedding cache */
+  cached: number;
+  /** Sent to the provider */
+  embedded: number;
+  /** ~4 characters per token for the texts sent */
+  estimatedTokens: number;
+}
+
 export interface VectorManagerOptions {
   /** Qdrant URL */
   url: string;
+  /** Storage backend (config.vector.provider): qdrant (default), lancedb, sqlite-vec or jsonl */
+  storeType?: string;
+  /** Directory for embedded backends (default: .cv/index) */
+  indexDir?: string;
+  /** HNSW settings (config.vector.hnsw) */
+  hnsw?: HnswOptions;
+  /** Pre-built store; takes precedence over storeType */
+  store?: VectorStore;
+  /** Collection name → collection holding it (config.vector.aliases) */
+  aliases?: Record<string, string>;
+  /** Vector codec (config.vector.quantization) */
+  quantization?: QuantizationOptions;
+  /** Shared index served by `cv index serve` (config.index.remote) */
+  remote?: RemoteStoreOptions;
   /** Repository ID - when provided, uses isolated collections {repoId}_{collection} */
   repoId?: string;
   /** OpenRouter API key (preferred for embeddings) */
@@ -86,21 +106,41 @@ export interface VectorManagerOptions {
   ollamaUrl?: string;
   /** LM Studio URL for local embeddings (default: http://localhost:1234/v1) */
   lmstudioUrl?: string;
+  /** Run this in-process model (e.g. local/bge-small-en-v1.5) instead of an embedding API */
+  localModel?: string;
+  /** Embedding backend from resolveProvider(); takes precedence over the key and URL options */
+  provider?: EmbeddingProvider;
   /** Enable content-addressed embedding cache */
   enableCache?: boolean;
-  /** Cache directory (default: .cv/embeddings) */
+  /** Cache directory (default: .cv/embeddings next to indexDir) */
   cacheDir?: string;
   /** Vector dimension size (default: auto-detected from model, 1536 for OpenAI, 768 for Ollama nomic-embed-text) */
   vectorSize?: number;
+  /** Repository root; enables branch-scoped code search */
+  repoRoot?: string;
+  /** Namespace code chunks by branch (config.vector.branchNamespaces, default: true when repoRoot is set) */
+  branchNamespaces?: boolean;
 }
 
 export class VectorManager {
-  private client: QdrantClient | null = null;
+  private store: VectorStore | null = null;
+  private storeOptions: {
+    type?: string;
+    indexDir?: string;
+    hnsw?: HnswOptions;
+    store?: VectorStore;
+    aliases?: Record<string, string>;
+    quantization?: QuantizationOptions;
+    remote?: RemoteStoreOptions;
+  };
   private openai: OpenAI | null = null;
   private openrouter: OpenAI | null = null;
+  private localEmbedder: LocalEmbedder | null = null;
+  private provider?: EmbeddingProvider;
+  private explicitVectorSize: boolean;
   private collections: VectorCollections;
   private embeddingModel: string;
-  private embeddingProvider: 'openai' | 'openrouter' | 'ollama' | 'lmstudio';
+  private embeddingProvider: EmbeddingProviderName;
   private ollamaUrl: string;
   private lmstudioUrl: string;
   private openrouterApiKey?: string;
@@ -113,6 +153,10 @@ export class VectorManager {
   private cacheEnabled: boolean = false;
   private cacheDir: string;
   private repoId?: string;
+  private fixtureRecorder?: FixtureRecorder;
+  private usage: EmbeddingUsage = { requested: 0, cached: 0, embedded: 0, estimatedTokens: 0 };
+  private repoRoot?: string;
+  private branchNamespaces: boolean;
 
   constructor(options: VectorManagerOptions);
   /** @deprecated Use options object instead */
@@ -140,11 +184,27 @@ export class VectorManager {
 
     this.url = opts.url;
     this.repoId = opts.repoId;
+    this.repoRoot = opts.repoRoot;
+    this.branchNamespaces = !!opts.repoRoot && opts.branchNamespaces !== false;
+    this.storeOptions = {
+      type: opts.storeType,
+      indexDir: opts.indexDir,
+      hnsw: opts.hnsw,
+      store: opts.store,
+      aliases: opts.aliases,
+      quantization: opts.quantization,
+      remote: opts.remote
+    };
     this.ollamaUrl = opts.ollamaUrl || process.env.OLLAMA_URL || process.env.CV_OLLAMA_URL || 'http://127.0.0.1:11434';
     this.lmstudioUrl = opts.lmstudioUrl || process.env.CV_LMSTUDIO_URL || process.env.LMSTUDIO_URL || 'http://127.0.0.1:1234/v1';
 
+    // In-process embeddings: explicit option, or CV_EMBEDDING_PROVIDER=local with CV_EMBEDDING_MODEL
+    const localModel = opts.localModel ||
+      (process.env.CV_EMBEDDING_PROVIDER === 'local' ? process.env.CV_EMBEDDING_MODEL || DEFAULT_LOCAL_EMBEDDING_MODEL : undefined);
+
     // If a local provider URL is explicitly provided, don't auto-detect cloud API keys from env
-    const useLocal = !!opts.ollamaUrl || !!opts.lmstudioUrl;
+    this.provider = opts.provider;
+    const useLocal = !!opts.ollamaUrl || !!opts.lmstudioUrl || !!localModel || !!this.provider;
     this.openaiApiKey = useLocal ? undefined : opts.openaiApiKey;
     this.openrouterApiKey = useLocal ? undefined : (opts.openrouterApiKey || process.env.OPENROUTER_API_KEY);
 
@@ -172,7 +232,8 @@ export class VectorManager {
 
     // Cache settings
     this.cacheEnabled = opts.enableCache ?? true;  // Enabled by default
-    this.cacheDir = opts.cacheDir ?? '.cv/embeddings';
+    this.cacheDir = opts.cacheDir ??
+      (opts.indexDir ? path.join(path.dirname(opts.indexDir), 'embeddings') : '.cv/embeddings');
 
     // Default model based on available provider
     // Local (Ollama/LM Studio) > OpenRouter > OpenAI
@@ -184,11 +245,13 @@ export class VectorManager {
           ? 'openai/text-embedding-3-small'
           : 'text-embedding-3-small';
 
-    this.embeddingModel = opts.embeddingModel || process.env.CV_EMBEDDING_MODEL || defaultModel;
+    this.embeddingModel = localModel || opts.embeddingModel || process.env.CV_EMBEDDING_MODEL || defaultModel;
 
     // Determine provider from model name or available keys
     const modelConfig = EMBEDDING_MODELS[this.embeddingModel];
-    if (modelConfig) {
+    if (localModel) {
+      this.embeddingProvider = 'local';
+    } else if (modelConfig) {
       this.embeddingProvider = modelConfig.provider;
     } else if (this.openrouterApiKey) {
       this.embeddingProvider = 'openrouter';
@@ -199,23 +262,44 @@ export class VectorManager {
     }
 
     this.vectorSize = opts.vectorSize || modelConfig?.dimension || 1536;
+    this.explicitVectorSize = !!opts.vectorSize;
+
+    if (this.provider) {
+      this.embeddingProvider = this.provider.name;
+      this.embeddingModel = this.provider.model;
+      this.vectorSize = opts.vectorSize || this.provider.dimension;
+    }
   }
 
   /**
-   * Connect to Qdrant and initialize embedding provider
+   * Connect to the vector store and initialize embedding provider
    * Provider priority: OpenRouter > OpenAI > Ollama
    */
   async connect(): Promise<void> {
     try {
-      // Initialize Qdrant client
-      this.client = new QdrantClient({ url: this.url });
-
-      // Test connection
-      await this.client.getCollections();
+      // Open the vector store (Qdrant server or embedded index)
+      this.store = this.storeOptions.store || await createVectorStore({
+        type: this.storeOptions.type,
+        url: this.url,
+        indexDir: this.storeOptions.indexDir,
+        hnsw: this.storeOptions.hnsw,
+        quantization: this.storeOptions.quantization,
+        aliases: this.storeOptions.aliases,
+        remote: this.storeOptions.remote
+      });
+      await this.store.connect();
 
       // Initialize embedding provider based on what's available
-      // Priority: Explicit local > OpenRouter > OpenAI > auto-detect local
-      if (this.embeddingProvider === 'lmstudio') {
+      // Priority: Resolved provider > Explicit local > OpenRouter > OpenAI > auto-detect local
+      if (this.provider) {
+        // Local servers may substitute a model that is actually loaded
+        await this.provider.init?.();
+        this.embeddingModel = this.provider.model;
+        if (!this.explicitVectorSize) this.vectorSize = this.provider.dimension;
+      } else if (this.embeddingProvider === 'local') {
+        // In-process model - nothing leaves the machine
+        await this.initLocal();
+      } else if (this.embeddingProvider === 'lmstudio') {
         // Explicit LM Studio request — uses OpenAI-compatible API
         await this.initLMStudio();
       } else if (this.embeddingProvider === 'ollama') {
@@ -225,7 +309,9 @@ export class VectorManager {
         // OpenRouter available - use it (preferred)
         this.openrouter = new OpenAI({
           apiKey: this.openrouterApiKey,
-          baseURL: 'https://openrouter.ai/api/v1'
+          baseURL: 'https://openrouter.ai/api/v1',
+          fetch: limitedFetch('openrouter'),
+          maxRetries: 0
         });
         this.embeddingProvider = 'openrouter';
         // Use OpenRouter model naming
@@ -234,7 +320,7 @@ export class VectorManager {
         }
       } else if (this.openaiApiKey) {
         // Fall back to OpenAI
-        this.openai = new OpenAI({ apiKey: this.openaiApiKey });
+        this.openai = new OpenAI({ apiKey: this.openaiApiKey, fetch: limitedFetch('openai'), maxRetries: 0 });
         this.embeddingProvider = 'openai';
         // Use OpenAI model naming (strip openai/ prefix if present)
         if (this.embeddingModel.startsWith('openai/')) {
@@ -261,6 +347,7 @@ export class VectorManager {
         }
       }
 
+      this.checkSharedIndexEmbedding();
       this.connected = true;
 
       // Initialize embedding cache if enabled
@@ -277,7 +364,26 @@ export class VectorManager {
       await this.ensureCollections();
 
     } catch (error: any) {
-      throw new VectorError(`Failed to connect to Qdrant: ${error.message}`, error);
+      throw new VectorError(`Failed to connect to ${this.getStoreType()} vector store: ${error.message}`, error);
+    }
+  }
+
+  /**
+   * A shared index only answers queries embedded with the model that built it
+   */
+  private checkSharedIndexEmbedding(): void {
+    const remote = this.getSharedIndex()?.base;
+    if (!(remote instanceof RemoteVectorStore)) return;
+    const { embedding } = remote.info();
+    const normalize = (model: string) => model.replace(/^openai\//, '');
+    const modelDiffers = !!embedding?.model && normalize(embedding.model) !== normalize(this.embeddingModel);
+    const sizeDiffers = !!embedding?.dimensions && embedding.dimensions !== this.vectorSize;
+    if (modelDiffers || sizeDiffers) {
+      throw new VectorError(
+        `The shared index at ${remote.url} was embedded with ${embedding?.model ?? 'another model'}` +
+        `${embedding?.dimensions ? ` (${embedding.dimensions} dimensions)` : ''}, ` +
+        `not ${this.embeddingModel} (${this.vectorSize}); set embedding.model to match`
+      );
     }
   }
 
@@ -349,7 +455,7 @@ export class VectorManager {
       console.log(`[VectorManager] Ollama embedding request: url=${this.ollamaUrl}, model=${this.embeddingModel}, textLen=${text.length}${text.length > maxLength ? ' (truncated)' : ''}`);
     }
 
-    const response = await fetch(`${this.ollamaUrl}/api/embeddings`, {
+    const response = await providerFetch('ollama', `${this.ollamaUrl}/api/embeddings`, {
       method: 'POST',
       headers: { 'Content-Type': 'application/json' },
       body: JSON.stringify({
@@ -528,8 +634,8 @@ export class VectorManager {
    * Ensure all collections exist
    */
   private async ensureCollections(): Promise<void> {
-    if (!this.client) {
-      throw new VectorError('Not connected to Qdrant');
+    if (!this.store) {
+      throw new VectorError('Not connected to vector store');
     }
 
     // Create code chunks collection
@@ -552,37 +658,45 @@ export class VectorManager {
    * Create collection if not exists
    */
   async ensureCollection(name: string, vectorSize: number): Promise<void> {
-    if (!this.client) {
-      throw new VectorError('Not connected to Qdrant');
+    if (!this.store) {
+      throw new VectorError('Not connected to vector store');
     }
 
     try {
-      // Check if collection exists
-      const collections = await this.client.getCollections();
-      const exists = collections.collections.some(c => c.name === name);
-
-      if (!exists) {
-        // Create collection
-        await this.client.createCollection(name, {
-          vectors: {
-            size: vectorSize,
-            distance: 'Cosine'
-          }
-        });
-      }
+      // Create collection if it doesn't exist
+      await this.store.createCollection(name, vectorSize);
     } catch (error: any) {
       throw new VectorError(`Failed to ensure collection ${name}: ${error.message}`, error);
     }
   }
 
+  /**
+   * Record embedding calls (provider, model, dimensions) into a fixture
+   */
+  setFixtureRecorder(recorder: FixtureRecorder | undefined): void {
+    this.fixtureRecorder = recorder;
+  }
+
+  private recordEmbeddings(embeddings: number[][], inputs: number, cached: number): void {
+    this.fixtureRecorder?.record({
+      type: 'embedding',
+      provider: this.embeddingProvider,
+      model: this.embeddingModel,
+      inputs,
+      dimensions: Array.from(new Set(embeddings.map(e => e?.length ?? 0))),
+      cached
+    });
+  }
+
   /**
    * Generate embedding for text (with content-addressed caching)
    */
   async embed(text: string): Promise<number[]> {
     // Check cache first
     if (this.cache) {
-      const cached = await this.cache.get(text);
+      const cached = await this.cache.get(text, this.embeddingModel);
       if (cached) {
+        this.recordEmbeddings([cached], 1, 1);
         return cached;
       }
     }
@@ -591,7 +705,11 @@ export class VectorManager {
     let embedding: number[];
 
     // Use the appropriate provider
-    if (this.embeddingProvider === 'lmstudio') {
+    if (this.provider) {
+      [embedding] = await embedInBatches(this.provider, [text]);
+    } else if (this.embeddingProvider === 'local') {
+      embedding = await this.requireLocal().embed(text);
+    } else if (this.embeddingProvider === 'lmstudio') {
       embedding = await this.embedWithLMStudio(text);
     } else if (this.embeddingProvider === 'ollama') {
       embedding = await this.embedWithOllama(text);
@@ -622,12 +740,35 @@ export class VectorManager {
 
     // Store in cache
     if (this.cache) {
-      await this.cache.set(text, embedding);
+      await this.cache.set(text, embedding, this.embeddingModel);
     }
 
+    this.recordEmbeddings([embedding], 1, 0);
     return embedding;
   }
 
+  /**
+   * Load the in-process embedding model
+   */
+  private async initLocal(): Promise<void> {
+    try {
+      this.localEmbedder = new LocalEmbedder(this.embeddingModel);
+      await this.localEmbedder.load();
+      this.embeddingModel = this.localEmbedder.model.name;
+      this.vectorSize = this.localEmbedder.model.dimension;
+    } catch (error: any) {
+      this.localEmbedder = null;
+      throw new VectorError(`Failed to load local embedding model: ${error.message}`, error);
+    }
+  }
+
+  private requireLocal(): LocalEmbedder {
+    if (!this.localEmbedder) {
+      throw new VectorError('Local embedding model not loaded');
+    }
+    return this.localEmbedder;
+  }
+
   /**
    * Check if Ollama is available
    */
@@ -734,7 +875,7 @@ export class VectorManager {
       console.log(`[VectorManager] LM Studio embedding request: url=${this.lmstudioUrl}, model=${this.embeddingModel}, textLen=${text.length}${text.length > maxLength ? ' (truncated)' : ''}`);
     }
 
-    const response = await fetch(`${this.lmstudioUrl}/embeddings`, {
+    const response = await providerFetch('lmstudio', `${this.lmstudioUrl}/embeddings`, {
       method: 'POST',
       headers: {
         'Content-Type': 'application/json',
@@ -806,7 +947,17 @@ export class VectorManager {
    * Try to generate embeddings with automatic model fallback (including OpenRouter and Ollama)
    */
   private async tryEmbeddingWithFallback(input: string | string[]): Promise<{ embeddings: number[][]; model: string }> {
+    if (this.provider) {
+      const texts = Array.isArray(input) ? input : [input];
+      return { embeddings: await embedInBatches(this.provider, texts), model: this.provider.model };
+    }
+
     // If using a local provider, use it directly
+    if (this.embeddingProvider === 'local') {
+      const texts = Array.isArray(input) ? input : [input];
+      return { embeddings: await this.requireLocal().embedBatch(texts), model: this.embeddingModel };
+    }
+
     if (this.embeddingProvider === 'lmstudio') {
       const texts = Array.isArray(input) ? input : [input];
       const embeddings = await this.embedBatchWithLMStudio(texts);
@@ -888,7 +1039,9 @@ export class VectorManager {
       try {
         this.openrouter = new OpenAI({
           apiKey: this.openrouterApiKey,
-          baseURL: 'https://openrouter.ai/api/v1'
+          baseURL: 'https://openrouter.ai/api/v1',
+          fetch: limitedFetch('openrouter'),
+          maxRetries: 0
         });
         this.embeddingProvider = 'openrouter';
         this.embeddingModel = 'openai/text-embedding-3-small';
@@ -946,7 +1099,7 @@ export class VectorManager {
     const cachedEmbeddings = new Map<string, number[]>();
 
     if (this.cache) {
-      const cacheResult = await this.cache.getBatch(texts);
+      const cacheResult = await this.cache.getBatch(texts, this.embeddingModel);
       for (const [text, embedding] of cacheResult.cached) {
         cachedEmbeddings.set(text, embedding);
       }
@@ -957,12 +1110,25 @@ export class VectorManager {
       }
     }
 
+    this.usage.requested += texts.length;
+    this.usage.cached += texts.length - textsToEmbed.length;
+    this.usage.embedded += textsToEmbed.length;
+    this.usage.estimatedTokens += textsToEmbed.reduce((sum, t) => sum + Math.ceil(t.length / 4), 0);
+
     // Generate embeddings for missing texts
     let newEmbeddings: number[][] = [];
 
     if (textsToEmbed.length > 0) {
+      // Resolved provider, split by its batch limit
+      if (this.provider) {
+        newEmbeddings = await embedInBatches(this.provider, textsToEmbed);
+      }
+      // In-process model
+      else if (this.embeddingProvider === 'local') {
+        newEmbeddings = await this.requireLocal().embedBatch(textsToEmbed);
+      }
       // If using LM Studio, use LM Studio batch
-      if (this.embeddingProvider === 'lmstudio') {
+      else if (this.embeddingProvider === 'lmstudio') {
         newEmbeddings = await this.embedBatchWithLMStudio(textsToEmbed);
       }
       // If using Ollama, use Ollama batch
@@ -1040,7 +1206,7 @@ export class VectorManager {
         for (let i = 0; i < textsToEmbed.length; i++) {
           newCache.set(textsToEmbed[i], newEmbeddings[i]);
         }
-        await this.cache.setBatch(newCache);
+        await this.cache.setBatch(newCache, this.embeddingModel);
       }
     }
 
@@ -1057,6 +1223,7 @@ export class VectorManager {
       }
     }
 
+    this.recordEmbeddings(result, texts.length, cachedEmbeddings.size);
     return result;
   }
 
@@ -1069,21 +1236,18 @@ export class VectorManager {
     vector: number[],
     payload: any
   ): Promise<void> {
-    if (!this.client) {
-      throw new VectorError('Not connected to Qdrant');
+    if (!this.store) {
+      throw new VectorError('Not connected to vector store');
     }
 
     try {
-      await this.client.upsert(collection, {
-        wait: true,
-        points: [
-          {
-            id: this.hashId(id),
-            vector,
-            payload: { ...payload, _id: id }
-          }
-        ]
-      });
+      await this.store.upsert(collection, [
+        {
+          id: this.hashId(id),
+          vector,
+          payload: { ...payload, _id: id }
+        }
+      ]);
     } catch (error: any) {
       throw new VectorError(`Failed to upsert vector: ${error.message}`, error);
     }
@@ -1096,8 +1260,8 @@ export class VectorManager {
     collection: string,
     items: Array<{ id: string; vector: number[]; payload: any }>
   ): Promise<void> {
-    if (!this.client) {
-      throw new VectorError('Not connected to Qdrant');
+    if (!this.store) {
+      throw new VectorError('Not connected to vector store');
     }
 
     try {
@@ -1106,14 +1270,11 @@ export class VectorManager {
       const batches = chunkArray(items, batchSize);
 
       for (const batch of batches) {
-        await this.client.upsert(collection, {
-          wait: true,
-          points: batch.map(item => ({
-            id: this.hashId(item.id),
-            vector: item.vector,
-            payload: { ...item.payload, _id: item.id }
-          }))
-        });
+        await this.store.upsert(collection, batch.map(item => ({
+          id: this.hashId(item.id),
+          vector: item.vector,
+          payload: { ...item.payload, _id: item.id }
+        })));
       }
     } catch (error: any) {
       throw new VectorError(`Failed to batch upsert: ${error.message}`, error);
@@ -1129,8 +1290,8 @@ export class VectorManager {
     limit: number = 10,
     filter?: any
   ): Promise<VectorSearchResult<T>[]> {
-    if (!this.client) {
-      throw new VectorError('Not connected to Qdrant');
+    if (!this.store) {
+      throw new VectorError('Not connected to vector store');
     }
 
     try {
@@ -1145,13 +1306,14 @@ export class VectorManager {
         console.log(`[VectorManager] Generated embedding of length ${queryVector.length}`);
       }
 
+      // Code chunks: only those on the checked-out branch
+      if (collection === this.collections.codeChunks || collection === 'code_chunks') {
+        const namespace = await this.getSearchNamespace();
+        if (namespace) filter = withBranchScope(filter, namespace);
+      }
+
       // Search
-      const results = await this.client.search(collection, {
-        vector: queryVector,
-        limit,
-        filter,
-        with_payload: true
-      });
+      const results = await this.store.search(collection, queryVector, { limit, filter });
 
       if (process.env.CV_DEBUG) {
         console.log(`[VectorManager] Search returned ${results.length} raw results`);
@@ -1182,6 +1344,8 @@ export class VectorManager {
     options?: {
       language?: string;
       file?: string;
+      /** Only chunks of this monorepo package (`payload.package`) */
+      package?: string;
       minScore?: number;
     }
   ): Promise<VectorSearchResult<CodeChunkPayload>[]> {
@@ -1203,6 +1367,14 @@ export class VectorManager {
       });
     }
 
+    if (options?.package) {
+      filter.must = filter.must || [];
+      filter.must.push({
+        key: 'package',
+        match: { value: options.package }
+      });
+    }
+
     const results = await this.search<CodeChunkPayload>(
       this.collections.codeChunks,
       query,
@@ -1301,21 +1473,20 @@ export class VectorManager {
    * @param parentId - Parent summary ID
    */
   async getSummaryChildren(parentId: string): Promise<VectorSearchResult<HierarchicalSummaryPayload>[]> {
-    if (!this.client) {
-      throw new VectorError('Not connected to Qdrant');
+    if (!this.store) {
+      throw new VectorError('Not connected to vector store');
     }
 
     try {
       // Search for summaries that have this parent
-      const results = await this.client.scroll(this.collections.summaries, {
+      const results = await this.store.scroll(this.collections.summaries, {
         filter: {
           must: [
             { key: 'parent', match: { value: parentId } }
           ]
         },
         limit: 100,
-        with_payload: true,
-        with_vector: false
+        withVector: false
       });
 
       return results.points.map(point => ({
@@ -1333,20 +1504,19 @@ export class VectorManager {
    * @param summaryId - Summary ID
    */
   async getSummary(summaryId: string): Promise<HierarchicalSummaryPayload | null> {
-    if (!this.client) {
-      throw new VectorError('Not connected to Qdrant');
+    if (!this.store) {
+      throw new VectorError('Not connected to vector store');
     }
 
     try {
-      const results = await this.client.scroll(this.collections.summaries, {
+      const results = await this.store.scroll(this.collections.summaries, {
         filter: {
           must: [
             { key: '_id', match: { value: summaryId } }
           ]
         },
         limit: 1,
-        with_payload: true,
-        with_vector: false
+        withVector: false
       });
 
       if (results.points.length === 0) {
@@ -1427,30 +1597,190 @@ export class VectorManager {
    * Delete vector by ID
    */
   async delete(collection: string, id: string): Promise<void> {
-    if (!this.client) {
-      throw new VectorError('Not connected to Qdrant');
+    if (!this.store) {
+      throw new VectorError('Not connected to vector store');
     }
 
     try {
-      await this.client.delete(collection, {
-        wait: true,
-        points: [this.hashId(id)]
-      });
+      await this.store.delete(collection, [this.hashId(id)]);
     } catch (error: any) {
       throw new VectorError(`Failed to delete vector: ${error.message}`, error);
     }
   }
 
+  /**
+   * Delete every point whose payload `file` is one of the given paths
+   * @returns number of points removed
+   */
+  async deleteByFiles(collection: string, files: string[]): Promise<number> {
+    if (!this.store) {
+      throw new VectorError('Not connected to vector store');
+    }
+    if (files.length === 0) return 0;
+
+    try {
+      const filter: VectorFilter = { must: [{ key: 'file', match: { any: files } }] };
+      const ids: Array<string | number> = [];
+      let offset: string | undefined;
+      do {
+        const page = await this.store.scroll(collection, { limit: 1000, offset, filter, withVector: false });
+        ids.push(...page.points.map(p => p.id));
+        offset = page.nextOffset;
+      } while (offset);
+
+      for (const batch of chunkArray(ids, 1000)) {
+        await this.store.delete(collection, batch);
+      }
+      return ids.length;
+    } catch (error: any) {
+      throw new VectorError(`Failed to delete vectors: ${error.message}`, error);
+    }
+  }
+
+  // ========== Branch Namespaces ==========
+
+  /**
+   * Whether code chunks are namespaced by branch
+   */
+  isBranchScoped(): boolean {
+    return this.branchNamespaces;
+  }
+
+  /**
+   * Branch code searches are filtered to: the checked-out branch, once it
+   * has been synced
+   */
+  async getSearchNamespace(): Promise<string | null> {
+    if (!this.branchNamespaces || !this.repoRoot) return null;
+    const namespace = getCurrentNamespace(this.repoRoot);
+    if (!namespace) return null;
+    const index = await loadBranchIndex(this.repoRoot);
+    return index.branches[namespace] ? namespace : null;
+  }
+
+  /**
+   * Upsert code chunks on a branch. Points are keyed by chunk id and content,
+   * so a chunk already stored for another branch gains this branch instead
+   * of being duplicated.
+   */
+  async upsertBranchChunks(
+    collection: string,
+    items: Array<{ id: string; vector: number[]; payload: any }>,
+    namespace: string
+  ): Promise<void> {
+    if (!this.store) {
+      throw new VectorError('Not connected to vector store');
+    }
+
+    try {
+      for (const batch of chunkArray(items, 100)) {
+        const keyed = batch.map(item => ({ item, pointId: this.hashId(chunkPointKey(item.id, item.payload.text ?? '')) }));
+        const existing = await this.store.retrieve(collection, keyed.map(k => k.pointId));
+        const branchesById = new Map(existing.map(point => [String(point.id), (point.payload.branches as string[] | undefined) ?? []]));
+
+        await this.store.upsert(collection, keyed.map(({ item, pointId }) => ({
+          id: pointId,
+          vector: item.vector,
+          payload: {
+            ...item.payload,
+            _id: item.id,
+            branches: [...new Set([...(branchesById.get(String(pointId)) ?? []), namespace])]
+          }
+        })));
+      }
+    } catch (error: any) {
+      throw new VectorError(`Failed to upsert branch chunks: ${error.message}`, error);
+    }
+  }
+
+  /**
+   * Take a branch off the points of `files` (every point when files is
+   * null); points left on no branch are deleted
+   * @returns number of points deleted
+   */
+  async removeBranch(collection: string, files: string[] | null, namespace: string): Promise<number> {
+    if (files && files.length === 0) return 0;
+    const must: VectorFilter['must'] = [branchCondition(namespace)];
+    if (files) must.push({ key: 'file', match: { any: files } });
+
+    return this.retagPoints(collection, { must }, branches => branches.filter(b => b !== namespace));
+  }
+
+  /**
+   * Put a branch on the points of `files`, taking from `fromNamespace` the
+   * points that describe their current content (untagged points from
+   * before namespacing when it is unset)
+   * @returns number of points tagged
+   */
+  async addBranch(collection: string, files: string[], namespace: string, fromNamespace?: string): Promise<number> {
+    if (files.length === 0) return 0;
+    const must: VectorFilter['must'] = [{ key: 'file', match: { any: files } }];
+    if (fromNamespace) must.push(branchCondition(fromNamespace));
+
+    let tagged = 0;
+    await this.retagPoints(collection, { must, must_not: [branchCondition(namespace)] }, branches => {
+      tagged++;
+      return [...branches, namespace];
+    });
+    return tagged;
+  }
+
+  /**
+   * Rewrite `payload.branches` of every point matching `filter`
+   * @returns number of points deleted because no branch was left
+   */
+  private async retagPoints(
+    collection: string,
+    filter: VectorFilter,
+    update: (branches: string[]) => string[]
+  ): Promise<number> {
+    if (!this.store) {
+      throw new VectorError('Not connected to vector store');
+    }
+
+    try {
+      // Collect ids first: retagging changes which points match the filter
+      const ids: Array<string | number> = [];
+      let offset: string | undefined;
+      do {
+        const page = await this.store.scroll(collection, { limit: 1000, offset, filter, withVector: false });
+        ids.push(...page.points.map(p => p.id));
+        offset = page.nextOffset;
+      } while (offset);
+
+      let deleted = 0;
+      for (const batch of chunkArray(ids, 100)) {
+        const points = await this.store.retrieve(collection, batch, { withVector: true });
+        const emptied: Array<string | number> = [];
+        const kept = [];
+        for (const point of points) {
+          const branches = update((point.payload.branches as string[] | undefined) ?? []);
+          if (branches.length === 0) {
+            emptied.push(point.id);
+          } else {
+            kept.push({ ...point, payload: { ...point.payload, branches } });
+          }
+        }
+        if (kept.length > 0) await this.store.upsert(collection, kept);
+        if (emptied.length > 0) await this.store.delete(collection, emptied);
+        deleted += emptied.length;
+      }
+      return deleted;
+    } catch (error: any) {
+      throw new VectorError(`Failed to update branch namespaces: ${error.message}`, error);
+    }
+  }
+
   /**
    * Clear entire collection
    */
   async clearCollection(collection: string): Promise<void> {
-    if (!this.client) {
-      throw new VectorError('Not connected to Qdrant');
+    if (!this.store) {
+      throw new VectorError('Not connected to vector store');
     }
 
     try {
-      await this.client.deleteCollection(collection);
+      await this.store.deleteCollection(collection);
       await this.ensureCollection(collection, this.vectorSize);
     } catch (error: any) {
       throw new VectorError(`Failed to clear collection: ${error.message}`, error);
@@ -1461,12 +1791,20 @@ export class VectorManager {
    * Get collection statistics
    */
   async getCollectionInfo(collection: string): Promise<any> {
-    if (!this.client) {
-      throw new VectorError('Not connected to Qdrant');
+    if (!this.store) {
+      throw new VectorError('Not connected to vector store');
     }
 
     try {
-      return await this.client.getCollection(collection);
+      const stats = await this.store.getCollectionStats(collection);
+      if (!stats) {
+        throw new Error(`Collection ${collection} not found`);
+      }
+      // Qdrant-shaped so callers can keep reading points_count / config.params.vectors.size
+      return {
+        points_count: stats.pointsCount,
+        config: { params: { vectors: { size: stats.vectorSize, distance: 'Cosine' } } }
+      };
     } catch (error: any) {
       throw new VectorError(`Failed to get collection info: ${error.message}`, error);
     }
@@ -1486,14 +1824,22 @@ export class VectorManager {
     needsRecreation: boolean;
     pointCount?: number;
   }> {
-    if (!this.client) {
-      throw new VectorError('Not connected to Qdrant');
+    if (!this.store) {
+      throw new VectorError('Not connected to vector store');
     }
 
     try {
-      const info = await this.client.getCollection(collection);
-      const existingDimensions = info.config?.params?.vectors?.size as number;
-      const pointCount = info.points_count ?? undefined;
+      const stats = await this.store.getCollectionStats(collection);
+      if (!stats) {
+        // Collection doesn't exist - compatible by default (will be created)
+        return {
+          compatible: true,
+          requiredDimensions: this.vectorSize,
+          needsRecreation: false
+        };
+      }
+      const existingDimensions = stats.vectorSize;
+      const pointCount = stats.pointsCount;
 
       return {
         compatible: existingDimensions === this.vectorSize,
@@ -1591,7 +1937,7 @@ export class VectorManager {
     const pointsLost = compat.pointCount || 0;
 
     try {
-      await this.client!.deleteCollection(collection);
+      await this.store!.deleteCollection(collection);
     } catch {
       // Collection might not exist
     }
@@ -1634,6 +1980,28 @@ export class VectorManager {
     };
   }
 
+  /**
+   * Backend holding this manager's vectors
+   */
+  getStoreType(): VectorStoreType {
+    return this.store?.type ?? this.storeOptions.store?.type ?? resolveVectorStoreType(this.storeOptions.type);
+  }
+
+  /**
+   * The underlying store, for copying points as they are (null before connect)
+   */
+  getStore(): VectorStore | null {
+    return this.store;
+  }
+
+  /**
+   * The shared index with its local overlay, when config.index.remote is set
+   */
+  getSharedIndex(): LayeredVectorStore | null {
+    const store = this.store instanceof AliasedVectorStore ? this.store.inner : this.store;
+    return store instanceof LayeredVectorStore ? store : null;
+  }
+
   /**
    * Get current embedding model and dimensions
    */
@@ -1649,6 +2017,22 @@ export class VectorManager {
     };
   }
 
+  /**
+   * Texts the active provider takes per embedding request
+   */
+  getEmbeddingBatchSize(): number {
+    return this.provider?.maxBatchSize ?? EMBEDDING_BATCH_LIMITS[this.embeddingProvider] ?? 50;
+  }
+
+  /**
+   * Embedding requests worth having in flight at once: the provider's
+   * concurrency limit, or one for the in-process model
+   */
+  getEmbeddingConcurrency(): number {
+    const name = this.provider?.name ?? this.embeddingProvider;
+    return name === 'local' ? 1 : getProviderLimiter(name).limits.maxConcurrent;
+  }
+
   /**
    * Scroll through all points in a collection
    * Used for exporting vectors to file storage
@@ -1665,34 +2049,16 @@ export class VectorManager {
     }>;
     next_page_offset?: string;
   }> {
-    if (!this.client) {
-      throw new VectorError('Not connected to Qdrant');
+    if (!this.store) {
+      throw new VectorError('Not connected to vector store');
     }
 
     try {
-      // Qdrant scroll API: offset is a point ID (number or string)
-      const scrollOptions: any = {
-        limit,
-        with_vector: true,
-        with_payload: true
-      };
-
-      // Only set offset if provided (skip on first call)
-      if (offset) {
-        // Try to parse as number first, otherwise use string
-        const parsedOffset = parseInt(offset, 10);
-        scrollOptions.offset = isNaN(parsedOffset) ? offset : parsedOffset;
-      }
-
-      const result = await this.client.scroll(collection, scrollOptions);
+      const result = await this.store.scroll(collection, { limit, offset, withVector: true });
 
       return {
-        points: result.points.map(p => ({
-          id: p.id,
-          vector: p.vector as number[],
-          payload: p.payload as Record<string, unknown>
-        })),
-        next_page_offset: result.next_page_offset != null ? String(result.next_page_offset) : undefined
+        points: result.points,
+        next_page_offset: result.nextOffset
       };
     } catch (error: any) {
       throw new VectorError(`Failed to scroll collection: ${error.message}`, error);
@@ -1709,8 +2075,12 @@ export class VectorManager {
       this.cache = null;
     }
 
+    if (this.store) {
+      await this.store.close();
+      this.store = null;
+    }
+
     this.connected = false;
-    this.client = null;
     this.openai = null;
   }
 
@@ -1758,6 +2128,13 @@ export class VectorManager {
     }
   }
 
+  /**
+   * Batch embedding counters (for sync reports)
+   */
+  getEmbeddingUsage(): EmbeddingUsage {
+    return { ...this.usage };
+  }
+
   /**
    * Check if caching is enabled
    */
@@ -1816,6 +2193,11 @@ export class VectorManager {
       parts.push(`// ${chunk.symbolKind}: ${chunk.symbolName}`);
     }
 
+    // Mark summary chunks standing in for oversized/generated files
+    if (chunk.synthetic) {
+      parts.push(`// Summary of ${chunk.syntheticReason === 'generated' ? 'generated' : 'oversized'} file`);
+    }
+
     // Add docstring if available
     if (chunk.docstring) {
       parts.push(`// ${chunk.docstring}`);
@@ -1827,6 +2209,24 @@ export class VectorManager {
 
     return parts.join('\n');
   }
+
+  /**
+   * Prepare document chunk text for embedding
+   */
+  prepareDocumentForEmbedding(chunk: Pick<DocumentChunk, 'documentType' | 'file' | 'heading' | 'tags' | 'text'>): string {
+    const parts: string[] = [];
+    parts.push(`// Document Type: ${chunk.documentType}`);
+    parts.push(`// File: ${chunk.file}`);
+    if (chunk.heading) {
+      parts.push(`// Section: ${chunk.heading}`);
+    }
+    if (chunk.tags?.length > 0) {
+      parts.push(`// Tags: ${chunk.tags.join(', ')}`);
+    }
+    parts.push('');
+    parts.push(chunk.text);
+    return parts.join('\n');
+  }
 }
 
 /**
@@ -1850,7 +2250,122 @@ export function createVectorManager(
 
 // Re-export cache types for external use
 export { EmbeddingCache, createEmbeddingCache, CacheStats } from './embedding-cache.js';
+export {
+  LocalEmbedder,
+  LocalEmbeddingModel,
+  LOCAL_EMBEDDING_MODELS,
+  DEFAULT_LOCAL_EMBEDDING_MODEL,
+  getLocalEmbeddingModel,
+  getLocalModelsDir,
+  isLocalEmbeddingRuntimeAvailable,
+  setLocalEmbeddingRuntime
+} from './local-embeddings.js';
+export {
+  EmbeddingProvider,
+  EmbeddingProviderName,
+  EMBEDDING_MODELS,
+  EMBEDDING_BATCH_LIMITS,
+  DEFAULT_EMBEDDING_MODELS,
+  OpenAIEmbeddingProvider,
+  OpenRouterEmbeddingProvider,
+  LMStudioEmbeddingProvider,
+  OllamaEmbeddingProvider,
+  AzureOpenAIEmbeddingProvider,
+  AzureOpenAIOptions,
+  LocalEmbeddingProvider,
+  ResolveProviderOptions,
+  resolveProvider,
+  embedInBatches
+} from './providers.js';
 export type { EmbeddingMetadata, EmbeddingIndex, EmbeddingCacheConfig } from './embedding-cache.js';
+export {
+  VectorStore,
+  VectorStoreType,
+  VectorPoint,
+  ScoredVectorPoint,
+  VectorCollectionStats,
+  VectorCodecInfo,
+  VectorFilter,
+  matchesFilter,
+  cosineSimilarity
+} from './store.js';
+export {
+  VECTOR_STORE_TYPES,
+  createVectorStore,
+  resolveVectorStoreType,
+  isEmbeddedVectorStore,
+  getVectorIndexDir
+} from './store-factory.js';
+export { migrateVectorStore, VectorMigrationProgress, VectorMigrationResult } from './migrate.js';
+export {
+  collectVectorGarbage,
+  directorySize,
+  VectorGcOptions,
+  VectorGcResult,
+  VectorGcCollectionResult
+} from './gc.js';
+export {
+  BRANCH_INDEX_FILE,
+  BranchIndex,
+  BranchIndexEntry,
+  getCurrentNamespace,
+  loadBranchIndex,
+  recordBranchSync,
+  forgetBranches,
+  chunkPointKey,
+  branchCondition,
+  withBranchScope
+} from './branches.js';
+export { HnswIndex, HnswParams, HnswHit, DEFAULT_HNSW_PARAMS } from './hnsw.js';
+export { HnswVectorStore, HnswOptions, DEFAULT_HNSW_OPTIONS } from './stores/hnsw-store.js';
+export { IndexFile, IndexSection, StringTable, writeIndexFile, readIndexFile } from './index-file.js';
+export { AliasedVectorStore } from './stores/aliased-store.js';
+export { QuantizedVectorStore } from './stores/quantized-store.js';
+export { RemoteVectorStore, RemoteStoreOptions } from './stores/remote-store.js';
+export { LayeredVectorStore } from './stores/layered-store.js';
+export {
+  createIndexServerHandler,
+  startIndexServer,
+  isAuthorized,
+  INDEX_PROTOCOL_VERSION,
+  IndexServerInfo,
+  IndexServerOptions,
+  SharedSyncState,
+  SharedTrackedFile
+} from './index-server.js';
+export {
+  QuantizationCodec,
+  QuantizationOptions,
+  DEFAULT_TRAIN_POINTS,
+  VectorCodec,
+  SerializedCodec,
+  F32Codec,
+  Int8Codec,
+  ProductCodec,
+  codecFromJSON,
+  trainCodec,
+  normalizeVector,
+  defaultSubvectors,
+  vectorBytes
+} from './quantization.js';
+export {
+  REEMBED_STATE_FILE,
+  ReembedCollection,
+  ReembedState,
+  ReembedProgress,
+  ReembedEmbedder,
+  RunReembedOptions,
+  getReembedStatePath,
+  loadReembedState,
+  saveReembedState,
+  clearReembedState,
+  reembedCollectionName,
+  planReembed,
+  reembedText,
+  runReembed,
+  catchUpReembed,
+  reembedConfig
+} from './reembed.js';
 
 /**
  * Standalone embedding generation function
@@ -1873,7 +2388,7 @@ export async function createEmbedding(
   // Prefer OpenRouter if available
   if (openrouterApiKey) {
     const embeddingModel = model || 'openai/text-embedding-3-small';
-    const response = await fetch('https://openrouter.ai/api/v1/embeddings', {
+    const response = await providerFetch('openrouter', 'https://openrouter.ai/api/v1/embeddings', {
       method: 'POST',
       headers: {
         'Authorization': `Bearer ${openrouterApiKey}`,
@@ -1899,7 +2414,7 @@ export async function createEmbedding(
   // Fall back to OpenAI
   if (openaiApiKey) {
     const embeddingModel = model || 'text-embedding-3-small';
-    const response = await fetch('https://api.openai.com/v1/embeddings', {
+    const response = await providerFetch('openai', 'https://api.openai.com/v1/embeddings', {
       method: 'POST',
       headers: {
         'Authorization': `Bearer ${openaiApiKey}`,
@@ -0,0 +1,140 @@
+/**
+ * Local Embeddings
+ * Runs a small ONNX embedding model in-process via @huggingface/transformers,
+ * so indexing and semantic search need no API key and send no code anywhere.
+ *
+ * The runtime is an optional dependency; models are downloaded once into
+ * ~/.cv/models (or CV_LOCAL_MODELS_DIR) and loaded from disk afterwards.
+ * Set CV_OFFLINE=1 to forbid downloads entirely. Builds that bundle the
+ * runtime (the static binaries) hand it over with setLocalEmbeddingRuntime().
+ */
+
+import * as os from 'os';
+import * as path from 'path';
+
+export interface LocalEmbeddingModel {
+  /** Name used in config, e.g. local/bge-small-en-v1.5 */
+  name: string;
+  /** Hugging Face repository with ONNX weights */
+  repo: string;
+  dimension: number;
+  pooling: 'cls' | 'mean';
+}
+
+export const LOCAL_EMBEDDING_MODELS: LocalEmbeddingModel[] = [
+  { name: 'local/bge-small-en-v1.5', repo: 'Xenova/bge-small-en-v1.5', dimension: 384, pooling: 'cls' },
+  { name: 'local/all-minilm-l6-v2', repo: 'Xenova/all-MiniLM-L6-v2', dimension: 384, pooling: 'mean' },
+  { name: 'local/nomic-embed-text-v1.5', repo: 'nomic-ai/nomic-embed-text-v1.5', dimension: 768, pooling: 'mean' }
+];
+
+export const DEFAULT_LOCAL_EMBEDDING_MODEL = 'local/bge-small-en-v1.5';
+
+const RUNTIME_PACKAGE = '@huggingface/transformers';
+
+export function getLocalEmbeddingModel(name: string): LocalEmbeddingModel | undefined {
+  const bare = name.replace(/^local\//, '').toLowerCase();
+  return LOCAL_EMBEDDING_MODELS.find(
+    m => m.name === name || m.name === `local/${bare}` || m.repo.toLowerCase() === bare
+  );
+}
+
+export function getLocalModelsDir(): string {
+  return process.env.CV_LOCAL_MODELS_DIR || path.join(os.homedir(), '.cv', 'models');
+}
+
+let bundledRuntime: any = null;
+
+/**
+ * Use a runtime bundled into the executable instead of importing the package
+ */
+export function setLocalEmbeddingRuntime(runtime: any): void {
+  bundledRuntime = runtime;
+}
+
+async function loadRuntime(): Promise<any> {
+  if (bundledRuntime) return bundledRuntime;
+  try {
+    // Kept out of static imports so the CLI starts without the optional native runtime
+    return await import(RUNTIME_PACKAGE);
+  } catch {
+    return null;
+  }
+}
+
+/**
+ * Whether the optional local embedding runtime is installed
+ */
+export async function isLocalEmbeddingRuntimeAvailable(): Promise<boolean> {
+  return (await loadRuntime()) !== null;
+}
+
+/**
+ * In-process embedding model; loads lazily on first use
+ */
+export class LocalEmbedder {
+  readonly model: LocalEmbeddingModel;
+  private extractor: any = null;
+  private loading: Promise<any> | null = null;
+
+  constructor(modelName: string = DEFAULT_LOCAL_EMBEDDING_MODEL) {
+    const model = getLocalEmbeddingModel(modelName);
+    if (!model) {
+      throw new Error(
+        `Unknown local embedding model: ${modelName}\n` +
+        `Available: ${LOCAL_EMBEDDING_MODELS.map(m => m.name).join(', ')}`
+      );
+    }
+    this.model = model;
+  }
+
+  /**
+   * Load the model, downloading it on first use unless offline
+   */
+  async load(): Promise<void> {
+    if (this.extractor) return;
+    if (!this.loading) {
+      this.loading = (async () => {
+        const runtime = await loadRuntime();
+        if (!runtime) {
+          throw new Error(
+            `Local embeddings need the optional ${RUNTIME_PACKAGE} package.\n` +
+            `Install it with: npm install -g ${RUNTIME_PACKAGE}`
+          );
+        }
+        runtime.env.cacheDir = getLocalModelsDir();
+        if (process.env.CV_OFFLINE === '1') {
+          runtime.env.allowRemoteModels = false;
+        }
+        return runtime.pipeline('feature-extraction', this.model.repo, { dtype: 'q8' });
+      })();
+    }
+    try {
+      this.extractor = await this.loading;
+    } catch (error) {
+      this.loading = null;
+      throw error;
+    }
+  }
+
+  /**
+   * Embed texts as normalized vectors. Queries and documents are embedded the
+   * same way because VectorManager caches both under the same content hash.
+   */
+  async embedBatch(texts: string[], batchSize: number = 16): Promise<number[][]> {
+    await this.load();
+    const vectors: number[][] = [];
+    for (let i = 0; i < texts.length; i += batchSize) {
+      const output = await this.extractor(texts.slice(i, i + batchSize), {
+        pooling: this.model.pooling,
+        normalize: true
+      });
+      vectors.push(...(output.tolist() as number[][]));
+    }
+    return vectors;
+  }
+
+  async embed(text: string): Promise<number[]> {
+    const [vector] = await this.embedBatch([text]);
+    return vector;
+  }
+}
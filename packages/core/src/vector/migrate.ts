@@ -0,0 +1,68 @@
+/**
+ * Vector Store Migration
+ *
+ * Copies collections between backends point by point (ids, vectors and
+ * payloads unchanged), so moving an index to or from an embedded store
+ * needs no re-embedding.
+ */
+
+import type { VectorStore } from './store.js';
+
+export interface VectorMigrationProgress {
+  collection: string;
+  copied: number;
+  total: number;
+}
+
+export interface VectorMigrationResult {
+  collections: Array<{ name: string; points: number; vectorSize: number }>;
+  totalPoints: number;
+}
+
+/**
+ * Copy collections from one connected store to another. Target collections
+ * are recreated, so a re-run replaces rather than duplicates.
+ */
+export async function migrateVectorStore(
+  source: VectorStore,
+  target: VectorStore,
+  options: {
+    /** Collections to copy (default: all in the source) */
+    collections?: string[];
+    batchSize?: number;
+    onProgress?: (progress: VectorMigrationProgress) => void;
+  } = {}
+): Promise<VectorMigrationResult> {
+  const batchSize = options.batchSize ?? 256;
+  const available = await source.listCollections();
+  const names = options.collections
+    ? options.collections.filter(name => available.includes(name))
+    : available;
+
+  const result: VectorMigrationResult = { collections: [], totalPoints: 0 };
+
+  for (const name of names) {
+    const stats = await source.getCollectionStats(name);
+    if (!stats) continue;
+
+    await target.deleteCollection(name).catch(() => { /* may not exist */ });
+    await target.createCollection(name, stats.vectorSize);
+
+    let copied = 0;
+    let offset: string | undefined;
+    do {
+      const page = await source.scroll(name, { limit: batchSize, offset, withVector: true });
+      if (page.points.length > 0) {
+        await target.upsert(name, page.points);
+        copied += page.points.length;
+        options.onProgress?.({ collection: name, copied, total: stats.pointsCount });
+      }
+      offset = page.nextOffset;
+    } while (offset);
+
+    result.collections.push({ name, points: copied, vectorSize: stats.vectorSize });
+    result.totalPoints += copied;
+  }
+
+  return result;
+}
@@ -0,0 +1,437 @@
+/**
+ * Embedding Providers
+ *
+ * Each embedding backend implements one small interface, and
+ * `resolveProvider()` is the single place that decides which backend a
+ * repository uses. VectorManager and the CLI commands no longer need their
+ * own provider checks, so a model name can't silently end up on a backend
+ * that doesn't serve it (e.g. an OpenAI model name sent to OpenRouter
+ * without its `openai/` prefix).
+ */
+
+import OpenAI from 'openai';
+import { chunkArray, VectorError } from '@cv-git/shared';
+import { LocalEmbedder, LOCAL_EMBEDDING_MODELS, DEFAULT_LOCAL_EMBEDDING_MODEL } from './local-embeddings.js';
+import { limitedFetch, providerFetch, retryDelay } from '../ai/http.js';
+
+export type EmbeddingProviderName = 'openai' | 'openrouter' | 'ollama' | 'lmstudio' | 'azure' | 'local';
+
+export interface EmbeddingProvider {
+  readonly name: EmbeddingProviderName;
+  /** Model (or Azure deployment) embeddings are requested from */
+  readonly model: string;
+  /** Vector dimension; may be corrected by init() for runtime-discovered models */
+  readonly dimension: number;
+  /** Most inputs sent in one request */
+  readonly maxBatchSize: number;
+  /** Check the backend is reachable and the model is available */
+  init?(): Promise<void>;
+  /** Embed up to maxBatchSize texts in one request */
+  embed(texts: string[]): Promise<number[][]>;
+}
+
+// Embedding model configurations with their vector dimensions
+export const EMBEDDING_MODELS: Record<string, { dimension: number; provider: EmbeddingProviderName }> = {
+  // OpenAI models (direct)
+  'text-embedding-3-small': { dimension: 1536, provider: 'openai' },
+  'text-embedding-3-large': { dimension: 3072, provider: 'openai' },
+  'text-embedding-ada-002': { dimension: 1536, provider: 'openai' },
+  // OpenRouter models (uses OpenAI-compatible API)
+  'openai/text-embedding-3-small': { dimension: 1536, provider: 'openrouter' },
+  'openai/text-embedding-3-large': { dimension: 3072, provider: 'openrouter' },
+  'openai/text-embedding-ada-002': { dimension: 1536, provider: 'openrouter' },
+  // Ollama models (local)
+  'nomic-embed-text': { dimension: 768, provider: 'ollama' },
+  'mxbai-embed-large': { dimension: 1024, provider: 'ollama' },
+  'all-minilm': { dimension: 384, provider: 'ollama' },
+  'snowflake-arctic-embed': { dimension: 1024, provider: 'ollama' },
+  // LM Studio models (local, OpenAI-compatible API)
+  // Model IDs are runtime-fetched; these are common defaults
+  'nomic-ai/nomic-embed-text-v1.5-gguf': { dimension: 768, provider: 'lmstudio' },
+  'text-embedding-bge-small-en-v1.5': { dimension: 384, provider: 'lmstudio' },
+  // In-process ONNX models (local-embeddings.ts)
+  ...Object.fromEntries(LOCAL_EMBEDDING_MODELS.map(m => [m.name, { dimension: m.dimension, provider: 'local' as const }])),
+};
+
+/**
+ * Inputs per request. OpenAI accepts 2048 but large code chunks hit the
+ * per-request token cap first; OpenRouter rate-limits big batches; Azure
+ * deployments of ada-002 reject more than 16; local servers embed serially.
+ */
+export const EMBEDDING_BATCH_LIMITS: Record<EmbeddingProviderName, number> = {
+  openai: 100,
+  openrouter: 50,
+  azure: 16,
+  ollama: 32,
+  lmstudio: 32,
+  local: 16
+};
+
+export const DEFAULT_EMBEDDING_MODELS: Record<EmbeddingProviderName, string> = {
+  openai: 'text-embedding-3-small',
+  openrouter: 'openai/text-embedding-3-small',
+  azure: 'text-embedding-3-small',
+  ollama: 'nomic-embed-text',
+  lmstudio: 'nomic-ai/nomic-embed-text-v1.5-gguf',
+  local: DEFAULT_LOCAL_EMBEDDING_MODEL
+};
+
+/** Local servers choke on long inputs in one batch slot (~125 tokens of code) */
+const LOCAL_SERVER_MAX_CHARS = 500;
+
+function truncate(text: string, maxChars: number): string {
+  return text.length > maxChars ? text.substring(0, maxChars) + '...' : text;
+}
+
+function dimensionFor(model: string, fallback: number): number {
+  return EMBEDDING_MODELS[model]?.dimension ?? fallback;
+}
+
+/**
+ * Empty strings are rejected by most APIs; embed the rest and pad with zero vectors
+ */
+async function embedNonEmpty(
+  texts: string[],
+  dimension: number,
+  embed: (texts: string[]) => Promise<number[][]>
+): Promise<number[][]> {
+  const valid = texts.filter(t => t && t.trim().length > 0);
+  if (valid.length === texts.length) return embed(texts);
+
+  const embeddings = valid.length > 0 ? await embed(valid) : [];
+  let next = 0;
+  return texts.map(t => (t && t.trim().length > 0 ? embeddings[next++] : new Array(dimension).fill(0)));
+}
+
+/**
+ * OpenAI and servers that speak its embeddings API
+ */
+class OpenAICompatibleProvider implements EmbeddingProvider {
+  readonly maxBatchSize: number;
+  protected client: OpenAI;
+
+  constructor(
+    readonly name: EmbeddingProviderName,
+    public model: string,
+    public dimension: number,
+    options: { apiKey: string; baseURL?: string; maxBatchSize?: number }
+  ) {
+    this.client = new OpenAI({
+      apiKey: options.apiKey,
+      baseURL: options.baseURL,
+      fetch: limitedFetch(name),
+      maxRetries: 0
+    });
+    this.maxBatchSize = options.maxBatchSize ?? EMBEDDING_BATCH_LIMITS[name];
+  }
+
+  async embed(texts: string[]): Promise<number[][]> {
+    return embedNonEmpty(texts, this.dimension, async valid => {
+      const response = await this.client.embeddings.create({
+        model: this.model,
+        input: valid,
+        encoding_format: 'float'
+      });
+      if (!response?.data || !Array.isArray(response.data)) {
+        throw new VectorError(`${this.name} returned an invalid embeddings response`);
+      }
+      return response.data.map(d => d.embedding);
+    });
+  }
+}
+
+export class OpenAIEmbeddingProvider extends OpenAICompatibleProvider {
+  constructor(options: { apiKey: string; model?: string; dimension?: number; baseURL?: string }) {
+    // OpenAI itself doesn't know OpenRouter's vendor prefix
+    const model = (options.model || DEFAULT_EMBEDDING_MODELS.openai).replace(/^openai\//, '');
+    super('openai', model, options.dimension ?? dimensionFor(model, 1536), options);
+  }
+}
+
+export class OpenRouterEmbeddingProvider extends OpenAICompatibleProvider {
+  constructor(options: { apiKey: string; model?: string; dimension?: number }) {
+    // OpenRouter routes by vendor/model
+    const requested = options.model || DEFAULT_EMBEDDING_MODELS.openrouter;
+    const model = requested.includes('/') ? requested : `openai/${requested}`;
+    super('openrouter', model, options.dimension ?? dimensionFor(model, 1536), {
+      apiKey: options.apiKey,
+      baseURL: 'https://openrouter.ai/api/v1'
+    });
+  }
+}
+
+export class LMStudioEmbeddingProvider extends OpenAICompatibleProvider {
+  private url: string;
+
+  constructor(options: { url: string; model?: string; dimension?: number }) {
+    const model = options.model || DEFAULT_EMBEDDING_MODELS.lmstudio;
+    super('lmstudio', model, options.dimension ?? dimensionFor(model, 768), {
+      apiKey: 'lm-studio',
+      baseURL: options.url
+    });
+    this.url = options.url;
+  }
+
+  /**
+   * Pick the configured model if loaded, else any loaded embedding model
+   */
+  async init(): Promise<void> {
+    const response = await fetch(`${this.url}/models`, { signal: AbortSignal.timeout(5000) }).catch(() => null);
+    if (!response?.ok) {
+      throw new VectorError('LM Studio not running. Start with: lms server start');
+    }
+    const data = await response.json() as { data?: Array<{ id: string }> };
+    const models = data.data?.map(m => m.id) || [];
+    if (models.includes(this.model)) return;
+
+    const embedModel = models.find(m => /embed|bge|nomic/i.test(m));
+    if (!embedModel) {
+      throw new VectorError('LM Studio has no embedding model loaded (e.g. nomic-embed-text-v1.5)');
+    }
+    this.model = embedModel;
+    this.dimension = dimensionFor(embedModel, this.dimension);
+  }
+
+  override async embed(texts: string[]): Promise<number[][]> {
+    return super.embed(texts.map(t => truncate(t, LOCAL_SERVER_MAX_CHARS)));
+  }
+}
+
+export class OllamaEmbeddingProvider implements EmbeddingProvider {
+  readonly name = 'ollama' as const;
+  readonly maxBatchSize = EMBEDDING_BATCH_LIMITS.ollama;
+  model: string;
+  dimension: number;
+  private url: string;
+
+  constructor(options: { url: string; model?: string; dimension?: number }) {
+    this.url = options.url.replace(/\/+$/, '');
+    this.model = options.model || DEFAULT_EMBEDDING_MODELS.ollama;
+    this.dimension = options.dimension ?? dimensionFor(this.model, 768);
+  }
+
+  /**
+   * Verify Ollama is up and has the model (or a known fallback) pulled
+   */
+  async init(): Promise<void> {
+    const response = await fetch(`${this.url}/api/tags`, { signal: AbortSignal.timeout(5000) }).catch(() => null);
+    if (!response?.ok) {
+      throw new VectorError('Ollama not running. Start with: ollama serve');
+    }
+    const data = await response.json() as { models?: Array<{ name: string }> };
+    const available = data.models?.map(m => m.name.split(':')[0]) || [];
+    const fallbacks = Object.entries(EMBEDDING_MODELS).filter(([, c]) => c.provider === 'ollama').map(([m]) => m);
+    const model = [this.model, ...fallbacks].find(m => available.some(a => a === m || a.startsWith(m)));
+    if (!model) {
+      throw new VectorError(`No embedding model found in Ollama. Install one with: ollama pull ${DEFAULT_EMBEDDING_MODELS.ollama}`);
+    }
+    if (model !== this.model) {
+      this.model = model;
+      this.dimension = dimensionFor(model, this.dimension);
+    }
+  }
+
+  async embed(texts: string[]): Promise<number[][]> {
+    const response = await providerFetch('ollama', `${this.url}/api/embed`, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json' },
+      body: JSON.stringify({
+        model: this.model,
+        input: texts.map(t => truncate(t, LOCAL_SERVER_MAX_CHARS))
+      }),
+      signal: AbortSignal.timeout(120000)  // First request loads the model
+    });
+    if (!response.ok) {
+      throw new VectorError(`Ollama embedding failed: ${await response.text()}`);
+    }
+    const data = await response.json() as { embeddings?: number[][] };
+    if (!data.embeddings || data.embeddings.length !== texts.length) {
+      throw new VectorError('Ollama returned an unexpected number of embeddings');
+    }
+    return data.embeddings;
+  }
+}
+
+export interface AzureOpenAIOptions {
+  /** https://<resource>.openai.azure.com */
+  endpoint: string;
+  apiKey: string;
+  /** Deployment name of the embedding model */
+  deployment: string;
+  apiVersion?: string;
+  /** Underlying model, used to look up the dimension */
+  model?: string;
+  dimension?: number;
+  maxBatchSize?: number;
+}
+
+export class AzureOpenAIEmbeddingProvider implements EmbeddingProvider {
+  readonly name = 'azure' as const;
+  readonly model: string;
+  readonly dimension: number;
+  readonly maxBatchSize: number;
+  private url: string;
+
+  constructor(private options: AzureOpenAIOptions) {
+    this.model = options.deployment;
+    this.dimension = options.dimension ?? dimensionFor(options.model || options.deployment, 1536);
+    this.maxBatchSize = options.maxBatchSize ?? EMBEDDING_BATCH_LIMITS.azure;
+    this.url = `${options.endpoint.replace(/\/+$/, '')}/openai/deployments/${encodeURIComponent(options.deployment)}` +
+      `/embeddings?api-version=${options.apiVersion || '2024-02-01'}`;
+  }
+
+  async embed(texts: string[]): Promise<number[][]> {
+    return embedNonEmpty(texts, this.dimension, async valid => {
+      const response = await providerFetch('azure', this.url, {
+        method: 'POST',
+        headers: { 'Content-Type': 'application/json', 'api-key': this.options.apiKey },
+        body: JSON.stringify({ input: valid }),
+        signal: AbortSignal.timeout(60000)
+      });
+      if (!response.ok) {
+        throw new VectorError(`Azure OpenAI embedding failed (${response.status}): ${await response.text()}`);
+      }
+      const data = await response.json() as { data?: Array<{ embedding: number[]; index: number }> };
+      if (!data.data) {
+        throw new VectorError('Azure OpenAI returned an invalid embeddings response');
+      }
+      return [...data.data].sort((a, b) => a.index - b.index).map(d => d.embedding);
+    });
+  }
+}
+
+export class LocalEmbeddingProvider implements EmbeddingProvider {
+  readonly name = 'local' as const;
+  readonly maxBatchSize = EMBEDDING_BATCH_LIMITS.local;
+  private embedder: LocalEmbedder;
+
+  constructor(options: { model?: string } = {}) {
+    this.embedder = new LocalEmbedder(options.model || DEFAULT_LOCAL_EMBEDDING_MODEL);
+  }
+
+  get model(): string {
+    return this.embedder.model.name;
+  }
+
+  get dimension(): number {
+    return this.embedder.model.dimension;
+  }
+
+  init(): Promise<void> {
+    return this.embedder.load();
+  }
+
+  embed(texts: string[]): Promise<number[][]> {
+    return this.embedder.embedBatch(texts, this.maxBatchSize);
+  }
+}
+
+function isRetryable(error: any): boolean {
+  const message = String(error?.message || '');
+  return error?.status === 429 || error?.status === 503 ||
+    /rate|429|503|No successful provider/i.test(message);
+}
+
+/**
+ * Embed any number of texts, split by the provider's batch limit. HTTP
+ * providers already retry each request (ai/http.ts); a batch that still
+ * fails with a rate limit is retried here as a whole.
+ */
+export async function embedInBatches(
+  provider: EmbeddingProvider,
+  texts: string[],
+  maxRetries: number = 3
+): Promise<number[][]> {
+  const embeddings: number[][] = [];
+  for (const batch of chunkArray(texts, provider.maxBatchSize)) {
+    for (let attempt = 0; ; attempt++) {
+      try {
+        embeddings.push(...await provider.embed(batch));
+        break;
+      } catch (error: any) {
+        if (!isRetryable(error) || attempt >= maxRetries - 1) {
+          throw error instanceof VectorError ? error : new VectorError(`${provider.name} embedding failed: ${error.message}`, error);
+        }
+        await new Promise(r => setTimeout(r, retryDelay(attempt + 1)));
+      }
+    }
+  }
+  return embeddings;
+}
+
+export interface ResolveProviderOptions {
+  /** Backend chosen in config or preferences; inferred from credentials when unset */
+  provider?: EmbeddingProviderName;
+  model?: string;
+  dimensions?: number;
+  openaiApiKey?: string;
+  openrouterApiKey?: string;
+  /** Set when the local server is known to be running */
+  ollamaUrl?: string;
+  lmstudioUrl?: string;
+  azure?: Omit<AzureOpenAIOptions, 'model' | 'dimension'>;
+}
+
+/**
+ * Model from config, unless it belongs to a different backend (e.g. the
+ * Ollama default left in config after switching to OpenRouter)
+ */
+function modelFor(provider: EmbeddingProviderName, model?: string): string | undefined {
+  if (!model) return undefined;
+  const known = EMBEDDING_MODELS[model]?.provider;
+  const compatible = !known || known === provider ||
+    (known === 'openai' && (provider === 'openrouter' || provider === 'azure')) ||
+    (known === 'openrouter' && provider === 'openai');
+  return compatible ? model : undefined;
+}
+
+/**
+ * Build the embedding backend for a repository. This is the one place
+ * provider selection happens; throws when the selected backend has no
+ * credentials or endpoint.
+ */
+export function resolveProvider(options: ResolveProviderOptions): EmbeddingProvider {
+  const name: EmbeddingProviderName | undefined = options.provider ??
+    (options.openrouterApiKey ? 'openrouter'
+      : options.openaiApiKey ? 'openai'
+        : options.azure ? 'azure'
+          : options.ollamaUrl ? 'ollama'
+            : options.lmstudioUrl ? 'lmstudio'
+              : undefined);
+
+  if (!name) {
+    throw new VectorError(
+      'No embedding provider available.\n' +
+      'Run: cv auth setup local (no API key)\n' +
+      'Or:  cv auth setup openrouter (cloud)\n' +
+      'Or:  Start Ollama: ollama serve'
+    );
+  }
+
+  const model = modelFor(name, options.model);
+  const dimension = options.dimensions;
+
+  switch (name) {
+    case 'openai':
+      if (!options.openaiApiKey) throw new VectorError('OpenAI API key not found. Run: cv auth setup openai');
+      return new OpenAIEmbeddingProvider({ apiKey: options.openaiApiKey, model, dimension });
+    case 'openrouter':
+      if (!options.openrouterApiKey) throw new VectorError('OpenRouter API key not found. Run: cv auth setup openrouter');
+      return new OpenRouterEmbeddingProvider({ apiKey: options.openrouterApiKey, model, dimension });
+    case 'azure':
+      if (!options.azure) {
+        throw new VectorError('Azure OpenAI not configured. Set AZURE_OPENAI_ENDPOINT, AZURE_OPENAI_API_KEY and AZURE_OPENAI_EMBEDDING_DEPLOYMENT');
+      }
+      return new AzureOpenAIEmbeddingProvider({ ...options.azure, model, dimension });
+    case 'ollama':
+      if (!options.ollamaUrl) throw new VectorError('Ollama not running. Start with: ollama serve');
+      return new OllamaEmbeddingProvider({ url: options.ollamaUrl, model, dimension });
+    case 'lmstudio':
+      if (!options.lmstudioUrl) throw new VectorError('LM Studio not running. Start with: lms server start');
+      return new LMStudioEmbeddingProvider({ url: options.lmstudioUrl, model, dimension });
+    case 'local':
+      return new LocalEmbeddingProvider({ model: model?.startsWith('local/') ? model : undefined });
+    default:
+      throw new VectorError(`Unknown embedding provider: ${name}`);
+  }
+}
@@ -0,0 +1,341 @@
+/**
+ * Vector quantization codecs
+ *
+ * Embeddings are unit vectors of 384-3072 f32 components, so most of an
+ * index is vectors. A codec trades some recall for size:
+ *
+ *   f32   4 bytes/dimension, exact (what the stores keep by default)
+ *   int8  1 byte/dimension: each component on a 256-step scale between its
+ *         observed min and max (4x smaller, ~1% recall loss)
+ *   pq    1 byte per subvector: each run of dimensions replaced by the
+ *         nearest of 256 centroids learned from the collection (8x-32x
+ *         smaller depending on `subvectors`, a few % recall loss)
+ *
+ * Searches never decode the stored vectors: the query stays f32 and is
+ * scored against the codes directly (asymmetric distance computation), via
+ * a weighted sum for int8 and per-subvector lookup tables for pq.
+ */
+
+export type QuantizationCodec = 'none' | 'int8' | 'pq';
+
+export interface QuantizationOptions {
+  /** Codec for new collections (default: none) */
+  codec?: QuantizationCodec;
+  /** pq only: codes per vector; more is larger and more accurate (default: dimension / 8) */
+  subvectors?: number;
+  /** Points a collection needs before its codec is trained; smaller ones stay f32 (default 1000) */
+  trainPoints?: number;
+}
+
+export const DEFAULT_TRAIN_POINTS = 1000;
+
+/** Vectors k-means (and the int8 ranges) learn from */
+const MAX_TRAIN_SAMPLE = 4096;
+const KMEANS_ITERATIONS = 8;
+const CENTROIDS = 256;
+
+export type SerializedCodec =
+  | { codec: 'f32'; dimension: number }
+  | { codec: 'int8'; dimension: number; min: string; step: string }
+  | { codec: 'pq'; dimension: number; subvectors: number; centroids: string[] };
+
+export interface VectorCodec {
+  readonly name: 'f32' | 'int8' | 'pq';
+  readonly dimension: number;
+  readonly bytesPerVector: number;
+  encode(vector: Float32Array): Uint8Array;
+  decode(code: Uint8Array): Float32Array;
+  /** Score codes against a unit query vector (approximate cosine similarity) */
+  scorer(query: Float32Array): (code: Uint8Array) => number;
+  toJSON(): SerializedCodec;
+}
+
+export function normalizeVector(vector: ArrayLike<number>): Float32Array {
+  const out = new Float32Array(vector.length);
+  let norm = 0;
+  for (let i = 0; i < vector.length; i++) norm += vector[i] * vector[i];
+  norm = Math.sqrt(norm) || 1;
+  for (let i = 0; i < vector.length; i++) out[i] = vector[i] / norm;
+  return out;
+}
+
+function toBase64(array: Float32Array): string {
+  return Buffer.from(array.buffer, array.byteOffset, array.byteLength).toString('base64');
+}
+
+function fromBase64(text: string): Float32Array {
+  const bytes = Buffer.from(text, 'base64');
+  return new Float32Array(bytes.buffer.slice(bytes.byteOffset, bytes.byteOffset + bytes.byteLength));
+}
+
+/** Deterministic random numbers so training is reproducible */
+function seededRandom(seed: number): () => number {
+  let state = seed >>> 0;
+  return () => {
+    state = (state + 0x6d2b79f5) >>> 0;
+    let t = state;
+    t = Math.imul(t ^ (t >>> 15), t | 1);
+    t ^= t + Math.imul(t ^ (t >>> 7), t | 61);
+    return ((t ^ (t >>> 14)) >>> 0) / 4294967296;
+  };
+}
+
+/** Evenly spaced sample of at most `size` vectors */
+function sample<T>(items: T[], size: number): T[] {
+  if (items.length <= size) return items;
+  const step = items.length / size;
+  return Array.from({ length: size }, (_, i) => items[Math.floor(i * step)]);
+}
+
+export class F32Codec implements VectorCodec {
+  readonly name = 'f32' as const;
+  readonly bytesPerVector: number;
+
+  constructor(readonly dimension: number) {
+    this.bytesPerVector = dimension * 4;
+  }
+
+  encode(vector: Float32Array): Uint8Array {
+    return new Uint8Array(vector.buffer.slice(vector.byteOffset, vector.byteOffset + vector.byteLength));
+  }
+
+  decode(code: Uint8Array): Float32Array {
+    return new Float32Array(code.buffer.slice(code.byteOffset, code.byteOffset + code.byteLength));
+  }
+
+  scorer(query: Float32Array): (code: Uint8Array) => number {
+    return code => {
+      const vector = new Float32Array(code.buffer, code.byteOffset, this.dimension);
+      let score = 0;
+      for (let i = 0; i < this.dimension; i++) score += query[i] * vector[i];
+      return score;
+    };
+  }
+
+  toJSON(): SerializedCodec {
+    return { codec: 'f32', dimension: this.dimension };
+  }
+}
+
+export class Int8Codec implements VectorCodec {
+  readonly name = 'int8' as const;
+  readonly dimension: number;
+  readonly bytesPerVector: number;
+
+  constructor(private min: Float32Array, private step: Float32Array) {
+    this.dimension = min.length;
+    this.bytesPerVector = min.length;
+  }
+
+  /**
+   * Per-component ranges of the training vectors
+   */
+  static train(vectors: Float32Array[]): Int8Codec {
+    const dimension = vectors[0].length;
+    const min = new Float32Array(dimension).fill(Infinity);
+    const max = new Float32Array(dimension).fill(-Infinity);
+    for (const vector of sample(vectors, MAX_TRAIN_SAMPLE)) {
+      for (let i = 0; i < dimension; i++) {
+        if (vector[i] < min[i]) min[i] = vector[i];
+        if (vector[i] > max[i]) max[i] = vector[i];
+      }
+    }
+    const step = new Float32Array(dimension);
+    for (let i = 0; i < dimension; i++) step[i] = (max[i] - min[i]) / 255 || 1e-9;
+    return new Int8Codec(min, step);
+  }
+
+  encode(vector: Float32Array): Uint8Array {
+    const code = new Uint8Array(this.dimension);
+    for (let i = 0; i < this.dimension; i++) {
+      // Components outside the trained range are clamped
+      code[i] = Math.max(0, Math.min(255, Math.round((vector[i] - this.min[i]) / this.step[i])));
+    }
+    return code;
+  }
+
+  decode(code: Uint8Array): Float32Array {
+    const vector = new Float32Array(this.dimension);
+    for (let i = 0; i < this.dimension; i++) vector[i] = this.min[i] + code[i] * this.step[i];
+    return vector;
+  }
+
+  scorer(query: Float32Array): (code: Uint8Array) => number {
+    // q·(min + code*step) = q·min + (q*step)·code
+    const weights = new Float32Array(this.dimension);
+    let bias = 0;
+    for (let i = 0; i < this.dimension; i++) {
+      bias += query[i] * this.min[i];
+      weights[i] = query[i] * this.step[i];
+    }
+    return code => {
+      let score = bias;
+      for (let i = 0; i < this.dimension; i++) score += weights[i] * code[i];
+      return score;
+    };
+  }
+
+  toJSON(): SerializedCodec {
+    return { codec: 'int8', dimension: this.dimension, min: toBase64(this.min), step: toBase64(this.step) };
+  }
+}
+
+export class ProductCodec implements VectorCodec {
+  readonly name = 'pq' as const;
+  readonly bytesPerVector: number;
+  /** Start of each subvector, plus the dimension */
+  private bounds: number[];
+
+  constructor(readonly dimension: number, private centroids: Float32Array[]) {
+    this.bytesPerVector = centroids.length;
+    this.bounds = subvectorBounds(dimension, centroids.length);
+  }
+
+  /**
+   * k-means (256 centroids) per subvector over a sample of the vectors
+   */
+  static train(vectors: Float32Array[], subvectors: number, seed = 1): ProductCodec {
+    const dimension = vectors[0].length;
+    const m = Math.max(1, Math.min(subvectors, dimension));
+    const bounds = subvectorBounds(dimension, m);
+    const training = sample(vectors, MAX_TRAIN_SAMPLE);
+    const random = seededRandom(seed);
+
+    const centroids = Array.from({ length: m }, (_, s) => {
+      const from = bounds[s];
+      const width = bounds[s + 1] - from;
+      const k = Math.min(CENTROIDS, training.length);
+      const points = training.map(v => v.subarray(from, from + width));
+
+      // Start from k distinct training points (Fisher-Yates)
+      const order = points.map((_, i) => i);
+      for (let i = order.length - 1; i > 0; i--) {
+        const j = Math.floor(random() * (i + 1));
+        [order[i], order[j]] = [order[j], order[i]];
+      }
+      const table = new Float32Array(k * width);
+      order.slice(0, k).forEach((p, c) => table.set(points[p], c * width));
+
+      const assignment = new Int32Array(points.length);
+      for (let iteration = 0; iteration < KMEANS_ITERATIONS; iteration++) {
+        points.forEach((point, p) => { assignment[p] = nearest(table, k, width, point); });
+        const sums = new Float64Array(k * width);
+        const counts = new Int32Array(k);
+        points.forEach((point, p) => {
+          const c = assignment[p];
+          counts[c]++;
+          for (let i = 0; i < width; i++) sums[c * width + i] += point[i];
+        });
+        for (let c = 0; c < k; c++) {
+          // Empty clusters keep their centroid
+          if (counts[c] === 0) continue;
+          for (let i = 0; i < width; i++) table[c * width + i] = sums[c * width + i] / counts[c];
+        }
+      }
+      return table;
+    });
+    return new ProductCodec(dimension, centroids);
+  }
+
+  private width(s: number): number {
+    return this.bounds[s + 1] - this.bounds[s];
+  }
+
+  encode(vector: Float32Array): Uint8Array {
+    const code = new Uint8Array(this.centroids.length);
+    this.centroids.forEach((table, s) => {
+      const width = this.width(s);
+      code[s] = nearest(table, table.length / width, width, vector.subarray(this.bounds[s], this.bounds[s + 1]));
+    });
+    return code;
+  }
+
+  decode(code: Uint8Array): Float32Array {
+    const vector = new Float32Array(this.dimension);
+    this.centroids.forEach((table, s) => {
+      const width = this.width(s);
+      vector.set(table.subarray(code[s] * width, (code[s] + 1) * width), this.bounds[s]);
+    });
+    return vector;
+  }
+
+  scorer(query: Float32Array): (code: Uint8Array) => number {
+    // Lookup table: query subvector · each centroid
+    const tables = this.centroids.map((table, s) => {
+      const width = this.width(s);
+      const q = query.subarray(this.bounds[s], this.bounds[s + 1]);
+      const scores = new Float32Array(table.length / width);
+      for (let c = 0; c < scores.length; c++) {
+        let score = 0;
+        for (let i = 0; i < width; i++) score += q[i] * table[c * width + i];
+        scores[c] = score;
+      }
+      return scores;
+    });
+    return code => {
+      let score = 0;
+      for (let s = 0; s < tables.length; s++) score += tables[s][code[s]];
+      return score;
+    };
+  }
+
+  toJSON(): SerializedCodec {
+    return { codec: 'pq', dimension: this.dimension, subvectors: this.centroids.length, centroids: this.centroids.map(toBase64) };
+  }
+}
+
+function subvectorBounds(dimension: number, subvectors: number): number[] {
+  return Array.from({ length: subvectors + 1 }, (_, s) => Math.floor((s * dimension) / subvectors));
+}
+
+/** Index of the centroid closest (L2) to `point` */
+function nearest(table: Float32Array, k: number, width: number, point: Float32Array): number {
+  let best = 0;
+  let bestDistance = Infinity;
+  for (let c = 0; c < k; c++) {
+    let distance = 0;
+    for (let i = 0; i < width; i++) {
+      const d = point[i] - table[c * width + i];
+      distance += d * d;
+    }
+    if (distance < bestDistance) {
+      bestDistance = distance;
+      best = c;
+    }
+  }
+  return best;
+}
+
+export function codecFromJSON(json: SerializedCodec): VectorCodec {
+  switch (json.codec) {
+    case 'int8':
+      return new Int8Codec(fromBase64(json.min), fromBase64(json.step));
+    case 'pq':
+      return new ProductCodec(json.dimension, json.centroids.map(fromBase64));
+    default:
+      return new F32Codec(json.dimension);
+  }
+}
+
+/**
+ * Train the configured codec on a collection's (unit) vectors
+ */
+export function trainCodec(vectors: Float32Array[], options: QuantizationOptions): VectorCodec {
+  const dimension = vectors[0].length;
+  if (options.codec === 'int8') return Int8Codec.train(vectors);
+  if (options.codec === 'pq') return ProductCodec.train(vectors, options.subvectors ?? defaultSubvectors(dimension));
+  return new F32Codec(dimension);
+}
+
+export function defaultSubvectors(dimension: number): number {
+  return Math.max(1, Math.round(dimension / 8));
+}
+
+/**
+ * Bytes one vector takes under a codec
+ */
+export function vectorBytes(codec: QuantizationCodec | VectorCodec['name'], dimension: number, subvectors?: number): number {
+  if (codec === 'int8') return dimension;
+  if (codec === 'pq') return subvectors ?? defaultSubvectors(dimension);
+  return dimension * 4;
+}
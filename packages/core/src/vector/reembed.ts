@@ -0,0 +1,299 @@
+/**
+ * Embedding Model Migration
+ *
+ * `cv index reembed --model <new>` moves the index to another embedding model
+ * without taking it down. Every collection is copied into a shadow collection
+ * (`<name>__<model>`), re-embedding the text each point already stores, so
+ * files need not be parsed again. Progress is saved to `.cv/reembed.json`
+ * after every batch, so an interrupted run resumes where it stopped.
+ *
+ * Until the cutover both indexes serve reads: commands keep using the current
+ * one (and `cv sync` keeps writing to it), while `cv eval --next` queries the
+ * new one. The cutover re-embeds whatever changed in the meantime, then
+ * switches `embedding` and `vector.aliases` in a single config write, so every
+ * command moves to the new model and its collections at once. The old
+ * collections stay until `--drop-old`.
+ */
+
+import * as fs from 'fs/promises';
+import * as path from 'path';
+import { getCVDir, CVConfig } from '@cv-git/shared';
+import type { VectorPoint, VectorStore } from './store.js';
+import type { EmbeddingProviderName } from './providers.js';
+
+export const REEMBED_STATE_FILE = 'reembed.json';
+
+export interface ReembedCollection {
+  /** Name commands use (config.vector.collections or repo-scoped) */
+  name: string;
+  /** Collection currently holding its vectors (differs after an earlier migration) */
+  source: string;
+  /** Shadow collection being built */
+  target: string;
+  /** Scroll cursor of the next batch; unset before the first one */
+  offset?: string;
+  /** Points copied so far */
+  copied: number;
+  /** Points without text to embed, left out */
+  skipped: number;
+  /** Points in the source when the migration started */
+  total: number;
+  done: boolean;
+}
+
+export interface ReembedState {
+  model: string;
+  provider: EmbeddingProviderName;
+  dimensions: number;
+  /** The model being replaced */
+  from: { model: string; provider: string };
+  startedAt: string;
+  updatedAt: string;
+  /** Process building the index, while it runs */
+  pid?: number;
+  /** Set once the cutover happened; the old collections are still there */
+  swappedAt?: string;
+  collections: ReembedCollection[];
+}
+
+export interface ReembedProgress {
+  collection: string;
+  copied: number;
+  total: number;
+}
+
+/** Re-embeds text the way sync first embedded it */
+export interface ReembedEmbedder {
+  embedBatch(texts: string[]): Promise<number[][]>;
+  prepareCodeForEmbedding(chunk: any): string;
+  prepareDocumentForEmbedding(chunk: any): string;
+}
+
+export interface RunReembedOptions {
+  store: VectorStore;
+  embedder: ReembedEmbedder;
+  /** Points per batch (default 64) */
+  batchSize?: number;
+  /** Most texts embedded per minute; unlimited when unset */
+  rateLimit?: number;
+  /** Persist progress; called after every batch */
+  save: (state: ReembedState) => Promise<void>;
+  onProgress?: (progress: ReembedProgress) => void;
+  /** Stop after the current batch (e.g. on SIGINT) */
+  signal?: AbortSignal;
+  /** For tests */
+  sleep?: (ms: number) => Promise<void>;
+}
+
+export function getReembedStatePath(repoRoot: string): string {
+  return path.join(getCVDir(repoRoot), REEMBED_STATE_FILE);
+}
+
+/**
+ * The migration in progress (or swapped but not cleaned up), if any
+ */
+export async function loadReembedState(repoRoot: string): Promise<ReembedState | null> {
+  try {
+    return JSON.parse(await fs.readFile(getReembedStatePath(repoRoot), 'utf-8')) as ReembedState;
+  } catch (error: any) {
+    if (error.code === 'ENOENT') return null;
+    throw error;
+  }
+}
+
+export async function saveReembedState(repoRoot: string, state: ReembedState): Promise<void> {
+  const target = getReembedStatePath(repoRoot);
+  await fs.mkdir(path.dirname(target), { recursive: true });
+  await fs.writeFile(`${target}.tmp`, JSON.stringify({ ...state, updatedAt: new Date().toISOString() }, null, 2), 'utf-8');
+  await fs.rename(`${target}.tmp`, target);
+}
+
+export async function clearReembedState(repoRoot: string): Promise<void> {
+  await fs.rm(getReembedStatePath(repoRoot), { force: true });
+}
+
+/**
+ * Shadow collection for a collection under a model
+ */
+export function reembedCollectionName(name: string, model: string): string {
+  const slug = model.toLowerCase().replace(/[^a-z0-9]+/g, '_').replace(/^_+|_+$/g, '');
+  return `${name}__${slug}`;
+}
+
+/**
+ * A new migration of `collections` (with their point counts) to `model`
+ */
+export function planReembed(options: {
+  collections: Array<{ name: string; points: number }>;
+  aliases?: Record<string, string>;
+  model: string;
+  provider: EmbeddingProviderName;
+  dimensions: number;
+  from: { model: string; provider: string };
+}): ReembedState {
+  const now = new Date().toISOString();
+  return {
+    model: options.model,
+    provider: options.provider,
+    dimensions: options.dimensions,
+    from: options.from,
+    startedAt: now,
+    updatedAt: now,
+    collections: options.collections.map(({ name, points }) => {
+      const source = options.aliases?.[name] ?? name;
+      const target = reembedCollectionName(name, options.model);
+      if (target === source) {
+        throw new Error(`${name} is already embedded with ${options.model}`);
+      }
+      return { name, source, target, copied: 0, skipped: 0, total: points, done: false };
+    })
+  };
+}
+
+/**
+ * The text a point was embedded from, or null when it has none
+ */
+export function reembedText(collection: string, payload: Record<string, unknown>, embedder: ReembedEmbedder): string | null {
+  if (typeof payload.text !== 'string' && typeof payload.summary !== 'string' && typeof payload.message !== 'string') {
+    return null;
+  }
+  if (collection.endsWith('code_chunks') && typeof payload.text === 'string') {
+    return embedder.prepareCodeForEmbedding(payload);
+  }
+  if (collection.endsWith('document_chunks') && typeof payload.text === 'string') {
+    return embedder.prepareDocumentForEmbedding(payload);
+  }
+  if (collection.endsWith('summaries') && typeof payload.summary === 'string') {
+    return payload.summary;
+  }
+  if (collection.endsWith('commits') && typeof payload.message === 'string') {
+    return payload.message;
+  }
+  return typeof payload.text === 'string' ? payload.text : null;
+}
+
+/**
+ * Re-embed points into a collection's target, keeping ids and payloads
+ */
+async function embedPoints(
+  collection: ReembedCollection,
+  points: VectorPoint[],
+  options: RunReembedOptions
+): Promise<{ written: number; skipped: number }> {
+  const texts: string[] = [];
+  const kept: VectorPoint[] = [];
+  for (const point of points) {
+    const text = reembedText(collection.name, point.payload, options.embedder);
+    if (text === null) continue;
+    texts.push(text);
+    kept.push(point);
+  }
+  if (kept.length > 0) {
+    const vectors = await options.embedder.embedBatch(texts);
+    await options.store.upsert(collection.target, kept.map((point, i) => ({ id: point.id, vector: vectors[i], payload: point.payload })));
+  }
+  return { written: kept.length, skipped: points.length - kept.length };
+}
+
+/**
+ * Build (or resume building) every target collection. Returns when all are
+ * done or `signal` aborts; progress is saved after each batch either way.
+ */
+export async function runReembed(state: ReembedState, options: RunReembedOptions): Promise<ReembedState> {
+  const batchSize = options.batchSize ?? 64;
+  const sleep = options.sleep ?? ((ms: number) => new Promise<void>(resolve => setTimeout(resolve, ms)));
+  const started = Date.now();
+  let sent = 0;
+
+  for (const collection of state.collections) {
+    if (collection.done) continue;
+    await options.store.createCollection(collection.target, state.dimensions);
+
+    do {
+      if (options.signal?.aborted) return state;
+      const page = await options.store.scroll(collection.source, { limit: batchSize, offset: collection.offset, withVector: false });
+      const { written, skipped } = await embedPoints(collection, page.points, options);
+      collection.copied += written;
+      collection.skipped += skipped;
+      collection.offset = page.nextOffset;
+      collection.done = !page.nextOffset;
+      sent += written;
+      await options.save(state);
+      options.onProgress?.({ collection: collection.name, copied: collection.copied, total: collection.total });
+
+      // Keep to the rate by waiting until the texts sent so far are due
+      if (options.rateLimit && !collection.done) {
+        const due = (sent / options.rateLimit) * 60_000 - (Date.now() - started);
+        if (due > 0) await sleep(due);
+      }
+    } while (!collection.done);
+  }
+  return state;
+}
+
+/** Payload equality regardless of key order */
+function samePayload(a: Record<string, unknown>, b: Record<string, unknown>): boolean {
+  const stable = (value: unknown): string => JSON.stringify(value, (_key, v) =>
+    v && typeof v === 'object' && !Array.isArray(v)
+      ? Object.fromEntries(Object.entries(v).sort(([x], [y]) => x.localeCompare(y)))
+      : v
+  );
+  return stable(a) === stable(b);
+}
+
+/**
+ * Bring finished targets up to date with writes made to the sources since
+ * they were copied: re-embeds new and changed points, deletes removed ones
+ */
+export async function catchUpReembed(
+  state: ReembedState,
+  options: Omit<RunReembedOptions, 'save' | 'rateLimit'>
+): Promise<{ updated: number; deleted: number }> {
+  const batchSize = options.batchSize ?? 64;
+  let updated = 0;
+  let deleted = 0;
+
+  for (const collection of state.collections) {
+    const seen = new Set<string>();
+    let offset: string | undefined;
+    do {
+      const page = await options.store.scroll(collection.source, { limit: batchSize, offset, withVector: false });
+      page.points.forEach(point => seen.add(String(point.id)));
+      const existing = new Map((await options.store.retrieve(collection.target, page.points.map(p => p.id)))
+        .map(point => [String(point.id), point]));
+      const stale = page.points.filter(point => {
+        const current = existing.get(String(point.id));
+        return !current || !samePayload(current.payload, point.payload);
+      });
+      updated += (await embedPoints(collection, stale, options)).written;
+      offset = page.nextOffset;
+    } while (offset);
+
+    const removed: Array<string | number> = [];
+    offset = undefined;
+    do {
+      const page = await options.store.scroll(collection.target, { limit: 1000, offset, withVector: false });
+      for (const point of page.points) {
+        if (!seen.has(String(point.id))) removed.push(point.id);
+      }
+      offset = page.nextOffset;
+    } while (offset);
+    if (removed.length > 0) await options.store.delete(collection.target, removed);
+    deleted += removed.length;
+  }
+  return { updated, deleted };
+}
+
+/**
+ * The configuration after the cutover: the new model, with every migrated
+ * collection served from its target
+ */
+export function reembedConfig(config: CVConfig, state: ReembedState): CVConfig {
+  const aliases = { ...config.vector.aliases };
+  for (const collection of state.collections) aliases[collection.name] = collection.target;
+  return {
+    ...config,
+    embedding: { ...config.embedding, provider: state.provider, model: state.model, dimensions: state.dimensions },
+    vector: { ...config.vector, aliases }
+  };
+}
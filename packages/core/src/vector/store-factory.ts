@@ -0,0 +1,136 @@
+/**
+ * Vector Store Factory — selects where VectorManager keeps its vectors.
+ *
+ * Routing:
+ *   qdrant     → Qdrant server (default; shared or CV-Hub indexes)
+ *   lancedb    → .cv/index/lancedb (embedded, no server)
+ *   sqlite-vec → .cv/index/vectors.db (embedded, single file)
+ *   jsonl      → .cv/index/jsonl (embedded, no native packages)
+ *
+ * Set via config.vector.provider; override with CV_GIT_VECTOR_BACKEND.
+ * Embedded stores are wrapped in HnswVectorStore (config.vector.hnsw) so
+ * large collections are searched through an HNSW graph; Qdrant gets the
+ * same m/ef settings for its own HNSW index. With config.vector.quantization
+ * embedded stores are wrapped in QuantizedVectorStore instead, which keeps
+ * int8/pq codes and scans them, and Qdrant quantizes with its own codecs.
+ * With config.vector.aliases
+ * the store is wrapped in AliasedVectorStore, which serves collections from
+ * the ones they were swapped to by `cv index reembed`. With config.index.remote
+ * the index is a shared one served by `cv index serve` (RemoteVectorStore),
+ * with local changes layered on top by LayeredVectorStore.
+ * Unlike the graph factory there is no silent fallback: an index living in
+ * one backend is invisible from another, so a missing package is an error.
+ */
+
+import * as path from 'path';
+import { VectorError } from '@cv-git/shared';
+import type { VectorStore, VectorStoreType } from './store.js';
+import { QdrantStore } from './stores/qdrant-store.js';
+import { HnswVectorStore, HnswOptions } from './stores/hnsw-store.js';
+import { AliasedVectorStore } from './stores/aliased-store.js';
+import { QuantizedVectorStore } from './stores/quantized-store.js';
+import { RemoteVectorStore, RemoteStoreOptions } from './stores/remote-store.js';
+import { LayeredVectorStore } from './stores/layered-store.js';
+import { JsonlVectorStore } from './stores/jsonl-store.js';
+import type { QuantizationOptions } from './quantization.js';
+
+export const VECTOR_STORE_TYPES: VectorStoreType[] = ['qdrant', 'lancedb', 'sqlite-vec', 'jsonl'];
+
+/** Package(s) each embedded backend needs */
+const EMBEDDED_PACKAGES: Record<Exclude<VectorStoreType, 'qdrant' | 'jsonl'>, string> = {
+  lancedb: '@lancedb/lancedb',
+  'sqlite-vec': 'better-sqlite3 sqlite-vec'
+};
+
+export interface CreateVectorStoreOptions {
+  /** Backend from config.vector.provider (anything unknown, e.g. 'chroma', means qdrant) */
+  type?: string;
+  /** Qdrant URL */
+  url?: string;
+  /** Embedded index directory (default: .cv/index under the current directory) */
+  indexDir?: string;
+  /** HNSW settings (config.vector.hnsw) */
+  hnsw?: HnswOptions;
+  /** Vector codec (config.vector.quantization) */
+  quantization?: QuantizationOptions;
+  /** Collection name → collection holding it (config.vector.aliases) */
+  aliases?: Record<string, string>;
+  /** Shared index server (config.index.remote); the backend settings above are then the server's business */
+  remote?: RemoteStoreOptions;
+}
+
+/**
+ * Embedded index directory for a repository
+ */
+export function getVectorIndexDir(repoRoot: string): string {
+  return path.join(repoRoot, '.cv', 'index');
+}
+
+/**
+ * Determine which backend to use based on env var override or config
+ */
+export function resolveVectorStoreType(configured?: string): VectorStoreType {
+  const override = process.env.CV_GIT_VECTOR_BACKEND?.toLowerCase() || configured;
+  return VECTOR_STORE_TYPES.includes(override as VectorStoreType) ? override as VectorStoreType : 'qdrant';
+}
+
+/**
+ * Check if a backend type uses an embedded database (no server needed)
+ */
+export function isEmbeddedVectorStore(type: VectorStoreType): boolean {
+  return type !== 'qdrant';
+}
+
+/**
+ * Create (but not connect) a vector store
+ */
+export async function createVectorStore(options: CreateVectorStoreOptions): Promise<VectorStore> {
+  const store = options.remote
+    ? new LayeredVectorStore(
+      new RemoteVectorStore(options.remote),
+      path.join(options.indexDir || path.join('.cv', 'index'), 'overlay.json')
+    )
+    : await createBaseStore(options);
+  return options.aliases && Object.keys(options.aliases).length > 0
+    ? new AliasedVectorStore(store, options.aliases)
+    : store;
+}
+
+async function createBaseStore(options: CreateVectorStoreOptions): Promise<VectorStore> {
+  const type = resolveVectorStoreType(options.type);
+  const indexDir = options.indexDir || path.join('.cv', 'index');
+
+  let embedded: VectorStore | undefined = type === 'jsonl'
+    ? new JsonlVectorStore({ dataDir: path.join(indexDir, 'jsonl') })
+    : undefined;
+  try {
+    if (type === 'lancedb') {
+      await import('@lancedb/lancedb');
+      const { LanceDBStore } = await import('./stores/lancedb-store.js');
+      embedded = new LanceDBStore({ dataDir: path.join(indexDir, 'lancedb') });
+    }
+    if (type === 'sqlite-vec') {
+      await Promise.all([import('better-sqlite3'), import('sqlite-vec')]);
+      const { SqliteVecStore } = await import('./stores/sqlite-vec-store.js');
+      embedded = new SqliteVecStore({ dbPath: path.join(indexDir, 'vectors.db') });
+    }
+  } catch (error: any) {
+    throw new VectorError(
+      `The ${type} vector store needs an optional package.\n` +
+      `Install it with: npm install -g ${EMBEDDED_PACKAGES[type as keyof typeof EMBEDDED_PACKAGES]}`,
+      error
+    );
+  }
+
+  if (embedded) {
+    if (options.quantization?.codec && options.quantization.codec !== 'none') {
+      return new QuantizedVectorStore(embedded, path.join(indexDir, 'quantization'), options.quantization);
+    }
+    return options.hnsw?.enabled === false
+      ? embedded
+      : new HnswVectorStore(embedded, path.join(indexDir, 'hnsw'), options.hnsw);
+  }
+
+  const url = options.url || process.env.CV_QDRANT_URL || process.env.QDRANT_URL || 'http://localhost:6333';
+  return new QdrantStore({ url, hnsw: options.hnsw, quantization: options.quantization });
+}
@@ -0,0 +1,168 @@
+/**
+ * VectorStore — storage abstraction behind VectorManager.
+ *
+ * VectorManager owns embedding, caching and payload conventions; a
+ * VectorStore only persists points and answers nearest-neighbour queries.
+ * Four backends exist:
+ *
+ *   QdrantStore      — Qdrant server via @qdrant/js-client-rest (default)
+ *   LanceDBStore     — embedded LanceDB under .cv/index/lancedb
+ *   SqliteVecStore   — embedded SQLite + sqlite-vec under .cv/index/vectors.db
+ *   JsonlVectorStore — embedded append-only files under .cv/index/jsonl
+ *
+ * Embedded stores are wrapped in HnswVectorStore, which answers searches on
+ * large collections from an HNSW graph persisted next to them.
+ *
+ * Filters use the Qdrant filter shape ({ must: [{ key, match: { value } }] })
+ * for every backend; embedded backends evaluate them with matchesFilter().
+ */
+
+export type VectorStoreType = 'qdrant' | 'lancedb' | 'sqlite-vec' | 'jsonl';
+
+export interface VectorPoint {
+  id: string | number;
+  vector: number[];
+  payload: Record<string, unknown>;
+}
+
+export interface ScoredVectorPoint {
+  id: string | number;
+  /** Cosine similarity (higher is closer) */
+  score: number;
+  payload: Record<string, unknown>;
+}
+
+export interface VectorCollectionStats {
+  vectorSize: number;
+  pointsCount: number;
+}
+
+export interface VectorCodecInfo {
+  codec: 'f32' | 'int8' | 'pq';
+  /** Bytes per stored vector */
+  bytesPerVector: number;
+  /** False while a collection is too small to have trained its codec */
+  trained: boolean;
+}
+
+export interface VectorScrollOptions {
+  limit: number;
+  /** Opaque cursor from the previous page */
+  offset?: string;
+  filter?: VectorFilter;
+  withVector?: boolean;
+}
+
+export interface VectorScrollPage {
+  points: VectorPoint[];
+  nextOffset?: string;
+}
+
+export interface VectorFilterCondition {
+  key: string;
+  match?: { value?: unknown; any?: unknown[] };
+  range?: { gt?: number; gte?: number; lt?: number; lte?: number };
+}
+
+export interface VectorFilter {
+  must?: Array<VectorFilterCondition | VectorFilter>;
+  should?: Array<VectorFilterCondition | VectorFilter>;
+  must_not?: Array<VectorFilterCondition | VectorFilter>;
+}
+
+export interface VectorStore {
+  readonly type: VectorStoreType;
+
+  /** Open the connection (or the embedded database) */
+  connect(): Promise<void>;
+
+  /** Release the connection / file handles */
+  close(): Promise<void>;
+
+  listCollections(): Promise<string[]>;
+
+  /** Create a cosine-distance collection; no-op if it exists */
+  createCollection(name: string, vectorSize: number): Promise<void>;
+
+  deleteCollection(name: string): Promise<void>;
+
+  /** null when the collection does not exist */
+  getCollectionStats(name: string): Promise<VectorCollectionStats | null>;
+
+  /** Insert or replace points by id */
+  upsert(name: string, points: VectorPoint[]): Promise<void>;
+
+  delete(name: string, ids: Array<string | number>): Promise<void>;
+
+  /** Points by id (missing ids are skipped) */
+  retrieve(name: string, ids: Array<string | number>, options?: { withVector?: boolean }): Promise<VectorPoint[]>;
+
+  search(
+    name: string,
+    vector: number[],
+    options: { limit: number; filter?: VectorFilter }
+  ): Promise<ScoredVectorPoint[]>;
+
+  scroll(name: string, options: VectorScrollOptions): Promise<VectorScrollPage>;
+
+  /** Reclaim space left by deleted points and rebuild search indexes */
+  compact?(): Promise<void>;
+
+  /** How a collection's vectors are stored (null: plain f32) */
+  getCodec?(name: string): Promise<VectorCodecInfo | null>;
+}
+
+function isCondition(entry: VectorFilterCondition | VectorFilter): entry is VectorFilterCondition {
+  return typeof (entry as VectorFilterCondition).key === 'string';
+}
+
+function matchesCondition(payload: Record<string, unknown>, condition: VectorFilterCondition): boolean {
+  const value = condition.key.split('.').reduce<unknown>(
+    (current, part) => (current && typeof current === 'object' ? (current as Record<string, unknown>)[part] : undefined),
+    payload
+  );
+  const values = Array.isArray(value) ? value : [value];
+
+  if (condition.match) {
+    if ('value' in condition.match && !values.includes(condition.match.value)) return false;
+    if (condition.match.any && !values.some(v => condition.match!.any!.includes(v))) return false;
+  }
+  if (condition.range) {
+    if (typeof value !== 'number') return false;
+    const { gt, gte, lt, lte } = condition.range;
+    if ((gt !== undefined && !(value > gt)) || (gte !== undefined && !(value >= gte)) ||
+        (lt !== undefined && !(value < lt)) || (lte !== undefined && !(value <= lte))) {
+      return false;
+    }
+  }
+  return true;
+}
+
+/**
+ * Evaluate a Qdrant-style filter against a payload
+ */
+export function matchesFilter(payload: Record<string, unknown>, filter?: VectorFilter): boolean {
+  if (!filter) return true;
+  const test = (entry: VectorFilterCondition | VectorFilter) =>
+    isCondition(entry) ? matchesCondition(payload, entry) : matchesFilter(payload, entry);
+
+  if (filter.must && !filter.must.every(test)) return false;
+  if (filter.must_not && filter.must_not.some(test)) return false;
+  if (filter.should && filter.should.length > 0 && !filter.should.some(test)) return false;
+  return true;
+}
+
+/**
+ * Cosine similarity, matching Qdrant's Cosine score
+ */
+export function cosineSimilarity(a: number[], b: number[]): number {
+  let dot = 0;
+  let normA = 0;
+  let normB = 0;
+  for (let i = 0; i < a.length; i++) {
+    dot += a[i] * b[i];
+    normA += a[i] * a[i];
+    normB += b[i] * b[i];
+  }
+  return normA === 0 || normB === 0 ? 0 : dot / (Math.sqrt(normA) * Math.sqrt(normB));
+}
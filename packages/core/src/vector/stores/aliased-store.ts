@@ -0,0 +1,93 @@
+/**
+ * AliasedVectorStore — serves collections from other collections.
+ *
+ * config.vector.aliases maps the names commands use (code_chunks,
+ * <repoId>_summaries, ...) to the collections that hold them. `cv index
+ * reembed` builds a new model's index beside the old one and then points
+ * the aliases at it, so every reader and writer moves over with a single
+ * config write and nothing that names collections needs to know.
+ */
+
+import type {
+  VectorStore,
+  VectorPoint,
+  ScoredVectorPoint,
+  VectorCodecInfo,
+  VectorCollectionStats,
+  VectorScrollOptions,
+  VectorScrollPage,
+  VectorFilter
+} from '../store.js';
+
+export class AliasedVectorStore implements VectorStore {
+  constructor(readonly inner: VectorStore, private aliases: Record<string, string>) {}
+
+  get type() {
+    return this.inner.type;
+  }
+
+  private resolve(name: string): string {
+    return this.aliases[name] ?? name;
+  }
+
+  connect(): Promise<void> {
+    return this.inner.connect();
+  }
+
+  close(): Promise<void> {
+    return this.inner.close();
+  }
+
+  /** Collections under the names commands use */
+  async listCollections(): Promise<string[]> {
+    const names = await this.inner.listCollections();
+    const targets = new Map(Object.entries(this.aliases).map(([alias, target]) => [target, alias]));
+    return names
+      .filter(name => !(name in this.aliases) || !names.includes(this.aliases[name]))
+      .map(name => targets.get(name) ?? name);
+  }
+
+  createCollection(name: string, vectorSize: number): Promise<void> {
+    return this.inner.createCollection(this.resolve(name), vectorSize);
+  }
+
+  deleteCollection(name: string): Promise<void> {
+    return this.inner.deleteCollection(this.resolve(name));
+  }
+
+  getCollectionStats(name: string): Promise<VectorCollectionStats | null> {
+    return this.inner.getCollectionStats(this.resolve(name));
+  }
+
+  upsert(name: string, points: VectorPoint[]): Promise<void> {
+    return this.inner.upsert(this.resolve(name), points);
+  }
+
+  delete(name: string, ids: Array<string | number>): Promise<void> {
+    return this.inner.delete(this.resolve(name), ids);
+  }
+
+  retrieve(name: string, ids: Array<string | number>, options?: { withVector?: boolean }): Promise<VectorPoint[]> {
+    return this.inner.retrieve(this.resolve(name), ids, options);
+  }
+
+  search(
+    name: string,
+    vector: number[],
+    options: { limit: number; filter?: VectorFilter }
+  ): Promise<ScoredVectorPoint[]> {
+    return this.inner.search(this.resolve(name), vector, options);
+  }
+
+  scroll(name: string, options: VectorScrollOptions): Promise<VectorScrollPage> {
+    return this.inner.scroll(this.resolve(name), options);
+  }
+
+  async compact(): Promise<void> {
+    await this.inner.compact?.();
+  }
+
+  async getCodec(name: string): Promise<VectorCodecInfo | null> {
+    return this.inner.getCodec?.(this.resolve(name)) ?? null;
+  }
+}
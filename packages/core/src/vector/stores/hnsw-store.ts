@@ -0,0 +1,253 @@
+/**
+ * HnswVectorStore — approximate search over an embedded store.
+ *
+ * Wraps LanceDB, sqlite-vec or the jsonl store, whose searches compare the
+ * query with every vector. Collections with at least `minPoints` points get
+ * an HNSW graph, built on first search and kept at
+ * <indexDir>/hnsw/<collection>.hnsw;
+ * smaller ones keep using the exact search, where the graph would cost
+ * more than it saves.
+ *
+ * The graph follows upserts and deletes made through this store. If the
+ * point count on disk no longer matches (another process wrote to the
+ * collection) it is rebuilt. Filtered searches over-fetch from the graph
+ * and fall back to an exact search when too few candidates match.
+ * Saved graphs are searched in place (see hnsw.ts), so the first search in
+ * a new process reads the file's header and the nodes it visits rather than
+ * rebuilding the graph.
+ */
+
+import * as fs from 'fs/promises';
+import * as path from 'path';
+import type {
+  VectorStore,
+  VectorPoint,
+  ScoredVectorPoint,
+  VectorCollectionStats,
+  VectorScrollOptions,
+  VectorScrollPage,
+  VectorFilter
+} from '../store.js';
+import { matchesFilter } from '../store.js';
+import { HnswIndex, DEFAULT_HNSW_PARAMS } from '../hnsw.js';
+
+export interface HnswOptions {
+  /** Set false to always search exactly (embedded stores only) */
+  enabled?: boolean;
+  /** Links per node (default 16); higher improves recall and memory use */
+  m?: number;
+  /** Candidate list while building (default 200) */
+  efConstruction?: number;
+  /** Candidate list while searching (default 64) */
+  efSearch?: number;
+  /** Collections smaller than this use exact search (default 20000) */
+  minPoints?: number;
+}
+
+type HnswSettings = Required<Omit<HnswOptions, 'enabled'>>;
+
+export const DEFAULT_HNSW_OPTIONS: HnswSettings = {
+  ...DEFAULT_HNSW_PARAMS,
+  efSearch: 64,
+  minPoints: 20000
+};
+
+/** Candidates fetched per requested result when a filter is applied */
+const FILTER_OVERFETCH = 8;
+/** Rebuild once this share of graph nodes are tombstones */
+const COMPACT_RATIO = 0.25;
+const SCROLL_BATCH = 1000;
+
+export class HnswVectorStore implements VectorStore {
+  private indexes = new Map<string, HnswIndex>();
+  private dirty = new Set<string>();
+  private settings: HnswSettings;
+
+  constructor(private inner: VectorStore, private dir: string, options: HnswOptions = {}) {
+    this.settings = {
+      m: options.m ?? DEFAULT_HNSW_OPTIONS.m,
+      efConstruction: options.efConstruction ?? DEFAULT_HNSW_OPTIONS.efConstruction,
+      efSearch: options.efSearch ?? DEFAULT_HNSW_OPTIONS.efSearch,
+      minPoints: options.minPoints ?? DEFAULT_HNSW_OPTIONS.minPoints
+    };
+  }
+
+  get type() {
+    return this.inner.type;
+  }
+
+  private indexPath(name: string): string {
+    return path.join(this.dir, `${name.replace(/[^\w.-]/g, '_')}.hnsw`);
+  }
+
+  /**
+   * The collection's graph if one is loaded or saved, without building it
+   */
+  private async existingIndex(name: string): Promise<HnswIndex | null> {
+    const loaded = this.indexes.get(name);
+    if (loaded) return loaded;
+    try {
+      const index = await HnswIndex.load(this.indexPath(name));
+      this.indexes.set(name, index);
+      return index;
+    } catch {
+      return null;
+    }
+  }
+
+  /**
+   * A graph matching the collection, (re)built from its points if needed
+   */
+  private async ensureIndex(name: string, stats: VectorCollectionStats): Promise<HnswIndex> {
+    let index = await this.existingIndex(name);
+    if (index && index.size === stats.pointsCount && index.dimension === stats.vectorSize) {
+      if (index.deletedRatio > COMPACT_RATIO) {
+        index = index.compact();
+        this.indexes.set(name, index);
+        this.dirty.add(name);
+      }
+      return index;
+    }
+
+    index?.close();
+    index = new HnswIndex(stats.vectorSize, this.settings);
+    let offset: string | undefined;
+    do {
+      const page = await this.inner.scroll(name, { limit: SCROLL_BATCH, offset, withVector: true });
+      for (const point of page.points) {
+        index.add(String(point.id), point.vector);
+      }
+      offset = page.nextOffset;
+    } while (offset);
+
+    this.indexes.set(name, index);
+    await this.save(name, index);
+    return index;
+  }
+
+  private async save(name: string, index: HnswIndex): Promise<void> {
+    await fs.mkdir(this.dir, { recursive: true });
+    const file = this.indexPath(name);
+    await fs.writeFile(`${file}.tmp`, index.serialize());
+    await fs.rename(`${file}.tmp`, file);
+    this.dirty.delete(name);
+  }
+
+  /**
+   * Write graphs changed since they were last saved
+   */
+  async flush(): Promise<void> {
+    for (const name of [...this.dirty]) {
+      const index = this.indexes.get(name);
+      if (index) await this.save(name, index);
+    }
+  }
+
+  async connect(): Promise<void> {
+    await this.inner.connect();
+  }
+
+  async close(): Promise<void> {
+    await this.flush();
+    this.indexes.forEach(index => index.close());
+    this.indexes.clear();
+    await this.inner.close();
+  }
+
+  listCollections(): Promise<string[]> {
+    return this.inner.listCollections();
+  }
+
+  createCollection(name: string, vectorSize: number): Promise<void> {
+    return this.inner.createCollection(name, vectorSize);
+  }
+
+  async deleteCollection(name: string): Promise<void> {
+    await this.inner.deleteCollection(name);
+    this.indexes.get(name)?.close();
+    this.indexes.delete(name);
+    this.dirty.delete(name);
+    await fs.rm(this.indexPath(name), { force: true });
+  }
+
+  /**
+   * Compact the embedded store, then rebuild every graph from the points
+   * left so tombstoned nodes and their links are gone
+   */
+  async compact(): Promise<void> {
+    await this.inner.compact?.();
+    for (const name of await this.inner.listCollections()) {
+      this.indexes.get(name)?.close();
+      this.indexes.delete(name);
+      this.dirty.delete(name);
+      await fs.rm(this.indexPath(name), { force: true });
+      const stats = await this.inner.getCollectionStats(name);
+      if (stats && stats.pointsCount >= this.settings.minPoints) {
+        await this.ensureIndex(name, stats);
+      }
+    }
+  }
+
+  getCollectionStats(name: string): Promise<VectorCollectionStats | null> {
+    return this.inner.getCollectionStats(name);
+  }
+
+  async upsert(name: string, points: VectorPoint[]): Promise<void> {
+    await this.inner.upsert(name, points);
+    const index = await this.existingIndex(name);
+    if (!index) return;
+    for (const point of points) {
+      index.add(String(point.id), point.vector);
+    }
+    this.dirty.add(name);
+  }
+
+  async delete(name: string, ids: Array<string | number>): Promise<void> {
+    await this.inner.delete(name, ids);
+    const index = await this.existingIndex(name);
+    if (!index) return;
+    for (const id of ids) {
+      index.remove(String(id));
+    }
+    this.dirty.add(name);
+  }
+
+  retrieve(name: string, ids: Array<string | number>, options?: { withVector?: boolean }): Promise<VectorPoint[]> {
+    return this.inner.retrieve(name, ids, options);
+  }
+
+  async search(
+    name: string,
+    vector: number[],
+    options: { limit: number; filter?: VectorFilter }
+  ): Promise<ScoredVectorPoint[]> {
+    const stats = await this.inner.getCollectionStats(name);
+    if (!stats || stats.pointsCount < this.settings.minPoints) {
+      return this.inner.search(name, vector, options);
+    }
+
+    const index = await this.ensureIndex(name, stats);
+    const k = options.filter ? options.limit * FILTER_OVERFETCH : options.limit;
+    const hits = index.search(vector, k, Math.max(this.settings.efSearch, k));
+    const points = await this.inner.retrieve(name, hits.map(h => h.id));
+    const payloads = new Map(points.map(p => [String(p.id), p.payload]));
+
+    const results: ScoredVectorPoint[] = [];
+    for (const hit of hits) {
+      const payload = payloads.get(hit.id);
+      if (!payload || !matchesFilter(payload, options.filter)) continue;
+      results.push({ id: hit.id, score: hit.score, payload });
+      if (results.length >= options.limit) break;
+    }
+
+    // A selective filter can leave the graph's neighbourhood empty
+    if (options.filter && results.length < options.limit) {
+      return this.inner.search(name, vector, options);
+    }
+    return results;
+  }
+
+  scroll(name: string, options: VectorScrollOptions): Promise<VectorScrollPage> {
+    return this.inner.scroll(name, options);
+  }
+}
@@ -0,0 +1,299 @@
+/**
+ * JsonlVectorStore — embedded store in plain files, with no native packages.
+ *
+ * Each collection is an append-only log `<name>.jsonl` in the data
+ * directory: a header line with the vector size, then one line per upsert
+ * or delete batch. Vectors are stored as base64 float32. A collection is
+ * read into memory on first use and searched exactly (HnswVectorStore
+ * takes over once it is large); the log is rewritten without superseded
+ * points by compact(), or automatically once they outnumber the live ones.
+ * A torn last line from an interrupted write is ignored on load.
+ */
+
+import { promises as fs } from 'fs';
+import * as path from 'path';
+import type {
+  VectorStore,
+  VectorPoint,
+  ScoredVectorPoint,
+  VectorCollectionStats,
+  VectorScrollOptions,
+  VectorScrollPage,
+  VectorFilter
+} from '../store.js';
+import { matchesFilter, cosineSimilarity } from '../store.js';
+
+export interface JsonlVectorStoreOptions {
+  /** Directory holding one log per collection */
+  dataDir: string;
+}
+
+/** Superseded entries tolerated before a log is rewritten on write */
+const MIN_COMPACT_ENTRIES = 1000;
+
+/** Points per line when a log is rewritten */
+const POINTS_PER_LINE = 500;
+
+interface StoredPoint {
+  /** Position in write order, used as the scroll cursor */
+  seq: number;
+  vector: number[];
+  payload: Record<string, unknown>;
+}
+
+interface Collection {
+  vectorSize: number;
+  /** Kept in seq order: upserts re-insert the id at the end */
+  points: Map<string, StoredPoint>;
+  nextSeq: number;
+  /** Point entries written to the log, live or not */
+  logged: number;
+}
+
+interface LoggedPoint {
+  id: string;
+  vector: string;
+  payload: Record<string, unknown>;
+}
+
+type LogLine = { vectorSize: number } | { upsert: LoggedPoint[] } | { delete: string[] };
+
+function encodeVector(vector: number[]): string {
+  return Buffer.from(new Float32Array(vector).buffer).toString('base64');
+}
+
+function decodeVector(encoded: string): number[] {
+  const bytes = Buffer.from(encoded, 'base64');
+  return Array.from(new Float32Array(bytes.buffer, bytes.byteOffset, bytes.byteLength / 4));
+}
+
+export class JsonlVectorStore implements VectorStore {
+  readonly type = 'jsonl' as const;
+  private connected = false;
+  private collections = new Map<string, Collection>();
+
+  constructor(private options: JsonlVectorStoreOptions) {}
+
+  private logPath(name: string): string {
+    return path.join(this.options.dataDir, `${encodeURIComponent(name)}.jsonl`);
+  }
+
+  private requireConnected(): void {
+    if (!this.connected) {
+      throw new Error('JsonlVectorStore: not connected');
+    }
+  }
+
+  private async collection(name: string): Promise<Collection | undefined> {
+    this.requireConnected();
+    const loaded = this.collections.get(name);
+    if (loaded) return loaded;
+
+    let text: string;
+    try {
+      text = await fs.readFile(this.logPath(name), 'utf-8');
+    } catch (error: any) {
+      if (error.code === 'ENOENT') return undefined;
+      throw error;
+    }
+
+    const lines = text.split('\n');
+    const header = JSON.parse(lines[0]) as { vectorSize: number };
+    const collection: Collection = { vectorSize: header.vectorSize, points: new Map(), nextSeq: 0, logged: 0 };
+    for (const line of lines.slice(1)) {
+      if (!line) continue;
+      let entry: LogLine;
+      try {
+        entry = JSON.parse(line);
+      } catch {
+        // Torn write at the end of the log
+        continue;
+      }
+      if ('upsert' in entry) {
+        for (const point of entry.upsert) {
+          collection.points.delete(point.id);
+          collection.points.set(point.id, { seq: collection.nextSeq++, vector: decodeVector(point.vector), payload: point.payload });
+        }
+        collection.logged += entry.upsert.length;
+      } else if ('delete' in entry) {
+        for (const id of entry.delete) {
+          collection.points.delete(id);
+        }
+      }
+    }
+    this.collections.set(name, collection);
+    return collection;
+  }
+
+  private async requireCollection(name: string): Promise<Collection> {
+    const collection = await this.collection(name);
+    if (!collection) {
+      throw new Error(`JsonlVectorStore: collection ${name} does not exist`);
+    }
+    return collection;
+  }
+
+  private async append(name: string, entry: LogLine): Promise<void> {
+    await fs.appendFile(this.logPath(name), JSON.stringify(entry) + '\n');
+  }
+
+  /**
+   * Rewrite a collection's log with only its live points
+   */
+  private async rewrite(name: string, collection: Collection): Promise<void> {
+    const lines = [JSON.stringify({ vectorSize: collection.vectorSize })];
+    const points = [...collection.points];
+    for (let i = 0; i < points.length; i += POINTS_PER_LINE) {
+      const upsert = points.slice(i, i + POINTS_PER_LINE).map(([id, point]) => (
+        { id, vector: encodeVector(point.vector), payload: point.payload }
+      ));
+      lines.push(JSON.stringify({ upsert }));
+    }
+
+    const file = this.logPath(name);
+    const tmp = `${file}.${process.pid}.tmp`;
+    await fs.writeFile(tmp, lines.join('\n') + '\n');
+    await fs.rename(tmp, file);
+    collection.logged = collection.points.size;
+  }
+
+  private async compactIfStale(name: string, collection: Collection): Promise<void> {
+    const superseded = collection.logged - collection.points.size;
+    if (superseded >= MIN_COMPACT_ENTRIES && superseded > collection.points.size) {
+      await this.rewrite(name, collection);
+    }
+  }
+
+  async connect(): Promise<void> {
+    await fs.mkdir(this.options.dataDir, { recursive: true });
+    this.connected = true;
+  }
+
+  async close(): Promise<void> {
+    this.collections.clear();
+    this.connected = false;
+  }
+
+  async listCollections(): Promise<string[]> {
+    this.requireConnected();
+    const files = await fs.readdir(this.options.dataDir);
+    return files
+      .filter(file => file.endsWith('.jsonl'))
+      .map(file => decodeURIComponent(file.slice(0, -'.jsonl'.length)))
+      .sort();
+  }
+
+  async createCollection(name: string, vectorSize: number): Promise<void> {
+    if (await this.collection(name)) return;
+    await fs.writeFile(this.logPath(name), JSON.stringify({ vectorSize }) + '\n', { flag: 'wx' });
+    this.collections.set(name, { vectorSize, points: new Map(), nextSeq: 0, logged: 0 });
+  }
+
+  async deleteCollection(name: string): Promise<void> {
+    this.requireConnected();
+    this.collections.delete(name);
+    await fs.rm(this.logPath(name), { force: true });
+  }
+
+  async compact(): Promise<void> {
+    for (const name of await this.listCollections()) {
+      const collection = await this.collection(name);
+      if (collection && collection.logged > collection.points.size) {
+        await this.rewrite(name, collection);
+      }
+    }
+  }
+
+  async getCollectionStats(name: string): Promise<VectorCollectionStats | null> {
+    const collection = await this.collection(name);
+    return collection ? { vectorSize: collection.vectorSize, pointsCount: collection.points.size } : null;
+  }
+
+  async upsert(name: string, points: VectorPoint[]): Promise<void> {
+    const collection = await this.requireCollection(name);
+    for (const point of points) {
+      if (point.vector.length !== collection.vectorSize) {
+        throw new Error(`JsonlVectorStore: expected ${collection.vectorSize} dimensions, got ${point.vector.length}`);
+      }
+    }
+    if (points.length === 0) return;
+
+    await this.append(name, {
+      upsert: points.map(point => ({ id: String(point.id), vector: encodeVector(point.vector), payload: point.payload }))
+    });
+    for (const point of points) {
+      const id = String(point.id);
+      collection.points.delete(id);
+      // Stored as written, so a fresh load returns the same float32-rounded values
+      collection.points.set(id, { seq: collection.nextSeq++, vector: Array.from(new Float32Array(point.vector)), payload: point.payload });
+    }
+    collection.logged += points.length;
+    await this.compactIfStale(name, collection);
+  }
+
+  async delete(name: string, ids: Array<string | number>): Promise<void> {
+    const collection = await this.collection(name);
+    if (!collection) return;
+    const present = ids.map(String).filter(id => collection.points.has(id));
+    if (present.length === 0) return;
+
+    await this.append(name, { delete: present });
+    for (const id of present) {
+      collection.points.delete(id);
+    }
+    await this.compactIfStale(name, collection);
+  }
+
+  async retrieve(name: string, ids: Array<string | number>, options: { withVector?: boolean } = {}): Promise<VectorPoint[]> {
+    const collection = await this.collection(name);
+    if (!collection) return [];
+    const withVector = options.withVector ?? false;
+
+    const points: VectorPoint[] = [];
+    for (const id of ids) {
+      const point = collection.points.get(String(id));
+      if (point) {
+        points.push({ id: String(id), vector: withVector ? point.vector : [], payload: point.payload });
+      }
+    }
+    return points;
+  }
+
+  async search(
+    name: string,
+    vector: number[],
+    options: { limit: number; filter?: VectorFilter }
+  ): Promise<ScoredVectorPoint[]> {
+    const collection = await this.collection(name);
+    if (!collection) return [];
+
+    const results: ScoredVectorPoint[] = [];
+    for (const [id, point] of collection.points) {
+      if (!matchesFilter(point.payload, options.filter)) continue;
+      results.push({ id, score: cosineSimilarity(vector, point.vector), payload: point.payload });
+    }
+    return results.sort((a, b) => b.score - a.score).slice(0, options.limit);
+  }
+
+  async scroll(name: string, options: VectorScrollOptions): Promise<VectorScrollPage> {
+    const collection = await this.collection(name);
+    if (!collection) return { points: [] };
+    const withVector = options.withVector ?? true;
+
+    // Keyset pagination: the offset is the last seq returned
+    const after = options.offset ? parseInt(options.offset, 10) : -1;
+    const points: VectorPoint[] = [];
+    let lastSeq = after;
+    let more = false;
+    for (const [id, point] of collection.points) {
+      if (point.seq <= after || !matchesFilter(point.payload, options.filter)) continue;
+      if (points.length >= options.limit) {
+        more = true;
+        break;
+      }
+      points.push({ id, vector: withVector ? point.vector : [], payload: point.payload });
+      lastSeq = point.seq;
+    }
+    return { points, nextOffset: more ? String(lastSeq) : undefined };
+  }
+}
@@ -0,0 +1,221 @@
+/**
+ * LanceDBStore — embedded LanceDB, no server process needed.
+ *
+ * Each collection is a Lance table with `id`, `vector` and a JSON `payload`
+ * column. Lance tables need a schema (and so a vector size) before the
+ * first row exists, so collection sizes are kept in collections.json and
+ * tables are created on first upsert.
+ *
+ * Payload filters are evaluated in JS. Filtered searches rank the whole
+ * table, which is fine at repository scale.
+ */
+
+import * as fs from 'fs/promises';
+import * as path from 'path';
+import type {
+  VectorStore,
+  VectorPoint,
+  ScoredVectorPoint,
+  VectorCollectionStats,
+  VectorScrollOptions,
+  VectorScrollPage,
+  VectorFilter
+} from '../store.js';
+import { matchesFilter } from '../store.js';
+
+export interface LanceDBStoreOptions {
+  /** Directory holding the Lance tables */
+  dataDir: string;
+}
+
+interface LanceRow {
+  id: string;
+  vector: number[];
+  payload: string;
+}
+
+const META_FILE = 'collections.json';
+
+function quote(value: string): string {
+  return `'${value.replace(/'/g, "''")}'`;
+}
+
+function toPoint(row: any, withVector: boolean): VectorPoint {
+  return {
+    id: row.id,
+    vector: withVector && row.vector ? Array.from(row.vector as ArrayLike<number>) : [],
+    payload: JSON.parse(row.payload || '{}')
+  };
+}
+
+export class LanceDBStore implements VectorStore {
+  readonly type = 'lancedb' as const;
+  private db: any = null;
+  private sizes: Record<string, number> = {};
+
+  constructor(private options: LanceDBStoreOptions) {}
+
+  private requireDb(): any {
+    if (!this.db) {
+      throw new Error('LanceDBStore: not connected');
+    }
+    return this.db;
+  }
+
+  private async saveMeta(): Promise<void> {
+    await fs.writeFile(path.join(this.options.dataDir, META_FILE), JSON.stringify(this.sizes, null, 2));
+  }
+
+  private async openTable(name: string): Promise<any | null> {
+    const db = this.requireDb();
+    const tables: string[] = await db.tableNames();
+    return tables.includes(name) ? db.openTable(name) : null;
+  }
+
+  async connect(): Promise<void> {
+    const lancedb = await import('@lancedb/lancedb');
+
+    await fs.mkdir(this.options.dataDir, { recursive: true });
+    this.db = await lancedb.connect(this.options.dataDir);
+
+    try {
+      this.sizes = JSON.parse(await fs.readFile(path.join(this.options.dataDir, META_FILE), 'utf-8'));
+    } catch {
+      this.sizes = {};
+    }
+  }
+
+  async close(): Promise<void> {
+    if (this.db) {
+      try { this.db.close?.(); } catch { /* ignore */ }
+      this.db = null;
+    }
+  }
+
+  async listCollections(): Promise<string[]> {
+    this.requireDb();
+    return Object.keys(this.sizes);
+  }
+
+  async createCollection(name: string, vectorSize: number): Promise<void> {
+    if (this.sizes[name]) return;
+    this.sizes[name] = vectorSize;
+    await this.saveMeta();
+  }
+
+  async deleteCollection(name: string): Promise<void> {
+    if (await this.openTable(name)) {
+      await this.requireDb().dropTable(name);
+    }
+    delete this.sizes[name];
+    await this.saveMeta();
+  }
+
+  async compact(): Promise<void> {
+    // Rewrites fragments without deleted rows and drops old table versions
+    for (const name of Object.keys(this.sizes)) {
+      const table = await this.openTable(name);
+      if (table) await table.optimize({ cleanupOlderThan: new Date() });
+    }
+  }
+
+  async getCollectionStats(name: string): Promise<VectorCollectionStats | null> {
+    if (!this.sizes[name]) return null;
+    const table = await this.openTable(name);
+    return {
+      vectorSize: this.sizes[name],
+      pointsCount: table ? await table.countRows() : 0
+    };
+  }
+
+  async upsert(name: string, points: VectorPoint[]): Promise<void> {
+    if (points.length === 0) return;
+    const vectorSize = this.sizes[name];
+    if (!vectorSize) {
+      throw new Error(`LanceDBStore: collection ${name} does not exist`);
+    }
+
+    const rows: LanceRow[] = points.map(p => {
+      if (p.vector.length !== vectorSize) {
+        throw new Error(`LanceDBStore: expected ${vectorSize} dimensions, got ${p.vector.length}`);
+      }
+      return { id: String(p.id), vector: p.vector, payload: JSON.stringify(p.payload) };
+    });
+
+    const table = await this.openTable(name);
+    if (!table) {
+      await this.requireDb().createTable(name, rows);
+      return;
+    }
+    await table.mergeInsert('id').whenMatchedUpdateAll().whenNotMatchedInsertAll().execute(rows);
+  }
+
+  async delete(name: string, ids: Array<string | number>): Promise<void> {
+    const table = await this.openTable(name);
+    if (!table || ids.length === 0) return;
+    await table.delete(`id IN (${ids.map(id => quote(String(id))).join(', ')})`);
+  }
+
+  async retrieve(name: string, ids: Array<string | number>, options: { withVector?: boolean } = {}): Promise<VectorPoint[]> {
+    const table = await this.openTable(name);
+    if (!table || ids.length === 0) return [];
+    const rows: any[] = await table
+      .query()
+      .where(`id IN (${ids.map(id => quote(String(id))).join(', ')})`)
+      .limit(ids.length)
+      .toArray();
+    return rows.map(row => toPoint(row, options.withVector ?? false));
+  }
+
+  async search(
+    name: string,
+    vector: number[],
+    options: { limit: number; filter?: VectorFilter }
+  ): Promise<ScoredVectorPoint[]> {
+    const table = await this.openTable(name);
+    if (!table) return [];
+
+    const candidates = options.filter ? await table.countRows() : options.limit;
+    const rows: any[] = await table
+      .vectorSearch(vector)
+      .distanceType('cosine')
+      .limit(Math.max(candidates, 1))
+      .toArray();
+
+    const results: ScoredVectorPoint[] = [];
+    for (const row of rows) {
+      const payload = JSON.parse(row.payload || '{}');
+      if (!matchesFilter(payload, options.filter)) continue;
+      results.push({ id: row.id, score: 1 - row._distance, payload });
+      if (results.length >= options.limit) break;
+    }
+    return results;
+  }
+
+  async scroll(name: string, options: VectorScrollOptions): Promise<VectorScrollPage> {
+    const table = await this.openTable(name);
+    if (!table) return { points: [] };
+
+    const withVector = options.withVector ?? true;
+    const start = options.offset ? parseInt(options.offset, 10) : 0;
+
+    if (!options.filter) {
+      const rows: any[] = await table.query().offset(start).limit(options.limit).toArray();
+      const next = start + rows.length;
+      return {
+        points: rows.map(row => toPoint(row, withVector)),
+        nextOffset: rows.length === options.limit && next < await table.countRows() ? String(next) : undefined
+      };
+    }
+
+    // Filtered scroll: the offset counts matching rows
+    const rows: any[] = await table.query().toArray();
+    const matching = rows.filter(row => matchesFilter(JSON.parse(row.payload || '{}'), options.filter));
+    const page = matching.slice(start, start + options.limit);
+    const next = start + page.length;
+    return {
+      points: page.map(row => toPoint(row, withVector)),
+      nextOffset: next < matching.length ? String(next) : undefined
+    };
+  }
+}
@@ -0,0 +1,231 @@
+/**
+ * LayeredVectorStore — a shared index with local changes on top.
+ *
+ * With config.index.remote the index comes from `cv index serve`, which
+ * nobody writes to but its own sync. Writes land in an overlay kept in
+ * .cv/index/overlay.json instead: upserted points, and the ids of shared
+ * points they deleted. Reads answer from both, hiding shared points the
+ * overlay deleted or replaced, so a search sees the shared index as it
+ * would be after syncing the local working tree.
+ *
+ * The overlay only holds files that differ from the commit the server
+ * indexed, so it is small and searched exactly. When the server moves to
+ * another commit, `cv sync` starts a fresh overlay from it (rebase()).
+ */
+
+import { promises as fs } from 'fs';
+import * as path from 'path';
+import { VectorError } from '@cv-git/shared';
+import type {
+  VectorStore,
+  VectorPoint,
+  ScoredVectorPoint,
+  VectorCodecInfo,
+  VectorCollectionStats,
+  VectorScrollOptions,
+  VectorScrollPage,
+  VectorFilter
+} from '../store.js';
+import { cosineSimilarity, matchesFilter } from '../store.js';
+
+interface OverlayCollection {
+  vectorSize: number;
+  /** Points written locally, by id */
+  points: Record<string, VectorPoint>;
+  /** Ids of shared points deleted locally */
+  removed: string[];
+}
+
+interface Overlay {
+  version: 1;
+  /** Commit of the shared index the overlay was started from */
+  base?: string;
+  collections: Record<string, OverlayCollection>;
+}
+
+const OVERLAY_PREFIX = 'o:';
+const BASE_PREFIX = 'b:';
+
+export class LayeredVectorStore implements VectorStore {
+  private overlay: Overlay = { version: 1, collections: {} };
+  private baseCollections = new Set<string>();
+  private saving: Promise<void> = Promise.resolve();
+
+  constructor(readonly base: VectorStore, private overlayFile: string) {}
+
+  get type() {
+    return this.base.type;
+  }
+
+  async connect(): Promise<void> {
+    await this.base.connect();
+    this.baseCollections = new Set(await this.base.listCollections());
+    try {
+      this.overlay = JSON.parse(await fs.readFile(this.overlayFile, 'utf-8')) as Overlay;
+    } catch (error: any) {
+      if (error.code !== 'ENOENT') throw error;
+      this.overlay = { version: 1, collections: {} };
+    }
+  }
+
+  async close(): Promise<void> {
+    await this.saving;
+    await this.base.close();
+  }
+
+  /** Commit the overlay was started from, if any */
+  getBaseCommit(): string | undefined {
+    return this.overlay.base;
+  }
+
+  /** Points kept locally, across collections */
+  overlaySize(): { points: number; removed: number } {
+    const collections = Object.values(this.overlay.collections);
+    return {
+      points: collections.reduce((n, c) => n + Object.keys(c.points).length, 0),
+      removed: collections.reduce((n, c) => n + c.removed.length, 0)
+    };
+  }
+
+  /** Drop every local change and start over from the shared index at `commit` */
+  async rebase(commit: string): Promise<void> {
+    this.overlay = { version: 1, base: commit, collections: {} };
+    this.baseCollections = new Set(await this.base.listCollections());
+    await this.save();
+  }
+
+  private save(): Promise<void> {
+    const json = JSON.stringify(this.overlay);
+    this.saving = this.saving.catch(() => {}).then(async () => {
+      await fs.mkdir(path.dirname(this.overlayFile), { recursive: true });
+      const tmp = `${this.overlayFile}.tmp`;
+      await fs.writeFile(tmp, json);
+      await fs.rename(tmp, this.overlayFile);
+    });
+    return this.saving;
+  }
+
+  private layer(name: string, vectorSize?: number): OverlayCollection {
+    return this.overlay.collections[name] ??= { vectorSize: vectorSize ?? 0, points: {}, removed: [] };
+  }
+
+  /** Shared point ids the overlay deletes or replaces */
+  private hidden(name: string): Set<string> {
+    const layer = this.overlay.collections[name];
+    return layer ? new Set([...layer.removed, ...Object.keys(layer.points)]) : new Set();
+  }
+
+  private overlayPoints(name: string, filter?: VectorFilter): VectorPoint[] {
+    const layer = this.overlay.collections[name];
+    if (!layer) return [];
+    return Object.values(layer.points).filter(p => matchesFilter(p.payload, filter));
+  }
+
+  async listCollections(): Promise<string[]> {
+    return [...new Set([...this.baseCollections, ...Object.keys(this.overlay.collections)])];
+  }
+
+  async createCollection(name: string, vectorSize: number): Promise<void> {
+    if (this.baseCollections.has(name) || this.overlay.collections[name]) return;
+    this.layer(name, vectorSize);
+    await this.save();
+  }
+
+  async deleteCollection(name: string): Promise<void> {
+    if (this.baseCollections.has(name)) {
+      throw new VectorError(`${name} belongs to the shared index; it can only be deleted on the server`);
+    }
+    delete this.overlay.collections[name];
+    await this.save();
+  }
+
+  /** Point counts are approximate: a local point may replace a shared one */
+  async getCollectionStats(name: string): Promise<VectorCollectionStats | null> {
+    const base = this.baseCollections.has(name) ? await this.base.getCollectionStats(name) : null;
+    const layer = this.overlay.collections[name];
+    if (!base && !layer) return null;
+    const added = layer ? Object.keys(layer.points).length : 0;
+    return {
+      vectorSize: base?.vectorSize ?? layer!.vectorSize,
+      pointsCount: Math.max(0, (base?.pointsCount ?? 0) - (layer?.removed.length ?? 0)) + added
+    };
+  }
+
+  async upsert(name: string, points: VectorPoint[]): Promise<void> {
+    if (points.length === 0) return;
+    const layer = this.layer(name, points[0].vector.length);
+    const upserted = new Set(points.map(p => String(p.id)));
+    for (const point of points) layer.points[String(point.id)] = point;
+    layer.removed = layer.removed.filter(id => !upserted.has(id));
+    await this.save();
+  }
+
+  async delete(name: string, ids: Array<string | number>): Promise<void> {
+    if (ids.length === 0) return;
+    const layer = this.layer(name);
+    const removed = new Set(layer.removed);
+    for (const id of ids.map(String)) {
+      delete layer.points[id];
+      if (this.baseCollections.has(name)) removed.add(id);
+    }
+    layer.removed = [...removed];
+    await this.save();
+  }
+
+  async retrieve(name: string, ids: Array<string | number>, options?: { withVector?: boolean }): Promise<VectorPoint[]> {
+    const layer = this.overlay.collections[name];
+    const hidden = this.hidden(name);
+    const local = ids.map(id => layer?.points[String(id)]).filter((p): p is VectorPoint => !!p)
+      .map(p => (options?.withVector ? p : { ...p, vector: [] }));
+    const shared = ids.filter(id => !hidden.has(String(id)));
+    const remote = shared.length > 0 && this.baseCollections.has(name)
+      ? await this.base.retrieve(name, shared, options)
+      : [];
+    return [...local, ...remote];
+  }
+
+  async search(
+    name: string,
+    vector: number[],
+    options: { limit: number; filter?: VectorFilter }
+  ): Promise<ScoredVectorPoint[]> {
+    const hidden = this.hidden(name);
+    const shared = this.baseCollections.has(name)
+      ? (await this.base.search(name, vector, { ...options, limit: options.limit + hidden.size }))
+        .filter(p => !hidden.has(String(p.id)))
+      : [];
+    const local = this.overlayPoints(name, options.filter)
+      .map(p => ({ id: p.id, score: cosineSimilarity(vector, p.vector), payload: p.payload }));
+    return [...shared, ...local].sort((a, b) => b.score - a.score).slice(0, options.limit);
+  }
+
+  /** Shared points first (cursors `b:`), then local ones (`o:`) */
+  async scroll(name: string, options: VectorScrollOptions): Promise<VectorScrollPage> {
+    const local = this.overlayPoints(name, options.filter);
+    const localStart = local.length > 0 ? `${OVERLAY_PREFIX}0` : undefined;
+
+    if (!options.offset?.startsWith(OVERLAY_PREFIX)) {
+      if (!this.baseCollections.has(name)) return this.scroll(name, { ...options, offset: `${OVERLAY_PREFIX}0` });
+      const hidden = this.hidden(name);
+      const page = await this.base.scroll(name, {
+        ...options,
+        offset: options.offset?.slice(BASE_PREFIX.length) || undefined
+      });
+      return {
+        points: page.points.filter(p => !hidden.has(String(p.id))),
+        nextOffset: page.nextOffset !== undefined ? `${BASE_PREFIX}${page.nextOffset}` : localStart
+      };
+    }
+
+    const start = Number(options.offset.slice(OVERLAY_PREFIX.length)) || 0;
+    const end = start + options.limit;
+    return {
+      points: local.slice(start, end).map(p => (options.withVector !== false ? p : { ...p, vector: [] })),
+      nextOffset: end < local.length ? `${OVERLAY_PREFIX}${end}` : undefined
+    };
+  }
+
+  async getCodec(name: string): Promise<VectorCodecInfo | null> {
+    return this.baseCollections.has(name) ? this.base.getCodec?.(name) ?? null : null;
+  }
+}
@@ -0,0 +1,203 @@
+/**
+ * QdrantStore — Qdrant server via @qdrant/js-client-rest.
+ *
+ * Filters are passed through unchanged since they already use Qdrant's shape.
+ */
+
+import { QdrantClient } from '@qdrant/js-client-rest';
+import type {
+  VectorStore,
+  VectorPoint,
+  ScoredVectorPoint,
+  VectorCodecInfo,
+  VectorCollectionStats,
+  VectorScrollOptions,
+  VectorScrollPage,
+  VectorFilter
+} from '../store.js';
+import { defaultSubvectors, QuantizationOptions, vectorBytes } from '../quantization.js';
+
+/**
+ * Qdrant ids are unsigned integers or UUIDs; embedded stores hand back
+ * VectorManager's numeric ids as strings
+ */
+function toPointId(id: string | number): string | number {
+  return typeof id === 'string' && /^\d+$/.test(id) ? Number(id) : id;
+}
+
+export interface QdrantStoreOptions {
+  url: string;
+  /** Qdrant's own HNSW settings for new collections and searches */
+  hnsw?: {
+    m?: number;
+    efConstruction?: number;
+    efSearch?: number;
+  };
+  /** Qdrant's scalar or product quantization for new collections; searches rescore with the originals */
+  quantization?: QuantizationOptions;
+}
+
+/** Product quantization ratios Qdrant accepts */
+const PQ_COMPRESSIONS = [4, 8, 16, 32, 64];
+
+function quantizationConfig(options: QuantizationOptions | undefined, vectorSize: number): Record<string, any> {
+  if (options?.codec === 'int8') {
+    return { quantization_config: { scalar: { type: 'int8', quantile: 0.99, always_ram: true } } };
+  }
+  if (options?.codec === 'pq') {
+    const ratio = (vectorSize * 4) / (options.subvectors ?? defaultSubvectors(vectorSize));
+    const compression = PQ_COMPRESSIONS.reduce((best, c) => (Math.abs(c - ratio) < Math.abs(best - ratio) ? c : best));
+    return { quantization_config: { product: { compression: `x${compression}`, always_ram: true } } };
+  }
+  return {};
+}
+
+export class QdrantStore implements VectorStore {
+  readonly type = 'qdrant' as const;
+  private client: QdrantClient | null = null;
+
+  constructor(private options: QdrantStoreOptions) {}
+
+  private requireClient(): QdrantClient {
+    if (!this.client) {
+      throw new Error('QdrantStore: not connected');
+    }
+    return this.client;
+  }
+
+  async connect(): Promise<void> {
+    this.client = new QdrantClient({ url: this.options.url });
+    // Test connection
+    await this.client.getCollections();
+  }
+
+  async close(): Promise<void> {
+    this.client = null;
+  }
+
+  async listCollections(): Promise<string[]> {
+    const { collections } = await this.requireClient().getCollections();
+    return collections.map(c => c.name);
+  }
+
+  async createCollection(name: string, vectorSize: number): Promise<void> {
+    if ((await this.listCollections()).includes(name)) return;
+    const hnsw = this.options.hnsw;
+    await this.requireClient().createCollection(name, {
+      vectors: {
+        size: vectorSize,
+        distance: 'Cosine'
+      },
+      ...(hnsw && (hnsw.m || hnsw.efConstruction) ? { hnsw_config: { m: hnsw.m, ef_construct: hnsw.efConstruction } } : {}),
+      ...quantizationConfig(this.options.quantization, vectorSize)
+    });
+  }
+
+  async deleteCollection(name: string): Promise<void> {
+    await this.requireClient().deleteCollection(name);
+  }
+
+  async getCollectionStats(name: string): Promise<VectorCollectionStats | null> {
+    try {
+      const info = await this.requireClient().getCollection(name);
+      return {
+        vectorSize: (info.config?.params?.vectors as { size?: number } | undefined)?.size ?? 0,
+        pointsCount: info.points_count ?? 0
+      };
+    } catch (error: any) {
+      if (error.message?.includes('not found') || error.status === 404) {
+        return null;
+      }
+      throw error;
+    }
+  }
+
+  async getCodec(name: string): Promise<VectorCodecInfo | null> {
+    const info = await this.requireClient().getCollection(name);
+    const config = info.config?.quantization_config as { scalar?: unknown; product?: { compression?: string } } | null | undefined;
+    const size = (info.config?.params?.vectors as { size?: number } | undefined)?.size ?? 0;
+    if (config?.scalar) return { codec: 'int8', bytesPerVector: vectorBytes('int8', size), trained: true };
+    if (config?.product) {
+      const ratio = parseInt(config.product.compression?.slice(1) ?? '', 10) || 16;
+      return { codec: 'pq', bytesPerVector: Math.ceil((size * 4) / ratio), trained: true };
+    }
+    return null;
+  }
+
+  async upsert(name: string, points: VectorPoint[]): Promise<void> {
+    await this.requireClient().upsert(name, {
+      wait: true,
+      points: points.map(p => ({ id: toPointId(p.id), vector: p.vector, payload: p.payload }))
+    });
+  }
+
+  async delete(name: string, ids: Array<string | number>): Promise<void> {
+    await this.requireClient().delete(name, {
+      wait: true,
+      points: ids.map(toPointId)
+    });
+  }
+
+  async retrieve(name: string, ids: Array<string | number>, options: { withVector?: boolean } = {}): Promise<VectorPoint[]> {
+    if (ids.length === 0) return [];
+    const points = await this.requireClient().retrieve(name, {
+      ids: ids.map(toPointId),
+      with_payload: true,
+      with_vector: options.withVector ?? false
+    });
+    return points.map(p => ({
+      id: p.id,
+      vector: (p.vector || []) as number[],
+      payload: (p.payload || {}) as Record<string, unknown>
+    }));
+  }
+
+  async search(
+    name: string,
+    vector: number[],
+    options: { limit: number; filter?: VectorFilter }
+  ): Promise<ScoredVectorPoint[]> {
+    const results = await this.requireClient().search(name, {
+      vector,
+      limit: options.limit,
+      filter: options.filter as any,
+      with_payload: true,
+      params: {
+        ...(this.options.hnsw?.efSearch ? { hnsw_ef: this.options.hnsw.efSearch } : {}),
+        ...(this.options.quantization?.codec && this.options.quantization.codec !== 'none'
+          ? { quantization: { rescore: true, oversampling: 2 } }
+          : {})
+      }
+    });
+    return results.map(r => ({
+      id: r.id,
+      score: r.score,
+      payload: (r.payload || {}) as Record<string, unknown>
+    }));
+  }
+
+  async scroll(name: string, options: VectorScrollOptions): Promise<VectorScrollPage> {
+    const scrollOptions: any = {
+      limit: options.limit,
+      filter: options.filter,
+      with_vector: options.withVector ?? true,
+      with_payload: true
+    };
+
+    // Qdrant scroll API: offset is a point ID (number or string)
+    if (options.offset) {
+      const parsedOffset = parseInt(options.offset, 10);
+      scrollOptions.offset = isNaN(parsedOffset) ? options.offset : parsedOffset;
+    }
+
+    const result = await this.requireClient().scroll(name, scrollOptions);
+    return {
+      points: result.points.map(p => ({
+        id: p.id,
+        vector: (p.vector || []) as number[],
+        payload: (p.payload || {}) as Record<string, unknown>
+      })),
+      nextOffset: result.next_page_offset != null ? String(result.next_page_offset) : undefined
+    };
+  }
+}
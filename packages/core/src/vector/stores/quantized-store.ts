@@ -0,0 +1,375 @@
+/**
+ * QuantizedVectorStore — compressed vectors over an embedded store.
+ *
+ * With config.vector.quantization.codec set to int8 or pq, new collections
+ * keep a code per point instead of its f32 vector: the inner store holds the
+ * payloads with the code under `_q` and a one-dimensional placeholder
+ * vector, and <indexDir>/quantization/<collection>.json holds the dimension
+ * and the trained codec. Points are stored f32 until the collection reaches
+ * `trainPoints`, then the codec is trained on them and every point is
+ * re-encoded.
+ *
+ * Searches score the query against every code without decoding it
+ * (asymmetric distance computation). The codes are kept in memory, which at
+ * 1 byte/dimension (int8) or 1 byte/subvector (pq) stays small enough to
+ * scan, so quantized collections need no HNSW graph. Retrieved and scrolled
+ * vectors are decoded on the fly. Collections created without quantization
+ * pass through unchanged until compact() converts them.
+ */
+
+import * as fs from 'fs/promises';
+import * as path from 'path';
+import type {
+  VectorStore,
+  VectorPoint,
+  ScoredVectorPoint,
+  VectorCodecInfo,
+  VectorCollectionStats,
+  VectorScrollOptions,
+  VectorScrollPage,
+  VectorFilter
+} from '../store.js';
+import { matchesFilter } from '../store.js';
+import {
+  codecFromJSON,
+  DEFAULT_TRAIN_POINTS,
+  F32Codec,
+  normalizeVector,
+  QuantizationOptions,
+  SerializedCodec,
+  trainCodec,
+  VectorCodec
+} from '../quantization.js';
+
+/** Payload key holding a point's code */
+const CODE_KEY = '_q';
+const BATCH = 256;
+/** Vectors sampled to train a codec */
+const TRAIN_SAMPLE = 4096;
+/** Suffix of the collection a collection is copied to while it is converted */
+const CONVERTING_SUFFIX = '__quantizing';
+
+interface CollectionMeta {
+  dimension: number;
+  /** Trained codec; null while points are still stored f32 */
+  codec: SerializedCodec | null;
+  /** Set while compact() copies a plain collection back in quantized */
+  converting?: boolean;
+}
+
+interface LoadedCollection {
+  meta: CollectionMeta;
+  f32: F32Codec;
+  codec: VectorCodec | null;
+  /** id → code, once a search needed them */
+  codes?: Map<string, { codec: VectorCodec; code: Uint8Array }>;
+}
+
+export class QuantizedVectorStore implements VectorStore {
+  private collections = new Map<string, LoadedCollection | null>();
+  private trainPoints: number;
+
+  constructor(private inner: VectorStore, private dir: string, private options: QuantizationOptions) {
+    this.trainPoints = options.trainPoints ?? DEFAULT_TRAIN_POINTS;
+  }
+
+  get type() {
+    return this.inner.type;
+  }
+
+  private metaPath(name: string): string {
+    return path.join(this.dir, `${name.replace(/[^\w.-]/g, '_')}.json`);
+  }
+
+  /**
+   * The collection's quantization state, or null for a plain collection
+   */
+  private async load(name: string): Promise<LoadedCollection | null> {
+    if (this.collections.has(name)) return this.collections.get(name)!;
+    let loaded: LoadedCollection | null = null;
+    try {
+      const meta: CollectionMeta = JSON.parse(await fs.readFile(this.metaPath(name), 'utf-8'));
+      loaded = { meta, f32: new F32Codec(meta.dimension), codec: meta.codec ? codecFromJSON(meta.codec) : null };
+    } catch {
+      loaded = null;
+    }
+    this.collections.set(name, loaded);
+    return loaded;
+  }
+
+  private async saveMeta(name: string, meta: CollectionMeta): Promise<void> {
+    await fs.mkdir(this.dir, { recursive: true });
+    const file = this.metaPath(name);
+    await fs.writeFile(`${file}.tmp`, JSON.stringify(meta), 'utf-8');
+    await fs.rename(`${file}.tmp`, file);
+  }
+
+  private encode(collection: LoadedCollection, vector: number[]): string {
+    const codec = collection.codec ?? collection.f32;
+    return `${codec.name}:${Buffer.from(codec.encode(normalizeVector(vector))).toString('base64')}`;
+  }
+
+  private parseCode(collection: LoadedCollection, value: unknown): { codec: VectorCodec; code: Uint8Array } | null {
+    if (typeof value !== 'string') return null;
+    const split = value.indexOf(':');
+    const name = value.slice(0, split);
+    const codec = name === 'f32' ? collection.f32 : collection.codec?.name === name ? collection.codec : null;
+    return codec ? { codec, code: new Uint8Array(Buffer.from(value.slice(split + 1), 'base64')) } : null;
+  }
+
+  /** A stored point as callers see it: decoded vector, no code in the payload */
+  private toPoint(collection: LoadedCollection, point: VectorPoint, withVector: boolean): VectorPoint {
+    const { [CODE_KEY]: code, ...payload } = point.payload;
+    const parsed = withVector ? this.parseCode(collection, code) : null;
+    return { id: point.id, vector: parsed ? Array.from(parsed.codec.decode(parsed.code)) : [], payload };
+  }
+
+  connect(): Promise<void> {
+    return this.inner.connect();
+  }
+
+  async close(): Promise<void> {
+    this.collections.clear();
+    await this.inner.close();
+  }
+
+  listCollections(): Promise<string[]> {
+    return this.inner.listCollections();
+  }
+
+  async createCollection(name: string, vectorSize: number): Promise<void> {
+    if ((await this.inner.listCollections()).includes(name)) return;
+    if (!this.options.codec || this.options.codec === 'none') {
+      return this.inner.createCollection(name, vectorSize);
+    }
+    const meta: CollectionMeta = { dimension: vectorSize, codec: null };
+    await this.saveMeta(name, meta);
+    this.collections.set(name, { meta, f32: new F32Codec(vectorSize), codec: null });
+    await this.inner.createCollection(name, 1);
+  }
+
+  async deleteCollection(name: string): Promise<void> {
+    await this.inner.deleteCollection(name);
+    this.collections.delete(name);
+    await fs.rm(this.metaPath(name), { force: true });
+  }
+
+  async getCollectionStats(name: string): Promise<VectorCollectionStats | null> {
+    const stats = await this.inner.getCollectionStats(name);
+    const collection = stats && await this.load(name);
+    return stats && collection ? { vectorSize: collection.meta.dimension, pointsCount: stats.pointsCount } : stats;
+  }
+
+  async getCodec(name: string): Promise<VectorCodecInfo | null> {
+    const collection = await this.load(name);
+    if (!collection) return null;
+    const codec = collection.codec ?? collection.f32;
+    return { codec: codec.name, bytesPerVector: codec.bytesPerVector, trained: !!collection.codec };
+  }
+
+  async upsert(name: string, points: VectorPoint[]): Promise<void> {
+    const collection = await this.load(name);
+    if (!collection) return this.inner.upsert(name, points);
+
+    const encoded = points.map(point => {
+      if (point.vector.length !== collection.meta.dimension) {
+        throw new Error(`QuantizedVectorStore: expected ${collection.meta.dimension} dimensions, got ${point.vector.length}`);
+      }
+      return { id: point.id, vector: [0], payload: { ...point.payload, [CODE_KEY]: this.encode(collection, point.vector) } };
+    });
+    await this.inner.upsert(name, encoded);
+    for (const point of encoded) {
+      const parsed = this.parseCode(collection, point.payload[CODE_KEY]);
+      if (collection.codes && parsed) collection.codes.set(String(point.id), parsed);
+    }
+
+    if (!collection.codec && !collection.meta.converting) {
+      const stats = await this.inner.getCollectionStats(name);
+      if (stats && stats.pointsCount >= this.trainPoints) await this.train(name, collection);
+    }
+  }
+
+  /**
+   * Train the codec on a sample of the collection, then re-encode every point
+   */
+  private async train(name: string, collection: LoadedCollection): Promise<void> {
+    const stats = await this.inner.getCollectionStats(name);
+    const stride = Math.max(1, Math.ceil((stats?.pointsCount ?? 0) / TRAIN_SAMPLE));
+    const ids: Array<string | number> = [];
+    const vectors: Float32Array[] = [];
+    let offset: string | undefined;
+    do {
+      const page = await this.inner.scroll(name, { limit: BATCH, offset, withVector: false });
+      for (const point of page.points) {
+        const parsed = this.parseCode(collection, point.payload[CODE_KEY]);
+        if (!parsed) continue;
+        if (ids.length % stride === 0) vectors.push(parsed.codec.decode(parsed.code));
+        ids.push(point.id);
+      }
+      offset = page.nextOffset;
+    } while (offset);
+    if (vectors.length === 0) return;
+
+    const codec = trainCodec(vectors, this.options);
+    collection.codec = codec;
+    collection.meta = { ...collection.meta, codec: codec.toJSON() };
+    await this.saveMeta(name, collection.meta);
+
+    for (let i = 0; i < ids.length; i += BATCH) {
+      const points = await this.retrieve(name, ids.slice(i, i + BATCH), { withVector: true });
+      await this.inner.upsert(name, points.map(point => ({
+        id: point.id,
+        vector: [0],
+        payload: { ...point.payload, [CODE_KEY]: this.encode(collection, point.vector) }
+      })));
+    }
+    collection.codes = undefined;
+  }
+
+  async delete(name: string, ids: Array<string | number>): Promise<void> {
+    await this.inner.delete(name, ids);
+    const codes = this.collections.get(name)?.codes;
+    for (const id of ids) codes?.delete(String(id));
+  }
+
+  async retrieve(name: string, ids: Array<string | number>, options: { withVector?: boolean } = {}): Promise<VectorPoint[]> {
+    const collection = await this.load(name);
+    if (!collection) return this.inner.retrieve(name, ids, options);
+    const points = await this.inner.retrieve(name, ids);
+    return points.map(point => this.toPoint(collection, point, options.withVector ?? false));
+  }
+
+  async scroll(name: string, options: VectorScrollOptions): Promise<VectorScrollPage> {
+    const collection = await this.load(name);
+    if (!collection) return this.inner.scroll(name, options);
+    const page = await this.inner.scroll(name, { ...options, withVector: false });
+    return {
+      points: page.points.map(point => this.toPoint(collection, point, options.withVector ?? true)),
+      nextOffset: page.nextOffset
+    };
+  }
+
+  /**
+   * Every code of the collection, reloaded when another process changed it
+   */
+  private async codes(name: string, collection: LoadedCollection): Promise<Map<string, { codec: VectorCodec; code: Uint8Array }>> {
+    const stats = await this.inner.getCollectionStats(name);
+    if (collection.codes && collection.codes.size === stats?.pointsCount) return collection.codes;
+
+    const codes = new Map<string, { codec: VectorCodec; code: Uint8Array }>();
+    let offset: string | undefined;
+    do {
+      const page = await this.inner.scroll(name, { limit: 1000, offset, withVector: false });
+      for (const point of page.points) {
+        const parsed = this.parseCode(collection, point.payload[CODE_KEY]);
+        if (parsed) codes.set(String(point.id), parsed);
+      }
+      offset = page.nextOffset;
+    } while (offset);
+    collection.codes = codes;
+    return codes;
+  }
+
+  async search(
+    name: string,
+    vector: number[],
+    options: { limit: number; filter?: VectorFilter }
+  ): Promise<ScoredVectorPoint[]> {
+    const collection = await this.load(name);
+    if (!collection) return this.inner.search(name, vector, options);
+
+    const query = normalizeVector(vector);
+    const scorers = new Map<VectorCodec, (code: Uint8Array) => number>();
+    const scored: Array<{ id: string; score: number }> = [];
+    for (const [id, { codec, code }] of await this.codes(name, collection)) {
+      let scorer = scorers.get(codec);
+      if (!scorer) scorers.set(codec, scorer = codec.scorer(query));
+      scored.push({ id, score: scorer(code) });
+    }
+    scored.sort((a, b) => b.score - a.score);
+
+    // Payloads in score order until enough pass the filter
+    const results: ScoredVectorPoint[] = [];
+    const batch = options.filter ? BATCH : options.limit;
+    for (let i = 0; i < scored.length && results.length < options.limit; i += batch) {
+      const hits = scored.slice(i, i + batch);
+      const points = new Map((await this.retrieve(name, hits.map(h => h.id))).map(p => [String(p.id), p]));
+      for (const hit of hits) {
+        const point = points.get(hit.id);
+        if (!point || !matchesFilter(point.payload, options.filter)) continue;
+        results.push({ id: point.id, score: hit.score, payload: point.payload });
+        if (results.length >= options.limit) break;
+      }
+    }
+    return results;
+  }
+
+  /**
+   * Compact the inner store, train codecs of collections that grew enough,
+   * and convert plain collections of at least `trainPoints` points
+   */
+  async compact(): Promise<void> {
+    await this.inner.compact?.();
+    if (!this.options.codec || this.options.codec === 'none') return;
+
+    const names = await this.inner.listCollections();
+    for (const name of names) {
+      if (name.endsWith(CONVERTING_SUFFIX)) continue;
+      const collection = await this.load(name);
+      const stats = await this.inner.getCollectionStats(name);
+      if (collection?.meta.converting) {
+        await this.convert(name, collection.meta.dimension);
+      } else if (!collection && stats && stats.pointsCount >= this.trainPoints) {
+        await this.convert(name, stats.vectorSize);
+      } else if (collection && !collection.codec && stats && stats.pointsCount >= this.trainPoints) {
+        await this.train(name, collection);
+      }
+    }
+  }
+
+  /**
+   * Rewrite a plain collection quantized: copy it aside, recreate it and
+   * copy it back. Safe to re-run after an interruption.
+   */
+  private async convert(name: string, dimension: number): Promise<void> {
+    const temp = `${name}${CONVERTING_SUFFIX}`;
+    let collection = await this.load(name);
+
+    if (!collection?.meta.converting) {
+      await this.inner.deleteCollection(temp);
+      await this.inner.createCollection(temp, dimension);
+      await copyPoints(this.inner, name, temp, page => page);
+
+      const meta: CollectionMeta = { dimension, codec: null, converting: true };
+      await this.saveMeta(name, meta);
+      await this.inner.deleteCollection(name);
+      await this.inner.createCollection(name, 1);
+      collection = { meta, f32: new F32Codec(dimension), codec: null };
+      this.collections.set(name, collection);
+    }
+
+    await copyPoints(this.inner, temp, name, points => points.map(point => ({
+      id: point.id,
+      vector: [0],
+      payload: { ...point.payload, [CODE_KEY]: this.encode(collection!, point.vector) }
+    })));
+    collection.meta = { dimension, codec: null };
+    await this.saveMeta(name, collection.meta);
+    await this.train(name, collection);
+    await this.inner.deleteCollection(temp);
+  }
+}
+
+async function copyPoints(
+  store: VectorStore,
+  from: string,
+  to: string,
+  map: (points: VectorPoint[]) => VectorPoint[]
+): Promise<void> {
+  let offset: string | undefined;
+  do {
+    const page = await store.scroll(from, { limit: BATCH, offset, withVector: true });
+    if (page.points.length > 0) await store.upsert(to, map(page.points));
+    offset = page.nextOffset;
+  } while (offset);
+}
@@ -0,0 +1,162 @@
+/**
+ * RemoteVectorStore — a shared index hosted by `cv index serve`.
+ *
+ * Reads go to the server (see index-server.ts for the API); it is updated
+ * only by the server's own sync, so writes are refused. createVectorStore()
+ * wraps it in LayeredVectorStore, which keeps local changes in an overlay.
+ */
+
+import { VectorError } from '@cv-git/shared';
+import type {
+  VectorStore,
+  VectorStoreType,
+  VectorPoint,
+  ScoredVectorPoint,
+  VectorCodecInfo,
+  VectorCollectionStats,
+  VectorScrollOptions,
+  VectorScrollPage,
+  VectorFilter
+} from '../store.js';
+import { INDEX_PROTOCOL_VERSION, IndexServerInfo, SharedSyncState } from '../index-server.js';
+import { assertOnline } from '../../ai/offline.js';
+
+export interface RemoteStoreOptions {
+  /** Server URL (config.index.remote) */
+  url: string;
+  /** Bearer token (config.index.token or CV_INDEX_TOKEN) */
+  token?: string;
+  /** Per-request timeout (default 30s) */
+  timeoutMs?: number;
+}
+
+export class RemoteVectorStore implements VectorStore {
+  /** The server's backend; known once connected */
+  type: VectorStoreType = 'qdrant';
+  private serverInfo: IndexServerInfo | null = null;
+  private baseUrl: string;
+
+  constructor(private options: RemoteStoreOptions) {
+    this.baseUrl = options.url.replace(/\/+$/, '');
+  }
+
+  get url(): string {
+    return this.baseUrl;
+  }
+
+  private async request<T>(method: 'GET' | 'POST', route: string, body?: unknown): Promise<T | null> {
+    assertOnline(`The shared index at ${this.baseUrl}`, this.baseUrl);
+    let response: Response;
+    try {
+      response = await fetch(`${this.baseUrl}${route}`, {
+        method,
+        headers: {
+          ...(this.options.token ? { Authorization: `Bearer ${this.options.token}` } : {}),
+          ...(body !== undefined ? { 'Content-Type': 'application/json' } : {})
+        },
+        body: body !== undefined ? JSON.stringify(body) : undefined,
+        signal: AbortSignal.timeout(this.options.timeoutMs ?? 30000)
+      });
+    } catch (error: any) {
+      throw new VectorError(`Shared index at ${this.baseUrl} is unreachable: ${error.message}`, error);
+    }
+    if (response.status === 404 && method === 'GET') return null;
+    if (!response.ok) {
+      const message = await response.json().then((b: any) => b?.error, () => undefined);
+      if (response.status === 401) {
+        throw new VectorError(`Shared index at ${this.baseUrl} rejected the token; set index.token or CV_INDEX_TOKEN`);
+      }
+      throw new VectorError(`Shared index at ${this.baseUrl}: ${message || `HTTP ${response.status}`}`);
+    }
+    return await response.json() as T;
+  }
+
+  private readOnly(): never {
+    throw new VectorError(`The shared index at ${this.baseUrl} is read-only`);
+  }
+
+  private collectionRoute(name: string, action?: string): string {
+    return `/v1/collections/${encodeURIComponent(name)}${action ? `/${action}` : ''}`;
+  }
+
+  async connect(): Promise<void> {
+    const info = await this.request<IndexServerInfo>('GET', '/v1/info');
+    if (!info || info.version !== INDEX_PROTOCOL_VERSION) {
+      throw new VectorError(`${this.baseUrl} is not a cv index server this version can read`);
+    }
+    this.serverInfo = info;
+    this.type = info.storeType;
+  }
+
+  async close(): Promise<void> {
+    this.serverInfo = null;
+  }
+
+  /** What the server reported on connect */
+  info(): IndexServerInfo {
+    if (!this.serverInfo) throw new VectorError('RemoteVectorStore: not connected');
+    return this.serverInfo;
+  }
+
+  /** Files the served index was synced from; null before its first sync */
+  syncState(): Promise<SharedSyncState | null> {
+    return this.request<SharedSyncState>('GET', '/v1/state');
+  }
+
+  async listCollections(): Promise<string[]> {
+    const body = await this.request<{ collections: string[] }>('GET', '/v1/collections');
+    return body?.collections ?? [];
+  }
+
+  async createCollection(name: string): Promise<void> {
+    if (!(await this.getCollectionStats(name))) this.readOnly();
+  }
+
+  async deleteCollection(): Promise<void> {
+    this.readOnly();
+  }
+
+  async getCollectionStats(name: string): Promise<VectorCollectionStats | null> {
+    const body = await this.request<{ stats: VectorCollectionStats }>('GET', this.collectionRoute(name));
+    return body?.stats ?? null;
+  }
+
+  async getCodec(name: string): Promise<VectorCodecInfo | null> {
+    const body = await this.request<{ codec: VectorCodecInfo | null }>('GET', this.collectionRoute(name));
+    return body?.codec ?? null;
+  }
+
+  async upsert(): Promise<void> {
+    this.readOnly();
+  }
+
+  async delete(): Promise<void> {
+    this.readOnly();
+  }
+
+  async retrieve(name: string, ids: Array<string | number>, options?: { withVector?: boolean }): Promise<VectorPoint[]> {
+    const body = await this.request<{ points: VectorPoint[] }>('POST', this.collectionRoute(name, 'retrieve'), {
+      ids,
+      withVector: options?.withVector === true
+    });
+    return body?.points ?? [];
+  }
+
+  async search(
+    name: string,
+    vector: number[],
+    options: { limit: number; filter?: VectorFilter }
+  ): Promise<ScoredVectorPoint[]> {
+    const body = await this.request<{ points: ScoredVectorPoint[] }>('POST', this.collectionRoute(name, 'search'), {
+      vector,
+      limit: options.limit,
+      filter: options.filter
+    });
+    return body?.points ?? [];
+  }
+
+  async scroll(name: string, options: VectorScrollOptions): Promise<VectorScrollPage> {
+    const body = await this.request<VectorScrollPage>('POST', this.collectionRoute(name, 'scroll'), options);
+    return body ?? { points: [] };
+  }
+}
@@ -0,0 +1,277 @@
+/**
+ * SqliteVecStore — embedded SQLite with the sqlite-vec extension.
+ *
+ * One database file holds every collection: a `points_<n>` table for ids
+ * and JSON payloads, and a `vec_<n>` vec0 virtual table (cosine distance)
+ * sharing its rowids. Unfiltered searches use vec0's KNN index; filtered
+ * searches scan in distance order until enough payloads match. The file is
+ * memory-mapped, so a fresh process reads the pages a query touches instead
+ * of paging the database in through SQLite's cache.
+ */
+
+import * as fs from 'fs';
+import * as path from 'path';
+import type {
+  VectorStore,
+  VectorPoint,
+  ScoredVectorPoint,
+  VectorCollectionStats,
+  VectorScrollOptions,
+  VectorScrollPage,
+  VectorFilter
+} from '../store.js';
+import { matchesFilter } from '../store.js';
+
+export interface SqliteVecStoreOptions {
+  /** Database file */
+  dbPath: string;
+}
+
+/** Bytes of the database SQLite maps instead of reading (mmap_size) */
+const MMAP_BYTES = 1 << 30;
+
+interface CollectionRow {
+  table_id: number;
+  vector_size: number;
+}
+
+function toBlob(vector: number[]): Buffer {
+  return Buffer.from(new Float32Array(vector).buffer);
+}
+
+function fromBlob(blob: Buffer): number[] {
+  return Array.from(new Float32Array(blob.buffer, blob.byteOffset, blob.byteLength / 4));
+}
+
+export class SqliteVecStore implements VectorStore {
+  readonly type = 'sqlite-vec' as const;
+  private db: any = null;
+
+  constructor(private options: SqliteVecStoreOptions) {}
+
+  private requireDb(): any {
+    if (!this.db) {
+      throw new Error('SqliteVecStore: not connected');
+    }
+    return this.db;
+  }
+
+  private collection(name: string): CollectionRow | undefined {
+    return this.requireDb()
+      .prepare('SELECT table_id, vector_size FROM collections WHERE name = ?')
+      .get(name);
+  }
+
+  private requireCollection(name: string): CollectionRow {
+    const collection = this.collection(name);
+    if (!collection) {
+      throw new Error(`SqliteVecStore: collection ${name} does not exist`);
+    }
+    return collection;
+  }
+
+  async connect(): Promise<void> {
+    const { default: Database } = await import('better-sqlite3');
+    const sqliteVec = await import('sqlite-vec');
+
+    fs.mkdirSync(path.dirname(this.options.dbPath), { recursive: true });
+    this.db = new Database(this.options.dbPath);
+    sqliteVec.load(this.db);
+    this.db.pragma('journal_mode = WAL');
+    this.db.pragma(`mmap_size = ${MMAP_BYTES}`);
+    this.db.exec(`
+      CREATE TABLE IF NOT EXISTS collections (
+        table_id INTEGER PRIMARY KEY AUTOINCREMENT,
+        name TEXT UNIQUE NOT NULL,
+        vector_size INTEGER NOT NULL
+      )
+    `);
+  }
+
+  async close(): Promise<void> {
+    if (this.db) {
+      try { this.db.close(); } catch { /* ignore */ }
+      this.db = null;
+    }
+  }
+
+  async listCollections(): Promise<string[]> {
+    return this.requireDb().prepare('SELECT name FROM collections ORDER BY name').all().map((r: any) => r.name);
+  }
+
+  async createCollection(name: string, vectorSize: number): Promise<void> {
+    if (this.collection(name)) return;
+    const db = this.requireDb();
+    db.transaction(() => {
+      const { lastInsertRowid } = db
+        .prepare('INSERT INTO collections (name, vector_size) VALUES (?, ?)')
+        .run(name, vectorSize);
+      const n = Number(lastInsertRowid);
+      db.exec(`CREATE TABLE points_${n} (rowid INTEGER PRIMARY KEY, id TEXT UNIQUE NOT NULL, payload TEXT NOT NULL)`);
+      db.exec(`CREATE VIRTUAL TABLE vec_${n} USING vec0(embedding float[${vectorSize}] distance_metric=cosine)`);
+    })();
+  }
+
+  async deleteCollection(name: string): Promise<void> {
+    const collection = this.collection(name);
+    if (!collection) return;
+    const db = this.requireDb();
+    db.transaction(() => {
+      db.exec(`DROP TABLE IF EXISTS vec_${collection.table_id}`);
+      db.exec(`DROP TABLE IF EXISTS points_${collection.table_id}`);
+      db.prepare('DELETE FROM collections WHERE name = ?').run(name);
+    })();
+  }
+
+  async compact(): Promise<void> {
+    const db = this.requireDb();
+    db.pragma('wal_checkpoint(TRUNCATE)');
+    db.exec('VACUUM');
+  }
+
+  async getCollectionStats(name: string): Promise<VectorCollectionStats | null> {
+    const collection = this.collection(name);
+    if (!collection) return null;
+    const { count } = this.requireDb()
+      .prepare(`SELECT COUNT(*) AS count FROM points_${collection.table_id}`)
+      .get();
+    return { vectorSize: collection.vector_size, pointsCount: count };
+  }
+
+  async upsert(name: string, points: VectorPoint[]): Promise<void> {
+    const { table_id: n, vector_size: vectorSize } = this.requireCollection(name);
+    const db = this.requireDb();
+    const find = db.prepare(`SELECT rowid FROM points_${n} WHERE id = ?`);
+    const insertPoint = db.prepare(`INSERT INTO points_${n} (id, payload) VALUES (?, ?)`);
+    const updatePoint = db.prepare(`UPDATE points_${n} SET payload = ? WHERE rowid = ?`);
+    const deleteVector = db.prepare(`DELETE FROM vec_${n} WHERE rowid = ?`);
+    const insertVector = db.prepare(`INSERT INTO vec_${n} (rowid, embedding) VALUES (?, ?)`);
+
+    db.transaction(() => {
+      for (const point of points) {
+        if (point.vector.length !== vectorSize) {
+          throw new Error(`SqliteVecStore: expected ${vectorSize} dimensions, got ${point.vector.length}`);
+        }
+        const id = String(point.id);
+        const payload = JSON.stringify(point.payload);
+        const existing = find.get(id);
+        let rowid: number;
+        if (existing) {
+          rowid = existing.rowid;
+          updatePoint.run(payload, rowid);
+          deleteVector.run(BigInt(rowid));
+        } else {
+          rowid = Number(insertPoint.run(id, payload).lastInsertRowid);
+        }
+        // vec0 only accepts integer rowids bound as BigInt
+        insertVector.run(BigInt(rowid), toBlob(point.vector));
+      }
+    })();
+  }
+
+  async delete(name: string, ids: Array<string | number>): Promise<void> {
+    const collection = this.collection(name);
+    if (!collection) return;
+    const n = collection.table_id;
+    const db = this.requireDb();
+    const find = db.prepare(`SELECT rowid FROM points_${n} WHERE id = ?`);
+    const deletePoint = db.prepare(`DELETE FROM points_${n} WHERE rowid = ?`);
+    const deleteVector = db.prepare(`DELETE FROM vec_${n} WHERE rowid = ?`);
+
+    db.transaction(() => {
+      for (const id of ids) {
+        const existing = find.get(String(id));
+        if (!existing) continue;
+        deletePoint.run(existing.rowid);
+        deleteVector.run(BigInt(existing.rowid));
+      }
+    })();
+  }
+
+  async retrieve(name: string, ids: Array<string | number>, options: { withVector?: boolean } = {}): Promise<VectorPoint[]> {
+    const collection = this.collection(name);
+    if (!collection || ids.length === 0) return [];
+    const n = collection.table_id;
+    const withVector = options.withVector ?? false;
+    const find = this.requireDb().prepare(`
+      SELECT p.id, p.payload${withVector ? ', v.embedding' : ''}
+      FROM points_${n} p${withVector ? ` JOIN vec_${n} v ON v.rowid = p.rowid` : ''}
+      WHERE p.id = ?
+    `);
+
+    const points: VectorPoint[] = [];
+    for (const id of ids) {
+      const row = find.get(String(id));
+      if (row) {
+        points.push({ id: row.id, vector: withVector ? fromBlob(row.embedding) : [], payload: JSON.parse(row.payload) });
+      }
+    }
+    return points;
+  }
+
+  async search(
+    name: string,
+    vector: number[],
+    options: { limit: number; filter?: VectorFilter }
+  ): Promise<ScoredVectorPoint[]> {
+    const collection = this.collection(name);
+    if (!collection) return [];
+    const n = collection.table_id;
+    const db = this.requireDb();
+
+    if (!options.filter) {
+      const rows = db.prepare(`
+        SELECT p.id, p.payload, v.distance
+        FROM vec_${n} v JOIN points_${n} p ON p.rowid = v.rowid
+        WHERE v.embedding MATCH ? AND k = ?
+        ORDER BY v.distance
+      `).all(toBlob(vector), options.limit);
+      return rows.map((r: any) => ({ id: r.id, score: 1 - r.distance, payload: JSON.parse(r.payload) }));
+    }
+
+    const results: ScoredVectorPoint[] = [];
+    const rows = db.prepare(`
+      SELECT p.id, p.payload, vec_distance_cosine(v.embedding, ?) AS distance
+      FROM points_${n} p JOIN vec_${n} v ON v.rowid = p.rowid
+      ORDER BY distance
+    `).iterate(toBlob(vector));
+    for (const row of rows as Iterable<any>) {
+      const payload = JSON.parse(row.payload);
+      if (!matchesFilter(payload, options.filter)) continue;
+      results.push({ id: row.id, score: 1 - row.distance, payload });
+      if (results.length >= options.limit) break;
+    }
+    return results;
+  }
+
+  async scroll(name: string, options: VectorScrollOptions): Promise<VectorScrollPage> {
+    const collection = this.collection(name);
+    if (!collection) return { points: [] };
+    const n = collection.table_id;
+    const withVector = options.withVector ?? true;
+
+    // Keyset pagination: the offset is the last rowid returned
+    const after = options.offset ? parseInt(options.offset, 10) : 0;
+    const rows = this.requireDb().prepare(`
+      SELECT p.rowid, p.id, p.payload${withVector ? ', v.embedding' : ''}
+      FROM points_${n} p${withVector ? ` JOIN vec_${n} v ON v.rowid = p.rowid` : ''}
+      WHERE p.rowid > ?
+      ORDER BY p.rowid
+    `).iterate(after);
+
+    const points: VectorPoint[] = [];
+    let lastRowid = after;
+    let more = false;
+    for (const row of rows as Iterable<any>) {
+      const payload = JSON.parse(row.payload);
+      if (!matchesFilter(payload, options.filter)) continue;
+      if (points.length >= options.limit) {
+        more = true;
+        break;
+      }
+      points.push({ id: row.id, vector: withVector ? fromBlob(row.embedding) : [], payload });
+      lastRowid = row.rowid;
+    }
+    return { points, nextOffset: more ? String(lastRowid) : undefined };
+  }
+}
@@ -0,0 +1,125 @@
+/**
+ * Workspace Repositories
+ *
+ * Sibling repositories registered with `cv workspace add` are listed in
+ * `workspace.repos` (paths relative to the repository root, so a team can
+ * commit them in .cvgit.toml). Each keeps its own index; `--workspace`
+ * queries search all of them and tag results with the repository they
+ * came from, so answers can cite `api:src/auth.ts:12-40`.
+ */
+
+import * as fs from 'fs/promises';
+import * as path from 'path';
+import { CodeChunkPayload, VectorSearchResult, isGitRepo } from '@cv-git/shared';
+import { reciprocalRankFusion } from '../context/keyword-index.js';
+import type { VectorManager } from '../vector/index.js';
+
+export interface WorkspaceMember {
+  /** Name used in citations (directory name, made unique) */
+  name: string;
+  /** Path as registered */
+  path: string;
+  /** Absolute repository root */
+  root: string;
+  exists: boolean;
+  /** Has .cv/config.json (`cv init` was run) */
+  initialized: boolean;
+}
+
+export interface WorkspaceIndex {
+  /** Repository name; unset for the current repository */
+  name?: string;
+  vector: Pick<VectorManager, 'searchCode' | 'getEmbeddingInfo'>;
+}
+
+/**
+ * Resolve registered paths against the repository root
+ */
+export async function listWorkspaceMembers(repoRoot: string, paths: string[] = []): Promise<WorkspaceMember[]> {
+  const taken = new Set<string>([path.basename(repoRoot)]);
+  const members: WorkspaceMember[] = [];
+
+  for (const registered of paths) {
+    const root = path.resolve(repoRoot, registered);
+    const base = path.basename(root);
+    let name = base;
+    for (let n = 2; taken.has(name); n++) name = `${base}-${n}`;
+    taken.add(name);
+
+    members.push({
+      name,
+      path: registered,
+      root,
+      exists: await isGitRepo(root),
+      initialized: await fs.access(path.join(root, '.cv', 'config.json')).then(() => true, () => false)
+    });
+  }
+
+  return members;
+}
+
+/**
+ * Registered paths with `target` added
+ * @throws when it is not a git repository or already registered
+ */
+export async function addWorkspaceRepo(repoRoot: string, paths: string[] = [], target: string): Promise<string[]> {
+  const root = path.resolve(target);
+  if (root === path.resolve(repoRoot)) {
+    throw new Error('Cannot add the current repository to its own workspace');
+  }
+  if (!(await isGitRepo(root))) {
+    throw new Error(`Not a git repository: ${target}`);
+  }
+  if (paths.some(p => path.resolve(repoRoot, p) === root)) {
+    throw new Error(`Already in the workspace: ${target}`);
+  }
+
+  const relative = path.relative(repoRoot, root).split(path.sep).join('/');
+  return [...paths, relative];
+}
+
+/**
+ * Registered paths without the repository named or located by `target`
+ * @throws when no registered repository matches
+ */
+export async function removeWorkspaceRepo(repoRoot: string, paths: string[] = [], target: string): Promise<string[]> {
+  const members = await listWorkspaceMembers(repoRoot, paths);
+  const root = path.resolve(target);
+  const match = members.find(m => m.name === target || m.path === target || m.root === root);
+  if (!match) {
+    throw new Error(`Not in the workspace: ${target}`);
+  }
+  return paths.filter(p => p !== match.path);
+}
+
+/**
+ * Search code in every index and merge the results. Scores are compared
+ * directly when all indexes use the same embedding model, and fused by
+ * rank otherwise.
+ */
+export async function searchWorkspaceCode(
+  indexes: WorkspaceIndex[],
+  query: string,
+  limit: number,
+  options?: { minScore?: number }
+): Promise<VectorSearchResult<CodeChunkPayload>[]> {
+  const rankings = await Promise.all(indexes.map(async index => {
+    try {
+      const results = await index.vector.searchCode(query, limit, options);
+      if (!index.name) return results;
+      const repo = index.name;
+      return results.map(result => ({ ...result, id: `${repo}:${result.id}`, payload: { ...result.payload, repo } }));
+    } catch (error: any) {
+      console.error(`Vector search failed${index.name ? ` in ${index.name}` : ''}: ${error.message}`);
+      return [];
+    }
+  }));
+
+  const models = new Set(indexes.map(index => index.vector.getEmbeddingInfo().model));
+  if (models.size <= 1) {
+    return rankings.flat().sort((a, b) => b.score - a.score).slice(0, limit);
+  }
+  return reciprocalRankFusion(rankings, chunk => chunk.id)
+    .slice(0, limit)
+    .map(({ item, score }) => ({ ...item, score }));
+}
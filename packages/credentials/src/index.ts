@@ -4,7 +4,7 @@
  * Platform-agnostic credential management for CV-Git
  *
  * Features:
- * - Secure storage (OS keychain or encrypted file)
+ * - Secure storage (OS keychain, or an encrypted file when no keychain is available)
  * - Support for multiple credential types (git platforms, AI services, etc.)
  * - Platform-agnostic design (works with GitHub, CV Platform, GitLab, etc.)
  * - Migration from environment variables
@@ -12,7 +12,11 @@
  */
 
 // Main manager
-export { CredentialManager, type CredentialManagerOptions } from './manager.js';
+export {
+  CredentialManager,
+  type CredentialManagerOptions,
+  type CredentialStorageInfo,
+} from './manager.js';
 
 // Storage backends
 export {
@@ -20,6 +24,9 @@ export {
   StorageBackend,
   KeychainStorage,
   EncryptedFileStorage,
+  PlainFileStorage,
+  getLocalMasterPassword,
+  defaultKeyPath,
 } from './storage/index.js';
 
 // Types
@@ -32,6 +39,7 @@ export {
   type AnthropicAPICredential,
   type OpenAIAPICredential,
   type OpenRouterAPICredential,
+  type GeminiAPICredential,
   type APIKeyCredential,
   // DNS providers
   type CloudflareCredential,
@@ -15,8 +15,12 @@ import * as path from 'path';
 import * as os from 'os';
 import {
   CredentialStorage,
+  StorageBackend,
   KeychainStorage,
+  EncryptedFileStorage,
   PlainFileStorage,
+  getLocalMasterPassword,
+  defaultKeyPath,
 } from './storage/index.js';
 import {
   Credential,
@@ -26,6 +30,7 @@ import {
   CreateCredentialInput,
   GitPlatformTokenCredential,
   AnthropicAPICredential,
+  GeminiAPICredential,
   OpenAIAPICredential,
   OpenRouterAPICredential,
   // DNS providers
@@ -45,37 +50,74 @@ export interface CredentialManagerOptions {
 
   /** Path to metadata file */
   metadataPath?: string;
+
+  /** Directory for the file backends (default: ~/.cv-git) */
+  dataDir?: string;
+
+  /**
+   * Generated key for the encrypted file, kept outside dataDir
+   * (default: defaultKeyPath(), under the XDG state directory)
+   */
+  keyPath?: string;
+}
+
+/**
+ * Where credentials are kept, for `cv auth status`
+ */
+export interface CredentialStorageInfo {
+  backend: string;
+  location?: string;
+  /** Why the OS keychain is not in use */
+  fallbackReason?: string;
+  /** Credentials moved out of the legacy plaintext file by init() */
+  migratedFromPlainFile: number;
 }
 
 export class CredentialManager {
   private storage: CredentialStorage;
   private metadataPath: string;
+  private dataDir: string;
+  private keyPath: string;
   private initialized: boolean = false;
+  private fallbackReason?: string;
+  private migratedFromPlainFile = 0;
 
   constructor(options?: CredentialManagerOptions) {
+    this.dataDir = options?.dataDir || path.join(os.homedir(), '.cv-git');
+    this.keyPath = options?.keyPath || defaultKeyPath();
     this.metadataPath =
       options?.metadataPath ||
-      path.join(os.homedir(), '.cv-git', 'credentials-metadata.json');
+      path.join(this.dataDir, 'credentials-metadata.json');
 
-    // Use provided storage or start with keychain (will validate on init)
-    this.storage = options?.storage || new KeychainStorage();
+    // Use provided storage, CV_CREDENTIAL_STORE, or start with keychain (will validate on init)
+    this.storage = options?.storage || this.backendFromEnv() || new KeychainStorage();
   }
 
   /**
    * Initialize storage and validate it's available
-   * Automatically falls back to plain file storage if keychain unavailable
+   *
+   * Falls back from the OS keychain to an encrypted file (and to a plain
+   * file only if encryption is unavailable), then moves credentials left in
+   * the legacy plaintext file into the active backend.
    */
   async init(): Promise<void> {
     if (this.initialized) return;
 
     const isAvailable = await this.storage.isAvailable();
 
-    if (!isAvailable && this.storage.getName() === 'keychain') {
-      // Fall back to plain file storage (like aws, gh, gcloud)
-      this.storage = new PlainFileStorage();
+    if (!isAvailable && this.storage.getName() === StorageBackend.KEYCHAIN) {
+      this.fallbackReason = 'OS keychain not available (no keychain service or keytar could not load)';
+      const encrypted = new EncryptedFileStorage(
+        await getLocalMasterPassword(this.keyPath, path.join(this.dataDir, 'credentials.key')),
+        path.join(this.dataDir, 'credentials.enc')
+      );
+      this.storage = (await encrypted.isAvailable())
+        ? encrypted
+        : new PlainFileStorage(path.join(this.dataDir, 'credentials.json'));
     }
 
     this.initialized = true;
+    await this.migratePlainFile();
   }
 
   /**
@@ -85,6 +127,58 @@ export class CredentialManager {
     return this.storage.getName();
   }
 
+  /**
+   * Active backend, where it keeps credentials and why the keychain is not used
+   */
+  async getStorageInfo(): Promise<CredentialStorageInfo> {
+    await this.init();
+    return {
+      backend: this.storage.getName(),
+      location: this.storage.getLocation?.(),
+      fallbackReason: this.fallbackReason,
+      migratedFromPlainFile: this.migratedFromPlainFile,
+    };
+  }
+
+  /**
+   * Backend forced with CV_CREDENTIAL_STORE (keychain, encrypted-file, plain-file)
+   */
+  private backendFromEnv(): CredentialStorage | undefined {
+    const backend = process.env.CV_CREDENTIAL_STORE;
+    if (!backend || backend === StorageBackend.KEYCHAIN) return undefined;
+    if (backend === StorageBackend.PLAIN_FILE) {
+      return new PlainFileStorage(path.join(this.dataDir, 'credentials.json'));
+    }
+    if (backend === StorageBackend.ENCRYPTED_FILE) {
+      return new LazyEncryptedStorage(this.dataDir, this.keyPath);
+    }
+    throw new Error(
+      `Unknown CV_CREDENTIAL_STORE "${backend}" (expected keychain, encrypted-file or plain-file)`
+    );
+  }
+
+  /**
+   * Move credentials from ~/.cv-git/credentials.json (written by earlier
+   * versions when the keychain was unavailable) into the active backend
+   */
+  private async migratePlainFile(): Promise<void> {
+    if (this.storage.getName() === StorageBackend.PLAIN_FILE) return;
+
+    const plain = new PlainFileStorage(path.join(this.dataDir, 'credentials.json'));
+    if (!(await plain.exists())) return;
+
+    const existing = new Set(await this.storage.list());
+    for (const key of await plain.list()) {
+      if (existing.has(key)) continue;
+      const value = await plain.retrieve(key);
+      if (value) {
+        await this.storage.store(key, value);
+        this.migratedFromPlainFile++;
+      }
+    }
+    await plain.destroy();
+  }
+
   /**
    * Make storage key from credential type and name
    */
@@ -420,6 +514,14 @@ export class CredentialManager {
     return cred ? (cred as OpenRouterAPICredential).apiKey : null;
   }
 
+  /**
+   * Get Gemini API key
+   */
+  async getGeminiKey(): Promise<string | null> {
+    const cred = await this.retrieve(CredentialType.GEMINI_API);
+    return cred ? (cred as GeminiAPICredential).apiKey : null;
+  }
+
   // ============================================================================
   // DNS Provider Credentials
   // ============================================================================
@@ -547,6 +649,11 @@ export class CredentialManager {
         type: CredentialType.OPENROUTER_API,
         name: 'default',
       },
+      {
+        envVar: 'GEMINI_API_KEY',
+        type: CredentialType.GEMINI_API,
+        name: 'default',
+      },
       // DNS providers
       {
         envVar: 'CLOUDFLARE_API_TOKEN',
@@ -646,6 +753,12 @@ export class CredentialManager {
           name,
           apiKey: value,
         });
+      } else if (type === CredentialType.GEMINI_API) {
+        await this.store<GeminiAPICredential>({
+          type: CredentialType.GEMINI_API,
+          name,
+          apiKey: value,
+        });
       } else if (type === CredentialType.CLOUDFLARE_API) {
         await this.store<CloudflareCredential>({
           type: CredentialType.CLOUDFLARE_API,
@@ -808,3 +921,48 @@ export class CredentialManager {
     });
   }
 }
+
+/**
+ * Encrypted file storage whose master password is read on first use, so the
+ * constructor can stay synchronous
+ */
+class LazyEncryptedStorage implements CredentialStorage {
+  private inner?: Promise<EncryptedFileStorage>;
+
+  constructor(private readonly dataDir: string, private readonly keyPath: string) {}
+
+  private get storage(): Promise<EncryptedFileStorage> {
+    const legacyKeyPath = path.join(this.dataDir, 'credentials.key');
+    return (this.inner ??= getLocalMasterPassword(this.keyPath, legacyKeyPath).then(
+      (password) => new EncryptedFileStorage(password, path.join(this.dataDir, 'credentials.enc'))
+    ));
+  }
+
+  getName(): string {
+    return StorageBackend.ENCRYPTED_FILE;
+  }
+
+  getLocation(): string {
+    return path.join(this.dataDir, 'credentials.enc');
+  }
+
+  async store(key: string, value: string): Promise<void> {
+    return (await this.storage).store(key, value);
+  }
+
+  async retrieve(key: string): Promise<string | null> {
+    return (await this.storage).retrieve(key);
+  }
+
+  async delete(key: string): Promise<void> {
+    return (await this.storage).delete(key);
+  }
+
+  async list(): Promise<string[]> {
+    return (await this.storage).list();
+  }
+
+  async isAvailable(): Promise<boolean> {
+    return (await this.storage).isAvailable();
+  }
+}
@@ -10,6 +10,14 @@
  * - Random salt and IV for each encryption
  * - Authentication tag for integrity verification
  * - File permissions restricted to owner only (chmod 600)
+ *
+ * Without a master password, a random key generated on first use is used.
+ * It is kept outside the data directory, in the XDG state directory
+ * (~/.local/state/cv-git/credentials.key, chmod 600) or %LOCALAPPDATA% on
+ * Windows, so a backup or dotfile sync of ~/.cv-git does not carry the key
+ * along with credentials.enc. A backup of the whole home directory still
+ * holds both; set CV_CREDENTIALS_PASSPHRASE to encrypt with a passphrase
+ * that is never written to disk.
  */
 
 import { createCipheriv, createDecipheriv, randomBytes, pbkdf2 } from 'crypto';
@@ -29,6 +37,61 @@ interface EncryptedData {
   encrypted: string;
 }
 
+/**
+ * Where the generated key is kept: CV_CREDENTIALS_KEY_FILE, else
+ * $XDG_STATE_HOME/cv-git/credentials.key (~/.local/state by default), or
+ * %LOCALAPPDATA%\cv-git\credentials.key on Windows
+ */
+export function defaultKeyPath(): string {
+  if (process.env.CV_CREDENTIALS_KEY_FILE) {
+    return process.env.CV_CREDENTIALS_KEY_FILE;
+  }
+  const stateDir = process.platform === 'win32'
+    ? process.env.LOCALAPPDATA || path.join(os.homedir(), 'AppData', 'Local')
+    : process.env.XDG_STATE_HOME || path.join(os.homedir(), '.local', 'state');
+  return path.join(stateDir, 'cv-git', 'credentials.key');
+}
+
+async function readKey(file: string): Promise<string | null> {
+  try {
+    return (await fs.readFile(file, 'utf8')).trim() || null;
+  } catch (error: any) {
+    if (error.code !== 'ENOENT') throw error;
+    return null;
+  }
+}
+
+/**
+ * Master password for the encrypted file: CV_CREDENTIALS_PASSPHRASE, or a
+ * random key created on first use
+ *
+ * @param keyPath - Path to the generated key (default: defaultKeyPath())
+ * @param legacyKeyPath - Where earlier versions kept the key; a key found
+ *   there is moved to keyPath so existing credentials still decrypt
+ */
+export async function getLocalMasterPassword(keyPath?: string, legacyKeyPath?: string): Promise<string> {
+  if (process.env.CV_CREDENTIALS_PASSPHRASE) {
+    return process.env.CV_CREDENTIALS_PASSPHRASE;
+  }
+
+  const file = keyPath || defaultKeyPath();
+  const existing = await readKey(file);
+  if (existing) return existing;
+
+  const legacy = legacyKeyPath && legacyKeyPath !== file ? await readKey(legacyKeyPath) : null;
+  const key = legacy ?? randomBytes(32).toString('hex');
+  await fs.mkdir(path.dirname(file), { recursive: true, mode: 0o700 });
+  // wx: never overwrite a key another process just created
+  try {
+    await fs.writeFile(file, key, { mode: 0o600, flag: 'wx' });
+  } catch (error: any) {
+    if (error.code !== 'EEXIST') throw error;
+    return (await fs.readFile(file, 'utf8')).trim();
+  }
+  if (legacy) await fs.rm(legacyKeyPath!, { force: true });
+  return key;
+}
+
 export class EncryptedFileStorage implements CredentialStorage {
   private readonly algorithm = 'aes-256-gcm';
   private readonly keyLength = 32; // 256 bits
@@ -57,6 +120,10 @@ export class EncryptedFileStorage implements CredentialStorage {
     return StorageBackend.ENCRYPTED_FILE;
   }
 
+  getLocation(): string {
+    return this.filePath;
+  }
+
   /**
    * Derive encryption key from master password using PBKDF2
    */
@@ -4,5 +4,5 @@
 
 export { CredentialStorage, StorageBackend } from './interface.js';
 export { KeychainStorage } from './keychain.js';
-export { EncryptedFileStorage } from './encrypted.js';
+export { EncryptedFileStorage, defaultKeyPath, getLocalMasterPassword } from './encrypted.js';
 export { PlainFileStorage } from './plain-file.js';
@@ -49,6 +49,11 @@ export interface CredentialStorage {
    * Get the name of this storage backend
    */
   getName(): string;
+
+  /**
+   * Where credentials are kept (file path or keychain service), for display
+   */
+  getLocation?(): string;
 }
 
 /**
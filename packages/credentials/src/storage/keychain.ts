@@ -52,6 +52,14 @@ export class KeychainStorage implements CredentialStorage {
     return StorageBackend.KEYCHAIN;
   }
 
+  getLocation(): string {
+    const store =
+      process.platform === 'darwin' ? 'macOS Keychain'
+        : process.platform === 'win32' ? 'Windows Credential Manager'
+          : 'Secret Service';
+    return `${store} (service "${this.serviceName}")`;
+  }
+
   async store(key: string, value: string): Promise<void> {
     try {
       const keytar = await getKeytar();
@@ -36,6 +36,29 @@ export class PlainFileStorage implements CredentialStorage {
     return StorageBackend.PLAIN_FILE;
   }
 
+  getLocation(): string {
+    return this.filePath;
+  }
+
+  /**
+   * Whether the credentials file exists
+   */
+  async exists(): Promise<boolean> {
+    try {
+      await fs.access(this.filePath);
+      return true;
+    } catch {
+      return false;
+    }
+  }
+
+  /**
+   * Delete the credentials file
+   */
+  async destroy(): Promise<void> {
+    await fs.rm(this.filePath, { force: true });
+  }
+
   /**
    * Load all credentials from file
    */
@@ -14,6 +14,7 @@ export enum CredentialType {
   ANTHROPIC_API = 'anthropic_api',
   OPENAI_API = 'openai_api',
   OPENROUTER_API = 'openrouter_api',
+  GEMINI_API = 'gemini_api',
 
   // DNS providers
   CLOUDFLARE_API = 'cloudflare_api',
@@ -146,6 +147,16 @@ export interface OpenRouterAPICredential extends BaseCredential {
   apiKey: string;
 }
 
+/**
+ * Google Gemini API key credential
+ */
+export interface GeminiAPICredential extends BaseCredential {
+  type: CredentialType.GEMINI_API;
+
+  /** API key */
+  apiKey: string;
+}
+
 /**
  * Generic API key credential
  */
@@ -310,6 +321,7 @@ export type Credential =
   | AnthropicAPICredential
   | OpenAIAPICredential
   | OpenRouterAPICredential
+  | GeminiAPICredential
   | APIKeyCredential
   // DNS providers
   | CloudflareCredential
@@ -1,1953 +1,37 @@
 #!/usr/bin/env node
 
 /**
- * CV-Git MCP Server
- * Model Context Protocol server exposing CV-Git functionality to AI agents
+ * cv-mcp
+ * Start the CV-Git MCP server
+ *
+ * Usage: cv-mcp [--sse] [--port 3333] [--host 127.0.0.1] [--index-only]
  */
 
-import { Server } from '@modelcontextprotocol/sdk/server/index.js';
-import { StdioServerTransport } from '@modelcontextprotocol/sdk/server/stdio.js';
-import {
-  CallToolRequestSchema,
-  ListToolsRequestSchema,
-  ListResourcesRequestSchema,
-  ReadResourceRequestSchema,
-  Tool,
-} from '@modelcontextprotocol/sdk/types.js';
+import { serve, ServeOptions } from './server.js';
+import { serverLogger } from './logger.js';
 
-// Resources handler
-import { listResources, readResource } from './resources.js';
-
-import {
-  FindArgs,
-  ExplainArgs,
-  GraphQueryArgs,
-  DoArgs,
-  ReviewArgs,
-  SyncArgs,
-  CommitsArgs,
-  FileHistoryArgs,
-  BlameArgs,
-  ToolResult,
-  SessionKnowledgeArgs,
-  SessionEgressArgs,
-} from './types.js';
-
-import {
-  successResult,
-  errorResult,
-  validateArgs,
-  formatSearchResults,
-  formatGraphResults,
-  formatTaskResult,
-  formatReview,
-  formatSyncResult,
-} from './utils.js';
-
-// Tool handlers
-import { handleFind } from './tools/search.js';
-import { handleExplain } from './tools/explain.js';
-import {
-  handleGraphQuery,
-  handleGraphStats,
-  handleGraphInspect,
-  handleGraphPath,
-  handleGraphDeadCode,
-  handleGraphComplexity,
-  handleGraphCycles,
-  handleGraphHotspots,
-  handleGraphNeighborhood,
-  handleGraphImpact,
-  handleGraphBridge,
-  handleSummaryView
-} from './tools/graph.js';
-import { handleDo, handleReview } from './tools/modify.js';
-import { handleSync } from './tools/sync.js';
-import { handlePRCreate, handlePRList, handlePRReview, handleReleaseCreate } from './tools/platform.js';
-import { handleConfigGet, handleStatus, handleDoctor } from './tools/system.js';
-import { handleContext, ContextArgs } from './tools/context.js';
-import { handleAutoContext, AutoContextArgs } from './tools/auto-context.js';
-import { serverLogger, toolLogger, resourceLogger } from './logger.js';
-import {
-  handlePRDContext,
-  handleRequirementTrace,
-  handleTestCoverage,
-  handleDocCoverage,
-  PRDContextArgs,
-  RequirementTraceArgs,
-  CoverageArgs,
-} from './tools/prd.js';
-import {
-  handleDocsSearch,
-  handleDocsIngest,
-  handleDocsList,
-  DocsSearchArgs,
-  DocsIngestArgs,
-  DocsListArgs,
-} from './tools/docs.js';
-import {
-  handleCommits,
-  handleFileHistory,
-  handleBlame,
-} from './tools/version.js';
-import {
-  handleCommitAnalyze,
-  handleCommitGenerate,
-  CommitAnalyzeArgs,
-  CommitGenerateArgs,
-} from './tools/commit.js';
-import { handleReason, ReasonArgs } from './tools/reason.js';
-import { handleTraverseContext, TraverseContextToolArgs } from './tools/traverse-context.js';
-import { handleManifoldStatus, ManifoldStatusArgs } from './tools/manifold-status.js';
-import { handleSessionKnowledge, handleSessionEgress } from './tools/session-knowledge.js';
-import {
-  handleCreateThread,
-  handleListThreads,
-  handleGetThreadSummary,
-  handleUpdateThreadStatus,
-  handleAddSegment,
-  handleEndSegment,
-  handleBridgeContext,
-  handleGetPendingBridges,
-  CreateThreadArgs,
-  ListThreadsArgs,
-  GetThreadSummaryArgs,
-  UpdateThreadStatusArgs,
-  AddSegmentArgs,
-  EndSegmentArgs,
-  BridgeContextArgs,
-  GetPendingBridgesArgs,
-} from './tools/thread.js';
-import {
-  handleDispatchSubtask,
-  handleSubtaskStatus,
-  DispatchSubtaskArgs,
-  SubtaskStatusArgs,
-} from './tools/subtask.js';
-import {
-  handleDeployList,
-  handleDeployPush,
-  handleDeployRollback,
-  handleDeployStatus,
-  DeployListArgs,
-  DeployPushArgs,
-  DeployRollbackArgs,
-  DeployStatusArgs,
-} from './tools/deploy.js';
-
-/**
- * Tool definitions
- */
-const tools: Tool[] = [
-  // Code Understanding Tools
-  {
-    name: 'cv_find',
-    description: 'Search for code using natural language semantic search. Returns relevant code snippets with similarity scores.',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        query: {
-          type: 'string',
-          description: 'Search query in natural language (e.g., "authentication logic", "error handling")',
-        },
-        limit: {
-          type: 'number',
-          description: 'Maximum number of results to return',
-          default: 10,
-        },
-        minScore: {
-          type: 'number',
-          description: 'Minimum similarity score (0-1)',
-          default: 0.5,
-        },
-        language: {
-          type: 'string',
-          description: 'Filter by programming language (e.g., "typescript", "python")',
-        },
-        file: {
-          type: 'string',
-          description: 'Filter by file path (partial match)',
-        },
-      },
-      required: ['query'],
-    },
-  },
-  {
-    name: 'cv_context',
-    description: 'Generate rich context about a codebase for AI coding assistants. Searches for relevant code, includes relationships from the knowledge graph, and optionally includes full file contents. Perfect for understanding code before making changes.',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        query: {
-          type: 'string',
-          description: 'What you want to understand or work on (natural language, e.g., "authentication flow", "error handling in API routes")',
-        },
-        limit: {
-          type: 'number',
-          description: 'Maximum number of code chunks to include',
-          default: 10,
-        },
-        depth: {
-          type: 'number',
-          description: 'Graph traversal depth for relationships (callers/callees)',
-          default: 2,
-        },
-        includeGraph: {
-          type: 'boolean',
-          description: 'Include code relationships from knowledge graph',
-          default: true,
-        },
-        includeFiles: {
-          type: 'boolean',
-          description: 'Include full file contents for matched code',
-          default: true,
-        },
-        minScore: {
-          type: 'number',
-          description: 'Minimum similarity score (0-1)',
-          default: 0.5,
-        },
-        format: {
-          type: 'string',
-          enum: ['markdown', 'xml', 'json'],
-          description: 'Output format',
-          default: 'markdown',
-        },
-      },
-      required: ['query'],
-    },
-  },
-  {
-    name: 'cv_auto_context',
-    description: `RECOMMENDED: Call this FIRST before any coding task to get relevant knowledge graph context automatically.
-
-Returns structured context optimized for AI system prompts including:
-- Semantically relevant code from the codebase
-- Call graph relationships (callers/callees)
-- Current file context and symbols
-- Related documentation
-
-USE THIS TOOL when:
-- Starting any coding task or question about the codebase
-- You need to understand code before making changes
-- The user asks about how something works
-
-This provides richer context than searching manually.`,
-    inputSchema: {
-      type: 'object',
-      properties: {
-        query: {
-          type: 'string',
-          description: 'What you want to understand or work on (natural language)',
-        },
-        currentFile: {
-          type: 'string',
-          description: 'Path to the file currently being edited (optional)',
-        },
-        format: {
-          type: 'string',
-          enum: ['xml', 'markdown', 'json'],
-          description: 'Output format (xml recommended for system prompts)',
-          default: 'xml',
-        },
-        budget: {
-          type: 'number',
-          description: 'Token budget for context (default: 20000)',
-          default: 20000,
-        },
-        includeDocs: {
-          type: 'boolean',
-          description: 'Include related documentation',
-          default: true,
-        },
-      },
-      required: ['query'],
-    },
-  },
-  {
-    name: 'cv_explain',
-    description: 'Get AI-powered explanation of code, symbols, or concepts. Provides detailed analysis including purpose, dependencies, and usage.',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        target: {
-          type: 'string',
-          description: 'What to explain: symbol name (function/class), file path, or concept',
-        },
-        noStream: {
-          type: 'boolean',
-          description: 'Disable streaming output',
-          default: false,
-        },
-      },
-      required: ['target'],
-    },
-  },
-  {
-    name: 'cv_reason',
-    description: `Deep codebase reasoning using recursive LLM analysis. Uses a decompose-execute-aggregate pattern to break complex queries into sub-tasks (graph queries, semantic search, explanations) and synthesize comprehensive answers.
-
-USE THIS TOOL when:
-- Questions require understanding multiple connected code components
-- You need to trace through call chains or dependencies
-- The answer requires synthesizing information from multiple sources
-- Simple search or explain doesn't provide enough context
-
-This provides deeper analysis than cv_explain by recursively gathering context.`,
-    inputSchema: {
-      type: 'object',
-      properties: {
-        query: {
-          type: 'string',
-          description: 'The question to reason about (e.g., "how does authentication work in this codebase", "what would break if I change this function")',
-        },
-        maxDepth: {
-          type: 'number',
-          description: 'Maximum recursion depth for reasoning (default: 5)',
-          default: 5,
-        },
-        showTrace: {
-          type: 'boolean',
-          description: 'Include the reasoning trace showing each step taken',
-          default: false,
-        },
-      },
-      required: ['query'],
-    },
-  },
-  {
-    name: 'cv_graph_query',
-    description: 'Query the knowledge graph for relationships between code elements (calls, imports, dependencies).',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        queryType: {
-          type: 'string',
-          enum: ['calls', 'called-by', 'imports', 'exports', 'functions', 'classes', 'files'],
-          description: 'Type of query: calls (what this calls), called-by (what calls this), imports, exports, or list functions/classes/files',
-        },
-        target: {
-          type: 'string',
-          description: 'Target symbol or file (required for calls/called-by/imports/exports)',
-        },
-        language: {
-          type: 'string',
-          description: 'Filter by language (for list queries)',
-        },
-        file: {
-          type: 'string',
-          description: 'Filter by file path (for list queries)',
-        },
-      },
-      required: ['queryType'],
-    },
-  },
-  {
-    name: 'cv_graph_stats',
-    description: 'Get statistics about the knowledge graph (files, symbols, relationships).',
-    inputSchema: {
-      type: 'object',
-      properties: {},
-    },
-  },
-  {
-    name: 'cv_graph_inspect',
-    description: 'Inspect detailed information about a specific symbol or file.',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        target: {
-          type: 'string',
-          description: 'Symbol name or file path to inspect',
-        },
-      },
-      required: ['target'],
-    },
-  },
-  {
-    name: 'cv_graph_path',
-    description: 'Find execution paths between two functions in the call graph. Useful for understanding how functions interact.',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        from: {
-          type: 'string',
-          description: 'Starting function name',
-        },
-        to: {
-          type: 'string',
-          description: 'Target function name',
-        },
-        maxDepth: {
-          type: 'number',
-          description: 'Maximum path depth to search',
-          default: 10,
-        },
-      },
-      required: ['from', 'to'],
-    },
-  },
-  {
-    name: 'cv_graph_dead_code',
-    description: 'Find potentially unreachable or unused functions. Identifies code that may be safe to remove.',
-    inputSchema: {
-      type: 'object',
-      properties: {},
-    },
-  },
-  {
-    name: 'cv_graph_complexity',
-    description: 'Find high-complexity functions based on cyclomatic complexity. Helps identify functions that may need refactoring.',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        threshold: {
-          type: 'number',
-          description: 'Minimum complexity threshold',
-          default: 10,
-        },
-        limit: {
-          type: 'number',
-          description: 'Maximum number of results to return',
-          default: 20,
-        },
-      },
-    },
-  },
-  {
-    name: 'cv_graph_cycles',
-    description: 'Find circular dependencies in the call graph. Detects potential architectural issues.',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        maxDepth: {
-          type: 'number',
-          description: 'Maximum cycle depth to search',
-          default: 5,
-        },
-      },
-    },
-  },
-  {
-    name: 'cv_graph_hotspots',
-    description: 'Find most-called functions (hot spots) in the codebase. Identifies functions that may benefit from optimization.',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        limit: {
-          type: 'number',
-          description: 'Number of hot spots to return',
-          default: 20,
-        },
-      },
-    },
-  },
-  {
-    name: 'cv_graph_neighborhood',
-    description: 'Explore the neighborhood of a symbol - shows callers, callees, and related code within a radius. Useful for understanding the local context of a symbol.',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        symbol: {
-          type: 'string',
-          description: 'Symbol name to explore',
-        },
-        depth: {
-          type: 'number',
-          description: 'Exploration depth (default: 2)',
-          default: 2,
-        },
-        direction: {
-          type: 'string',
-          enum: ['incoming', 'outgoing', 'both'],
-          description: 'Direction to explore (incoming=callers, outgoing=callees)',
-          default: 'both',
-        },
-      },
-      required: ['symbol'],
-    },
-  },
-  {
-    name: 'cv_graph_impact',
-    description: 'Analyze the impact of changing a symbol. Shows all code that would be affected by modifications, including risk assessment.',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        symbol: {
-          type: 'string',
-          description: 'Symbol to analyze',
-        },
-        depth: {
-          type: 'number',
-          description: 'Impact analysis depth (default: 3)',
-          default: 3,
-        },
-      },
-      required: ['symbol'],
-    },
-  },
-  {
-    name: 'cv_graph_bridge',
-    description: 'Find code that connects two symbols or concepts. Useful for understanding relationships and dependencies between distant parts of the codebase.',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        source: {
-          type: 'string',
-          description: 'Source symbol or concept',
-        },
-        target: {
-          type: 'string',
-          description: 'Target symbol or concept',
-        },
-        maxDepth: {
-          type: 'number',
-          description: 'Maximum path depth to search (default: 5)',
-          default: 5,
-        },
-      },
-      required: ['source', 'target'],
-    },
-  },
-  {
-    name: 'cv_summary_view',
-    description: 'Get a high-level summary of the codebase including architecture, patterns, key components, and statistics. Useful for understanding codebase at a glance.',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        aspect: {
-          type: 'string',
-          enum: ['overview', 'architecture', 'patterns', 'statistics'],
-          description: 'Which aspect of the summary to focus on (default: overview)',
-          default: 'overview',
-        },
-      },
-    },
-  },
-
-  // Version-Aware Tools (Code Evolution)
-  {
-    name: 'cv_commits',
-    description: 'List recent commits from the knowledge graph. Can filter by file or author. Shows commit history with metadata.',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        limit: {
-          type: 'number',
-          description: 'Maximum number of commits to return',
-          default: 20,
-        },
-        file: {
-          type: 'string',
-          description: 'Filter to commits that modified this file path',
-        },
-        author: {
-          type: 'string',
-          description: 'Filter to commits by this author (partial match)',
-        },
-      },
-    },
-  },
-  {
-    name: 'cv_file_history',
-    description: 'Get the complete modification history of a file. Shows all commits that changed the file with insertion/deletion counts.',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        file: {
-          type: 'string',
-          description: 'File path to get history for',
-        },
-        limit: {
-          type: 'number',
-          description: 'Maximum number of commits to return',
-          default: 10,
-        },
-        showDiff: {
-          type: 'boolean',
-          description: 'Include diff summaries (future feature)',
-          default: false,
-        },
-      },
-      required: ['file'],
-    },
-  },
-  {
-    name: 'cv_blame',
-    description: 'Show which commits last modified code. For files, shows blame for each symbol. For symbol names, shows recent commits affecting that symbol.',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        target: {
-          type: 'string',
-          description: 'File path or symbol name to get blame for',
-        },
-      },
-      required: ['target'],
-    },
-  },
-
-  // Code Modification Tools
-  {
-    name: 'cv_do',
-    description: 'Execute a task with AI assistance. Can generate code, modify existing code, or perform refactoring. Returns execution plan and changes made.',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        task: {
-          type: 'string',
-          description: 'Task description in natural language (e.g., "add logging to error handlers", "refactor authentication")',
-        },
-        planOnly: {
-          type: 'boolean',
-          description: 'Only generate execution plan without making changes',
-          default: false,
-        },
-        autoApprove: {
-          type: 'boolean',
-          description: 'Automatically approve and execute plan without user confirmation',
-          default: false,
-        },
-      },
-      required: ['task'],
-    },
-  },
-  {
-    name: 'cv_review',
-    description: 'AI-powered code review. Analyzes code changes for bugs, style issues, security concerns, and best practices.',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        ref: {
-          type: 'string',
-          description: 'Git ref to review (commit SHA, branch name)',
-          default: 'HEAD',
-        },
-        staged: {
-          type: 'boolean',
-          description: 'Review staged changes instead of a commit',
-          default: false,
-        },
-        context: {
-          type: 'boolean',
-          description: 'Include related code context in review',
-          default: false,
-        },
-      },
-    },
-  },
-  {
-    name: 'cv_sync',
-    description: 'Synchronize the knowledge graph with the repository. Parses code, extracts symbols, and builds/updates the graph.',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        incremental: {
-          type: 'boolean',
-          description: 'Only sync changed files (faster)',
-          default: false,
-        },
-        force: {
-          type: 'boolean',
-          description: 'Force full rebuild of the graph',
-          default: false,
-        },
-      },
-    },
-  },
-
-  // Platform Integration Tools
-  {
-    name: 'cv_pr_create',
-    description: 'Create a pull request on GitHub. Requires GitHub CLI (gh) to be installed and authenticated.',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        title: {
-          type: 'string',
-          description: 'Pull request title',
-        },
-        body: {
-          type: 'string',
-          description: 'Pull request description',
-        },
-        base: {
-          type: 'string',
-          description: 'Base branch for the PR',
-          default: 'main',
-        },
-        draft: {
-          type: 'boolean',
-          description: 'Create as a draft PR',
-          default: false,
-        },
-      },
-    },
-  },
-  {
-    name: 'cv_pr_list',
-    description: 'List pull requests from the repository. Requires GitHub CLI (gh) to be installed and authenticated.',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        state: {
-          type: 'string',
-          enum: ['open', 'closed', 'all'],
-          description: 'Filter by PR state',
-          default: 'open',
-        },
-        limit: {
-          type: 'number',
-          description: 'Maximum number of PRs to list',
-          default: 10,
-        },
-      },
-    },
-  },
-  {
-    name: 'cv_pr_review',
-    description: 'Get details and review information for a pull request. Requires GitHub CLI (gh) to be installed and authenticated.',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        number: {
-          type: 'number',
-          description: 'Pull request number',
-        },
-      },
-      required: ['number'],
-    },
-  },
-  {
-    name: 'cv_release_create',
-    description: 'Create a new release on GitHub. Requires GitHub CLI (gh) to be installed and authenticated.',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        version: {
-          type: 'string',
-          description: 'Version tag (e.g., v1.0.0)',
-        },
-        title: {
-          type: 'string',
-          description: 'Release title',
-        },
-        notes: {
-          type: 'string',
-          description: 'Release notes (auto-generated if not provided)',
-        },
-        draft: {
-          type: 'boolean',
-          description: 'Create as a draft release',
-          default: false,
-        },
-        prerelease: {
-          type: 'boolean',
-          description: 'Mark as a pre-release',
-          default: false,
-        },
-      },
-      required: ['version'],
-    },
-  },
-
-  // System Tools
-  {
-    name: 'cv_config_get',
-    description: 'Get a configuration value from CV-Git config. Supports nested keys with dot notation (e.g., "ai.model").',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        key: {
-          type: 'string',
-          description: 'Configuration key to retrieve (use dot notation for nested keys)',
-        },
-      },
-      required: ['key'],
-    },
-  },
-  {
-    name: 'cv_status',
-    description: 'Get comprehensive status of CV-Git repository including git status, CV-Git initialization, and service health.',
-    inputSchema: {
-      type: 'object',
-      properties: {},
-    },
-  },
-  {
-    name: 'cv_doctor',
-    description: 'Run comprehensive diagnostics to check CV-Git setup, dependencies, services, and configuration.',
-    inputSchema: {
-      type: 'object',
-      properties: {},
-    },
-  },
-
-  // PRD Integration Tools
-  {
-    name: 'cv_prd_context',
-    description: 'Get unified PRD context for AI including requirements, test cases, documentation, and designs. Returns comprehensive context for understanding what to build and how it should be tested.',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        query: {
-          type: 'string',
-          description: 'Natural language query to find relevant PRD artifacts',
-        },
-        prdId: {
-          type: 'string',
-          description: 'Optional PRD ID to filter results',
-        },
-        includeTypes: {
-          type: 'array',
-          items: { type: 'string' },
-          description: 'Artifact types to include (requirement, test_case, documentation, etc.)',
-        },
-        depth: {
-          type: 'number',
-          description: 'Graph traversal depth for related artifacts',
-          default: 3,
-        },
-      },
-      required: ['query'],
-    },
-  },
-  {
-    name: 'cv_requirement_trace',
-    description: 'Get full traceability for a requirement: dependencies, tests, documentation, designs, and code implementations.',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        chunkId: {
-          type: 'string',
-          description: 'Requirement chunk ID to trace',
-        },
-        depth: {
-          type: 'number',
-          description: 'Graph traversal depth',
-          default: 3,
-        },
-      },
-      required: ['chunkId'],
-    },
-  },
-  {
-    name: 'cv_test_coverage',
-    description: 'Get test coverage metrics for a PRD. Shows how many requirements have test cases.',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        prdId: {
-          type: 'string',
-          description: 'PRD ID to get coverage for',
-        },
-      },
-      required: ['prdId'],
-    },
-  },
-  {
-    name: 'cv_doc_coverage',
-    description: 'Get documentation coverage metrics for a PRD. Shows how many requirements are documented.',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        prdId: {
-          type: 'string',
-          description: 'PRD ID to get coverage for',
-        },
-      },
-      required: ['prdId'],
-    },
-  },
-
-  // Documentation Knowledge Graph Tools
-  {
-    name: 'cv_docs_search',
-    description: 'Search documentation in the knowledge graph using semantic search. Includes both active and archived documents. Use this to find design docs, historical decisions, and project documentation.',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        query: {
-          type: 'string',
-          description: 'Search query in natural language (e.g., "authentication design", "sync strategy")',
-        },
-        limit: {
-          type: 'number',
-          description: 'Maximum number of results to return',
-          default: 10,
-        },
-        minScore: {
-          type: 'number',
-          description: 'Minimum similarity score (0-1)',
-          default: 0.5,
-        },
-        type: {
-          type: 'string',
-          description: 'Filter by document type (e.g., "design_spec", "readme", "guide", "api_doc")',
-        },
-        archivedOnly: {
-          type: 'boolean',
-          description: 'Only return results from archived documents',
-          default: false,
-        },
-        activeOnly: {
-          type: 'boolean',
-          description: 'Only return results from active (non-archived) documents',
-          default: false,
-        },
-      },
-      required: ['query'],
-    },
-  },
-  {
-    name: 'cv_docs_ingest',
-    description: 'Ingest a markdown document into the knowledge graph. Creates document nodes, relationships, and vector embeddings for semantic search. Use this to add new documentation or update existing docs.',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        path: {
-          type: 'string',
-          description: 'Relative path for the document (e.g., "docs/DESIGN.md")',
-        },
-        content: {
-          type: 'string',
-          description: 'Full markdown content of the document',
-        },
-        archive: {
-          type: 'boolean',
-          description: 'Store only in .cv/documents/ (not in repo filesystem)',
-          default: false,
-        },
-        frontmatter: {
-          type: 'object',
-          description: 'Optional YAML frontmatter fields (type, status, tags, relates_to)',
-        },
-      },
-      required: ['path', 'content'],
-    },
-  },
-  {
-    name: 'cv_docs_list',
-    description: 'List documents in the knowledge graph. Shows document paths, titles, types, and archived status.',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        type: {
-          type: 'string',
-          description: 'Filter by document type',
-        },
-        archived: {
-          type: 'boolean',
-          description: 'Filter by archived status (true=archived only, false=active only, omit for all)',
-        },
-        limit: {
-          type: 'number',
-          description: 'Maximum number of documents to return',
-          default: 50,
-        },
-      },
-    },
-  },
-
-  // AI Commit Message Generation Tools
-  {
-    name: 'cv_commit_analyze',
-    description: 'Analyze staged git changes using AI and knowledge graph. Returns structured information about files changed, symbols added/modified/deleted, breaking changes detected, and suggested commit type/scope.',
-    inputSchema: {
-      type: 'object',
-      properties: {},
-    },
-  },
-  {
-    name: 'cv_commit_generate',
-    description: 'Generate a conventional commit message from staged changes using AI analysis. Uses knowledge graph to detect breaking changes and affected callers. Returns a ready-to-use commit message.',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        type: {
-          type: 'string',
-          description: 'Override commit type (feat, fix, refactor, docs, test, chore, style, perf, build, ci)',
-        },
-        scope: {
-          type: 'string',
-          description: 'Override commit scope (e.g., "auth", "api", "ui")',
-        },
-      },
-    },
-  },
-
-  // Context Manifold
-  {
-    name: 'cv_manifold_status',
-    description: 'Get diagnostics for the context manifold. Shows health of all 9 dimensions (structural, semantic, temporal, requirements, summary, navigational, session, intent, impact) and their data sources.',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        refresh: {
-          type: 'boolean',
-          description: 'Refresh manifold state before reporting',
-          default: false,
-        },
-      },
-    },
-  },
-
-  // Traversal-Aware Context (Claude Code Integration)
-  {
-    name: 'cv_traverse_context',
-    description: `Traversal-aware dynamic context for navigating codebases. Tracks your position (repo → module → file → symbol) and returns context appropriate for each level.
-
-USE THIS TOOL when:
-- You want to explore a codebase hierarchically
-- You need context that changes as you navigate
-- You want to drill into specific files/symbols
-- You need to understand code relationships at different granularities
-
-DIRECTIONS:
-- jump: Go directly to a file, symbol, or module
-- in: Drill down (repo→module→file→symbol)
-- out: Zoom out (symbol→file→module→repo)
-- lateral: Move to sibling at same level
-- stay: Refresh context at current position
-
-The tool maintains session state across calls for continuous navigation.`,
-    inputSchema: {
-      type: 'object',
-      properties: {
-        file: {
-          type: 'string',
-          description: 'Target file path (for jump or drill-down)',
-        },
-        symbol: {
-          type: 'string',
-          description: 'Target symbol name (for jump or drill-down)',
-        },
-        module: {
-          type: 'string',
-          description: 'Target module/directory (for jump or drill-down)',
-        },
-        direction: {
-          type: 'string',
-          enum: ['in', 'out', 'lateral', 'jump', 'stay'],
-          description: 'Navigation direction',
-          default: 'jump',
-        },
-        sessionId: {
-          type: 'string',
-          description: 'Session ID for stateful navigation (auto-generated if not provided)',
-        },
-        includeCallers: {
-          type: 'boolean',
-          description: 'Include callers of current symbol',
-          default: true,
-        },
-        includeCallees: {
-          type: 'boolean',
-          description: 'Include callees of current symbol',
-          default: true,
-        },
-        format: {
-          type: 'string',
-          enum: ['xml', 'markdown', 'json'],
-          description: 'Output format (xml recommended for Claude)',
-          default: 'xml',
-        },
-        budget: {
-          type: 'number',
-          description: 'Token budget for context',
-          default: 4000,
-        },
-      },
-    },
-  },
-
-  // ========== Session Knowledge Tools ==========
-  {
-    name: 'cv_session_knowledge',
-    description: `Query session knowledge from the knowledge graph. Returns past session knowledge nodes that touched specific files or symbols.
-
-USE THIS TOOL when:
-- You want to know what previous sessions did with certain files
-- You want cross-session context about files you're working on
-- You need to understand prior work on a symbol or module`,
-    inputSchema: {
-      type: 'object',
-      properties: {
-        files: {
-          type: 'array',
-          items: { type: 'string' },
-          description: 'File paths to search for in session knowledge',
-        },
-        symbols: {
-          type: 'array',
-          items: { type: 'string' },
-          description: 'Qualified symbol names to search for',
-        },
-        excludeSessionId: {
-          type: 'string',
-          description: 'Session ID to exclude (avoid echo from current session)',
-        },
-        limit: {
-          type: 'number',
-          description: 'Maximum results (default: 10)',
-          default: 10,
-        },
-      },
-    },
-  },
-
-  // Thread Continuity Tools (CV-Hub integration)
-  {
-    name: 'cv_thread_create',
-    description:
-      'Create a workflow thread for tracking work across multiple sessions and platforms (Claude.ai ↔ Claude Code). Requires CV_HUB_URL and CV_HUB_TOKEN env vars.',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        title: {
-          type: 'string',
-          description: 'Thread title describing the overall goal',
-        },
-        description: {
-          type: 'string',
-          description: 'Detailed description of the workflow',
-        },
-        repository_id: {
-          type: 'string',
-          description: 'Associated repository UUID',
-        },
-        metadata: {
-          type: 'object',
-          description: 'Arbitrary metadata (tags, labels, etc.)',
-        },
-      },
-      required: ['title'],
-    },
-  },
-  {
-    name: 'cv_thread_list',
-    description:
-      'List workflow threads with filtering by status or repository. Requires CV_HUB_URL and CV_HUB_TOKEN env vars.',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        status: {
-          type: 'string',
-          enum: ['active', 'paused', 'completed', 'archived'],
-          description: 'Filter by thread status',
-        },
-        repository_id: {
-          type: 'string',
-          description: 'Filter by repository UUID',
-        },
-        limit: {
-          type: 'number',
-          description: 'Max results (default: 20)',
-          default: 20,
-        },
-      },
-    },
-  },
-  {
-    name: 'cv_session_egress',
-    description: `Write session knowledge to the knowledge graph. Creates a SessionKnowledge node with ABOUT edges to files/symbols and FOLLOWS edge to the previous turn.
-
-USE THIS TOOL when:
-- You want to persist what you learned during a session turn
-- You want to record which files and symbols were relevant
-- Called automatically by hooks after each turn`,
-    inputSchema: {
-      type: 'object',
-      properties: {
-        sessionId: {
-          type: 'string',
-          description: 'Session identifier',
-        },
-        turnNumber: {
-          type: 'number',
-          description: 'Turn number within the session',
-        },
-        transcript_segment: {
-          type: 'string',
-          description: 'The transcript/summary text for this turn',
-        },
-        files_touched: {
-          type: 'array',
-          items: { type: 'string' },
-          description: 'File paths touched during this turn',
-        },
-        symbols_referenced: {
-          type: 'array',
-          items: { type: 'string' },
-          description: 'Qualified symbol names referenced',
-        },
-        concern: {
-          type: 'string',
-          description: 'Context concern (codebase, deployment, etc.)',
-          default: 'codebase',
-        },
-      },
-      required: ['sessionId', 'turnNumber', 'transcript_segment'],
-    },
-  },
-
-  {
-    name: 'cv_thread_summary',
-    description:
-      'Get a comprehensive summary of a workflow thread including all segments, edges, and pending bridges.',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        thread_id: {
-          type: 'string',
-          description: 'Thread UUID to summarize',
-        },
-      },
-      required: ['thread_id'],
-    },
-  },
-  {
-    name: 'cv_thread_status',
-    description: 'Update the status of a workflow thread.',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        thread_id: {
-          type: 'string',
-          description: 'Thread UUID',
-        },
-        status: {
-          type: 'string',
-          enum: ['active', 'paused', 'completed', 'archived'],
-          description: 'New thread status',
-        },
-      },
-      required: ['thread_id', 'status'],
-    },
-  },
-  {
-    name: 'cv_segment_add',
-    description:
-      'Add a new segment to a workflow thread. Segments represent individual work sessions on specific platforms (claude_ai, claude_code, cv_hub_api).',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        thread_id: {
-          type: 'string',
-          description: 'Thread UUID',
-        },
-        platform: {
-          type: 'string',
-          description: 'Platform identifier (e.g. claude_ai, claude_code, cv_hub_api)',
-        },
-        segment_type: {
-          type: 'string',
-          enum: ['planning', 'execution', 'review', 'research', 'debugging'],
-          description: 'Type of work in this segment',
-        },
-        title: {
-          type: 'string',
-          description: 'Short title for the segment',
-        },
-        summary: {
-          type: 'string',
-          description: 'What this segment accomplished',
-        },
-        session_identifier: {
-          type: 'string',
-          description: 'External session ID (e.g. Claude conversation ID)',
-        },
-        context_snapshot: {
-          type: 'object',
-          description: 'Key context snapshot at segment start',
-        },
-        tools_used: {
-          type: 'array',
-          items: { type: 'string' },
-          description: 'Tools used in this segment',
-        },
-        files_modified: {
-          type: 'array',
-          items: { type: 'string' },
-          description: 'Files modified in this segment',
-        },
-        previous_segment_id: {
-          type: 'string',
-          description: 'Previous segment ID (creates an edge)',
-        },
-        edge_type: {
-          type: 'string',
-          enum: ['continuation', 'fork', 'merge', 'handoff'],
-          description: 'Type of edge from previous segment',
-        },
-      },
-      required: ['thread_id', 'platform'],
-    },
-  },
-  {
-    name: 'cv_segment_end',
-    description:
-      'Mark a segment as ended with summary and result snapshot.',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        segment_id: {
-          type: 'string',
-          description: 'Segment UUID',
-        },
-        thread_id: {
-          type: 'string',
-          description: 'Thread UUID the segment belongs to',
-        },
-        summary: {
-          type: 'string',
-          description: 'Summary of what was accomplished',
-        },
-        result_snapshot: {
-          type: 'object',
-          description: 'Snapshot of key results at segment end',
-        },
-        files_modified: {
-          type: 'array',
-          items: { type: 'string' },
-          description: 'Final list of files modified',
-        },
-        tools_used: {
-          type: 'array',
-          items: { type: 'string' },
-          description: 'Final list of tools used',
-        },
-      },
-      required: ['segment_id', 'thread_id'],
-    },
-  },
-  {
-    name: 'cv_bridge_context',
-    description:
-      'Create a context bridge to transfer context between segments or platforms. Used for handing off work from Claude.ai to Claude Code or vice versa.',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        thread_id: {
-          type: 'string',
-          description: 'Thread UUID',
-        },
-        from_segment_id: {
-          type: 'string',
-          description: 'Source segment UUID',
-        },
-        to_segment_id: {
-          type: 'string',
-          description: 'Target segment UUID (optional — can be linked later)',
-        },
-        bridge_type: {
-          type: 'string',
-          enum: ['task_dispatch', 'result_return', 'context_share', 'handoff'],
-          description: 'Type of context bridge',
-        },
-        summary: {
-          type: 'string',
-          description: 'Brief summary of context being bridged',
-        },
-        context: {
-          type: 'string',
-          description: 'Detailed context to transfer',
-        },
-        decisions: {
-          type: 'array',
-          items: { type: 'string' },
-          description: 'Key decisions made',
-        },
-        task_ids: {
-          type: 'array',
-          items: { type: 'string' },
-          description: 'Related task IDs',
-        },
-        expires_in_minutes: {
-          type: 'number',
-          description: 'Bridge expiry in minutes (default: 1440 = 24h)',
-          default: 1440,
-        },
-      },
-      required: ['thread_id', 'from_segment_id'],
-    },
-  },
-  {
-    name: 'cv_bridge_pending',
-    description:
-      'Get pending context bridges for a thread, optionally filtered by target segment. Use this to receive context from a previous session.',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        thread_id: {
-          type: 'string',
-          description: 'Thread UUID',
-        },
-        segment_id: {
-          type: 'string',
-          description: 'Target segment UUID to filter bridges for',
-        },
-      },
-      required: ['thread_id'],
-    },
-  },
-
-  // Sub-task Dispatch Tools
-  {
-    name: 'cv_dispatch_subtask',
-    description:
-      'Dispatch a sub-task to another executor machine via CV-Hub. Use this when: (1) a task is too large for one session, (2) a step should run on a different machine (e.g., CI for tests), or (3) you want parallel execution. The sub-task will be linked to the current thread.',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        target: {
-          type: 'string',
-          description:
-            "Machine ID of the target executor. Use 'self' to dispatch back to this machine's queue (useful for deferring work to a fresh context window).",
-        },
-        task_type: {
-          type: 'string',
-          enum: [
-            'build',
-            'test',
-            'deploy',
-            'analyze',
-            'generate',
-            'refactor',
-          ],
-          description: 'Type of sub-task',
-        },
-        prompt: {
-          type: 'string',
-          description:
-            'Detailed instructions for the sub-task. Be specific — the executing agent will have no prior context except what you write here.',
-        },
-        priority: {
-          type: 'string',
-          enum: ['low', 'normal', 'high', 'critical'],
-          description: 'Priority level (default: normal)',
-        },
-        context: {
-          type: 'object',
-          description:
-            'Additional context: repo path, branch, files, validation rules',
-          properties: {
-            repo: { type: 'string' },
-            branch: { type: 'string' },
-            relevant_files: {
-              type: 'array',
-              items: { type: 'string' },
-              description: 'Files the sub-task should focus on',
-            },
-            cv_git_refs: {
-              type: 'array',
-              items: { type: 'string' },
-              description: 'CV-Git symbols relevant to the sub-task',
-            },
-            validation: {
-              type: 'object',
-              properties: {
-                run_tests: { type: 'boolean' },
-                min_coverage: { type: 'number' },
-                lint: { type: 'boolean' },
-              },
-            },
-            depends_on_task: {
-              type: 'string',
-              description:
-                'Task ID that must complete before this sub-task can run',
-            },
-          },
-        },
-        wait: {
-          type: 'boolean',
-          description:
-            'If true, poll until the sub-task completes and return the result inline. If false (default), return immediately with the task ID.',
-          default: false,
-        },
-      },
-      required: ['target', 'task_type', 'prompt'],
-    },
-  },
-  {
-    name: 'cv_subtask_status',
-    description:
-      'Check the status of a previously dispatched sub-task.',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        task_id: {
-          type: 'string',
-          description: 'The task ID returned by cv_dispatch_subtask',
-        },
-      },
-      required: ['task_id'],
-    },
-  },
-
-  // Deploy Tools
-  {
-    name: 'cv_deploy_list',
-    description: 'List all deploy targets defined in deploy/*.yaml config files. Shows target name, provider, and services.',
-    inputSchema: {
-      type: 'object',
-      properties: {},
-    },
-  },
-  {
-    name: 'cv_deploy_push',
-    description: 'Deploy a target through the full lifecycle: preflight → build → push → deploy → health check. Supports dry-run mode.',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        target: {
-          type: 'string',
-          description: 'Deploy target name (matches deploy/{target}.yaml)',
-        },
-        ref: {
-          type: 'string',
-          description: 'Git ref to deploy (default: HEAD)',
-        },
-        dry_run: {
-          type: 'boolean',
-          description: 'Preview without executing (default: false)',
-          default: false,
-        },
-      },
-      required: ['target'],
-    },
-  },
-  {
-    name: 'cv_deploy_rollback',
-    description: 'Rollback a deploy target to a previous version.',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        target: {
-          type: 'string',
-          description: 'Deploy target name',
-        },
-        to_version: {
-          type: 'string',
-          description: 'Specific version to rollback to (default: "previous")',
-          default: 'previous',
-        },
-      },
-      required: ['target'],
-    },
-  },
-  {
-    name: 'cv_deploy_status',
-    description: 'Check the health and status of a deploy target. Runs provider-specific health checks.',
-    inputSchema: {
-      type: 'object',
-      properties: {
-        target: {
-          type: 'string',
-          description: 'Deploy target name',
-        },
-      },
-      required: ['target'],
-    },
-  },
-];
-
-/**
- * Create and configure the MCP server
- */
-const server = new Server(
-  {
-    name: 'cv-git',
-    version: '1.0.0',
-  },
-  {
-    capabilities: {
-      tools: {},
-      resources: {},
-    },
-  }
-);
-
-/**
- * Handle list tools request
- */
-server.setRequestHandler(ListToolsRequestSchema, async () => {
-  return { tools };
-});
-
-/**
- * Handle list resources request
- */
-server.setRequestHandler(ListResourcesRequestSchema, async () => {
-  resourceLogger.debug('Listing resources');
-  const resources = listResources();
-  resourceLogger.info('Resources listed', { count: resources.length });
-  return { resources };
-});
-
-/**
- * Handle read resource request
- */
-server.setRequestHandler(ReadResourceRequestSchema, async (request) => {
-  const { uri } = request.params;
-  resourceLogger.debug('Reading resource', { uri });
-  const start = Date.now();
-
-  try {
-    const content = await readResource(uri);
-    const duration = Date.now() - start;
-    resourceLogger.info('Resource read', { uri, duration });
-    return {
-      contents: [content],
-    };
-  } catch (error: any) {
-    const duration = Date.now() - start;
-    resourceLogger.error('Resource read failed', { uri, duration, error: error.message });
-    return {
-      contents: [{
-        uri,
-        mimeType: 'application/json',
-        text: JSON.stringify({ error: error.message }),
-      }],
-    };
-  }
-});
-
-/**
- * Handle tool call request
- */
-server.setRequestHandler(CallToolRequestSchema, async (request) => {
-  const { name, arguments: args } = request.params;
-  toolLogger.debug('Tool call received', { tool: name, args: Object.keys(args || {}) });
-  const start = Date.now();
-
-  try {
-    let result: ToolResult;
-
-    switch (name) {
-      // Code Understanding
-      case 'cv_find':
-        validateArgs(args, ['query']);
-        result = await handleFind(args as unknown as FindArgs);
-        break;
-
-      case 'cv_context':
-        validateArgs(args, ['query']);
-        result = await handleContext(args as unknown as ContextArgs);
-        break;
-
-      case 'cv_auto_context':
-        validateArgs(args, ['query']);
-        result = await handleAutoContext(args as unknown as AutoContextArgs);
+function parseArgs(argv: string[]): ServeOptions {
+  const options: ServeOptions = {};
+  for (let i = 0; i < argv.length; i++) {
+    switch (argv[i]) {
+      case '--sse':
+        options.transport = 'sse';
         break;
-
-      case 'cv_explain':
-        validateArgs(args, ['target']);
-        result = await handleExplain(args as unknown as ExplainArgs);
-        break;
-
-      case 'cv_reason':
-        validateArgs(args, ['query']);
-        result = await handleReason(args as unknown as ReasonArgs);
+      case '--port':
+        options.port = parseInt(argv[++i], 10);
         break;
-
-      case 'cv_graph_query':
-        validateArgs(args, ['queryType']);
-        result = await handleGraphQuery(args as unknown as GraphQueryArgs);
-        break;
-
-      case 'cv_graph_stats':
-        result = await handleGraphStats();
-        break;
-
-      case 'cv_graph_inspect':
-        validateArgs(args, ['target']);
-        result = await handleGraphInspect(args as { target: string });
+      case '--host':
+        options.host = argv[++i];
         break;
-
-      case 'cv_graph_path':
-        validateArgs(args, ['from', 'to']);
-        result = await handleGraphPath(args as { from: string; to: string; maxDepth?: number });
-        break;
-
-      case 'cv_graph_dead_code':
-        result = await handleGraphDeadCode();
-        break;
-
-      case 'cv_graph_complexity':
-        result = await handleGraphComplexity(args as { threshold?: number; limit?: number });
-        break;
-
-      case 'cv_graph_cycles':
-        result = await handleGraphCycles(args as { maxDepth?: number });
-        break;
-
-      case 'cv_graph_hotspots':
-        result = await handleGraphHotspots(args as { limit?: number });
-        break;
-
-      case 'cv_graph_neighborhood':
-        validateArgs(args, ['symbol']);
-        result = await handleGraphNeighborhood(args as { symbol: string; depth?: number; direction?: string });
-        break;
-
-      case 'cv_graph_impact':
-        validateArgs(args, ['symbol']);
-        result = await handleGraphImpact(args as { symbol: string; depth?: number });
-        break;
-
-      case 'cv_graph_bridge':
-        validateArgs(args, ['source', 'target']);
-        result = await handleGraphBridge(args as { source: string; target: string; maxDepth?: number });
-        break;
-
-      case 'cv_summary_view':
-        result = await handleSummaryView(args as { aspect?: string });
-        break;
-
-      // Version-Aware Tools
-      case 'cv_commits':
-        result = await handleCommits(args as unknown as CommitsArgs);
-        break;
-
-      case 'cv_file_history':
-        validateArgs(args, ['file']);
-        result = await handleFileHistory(args as unknown as FileHistoryArgs);
+      case '--index-only':
+        options.indexOnly = true;
         break;
-
-      case 'cv_blame':
-        validateArgs(args, ['target']);
-        result = await handleBlame(args as unknown as BlameArgs);
-        break;
-
-      // Code Modification
-      case 'cv_do':
-        validateArgs(args, ['task']);
-        result = await handleDo(args as unknown as DoArgs);
-        break;
-
-      case 'cv_review':
-        result = await handleReview(args as unknown as ReviewArgs);
-        break;
-
-      case 'cv_sync':
-        result = await handleSync(args as unknown as SyncArgs);
-        break;
-
-      // Platform Integration
-      case 'cv_pr_create':
-        result = await handlePRCreate(args as any);
-        break;
-
-      case 'cv_pr_list':
-        result = await handlePRList(args as any);
-        break;
-
-      case 'cv_pr_review':
-        validateArgs(args, ['number']);
-        result = await handlePRReview(args as any);
-        break;
-
-      case 'cv_release_create':
-        validateArgs(args, ['version']);
-        result = await handleReleaseCreate(args as any);
-        break;
-
-      // System Operations
-      case 'cv_config_get':
-        validateArgs(args, ['key']);
-        result = await handleConfigGet(args as any);
-        break;
-
-      case 'cv_status':
-        result = await handleStatus();
-        break;
-
-      case 'cv_doctor':
-        result = await handleDoctor();
-        break;
-
-      // PRD Integration Tools
-      case 'cv_prd_context':
-        validateArgs(args, ['query']);
-        result = await handlePRDContext(args as unknown as PRDContextArgs);
-        break;
-
-      case 'cv_requirement_trace':
-        validateArgs(args, ['chunkId']);
-        result = await handleRequirementTrace(args as unknown as RequirementTraceArgs);
-        break;
-
-      case 'cv_test_coverage':
-        validateArgs(args, ['prdId']);
-        result = await handleTestCoverage(args as unknown as CoverageArgs);
-        break;
-
-      case 'cv_doc_coverage':
-        validateArgs(args, ['prdId']);
-        result = await handleDocCoverage(args as unknown as CoverageArgs);
-        break;
-
-      // Documentation Knowledge Graph Tools
-      case 'cv_docs_search':
-        validateArgs(args, ['query']);
-        result = await handleDocsSearch(args as unknown as DocsSearchArgs);
-        break;
-
-      case 'cv_docs_ingest':
-        validateArgs(args, ['path', 'content']);
-        result = await handleDocsIngest(args as unknown as DocsIngestArgs);
-        break;
-
-      case 'cv_docs_list':
-        result = await handleDocsList(args as unknown as DocsListArgs);
-        break;
-
-      // AI Commit Message Generation
-      case 'cv_commit_analyze':
-        result = await handleCommitAnalyze(args as unknown as CommitAnalyzeArgs);
-        break;
-
-      case 'cv_commit_generate':
-        result = await handleCommitGenerate(args as unknown as CommitGenerateArgs);
-        break;
-
-      // Context Manifold
-      case 'cv_manifold_status':
-        result = await handleManifoldStatus(args as unknown as ManifoldStatusArgs);
-        break;
-
-      // Traversal-Aware Context
-      case 'cv_traverse_context':
-        result = await handleTraverseContext(args as unknown as TraverseContextToolArgs);
-        break;
-
-      // Session Knowledge
-      case 'cv_session_knowledge':
-        result = await handleSessionKnowledge(args as unknown as SessionKnowledgeArgs);
-        break;
-
-      case 'cv_session_egress':
-        result = await handleSessionEgress(args as unknown as SessionEgressArgs);
-      // Thread Continuity (CV-Hub integration)
-      case 'cv_thread_create':
-        validateArgs(args, ['title']);
-        result = await handleCreateThread(args as unknown as CreateThreadArgs);
-        break;
-
-      case 'cv_thread_list':
-        result = await handleListThreads(args as unknown as ListThreadsArgs);
-        break;
-
-      case 'cv_thread_summary':
-        validateArgs(args, ['thread_id']);
-        result = await handleGetThreadSummary(args as unknown as GetThreadSummaryArgs);
-        break;
-
-      case 'cv_thread_status':
-        validateArgs(args, ['thread_id', 'status']);
-        result = await handleUpdateThreadStatus(args as unknown as UpdateThreadStatusArgs);
-        break;
-
-      case 'cv_segment_add':
-        validateArgs(args, ['thread_id', 'platform']);
-        result = await handleAddSegment(args as unknown as AddSegmentArgs);
-        break;
-
-      case 'cv_segment_end':
-        validateArgs(args, ['segment_id', 'thread_id']);
-        result = await handleEndSegment(args as unknown as EndSegmentArgs);
-        break;
-
-      case 'cv_bridge_context':
-        validateArgs(args, ['thread_id', 'from_segment_id']);
-        result = await handleBridgeContext(args as unknown as BridgeContextArgs);
-        break;
-
-      case 'cv_bridge_pending':
-        validateArgs(args, ['thread_id']);
-        result = await handleGetPendingBridges(args as unknown as GetPendingBridgesArgs);
-        break;
-
-      case 'cv_dispatch_subtask':
-        validateArgs(args, ['target', 'task_type', 'prompt']);
-        result = await handleDispatchSubtask(args as unknown as DispatchSubtaskArgs);
-        break;
-
-      case 'cv_subtask_status':
-        validateArgs(args, ['task_id']);
-        result = await handleSubtaskStatus(args as unknown as SubtaskStatusArgs);
-        break;
-
-      // Deploy Tools
-      case 'cv_deploy_list':
-        result = await handleDeployList(args as unknown as DeployListArgs);
-        break;
-
-      case 'cv_deploy_push':
-        validateArgs(args, ['target']);
-        result = await handleDeployPush(args as unknown as DeployPushArgs);
-        break;
-
-      case 'cv_deploy_rollback':
-        validateArgs(args, ['target']);
-        result = await handleDeployRollback(args as unknown as DeployRollbackArgs);
-        break;
-
-      case 'cv_deploy_status':
-        validateArgs(args, ['target']);
-        result = await handleDeployStatus(args as unknown as DeployStatusArgs);
-        break;
-
-      default:
-        throw new Error(`Unknown tool: ${name}`);
     }
-
-    // Return MCP-compliant result
-    const duration = Date.now() - start;
-    toolLogger.info('Tool call completed', { tool: name, duration, isError: result.isError });
-    return {
-      content: result.content,
-      isError: result.isError
-    };
-  } catch (error: any) {
-    const duration = Date.now() - start;
-    toolLogger.error('Tool call failed', { tool: name, duration, error: error.message });
-    const errResult = errorResult(`Failed to execute ${name}`, error);
-    return {
-      content: errResult.content,
-      isError: true
-    };
   }
-});
-
-/**
- * Handle errors
- */
-server.onerror = (error) => {
-  serverLogger.error('MCP protocol error', { error: String(error) });
-};
-
-process.on('SIGINT', async () => {
-  serverLogger.info('Shutting down');
-  await server.close();
-  process.exit(0);
-});
-
-/**
- * Start the server
- */
-async function main() {
-  serverLogger.info('Starting CV-Git MCP Server', {
-    logLevel: process.env.CV_LOG_LEVEL || 'info',
-    debug: !!process.env.CV_DEBUG
-  });
-  const transport = new StdioServerTransport();
-  await server.connect(transport);
-  serverLogger.info('Server connected via stdio');
+  return options;
 }
 
-main().catch((error) => {
+serve(parseArgs(process.argv.slice(2))).catch((error) => {
   serverLogger.error('Fatal startup error', { error: error.message });
   process.exit(1);
 });
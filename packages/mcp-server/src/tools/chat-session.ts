@@ -0,0 +1,101 @@
+/**
+ * Chat Session Tool Handlers
+ * Lets editor clients attach to the same persistent sessions as `cv chat`,
+ * so a conversation can move between the terminal and the editor
+ */
+
+import { createChatSessionStore, ChatSessionConflictError, ChatSessionMessage } from '@cv-git/core';
+import { findRepoRoot } from '@cv-git/shared';
+import { ToolResult } from '../types.js';
+import { successResult, errorResult } from '../utils.js';
+
+export interface ChatSessionListArgs {
+  limit?: number;
+}
+
+export interface ChatSessionGetArgs {
+  session_id: string;
+  /** Only return messages appended after this revision */
+  since_revision?: number;
+}
+
+export interface ChatSessionAppendArgs {
+  /** Omit to start a new session */
+  session_id?: string;
+  role: 'user' | 'assistant';
+  content: string;
+  client?: string;
+  model?: string;
+  /** Fail if the session has moved past this revision */
+  expected_revision?: number;
+}
+
+async function openStore() {
+  const repoRoot = await findRepoRoot();
+  if (!repoRoot) {
+    throw new Error('Not in a CV-Git repository. Run `cv init` first.');
+  }
+  return createChatSessionStore(repoRoot);
+}
+
+function formatMessage(m: ChatSessionMessage): string {
+  return `[r${m.revision} ${m.client}] ${m.role}: ${m.content}`;
+}
+
+export async function handleChatSessionList(args: ChatSessionListArgs): Promise<ToolResult> {
+  try {
+    const sessions = (await (await openStore()).list()).slice(0, args.limit ?? 20);
+    if (sessions.length === 0) {
+      return successResult('No saved chat sessions.');
+    }
+    return successResult(JSON.stringify(sessions, null, 2));
+  } catch (error: any) {
+    return errorResult('Failed to list chat sessions', error);
+  }
+}
+
+export async function handleChatSessionGet(args: ChatSessionGetArgs): Promise<ToolResult> {
+  try {
+    const session = await (await openStore()).get(args.session_id);
+    if (!session) {
+      return errorResult(`Chat session not found: ${args.session_id}`);
+    }
+
+    const since = args.since_revision ?? 0;
+    const messages = session.messages.filter(m => m.revision > since);
+    const header = `Session ${session.id} (revision ${session.revision}${session.model ? `, model ${session.model}` : ''})`;
+    return successResult([header, '', ...messages.map(formatMessage)].join('\n'));
+  } catch (error: any) {
+    return errorResult('Failed to read chat session', error);
+  }
+}
+
+export async function handleChatSessionAppend(args: ChatSessionAppendArgs): Promise<ToolResult> {
+  try {
+    const store = await openStore();
+    let id = args.session_id;
+    if (id) {
+      const resolved = await store.resolveId(id);
+      if (!resolved) {
+        return errorResult(`Chat session not found: ${id}`);
+      }
+      id = resolved;
+    } else {
+      id = (await store.create({ model: args.model })).id;
+    }
+
+    const session = await store.append(
+      id,
+      [{ role: args.role, content: args.content, client: args.client || 'mcp' }],
+      { expectedRevision: args.expected_revision, model: args.model }
+    );
+    return successResult(`Appended to session ${session.id} at revision ${session.revision}`);
+  } catch (error: any) {
+    if (error instanceof ChatSessionConflictError) {
+      return errorResult(
+        `Session moved to revision ${error.actualRevision}; call cv_chat_session_get with since_revision=${error.expectedRevision} and retry`
+      );
+    }
+    return errorResult('Failed to append to chat session', error);
+  }
+}
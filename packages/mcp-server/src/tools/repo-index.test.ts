@@ -0,0 +1,128 @@
+/**
+ * Unit tests for the Index MCP Tool Handlers
+ */
+
+import { describe, it, expect, vi, beforeEach, afterEach } from 'vitest';
+import { promises as fs } from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+
+const { mockFindSymbols, mockTraverseCalls, mockGraphManager } = vi.hoisted(() => ({
+  mockFindSymbols: vi.fn(),
+  mockTraverseCalls: vi.fn(),
+  mockGraphManager: {
+    connect: vi.fn().mockResolvedValue(undefined),
+    close: vi.fn().mockResolvedValue(undefined),
+  },
+}));
+
+vi.mock('@cv-git/core', () => ({
+  findSymbols: mockFindSymbols,
+  traverseCalls: mockTraverseCalls,
+  loadSyncHistory: vi.fn().mockResolvedValue([]),
+}));
+
+vi.mock('@cv-git/shared', () => ({
+  findRepoRoot: vi.fn(),
+}));
+
+vi.mock('../utils.js', async (importOriginal) => ({
+  ...(await importOriginal<typeof import('../utils.js')>()),
+  createIsolatedGraphManager: vi.fn().mockResolvedValue({ graph: mockGraphManager, repoRoot: '/repo' }),
+}));
+
+vi.mock('./search.js', () => ({
+  searchCode: vi.fn(),
+}));
+
+import { handleGetCallers, handleGetSymbol, readFileChunk, MAX_CHUNK_LINES } from './repo-index.js';
+
+const symbol = (name: string, file = 'src/pool.ts') => ({
+  name,
+  qualifiedName: `${file}:${name}`,
+  kind: 'function',
+  file,
+  startLine: 10,
+  endLine: 20,
+  signature: `function ${name}()`,
+});
+
+describe('readFileChunk', () => {
+  let repoRoot: string;
+
+  beforeEach(async () => {
+    repoRoot = await fs.mkdtemp(path.join(os.tmpdir(), 'cv-mcp-'));
+    await fs.mkdir(path.join(repoRoot, 'src'));
+    const lines = Array.from({ length: 500 }, (_, i) => `line ${i + 1}`);
+    await fs.writeFile(path.join(repoRoot, 'src', 'big.ts'), lines.join('\n') + '\n');
+  });
+
+  afterEach(async () => {
+    await fs.rm(repoRoot, { recursive: true, force: true });
+  });
+
+  it('returns the requested lines', async () => {
+    const chunk = await readFileChunk(repoRoot, 'src/big.ts', 3, 5);
+    expect(chunk).toEqual({ file: 'src/big.ts', startLine: 3, endLine: 5, totalLines: 500, text: 'line 3\nline 4\nline 5' });
+  });
+
+  it('caps the number of lines', async () => {
+    const chunk = await readFileChunk(repoRoot, 'src/big.ts');
+    expect(chunk.endLine).toBe(MAX_CHUNK_LINES);
+    expect(chunk.text.split('\n')).toHaveLength(MAX_CHUNK_LINES);
+  });
+
+  it('clamps ranges past the end of the file', async () => {
+    const chunk = await readFileChunk(repoRoot, 'src/big.ts', 499, 900);
+    expect(chunk).toMatchObject({ startLine: 499, endLine: 500, text: 'line 499\nline 500' });
+  });
+
+  it('refuses paths outside the repository', async () => {
+    await expect(readFileChunk(repoRoot, '../etc/passwd')).rejects.toThrow('outside the repository');
+  });
+});
+
+describe('handleGetSymbol', () => {
+  beforeEach(() => {
+    vi.clearAllMocks();
+  });
+
+  it('returns matches as JSON', async () => {
+    mockFindSymbols.mockResolvedValue([symbol('drain')]);
+    const result = await handleGetSymbol({ symbol: 'drain' });
+    expect(result.isError).toBeUndefined();
+    expect(JSON.parse(result.content[0].text)).toEqual({
+      symbol: 'drain',
+      matches: [{
+        qualifiedName: 'src/pool.ts:drain',
+        name: 'drain',
+        kind: 'function',
+        file: 'src/pool.ts',
+        startLine: 10,
+        endLine: 20,
+        signature: 'function drain()',
+      }],
+    });
+    expect(mockGraphManager.close).toHaveBeenCalled();
+  });
+
+  it('reports unknown symbols as errors', async () => {
+    mockFindSymbols.mockResolvedValue([]);
+    const result = await handleGetSymbol({ symbol: 'missing' });
+    expect(result.isError).toBe(true);
+    expect(result.content[0].text).toContain('No symbol found for: missing');
+  });
+});
+
+describe('handleGetCallers', () => {
+  it('follows calls up to the requested depth, at most 5', async () => {
+    mockFindSymbols.mockResolvedValue([symbol('drain')]);
+    mockTraverseCalls.mockResolvedValue([{ symbol: symbol('flush', 'src/queue.ts'), depth: 1, via: 'src/pool.ts:drain' }]);
+
+    const result = await handleGetCallers({ symbol: 'drain', depth: 9 });
+    expect(mockTraverseCalls).toHaveBeenCalledWith(mockGraphManager, [symbol('drain')], 'callers', 5);
+    expect(JSON.parse(result.content[0].text).callers).toEqual([
+      expect.objectContaining({ qualifiedName: 'src/queue.ts:flush', depth: 1, via: 'src/pool.ts:drain' }),
+    ]);
+  });
+});
@@ -0,0 +1,227 @@
+/**
+ * Index Tool Handlers
+ * Implements semantic_search, get_symbol, get_callers, get_file_chunk and repo_stats
+ *
+ * A small read-only surface over the cv-git index for editors and agents
+ * (Claude Desktop, Zed, ...). Unlike the cv_* tools, results are JSON so
+ * clients can use them without parsing prose.
+ */
+
+import { promises as fs } from 'fs';
+import * as path from 'path';
+import { findSymbols, loadSyncHistory, traverseCalls } from '@cv-git/core';
+import { findRepoRoot, SymbolNode } from '@cv-git/shared';
+import { ToolResult } from '../types.js';
+import { successResult, errorResult, createIsolatedGraphManager } from '../utils.js';
+import { searchCode } from './search.js';
+
+/** Most lines get_file_chunk returns in one call */
+export const MAX_CHUNK_LINES = 400;
+
+export interface SemanticSearchArgs {
+  query: string;
+  limit?: number;
+  language?: string;
+  file?: string;
+}
+
+export interface GetSymbolArgs {
+  symbol: string;
+}
+
+export interface GetCallersArgs {
+  symbol: string;
+  depth?: number;
+  direction?: 'callers' | 'callees';
+}
+
+export interface GetFileChunkArgs {
+  file: string;
+  startLine?: number;
+  endLine?: number;
+}
+
+export interface FileChunk {
+  file: string;
+  startLine: number;
+  endLine: number;
+  totalLines: number;
+  text: string;
+}
+
+function jsonResult(value: unknown): ToolResult {
+  return successResult(JSON.stringify(value, null, 2));
+}
+
+function symbolJson(symbol: SymbolNode) {
+  return {
+    qualifiedName: symbol.qualifiedName,
+    name: symbol.name,
+    kind: symbol.kind,
+    file: symbol.file,
+    startLine: symbol.startLine,
+    endLine: symbol.endLine,
+    ...(symbol.signature ? { signature: symbol.signature } : {}),
+    ...(symbol.docstring ? { docstring: symbol.docstring } : {}),
+  };
+}
+
+/**
+ * Lines `startLine`-`endLine` (1-based, inclusive) of a file in the
+ * repository, at most MAX_CHUNK_LINES of them
+ *
+ * @throws when the path leaves the repository or the file cannot be read
+ */
+export async function readFileChunk(
+  repoRoot: string,
+  file: string,
+  startLine: number = 1,
+  endLine?: number
+): Promise<FileChunk> {
+  const root = path.resolve(repoRoot);
+  const absolute = path.resolve(root, file);
+  if (absolute !== root && !absolute.startsWith(root + path.sep)) {
+    throw new Error(`Path is outside the repository: ${file}`);
+  }
+
+  const lines = (await fs.readFile(absolute, 'utf-8')).split('\n');
+  if (lines.length > 1 && lines[lines.length - 1] === '') lines.pop();
+
+  const start = Math.max(1, Math.floor(startLine));
+  const end = Math.min(lines.length, Math.floor(endLine ?? lines.length), start + MAX_CHUNK_LINES - 1);
+  return {
+    file: path.relative(root, absolute).split(path.sep).join('/'),
+    startLine: start,
+    endLine: Math.max(start - 1, end),
+    totalLines: lines.length,
+    text: lines.slice(start - 1, end).join('\n'),
+  };
+}
+
+/**
+ * Handle semantic_search tool call
+ */
+export async function handleSemanticSearch(args: SemanticSearchArgs): Promise<ToolResult> {
+  try {
+    const { results, usedFallback } = await searchCode({ ...args, minScore: 0.3 });
+    return jsonResult({
+      query: args.query,
+      source: usedFallback ? 'local-cache' : 'qdrant',
+      results: results.map(r => ({
+        file: r.file,
+        startLine: r.startLine,
+        endLine: r.endLine,
+        ...(r.symbolName ? { symbol: r.symbolName } : {}),
+        score: Number(r.score.toFixed(3)),
+        text: r.text,
+      })),
+    });
+  } catch (error: any) {
+    return errorResult('Semantic search failed', error);
+  }
+}
+
+/**
+ * Handle get_symbol tool call
+ */
+export async function handleGetSymbol(args: GetSymbolArgs): Promise<ToolResult> {
+  try {
+    const { graph } = await createIsolatedGraphManager();
+    await graph.connect();
+    try {
+      const symbols = await findSymbols(graph, args.symbol);
+      if (symbols.length === 0) {
+        return errorResult(`No symbol found for: ${args.symbol}`);
+      }
+      return jsonResult({ symbol: args.symbol, matches: symbols.map(symbolJson) });
+    } finally {
+      await graph.close();
+    }
+  } catch (error: any) {
+    return errorResult('Symbol lookup failed', error);
+  }
+}
+
+/**
+ * Handle get_callers tool call
+ */
+export async function handleGetCallers(args: GetCallersArgs): Promise<ToolResult> {
+  try {
+    const direction = args.direction ?? 'callers';
+    const depth = Math.min(5, Math.max(1, Math.floor(args.depth ?? 1)));
+    const { graph } = await createIsolatedGraphManager();
+    await graph.connect();
+    try {
+      const roots = await findSymbols(graph, args.symbol);
+      if (roots.length === 0) {
+        return errorResult(`No symbol found for: ${args.symbol}`);
+      }
+      const entries = await traverseCalls(graph, roots, direction, depth);
+      return jsonResult({
+        symbol: args.symbol,
+        matches: roots.map(root => root.qualifiedName),
+        [direction]: entries.map(entry => ({ ...symbolJson(entry.symbol), depth: entry.depth, via: entry.via })),
+      });
+    } finally {
+      await graph.close();
+    }
+  } catch (error: any) {
+    return errorResult('Call graph lookup failed', error);
+  }
+}
+
+/**
+ * Handle get_file_chunk tool call
+ */
+export async function handleGetFileChunk(args: GetFileChunkArgs): Promise<ToolResult> {
+  try {
+    const repoRoot = await findRepoRoot();
+    if (!repoRoot) {
+      return errorResult('Not in a CV-Git repository. Run `cv init` first.');
+    }
+    return jsonResult(await readFileChunk(repoRoot, args.file, args.startLine, args.endLine));
+  } catch (error: any) {
+    return errorResult('Failed to read file', error);
+  }
+}
+
+/**
+ * Handle repo_stats tool call
+ */
+export async function handleRepoStats(): Promise<ToolResult> {
+  try {
+    const { graph, repoRoot } = await createIsolatedGraphManager();
+    await graph.connect();
+    try {
+      const stats = await graph.getStats();
+      const languages: Record<string, number> = {};
+      for (const row of await graph.query('MATCH (f:File) RETURN f.language as language, count(f) as count')) {
+        if (row.language) languages[String(row.language)] = Number(row.count) || 0;
+      }
+      const [lastSync] = await loadSyncHistory(repoRoot, 1).catch(() => []);
+
+      return jsonResult({
+        repoRoot,
+        files: stats.fileCount,
+        symbols: stats.symbolCount,
+        functions: stats.functionCount,
+        classes: stats.classCount,
+        commits: stats.commitCount,
+        relationships: stats.relationshipCount,
+        languages,
+        lastSync: lastSync
+          ? {
+            at: new Date(lastSync.timestamp).toISOString(),
+            type: lastSync.type,
+            success: lastSync.success,
+            vectors: lastSync.stats.vectorsCreated,
+          }
+          : null,
+      });
+    } finally {
+      await graph.close();
+    }
+  } catch (error: any) {
+    return errorResult('Failed to get repository statistics', error);
+  }
+}
@@ -20,106 +20,112 @@ import { findRepoRoot } from '@cv-git/shared';
 import { getOpenAIApiKey, getOpenRouterApiKey } from '../credentials.js';
 
 /**
- * Handle cv_find tool call
+ * Semantic search over the index: Qdrant first, the local cache when Qdrant
+ * is unavailable
+ *
+ * @throws when there is no repository, no embedding key or no index
  */
-export async function handleFind(args: FindArgs): Promise<ToolResult> {
-  try {
-    const { query, limit = 10, minScore = 0.5, language, file } = args;
+export async function searchCode(args: FindArgs): Promise<{ results: SearchResult[]; usedFallback: boolean }> {
+  const { query, limit = 10, minScore = 0.5, language, file } = args;
 
-    // Find repository root
-    const repoRoot = await findRepoRoot();
-    if (!repoRoot) {
-      return errorResult('Not in a CV-Git repository. Run `cv init` first.');
-    }
+  // Find repository root
+  const repoRoot = await findRepoRoot();
+  if (!repoRoot) {
+    throw new Error('Not in a CV-Git repository. Run `cv init` first.');
+  }
+
+  // Load configuration
+  const config = await configManager.load(repoRoot);
 
-    // Load configuration
-    const config = await configManager.load(repoRoot);
+  // Get API keys from credential manager
+  const openaiApiKey = config.ai.apiKey || await getOpenAIApiKey();
+  const openrouterApiKey = await getOpenRouterApiKey();
 
-    // Get API keys from credential manager
-    const openaiApiKey = config.ai.apiKey || await getOpenAIApiKey();
-    const openrouterApiKey = await getOpenRouterApiKey();
+  if (!openaiApiKey && !openrouterApiKey) {
+    throw new Error(
+      'No embedding API key found. Run `cv auth setup openai` or `cv auth setup openrouter`.'
+    );
+  }
 
-    if (!openaiApiKey && !openrouterApiKey) {
-      return errorResult(
-        'No embedding API key found. Run `cv auth setup openai` or `cv auth setup openrouter`.'
+  try {
+    // Get service URLs (checks services.json for dynamic ports first)
+    const serviceUrls = await getServiceUrls(config);
+
+    // Initialize vector manager with proper options
+    const vector = createVectorManager({
+      url: serviceUrls.qdrant,
+      openrouterApiKey,
+      openaiApiKey,
+      collections: config.vector.collections,
+    });
+
+    await vector.connect();
+
+    // Perform search
+    const vectorResults = await vector.searchCode(query, limit, {
+      language,
+      file,
+      minScore,
+    });
+
+    await vector.close();
+
+    // Map VectorSearchResult<CodeChunkPayload> to SearchResult
+    const results = vectorResults.map(vr => ({
+      file: vr.payload.file,
+      startLine: vr.payload.startLine,
+      endLine: vr.payload.endLine,
+      symbolName: vr.payload.symbolName,
+      language: vr.payload.language,
+      text: vr.payload.text,
+      score: vr.score,
+      docstring: vr.payload.docstring,
+    }));
+    return { results, usedFallback: false };
+  } catch (qdrantError: any) {
+    // Qdrant unavailable - try local fallback
+    const hasLocal = await hasLocalVectors(repoRoot);
+
+    if (!hasLocal) {
+      throw new Error(
+        'Qdrant unavailable and no local vector cache found.\n' +
+        'Start Qdrant: docker run -d -p 6333:6333 qdrant/qdrant\n' +
+        'Or run `cv sync` to populate the local cache.'
       );
     }
 
-    // Try Qdrant first, fall back to local cache if unavailable
-    let results: SearchResult[] = [];
-    let usedFallback = false;
-
-    try {
-      // Get service URLs (checks services.json for dynamic ports first)
-      const serviceUrls = await getServiceUrls(config);
-
-      // Initialize vector manager with proper options
-      const vector = createVectorManager({
-        url: serviceUrls.qdrant,
-        openrouterApiKey,
-        openaiApiKey,
-        collections: config.vector.collections,
-      });
-
-      await vector.connect();
-
-      // Perform search
-      const vectorResults = await vector.searchCode(query, limit, {
-        language,
-        file,
-        minScore,
-      });
-
-      await vector.close();
-
-      // Map VectorSearchResult<CodeChunkPayload> to SearchResult
-      results = vectorResults.map(vr => ({
-        file: vr.payload.file,
-        startLine: vr.payload.startLine,
-        endLine: vr.payload.endLine,
-        symbolName: vr.payload.symbolName,
-        language: vr.payload.language,
-        text: vr.payload.text,
-        score: vr.score,
-        docstring: vr.payload.docstring,
-      }));
-    } catch (qdrantError: any) {
-      // Qdrant unavailable - try local fallback
-      const hasLocal = await hasLocalVectors(repoRoot);
-
-      if (!hasLocal) {
-        return errorResult(
-          'Qdrant unavailable and no local vector cache found.\n' +
-          'Start Qdrant: docker run -d -p 6333:6333 qdrant/qdrant\n' +
-          'Or run `cv sync` to populate the local cache.'
-        );
-      }
-
-      // Generate embedding for query
-      const queryVector = await createEmbedding(query, {
-        openrouterApiKey,
-        openaiApiKey,
-      });
-
-      // Search local cache
-      const localResults = await searchLocalVectors(repoRoot, queryVector, limit, {
-        minScore,
-        language,
-        file,
-      });
-
-      results = localResults.map(lr => ({
-        file: lr.payload.file,
-        startLine: lr.payload.startLine,
-        endLine: lr.payload.endLine,
-        symbolName: lr.payload.symbolName,
-        language: lr.payload.language,
-        text: lr.text,
-        score: lr.score,
-      }));
-
-      usedFallback = true;
-    }
+    // Generate embedding for query
+    const queryVector = await createEmbedding(query, {
+      openrouterApiKey,
+      openaiApiKey,
+    });
+
+    // Search local cache
+    const localResults = await searchLocalVectors(repoRoot, queryVector, limit, {
+      minScore,
+      language,
+      file,
+    });
+
+    const results = localResults.map(lr => ({
+      file: lr.payload.file,
+      startLine: lr.payload.startLine,
+      endLine: lr.payload.endLine,
+      symbolName: lr.payload.symbolName,
+      language: lr.payload.language,
+      text: lr.text,
+      score: lr.score,
+    }));
+    return { results, usedFallback: true };
+  }
+}
+
+/**
+ * Handle cv_find tool call
+ */
+export async function handleFind(args: FindArgs): Promise<ToolResult> {
+  try {
+    const { results, usedFallback } = await searchCode(args);
 
     // Format and return results
     let formattedResults = formatSearchResults(results);
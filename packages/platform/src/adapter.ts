@@ -16,6 +16,9 @@ import type {
   User,
   Commit,
   Branch,
+  ReviewComment,
+  PullRequestComment,
+  PullRequestReview,
 } from './types/common.js';
 
 /**
@@ -115,6 +118,20 @@ export interface ListIssueOptions {
   direction?: 'asc' | 'desc';
 }
 
+/**
+ * Options for submitting a pull request review
+ */
+export interface CreateReviewOptions {
+  /** Review summary (markdown) */
+  body: string;
+
+  /** Inline comments; each must be on a line the PR diff shows */
+  comments?: ReviewComment[];
+
+  /** Commit the comments refer to (defaults to the PR head) */
+  commitSha?: string;
+}
+
 /**
  * Git Platform Adapter
  *
@@ -218,6 +235,36 @@ export interface GitPlatformAdapter {
     options?: { commitMessage?: string; mergeMethod?: 'merge' | 'squash' | 'rebase' }
   ): Promise<PullRequest>;
 
+  // ============================================================================
+  // Review Operations (optional: not every platform supports them)
+  // ============================================================================
+
+  /**
+   * Get the unified diff of a pull request
+   *
+   * @param number - PR number
+   * @returns Diff text
+   */
+  getPRDiff?(number: number): Promise<string>;
+
+  /**
+   * List the review bodies and comments on a pull request
+   *
+   * @param number - PR number
+   * @returns Comments, inline ones with a path
+   */
+  listPRComments?(number: number): Promise<PullRequestComment[]>;
+
+  /**
+   * Submit a review with inline comments (a comment-only review, neither
+   * approving nor requesting changes)
+   *
+   * @param number - PR number
+   * @param options - Review body and comments
+   * @returns Submitted review
+   */
+  createPRReview?(number: number, options: CreateReviewOptions): Promise<PullRequestReview>;
+
   // ============================================================================
   // Release Operations
   // ============================================================================
@@ -16,6 +16,7 @@ import type {
   CreateReleaseOptions,
   CreateIssueOptions,
   ListIssueOptions,
+  CreateReviewOptions,
 } from '../adapter.js';
 import {
   type Repository,
@@ -27,6 +28,8 @@ import {
   type User,
   type Commit,
   type Branch,
+  type PullRequestComment,
+  type PullRequestReview,
 } from '../types/common.js';
 
 export class GitHubAdapter implements GitPlatformAdapter {
@@ -261,6 +264,87 @@ export class GitHubAdapter implements GitPlatformAdapter {
       changedFiles: pr.changed_files,
       additions: pr.additions,
       deletions: pr.deletions,
+      headSha: pr.head.sha,
+    };
+  }
+
+  // ============================================================================
+  // Reviews
+  // ============================================================================
+
+  async getPRDiff(number: number): Promise<string> {
+    await this.init();
+    const { owner, name } = await this.getRepoInfo();
+
+    const { data } = await this.octokit.pulls.get({
+      owner,
+      repo: name,
+      pull_number: number,
+      mediaType: { format: 'diff' },
+    });
+
+    // With the diff media type the response body is the raw diff
+    return data as unknown as string;
+  }
+
+  async listPRComments(number: number): Promise<PullRequestComment[]> {
+    await this.init();
+    const { owner, name } = await this.getRepoInfo();
+
+    const [inline, reviews, comments] = await Promise.all([
+      this.octokit.paginate(this.octokit.pulls.listReviewComments, {
+        owner,
+        repo: name,
+        pull_number: number,
+        per_page: 100,
+      }),
+      this.octokit.paginate(this.octokit.pulls.listReviews, {
+        owner,
+        repo: name,
+        pull_number: number,
+        per_page: 100,
+      }),
+      this.octokit.paginate(this.octokit.issues.listComments, {
+        owner,
+        repo: name,
+        issue_number: number,
+        per_page: 100,
+      }),
+    ]);
+
+    const author = (user: any) => (user ? { username: user.login, url: user.html_url } : undefined);
+    return [
+      ...inline.map((c: any) => ({ body: c.body || '', path: c.path, author: author(c.user) })),
+      ...reviews.map((r: any) => ({ body: r.body || '', author: author(r.user) })),
+      ...comments.map((c: any) => ({ body: c.body || '', author: author(c.user) })),
+    ];
+  }
+
+  async createPRReview(number: number, options: CreateReviewOptions): Promise<PullRequestReview> {
+    await this.init();
+    const { owner, name } = await this.getRepoInfo();
+
+    const comments = options.comments ?? [];
+    const { data } = await this.octokit.pulls.createReview({
+      owner,
+      repo: name,
+      pull_number: number,
+      commit_id: options.commitSha,
+      body: options.body,
+      event: 'COMMENT',
+      comments: comments.map((c) => ({
+        path: c.path,
+        line: c.line,
+        side: 'RIGHT' as const,
+        ...(c.startLine !== undefined ? { start_line: c.startLine, start_side: 'RIGHT' as const } : {}),
+        body: c.body,
+      })),
+    });
+
+    return {
+      id: data.id.toString(),
+      url: data.html_url,
+      comments: comments.length,
     };
   }
 
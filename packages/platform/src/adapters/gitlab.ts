@@ -1,7 +1,8 @@
 /**
  * GitLab Platform Adapter
  *
- * Implements GitPlatformAdapter for GitLab.
+ * Implements GitPlatformAdapter for GitLab.com and self-hosted instances
+ * (custom API/web URLs, custom CA or skipped TLS verification).
  * Converts GitLab-specific API responses to platform-agnostic types.
  */
 
@@ -15,6 +16,7 @@ import type {
   CreateReleaseOptions,
   CreateIssueOptions,
   ListIssueOptions,
+  CreateReviewOptions,
 } from '../adapter.js';
 import {
   type Repository,
@@ -26,7 +28,10 @@ import {
   type User,
   type Commit,
   type Branch,
+  type PullRequestComment,
+  type PullRequestReview,
 } from '../types/common.js';
+import { createFetch, type FetchLike, type TlsOptions } from '../http.js';
 
 /**
  * GitLab API response types
@@ -79,6 +84,25 @@ interface GitLabMergeRequest {
   };
 }
 
+export interface GitLabChange {
+  old_path: string;
+  new_path: string;
+  new_file: boolean;
+  renamed_file: boolean;
+  deleted_file: boolean;
+  diff: string;
+}
+
+interface GitLabNote {
+  id: number;
+  body: string;
+  system: boolean;
+  author: GitLabUser;
+  position?: {
+    new_path?: string;
+  };
+}
+
 interface GitLabRelease {
   tag_name: string;
   name: string;
@@ -134,18 +158,23 @@ export class GitLabAdapter implements GitPlatformAdapter {
   private initialized = false;
   private baseUrl = 'https://gitlab.com/api/v4';
   private webUrl = 'https://gitlab.com';
+  private http: FetchLike;
 
   constructor(
     private credentials: CredentialManager,
-    options?: { apiUrl?: string; webUrl?: string }
+    options?: { apiUrl?: string; webUrl?: string; tls?: TlsOptions }
   ) {
     this.git = simpleGit();
-    if (options?.apiUrl) {
-      this.baseUrl = options.apiUrl;
-    }
     if (options?.webUrl) {
-      this.webUrl = options.webUrl;
+      this.webUrl = options.webUrl.replace(/\/+$/, '');
+    }
+    if (options?.apiUrl) {
+      this.baseUrl = options.apiUrl.replace(/\/+$/, '');
+    } else if (options?.webUrl) {
+      // Self-hosted: the API lives under the instance URL
+      this.baseUrl = `${this.webUrl}/api/v4`;
     }
+    this.http = createFetch(options?.tls);
   }
 
   async init(): Promise<void> {
@@ -181,7 +210,7 @@ export class GitLabAdapter implements GitPlatformAdapter {
     await this.init();
 
     const url = `${this.baseUrl}${endpoint}`;
-    const response = await fetch(url, {
+    const response = await this.http(url, {
       method,
       headers: {
         'PRIVATE-TOKEN': this.token!,
@@ -208,7 +237,7 @@ export class GitLabAdapter implements GitPlatformAdapter {
   // ============================================================================
 
   async validateToken(token: string): Promise<User> {
-    const response = await fetch(`${this.baseUrl}/user`, {
+    const response = await this.http(`${this.baseUrl}/user`, {
       headers: {
         'PRIVATE-TOKEN': token,
       },
@@ -232,7 +261,7 @@ export class GitLabAdapter implements GitPlatformAdapter {
   async getTokenScopes(token: string): Promise<string[]> {
     // GitLab personal access tokens don't expose scopes via API
     // We can check what operations work instead
-    const response = await fetch(`${this.baseUrl}/personal_access_tokens/self`, {
+    const response = await this.http(`${this.baseUrl}/personal_access_tokens/self`, {
       headers: {
         'PRIVATE-TOKEN': token,
       },
@@ -259,18 +288,18 @@ export class GitLabAdapter implements GitPlatformAdapter {
       throw new Error('No git remote found. Not a git repository?');
     }
 
-    // Parse GitLab URL (https or ssh)
+    // Parse GitLab URL (https or ssh) on the configured instance
     // https://gitlab.com/owner/repo.git
-    // https://gitlab.com/group/subgroup/repo.git
+    // https://gitlab.example.com/group/subgroup/repo.git
     // git@gitlab.com:owner/repo.git
-    // git@gitlab.com:group/subgroup/repo.git
-    const match = origin.refs.push.match(/gitlab\.com[:/](.+?)(?:\.git)?$/);
+    // ssh://git@gitlab.example.com:2222/group/subgroup/repo.git
+    const projectPath = parseGitLabProjectPath(origin.refs.push, this.webUrl);
 
-    if (!match) {
-      throw new Error('Not a GitLab repository');
+    if (!projectPath) {
+      throw new Error(`Not a GitLab repository on ${new URL(this.webUrl).hostname}`);
     }
 
-    return match[1];
+    return projectPath;
   }
 
   async getRepoInfo(): Promise<Repository> {
@@ -456,6 +485,93 @@ export class GitLabAdapter implements GitPlatformAdapter {
       mergedAt: mr.merged_at ? new Date(mr.merged_at) : undefined,
       isDraft: mr.draft,
       changedFiles: mr.changes_count ? parseInt(mr.changes_count, 10) : undefined,
+      headSha: mr.diff_refs?.head_sha,
+    };
+  }
+
+  // ============================================================================
+  // Merge Request Reviews (discussions)
+  // ============================================================================
+
+  async getPRDiff(number: number): Promise<string> {
+    const projectPath = await this.getProjectPath();
+    const encodedPath = encodeURIComponent(projectPath);
+
+    // The changes endpoint is available on older self-hosted versions too
+    const mr = await this.request<{ changes: GitLabChange[] }>(
+      'GET',
+      `/projects/${encodedPath}/merge_requests/${number}/changes?access_raw_diffs=true`
+    );
+
+    return gitLabChangesToDiff(mr.changes);
+  }
+
+  async listPRComments(number: number): Promise<PullRequestComment[]> {
+    const projectPath = await this.getProjectPath();
+    const encodedPath = encodeURIComponent(projectPath);
+
+    const notes: GitLabNote[] = [];
+    for (let page = 1; page <= 20; page++) {
+      const batch = await this.request<GitLabNote[]>(
+        'GET',
+        `/projects/${encodedPath}/merge_requests/${number}/notes?per_page=100&page=${page}`
+      );
+      notes.push(...batch);
+      if (batch.length < 100) break;
+    }
+
+    return notes
+      .filter((note) => !note.system)
+      .map((note) => ({
+        body: note.body,
+        path: note.position?.new_path,
+        author: { username: note.author.username, url: note.author.web_url },
+      }));
+  }
+
+  async createPRReview(number: number, options: CreateReviewOptions): Promise<PullRequestReview> {
+    const projectPath = await this.getProjectPath();
+    const encodedPath = encodeURIComponent(projectPath);
+
+    const mr = await this.request<GitLabMergeRequest>(
+      'GET',
+      `/projects/${encodedPath}/merge_requests/${number}`
+    );
+    if (!mr.diff_refs) {
+      throw new Error(`Merge request !${number} has no diff yet`);
+    }
+
+    // GitLab has no review object: each inline comment is a discussion on
+    // the diff, and the summary is a note on the merge request
+    let posted = 0;
+    for (const comment of options.comments ?? []) {
+      await this.request('POST', `/projects/${encodedPath}/merge_requests/${number}/discussions`, {
+        body: comment.body,
+        position: {
+          position_type: 'text',
+          base_sha: mr.diff_refs.base_sha,
+          start_sha: mr.diff_refs.start_sha,
+          head_sha: options.commitSha ?? mr.diff_refs.head_sha,
+          new_path: comment.path,
+          old_path: comment.path,
+          new_line: comment.line,
+          // Unchanged lines are addressed by both sides
+          ...(comment.oldLine !== undefined ? { old_line: comment.oldLine } : {}),
+        },
+      });
+      posted++;
+    }
+
+    const note = await this.request<GitLabNote>(
+      'POST',
+      `/projects/${encodedPath}/merge_requests/${number}/notes`,
+      { body: options.body }
+    );
+
+    return {
+      id: note.id.toString(),
+      url: `${mr.web_url}#note_${note.id}`,
+      comments: posted,
     };
   }
 
@@ -723,3 +839,29 @@ export class GitLabAdapter implements GitPlatformAdapter {
     };
   }
 }
+
+/**
+ * Project path (`group/subgroup/repo`) of a remote URL on the GitLab
+ * instance at `webUrl`, or null for remotes on other hosts
+ */
+export function parseGitLabProjectPath(remoteUrl: string, webUrl: string = 'https://gitlab.com'): string | null {
+  const host = new URL(webUrl).hostname.replace(/[.*+?^${}()|[\]\\]/g, '\\$&');
+  // Optional user@, optional :port, then : (scp-style) or / before the path
+  const match = remoteUrl.match(new RegExp(`[@/]${host}(?::\\d+)?[:/](.+?)(?:\\.git)?/?$`));
+  return match ? match[1] : null;
+}
+
+/**
+ * Unified diff from the merge request changes API (per-file hunks without
+ * headers)
+ */
+export function gitLabChangesToDiff(changes: GitLabChange[]): string {
+  return changes
+    .map((change) => {
+      const from = change.new_file ? '/dev/null' : `a/${change.old_path}`;
+      const to = change.deleted_file ? '/dev/null' : `b/${change.new_path}`;
+      const body = change.diff.endsWith('\n') ? change.diff : `${change.diff}\n`;
+      return `diff --git a/${change.old_path} b/${change.new_path}\n--- ${from}\n+++ ${to}\n${body}`;
+    })
+    .join('');
+}
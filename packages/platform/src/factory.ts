@@ -9,6 +9,7 @@
 import type { CredentialManager } from '@cv-git/credentials';
 import { GitPlatform } from '@cv-git/credentials';
 import type { GitPlatformAdapter } from './adapter.js';
+import type { TlsOptions } from './http.js';
 import { GitHubAdapter } from './adapters/github.js';
 import { GitLabAdapter } from './adapters/gitlab.js';
 import { BitbucketAdapter } from './adapters/bitbucket.js';
@@ -26,6 +27,9 @@ export interface PlatformConfig {
 
   /** Platform web URL (optional, uses default for known platforms) */
   webUrl?: string;
+
+  /** TLS settings for self-hosted instances (custom CA, skip verification) */
+  tls?: TlsOptions;
 }
 
 /**
@@ -51,6 +55,7 @@ export function createPlatformAdapter(
       return new GitLabAdapter(credentials, {
         apiUrl: config.apiUrl,
         webUrl: config.webUrl,
+        tls: config.tls,
       });
 
     case GitPlatform.BITBUCKET:
@@ -87,7 +92,8 @@ export function createPlatformAdapter(
 export function detectPlatformFromRemote(remoteUrl: string): GitPlatform {
   if (remoteUrl.includes('github.com')) {
     return GitPlatform.GITHUB;
-  } else if (remoteUrl.includes('gitlab.com')) {
+  } else if (remoteUrl.includes('gitlab.com') || /[@/.]gitlab\./.test(remoteUrl)) {
+    // gitlab.com, or a self-hosted instance such as gitlab.example.com
     return GitPlatform.GITLAB;
   } else if (remoteUrl.includes('bitbucket.org')) {
     return GitPlatform.BITBUCKET;
@@ -0,0 +1,79 @@
+/**
+ * HTTP with TLS Settings
+ *
+ * Self-hosted instances often sit behind an internal CA or use self-signed
+ * certificates. Global fetch has no per-request TLS options, so when a CA
+ * file or `insecure` is configured, requests go through node:https with a
+ * dedicated agent instead. Without TLS settings this is plain fetch.
+ */
+
+import * as fs from 'fs';
+import * as http from 'http';
+import * as https from 'https';
+import { rootCertificates } from 'tls';
+
+/**
+ * TLS settings for a platform's API
+ */
+export interface TlsOptions {
+  /** PEM file with the CA certificate(s) to trust, in addition to the system CAs */
+  caFile?: string;
+
+  /** Skip certificate verification (testing only) */
+  insecure?: boolean;
+}
+
+export type FetchLike = (url: string, init?: RequestInit) => Promise<Response>;
+
+/**
+ * fetch honoring the TLS settings
+ *
+ * @throws when the CA file cannot be read
+ */
+export function createFetch(tls?: TlsOptions): FetchLike {
+  if (!tls?.caFile && !tls?.insecure) {
+    return (url, init) => fetch(url, init);
+  }
+
+  const agent = new https.Agent({
+    // Setting `ca` replaces the bundled roots, so keep them alongside the extra CA
+    ca: tls.caFile ? [...rootCertificates, fs.readFileSync(tls.caFile, 'utf-8')] : undefined,
+    rejectUnauthorized: !tls.insecure,
+    keepAlive: true,
+  });
+
+  return (url, init = {}) =>
+    new Promise<Response>((resolve, reject) => {
+      const target = new URL(url);
+      const transport = target.protocol === 'http:' ? http : https;
+      const request = transport.request(
+        target,
+        {
+          method: init.method ?? 'GET',
+          headers: Object.fromEntries(new Headers(init.headers).entries()),
+          ...(transport === https ? { agent } : {}),
+        },
+        (response) => {
+          const chunks: Buffer[] = [];
+          response.on('data', (chunk: Buffer) => chunks.push(chunk));
+          response.on('error', reject);
+          response.on('end', () => {
+            const status = response.statusCode ?? 0;
+            const headers = new Headers();
+            for (const [name, value] of Object.entries(response.headers)) {
+              if (value !== undefined) headers.set(name, Array.isArray(value) ? value.join(', ') : value);
+            }
+            // Responses with these statuses must not have a body
+            const body = status === 204 || status === 304 ? null : Buffer.concat(chunks);
+            resolve(new Response(body, { status, statusText: response.statusMessage, headers }));
+          });
+        }
+      );
+      request.on('error', reject);
+      if (init.signal) {
+        init.signal.addEventListener('abort', () => request.destroy(new Error('Request aborted')));
+      }
+      if (init.body !== undefined && init.body !== null) request.write(init.body as string | Buffer);
+      request.end();
+    });
+}
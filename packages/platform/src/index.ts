@@ -18,6 +18,7 @@ export {
   type CreateReleaseOptions,
   type CreateIssueOptions,
   type ListIssueOptions,
+  type CreateReviewOptions,
 } from './adapter.js';
 
 // Platform-agnostic types
@@ -32,8 +33,14 @@ export {
   type Commit,
   type Branch,
   type DiffStats,
+  type ReviewComment,
+  type PullRequestComment,
+  type PullRequestReview,
 } from './types/index.js';
 
+// HTTP with TLS settings (self-hosted instances)
+export { createFetch, type TlsOptions, type FetchLike } from './http.js';
+
 // Factory
 export {
   createPlatformAdapter,
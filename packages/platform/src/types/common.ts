@@ -97,6 +97,9 @@ export interface PullRequest {
 
   /** Number of deletions */
   deletions?: number;
+
+  /** Head commit SHA */
+  headSha?: string;
 }
 
 /**
@@ -265,3 +268,52 @@ export interface DiffStats {
   /** Total changes */
   total: number;
 }
+
+/**
+ * Inline comment on a pull request's diff
+ */
+export interface ReviewComment {
+  /** File path (after the change) */
+  path: string;
+
+  /** Line commented on, or the last line of a range (new side of the diff) */
+  line: number;
+
+  /** First line of a multi-line comment */
+  startLine?: number;
+
+  /** Old-side line, when the commented line is unchanged context */
+  oldLine?: number;
+
+  /** Comment text (markdown) */
+  body: string;
+}
+
+/**
+ * Comment already on a pull request: a review body, a general comment or an
+ * inline comment (with a path)
+ */
+export interface PullRequestComment {
+  /** Comment text */
+  body: string;
+
+  /** File path, for inline comments */
+  path?: string;
+
+  /** Comment author */
+  author?: User;
+}
+
+/**
+ * Review submitted on a pull request
+ */
+export interface PullRequestReview {
+  /** Review ID */
+  id: string;
+
+  /** Review URL */
+  url: string;
+
+  /** Number of inline comments posted */
+  comments: number;
+}
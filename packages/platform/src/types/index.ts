@@ -13,4 +13,7 @@ export {
   type Commit,
   type Branch,
   type DiffStats,
+  type ReviewComment,
+  type PullRequestComment,
+  type PullRequestReview,
 } from './common.js';
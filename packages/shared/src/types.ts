@@ -41,6 +41,11 @@ export interface CallInfo {
   isConditional: boolean; // Inside if/try/catch block
 }
 
+export interface InheritanceInfo {
+  name: string;                        // Name of the base class, interface or trait
+  type: 'extends' | 'implements';
+}
+
 // ========== Graph Node Types ==========
 
 export interface FileNode {
@@ -71,9 +76,11 @@ export interface SymbolNode {
   isAsync: boolean;
   isStatic: boolean;
   complexity: number;
+  cognitiveComplexity?: number; // SonarSource cognitive complexity (functions and methods)
   vectorId?: string;       // Primary chunk ID (backwards compat)
   vectorIds?: string[];    // All chunk IDs for this symbol
   calls?: CallInfo[];      // Functions/methods this symbol calls
+  inherits?: InheritanceInfo[]; // Types this class/struct extends or implements
   createdAt: number;
   updatedAt: number;
 }
@@ -340,12 +347,22 @@ export interface CodeChunk {
   language: string;
   startLine: number;
   endLine: number;
+  /** UTF-8 byte offset of the chunk's first character in the file */
+  startByte?: number;
+  /** UTF-8 byte offset just past the chunk's last character */
+  endByte?: number;
   text: string;
   symbolName?: string;
   symbolKind?: SymbolKind;
   summary?: string;
   docstring?: string;
   complexity?: number;
+  /** True when the chunk is an AI/structural summary standing in for the file's source */
+  synthetic?: boolean;
+  /** Why the file was summarized instead of chunked */
+  syntheticReason?: 'too-large' | 'generated';
+  /** Size in bytes of the original file (synthetic chunks only) */
+  originalSize?: number;
 }
 
 // ========== Vector Types ==========
@@ -368,12 +385,22 @@ export interface CodeChunkPayload extends VectorPayload {
   symbolKind?: SymbolKind;
   startLine: number;
   endLine: number;
+  startByte?: number;
+  endByte?: number;
   text: string;
   summary?: string;
   docstring?: string;
   imports: string[];
   complexity?: number;
   lastModified: number;
+  /** Summary chunk standing in for an oversized or generated file */
+  synthetic?: boolean;
+  syntheticReason?: 'too-large' | 'generated';
+  originalSize?: number;
+  /** Monorepo package the file belongs to (see `cv explain --package`) */
+  package?: string;
+  /** Workspace repository the chunk was retrieved from (unset for the current one) */
+  repo?: string;
 }
 
 export interface DocstringPayload extends VectorPayload {
@@ -580,6 +607,13 @@ export interface Plan {
   estimatedComplexity: 'low' | 'medium' | 'high';
   affectedFiles: string[];
   risks?: string[];
+  /** Shell commands to run (tests, migrations, codegen) */
+  commands?: string[];
+  /** Estimated cost of executing the plan's code generation step */
+  estimatedTokens?: {
+    prompt: number;
+    maxCompletion: number;
+  };
 }
 
 export interface PlanStep {
@@ -587,6 +621,7 @@ export interface PlanStep {
   type: 'create' | 'modify' | 'delete' | 'rename';
   file: string;
   details?: string;
+  commands?: string[];
 }
 
 export interface Diff {
@@ -713,13 +748,29 @@ export interface CVConfig {
     apiKey?: string;
     maxTokens: number;
     temperature: number;
+    /**
+     * Models to try, in order, when the chosen one hits a rate limit,
+     * timeout or server error, e.g. ["openai/gpt-4o", "ollama/llama3"]
+     * or "openai/gpt-4o -> ollama/llama3"
+     */
+    fallback?: string[] | string;
   };
   embedding: {
-    provider: 'openrouter' | 'openai' | 'ollama' | 'lmstudio';
+    provider: 'openrouter' | 'openai' | 'ollama' | 'lmstudio' | 'azure' | 'local';
     model: string;
     apiKey?: string;
     url?: string;
     dimensions: number;
+    /** Chunks per embedding request during sync, up to the provider's limit (default: that limit) */
+    batchSize?: number;
+    /** Embedding requests cv sync keeps in flight (default: the provider's rateLimits maxConcurrent) */
+    concurrency?: number;
+    /** Azure OpenAI resource; falls back to AZURE_OPENAI_* environment variables */
+    azure?: {
+      endpoint?: string;
+      deployment?: string;
+      apiVersion?: string;
+    };
   };
   graph: {
     provider: 'falkordb' | 'falkordblite' | 'ladybugdb' | 'auto';
@@ -728,7 +779,7 @@ export interface CVConfig {
     database: string;
   };
   vector: {
-    provider: 'qdrant' | 'chroma';
+    provider: 'qdrant' | 'chroma' | 'lancedb' | 'sqlite-vec' | 'jsonl';
     url: string;
     embedded: boolean;
     collections: {
@@ -737,12 +788,139 @@ export interface CVConfig {
       commits: string;
       documentChunks: string;
     };
+    /** HNSW graph settings; embedded stores use exact search below minPoints */
+    hnsw?: {
+      enabled?: boolean;
+      m?: number;
+      efConstruction?: number;
+      efSearch?: number;
+      minPoints?: number;
+    };
+    /** Index code chunks per branch and scope code search to the checked-out branch (default: true) */
+    branchNamespaces?: boolean;
+    /** Store vectors as int8 or product-quantized codes (see `cv index stats`) */
+    quantization?: {
+      codec?: 'none' | 'int8' | 'pq';
+      /** pq: codes per vector (default: dimensions / 8) */
+      subvectors?: number;
+      /** Embedded stores: points before a collection's codec is trained (default 1000) */
+      trainPoints?: number;
+    };
+    /** Collection name → collection that holds it, set by `cv index reembed` when it swaps models */
+    aliases?: Record<string, string>;
   };
   sync: {
     autoSync: boolean;
     syncOnCommit: boolean;
     excludePatterns: string[];
     includeLanguages: string[];
+    /** How source files are split for embedding; parsers' symbol chunking when unset */
+    chunking?: {
+      strategy: 'function' | 'class' | 'sliding-window';
+      windowLines?: number;
+      overlapLines?: number;
+    };
+  };
+  /** Retrieval tuning; written by `cv calibrate` */
+  retrieval?: {
+    /** Similarity cut-off for retrieved chunks; overrides the adaptive threshold */
+    minScore?: number;
+    /** Adaptive cut-off: percentile of random-pair similarity sampled at sync time (default: 90) */
+    minScorePercentile?: number;
+    /** Chunks passed to the model */
+    topK?: number;
+    /** Re-rank by which chunks past answers cited */
+    rerank?: boolean;
+    /** Fuse BM25 keyword matches with vector search (default: true) */
+    hybrid?: boolean;
+    /** Tokens of retrieved code packed into prompts (default: 16000, capped by the model's window) */
+    contextTokens?: number;
+    /** Also search reformulations of the query and fuse the results (default: off) */
+    expansion?: {
+      mode?: 'off' | 'synonyms' | 'llm';
+      /** Reformulations per query, 1-4 (default: 3) */
+      queries?: number;
+      /** Codebase vocabulary: term -> words that mean the same */
+      synonyms?: Record<string, string[]>;
+      /** Mode per command (explain, ask, do, ...); overrides `mode` */
+      commands?: Record<string, 'off' | 'synonyms' | 'llm'>;
+    };
+    calibratedAt?: string;
+  };
+  /** Cross-encoder or provider rerank of retrieved chunks before they reach the model */
+  rerank?: {
+    provider: 'none' | 'local' | 'cohere' | 'jina' | 'voyage';
+    model?: string;
+    /** Falls back to COHERE_API_KEY, JINA_API_KEY or VOYAGE_API_KEY */
+    apiKey?: string;
+    url?: string;
+    /** Candidates reranked per query (default: 3x the chunks kept) */
+    candidates?: number;
+  };
+  /**
+   * Per-provider request limits, keyed by provider (openai, openrouter,
+   * anthropic, gemini, azure, ollama, lmstudio, cohere, ...); unset fields
+   * keep the built-in defaults
+   */
+  rateLimits?: Record<string, {
+    requestsPerMinute?: number;
+    maxConcurrent?: number;
+    maxRetries?: number;
+    baseDelayMs?: number;
+    maxDelayMs?: number;
+  }>;
+  review?: {
+    /** Review policies file, relative to the repository (default: .cv/review-policies.yaml) */
+    policies?: string;
+  };
+  /**
+   * Masking of secrets and personal data in everything sent to model and
+   * embedding providers (on by default)
+   */
+  redaction?: {
+    enabled?: boolean;
+    /** Mask long random-looking strings (default: true) */
+    entropy?: boolean;
+    /** Mask email addresses (default: true) */
+    emails?: boolean;
+    /** Extra patterns to mask, name -> regular expression */
+    patterns?: Record<string, string>;
+    /** Regular expressions for matches to leave as they are */
+    allow?: string[];
+    /** Also redact requests to Ollama and LM Studio (default: false) */
+    local?: boolean;
+  };
+  /** Sibling repositories searched by `--workspace` (see `cv workspace add`) */
+  workspace?: {
+    /** Repository paths, relative to this repository's root */
+    repos?: string[];
+  };
+  /** Team index served by `cv index serve` */
+  index?: {
+    /** Query this server's index (e.g. https://cv.internal:7433) with local changes layered on top */
+    remote?: string;
+    /** Bearer token for the server; falls back to CV_INDEX_TOKEN */
+    token?: string;
+  };
+  /** `cv deadcode` settings */
+  deadcode?: {
+    /** Intentional entry points: symbol names, qualified names or file globs (`legacy_*`, `src/bin/**`) */
+    allow?: string[];
+  };
+  /** `cv test-gen` settings */
+  test?: {
+    /** Command that runs the tests; `{file}` is replaced by the test file (default: from Cargo.toml, go.mod, package.json, ...) */
+    command?: string;
+  };
+  /** `cv docgen` settings */
+  docgen?: {
+    /** Items never documented: symbol names, qualified names or file globs (`*_internal`, `src/generated/**`) */
+    ignore?: string[];
+  };
+  /** `cv security` settings */
+  security?: {
+    /** Detector IDs (`hardcoded-secret`) and file globs (`fixtures/**`) not scanned */
+    ignore?: string[];
   };
   docs: {
     enabled: boolean;
@@ -57,6 +57,65 @@ export async function ensureDir(dir: string): Promise<void> {
   }
 }
 
+/**
+ * Normalize a path to forward slashes, the form git and the index use
+ * for repository-relative paths on every platform
+ */
+export function toPosixPath(p: string): string {
+  return p.replace(/\\/g, '/');
+}
+
+/** Directory APIs cap paths at MAX_PATH (260) minus room for an 8.3 file name */
+const WINDOWS_LONG_PATH_THRESHOLD = 248;
+
+/**
+ * Prefix long absolute Windows paths with \\?\ so fs calls bypass MAX_PATH.
+ * Paths on other platforms, short paths and relative paths are returned unchanged.
+ */
+export function toLongPath(p: string, platform: NodeJS.Platform = process.platform): string {
+  if (
+    platform !== 'win32' ||
+    p.length < WINDOWS_LONG_PATH_THRESHOLD ||
+    p.startsWith('\\\\?\\') ||
+    !path.win32.isAbsolute(p)
+  ) {
+    return p;
+  }
+
+  const resolved = path.win32.resolve(p);
+  if (resolved.startsWith('\\\\')) {
+    // UNC share: \\server\share -> \\?\UNC\server\share
+    return '\\\\?\\UNC\\' + resolved.slice(2);
+  }
+  return '\\\\?\\' + resolved;
+}
+
+/**
+ * Whether the terminal can render Unicode symbols and box drawing.
+ * Legacy Windows consoles (conhost with a non-UTF-8 code page) cannot.
+ */
+export function supportsUnicode(
+  env: NodeJS.ProcessEnv = process.env,
+  platform: NodeJS.Platform = process.platform
+): boolean {
+  if (env.CV_ASCII === '1') {
+    return false;
+  }
+  if (platform !== 'win32') {
+    return env.TERM !== 'linux';
+  }
+  return Boolean(
+    env.CI ||
+    env.WT_SESSION ||
+    env.TERMINUS_SUBLIME ||
+    env.ConEmuTask === '{cmd::Cmder}' ||
+    env.TERM_PROGRAM === 'vscode' ||
+    env.TERM === 'xterm-256color' ||
+    env.TERM === 'alacritty' ||
+    env.TERMINAL_EMULATOR === 'JetBrains-JediTerm'
+  );
+}
+
 /**
  * Generate a unique ID for a code chunk
  */
@@ -84,11 +143,30 @@ export function parseChunkId(chunkId: string): { file: string; startLine: number
   return { file, startLine, endLine };
 }
 
+/**
+ * Extensions registered at runtime by language packs (take precedence over the built-in map)
+ */
+const registeredExtensions = new Map<string, string>();
+
+/**
+ * Register file extensions for a language
+ */
+export function registerLanguageExtensions(language: string, extensions: string[]): void {
+  for (const ext of extensions) {
+    registeredExtensions.set(ext.toLowerCase(), language);
+  }
+}
+
 /**
  * Detect language from file extension
  */
 export function detectLanguage(filePath: string): string {
   const ext = path.extname(filePath).toLowerCase();
+  const registered = registeredExtensions.get(ext);
+  if (registered) {
+    return registered;
+  }
+
   const languageMap: Record<string, string> = {
     '.ts': 'typescript',
     '.tsx': 'typescript',
@@ -149,7 +227,7 @@ export function shouldSyncFile(
  * - ** matches anything including /
  * - ? matches single character
  */
-function matchGlob(str: string, pattern: string): boolean {
+export function matchGlob(str: string, pattern: string): boolean {
   // Normalize path separators
   const normalizedStr = str.replace(/\\/g, '/');
   const normalizedPattern = pattern.replace(/\\/g, '/');
@@ -0,0 +1,183 @@
+#!/usr/bin/env node
+/**
+ * HNSW Index File Benchmarks
+ *
+ * Writes a synthetic saved graph the size of a large repository (1.5M
+ * chunks by default) and measures how long a new process takes to open it
+ * and answer its first searches. Opening reads only the header and the
+ * tombstones, so it should stay under the cold-start budget however many
+ * points the file holds.
+ *
+ * Usage:
+ *   node index-file-benchmark.mjs [options]
+ *
+ * Options:
+ *   --chunks=<n>      Points in the graph (default: 1500000)
+ *   --dimension=<n>   Vector dimension (default: 384)
+ *   --budget=<ms>     Cold-start budget for opening (default: 100)
+ *   --keep            Keep the generated file in the temp directory
+ *   --json            Output results as JSON
+ */
+
+import { performance } from 'perf_hooks';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { HnswIndex } from '../../packages/core/dist/vector/hnsw.js';
+
+const args = process.argv.slice(2);
+const option = (name, fallback) => {
+  const arg = args.find(a => a.startsWith(`--${name}=`));
+  return arg ? Number(arg.split('=')[1]) : fallback;
+};
+const CHUNKS = option('chunks', 1_500_000);
+const DIMENSION = option('dimension', 384);
+const BUDGET_MS = option('budget', 100);
+const LINKS = 32;
+const BATCH = 65536;
+const outputJson = args.includes('--json');
+
+/** Deterministic RNG (mulberry32) so every run writes the same graph */
+function rng(seed) {
+  return () => {
+    seed = (seed + 0x6d2b79f5) | 0;
+    let t = seed;
+    t = Math.imul(t ^ (t >>> 15), t | 1);
+    t ^= t + Math.imul(t ^ (t >>> 7), t | 61);
+    return ((t ^ (t >>> 14)) >>> 0) / 4294967296;
+  };
+}
+
+const align = n => Math.ceil(n / 8) * 8;
+
+/**
+ * Stream a version 2 HNSW file (see index-file.ts for the layout) without
+ * holding it in memory: a single layer where every node links to LINKS
+ * random others
+ */
+function writeGraph(file) {
+  const random = rng(42);
+  const id = i => `src/module${Math.floor(i / 100)}/file${i % 100}.ts:${i}`;
+  const idBytes = Array.from({ length: CHUNKS }, (_, i) => Buffer.byteLength(id(i)));
+
+  const lengths = {
+    vectors: CHUNKS * DIMENSION * 4,
+    linkStart: (CHUNKS + 1) * 4,
+    links: CHUNKS * (1 + LINKS) * 4,
+    levels: CHUNKS,
+    ids: idBytes.reduce((a, b) => a + b, 0),
+    'ids.offsets': (CHUNKS + 1) * 4,
+    deleted: 0,
+  };
+  const sections = {};
+  let size = 0;
+  for (const [name, length] of Object.entries(lengths)) {
+    sections[name] = [size, length];
+    size = align(size + length);
+  }
+  const header = Buffer.from(JSON.stringify({
+    version: 2, dimension: DIMENSION, m: LINKS / 2, efConstruction: 200,
+    entryPoint: 0, maxLevel: 0, count: CHUNKS, sections,
+  }));
+  const start = align(8 + header.length);
+
+  const fd = fs.openSync(file, 'w');
+  try {
+    const prefix = Buffer.alloc(start);
+    prefix.write('HNSW', 0, 'ascii');
+    prefix.writeUInt32LE(header.length, 4);
+    header.copy(prefix, 8);
+    fs.writeSync(fd, prefix, 0, prefix.length, 0);
+    const write = (name, data, at) => fs.writeSync(fd, data, 0, data.byteLength, start + sections[name][0] + at);
+
+    for (let from = 0; from < CHUNKS; from += BATCH) {
+      const count = Math.min(BATCH, CHUNKS - from);
+      const vectors = new Float32Array(count * DIMENSION);
+      for (let i = 0; i < vectors.length; i++) vectors[i] = random() * 2 - 1;
+      write('vectors', Buffer.from(vectors.buffer), from * DIMENSION * 4);
+
+      const links = new Uint32Array(count * (1 + LINKS));
+      for (let n = 0; n < count; n++) {
+        links[n * (1 + LINKS)] = LINKS;
+        for (let l = 1; l <= LINKS; l++) links[n * (1 + LINKS) + l] = Math.floor(random() * CHUNKS);
+      }
+      write('links', Buffer.from(links.buffer), from * (1 + LINKS) * 4);
+    }
+
+    const linkStart = Uint32Array.from({ length: CHUNKS + 1 }, (_, n) => n * (1 + LINKS));
+    write('linkStart', Buffer.from(linkStart.buffer), 0);
+    write('levels', Buffer.alloc(CHUNKS), 0);
+    const offsets = new Uint32Array(CHUNKS + 1);
+    idBytes.forEach((length, i) => { offsets[i + 1] = offsets[i] + length; });
+    write('ids.offsets', Buffer.from(offsets.buffer), 0);
+    write('ids', Buffer.from(Array.from({ length: CHUNKS }, (_, i) => id(i)).join('')), 0);
+    fs.ftruncateSync(fd, start + size);
+  } finally {
+    fs.closeSync(fd);
+  }
+  return start + size;
+}
+
+async function main() {
+  const dir = fs.mkdtempSync(path.join(os.tmpdir(), 'cv-index-bench-'));
+  const file = path.join(dir, 'code_chunks.hnsw');
+  const random = rng(7);
+  const query = () => Array.from({ length: DIMENSION }, () => random() * 2 - 1);
+
+  try {
+    if (!outputJson) console.log(`🧪 HNSW index file: ${CHUNKS.toLocaleString()} chunks × ${DIMENSION} dimensions\n`);
+    let started = performance.now();
+    const bytes = writeGraph(file);
+    const writeMs = performance.now() - started;
+
+    started = performance.now();
+    const index = await HnswIndex.load(file);
+    const openMs = performance.now() - started;
+
+    started = performance.now();
+    index.search(query(), 10);
+    const firstSearchMs = performance.now() - started;
+
+    const times = [];
+    for (let i = 0; i < 20; i++) {
+      started = performance.now();
+      index.search(query(), 10);
+      times.push(performance.now() - started);
+    }
+    index.close();
+
+    const results = {
+      chunks: CHUNKS,
+      dimension: DIMENSION,
+      fileMB: Number((bytes / 1024 / 1024).toFixed(1)),
+      writeMs: Number(writeMs.toFixed(1)),
+      openMs: Number(openMs.toFixed(3)),
+      firstSearchMs: Number(firstSearchMs.toFixed(3)),
+      searchAvgMs: Number((times.reduce((a, b) => a + b, 0) / times.length).toFixed(3)),
+      budgetMs: BUDGET_MS,
+      passed: openMs < BUDGET_MS,
+    };
+
+    if (outputJson) {
+      console.log(JSON.stringify(results, null, 2));
+    } else {
+      console.log(`  File:          ${results.fileMB} MB (written in ${results.writeMs} ms)`);
+      console.log(`  Open:          ${results.openMs} ms (budget ${BUDGET_MS} ms)`);
+      console.log(`  First search:  ${results.firstSearchMs} ms`);
+      console.log(`  Search (avg):  ${results.searchAvgMs} ms\n`);
+      console.log(results.passed ? '✅ Cold start within budget' : '❌ Cold start over budget');
+    }
+    process.exitCode = results.passed ? 0 : 1;
+  } finally {
+    if (args.includes('--keep')) {
+      if (!outputJson) console.log(`\nKept ${file}`);
+    } else {
+      fs.rmSync(dir, { recursive: true, force: true });
+    }
+  }
+}
+
+main().catch(error => {
+  console.error(error);
+  process.exit(1);
+});
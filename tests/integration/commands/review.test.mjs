@@ -0,0 +1,113 @@
+/**
+ * Review Command Tests
+ * Tests for the exit codes hooks rely on: cv review --fast and --budget
+ * give up with exit 0 instead of blocking a commit or push
+ */
+
+import * as net from 'net';
+import * as path from 'path';
+import { promises as fs } from 'fs';
+import { execFileSync } from 'child_process';
+import {
+  runCV,
+  assertSuccess,
+  assertContains,
+  createTempDir,
+  cleanupTempDir,
+  TestRunner,
+} from '../../helpers/cli-test-utils.mjs';
+
+const runner = new TestRunner('Review Command Tests');
+
+/** A git repo with a staged change; `cv: true` also sets it up as a CV-Git repo */
+async function stagedRepo({ cv }) {
+  const dir = await createTempDir('cv-review-');
+  const git = (...args) => execFileSync('git', args, { cwd: dir });
+  git('init', '-q');
+  await fs.writeFile(path.join(dir, 'index.js'), 'export const answer = 42;\n');
+  git('add', 'index.js');
+  if (cv) {
+    await fs.mkdir(path.join(dir, '.cv'));
+    await fs.writeFile(path.join(dir, '.cv', 'config.json'), JSON.stringify({
+      repository: { name: 'review-test', repoId: 'review-test' },
+      graph: { url: 'redis://localhost:6379', database: 'cv_review_test' },
+    }));
+  }
+  return dir;
+}
+
+// No stored credentials: plain-file store under an empty HOME
+const noCredentials = (home) => ({
+  HOME: home,
+  CV_CREDENTIAL_STORE: 'plain-file',
+  ANTHROPIC_API_KEY: '',
+});
+
+// cv review --budget <invalid>
+await runner.test('cv review --budget 0 is a usage error', async () => {
+  const result = await runCV(['review', '--staged', '--budget', '0']);
+  if (result.code !== 2) {
+    throw new Error(`expected exit code 2, got ${result.code}`);
+  }
+  assertContains(result.stderr, 'Invalid --budget', 'should explain the bad budget');
+});
+
+// cv review --fast outside a CV-Git repo
+await runner.test('cv review --fast exits 0 outside a CV-Git repository', async () => {
+  const dir = await stagedRepo({ cv: false });
+  try {
+    const result = await runCV(['review', '--staged', '--fast'], { cwd: dir, env: noCredentials(dir) });
+    assertSuccess(result, 'review --fast outside a CV-Git repo');
+  } finally {
+    await cleanupTempDir(dir);
+  }
+});
+
+// cv review --fast without an API key
+await runner.test('cv review --fast exits 0 without an API key', async () => {
+  const dir = await stagedRepo({ cv: true });
+  try {
+    const fast = await runCV(['review', '--staged', '--fast', '--json'], { cwd: dir, env: noCredentials(dir) });
+    assertSuccess(fast, 'review --fast without a key');
+    assertContains(fast.stdout, 'No Anthropic API key', 'should report why the review was skipped');
+
+    const strict = await runCV(['review', '--staged'], { cwd: dir, env: noCredentials(dir) });
+    if (strict.code !== 2) {
+      throw new Error(`without --fast, expected exit code 2, got ${strict.code}`);
+    }
+  } finally {
+    await cleanupTempDir(dir);
+  }
+});
+
+// cv review --budget against a provider that never answers
+await runner.test('cv review --budget exits 0 when the review takes too long', async () => {
+  const dir = await stagedRepo({ cv: true });
+  const sockets = [];
+  const server = net.createServer(socket => sockets.push(socket));
+  await new Promise(resolve => server.listen(0, '127.0.0.1', resolve));
+  try {
+    const started = Date.now();
+    const result = await runCV(['review', '--staged', '--budget', '1', '--json'], {
+      cwd: dir,
+      env: {
+        ...noCredentials(dir),
+        ANTHROPIC_API_KEY: 'sk-ant-test',
+        ANTHROPIC_BASE_URL: `http://127.0.0.1:${server.address().port}`,
+      },
+    });
+    assertSuccess(result, 'review past its budget');
+    assertContains(result.stdout, 'did not finish within 1s', 'should report the budget');
+    if (Date.now() - started > 15000) {
+      throw new Error('the budget did not cut the review short');
+    }
+  } finally {
+    sockets.forEach(socket => socket.destroy());
+    server.close();
+    await cleanupTempDir(dir);
+  }
+});
+
+// Summary
+runner.summary();
+runner.exit();
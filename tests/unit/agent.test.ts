@@ -0,0 +1,167 @@
+/**
+ * Agent Mode Tests
+ * Tests for step plan parsing, step-by-step execution with approval and the audit log
+ */
+
+import { describe, it, expect, beforeEach, afterEach } from 'vitest';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import {
+  parseAgentPlan,
+  AgentRunner,
+  AgentPlan,
+  AgentRunnerOptions,
+  StepDecision
+} from '../../packages/core/src/ai/agent.js';
+
+describe('parseAgentPlan', () => {
+  it('should parse read, edit and run steps', () => {
+    const plan = parseAgentPlan(
+      '```json\n' + JSON.stringify({
+        steps: [
+          { kind: 'read', description: 'Look at the parser', files: ['src/parser.ts'] },
+          { kind: 'edit', description: 'Handle empty input', files: ['src/parser.ts'] },
+          { kind: 'run', description: 'Run the tests', command: 'npm test' }
+        ]
+      }) + '\n```',
+      'Fix parser'
+    );
+
+    expect(plan.task).toBe('Fix parser');
+    expect(plan.steps.map(s => [s.index, s.kind])).toEqual([[1, 'read'], [2, 'edit'], [3, 'run']]);
+    expect(plan.steps[2].command).toBe('npm test');
+    expect(plan.steps[0].command).toBeUndefined();
+  });
+
+  it('should drop steps that cannot be executed and renumber', () => {
+    const plan = parseAgentPlan(JSON.stringify({
+      steps: [
+        { kind: 'read', description: 'Nothing to read' },
+        { kind: 'deploy', description: 'Ship it' },
+        { kind: 'run', description: 'No command' },
+        { type: 'EDIT', file: 'a.ts', description: 'Edit a' }
+      ]
+    }), 'Task');
+
+    expect(plan.steps).toEqual([{ index: 1, kind: 'edit', description: 'Edit a', files: ['a.ts'] }]);
+  });
+
+  it('should fall back to a single edit step', () => {
+    expect(parseAgentPlan('I would start by reading the code.', 'Do it').steps)
+      .toEqual([{ index: 1, kind: 'edit', description: 'Do it', files: [] }]);
+  });
+});
+
+describe('AgentRunner', () => {
+  let repoRoot: string;
+
+  const plan: AgentPlan = {
+    task: 'Add a greeting',
+    steps: [
+      { index: 1, kind: 'read', description: 'Read the module', files: ['hello.txt'] },
+      { index: 2, kind: 'edit', description: 'Change the greeting', files: ['hello.txt'] },
+      { index: 3, kind: 'run', description: 'Check', files: [], command: 'check' }
+    ]
+  };
+
+  const runner = (overrides: Partial<AgentRunnerOptions> = {}, decisions: StepDecision[] = []) => {
+    const notes: string[] = [];
+    const commands: string[] = [];
+    const options: AgentRunnerOptions = {
+      repoRoot,
+      generateEdit: async (_step, n) => {
+        notes.push(n);
+        return '```hello.txt\n<<<<<<< SEARCH\nhello\n=======\nhello, world\n>>>>>>> REPLACE\n```';
+      },
+      runCommand: async command => {
+        commands.push(command);
+        return { exitCode: 0, output: 'ok' };
+      },
+      approve: async () => decisions.shift() ?? 'approve',
+      ...overrides
+    };
+    return { agent: new AgentRunner(options), notes, commands };
+  };
+
+  const auditLog = (file: string) =>
+    fs.readFileSync(file, 'utf-8').trim().split('\n').map(line => JSON.parse(line));
+
+  beforeEach(() => {
+    repoRoot = fs.mkdtempSync(path.join(os.tmpdir(), 'cv-agent-'));
+    fs.writeFileSync(path.join(repoRoot, 'hello.txt'), 'hello\n');
+  });
+
+  afterEach(() => {
+    fs.rmSync(repoRoot, { recursive: true, force: true });
+  });
+
+  it('should execute every step and pass read files to the edit', async () => {
+    const { agent, notes, commands } = runner();
+    const result = await agent.run(plan);
+
+    expect(result.completed).toBe(true);
+    expect(result.outcomes.map(o => o.status)).toEqual(['done', 'done', 'done']);
+    expect(notes[0]).toContain('### hello.txt');
+    expect(notes[0]).toContain('hello\n');
+    expect(commands).toEqual(['check']);
+    expect(fs.readFileSync(path.join(repoRoot, 'hello.txt'), 'utf-8')).toBe('hello, world\n');
+    expect(result.outcomes[1].files).toEqual(['hello.txt']);
+    expect(result.outcomes[1].changeSet).toBeDefined();
+  });
+
+  it('should write an audit log of the plan, decisions and results', async () => {
+    const { agent } = runner({}, ['approve', 'all']);
+    const result = await agent.run(plan);
+
+    expect(path.relative(repoRoot, result.logFile)).toBe(path.join('.cv', 'agent', `${result.runId}.jsonl`));
+    const entries = auditLog(result.logFile);
+    expect(entries.map(e => e.type)).toEqual(['plan', 'decision', 'step', 'decision', 'step', 'step', 'end']);
+    expect(entries[3]).toMatchObject({ step: 2, decision: 'all' });
+    expect(entries[4]).toMatchObject({ step: 2, status: 'done', files: ['hello.txt'] });
+    expect(entries[6]).toMatchObject({ completed: true });
+  });
+
+  it('should leave files alone when an edit is skipped', async () => {
+    const { agent, commands } = runner({}, ['approve', 'skip', 'approve']);
+    const result = await agent.run(plan);
+
+    expect(result.outcomes.map(o => o.status)).toEqual(['done', 'skipped', 'done']);
+    expect(fs.readFileSync(path.join(repoRoot, 'hello.txt'), 'utf-8')).toBe('hello\n');
+    expect(commands).toEqual(['check']);
+  });
+
+  it('should stop when the user aborts', async () => {
+    const { agent, commands } = runner({}, ['abort']);
+    const result = await agent.run(plan);
+
+    expect(result.completed).toBe(false);
+    expect(result.outcomes.map(o => o.status)).toEqual(['skipped']);
+    expect(commands).toEqual([]);
+    expect(auditLog(result.logFile).pop()).toMatchObject({ type: 'end', completed: false });
+  });
+
+  it('should stop at a failing command', async () => {
+    const { agent } = runner({ runCommand: async () => ({ exitCode: 2, output: 'boom' }) });
+    const result = await agent.run({
+      task: 'Check twice',
+      steps: [
+        { index: 1, kind: 'run', description: 'First', files: [], command: 'a' },
+        { index: 2, kind: 'run', description: 'Second', files: [], command: 'b' }
+      ]
+    });
+
+    expect(result.completed).toBe(false);
+    expect(result.outcomes).toHaveLength(1);
+    expect(result.outcomes[0]).toMatchObject({ status: 'failed', error: 'Command exited with code 2' });
+  });
+
+  it('should refuse edits to files with uncommitted changes', async () => {
+    const { agent } = runner({ uncommitted: async patch => patch.files.map(f => f.file) });
+    const result = await agent.run({ task: plan.task, steps: [plan.steps[1]] });
+
+    expect(result.outcomes[0].status).toBe('failed');
+    expect(result.outcomes[0].error).toContain('uncommitted changes: hello.txt');
+    expect(fs.readFileSync(path.join(repoRoot, 'hello.txt'), 'utf-8')).toBe('hello\n');
+  });
+});
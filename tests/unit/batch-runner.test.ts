@@ -0,0 +1,82 @@
+/**
+ * Batch Runner Tests
+ */
+
+import { describe, it, expect, beforeEach, afterEach } from 'vitest';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { BatchRunner, batchStatePath, formatBatchReport, loadBatchState } from '../../packages/core/src/batch/index.js';
+
+describe('BatchRunner', () => {
+  let dir: string;
+  let statePath: string;
+
+  beforeEach(() => {
+    dir = fs.mkdtempSync(path.join(os.tmpdir(), 'cv-batch-'));
+    statePath = path.join(dir, 'batch', 'docs.json');
+  });
+
+  afterEach(() => {
+    fs.rmSync(dir, { recursive: true, force: true });
+  });
+
+  it('should process every item and aggregate a report', async () => {
+    const runner = new BatchRunner('docs', 'docs', ['a.ts', 'b.ts', 'c.ts'], async item => ({
+      output: `docs for ${item}`,
+      tokens: 10
+    }), { statePath, requestsPerMinute: 0, concurrency: 2 });
+
+    const report = await runner.run();
+
+    expect(report.done).toBe(3);
+    expect(report.tokensUsed).toBe(30);
+    expect(report.results.map(r => r.item)).toEqual(['a.ts', 'b.ts', 'c.ts']);
+    expect(formatBatchReport(report)).toContain('## b.ts');
+  });
+
+  it('should resume without re-running finished items', async () => {
+    const calls: string[] = [];
+    const flaky = async (item: string) => {
+      calls.push(item);
+      if (item === 'b.ts' && calls.filter(c => c === 'b.ts').length === 1) {
+        throw new Error('rate limited');
+      }
+      return { output: item, tokens: 5 };
+    };
+
+    const first = await new BatchRunner('docs', 'docs', ['a.ts', 'b.ts'], flaky, {
+      statePath, requestsPerMinute: 0, concurrency: 1
+    }).run();
+    expect(first.failed).toBe(1);
+    expect((await loadBatchState(statePath))?.results['a.ts'].status).toBe('done');
+
+    const second = await new BatchRunner('docs', 'docs', ['a.ts', 'b.ts'], flaky, {
+      statePath, requestsPerMinute: 0, concurrency: 1
+    }).run();
+
+    expect(second.done).toBe(2);
+    expect(second.resumed).toBe(1);
+    expect(calls).toEqual(['a.ts', 'b.ts', 'b.ts']);
+  });
+
+  it('should stop starting items when the token budget runs out', async () => {
+    const report = await new BatchRunner('review', 'review', ['a.ts', 'b.ts', 'c.ts'], async item => ({
+      output: item,
+      tokens: 60
+    }), { requestsPerMinute: 0, concurrency: 1, tokenBudget: 100 }).run();
+
+    expect(report.done).toBe(2);
+    expect(report.skipped).toBe(1);
+    expect(report.budgetExhausted).toBe(true);
+  });
+});
+
+describe('batchStatePath', () => {
+  it('should keep state files inside the batch directory', () => {
+    expect(batchStatePath('/repo/.cv/batch', 'docs-v2')).toBe(path.join('/repo/.cv/batch', 'docs-v2.json'));
+    for (const name of ['../../x', 'a/b', 'a\\b', '..', '']) {
+      expect(() => batchStatePath('/repo/.cv/batch', name)).toThrow('Invalid batch name');
+    }
+  });
+});
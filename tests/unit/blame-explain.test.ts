@@ -0,0 +1,118 @@
+/**
+ * Blame Explain Tests
+ * Tests for blame parsing, commit grouping and the blame-explain prompt
+ */
+
+import { describe, it, expect } from 'vitest';
+import {
+  collectBlameHistory,
+  groupBlame,
+  parseBlamePorcelain,
+  parseLineRange,
+  UNCOMMITTED_SHA,
+} from '../../packages/core/src/git/blame.js';
+import { buildBlamePrompt, citedCommits } from '../../packages/core/src/ai/blame.js';
+
+const A = 'a'.repeat(40);
+const B = 'b'.repeat(40);
+
+function porcelain(sha: string, line: number, author: string, time: number, summary: string, text: string): string {
+  return [
+    `${sha} ${line} ${line} 1`,
+    `author ${author}`,
+    `author-mail <${author.toLowerCase()}@example.com>`,
+    `author-time ${time}`,
+    'author-tz +0000',
+    `committer ${author}`,
+    `summary ${summary}`,
+    'filename src/compute/engine.rs',
+    `\t${text}`,
+  ].join('\n');
+}
+
+const raw = [
+  porcelain(A, 120, 'Ada', 1700000000, 'Add chunked compute', 'fn compute_heavy_task(&self) {'),
+  porcelain(A, 121, 'Ada', 1700000000, 'Add chunked compute', '    let chunks = self.split();'),
+  porcelain(B, 122, 'Bob', 1710000000, 'Cap pool size to avoid OOM', '    let pool = Pool::new(MAX_THREADS);'),
+  porcelain(A, 123, 'Ada', 1700000000, 'Add chunked compute', '}'),
+  porcelain(UNCOMMITTED_SHA, 124, 'Not Committed Yet', 1720000000, 'Version of engine.rs from engine.rs', '// TODO'),
+].join('\n') + '\n';
+
+describe('parseLineRange', () => {
+  it('parses ranges and single lines', () => {
+    expect(parseLineRange('src/compute/engine.rs:120-160')).toEqual({ file: 'src/compute/engine.rs', startLine: 120, endLine: 160 });
+    expect(parseLineRange('src/a.ts:7')).toEqual({ file: 'src/a.ts', startLine: 7, endLine: 7 });
+  });
+
+  it('rejects missing or inverted ranges', () => {
+    expect(() => parseLineRange('src/a.ts')).toThrow(/<file>:<start>-<end>/);
+    expect(() => parseLineRange('src/a.ts:20-10')).toThrow(/Invalid line range/);
+  });
+});
+
+describe('parseBlamePorcelain', () => {
+  it('reads each line with its commit', () => {
+    const lines = parseBlamePorcelain(raw);
+    expect(lines).toHaveLength(5);
+    expect(lines[2]).toEqual({
+      sha: B,
+      line: 122,
+      author: 'Bob',
+      authorEmail: 'bob@example.com',
+      date: 1710000000000,
+      summary: 'Cap pool size to avoid OOM',
+      text: '    let pool = Pool::new(MAX_THREADS);',
+    });
+  });
+});
+
+describe('groupBlame', () => {
+  it('groups lines into commits with contiguous ranges, most lines first', () => {
+    const commits = groupBlame(parseBlamePorcelain(raw));
+    expect(commits.map(c => c.sha)).toEqual([A, UNCOMMITTED_SHA, B]);
+    expect(commits[0].ranges).toEqual([[120, 121], [123, 123]]);
+    expect(commits[2].ranges).toEqual([[122, 122]]);
+  });
+});
+
+describe('collectBlameHistory', () => {
+  it('loads messages and diffs, skipping uncommitted lines', async () => {
+    const shown: string[] = [];
+    const git = {
+      getBlame: async () => parseBlamePorcelain(raw),
+      getCommitPatch: async (sha: string, file?: string) => {
+        shown.push(`${sha.slice(0, 1)}:${file}`);
+        return { message: `${sha.slice(0, 1)} message\n\nLonger body\n`, diff: 'x'.repeat(50) };
+      },
+    };
+    const history = await collectBlameHistory(git as any, { file: 'src/compute/engine.rs', startLine: 120, endLine: 124 }, { maxDiffChars: 10 });
+    expect(shown).toEqual(['a:src/compute/engine.rs', 'b:src/compute/engine.rs']);
+    expect(history.commits[0].message).toBe('a message\n\nLonger body');
+    expect(history.commits[0].diff).toBe('xxxxxxxxxx\n... (diff truncated)');
+    expect(history.commits[1].message).toBeUndefined();
+  });
+});
+
+describe('buildBlamePrompt', () => {
+  it('annotates code with commits and includes their messages and diffs', async () => {
+    const git = {
+      getBlame: async () => parseBlamePorcelain(raw),
+      getCommitPatch: async (sha: string) => ({ message: sha === B ? 'Cap pool size to avoid OOM\n\nLarge inputs spawned a thread per chunk.' : 'Add chunked compute', diff: '+let pool' }),
+    };
+    const history = await collectBlameHistory(git as any, { file: 'src/compute/engine.rs', startLine: 120, endLine: 124 });
+    const prompt = buildBlamePrompt(history);
+
+    expect(prompt).toContain('## Code (src/compute/engine.rs:120-124)');
+    expect(prompt).toContain('  122 bbbbbbb |     let pool = Pool::new(MAX_THREADS);');
+    expect(prompt).toContain('  124 uncommitted | // TODO');
+    expect(prompt).toContain('### bbbbbbb - Bob, 2024-03-09 (lines 122)');
+    expect(prompt).toContain('Large inputs spawned a thread per chunk.');
+    expect(prompt).toContain('### aaaaaaa - Ada, 2023-11-14 (lines 120-121, 123)');
+    expect(prompt).toContain('[abc1234]');
+  });
+
+  it('finds the commits an explanation cites', () => {
+    const history = { file: 'f', startLine: 1, endLine: 2, lines: [], commits: groupBlame(parseBlamePorcelain(raw)) };
+    expect(citedCommits('The pool cap came from [bbbbbbb].', history)).toEqual([B]);
+  });
+});
@@ -0,0 +1,188 @@
+/**
+ * Branch Namespace Tests
+ * Tests for per-branch tagging of code chunks and branch-scoped search
+ */
+
+import { describe, it, expect, beforeEach, afterEach } from 'vitest';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import {
+  chunkPointKey,
+  forgetBranches,
+  getCurrentNamespace,
+  loadBranchIndex,
+  recordBranchSync,
+  withBranchScope,
+} from '../../packages/core/src/vector/branches.js';
+import { VectorManager } from '../../packages/core/src/vector/index.js';
+import { matchesFilter, VectorFilter, VectorPoint, VectorStore } from '../../packages/core/src/vector/store.js';
+import type { EmbeddingProvider } from '../../packages/core/src/vector/providers.js';
+
+class MemoryStore implements VectorStore {
+  readonly type = 'lancedb' as const;
+  points = new Map<string, VectorPoint>();
+
+  async connect() {}
+  async close() {}
+  async listCollections() { return ['code_chunks']; }
+  async createCollection() {}
+  async deleteCollection() { this.points.clear(); }
+  async getCollectionStats() { return { vectorSize: 2, pointsCount: this.points.size }; }
+  async upsert(_name: string, points: VectorPoint[]) {
+    for (const p of points) this.points.set(String(p.id), p);
+  }
+  async delete(_name: string, ids: Array<string | number>) {
+    for (const id of ids) this.points.delete(String(id));
+  }
+  async retrieve(_name: string, ids: Array<string | number>) {
+    return ids.map(id => this.points.get(String(id))).filter((p): p is VectorPoint => !!p);
+  }
+  async search(_name: string, _vector: number[], options: { limit: number; filter?: VectorFilter }) {
+    return [...this.points.values()]
+      .filter(p => matchesFilter(p.payload, options.filter))
+      .slice(0, options.limit)
+      .map(p => ({ ...p, score: 1 }));
+  }
+  async scroll(_name: string, options: { limit: number; filter?: VectorFilter }) {
+    return { points: [...this.points.values()].filter(p => matchesFilter(p.payload, options.filter)).slice(0, options.limit) };
+  }
+}
+
+const provider: EmbeddingProvider = {
+  name: 'ollama',
+  model: 'test-embed',
+  dimension: 2,
+  maxBatchSize: 16,
+  embed: async texts => texts.map(() => [1, 0]),
+};
+
+function writeHead(repoRoot: string, head: string) {
+  fs.mkdirSync(path.join(repoRoot, '.git'), { recursive: true });
+  fs.writeFileSync(path.join(repoRoot, '.git', 'HEAD'), `${head}\n`);
+}
+
+describe('branch namespaces', () => {
+  let repoRoot: string;
+
+  beforeEach(() => {
+    repoRoot = fs.mkdtempSync(path.join(os.tmpdir(), 'cv-branches-'));
+  });
+
+  afterEach(() => {
+    fs.rmSync(repoRoot, { recursive: true, force: true });
+  });
+
+  it('should name the namespace after the branch or the detached commit', () => {
+    expect(getCurrentNamespace(repoRoot)).toBeNull();
+
+    writeHead(repoRoot, 'ref: refs/heads/feature/login');
+    expect(getCurrentNamespace(repoRoot)).toBe('feature/login');
+
+    writeHead(repoRoot, '0123456789abcdef0123456789abcdef01234567');
+    expect(getCurrentNamespace(repoRoot)).toBe('@0123456789ab');
+  });
+
+  it('should follow the gitdir file of a worktree', () => {
+    const worktree = path.join(repoRoot, 'wt');
+    const gitDir = path.join(repoRoot, 'main.git', 'worktrees', 'wt');
+    fs.mkdirSync(worktree, { recursive: true });
+    fs.mkdirSync(gitDir, { recursive: true });
+    fs.writeFileSync(path.join(gitDir, 'HEAD'), 'ref: refs/heads/hotfix\n');
+    fs.writeFileSync(path.join(worktree, '.git'), `gitdir: ${gitDir}\n`);
+
+    expect(getCurrentNamespace(worktree)).toBe('hotfix');
+  });
+
+  it('should record synced branches and forget removed ones', async () => {
+    expect(await loadBranchIndex(repoRoot)).toEqual({ branches: {} });
+
+    await recordBranchSync(repoRoot, 'main', 'aaa');
+    await recordBranchSync(repoRoot, 'feature', 'bbb');
+    let index = await loadBranchIndex(repoRoot);
+    expect(index.last).toBe('feature');
+    expect(Object.keys(index.branches)).toEqual(['main', 'feature']);
+    expect(index.branches.main.commit).toBe('aaa');
+
+    await forgetBranches(repoRoot, ['feature']);
+    index = await loadBranchIndex(repoRoot);
+    expect(index.last).toBeUndefined();
+    expect(Object.keys(index.branches)).toEqual(['main']);
+  });
+
+  it('should key points by chunk id and content', () => {
+    expect(chunkPointKey('src/a.ts:foo', 'x')).toBe(chunkPointKey('src/a.ts:foo', 'x'));
+    expect(chunkPointKey('src/a.ts:foo', 'x')).not.toBe(chunkPointKey('src/a.ts:foo', 'y'));
+    expect(chunkPointKey('src/a.ts:foo', 'x')).toMatch(/^src\/a\.ts:foo#[0-9a-f]{16}$/);
+  });
+
+  it('should add the branch to an existing filter', () => {
+    const filter = withBranchScope({ must: [{ key: 'language', match: { value: 'go' } }] }, 'main');
+    expect(matchesFilter({ language: 'go', branches: ['dev', 'main'] }, filter)).toBe(true);
+    expect(matchesFilter({ language: 'go', branches: ['dev'] }, filter)).toBe(false);
+    expect(matchesFilter({ language: 'go' }, filter)).toBe(false);
+  });
+
+  describe('VectorManager', () => {
+    let store: MemoryStore;
+    let vector: VectorManager;
+
+    const chunk = (id: string, file: string, text: string) => ({ id, vector: [1, 0], payload: { file, text } });
+
+    beforeEach(async () => {
+      store = new MemoryStore();
+      vector = new VectorManager({
+        url: 'http://localhost:6333',
+        store,
+        provider,
+        enableCache: false,
+        repoRoot,
+        collections: { codeChunks: 'code_chunks' },
+      });
+      await vector.connect();
+    });
+
+    it('should store a chunk shared by two branches once', async () => {
+      await vector.upsertBranchChunks('code_chunks', [chunk('a:foo', 'a.ts', 'same')], 'main');
+      await vector.upsertBranchChunks('code_chunks', [chunk('a:foo', 'a.ts', 'same'), chunk('b:bar', 'b.ts', 'new')], 'feature');
+
+      const points = [...store.points.values()];
+      expect(points).toHaveLength(2);
+      expect(points.find(p => p.payload._id === 'a:foo')!.payload.branches).toEqual(['main', 'feature']);
+      expect(points.find(p => p.payload._id === 'b:bar')!.payload.branches).toEqual(['feature']);
+    });
+
+    it('should delete points once no branch is left on them', async () => {
+      await vector.upsertBranchChunks('code_chunks', [chunk('a:foo', 'a.ts', 'same')], 'main');
+      await vector.upsertBranchChunks('code_chunks', [chunk('a:foo', 'a.ts', 'same'), chunk('b:bar', 'b.ts', 'new')], 'feature');
+
+      expect(await vector.removeBranch('code_chunks', ['a.ts', 'b.ts'], 'feature')).toBe(1);
+      const points = [...store.points.values()];
+      expect(points).toHaveLength(1);
+      expect(points[0].payload.branches).toEqual(['main']);
+    });
+
+    it('should tag unchanged files from the previous branch', async () => {
+      await vector.upsertBranchChunks('code_chunks', [chunk('a:foo', 'a.ts', 'x'), chunk('b:bar', 'b.ts', 'y')], 'main');
+
+      expect(await vector.addBranch('code_chunks', ['a.ts'], 'feature', 'main')).toBe(1);
+      expect(await vector.addBranch('code_chunks', ['a.ts'], 'feature', 'main')).toBe(0);
+      const tagged = [...store.points.values()].filter(p => (p.payload.branches as string[]).includes('feature'));
+      expect(tagged.map(p => p.payload.file)).toEqual(['a.ts']);
+    });
+
+    it('should scope code search to the checked-out branch once it is synced', async () => {
+      writeHead(repoRoot, 'ref: refs/heads/feature');
+      await vector.upsertBranchChunks('code_chunks', [chunk('a:foo', 'a.ts', 'old')], 'main');
+      await vector.upsertBranchChunks('code_chunks', [chunk('b:bar', 'b.ts', 'new')], 'feature');
+
+      // Never synced on this branch: unfiltered
+      expect(await vector.getSearchNamespace()).toBeNull();
+      expect(await vector.search('code_chunks', 'bar')).toHaveLength(2);
+
+      await recordBranchSync(repoRoot, 'feature');
+      const results = await vector.search('code_chunks', 'bar');
+      expect(results.map(r => r.id)).toEqual(['b:bar']);
+    });
+  });
+});
@@ -0,0 +1,121 @@
+/**
+ * Change Set Tests
+ * Tests for recording AI-applied changes and rolling them back with cv undo
+ */
+
+import { describe, it, expect, beforeEach, afterEach } from 'vitest';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import {
+  recordChangeSet,
+  listChangeSets,
+  findChangeSet,
+  undoChangeSet
+} from '../../packages/core/src/code/changesets.js';
+
+describe('change sets', () => {
+  let repoRoot: string;
+
+  const write = (file: string, content: string) => {
+    fs.mkdirSync(path.dirname(path.join(repoRoot, file)), { recursive: true });
+    fs.writeFileSync(path.join(repoRoot, file), content);
+  };
+  const read = (file: string) =>
+    fs.existsSync(path.join(repoRoot, file)) ? fs.readFileSync(path.join(repoRoot, file), 'utf-8') : null;
+
+  // Apply a change on disk and record it, the way cv do does
+  const applyAndRecord = async (changes: Array<{ file: string; before: string | null; after: string | null }>) => {
+    for (const change of changes) {
+      if (change.after === null) fs.rmSync(path.join(repoRoot, change.file));
+      else write(change.file, change.after);
+    }
+    return recordChangeSet(repoRoot, changes, { command: 'do', description: 'test change', head: 'abc' });
+  };
+
+  beforeEach(() => {
+    repoRoot = fs.mkdtempSync(path.join(os.tmpdir(), 'cv-changesets-'));
+  });
+
+  afterEach(() => {
+    fs.rmSync(repoRoot, { recursive: true, force: true });
+  });
+
+  it('should record before/after hashes under .cv/changesets', async () => {
+    write('a.txt', 'old\n');
+    const cs = await applyAndRecord([
+      { file: 'a.txt', before: 'old\n', after: 'new\n' },
+      { file: 'b.txt', before: null, after: 'created\n' }
+    ]);
+
+    expect(fs.existsSync(path.join(repoRoot, '.cv', 'changesets', `${cs.id}.json`))).toBe(true);
+    expect(cs.files[0].beforeHash).toMatch(/^[0-9a-f]{64}$/);
+    expect(cs.files[1].beforeHash).toBeNull();
+    expect(cs.head).toBe('abc');
+
+    const listed = await listChangeSets(repoRoot);
+    expect(listed.map(c => c.id)).toEqual([cs.id]);
+    expect((await findChangeSet(repoRoot, cs.id.slice(0, 6)))?.id).toBe(cs.id);
+    expect(await findChangeSet(repoRoot, 'zzzz')).toBeNull();
+  });
+
+  it('should revert modifications, creations and deletions', async () => {
+    write('a.txt', 'old\n');
+    write('gone.txt', 'keep me\n');
+    const cs = await applyAndRecord([
+      { file: 'a.txt', before: 'old\n', after: 'new\n' },
+      { file: 'src/b.txt', before: null, after: 'created\n' },
+      { file: 'gone.txt', before: 'keep me\n', after: null }
+    ]);
+
+    const result = await undoChangeSet(repoRoot, cs);
+
+    expect(result.conflicts).toEqual([]);
+    expect(result.reverted.sort()).toEqual(['a.txt', 'gone.txt', 'src/b.txt']);
+    expect(read('a.txt')).toBe('old\n');
+    expect(read('src/b.txt')).toBeNull();
+    expect(read('gone.txt')).toBe('keep me\n');
+    expect(result.changeSet.undoneAt).toBeDefined();
+    expect((await listChangeSets(repoRoot))[0].undoneAt).toBeDefined();
+    await expect(undoChangeSet(repoRoot, result.changeSet)).rejects.toThrow(/already undone/);
+  });
+
+  it('should report conflicts and change nothing when a file was edited since', async () => {
+    write('a.txt', 'a0\n');
+    write('b.txt', 'b0\n');
+    const cs = await applyAndRecord([
+      { file: 'a.txt', before: 'a0\n', after: 'a1\n' },
+      { file: 'b.txt', before: 'b0\n', after: 'b1\n' }
+    ]);
+    write('b.txt', 'b1 plus my edit\n');
+
+    const result = await undoChangeSet(repoRoot, cs);
+
+    expect(result.reverted).toEqual([]);
+    expect(result.conflicts).toEqual([{ file: 'b.txt', reason: 'edited since the change was applied' }]);
+    expect(read('a.txt')).toBe('a1\n');
+    expect(read('b.txt')).toBe('b1 plus my edit\n');
+    expect((await listChangeSets(repoRoot))[0].undoneAt).toBeUndefined();
+  });
+
+  it('should revert over conflicts with force', async () => {
+    write('a.txt', 'a0\n');
+    const cs = await applyAndRecord([{ file: 'a.txt', before: 'a0\n', after: 'a1\n' }]);
+    fs.rmSync(path.join(repoRoot, 'a.txt'));
+
+    const result = await undoChangeSet(repoRoot, cs, { force: true });
+
+    expect(result.conflicts).toEqual([{ file: 'a.txt', reason: 'deleted since the change was applied' }]);
+    expect(result.reverted).toEqual(['a.txt']);
+    expect(read('a.txt')).toBe('a0\n');
+  });
+
+  it('should list newest first and reject ambiguous prefixes', async () => {
+    const first = await applyAndRecord([{ file: 'x.txt', before: null, after: 'x\n' }]);
+    await new Promise(resolve => setTimeout(resolve, 5));
+    const second = await applyAndRecord([{ file: 'y.txt', before: null, after: 'y\n' }]);
+
+    expect((await listChangeSets(repoRoot)).map(c => c.id)).toEqual([second.id, first.id]);
+    await expect(findChangeSet(repoRoot, '')).rejects.toThrow(/ambiguous/);
+  });
+});
@@ -0,0 +1,136 @@
+/**
+ * Chat Session Store Tests
+ */
+
+import { describe, it, expect, beforeEach, afterEach } from 'vitest';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import {
+  ChatSessionStore,
+  ChatSessionConflictError,
+  ChatSessionMessage,
+  toModelContent,
+  planChatHistory,
+  chatHistoryMessages,
+  buildChatSummaryPrompt
+} from '../../packages/core/src/chat/index.js';
+
+describe('ChatSessionStore', () => {
+  let dir: string;
+  let store: ChatSessionStore;
+
+  beforeEach(() => {
+    dir = fs.mkdtempSync(path.join(os.tmpdir(), 'cv-chat-'));
+    store = new ChatSessionStore(dir);
+  });
+
+  afterEach(() => {
+    fs.rmSync(dir, { recursive: true, force: true });
+  });
+
+  it('should continue a session across clients', async () => {
+    const session = await store.create({ model: 'claude-sonnet-4-5' });
+    await store.append(session.id, [
+      { role: 'user', content: 'How does sync work?', context: 'src/sync.ts', client: 'cli' },
+      { role: 'assistant', content: 'It diffs git state.', client: 'cli' }
+    ]);
+    await store.append(session.id, [{ role: 'user', content: 'And deletes?', client: 'vscode' }]);
+
+    const loaded = await store.get(session.id.slice(0, 10));
+    expect(loaded?.revision).toBe(2);
+    expect(loaded?.title).toBe('How does sync work?');
+    expect(loaded?.messages.map(m => m.client)).toEqual(['cli', 'cli', 'vscode']);
+    expect(toModelContent(loaded!.messages[0])).toContain('<codebase_context>');
+
+    const since = await store.messagesSince(session.id, 1);
+    expect(since.map(m => m.content)).toEqual(['And deletes?']);
+  });
+
+  it('should not lose messages under concurrent appends', async () => {
+    const session = await store.create();
+    await Promise.all(
+      Array.from({ length: 8 }, (_, i) =>
+        store.append(session.id, [{ role: 'user', content: `message ${i}`, client: i % 2 ? 'cli' : 'vscode' }])
+      )
+    );
+
+    const loaded = await store.get(session.id);
+    expect(loaded?.messages).toHaveLength(8);
+    expect(new Set(loaded?.messages.map(m => m.revision)).size).toBe(8);
+  });
+
+  it('should reject stale writes when a revision is expected', async () => {
+    const session = await store.create();
+    await store.append(session.id, [{ role: 'user', content: 'first' }]);
+
+    await expect(
+      store.append(session.id, [{ role: 'user', content: 'stale' }], { expectedRevision: 0 })
+    ).rejects.toBeInstanceOf(ChatSessionConflictError);
+  });
+
+  it('should list sessions newest first and resolve latest', async () => {
+    const older = await store.create();
+    await store.append(older.id, [{ role: 'user', content: 'old' }]);
+    await new Promise(resolve => setTimeout(resolve, 5));
+    const newer = await store.create();
+    await store.append(newer.id, [{ role: 'user', content: 'new' }]);
+
+    expect((await store.list()).map(s => s.id)).toEqual([newer.id, older.id]);
+    expect(await store.resolveId('latest')).toBe(newer.id);
+  });
+
+  it('should keep the summary that covers the most revisions', async () => {
+    const session = await store.create();
+    await store.append(session.id, [{ role: 'user', content: 'q' }, { role: 'assistant', content: 'a' }]);
+
+    await store.setSummary(session.id, 'through 2', 2);
+    await store.setSummary(session.id, 'through 1', 1);
+
+    expect((await store.get(session.id))?.summary).toEqual({ text: 'through 2', throughRevision: 2 });
+  });
+});
+
+describe('chat history', () => {
+  const turns = (count: number, size: number): ChatSessionMessage[] =>
+    Array.from({ length: count * 2 }, (_, i) => ({
+      role: i % 2 === 0 ? 'user' as const : 'assistant' as const,
+      content: `${i}:${'x'.repeat(size)}`,
+      client: 'cli',
+      timestamp: i,
+      revision: Math.floor(i / 2) + 1
+    }));
+
+  it('should send short histories verbatim', () => {
+    const messages = turns(3, 10);
+    const plan = planChatHistory(messages);
+
+    expect(plan.summarize).toEqual([]);
+    expect(chatHistoryMessages(plan).map(m => m.content)).toEqual(messages.map(m => m.content));
+  });
+
+  it('should summarize older turns and keep recent ones starting at a question', () => {
+    const messages = turns(10, 100);
+    const plan = planChatHistory(messages, { maxChars: 500, keepRecent: 3 });
+
+    expect(plan.recent.map(m => m.role)).toEqual(['user', 'assistant']);
+    expect(plan.summarize).toHaveLength(18);
+
+    const history = chatHistoryMessages(plan, 'We discussed sync.');
+    expect(history[0]).toEqual({ role: 'user', content: 'Summary of our conversation so far:\n\nWe discussed sync.' });
+    expect(history[1].role).toBe('assistant');
+    expect(history.slice(2).map(m => m.content)).toEqual(plan.recent.map(m => m.content));
+  });
+
+  it('should extend a previous summary without retrieved context', () => {
+    const prompt = buildChatSummaryPrompt([
+      { role: 'user', content: 'Why?', context: 'SECRET CONTEXT', client: 'cli', timestamp: 0, revision: 3 },
+      { role: 'assistant', content: 'Because.', client: 'cli', timestamp: 0, revision: 3 }
+    ], 'Earlier: sync internals.');
+
+    expect(prompt).toContain('## Summary so far\nEarlier: sync internals.');
+    expect(prompt).toContain('Developer: Why?');
+    expect(prompt).toContain('Assistant: Because.');
+    expect(prompt).not.toContain('SECRET CONTEXT');
+  });
+});
@@ -0,0 +1,67 @@
+/**
+ * Chunking Strategy Tests
+ */
+
+import { describe, it, expect } from 'vitest';
+import { SymbolNode } from '../../packages/shared/src/types.js';
+import {
+  createChunkingStrategy,
+  SlidingWindowStrategy
+} from '../../packages/core/src/parser/chunking.js';
+
+function symbol(name: string, kind: SymbolNode['kind'], startLine: number, endLine: number): SymbolNode {
+  return {
+    name,
+    qualifiedName: `src/shapes.ts:${name}`,
+    kind,
+    file: 'src/shapes.ts',
+    startLine,
+    endLine,
+    visibility: 'public',
+    isAsync: false,
+    isStatic: false,
+    complexity: 1,
+    createdAt: 0,
+    updatedAt: 0
+  };
+}
+
+const content = Array.from({ length: 30 }, (_, i) => `line ${i + 1}`).join('\n');
+const symbols = [
+  symbol('Circle', 'class', 1, 12),
+  symbol('area', 'method', 3, 6),
+  symbol('scale', 'method', 8, 11),
+  symbol('describe', 'function', 20, 25)
+];
+const input = { filePath: 'src/shapes.ts', language: 'typescript', content, symbols };
+
+describe('Chunking strategies', () => {
+  it('should chunk every function, method and class at function level', () => {
+    const chunks = createChunkingStrategy({ strategy: 'function' }).chunk(input);
+    expect(chunks.map(c => c.symbolName)).toEqual(['Circle', 'area', 'scale', 'describe']);
+    expect(chunks[1]).toMatchObject({ startLine: 3, endLine: 6, text: 'line 3\nline 4\nline 5\nline 6' });
+  });
+
+  it('should keep methods inside their class at class level', () => {
+    const chunks = createChunkingStrategy({ strategy: 'class' }).chunk(input);
+    expect(chunks.map(c => c.symbolName)).toEqual(['Circle', 'describe']);
+  });
+
+  it('should overlap sliding windows and cover the whole file', () => {
+    const chunks = new SlidingWindowStrategy(12, 4).chunk(input);
+    expect(chunks.map(c => [c.startLine, c.endLine])).toEqual([[1, 12], [9, 20], [17, 28], [25, 30]]);
+    expect(chunks[1].startByte).toBe(Buffer.byteLength(content.split('\n').slice(0, 8).join('\n')) + 1);
+  });
+
+  it('should fall back to windows when a large file has no symbols', () => {
+    const big = Array.from({ length: 500 }, (_, i) => `x${i}`).join('\n');
+    const chunks = createChunkingStrategy({ strategy: 'class', windowLines: 100, overlapLines: 20 })
+      .chunk({ filePath: 'data.sql', language: 'sql', content: big, symbols: [] });
+    expect(chunks.length).toBe(6);
+    expect(chunks[chunks.length - 1].endLine).toBe(500);
+  });
+
+  it('should reject unknown strategies', () => {
+    expect(() => createChunkingStrategy({ strategy: 'paragraph' as any })).toThrow(/Unknown chunking strategy/);
+  });
+});
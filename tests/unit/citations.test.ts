@@ -0,0 +1,68 @@
+/**
+ * Citation Link Tests
+ */
+
+import { describe, it, expect } from 'vitest';
+import * as path from 'path';
+import {
+  formatCitation,
+  parseCitation,
+  sourcesToJson,
+  terminalSupportsHyperlinks,
+  validateCitationFormat
+} from '../../packages/cli/src/utils/citations.js';
+
+const repoRoot = path.resolve('/work/repo');
+const citation = { file: 'src/app.ts', line: 12, endLine: 20 };
+
+describe('Citation links', () => {
+  it('should parse file, line and range references', () => {
+    expect(parseCitation('src/app.ts')).toEqual({ file: 'src/app.ts' });
+    expect(parseCitation('src/app.ts:12')).toEqual({ file: 'src/app.ts', line: 12, endLine: undefined });
+    expect(parseCitation('src/app.ts:12-20')).toEqual(citation);
+  });
+
+  it('should render editor URLs and plain references', () => {
+    const abs = path.resolve(repoRoot, 'src/app.ts');
+    expect(formatCitation(citation, { format: 'plain', repoRoot })).toBe('src/app.ts:12-20');
+    expect(formatCitation(citation, { format: 'vscode', repoRoot }))
+      .toBe(`vscode://file/${abs.replace(/\\/g, '/').replace(/^\//, '')}:12`);
+    expect(formatCitation(citation, { format: 'idea', repoRoot }))
+      .toBe(`idea://open?file=${encodeURIComponent(abs)}&line=12`);
+    expect(formatCitation(citation, { format: 'subl://open?url=file://{abs}&line={line}', repoRoot }))
+      .toBe(`subl://open?url=file://${abs}&line=12`);
+  });
+
+  it('should wrap the label in an OSC 8 hyperlink', () => {
+    const link = formatCitation(citation, { format: 'osc8', repoRoot });
+    expect(link.startsWith('\u001b]8;;file://')).toBe(true);
+    expect(link).toContain('\u001b\\src/app.ts:12-20\u001b]8;;\u001b\\');
+  });
+
+  it('should only auto-enable hyperlinks in capable terminals', () => {
+    expect(terminalSupportsHyperlinks({ TERM_PROGRAM: 'iTerm.app' }, true)).toBe(true);
+    expect(terminalSupportsHyperlinks({ TERM_PROGRAM: 'iTerm.app' }, false)).toBe(false);
+    expect(terminalSupportsHyperlinks({ TERM_PROGRAM: 'Apple_Terminal' }, true)).toBe(false);
+    expect(formatCitation(citation, { format: 'auto', repoRoot, isTTY: false, env: {} })).toBe('src/app.ts:12-20');
+  });
+
+  it('should validate configured formats', () => {
+    expect(validateCitationFormat('vscode')).toBeNull();
+    expect(validateCitationFormat('{path}#L{line}')).toBeNull();
+    expect(validateCitationFormat('emacs')).toMatch(/Unknown citations.format/);
+  });
+});
+
+describe('Sources output', () => {
+  it('should give editors absolute paths and line ranges', () => {
+    const [source] = sourcesToJson([{ file: 'src/app.ts', startLine: 12, endLine: 20, symbolName: 'main', score: 0.8 }], repoRoot);
+    expect(source).toEqual({
+      file: 'src/app.ts',
+      path: path.resolve(repoRoot, 'src/app.ts'),
+      startLine: 12,
+      endLine: 20,
+      symbolName: 'main',
+      score: 0.8
+    });
+  });
+});
@@ -0,0 +1,118 @@
+/**
+ * Code Grep Tests
+ * Tests for cv grep filtering, context lines and fused ranking
+ */
+
+import { describe, it, expect } from 'vitest';
+import { grepChunk, grepResults, matchesGrepFilters } from '../../packages/core/src/context/code-grep.js';
+import type { KeywordHit } from '../../packages/core/src/context/keyword-index.js';
+
+const POOL = [
+  'pub fn drain(queue: &mut Queue) -> usize {',
+  '    let mut done = 0;',
+  '    while let Some(job) = queue.pop() {',
+  '        job.run();',
+  '        done += 1;',
+  '    }',
+  '    thread::sleep(BACKOFF);',
+  '    done',
+  '}',
+].join('\n');
+
+function chunk(id: string, file: string, overrides: Record<string, unknown> = {}): any {
+  return {
+    id,
+    score: 0.8,
+    payload: {
+      file,
+      language: 'rust',
+      symbolName: id,
+      symbolKind: 'function',
+      startLine: 10,
+      endLine: 18,
+      text: POOL,
+      imports: [],
+      lastModified: 0,
+      ...overrides,
+    },
+  };
+}
+
+const keywordHit = (result: any): KeywordHit => ({ doc: { id: result.id, ...result.payload }, score: 3 });
+
+describe('matchesGrepFilters', () => {
+  const payload = chunk('drain', 'src/compute/pool.rs').payload;
+
+  it('filters by language, ignoring case', () => {
+    expect(matchesGrepFilters(payload, { language: 'Rust' })).toBe(true);
+    expect(matchesGrepFilters(payload, { language: 'go' })).toBe(false);
+  });
+
+  it('filters by path globs, with ! to exclude', () => {
+    expect(matchesGrepFilters(payload, { paths: ['src/compute/**'] })).toBe(true);
+    expect(matchesGrepFilters(payload, { paths: ['*.rs'] })).toBe(true);
+    expect(matchesGrepFilters(payload, { paths: ['src/net/**'] })).toBe(false);
+    expect(matchesGrepFilters(payload, { paths: ['src/**', '!src/compute/**'] })).toBe(false);
+  });
+
+  it('filters by symbol kind', () => {
+    expect(matchesGrepFilters(payload, { symbolKinds: ['function', 'method'] })).toBe(true);
+    expect(matchesGrepFilters(payload, { symbolKinds: ['struct'] })).toBe(false);
+    expect(matchesGrepFilters({ ...payload, symbolKind: undefined }, { symbolKinds: ['function'] })).toBe(false);
+  });
+});
+
+describe('grepChunk', () => {
+  it('shows regex matches with context, numbered from the chunk start', () => {
+    const hunks = grepChunk(POOL, 10, 'ignored', /sleep/, 1);
+    expect(hunks).toEqual([[
+      { line: 15, text: '    }', match: false },
+      { line: 16, text: '    thread::sleep(BACKOFF);', match: true },
+      { line: 17, text: '    done', match: false },
+    ]]);
+  });
+
+  it('merges overlapping context and splits distant matches', () => {
+    const hunks = grepChunk(POOL, 1, '', /done/, 0)!;
+    expect(hunks.map(hunk => hunk.map(line => line.line))).toEqual([[2], [5], [8]]);
+
+    const merged = grepChunk(POOL, 1, '', /done/, 2)!;
+    expect(merged).toHaveLength(1);
+    expect(merged[0].map(line => line.line)).toEqual([1, 2, 3, 4, 5, 6, 7, 8, 9]);
+  });
+
+  it('returns null when the regex matches no line', () => {
+    expect(grepChunk(POOL, 1, '', /mutex/, 2)).toBeNull();
+  });
+
+  it('without a regex, shows lines sharing a term with the query, else the first line', () => {
+    expect(grepChunk(POOL, 1, 'pop the queue', undefined, 0)!.flat().map(line => line.line)).toEqual([1, 3]);
+    expect(grepChunk(POOL, 1, 'unrelated words', undefined, 0)).toEqual([[
+      { line: 1, text: 'pub fn drain(queue: &mut Queue) -> usize {', match: true },
+    ]]);
+  });
+});
+
+describe('grepResults', () => {
+  it('ranks chunks found by both retrievers first and applies filters and the limit', () => {
+    const pool = chunk('drain', 'src/compute/pool.rs');
+    const net = chunk('retry', 'src/net/client.rs');
+    const test = chunk('drain_test', 'tests/pool.rs');
+
+    const matches = grepResults('backoff', [net, pool, test], [keywordHit(pool)], { paths: ['src/**'] }, { limit: 5, context: 0 });
+    expect(matches.map(match => match.file)).toEqual(['src/compute/pool.rs', 'src/net/client.rs']);
+    expect(matches[0].score).toBeGreaterThan(matches[1].score);
+
+    expect(grepResults('backoff', [net, pool], [], {}, { limit: 1, context: 0 })).toHaveLength(1);
+  });
+
+  it('drops chunks the regex does not match and summary chunks', () => {
+    const pool = chunk('drain', 'src/compute/pool.rs');
+    const other = chunk('parse', 'src/parse.rs', { text: 'fn parse() {}' });
+    const summary = chunk('bundle', 'dist/bundle.js', { synthetic: true });
+
+    const matches = grepResults('queue', [other, pool, summary], [], { regex: /sleep\(/ }, { limit: 10, context: 0 });
+    expect(matches.map(match => match.symbolName)).toEqual(['drain']);
+    expect(matches[0].hunks).toEqual([[{ line: 16, text: '    thread::sleep(BACKOFF);', match: true }]]);
+  });
+});
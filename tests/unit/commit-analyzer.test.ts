@@ -0,0 +1,57 @@
+/**
+ * Commit Analyzer Tests
+ * Tests for retrieving indexed code related to a staged change
+ */
+
+import { describe, it, expect } from 'vitest';
+import { createCommitAnalyzer, CommitAnalysis } from '../../packages/core/src/ai/commit-analyzer.js';
+
+function analysis(overrides: Partial<CommitAnalysis> = {}): CommitAnalysis {
+  return {
+    filesChanged: ['src/auth.ts'],
+    linesAdded: 1,
+    linesRemoved: 1,
+    symbolsAdded: [],
+    symbolsModified: [{ name: 'login', qualifiedName: 'login', kind: 'function', file: 'src/auth.ts', changeType: 'modified' }],
+    symbolsDeleted: [],
+    callersAffected: [],
+    modulesAffected: ['src'],
+    complexityDelta: 0,
+    suggestedType: 'fix',
+    suggestedScope: 'auth',
+    isBreakingChange: false,
+    breakingChanges: [],
+    rawDiff: 'diff --git a/src/auth.ts b/src/auth.ts\n--- a/src/auth.ts\n+++ b/src/auth.ts\n-  return token;\n+  return refresh(token);\n',
+    ...overrides
+  };
+}
+
+describe('CommitAnalyzer.gatherRelatedContext', () => {
+  const analyzer = createCommitAnalyzer({ repoRoot: process.cwd(), provider: 'none' });
+
+  it('should search the index with the changed symbols, files and lines', async () => {
+    let query = '';
+    const vector: any = {
+      searchCode: async (q: string, limit: number) => {
+        query = q;
+        return [{ id: '1', score: 0.8, payload: { file: 'src/session.ts', startLine: 3, endLine: 9, symbolName: 'refresh', text: 'export function refresh() {}' } }]
+          .slice(0, limit);
+      }
+    };
+
+    const related = await analyzer.gatherRelatedContext(analysis(), vector, 3);
+
+    expect(query).toContain('login');
+    expect(query).toContain('src/auth.ts');
+    expect(query).toContain('return refresh(token);');
+    expect(query).not.toContain('+++');
+    expect(related).toEqual([
+      { file: 'src/session.ts', startLine: 3, endLine: 9, symbolName: 'refresh', text: 'export function refresh() {}' }
+    ]);
+  });
+
+  it('should fall back to no context when the index is unavailable', async () => {
+    const vector: any = { searchCode: async () => { throw new Error('not connected'); } };
+    expect(await analyzer.gatherRelatedContext(analysis(), vector)).toEqual([]);
+  });
+});
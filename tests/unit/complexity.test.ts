@@ -0,0 +1,142 @@
+/**
+ * Complexity Tests
+ * Tests for cyclomatic/cognitive complexity and the complexity report
+ */
+
+import { describe, it, expect } from 'vitest';
+import { cognitiveComplexity, cyclomaticComplexity } from '../../packages/core/src/parser/complexity.js';
+import { complexityReport, formatComplexityCsv } from '../../packages/core/src/graph/complexity.js';
+import type { TreeSitterNode } from '../../packages/core/src/parser/base.js';
+
+let row = 0;
+
+/** Minimal syntax node; `fields` names children for childForFieldName */
+function node(type: string, children: TreeSitterNode[] = [], fields: Record<string, TreeSitterNode> = {}, text = type): TreeSitterNode {
+  const position = { row: row++, column: 0 };
+  return {
+    type,
+    text,
+    startPosition: position,
+    endPosition: position,
+    children,
+    namedChildren: children.filter(child => !/^\W+$|^(and|or)$/.test(child.type)),
+    childForFieldName: (name: string) => fields[name] ?? null,
+  };
+}
+
+function logical(op: string, left: TreeSitterNode, right: TreeSitterNode, type = 'binary_expression'): TreeSitterNode {
+  const operator = node(op);
+  return node(type, [left, operator, right], { operator });
+}
+
+const id = (name = 'x') => node('identifier', [], {}, name);
+const block = (...children: TreeSitterNode[]) => node('statement_block', children);
+
+describe('cyclomaticComplexity', () => {
+  it('is 1 for straight-line code', () => {
+    expect(cyclomaticComplexity(node('function_declaration', [block(id())]))).toBe(1);
+  });
+
+  it('counts branches, loops, cases and boolean operators', () => {
+    // if (a && b) { for (...) {} } switch (x) { case 1: ...; default: ... }
+    const fn = node('function_declaration', [block(
+      node('if_statement', [logical('&&', id('a'), id('b')), block(node('for_statement', [block()]))]),
+      node('switch_statement', [
+        node('switch_case', [], {}, 'case 1: break;'),
+        node('switch_default', [], {}, 'default: break;'),
+      ]),
+    )]);
+    // 1 + if + && + for + case
+    expect(cyclomaticComplexity(fn)).toBe(5);
+  });
+
+  it('ignores default arms in Rust matches', () => {
+    const fn = node('function_item', [node('match_expression', [
+      node('match_arm', [], {}, 'Some(x) => x,'),
+      node('match_arm', [], {}, 'None => 0,'),
+      node('match_arm', [], {}, '_ => 1,'),
+    ])]);
+    expect(cyclomaticComplexity(fn)).toBe(3);
+  });
+});
+
+describe('cognitiveComplexity', () => {
+  it('adds nesting to nested control flow', () => {
+    // for { if { while {} } } => 1 + (1 + 1) + (1 + 2)
+    const fn = node('function_declaration', [block(
+      node('for_statement', [block(node('if_statement', [id(), block(node('while_statement', [block()]))]))]),
+    )]);
+    expect(cognitiveComplexity(fn)).toBe(6);
+  });
+
+  it('counts else if and else without nesting (TypeScript shape)', () => {
+    const inner = node('if_statement', [id(), block(), node('else_clause', [block()])]);
+    const elseIf = node('else_clause', [inner]);
+    const outer = node('if_statement', [id(), block(), elseIf], { alternative: elseIf });
+    // if + else if + else
+    expect(cognitiveComplexity(node('function_declaration', [block(outer)]))).toBe(3);
+  });
+
+  it('counts else if and else without nesting (Go shape)', () => {
+    const elseBlock = block();
+    const inner = node('if_statement', [id(), block(), elseBlock], { alternative: elseBlock });
+    const outer = node('if_statement', [id(), block(), inner], { alternative: inner });
+    expect(cognitiveComplexity(node('function_declaration', [block(outer)]))).toBe(3);
+  });
+
+  it('counts Python elif and else', () => {
+    const elif = node('elif_clause', [id(), block()]);
+    const els = node('else_clause', [block()]);
+    const fn = node('function_definition', [block(node('if_statement', [id(), block(), elif, els], { alternative: elif }))]);
+    expect(cognitiveComplexity(fn)).toBe(3);
+  });
+
+  it('adds one per run of like boolean operators', () => {
+    // a && b && c     => 1
+    const same = logical('&&', logical('&&', id('a'), id('b')), id('c'));
+    expect(cognitiveComplexity(node('function_declaration', [same]))).toBe(1);
+    // a and b or c    => 2
+    const mixed = logical('or', logical('and', id('a'), id('b'), 'boolean_operator'), id('c'), 'boolean_operator');
+    expect(cognitiveComplexity(node('function_definition', [mixed]))).toBe(2);
+  });
+
+  it('deepens nesting inside closures', () => {
+    // () => { if (x) {} } inside a function => if at nesting 1
+    const fn = node('function_declaration', [block(node('arrow_function', [block(node('if_statement', [id(), block()]))]))]);
+    expect(cognitiveComplexity(fn)).toBe(2);
+  });
+});
+
+describe('complexityReport', () => {
+  const rows = [
+    { qualifiedName: 'src/dsp.rs:fourier_transform', name: 'fourier_transform', kind: 'function', file: 'src/dsp.rs', startLine: 10, endLine: 90, complexity: 18, cognitive: 31 },
+    { qualifiedName: 'src/dsp.rs:window', name: 'window', kind: 'function', file: 'src/dsp.rs', startLine: 100, endLine: 110, complexity: 3, cognitive: 2 },
+    { qualifiedName: 'src/io.rs:Reader.read', name: 'read', kind: 'method', file: 'src/io.rs', startLine: 5, endLine: 60, complexity: 22, cognitive: 12 },
+    { qualifiedName: 'lib/old.py:legacy', name: 'legacy', kind: 'function', file: 'lib/old.py', startLine: 1, endLine: 4, complexity: 2, cognitive: null },
+  ];
+  const graph = { query: async () => rows };
+
+  it('ranks by cognitive complexity by default', async () => {
+    const entries = await complexityReport(graph as any);
+    expect(entries.map(e => e.name)).toEqual(['fourier_transform', 'read', 'window', 'legacy']);
+    expect(entries[0]).toMatchObject({ cyclomatic: 18, cognitive: 31, lines: 81 });
+    expect(entries[3].cognitive).toBeNull();
+  });
+
+  it('sorts by other fields, filters and limits', async () => {
+    expect((await complexityReport(graph as any, { sort: 'cyclomatic', top: 2 })).map(e => e.name))
+      .toEqual(['read', 'fourier_transform']);
+    expect((await complexityReport(graph as any, { sort: 'lines', file: 'src/dsp.rs' })).map(e => e.name))
+      .toEqual(['fourier_transform', 'window']);
+    expect((await complexityReport(graph as any, { min: 10 })).map(e => e.name))
+      .toEqual(['fourier_transform', 'read']);
+  });
+
+  it('formats CSV with a header row', async () => {
+    const csv = formatComplexityCsv(await complexityReport(graph as any, { top: 1 }));
+    expect(csv).toBe(
+      'name,kind,file,start_line,end_line,lines,cyclomatic,cognitive\n' +
+      'fourier_transform,function,src/dsp.rs,10,90,81,18,31\n'
+    );
+  });
+});
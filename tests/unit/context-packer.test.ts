@@ -0,0 +1,117 @@
+/**
+ * Context Packer Tests
+ * Tests for token budgets, overlap merging and line-boundary truncation
+ */
+
+import { describe, it, expect } from 'vitest';
+import {
+  packChunks,
+  getContextWindow,
+  resolveContextBudget,
+  encodingForModel,
+  estimateTokenCounter,
+  contextSources,
+  TokenCounter
+} from '../../packages/core/src/ai/context-packer.js';
+
+/** One token per character keeps budgets easy to reason about */
+const charCounter: TokenCounter = { encoding: 'chars', count: text => text.length };
+
+function lines(start: number, end: number): string {
+  return Array.from({ length: end - start + 1 }, (_, i) => `line${start + i}`).join('\n');
+}
+
+function chunk(id: string, file: string, startLine: number, endLine: number, score: number, text = lines(startLine, endLine)) {
+  return {
+    id,
+    score,
+    payload: { id, file, language: 'typescript', startLine, endLine, text, imports: [], lastModified: 0, symbolName: id }
+  };
+}
+
+describe('packChunks', () => {
+  it('should take chunks best score first until the budget is spent', () => {
+    const chunks = [chunk('low', 'a.ts', 1, 2, 0.2), chunk('high', 'b.ts', 1, 2, 0.9), chunk('mid', 'c.ts', 1, 2, 0.5)];
+    const one = packChunks(chunks, { budget: 1000, counter: charCounter });
+    expect(one.chunks.map(c => c.id)).toEqual(['high', 'mid', 'low']);
+
+    const cost = one.tokens / 3;
+    const two = packChunks(chunks, { budget: cost * 2, counter: charCounter, minPartialTokens: 1000 });
+    expect(two.chunks.map(c => c.id)).toEqual(['high', 'mid']);
+    expect(two.dropped).toBe(1);
+    expect(two.tokens).toBeLessThanOrEqual(cost * 2);
+  });
+
+  it('should merge overlapping ranges of the same file into one citation', () => {
+    const packed = packChunks(
+      [chunk('a', 'x.ts', 10, 20, 0.9), chunk('b', 'x.ts', 15, 30, 0.8), chunk('c', 'x.ts', 12, 14, 0.7)],
+      { budget: 10000, counter: charCounter }
+    );
+
+    expect(packed.chunks).toHaveLength(1);
+    expect(packed.merged).toBe(2);
+    const { payload } = packed.chunks[0];
+    expect([payload.startLine, payload.endLine]).toEqual([10, 30]);
+    expect(payload.text).toBe(lines(10, 30));
+    expect(packed.chunks[0].score).toBe(0.9);
+  });
+
+  it('should keep overlapping chunks whose text does not match their lines', () => {
+    const packed = packChunks(
+      [chunk('a', 'x.ts', 1, 10, 0.9), chunk('b', 'x.ts', 5, 15, 0.8, 'summary of a large file')],
+      { budget: 10000, counter: charCounter }
+    );
+    expect(packed.chunks.map(c => c.id)).toEqual(['a', 'b']);
+  });
+
+  it('should cut the last chunk at a line boundary and update its range', () => {
+    const first = chunk('first', 'a.ts', 1, 3, 0.9);
+    const firstCost = packChunks([first], { budget: 1000, counter: charCounter }).tokens;
+    const packed = packChunks([first, chunk('second', 'b.ts', 1, 40, 0.5)], {
+      budget: firstCost + 80,
+      counter: charCounter,
+      minPartialTokens: 20
+    });
+
+    expect(packed.truncated).toBe(1);
+    const second = packed.chunks[1].payload;
+    expect(second.startLine).toBe(1);
+    expect(second.endLine).toBeLessThan(40);
+    expect(second.text).toBe(lines(1, second.endLine));
+    expect(packed.tokens).toBeLessThanOrEqual(firstCost + 80);
+  });
+
+  it('should estimate tokens at about four characters each by default', () => {
+    expect(estimateTokenCounter.count('abcdefgh')).toBe(2);
+  });
+});
+
+describe('contextSources', () => {
+  it('should list each cited range once, keeping the first (best) hit', () => {
+    const chunks = [chunk('x', 'a.ts', 10, 20, 0.9), chunk('y', 'b.ts', 1, 5, 0.5), chunk('z', 'a.ts', 10, 20, 0.4)];
+    expect(contextSources(chunks)).toEqual([
+      { file: 'a.ts', startLine: 10, endLine: 20, symbolName: 'x', score: 0.9 },
+      { file: 'b.ts', startLine: 1, endLine: 5, symbolName: 'y', score: 0.5 }
+    ]);
+  });
+});
+
+describe('model budgets', () => {
+  it('should look up windows by the longest model prefix', () => {
+    expect(getContextWindow('claude-sonnet-4-5')).toBe(200000);
+    expect(getContextWindow('gpt-4o-mini')).toBe(128000);
+    expect(getContextWindow('openai/gpt-4')).toBe(8192);
+    expect(getContextWindow('some-local-model')).toBe(8192);
+  });
+
+  it('should cap the configured budget so the prompt and reply still fit', () => {
+    expect(resolveContextBudget('claude-sonnet-4-5', 4096)).toBe(16000);
+    expect(resolveContextBudget('claude-sonnet-4-5', 4096, 50000)).toBe(50000);
+    expect(resolveContextBudget('gpt-4', 4096, 50000)).toBe(8192 - 4096 - 2000);
+  });
+
+  it('should pick the o200k encoding for newer OpenAI models', () => {
+    expect(encodingForModel('gpt-4o')).toBe('o200k_base');
+    expect(encodingForModel('claude-3-5-sonnet-20241022')).toBe('cl100k_base');
+  });
+});
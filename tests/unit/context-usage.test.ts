@@ -0,0 +1,68 @@
+/**
+ * Context Usage Model Tests
+ */
+
+import { describe, it, expect } from 'vitest';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import {
+  ContextUsageModel,
+  isChunkCited,
+  loadContextUsage,
+  saveContextUsage,
+  MIN_ANSWERS_FOR_WEIGHTING,
+  MIN_USAGE_WEIGHT
+} from '../../packages/core/src/context/usage-model.js';
+
+function chunk(file: string, score: number, symbolName?: string, symbolKind: any = 'function'): any {
+  return {
+    id: `${file}:${symbolName}`,
+    score,
+    payload: { file, symbolName, symbolKind, startLine: 1, endLine: 10, text: '', imports: [], lastModified: 0 }
+  };
+}
+
+describe('ContextUsageModel', () => {
+  it('should detect citations by path, basename or symbol', () => {
+    expect(isChunkCited(chunk('src/sync/engine.ts', 1), 'See src/sync/engine.ts')).toBe(true);
+    expect(isChunkCited(chunk('src/sync/engine.ts', 1), 'engine.ts handles it')).toBe(true);
+    expect(isChunkCited(chunk('a.ts', 1, 'runSync'), 'Call `runSync()` first')).toBe(true);
+    expect(isChunkCited(chunk('b.ts', 1, 'run'), 'rerun everything')).toBe(false);
+  });
+
+  it('should not reweight until enough answers are observed', () => {
+    const model = new ContextUsageModel();
+    model.record([chunk('docs/old.md', 0.9, undefined, undefined)], 'unrelated');
+    expect(model.weight(chunk('docs/old.md', 0.9))).toBe(1);
+  });
+
+  it('should down-weight paths that are retrieved but never cited', () => {
+    const model = new ContextUsageModel();
+    const used = chunk('src/core.ts', 0.7, 'processItem');
+    const noise = chunk('vendor/blob.ts', 0.8, 'generatedThing');
+    for (let i = 0; i < MIN_ANSWERS_FOR_WEIGHTING * 4; i++) {
+      model.record([used, noise], 'processItem does the work');
+    }
+
+    expect(model.weight(noise)).toBeLessThan(1);
+    expect(model.weight(noise)).toBeGreaterThanOrEqual(MIN_USAGE_WEIGHT);
+    expect(model.weight(used)).toBe(1);
+    expect(model.rerank([noise, used], 1)[0].payload.file).toBe('src/core.ts');
+  });
+
+  it('should persist state per repository', async () => {
+    const repo = fs.mkdtempSync(path.join(os.tmpdir(), 'cv-usage-'));
+    try {
+      const model = new ContextUsageModel();
+      model.record([chunk('src/a.ts', 0.5)], 'src/a.ts');
+      await saveContextUsage(repo, model);
+
+      const loaded = await loadContextUsage(repo);
+      expect(loaded.getState().paths['src/a.ts']).toEqual({ retrieved: 1, cited: 1 });
+      expect(loaded.getState().answers).toBe(1);
+    } finally {
+      fs.rmSync(repo, { recursive: true, force: true });
+    }
+  });
+});
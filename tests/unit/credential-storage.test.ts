@@ -0,0 +1,105 @@
+/**
+ * Credential Storage Tests
+ * Tests for the encrypted-file fallback and migration off the plaintext file
+ */
+
+import { describe, it, expect, beforeEach, afterEach } from 'vitest';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { CredentialManager } from '../../packages/credentials/src/manager.js';
+import { getLocalMasterPassword } from '../../packages/credentials/src/storage/encrypted.js';
+import { AnthropicAPICredential, CredentialType } from '../../packages/credentials/src/types/index.js';
+
+describe('credential storage', () => {
+  let dataDir: string;
+  let keyPath: string;
+  const env = { store: process.env.CV_CREDENTIAL_STORE, passphrase: process.env.CV_CREDENTIALS_PASSPHRASE };
+
+  beforeEach(() => {
+    dataDir = fs.mkdtempSync(path.join(os.tmpdir(), 'cv-credentials-'));
+    keyPath = path.join(fs.mkdtempSync(path.join(os.tmpdir(), 'cv-credentials-state-')), 'cv-git', 'credentials.key');
+    process.env.CV_CREDENTIAL_STORE = 'encrypted-file';
+    delete process.env.CV_CREDENTIALS_PASSPHRASE;
+  });
+
+  afterEach(() => {
+    fs.rmSync(dataDir, { recursive: true, force: true });
+    fs.rmSync(path.dirname(path.dirname(keyPath)), { recursive: true, force: true });
+    for (const [key, value] of [['CV_CREDENTIAL_STORE', env.store], ['CV_CREDENTIALS_PASSPHRASE', env.passphrase]] as const) {
+      if (value === undefined) delete process.env[key];
+      else process.env[key] = value;
+    }
+  });
+
+  it('should generate the file key once, owner-readable only', async () => {
+    const key = await getLocalMasterPassword(keyPath);
+
+    expect(key).toMatch(/^[0-9a-f]{64}$/);
+    expect(await getLocalMasterPassword(keyPath)).toBe(key);
+    if (process.platform !== 'win32') {
+      expect(fs.statSync(keyPath).mode & 0o777).toBe(0o600);
+    }
+
+    process.env.CV_CREDENTIALS_PASSPHRASE = 'correct horse';
+    expect(await getLocalMasterPassword(keyPath)).toBe('correct horse');
+  });
+
+  it('should keep API keys out of the files on disk', async () => {
+    const credentials = new CredentialManager({ dataDir, keyPath });
+    await credentials.store<AnthropicAPICredential>({
+      type: CredentialType.ANTHROPIC_API,
+      name: 'default',
+      apiKey: 'sk-ant-secret-value',
+    });
+
+    const info = await credentials.getStorageInfo();
+    expect(info.backend).toBe('encrypted-file');
+    expect(info.location).toBe(path.join(dataDir, 'credentials.enc'));
+
+    for (const file of fs.readdirSync(dataDir)) {
+      expect(fs.readFileSync(path.join(dataDir, file), 'utf8')).not.toContain('sk-ant-secret-value');
+    }
+    expect(await new CredentialManager({ dataDir, keyPath }).getAnthropicKey()).toBe('sk-ant-secret-value');
+  });
+
+  it('should keep the generated key outside the data directory', async () => {
+    const credentials = new CredentialManager({ dataDir, keyPath });
+    await credentials.store<AnthropicAPICredential>({
+      type: CredentialType.ANTHROPIC_API,
+      name: 'default',
+      apiKey: 'sk-ant-secret-value',
+    });
+
+    expect(fs.existsSync(keyPath)).toBe(true);
+    expect(fs.readdirSync(dataDir)).not.toContain('credentials.key');
+  });
+
+  it('should move a key left in the data directory by earlier versions', async () => {
+    const legacyKeyPath = path.join(dataDir, 'credentials.key');
+    fs.writeFileSync(legacyKeyPath, 'a'.repeat(64));
+
+    expect(await getLocalMasterPassword(keyPath, legacyKeyPath)).toBe('a'.repeat(64));
+    expect(fs.readFileSync(keyPath, 'utf8')).toBe('a'.repeat(64));
+    expect(fs.existsSync(legacyKeyPath)).toBe(false);
+    if (process.platform !== 'win32') {
+      expect(fs.statSync(keyPath).mode & 0o777).toBe(0o600);
+    }
+  });
+
+  it('should move credentials out of the legacy plaintext file', async () => {
+    const credential = { type: 'anthropic_api', name: 'default', apiKey: 'sk-ant-legacy', createdAt: new Date() };
+    fs.writeFileSync(path.join(dataDir, 'credentials.json'), JSON.stringify({ 'anthropic_api:default': JSON.stringify(credential) }));
+    fs.writeFileSync(path.join(dataDir, 'credentials-metadata.json'), JSON.stringify([{ id: '1', type: 'anthropic_api', name: 'default', createdAt: credential.createdAt }]));
+
+    const credentials = new CredentialManager({ dataDir, keyPath });
+    expect((await credentials.getStorageInfo()).migratedFromPlainFile).toBe(1);
+    expect(fs.existsSync(path.join(dataDir, 'credentials.json'))).toBe(false);
+    expect(await credentials.getAnthropicKey()).toBe('sk-ant-legacy');
+  });
+
+  it('should reject an unknown CV_CREDENTIAL_STORE', () => {
+    process.env.CV_CREDENTIAL_STORE = 'vault';
+    expect(() => new CredentialManager({ dataDir, keyPath })).toThrow('Unknown CV_CREDENTIAL_STORE "vault"');
+  });
+});
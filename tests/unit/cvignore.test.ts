@@ -0,0 +1,128 @@
+/**
+ * .cvignore Tests
+ * Tests for index exclusion rules and the skipped-file audit
+ */
+
+import { describe, it, expect } from 'vitest';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import {
+  BUILTIN_IGNORE_RULES,
+  CvIgnore,
+  loadCvIgnore,
+  parseIgnoreRules,
+  selectSyncFiles,
+} from '../../packages/core/src/sync/cvignore.js';
+
+const ignore = (content: string) => new CvIgnore(parseIgnoreRules(content));
+
+describe('parseIgnoreRules', () => {
+  it('should skip comments and blank lines and keep line numbers', () => {
+    const rules = parseIgnoreRules('# generated\n\nsrc/gen/\n!src/gen/keep.ts\n\\#literal\n');
+    expect(rules.map(r => [r.line, r.pattern, r.negated, r.directoryOnly])).toEqual([
+      [3, 'src/gen/', false, true],
+      [4, '!src/gen/keep.ts', true, false],
+      [5, '\\#literal', false, false],
+    ]);
+  });
+});
+
+describe('CvIgnore', () => {
+  it('should match names at any depth unless the rule has a slash', () => {
+    const rules = ignore('*.snap\nfixtures/\n/scripts/\ndocs/api/*.ts');
+    expect(rules.ignores('src/__snapshots__/a.test.ts.snap')).toBe(true);
+    expect(rules.ignores('packages/core/fixtures/repo/a.ts')).toBe(true);
+    expect(rules.ignores('scripts/build.ts')).toBe(true);
+    expect(rules.ignores('packages/core/scripts/build.ts')).toBe(false);
+    expect(rules.ignores('docs/api/index.ts')).toBe(true);
+    expect(rules.ignores('docs/api/v2/index.ts')).toBe(false);
+  });
+
+  it('should only match directories with a trailing slash', () => {
+    const rules = ignore('generated/');
+    expect(rules.ignores('src/generated/client.ts')).toBe(true);
+    expect(rules.ignores('src/generated')).toBe(false);
+  });
+
+  it('should support ** and character classes', () => {
+    const rules = ignore('src/**/gen/*.ts\n**/migrations\nlog[0-9].txt');
+    expect(rules.ignores('src/gen/a.ts')).toBe(true);
+    expect(rules.ignores('src/a/b/gen/a.ts')).toBe(true);
+    expect(rules.ignores('lib/gen/a.ts')).toBe(false);
+    expect(rules.ignores('db/migrations/001.py')).toBe(true);
+    expect(rules.ignores('log1.txt')).toBe(true);
+    expect(rules.ignores('logs.txt')).toBe(false);
+  });
+
+  it('should let the last matching rule win', () => {
+    const rules = ignore('vendor/\n!vendor/our-fork/\nvendor/our-fork/tests/');
+    expect(rules.ignores('vendor/lodash/index.js')).toBe(true);
+    expect(rules.ignores('vendor/our-fork/src/index.ts')).toBe(false);
+    expect(rules.ignores('vendor/our-fork/tests/a.ts')).toBe(true);
+  });
+
+  it('should skip dependencies, build output, generated code and lockfiles by default', () => {
+    const rules = new CvIgnore();
+    for (const file of [
+      'node_modules/react/index.js',
+      'packages/web/node_modules/x/index.js',
+      'packages/core/dist/index.js',
+      'target/debug/build.rs',
+      'public/app.min.js',
+      'api/user_pb2.py',
+      'yarn.lock',
+      'packages/web/package-lock.json',
+      'Cargo.lock',
+    ]) {
+      expect(rules.match(file)?.source, file).toBe('default');
+    }
+    expect(rules.ignores('src/build/index.ts')).toBe(false);
+    expect(rules.ignores('src/env/index.ts')).toBe(false);
+    expect(BUILTIN_IGNORE_RULES).toContain('node_modules/');
+  });
+});
+
+describe('selectSyncFiles', () => {
+  it('should report why each file was skipped', () => {
+    const rules = new CvIgnore([
+      ...parseIgnoreRules(BUILTIN_IGNORE_RULES, 'default'),
+      ...parseIgnoreRules('# team rules\nsrc/legacy/\n', 'cvignore'),
+    ]);
+
+    const selection = selectSyncFiles([
+      'src/index.ts',
+      'src/legacy/old.ts',
+      'node_modules/x/index.js',
+      'fixtures/sample.ts',
+      'assets/logo.png',
+      'README.txt',
+      'main.go',
+    ], rules, { excludePatterns: ['fixtures/**'], includeLanguages: ['typescript'] });
+
+    expect(selection.files).toEqual(['src/index.ts']);
+    expect(selection.skipped).toEqual([
+      { file: 'src/legacy/old.ts', reason: 'cvignore', detail: 'src/legacy/', line: 2 },
+      { file: 'node_modules/x/index.js', reason: 'default', detail: 'node_modules/' },
+      { file: 'fixtures/sample.ts', reason: 'exclude-pattern', detail: 'fixtures/**' },
+      { file: 'assets/logo.png', reason: 'binary', detail: '.png' },
+      { file: 'README.txt', reason: 'language', detail: '.txt' },
+      { file: 'main.go', reason: 'language', detail: 'go' },
+    ]);
+  });
+
+  it('should read .cvignore from the repository root', async () => {
+    const repoRoot = fs.mkdtempSync(path.join(os.tmpdir(), 'cv-cvignore-'));
+    try {
+      expect((await loadCvIgnore(repoRoot)).ignores('src/gen/a.ts')).toBe(false);
+
+      fs.writeFileSync(path.join(repoRoot, '.cvignore'), 'gen/\n!vendor/\n');
+      const rules = await loadCvIgnore(repoRoot);
+      expect(rules.ignores('src/gen/a.ts')).toBe(true);
+      expect(rules.ignores('vendor/lib/a.go')).toBe(false);
+      expect(rules.ignores('node_modules/a.js')).toBe(true);
+    } finally {
+      fs.rmSync(repoRoot, { recursive: true, force: true });
+    }
+  });
+});
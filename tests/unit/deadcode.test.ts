@@ -0,0 +1,155 @@
+/**
+ * Dead Code Tests
+ * Tests for unreferenced symbol detection and the deadcode allowlist
+ */
+
+import { describe, it, expect } from 'vitest';
+import { detectDeadCode } from '../../packages/core/src/graph/deadcode.js';
+
+interface Row {
+  qualifiedName: string;
+  kind: string;
+  startLine: number;
+  endLine: number;
+  visibility?: string;
+}
+
+function sym(qualifiedName: string, kind: string, startLine: number, endLine: number, visibility = 'public'): Row {
+  return { qualifiedName, kind, startLine, endLine, visibility };
+}
+
+const sources: Record<string, string> = {
+  'src/lib.rs': [
+    'pub fn compute(x: u32) -> u32 {',   // 1
+    '    helper(x)',                      // 2
+    '}',                                  // 3
+    'fn helper(x: u32) -> u32 { x }',     // 4
+    'fn legacy_computation() -> u32 {',   // 5
+    '    42',                             // 6
+    '}',                                  // 7
+    'pub fn unused_api() {}',             // 8
+    '#[test]',                            // 9
+    'fn it_works() {}',                   // 10
+    'fn main() { compute(1); }',          // 11
+  ].join('\n'),
+  'src/shapes.rs': [
+    'pub struct Circle;',                 // 1
+    'impl Shape for Circle {',            // 2
+    '    fn area(&self) -> f64 { 0.0 }',  // 3
+    '}',                                  // 4
+  ].join('\n'),
+  'app/util.py': [
+    'def _private_helper():',             // 1
+    '    pass',                           // 2
+    'def public_helper():',               // 3
+    '    pass',                           // 4
+    'def used_as_callback():',            // 5
+    '    pass',                           // 6
+    'register(used_as_callback)',         // 7
+    '@app.route("/")',                    // 8
+    'def index():',                       // 9
+    '    pass',                           // 10
+  ].join('\n'),
+  'web/format.ts': [
+    'export function formatDate() {}',    // 1
+    'function localOnly() {}',            // 2
+    'function listedExport() {}',         // 3
+    'export { listedExport };',           // 4
+  ].join('\n'),
+  'tests/lib_test.rs': 'fn fixture() {}',
+};
+
+const symbols: Row[] = [
+  sym('src/lib.rs:compute', 'function', 1, 3),
+  sym('src/lib.rs:helper', 'function', 4, 4, 'private'),
+  sym('src/lib.rs:legacy_computation', 'function', 5, 7, 'private'),
+  sym('src/lib.rs:unused_api', 'function', 8, 8),
+  sym('src/lib.rs:it_works', 'function', 10, 10, 'private'),
+  sym('src/lib.rs:main', 'function', 11, 11, 'private'),
+  sym('src/shapes.rs:Circle', 'struct', 1, 1),
+  sym('src/shapes.rs:Circle.area', 'method', 3, 3, 'private'),
+  sym('app/util.py:_private_helper', 'function', 1, 2, 'private'),
+  sym('app/util.py:public_helper', 'function', 3, 4),
+  sym('app/util.py:used_as_callback', 'function', 5, 6),
+  sym('app/util.py:index', 'function', 9, 10),
+  sym('web/format.ts:formatDate', 'function', 1, 1),
+  sym('web/format.ts:localOnly', 'function', 2, 2),
+  sym('web/format.ts:listedExport', 'function', 3, 3),
+  sym('tests/lib_test.rs:fixture', 'function', 1, 1, 'private'),
+];
+
+const calls: Array<[string, string]> = [
+  ['src/lib.rs:main', 'src/lib.rs:compute'],
+  ['src/lib.rs:compute', 'src/lib.rs:helper'],
+];
+
+const inherits: string[] = ['src/shapes.rs:Circle'];
+
+/** Answers the queries detectDeadCode sends, from the tables above */
+const graph = {
+  async query(cypher: string) {
+    if (cypher.includes('CALLS')) return calls.map(([, target]) => ({ id: target }));
+    if (cypher.includes('INHERITS')) return inherits.map(id => ({ id }));
+    if (cypher.includes('(f:File)')) return Object.keys(sources).map(path => ({ path }));
+    return symbols.map(s => ({ ...s, name: s.qualifiedName.split(':')[1].split('.').pop(), file: s.qualifiedName.split(':')[0] }));
+  },
+};
+
+const readFile = async (file: string) => sources[file] ?? null;
+
+async function deadNames(allow?: string[]) {
+  const report = await detectDeadCode(graph as any, { readFile, allow });
+  return Object.fromEntries(report.dead.map(s => [s.name, s.confidence]));
+}
+
+describe('detectDeadCode', () => {
+  it('flags private unreferenced items with high confidence', async () => {
+    const dead = await deadNames();
+    expect(dead.legacy_computation).toBe('high');
+    expect(dead._private_helper).toBe('high');
+    expect(dead.localOnly).toBe('high');
+  });
+
+  it('treats public and exported items as medium confidence', async () => {
+    const dead = await deadNames();
+    expect(dead.unused_api).toBe('medium');
+    expect(dead.public_helper).toBe('medium');
+    expect(dead.formatDate).toBe('medium');
+    expect(dead.listedExport).toBe('medium');
+  });
+
+  it('keeps called and referenced symbols', async () => {
+    const dead = await deadNames();
+    expect(dead).not.toHaveProperty('helper');
+    expect(dead).not.toHaveProperty('compute');
+    expect(dead).not.toHaveProperty('used_as_callback');
+  });
+
+  it('skips entry points and tests', async () => {
+    const dead = await deadNames();
+    expect(dead).not.toHaveProperty('main');
+    expect(dead).not.toHaveProperty('it_works');
+    expect(dead).not.toHaveProperty('fixture');
+  });
+
+  it('downgrades trait impls and decorated items to low confidence', async () => {
+    const dead = await deadNames();
+    expect(dead.area).toBe('low');
+    expect(dead.index).toBe('low');
+  });
+
+  it('honours the allowlist by name and file glob', async () => {
+    const report = await detectDeadCode(graph as any, { readFile, allow: ['legacy_*', 'web/**'] });
+    const names = report.dead.map(s => s.name);
+    expect(names).not.toContain('legacy_computation');
+    expect(names).not.toContain('formatDate');
+    expect(names).not.toContain('localOnly');
+    expect(report.allowed).toBe(4);
+  });
+
+  it('scopes to a path and orders by confidence', async () => {
+    const report = await detectDeadCode(graph as any, { readFile, file: 'src/' });
+    expect(report.dead.every(s => s.file.startsWith('src/'))).toBe(true);
+    expect(report.dead.map(s => s.confidence)).toEqual(['high', 'medium', 'low']);
+  });
+});
@@ -116,6 +116,25 @@ describe('DeltaSyncManager', () => {
       expect(delta.added).toHaveLength(1);
       expect(delta.deleted).toHaveLength(1);
     });
+
+    it('should treat unread paths as unchanged without flagging them deleted', async () => {
+      await manager.markSynced(new Map([
+        ['src/keep.ts', 'unchanged'],
+        ['src/modify.ts', 'original'],
+        ['src/delete.ts', 'to be deleted']
+      ]), 'code');
+
+      const delta = await manager.computeDelta(
+        new Map([['src/modify.ts', 'modified']]),
+        'code',
+        ['src/keep.ts', 'src/new.ts']
+      );
+
+      expect(delta.unchanged).toEqual(['src/keep.ts']);
+      expect(delta.modified).toEqual(['src/modify.ts']);
+      expect(delta.added).toEqual(['src/new.ts']);
+      expect(delta.deleted).toEqual(['src/delete.ts']);
+    });
   });
 
   describe('file types', () => {
@@ -187,6 +206,17 @@ describe('DeltaSyncManager', () => {
 
       await newManager.close();
     });
+
+    it('should persist files left dirty at the last commit', async () => {
+      expect(await manager.getDirtyFiles()).toBeNull();
+      await manager.setLastCommit('abc123', ['src/wip.ts']);
+      await manager.close();
+
+      const newManager = createDeltaSyncManager(tempDir);
+      expect(await newManager.getDirtyFiles()).toEqual(['src/wip.ts']);
+
+      await newManager.close();
+    });
   });
 
   describe('markDeleted', () => {
@@ -0,0 +1,172 @@
+/**
+ * Diff Summary Tests
+ * Tests for range parsing, change clustering, API change detection and risky files
+ */
+
+import { describe, it, expect } from 'vitest';
+import {
+  areaOf,
+  behaviorFiles,
+  buildClusterSummaryPrompt,
+  clusterChanges,
+  detectApiChanges,
+  formatDiffSummaryMarkdown,
+  parseCommitRange,
+  rankRiskyFiles,
+} from '../../packages/core/src/ai/diff-summary.js';
+import type { ChurnCommit } from '../../packages/core/src/git/hotspots.js';
+import type { RepoPackage } from '../../packages/core/src/sync/packages.js';
+
+const packages: RepoPackage[] = [
+  { name: '@acme/core', dir: 'packages/core', kind: 'npm' },
+  { name: '@acme/cli', dir: 'packages/cli', kind: 'npm' },
+];
+
+function fileDiff(file: string, lines: string[]): string {
+  return [
+    `diff --git a/${file} b/${file}`,
+    `--- a/${file}`,
+    `+++ b/${file}`,
+    '@@ -1,3 +1,3 @@',
+    ...lines,
+  ].join('\n');
+}
+
+const diff = [
+  fileDiff('packages/core/src/auth/session.ts', [
+    '-export function login(user: string) {',
+    '+export function login(user: string, token: string) {',
+    '+export const SESSION_TTL = 60;',
+    '-export function logout() {',
+    '   return true;',
+  ]),
+  fileDiff('packages/core/migrations/001.sql', ['+ALTER TABLE users ADD COLUMN token TEXT;']),
+  fileDiff('packages/cli/src/commands/run.ts', ['-  run(1);', '+  run(2);']),
+  fileDiff('README.md', ['+More docs']),
+].join('\n');
+
+const commits: ChurnCommit[] = [
+  {
+    sha: 'a'.repeat(40), author: 'Ada', authorEmail: 'ada@example.com', date: 0, subject: 'Require tokens for login',
+    files: [{ path: 'packages/core/src/auth/session.ts', added: 2, deleted: 2 }, { path: 'packages/core/migrations/001.sql', added: 1, deleted: 0 }],
+  },
+  {
+    sha: 'b'.repeat(40), author: 'Bob', authorEmail: 'bob@example.com', date: 0, subject: 'Tweak run',
+    files: [{ path: 'packages/cli/src/commands/run.ts', added: 1, deleted: 1 }],
+  },
+];
+
+describe('parseCommitRange', () => {
+  it('diffs from the merge base', () => {
+    expect(parseCommitRange('main..feature')).toEqual({ log: 'main..feature', diff: 'main...feature' });
+    expect(parseCommitRange('main...feature')).toEqual({ log: 'main..feature', diff: 'main...feature' });
+    expect(parseCommitRange('main')).toEqual({ log: 'main..HEAD', diff: 'main...HEAD' });
+    expect(parseCommitRange('main..')).toEqual({ log: 'main..HEAD', diff: 'main...HEAD' });
+  });
+});
+
+describe('areaOf', () => {
+  it('uses the package and its first directory, skipping src', () => {
+    expect(areaOf('packages/core/src/auth/session.ts', packages)).toBe('@acme/core/auth');
+    expect(areaOf('packages/core/index.ts', packages)).toBe('@acme/core');
+    expect(areaOf('docs/guide/setup/intro.md', packages)).toBe('docs/guide');
+    expect(areaOf('README.md', packages)).toBe('(root)');
+  });
+});
+
+describe('clusterChanges', () => {
+  it('groups files by area with line counts and commit subjects', () => {
+    const clusters = clusterChanges(diff, commits, packages);
+    expect(clusters.map(c => c.area)).toEqual(['@acme/core/auth', '@acme/cli/commands', '(root)', '@acme/core/migrations']);
+    expect(clusters[0]).toMatchObject({ insertions: 2, deletions: 2, commits: ['Require tokens for login'] });
+    expect(clusters[1].commits).toEqual(['Tweak run']);
+  });
+
+  it('folds the smallest areas into "other"', () => {
+    const clusters = clusterChanges(diff, commits, packages, { maxClusters: 2 });
+    expect(clusters.map(c => c.area)).toEqual(['@acme/core/auth', 'other']);
+    expect(clusters[1].files.map(f => f.file)).toEqual(['packages/cli/src/commands/run.ts', 'README.md', 'packages/core/migrations/001.sql']);
+    expect(clusters[1].commits).toEqual(['Tweak run', 'Require tokens for login']);
+  });
+});
+
+describe('detectApiChanges', () => {
+  it('reports added, removed and changed exports', () => {
+    expect(detectApiChanges(diff)).toEqual([
+      {
+        file: 'packages/core/src/auth/session.ts',
+        kind: 'changed',
+        signature: 'export function login(user: string, token: string)',
+        previous: 'export function login(user: string)',
+      },
+      { file: 'packages/core/src/auth/session.ts', kind: 'added', signature: 'export const SESSION_TTL = 60;' },
+      { file: 'packages/core/src/auth/session.ts', kind: 'removed', signature: 'export function logout()' },
+    ]);
+  });
+
+  it('handles Rust, Go and Java declarations and skips tests', () => {
+    const other = [
+      fileDiff('src/lib.rs', ['+pub(crate) fn parse(input: &str) -> Ast {']),
+      fileDiff('pkg/api.go', ['+func (s *Server) Serve(addr string) error {', '+func helper() {}']),
+      fileDiff('src/Main.java', ['+    public static void main(String[] args) {']),
+      fileDiff('tests/api.test.ts', ['+export function fixture() {']),
+    ].join('\n');
+    expect(detectApiChanges(other).map(c => c.signature)).toEqual([
+      'pub(crate) fn parse(input: &str) -> Ast',
+      'func (s *Server) Serve(addr string) error',
+      'public static void main(String[] args)',
+    ]);
+  });
+});
+
+describe('rankRiskyFiles', () => {
+  it('ranks sensitive, API-changing files without tests first', () => {
+    const clusters = clusterChanges(diff, commits, packages);
+    const risky = rankRiskyFiles(clusters, detectApiChanges(diff));
+    expect(risky.map(f => f.file)).toEqual(['packages/core/src/auth/session.ts', 'packages/core/migrations/001.sql']);
+    expect(risky[0].reasons).toEqual([
+      'changes or removes 2 exported declaration(s)',
+      'security- or data-sensitive path',
+      'no test changes in this area',
+    ]);
+    expect(behaviorFiles(clusters)).toEqual(['packages/core/migrations/001.sql']);
+  });
+
+  it('does not flag the missing-tests reason when the area has tests', () => {
+    const withTests = diff + '\n' + fileDiff('packages/core/src/auth/session.test.ts', ['+it()']);
+    const clusters = clusterChanges(withTests, [], packages);
+    const risky = rankRiskyFiles(clusters, detectApiChanges(withTests));
+    expect(risky[0].reasons).not.toContain('no test changes in this area');
+  });
+});
+
+describe('prompts and markdown', () => {
+  it('includes commits and diff in the cluster prompt', () => {
+    const [cluster] = clusterChanges(diff, commits, packages);
+    const prompt = buildClusterSummaryPrompt(cluster);
+    expect(prompt).toContain('"@acme/core/auth"');
+    expect(prompt).toContain('- Require tokens for login');
+    expect(prompt).toContain('+export const SESSION_TTL = 60;');
+  });
+
+  it('formats a PR description', () => {
+    const clusters = clusterChanges(diff, commits, packages);
+    clusters[0].summary = 'Login now requires a token.';
+    const apiChanges = detectApiChanges(diff);
+    const markdown = formatDiffSummaryMarkdown({
+      range: 'main..feature',
+      commits: 2,
+      overview: 'Adds token-based login.',
+      clusters,
+      apiChanges,
+      behaviorFiles: behaviorFiles(clusters),
+      risky: rankRiskyFiles(clusters, apiChanges),
+    });
+    expect(markdown).toContain('## Summary\n\nAdds token-based login.');
+    expect(markdown).toContain('_2 commits, 4 files changed (`main..feature`)_');
+    expect(markdown).toContain('### @acme/core/auth (1 file, +2 −2)\n\nLogin now requires a token.');
+    expect(markdown).toContain('- **changed** `export function login(user: string, token: string)` in `packages/core/src/auth/session.ts` (was `export function login(user: string)`)');
+    expect(markdown).toContain('- `packages/core/migrations/001.sql` changed');
+    expect(markdown).toContain('| `packages/core/src/auth/session.ts` | +2 −2 |');
+  });
+});
@@ -0,0 +1,138 @@
+/**
+ * Doc Generation Tests
+ * Tests for finding undocumented public items and writing doc comments in each language's style
+ */
+
+import { describe, it, expect } from 'vitest';
+import {
+  applyDocComments,
+  buildDocgenPrompt,
+  findDocComment,
+  findDocTargets,
+  groupDocTargets,
+  parseDocgenResponse,
+  renderDocComment
+} from '../../packages/core/src/ai/docgen.js';
+
+const sources: Record<string, string> = {
+  'src/engine.rs': [
+    '/// Multiplies two matrices.',                     // 1
+    'pub fn multiply(a: &M, b: &M) -> M { todo!() }',  // 2
+    'pub fn transpose(a: &M) -> M { todo!() }',        // 3
+    'fn helper() {}',                                  // 4
+    '#[derive(Debug)]',                                // 5
+    'pub struct Matrix;',                              // 6
+  ].join('\n'),
+  'src/pool.ts': [
+    'export function drain() {}',                      // 1
+    'function local() {}',                             // 2
+  ].join('\n'),
+  'src/generated/api.ts': 'export function call() {}',
+  'tests/engine.rs': 'pub fn fixture() {}',
+};
+
+const rows = [
+  { qualifiedName: 'src/engine.rs:multiply', name: 'multiply', kind: 'function', file: 'src/engine.rs', startLine: 2, endLine: 2, visibility: 'public' },
+  { qualifiedName: 'src/engine.rs:transpose', name: 'transpose', kind: 'function', file: 'src/engine.rs', startLine: 3, endLine: 3, visibility: 'public' },
+  { qualifiedName: 'src/engine.rs:helper', name: 'helper', kind: 'function', file: 'src/engine.rs', startLine: 4, endLine: 4, visibility: 'private' },
+  { qualifiedName: 'src/engine.rs:Matrix', name: 'Matrix', kind: 'struct', file: 'src/engine.rs', startLine: 6, endLine: 6, visibility: 'public' },
+  { qualifiedName: 'src/pool.ts:drain', name: 'drain', kind: 'function', file: 'src/pool.ts', startLine: 1, endLine: 1, visibility: 'public' },
+  { qualifiedName: 'src/pool.ts:local', name: 'local', kind: 'function', file: 'src/pool.ts', startLine: 2, endLine: 2, visibility: 'public' },
+  { qualifiedName: 'src/generated/api.ts:call', name: 'call', kind: 'function', file: 'src/generated/api.ts', startLine: 1, endLine: 1, visibility: 'public' },
+  { qualifiedName: 'tests/engine.rs:fixture', name: 'fixture', kind: 'function', file: 'tests/engine.rs', startLine: 1, endLine: 1, visibility: 'public' },
+];
+
+const graph = { query: async () => rows };
+const readFile = async (file: string) => sources[file] ?? null;
+
+describe('findDocTargets', () => {
+  it('finds public items outside tests, skipping ignored ones', async () => {
+    const targets = await findDocTargets(graph, { ignore: ['src/generated/**'], readFile });
+    expect(targets.map(t => [t.name, t.documented])).toEqual([
+      ['multiply', true],
+      ['transpose', false],
+      ['Matrix', false],
+      ['drain', false],
+    ]);
+  });
+
+  it('keeps only undocumented items under a path with missingOnly', async () => {
+    const targets = await findDocTargets(graph, { path: 'src/engine.rs', missingOnly: true, readFile });
+    expect(targets.map(t => t.name)).toEqual(['transpose', 'Matrix']);
+  });
+});
+
+describe('findDocComment', () => {
+  it('finds comments above attributes and Python docstrings in the body', () => {
+    expect(findDocComment(['/// A', '/// B', '#[inline]', 'pub fn f() {}'], 4, 'rust')).toEqual({ start: 0, end: 1 });
+    expect(findDocComment(['/**', ' * Drains.', ' */', 'export function drain() {}'], 4, 'typescript')).toEqual({ start: 0, end: 2 });
+    expect(findDocComment(['/* not a doc */', 'function f() {}'], 2, 'typescript')).toBeNull();
+    expect(findDocComment(['def f(x,', '      y):', '    """Doc."""', '    pass'], 1, 'python')).toEqual({ start: 2, end: 2 });
+    expect(findDocComment(['def f():', '    pass'], 1, 'python')).toBeNull();
+  });
+});
+
+describe('renderDocComment', () => {
+  it('uses each language\'s doc form', () => {
+    expect(renderDocComment('Multiplies.\n\nPanics on mismatch.', 'rust', '    '))
+      .toEqual(['    /// Multiplies.', '    ///', '    /// Panics on mismatch.']);
+    expect(renderDocComment('Drain returns the queue.', 'go', '')).toEqual(['// Drain returns the queue.']);
+    expect(renderDocComment('Drain the queue.', 'typescript', '  ')).toEqual(['  /**', '   * Drain the queue.', '   */']);
+    expect(renderDocComment('Drain.', 'python', '    ')).toEqual(['    """Drain."""']);
+    expect(renderDocComment('Drain.\n\nReturns:\n    Items.', 'python', '    '))
+      .toEqual(['    """Drain.', '', '    Returns:', '        Items.', '    """']);
+  });
+});
+
+describe('applyDocComments', () => {
+  it('inserts above attributes and replaces existing docs', () => {
+    const updated = applyDocComments(sources['src/engine.rs'], 'rust', [
+      { startLine: 2, doc: 'Matrix product of `a` and `b`.' },
+      { startLine: 6, doc: 'A dense matrix.' },
+    ]);
+    expect(updated.split('\n')).toEqual([
+      '/// Matrix product of `a` and `b`.',
+      'pub fn multiply(a: &M, b: &M) -> M { todo!() }',
+      'pub fn transpose(a: &M) -> M { todo!() }',
+      'fn helper() {}',
+      '/// A dense matrix.',
+      '#[derive(Debug)]',
+      'pub struct Matrix;',
+    ]);
+  });
+
+  it('puts Python docstrings at the top of the body', () => {
+    const source = 'class Pool:\n    def drain(self):\n        return []\n';
+    expect(applyDocComments(source, 'python', [{ startLine: 2, doc: 'Empty the pool.' }]))
+      .toBe('class Pool:\n    def drain(self):\n        """Empty the pool."""\n        return []\n');
+  });
+});
+
+describe('docgen prompt and response', () => {
+  const targets = [
+    { qualifiedName: 'src/pool.ts:drain', name: 'drain', kind: 'function', file: 'src/pool.ts', startLine: 1, endLine: 1, language: 'typescript', documented: false },
+  ];
+
+  it('lists each item with the style guide', () => {
+    const prompt = buildDocgenPrompt('src/pool.ts', sources['src/pool.ts'], targets);
+    expect(prompt).toContain('TSDoc');
+    expect(prompt).toContain('### src/pool.ts:drain (function, line 1)');
+    expect(prompt).toContain('export function drain() {}');
+  });
+
+  it('keeps docs for requested items and strips stray comment markers', () => {
+    const docs = parseDocgenResponse('```json\n' + JSON.stringify({
+      docs: [
+        { id: 'src/pool.ts:drain', doc: '/**\n * Empty the queue.\n */' },
+        { id: 'src/pool.ts:other', doc: 'Not asked for.' },
+      ],
+    }) + '\n```', targets);
+    expect(docs).toEqual([{ qualifiedName: 'src/pool.ts:drain', doc: 'Empty the queue.' }]);
+    expect(() => parseDocgenResponse('Sorry, no.', targets)).toThrow('JSON');
+  });
+
+  it('groups targets by file', () => {
+    const many = Array.from({ length: 25 }, (_, i) => ({ ...targets[0], qualifiedName: `q${i}`, startLine: i + 1 }));
+    expect(groupDocTargets(many).map(group => group.length)).toEqual([20, 5]);
+  });
+});
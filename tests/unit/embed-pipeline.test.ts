@@ -0,0 +1,142 @@
+/**
+ * Embedding Pipeline Tests
+ * Tests for batching, bounded parallelism, backpressure and progress while cv sync embeds chunks
+ */
+
+import { describe, it, expect } from 'vitest';
+import { BoundedChannel, EmbedProgress, runEmbedPipeline } from '../../packages/core/src/sync/embed-pipeline.js';
+
+const tick = () => new Promise(resolve => setTimeout(resolve, 1));
+const range = (n: number) => Array.from({ length: n }, (_, i) => i);
+
+describe('BoundedChannel', () => {
+  it('delivers in order, waits while full and drains after close', async () => {
+    const channel = new BoundedChannel<number>(2);
+    await channel.send(1);
+    await channel.send(2);
+    let sent = false;
+    const third = channel.send(3).then(() => { sent = true; });
+    await tick();
+    expect(sent).toBe(false);
+
+    expect(await channel.receive()).toBe(1);
+    await third;
+    channel.close();
+    const rest: number[] = [];
+    for await (const item of channel) rest.push(item);
+    expect(rest).toEqual([2, 3]);
+  });
+});
+
+describe('runEmbedPipeline', () => {
+  it('embeds in batches and stores every chunk with its own vector', async () => {
+    const requests: number[][] = [];
+    const stored = new Map<number, number[]>();
+    const count = await runEmbedPipeline(range(10), {
+      batchSize: 4,
+      concurrency: 2,
+      total: 10,
+      embed: async items => {
+        requests.push(items);
+        await tick();
+        return items.map(i => [i, i * 2]);
+      },
+      store: async (items, vectors) => {
+        items.forEach((item, idx) => stored.set(item, vectors[idx]));
+      },
+    });
+
+    expect(count).toBe(10);
+    expect(requests.map(r => r.length).sort()).toEqual([2, 4, 4]);
+    expect([...stored.keys()].sort((a, b) => a - b)).toEqual(range(10));
+    expect(stored.get(7)).toEqual([7, 14]);
+  });
+
+  it('keeps at most `concurrency` requests in flight', async () => {
+    let inFlight = 0;
+    let peak = 0;
+    await runEmbedPipeline(range(40), {
+      batchSize: 2,
+      concurrency: 3,
+      total: 40,
+      embed: async items => {
+        peak = Math.max(peak, ++inFlight);
+        await tick();
+        inFlight--;
+        return items.map(() => [1]);
+      },
+      store: async () => {},
+    });
+    expect(peak).toBe(3);
+  });
+
+  it('stops reading chunks while storage falls behind', async () => {
+    let pulled = 0;
+    let stored = 0;
+    let maxAhead = 0;
+    function* chunks() {
+      for (const i of range(200)) {
+        pulled++;
+        maxAhead = Math.max(maxAhead, pulled - stored);
+        yield i;
+      }
+    }
+    await runEmbedPipeline(chunks(), {
+      batchSize: 5,
+      concurrency: 2,
+      total: 200,
+      embed: async items => items.map(() => [1]),
+      store: async items => {
+        await tick();
+        stored += items.length;
+      },
+    });
+    expect(stored).toBe(200);
+    // Queued batches, batches being embedded and the one being stored, not the whole source
+    expect(maxAhead).toBeLessThan(60);
+  });
+
+  it('rethrows the first failure and stops storing', async () => {
+    const stored: number[] = [];
+    await expect(runEmbedPipeline(range(20), {
+      batchSize: 2,
+      concurrency: 2,
+      total: 20,
+      embed: async items => {
+        if (items.includes(6)) throw new Error('rate limited');
+        await tick();
+        return items.map(() => [1]);
+      },
+      store: async items => { stored.push(...items); },
+    })).rejects.toThrow('rate limited');
+    expect(stored.length).toBeLessThan(20);
+  });
+
+  it('rejects a provider that returns the wrong number of vectors', async () => {
+    await expect(runEmbedPipeline(range(3), {
+      batchSize: 3,
+      concurrency: 1,
+      total: 3,
+      embed: async () => [[1]],
+      store: async () => {},
+    })).rejects.toThrow('Expected 3 embeddings, got 1');
+  });
+
+  it('reports throughput and time left', async () => {
+    let clock = 0;
+    const progress: EmbedProgress[] = [];
+    await runEmbedPipeline(range(8), {
+      batchSize: 2,
+      concurrency: 1,
+      total: 8,
+      now: () => clock,
+      embed: async items => items.map(() => [1]),
+      store: async () => { clock += 1000; },
+      onProgress: p => progress.push(p),
+    });
+
+    expect(progress.map(p => p.done)).toEqual([2, 4, 6, 8]);
+    expect(progress[0]).toEqual({ done: 2, total: 8, elapsedMs: 1000, rate: 2, etaMs: 3000 });
+    expect(progress[3].etaMs).toBe(0);
+  });
+});
@@ -394,5 +394,58 @@ describe('EmbeddingCache', () => {
 
       await newCache.close();
     });
+
+    it('should keep other models\' embeddings for when the model switches back', async () => {
+      await cache.set('data', Array.from({ length: 1536 }, () => 0.1));
+      await cache.close();
+
+      const otherModel = createEmbeddingCache({ cacheDir: tempDir, model: 'nomic-embed-text', dimensions: 768 });
+      await otherModel.initialize();
+      await otherModel.set('data', Array.from({ length: 768 }, () => 0.2));
+      expect((await otherModel.getStats()).entriesByModel).toEqual({
+        'openai/text-embedding-3-small': 1,
+        'nomic-embed-text': 1
+      });
+      await otherModel.close();
+
+      const switchedBack = createEmbeddingCache({ cacheDir: tempDir, model: 'openai/text-embedding-3-small', dimensions: 1536 });
+      await switchedBack.initialize();
+      const vector = await switchedBack.get('data');
+      expect(vector).toHaveLength(1536);
+      expect(vector![0]).toBeCloseTo(0.1, 5);
+      await switchedBack.close();
+    });
+  });
+
+  describe('model keys', () => {
+    it('should look up embeddings by the model passed in', async () => {
+      await cache.set('shared text', [0.5, 0.5], 'nomic-embed-text');
+
+      expect(await cache.get('shared text')).toBeNull();
+      expect(await cache.get('shared text', 'nomic-embed-text')).toEqual([0.5, 0.5]);
+      expect(await cache.has('shared text', 'nomic-embed-text')).toBe(true);
+
+      const batch = await cache.getBatch(['shared text', 'other'], 'nomic-embed-text');
+      expect(batch.cached.size).toBe(1);
+      expect(batch.missing).toEqual(['other']);
+    });
+
+    it('should count only the current model in totals', async () => {
+      await cache.set('a', Array.from({ length: 1536 }, () => 0.1));
+      await cache.setBatch(new Map([['b', [0.1, 0.2]], ['c', [0.3, 0.4]]]), 'all-minilm');
+
+      const stats = await cache.getStats();
+      expect(stats.totalEntries).toBe(1);
+      expect(stats.totalSizeBytes).toBe(6144);
+      expect(stats.entriesByModel['all-minilm']).toBe(2);
+    });
+
+    it('should export only the current model', async () => {
+      await cache.set('a', Array.from({ length: 1536 }, () => 0.1));
+      await cache.set('b', [0.1, 0.2], 'all-minilm');
+
+      const exported = await cache.export();
+      expect(exported.embeddings).toHaveLength(1);
+    });
   });
 });
@@ -0,0 +1,76 @@
+/**
+ * Embedding Provider Tests
+ * Tests for provider resolution, model naming and batch limits
+ */
+
+import { describe, it, expect } from 'vitest';
+import {
+  resolveProvider,
+  embedInBatches,
+  EmbeddingProvider,
+  EMBEDDING_BATCH_LIMITS
+} from '../../packages/core/src/vector/providers.js';
+
+function fakeProvider(maxBatchSize: number, failures: number = 0): EmbeddingProvider & { calls: number[] } {
+  let remaining = failures;
+  const calls: number[] = [];
+  return {
+    name: 'openai',
+    model: 'fake',
+    dimension: 2,
+    maxBatchSize,
+    calls,
+    async embed(texts: string[]) {
+      if (remaining-- > 0) throw Object.assign(new Error('rate limited'), { status: 429 });
+      calls.push(texts.length);
+      return texts.map(t => [t.length, 0]);
+    }
+  };
+}
+
+describe('resolveProvider', () => {
+  it('should prefer OpenRouter, then OpenAI, then Azure when no provider is chosen', () => {
+    const azure = { endpoint: 'https://x.openai.azure.com', apiKey: 'k', deployment: 'embed' };
+    expect(resolveProvider({ openrouterApiKey: 'or', openaiApiKey: 'oa', azure }).name).toBe('openrouter');
+    expect(resolveProvider({ openaiApiKey: 'oa', azure }).name).toBe('openai');
+    expect(resolveProvider({ azure }).name).toBe('azure');
+  });
+
+  it('should name models the way each backend expects', () => {
+    expect(resolveProvider({ provider: 'openrouter', openrouterApiKey: 'k', model: 'text-embedding-3-large' }).model)
+      .toBe('openai/text-embedding-3-large');
+    expect(resolveProvider({ provider: 'openai', openaiApiKey: 'k', model: 'openai/text-embedding-3-small' }).model)
+      .toBe('text-embedding-3-small');
+  });
+
+  it('should drop a model that belongs to another backend', () => {
+    const provider = resolveProvider({ openrouterApiKey: 'k', model: 'nomic-embed-text' });
+    expect(provider.model).toBe('openai/text-embedding-3-small');
+    expect(provider.dimension).toBe(1536);
+  });
+
+  it('should fail when the chosen backend is not configured', () => {
+    expect(() => resolveProvider({ provider: 'azure', openaiApiKey: 'k' })).toThrow(/Azure OpenAI not configured/);
+    expect(() => resolveProvider({})).toThrow(/No embedding provider available/);
+  });
+
+  it('should apply per-provider batch limits', () => {
+    const azure = resolveProvider({ azure: { endpoint: 'https://x', apiKey: 'k', deployment: 'd' } });
+    expect(azure.maxBatchSize).toBe(EMBEDDING_BATCH_LIMITS.azure);
+    expect(resolveProvider({ ollamaUrl: 'http://127.0.0.1:11434' }).maxBatchSize).toBe(EMBEDDING_BATCH_LIMITS.ollama);
+  });
+});
+
+describe('embedInBatches', () => {
+  it('should split input by the provider batch limit and keep order', async () => {
+    const provider = fakeProvider(2);
+    const vectors = await embedInBatches(provider, ['a', 'bb', 'ccc', 'dddd', 'eeeee']);
+    expect(provider.calls).toEqual([2, 2, 1]);
+    expect(vectors.map(v => v[0])).toEqual([1, 2, 3, 4, 5]);
+  });
+
+  it('should retry rate-limited batches', async () => {
+    const provider = fakeProvider(10, 1);
+    expect(await embedInBatches(provider, ['a'], 2)).toEqual([[1, 0]]);
+  }, 10000);
+});
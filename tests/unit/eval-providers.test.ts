@@ -0,0 +1,71 @@
+/**
+ * Embedding Provider Evaluation Tests
+ */
+
+import { describe, it, expect } from 'vitest';
+import {
+  evaluateProvider,
+  embeddingPrice,
+  formatProviderComparison,
+  EvalCorpusChunk,
+  EvalDataset,
+  EvalEmbedder
+} from '../../packages/core/src/eval/index.js';
+
+const corpus: EvalCorpusChunk[] = [
+  { id: '1', file: 'src/auth.ts', symbolName: 'login', text: 'login password session' },
+  { id: '2', file: 'src/db.ts', symbolName: 'connect', text: 'database connection pool' },
+  { id: '3', file: 'src/cache.ts', text: 'cache eviction ttl' }
+];
+
+const dataset: EvalDataset = {
+  cases: [
+    { query: 'password', expected: ['src/auth.ts:login'] },
+    { query: 'database', expected: ['src/db.ts'] }
+  ]
+};
+
+const VOCAB = ['login', 'password', 'session', 'database', 'connection', 'pool', 'cache', 'eviction', 'ttl'];
+
+/** Bag-of-words embedder; `blind` ignores the query so ranking is arbitrary */
+function embedder(blind = false): EvalEmbedder {
+  const vec = (text: string) => VOCAB.map(w => (text.includes(w) ? 1 : 0));
+  return {
+    embed: async text => (blind ? VOCAB.map(() => 1) : vec(text)),
+    embedBatch: async texts => texts.map(t => (blind ? [...vec(t).slice(0, 8), 5] : vec(t)))
+  };
+}
+
+describe('evaluateProvider', () => {
+  it('should score a provider that ranks relevant chunks first', async () => {
+    const result = await evaluateProvider(
+      { name: 'test', model: 'text-embedding-3-small', embedder: embedder() },
+      corpus,
+      dataset,
+      { k: 1 }
+    );
+    expect(result.recallAtK).toBe(1);
+    expect(result.mrr).toBe(1);
+    expect(result.tokens).toBeGreaterThan(0);
+    expect(result.estimatedCostUsd).toBeGreaterThan(0);
+  });
+
+  it('should separate a weaker provider', async () => {
+    const good = await evaluateProvider({ name: 'a', model: 'nomic-embed-text', embedder: embedder() }, corpus, dataset, { k: 1 });
+    const weak = await evaluateProvider({ name: 'b', model: 'mystery-model', embedder: embedder(true) }, corpus, dataset, { k: 1 });
+
+    expect(weak.recallAtK).toBeLessThan(good.recallAtK);
+    expect(good.estimatedCostUsd).toBe(0);
+    expect(weak.estimatedCostUsd).toBeNull();
+
+    const table = formatProviderComparison([good, weak]);
+    expect(table).toContain('Recall@1');
+    expect(table).toContain('| mystery-model |');
+  });
+
+  it('should price models with or without a provider prefix', () => {
+    expect(embeddingPrice('openai/text-embedding-3-large')).toBe(0.13);
+    expect(embeddingPrice('all-minilm')).toBe(0);
+    expect(embeddingPrice('unknown')).toBeUndefined();
+  });
+});
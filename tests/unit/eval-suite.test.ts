@@ -0,0 +1,97 @@
+/**
+ * Retrieval Eval Suite Tests
+ * Tests for parsing the YAML query suite, scoring recall@k and MRR, and thresholds
+ */
+
+import { describe, it, expect } from 'vitest';
+import {
+  EvalSuiteError,
+  formatSuiteReport,
+  parseEvalSuite,
+  runEvalSuite,
+  EvalHit,
+} from '../../packages/core/src/eval/suite.js';
+
+const SUITE = `
+k: 3
+thresholds:
+  recall: 0.75
+  mrr: 0.5
+queries:
+  - query: where do users log in?
+    expected:
+      - src/auth.ts:login
+      - ./src/session.ts
+  - query: database connection
+    expected: src/db.ts
+`;
+
+const index: Record<string, EvalHit[]> = {
+  'where do users log in?': [
+    { file: 'src/auth.ts', symbolName: 'logout', score: 0.9 },
+    { file: 'src/auth.ts', symbolName: 'login', score: 0.8 },
+    { file: 'src/session.ts', symbolName: 'Session', score: 0.7 },
+    { file: 'src/cache.ts', score: 0.6 },
+  ],
+  'database connection': [{ file: 'src/db.ts', symbolName: 'connect', score: 0.7 }],
+};
+const search = async (query: string) => index[query] ?? [];
+
+describe('parseEvalSuite', () => {
+  it('reads k, thresholds and queries', () => {
+    expect(parseEvalSuite(SUITE)).toEqual({
+      k: 3,
+      thresholds: { recall: 0.75, mrr: 0.5 },
+      cases: [
+        { query: 'where do users log in?', expected: ['src/auth.ts:login', 'src/session.ts'] },
+        { query: 'database connection', expected: ['src/db.ts'] },
+      ],
+    });
+  });
+
+  it('accepts a bare list of queries with defaults', () => {
+    const suite = parseEvalSuite('- query: cache\n  expected: [src/cache.ts]\n');
+    expect(suite.k).toBe(10);
+    expect(suite.thresholds).toEqual({});
+  });
+
+  it('rejects invalid suites with the location', () => {
+    expect(() => parseEvalSuite('queries: []', 'q.yaml')).toThrow(EvalSuiteError);
+    expect(() => parseEvalSuite('queries:\n  - query: cache\n', 'q.yaml')).toThrow('q.yaml: query 1 ("cache")');
+    expect(() => parseEvalSuite('thresholds: { recall: 80 }\nqueries:\n  - { query: a, expected: [b] }\n')).toThrow('thresholds.recall');
+    expect(() => parseEvalSuite('k: 0\nqueries:\n  - { query: a, expected: [b] }\n')).toThrow('"k"');
+  });
+});
+
+describe('runEvalSuite', () => {
+  it('scores recall@k and MRR per query and overall', async () => {
+    const report = await runEvalSuite(parseEvalSuite(SUITE), search);
+    expect(report.cases.map(c => [c.firstRelevantRank, c.recall, c.missing])).toEqual([
+      [2, 1, []],
+      [1, 1, []],
+    ]);
+    expect(report.recallAtK).toBe(1);
+    expect(report.mrr).toBe(0.75);
+    expect(report.failures).toEqual([]);
+  });
+
+  it('only counts the top k and fails below the thresholds', async () => {
+    const report = await runEvalSuite(parseEvalSuite(SUITE), search, { k: 1 });
+    expect(report.cases[0]).toMatchObject({ firstRelevantRank: null, recall: 0, missing: ['src/auth.ts:login', 'src/session.ts'] });
+    expect(report.recallAtK).toBe(0.5);
+    expect(report.failures).toEqual(['recall@1 0.500 is below 0.75']);
+  });
+
+  it('fails when the search returns nothing', async () => {
+    const report = await runEvalSuite(parseEvalSuite(SUITE), async () => [], { thresholds: { recall: 0.1 } });
+    expect(report.recallAtK).toBe(0);
+    expect(report.failures).toEqual(['recall@3 0.000 is below 0.1', 'MRR 0.000 is below 0.5']);
+  });
+
+  it('writes a markdown summary', async () => {
+    const markdown = formatSuiteReport(await runEvalSuite(parseEvalSuite(SUITE), search, { k: 1 }));
+    expect(markdown).toContain('**Retrieval eval: failed**');
+    expect(markdown).toContain('- recall@1: 0.500 (min 0.75)');
+    expect(markdown).toContain('| where do users log in? | — | 0.00 | `src/auth.ts:login`, `src/session.ts` |');
+  });
+});
@@ -0,0 +1,152 @@
+/**
+ * Fallback Chain Tests
+ * Tests for retrying requests on the next model of a chain
+ */
+
+import { describe, it, expect, vi } from 'vitest';
+import {
+  FallbackClient,
+  FallbackModel,
+  isRetryableProviderError,
+  parseFallbackChain,
+} from '../../packages/core/src/ai/fallback.js';
+import { AIClient, AIStreamHandler } from '../../packages/core/src/ai/types.js';
+
+function statusError(status: number): Error {
+  return Object.assign(new Error(`${status} status code`), { status });
+}
+
+function fakeClient(name: string, reply: (handler?: AIStreamHandler) => Promise<string>): AIClient {
+  return {
+    getProvider: () => name,
+    getModel: () => `${name}-model`,
+    setModel: () => {},
+    isReady: async () => true,
+    chat: () => reply(),
+    chatStream: (_messages, _system, handler) => reply(handler),
+    complete: (_prompt, handler) => reply(handler),
+  };
+}
+
+function model(spec: string, reply: (handler?: AIStreamHandler) => Promise<string>): FallbackModel {
+  return { spec, create: async () => fakeClient(spec, reply) };
+}
+
+const question = [{ role: 'user' as const, content: 'hi' }];
+
+describe('parseFallbackChain', () => {
+  it('should split arrow and comma separated chains', () => {
+    expect(parseFallbackChain('openrouter/claude-sonnet-4-5 -> openai/gpt-4o -> ollama/llama3'))
+      .toEqual(['openrouter/claude-sonnet-4-5', 'openai/gpt-4o', 'ollama/llama3']);
+    expect(parseFallbackChain('openai/gpt-4o, ollama/llama3')).toEqual(['openai/gpt-4o', 'ollama/llama3']);
+    expect(parseFallbackChain([' openai/gpt-4o ', ''])).toEqual(['openai/gpt-4o']);
+    expect(parseFallbackChain(undefined)).toEqual([]);
+  });
+});
+
+describe('isRetryableProviderError', () => {
+  it('should retry rate limits, server errors and network failures', () => {
+    expect(isRetryableProviderError(statusError(429))).toBe(true);
+    expect(isRetryableProviderError(statusError(503))).toBe(true);
+    expect(isRetryableProviderError(new Error('Gemini API error: 500 - internal'))).toBe(true);
+    expect(isRetryableProviderError(Object.assign(new Error('fetch failed'), { cause: { code: 'ECONNREFUSED' } }))).toBe(true);
+  });
+
+  it('should not retry client errors', () => {
+    expect(isRetryableProviderError(statusError(400))).toBe(false);
+    expect(isRetryableProviderError(statusError(401))).toBe(false);
+    expect(isRetryableProviderError(new Error('LM Studio API error: 404 Not Found - no model'))).toBe(false);
+  });
+});
+
+describe('FallbackClient', () => {
+  it('should move to the next model on a rate limit', async () => {
+    const onFallback = vi.fn();
+    const client = new FallbackClient([
+      model('anthropic/claude-sonnet-4-5', async () => { throw statusError(429); }),
+      model('openai/gpt-4o', async () => 'from openai'),
+    ], { onFallback });
+
+    expect(await client.chat(question)).toBe('from openai');
+    expect(client.getAnsweredBy()).toBe('openai/gpt-4o');
+    expect(client.getProvider()).toBe('openai/gpt-4o');
+    expect(onFallback).toHaveBeenCalledWith(expect.objectContaining({
+      from: 'anthropic/claude-sonnet-4-5',
+      to: 'openai/gpt-4o',
+    }));
+  });
+
+  it('should report errors another model would not fix', async () => {
+    const second = vi.fn(async () => 'unused');
+    const client = new FallbackClient([
+      model('anthropic/claude-sonnet-4-5', async () => { throw statusError(400); }),
+      model('openai/gpt-4o', second),
+    ]);
+
+    await expect(client.chat(question)).rejects.toThrow('400');
+    expect(second).not.toHaveBeenCalled();
+  });
+
+  it('should throw the last error when every model fails', async () => {
+    const client = new FallbackClient([
+      model('a', async () => { throw statusError(503); }),
+      model('b', async () => { throw statusError(502); }),
+    ]);
+
+    await expect(client.chat(question)).rejects.toThrow('502');
+  });
+
+  it('should skip a model that cannot be set up', async () => {
+    const client = new FallbackClient([
+      { spec: 'gemini', create: async () => { throw new Error('No gemini API key found'); } },
+      model('ollama/llama3', async () => 'local answer'),
+    ]);
+
+    expect(await client.chat(question)).toBe('local answer');
+    expect(client.getAnsweredBy()).toBe('ollama/llama3');
+  });
+
+  it('should fall back before the first streamed token', async () => {
+    const tokens: string[] = [];
+    const client = new FallbackClient([
+      model('a', async () => { throw statusError(529); }),
+      model('b', async handler => { handler?.onToken?.('ok'); return 'ok'; }),
+    ]);
+
+    expect(await client.chatStream(question, undefined, { onToken: t => tokens.push(t) })).toBe('ok');
+    expect(tokens).toEqual(['ok']);
+  });
+
+  it('should not fall back once tokens have been streamed', async () => {
+    const tokens: string[] = [];
+    const onError = vi.fn();
+    const second = vi.fn(async () => 'unused');
+    const client = new FallbackClient([
+      model('a', async handler => {
+        handler?.onToken?.('partial');
+        throw statusError(503);
+      }),
+      model('b', second),
+    ]);
+
+    await expect(client.chatStream(question, undefined, { onToken: t => tokens.push(t), onError }))
+      .rejects.toThrow('503');
+    expect(tokens).toEqual(['partial']);
+    expect(second).not.toHaveBeenCalled();
+    expect(onError).toHaveBeenCalledTimes(1);
+  });
+
+  it('should pass tool requests to the first model that supports tools', async () => {
+    const withTools = {
+      ...fakeClient('openai/gpt-4o', async () => ''),
+      chatWithTools: async () => ({ text: 'done', toolCalls: [] }),
+    };
+    const client = new FallbackClient([
+      model('ollama/llama3', async () => ''),
+      { spec: 'openai/gpt-4o', create: async () => withTools },
+    ]);
+
+    expect(await client.chatWithTools(question, [])).toEqual({ text: 'done', toolCalls: [] });
+    expect(client.getAnsweredBy()).toBe('openai/gpt-4o');
+  });
+});
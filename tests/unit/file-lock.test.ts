@@ -92,6 +92,19 @@ describe('File Locking', () => {
 
       await lock.release();
     });
+
+    it('should reclaim a fresh lock whose owner process has exited', async () => {
+      const lockPath = `${testFile}.lock`;
+      await fs.writeFile(lockPath, JSON.stringify({
+        pid: 2 ** 22 + 12345, // above any real pid_max
+        hostname: os.hostname(),
+        createdAt: new Date().toISOString(),
+      }));
+
+      const lock = await acquireLock(testFile, { timeout: 1000 });
+      expect((await getLockInfo(testFile))?.pid).toBe(process.pid);
+      await lock.release();
+    });
   });
 
   describe('withLock', () => {
@@ -0,0 +1,86 @@
+/**
+ * Graph Expansion Tests
+ */
+
+import { describe, it, expect } from 'vitest';
+import {
+  expandGraphContext,
+  parseExpandDepth,
+  estimateSymbolTokens
+} from '../../packages/core/src/ai/graph-expansion.js';
+
+function symbol(name: string, docstring = ''): any {
+  return { name, qualifiedName: `src/app.ts:${name}`, file: 'src/app.ts', docstring };
+}
+
+// a -> b -> c -> d, and a -> e
+function createFakeGraph(): any {
+  const calls: Record<string, string[]> = { a: ['b', 'e'], b: ['c'], c: ['d'] };
+  const byQualified = (q: string) => q.split(':')[1];
+
+  return {
+    async getCallers(q: string) {
+      const name = byQualified(q);
+      return Object.entries(calls)
+        .filter(([, callees]) => callees.includes(name))
+        .map(([caller]) => symbol(caller));
+    },
+    async getCallees(q: string) {
+      return (calls[byQualified(q)] || []).map(n => symbol(n));
+    },
+    async getFileDependencies(path: string) {
+      return path === 'src/app.ts' ? ['src/util.ts'] : [];
+    },
+    async getFileNode(path: string) {
+      return { path };
+    }
+  };
+}
+
+describe('Graph Expansion', () => {
+  it('should only return seeds at depth 0', async () => {
+    const result = await expandGraphContext(
+      createFakeGraph(),
+      { symbols: [symbol('b')], files: ['src/app.ts'] },
+      { depth: 0 }
+    );
+
+    expect(result.symbols.map(s => s.name)).toEqual(['b']);
+    expect(result.files.map(f => f.path)).toEqual(['src/app.ts']);
+  });
+
+  it('should follow callers, callees and imports per hop', async () => {
+    const graph = createFakeGraph();
+
+    const one = await expandGraphContext(graph, { symbols: [symbol('b')], files: ['src/app.ts'] }, { depth: 1 });
+    expect(one.symbols.map(s => s.name).sort()).toEqual(['a', 'b', 'c']);
+    expect(one.files.map(f => f.path)).toEqual(['src/app.ts', 'src/util.ts']);
+
+    const two = await expandGraphContext(graph, { symbols: [symbol('b')], files: [] }, { depth: 2 });
+    expect(two.symbols.map(s => s.name).sort()).toEqual(['a', 'b', 'c', 'd', 'e']);
+    expect(two.hops.get('src/app.ts:d')).toBe(2);
+  });
+
+  it('should prune farther hops first when over budget', async () => {
+    const seed = symbol('b');
+    const budget = estimateSymbolTokens(seed) + estimateSymbolTokens(symbol('a')) + estimateSymbolTokens(symbol('c'));
+
+    const result = await expandGraphContext(
+      createFakeGraph(),
+      { symbols: [seed], files: [] },
+      { depth: 3, tokenBudget: budget }
+    );
+
+    expect(result.symbols.map(s => s.name).sort()).toEqual(['a', 'b', 'c']);
+    expect(result.pruned).toBeGreaterThan(0);
+    expect(result.tokensUsed).toBeLessThanOrEqual(budget);
+  });
+
+  it('should parse expand depth values', () => {
+    expect(parseExpandDepth(undefined)).toBe(1);
+    expect(parseExpandDepth('3')).toBe(3);
+    expect(parseExpandDepth('-1')).toBe(1);
+    expect(parseExpandDepth('nope')).toBe(1);
+    expect(parseExpandDepth('99')).toBe(5);
+  });
+});
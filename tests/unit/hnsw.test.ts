@@ -0,0 +1,345 @@
+/**
+ * HNSW Tests
+ * Tests for the approximate index, its file format, and the store wrapper
+ * that decides between graph and exact search
+ */
+
+import { describe, it, expect, afterEach } from 'vitest';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { HnswIndex } from '../../packages/core/src/vector/hnsw.js';
+import { IndexFile, readIndexFile, StringTable, writeIndexFile } from '../../packages/core/src/vector/index-file.js';
+import { HnswVectorStore } from '../../packages/core/src/vector/stores/hnsw-store.js';
+import {
+  cosineSimilarity,
+  matchesFilter,
+  VectorStore,
+  VectorPoint,
+  VectorFilter
+} from '../../packages/core/src/vector/store.js';
+
+/** Deterministic RNG (mulberry32) so recall does not vary between runs */
+function rng(seed: number): () => number {
+  return () => {
+    seed = (seed + 0x6d2b79f5) | 0;
+    let t = seed;
+    t = Math.imul(t ^ (t >>> 15), t | 1);
+    t ^= t + Math.imul(t ^ (t >>> 7), t | 61);
+    return ((t ^ (t >>> 14)) >>> 0) / 4294967296;
+  };
+}
+
+function randomVectors(count: number, dimension: number, random: () => number): number[][] {
+  return Array.from({ length: count }, () => Array.from({ length: dimension }, () => random() * 2 - 1));
+}
+
+function exactTop(vectors: Map<string, number[]>, query: number[], k: number): string[] {
+  return [...vectors]
+    .map(([id, v]) => ({ id, score: cosineSimilarity(query, v) }))
+    .sort((a, b) => b.score - a.score)
+    .slice(0, k)
+    .map(r => r.id);
+}
+
+function recall(index: HnswIndex, vectors: Map<string, number[]>, queries: number[][], k: number): number {
+  let found = 0;
+  for (const query of queries) {
+    const expected = new Set(exactTop(vectors, query, k));
+    found += index.search(query, k).filter(hit => expected.has(hit.id)).length;
+  }
+  return found / (queries.length * k);
+}
+
+class MemoryStore implements VectorStore {
+  readonly type = 'lancedb' as const;
+  points = new Map<string, VectorPoint>();
+  exactSearches = 0;
+
+  constructor(private dimension: number) {}
+
+  async connect() {}
+  async close() {}
+  async listCollections() { return ['code']; }
+  async createCollection() {}
+  async deleteCollection() { this.points.clear(); }
+  async getCollectionStats() {
+    return { vectorSize: this.dimension, pointsCount: this.points.size };
+  }
+  async upsert(_name: string, points: VectorPoint[]) {
+    for (const p of points) this.points.set(String(p.id), p);
+  }
+  async delete(_name: string, ids: Array<string | number>) {
+    for (const id of ids) this.points.delete(String(id));
+  }
+  async retrieve(_name: string, ids: Array<string | number>) {
+    return ids.map(id => this.points.get(String(id))).filter((p): p is VectorPoint => !!p);
+  }
+  async search(_name: string, vector: number[], options: { limit: number; filter?: VectorFilter }) {
+    this.exactSearches++;
+    return [...this.points.values()]
+      .filter(p => matchesFilter(p.payload, options.filter))
+      .map(p => ({ id: p.id, score: cosineSimilarity(vector, p.vector), payload: p.payload }))
+      .sort((a, b) => b.score - a.score)
+      .slice(0, options.limit);
+  }
+  async scroll(_name: string, options: { limit: number; offset?: string }) {
+    const all = [...this.points.values()];
+    const start = options.offset ? parseInt(options.offset, 10) : 0;
+    const end = start + options.limit;
+    return { points: all.slice(start, end), nextOffset: end < all.length ? String(end) : undefined };
+  }
+}
+
+describe('HnswIndex', () => {
+  const random = rng(42);
+  const data = randomVectors(2000, 16, random);
+  const vectors = new Map(data.map((v, i) => [String(i), v]));
+  const queries = randomVectors(50, 16, random);
+
+  function build(): HnswIndex {
+    const index = new HnswIndex(16, { m: 16, efConstruction: 100 }, rng(7));
+    for (const [id, v] of vectors) index.add(id, v);
+    return index;
+  }
+
+  it('should find most exact nearest neighbours', () => {
+    expect(recall(build(), vectors, queries, 10)).toBeGreaterThan(0.9);
+  });
+
+  it('should round-trip through serialize', () => {
+    const index = build();
+    const restored = HnswIndex.deserialize(index.serialize());
+
+    expect(restored.size).toBe(index.size);
+    expect(restored.dimension).toBe(16);
+    expect(restored.search(queries[0], 10)).toEqual(index.search(queries[0], 10));
+  });
+
+  it('should search a loaded index in place and change it like any other', () => {
+    const index = build();
+    index.remove('7');
+    const restored = HnswIndex.deserialize(index.serialize());
+    expect(restored.size).toBe(1999);
+    expect(restored.deletedRatio).toBeCloseTo(1 / 2000);
+    expect(restored.search(queries[1], 10)).toEqual(index.search(queries[1], 10));
+
+    restored.add('new', queries[1]);
+    restored.remove('8');
+    expect(restored.has('7')).toBe(false);
+    expect(restored.size).toBe(1999);
+    expect(restored.search(queries[1], 1)[0].id).toBe('new');
+
+    const again = HnswIndex.deserialize(restored.serialize());
+    expect(again.search(queries[1], 10)).toEqual(restored.search(queries[1], 10));
+  });
+
+  it('should search a saved index read from its file on demand', async () => {
+    const index = build();
+    index.remove('7');
+    const dir = fs.mkdtempSync(path.join(os.tmpdir(), 'cv-hnsw-file-'));
+    try {
+      const file = path.join(dir, 'graph.hnsw');
+      fs.writeFileSync(file, index.serialize());
+      const loaded = await HnswIndex.load(file);
+      expect(loaded.size).toBe(1999);
+      expect(loaded.has('8')).toBe(true);
+      expect(loaded.has('7')).toBe(false);
+      expect(loaded.has('missing')).toBe(false);
+      // Membership is answered from the file, without unpacking the graph
+      expect((loaded as any).loaded).not.toBeNull();
+      expect(loaded.search(queries[2], 10)).toEqual(index.search(queries[2], 10));
+
+      // The first change reads everything and lets go of the file
+      loaded.add('new', queries[2]);
+      fs.rmSync(file);
+      expect(loaded.search(queries[2], 1)[0].id).toBe('new');
+      loaded.close();
+
+      fs.writeFileSync(file, 'not an index');
+      await expect(HnswIndex.load(file)).rejects.toThrow(/not an index file/);
+    } finally {
+      fs.rmSync(dir, { recursive: true, force: true });
+    }
+  });
+
+  it('should load version 1 index files', () => {
+    const header = Buffer.from(JSON.stringify({
+      version: 1, dimension: 2, m: 16, efConstruction: 200, entryPoint: 0, maxLevel: 0,
+      ids: ['a', 'b', 'c'], levels: [0, 0, 0], deleted: [2]
+    }));
+    const prefix = Buffer.alloc(8);
+    prefix.write('HNSW', 0, 'ascii');
+    prefix.writeUInt32LE(header.length, 4);
+    const padding = Buffer.alloc((4 - ((8 + header.length) % 4)) % 4, 0x20);
+    const vectors = new Float32Array([1, 0, 0, 1, 1, 1]);
+    const links = new Uint32Array([2, 1, 2, 2, 0, 2, 2, 0, 1]);
+    const index = HnswIndex.deserialize(Buffer.concat([
+      prefix, header, padding, Buffer.from(vectors.buffer), Buffer.from(links.buffer)
+    ]));
+
+    expect(index.size).toBe(2);
+    expect(index.search([0.1, 1], 2).map(hit => hit.id)).toEqual(['b', 'a']);
+  });
+
+  it('should not return removed points and drop them on compact', () => {
+    const index = build();
+    const remaining = new Map(vectors);
+    for (let i = 0; i < 500; i++) {
+      index.remove(String(i));
+      remaining.delete(String(i));
+    }
+
+    expect(index.size).toBe(1500);
+    expect(index.deletedRatio).toBeCloseTo(0.25);
+    for (const query of queries) {
+      expect(index.search(query, 10).every(hit => remaining.has(hit.id))).toBe(true);
+    }
+    expect(recall(index, remaining, queries, 10)).toBeGreaterThan(0.9);
+
+    const compacted = index.compact();
+    expect(compacted.size).toBe(1500);
+    expect(compacted.deletedRatio).toBe(0);
+    expect(recall(compacted, remaining, queries, 10)).toBeGreaterThan(0.9);
+  });
+
+  it('should replace a point added twice', () => {
+    const index = new HnswIndex(2);
+    index.add('a', [1, 0]);
+    index.add('b', [0, 1]);
+    index.add('a', [0, -1]);
+
+    expect(index.size).toBe(2);
+    expect(index.search([1, 0], 1)[0].score).toBeCloseTo(0);
+  });
+
+  it('should reject vectors of the wrong dimension', () => {
+    expect(() => new HnswIndex(3).add('a', [1, 2])).toThrow(/dimensions/);
+  });
+});
+
+describe('index files', () => {
+  it('should lay out aligned sections readable in place', async () => {
+    const file = writeIndexFile('TEST', { count: 2 }, {
+      bytes: new Uint8Array([1, 2, 3]),
+      vectors: new Float32Array([0.5, -1]),
+      ...StringTable.sections('ids', ['src/a.ts:1', 'docs/é.md'])
+    });
+    const dir = fs.mkdtempSync(path.join(os.tmpdir(), 'cv-index-file-'));
+    try {
+      fs.writeFileSync(path.join(dir, 'test.idx'), file);
+      const opened = IndexFile.open<{ count: number }>(await readIndexFile(path.join(dir, 'test.idx')), 'TEST');
+      expect(opened.header.count).toBe(2);
+      expect(Array.from(opened.bytes('bytes'))).toEqual([1, 2, 3]);
+      expect(Array.from(opened.f32('vectors'))).toEqual([0.5, -1]);
+      expect(opened.strings('ids').toArray()).toEqual(['src/a.ts:1', 'docs/é.md']);
+      expect(Object.values(opened.header.sections).every(([offset]) => offset % 8 === 0)).toBe(true);
+
+      const onDemand = IndexFile.openFile<{ count: number }>(path.join(dir, 'test.idx'), 'TEST');
+      expect(Array.from(onDemand.f32At('vectors', 1, 1))).toEqual([-1]);
+      expect(onDemand.strings('ids').get(1)).toBe('docs/é.md');
+      expect(onDemand.stringAt('ids', 0)).toBe('src/a.ts:1');
+      expect(onDemand.findString('ids', 'docs/é.md')).toBe(1);
+      expect(onDemand.findString('ids', 'docs/é.md', () => false)).toBe(-1);
+      expect(onDemand.findString('ids', 'src/b.ts:1')).toBe(-1);
+      expect(() => onDemand.f32At('vectors', 1, 2)).toThrow(/past the end of vectors/);
+      onDemand.close();
+      expect(() => onDemand.u32At('ids.offsets', 0, 1)).toThrow(/closed/);
+    } finally {
+      fs.rmSync(dir, { recursive: true, force: true });
+    }
+  });
+
+  it('should find strings in lists written without a hash table', () => {
+    const { ids, 'ids.offsets': offsets } = StringTable.sections('ids', ['a', 'b', 'a']);
+    const file = IndexFile.open(writeIndexFile('TEST', {}, { ids, 'ids.offsets': offsets }), 'TEST');
+    expect(file.findString('ids', 'a', i => i > 0)).toBe(2);
+    expect(file.findString('ids', 'c')).toBe(-1);
+  });
+
+  it('should read from unaligned buffers and reject other files', () => {
+    const file = writeIndexFile('TEST', {}, { vectors: new Float32Array([3, 4]) });
+    const unaligned = Buffer.alloc(file.length + 1);
+    file.copy(unaligned, 1);
+    expect(Array.from(IndexFile.open(unaligned.subarray(1), 'TEST').f32('vectors'))).toEqual([3, 4]);
+
+    expect(() => IndexFile.open(file, 'HNSW')).toThrow(/Not a HNSW index file/);
+    expect(() => IndexFile.open(file, 'TEST').u32('links')).toThrow(/no links section/);
+    expect(() => IndexFile.open(file.subarray(0, file.length - 4), 'TEST').f32('vectors')).toThrow(/truncated/);
+  });
+});
+
+describe('HnswVectorStore', () => {
+  const dirs: string[] = [];
+
+  afterEach(() => {
+    for (const dir of dirs.splice(0)) fs.rmSync(dir, { recursive: true, force: true });
+  });
+
+  function setup(count: number, minPoints: number) {
+    const dir = fs.mkdtempSync(path.join(os.tmpdir(), 'cv-hnsw-'));
+    dirs.push(dir);
+    const inner = new MemoryStore(8);
+    const random = rng(3);
+    randomVectors(count, 8, random).forEach((vector, i) => {
+      inner.points.set(String(i), { id: String(i), vector, payload: { lang: i % 10 === 0 ? 'go' : 'ts' } });
+    });
+    return { inner, dir, store: new HnswVectorStore(inner, dir, { minPoints, efSearch: 64 }) };
+  }
+
+  it('should use exact search below minPoints', async () => {
+    const { inner, dir, store } = setup(100, 1000);
+    await store.search('code', inner.points.get('5')!.vector, { limit: 5 });
+
+    expect(inner.exactSearches).toBe(1);
+    expect(fs.readdirSync(dir)).toEqual([]);
+  });
+
+  it('should build and persist a graph at minPoints', async () => {
+    const { inner, dir, store } = setup(500, 200);
+    const query = inner.points.get('5')!.vector;
+    const results = await store.search('code', query, { limit: 5 });
+
+    expect(inner.exactSearches).toBe(0);
+    expect(results[0].id).toBe('5');
+    expect(results[0].payload).toEqual({ lang: 'ts' });
+    expect(fs.existsSync(path.join(dir, 'code.hnsw'))).toBe(true);
+
+    const reopened = new HnswVectorStore(inner, dir, { minPoints: 200 });
+    expect((await reopened.search('code', query, { limit: 1 }))[0].id).toBe('5');
+    expect(inner.exactSearches).toBe(0);
+  });
+
+  it('should keep the graph in step with upserts and deletes', async () => {
+    const { inner, store } = setup(500, 200);
+    await store.search('code', [1, 0, 0, 0, 0, 0, 0, 0], { limit: 1 });
+
+    await store.upsert('code', [{ id: 'new', vector: [0, 0, 0, 0, 0, 0, 0, 1], payload: { lang: 'rs' } }]);
+    expect((await store.search('code', [0, 0, 0, 0, 0, 0, 0, 1], { limit: 1 }))[0].id).toBe('new');
+
+    await store.delete('code', ['new']);
+    const results = await store.search('code', [0, 0, 0, 0, 0, 0, 0, 1], { limit: 5 });
+    expect(results.map(r => r.id)).not.toContain('new');
+    expect(inner.exactSearches).toBe(0);
+  });
+
+  it('should filter graph results and fall back to exact search for selective filters', async () => {
+    const { inner, store } = setup(500, 200);
+    const query = inner.points.get('10')!.vector;
+
+    const go = await store.search('code', query, {
+      limit: 3,
+      filter: { must: [{ key: 'lang', match: { value: 'go' } }] }
+    });
+    expect(go.every(r => r.payload.lang === 'go')).toBe(true);
+    expect(go[0].id).toBe('10');
+
+    const before = inner.exactSearches;
+    const none = await store.search('code', query, {
+      limit: 3,
+      filter: { must: [{ key: 'lang', match: { value: 'rs' } }] }
+    });
+    expect(none).toEqual([]);
+    expect(inner.exactSearches).toBe(before + 1);
+  });
+});
@@ -0,0 +1,108 @@
+/**
+ * Hotspot Tests
+ * Tests for churn mining and churn x complexity ranking
+ */
+
+import { describe, it, expect } from 'vitest';
+import {
+  buildHotspotPrompt,
+  CHURN_LOG_FORMAT,
+  collectHotspots,
+  fileChurn,
+  parseChurnLog,
+  rankFunctionHotspots,
+  rankHotspots,
+} from '../../packages/core/src/git/hotspots.js';
+import type { ComplexityEntry } from '../../packages/core/src/graph/complexity.js';
+
+const R = '\x1e';
+const F = '\x1f';
+
+const log = [
+  `${R}a1${F}Ada${F}ada@example.com${F}1700000300${F}Fix overflow in fourier_transform\n\n12\t4\tsrc/dsp.rs\n1\t1\tREADME.md`,
+  `${R}b2${F}Bob${F}bob@example.com${F}1700000200${F}Speed up window\n\n30\t2\tsrc/dsp.rs\n5\t0\tsrc/io.rs`,
+  `${R}c3${F}Ada${F}ADA@example.com${F}1700000100${F}Move reader\n\n-\t-\tassets/logo.png\n3\t3\tsrc/{old => }/io.rs`,
+].join('\n');
+
+function fn(name: string, file: string, cyclomatic: number, cognitive: number | null = cyclomatic): ComplexityEntry {
+  return { qualifiedName: `${file}:${name}`, name, kind: 'function', file, startLine: 1, endLine: 10, lines: 10, cyclomatic, cognitive };
+}
+
+const functions = [
+  fn('fourier_transform', 'src/dsp.rs', 18, 31),
+  fn('window', 'src/dsp.rs', 2),
+  fn('read', 'src/io.rs', 6, null),
+];
+
+describe('parseChurnLog', () => {
+  it('parses commits, numstat lines and renames', () => {
+    const commits = parseChurnLog(log);
+    expect(commits).toHaveLength(3);
+    expect(commits[0]).toMatchObject({ sha: 'a1', author: 'Ada', subject: 'Fix overflow in fourier_transform', date: 1700000300000 });
+    expect(commits[0].files).toEqual([
+      { path: 'src/dsp.rs', added: 12, deleted: 4 },
+      { path: 'README.md', added: 1, deleted: 1 },
+    ]);
+    expect(commits[2].files).toEqual([
+      { path: 'assets/logo.png', added: 0, deleted: 0 },
+      { path: 'src/io.rs', added: 3, deleted: 3 },
+    ]);
+  });
+
+  it('matches the format git is asked for', () => {
+    expect(CHURN_LOG_FORMAT).toBe(`${R}%H${F}%an${F}%ae${F}%ct${F}%s`);
+  });
+});
+
+describe('fileChurn', () => {
+  it('counts commits, distinct authors and fix commits per file', () => {
+    const churn = Object.fromEntries(fileChurn(parseChurnLog(log)).map(c => [c.file, c]));
+    expect(churn['src/dsp.rs']).toMatchObject({ commits: 2, authors: 2, fixes: 1, linesChanged: 48, lastChanged: 1700000300000 });
+    // Author emails compare case-insensitively
+    expect(churn['src/io.rs']).toMatchObject({ commits: 2, authors: 2, fixes: 0 });
+  });
+});
+
+describe('rankHotspots', () => {
+  it('multiplies churn by complexity and scales to 100', () => {
+    const hotspots = rankHotspots(fileChurn(parseChurnLog(log)), functions);
+    // Files without indexed functions are left out
+    expect(hotspots.map(h => h.file)).toEqual(['src/dsp.rs', 'src/io.rs']);
+    expect(hotspots[0]).toMatchObject({ score: 100, complexity: 20, functions: 2, churn: 6 });
+    // io.rs: churn (2 + 0) * 2 = 4, complexity 6 => 24 / 120
+    expect(hotspots[1]).toMatchObject({ score: 20, complexity: 6, churn: 4 });
+  });
+
+  it('ranks functions in hotspot files by file churn and their own complexity', () => {
+    const hotspots = rankHotspots(fileChurn(parseChurnLog(log)), functions);
+    const ranked = rankFunctionHotspots(hotspots, functions);
+    expect(ranked.map(f => f.name)).toEqual(['fourier_transform', 'read', 'window']);
+    expect(ranked[0].score).toBe(100);
+  });
+});
+
+describe('collectHotspots', () => {
+  it('mines git and the graph', async () => {
+    const calls: any[] = [];
+    const git = { getChurnHistory: async (options: any) => { calls.push(options); return parseChurnLog(log); } };
+    const graph = {
+      query: async () => functions.map(f => ({ ...f, complexity: f.cyclomatic })),
+    };
+    const result = await collectHotspots(git as any, graph as any, { since: '6 months ago', file: 'src/' });
+    expect(calls[0]).toEqual({ since: '6 months ago', maxCount: undefined, paths: ['src/'] });
+    expect(result.commits).toBe(3);
+    expect(result.files[0].file).toBe('src/dsp.rs');
+    expect(result.functions[0].name).toBe('fourier_transform');
+  });
+});
+
+describe('buildHotspotPrompt', () => {
+  it('lists hotspot files and functions for the reviewer', () => {
+    const hotspots = rankHotspots(fileChurn(parseChurnLog(log)), functions);
+    const prompt = buildHotspotPrompt(hotspots.slice(0, 1), rankFunctionHotspots(hotspots, functions).slice(0, 1));
+    expect(prompt).toContain('## Hotspots');
+    expect(prompt).toContain('src/dsp.rs (risk 100/100: 2 commits, 1 bug fixes, 2 authors, complexity 20)');
+    expect(prompt).toContain('fourier_transform (src/dsp.rs:1-10, cyclomatic 18, cognitive 31)');
+    expect(buildHotspotPrompt([])).toBe('');
+  });
+});
@@ -0,0 +1,73 @@
+/**
+ * Index Drift Detection Tests
+ */
+
+import { describe, it, expect, beforeEach, afterEach } from 'vitest';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { detectIndexDrift } from '../../packages/core/src/sync/drift.js';
+
+function chunk(file: string, lastModified: number): any {
+  return {
+    id: `${file}:1`,
+    score: 1,
+    payload: { file, language: 'typescript', startLine: 1, endLine: 1, text: '', imports: [], lastModified }
+  };
+}
+
+describe('Index Drift Detection', () => {
+  let repoRoot: string;
+
+  beforeEach(() => {
+    repoRoot = fs.mkdtempSync(path.join(os.tmpdir(), 'cv-drift-'));
+    fs.writeFileSync(path.join(repoRoot, 'fresh.ts'), 'export const a = 1;\n');
+    fs.writeFileSync(path.join(repoRoot, 'stale.ts'), 'export const b = 2;\n');
+  });
+
+  afterEach(() => {
+    fs.rmSync(repoRoot, { recursive: true, force: true });
+  });
+
+  it('should report files modified or deleted after indexing', async () => {
+    const future = Date.now() + 60_000;
+    const past = Date.now() - 60_000;
+
+    const drift = await detectIndexDrift(repoRoot, {
+      chunks: [chunk('fresh.ts', future), chunk('stale.ts', past), chunk('gone.ts', past)],
+      files: []
+    });
+
+    expect(drift.map(d => [d.file, d.reason])).toEqual([
+      ['stale.ts', 'modified'],
+      ['gone.ts', 'deleted']
+    ]);
+  });
+
+  it('should use the oldest chunk of a file', async () => {
+    const drift = await detectIndexDrift(repoRoot, {
+      chunks: [chunk('stale.ts', Date.now() + 60_000), chunk('stale.ts', Date.now() - 60_000)],
+      files: []
+    });
+    expect(drift).toHaveLength(1);
+  });
+
+  it('should ignore touched files whose content hash still matches', async () => {
+    const git: any = {
+      getWorkingTreeHash: async (file: string) => (file === 'stale.ts' ? 'abc123' : 'other')
+    };
+    const files: any[] = [
+      { path: 'stale.ts', gitHash: 'abc123' },
+      { path: 'fresh.ts', gitHash: 'def456' }
+    ];
+    const past = Date.now() - 60_000;
+
+    const drift = await detectIndexDrift(
+      repoRoot,
+      { chunks: [chunk('stale.ts', past), chunk('fresh.ts', past)], files },
+      { git }
+    );
+
+    expect(drift.map(d => d.file)).toEqual(['fresh.ts']);
+  });
+});
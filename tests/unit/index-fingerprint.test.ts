@@ -0,0 +1,74 @@
+/**
+ * Shared Index Fingerprint Tests
+ */
+
+import { describe, it, expect, beforeEach, afterEach } from 'vitest';
+import { execFileSync } from 'child_process';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import {
+  computeFingerprint,
+  checkIndexCompatibility,
+  normalizeRemoteUrl
+} from '../../packages/core/src/storage/fingerprint.js';
+
+function git(cwd: string, ...args: string[]): string {
+  return execFileSync('git', args, { cwd, encoding: 'utf-8' }).trim();
+}
+
+function commit(cwd: string, message: string): void {
+  fs.writeFileSync(path.join(cwd, 'file.txt'), message);
+  git(cwd, 'add', '.');
+  git(cwd, '-c', 'user.name=test', '-c', 'user.email=test@example.com', 'commit', '-qm', message);
+}
+
+function manifest(fingerprint: any, model = 'openai/text-embedding-3-small'): any {
+  return { fingerprint, embedding: { provider: 'openrouter', model, dimensions: 1536 } };
+}
+
+describe('Index fingerprints', () => {
+  let repo: string;
+
+  beforeEach(() => {
+    repo = fs.mkdtempSync(path.join(os.tmpdir(), 'cv-fingerprint-'));
+    git(repo, 'init', '-q');
+    git(repo, 'remote', 'add', 'origin', 'git@github.com:Acme/widgets.git');
+    commit(repo, 'first');
+  });
+
+  afterEach(() => {
+    fs.rmSync(repo, { recursive: true, force: true });
+  });
+
+  it('should normalize equivalent remote URLs', () => {
+    const expected = 'github.com/Acme/widgets';
+    expect(normalizeRemoteUrl('git@github.com:Acme/widgets.git')).toBe(expected);
+    expect(normalizeRemoteUrl('https://GitHub.com/Acme/widgets')).toBe(expected);
+    expect(normalizeRemoteUrl('ssh://git@github.com:22/Acme/widgets.git/')).toBe(expected);
+  });
+
+  it('should report how far behind a matching index is', () => {
+    const indexed = computeFingerprint(repo);
+    commit(repo, 'second');
+    commit(repo, 'third');
+
+    const result = checkIndexCompatibility(repo, manifest(indexed), { embeddingModel: 'text-embedding-3-small' });
+    expect(result.compatible).toBe(true);
+    expect(result.commitsBehind).toBe(2);
+  });
+
+  it('should refuse indexes from another repository or embedding model', () => {
+    const foreign = { ...computeFingerprint(repo), rootCommit: 'f'.repeat(40), remote: 'github.com/other/repo' };
+    const result = checkIndexCompatibility(repo, manifest(foreign), { embeddingModel: 'nomic-embed-text' });
+
+    expect(result.compatible).toBe(false);
+    expect(result.problems).toHaveLength(3);
+  });
+
+  it('should warn but allow indexes without a fingerprint', () => {
+    const result = checkIndexCompatibility(repo, manifest(undefined));
+    expect(result.compatible).toBe(true);
+    expect(result.warnings[0]).toContain('no fingerprint');
+  });
+});
@@ -0,0 +1,251 @@
+/**
+ * Shared Index Tests
+ * Tests for cv index serve, the remote store that queries it and the local overlay layered on top
+ */
+
+import { describe, it, expect, beforeAll, afterAll, beforeEach } from 'vitest';
+import * as http from 'http';
+import * as os from 'os';
+import * as path from 'path';
+import { promises as fs } from 'fs';
+import type { AddressInfo } from 'net';
+import {
+  INDEX_PROTOCOL_VERSION,
+  isAuthorized,
+  startIndexServer,
+  SharedSyncState,
+} from '../../packages/core/src/vector/index-server.js';
+import { RemoteVectorStore } from '../../packages/core/src/vector/stores/remote-store.js';
+import { LayeredVectorStore } from '../../packages/core/src/vector/stores/layered-store.js';
+import {
+  cosineSimilarity,
+  matchesFilter,
+  VectorFilter,
+  VectorPoint,
+  VectorStore,
+} from '../../packages/core/src/vector/store.js';
+
+class MemoryStore implements VectorStore {
+  readonly type = 'sqlite-vec' as const;
+  collections = new Map<string, Map<string, VectorPoint>>();
+
+  async connect() {}
+  async close() {}
+  async listCollections() { return [...this.collections.keys()]; }
+  async createCollection(name: string) {
+    if (!this.collections.has(name)) this.collections.set(name, new Map());
+  }
+  async deleteCollection(name: string) { this.collections.delete(name); }
+  async getCollectionStats(name: string) {
+    const c = this.collections.get(name);
+    return c ? { vectorSize: 2, pointsCount: c.size } : null;
+  }
+  async upsert(name: string, points: VectorPoint[]) {
+    for (const p of points) this.collections.get(name)!.set(String(p.id), p);
+  }
+  async delete(name: string, ids: Array<string | number>) {
+    for (const id of ids) this.collections.get(name)?.delete(String(id));
+  }
+  async retrieve(name: string, ids: Array<string | number>) {
+    const points = this.collections.get(name);
+    return ids.map(id => points?.get(String(id))).filter((p): p is VectorPoint => !!p);
+  }
+  async search(name: string, vector: number[], options: { limit: number; filter?: VectorFilter }) {
+    return [...this.collections.get(name)!.values()]
+      .filter(p => matchesFilter(p.payload, options.filter))
+      .map(p => ({ id: p.id, score: cosineSimilarity(vector, p.vector), payload: p.payload }))
+      .sort((a, b) => b.score - a.score)
+      .slice(0, options.limit);
+  }
+  async scroll(name: string, options: { limit: number; offset?: string; filter?: VectorFilter }) {
+    const all = [...this.collections.get(name)!.values()].filter(p => matchesFilter(p.payload, options.filter));
+    const start = options.offset ? parseInt(options.offset, 10) : 0;
+    const end = start + options.limit;
+    return { points: all.slice(start, end), nextOffset: end < all.length ? String(end) : undefined };
+  }
+}
+
+function sharedStore(): MemoryStore {
+  const store = new MemoryStore();
+  store.collections.set('code_chunks', new Map([
+    ['a', { id: 'a', vector: [1, 0], payload: { file: 'src/auth.ts', branches: ['main'] } }],
+    ['b', { id: 'b', vector: [0.8, 0.6], payload: { file: 'src/db.ts', branches: ['main'] } }],
+    ['c', { id: 'c', vector: [0, 1], payload: { file: 'src/ui.ts', branches: ['main'] } }],
+    ['old', { id: 'old', vector: [1, 0], payload: { file: 'src/auth.ts', branches: ['release'] } }],
+  ]));
+  return store;
+}
+
+const state: SharedSyncState = {
+  commit: 'abc123',
+  dirtyFiles: [],
+  files: [{ path: 'src/auth.ts', contentHash: 'h1', size: 10, type: 'code' }],
+};
+
+async function allPoints(store: VectorStore, name: string, limit = 2): Promise<string[]> {
+  const ids: string[] = [];
+  let offset: string | undefined;
+  do {
+    const page = await store.scroll(name, { limit, offset, withVector: false });
+    ids.push(...page.points.map(p => String(p.id)));
+    offset = page.nextOffset;
+  } while (offset);
+  return ids.sort();
+}
+
+describe('isAuthorized', () => {
+  it('accepts only a bearer token it knows', () => {
+    expect(isAuthorized('Bearer s3cret', ['other', 's3cret'])).toBe(true);
+    expect(isAuthorized('bearer s3cret', ['s3cret'])).toBe(true);
+    expect(isAuthorized('Bearer wrong', ['s3cret'])).toBe(false);
+    expect(isAuthorized('s3cret', ['s3cret'])).toBe(false);
+    expect(isAuthorized(undefined, ['s3cret'])).toBe(false);
+  });
+});
+
+describe('cv index serve', () => {
+  let server: http.Server;
+  let url: string;
+
+  beforeAll(async () => {
+    server = await startIndexServer({
+      store: sharedStore(),
+      tokens: ['s3cret'],
+      scope: { must: [{ key: 'branches', match: { value: 'main' } }] },
+      info: async () => ({ version: INDEX_PROTOCOL_VERSION, storeType: 'sqlite-vec', commit: 'abc123', embedding: { model: 'm', dimensions: 2 } }),
+      state: async () => state,
+      host: '127.0.0.1',
+      port: 0,
+    });
+    url = `http://127.0.0.1:${(server.address() as AddressInfo).port}`;
+  });
+
+  afterAll(() => {
+    server.close();
+  });
+
+  it('serves the index to a client with the token', async () => {
+    const remote = new RemoteVectorStore({ url: `${url}/`, token: 's3cret' });
+    await remote.connect();
+    expect(remote.type).toBe('sqlite-vec');
+    expect(remote.info().commit).toBe('abc123');
+    expect(await remote.syncState()).toEqual(state);
+    expect(await remote.listCollections()).toEqual(['code_chunks']);
+    expect(await remote.getCollectionStats('code_chunks')).toEqual({ vectorSize: 2, pointsCount: 4 });
+    expect(await remote.getCollectionStats('missing')).toBeNull();
+
+    const hits = await remote.search('code_chunks', [1, 0], { limit: 2 });
+    expect(hits.map(h => h.id)).toEqual(['a', 'b']);
+  });
+
+  it('only serves the scoped branch', async () => {
+    const remote = new RemoteVectorStore({ url, token: 's3cret' });
+    await remote.connect();
+    expect(await allPoints(remote, 'code_chunks')).toEqual(['a', 'b', 'c']);
+    expect(await remote.retrieve('code_chunks', ['a', 'old'])).toHaveLength(1);
+  });
+
+  it('rejects clients without the token', async () => {
+    await expect(new RemoteVectorStore({ url, token: 'guess' }).connect()).rejects.toThrow('rejected the token');
+    await expect(new RemoteVectorStore({ url }).connect()).rejects.toThrow('rejected the token');
+  });
+
+  it('is read-only', async () => {
+    const remote = new RemoteVectorStore({ url, token: 's3cret' });
+    await remote.connect();
+    await expect(remote.upsert()).rejects.toThrow('read-only');
+    await expect(remote.createCollection('new_collection')).rejects.toThrow('read-only');
+    await expect(remote.createCollection('code_chunks')).resolves.toBeUndefined();
+
+    const res = await fetch(`${url}/v1/collections/code_chunks`, { method: 'DELETE', headers: { Authorization: 'Bearer s3cret' } });
+    expect(res.status).toBe(405);
+  });
+
+  it('rejects a malformed collection name with 400', async () => {
+    const res = await fetch(`${url}/v1/collections/%E0%A4%A`, { headers: { Authorization: 'Bearer s3cret' } });
+    expect(res.status).toBe(400);
+    expect(await res.json()).toEqual({ error: 'Malformed collection name' });
+  });
+});
+
+describe('LayeredVectorStore', () => {
+  let dir: string;
+  let overlayFile: string;
+
+  beforeEach(async () => {
+    dir = await fs.mkdtemp(path.join(os.tmpdir(), 'cv-overlay-'));
+    overlayFile = path.join(dir, 'overlay.json');
+  });
+
+  async function layered(base: VectorStore = sharedStore()): Promise<LayeredVectorStore> {
+    const store = new LayeredVectorStore(base, overlayFile);
+    await store.connect();
+    return store;
+  }
+
+  it('searches local changes on top of the shared index', async () => {
+    const store = await layered();
+    // src/auth.ts was edited: its shared chunk goes, the new one is local
+    await store.delete('code_chunks', ['a']);
+    await store.upsert('code_chunks', [{ id: 'a2', vector: [0.9, 0.1], payload: { file: 'src/auth.ts' } }]);
+
+    const hits = await store.search('code_chunks', [1, 0], { limit: 3 });
+    expect(hits.map(h => h.id)).toEqual(['old', 'a2', 'b']);
+    expect(hits.find(h => h.id === 'a')).toBeUndefined();
+
+    const filtered = await store.search('code_chunks', [1, 0], { limit: 3, filter: { must: [{ key: 'file', match: { value: 'src/auth.ts' } }] } });
+    expect(filtered.map(h => h.id)).toEqual(['old', 'a2']);
+  });
+
+  it('lets a local point replace a shared one with the same id', async () => {
+    const store = await layered();
+    await store.upsert('code_chunks', [{ id: 'c', vector: [1, 0], payload: { file: 'src/ui.ts', edited: true } }]);
+    const hits = await store.search('code_chunks', [1, 0], { limit: 10 });
+    expect(hits.filter(h => h.id === 'c')).toHaveLength(1);
+    expect(hits.find(h => h.id === 'c')!.payload.edited).toBe(true);
+    expect((await store.retrieve('code_chunks', ['c', 'b'])).map(p => p.id)).toEqual(['c', 'b']);
+  });
+
+  it('scrolls through shared and local points without the hidden ones', async () => {
+    const store = await layered();
+    await store.delete('code_chunks', ['b']);
+    await store.upsert('code_chunks', [
+      { id: 'n1', vector: [1, 1], payload: { file: 'src/new.ts' } },
+      { id: 'n2', vector: [1, 1], payload: { file: 'src/new.ts' } },
+      { id: 'n3', vector: [1, 1], payload: { file: 'src/new.ts' } },
+    ]);
+    expect(await allPoints(store, 'code_chunks')).toEqual(['a', 'c', 'n1', 'n2', 'n3', 'old']);
+    expect(await store.getCollectionStats('code_chunks')).toEqual({ vectorSize: 2, pointsCount: 6 });
+  });
+
+  it('never writes to the shared index', async () => {
+    const base = sharedStore();
+    const store = await layered(base);
+    await store.delete('code_chunks', ['a', 'b']);
+    await store.upsert('code_chunks', [{ id: 'x', vector: [1, 0], payload: {} }]);
+    await store.createCollection('commits', 2);
+    expect(base.collections.get('code_chunks')!.size).toBe(4);
+    expect(base.collections.has('commits')).toBe(false);
+    expect(await store.listCollections()).toEqual(['code_chunks', 'commits']);
+    await expect(store.deleteCollection('code_chunks')).rejects.toThrow('shared index');
+  });
+
+  it('keeps the overlay across runs and starts over on rebase', async () => {
+    const first = await layered();
+    await first.rebase('abc123');
+    await first.delete('code_chunks', ['a']);
+    await first.upsert('code_chunks', [{ id: 'a2', vector: [1, 0], payload: {} }]);
+    await first.close();
+
+    const second = await layered();
+    const ids = async () => (await second.search('code_chunks', [1, 0], { limit: 10 })).map(h => String(h.id)).sort();
+    expect(second.getBaseCommit()).toBe('abc123');
+    expect(second.overlaySize()).toEqual({ points: 1, removed: 1 });
+    expect(await ids()).toEqual(['a2', 'b', 'c', 'old']);
+
+    await second.rebase('def456');
+    expect(second.getBaseCommit()).toBe('def456');
+    expect(second.overlaySize()).toEqual({ points: 0, removed: 0 });
+    expect(await ids()).toEqual(['a', 'b', 'c', 'old']);
+  });
+});
@@ -0,0 +1,110 @@
+/**
+ * JSON Output Tests
+ * Tests for the versioned --json/--jsonl documents and exit codes
+ */
+
+import { describe, it, expect, vi, beforeEach, afterEach } from 'vitest';
+import {
+  createJsonReporter,
+  ExitCode,
+  JSON_SCHEMA_VERSION,
+  JsonReporter,
+} from '../../packages/cli/src/utils/output.js';
+
+describe('JsonReporter', () => {
+  const log = console.log;
+  const info = console.info;
+  let written: string[];
+
+  beforeEach(() => {
+    written = [];
+    vi.spyOn(process.stdout, 'write').mockImplementation((chunk: any) => {
+      written.push(String(chunk));
+      return true;
+    });
+    process.exitCode = undefined;
+  });
+
+  afterEach(() => {
+    vi.restoreAllMocks();
+    console.log = log;
+    console.info = info;
+    process.exitCode = undefined;
+  });
+
+  it('prints one versioned document with --json and skips events', () => {
+    const report = new JsonReporter('find', 'json');
+    report.event('match', { file: 'a.ts' });
+    report.result({ results: [] });
+
+    expect(written).toHaveLength(1);
+    expect(JSON.parse(written[0])).toEqual({
+      schemaVersion: JSON_SCHEMA_VERSION,
+      command: 'find',
+      ok: true,
+      exitCode: 0,
+      data: { results: [] },
+    });
+    expect(process.exitCode).toBe(ExitCode.Success);
+  });
+
+  it('streams one event per line with --jsonl, ending with the result', () => {
+    const report = new JsonReporter('review', 'jsonl');
+    report.event('finding', { id: 'f1' });
+    report.result({ findings: [{ id: 'f1' }] }, ExitCode.CheckFailed);
+
+    expect(written.every(line => line.endsWith('\n') && !line.slice(0, -1).includes('\n'))).toBe(true);
+    expect(written.map(line => JSON.parse(line))).toEqual([
+      { schemaVersion: 1, command: 'review', type: 'finding', data: { id: 'f1' } },
+      { schemaVersion: 1, command: 'review', type: 'result', ok: false, exitCode: 1, data: { findings: [{ id: 'f1' }] } },
+    ]);
+    expect(process.exitCode).toBe(ExitCode.CheckFailed);
+  });
+
+  it('exits with an error document on failure', () => {
+    const exit = vi.spyOn(process, 'exit').mockImplementation((() => { throw new Error('exit'); }) as any);
+    const report = new JsonReporter('explain', 'json');
+
+    expect(() => report.fail('Not in a CV-Git repository', 'NOT_INITIALIZED')).toThrow('exit');
+    expect(exit).toHaveBeenCalledWith(ExitCode.Error);
+    expect(JSON.parse(written[0])).toEqual({
+      schemaVersion: 1,
+      command: 'explain',
+      ok: false,
+      exitCode: 2,
+      error: { code: 'NOT_INITIALIZED', message: 'Not in a CV-Git repository' },
+    });
+  });
+
+  it('turns console.log progress into log events with --jsonl', () => {
+    const report = createJsonReporter('sync', { jsonl: true })!;
+    report.captureLogs();
+    console.log('\u001b[32mParsed %d files\u001b[39m', 12);
+    console.log('');
+
+    expect(written.map(line => JSON.parse(line))).toEqual([
+      { schemaVersion: 1, command: 'sync', type: 'log', data: { message: 'Parsed 12 files' } },
+    ]);
+  });
+});
+
+describe('createJsonReporter', () => {
+  const log = console.log;
+  const info = console.info;
+
+  afterEach(() => {
+    console.log = log;
+    console.info = info;
+  });
+
+  it('returns null without --json or --jsonl', () => {
+    expect(createJsonReporter('usage', {})).toBeNull();
+    expect(console.log).toBe(log);
+  });
+
+  it('prefers --jsonl and moves console.log to stderr', () => {
+    const report = createJsonReporter('usage', { json: true, jsonl: true });
+    expect(report?.mode).toBe('jsonl');
+    expect(console.log).toBe(console.error);
+  });
+});
@@ -0,0 +1,100 @@
+/**
+ * JSONL Vector Store Tests
+ * Tests for the file-backed embedded store: persistence, search, scrolling and compaction
+ */
+
+import { describe, it, expect, beforeEach, afterEach } from 'vitest';
+import { promises as fs } from 'fs';
+import * as path from 'path';
+import * as os from 'os';
+import { JsonlVectorStore } from '../../packages/core/src/vector/stores/jsonl-store.js';
+
+describe('JsonlVectorStore', () => {
+  let dataDir: string;
+  let store: JsonlVectorStore;
+
+  async function reopen(): Promise<JsonlVectorStore> {
+    await store.close();
+    store = new JsonlVectorStore({ dataDir });
+    await store.connect();
+    return store;
+  }
+
+  beforeEach(async () => {
+    dataDir = await fs.mkdtemp(path.join(os.tmpdir(), 'cv-jsonl-store-test-'));
+    store = new JsonlVectorStore({ dataDir });
+    await store.connect();
+  });
+
+  afterEach(async () => {
+    await store.close();
+    await fs.rm(dataDir, { recursive: true, force: true });
+  });
+
+  it('should persist upserts and deletes across reopens', async () => {
+    await store.createCollection('code/chunks', 2);
+    await store.upsert('code/chunks', [
+      { id: 'a', vector: [1, 0], payload: { file: 'a.ts' } },
+      { id: 2, vector: [0, 1], payload: { file: 'b.ts' } }
+    ]);
+    await store.upsert('code/chunks', [{ id: 'a', vector: [0.5, 0.5], payload: { file: 'a2.ts' } }]);
+    await store.delete('code/chunks', [2, 'missing']);
+
+    await reopen();
+    expect(await store.listCollections()).toEqual(['code/chunks']);
+    expect(await store.getCollectionStats('code/chunks')).toEqual({ vectorSize: 2, pointsCount: 1 });
+    expect(await store.retrieve('code/chunks', ['a', 2], { withVector: true })).toEqual([
+      { id: 'a', vector: [0.5, 0.5], payload: { file: 'a2.ts' } }
+    ]);
+  });
+
+  it('should rank by cosine similarity and apply filters', async () => {
+    await store.createCollection('c', 2);
+    await store.upsert('c', [
+      { id: 'x', vector: [1, 0], payload: { language: 'go' } },
+      { id: 'y', vector: [1, 1], payload: { language: 'rust' } },
+      { id: 'z', vector: [0, 1], payload: { language: 'rust' } }
+    ]);
+
+    const all = await store.search('c', [1, 0.1], { limit: 2 });
+    expect(all.map(r => r.id)).toEqual(['x', 'y']);
+    const rust = await store.search('c', [1, 0.1], { limit: 5, filter: { must: [{ key: 'language', match: { value: 'rust' } }] } });
+    expect(rust.map(r => r.id)).toEqual(['y', 'z']);
+  });
+
+  it('should scroll every point once in pages', async () => {
+    await store.createCollection('c', 1);
+    await store.upsert('c', Array.from({ length: 7 }, (_, i) => ({ id: String(i), vector: [i + 1], payload: { i } })));
+
+    const seen: string[] = [];
+    let offset: string | undefined;
+    do {
+      const page = await store.scroll('c', { limit: 3, offset, withVector: false });
+      seen.push(...page.points.map(p => String(p.id)));
+      offset = page.nextOffset;
+    } while (offset);
+    expect(seen).toEqual(['0', '1', '2', '3', '4', '5', '6']);
+  });
+
+  it('should reject vectors of the wrong size', async () => {
+    await store.createCollection('c', 3);
+    await expect(store.upsert('c', [{ id: 'a', vector: [1, 2], payload: {} }])).rejects.toThrow('expected 3 dimensions');
+  });
+
+  it('should ignore a torn last line and drop superseded points on compact', async () => {
+    await store.createCollection('c', 1);
+    for (let i = 0; i < 5; i++) {
+      await store.upsert('c', [{ id: 'a', vector: [i + 1], payload: { i } }]);
+    }
+    const log = path.join(dataDir, 'c.jsonl');
+    await fs.appendFile(log, '{"upsert":[{"id":"b"');
+
+    await reopen();
+    expect(await store.retrieve('c', ['a', 'b'])).toEqual([{ id: 'a', vector: [], payload: { i: 4 } }]);
+
+    await store.compact();
+    expect((await fs.readFile(log, 'utf-8')).trim().split('\n')).toHaveLength(2);
+    await reopen();
+    expect(await store.getCollectionStats('c')).toEqual({ vectorSize: 1, pointsCount: 1 });
+  });
+});
@@ -0,0 +1,129 @@
+/**
+ * Keyword Index Tests
+ * Tests for BM25 tokenisation and ranking, rank fusion, and hybrid retrieval
+ */
+
+import { describe, it, expect, afterEach } from 'vitest';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import {
+  KeywordIndex,
+  KeywordDocument,
+  tokenize,
+  reciprocalRankFusion,
+  loadKeywordIndex,
+  saveKeywordIndex
+} from '../../packages/core/src/context/keyword-index.js';
+import { AIManager } from '../../packages/core/src/ai/index.js';
+
+function doc(id: string, file: string, text: string, symbolName?: string): KeywordDocument {
+  return { id, file, language: 'python', symbolName, startLine: 1, endLine: 5, text };
+}
+
+const docs = [
+  doc('a', 'ml/optim.py', 'def gradient_descent(params, lr):\n    return params - lr * grad(params)', 'gradient_descent'),
+  doc('b', 'ml/linalg.py', 'def matrix_inverse(m):\n    # gradient free\n    return solve(m, identity(m))', 'matrix_inverse'),
+  doc('c', 'ml/stats.py', 'def descent_rate(values):\n    return mean(values) / std(values)', 'descent_rate'),
+  doc('d', 'web/server.py', 'class HTTPServer:\n    def handle_request(self):\n        pass', 'HTTPServer')
+];
+
+describe('tokenize', () => {
+  it('should keep identifiers whole and split them into words', () => {
+    expect(tokenize('gradient_descent')).toEqual(['gradient_descent', 'gradient', 'descent']);
+    expect(tokenize('parseHTTPServer')).toEqual(['parsehttpserver', 'parse', 'http', 'server']);
+    expect(tokenize('a + b == 42')).toEqual(['42']);
+  });
+});
+
+describe('KeywordIndex', () => {
+  it('should rank the chunk containing an exact identifier first', () => {
+    const index = new KeywordIndex(docs);
+    const hits = index.search('gradient_descent', 3);
+
+    expect(hits[0].doc.id).toBe('a');
+    expect(hits.map(h => h.doc.id)).toContain('c');
+    expect(hits[0].score).toBeGreaterThan(hits[1].score);
+  });
+
+  it('should match camelCase parts and return nothing for unknown terms', () => {
+    const index = new KeywordIndex(docs);
+    expect(index.search('http server', 1)[0].doc.id).toBe('d');
+    expect(index.search('kubernetes', 5)).toEqual([]);
+  });
+
+  it('should replace and remove chunks by file', () => {
+    const index = new KeywordIndex(docs);
+    index.search('gradient', 1);
+
+    expect(index.removeFiles(['ml/optim.py'])).toBe(1);
+    expect(index.search('gradient_descent', 5).map(h => h.doc.id)).not.toContain('a');
+
+    index.add([doc('e', 'ml/optim.py', 'def adam(params): pass', 'adam')]);
+    expect(index.size).toBe(4);
+    expect(index.search('adam', 1)[0].doc.id).toBe('e');
+  });
+
+  describe('persistence', () => {
+    const dirs: string[] = [];
+    afterEach(() => {
+      for (const dir of dirs.splice(0)) fs.rmSync(dir, { recursive: true, force: true });
+    });
+
+    it('should round-trip through .cv/keyword-index.json', async () => {
+      const repoRoot = fs.mkdtempSync(path.join(os.tmpdir(), 'cv-keywords-'));
+      dirs.push(repoRoot);
+
+      expect((await loadKeywordIndex(repoRoot)).size).toBe(0);
+      await saveKeywordIndex(repoRoot, new KeywordIndex(docs));
+
+      const loaded = await loadKeywordIndex(repoRoot);
+      expect(loaded.size).toBe(4);
+      expect(loaded.search('matrix_inverse', 1)[0].doc.id).toBe('b');
+    });
+  });
+});
+
+describe('reciprocalRankFusion', () => {
+  it('should favour items ranked well in both lists and scale the best possible to 1', () => {
+    const fused = reciprocalRankFusion([['x', 'y', 'z'], ['y', 'w']], id => id);
+
+    expect(fused.map(f => f.item)).toEqual(['y', 'x', 'w', 'z']);
+    expect(reciprocalRankFusion([['x'], ['x']], id => id)[0].score).toBeCloseTo(1);
+  });
+});
+
+describe('hybrid gatherContext', () => {
+  const vectorHit = (id: string, file: string, score: number) => ({
+    id,
+    score,
+    payload: { id, file, language: 'python', startLine: 1, endLine: 5, text: '', imports: [], lastModified: 0 }
+  });
+
+  function createAI(vectorResults: any[], hybrid?: boolean): AIManager {
+    const vector: any = { searchCode: async () => vectorResults };
+    const ai = new AIManager(
+      { provider: 'anthropic', model: 'test-model', apiKey: 'sk-test', retrieval: { hybrid } },
+      vector
+    );
+    ai.setKeywordIndex(new KeywordIndex(docs));
+    return ai;
+  }
+
+  it('should pull in exact identifier matches that vector search missed', async () => {
+    const ai = createAI([vectorHit('b', 'ml/linalg.py', 0.8), vectorHit('c', 'ml/stats.py', 0.7)]);
+    const context = await ai.gatherContext('gradient_descent', { maxChunks: 3 });
+
+    expect(context.chunks.map(c => c.id)).toEqual(['b', 'c', 'a']);
+    expect(context.chunks[2].payload.file).toBe('ml/optim.py');
+    expect(context.chunks.every(c => c.score <= 1)).toBe(true);
+  });
+
+  it('should keep vector results alone when hybrid is off', async () => {
+    const ai = createAI([vectorHit('b', 'ml/linalg.py', 0.8)], false);
+    const context = await ai.gatherContext('gradient_descent', { maxChunks: 3 });
+
+    expect(context.chunks.map(c => c.id)).toEqual(['b']);
+    expect(context.chunks[0].score).toBe(0.8);
+  });
+});
@@ -0,0 +1,73 @@
+/**
+ * Language Pack Registry Tests
+ */
+
+import { describe, it, expect } from 'vitest';
+import {
+  registerLanguagePack,
+  getLanguagePack,
+  getLanguagePackForExtension,
+  getChunkingRules,
+  getDefaultIndexedLanguages,
+  isTestFile
+} from '../../packages/core/src/parser/language-packs.js';
+import { createSimpleParsers } from '../../packages/core/src/parser/simple.js';
+import { detectLanguage } from '../../packages/shared/src/utils.js';
+
+describe('Language Packs', () => {
+  it('should resolve packs by extension', () => {
+    expect(getLanguagePackForExtension('.rs')?.id).toBe('rust');
+    expect(getLanguagePackForExtension('.TSX')?.id).toBe('typescript');
+    expect(getLanguagePackForExtension('.unknown')).toBeUndefined();
+  });
+
+  it('should apply default chunking rules', () => {
+    const rules = getChunkingRules('python');
+    expect(rules.symbolKinds).toEqual(['function', 'method', 'class']);
+    expect(rules.wholeFileMaxLines).toBe(200);
+  });
+
+  it('should keep the historical default indexed languages', () => {
+    const languages = getDefaultIndexedLanguages();
+    expect(languages).toEqual(expect.arrayContaining(['typescript', 'javascript', 'python', 'go', 'rust']));
+    expect(languages).not.toContain('java');
+  });
+
+  it('should detect test files per language', () => {
+    expect(isTestFile('src/engine.test.ts')).toBe(true);
+    expect(isTestFile('pkg/server_test.go')).toBe(true);
+    expect(isTestFile('app/test_models.py')).toBe(true);
+    expect(isTestFile('crates/core/tests/integration.rs')).toBe(true);
+    expect(isTestFile('src/main/java/App.java')).toBe(false);
+    expect(isTestFile('src/compute/engine.rs')).toBe(false);
+  });
+
+  it('should provide a regex parser for regex-only packs', async () => {
+    const kotlin = createSimpleParsers().get('kotlin');
+    expect(kotlin).toBeDefined();
+
+    const parsed = await kotlin!.parseFile('Main.kt', 'class Greeter {\n  fun greet(name: String) {\n  }\n}\n');
+    expect(parsed.symbols.map(s => s.name)).toEqual(expect.arrayContaining(['Greeter', 'greet']));
+  });
+
+  it('should register new packs for parsing and language detection', async () => {
+    registerLanguagePack({
+      id: 'elixir',
+      displayName: 'Elixir',
+      extensions: ['.ex', '.exs'],
+      simplePatterns: {
+        function: /def\s+(\w+)/gm,
+        class: /defmodule\s+([\w.]+)/gm,
+        import: /(?:import|alias|use)\s+([\w.]+)/gm,
+        export: /def\s+(\w+)/gm
+      },
+      testFilePatterns: ['**/*_test.exs'],
+      indexByDefault: false
+    });
+
+    expect(getLanguagePack('elixir')?.displayName).toBe('Elixir');
+    expect(detectLanguage('lib/app.ex')).toBe('elixir');
+    expect(isTestFile('test/app_test.exs')).toBe(true);
+    expect(createSimpleParsers().has('elixir')).toBe(true);
+  });
+});
@@ -0,0 +1,45 @@
+/**
+ * Local Embedding Provider Tests
+ */
+
+import { describe, it, expect, afterEach } from 'vitest';
+import {
+  getLocalEmbeddingModel,
+  LocalEmbedder,
+  DEFAULT_LOCAL_EMBEDDING_MODEL
+} from '../../packages/core/src/vector/local-embeddings.js';
+import { VectorManager } from '../../packages/core/src/vector/index.js';
+
+describe('Local embeddings', () => {
+  const savedEnv = { ...process.env };
+
+  afterEach(() => {
+    process.env = { ...savedEnv };
+  });
+
+  it('should resolve models by config name or repository', () => {
+    expect(getLocalEmbeddingModel(DEFAULT_LOCAL_EMBEDDING_MODEL)?.dimension).toBe(384);
+    expect(getLocalEmbeddingModel('Xenova/all-MiniLM-L6-v2')?.name).toBe('local/all-minilm-l6-v2');
+    expect(getLocalEmbeddingModel('local/unknown')).toBeUndefined();
+    expect(() => new LocalEmbedder('local/unknown')).toThrow(/Unknown local embedding model/);
+  });
+
+  it('should select the local provider without any API key', () => {
+    process.env.OPENAI_API_KEY = 'sk-test';
+    process.env.OPENROUTER_API_KEY = 'sk-or-test';
+    const vector = new VectorManager({ url: 'http://localhost:6333', localModel: 'local/nomic-embed-text-v1.5' });
+    expect(vector.getEmbeddingInfo()).toEqual({
+      model: 'local/nomic-embed-text-v1.5',
+      provider: 'local',
+      dimensions: 768
+    });
+  });
+
+  it('should honor CV_EMBEDDING_PROVIDER=local', () => {
+    process.env.CV_EMBEDDING_PROVIDER = 'local';
+    delete process.env.CV_EMBEDDING_MODEL;
+    const vector = new VectorManager({ url: 'http://localhost:6333' });
+    expect(vector.getEmbeddingInfo().provider).toBe('local');
+    expect(vector.getEmbeddingInfo().model).toBe(DEFAULT_LOCAL_EMBEDDING_MODEL);
+  });
+});
@@ -0,0 +1,178 @@
+/**
+ * LSP Server Tests
+ * Tests for message framing, explain targets, review diagnostics and the
+ * request flow of `cv lsp`
+ */
+
+import { describe, it, expect, vi } from 'vitest';
+import { pathToFileURL } from 'url';
+import * as path from 'path';
+import { encodeMessage, MessageReader, LspMessage } from '../../packages/cli/src/lsp/protocol.js';
+import {
+  explainTarget,
+  findingsToDiagnostics,
+  LspBackend,
+  LspServer,
+  REVIEW_COMMAND,
+} from '../../packages/cli/src/lsp/server.js';
+import type { ReviewFinding } from '../../packages/core/src/ai/review.js';
+
+const root = path.resolve('/work/repo');
+const uri = (file: string) => pathToFileURL(path.join(root, file)).href;
+
+function finding(overrides: Partial<ReviewFinding>): ReviewFinding {
+  return {
+    id: 'aaaa1111',
+    severity: 'high',
+    file: 'src/pool.ts',
+    line: 3,
+    title: 'Unbounded queue',
+    message: 'The queue grows without limit.',
+    ...overrides,
+  };
+}
+
+function createServer(backend: Partial<LspBackend>) {
+  const sent: LspMessage[] = [];
+  const server = new LspServer({
+    createBackend: async () => ({
+      search: async () => [],
+      explain: async () => '',
+      review: async () => [],
+      ...backend,
+    }),
+    send: message => sent.push(message),
+  });
+  const request = async (id: number, method: string, params: unknown = {}) => {
+    await server.handle({ jsonrpc: '2.0', id, method, params });
+    return sent.find(m => m.id === id && m.method === undefined);
+  };
+  return { server, sent, request };
+}
+
+describe('MessageReader', () => {
+  it('splits framed messages across chunk boundaries', () => {
+    const reader = new MessageReader();
+    const bytes = Buffer.concat([
+      encodeMessage({ jsonrpc: '2.0', id: 1, method: 'initialize', params: { text: 'héllo' } }),
+      encodeMessage({ jsonrpc: '2.0', method: 'initialized' }),
+    ]);
+
+    expect(reader.push(bytes.subarray(0, 30))).toEqual([]);
+    const messages = reader.push(bytes.subarray(30));
+    expect(messages.map(m => m.method)).toEqual(['initialize', 'initialized']);
+    expect(messages[0].params.text).toBe('héllo');
+  });
+
+  it('skips malformed bodies and keeps reading', () => {
+    const onError = vi.fn();
+    const reader = new MessageReader(onError);
+    const messages = reader.push(Buffer.concat([
+      Buffer.from('Content-Length: 3\r\n\r\n{x}'),
+      encodeMessage({ jsonrpc: '2.0', method: 'exit' }),
+    ]));
+    expect(messages.map(m => m.method)).toEqual(['exit']);
+    expect(onError).toHaveBeenCalledTimes(1);
+  });
+});
+
+describe('explainTarget', () => {
+  const text = 'export class Pool {\n  drain(limit: number) {\n    return this.queue.splice(0, limit);\n  }\n}';
+
+  it('uses the word under the cursor when nothing is selected', () => {
+    const at = { line: 1, character: 4 };
+    expect(explainTarget(text, { start: at, end: at }, 'src/pool.ts')).toBe('drain (in src/pool.ts)');
+  });
+
+  it('quotes a multi-line selection with its location', () => {
+    const target = explainTarget(text, { start: { line: 1, character: 2 }, end: { line: 3, character: 3 } }, 'src/pool.ts');
+    expect(target).toBe('this code from src/pool.ts (lines 2-4):\n\ndrain(limit: number) {\n    return this.queue.splice(0, limit);\n  }');
+  });
+
+  it('returns null on whitespace', () => {
+    const at = { line: 1, character: 0 };
+    expect(explainTarget(text, { start: at, end: at }, 'src/pool.ts')).toBeNull();
+  });
+});
+
+describe('findingsToDiagnostics', () => {
+  it('maps severities and 1-based line ranges', () => {
+    const diagnostics = findingsToDiagnostics([
+      finding({ line: 3, endLine: 5, rule: 'bounded-queues', suggestion: 'Cap it.' }),
+      finding({ id: 'bbbb2222', severity: 'low', line: 9, title: 'Naming', message: 'Naming' }),
+    ], (_file, line) => (line === 4 ? 12 : undefined));
+
+    expect(diagnostics.get('src/pool.ts')).toEqual([
+      {
+        range: { start: { line: 2, character: 0 }, end: { line: 4, character: 12 } },
+        severity: 1,
+        code: 'bounded-queues',
+        source: 'cv',
+        message: 'Unbounded queue\nThe queue grows without limit.\nSuggestion: Cap it.',
+      },
+      {
+        range: { start: { line: 8, character: 0 }, end: { line: 8, character: 1000 } },
+        severity: 3,
+        code: 'bbbb2222',
+        source: 'cv',
+        message: 'Naming',
+      },
+    ]);
+  });
+});
+
+describe('LspServer', () => {
+  it('rejects requests before initialize', async () => {
+    const { request } = createServer({});
+    const response = await request(1, 'workspace/symbol', { query: 'pool' });
+    expect(response?.error?.code).toBe(-32002);
+  });
+
+  it('answers workspace/symbol with semantic search hits', async () => {
+    const search = vi.fn(async () => [{ name: 'drain', kind: 'method' as const, file: 'src/pool.ts', startLine: 2, endLine: 4 }]);
+    const { request } = createServer({ search });
+    const init = await request(1, 'initialize', { rootUri: pathToFileURL(root).href, capabilities: {} });
+    expect(init?.result.capabilities.workspaceSymbolProvider).toBe(true);
+
+    expect((await request(2, 'workspace/symbol', { query: '' }))?.result).toEqual([]);
+    const response = await request(3, 'workspace/symbol', { query: 'empty the queue' });
+    expect(search).toHaveBeenCalledWith('empty the queue', 30);
+    expect(response?.result).toEqual([{
+      name: 'drain',
+      kind: 6,
+      location: { uri: uri('src/pool.ts'), range: { start: { line: 1, character: 0 }, end: { line: 3, character: 0 } } },
+      containerName: 'src/pool.ts',
+    }]);
+  });
+
+  it('publishes review findings on save and clears them once fixed', async () => {
+    const review = vi.fn()
+      .mockResolvedValueOnce([finding({})])
+      .mockResolvedValueOnce([]);
+    const { server, sent, request } = createServer({ review });
+    await request(1, 'initialize', { rootUri: pathToFileURL(root).href, capabilities: {} });
+
+    const published = () => sent.filter(m => m.method === 'textDocument/publishDiagnostics');
+    await server.handle({ jsonrpc: '2.0', method: 'textDocument/didSave', params: { textDocument: { uri: uri('src/pool.ts') } } });
+    await vi.waitFor(() => expect(published()).toHaveLength(1));
+    await request(2, 'workspace/executeCommand', { command: REVIEW_COMMAND, arguments: [uri('src/pool.ts')] });
+
+    expect(review).toHaveBeenCalledWith(['src/pool.ts']);
+    expect(published().map(m => [m.params.uri, m.params.diagnostics.length])).toEqual([
+      [uri('src/pool.ts'), 1],
+      [uri('src/pool.ts'), 0],
+    ]);
+  });
+
+  it('offers explain and review code actions for workspace files only', async () => {
+    const { request } = createServer({});
+    await request(1, 'initialize', { rootUri: pathToFileURL(root).href, capabilities: {} });
+    const range = { start: { line: 0, character: 0 }, end: { line: 0, character: 0 } };
+
+    const actions = await request(2, 'textDocument/codeAction', { textDocument: { uri: uri('src/pool.ts') }, range });
+    expect(actions?.result.map((a: any) => a.title)).toEqual(['Explain with cv', 'Review changes with cv']);
+
+    const outside = await request(3, 'textDocument/codeAction', { textDocument: { uri: pathToFileURL('/tmp/other.ts').href }, range });
+    expect(outside?.result).toEqual([]);
+  });
+});
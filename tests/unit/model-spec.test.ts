@@ -0,0 +1,33 @@
+/**
+ * Model Spec Tests
+ * Tests for parsing `--model provider/model` values
+ */
+
+import { describe, it, expect } from 'vitest';
+import { parseModelSpec } from '../../packages/core/src/ai/factory.js';
+
+describe('parseModelSpec', () => {
+  it('should split provider and model', () => {
+    expect(parseModelSpec('anthropic/claude-sonnet-4-5')).toEqual({ provider: 'anthropic', model: 'claude-sonnet-4-5' });
+    expect(parseModelSpec('gemini/gemini-2.5-pro')).toEqual({ provider: 'gemini', model: 'gemini-2.5-pro' });
+    expect(parseModelSpec('ollama/qwen2.5-coder:14b')).toEqual({ provider: 'ollama', model: 'qwen2.5-coder:14b' });
+  });
+
+  it('should keep the rest of the spec as the model id', () => {
+    expect(parseModelSpec('openrouter/openai/gpt-4o')).toEqual({ provider: 'openrouter', model: 'openai/gpt-4o' });
+  });
+
+  it('should accept provider aliases and a provider alone', () => {
+    expect(parseModelSpec('Google/gemini-2.5-flash')).toEqual({ provider: 'gemini', model: 'gemini-2.5-flash' });
+    expect(parseModelSpec('claude/claude-opus-4-1')).toEqual({ provider: 'anthropic', model: 'claude-opus-4-1' });
+    expect(parseModelSpec('gemini')).toEqual({ provider: 'gemini' });
+    expect(parseModelSpec('lmstudio/')).toEqual({ provider: 'lmstudio' });
+  });
+
+  it('should treat other values as a model for the default provider', () => {
+    expect(parseModelSpec('claude-sonnet-4-5')).toEqual({ model: 'claude-sonnet-4-5' });
+    expect(parseModelSpec('meta-llama/llama-3.1-70b-instruct')).toEqual({ model: 'meta-llama/llama-3.1-70b-instruct' });
+    expect(parseModelSpec(undefined)).toEqual({});
+    expect(parseModelSpec('  ')).toEqual({});
+  });
+});
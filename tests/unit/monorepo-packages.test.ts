@@ -0,0 +1,130 @@
+/**
+ * Monorepo Package Tests
+ * Tests for detecting package boundaries and scoping searches to them
+ */
+
+import { describe, it, expect, beforeEach, afterEach } from 'vitest';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import {
+  detectPackages,
+  findPackage,
+  loadPackages,
+  packageForDirectory,
+  packageForFile,
+  savePackages,
+  RepoPackage,
+} from '../../packages/core/src/sync/packages.js';
+
+describe('detectPackages', () => {
+  let root: string;
+
+  const write = (file: string, content: string) => {
+    fs.mkdirSync(path.dirname(path.join(root, file)), { recursive: true });
+    fs.writeFileSync(path.join(root, file), content);
+    return file;
+  };
+
+  beforeEach(() => {
+    root = fs.mkdtempSync(path.join(os.tmpdir(), 'cv-packages-'));
+  });
+
+  afterEach(() => {
+    fs.rmSync(root, { recursive: true, force: true });
+  });
+
+  it('should find package.json workspace members by name', async () => {
+    const files = [
+      write('package.json', JSON.stringify({ workspaces: ['packages/*', '!packages/legacy'] })),
+      write('packages/web/package.json', JSON.stringify({ name: '@acme/web' })),
+      write('packages/api/package.json', '{ not json'),
+      write('packages/legacy/package.json', JSON.stringify({ name: 'legacy' })),
+      write('tools/package.json', JSON.stringify({ name: 'tools' })),
+    ];
+
+    const packages = await detectPackages(root, files);
+
+    expect(packages.map(p => [p.name, p.dir, p.kind]).sort()).toEqual([
+      ['@acme/web', 'packages/web', 'npm'],
+      ['api', 'packages/api', 'npm'],
+    ]);
+  });
+
+  it('should read pnpm-workspace.yaml packages', async () => {
+    const files = [
+      write('package.json', JSON.stringify({ name: 'root' })),
+      write('pnpm-workspace.yaml', "packages:\n  - 'apps/*'\n  - \"libs/**\" # shared code\nonlyBuiltDependencies:\n  - esbuild\n"),
+      write('apps/site/package.json', JSON.stringify({ name: 'site' })),
+      write('libs/ui/button/package.json', JSON.stringify({ name: 'button' })),
+    ];
+
+    const packages = await detectPackages(root, files);
+    expect(packages.map(p => p.name).sort()).toEqual(['button', 'site']);
+  });
+
+  it('should find Cargo workspace members and Go modules', async () => {
+    const files = [
+      write('Cargo.toml', '[workspace]\nmembers = ["crates/*"]\nexclude = ["crates/scratch"]\n'),
+      write('crates/core/Cargo.toml', '[package]\nname = "acme-core"\nversion = "0.1.0"\n'),
+      write('crates/scratch/Cargo.toml', '[package]\nname = "scratch"\n'),
+      write('go.mod', 'module github.com/acme/mono\n\ngo 1.22\n'),
+      write('services/auth/go.mod', 'module github.com/acme/mono/services/auth\n'),
+    ];
+
+    const packages = await detectPackages(root, files);
+
+    expect(packages.map(p => [p.name, p.dir, p.kind])).toEqual([
+      ['github.com/acme/mono/services/auth', 'services/auth', 'go'],
+      ['acme-core', 'crates/core', 'cargo'],
+      ['github.com/acme/mono', '.', 'go'],
+    ]);
+  });
+
+  it('should not treat a single repository as a monorepo', async () => {
+    const files = [
+      write('package.json', JSON.stringify({ name: 'app' })),
+      write('go.mod', 'module example.com/app\n'),
+      write('Cargo.toml', '[package]\nname = "app"\n'),
+    ];
+    expect(await detectPackages(root, files)).toEqual([]);
+  });
+
+  it('should record packages for later runs', async () => {
+    const packages: RepoPackage[] = [{ name: 'web', dir: 'packages/web', kind: 'npm' }];
+    expect(await loadPackages(root)).toEqual([]);
+    await savePackages(root, packages);
+    expect(await loadPackages(root)).toEqual(packages);
+  });
+});
+
+describe('package lookup', () => {
+  const packages: RepoPackage[] = [
+    { name: 'github.com/acme/mono/services/auth', dir: 'services/auth', kind: 'go' },
+    { name: '@acme/web', dir: 'packages/web', kind: 'npm' },
+    { name: 'github.com/acme/mono', dir: '.', kind: 'go' },
+  ];
+
+  it('should assign files to the innermost package', () => {
+    expect(packageForFile(packages, 'services/auth/token.go')?.dir).toBe('services/auth');
+    expect(packageForFile(packages, 'packages/web/src/app.ts')?.name).toBe('@acme/web');
+    expect(packageForFile(packages, 'packages/webapp/x.ts')?.dir).toBe('.');
+    expect(packageForFile(packages.slice(0, 2), 'README.md')).toBeUndefined();
+  });
+
+  it('should scope to the package of the working directory', () => {
+    const root = path.join(os.tmpdir(), 'mono');
+    expect(packageForDirectory(packages, root, path.join(root, 'packages', 'web', 'src'))?.name).toBe('@acme/web');
+    expect(packageForDirectory(packages, root, root)).toBeUndefined();
+    expect(packageForDirectory(packages, root, path.join(root, 'docs'))).toBeUndefined();
+    expect(packageForDirectory(packages, root, os.tmpdir())).toBeUndefined();
+  });
+
+  it('should find packages by name, short name or directory', () => {
+    expect(findPackage(packages, '@acme/web')?.dir).toBe('packages/web');
+    expect(findPackage(packages, 'web')?.dir).toBe('packages/web');
+    expect(findPackage(packages, './services/auth/')?.kind).toBe('go');
+    expect(findPackage(packages, 'auth')?.dir).toBe('services/auth');
+    expect(findPackage(packages, 'mobile')).toBeUndefined();
+  });
+});
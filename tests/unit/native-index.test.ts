@@ -0,0 +1,79 @@
+/**
+ * Native Indexer Unit Tests
+ * Tests for reading cv-index output and falling back when it is unavailable
+ */
+
+import { describe, it, expect, beforeEach, afterEach } from 'vitest';
+import { promises as fs } from 'fs';
+import * as path from 'path';
+import * as os from 'os';
+import { createNativeIndexer, findNativeIndexer, isNativelyIndexable } from '@cv-git/core';
+
+// Stands in for cv-index: one chunk per `.rs`/`.py` argument, nothing for
+// `.go`, and a failed run (no output, exit 1) for files named bad.*
+const FAKE_INDEXER = `
+const files = process.argv.slice(2);
+let failed = false;
+for (const file of files) {
+  if (file.includes('bad.')) { failed = true; continue; }
+  if (file.endsWith('.go')) continue;
+  process.stdout.write(JSON.stringify({
+    id: file + ':1:3', file, language: 'rust', startLine: 1, endLine: 3,
+    startByte: 0, endByte: 20, text: 'fn a() {}', symbolName: 'a', symbolKind: 'function'
+  }) + '\\n');
+}
+process.exit(failed ? 1 : 0);
+`;
+
+describe('native indexer', () => {
+  let tempDir: string;
+  let script: string;
+
+  beforeEach(async () => {
+    tempDir = await fs.mkdtemp(path.join(os.tmpdir(), 'cv-native-index-test-'));
+    script = path.join(tempDir, 'fake-cv-index.cjs');
+    await fs.writeFile(script, FAKE_INDEXER);
+  });
+
+  afterEach(async () => {
+    await fs.rm(tempDir, { recursive: true, force: true });
+  });
+
+  it('should group chunks by file and only pass supported languages', async () => {
+    const indexer = createNativeIndexer(process.execPath, [script]);
+    const chunks = await indexer.index(tempDir, ['src/a.rs', 'tool.py', 'README.md', 'empty.go']);
+
+    expect([...chunks.keys()].sort()).toEqual(['empty.go', 'src/a.rs', 'tool.py']);
+    expect(chunks.get('src/a.rs')).toEqual([
+      expect.objectContaining({ id: 'src/a.rs:1:3', symbolName: 'a', symbolKind: 'function', startByte: 0 })
+    ]);
+    // Indexed cleanly without symbols: no chunks, rather than a fallback
+    expect(chunks.get('empty.go')).toEqual([]);
+  });
+
+  it('should leave files without output to the fallback parser when cv-index fails', async () => {
+    const indexer = createNativeIndexer(process.execPath, [script]);
+    const chunks = await indexer.index(tempDir, ['src/a.rs', 'src/bad.rs', 'empty.go']);
+
+    expect(chunks.get('src/a.rs')).toHaveLength(1);
+    expect(chunks.has('src/bad.rs')).toBe(false);
+    expect(chunks.has('empty.go')).toBe(false);
+  });
+
+  it('should reject when the command cannot be started', async () => {
+    const indexer = createNativeIndexer(path.join(tempDir, 'missing-cv-index'));
+    await expect(indexer.index(tempDir, ['src/a.rs'])).rejects.toThrow();
+  });
+
+  it('should find no indexer when disabled or not installed', async () => {
+    expect(await findNativeIndexer({ CV_INDEX_BIN: 'off' })).toBeNull();
+    expect(await findNativeIndexer({ CV_INDEX_BIN: path.join(tempDir, 'missing-cv-index') })).toBeNull();
+  });
+
+  it('should recognise the languages cv-index supports', () => {
+    expect(isNativelyIndexable('src/lib.rs')).toBe(true);
+    expect(isNativelyIndexable('app/page.tsx')).toBe(true);
+    expect(isNativelyIndexable('tool.pyi')).toBe(true);
+    expect(isNativelyIndexable('Main.java')).toBe(false);
+  });
+});
@@ -0,0 +1,108 @@
+/**
+ * Offline Mode Tests
+ * Tests for --offline, network detection and refusing cloud requests up front
+ */
+
+import { describe, it, expect, vi, beforeEach, afterEach } from 'vitest';
+import type { NetworkInterfaceInfo } from 'os';
+import {
+  OfflineError,
+  detectOffline,
+  getOfflineReason,
+  initOfflineMode,
+  isLocalUrl,
+  isOffline,
+  setOffline,
+} from '../../packages/core/src/ai/offline.js';
+import { configureProviderLimits, providerFetch } from '../../packages/core/src/ai/http.js';
+import { isRetryableProviderError } from '../../packages/core/src/ai/fallback.js';
+
+function iface(address: string, internal = false): NetworkInterfaceInfo {
+  return { address, internal, family: address.includes(':') ? 'IPv6' : 'IPv4', netmask: '', mac: '', cidr: null } as NetworkInterfaceInfo;
+}
+
+describe('detectOffline', () => {
+  it('should treat loopback and link-local addresses as no network', () => {
+    expect(detectOffline({ lo: [iface('127.0.0.1', true), iface('::1', true)] })).toBe(true);
+    expect(detectOffline({ lo: [iface('127.0.0.1', true)], en0: [iface('fe80::1'), iface('169.254.3.4')] })).toBe(true);
+    expect(detectOffline({ lo: [iface('127.0.0.1', true)], en0: [iface('192.168.1.20')] })).toBe(false);
+  });
+});
+
+describe('isLocalUrl', () => {
+  it('should accept only this machine', () => {
+    expect(isLocalUrl('http://localhost:11434/api/embed')).toBe(true);
+    expect(isLocalUrl('http://127.0.0.1:1234/v1')).toBe(true);
+    expect(isLocalUrl('http://[::1]:6333')).toBe(true);
+    expect(isLocalUrl('https://api.openai.com/v1/embeddings')).toBe(false);
+    expect(isLocalUrl('not a url')).toBe(false);
+  });
+});
+
+describe('initOfflineMode', () => {
+  const env = process.env.CV_OFFLINE;
+
+  afterEach(() => {
+    setOffline(false);
+    if (env === undefined) delete process.env.CV_OFFLINE;
+    else process.env.CV_OFFLINE = env;
+  });
+
+  it('should go offline for --offline and CV_OFFLINE=1', () => {
+    process.env.CV_OFFLINE = '0';
+    expect(initOfflineMode(true)).toBe('flag');
+    setOffline(false);
+    process.env.CV_OFFLINE = '1';
+    expect(initOfflineMode()).toBe('flag');
+  });
+
+  it('should not detect anything with CV_OFFLINE=0', () => {
+    process.env.CV_OFFLINE = '0';
+    expect(initOfflineMode()).toBeUndefined();
+    expect(isOffline()).toBe(false);
+  });
+});
+
+describe('providerFetch offline', () => {
+  beforeEach(() => {
+    configureProviderLimits({ test: { maxRetries: 1, baseDelayMs: 1, maxDelayMs: 5 } });
+  });
+
+  afterEach(() => {
+    vi.unstubAllGlobals();
+    configureProviderLimits(undefined);
+    setOffline(false);
+  });
+
+  it('should refuse cloud requests without sending them', async () => {
+    const mockFetch = vi.fn(async () => new Response('ok'));
+    vi.stubGlobal('fetch', mockFetch);
+    setOffline(true);
+
+    await expect(providerFetch('openai', 'https://api.openai.com/v1/embeddings')).rejects.toThrow(
+      'The openai API needs network access, and cv is offline (--offline)'
+    );
+    expect(mockFetch).not.toHaveBeenCalled();
+
+    expect((await providerFetch('ollama', 'http://127.0.0.1:11434/api/embed')).status).toBe(200);
+    expect(mockFetch).toHaveBeenCalledTimes(1);
+  });
+
+  it('should go offline when DNS keeps failing', async () => {
+    const dns = Object.assign(new TypeError('fetch failed'), { cause: { code: 'EAI_AGAIN' } });
+    const mockFetch = vi.fn(async () => { throw dns; });
+    vi.stubGlobal('fetch', mockFetch);
+
+    await expect(providerFetch('test', 'https://api.example.com')).rejects.toBeInstanceOf(OfflineError);
+    expect(mockFetch).toHaveBeenCalledTimes(2);
+    expect(getOfflineReason()).toBe('unreachable');
+
+    await expect(providerFetch('test', 'https://api.example.com')).rejects.toThrow('the network became unreachable');
+    expect(mockFetch).toHaveBeenCalledTimes(2);
+  });
+
+  it('should let a fallback chain move on to a local model', () => {
+    setOffline(true);
+    expect(isRetryableProviderError(new OfflineError('Using anthropic models'))).toBe(true);
+  });
+});
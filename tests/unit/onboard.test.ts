@@ -0,0 +1,180 @@
+/**
+ * Onboarding Tour Tests
+ * Tests for building the repo tour from files, graph and history, the model's prose, and the walkthrough
+ */
+
+import { describe, it, expect, vi } from 'vitest';
+import {
+  applyOnboardResponse,
+  buildOnboardPrompt,
+  buildRepoTour,
+  formatTourMarkdown,
+  moduleOf,
+  tourSections
+} from '../../packages/core/src/ai/onboard.js';
+import type { ChurnCommit } from '../../packages/core/src/git/hotspots.js';
+import { Walkthrough } from '../../packages/cli/src/tui/walkthrough.js';
+import { stripAnsi } from '../../packages/cli/src/utils/formatting.js';
+
+const files = [
+  'Cargo.toml',
+  'README.md',
+  'crates/cli/src/main.rs',
+  'crates/engine/src/lib.rs',
+  'crates/engine/src/cache.rs',
+  'crates/engine/tests/engine.rs',
+  'crates/storage/src/lib.rs',
+];
+
+function sym(qualifiedName: string, kind: string) {
+  const [file, path] = qualifiedName.split(':');
+  return { qualifiedName, name: path.split('.').pop()!, kind, file };
+}
+
+const graph = {
+  symbols: [
+    sym('crates/cli/src/main.rs:main', 'function'),
+    sym('crates/engine/src/lib.rs:ComputeEngine', 'struct'),
+    sym('crates/engine/src/lib.rs:ComputeEngine.compute', 'method'),
+    sym('crates/engine/src/cache.rs:Cache.get', 'method'),
+    sym('crates/engine/tests/engine.rs:computes', 'function'),
+    sym('crates/storage/src/lib.rs:Store.load', 'method'),
+  ],
+  calls: [
+    { source: 'crates/cli/src/main.rs:main', target: 'crates/engine/src/lib.rs:ComputeEngine.compute' },
+    { source: 'crates/engine/src/lib.rs:ComputeEngine.compute', target: 'crates/engine/src/cache.rs:Cache.get' },
+    { source: 'crates/engine/src/lib.rs:ComputeEngine.compute', target: 'crates/storage/src/lib.rs:Store.load' },
+    { source: 'crates/engine/tests/engine.rs:computes', target: 'crates/engine/src/lib.rs:ComputeEngine.compute' },
+    { source: 'crates/cli/src/main.rs:main', target: 'crates/storage/src/lib.rs:Store.load' },
+  ],
+};
+
+const commits: ChurnCommit[] = [
+  { sha: 'c3c3c3c3c3', author: 'Bo', authorEmail: 'bo@example.com', date: Date.UTC(2026, 9, 12), subject: 'Cache results', files: [{ path: 'crates/engine/src/lib.rs', added: 2, deleted: 0 }] },
+  { sha: 'b2b2b2b2b2', author: 'Bo', authorEmail: 'bo@example.com', date: Date.UTC(2026, 9, 10), subject: 'Add cache', files: [{ path: 'crates/engine/src/cache.rs', added: 3, deleted: 1 }, { path: 'crates/cli/src/main.rs', added: 1, deleted: 1 }] },
+  { sha: 'a1a1a1a1a1', author: 'Ana', authorEmail: 'ana@example.com', date: Date.UTC(2026, 9, 1), subject: 'Initial engine', files: [{ path: 'crates/engine/src/lib.rs', added: 10, deleted: 2 }, { path: 'crates/engine/tests/engine.rs', added: 5, deleted: 0 }] },
+];
+
+describe('moduleOf', () => {
+  it('uses workspace members, source subdirectories and top-level directories', () => {
+    expect(moduleOf('packages/core/src/ai/review.ts')).toBe('packages/core');
+    expect(moduleOf('src/engine/mod.rs')).toBe('src/engine');
+    expect(moduleOf('src/main.rs')).toBe('src');
+    expect(moduleOf('docs/guide.md')).toBe('docs');
+    expect(moduleOf('build.rs')).toBe('.');
+  });
+});
+
+describe('buildRepoTour', () => {
+  const tour = buildRepoTour({ files, graph, commits, descriptions: { 'crates/storage': 'Persists results.' } });
+
+  it('describes code modules, most depended-on first', () => {
+    expect(tour.modules.map(m => [m.path, m.files, m.symbols, m.keySymbols])).toEqual([
+      ['crates/storage', 1, 1, ['load']],
+      ['crates/engine', 2, 3, ['compute', 'get']],
+      ['crates/cli', 1, 1, []],
+    ]);
+    const engine = tour.modules.find(m => m.path === 'crates/engine')!;
+    expect(engine.languages).toEqual(['rust']);
+    expect(engine.churn).toBe(23);
+    expect(engine.authors).toEqual(['Bo', 'Ana']);
+    expect(tour.modules[0].responsibility).toBe('Persists results.');
+  });
+
+  it('finds entry points and the calls between modules, ignoring tests', () => {
+    expect(tour.entryPoints).toEqual([
+      { file: 'crates/cli/src/main.rs', kind: 'executable', symbol: 'main' },
+      { file: 'crates/engine/src/lib.rs', kind: 'library root' },
+      { file: 'crates/storage/src/lib.rs', kind: 'library root' },
+    ]);
+    expect(tour.flows).toEqual([
+      { from: 'crates/cli', to: 'crates/engine', calls: 1, examples: ['main → compute'] },
+      { from: 'crates/cli', to: 'crates/storage', calls: 1, examples: ['main → load'] },
+      { from: 'crates/engine', to: 'crates/storage', calls: 1, examples: ['compute → load'] },
+    ]);
+    expect(tour.recentCommits[0]).toEqual({ sha: 'c3c3c3c3c3', subject: 'Cache results', author: 'Bo', date: Date.UTC(2026, 9, 12) });
+  });
+
+  it('suggests entry points, then each module\'s most used file', () => {
+    expect(tour.reading.map(r => r.file)).toEqual([
+      'crates/cli/src/main.rs',
+      'crates/engine/src/lib.rs',
+      'crates/storage/src/lib.rs',
+    ]);
+    const small = buildRepoTour({ files: files.filter(f => !f.endsWith('lib.rs')), graph, commits, maxModules: 2 });
+    expect(small.reading).toEqual([
+      { file: 'crates/cli/src/main.rs', why: 'executable: starts at main' },
+      { file: 'crates/engine/src/cache.rs', why: 'most used file in crates/engine (1 call(s) from other files)' },
+    ]);
+  });
+
+  it('keeps only flows between the modules shown', () => {
+    const small = buildRepoTour({ files, graph, commits, maxModules: 2 });
+    expect(small.omittedModules).toBe(1);
+    expect(small.flows.map(f => `${f.from}>${f.to}`)).toEqual(['crates/engine>crates/storage']);
+  });
+});
+
+describe('onboard prompt and response', () => {
+  const tour = buildRepoTour({ files, graph, commits });
+
+  it('gives the model the facts', () => {
+    const prompt = buildOnboardPrompt(tour, '# engine-ws\nA compute engine.');
+    expect(prompt).toContain('A compute engine.');
+    expect(prompt).toContain('- crates/engine: 2 file(s); 3 symbol(s); rust; key symbols: compute, get');
+    expect(prompt).toContain('- crates/engine → crates/storage: 1 call(s), e.g. compute → load');
+    expect(prompt).toContain('"dataFlow"');
+  });
+
+  it('merges the prose, reordering only known files', () => {
+    const merged = applyOnboardResponse(tour, '```json\n' + JSON.stringify({
+      overview: 'A compute engine with a CLI.',
+      modules: [{ path: 'crates/engine', responsibility: 'Runs computations.' }, { path: 'crates/gpu', responsibility: 'Invented.' }],
+      dataFlow: 'The CLI asks the engine, which reads from storage.',
+      reading: [{ file: 'crates/engine/src/lib.rs', why: 'The engine core.' }, { file: 'src/invented.rs', why: 'x' }],
+    }) + '\n```');
+    expect(merged.overview).toBe('A compute engine with a CLI.');
+    expect(merged.dataFlow).toBe('The CLI asks the engine, which reads from storage.');
+    expect(merged.modules.find(m => m.path === 'crates/engine')?.responsibility).toBe('Runs computations.');
+    expect(merged.reading.map(r => [r.file, r.why])).toEqual([
+      ['crates/engine/src/lib.rs', 'The engine core.'],
+      ['crates/cli/src/main.rs', 'executable: starts at main'],
+      ['crates/storage/src/lib.rs', 'library root'],
+    ]);
+    expect(() => applyOnboardResponse(tour, 'No idea.')).toThrow('JSON');
+  });
+});
+
+describe('tour output', () => {
+  const tour = buildRepoTour({ files, graph, commits });
+
+  it('renders markdown sections', () => {
+    const markdown = formatTourMarkdown({ ...tour, overview: 'A compute engine.' }, 'engine-ws');
+    expect(markdown).toContain('# Welcome to engine-ws\n\nA compute engine.');
+    expect(markdown).toContain('### `crates/engine`');
+    expect(markdown).toContain('- `crates/cli` → `crates/storage`: 1 call(s), e.g. `main → load`');
+    expect(markdown).toContain('1. `crates/cli/src/main.rs` — executable: starts at main');
+    expect(markdown).toContain('- 2026-10-12 `c3c3c3c` Cache results — Bo');
+    expect(tourSections(tour).map(s => s.title)).toEqual([
+      'Welcome to this repository', 'Modules', 'Entry points', 'Data flow', 'Start reading here', 'Recent activity',
+    ]);
+  });
+
+  it('steps through the sections in the walkthrough', () => {
+    const onQuit = vi.fn();
+    const walkthrough = new Walkthrough({ sections: tourSections(tour, 'engine-ws'), onChange: () => undefined, onQuit });
+    const screen = () => walkthrough.render(60, 16).map(stripAnsi);
+
+    expect(screen()).toHaveLength(16);
+    expect(screen()[0]).toContain('Welcome to engine-ws  (1/6)');
+    walkthrough.key(undefined, { name: 'right' });
+    expect(screen()[0]).toContain('Modules  (2/6)');
+    expect(screen().some(line => line.includes('crates/engine'))).toBe(true);
+    walkthrough.key('5');
+    expect(screen()[0]).toContain('Start reading here');
+    walkthrough.key(undefined, { name: 'left' });
+    expect(walkthrough.section).toBe(3);
+    walkthrough.key('q');
+    expect(onQuit).toHaveBeenCalled();
+  });
+});
@@ -0,0 +1,127 @@
+/**
+ * Oversized File Summarization Tests
+ */
+
+import { describe, it, expect, beforeEach, afterEach, vi } from 'vitest';
+import { promises as fs } from 'fs';
+import * as path from 'path';
+import * as os from 'os';
+import {
+  isGeneratedContent,
+  classifyOversizedFile,
+  summarizeOversizedFile,
+  extractStructuralSummary
+} from '../../packages/core/src/sync/oversized.js';
+
+describe('Oversized File Summarization', () => {
+  let tempDir: string;
+
+  beforeEach(async () => {
+    tempDir = await fs.mkdtemp(path.join(os.tmpdir(), 'cv-oversized-test-'));
+  });
+
+  afterEach(async () => {
+    try {
+      await fs.rm(tempDir, { recursive: true });
+    } catch {
+      // Ignore cleanup errors
+    }
+    delete process.env.CV_SUMMARIZE_THRESHOLD;
+  });
+
+  describe('isGeneratedContent', () => {
+    it('should detect generated header markers', () => {
+      expect(isGeneratedContent('// Code generated by protoc-gen-go. DO NOT EDIT.\npackage pb')).toBe(true);
+    });
+
+    it('should detect minified content', () => {
+      expect(isGeneratedContent('x'.repeat(5000))).toBe(true);
+    });
+
+    it('should not flag ordinary source', () => {
+      expect(isGeneratedContent('function add(a, b) {\n  return a + b;\n}\n')).toBe(false);
+    });
+  });
+
+  describe('classifyOversizedFile', () => {
+    it('should route files above the threshold to the summary tier', () => {
+      expect(classifyOversizedFile(300 * 1024, null)).toBe('too-large');
+    });
+
+    it('should respect CV_SUMMARIZE_THRESHOLD', () => {
+      process.env.CV_SUMMARIZE_THRESHOLD = '1024';
+      expect(classifyOversizedFile(2048, null)).toBe('too-large');
+    });
+
+    it('should skip files above the hard limit', () => {
+      expect(classifyOversizedFile(100, null, { hardLimit: 50 })).toBeNull();
+    });
+
+    it('should return null when disabled', () => {
+      expect(classifyOversizedFile(300 * 1024, null, { enabled: false })).toBeNull();
+    });
+
+    it('should flag large generated files under the threshold', () => {
+      const content = '// @generated\n' + 'const a = 1;\n'.repeat(10000);
+      expect(classifyOversizedFile(content.length, content)).toBe('generated');
+    });
+  });
+
+  describe('summarizeOversizedFile', () => {
+    it('should produce a single synthetic chunk spanning the file', async () => {
+      const filePath = path.join(tempDir, 'schema.json');
+      await fs.writeFile(filePath, '{\n  "name": "demo",\n  "version": 1\n}\n');
+
+      const parsed = await summarizeOversizedFile('schema.json', filePath, 'json', 'too-large');
+
+      expect(parsed.chunks).toHaveLength(1);
+      expect(parsed.chunks[0].synthetic).toBe(true);
+      expect(parsed.chunks[0].syntheticReason).toBe('too-large');
+      expect(parsed.chunks[0].startLine).toBe(1);
+      expect(parsed.chunks[0].endLine).toBe(5);
+      expect(parsed.symbols).toHaveLength(0);
+    });
+
+    it('should use the summarizer when provided', async () => {
+      const filePath = path.join(tempDir, 'bundle.js');
+      await fs.writeFile(filePath, 'var a=1;');
+      const summarizer = vi.fn().mockResolvedValue('  Minified bundle of the web app.  ');
+
+      const parsed = await summarizeOversizedFile('bundle.js', filePath, 'javascript', 'generated', { summarizer });
+
+      expect(summarizer).toHaveBeenCalledOnce();
+      expect(parsed.chunks[0].text).toBe('Minified bundle of the web app.');
+    });
+
+    it('should fall back to a structural summary when the summarizer fails', async () => {
+      const filePath = path.join(tempDir, 'types.ts');
+      await fs.writeFile(filePath, 'export interface Foo {}\n');
+      const summarizer = vi.fn().mockRejectedValue(new Error('rate limited'));
+
+      const parsed = await summarizeOversizedFile('types.ts', filePath, 'typescript', 'too-large', { summarizer });
+
+      expect(parsed.chunks[0].text).toContain('Oversized file types.ts');
+    });
+
+    it('should reject binary content', async () => {
+      const filePath = path.join(tempDir, 'blob.dat');
+      await fs.writeFile(filePath, Buffer.from([0x00, 0x01, 0x02]));
+
+      await expect(
+        summarizeOversizedFile('blob.dat', filePath, 'unknown', 'too-large')
+      ).rejects.toThrow('Binary content detected');
+    });
+  });
+
+  describe('extractStructuralSummary', () => {
+    it('should list top-level names from the sample', () => {
+      const summary = extractStructuralSummary(
+        'gen/api.ts', 'generated', 4096, 120,
+        'export interface User {}\nexport function getUser() {}\n'
+      );
+      expect(summary).toContain('Machine-generated file api.ts');
+      expect(summary).toContain('User');
+      expect(summary).toContain('getUser');
+    });
+  });
+});
@@ -0,0 +1,127 @@
+/**
+ * Patch Engine Tests
+ * Tests for diff previews, all-or-nothing application and the dirty-file guard
+ */
+
+import { describe, it, expect, beforeEach, afterEach } from 'vitest';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { PatchEngine, PatchError, unifiedDiff, uncommittedFiles } from '../../packages/core/src/code/patch-engine.js';
+import { Edit } from '../../packages/core/src/code/types.js';
+
+function edit(fields: Partial<Edit> & Pick<Edit, 'file' | 'type'>): Edit {
+  return { id: fields.file, status: 'pending', messageId: 'test', createdAt: 0, ...fields };
+}
+
+describe('unifiedDiff', () => {
+  it('should render hunks with context like git', () => {
+    const before = 'a\nb\nc\nd\ne\nf\ng\nh\ni\nj\n';
+    const after = 'a\nB\nc\nd\ne\nf\ng\nh\ni\nj\nk\n';
+    expect(unifiedDiff('x.txt', before, after)).toBe([
+      '--- a/x.txt', '+++ b/x.txt',
+      '@@ -1,5 +1,5 @@', ' a', '-b', '+B', ' c', ' d', ' e',
+      '@@ -8,3 +8,4 @@', ' h', ' i', ' j', '+k', ''
+    ].join('\n'));
+  });
+
+  it('should diff created and deleted files against /dev/null', () => {
+    expect(unifiedDiff('n.txt', null, 'one\ntwo\n')).toBe('--- /dev/null\n+++ b/n.txt\n@@ -0,0 +1,2 @@\n+one\n+two\n');
+    expect(unifiedDiff('n.txt', 'one\n', null)).toBe('--- a/n.txt\n+++ /dev/null\n@@ -1 +0,0 @@\n-one\n');
+    expect(unifiedDiff('n.txt', 'same\n', 'same\n')).toBe('');
+  });
+});
+
+describe('PatchEngine', () => {
+  let repoRoot: string;
+  let engine: PatchEngine;
+
+  beforeEach(() => {
+    repoRoot = fs.mkdtempSync(path.join(os.tmpdir(), 'cv-patch-'));
+    engine = new PatchEngine(repoRoot);
+    fs.mkdirSync(path.join(repoRoot, 'src'));
+    fs.writeFileSync(path.join(repoRoot, 'src/app.ts'), 'const a = 1;\nconst b = 2;\n');
+    fs.writeFileSync(path.join(repoRoot, 'src/old.ts'), 'legacy\n');
+  });
+
+  afterEach(() => {
+    fs.rmSync(repoRoot, { recursive: true, force: true });
+  });
+
+  const read = (file: string) => fs.readFileSync(path.join(repoRoot, file), 'utf-8');
+
+  it('should preview without touching files, then apply everything', async () => {
+    const patch = await engine.prepare([
+      edit({ file: 'src/app.ts', type: 'modify', searchReplaceBlocks: [{ search: 'const b = 2;', replace: 'const b = 3;' }] }),
+      edit({ file: 'src/new/util.ts', type: 'create', newContent: 'export {};\n' }),
+      edit({ file: 'src/old.ts', type: 'delete' })
+    ]);
+
+    expect(patch.files.map(f => f.file)).toEqual(['src/app.ts', 'src/new/util.ts', 'src/old.ts']);
+    expect(patch.files[0].diff).toContain('-const b = 2;\n+const b = 3;');
+    expect(read('src/app.ts')).toBe('const a = 1;\nconst b = 2;\n');
+
+    const applied = await engine.apply(patch);
+    expect(read('src/app.ts')).toBe('const a = 1;\nconst b = 3;\n');
+    expect(read('src/new/util.ts')).toBe('export {};\n');
+    expect(fs.existsSync(path.join(repoRoot, 'src/old.ts'))).toBe(false);
+    expect(applied[1]).toMatchObject({ file: 'src/new/util.ts', beforeHash: null });
+    expect(applied[2]).toMatchObject({ file: 'src/old.ts', afterHash: null });
+  });
+
+  it('should fail the whole patch if one edit does not apply', async () => {
+    await expect(engine.prepare([
+      edit({ file: 'src/app.ts', type: 'modify', searchReplaceBlocks: [{ search: 'missing', replace: 'x' }] }),
+      edit({ file: 'src/other.ts', type: 'create', newContent: 'x\n' })
+    ])).rejects.toThrow(PatchError);
+  });
+
+  it('should leave every file untouched when applying fails part-way', async () => {
+    const patch = await engine.prepare([
+      edit({ file: 'src/app.ts', type: 'create', newContent: 'changed\n' }),
+      edit({ file: 'src/old.ts/inner.ts', type: 'create', newContent: 'x\n' })
+    ]);
+
+    await expect(engine.apply(patch)).rejects.toThrow(/no files were changed/);
+    expect(read('src/app.ts')).toBe('const a = 1;\nconst b = 2;\n');
+    expect(fs.readdirSync(path.join(repoRoot, 'src')).sort()).toEqual(['app.ts', 'old.ts']);
+  });
+
+  it('should refuse files that changed after the preview and paths outside the tree', async () => {
+    const patch = await engine.prepare([edit({ file: 'src/app.ts', type: 'create', newContent: 'changed\n' })]);
+    fs.writeFileSync(path.join(repoRoot, 'src/app.ts'), 'edited by hand\n');
+
+    await expect(engine.apply(patch)).rejects.toThrow(/changed since the patch was prepared/);
+    expect(read('src/app.ts')).toBe('edited by hand\n');
+    await expect(engine.prepare([edit({ file: '../escape.ts', type: 'create', newContent: 'x' })])).rejects.toThrow(/outside the working tree/);
+  });
+
+  it('should restore secrets the model echoed back as redaction masks', async () => {
+    fs.writeFileSync(path.join(repoRoot, 'src/config.ts'), 'const password = "hunter2hunter2";\nconst port = 80;\n');
+    const patch = await engine.prepare([
+      edit({ file: 'src/config.ts', type: 'create', newContent: 'const password = "[REDACTED:secret-assignment]";\nconst port = 8080;\n' })
+    ]);
+    expect(patch.files[0].after).toBe('const password = "hunter2hunter2";\nconst port = 8080;\n');
+    expect(patch.files[0].diff).not.toContain('REDACTED');
+  });
+
+  it('should refuse to write a mask it cannot restore', async () => {
+    fs.writeFileSync(path.join(repoRoot, 'src/config.ts'), 'const password = "hunter2hunter2";\nconst token = "Xy7_kLm2-Pq9Rs4Tu";\n');
+    await expect(engine.prepare([
+      edit({ file: 'src/config.ts', type: 'create', newContent: 'const password = "[REDACTED:secret-assignment]";\n' })
+    ])).rejects.toThrow(/\[REDACTED:secret-assignment\].*refusing to write a redaction placeholder/);
+    await expect(engine.prepare([
+      edit({ file: 'src/new.ts', type: 'create', newContent: 'const key = "[REDACTED:api-key]";\n' })
+    ])).rejects.toThrow(PatchError);
+    expect(read('src/config.ts')).toContain('hunter2hunter2');
+  });
+
+  it('should list patched files with uncommitted changes', async () => {
+    const patch = await engine.prepare([
+      edit({ file: 'src/app.ts', type: 'create', newContent: 'changed\n' }),
+      edit({ file: 'src/old.ts', type: 'delete' })
+    ]);
+    const status = { modified: ['src/app.ts'], added: [], deleted: [], renamed: [], untracked: ['notes.txt'], staged: [] };
+    expect(uncommittedFiles(patch, status)).toEqual(['src/app.ts']);
+  });
+});
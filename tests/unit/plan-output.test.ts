@@ -0,0 +1,46 @@
+/**
+ * Structured Plan Tests
+ */
+
+import { describe, it, expect } from 'vitest';
+import { AIManager } from '../../packages/core/src/ai/index.js';
+import { FixtureReplayer } from '../../packages/core/src/fixtures/index.js';
+
+const context: any = { chunks: [], symbols: [], files: [] };
+
+function planWith(response: string): Promise<any> {
+  const ai = new AIManager({ provider: 'anthropic', model: 'test-model', apiKey: 'sk-test', maxTokens: 2048 });
+  ai.setFixtureReplayer(new FixtureReplayer({
+    version: 1,
+    command: 'do',
+    args: [],
+    recordedAt: 0,
+    entries: [{ type: 'response', text: response }]
+  }));
+  return ai.generatePlan('add a flag', context);
+}
+
+describe('Structured Plans', () => {
+  it('should parse commands and attach token estimates', async () => {
+    const plan = await planWith(JSON.stringify({
+      steps: [
+        { description: 'Add option', type: 'modify', file: 'src/cli.ts', commands: ['npm run build'] }
+      ],
+      commands: ['npm test'],
+      estimatedComplexity: 'low',
+      risks: []
+    }));
+
+    expect(plan.affectedFiles).toEqual(['src/cli.ts']);
+    expect(plan.steps[0].commands).toEqual(['npm run build']);
+    expect(plan.commands).toEqual(['npm test']);
+    expect(plan.estimatedTokens.maxCompletion).toBe(2048);
+    expect(plan.estimatedTokens.prompt).toBeGreaterThan(0);
+  });
+
+  it('should default commands when the model omits them', async () => {
+    const plan = await planWith('{"steps": [], "estimatedComplexity": "medium"}');
+    expect(plan.commands).toEqual([]);
+    expect(plan.estimatedTokens).toBeDefined();
+  });
+});
@@ -0,0 +1,73 @@
+/**
+ * PR Description Section Tests
+ */
+
+import { describe, it, expect } from 'vitest';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import {
+  buildChangelogSection,
+  buildSummarySection,
+  upsertGeneratedSection,
+  detectCIPullRequest,
+  PR_SECTION_START,
+  PR_SECTION_END,
+  PR_SUMMARY_START,
+  PR_SUMMARY_END
+} from '../../packages/cli/src/utils/pr-description.js';
+
+describe('PR description sections', () => {
+  const commits = [
+    { hash: 'bbbbbbbbbb', message: 'fix(sync): handle deleted files' },
+    { hash: 'aaaaaaaaaa', message: 'feat: add watch daemon' }
+  ];
+
+  it('should group conventional commits oldest first', () => {
+    const section = buildChangelogSection(commits);
+    expect(section.startsWith(PR_SECTION_START)).toBe(true);
+    expect(section.endsWith(PR_SECTION_END)).toBe(true);
+    expect(section).toContain('## Changes (2 commits)');
+    expect(section.indexOf('### Features')).toBeLessThan(section.indexOf('### Fixes'));
+    expect(section).toContain('- handle deleted files (bbbbbbb)');
+  });
+
+  it('should replace the section in place and keep author text', () => {
+    const original = upsertGeneratedSection('## Summary\n\nMy notes', buildChangelogSection(commits.slice(1)));
+    expect(original).toContain('My notes');
+
+    const updated = upsertGeneratedSection(original + '\nFooter', buildChangelogSection(commits));
+    expect(updated.split(PR_SECTION_START)).toHaveLength(2);
+    expect(updated).toContain('handle deleted files');
+    expect(updated.startsWith('## Summary\n\nMy notes')).toBe(true);
+    expect(updated.endsWith('Footer')).toBe(true);
+
+    // Re-running with the same commits is a no-op
+    expect(upsertGeneratedSection(updated, buildChangelogSection(commits))).toBe(updated);
+  });
+
+  it('should keep the summary and changelog sections apart', () => {
+    const markers = { start: PR_SUMMARY_START, end: PR_SUMMARY_END, prepend: true };
+    const withChangelog = upsertGeneratedSection('My notes', buildChangelogSection(commits));
+    const described = upsertGeneratedSection(withChangelog, buildSummarySection('## Summary\n\nFirst'), markers);
+    expect(described.startsWith(`${PR_SUMMARY_START}\n## Summary\n\nFirst\n${PR_SUMMARY_END}\n\nMy notes`)).toBe(true);
+
+    const redescribed = upsertGeneratedSection(described, buildSummarySection('## Summary\n\nSecond'), markers);
+    expect(redescribed).toContain('Second');
+    expect(redescribed).not.toContain('First');
+    expect(redescribed).toContain('handle deleted files');
+  });
+
+  it('should detect the PR number from CI environments', () => {
+    const dir = fs.mkdtempSync(path.join(os.tmpdir(), 'cv-pr-'));
+    try {
+      const eventPath = path.join(dir, 'event.json');
+      fs.writeFileSync(eventPath, JSON.stringify({ pull_request: { number: 42 } }));
+      expect(detectCIPullRequest({ GITHUB_EVENT_PATH: eventPath })).toBe(42);
+      expect(detectCIPullRequest({ CI_MERGE_REQUEST_IID: '7' })).toBe(7);
+      expect(detectCIPullRequest({})).toBeUndefined();
+    } finally {
+      fs.rmSync(dir, { recursive: true, force: true });
+    }
+  });
+});
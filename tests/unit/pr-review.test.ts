@@ -0,0 +1,100 @@
+/**
+ * PR Review Tests
+ * Tests for mapping review findings to inline pull request comments
+ */
+
+import { describe, it, expect } from 'vitest';
+import {
+  commentableLines,
+  formatFindingComment,
+  planPullRequestReview,
+  postedFindingIds,
+} from '../../packages/core/src/ai/pr-review.js';
+import type { ReviewFinding } from '../../packages/core/src/ai/review.js';
+
+const diff = [
+  'diff --git a/src/pool.ts b/src/pool.ts',
+  '--- a/src/pool.ts',
+  '+++ b/src/pool.ts',
+  '@@ -10,4 +10,5 @@ export class Pool {',
+  '   constructor() {',
+  '-    this.size = 0;',
+  '+    this.size = MAX;',
+  '+    this.queue = [];',
+  '   }',
+  ' }',
+].join('\n');
+
+function finding(overrides: Partial<ReviewFinding>): ReviewFinding {
+  return {
+    id: 'aaaa1111',
+    severity: 'high',
+    file: 'src/pool.ts',
+    line: 11,
+    title: 'Unbounded queue',
+    message: 'The queue grows without limit.',
+    ...overrides,
+  };
+}
+
+describe('commentableLines', () => {
+  it('includes added and context lines on the new side', () => {
+    expect([...commentableLines(diff).get('src/pool.ts')!.keys()]).toEqual([10, 11, 12, 13, 14]);
+  });
+
+  it('maps context lines to their old-side line', () => {
+    const lines = commentableLines(diff).get('src/pool.ts')!;
+    expect(lines.get(10)).toBe(10);
+    expect(lines.get(11)).toBeUndefined();
+    expect(lines.get(13)).toBe(12);
+  });
+});
+
+describe('planPullRequestReview', () => {
+  it('comments inline on lines in the diff and lists the rest in the body', () => {
+    const plan = planPullRequestReview({
+      summary: 'Sizing change.',
+      findings: [
+        finding({ id: 'aaaa1111', line: 11, endLine: 12 }),
+        finding({ id: 'bbbb2222', line: 40, title: 'Leak in drain', severity: 'medium' }),
+        finding({ id: 'cccc3333', file: 'src/other.ts', line: 3, title: 'Elsewhere', severity: 'low' }),
+      ],
+    }, diff);
+
+    expect(plan.comments).toEqual([
+      { path: 'src/pool.ts', line: 12, startLine: 11, body: formatFindingComment(finding({ id: 'aaaa1111', line: 11, endLine: 12 })) },
+    ]);
+    expect(plan.body).toContain('Sizing change.');
+    expect(plan.body).toContain('Findings: 1 high, 1 medium, 1 low');
+    expect(plan.body).toContain('### Outside the diff');
+    expect(plan.body).toContain('- `src/pool.ts:40`');
+    expect(plan.body).toContain('- `src/other.ts:3`');
+  });
+
+  it('falls back to a single-line comment when the range starts outside the diff', () => {
+    const plan = planPullRequestReview({ summary: '', findings: [finding({ line: 2, endLine: 12 })] }, diff);
+    expect(plan.comments[0]).toMatchObject({ line: 12 });
+    expect(plan.comments[0].startLine).toBeUndefined();
+  });
+
+  it('skips findings already posted', () => {
+    const posted = postedFindingIds([formatFindingComment(finding({})), 'unrelated comment']);
+    expect([...posted]).toEqual(['aaaa1111']);
+
+    const plan = planPullRequestReview({ summary: 'Again.', findings: [finding({})] }, diff, posted);
+    expect(plan.comments).toEqual([]);
+    expect(plan.skipped.map(f => f.id)).toEqual(['aaaa1111']);
+    expect(plan.body).toContain('No new findings.');
+  });
+});
+
+describe('formatFindingComment', () => {
+  it('renders severity, message, suggestion and the ID marker', () => {
+    const body = formatFindingComment(finding({ suggestion: 'Cap the queue.', rule: 'bounded-queues' }));
+    expect(body).toContain('🟠 **High**: Unbounded queue');
+    expect(body).toContain('The queue grows without limit.');
+    expect(body).toContain('**Suggestion:** Cap the queue.');
+    expect(body).toContain('<sub>Rule: bounded-queues</sub>');
+    expect(body.endsWith('<!-- cv-git:finding:aaaa1111 -->')).toBe(true);
+  });
+});
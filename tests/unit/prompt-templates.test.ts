@@ -0,0 +1,96 @@
+/**
+ * Prompt Template Tests
+ * Tests for .cv/prompts/ parsing, validation and rendering of prompts and system prompts
+ */
+
+import { describe, it, expect, beforeEach, afterEach } from 'vitest';
+import * as os from 'os';
+import * as path from 'path';
+import { promises as fs } from 'fs';
+import {
+  NO_PROMPT_TEMPLATES,
+  PROMPT_VARIABLES,
+  PromptTemplateError,
+  loadPromptTemplates,
+  parseTemplate,
+  renderTemplate,
+} from '../../packages/core/src/ai/prompt-templates.js';
+
+const render = (source: string, values: Record<string, string | undefined>) =>
+  renderTemplate(parseTemplate(source, 'prompts/test.md', PROMPT_VARIABLES.command), values);
+
+describe('parseTemplate', () => {
+  it('should render variables, sections and comments', () => {
+    const source = '{{! compliance }}Repo {{ repo }}.{{#if context}}\nCode:\n{{context}}{{/if}}\n{{prompt}}';
+    expect(render(source, { repo: 'cv-git', context: 'fn a()', prompt: 'Explain' })).toBe('Repo cv-git.\nCode:\nfn a()\nExplain');
+    expect(render(source, { repo: 'cv-git', context: '  ', prompt: 'Explain' })).toBe('Repo cv-git.\nExplain');
+  });
+
+  it('should reject unknown variables with the line they are on', () => {
+    expect(() => parseTemplate('ok\n{{repository}}', 'prompts/explain.md', PROMPT_VARIABLES.command))
+      .toThrow('prompts/explain.md:2: unknown variable "repository"');
+    expect(() => parseTemplate('{{#if prompt}}x{{/if}}', 'prompts/system.md', PROMPT_VARIABLES.system))
+      .toThrow(PromptTemplateError);
+  });
+
+  it('should reject unclosed sections and tags', () => {
+    expect(() => parseTemplate('{{#if context}}\n{{context}}', 'f.md', PROMPT_VARIABLES.command)).toThrow('f.md:1: {{#if context}} is never closed');
+    expect(() => parseTemplate('{{/if}}', 'f.md', PROMPT_VARIABLES.command)).toThrow('{{/if}} without {{#if}}');
+    expect(() => parseTemplate('a\n{{prompt', 'f.md', PROMPT_VARIABLES.command)).toThrow('f.md:2: "{{" is never closed');
+    expect(() => parseTemplate('{{#each context}}{{/each}}', 'f.md', PROMPT_VARIABLES.command)).toThrow('unknown tag');
+  });
+});
+
+describe('loadPromptTemplates', () => {
+  let repoRoot: string;
+  let promptsDir: string;
+
+  beforeEach(async () => {
+    repoRoot = await fs.mkdtemp(path.join(os.tmpdir(), 'cv-prompts-'));
+    promptsDir = path.join(repoRoot, '.cv', 'prompts');
+    await fs.mkdir(promptsDir, { recursive: true });
+  });
+
+  afterEach(async () => {
+    await fs.rm(repoRoot, { recursive: true, force: true });
+  });
+
+  it('should use the built-in prompts without a directory', async () => {
+    await fs.rm(promptsDir, { recursive: true });
+    const templates = await loadPromptTemplates(repoRoot);
+    expect(templates).toBe(NO_PROMPT_TEMPLATES);
+    expect(templates.renderPrompt('explain', 'built-in')).toBe('built-in');
+    expect(templates.renderSystem('explain')).toBeUndefined();
+  });
+
+  it('should render command prompts and system prompt overrides', async () => {
+    await fs.writeFile(path.join(repoRoot, '.cv', 'sync_state.json'), JSON.stringify({ languages: { python: 3, typescript: 40 } }));
+    await fs.writeFile(path.join(promptsDir, 'system.md'), 'Follow the {{repo}} compliance rules.');
+    await fs.writeFile(path.join(promptsDir, 'review.system.md'), '{{system}}\nReview {{language}} code.');
+    await fs.writeFile(path.join(promptsDir, 'explain.md'), '{{prompt}}\n\nTarget: {{input}}');
+
+    const templates = await loadPromptTemplates(repoRoot, 'billing');
+    expect(templates.files()).toEqual(['explain.md', 'review.system.md', 'system.md']);
+    expect(templates.renderPrompt('explain', 'Explain it', { input: 'src/a.ts' })).toBe('Explain it\n\nTarget: src/a.ts');
+    expect(templates.renderPrompt('review', 'Review it')).toBe('Review it');
+    expect(templates.renderSystem('explain')).toBe('Follow the billing compliance rules.');
+    expect(templates.renderSystem('review')).toBe('Follow the billing compliance rules.\nReview typescript code.');
+    expect(templates.renderSystem()).toBe('Follow the billing compliance rules.');
+  });
+
+  it('should change the fingerprint with the sources', async () => {
+    await fs.writeFile(path.join(promptsDir, 'system.md'), 'One');
+    const first = (await loadPromptTemplates(repoRoot)).fingerprint;
+    await fs.writeFile(path.join(promptsDir, 'system.md'), 'Two');
+    expect((await loadPromptTemplates(repoRoot)).fingerprint).not.toBe(first);
+  });
+
+  it('should reject unknown files and templates that drop the input', async () => {
+    await fs.writeFile(path.join(promptsDir, 'chat.md'), '{{prompt}}');
+    await expect(loadPromptTemplates(repoRoot)).rejects.toThrow('prompts/chat.md: not a prompt template');
+    await fs.rm(path.join(promptsDir, 'chat.md'));
+
+    await fs.writeFile(path.join(promptsDir, 'commit.md'), 'Write a commit message for {{repo}}.');
+    await expect(loadPromptTemplates(repoRoot)).rejects.toThrow('uses neither {{prompt}} nor {{input}}');
+  });
+});
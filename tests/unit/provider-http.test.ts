@@ -0,0 +1,144 @@
+/**
+ * Provider HTTP Tests
+ * Tests for rate limiting, concurrency caps and retries of provider requests
+ */
+
+import { describe, it, expect, vi, beforeEach, afterEach } from 'vitest';
+import {
+  ProviderLimiter,
+  configureProviderLimits,
+  getProviderLimiter,
+  parseRetryAfter,
+  providerFetch,
+  retryDelay,
+} from '../../packages/core/src/ai/http.js';
+
+describe('parseRetryAfter', () => {
+  it('should read seconds, HTTP dates and retry-after-ms', () => {
+    expect(parseRetryAfter(new Headers({ 'retry-after': '3' }))).toBe(3000);
+    expect(parseRetryAfter(new Headers({ 'retry-after-ms': '250', 'retry-after': '3' }))).toBe(250);
+
+    const now = Date.parse('2026-01-01T00:00:00Z');
+    expect(parseRetryAfter(new Headers({ 'retry-after': 'Thu, 01 Jan 2026 00:00:05 GMT' }), now)).toBe(5000);
+    expect(parseRetryAfter(new Headers())).toBeUndefined();
+    expect(parseRetryAfter(new Headers({ 'retry-after': 'soon' }))).toBeUndefined();
+  });
+});
+
+describe('retryDelay', () => {
+  it('should stay within the exponential window', () => {
+    for (let attempt = 0; attempt < 6; attempt++) {
+      const delay = retryDelay(attempt, { baseDelayMs: 100, maxDelayMs: 1000 });
+      expect(delay).toBeGreaterThanOrEqual(0);
+      expect(delay).toBeLessThanOrEqual(Math.min(1000, 100 * 2 ** attempt));
+    }
+  });
+
+  it('should wait at least as long as Retry-After asks', () => {
+    expect(retryDelay(0, { baseDelayMs: 100 }, 5000)).toBeGreaterThanOrEqual(5000);
+  });
+});
+
+describe('ProviderLimiter', () => {
+  it('should cap requests in flight', async () => {
+    const limiter = new ProviderLimiter({ maxConcurrent: 2 });
+    let running = 0;
+    let peak = 0;
+
+    await Promise.all(Array.from({ length: 6 }, () => limiter.run(async () => {
+      running++;
+      peak = Math.max(peak, running);
+      await new Promise(r => setTimeout(r, 5));
+      running--;
+    })));
+
+    expect(peak).toBe(2);
+    expect(limiter.inFlight).toBe(0);
+  });
+
+  it('should space requests by the per-minute rate once the burst is used', async () => {
+    // 6000/min = one every 10ms, burst of 1
+    const limiter = new ProviderLimiter({ requestsPerMinute: 6000, maxConcurrent: 1 });
+    const started: number[] = [];
+
+    for (let i = 0; i < 3; i++) {
+      await limiter.run(async () => { started.push(Date.now()); });
+    }
+
+    expect(started[2] - started[0]).toBeGreaterThanOrEqual(15);
+  });
+
+  it('should release the slot when a request fails', async () => {
+    const limiter = new ProviderLimiter({ maxConcurrent: 1 });
+    await expect(limiter.run(async () => { throw new Error('boom'); })).rejects.toThrow('boom');
+    expect(await limiter.run(async () => 'next')).toBe('next');
+  });
+});
+
+describe('providerFetch', () => {
+  beforeEach(() => {
+    configureProviderLimits({ test: { maxRetries: 2, baseDelayMs: 1, maxDelayMs: 5 } });
+  });
+
+  afterEach(() => {
+    vi.unstubAllGlobals();
+    configureProviderLimits(undefined);
+  });
+
+  it('should retry rate limits and server errors', async () => {
+    const mockFetch = vi.fn()
+      .mockResolvedValueOnce(new Response('slow down', { status: 429, headers: { 'retry-after-ms': '1' } }))
+      .mockResolvedValueOnce(new Response('oops', { status: 503 }))
+      .mockResolvedValueOnce(new Response('ok', { status: 200 }));
+    vi.stubGlobal('fetch', mockFetch);
+
+    const response = await providerFetch('test', 'https://api.example.com/v1/embeddings', { method: 'POST', body: '{}' });
+
+    expect(response.status).toBe(200);
+    expect(mockFetch).toHaveBeenCalledTimes(3);
+  });
+
+  it('should return the last response once retries run out', async () => {
+    const mockFetch = vi.fn(async () => new Response('slow down', { status: 429 }));
+    vi.stubGlobal('fetch', mockFetch);
+
+    const response = await providerFetch('test', 'https://api.example.com');
+
+    expect(response.status).toBe(429);
+    expect(mockFetch).toHaveBeenCalledTimes(3);
+  });
+
+  it('should not retry client errors', async () => {
+    const mockFetch = vi.fn(async () => new Response('bad key', { status: 401 }));
+    vi.stubGlobal('fetch', mockFetch);
+
+    expect((await providerFetch('test', 'https://api.example.com')).status).toBe(401);
+    expect(mockFetch).toHaveBeenCalledTimes(1);
+  });
+
+  it('should not wait out a Retry-After longer than two minutes', async () => {
+    const mockFetch = vi.fn(async () => new Response('quota', { status: 429, headers: { 'retry-after': '3600' } }));
+    vi.stubGlobal('fetch', mockFetch);
+
+    expect((await providerFetch('test', 'https://api.example.com')).status).toBe(429);
+    expect(mockFetch).toHaveBeenCalledTimes(1);
+  });
+
+  it('should retry dropped connections but not timeouts', async () => {
+    const mockFetch = vi.fn()
+      .mockRejectedValueOnce(new TypeError('fetch failed'))
+      .mockResolvedValueOnce(new Response('ok'));
+    vi.stubGlobal('fetch', mockFetch);
+    expect((await providerFetch('test', 'https://api.example.com')).status).toBe(200);
+
+    const timeout = Object.assign(new Error('The operation timed out'), { name: 'TimeoutError' });
+    vi.stubGlobal('fetch', vi.fn(async () => { throw timeout; }));
+    await expect(providerFetch('test', 'https://api.example.com')).rejects.toThrow('timed out');
+  });
+
+  it('should share one limiter per provider', () => {
+    expect(getProviderLimiter('test')).toBe(getProviderLimiter('test'));
+    expect(getProviderLimiter('test').limits.maxRetries).toBe(2);
+    expect(getProviderLimiter('openai').limits.maxConcurrent).toBe(8);
+  });
+});
@@ -0,0 +1,234 @@
+/**
+ * Vector Quantization Tests
+ * Tests for the int8 and product quantization codecs and the quantized embedded store
+ */
+
+import { describe, it, expect, beforeEach, afterEach } from 'vitest';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import {
+  codecFromJSON,
+  Int8Codec,
+  normalizeVector,
+  ProductCodec,
+  trainCodec,
+  vectorBytes,
+  VectorCodec,
+} from '../../packages/core/src/vector/quantization.js';
+import { QuantizedVectorStore } from '../../packages/core/src/vector/stores/quantized-store.js';
+import { matchesFilter } from '../../packages/core/src/vector/store.js';
+import type { VectorFilter, VectorPoint, VectorStore } from '../../packages/core/src/vector/store.js';
+
+class MemoryStore implements VectorStore {
+  readonly type = 'lancedb' as const;
+  collections = new Map<string, Map<string, VectorPoint>>();
+  sizes = new Map<string, number>();
+
+  async connect() {}
+  async close() {}
+  async listCollections() { return [...this.collections.keys()]; }
+  async createCollection(name: string, vectorSize: number) {
+    if (!this.collections.has(name)) {
+      this.collections.set(name, new Map());
+      this.sizes.set(name, vectorSize);
+    }
+  }
+  async deleteCollection(name: string) { this.collections.delete(name); }
+  async getCollectionStats(name: string) {
+    const c = this.collections.get(name);
+    return c ? { vectorSize: this.sizes.get(name)!, pointsCount: c.size } : null;
+  }
+  async upsert(name: string, points: VectorPoint[]) {
+    for (const p of points) this.collections.get(name)!.set(String(p.id), p);
+  }
+  async delete(name: string, ids: Array<string | number>) {
+    for (const id of ids) this.collections.get(name)?.delete(String(id));
+  }
+  async retrieve(name: string, ids: Array<string | number>) {
+    const points = this.collections.get(name);
+    return ids.map(id => points?.get(String(id))).filter((p): p is VectorPoint => !!p);
+  }
+  async search(name: string, vector: number[], options: { limit: number; filter?: VectorFilter }) {
+    return [...this.collections.get(name)!.values()]
+      .filter(p => matchesFilter(p.payload, options.filter))
+      .map(p => ({ id: p.id, score: dot(normalizeVector(p.vector), normalizeVector(vector)), payload: p.payload }))
+      .sort((a, b) => b.score - a.score)
+      .slice(0, options.limit);
+  }
+  async scroll(name: string, options: { limit: number; offset?: string }) {
+    const all = [...this.collections.get(name)!.values()];
+    const start = options.offset ? parseInt(options.offset, 10) : 0;
+    const end = start + options.limit;
+    return { points: all.slice(start, end), nextOffset: end < all.length ? String(end) : undefined };
+  }
+}
+
+function dot(a: ArrayLike<number>, b: ArrayLike<number>): number {
+  let sum = 0;
+  for (let i = 0; i < a.length; i++) sum += a[i] * b[i];
+  return sum;
+}
+
+/** Deterministic vectors in [-1, 1) */
+function randomVectors(count: number, dimension: number, seed = 7): number[][] {
+  let state = seed;
+  const next = () => {
+    state = (state * 1103515245 + 12345) % 2147483648;
+    return state / 1073741824 - 1;
+  };
+  return Array.from({ length: count }, () => Array.from({ length: dimension }, next));
+}
+
+/** Share of the exact top-k the codec's scorer also ranks in its top-k */
+function recallAt(codec: VectorCodec, vectors: Float32Array[], queries: Float32Array[], k: number): number {
+  const codes = vectors.map(v => codec.encode(v));
+  let found = 0;
+  for (const query of queries) {
+    const top = (scores: number[]) => scores.map((score, i) => ({ score, i })).sort((a, b) => b.score - a.score).slice(0, k).map(s => s.i);
+    const exact = new Set(top(vectors.map(v => dot(v, query))));
+    const scorer = codec.scorer(query);
+    found += top(codes.map(scorer)).filter(i => exact.has(i)).length;
+  }
+  return found / (queries.length * k);
+}
+
+describe('codecs', () => {
+  const vectors = randomVectors(400, 32).map(normalizeVector);
+  const queries = randomVectors(10, 32, 99).map(normalizeVector);
+
+  it('int8 stores a byte per dimension and keeps nearly every neighbour', () => {
+    const codec = Int8Codec.train(vectors);
+    expect(codec.bytesPerVector).toBe(32);
+    expect(codec.encode(vectors[0])).toHaveLength(32);
+
+    const decoded = codec.decode(codec.encode(vectors[0]));
+    const error = Math.max(...Array.from(decoded, (x, i) => Math.abs(x - vectors[0][i])));
+    expect(error).toBeLessThan(0.01);
+    expect(recallAt(codec, vectors, queries, 10)).toBeGreaterThanOrEqual(0.9);
+  });
+
+  it('pq stores a byte per subvector and keeps most neighbours', () => {
+    const codec = ProductCodec.train(vectors, 8);
+    expect(codec.bytesPerVector).toBe(8);
+    expect(codec.encode(vectors[0])).toHaveLength(8);
+    expect(recallAt(codec, vectors, queries, 10)).toBeGreaterThanOrEqual(0.6);
+  });
+
+  it('round-trips trained codecs through JSON', () => {
+    for (const codec of [trainCodec(vectors, { codec: 'int8' }), trainCodec(vectors, { codec: 'pq', subvectors: 4 })]) {
+      const restored = codecFromJSON(JSON.parse(JSON.stringify(codec.toJSON())));
+      expect(restored.name).toBe(codec.name);
+      expect(restored.encode(vectors[3])).toEqual(codec.encode(vectors[3]));
+      expect(restored.scorer(queries[0])(codec.encode(vectors[3]))).toBeCloseTo(codec.scorer(queries[0])(codec.encode(vectors[3])), 5);
+    }
+  });
+
+  it('reports bytes per vector for each codec', () => {
+    expect(vectorBytes('f32', 768)).toBe(3072);
+    expect(vectorBytes('none', 768)).toBe(3072);
+    expect(vectorBytes('int8', 768)).toBe(768);
+    expect(vectorBytes('pq', 768)).toBe(96);
+    expect(vectorBytes('pq', 768, 48)).toBe(48);
+  });
+});
+
+describe('QuantizedVectorStore', () => {
+  let dir: string;
+  let inner: MemoryStore;
+  const vectors = randomVectors(30, 8);
+  const points = (from: number, to: number): VectorPoint[] =>
+    vectors.slice(from, to).map((vector, i) => ({ id: `p${from + i}`, vector, payload: { file: `src/${from + i}.ts`, lang: (from + i) % 2 ? 'ts' : 'py' } }));
+
+  beforeEach(() => {
+    dir = fs.mkdtempSync(path.join(os.tmpdir(), 'cv-quantized-'));
+    inner = new MemoryStore();
+  });
+
+  afterEach(() => {
+    fs.rmSync(dir, { recursive: true, force: true });
+  });
+
+  it('stores f32 codes until the collection has enough points to train', async () => {
+    const store = new QuantizedVectorStore(inner, dir, { codec: 'int8', trainPoints: 20 });
+    await store.createCollection('code_chunks', 8);
+    await store.upsert('code_chunks', points(0, 10));
+
+    expect(inner.sizes.get('code_chunks')).toBe(1);
+    expect(inner.collections.get('code_chunks')!.get('p0')!.payload._q).toMatch(/^f32:/);
+    expect(await store.getCodec('code_chunks')).toEqual({ codec: 'f32', bytesPerVector: 32, trained: false });
+    expect(await store.getCollectionStats('code_chunks')).toEqual({ vectorSize: 8, pointsCount: 10 });
+
+    await store.upsert('code_chunks', points(10, 30));
+    expect([...inner.collections.get('code_chunks')!.values()].every(p => String(p.payload._q).startsWith('int8:'))).toBe(true);
+    expect(await store.getCodec('code_chunks')).toEqual({ codec: 'int8', bytesPerVector: 8, trained: true });
+
+    // The trained codec survives a restart
+    const reopened = new QuantizedVectorStore(inner, dir, { codec: 'int8', trainPoints: 20 });
+    expect((await reopened.getCodec('code_chunks'))?.trained).toBe(true);
+  });
+
+  it('decodes vectors and hides the code in retrieved and scrolled points', async () => {
+    const store = new QuantizedVectorStore(inner, dir, { codec: 'int8', trainPoints: 20 });
+    await store.createCollection('code_chunks', 8);
+    await store.upsert('code_chunks', points(0, 30));
+
+    const [point] = await store.retrieve('code_chunks', ['p4'], { withVector: true });
+    expect(point.payload).toEqual({ file: 'src/4.ts', lang: 'py' });
+    const unit = normalizeVector(vectors[4]);
+    point.vector.forEach((x, i) => expect(x).toBeCloseTo(unit[i], 1));
+
+    const page = await store.scroll('code_chunks', { limit: 5 });
+    expect(page.points).toHaveLength(5);
+    expect(page.points[0].vector).toHaveLength(8);
+    expect(page.points[0].payload._q).toBeUndefined();
+  });
+
+  it('finds nearest neighbours from the codes and applies filters', async () => {
+    const store = new QuantizedVectorStore(inner, dir, { codec: 'pq', subvectors: 4, trainPoints: 20 });
+    await store.createCollection('code_chunks', 8);
+    await store.upsert('code_chunks', points(0, 30));
+
+    const [best] = await store.search('code_chunks', vectors[12], { limit: 3 });
+    expect(best.id).toBe('p12');
+    expect(best.payload._q).toBeUndefined();
+
+    const filtered = await store.search('code_chunks', vectors[12], { limit: 5, filter: { lang: 'ts' } });
+    expect(filtered).toHaveLength(5);
+    expect(filtered.every(hit => hit.payload.lang === 'ts')).toBe(true);
+
+    await store.delete('code_chunks', ['p12']);
+    expect((await store.search('code_chunks', vectors[12], { limit: 3 })).map(h => h.id)).not.toContain('p12');
+  });
+
+  it('leaves collections alone without a codec', async () => {
+    const store = new QuantizedVectorStore(inner, dir, { codec: 'none' });
+    await store.createCollection('code_chunks', 8);
+    await store.upsert('code_chunks', points(0, 5));
+
+    expect(inner.collections.get('code_chunks')!.get('p0')).toEqual(points(0, 1)[0]);
+    expect(await store.getCodec('code_chunks')).toBeNull();
+    expect((await store.search('code_chunks', vectors[2], { limit: 1 }))[0].id).toBe('p2');
+  });
+
+  it('converts collections indexed before quantization was enabled on compact', async () => {
+    await inner.createCollection('code_chunks', 8);
+    await inner.upsert('code_chunks', points(0, 30));
+    await inner.createCollection('document_chunks', 8);
+    await inner.upsert('document_chunks', points(0, 5));
+
+    const store = new QuantizedVectorStore(inner, dir, { codec: 'int8', trainPoints: 20 });
+    expect(await store.getCodec('code_chunks')).toBeNull();
+    await store.compact();
+
+    expect(await store.getCodec('code_chunks')).toEqual({ codec: 'int8', bytesPerVector: 8, trained: true });
+    expect(inner.sizes.get('code_chunks')).toBe(1);
+    expect(inner.collections.has('code_chunks__quantizing')).toBe(false);
+    expect(await store.getCollectionStats('code_chunks')).toEqual({ vectorSize: 8, pointsCount: 30 });
+    expect((await store.search('code_chunks', vectors[7], { limit: 1 }))[0].id).toBe('p7');
+
+    // Too small to train: left as it was
+    expect(await store.getCodec('document_chunks')).toBeNull();
+    expect(inner.sizes.get('document_chunks')).toBe(8);
+  });
+});
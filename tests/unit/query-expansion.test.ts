@@ -0,0 +1,88 @@
+/**
+ * Query Expansion Tests
+ * Tests for synonym and model reformulations of retrieval queries, and their per-command settings
+ */
+
+import { describe, it, expect } from 'vitest';
+import {
+  QueryExpander,
+  buildSynonymMap,
+  expandWithSynonyms,
+  parseExpansions,
+  resolveQueryExpansion,
+} from '../../packages/core/src/context/query-expansion.js';
+
+describe('expandWithSynonyms', () => {
+  it('should substitute synonyms of known terms', () => {
+    expect(expandWithSynonyms('where is auth handled', 3)).toEqual([
+      'where is authentication handled',
+      'where is login handled',
+      'where is signin handled',
+    ]);
+  });
+
+  it('should substitute plurals and keep the unknown words', () => {
+    expect(expandWithSynonyms('failed logins', 1)).toEqual(['failed auth']);
+    expect(expandWithSynonyms('frobnicate the widgets', 2)).toEqual(['frobnicate the component', 'frobnicate the element']);
+    expect(expandWithSynonyms('frobnicate', 3)).toEqual([]);
+  });
+
+  it('should prefer configured synonyms', () => {
+    const synonyms = buildSynonymMap({ tenant: ['org', 'workspace'] });
+    expect(expandWithSynonyms('tenant config', 2, synonyms)).toEqual(['org configuration', 'workspace settings']);
+    expect(synonyms.get('org')).toEqual(['tenant', 'workspace']);
+  });
+});
+
+describe('parseExpansions', () => {
+  it('should strip numbering and quotes, and drop repeats and the query', () => {
+    const reply = 'Here are the queries:\n1. "login handler"\n2) Auth\n- session token validation\n* login handler\n';
+    expect(parseExpansions(reply, 'auth', 3)).toEqual(['login handler', 'session token validation']);
+    expect(parseExpansions('a\nb\nc\nd', 'q', 2)).toEqual(['a', 'b']);
+  });
+});
+
+describe('QueryExpander', () => {
+  it('should put the query first and clamp the reformulations', async () => {
+    const expander = new QueryExpander({ mode: 'synonyms', queries: 10 });
+    expect(expander.count).toBe(4);
+    const queries = await expander.expand('db retry');
+    expect(queries[0]).toBe('db retry');
+    expect(queries).toHaveLength(5);
+    expect(await new QueryExpander({ mode: 'off' }).expand('db retry')).toEqual(['db retry']);
+  });
+
+  it('should ask the model once per query', async () => {
+    const prompts: string[] = [];
+    const expander = new QueryExpander({ mode: 'llm', queries: 2 }, async prompt => {
+      prompts.push(prompt);
+      return 'login flow\ncredential check\nsession handling';
+    });
+    expect(await expander.expand('auth')).toEqual(['auth', 'login flow', 'credential check']);
+    await expander.expand('auth');
+    expect(prompts).toHaveLength(1);
+    expect(prompts[0]).toContain('exactly 2 lines');
+  });
+
+  it('should fall back to synonyms when the model fails', async () => {
+    const expander = new QueryExpander({ mode: 'llm', queries: 1 }, async () => {
+      throw new Error('offline');
+    });
+    expect(await expander.expand('auth')).toEqual(['auth', 'authentication']);
+  });
+});
+
+describe('resolveQueryExpansion', () => {
+  const config = { mode: 'synonyms' as const, queries: 2, commands: { explain: 'llm' as const } };
+
+  it('should take the flag, then the command, then the default', () => {
+    expect(resolveQueryExpansion(config, 'explain', 'off').mode).toBe('off');
+    expect(resolveQueryExpansion(config, 'explain')).toEqual({ mode: 'llm', queries: 2, synonyms: undefined });
+    expect(resolveQueryExpansion(config, 'do').mode).toBe('synonyms');
+    expect(resolveQueryExpansion(undefined, 'do').mode).toBe('off');
+  });
+
+  it('should reject unknown modes', () => {
+    expect(() => resolveQueryExpansion(config, 'ask', 'wordnet')).toThrow('Unknown query expansion "wordnet"');
+  });
+});
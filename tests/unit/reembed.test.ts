@@ -0,0 +1,224 @@
+/**
+ * Embedding Model Migration Tests
+ * Tests for building the new model's collections, resuming, catching up and the alias cutover
+ */
+
+import { describe, it, expect } from 'vitest';
+import {
+  catchUpReembed,
+  planReembed,
+  reembedCollectionName,
+  reembedConfig,
+  ReembedState,
+  runReembed,
+} from '../../packages/core/src/vector/reembed.js';
+import { AliasedVectorStore } from '../../packages/core/src/vector/stores/aliased-store.js';
+import type { VectorPoint, VectorStore } from '../../packages/core/src/vector/store.js';
+
+class MemoryStore implements VectorStore {
+  readonly type = 'lancedb' as const;
+  collections = new Map<string, Map<string, VectorPoint>>();
+  sizes = new Map<string, number>();
+
+  async connect() {}
+  async close() {}
+  async listCollections() { return [...this.collections.keys()]; }
+  async createCollection(name: string, vectorSize: number) {
+    if (!this.collections.has(name)) {
+      this.collections.set(name, new Map());
+      this.sizes.set(name, vectorSize);
+    }
+  }
+  async deleteCollection(name: string) { this.collections.delete(name); }
+  async getCollectionStats(name: string) {
+    const c = this.collections.get(name);
+    return c ? { vectorSize: this.sizes.get(name) ?? 2, pointsCount: c.size } : null;
+  }
+  async upsert(name: string, points: VectorPoint[]) {
+    for (const p of points) this.collections.get(name)!.set(String(p.id), p);
+  }
+  async delete(name: string, ids: Array<string | number>) {
+    for (const id of ids) this.collections.get(name)?.delete(String(id));
+  }
+  async retrieve(name: string, ids: Array<string | number>) {
+    const points = this.collections.get(name);
+    return ids.map(id => points?.get(String(id))).filter((p): p is VectorPoint => !!p);
+  }
+  async search() { return []; }
+  async scroll(name: string, options: { limit: number; offset?: string }) {
+    const all = [...this.collections.get(name)!.values()];
+    const start = options.offset ? parseInt(options.offset, 10) : 0;
+    const end = start + options.limit;
+    return { points: all.slice(start, end), nextOffset: end < all.length ? String(end) : undefined };
+  }
+}
+
+function storeWith(collections: Record<string, Array<Record<string, unknown>>>): MemoryStore {
+  const store = new MemoryStore();
+  for (const [name, payloads] of Object.entries(collections)) {
+    store.collections.set(name, new Map(payloads.map((payload, i) => [`${name}-${i}`, { id: `${name}-${i}`, vector: [1, 0], payload }])));
+  }
+  return store;
+}
+
+/** Records every text it embeds; vectors are [length, 1, 1] */
+function fakeEmbedder() {
+  const embedded: string[] = [];
+  return {
+    embedded,
+    embedBatch: async (texts: string[]) => {
+      embedded.push(...texts);
+      return texts.map(t => [t.length, 1, 1]);
+    },
+    prepareCodeForEmbedding: (chunk: any) => `code ${chunk.file}: ${chunk.text}`,
+    prepareDocumentForEmbedding: (chunk: any) => `doc ${chunk.file}: ${chunk.text}`,
+  };
+}
+
+function plan(store: MemoryStore): ReembedState {
+  return planReembed({
+    collections: [...store.collections.entries()].map(([name, points]) => ({ name, points: points.size })),
+    model: 'nomic-embed-text',
+    provider: 'ollama',
+    dimensions: 3,
+    from: { model: 'text-embedding-3-small', provider: 'openai' },
+  });
+}
+
+const fixture = () => storeWith({
+  code_chunks: [
+    { file: 'src/a.ts', text: 'function a() {}' },
+    { file: 'src/b.ts', text: 'function b() {}' },
+    { file: 'src/c.ts', text: 'function c() {}' },
+  ],
+  document_chunks: [{ file: 'README.md', text: '# Usage' }],
+  abc123_summaries: [{ file: 'src', summary: 'Source tree' }, { file: 'empty' }],
+});
+
+describe('planReembed', () => {
+  it('names a target per collection and keeps the current source', () => {
+    expect(reembedCollectionName('code_chunks', 'openai/text-embedding-3-large')).toBe('code_chunks__openai_text_embedding_3_large');
+
+    const state = planReembed({
+      collections: [{ name: 'code_chunks', points: 3 }],
+      aliases: { code_chunks: 'code_chunks__text_embedding_3_small' },
+      model: 'nomic-embed-text',
+      provider: 'ollama',
+      dimensions: 768,
+      from: { model: 'text-embedding-3-small', provider: 'openai' },
+    });
+    expect(state.collections).toEqual([{
+      name: 'code_chunks',
+      source: 'code_chunks__text_embedding_3_small',
+      target: 'code_chunks__nomic_embed_text',
+      copied: 0,
+      skipped: 0,
+      total: 3,
+      done: false,
+    }]);
+  });
+});
+
+describe('runReembed', () => {
+  it('re-embeds each collection\'s text into its target with the same ids and payloads', async () => {
+    const store = fixture();
+    const embedder = fakeEmbedder();
+    const state = await runReembed(plan(store), { store, embedder, batchSize: 2, save: async () => {} });
+
+    expect(state.collections.map(c => [c.name, c.copied, c.skipped, c.done])).toEqual([
+      ['code_chunks', 3, 0, true],
+      ['document_chunks', 1, 0, true],
+      ['abc123_summaries', 1, 1, true],
+    ]);
+    expect(embedder.embedded).toContain('code src/a.ts: function a() {}');
+    expect(embedder.embedded).toContain('doc README.md: # Usage');
+    expect(embedder.embedded).toContain('Source tree');
+
+    const target = store.collections.get('code_chunks__nomic_embed_text')!;
+    expect(store.sizes.get('code_chunks__nomic_embed_text')).toBe(3);
+    expect(target.get('code_chunks-1')).toEqual({
+      id: 'code_chunks-1',
+      vector: ['code src/b.ts: function b() {}'.length, 1, 1],
+      payload: { file: 'src/b.ts', text: 'function b() {}' },
+    });
+    // The old collections are untouched
+    expect(store.collections.get('code_chunks')!.get('code_chunks-1')!.vector).toEqual([1, 0]);
+  });
+
+  it('stops on abort and resumes from the saved offset', async () => {
+    const store = fixture();
+    const controller = new AbortController();
+    let state = plan(store);
+    state = await runReembed(state, {
+      store,
+      embedder: fakeEmbedder(),
+      batchSize: 2,
+      signal: controller.signal,
+      save: async () => controller.abort(),
+    });
+    expect(state.collections[0]).toMatchObject({ copied: 2, offset: '2', done: false });
+
+    const resumed = fakeEmbedder();
+    state = await runReembed(JSON.parse(JSON.stringify(state)), { store, embedder: resumed, batchSize: 2, save: async () => {} });
+    expect(resumed.embedded.filter(t => t.startsWith('code '))).toEqual(['code src/c.ts: function c() {}']);
+    expect(state.collections.every(c => c.done)).toBe(true);
+    expect(store.collections.get('code_chunks__nomic_embed_text')!.size).toBe(3);
+  });
+
+  it('waits to stay under the rate limit', async () => {
+    const store = fixture();
+    const waits: number[] = [];
+    await runReembed(plan(store), {
+      store,
+      embedder: fakeEmbedder(),
+      batchSize: 1,
+      rateLimit: 60,
+      save: async () => {},
+      sleep: async ms => { waits.push(ms); },
+    });
+    // One text a second: no wait after the last batch of each collection
+    expect(waits).toHaveLength(3);
+    expect(waits[0]).toBeGreaterThan(900);
+  });
+});
+
+describe('catchUpReembed', () => {
+  it('re-embeds points changed since the copy and removes deleted ones', async () => {
+    const store = fixture();
+    const state = await runReembed(plan(store), { store, embedder: fakeEmbedder(), save: async () => {} });
+
+    const code = store.collections.get('code_chunks')!;
+    code.set('code_chunks-0', { id: 'code_chunks-0', vector: [1, 0], payload: { text: 'function a(x) {}', file: 'src/a.ts' } });
+    code.delete('code_chunks-2');
+    code.set('new', { id: 'new', vector: [1, 0], payload: { file: 'src/d.ts', text: 'function d() {}' } });
+
+    const embedder = fakeEmbedder();
+    expect(await catchUpReembed(state, { store, embedder })).toEqual({ updated: 2, deleted: 1 });
+    expect(embedder.embedded).toEqual(['code src/a.ts: function a(x) {}', 'code src/d.ts: function d() {}']);
+    expect([...store.collections.get('code_chunks__nomic_embed_text')!.keys()].sort())
+      .toEqual(['code_chunks-0', 'code_chunks-1', 'new']);
+  });
+});
+
+describe('cutover', () => {
+  it('switches the model and serves every collection from its target', async () => {
+    const store = fixture();
+    const state = await runReembed(plan(store), { store, embedder: fakeEmbedder(), save: async () => {} });
+    const config: any = {
+      embedding: { provider: 'openai', model: 'text-embedding-3-small', dimensions: 1536 },
+      vector: { provider: 'lancedb', collections: { codeChunks: 'code_chunks' } },
+    };
+    const next = reembedConfig(config, state);
+    expect(next.embedding).toEqual({ provider: 'ollama', model: 'nomic-embed-text', dimensions: 3 });
+    expect(next.vector.aliases).toEqual({
+      code_chunks: 'code_chunks__nomic_embed_text',
+      document_chunks: 'document_chunks__nomic_embed_text',
+      abc123_summaries: 'abc123_summaries__nomic_embed_text',
+    });
+
+    const aliased = new AliasedVectorStore(store, next.vector.aliases!);
+    expect((await aliased.scroll('code_chunks', { limit: 10 })).points[0].vector).toEqual(['code src/a.ts: function a() {}'.length, 1, 1]);
+    expect(await aliased.getCollectionStats('code_chunks')).toEqual({ vectorSize: 3, pointsCount: 3 });
+    expect((await aliased.listCollections()).sort()).toEqual(['abc123_summaries', 'code_chunks', 'document_chunks']);
+  });
+});
@@ -0,0 +1,137 @@
+/**
+ * Refactor Tests
+ * Tests for symbol mentions, graph-driven refactoring scope, prompts and patch reversal
+ */
+
+import { describe, it, expect } from 'vitest';
+import type { SymbolNode } from '../../packages/shared/src/types.js';
+import {
+  buildRefactorPrompt,
+  collectRefactorScope,
+  detectBuildCommand,
+  extractSymbolMentions,
+  reversePatch
+} from '../../packages/core/src/ai/refactor.js';
+
+function sym(qualifiedName: string, kind: string, file: string, startLine = 1, endLine = 10): SymbolNode {
+  const name = qualifiedName.split(/[:.]/).pop()!;
+  return { qualifiedName, name, kind, file, startLine, endLine } as SymbolNode;
+}
+
+const engine = sym('src/engine.rs:ComputeEngine', 'struct', 'src/engine.rs', 5, 60);
+const compute = sym('src/engine.rs:ComputeEngine.compute', 'method', 'src/engine.rs', 20, 40);
+const cached = sym('src/engine.rs:ComputeEngine.cached', 'method', 'src/engine.rs', 42, 55);
+const main = sym('src/main.rs:main', 'function', 'src/main.rs');
+const worker = sym('src/worker.rs:run', 'function', 'src/worker.rs');
+const fixture = sym('tests/engine.rs:engine_computes', 'function', 'tests/engine.rs');
+const lru = sym('src/lru.rs:Lru.get', 'method', 'src/lru.rs');
+const gpu = sym('src/gpu.rs:GpuEngine', 'struct', 'src/gpu.rs');
+const symbols = [engine, compute, cached, main, worker, fixture, lru, gpu];
+
+const calls: Record<string, SymbolNode[]> = {
+  [compute.qualifiedName]: [main, fixture],
+  [cached.qualifiedName]: [worker],
+};
+const callees: Record<string, SymbolNode[]> = { [cached.qualifiedName]: [lru] };
+
+const graph = {
+  async query(_cypher: string, params: any = {}) {
+    return symbols
+      .filter(s => s.qualifiedName === params.query || s.qualifiedName.endsWith(params.suffix) || s.name === params.query)
+      .map(s => ({ s }));
+  },
+  getFileSymbols: async (file: string) => symbols.filter(s => s.file === file),
+  getCallers: async (name: string) => calls[name] ?? [],
+  getCallees: async (name: string) => callees[name] ?? [],
+  getSubtypes: async (name: string) => (name === engine.qualifiedName ? [{ symbol: gpu, type: 'extends' as const }] : []),
+  getSupertypes: async () => [],
+};
+
+const testSources: Record<string, string> = {
+  'tests/engine.rs': 'fn engine_computes() { ComputeEngine::new().compute(); }',
+  'tests/bench.rs': 'fn bench() { let e = ComputeEngine::default(); }',
+  'tests/other.rs': 'fn unrelated() {}',
+};
+
+describe('extractSymbolMentions', () => {
+  it('picks out code-like names and backticked ones', () => {
+    expect(extractSymbolMentions('extract the caching logic in ComputeEngine into a Cache trait')).toEqual(['ComputeEngine']);
+    expect(extractSymbolMentions('rename `run()` to execute, e.g. in Engine::start and compute_heavy_task'))
+      .toEqual(['run', 'Engine::start', 'compute_heavy_task']);
+  });
+});
+
+describe('collectRefactorScope', () => {
+  it('follows members, callers, implementations, callees and tests', async () => {
+    const scope = await collectRefactorScope(graph, 'extract the caching in ComputeEngine', {
+      testFiles: Object.keys(testSources),
+      readFile: async file => testSources[file] ?? null,
+    });
+
+    expect(scope.symbols.map(s => s.qualifiedName)).toEqual([engine.qualifiedName]);
+    expect(scope.files.map(f => [f.file, f.reasons])).toEqual([
+      ['src/engine.rs', ['definition']],
+      ['src/gpu.rs', ['implementation']],
+      ['src/main.rs', ['caller']],
+      ['src/worker.rs', ['caller']],
+      ['tests/bench.rs', ['test']],
+      ['tests/engine.rs', ['test']],
+      ['src/lru.rs', ['callee']],
+    ]);
+    expect(scope.files.find(f => f.file === 'src/worker.rs')?.symbols).toEqual(['cached']);
+  });
+
+  it('drops the least direct files past maxFiles', async () => {
+    const scope = await collectRefactorScope(graph, 'split ComputeEngine', { maxFiles: 2 });
+    expect(scope.files.map(f => f.file)).toEqual(['src/engine.rs', 'src/gpu.rs']);
+    expect(scope.omitted).toBe(4);
+  });
+
+  it('finds nothing for instructions naming no known symbol', async () => {
+    const scope = await collectRefactorScope(graph, 'make everything faster');
+    expect(scope).toEqual({ symbols: [], files: [], omitted: 0 });
+  });
+});
+
+describe('buildRefactorPrompt', () => {
+  const scope = {
+    symbols: [engine],
+    files: [{ file: 'src/engine.rs', reasons: ['definition' as const], symbols: ['ComputeEngine'] }],
+    omitted: 3,
+  };
+
+  it('includes the scope, the code and the edit format', () => {
+    const prompt = buildRefactorPrompt('extract a Cache trait', scope, [{ file: 'src/engine.rs', text: 'pub struct ComputeEngine;' }]);
+    expect(prompt).toContain('Refactoring: extract a Cache trait');
+    expect(prompt).toContain('- src/engine.rs:ComputeEngine (struct, src/engine.rs:5-60)');
+    expect(prompt).toContain('- src/engine.rs: definition of ComputeEngine');
+    expect(prompt).toContain('3 more file(s)');
+    expect(prompt).toContain('pub struct ComputeEngine;');
+    expect(prompt).toContain('<<<<<<< SEARCH');
+    expect(prompt).not.toContain('Previous attempt');
+  });
+
+  it('feeds back a failed build', () => {
+    const prompt = buildRefactorPrompt('extract a Cache trait', scope, [], {
+      command: 'cargo build --all-targets',
+      output: 'error[E0599]: no method named `cached` found',
+      diff: '-    fn cached(&self)',
+    });
+    expect(prompt).toContain('checked with `cargo build --all-targets`, which failed');
+    expect(prompt).toContain('error[E0599]');
+    expect(prompt).toContain('-    fn cached(&self)');
+  });
+});
+
+describe('reversePatch and detectBuildCommand', () => {
+  it('swaps before and after', () => {
+    const patch = { files: [{ file: 'src/cache.rs', before: null, after: 'pub trait Cache {}', diff: '+pub trait Cache {}' }] };
+    expect(reversePatch(patch)).toEqual({ files: [{ file: 'src/cache.rs', before: 'pub trait Cache {}', after: null, diff: '' }] });
+  });
+
+  it('detects the build from root manifests', () => {
+    expect(detectBuildCommand(['Cargo.toml'])).toBe('cargo build --all-targets');
+    expect(detectBuildCommand(['package.json', 'tsconfig.json'])).toBe('npx tsc --noEmit');
+    expect(detectBuildCommand(['README.md'])).toBeUndefined();
+  });
+});
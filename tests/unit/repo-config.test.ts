@@ -0,0 +1,146 @@
+/**
+ * Repository Config Tests
+ * Tests for .cvgit.toml schema validation and layering with .cv/config.json
+ */
+
+import { describe, it, expect } from 'vitest';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import {
+  formatRepoConfigIssues,
+  loadRepoConfig,
+  omitRepoValues,
+  parseRepoConfig,
+  resolveConfigLayers,
+  validateRepoConfig,
+} from '../../packages/core/src/config/repo-config.js';
+
+function validate(content: string) {
+  const lines = new Map<string, number>();
+  return validateRepoConfig(parseRepoConfig(content, lines), lines);
+}
+
+describe('validateRepoConfig', () => {
+  it('should accept the team settings', () => {
+    expect(validate([
+      '[ai]',
+      'model = "claude-sonnet-4-5"',
+      'fallback = "openai/gpt-4o -> ollama/llama3"',
+      '',
+      '[sync]',
+      'excludePatterns = ["fixtures/**"]',
+      'chunking = { strategy = "sliding-window", windowLines = 60, overlapLines = 10 }',
+      '',
+      '[review]',
+      'policies = "docs/review-policies.yaml"',
+      '',
+      '[rateLimits.openai]',
+      'maxConcurrent = 2',
+      '',
+      '[sandbox]',
+      'allow = ["npm test"]',
+    ].join('\n'))).toEqual([]);
+  });
+
+  it('should report each problem with its line', () => {
+    const issues = validate([
+      '[sync]',
+      'excludePatterns = "fixtures/**"',
+      '',
+      '[sync.chunking]',
+      'strategy = "paragraph"',
+      'windowLines = 0',
+      '',
+      '[retrieval]',
+      'topk = 5',
+    ].join('\n'));
+
+    expect(issues).toEqual([
+      { path: 'sync.excludePatterns', line: 2, message: 'sync.excludePatterns must be an array of strings (got "fixtures/**")' },
+      {
+        path: 'sync.chunking.strategy',
+        line: 5,
+        message: 'sync.chunking.strategy must be one of "function", "class", "sliding-window" (got "paragraph")',
+      },
+      { path: 'sync.chunking.windowLines', line: 6, message: 'sync.chunking.windowLines must be an integer of at least 1 (got 0)' },
+      { path: 'retrieval.topk', line: 9, message: 'unknown setting "retrieval.topk" in [retrieval]; did you mean "retrieval.topK"?' },
+    ]);
+    expect(formatRepoConfigIssues(issues.slice(0, 1))).toBe(
+      '.cvgit.toml:2: sync.excludePatterns must be an array of strings (got "fixtures/**")'
+    );
+  });
+
+  it('should refuse committed API keys, bad regexes and unknown tables', () => {
+    const messages = validate([
+      '[ai]',
+      'apiKey = "sk-live"',
+      '',
+      '[redaction]',
+      'allow = ["("]',
+      '',
+      '[embeddings]',
+      'model = "x"',
+    ].join('\n')).map(issue => `${issue.line}: ${issue.message}`);
+
+    expect(messages[0]).toBe('2: ai.apiKey must not be committed; use `cv auth setup` or an environment variable');
+    expect(messages[1]).toMatch(/^5: redaction\.allow must be a valid regular expression/);
+    expect(messages[2]).toBe('7: unknown setting "embeddings"; did you mean "embedding"?');
+  });
+
+  it('should list every problem when loading the file', async () => {
+    const repoRoot = fs.mkdtempSync(path.join(os.tmpdir(), 'cv-repo-config-'));
+    try {
+      fs.writeFileSync(path.join(repoRoot, '.cvgit.toml'), '[ai]\ntemperature = 5\n\n[sandbox]\nnetwork = false\n');
+      await expect(loadRepoConfig(repoRoot)).rejects.toThrow('.cvgit.toml:2: ai.temperature must be a number from 0 to 2 (got 5)');
+
+      fs.writeFileSync(path.join(repoRoot, '.cvgit.toml'), '[ai]\nmodel = "gpt-4o"\n\n[sandbox]\nnetwork = false\n');
+      expect(await loadRepoConfig(repoRoot)).toEqual({ ai: { model: 'gpt-4o' } });
+
+      fs.writeFileSync(path.join(repoRoot, '.cvgit.toml'), '[ai]\nmodel = "gpt-4o\n');
+      await expect(loadRepoConfig(repoRoot)).rejects.toThrow(/^\.cvgit\.toml:\d+: /);
+    } finally {
+      fs.rmSync(repoRoot, { recursive: true, force: true });
+    }
+  });
+});
+
+describe('resolveConfigLayers', () => {
+  const defaults = {
+    ai: { model: 'claude-sonnet-4', temperature: 0.2 },
+    sync: { excludePatterns: ['node_modules/**'], autoSync: true },
+  };
+
+  it('should apply the repository file over defaults and local changes over both', () => {
+    const repo = { ai: { model: 'gpt-4o' }, sync: { excludePatterns: ['fixtures/**'] } };
+    // .cv/config.json is written with every default filled in
+    const local = {
+      ai: { model: 'claude-sonnet-4', temperature: 0.7 },
+      sync: { excludePatterns: ['node_modules/**'], autoSync: true },
+    };
+
+    const { config, sources } = resolveConfigLayers(defaults, repo, local);
+
+    expect(config).toEqual({
+      ai: { model: 'gpt-4o', temperature: 0.7 },
+      sync: { excludePatterns: ['fixtures/**'], autoSync: true },
+    });
+    expect(sources).toEqual({
+      'ai.model': 'repo',
+      'ai.temperature': 'local',
+      'sync.excludePatterns': 'repo',
+      'sync.autoSync': 'default',
+    });
+  });
+
+  it('should let a user override a team setting', () => {
+    const { config, sources } = resolveConfigLayers(defaults, { ai: { model: 'gpt-4o' } }, { ai: { model: 'llama3' } });
+    expect(config.ai.model).toBe('llama3');
+    expect(sources['ai.model']).toBe('local');
+  });
+
+  it('should keep team values out of the saved local config', () => {
+    const saved = omitRepoValues({ ai: { model: 'gpt-4o', temperature: 0.7 } }, { ai: { model: 'gpt-4o' } });
+    expect(saved).toEqual({ ai: { temperature: 0.7 } });
+  });
+});
@@ -0,0 +1,112 @@
+/**
+ * Reranker Tests
+ * Tests for provider rerank responses, chunk reordering and the rerank stage in gatherContext
+ */
+
+import { describe, it, expect, vi, afterEach } from 'vitest';
+import {
+  ApiReranker,
+  createReranker,
+  rerankChunks,
+  Reranker
+} from '../../packages/core/src/context/reranker.js';
+import { AIManager } from '../../packages/core/src/ai/index.js';
+
+const chunk = (id: string, text: string, score: number) => ({
+  id,
+  score,
+  payload: { id, file: `src/${id}.ts`, language: 'typescript', startLine: 1, endLine: 5, text, imports: [], lastModified: 0 }
+});
+
+/** Scores documents by whether they mention "token" */
+const keywordReranker: Reranker = {
+  name: 'local',
+  model: 'test',
+  score: async (_query, documents) => documents.map(doc => (doc.includes('token') ? 0.9 : 0.1))
+};
+
+describe('rerankChunks', () => {
+  it('should reorder chunks by reranker score and replace their scores', async () => {
+    const chunks = [chunk('a', 'parse args', 0.8), chunk('b', 'refresh token', 0.6), chunk('c', 'log line', 0.5)];
+    const reranked = await rerankChunks(keywordReranker, 'how are tokens refreshed', chunks, 2);
+
+    expect(reranked.map(c => c.id)).toEqual(['b', 'a']);
+    expect(reranked[0].score).toBe(0.9);
+  });
+});
+
+describe('ApiReranker', () => {
+  afterEach(() => {
+    vi.unstubAllGlobals();
+  });
+
+  it('should map provider results back to input order', async () => {
+    const fetchMock = vi.fn(async () => new Response(JSON.stringify({
+      results: [{ index: 2, relevance_score: 0.95 }, { index: 0, relevance_score: 0.4 }]
+    })));
+    vi.stubGlobal('fetch', fetchMock);
+
+    const scores = await new ApiReranker('cohere', 'key').score('q', ['x', 'y', 'z']);
+
+    expect(scores).toEqual([0.4, -1, 0.95]);
+    const [url, init] = fetchMock.mock.calls[0] as unknown as [string, RequestInit];
+    expect(url).toBe('https://api.cohere.com/v2/rerank');
+    expect(JSON.parse(init.body as string)).toMatchObject({ model: 'rerank-v3.5', query: 'q', documents: ['x', 'y', 'z'] });
+  });
+
+  it('should read Voyage-style data arrays and surface HTTP errors', async () => {
+    vi.stubGlobal('fetch', vi.fn(async () => new Response(JSON.stringify({ data: [{ index: 0, relevance_score: 0.7 }] }))));
+    expect(await new ApiReranker('voyage', 'key').score('q', ['x'])).toEqual([0.7]);
+
+    vi.stubGlobal('fetch', vi.fn(async () => new Response('bad key', { status: 401 })));
+    await expect(new ApiReranker('jina', 'key').score('q', ['x'])).rejects.toThrow(/401/);
+  });
+});
+
+describe('createReranker', () => {
+  it('should be off by default and require a key for API providers', () => {
+    const saved = process.env.COHERE_API_KEY;
+    delete process.env.COHERE_API_KEY;
+    try {
+      expect(createReranker(undefined)).toBeUndefined();
+      expect(createReranker({ provider: 'none' })).toBeUndefined();
+      expect(() => createReranker({ provider: 'cohere' })).toThrow(/COHERE_API_KEY/);
+      expect(createReranker({ provider: 'cohere', apiKey: 'k' })?.model).toBe('rerank-v3.5');
+      expect(createReranker({ provider: 'local' })?.model).toBe('local/ms-marco-minilm-l6-v2');
+    } finally {
+      if (saved !== undefined) process.env.COHERE_API_KEY = saved;
+    }
+  });
+});
+
+describe('gatherContext rerank stage', () => {
+  it('should over-fetch candidates and keep the best reranked chunks', async () => {
+    const searchCode = vi.fn(async (_query: string, limit: number) =>
+      [chunk('a', 'parse args', 0.8), chunk('b', 'log line', 0.7), chunk('c', 'refresh token', 0.6)].slice(0, limit)
+    );
+    const ai = new AIManager(
+      { provider: 'anthropic', model: 'test-model', apiKey: 'sk-test' },
+      { searchCode } as any
+    );
+    ai.setReranker(keywordReranker);
+
+    const context = await ai.gatherContext('how are tokens refreshed', { maxChunks: 1 });
+
+    expect(searchCode.mock.calls[0][1]).toBe(3);
+    expect(context.chunks.map(c => c.id)).toEqual(['c']);
+  });
+
+  it('should keep retrieval order when the reranker fails', async () => {
+    const ai = new AIManager(
+      { provider: 'anthropic', model: 'test-model', apiKey: 'sk-test' },
+      { searchCode: async () => [chunk('a', 'x', 0.8), chunk('b', 'y', 0.7)] } as any
+    );
+    ai.setReranker({ ...keywordReranker, score: async () => { throw new Error('offline'); } });
+    const error = vi.spyOn(console, 'error').mockImplementation(() => {});
+
+    const context = await ai.gatherContext('q', { maxChunks: 2 });
+    expect(context.chunks.map(c => c.id)).toEqual(['a', 'b']);
+    expect(error).toHaveBeenCalled();
+    error.mockRestore();
+  });
+});
@@ -0,0 +1,27 @@
+/**
+ * Retrieval API Tests
+ */
+
+import { describe, it, expect } from 'vitest';
+import { citedSources, Citation } from '../../packages/core/src/retrieval/index.js';
+
+function source(file: string): Citation {
+  return { file, startLine: 1, endLine: 10, language: 'typescript', score: 0.8, text: '' };
+}
+
+const sources = [source('a.ts'), source('b.ts'), source('c.ts')];
+
+describe('citedSources', () => {
+  it('should return the sources referenced by [n] markers in order', () => {
+    const cited = citedSources('Tokens refresh in [3], called from [1, 3].', sources);
+    expect(cited.map(s => s.file)).toEqual(['a.ts', 'c.ts']);
+  });
+
+  it('should ignore out-of-range markers', () => {
+    expect(citedSources('See [2] and [7].', sources).map(s => s.file)).toEqual(['b.ts']);
+  });
+
+  it('should fall back to every source when nothing is cited', () => {
+    expect(citedSources('It depends.', sources)).toHaveLength(3);
+  });
+});
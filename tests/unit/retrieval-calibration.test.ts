@@ -0,0 +1,44 @@
+/**
+ * Retrieval Calibration Tests
+ */
+
+import { describe, it, expect } from 'vitest';
+import {
+  calibrateMinScore,
+  calibrateRetrieval,
+  calibrateTopK,
+  QueryJudgment
+} from '../../packages/core/src/context/calibration.js';
+
+function judged(query: string, results: Array<[number, boolean]>): QueryJudgment {
+  return { query, results: results.map(([score, relevant]) => ({ score, relevant })) };
+}
+
+const judgments = [
+  judged('where are tokens refreshed', [[0.82, true], [0.71, true], [0.55, false], [0.41, false]]),
+  judged('how is config loaded', [[0.78, true], [0.62, false], [0.6, true], [0.38, false]]),
+  judged('retry logic', [[0.66, true], [0.52, false], [0.33, false]])
+];
+
+describe('Retrieval calibration', () => {
+  it('should pick the threshold that separates relevant results', () => {
+    const result = calibrateMinScore(judgments);
+    expect(result?.minScore).toBe(0.6);
+    expect(result?.recall).toBe(1);
+  });
+
+  it('should size top-k to reach relevant results', () => {
+    expect(calibrateTopK(judgments, 0.6)).toBe(3);
+    expect(calibrateTopK([judged('q', [[0.9, true]])], 0.5)).toBe(3);
+  });
+
+  it('should keep re-ranking only when it does not hurt precision', () => {
+    const worse = judgments.map(j => ({ ...j, results: [...j.results].reverse() }));
+    expect(calibrateRetrieval(judgments, judgments)?.rerank).toBe(true);
+    expect(calibrateRetrieval(judgments, worse)?.rerank).toBe(false);
+  });
+
+  it('should not calibrate without any relevant result', () => {
+    expect(calibrateRetrieval([judged('q', [[0.5, false]])])).toBeNull();
+  });
+});
@@ -0,0 +1,132 @@
+/**
+ * Review Hook Tests
+ * Tests for the pre-commit and pre-push scripts `cv hooks install` writes,
+ * run with sh against a stub `cv` that records how it was called
+ */
+
+import { describe, it, expect, beforeEach, afterEach } from 'vitest';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { execFileSync, spawnSync } from 'child_process';
+import {
+  BYPASS_ENV,
+  CheckHookOptions,
+  combineHooks,
+  preCommitHook,
+  prePushHook,
+} from '../../packages/cli/src/utils/review-hooks.js';
+
+const ZERO = '0'.repeat(40);
+const checks: CheckHookOptions = { budget: 20, failOn: 'critical', secretScan: false };
+
+describe('review hooks', () => {
+  let dir: string;
+  let calls: string;
+
+  /** Run a hook script as git would: `<hook> <remote> <url>` with refs on stdin */
+  const run = (script: string, options: { stdin?: string; env?: Record<string, string>; cvExit?: number } = {}) => {
+    const hook = path.join(dir, 'hook');
+    fs.writeFileSync(hook, script, { mode: 0o755 });
+    return spawnSync('sh', [hook, 'origin', 'git@example.com:repo.git'], {
+      cwd: dir,
+      input: options.stdin ?? '',
+      encoding: 'utf-8',
+      env: {
+        ...process.env,
+        PATH: `${path.join(dir, 'bin')}${path.delimiter}${process.env.PATH}`,
+        CV_STUB_EXIT: String(options.cvExit ?? 0),
+        [BYPASS_ENV]: '',
+        ...options.env,
+      },
+    });
+  };
+  const cvCalls = () => fs.existsSync(calls) ? fs.readFileSync(calls, 'utf-8').trim().split('\n') : [];
+  const git = (...args: string[]) => execFileSync('git', args, { cwd: dir, encoding: 'utf-8' }).trim();
+
+  beforeEach(() => {
+    dir = fs.mkdtempSync(path.join(os.tmpdir(), 'cv-review-hooks-'));
+    calls = path.join(dir, 'cv-calls.txt');
+    fs.mkdirSync(path.join(dir, 'bin'));
+    fs.writeFileSync(
+      path.join(dir, 'bin', 'cv'),
+      `#!/bin/sh\necho "$*" >> "${calls}"\nexit $CV_STUB_EXIT\n`,
+      { mode: 0o755 }
+    );
+    git('init', '-q');
+    git('-c', 'user.name=t', '-c', 'user.email=t@t', 'commit', '-q', '--allow-empty', '-m', 'base');
+    git('-c', 'user.name=t', '-c', 'user.email=t@t', 'commit', '-q', '--allow-empty', '-m', 'change');
+  });
+
+  afterEach(() => {
+    fs.rmSync(dir, { recursive: true, force: true });
+  });
+
+  describe('pre-commit', () => {
+    it('should review the staged changes and block on findings', () => {
+      expect(run(preCommitHook(checks)).status).toBe(0);
+      expect(cvCalls()).toEqual(['review --staged --fast --budget 20 --fail-on critical']);
+
+      const blocked = run(preCommitHook(checks), { cvExit: 1 });
+      expect(blocked.status).toBe(1);
+      expect(blocked.stderr).toContain('commit blocked by review findings');
+    });
+
+    it('should skip the checks when bypassed', () => {
+      const result = run(preCommitHook({ ...checks, secretScan: true }), { cvExit: 1, env: { [BYPASS_ENV]: '1' } });
+      expect(result.status).toBe(0);
+      expect(cvCalls()).toEqual([]);
+    });
+
+    it('should scan for secrets before the review', () => {
+      const result = run(preCommitHook({ ...checks, secretScan: true }), { cvExit: 1 });
+      expect(result.status).toBe(1);
+      expect(result.stderr).toContain('contain secrets');
+      expect(cvCalls()).toEqual(['redact --check --staged --exit-code']);
+    });
+  });
+
+  describe('pre-push', () => {
+    it('should review the commits an existing branch gains', () => {
+      const [head, base] = [git('rev-parse', 'HEAD'), git('rev-parse', 'HEAD~1')];
+      const result = run(prePushHook(checks), { stdin: `refs/heads/main ${head} refs/heads/main ${base}\n` });
+      expect(result.status).toBe(0);
+      expect(cvCalls()).toEqual([`review --range ${base}..${head} --fast --budget 20 --fail-on critical`]);
+    });
+
+    it('should skip deleted branches and compare new ones with the remote default branch', () => {
+      const [head, base] = [git('rev-parse', 'HEAD'), git('rev-parse', 'HEAD~1')];
+      const refs = `(delete) ${ZERO} refs/heads/old ${base}\nrefs/heads/feature ${head} refs/heads/feature ${ZERO}\n`;
+
+      // Without origin/HEAD there is nothing to compare a new branch with
+      expect(run(prePushHook(checks), { stdin: refs }).status).toBe(0);
+      expect(cvCalls()).toEqual([]);
+
+      git('update-ref', 'refs/remotes/origin/HEAD', base);
+      expect(run(prePushHook(checks), { stdin: refs }).status).toBe(0);
+      expect(cvCalls()).toEqual([`review --range ${base}..${head} --fast --budget 20 --fail-on critical`]);
+    });
+
+    it('should block the push on findings unless bypassed', () => {
+      const [head, base] = [git('rev-parse', 'HEAD'), git('rev-parse', 'HEAD~1')];
+      const stdin = `refs/heads/main ${head} refs/heads/main ${base}\n`;
+
+      const blocked = run(prePushHook(checks), { stdin, cvExit: 1 });
+      expect(blocked.status).toBe(1);
+      expect(blocked.stderr).toContain('push blocked by review findings in refs/heads/main');
+      expect(run(prePushHook(checks), { stdin, cvExit: 1, env: { [BYPASS_ENV]: '1' } }).status).toBe(0);
+    });
+
+    it('should hand the refs on to a preserved hook', () => {
+      const [head, base] = [git('rev-parse', 'HEAD'), git('rev-parse', 'HEAD~1')];
+      const stdin = `refs/heads/main ${head} refs/heads/main ${base}\n`;
+      const received = path.join(dir, 'received.txt');
+      const existing = `#!/bin/sh\ncat > "${received}"\necho "remote $1"\n`;
+
+      const result = run(combineHooks(prePushHook(checks), existing), { stdin });
+      expect(result.status).toBe(0);
+      expect(result.stdout).toContain('remote origin');
+      expect(fs.readFileSync(received, 'utf-8')).toBe(stdin);
+    });
+  });
+});
@@ -0,0 +1,98 @@
+/**
+ * Review Policy Tests
+ * Tests for loading house rules and reporting findings against them
+ */
+
+import { describe, it, expect, afterEach } from 'vitest';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import {
+  parseReviewPolicies,
+  loadReviewPolicies,
+  applicablePolicies,
+  buildPolicyPrompt,
+  applyPolicies,
+  policyReport,
+  ReviewPolicyError
+} from '../../packages/core/src/ai/review-policies.js';
+import { parseReviewResult } from '../../packages/core/src/ai/review.js';
+
+const POLICIES = `
+rules:
+  - id: no-unwrap-in-lib
+    name: No unwrap in library code
+    severity: high
+    files: ["src/**/*.rs", "!src/bin/**"]
+    criteria: |
+      Library code must not call .unwrap() or .expect(); propagate errors instead.
+  - id: error-types
+    criteria: Error types must implement std::error::Error.
+`;
+
+describe('parseReviewPolicies', () => {
+  it('should read rules with defaults for name and severity', () => {
+    const [unwrap, errors] = parseReviewPolicies(POLICIES);
+    expect(unwrap).toMatchObject({ id: 'no-unwrap-in-lib', severity: 'high', files: ['src/**/*.rs', '!src/bin/**'] });
+    expect(errors).toEqual({
+      id: 'error-types',
+      name: 'error-types',
+      criteria: 'Error types must implement std::error::Error.',
+      severity: 'medium',
+      files: undefined
+    });
+  });
+
+  it('should reject rules without criteria, bad severities and duplicate ids', () => {
+    expect(() => parseReviewPolicies('rules:\n  - id: a\n')).toThrow(/criteria/);
+    expect(() => parseReviewPolicies('rules:\n  - id: a\n    criteria: x\n    severity: urgent\n')).toThrow(/severity/);
+    expect(() => parseReviewPolicies('rules:\n  - {id: a, criteria: x}\n  - {id: a, criteria: y}\n')).toThrow(ReviewPolicyError);
+    expect(parseReviewPolicies('')).toEqual([]);
+  });
+});
+
+describe('applying policies', () => {
+  const policies = parseReviewPolicies(POLICIES);
+
+  it('should only include rules that match a changed file', () => {
+    expect(applicablePolicies(policies, ['src/bin/main.rs']).map(p => p.id)).toEqual(['error-types']);
+    expect(applicablePolicies(policies, ['src/lib/parse.rs']).map(p => p.id)).toEqual(['no-unwrap-in-lib', 'error-types']);
+    expect(buildPolicyPrompt(policies)).toContain('### no-unwrap-in-lib: No unwrap in library code');
+  });
+
+  it('should report each rule and use its severity for violations', () => {
+    const { findings } = parseReviewResult(JSON.stringify({
+      summary: '',
+      findings: [
+        { severity: 'low', file: 'src/lib/parse.rs', line: 4, title: 'unwrap on user input', rule: 'no-unwrap-in-lib' },
+        { severity: 'low', file: 'src/lib/parse.rs', line: 9, title: 'Typo', rule: 'spelling' }
+      ]
+    }));
+
+    const applied = applyPolicies(findings, policies);
+    expect(applied.map(f => [f.severity, f.rule])).toEqual([['high', 'no-unwrap-in-lib'], ['low', undefined]]);
+    expect(policyReport(policies, applied)).toEqual([
+      { id: 'no-unwrap-in-lib', name: 'No unwrap in library code', status: 'fail', findings: 1 },
+      { id: 'error-types', name: 'error-types', status: 'pass', findings: 0 }
+    ]);
+  });
+});
+
+describe('loadReviewPolicies', () => {
+  const dirs: string[] = [];
+  afterEach(() => {
+    for (const dir of dirs.splice(0)) fs.rmSync(dir, { recursive: true, force: true });
+  });
+
+  it('should load .cv/review-policies.yaml and treat a missing file as no rules', async () => {
+    const repoRoot = fs.mkdtempSync(path.join(os.tmpdir(), 'cv-policies-'));
+    dirs.push(repoRoot);
+
+    expect(await loadReviewPolicies(repoRoot)).toEqual([]);
+    await expect(loadReviewPolicies(repoRoot, 'missing.yaml')).rejects.toThrow(ReviewPolicyError);
+
+    fs.mkdirSync(path.join(repoRoot, '.cv'));
+    fs.writeFileSync(path.join(repoRoot, '.cv', 'review-policies.yaml'), POLICIES);
+    expect((await loadReviewPolicies(repoRoot)).map(p => p.id)).toEqual(['no-unwrap-in-lib', 'error-types']);
+  });
+});
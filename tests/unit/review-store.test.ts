@@ -0,0 +1,88 @@
+/**
+ * Review Findings Store Tests
+ * Tests for recording review findings and triaging them
+ */
+
+import { describe, it, expect, beforeEach, afterEach } from 'vitest';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import {
+  loadFindingsStore,
+  recordFindings,
+  saveFindingsStore,
+  setFindingStatus,
+  withoutDismissed,
+} from '../../packages/core/src/ai/review-store.js';
+import type { ReviewFinding } from '../../packages/core/src/ai/review.js';
+
+function finding(overrides: Partial<ReviewFinding>): ReviewFinding {
+  return {
+    id: 'aaaa1111',
+    severity: 'medium',
+    file: 'src/pool.ts',
+    line: 3,
+    title: 'Unbounded queue',
+    message: 'The queue grows without limit.',
+    ...overrides,
+  };
+}
+
+describe('findings store', () => {
+  const monday = new Date('2026-10-12T09:00:00Z');
+  const tuesday = new Date('2026-10-13T09:00:00Z');
+
+  it('records new findings as open and keeps triage across runs', () => {
+    let store = recordFindings({ version: 1, findings: [] }, [finding({})], 'HEAD', monday);
+    expect(store.findings[0]).toMatchObject({ status: 'open', ref: 'HEAD', firstSeen: monday.toISOString() });
+
+    store = setFindingStatus(store, 'aaaa1111', 'dismissed', monday);
+    store = recordFindings(store, [
+      finding({ line: 7 }),
+      finding({ id: 'bbbb2222', severity: 'critical', title: 'SQL injection' }),
+    ], 'staged', tuesday);
+
+    expect(store.findings.map(f => [f.id, f.status])).toEqual([['bbbb2222', 'open'], ['aaaa1111', 'dismissed']]);
+    expect(store.findings[1]).toMatchObject({
+      line: 7,
+      ref: 'staged',
+      firstSeen: monday.toISOString(),
+      lastSeen: tuesday.toISOString(),
+      triagedAt: monday.toISOString(),
+    });
+  });
+
+  it('hides dismissed findings from later reviews', () => {
+    const store = setFindingStatus(
+      recordFindings({ version: 1, findings: [] }, [finding({}), finding({ id: 'bbbb2222' })], 'HEAD'),
+      'bbbb2222',
+      'dismissed'
+    );
+    expect(withoutDismissed([finding({}), finding({ id: 'bbbb2222' }), finding({ id: 'cccc3333' })], store)
+      .map(f => f.id)).toEqual(['aaaa1111', 'cccc3333']);
+    expect(setFindingStatus(store, 'unknown', 'accepted').findings).toEqual(store.findings);
+  });
+
+  describe('on disk', () => {
+    let repoRoot: string;
+
+    beforeEach(() => {
+      repoRoot = fs.mkdtempSync(path.join(os.tmpdir(), 'cv-findings-'));
+    });
+
+    afterEach(() => {
+      fs.rmSync(repoRoot, { recursive: true, force: true });
+    });
+
+    it('round-trips and starts empty when missing or corrupt', async () => {
+      expect(await loadFindingsStore(repoRoot)).toEqual({ version: 1, findings: [] });
+
+      const store = recordFindings({ version: 1, findings: [] }, [finding({})], 'HEAD', monday);
+      await saveFindingsStore(repoRoot, store);
+      expect(await loadFindingsStore(repoRoot)).toEqual(store);
+
+      fs.writeFileSync(path.join(repoRoot, '.cv', 'review', 'findings.json'), '{');
+      expect((await loadFindingsStore(repoRoot)).findings).toEqual([]);
+    });
+  });
+});
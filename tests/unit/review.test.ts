@@ -0,0 +1,136 @@
+/**
+ * Review Findings Tests
+ * Tests for diff scoping, touched-symbol detection and parsing model findings
+ */
+
+import { describe, it, expect } from 'vitest';
+import {
+  splitDiff,
+  filterDiff,
+  touchedLines,
+  touchedSymbols,
+  parseReviewResult,
+  groupFindings,
+  findingId,
+  hasFindingsAtOrAbove,
+  toSarif
+} from '../../packages/core/src/ai/review.js';
+
+const DIFF = [
+  'diff --git a/src/auth.ts b/src/auth.ts',
+  'index 1111111..2222222 100644',
+  '--- a/src/auth.ts',
+  '+++ b/src/auth.ts',
+  '@@ -10,4 +10,5 @@ export function login() {',
+  '   const user = find();',
+  '-  return token;',
+  '+  const fresh = refresh(token);',
+  '+  return fresh;',
+  '   }',
+  '@@ -40,3 +41,2 @@',
+  '   a();',
+  '-  b();',
+  '   c();',
+  'diff --git a/docs/old.md b/docs/old.md',
+  'deleted file mode 100644',
+  '--- a/docs/old.md',
+  '+++ /dev/null',
+  '@@ -1 +0,0 @@',
+  '-gone',
+  ''
+].join('\n');
+
+describe('diff scoping', () => {
+  it('should split per file, naming deletions by their old path', () => {
+    expect(splitDiff(DIFF).map(s => s.file)).toEqual(['src/auth.ts', 'docs/old.md']);
+  });
+
+  it('should keep only files matching the globs', () => {
+    const filtered = filterDiff(DIFF, ['*.ts']);
+    expect(splitDiff(filtered).map(s => s.file)).toEqual(['src/auth.ts']);
+    expect(filterDiff(DIFF, ['src/**'])).toBe(filtered);
+    expect(filterDiff(DIFF, [])).toBe(DIFF);
+    expect(filterDiff(DIFF, ['lib/**'])).toBe('');
+  });
+
+  it('should map added and removed lines to new-file ranges', () => {
+    expect(touchedLines(DIFF).get('src/auth.ts')).toEqual([[11, 11], [11, 12], [42, 42]]);
+  });
+
+  it('should find symbols overlapping the touched ranges', () => {
+    const symbols: any[] = [
+      { name: 'login', startLine: 8, endLine: 14 },
+      { name: 'logout', startLine: 20, endLine: 30 },
+      { name: 'helper', startLine: 40, endLine: 44 }
+    ];
+    const ranges = touchedLines(DIFF).get('src/auth.ts')!;
+    expect(touchedSymbols(symbols, ranges).map(s => s.name)).toEqual(['login', 'helper']);
+  });
+});
+
+describe('parseReviewResult', () => {
+  it('should read findings from a fenced JSON reply and sort by severity', () => {
+    const reply = 'Here is the review:\n```json\n' + JSON.stringify({
+      summary: 'Mostly fine.',
+      findings: [
+        { severity: 'low', file: 'src/auth.ts', line: 12, title: 'Naming' },
+        { severity: 'error', file: 'b/src/auth.ts', line: '11', endLine: 11, title: 'Token leak', message: 'Refresh token is logged' },
+        { severity: 'high', line: 3, title: 'No file' }
+      ]
+    }) + '\n```';
+
+    const result = parseReviewResult(reply);
+    expect(result.summary).toBe('Mostly fine.');
+    expect(result.findings.map(({ id, ...rest }) => rest)).toEqual([
+      { severity: 'high', file: 'src/auth.ts', line: 11, endLine: undefined, title: 'Token leak', message: 'Refresh token is logged', suggestion: undefined, rule: undefined },
+      { severity: 'low', file: 'src/auth.ts', line: 12, endLine: undefined, title: 'Naming', message: 'Naming', suggestion: undefined, rule: undefined }
+    ]);
+    expect(groupFindings(result.findings).map(g => g.severity)).toEqual(['high', 'low']);
+  });
+
+  it('should keep a non-JSON reply as the summary', () => {
+    expect(parseReviewResult('Looks good to me.')).toEqual({ summary: 'Looks good to me.', findings: [] });
+  });
+});
+
+describe('CI output', () => {
+  const reply = (line: number, title: string) => JSON.stringify({
+    summary: '',
+    findings: [
+      { severity: 'medium', file: 'src/auth.ts', line, title },
+      { severity: 'medium', file: 'src/auth.ts', line: line + 1, title }
+    ]
+  });
+
+  it('should give the same problem the same ID across runs, even when lines move', () => {
+    const first = parseReviewResult(reply(10, 'Unchecked error'));
+    const second = parseReviewResult(reply(14, 'Unchecked  error!'));
+
+    expect(first.findings).toHaveLength(1);
+    expect(second.findings[0].id).toBe(first.findings[0].id);
+    expect(findingId({ file: 'src/other.ts', title: 'Unchecked error' })).not.toBe(first.findings[0].id);
+  });
+
+  it('should compare findings against the --fail-on threshold', () => {
+    const { findings } = parseReviewResult(reply(10, 'Unchecked error'));
+    expect(hasFindingsAtOrAbove(findings, 'high')).toBe(false);
+    expect(hasFindingsAtOrAbove(findings, 'medium')).toBe(true);
+    expect(hasFindingsAtOrAbove(findings, 'info')).toBe(true);
+  });
+
+  it('should emit SARIF results with locations and fingerprints', () => {
+    const { findings } = parseReviewResult(reply(10, 'Unchecked error'));
+    const sarif: any = toSarif(findings, { toolVersion: '1.2.3' });
+    const [result] = sarif.runs[0].results;
+
+    expect(sarif.version).toBe('2.1.0');
+    expect(sarif.runs[0].tool.driver).toMatchObject({ name: 'cv-git', version: '1.2.3' });
+    expect(result).toMatchObject({
+      ruleId: 'cv-review',
+      ruleIndex: 0,
+      level: 'warning',
+      locations: [{ physicalLocation: { artifactLocation: { uri: 'src/auth.ts' }, region: { startLine: 10 } } }],
+      partialFingerprints: { 'cvFindingHash/v1': findings[0].id }
+    });
+  });
+});
@@ -0,0 +1,141 @@
+/**
+ * Command Sandbox Tests
+ * Tests for the .cvgit.toml sandbox policy, allowlist matching, env filtering and timeouts
+ */
+
+import { describe, it, expect, beforeEach, afterEach } from 'vitest';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import {
+  loadSandboxPolicy,
+  parseSandboxPolicy,
+  isCommandAllowed,
+  sandboxEnv,
+  runSandboxed,
+  SandboxError,
+  SandboxPolicy,
+  DEFAULT_SANDBOX_POLICY
+} from '../../packages/core/src/sandbox/index.js';
+
+describe('sandbox policy', () => {
+  let repoRoot: string;
+
+  beforeEach(() => {
+    repoRoot = fs.mkdtempSync(path.join(os.tmpdir(), 'cv-sandbox-'));
+  });
+
+  afterEach(() => {
+    fs.rmSync(repoRoot, { recursive: true, force: true });
+  });
+
+  it('should default to no allowlist, no network and a timeout', async () => {
+    expect(await loadSandboxPolicy(repoRoot)).toEqual(DEFAULT_SANDBOX_POLICY);
+  });
+
+  it('should load the [sandbox] table from .cvgit.toml', async () => {
+    fs.writeFileSync(path.join(repoRoot, '.cvgit.toml'), [
+      '[sandbox]',
+      'allow = ["npm test", "cargo test *"]',
+      'env = ["NODE_ENV"]',
+      'network = true',
+      'timeout = 30'
+    ].join('\n'));
+
+    expect(await loadSandboxPolicy(repoRoot)).toEqual({
+      allow: ['npm test', 'cargo test *'],
+      env: ['NODE_ENV'],
+      network: true,
+      timeoutMs: 30000
+    });
+  });
+
+  it('should reject invalid settings with the file and line', async () => {
+    expect(() => parseSandboxPolicy({ allow: 'npm test' })).toThrow('sandbox.allow must be an array of strings');
+    expect(() => parseSandboxPolicy({ timeout: -1 })).toThrow('sandbox.timeout must be a positive number');
+    expect(() => parseSandboxPolicy({ network: 'no' })).toThrow('sandbox.network must be true or false');
+
+    fs.writeFileSync(path.join(repoRoot, '.cvgit.toml'), '[sandbox]\nallow = [npm]\n');
+    await expect(loadSandboxPolicy(repoRoot)).rejects.toThrow('.cvgit.toml:2:');
+  });
+});
+
+describe('isCommandAllowed', () => {
+  const policy: SandboxPolicy = { ...DEFAULT_SANDBOX_POLICY, allow: ['npm test', 'cargo test *'] };
+
+  it('should match exact commands and wildcards', () => {
+    expect(isCommandAllowed('npm test', policy)).toBe(true);
+    expect(isCommandAllowed('  npm   test ', policy)).toBe(true);
+    expect(isCommandAllowed('cargo test --workspace', policy)).toBe(true);
+    expect(isCommandAllowed('npm test --watch', policy)).toBe(false);
+    expect(isCommandAllowed('npm publish', policy)).toBe(false);
+  });
+
+  it('should never match commands that chain, pipe or substitute', () => {
+    expect(isCommandAllowed('cargo test; rm -rf ~', policy)).toBe(false);
+    expect(isCommandAllowed('cargo test && curl x', policy)).toBe(false);
+    expect(isCommandAllowed('cargo test | sh', policy)).toBe(false);
+    expect(isCommandAllowed('cargo test $(whoami)', policy)).toBe(false);
+    expect(isCommandAllowed('cargo test > out', policy)).toBe(false);
+  });
+});
+
+describe('sandboxEnv', () => {
+  it('should pass only the basics and allowlisted variables', () => {
+    const env = sandboxEnv(
+      { ...DEFAULT_SANDBOX_POLICY, env: ['NODE_ENV'] },
+      { PATH: '/bin', HOME: '/home/me', NODE_ENV: 'test', ANTHROPIC_API_KEY: 'secret', AWS_SECRET_ACCESS_KEY: 'x' }
+    );
+    expect(env).toEqual({ PATH: '/bin', HOME: '/home/me', NODE_ENV: 'test', CI: '1' });
+  });
+});
+
+describe.skipIf(process.platform === 'win32')('runSandboxed', () => {
+  let repoRoot: string;
+  const policy: SandboxPolicy = { ...DEFAULT_SANDBOX_POLICY, network: true, timeoutMs: 10000 };
+
+  beforeEach(() => {
+    repoRoot = fs.mkdtempSync(path.join(os.tmpdir(), 'cv-sandbox-run-'));
+    fs.mkdirSync(path.join(repoRoot, 'pkg'));
+  });
+
+  afterEach(() => {
+    fs.rmSync(repoRoot, { recursive: true, force: true });
+  });
+
+  it('should run in the repository and stream output', async () => {
+    const chunks: string[] = [];
+    const result = await runSandboxed('pwd; echo err >&2; exit 3', {
+      repoRoot, cwd: 'pkg', policy, onOutput: c => chunks.push(c)
+    });
+
+    expect(result.exitCode).toBe(3);
+    expect(result.timedOut).toBe(false);
+    expect(fs.realpathSync(result.output.split('\n')[0])).toBe(fs.realpathSync(path.join(repoRoot, 'pkg')));
+    expect(result.output).toContain('err');
+    expect(chunks.join('')).toBe(result.output);
+  });
+
+  it('should hide environment variables that are not allowlisted', async () => {
+    process.env.CV_SANDBOX_TEST_SECRET = 'hunter2';
+    try {
+      const result = await runSandboxed('echo "[$CV_SANDBOX_TEST_SECRET]"', { repoRoot, policy });
+      expect(result.output.trim()).toBe('[]');
+    } finally {
+      delete process.env.CV_SANDBOX_TEST_SECRET;
+    }
+  });
+
+  it('should refuse a working directory outside the repository', async () => {
+    await expect(runSandboxed('true', { repoRoot, cwd: '..', policy })).rejects.toThrow(SandboxError);
+  });
+
+  it('should kill commands that run past the timeout', async () => {
+    const started = Date.now();
+    const result = await runSandboxed('sleep 5', { repoRoot, policy: { ...policy, timeoutMs: 200 } });
+
+    expect(result.timedOut).toBe(true);
+    expect(result.exitCode).toBe(124);
+    expect(Date.now() - started).toBeLessThan(4000);
+  });
+});
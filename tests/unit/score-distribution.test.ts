@@ -0,0 +1,110 @@
+/**
+ * Score Distribution Tests
+ * Tests for background similarity sampling and the adaptive minScore
+ */
+
+import { describe, it, expect, afterEach } from 'vitest';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import {
+  computeScoreDistribution,
+  scoreAtPercentile,
+  resolveMinScore,
+  sampleScoreDistribution,
+  loadScoreDistribution,
+  saveScoreDistribution
+} from '../../packages/core/src/context/score-distribution.js';
+
+/** Deterministic RNG (mulberry32) */
+function rng(seed: number): () => number {
+  return () => {
+    seed = (seed + 0x6d2b79f5) | 0;
+    let t = seed;
+    t = Math.imul(t ^ (t >>> 15), t | 1);
+    t ^= t + Math.imul(t ^ (t >>> 7), t | 61);
+    return ((t ^ (t >>> 14)) >>> 0) / 4294967296;
+  };
+}
+
+/** Vectors sharing a common offset, like embedding models with a narrow score range */
+function vectors(count: number, offset: number, random: () => number): number[][] {
+  return Array.from({ length: count }, () => Array.from({ length: 16 }, () => offset + random() * 2 - 1));
+}
+
+describe('computeScoreDistribution', () => {
+  it('should place the threshold where each model scores unrelated chunks', () => {
+    const spread = computeScoreDistribution(vectors(200, 0, rng(1)), 'spread', { random: rng(2) })!;
+    const narrow = computeScoreDistribution(vectors(200, 2, rng(1)), 'narrow', { random: rng(2) })!;
+
+    expect(spread.quantiles).toHaveLength(101);
+    expect(spread.quantiles[50]).toBeLessThan(0.2);
+    expect(narrow.quantiles[50]).toBeGreaterThan(0.6);
+    expect(scoreAtPercentile(narrow, 90)).toBeGreaterThan(scoreAtPercentile(spread, 90));
+  });
+
+  it('should interpolate between percentiles and need two vectors', () => {
+    const distribution = computeScoreDistribution(vectors(50, 0, rng(3)), 'm', { random: rng(4) })!;
+    const mid = scoreAtPercentile(distribution, 90.5);
+
+    expect(mid).toBeGreaterThanOrEqual(distribution.quantiles[90]);
+    expect(mid).toBeLessThanOrEqual(distribution.quantiles[91]);
+    expect(computeScoreDistribution([[1, 0]], 'm')).toBeNull();
+  });
+});
+
+describe('resolveMinScore', () => {
+  const distribution = computeScoreDistribution(vectors(100, 1, rng(5)), 'm', { random: rng(6) })!;
+
+  it('should prefer config, then the distribution, then the fallback', () => {
+    expect(resolveMinScore({ minScore: 0.4 }, distribution, 0.25)).toBe(0.4);
+    expect(resolveMinScore({ minScorePercentile: 75 }, distribution, 0.25)).toBe(scoreAtPercentile(distribution, 75));
+    expect(resolveMinScore(undefined, distribution, 0.25)).toBe(scoreAtPercentile(distribution, 90));
+    expect(resolveMinScore(undefined, null, 0.25)).toBe(0.25);
+  });
+
+  it('should ignore distributions sampled from too few vectors', () => {
+    const tiny = computeScoreDistribution(vectors(5, 1, rng(7)), 'm')!;
+    expect(resolveMinScore(undefined, tiny, 0.25)).toBe(0.25);
+  });
+});
+
+describe('sampleScoreDistribution', () => {
+  it('should page through the collection up to the sample size', async () => {
+    const all = vectors(250, 0, rng(8));
+    const vector: any = {
+      getEmbeddingInfo: () => ({ model: 'test-model', provider: 'local', dimensions: 16 }),
+      scroll: async (_collection: string, limit: number, offset?: string) => {
+        const start = offset ? parseInt(offset, 10) : 0;
+        const end = Math.min(start + limit, all.length);
+        return {
+          points: all.slice(start, end).map((v, i) => ({ id: start + i, vector: v, payload: {} })),
+          next_page_offset: end < all.length ? String(end) : undefined
+        };
+      }
+    };
+
+    const distribution = await sampleScoreDistribution(vector, 'code_chunks', { points: 150, pairs: 500 });
+    expect(distribution).toMatchObject({ model: 'test-model', points: 150, pairs: 500, dimension: 16 });
+  });
+});
+
+describe('persistence', () => {
+  const dirs: string[] = [];
+  afterEach(() => {
+    for (const dir of dirs.splice(0)) fs.rmSync(dir, { recursive: true, force: true });
+  });
+
+  it('should keep one distribution per embedding model', async () => {
+    const repoRoot = fs.mkdtempSync(path.join(os.tmpdir(), 'cv-scores-'));
+    dirs.push(repoRoot);
+
+    await saveScoreDistribution(repoRoot, computeScoreDistribution(vectors(30, 0, rng(9)), 'model-a')!);
+    await saveScoreDistribution(repoRoot, computeScoreDistribution(vectors(30, 2, rng(9)), 'model-b')!);
+
+    expect((await loadScoreDistribution(repoRoot, 'model-a'))?.model).toBe('model-a');
+    expect((await loadScoreDistribution(repoRoot, 'model-b'))?.model).toBe('model-b');
+    expect(await loadScoreDistribution(repoRoot, 'model-c')).toBeNull();
+    expect(await loadScoreDistribution(repoRoot, undefined)).toBeNull();
+  });
+});
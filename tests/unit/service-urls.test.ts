@@ -7,6 +7,7 @@ import {
   getFalkorDbUrl,
   getQdrantUrl,
   getOllamaUrl,
+  getGeminiUrl,
   getServiceUrls,
   DEFAULT_URLS
 } from '../../packages/core/src/config/service-urls.js';
@@ -20,7 +21,8 @@ describe('Service URL Configuration', () => {
     const envVars = [
       'CV_FALKORDB_URL', 'FALKORDB_URL',
       'CV_QDRANT_URL', 'QDRANT_URL',
-      'CV_OLLAMA_URL', 'OLLAMA_HOST', 'OLLAMA_URL'
+      'CV_OLLAMA_URL', 'OLLAMA_HOST', 'OLLAMA_URL',
+      'CV_GEMINI_URL'
     ];
     for (const key of envVars) {
       originalEnv[key] = process.env[key];
@@ -131,6 +133,17 @@ describe('Service URL Configuration', () => {
     });
   });
 
+  describe('getGeminiUrl', () => {
+    it('should return the public API by default', () => {
+      expect(getGeminiUrl()).toBe('https://generativelanguage.googleapis.com/v1beta');
+    });
+
+    it('should prefer CV_GEMINI_URL over config and strip trailing slashes', () => {
+      process.env.CV_GEMINI_URL = 'http://proxy:8080/v1beta/';
+      expect(getGeminiUrl('http://config/v1beta')).toBe('http://proxy:8080/v1beta');
+    });
+  });
+
   describe('getServiceUrls', () => {
     it('should return all default URLs when no config', () => {
       const urls = getServiceUrls();
@@ -0,0 +1,56 @@
+/**
+ * Stream Reader Tests
+ * Tests for reading SSE and NDJSON response bodies split at arbitrary chunk boundaries
+ */
+
+import { describe, it, expect } from 'vitest';
+import { readSSE, readNDJSON } from '../../packages/core/src/ai/stream.js';
+
+/** Response body delivering `text` in chunks of `size` bytes */
+function body(text: string, size: number): ReadableStream<Uint8Array> {
+  const bytes = new TextEncoder().encode(text);
+  return new ReadableStream({
+    start(controller) {
+      for (let i = 0; i < bytes.length; i += size) controller.enqueue(bytes.slice(i, i + size));
+      controller.close();
+    }
+  });
+}
+
+async function collect<T>(iterable: AsyncIterable<T>): Promise<T[]> {
+  const items: T[] = [];
+  for await (const item of iterable) items.push(item);
+  return items;
+}
+
+describe('readSSE', () => {
+  const stream = 'data: {"a":1}\n\n: keep-alive\n\nevent: message\ndata: {"b":"héllo"}\n\ndata: [DONE]\n\ndata: ignored\n\n';
+
+  it('should yield data payloads whatever the chunk size', async () => {
+    for (const size of [1, 2, 3, 7, stream.length]) {
+      expect(await collect(readSSE(body(stream, size)))).toEqual(['{"a":1}', '{"b":"héllo"}']);
+    }
+  });
+
+  it('should handle CRLF line endings split across chunks', async () => {
+    const crlf = 'data: one\r\n\r\ndata: two\r\n\r\n';
+    for (const size of [1, 5, 11]) {
+      expect(await collect(readSSE(body(crlf, size)))).toEqual(['one', 'two']);
+    }
+  });
+
+  it('should join multi-line data and flush an unterminated last event', async () => {
+    expect(await collect(readSSE(body('data: first\ndata: second\n\ndata:last', 4))))
+      .toEqual(['first\nsecond', 'last']);
+  });
+});
+
+describe('readNDJSON', () => {
+  it('should parse objects split across chunks and skip bad lines', async () => {
+    const ndjson = '{"message":{"content":"Hi"}}\nnot json\n\n{"done":true}';
+    for (const size of [1, 6, ndjson.length]) {
+      expect(await collect(readNDJSON(body(ndjson, size))))
+        .toEqual([{ message: { content: 'Hi' } }, { done: true }]);
+    }
+  });
+});
@@ -0,0 +1,168 @@
+/**
+ * Structured Output Tests
+ * Tests for cv ask --schema: schema checks, validation and retrying replies that don't match
+ */
+
+import { describe, it, expect } from 'vitest';
+import * as os from 'os';
+import * as path from 'path';
+import { promises as fs } from 'fs';
+import {
+  StructuredOutputError,
+  checkJsonSchema,
+  generateStructured,
+  loadJsonSchema,
+  parseJsonReply,
+  validateJsonSchema,
+} from '../../packages/core/src/ai/structured-output.js';
+import type { AIClient, AITool, AIToolMessage, AIToolResult } from '../../packages/core/src/ai/types.js';
+
+const endpoints = {
+  type: 'object',
+  properties: {
+    endpoints: {
+      type: 'array',
+      items: { $ref: '#/$defs/endpoint' },
+    },
+  },
+  required: ['endpoints'],
+  additionalProperties: false,
+  $defs: {
+    endpoint: {
+      type: 'object',
+      properties: {
+        method: { enum: ['GET', 'POST', 'PUT', 'DELETE'] },
+        path: { type: 'string', pattern: '^/' },
+      },
+      required: ['method', 'path'],
+    },
+  },
+};
+
+/** Client whose replies are scripted; records what it was sent */
+function scriptedClient(replies: Array<AIToolResult | string>, tools = true) {
+  const sent: { messages: AIToolMessage[]; tools?: AITool[] }[] = [];
+  const next = () => replies.shift()!;
+  const client: AIClient = {
+    getModel: () => 'test-model',
+    setModel: () => {},
+    getProvider: () => 'test',
+    isReady: async () => true,
+    chat: async messages => {
+      sent.push({ messages: [...messages] });
+      return next() as string;
+    },
+    chatStream: async () => '',
+    complete: async () => '',
+    ...(tools && {
+      chatWithTools: async (messages: AIToolMessage[], offered: AITool[]) => {
+        sent.push({ messages: [...messages], tools: offered });
+        return next() as AIToolResult;
+      },
+    }),
+  };
+  return { client, sent };
+}
+
+const call = (args: Record<string, unknown>): AIToolResult => ({ text: '', toolCalls: [{ id: 'c1', name: 'answer', arguments: args }] });
+
+describe('validateJsonSchema', () => {
+  it('should accept matching values', () => {
+    expect(validateJsonSchema({ endpoints: [{ method: 'GET', path: '/users' }] }, endpoints)).toEqual([]);
+    expect(validateJsonSchema(3, { type: ['integer', 'null'], minimum: 1 })).toEqual([]);
+    expect(validateJsonSchema(null, { type: ['integer', 'null'] })).toEqual([]);
+  });
+
+  it('should report where a value does not match', () => {
+    const errors = validateJsonSchema({ endpoints: [{ method: 'FETCH', path: 'users' }, { path: '/' }], extra: 1 }, endpoints);
+    expect(errors).toEqual([
+      '$.endpoints[0].method: expected one of "GET", "POST", "PUT", "DELETE"',
+      '$.endpoints[0].path: does not match ^/',
+      '$.endpoints[1]: missing required property "method"',
+      '$: unexpected property "extra"',
+    ]);
+    expect(validateJsonSchema('3', { type: 'integer' })).toEqual(['$: expected integer, got string']);
+    expect(validateJsonSchema([1, 1], { type: 'array', uniqueItems: true, maxItems: 1 })).toHaveLength(2);
+    expect(validateJsonSchema(5, { oneOf: [{ type: 'integer' }, { type: 'number' }] })[0]).toContain('matches 2 of the oneOf schemas');
+  });
+});
+
+describe('checkJsonSchema', () => {
+  it('should reject unknown types, unresolved refs and remote refs', () => {
+    expect(checkJsonSchema(endpoints)).toEqual([]);
+    expect(checkJsonSchema([])).toEqual(['the schema must be a JSON object']);
+    expect(checkJsonSchema({ type: 'list' })).toEqual(['#: unknown type "list"']);
+    expect(checkJsonSchema({ items: { $ref: '#/$defs/missing' } })[0]).toContain('does not resolve');
+    expect(checkJsonSchema({ $ref: 'https://example.com/schema.json' })[0]).toContain('only local $refs');
+  });
+
+  it('should not mistake property names for keywords', () => {
+    expect(checkJsonSchema({ type: 'object', properties: { type: { type: 'string' } } })).toEqual([]);
+  });
+
+  it('should name the file when loading a bad schema', async () => {
+    const dir = await fs.mkdtemp(path.join(os.tmpdir(), 'cv-schema-'));
+    const file = path.join(dir, 'schema.json');
+    await fs.writeFile(file, '{ "type": ');
+    await expect(loadJsonSchema(file)).rejects.toThrow(`${file} is not valid JSON`);
+    await fs.writeFile(file, JSON.stringify(endpoints));
+    expect(await loadJsonSchema(file)).toEqual(endpoints);
+    await fs.rm(dir, { recursive: true, force: true });
+  });
+});
+
+describe('generateStructured', () => {
+  it('should send the schema as the answer tool, with refs inlined', async () => {
+    const { client, sent } = scriptedClient([call({ endpoints: [{ method: 'POST', path: '/login' }] })]);
+    const result = await generateStructured(client, 'List the endpoints', endpoints);
+
+    expect(result).toEqual({ value: { endpoints: [{ method: 'POST', path: '/login' }] }, attempts: 1, mode: 'tool' });
+    const parameters = sent[0].tools![0].parameters as any;
+    expect(parameters.properties.endpoints.items.required).toEqual(['method', 'path']);
+    expect(parameters.$defs).toBeUndefined();
+  });
+
+  it('should send schema errors back and retry', async () => {
+    const { client, sent } = scriptedClient([
+      call({ endpoints: [{ method: 'get', path: '/users' }] }),
+      call({ endpoints: [{ method: 'GET', path: '/users' }] }),
+    ]);
+    const retries: string[][] = [];
+    const result = await generateStructured(client, 'List the endpoints', endpoints, { onRetry: (_, errors) => retries.push(errors) });
+
+    expect(result.attempts).toBe(2);
+    expect(retries).toEqual([['$.endpoints[0].method: expected one of "GET", "POST", "PUT", "DELETE"']]);
+    const feedback = sent[1].messages[sent[1].messages.length - 1];
+    expect(feedback.role).toBe('tool');
+    expect(feedback.content).toContain('does not match the schema');
+  });
+
+  it('should give up after the last attempt', async () => {
+    const { client } = scriptedClient([call({}), call({})]);
+    const failure = generateStructured(client, 'List the endpoints', endpoints, { maxAttempts: 2 });
+    await expect(failure).rejects.toBeInstanceOf(StructuredOutputError);
+    await expect(failure).rejects.toThrow('after 2 attempts: $: missing required property "endpoints"');
+  });
+
+  it('should wrap schemas that are not objects', async () => {
+    const { client, sent } = scriptedClient([call({ answer: ['a', 'b'] })]);
+    const result = await generateStructured(client, 'Names', { type: 'array', items: { type: 'string' } });
+    expect(result.value).toEqual(['a', 'b']);
+    expect((sent[0].tools![0].parameters as any).required).toEqual(['answer']);
+  });
+
+  it('should ask for JSON in the prompt without tool calling', async () => {
+    const { client, sent } = scriptedClient(['Sure:\n```json\n{"endpoints": []}\n```'], false);
+    const result = await generateStructured(client, 'List the endpoints', endpoints);
+    expect(result).toEqual({ value: { endpoints: [] }, attempts: 1, mode: 'json' });
+    expect(sent[0].messages[0].content).toContain('matching this JSON Schema');
+  });
+});
+
+describe('parseJsonReply', () => {
+  it('should find JSON in the reply', () => {
+    expect(parseJsonReply('[1, 2]')).toEqual([1, 2]);
+    expect(parseJsonReply('Here it is: {"a": 1} hope that helps')).toEqual({ a: 1 });
+    expect(parseJsonReply('no json here')).toBeUndefined();
+  });
+});
@@ -0,0 +1,75 @@
+/**
+ * Summary Cache Unit Tests
+ * Tests for blob-hash keyed summary and explanation storage
+ */
+
+import { describe, it, expect, beforeEach, afterEach } from 'vitest';
+import { promises as fs } from 'fs';
+import * as path from 'path';
+import * as os from 'os';
+import {
+  SummaryCache,
+  gitBlobHash,
+  gitBlobHashFile,
+  combineBlobHashes
+} from '../../packages/core/src/services/summary-cache.js';
+
+describe('SummaryCache', () => {
+  let tempDir: string;
+  let cache: SummaryCache;
+
+  beforeEach(async () => {
+    tempDir = await fs.mkdtemp(path.join(os.tmpdir(), 'cv-summary-cache-test-'));
+    cache = new SummaryCache(tempDir);
+  });
+
+  afterEach(async () => {
+    await fs.rm(tempDir, { recursive: true, force: true });
+  });
+
+  it('should hash content the way git hash-object does', async () => {
+    // `printf 'hello\n' | git hash-object --stdin`
+    expect(gitBlobHash('hello\n')).toBe('ce013625030ba8dba906f756967f9e9ca394464a');
+    const file = path.join(tempDir, 'hello.txt');
+    await fs.writeFile(file, 'hello\n');
+    expect(await gitBlobHashFile(file)).toBe(gitBlobHash('hello\n'));
+  });
+
+  it('should miss when the blob or prompt version changes', async () => {
+    const blob = gitBlobHash('export const a = 1;\n');
+    await cache.set('summaries', blob, 'file-v1', 'Defines a.');
+
+    expect(await cache.get('summaries', blob, 'file-v1')).toBe('Defines a.');
+    expect(await cache.get('summaries', blob, 'file-v2')).toBeNull();
+    expect(await cache.get('summaries', gitBlobHash('export const a = 2;\n'), 'file-v1')).toBeNull();
+    expect(await cache.get('explanations', blob, 'file-v1')).toBeNull();
+  });
+
+  it('should persist entries and count hits across instances', async () => {
+    let calls = 0;
+    const generate = async () => `summary ${++calls}`;
+    await cache.getOrCreate('summaries', 'abc', 'v1', generate);
+
+    const reopened = new SummaryCache(tempDir);
+    expect(await reopened.getOrCreate('summaries', 'abc', 'v1', generate)).toBe('summary 1');
+    expect(calls).toBe(1);
+
+    const [summaries, explanations] = await reopened.stats();
+    expect(summaries).toMatchObject({ kind: 'summaries', entries: 1, hits: 1, promptVersions: { v1: 1 } });
+    expect(explanations.entries).toBe(0);
+  });
+
+  it('should clear one kind at a time', async () => {
+    await cache.set('summaries', 'a', 'v1', 'x');
+    await cache.set('explanations', 'b', 'v1', 'y');
+
+    expect(await cache.clear('summaries')).toBe(1);
+    expect(await cache.get('summaries', 'a', 'v1')).toBeNull();
+    expect(await cache.get('explanations', 'b', 'v1')).toBe('y');
+  });
+
+  it('should combine blob hashes independently of order', () => {
+    expect(combineBlobHashes(['a', 'b'], 'target')).toBe(combineBlobHashes(['b', 'a'], 'target'));
+    expect(combineBlobHashes(['a', 'b'], 'target')).not.toBe(combineBlobHashes(['a', 'b'], 'other'));
+  });
+});
@@ -0,0 +1,150 @@
+/**
+ * Symbol Graph Tests
+ * Tests for symbol lookup, call traversal and graph export
+ */
+
+import { describe, it, expect } from 'vitest';
+import {
+  collectSymbolGraph,
+  findSymbols,
+  formatSymbolGraph,
+  traverseCalls,
+} from '../../packages/core/src/graph/export.js';
+import type { SymbolNode } from '../../packages/shared/src/types.js';
+
+function symbol(qualifiedName: string, kind: SymbolNode['kind'] = 'function'): SymbolNode {
+  const [file, member] = qualifiedName.split(':');
+  return {
+    name: member.split('.').pop()!,
+    qualifiedName,
+    kind,
+    file,
+    startLine: 1,
+    endLine: 5,
+    visibility: 'public',
+    isAsync: false,
+    isStatic: false,
+    complexity: 1,
+    createdAt: 0,
+    updatedAt: 0,
+  };
+}
+
+const symbols = [
+  symbol('src/main.rs:main'),
+  symbol('src/engine.rs:ComputeEngine', 'struct'),
+  symbol('src/engine.rs:ComputeEngine.compute_heavy_task', 'method'),
+  symbol('src/engine.rs:ComputeEngine.split', 'method'),
+  symbol('src/pool.rs:spawn'),
+  symbol('src/traits.rs:ParallelCompute', 'interface'),
+  symbol('src/other.rs:compute_heavy_task'),
+];
+
+const calls: Array<[string, string]> = [
+  ['src/main.rs:main', 'src/engine.rs:ComputeEngine.compute_heavy_task'],
+  ['src/engine.rs:ComputeEngine.compute_heavy_task', 'src/engine.rs:ComputeEngine.split'],
+  ['src/engine.rs:ComputeEngine.compute_heavy_task', 'src/pool.rs:spawn'],
+  ['src/engine.rs:ComputeEngine.split', 'src/pool.rs:spawn'],
+];
+
+const bySymbol = new Map(symbols.map(s => [s.qualifiedName, s]));
+
+/** Answers the queries the helpers send, from the tables above */
+const graph = {
+  async query(cypher: string, params?: Record<string, any>) {
+    if (cypher.includes('ENDS WITH')) {
+      return symbols
+        .filter(s => s.qualifiedName === params!.query || s.qualifiedName.endsWith(params!.suffix) || s.name === params!.query)
+        .map(s => ({ s }));
+    }
+    if (cypher.includes('CALLS')) return calls.map(([source, target]) => ({ source, target }));
+    if (cypher.includes('INHERITS')) {
+      return [{ source: 'src/engine.rs:ComputeEngine', target: 'src/traits.rs:ParallelCompute', type: 'implements' }];
+    }
+    return symbols.map(s => ({ id: s.qualifiedName, name: s.name, kind: s.kind, file: s.file, line: s.startLine }));
+  },
+  async getCallers(qualifiedName: string) {
+    return calls.filter(([, target]) => target === qualifiedName).map(([source]) => bySymbol.get(source)!);
+  },
+  async getCallees(qualifiedName: string) {
+    return calls.filter(([source]) => source === qualifiedName).map(([, target]) => bySymbol.get(target)!);
+  },
+};
+
+describe('findSymbols', () => {
+  it('should find methods written Type::method or Type.method', async () => {
+    for (const query of ['ComputeEngine::compute_heavy_task', 'ComputeEngine.compute_heavy_task']) {
+      const found = await findSymbols(graph as any, query);
+      expect(found.map(s => s.qualifiedName)).toEqual(['src/engine.rs:ComputeEngine.compute_heavy_task']);
+    }
+  });
+
+  it('should return every symbol with a bare name, and only the exact qualified name', async () => {
+    expect((await findSymbols(graph as any, 'compute_heavy_task')).map(s => s.file)).toEqual(['src/engine.rs', 'src/other.rs']);
+    expect((await findSymbols(graph as any, 'src/other.rs:compute_heavy_task')).map(s => s.file)).toEqual(['src/other.rs']);
+    expect(await findSymbols(graph as any, 'missing')).toEqual([]);
+  });
+});
+
+describe('traverseCalls', () => {
+  const root = [bySymbol.get('src/engine.rs:ComputeEngine.compute_heavy_task')!];
+
+  it('should list direct callers and callees', async () => {
+    expect((await traverseCalls(graph, root, 'callers')).map(e => e.symbol.name)).toEqual(['main']);
+    expect((await traverseCalls(graph, root, 'callees')).map(e => e.symbol.name)).toEqual(['split', 'spawn']);
+  });
+
+  it('should list each symbol once at its shortest depth', async () => {
+    const entries = await traverseCalls(graph, [bySymbol.get('src/main.rs:main')!], 'callees', 5);
+    expect(entries.map(e => [e.symbol.name, e.depth, e.via.split(':')[1]])).toEqual([
+      ['compute_heavy_task', 1, 'main'],
+      ['split', 2, 'ComputeEngine.compute_heavy_task'],
+      ['spawn', 2, 'ComputeEngine.compute_heavy_task'],
+    ]);
+  });
+});
+
+describe('collectSymbolGraph', () => {
+  it('should keep only connected symbols', async () => {
+    const result = await collectSymbolGraph(graph as any);
+    expect(result.nodes.map(n => n.id)).not.toContain('src/other.rs:compute_heavy_task');
+    expect(result.edges).toHaveLength(5);
+    expect(result.edges.at(-1)).toEqual({
+      source: 'src/engine.rs:ComputeEngine', target: 'src/traits.rs:ParallelCompute', type: 'implements',
+    });
+  });
+
+  it('should filter by relation and file', async () => {
+    const result = await collectSymbolGraph(graph as any, { relations: ['calls'], file: './src/main.rs' });
+    expect(result.edges).toEqual([{ source: 'src/main.rs:main', target: 'src/engine.rs:ComputeEngine.compute_heavy_task', type: 'calls' }]);
+    expect(result.nodes.map(n => n.name)).toEqual(['compute_heavy_task', 'main']);
+  });
+});
+
+describe('formatSymbolGraph', () => {
+  const symbolGraph = {
+    nodes: [
+      { id: 'a.ts:A', name: 'A', kind: 'class', file: 'a.ts', line: 1 },
+      { id: 'b.ts:"B"<T>', name: 'B', kind: 'interface', file: 'b.ts', line: 3 },
+    ],
+    edges: [{ source: 'a.ts:A', target: 'b.ts:"B"<T>', type: 'implements' as const }],
+  };
+
+  it('should render Graphviz DOT', () => {
+    const dot = formatSymbolGraph(symbolGraph, 'dot');
+    expect(dot).toMatch(/^digraph symbols \{/);
+    expect(dot).toContain('"a.ts:A" [label="A\\na.ts:1", shape=component];');
+    expect(dot).toContain('"a.ts:A" -> "b.ts:\\"B\\"<T>" [style=dashed, arrowhead=empty, label="implements"];');
+  });
+
+  it('should render escaped GraphML', () => {
+    const graphml = formatSymbolGraph(symbolGraph, 'graphml');
+    expect(graphml).toContain('<node id="b.ts:&quot;B&quot;&lt;T&gt;">');
+    expect(graphml).toContain('<edge id="e0" source="a.ts:A" target="b.ts:&quot;B&quot;&lt;T&gt;">');
+    expect(graphml).toContain('<data key="type">implements</data>');
+  });
+
+  it('should render JSON', () => {
+    expect(JSON.parse(formatSymbolGraph(symbolGraph, 'json'))).toEqual(symbolGraph);
+  });
+});
@@ -0,0 +1,69 @@
+/**
+ * Sync History Tests
+ */
+
+import { describe, it, expect, beforeEach, afterEach } from 'vitest';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import {
+  appendSyncHistory,
+  loadSyncHistory,
+  getSyncHistoryPath,
+  SYNC_HISTORY_LIMIT
+} from '../../packages/core/src/sync/history.js';
+import type { SyncReport } from '../../packages/core/src/sync/index.js';
+
+function report(timestamp: number, added = 0): SyncReport {
+  return {
+    timestamp,
+    duration: 1.5,
+    type: 'delta',
+    success: true,
+    stats: { filesProcessed: added, filesFailed: 0, symbolsCreated: 0, vectorsCreated: 0 },
+    changes: { added, modified: 0, deleted: 0 },
+    phases: { parse: 200, embed: 900 },
+    errors: []
+  };
+}
+
+describe('Sync history', () => {
+  let repoRoot: string;
+
+  beforeEach(() => {
+    repoRoot = fs.mkdtempSync(path.join(os.tmpdir(), 'cv-sync-history-'));
+    fs.mkdirSync(path.join(repoRoot, '.cv'));
+  });
+
+  afterEach(() => {
+    fs.rmSync(repoRoot, { recursive: true, force: true });
+  });
+
+  it('should return reports newest first', async () => {
+    await appendSyncHistory(repoRoot, report(1, 3));
+    await appendSyncHistory(repoRoot, report(2, 1));
+
+    const history = await loadSyncHistory(repoRoot);
+    expect(history.map(r => r.timestamp)).toEqual([2, 1]);
+    expect(history[1].changes?.added).toBe(3);
+    expect(await loadSyncHistory(repoRoot, 1)).toHaveLength(1);
+  });
+
+  it('should keep only the newest entries', async () => {
+    const lines = Array.from({ length: SYNC_HISTORY_LIMIT }, (_, i) => JSON.stringify(report(i)));
+    fs.writeFileSync(getSyncHistoryPath(repoRoot), lines.join('\n') + '\n');
+
+    await appendSyncHistory(repoRoot, report(SYNC_HISTORY_LIMIT));
+
+    const history = await loadSyncHistory(repoRoot);
+    expect(history).toHaveLength(SYNC_HISTORY_LIMIT);
+    expect(history[0].timestamp).toBe(SYNC_HISTORY_LIMIT);
+    expect(history[history.length - 1].timestamp).toBe(1);
+  });
+
+  it('should skip truncated lines and tolerate a missing file', async () => {
+    expect(await loadSyncHistory(repoRoot)).toEqual([]);
+    fs.writeFileSync(getSyncHistoryPath(repoRoot), JSON.stringify(report(1)) + '\n{"timestamp": 2, "dur');
+    expect((await loadSyncHistory(repoRoot)).map(r => r.timestamp)).toEqual([1]);
+  });
+});
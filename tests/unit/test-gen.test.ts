@@ -0,0 +1,187 @@
+/**
+ * Test Generation Tests
+ * Tests for target parsing, test layout detection, prompts and the generate-run-fix loop
+ */
+
+import { describe, it, expect, vi } from 'vitest';
+import {
+  applyTestCode,
+  buildTestGenPrompt,
+  detectTestCommand,
+  extractTestCode,
+  parseTestTarget,
+  pickTestExamples,
+  planTestLayout,
+  runTestGeneration,
+  testCommandFor,
+  TestGenContext
+} from '../../packages/core/src/ai/test-gen.js';
+
+const rustContext = (): TestGenContext => ({
+  target: { file: 'src/compute/engine.rs', symbol: 'multiply_matrices' },
+  language: 'rust',
+  code: 'pub fn multiply_matrices(a: &Matrix, b: &Matrix) -> Matrix {\n    todo!()\n}',
+  startLine: 42,
+  callers: [{ name: 'run', file: 'src/main.rs', code: 'let c = multiply_matrices(&a, &b);' }],
+  layout: planTestLayout('src/compute/engine.rs', [], 'pub fn multiply_matrices() {}'),
+  examples: [],
+  testCommand: 'cargo test'
+});
+
+describe('parseTestTarget', () => {
+  it('splits the file from the symbol at the first ::', () => {
+    expect(parseTestTarget('src/compute/engine.rs::multiply_matrices'))
+      .toEqual({ file: 'src/compute/engine.rs', symbol: 'multiply_matrices' });
+    expect(parseTestTarget('./engine.rs::Matrix::multiply')).toEqual({ file: 'engine.rs', symbol: 'Matrix::multiply' });
+    expect(parseTestTarget('src/pool.ts')).toEqual({ file: 'src/pool.ts' });
+  });
+});
+
+describe('planTestLayout', () => {
+  it('uses an inline #[cfg(test)] module for Rust unless the crate has integration tests', () => {
+    expect(planTestLayout('src/engine.rs', [], 'fn f() {}').placement).toBe('inline');
+    const integration = planTestLayout('src/engine.rs', ['tests/pool.rs'], 'fn f() {}');
+    expect(integration).toMatchObject({ placement: 'file', testFile: 'tests/engine.rs' });
+    expect(planTestLayout('src/engine.rs', ['tests/pool.rs'], '#[cfg(test)]\nmod tests {}').placement).toBe('inline');
+  });
+
+  it('puts Go tests next to the source and Python tests in the tests directory', () => {
+    expect(planTestLayout('pkg/pool/pool.go', [], '').testFile).toBe('pkg/pool/pool_test.go');
+    expect(planTestLayout('app/views.py', ['tests/test_models.py'], '').testFile).toBe('tests/test_views.py');
+    expect(planTestLayout('app/views.py', [], '').testFile).toBe('app/test_views.py');
+  });
+
+  it('follows the repository\'s JavaScript convention', () => {
+    const separate = planTestLayout('packages/core/src/pool.ts', ['tests/unit/a.test.ts', 'tests/unit/b.test.ts'], '');
+    expect(separate.testFile).toBe('tests/unit/pool.test.ts');
+    const colocated = planTestLayout('src/pool.ts', ['src/a.spec.ts', 'src/b.spec.ts'], '');
+    expect(colocated.testFile).toBe('src/pool.spec.ts');
+  });
+
+  it('mirrors src/main into src/test for Java', () => {
+    expect(planTestLayout('src/main/java/com/acme/pool.java', [], '').testFile)
+      .toBe('src/test/java/com/acme/PoolTest.java');
+  });
+});
+
+describe('pickTestExamples', () => {
+  it('prefers the nearest test files of the same language', () => {
+    const tests = ['tests/other.test.ts', 'packages/core/tests/near.test.ts', 'tests/pool_test.go'];
+    expect(pickTestExamples('packages/core/src/pool.ts', tests)).toEqual(['packages/core/tests/near.test.ts', 'tests/other.test.ts']);
+  });
+});
+
+describe('detectTestCommand', () => {
+  it('detects the command from root manifests', () => {
+    expect(detectTestCommand(['Cargo.toml', 'README.md'])).toBe('cargo test');
+    expect(detectTestCommand(['go.mod'])).toBe('go test ./...');
+    expect(detectTestCommand(['pyproject.toml'])).toBe('pytest');
+    expect(detectTestCommand(['README.md'])).toBeUndefined();
+  });
+
+  it('substitutes the test file', () => {
+    expect(testCommandFor('pnpm vitest run {file}', 'tests/unit/pool.test.ts')).toBe('pnpm vitest run tests/unit/pool.test.ts');
+  });
+});
+
+describe('buildTestGenPrompt', () => {
+  it('includes the code, callers and placement', () => {
+    const prompt = buildTestGenPrompt(rustContext());
+    expect(prompt).toContain('`multiply_matrices` in src/compute/engine.rs');
+    expect(prompt).toContain('from line 42');
+    expect(prompt).toContain('let c = multiply_matrices(&a, &b);');
+    expect(prompt).toContain('#[cfg(test)]');
+    expect(prompt).toContain('`multiply_matrices_tests`');
+    expect(prompt).toContain('`cargo test`');
+    expect(prompt).not.toContain('previous tests failed');
+  });
+
+  it('feeds back the failed attempt with the end of its output', () => {
+    const output = 'x'.repeat(10000) + '\nthread panicked at assertion failed';
+    const prompt = buildTestGenPrompt(rustContext(), { code: 'mod multiply_matrices_tests {}', exitCode: 101, output });
+    expect(prompt).toContain('Your previous tests failed');
+    expect(prompt).toContain('exit code 101');
+    expect(prompt).toContain('thread panicked at assertion failed');
+    expect(prompt.length).toBeLessThan(9000);
+  });
+});
+
+describe('extractTestCode and applyTestCode', () => {
+  it('takes the fenced block from the reply', () => {
+    expect(extractTestCode('Here you go:\n```rust\n#[cfg(test)]\nmod t {}\n```\n')).toBe('#[cfg(test)]\nmod t {}\n');
+    expect(extractTestCode('mod t {}')).toBe('mod t {}\n');
+    expect(() => extractTestCode('```\n```')).toThrow('no test code');
+  });
+
+  it('appends inline modules and replaces test files', () => {
+    const inline = rustContext().layout;
+    expect(applyTestCode(inline, 'fn f() {}\n\n', 'mod t {}\n')).toBe('fn f() {}\n\nmod t {}\n');
+    expect(applyTestCode({ placement: 'file', testFile: 'x_test.go', convention: '' }, 'old', 'new\n')).toBe('new\n');
+  });
+});
+
+describe('runTestGeneration', () => {
+  it('retries with the failure until the tests pass', async () => {
+    const prompts: string[] = [];
+    const writes: string[] = [];
+    const runs = [{ exitCode: 101, output: 'assertion failed: left == right' }, { exitCode: 0, output: 'ok' }];
+    const result = await runTestGeneration({
+      context: rustContext(),
+      attempts: 3,
+      original: 'fn f() {}\n',
+      generate: async prompt => {
+        prompts.push(prompt);
+        return '```rust\nmod multiply_matrices_tests {}\n```';
+      },
+      write: async content => { writes.push(content); },
+      runTests: vi.fn(async () => runs.shift()!)
+    });
+
+    expect(result.passed).toBe(true);
+    expect(result.attempts).toHaveLength(2);
+    expect(prompts[1]).toContain('assertion failed: left == right');
+    // Each attempt starts again from the original file
+    expect(writes[1]).toBe('fn f() {}\n\nmod multiply_matrices_tests {}\n');
+  });
+
+  it('stops after the last attempt, or after one when not running', async () => {
+    const failing = await runTestGeneration({
+      context: rustContext(),
+      attempts: 2,
+      original: null,
+      generate: async () => 'mod t {}',
+      write: async () => {},
+      runTests: async () => ({ exitCode: 1, output: 'FAILED' })
+    });
+    expect(failing).toMatchObject({ passed: false });
+    expect(failing.attempts).toHaveLength(2);
+
+    const generate = vi.fn(async () => 'mod t {}');
+    const unrun = await runTestGeneration({ context: rustContext(), attempts: 3, original: null, generate, write: async () => {} });
+    expect(generate).toHaveBeenCalledTimes(1);
+    expect(unrun.attempts[0].exitCode).toBeNull();
+  });
+
+  it('writes secrets the model echoed as masks and refuses masks it cannot restore', async () => {
+    const context = { ...rustContext(), code: 'pub fn multiply_matrices() {\n    let api_secret = "hunter2hunter2";\n}' };
+    const writes: string[] = [];
+    await runTestGeneration({
+      context,
+      attempts: 1,
+      original: null,
+      generate: async () => 'mod t { fn f() { let api_secret = "[REDACTED:secret-assignment]"; } }',
+      write: async content => { writes.push(content); }
+    });
+    expect(writes[0]).toContain('"hunter2hunter2"');
+
+    const write = vi.fn(async () => {});
+    await expect(runTestGeneration({
+      context: rustContext(),
+      attempts: 1,
+      original: null,
+      generate: async () => 'mod t { const KEY: &str = "[REDACTED:api-key]"; }',
+      write
+    })).rejects.toThrow(/refusing to write a redaction placeholder/);
+    expect(write).not.toHaveBeenCalled();
+  });
+});
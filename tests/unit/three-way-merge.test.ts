@@ -0,0 +1,173 @@
+/**
+ * Three-Way Merge Tests
+ * Applying proposed edits to files that changed after they were proposed
+ */
+
+import { describe, it, expect, beforeEach, afterEach } from 'vitest';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { MAX_EDIT_DISTANCE, matchLines, merge3, resolveMerge } from '../../packages/core/src/code/merge.js';
+import { FileOperations } from '../../packages/core/src/code/file-ops.js';
+import { Edit } from '../../packages/core/src/code/types.js';
+
+const base = 'one\ntwo\nthree\nfour\nfive\n';
+
+describe('merge3', () => {
+  it('should combine changes to different regions', () => {
+    const result = merge3(base, 'one\nTWO\nthree\nfour\nfive\n', 'one\ntwo\nthree\nfour\nFIVE\n');
+    expect(result.conflicts).toBe(0);
+    expect(result.content).toBe('one\nTWO\nthree\nfour\nFIVE\n');
+  });
+
+  it('should report overlapping changes as conflicts', () => {
+    const result = merge3(base, 'one\nmine\nthree\nfour\nfive\n', 'one\ntheirs\nthree\nfour\nfive\n');
+    expect(result.conflicts).toBe(1);
+    expect(result.content).toContain('<<<<<<< working copy\nmine\n=======\ntheirs\n>>>>>>> proposed edit');
+
+    const conflict = result.hunks.find(h => h.kind === 'conflict');
+    expect(conflict).toMatchObject({ ours: ['mine'], theirs: ['theirs'], base: ['two'], oursLine: 2 });
+
+    expect(resolveMerge(result, ['theirs'])).toBe('one\ntheirs\nthree\nfour\nfive\n');
+    expect(resolveMerge(result, ['both'])).toBe('one\nmine\ntheirs\nthree\nfour\nfive\n');
+  });
+
+  it('should treat identical changes on both sides as clean', () => {
+    const changed = 'one\ntwo\n3\nfour\nfive\n';
+    expect(merge3(base, changed, changed)).toMatchObject({ conflicts: 0, content: changed });
+  });
+});
+
+describe('matchLines', () => {
+  it('should match a longest common subsequence', () => {
+    expect(matchLines(['a', 'b', 'c', 'a', 'b', 'b', 'a'], ['c', 'b', 'a', 'b', 'a', 'c']))
+      .toEqual([-1, 1, -1, 2, 3, -1, 4]);
+    expect(matchLines(['x', 'a'], ['a', 'x'])).toEqual([-1, 0]);
+    expect(matchLines([], ['a'])).toEqual([]);
+  });
+
+  it('should diff large files quickly, replacing regions that share almost nothing', () => {
+    const lines = Array.from({ length: 20000 }, (_, i) => `line ${i}`);
+    const edited = lines.map((line, i) => (i % 500 === 0 ? `${line} changed` : line));
+    const matches = matchLines(lines, edited);
+    expect(matches.filter(j => j < 0)).toHaveLength(40);
+    expect(matches[1]).toBe(1);
+
+    const unrelated = Array.from({ length: MAX_EDIT_DISTANCE }, (_, i) => `other ${i}`);
+    expect(matchLines(lines, ['line 0', ...unrelated]).filter(j => j >= 0)).toEqual([0]);
+  });
+});
+
+describe('FileOperations with a drifted working copy', () => {
+  let repoRoot: string;
+  const file = 'src/app.ts';
+
+  const modifyEdit = (blocks: Edit['searchReplaceBlocks']): Edit => ({
+    id: 'edit-1',
+    file,
+    type: 'modify',
+    searchReplaceBlocks: blocks,
+    baseContent: base,
+    status: 'approved',
+    messageId: 'msg-1',
+    createdAt: Date.now()
+  });
+
+  beforeEach(() => {
+    repoRoot = fs.mkdtempSync(path.join(os.tmpdir(), 'cv-merge-'));
+    fs.mkdirSync(path.join(repoRoot, 'src'));
+  });
+
+  afterEach(() => {
+    fs.rmSync(repoRoot, { recursive: true, force: true });
+  });
+
+  it('should merge the edit into uncommitted changes elsewhere in the file', async () => {
+    fs.writeFileSync(path.join(repoRoot, file), 'one\ntwo\nthree\nfour\nfive\nsix\n');
+    const ops = new FileOperations(repoRoot);
+
+    const result = await ops.applyEdit(modifyEdit([{ search: 'two\n', replace: 'TWO\n' }]));
+
+    expect(result.success).toBe(true);
+    expect(result.merged).toBe(true);
+    expect(fs.readFileSync(path.join(repoRoot, file), 'utf-8')).toBe('one\nTWO\nthree\nfour\nfive\nsix\n');
+  });
+
+  it('should leave the file untouched when conflicts are not resolved', async () => {
+    const userVersion = 'one\nmine\nthree\nfour\nfive\n';
+    fs.writeFileSync(path.join(repoRoot, file), userVersion);
+    const ops = new FileOperations(repoRoot);
+    const edit = modifyEdit([{ search: 'two\n', replace: 'theirs\n' }]);
+
+    const unresolved = await ops.applyEdit(edit);
+    expect(unresolved.success).toBe(false);
+    expect(unresolved.error).toContain('conflict');
+
+    const cancelled = await ops.applyEdit(edit, { resolveConflict: async () => null });
+    expect(cancelled.success).toBe(false);
+    expect(fs.readFileSync(path.join(repoRoot, file), 'utf-8')).toBe(userVersion);
+
+    const resolved = await ops.applyEdit(edit, { resolveConflict: async () => 'theirs' });
+    expect(resolved).toMatchObject({ success: true, merged: true, conflictsResolved: 1 });
+    expect(fs.readFileSync(path.join(repoRoot, file), 'utf-8')).toBe('one\ntheirs\nthree\nfour\nfive\n');
+  });
+
+  it('should not delete a file that changed since the deletion was proposed', async () => {
+    fs.writeFileSync(path.join(repoRoot, file), base + 'six\n');
+    const ops = new FileOperations(repoRoot);
+
+    const result = await ops.applyEdit({ ...modifyEdit(undefined), type: 'delete' });
+
+    expect(result.success).toBe(false);
+    expect(fs.existsSync(path.join(repoRoot, file))).toBe(true);
+  });
+});
+
+describe('FileOperations with redacted model output', () => {
+  let repoRoot: string;
+  const file = 'src/config.ts';
+  const secret = 'const password = "hunter2hunter2";\nconst port = 80;\n';
+
+  const edit = (fields: Partial<Edit>): Edit => ({
+    id: 'edit-1',
+    file,
+    type: 'modify',
+    status: 'approved',
+    messageId: 'msg-1',
+    createdAt: Date.now(),
+    ...fields
+  });
+
+  beforeEach(() => {
+    repoRoot = fs.mkdtempSync(path.join(os.tmpdir(), 'cv-redacted-'));
+    fs.mkdirSync(path.join(repoRoot, 'src'));
+    fs.writeFileSync(path.join(repoRoot, file), secret);
+  });
+
+  afterEach(() => {
+    fs.rmSync(repoRoot, { recursive: true, force: true });
+  });
+
+  it('should write the secrets back where the model echoed their masks', async () => {
+    const ops = new FileOperations(repoRoot);
+    const result = await ops.applyEdit(edit({
+      searchReplaceBlocks: [{
+        search: 'const password = "[REDACTED:secret-assignment]";\nconst port = 80;\n',
+        replace: 'const password = "[REDACTED:secret-assignment]";\nconst port = 8080;\n'
+      }]
+    }));
+
+    expect(result.success).toBe(true);
+    expect(fs.readFileSync(path.join(repoRoot, file), 'utf-8')).toBe('const password = "hunter2hunter2";\nconst port = 8080;\n');
+  });
+
+  it('should reject an edit with a mask it cannot restore and leave the file alone', async () => {
+    const ops = new FileOperations(repoRoot);
+    const result = await ops.applyEdit(edit({ type: 'create', file: 'src/new.ts', newContent: 'const key = "[REDACTED:api-key]";\n' }));
+
+    expect(result.success).toBe(false);
+    expect(result.error).toMatch(/refusing to write a redaction placeholder/);
+    expect(fs.existsSync(path.join(repoRoot, 'src/new.ts'))).toBe(false);
+    expect(fs.readFileSync(path.join(repoRoot, file), 'utf-8')).toBe(secret);
+  });
+});
@@ -0,0 +1,186 @@
+/**
+ * TUI Tests
+ * Tests for the cv tui panes, keys and screen layout
+ */
+
+import { describe, it, expect, vi } from 'vitest';
+import { TuiApp, TuiBackend } from '../../packages/cli/src/tui/app.js';
+import { fit, scrollStart, wrap } from '../../packages/cli/src/tui/render.js';
+import { stripAnsi } from '../../packages/cli/src/utils/formatting.js';
+import type { GrepMatch } from '../../packages/core/src/context/code-grep.js';
+import type { StoredFinding } from '../../packages/core/src/ai/review-store.js';
+
+function match(file: string, symbolName: string): GrepMatch {
+  return {
+    file,
+    startLine: 10,
+    endLine: 11,
+    symbolName,
+    symbolKind: 'function',
+    language: 'typescript',
+    score: 1,
+    hunks: [[{ line: 10, text: `function ${symbolName}() {`, match: true }, { line: 11, text: '}', match: false }]],
+  };
+}
+
+function stored(id: string, status: StoredFinding['status'] = 'open'): StoredFinding {
+  return {
+    id,
+    severity: 'high',
+    file: 'src/pool.ts',
+    line: 3,
+    title: `Finding ${id}`,
+    message: 'The queue grows without limit.',
+    status,
+    ref: 'HEAD',
+    firstSeen: '2026-10-12T09:00:00.000Z',
+    lastSeen: '2026-10-12T09:00:00.000Z',
+  };
+}
+
+function createApp(backend: Partial<TuiBackend> = {}) {
+  const onQuit = vi.fn();
+  const app = new TuiApp({
+    backend: {
+      search: async () => [match('src/pool.ts', 'drain'), match('src/net.ts', 'retry')],
+      chat: async () => '',
+      findings: async () => [],
+      setFindingStatus: async () => [],
+      readLines: async () => [],
+      ...backend,
+    },
+    onChange: () => undefined,
+    onQuit,
+  });
+  const type = async (text: string) => {
+    for (const char of text) await app.key(char, { name: char, sequence: char });
+  };
+  const press = (name: string, modifiers: { ctrl?: boolean; shift?: boolean } = {}) => app.key(undefined, { name, ...modifiers });
+  return { app, type, press, onQuit };
+}
+
+describe('TuiApp', () => {
+  it('searches from the search box and moves focus to the results', async () => {
+    const search = vi.fn(async () => [match('src/pool.ts', 'drain'), match('src/net.ts', 'retry')]);
+    const { app, type, press } = createApp({ search });
+
+    await type('drain queue');
+    await press('backspace');
+    await press('return');
+
+    expect(search).toHaveBeenCalledWith('drain queu');
+    expect(app.state.focus).toBe('results');
+    await press('down');
+    await press('down');
+    expect(app.state.resultCursor).toBe(1);
+  });
+
+  it('scopes chat to selected files, falling back to the highlighted result', async () => {
+    const chat = vi.fn(async (_turns, _files, onToken: (token: string) => void) => {
+      onToken('It ');
+      onToken('sleeps.');
+      return 'It sleeps.';
+    });
+    const { app, type, press } = createApp({ chat });
+    await type('drain');
+    await press('return');
+    expect(app.chatScope()).toEqual(['src/pool.ts']);
+
+    await press('down');
+    await type(' ');
+    await press('tab');
+    await press('tab');
+    expect(app.state.focus).toBe('chat');
+    await type('why?');
+    await press('return');
+
+    expect(chat).toHaveBeenCalledWith([{ role: 'user', content: 'why?' }], ['src/net.ts'], expect.any(Function));
+    expect(app.state.chat).toEqual([
+      { role: 'user', content: 'why?' },
+      { role: 'assistant', content: 'It sleeps.' },
+    ]);
+    expect(app.state.chatInput).toBe('');
+  });
+
+  it('drops the pending reply and reports a failed chat', async () => {
+    const { app, type, press } = createApp({ chat: async () => { throw new Error('no API key'); } });
+    await press('tab', { shift: true });
+    await type('hello');
+    await press('return');
+
+    expect(app.state.chat).toEqual([{ role: 'user', content: 'hello' }]);
+    expect(app.state.status).toBe('Chat failed: no API key');
+  });
+
+  it('triages findings and writes the status back to the store', async () => {
+    let findings = [stored('a'), stored('b'), stored('c', 'dismissed')];
+    const setFindingStatus = vi.fn(async (id: string, status: StoredFinding['status']) => {
+      findings = findings.map(f => (f.id === id ? { ...f, status } : f));
+      return findings;
+    });
+    const { app, type, press } = createApp({ findings: async () => findings, setFindingStatus });
+    await app.start();
+    await press('tab');
+    await press('tab');
+    expect(app.state.focus).toBe('findings');
+    expect(app.visibleFindings().map(f => f.id)).toEqual(['a', 'b']);
+
+    await type('j');
+    await type('d');
+    expect(setFindingStatus).toHaveBeenCalledWith('b', 'dismissed');
+    expect(app.visibleFindings().map(f => f.id)).toEqual(['a']);
+    expect(app.state.findingCursor).toBe(0);
+
+    await type('a');
+    expect(app.state.findings[0].status).toBe('accepted');
+
+    await type('h');
+    expect(app.visibleFindings().map(f => f.id)).toEqual(['a', 'b', 'c']);
+    await type('G');
+    await type('o');
+    expect(setFindingStatus).toHaveBeenLastCalledWith('c', 'open');
+  });
+
+  it('quits on Ctrl-C anywhere and q in the lists', async () => {
+    const { type, press, onQuit } = createApp();
+    await type('q');
+    expect(onQuit).not.toHaveBeenCalled();
+    await press('c', { ctrl: true });
+    expect(onQuit).toHaveBeenCalledTimes(1);
+  });
+
+  it('renders exactly the terminal size', async () => {
+    const { app, type, press } = createApp();
+    await type('drain');
+    await press('return');
+
+    for (const [width, height] of [[80, 24], [120, 40], [30, 10]]) {
+      const lines = app.render(width, height);
+      expect(lines).toHaveLength(height);
+      expect(lines.every(line => stripAnsi(line).length === width)).toBe(true);
+    }
+    const screen = app.render(80, 24).map(stripAnsi).join('\n');
+    expect(screen).toContain('src/pool.ts:10 drain');
+    expect(screen).toContain('10 function drain() {');
+  });
+});
+
+describe('layout helpers', () => {
+  it('fits text to a width', () => {
+    expect(fit('abc', 5)).toBe('abc  ');
+    expect(fit('abcdef', 4)).toBe('abc…');
+    expect(fit('a\tb', 4)).toBe('a  b');
+  });
+
+  it('wraps words and splits long ones', () => {
+    expect(wrap('the quick brown fox', 9)).toEqual(['the quick', 'brown fox']);
+    expect(wrap('abcdefghij', 4)).toEqual(['abcd', 'efgh', 'ij']);
+    expect(wrap('one\n\ntwo', 10)).toEqual(['one', '', 'two']);
+  });
+
+  it('keeps the cursor in view', () => {
+    expect(scrollStart(3, 5, 10)).toBe(0);
+    expect(scrollStart(50, 100, 10)).toBe(45);
+    expect(scrollStart(99, 100, 10)).toBe(90);
+  });
+});
@@ -0,0 +1,127 @@
+/**
+ * Usage Tracking Tests
+ * Tests for token counts, pricing and the usage report
+ */
+
+import { describe, it, expect, beforeEach, afterEach } from 'vitest';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import {
+  captureUsage,
+  estimateCost,
+  flushUsage,
+  loadUsage,
+  parseUsage,
+  startUsageTracking,
+  summarizeUsage,
+  UsageRecord,
+} from '../../packages/core/src/ai/usage.js';
+
+describe('estimateCost', () => {
+  it('should price models by name prefix', () => {
+    expect(estimateCost('anthropic', 'claude-sonnet-4-5-20250929', 1_000_000, 100_000)).toBeCloseTo(4.5);
+    expect(estimateCost('openrouter', 'openai/gpt-4o-mini', 1_000_000, 0)).toBeCloseTo(0.15);
+    expect(estimateCost('gemini', 'models/gemini-2.5-flash', 0, 1_000_000)).toBeCloseTo(2.5);
+    expect(estimateCost('openai', 'text-embedding-3-small', 500_000, 0)).toBeCloseTo(0.01);
+  });
+
+  it('should treat local models as free and unknown models as unpriced', () => {
+    expect(estimateCost('ollama', 'llama3', 10_000, 10_000)).toBe(0);
+    expect(estimateCost('openrouter', 'someone/new-model', 10_000, 10_000)).toBeNull();
+  });
+});
+
+describe('parseUsage', () => {
+  it('should read each provider format', () => {
+    expect(parseUsage({ model: 'gpt-4o', usage: { prompt_tokens: 12, completion_tokens: 3 } }))
+      .toEqual({ model: 'gpt-4o', promptTokens: 12, completionTokens: 3 });
+    expect(parseUsage({ type: 'message_start', message: { model: 'claude-3-5-haiku', usage: { input_tokens: 40, output_tokens: 1 } } }))
+      .toEqual({ model: 'claude-3-5-haiku', promptTokens: 40, completionTokens: 1 });
+    expect(parseUsage({ usageMetadata: { promptTokenCount: 7, candidatesTokenCount: 9 } }))
+      .toEqual({ promptTokens: 7, completionTokens: 9 });
+    expect(parseUsage({ model: 'llama3', done: true, prompt_eval_count: 20, eval_count: 5 }))
+      .toEqual({ model: 'llama3', promptTokens: 20, completionTokens: 5 });
+    expect(parseUsage('not json')).toEqual({});
+  });
+});
+
+describe('usage log', () => {
+  let tmpDir: string;
+  let file: string;
+
+  beforeEach(() => {
+    tmpDir = fs.mkdtempSync(path.join(os.tmpdir(), 'cv-usage-'));
+    file = path.join(tmpDir, 'usage.jsonl');
+    startUsageTracking('explain', { repo: '/work/app', file });
+  });
+
+  afterEach(() => {
+    flushUsage();
+    fs.rmSync(tmpDir, { recursive: true, force: true });
+  });
+
+  it('should record counts from JSON and streamed responses', async () => {
+    const body = JSON.stringify({ model: 'gpt-4o', messages: [] });
+    await captureUsage('openai', 'https://api.openai.com/v1/chat/completions', { body }, new Response(
+      JSON.stringify({ usage: { prompt_tokens: 100, completion_tokens: 20 } }),
+      { headers: { 'content-type': 'application/json' } }
+    ));
+    await captureUsage('openai', 'https://api.openai.com/v1/chat/completions', { body }, new Response(
+      'data: {"choices":[{"delta":{"content":"hi"}}]}\n\n' +
+      'data: {"choices":[],"usage":{"prompt_tokens":50,"completion_tokens":10}}\n\ndata: [DONE]\n\n',
+      { headers: { 'content-type': 'text/event-stream' } }
+    ));
+    await captureUsage('ollama', 'http://localhost:11434/api/embed', { body: JSON.stringify({ model: 'nomic-embed-text' }) },
+      new Response(JSON.stringify({ embeddings: [[0.1]], prompt_eval_count: 8 }), {
+        headers: { 'content-type': 'application/json' },
+      }));
+
+    const records = flushUsage();
+    const chat = records.find(r => r.model === 'gpt-4o')!;
+    expect(chat).toMatchObject({ command: 'explain', repo: '/work/app', kind: 'chat', requests: 2, promptTokens: 150, completionTokens: 30 });
+    expect(chat.costUsd).toBeCloseTo((150 * 2.5 + 30 * 10) / 1_000_000);
+    expect(records.find(r => r.model === 'nomic-embed-text')).toMatchObject({ kind: 'embedding', promptTokens: 8, costUsd: 0 });
+
+    expect(loadUsage(file)).toHaveLength(2);
+  });
+
+  it('should estimate prompt tokens when the provider reports none', async () => {
+    await captureUsage('lmstudio', 'http://localhost:1234/v1/chat/completions', { body: JSON.stringify({ model: 'qwen', prompt: 'x'.repeat(400) }) },
+      new Response('{}', { headers: { 'content-type': 'application/json' } }));
+
+    const [record] = flushUsage();
+    expect(record.estimated).toBe(true);
+    expect(record.promptTokens).toBeGreaterThan(100);
+  });
+
+  it('should skip truncated lines and filter by date', () => {
+    fs.writeFileSync(file, [
+      JSON.stringify({ timestamp: '2026-01-01T10:00:00Z', command: 'sync', provider: 'openai', model: 'a', kind: 'embedding', requests: 1, promptTokens: 1, completionTokens: 0, costUsd: 0 }),
+      '{"timestamp": "2026-02',
+      JSON.stringify({ timestamp: '2026-02-01T10:00:00Z', command: 'sync', provider: 'openai', model: 'a', kind: 'embedding', requests: 1, promptTokens: 1, completionTokens: 0, costUsd: 0 }),
+    ].join('\n'));
+
+    expect(loadUsage(file)).toHaveLength(2);
+    expect(loadUsage(file, new Date('2026-01-15T00:00:00Z'))).toHaveLength(1);
+  });
+});
+
+describe('summarizeUsage', () => {
+  it('should total by command, day and model', () => {
+    const base = { provider: 'openai', kind: 'chat' as const, requests: 1, completionTokens: 10 };
+    const records: UsageRecord[] = [
+      { ...base, timestamp: '2026-03-01T12:00:00', command: 'explain', model: 'gpt-4o', promptTokens: 100, costUsd: 0.5 },
+      { ...base, timestamp: '2026-03-01T13:00:00', command: 'do', model: 'gpt-4o', promptTokens: 200, costUsd: 1 },
+      { ...base, timestamp: '2026-03-02T09:00:00', command: 'explain', model: 'mystery', promptTokens: 50, costUsd: null },
+    ];
+
+    const summary = summarizeUsage(records);
+
+    expect(summary.total).toEqual({ requests: 3, promptTokens: 350, completionTokens: 30, costUsd: 1.5, unpriced: 1 });
+    expect(summary.byCommand.explain.requests).toBe(2);
+    expect(summary.byDay['2026-03-01'].costUsd).toBeCloseTo(1.5);
+    expect(summary.byModel['openai/gpt-4o'].promptTokens).toBe(300);
+    expect(summary.byModel['openai/mystery'].unpriced).toBe(1);
+  });
+});
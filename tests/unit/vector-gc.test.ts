@@ -0,0 +1,156 @@
+/**
+ * Vector GC Tests
+ * Tests for pruning vectors of deleted files, commits and branches
+ */
+
+import { describe, it, expect } from 'vitest';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { collectVectorGarbage, directorySize } from '../../packages/core/src/vector/gc.js';
+import type { VectorPoint, VectorStore } from '../../packages/core/src/vector/store.js';
+
+class MemoryStore implements VectorStore {
+  readonly type = 'lancedb' as const;
+  collections = new Map<string, Map<string, VectorPoint>>();
+
+  async connect() {}
+  async close() {}
+  async listCollections() { return [...this.collections.keys()]; }
+  async createCollection(name: string) {
+    if (!this.collections.has(name)) this.collections.set(name, new Map());
+  }
+  async deleteCollection(name: string) { this.collections.delete(name); }
+  async getCollectionStats(name: string) {
+    const c = this.collections.get(name);
+    return c ? { vectorSize: 2, pointsCount: c.size } : null;
+  }
+  async upsert(name: string, points: VectorPoint[]) {
+    for (const p of points) this.collections.get(name)!.set(String(p.id), p);
+  }
+  async delete(name: string, ids: Array<string | number>) {
+    for (const id of ids) this.collections.get(name)?.delete(String(id));
+  }
+  async retrieve(name: string, ids: Array<string | number>) {
+    const points = this.collections.get(name);
+    return ids.map(id => points?.get(String(id))).filter((p): p is VectorPoint => !!p);
+  }
+  async search() { return []; }
+  async scroll(name: string, options: { limit: number; offset?: string }) {
+    const all = [...this.collections.get(name)!.values()];
+    const start = options.offset ? parseInt(options.offset, 10) : 0;
+    const end = start + options.limit;
+    return { points: all.slice(start, end), nextOffset: end < all.length ? String(end) : undefined };
+  }
+}
+
+function storeWith(collections: Record<string, Array<Record<string, unknown>>>): MemoryStore {
+  const store = new MemoryStore();
+  for (const [name, payloads] of Object.entries(collections)) {
+    store.collections.set(name, new Map(payloads.map((payload, i) => [`${name}-${i}`, { id: `${name}-${i}`, vector: [i, 1], payload }])));
+  }
+  return store;
+}
+
+const files = (store: MemoryStore, name: string) =>
+  [...store.collections.get(name)!.values()].map(p => p.payload.file);
+
+describe('collectVectorGarbage', () => {
+  it('should delete points of files that are no longer tracked', async () => {
+    const store = storeWith({
+      code_chunks: [{ file: 'src/a.ts' }, { file: 'src/old.ts' }, { file: 'src/old.ts' }],
+      summaries: [{ file: 'src', level: 3 }, { file: 'legacy', level: 3 }, { file: 'src/a.ts', level: 2 }],
+      other_repo: [{ file: 'src/old.ts' }],
+    });
+
+    const result = await collectVectorGarbage(store, {
+      collections: ['code_chunks', 'summaries', 'missing'],
+      liveFiles: ['src/a.ts', 'README.md'],
+    });
+
+    expect(result.deleted).toBe(3);
+    expect(result.collections.map(c => [c.name, c.scanned, c.orphanedFiles])).toEqual([
+      ['code_chunks', 3, 2],
+      ['summaries', 3, 1],
+    ]);
+    expect(files(store, 'code_chunks')).toEqual(['src/a.ts']);
+    expect(files(store, 'summaries')).toEqual(['src', 'src/a.ts']);
+    expect(store.collections.get('other_repo')!.size).toBe(1);
+  });
+
+  it('should delete commit points whose commit is gone', async () => {
+    const store = storeWith({
+      commits: [
+        { sha: 'aaa', file: '', filesChanged: ['gone.ts'] },
+        { sha: 'bbb', file: '', filesChanged: [] },
+      ],
+    });
+
+    const checked: string[] = [];
+    const result = await collectVectorGarbage(store, {
+      collections: ['commits'],
+      liveFiles: [],
+      commitExists: async sha => { checked.push(sha); return sha === 'aaa'; },
+    });
+
+    expect(result.collections[0].orphanedCommits).toBe(1);
+    expect([...store.collections.get('commits')!.values()].map(p => p.payload.sha)).toEqual(['aaa']);
+    expect(checked).toEqual(['aaa', 'bbb']);
+  });
+
+  it('should drop deleted branches and keep files that exist on other branches', async () => {
+    const store = storeWith({
+      code_chunks: [
+        { file: 'src/a.ts', branches: ['main', 'old-feature'] },
+        { file: 'src/b.ts', branches: ['old-feature'] },
+        { file: 'src/only-on-dev.ts', branches: ['main', 'dev'] },
+        { file: 'src/removed.ts', branches: ['main'] },
+      ],
+    });
+
+    const result = await collectVectorGarbage(store, {
+      collections: ['code_chunks'],
+      liveFiles: ['src/a.ts', 'src/b.ts'],
+      liveNamespaces: ['main', 'dev'],
+      currentNamespace: 'main',
+    });
+
+    expect(result.removedNamespaces).toEqual(['old-feature']);
+    expect(result.collections[0]).toMatchObject({ orphanedBranches: 1, orphanedFiles: 1, retagged: 2 });
+    const points = [...store.collections.get('code_chunks')!.values()];
+    expect(points.map(p => [p.payload.file, p.payload.branches])).toEqual([
+      ['src/a.ts', ['main']],
+      ['src/only-on-dev.ts', ['dev']],
+    ]);
+    expect(points[0].vector).toEqual([0, 1]);
+  });
+
+  it('should leave the store untouched on a dry run', async () => {
+    const store = storeWith({ code_chunks: [{ file: 'gone.ts' }, { file: 'x.ts', branches: ['deleted'] }] });
+
+    const result = await collectVectorGarbage(store, {
+      collections: ['code_chunks'],
+      liveFiles: ['x.ts'],
+      liveNamespaces: ['main'],
+      dryRun: true,
+    });
+
+    expect(result.deleted).toBe(2);
+    expect(store.collections.get('code_chunks')!.size).toBe(2);
+  });
+});
+
+describe('directorySize', () => {
+  it('should add up the files under a directory', async () => {
+    const dir = fs.mkdtempSync(path.join(os.tmpdir(), 'cv-gc-'));
+    try {
+      fs.mkdirSync(path.join(dir, 'hnsw'));
+      fs.writeFileSync(path.join(dir, 'a.bin'), Buffer.alloc(100));
+      fs.writeFileSync(path.join(dir, 'hnsw', 'b.hnsw'), Buffer.alloc(50));
+      expect(await directorySize(dir)).toBe(150);
+      expect(await directorySize(path.join(dir, 'missing'))).toBe(0);
+    } finally {
+      fs.rmSync(dir, { recursive: true, force: true });
+    }
+  });
+});
@@ -0,0 +1,130 @@
+/**
+ * Vector Store Tests
+ * Tests for backend selection, filter evaluation and migration between stores
+ */
+
+import { describe, it, expect, afterEach } from 'vitest';
+import {
+  matchesFilter,
+  cosineSimilarity,
+  VectorStore,
+  VectorPoint
+} from '../../packages/core/src/vector/store.js';
+import { resolveVectorStoreType, isEmbeddedVectorStore } from '../../packages/core/src/vector/store-factory.js';
+import { migrateVectorStore } from '../../packages/core/src/vector/migrate.js';
+
+class MemoryStore implements VectorStore {
+  readonly type = 'lancedb' as const;
+  collections = new Map<string, { size: number; points: Map<string, VectorPoint> }>();
+
+  async connect() {}
+  async close() {}
+  async listCollections() { return [...this.collections.keys()]; }
+  async createCollection(name: string, vectorSize: number) {
+    if (!this.collections.has(name)) this.collections.set(name, { size: vectorSize, points: new Map() });
+  }
+  async deleteCollection(name: string) { this.collections.delete(name); }
+  async getCollectionStats(name: string) {
+    const c = this.collections.get(name);
+    return c ? { vectorSize: c.size, pointsCount: c.points.size } : null;
+  }
+  async upsert(name: string, points: VectorPoint[]) {
+    for (const p of points) this.collections.get(name)!.points.set(String(p.id), p);
+  }
+  async delete(name: string, ids: Array<string | number>) {
+    for (const id of ids) this.collections.get(name)?.points.delete(String(id));
+  }
+  async retrieve(name: string, ids: Array<string | number>) {
+    const points = this.collections.get(name)?.points;
+    return ids.map(id => points?.get(String(id))).filter((p): p is VectorPoint => !!p);
+  }
+  async search() { return []; }
+  async scroll(name: string, options: { limit: number; offset?: string }) {
+    const all = [...this.collections.get(name)!.points.values()];
+    const start = options.offset ? parseInt(options.offset, 10) : 0;
+    const end = start + options.limit;
+    return { points: all.slice(start, end), nextOffset: end < all.length ? String(end) : undefined };
+  }
+}
+
+function seeded(name: string, count: number): MemoryStore {
+  const store = new MemoryStore();
+  store.collections.set(name, {
+    size: 2,
+    points: new Map(Array.from({ length: count }, (_, i) => [String(i), { id: String(i), vector: [i, 1], payload: { i } }]))
+  });
+  return store;
+}
+
+describe('matchesFilter', () => {
+  const payload = { language: 'typescript', file: 'src/a.ts', level: 2, tags: ['api', 'auth'] };
+
+  it('should evaluate must, should and must_not', () => {
+    expect(matchesFilter(payload, { must: [{ key: 'language', match: { value: 'typescript' } }] })).toBe(true);
+    expect(matchesFilter(payload, { must_not: [{ key: 'language', match: { value: 'typescript' } }] })).toBe(false);
+    expect(matchesFilter(payload, {
+      should: [{ key: 'language', match: { value: 'go' } }, { key: 'file', match: { value: 'src/a.ts' } }]
+    })).toBe(true);
+  });
+
+  it('should match array payloads, any-of and ranges', () => {
+    expect(matchesFilter(payload, { must: [{ key: 'tags', match: { value: 'auth' } }] })).toBe(true);
+    expect(matchesFilter(payload, { must: [{ key: 'language', match: { any: ['python', 'typescript'] } }] })).toBe(true);
+    expect(matchesFilter(payload, { must: [{ key: 'level', range: { gte: 1, lt: 2 } }] })).toBe(false);
+  });
+});
+
+describe('cosineSimilarity', () => {
+  it('should score identical direction as 1 and orthogonal as 0', () => {
+    expect(cosineSimilarity([1, 2], [2, 4])).toBeCloseTo(1);
+    expect(cosineSimilarity([1, 0], [0, 1])).toBe(0);
+    expect(cosineSimilarity([0, 0], [1, 1])).toBe(0);
+  });
+});
+
+describe('resolveVectorStoreType', () => {
+  afterEach(() => {
+    delete process.env.CV_GIT_VECTOR_BACKEND;
+  });
+
+  it('should fall back to qdrant for unknown providers', () => {
+    expect(resolveVectorStoreType('chroma')).toBe('qdrant');
+    expect(resolveVectorStoreType(undefined)).toBe('qdrant');
+    expect(resolveVectorStoreType('sqlite-vec')).toBe('sqlite-vec');
+    expect(resolveVectorStoreType('jsonl')).toBe('jsonl');
+    expect(isEmbeddedVectorStore('jsonl')).toBe(true);
+  });
+
+  it('should let CV_GIT_VECTOR_BACKEND override config', () => {
+    process.env.CV_GIT_VECTOR_BACKEND = 'LanceDB';
+    expect(resolveVectorStoreType('qdrant')).toBe('lancedb');
+    expect(isEmbeddedVectorStore(resolveVectorStoreType('qdrant'))).toBe(true);
+  });
+});
+
+describe('migrateVectorStore', () => {
+  it('should copy every point across pages', async () => {
+    const source = seeded('repo_code_chunks', 5);
+    const target = new MemoryStore();
+    const progress: number[] = [];
+
+    const result = await migrateVectorStore(source, target, {
+      batchSize: 2,
+      onProgress: p => progress.push(p.copied)
+    });
+
+    expect(result.totalPoints).toBe(5);
+    expect(await target.getCollectionStats('repo_code_chunks')).toEqual({ vectorSize: 2, pointsCount: 5 });
+    expect(progress[progress.length - 1]).toBe(5);
+  });
+
+  it('should replace rather than duplicate on re-run', async () => {
+    const source = seeded('repo_code_chunks', 3);
+    const target = seeded('repo_code_chunks', 10);
+
+    await migrateVectorStore(source, target, { collections: ['repo_code_chunks', 'missing'] });
+
+    expect((await target.getCollectionStats('repo_code_chunks'))?.pointsCount).toBe(3);
+    expect(await target.listCollections()).toEqual(['repo_code_chunks']);
+  });
+});
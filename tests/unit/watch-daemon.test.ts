@@ -0,0 +1,89 @@
+/**
+ * Watch Daemon Socket Tests
+ */
+
+import { describe, it, expect, beforeEach, afterEach } from 'vitest';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import type { Server } from 'net';
+import {
+  ensureFreshIndex,
+  getWatchPidPath,
+  requestWatchDaemon,
+  startWatchServer,
+  stopWatchServer,
+  WatchDaemonStatus
+} from '../../packages/cli/src/utils/watch-daemon.js';
+
+describe('watch daemon socket', () => {
+  let repoRoot: string;
+  let server: Server | undefined;
+  let pending: number;
+  let stopped: boolean;
+
+  const status = (): WatchDaemonStatus => ({
+    pid: process.pid,
+    repoRoot,
+    startedAt: 1,
+    pending,
+    syncing: false,
+    syncCount: 0
+  });
+
+  const handlers = {
+    status,
+    flush: async () => {
+      await new Promise(resolve => setTimeout(resolve, 20));
+      pending = 0;
+    },
+    stop: async () => {
+      stopped = true;
+    }
+  };
+
+  beforeEach(() => {
+    repoRoot = fs.mkdtempSync(path.join(os.tmpdir(), 'cv-watch-'));
+    fs.mkdirSync(path.join(repoRoot, '.cv'));
+    pending = 3;
+    stopped = false;
+  });
+
+  afterEach(async () => {
+    if (server) await stopWatchServer(repoRoot, server);
+    server = undefined;
+    fs.rmSync(repoRoot, { recursive: true, force: true });
+  });
+
+  it('should report no daemon when nothing is listening', async () => {
+    expect(await requestWatchDaemon(repoRoot, 'status', 500)).toBeNull();
+    expect(await ensureFreshIndex(repoRoot, 500)).toBe(false);
+  });
+
+  it('should answer status and flush requests', async () => {
+    server = await startWatchServer(repoRoot, handlers);
+    expect(fs.readFileSync(getWatchPidPath(repoRoot), 'utf-8')).toBe(String(process.pid));
+
+    expect(await requestWatchDaemon<WatchDaemonStatus>(repoRoot, 'status')).toMatchObject({ pending: 3 });
+    expect(await ensureFreshIndex(repoRoot)).toBe(true);
+    expect(await requestWatchDaemon<WatchDaemonStatus>(repoRoot, 'status')).toMatchObject({ pending: 0 });
+  });
+
+  it('should reply to stop before stopping', async () => {
+    server = await startWatchServer(repoRoot, handlers);
+    expect(await requestWatchDaemon(repoRoot, 'stop')).toEqual({ ok: true });
+    await new Promise(resolve => setTimeout(resolve, 20));
+    expect(stopped).toBe(true);
+  });
+
+  it('should refuse to start a second server for the same repository', async () => {
+    server = await startWatchServer(repoRoot, handlers);
+    await expect(startWatchServer(repoRoot, handlers)).rejects.toThrow(/already running/);
+  });
+
+  it.skipIf(process.platform === 'win32')('should replace a socket left by a crashed daemon', async () => {
+    fs.writeFileSync(path.join(repoRoot, '.cv', 'watch.sock'), '');
+    server = await startWatchServer(repoRoot, handlers);
+    expect(await requestWatchDaemon(repoRoot, 'status')).not.toBeNull();
+  });
+});
@@ -0,0 +1,141 @@
+/**
+ * Web Server Tests
+ * Tests for the cv serve API routes, chat streaming and request checks
+ */
+
+import { describe, it, expect, beforeAll, afterAll, vi } from 'vitest';
+import * as http from 'http';
+import type { AddressInfo } from 'net';
+import { isAllowedHost, startWebServer, WebBackend } from '../../packages/cli/src/web/server.js';
+
+const chunk = {
+  file: 'src/pool.ts',
+  startLine: 10,
+  endLine: 12,
+  symbolName: 'drain',
+  symbolKind: 'method',
+  language: 'typescript',
+  text: 'drain() {\n  return this.queue.splice(0);\n}',
+};
+
+const backend: WebBackend = {
+  search: vi.fn(async () => [{ ...chunk, score: 0.9 }]),
+  files: async () => [{ file: 'src/pool.ts', language: 'typescript', chunks: 1 }],
+  chunks: async file => (file === 'src/pool.ts' ? [chunk] : []),
+  health: async () => ({ index: { chunks: 1 } }),
+  chat: async (messages, handlers) => {
+    if (messages[0].content === 'fail') throw new Error('no API key');
+    handlers.onSources([{ index: 1, file: 'src/pool.ts', startLine: 10, endLine: 12 }]);
+    handlers.onToken('It empties ');
+    handlers.onToken('the queue [1].');
+    return 'It empties the queue [1].';
+  },
+};
+
+let server: http.Server;
+let port: number;
+
+function request(
+  method: string,
+  path: string,
+  options: { body?: string; headers?: Record<string, string> } = {}
+): Promise<{ status: number; headers: http.IncomingHttpHeaders; body: string }> {
+  return new Promise((resolve, reject) => {
+    const req = http.request({ host: '127.0.0.1', port, method, path, headers: options.headers }, res => {
+      let body = '';
+      res.setEncoding('utf-8');
+      res.on('data', part => (body += part));
+      res.on('end', () => resolve({ status: res.statusCode ?? 0, headers: res.headers, body }));
+    });
+    req.on('error', reject);
+    req.end(options.body);
+  });
+}
+
+const postChat = (messages: unknown, headers: Record<string, string> = {}) =>
+  request('POST', '/api/chat', { body: JSON.stringify({ messages }), headers: { 'Content-Type': 'application/json', ...headers } });
+
+beforeAll(async () => {
+  server = await startWebServer({ backend, host: '127.0.0.1', port: 0 });
+  port = (server.address() as AddressInfo).port;
+});
+
+afterAll(() => {
+  server.close();
+});
+
+describe('cv serve API', () => {
+  it('serves the page', async () => {
+    const res = await request('GET', '/');
+    expect(res.status).toBe(200);
+    expect(res.headers['content-type']).toContain('text/html');
+    expect(res.body).toContain('<title>cv-git</title>');
+  });
+
+  it('searches with a clamped limit', async () => {
+    const res = await request('GET', '/api/search?q=drain%20queue&limit=500');
+    expect(res.status).toBe(200);
+    expect(JSON.parse(res.body).results[0]).toMatchObject({ file: 'src/pool.ts', score: 0.9 });
+    expect(backend.search).toHaveBeenCalledWith('drain queue', 100);
+
+    expect((await request('GET', '/api/search?q=')).status).toBe(400);
+  });
+
+  it('lists files and their chunks, with 404 for files not indexed', async () => {
+    expect(JSON.parse((await request('GET', '/api/files')).body).files).toHaveLength(1);
+    expect(JSON.parse((await request('GET', '/api/chunks?file=src%2Fpool.ts')).body).chunks).toEqual([chunk]);
+
+    const missing = await request('GET', '/api/chunks?file=README.md');
+    expect(missing.status).toBe(404);
+    expect(JSON.parse(missing.body).error).toBe('README.md is not indexed');
+  });
+
+  it('streams chat answers as server-sent events, sources first', async () => {
+    const res = await postChat([{ role: 'user', content: 'what does drain do?' }]);
+    expect(res.headers['content-type']).toContain('text/event-stream');
+
+    const events = res.body.trim().split('\n\n').map(raw => {
+      const [event, data] = raw.split('\n');
+      return [event.replace('event: ', ''), JSON.parse(data.replace('data: ', ''))];
+    });
+    expect(events).toEqual([
+      ['sources', [{ index: 1, file: 'src/pool.ts', startLine: 10, endLine: 12 }]],
+      ['token', 'It empties '],
+      ['token', 'the queue [1].'],
+      ['done', { answer: 'It empties the queue [1].' }],
+    ]);
+  });
+
+  it('reports chat failures as an error event', async () => {
+    const res = await postChat([{ role: 'user', content: 'fail' }]);
+    expect(res.body).toBe('event: error\ndata: {"message":"no API key"}\n\n');
+  });
+
+  it('rejects malformed chat requests', async () => {
+    expect((await postChat([])).status).toBe(400);
+    expect((await postChat([{ role: 'system', content: 'x' }])).status).toBe(400);
+    expect((await postChat([{ role: 'user', content: 'hi' }, { role: 'assistant', content: 'hello' }])).status).toBe(400);
+    expect((await request('POST', '/api/chat', { body: '{}', headers: { 'Content-Type': 'text/plain' } })).status).toBe(415);
+  });
+
+  it('refuses other Host headers and cross-origin posts', async () => {
+    expect((await request('GET', '/api/files', { headers: { Host: 'evil.example:4000' } })).status).toBe(403);
+    const crossOrigin = await postChat([{ role: 'user', content: 'hi' }], { Origin: 'https://evil.example' });
+    expect(crossOrigin.status).toBe(403);
+    expect(JSON.parse(crossOrigin.body).error).toBe('Cross-origin requests are not allowed');
+  });
+});
+
+describe('isAllowedHost', () => {
+  it('accepts local names and the bound address', () => {
+    expect(isAllowedHost('localhost:4000', '127.0.0.1')).toBe(true);
+    expect(isAllowedHost('[::1]:4000', '127.0.0.1')).toBe(true);
+    expect(isAllowedHost('devbox.lan:4000', 'devbox.lan')).toBe(true);
+    expect(isAllowedHost('evil.example', '127.0.0.1')).toBe(false);
+    expect(isAllowedHost(undefined, '127.0.0.1')).toBe(false);
+  });
+
+  it('accepts any host when bound to all interfaces', () => {
+    expect(isAllowedHost('10.0.0.5:4000', '0.0.0.0')).toBe(true);
+  });
+});
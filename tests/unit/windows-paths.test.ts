@@ -0,0 +1,79 @@
+/**
+ * Windows Path and Console Tests
+ * Platform-parameterised so they run everywhere; the long-path read is
+ * exercised for real on the Windows CI runner
+ */
+
+import { describe, it, expect, beforeAll, afterAll } from 'vitest';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import { toPosixPath, toLongPath, supportsUnicode } from '../../packages/shared/src/utils.js';
+import { safeReadFile } from '../../packages/core/src/sync/file-utils.js';
+
+describe('toPosixPath', () => {
+  it('should convert backslashes to forward slashes', () => {
+    expect(toPosixPath('src\\core\\index.ts')).toBe('src/core/index.ts');
+    expect(toPosixPath('src/core/index.ts')).toBe('src/core/index.ts');
+  });
+});
+
+describe('toLongPath', () => {
+  const deep = 'C:\\repo\\' + Array.from({ length: 20 }, (_, i) => `directory-${i}`).join('\\') + '\\file.ts';
+
+  it('should leave short and non-Windows paths alone', () => {
+    expect(toLongPath('C:\\repo\\file.ts', 'win32')).toBe('C:\\repo\\file.ts');
+    expect(toLongPath('/repo/' + 'a/'.repeat(200), 'linux')).toBe('/repo/' + 'a/'.repeat(200));
+  });
+
+  it('should prefix long absolute Windows paths', () => {
+    expect(toLongPath(deep, 'win32')).toBe('\\\\?\\' + deep);
+    expect(toLongPath('\\\\?\\' + deep, 'win32')).toBe('\\\\?\\' + deep);
+  });
+
+  it('should use the UNC form for network shares', () => {
+    const share = '\\\\server\\share\\' + 'x'.repeat(260);
+    expect(toLongPath(share, 'win32')).toBe('\\\\?\\UNC\\server\\share\\' + 'x'.repeat(260));
+  });
+
+  it('should not touch relative paths', () => {
+    const relative = 'src\\' + 'y'.repeat(300);
+    expect(toLongPath(relative, 'win32')).toBe(relative);
+  });
+});
+
+describe('supportsUnicode', () => {
+  it('should fall back to ASCII on legacy Windows consoles', () => {
+    expect(supportsUnicode({}, 'win32')).toBe(false);
+    expect(supportsUnicode({ WT_SESSION: '1' }, 'win32')).toBe(true);
+    expect(supportsUnicode({ TERM_PROGRAM: 'vscode' }, 'win32')).toBe(true);
+  });
+
+  it('should honour CV_ASCII everywhere', () => {
+    expect(supportsUnicode({ CV_ASCII: '1', TERM: 'xterm-256color' }, 'darwin')).toBe(false);
+    expect(supportsUnicode({ TERM: 'xterm-256color' }, 'darwin')).toBe(true);
+  });
+});
+
+describe('safeReadFile with deep paths', () => {
+  let root: string;
+  let deepFile: string;
+
+  beforeAll(() => {
+    root = fs.mkdtempSync(path.join(os.tmpdir(), 'cv-longpath-'));
+    const segments = Array.from({ length: 12 }, (_, i) => `very-long-directory-name-${i}`);
+    const dir = toLongPath(path.join(root, ...segments));
+    fs.mkdirSync(dir, { recursive: true });
+    deepFile = path.join(root, ...segments, 'deep.ts');
+    fs.writeFileSync(toLongPath(deepFile), 'export const x = 1;\n');
+  });
+
+  afterAll(() => {
+    fs.rmSync(toLongPath(root), { recursive: true, force: true });
+  });
+
+  it('should read files beyond MAX_PATH', async () => {
+    expect(deepFile.length).toBeGreaterThan(260);
+    expect(await safeReadFile(deepFile)).toEqual({ content: 'export const x = 1;\n' });
+  });
+});
@@ -0,0 +1,144 @@
+/**
+ * Workspace Tests
+ * Tests for registering sibling repositories and cross-repo search
+ */
+
+import { describe, it, expect, beforeEach, afterEach } from 'vitest';
+import * as fs from 'fs';
+import * as os from 'os';
+import * as path from 'path';
+import {
+  addWorkspaceRepo,
+  listWorkspaceMembers,
+  removeWorkspaceRepo,
+  searchWorkspaceCode,
+  WorkspaceIndex,
+} from '../../packages/core/src/workspace/index.js';
+import { formatChunkCitation } from '../../packages/core/src/ai/context-packer.js';
+import type { CodeChunkPayload, VectorSearchResult } from '../../packages/shared/src/types.js';
+
+function chunk(id: string, file: string, score: number): VectorSearchResult<CodeChunkPayload> {
+  return {
+    id,
+    score,
+    payload: { id, file, language: 'typescript', chunkType: 'function', startLine: 1, endLine: 10, text: '' } as CodeChunkPayload,
+  };
+}
+
+function index(name: string | undefined, model: string, results: VectorSearchResult<CodeChunkPayload>[]): WorkspaceIndex {
+  return {
+    name,
+    vector: {
+      searchCode: async () => results,
+      getEmbeddingInfo: () => ({ model, provider: 'test', dimensions: 2 }),
+    } as WorkspaceIndex['vector'],
+  };
+}
+
+describe('workspace repositories', () => {
+  let dir: string;
+  let root: string;
+
+  const repo = (name: string, initialized = true) => {
+    const repoPath = path.join(dir, name);
+    fs.mkdirSync(path.join(repoPath, '.git'), { recursive: true });
+    if (initialized) {
+      fs.mkdirSync(path.join(repoPath, '.cv'), { recursive: true });
+      fs.writeFileSync(path.join(repoPath, '.cv', 'config.json'), '{}');
+    }
+    return repoPath;
+  };
+
+  beforeEach(() => {
+    dir = fs.mkdtempSync(path.join(os.tmpdir(), 'cv-workspace-'));
+    root = repo('web');
+  });
+
+  afterEach(() => {
+    fs.rmSync(dir, { recursive: true, force: true });
+  });
+
+  it('should add repositories as paths relative to the root', async () => {
+    repo('api');
+    const repos = await addWorkspaceRepo(root, [], path.join(dir, 'api'));
+    expect(repos).toEqual(['../api']);
+  });
+
+  it('should reject the current repository, duplicates and non-repositories', async () => {
+    repo('api');
+    fs.mkdirSync(path.join(dir, 'plain'));
+
+    await expect(addWorkspaceRepo(root, [], root)).rejects.toThrow('current repository');
+    await expect(addWorkspaceRepo(root, ['../api'], path.join(dir, 'api'))).rejects.toThrow('Already in the workspace');
+    await expect(addWorkspaceRepo(root, [], path.join(dir, 'plain'))).rejects.toThrow('Not a git repository');
+  });
+
+  it('should list members with unique names and their status', async () => {
+    repo('api');
+    repo(path.join('other', 'api'), false);
+    repo(path.join('nested', 'web'));
+
+    const members = await listWorkspaceMembers(root, ['../api', '../other/api', '../nested/web', '../gone']);
+
+    expect(members.map(m => [m.name, m.exists, m.initialized])).toEqual([
+      ['api', true, true],
+      ['api-2', true, false],
+      ['web-2', true, true],
+      ['gone', false, false],
+    ]);
+    expect(members[0].root).toBe(path.join(dir, 'api'));
+  });
+
+  it('should remove a repository by name or path', async () => {
+    repo('api');
+    repo('docs');
+
+    expect(await removeWorkspaceRepo(root, ['../api', '../docs'], 'docs')).toEqual(['../api']);
+    expect(await removeWorkspaceRepo(root, ['../api', '../docs'], '../api')).toEqual(['../docs']);
+    await expect(removeWorkspaceRepo(root, ['../api'], 'missing')).rejects.toThrow('Not in the workspace');
+  });
+});
+
+describe('searchWorkspaceCode', () => {
+  it('should merge by score and tag results of other repositories', async () => {
+    const results = await searchWorkspaceCode([
+      index(undefined, 'm', [chunk('1', 'src/app.ts', 0.7)]),
+      index('api', 'm', [chunk('1', 'src/auth.ts', 0.9), chunk('2', 'src/db.ts', 0.2)]),
+    ], 'auth', 2);
+
+    expect(results.map(r => [r.id, r.payload.repo, r.payload.file])).toEqual([
+      ['api:1', 'api', 'src/auth.ts'],
+      ['1', undefined, 'src/app.ts'],
+    ]);
+    expect(formatChunkCitation(results[0].payload)).toBe('api:src/auth.ts:1-10');
+  });
+
+  it('should fuse by rank when repositories use different embedding models', async () => {
+    const results = await searchWorkspaceCode([
+      index(undefined, 'small', [chunk('1', 'src/app.ts', 0.4), chunk('2', 'src/ui.ts', 0.3)]),
+      index('api', 'large', [chunk('1', 'src/auth.ts', 0.95), chunk('2', 'src/db.ts', 0.9)]),
+    ], 'auth', 4);
+
+    // Each repository's best match ranks ahead of either one's second best
+    expect(results.slice(0, 2).map(r => r.payload.file).sort()).toEqual(['src/app.ts', 'src/auth.ts']);
+    expect(results).toHaveLength(4);
+  });
+
+  it('should keep results of the other indexes when one fails', async () => {
+    const broken: WorkspaceIndex = {
+      name: 'broken',
+      vector: {
+        searchCode: async () => { throw new Error('down'); },
+        getEmbeddingInfo: () => ({ model: 'm', provider: 'test', dimensions: 2 }),
+      } as WorkspaceIndex['vector'],
+    };
+    const errors = console.error;
+    console.error = () => {};
+    try {
+      const results = await searchWorkspaceCode([index(undefined, 'm', [chunk('1', 'a.ts', 0.5)]), broken], 'q', 5);
+      expect(results.map(r => r.payload.file)).toEqual(['a.ts']);
+    } finally {
+      console.error = errors;
+    }
+  });
+});